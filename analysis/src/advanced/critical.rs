@@ -70,6 +70,8 @@ mod tests {
             pv: vec![],
             depth: 18,
             clock_ms: None,
+            excluded: None,
+            only_move: false,
         }
     }
 
@@ -1,5 +1,6 @@
 use chess::is_white_ply;
 
+use crate::phase::{Phase, PhaseBoundaries};
 use crate::review_types::{MoveClassification, PositionReview};
 
 use super::types::PsychologicalProfile;
@@ -37,7 +38,7 @@ pub fn compute_psychological_profile(
 
     // Phase breakdown
     let (opening_avg_cp_loss, middlegame_avg_cp_loss, endgame_avg_cp_loss) =
-        compute_phase_breakdown(&side_positions);
+        compute_phase_breakdown(&side_positions, &PhaseBoundaries::of_review(positions));
 
     PsychologicalProfile {
         color,
@@ -77,10 +78,7 @@ fn empty_profile(color: char) -> PsychologicalProfile {
 }
 
 fn is_error(classification: &MoveClassification) -> bool {
-    matches!(
-        classification,
-        MoveClassification::Inaccuracy | MoveClassification::Mistake | MoveClassification::Blunder
-    )
+    classification.is_error()
 }
 
 fn is_good_move(classification: &MoveClassification) -> bool {
@@ -90,6 +88,7 @@ fn is_good_move(classification: &MoveClassification) -> bool {
             | MoveClassification::Excellent
             | MoveClassification::Good
             | MoveClassification::Brilliant
+            | MoveClassification::Great
     )
 }
 
@@ -275,19 +274,22 @@ fn pearson_correlation(pairs: &[(f64, f64)]) -> f32 {
     }
 }
 
-/// Compute average cp_loss bucketed by game phase.
-/// Opening: plies 1-30, Middlegame: plies 31-70, Endgame: plies 71+.
-fn compute_phase_breakdown(side_positions: &[&PositionReview]) -> (f64, f64, f64) {
+/// Compute average cp_loss bucketed by game phase, with the game's own
+/// phase boundaries.
+fn compute_phase_breakdown(
+    side_positions: &[&PositionReview],
+    phases: &PhaseBoundaries,
+) -> (f64, f64, f64) {
     let mut opening_losses: Vec<f64> = Vec::new();
     let mut middlegame_losses: Vec<f64> = Vec::new();
     let mut endgame_losses: Vec<f64> = Vec::new();
 
     for pos in side_positions {
         let loss = pos.cp_loss as f64;
-        match pos.ply {
-            1..=30 => opening_losses.push(loss),
-            31..=70 => middlegame_losses.push(loss),
-            _ => endgame_losses.push(loss),
+        match phases.phase_of(pos.ply) {
+            Phase::Opening => opening_losses.push(loss),
+            Phase::Middlegame => middlegame_losses.push(loss),
+            Phase::Endgame => endgame_losses.push(loss),
         }
     }
 
@@ -327,6 +329,8 @@ mod tests {
             pv: vec![],
             depth: 18,
             clock_ms: None,
+            excluded: None,
+            only_move: false,
         }
     }
 
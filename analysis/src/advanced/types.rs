@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 
-use crate::board_analysis::{PositionKingSafety, PositionTensionMetrics, TacticalTag};
+use crate::board_analysis::{
+    PositionKingSafety, PositionMobility, PositionTensionMetrics, TacticalTag,
+};
 
 /// Advanced analysis result for a single position.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -12,6 +14,8 @@ pub struct AdvancedPositionAnalysis {
     pub tactical_tags_after: Vec<TacticalTag>,
     pub king_safety: PositionKingSafety,
     pub tension: PositionTensionMetrics,
+    #[serde(default)]
+    pub mobility: PositionMobility,
     /// Whether this position was flagged as critical (multi-signal).
     pub is_critical: bool,
     /// If re-analyzed at deeper depth, the depth used.
@@ -42,11 +46,12 @@ pub struct PsychologicalProfile {
     pub avg_blunder_time_ms: Option<u64>,
     /// Average time spent on good moves (Best/Excellent/Good) (ms).
     pub avg_good_move_time_ms: Option<u64>,
-    /// Average cp_loss during opening phase (plies 1-30).
+    /// Average cp_loss during the opening, as the game's positions mark it
+    /// (plies 1-30 without them).
     pub opening_avg_cp_loss: f64,
-    /// Average cp_loss during middlegame phase (plies 31-70).
+    /// Average cp_loss during the middlegame (plies 31-70 without positions).
     pub middlegame_avg_cp_loss: f64,
-    /// Average cp_loss during endgame phase (plies 71+).
+    /// Average cp_loss during the endgame (plies 71+ without positions).
     pub endgame_avg_cp_loss: f64,
 }
 
@@ -0,0 +1,205 @@
+use cozy_chess::{Board, Move, Piece, Square};
+
+use super::detector::{TacticalContext, TacticalDetector};
+use super::helpers::{parse_uci_move, piece_attacks};
+use super::tactical_types::{TacticalEvidence, TacticalTag, TacticalTagKind};
+
+/// Detects deflections and decoys in the engine's best line.
+///
+/// Both play out over the first three plies of the line: a forcing move (a
+/// check, or a capture the opponent answers by recapturing), the opponent's
+/// forced reply, and the move that cashes in on it. The reply is a decoy
+/// when it takes the defender onto the square of the forcing move and the
+/// follow-up hits it there; it is a deflection when it takes the defender
+/// away from the square the follow-up lands on.
+pub struct DeflectionDetector;
+
+impl TacticalDetector for DeflectionDetector {
+    fn detect(&self, ctx: &TacticalContext) -> Vec<TacticalTag> {
+        let line = match ctx.best_line {
+            Some(line) if line.len() >= 3 => line,
+            _ => return vec![],
+        };
+        let side = ctx.side_to_move_before;
+
+        // The line starts from the position before the move
+        let mut boards = vec![ctx.before.clone()];
+        let mut moves = Vec::new();
+        for uci in &line[..3] {
+            let board = boards.last().unwrap();
+            let Some(mv) = parse_uci_move(board, uci) else {
+                return vec![];
+            };
+            let mut next = board.clone();
+            next.play_unchecked(mv);
+            moves.push(mv);
+            boards.push(next);
+        }
+        let (lure, reply, follow_up) = (moves[0], moves[1], moves[2]);
+        let (after_lure, after_reply, after_follow_up) = (&boards[1], &boards[2], &boards[3]);
+
+        let recaptured = reply.to == lure.to;
+        let forced = recaptured || !after_lure.checkers().is_empty();
+        if !forced {
+            return vec![];
+        }
+        let Some(defender) = after_lure.piece_on(reply.from) else {
+            return vec![];
+        };
+
+        // Decoy: the defender takes the bait and is hit where it now stands
+        if recaptured {
+            let hit_by_follow_up = after_follow_up.piece_on(follow_up.to).is_some_and(|piece| {
+                piece_attacks(after_follow_up, follow_up.to, piece, side).has(reply.to)
+            });
+            if follow_up.to == reply.to || hit_by_follow_up {
+                return vec![tag(
+                    TacticalTagKind::Decoy,
+                    lure,
+                    reply,
+                    reply.to,
+                    0.6,
+                    format!(
+                        "decoy: {} lures the {} from {} onto {}",
+                        lure,
+                        piece_word(defender),
+                        reply.from,
+                        reply.to
+                    ),
+                )];
+            }
+        }
+
+        // Deflection: the defender leaves a square the follow-up then exploits
+        let target = follow_up.to;
+        let exploits =
+            after_reply.piece_on(target).is_some() || !after_follow_up.checkers().is_empty();
+        if target == reply.to || !exploits {
+            return vec![];
+        }
+        let guarded_before = defends(after_lure, reply.from, defender, target);
+        let guarded_after = defends(after_reply, reply.to, defender, target);
+        if !guarded_before || guarded_after {
+            return vec![];
+        }
+
+        vec![tag(
+            TacticalTagKind::Deflection,
+            lure,
+            reply,
+            target,
+            0.7,
+            format!(
+                "deflection: {} draws the {} on {} away from {}",
+                lure,
+                piece_word(defender),
+                reply.from,
+                target
+            ),
+        )]
+    }
+}
+
+/// Whether the piece of kind `piece` on `from` covers `target`.
+fn defends(board: &Board, from: Square, piece: Piece, target: Square) -> bool {
+    let Some(color) = board.color_on(from) else {
+        return false;
+    };
+    piece_attacks(board, from, piece, color).has(target)
+}
+
+fn tag(
+    kind: TacticalTagKind,
+    lure: Move,
+    reply: Move,
+    target: Square,
+    confidence: f32,
+    note: String,
+) -> TacticalTag {
+    TacticalTag {
+        kind,
+        attacker: Some(lure.to.to_string()),
+        victims: vec![reply.from.to_string()],
+        target_square: Some(target.to_string()),
+        confidence,
+        note: Some(note),
+        evidence: TacticalEvidence {
+            lines: vec![],
+            threatened_pieces: vec![target.to_string()],
+            defended_by: vec![reply.from.to_string()],
+        },
+    }
+}
+
+fn piece_word(piece: Piece) -> &'static str {
+    match piece {
+        Piece::Pawn => "pawn",
+        Piece::Knight => "knight",
+        Piece::Bishop => "bishop",
+        Piece::Rook => "rook",
+        Piece::Queen => "queen",
+        Piece::King => "king",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board_analysis::attack_map::AttackMap;
+
+    fn detect(fen: &str, line: &[&str]) -> Vec<TacticalTag> {
+        let board: Board = fen.parse().unwrap();
+        let attacks = AttackMap::compute(&board);
+        let line: Vec<String> = line.iter().map(|m| m.to_string()).collect();
+        let ctx = TacticalContext {
+            before: &board,
+            after: &board,
+            mv: None,
+            side_to_move_before: board.side_to_move(),
+            before_attacks: &attacks,
+            after_attacks: &attacks,
+            eval_before: None,
+            eval_after: None,
+            best_line: Some(&line),
+        };
+        DeflectionDetector.detect(&ctx)
+    }
+
+    #[test]
+    fn detects_deflection_of_a_defending_rook() {
+        // Rxe8+ forces Rdxe8, and the d5 knight loses its only defender
+        let tags = detect(
+            "3rr1k1/5ppp/8/3n4/8/1B6/5PPP/4R1K1 w - - 0 1",
+            &["e1e8", "d8e8", "b3d5"],
+        );
+        assert_eq!(tags.len(), 1);
+        assert_eq!(tags[0].kind, TacticalTagKind::Deflection);
+        assert_eq!(tags[0].attacker.as_deref(), Some("e8"));
+        assert_eq!(tags[0].victims, vec!["d8".to_string()]);
+        assert_eq!(tags[0].target_square.as_deref(), Some("d5"));
+    }
+
+    #[test]
+    fn detects_decoy_onto_a_forking_square() {
+        // Rh8+ Kxh8 puts the king where Nf7+ forks it with the queen
+        let tags = detect(
+            "3q2k1/8/8/6N1/8/8/8/6KR w - - 0 1",
+            &["h1h8", "g8h8", "g5f7"],
+        );
+        assert_eq!(tags.len(), 1);
+        assert_eq!(tags[0].kind, TacticalTagKind::Decoy);
+        assert_eq!(tags[0].victims, vec!["g8".to_string()]);
+        assert_eq!(tags[0].target_square.as_deref(), Some("h8"));
+    }
+
+    #[test]
+    fn quiet_lines_have_no_deflection() {
+        let tags = detect(
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            &["e2e4", "e7e5", "g1f3"],
+        );
+        assert!(tags.is_empty());
+        let tags = detect("3rr1k1/5ppp/8/3n4/8/1B6/5PPP/4R1K1 w - - 0 1", &["e1e8"]);
+        assert!(tags.is_empty());
+    }
+}
@@ -0,0 +1,101 @@
+use cozy_chess::{Board, Color, Piece, Square};
+
+use super::attack_map::AttackMap;
+use super::helpers::piece_attacks;
+
+/// A count per square for each side, for drawing board heatmaps.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SquareCounts {
+    white: [u8; 64],
+    black: [u8; 64],
+}
+
+impl SquareCounts {
+    fn empty() -> Self {
+        Self {
+            white: [0; 64],
+            black: [0; 64],
+        }
+    }
+
+    pub fn get(&self, sq: Square, color: Color) -> u8 {
+        match color {
+            Color::White => self.white[sq as usize],
+            Color::Black => self.black[sq as usize],
+        }
+    }
+
+    fn set(&mut self, sq: Square, color: Color, count: u8) {
+        match color {
+            Color::White => self.white[sq as usize] = count,
+            Color::Black => self.black[sq as usize] = count,
+        }
+    }
+
+    /// Whether both sides count on `sq`: for attack density, a square
+    /// attacked by both.
+    pub fn is_contested(&self, sq: Square) -> bool {
+        self.get(sq, Color::White) > 0 && self.get(sq, Color::Black) > 0
+    }
+}
+
+/// Attack density: how many pieces of each side attack each square.
+pub fn attack_density(attacks: &AttackMap) -> SquareCounts {
+    let mut counts = SquareCounts::empty();
+    for sq in Square::ALL {
+        for color in [Color::White, Color::Black] {
+            counts.set(sq, color, attacks.attackers_of(sq, color).len() as u8);
+        }
+    }
+    counts
+}
+
+/// Piece activity: on each occupied square, the number of squares its piece
+/// attacks that its own side does not occupy. Empty squares count zero.
+pub fn piece_activity(board: &Board) -> SquareCounts {
+    let mut counts = SquareCounts::empty();
+    for color in [Color::White, Color::Black] {
+        let own = board.colors(color);
+        for piece in Piece::ALL {
+            for sq in board.colored_pieces(color, piece) {
+                let reach = piece_attacks(board, sq, piece, color) & !own;
+                counts.set(sq, color, reach.len() as u8);
+            }
+        }
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attack_density_counts_each_attacker() {
+        let board = Board::default();
+        let counts = attack_density(&AttackMap::compute(&board));
+        // Ng1, e2 and g2 cover f3
+        assert_eq!(counts.get(Square::F3, Color::White), 3);
+        assert_eq!(counts.get(Square::F3, Color::Black), 0);
+        assert_eq!(counts.get(Square::E4, Color::White), 0);
+        assert!(!counts.is_contested(Square::E4));
+
+        // The e4 and e6 pawns both cover d5 and f5
+        let board: Board = "4k3/8/4p3/8/4P3/8/8/4K3 w - - 0 1".parse().unwrap();
+        let counts = attack_density(&AttackMap::compute(&board));
+        assert!(counts.is_contested(Square::D5));
+        assert!(counts.is_contested(Square::F5));
+        assert!(!counts.is_contested(Square::E5));
+    }
+
+    #[test]
+    fn piece_activity_skips_own_pieces() {
+        let board = Board::default();
+        let activity = piece_activity(&board);
+        // Ng1 reaches f3 and h3 but not its own e2 pawn
+        assert_eq!(activity.get(Square::G1, Color::White), 2);
+        assert_eq!(activity.get(Square::A1, Color::White), 0);
+        assert_eq!(activity.get(Square::B8, Color::Black), 2);
+        assert_eq!(activity.get(Square::E4, Color::White), 0);
+    }
+}
@@ -1,4 +1,4 @@
-use cozy_chess::{BitBoard, Board, Color, File, Piece, Square};
+use cozy_chess::{BitBoard, Board, Color, File, Move, Piece, Square};
 
 /// Returns the union of all attack squares for a given color.
 pub fn attacked_squares(board: &Board, color: Color) -> BitBoard {
@@ -78,6 +78,19 @@ pub fn piece_attacks(board: &Board, sq: Square, piece: Piece, color: Color) -> B
     }
 }
 
+/// A UCI move from an engine line as a legal move on `board`, with the
+/// engine's king-to-rook-square castling notation converted.
+pub fn parse_uci_move(board: &Board, uci: &str) -> Option<Move> {
+    let mv: Move = uci.parse().ok()?;
+    let mut legal = Vec::new();
+    board.generate_moves(|moves| {
+        legal.extend(moves);
+        false
+    });
+    let mv = chess::convert_uci_castling_to_cozy(mv, &legal);
+    legal.contains(&mv).then_some(mv)
+}
+
 /// Returns the files adjacent to the king (including the king's own file), clamped to the board.
 pub fn king_zone_files(king_sq: Square) -> impl Iterator<Item = File> {
     let king_file = king_sq.file() as i8;
@@ -0,0 +1,85 @@
+use cozy_chess::{Board, Color, Piece, Rank, Square};
+use serde::{Deserialize, Serialize};
+
+use super::attack_map::AttackMap;
+
+/// Mobility and space for one side.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MobilityMetrics {
+    /// Squares the side's knights, bishops, rooks and queens can move to,
+    /// counted once per piece; squares held by its own pieces do not count.
+    pub mobility: u16,
+    /// Squares in the opponent's half of the board the side attacks.
+    pub space: u8,
+}
+
+/// Mobility and space for both sides.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PositionMobility {
+    pub white: MobilityMetrics,
+    pub black: MobilityMetrics,
+}
+
+impl PositionMobility {
+    pub fn side(&self, color: Color) -> MobilityMetrics {
+        match color {
+            Color::White => self.white,
+            Color::Black => self.black,
+        }
+    }
+}
+
+/// Compute mobility and space for both sides from the position's attacks.
+pub fn compute_mobility(board: &Board, attacks: &AttackMap) -> PositionMobility {
+    PositionMobility {
+        white: compute_side_mobility(board, attacks, Color::White),
+        black: compute_side_mobility(board, attacks, Color::Black),
+    }
+}
+
+fn compute_side_mobility(board: &Board, attacks: &AttackMap, color: Color) -> MobilityMetrics {
+    let own = board.colors(color);
+    let mut metrics = MobilityMetrics::default();
+    for sq in Square::ALL {
+        let attackers = attacks.attackers_of(sq, color);
+        if !own.has(sq) {
+            // Pawns and the king are left out: their reach says little about
+            // how freely a side can manoeuvre
+            metrics.mobility += attackers
+                .iter()
+                .filter(|a| !matches!(a.piece, Piece::Pawn | Piece::King))
+                .count() as u16;
+        }
+        if !attackers.is_empty() && sq.rank().relative_to(color) as usize >= Rank::Fifth as usize {
+            metrics.space += 1;
+        }
+    }
+    metrics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mobility(fen: &str) -> PositionMobility {
+        let board: Board = fen.parse().unwrap();
+        compute_mobility(&board, &AttackMap::compute(&board))
+    }
+
+    #[test]
+    fn starting_position_only_knights_move() {
+        let metrics = mobility("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        for side in [metrics.white, metrics.black] {
+            assert_eq!(side.mobility, 4);
+            assert_eq!(side.space, 0);
+        }
+    }
+
+    #[test]
+    fn centralised_queen_has_mobility_and_space() {
+        let metrics = mobility("4k3/8/8/8/3Q4/8/8/4K3 w - - 0 1");
+        assert_eq!(metrics.side(Color::White).mobility, 27);
+        assert_eq!(metrics.side(Color::White).space, 11);
+        assert_eq!(metrics.black, MobilityMetrics::default());
+    }
+}
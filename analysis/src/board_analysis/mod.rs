@@ -1,27 +1,37 @@
 pub mod attack_map;
 pub mod back_rank_detector;
+pub mod deflection_detector;
 pub mod detector;
 pub mod discovered_attack_detector;
 pub mod fork_detector;
 pub mod hanging_detector;
+pub mod heatmap;
 pub mod helpers;
 pub mod king_safety;
 pub mod mate_threat_detector;
+pub mod mobility;
 pub mod pin_detector;
 pub mod reducer;
 pub mod sacrifice_detector;
 pub mod skewer_detector;
+pub mod square_control;
 pub mod tactical_types;
 pub mod tension;
 pub mod zwischenzug_detector;
 
 pub use attack_map::{AttackMap, Attacker, PinInfo};
 pub use detector::{TacticalContext, TacticalDetector};
+pub use heatmap::{attack_density, piece_activity, SquareCounts};
 pub use king_safety::{compute_king_safety, KingSafetyMetrics, PositionKingSafety};
+pub use mobility::{compute_mobility, MobilityMetrics, PositionMobility};
+pub use square_control::{is_outpost, square_control, SquareControl};
 pub use tactical_types::{TacticalEvidence, TacticalLine, TacticalTag, TacticalTagKind};
 pub use tension::{compute_tension, PositionTensionMetrics};
 
+use cozy_chess::{Board, Move};
+
 use back_rank_detector::BackRankDetector;
+use deflection_detector::DeflectionDetector;
 use discovered_attack_detector::DiscoveredAttackDetector;
 use fork_detector::{DoubleAttackDetector, ForkDetector};
 use hanging_detector::HangingPieceDetector;
@@ -51,6 +61,7 @@ pub fn detect_tactics(ctx: &TacticalContext, max_results: Option<usize>) -> Vec<
         Box::new(HangingPieceDetector),
         Box::new(BackRankDetector),
         Box::new(ZwischenzugDetector),
+        Box::new(DeflectionDetector),
     ];
 
     let tags: Vec<TacticalTag> = detectors.iter().flat_map(|d| d.detect(ctx)).collect();
@@ -58,6 +69,32 @@ pub fn detect_tactics(ctx: &TacticalContext, max_results: Option<usize>) -> Vec<
     reduce_tags(tags, max_results)
 }
 
+/// Whether `mv`, played from `before` into `after`, gives up material
+/// without the evaluation dropping, per the [`SacrificeDetector`]. The
+/// evaluations are centipawns from White's perspective.
+pub fn is_sacrifice(
+    before: &Board,
+    after: &Board,
+    mv: Move,
+    eval_before: i32,
+    eval_after: i32,
+) -> bool {
+    let before_attacks = AttackMap::compute(before);
+    let after_attacks = AttackMap::compute(after);
+    let ctx = TacticalContext {
+        before,
+        after,
+        mv: Some(mv),
+        side_to_move_before: before.side_to_move(),
+        before_attacks: &before_attacks,
+        after_attacks: &after_attacks,
+        eval_before: Some(eval_before),
+        eval_after: Some(eval_after),
+        best_line: None,
+    };
+    !SacrificeDetector.detect(&ctx).is_empty()
+}
+
 #[cfg(test)]
 mod tests {
     use cozy_chess::{Board, Color, Move, Square};
@@ -85,6 +122,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn is_sacrifice_needs_the_eval_to_hold() {
+        let before: Board = "4k3/5p2/8/3Q4/8/8/8/4K3 w - - 0 1".parse().unwrap();
+        let after: Board = "4k3/5Q2/8/8/8/8/8/4K3 b - - 0 1".parse().unwrap();
+        let mv = Move {
+            from: Square::D5,
+            to: Square::F7,
+            promotion: None,
+        };
+        assert!(is_sacrifice(&before, &after, mv, 50, 30));
+        assert!(!is_sacrifice(&before, &after, mv, 50, -800));
+    }
+
     // -----------------------------------------------------------------------
     // Starting position: no tactics expected
     // -----------------------------------------------------------------------
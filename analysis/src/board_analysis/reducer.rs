@@ -13,6 +13,8 @@ fn kind_priority(kind: &TacticalTagKind) -> u8 {
         TacticalTagKind::BackRankWeakness => 7,
         TacticalTagKind::HangingPiece => 8,
         TacticalTagKind::Zwischenzug => 9,
+        TacticalTagKind::Deflection => 10,
+        TacticalTagKind::Decoy => 11,
     }
 }
 
@@ -0,0 +1,138 @@
+use cozy_chess::{BitBoard, Board, Color, File, Piece, Rank, Square};
+
+use super::attack_map::{AttackMap, Attacker};
+use super::helpers::piece_value;
+
+/// Who covers a single square, and whether it is an outpost.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SquareControl {
+    pub square: Square,
+    /// The piece on the square, if any.
+    pub occupant: Option<(Color, Piece)>,
+    /// Pieces of each side attacking the square, cheapest first.
+    pub white: Vec<Attacker>,
+    pub black: Vec<Attacker>,
+    /// The side with more pieces on the square; `None` when level.
+    pub controller: Option<Color>,
+    /// The side for which the square is an outpost: well up the board,
+    /// covered by one of its pawns and out of reach of every enemy pawn.
+    pub outpost_for: Option<Color>,
+}
+
+impl SquareControl {
+    pub fn attackers(&self, color: Color) -> &[Attacker] {
+        match color {
+            Color::White => &self.white,
+            Color::Black => &self.black,
+        }
+    }
+}
+
+/// Square control of `sq` on `board`, from its attack map.
+pub fn square_control(board: &Board, attacks: &AttackMap, sq: Square) -> SquareControl {
+    let sorted = |color: Color| {
+        let mut attackers = attacks.attackers_of(sq, color).to_vec();
+        attackers.sort_by_key(|a| piece_value(a.piece));
+        attackers
+    };
+    let white = sorted(Color::White);
+    let black = sorted(Color::Black);
+    let controller = match white.len().cmp(&black.len()) {
+        std::cmp::Ordering::Greater => Some(Color::White),
+        std::cmp::Ordering::Less => Some(Color::Black),
+        std::cmp::Ordering::Equal => None,
+    };
+    let occupant = board.color_on(sq).zip(board.piece_on(sq));
+    let outpost_for = [Color::White, Color::Black]
+        .into_iter()
+        .find(|&color| is_outpost(board, attacks, sq, color));
+
+    SquareControl {
+        square: sq,
+        occupant,
+        white,
+        black,
+        controller,
+        outpost_for,
+    }
+}
+
+/// Whether `sq` is an outpost for `color`: on the fourth to sixth rank from
+/// its side, not held by one of its own pawns, covered by one of its pawns,
+/// and with no enemy pawn left on a neighbouring file that could advance to
+/// attack it.
+pub fn is_outpost(board: &Board, attacks: &AttackMap, sq: Square, color: Color) -> bool {
+    let relative_rank = sq.rank().relative_to(color) as usize;
+    if !(3..=5).contains(&relative_rank) {
+        return false;
+    }
+    let own_pawns = board.colored_pieces(color, Piece::Pawn);
+    if own_pawns.has(sq) {
+        return false;
+    }
+    let pawn_support = attacks
+        .attackers_of(sq, color)
+        .iter()
+        .any(|a| a.piece == Piece::Pawn);
+    if !pawn_support {
+        return false;
+    }
+
+    // Enemy pawns still behind the square, seen from their side, can come
+    // forward and chase a piece off it
+    let enemy_pawns = board.colored_pieces(!color, Piece::Pawn);
+    let ahead = Rank::ALL
+        .into_iter()
+        .filter(|rank| rank.relative_to(color) as usize > relative_rank)
+        .fold(BitBoard::EMPTY, |acc, rank| acc | rank.bitboard());
+    let neighbours = [sq.file() as i8 - 1, sq.file() as i8 + 1]
+        .into_iter()
+        .filter_map(|f| usize::try_from(f).ok().and_then(File::try_index))
+        .fold(BitBoard::EMPTY, |acc, file| acc | file.bitboard());
+    (enemy_pawns & ahead & neighbours).is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn control(fen: &str, sq: Square) -> SquareControl {
+        let board: Board = fen.parse().unwrap();
+        square_control(&board, &AttackMap::compute(&board), sq)
+    }
+
+    #[test]
+    fn square_control_lists_both_sides_cheapest_first() {
+        // d5 is hit by the c4 pawn for White and the e6 pawn for Black
+        let info = control("4k3/8/4p3/8/2P5/8/8/4K3 w - - 0 1", Square::D5);
+        assert_eq!(info.occupant, None);
+        let white: Vec<Piece> = info.white.iter().map(|a| a.piece).collect();
+        assert_eq!(white, vec![Piece::Pawn]);
+        assert_eq!(info.black.len(), 1);
+        assert_eq!(info.controller, None);
+
+        let info = control("4k3/8/4p3/8/2P5/2N5/8/4K3 w - - 0 1", Square::D5);
+        let white: Vec<Piece> = info.white.iter().map(|a| a.piece).collect();
+        assert_eq!(white, vec![Piece::Pawn, Piece::Knight]);
+        assert_eq!(info.controller, Some(Color::White));
+    }
+
+    #[test]
+    fn outpost_needs_pawn_support_and_no_enemy_pawn_to_chase() {
+        // d5 is covered by e4 and no black c- or e-pawn can reach it
+        let fen = "4k3/8/8/8/4P3/8/8/4K3 w - - 0 1";
+        assert_eq!(control(fen, Square::D5).outpost_for, Some(Color::White));
+
+        // The c7 pawn can still come to c6
+        let fen = "4k3/2p5/8/8/4P3/8/8/4K3 w - - 0 1";
+        assert_eq!(control(fen, Square::D5).outpost_for, None);
+
+        // Too close to home
+        let fen = "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1";
+        assert_eq!(control(fen, Square::D3).outpost_for, None);
+
+        // The same holds for Black, mirrored
+        let fen = "4k3/8/8/4p3/8/8/8/4K3 b - - 0 1";
+        assert_eq!(control(fen, Square::D4).outpost_for, Some(Color::Black));
+    }
+}
@@ -12,6 +12,28 @@ pub enum TacticalTagKind {
     Zwischenzug,
     BackRankWeakness,
     MateThreat,
+    Deflection,
+    Decoy,
+}
+
+impl TacticalTagKind {
+    /// Lower-case name for prose, e.g. "discovered attack".
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Fork => "fork",
+            Self::Pin => "pin",
+            Self::Skewer => "skewer",
+            Self::DiscoveredAttack => "discovered attack",
+            Self::DoubleAttack => "double attack",
+            Self::HangingPiece => "hanging piece",
+            Self::Sacrifice => "sacrifice",
+            Self::Zwischenzug => "zwischenzug",
+            Self::BackRankWeakness => "back-rank weakness",
+            Self::MateThreat => "mate threat",
+            Self::Deflection => "deflection",
+            Self::Decoy => "decoy",
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -0,0 +1,211 @@
+//! Endgame types.
+//!
+//! Once a position counts as an endgame (see [`crate::phase`]), the pieces
+//! left besides kings and pawns say which kind of ending it is: a rook
+//! ending, opposite-coloured bishops, king and pawns only, and so on.
+
+use cozy_chess::{Board, Color, Piece, Square};
+use serde::{Deserialize, Serialize};
+
+use crate::phase::is_endgame;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EndgameType {
+    /// Kings and pawns only.
+    Pawn,
+    Knight,
+    /// Bishops only, not one each on opposite colours.
+    Bishop,
+    /// One bishop each, running on squares of opposite colours.
+    OppositeBishops,
+    /// Knights and bishops together.
+    Minor,
+    Rook,
+    RookAndMinor,
+    Queen,
+    /// Queens together with other pieces.
+    Mixed,
+}
+
+impl EndgameType {
+    pub const ALL: [EndgameType; 9] = [
+        Self::Pawn,
+        Self::Knight,
+        Self::Bishop,
+        Self::OppositeBishops,
+        Self::Minor,
+        Self::Rook,
+        Self::RookAndMinor,
+        Self::Queen,
+        Self::Mixed,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Pawn => "King and pawn",
+            Self::Knight => "Knight",
+            Self::Bishop => "Bishop",
+            Self::OppositeBishops => "Opposite bishops",
+            Self::Minor => "Minor piece",
+            Self::Rook => "Rook",
+            Self::RookAndMinor => "Rook and minor",
+            Self::Queen => "Queen",
+            Self::Mixed => "Mixed",
+        }
+    }
+
+    /// Stable name for storage.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::Pawn => "pawn",
+            Self::Knight => "knight",
+            Self::Bishop => "bishop",
+            Self::OppositeBishops => "opposite_bishops",
+            Self::Minor => "minor",
+            Self::Rook => "rook",
+            Self::RookAndMinor => "rook_and_minor",
+            Self::Queen => "queen",
+            Self::Mixed => "mixed",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|t| t.key() == key)
+    }
+}
+
+/// The kind of ending on the board; `None` before the endgame.
+pub fn classify_endgame(board: &Board) -> Option<EndgameType> {
+    if !is_endgame(board) {
+        return None;
+    }
+    let count = |piece: Piece| board.pieces(piece).len();
+    let (knights, bishops) = (count(Piece::Knight), count(Piece::Bishop));
+    let (rooks, queens) = (count(Piece::Rook), count(Piece::Queen));
+    let minors = knights + bishops;
+
+    let kind = match (queens > 0, rooks > 0, minors > 0) {
+        (false, false, false) => EndgameType::Pawn,
+        (true, false, false) => EndgameType::Queen,
+        (true, _, _) => EndgameType::Mixed,
+        (false, true, false) => EndgameType::Rook,
+        (false, true, true) => EndgameType::RookAndMinor,
+        (false, false, true) if bishops == 0 => EndgameType::Knight,
+        (false, false, true) if knights == 0 && opposite_bishops(board) => {
+            EndgameType::OppositeBishops
+        }
+        (false, false, true) if knights == 0 => EndgameType::Bishop,
+        (false, false, true) => EndgameType::Minor,
+    };
+    Some(kind)
+}
+
+/// The endgame type seen in the most positions, from the FEN after each ply
+/// in order. A tie goes to the type reached last, the ending the game ran
+/// into. `None` for a game that never reached an endgame.
+pub fn dominant_endgame<'a>(fens: impl IntoIterator<Item = &'a str>) -> Option<EndgameType> {
+    // Each type with how often it was seen and when last
+    let mut seen: Vec<(EndgameType, usize, usize)> = Vec::new();
+    for (i, fen) in fens.into_iter().enumerate() {
+        let Some(kind) = fen
+            .parse::<Board>()
+            .ok()
+            .as_ref()
+            .and_then(classify_endgame)
+        else {
+            continue;
+        };
+        match seen.iter_mut().find(|(t, _, _)| *t == kind) {
+            Some(entry) => {
+                entry.1 += 1;
+                entry.2 = i;
+            }
+            None => seen.push((kind, 1, i)),
+        }
+    }
+    seen.into_iter()
+        .max_by_key(|&(_, count, last)| (count, last))
+        .map(|(kind, _, _)| kind)
+}
+
+/// Whether each side has one bishop and they run on different colours.
+fn opposite_bishops(board: &Board) -> bool {
+    let white = board.colored_pieces(Color::White, Piece::Bishop);
+    let black = board.colored_pieces(Color::Black, Piece::Bishop);
+    let (Some(w), Some(b)) = (white.into_iter().next(), black.into_iter().next()) else {
+        return false;
+    };
+    let dark = |sq: Square| (sq.file() as usize + sq.rank() as usize) % 2 == 0;
+    white.len() == 1 && black.len() == 1 && dark(w) != dark(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classify(fen: &str) -> Option<EndgameType> {
+        classify_endgame(&fen.parse().unwrap())
+    }
+
+    #[test]
+    fn test_classify_endgame_by_the_pieces_left() {
+        assert_eq!(
+            classify("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"),
+            None
+        );
+        assert_eq!(
+            classify("8/5k2/5p2/8/4P3/5K2/8/8 w - - 0 1"),
+            Some(EndgameType::Pawn)
+        );
+        assert_eq!(
+            classify("8/5k2/r4p2/8/4P3/5K2/R7/8 w - - 0 1"),
+            Some(EndgameType::Rook)
+        );
+        // Light-squared against dark-squared bishop
+        assert_eq!(
+            classify("8/5k2/2b2p2/8/4P3/5K2/3B4/8 w - - 0 1"),
+            Some(EndgameType::OppositeBishops)
+        );
+        assert_eq!(
+            classify("8/5k2/3b1p2/8/4P3/5K2/3B4/8 w - - 0 1"),
+            Some(EndgameType::Bishop)
+        );
+        assert_eq!(
+            classify("8/5k2/3n1p2/8/4P3/5K2/2B5/8 w - - 0 1"),
+            Some(EndgameType::Minor)
+        );
+        assert_eq!(
+            classify("8/5k2/3n1p2/8/4P3/5K2/2R5/8 w - - 0 1"),
+            Some(EndgameType::RookAndMinor)
+        );
+        assert_eq!(
+            classify("8/5k2/3q1p2/8/4P3/5K2/2R5/8 w - - 0 1"),
+            Some(EndgameType::Mixed)
+        );
+    }
+
+    #[test]
+    fn test_dominant_endgame_counts_positions() {
+        let rook = "8/5k2/r4p2/8/4P3/5K2/R7/8 w - - 0 1";
+        let pawn = "8/5k2/5p2/8/4P3/5K2/8/8 w - - 0 1";
+        let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        assert_eq!(
+            dominant_endgame([start, rook, rook, pawn]),
+            Some(EndgameType::Rook)
+        );
+        // Level counts go to the ending the game finished in
+        assert_eq!(
+            dominant_endgame([start, rook, pawn]),
+            Some(EndgameType::Pawn)
+        );
+        assert_eq!(dominant_endgame([start, ""]), None);
+    }
+
+    #[test]
+    fn test_endgame_keys_round_trip() {
+        for kind in EndgameType::ALL {
+            assert_eq!(EndgameType::from_key(kind.key()), Some(kind));
+        }
+        assert_eq!(EndgameType::from_key("bogus"), None);
+    }
+}
@@ -0,0 +1,251 @@
+//! Why the engine's best move is best.
+//!
+//! The best line of a reviewed position is played out move by move, running
+//! the tactical detectors after each one, and the material and king safety
+//! at its end are compared with where it started. What comes out is a few
+//! facts about the line ("wins a rook", "fork after Nf6"), not prose about
+//! the move.
+
+use cozy_chess::{Board, Color, GameStatus, Piece};
+use serde::{Deserialize, Serialize};
+
+use crate::board_analysis::helpers::{parse_uci_move, piece_value};
+use crate::board_analysis::{
+    compute_king_safety, detect_tactics, AttackMap, TacticalContext, TacticalTag,
+};
+
+/// Plies of the best line played out; deeper moves are too speculative to
+/// explain anything.
+pub const MAX_EXPLAINED_PLIES: usize = 8;
+/// Tags kept for each move of the line.
+const TAGS_PER_MOVE: usize = 2;
+/// Tactics named in the points, at most.
+const TACTICS_IN_POINTS: usize = 2;
+/// Change in king exposure (0.0 safe to 1.0 exposed) worth a point.
+const KING_SAFETY_SHIFT: f32 = 0.2;
+
+/// One move of the best line.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LineStep {
+    pub san: String,
+    /// Played by the side whose best move is explained.
+    pub by_mover: bool,
+    /// Tactics on the board once the move is played.
+    pub tags: Vec<TacticalTag>,
+}
+
+/// The best line of a position and what it achieves.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MoveExplanation {
+    pub line: Vec<LineStep>,
+    /// Material balance in centipawns for the side to move, before the line
+    /// and at its end.
+    pub material_before: i32,
+    pub material_after: i32,
+    /// King exposure of the side to move, before and after the line.
+    pub own_king_before: f32,
+    pub own_king_after: f32,
+    /// King exposure of the opponent, before and after the line.
+    pub their_king_before: f32,
+    pub their_king_after: f32,
+    pub ends_in_mate: bool,
+    /// Short sentences, most important first.
+    pub points: Vec<String>,
+}
+
+impl MoveExplanation {
+    /// Material won over the line, in centipawns; negative when given up.
+    pub fn material_gain(&self) -> i32 {
+        self.material_after - self.material_before
+    }
+}
+
+/// Play out `line_uci` from `fen` and explain it. The line stops at the
+/// first move that does not parse or is illegal, and at
+/// [`MAX_EXPLAINED_PLIES`]. `None` if the position does not parse or the
+/// first move is unplayable.
+pub fn explain_best_line(fen: &str, line_uci: &[String]) -> Option<MoveExplanation> {
+    let start: Board = fen.parse().ok()?;
+    let mover = start.side_to_move();
+
+    let mut board = start.clone();
+    let mut line = Vec::new();
+    for uci in line_uci.iter().take(MAX_EXPLAINED_PLIES) {
+        let Some(mv) = parse_uci_move(&board, uci) else {
+            break;
+        };
+        let san = chess::format_move_as_san(&board, mv);
+        let mut after = board.clone();
+        after.play_unchecked(mv);
+
+        let before_attacks = AttackMap::compute(&board);
+        let after_attacks = AttackMap::compute(&after);
+        let ctx = TacticalContext {
+            before: &board,
+            after: &after,
+            mv: Some(mv),
+            side_to_move_before: board.side_to_move(),
+            before_attacks: &before_attacks,
+            after_attacks: &after_attacks,
+            eval_before: None,
+            eval_after: None,
+            best_line: None,
+        };
+        line.push(LineStep {
+            san,
+            by_mover: board.side_to_move() == mover,
+            tags: detect_tactics(&ctx, Some(TAGS_PER_MOVE)),
+        });
+        board = after;
+    }
+    if line.is_empty() {
+        return None;
+    }
+
+    let exposure = |board: &Board, color: Color| {
+        let safety = compute_king_safety(board);
+        match color {
+            Color::White => safety.white.exposure_score,
+            Color::Black => safety.black.exposure_score,
+        }
+    };
+    let mut explanation = MoveExplanation {
+        material_before: material_balance(&start, mover),
+        material_after: material_balance(&board, mover),
+        own_king_before: exposure(&start, mover),
+        own_king_after: exposure(&board, mover),
+        their_king_before: exposure(&start, !mover),
+        their_king_after: exposure(&board, !mover),
+        ends_in_mate: board.status() == GameStatus::Won,
+        line,
+        points: Vec::new(),
+    };
+    explanation.points = points(&explanation);
+    Some(explanation)
+}
+
+/// Material of `color` less the opponent's, in centipawns.
+fn material_balance(board: &Board, color: Color) -> i32 {
+    let side = |color: Color| -> i32 {
+        [
+            Piece::Pawn,
+            Piece::Knight,
+            Piece::Bishop,
+            Piece::Rook,
+            Piece::Queen,
+        ]
+        .into_iter()
+        .map(|piece| board.colored_pieces(color, piece).len() as i32 * piece_value(piece) as i32)
+        .sum()
+    };
+    side(color) - side(!color)
+}
+
+fn points(explanation: &MoveExplanation) -> Vec<String> {
+    let mut points = Vec::new();
+
+    if explanation.ends_in_mate {
+        let moves = explanation.line.iter().filter(|s| s.by_mover).count();
+        points.push(format!("Forces checkmate in {}", moves));
+    }
+
+    let gain = explanation.material_gain();
+    if gain >= piece_value(Piece::Pawn) as i32 {
+        points.push(format!("Wins {}", material_words(gain)));
+    } else if gain <= -(piece_value(Piece::Pawn) as i32) && !explanation.ends_in_mate {
+        points.push(format!(
+            "Gives up {} for the initiative",
+            material_words(-gain)
+        ));
+    }
+
+    let mut named = Vec::new();
+    for step in explanation.line.iter().filter(|s| s.by_mover) {
+        let Some(tag) = step.tags.first() else {
+            continue;
+        };
+        if named.contains(&tag.kind) || named.len() == TACTICS_IN_POINTS {
+            continue;
+        }
+        named.push(tag.kind.clone());
+        let name = tag.kind.name();
+        points.push(format!(
+            "{}{} after {}",
+            name[..1].to_uppercase(),
+            &name[1..],
+            step.san
+        ));
+    }
+
+    if explanation.their_king_after - explanation.their_king_before >= KING_SAFETY_SHIFT {
+        points.push("Opens up the opponent's king".to_string());
+    }
+    if explanation.own_king_before - explanation.own_king_after >= KING_SAFETY_SHIFT {
+        points.push("Makes the king safer".to_string());
+    } else if explanation.own_king_after - explanation.own_king_before >= KING_SAFETY_SHIFT {
+        points.push("Leaves the king more exposed".to_string());
+    }
+
+    if points.is_empty() {
+        points.push("Keeps the material level: a positional choice".to_string());
+    }
+    points
+}
+
+/// "a rook", "a minor piece", "2 pawns": the nearest piece for `cp` centipawns.
+fn material_words(cp: i32) -> String {
+    let nearest = [
+        (Piece::Queen, "a queen"),
+        (Piece::Rook, "a rook"),
+        (Piece::Bishop, "a minor piece"),
+    ]
+    .into_iter()
+    .find(|(piece, _)| (cp - piece_value(*piece) as i32).abs() <= 50);
+    match nearest {
+        Some((_, words)) => words.to_string(),
+        None => {
+            let pawns = (cp as f64 / 100.0).round() as i32;
+            if pawns == 1 {
+                "a pawn".to_string()
+            } else {
+                format!("{} pawns", pawns)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(moves: &[&str]) -> Vec<String> {
+        moves.iter().map(|m| m.to_string()).collect()
+    }
+
+    #[test]
+    fn test_explains_winning_a_hanging_piece() {
+        let explanation =
+            explain_best_line("4k3/8/8/3n4/8/5B2/8/4K3 w - - 0 1", &line(&["f3d5"])).unwrap();
+        assert_eq!(explanation.line[0].san, "Bxd5");
+        assert!(explanation.line[0].by_mover);
+        assert_eq!(explanation.material_gain(), 320);
+        assert_eq!(explanation.points[0], "Wins a minor piece");
+    }
+
+    #[test]
+    fn test_explains_a_mating_line() {
+        let explanation =
+            explain_best_line("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1", &line(&["a1a8"])).unwrap();
+        assert!(explanation.ends_in_mate);
+        assert_eq!(explanation.points[0], "Forces checkmate in 1");
+    }
+
+    #[test]
+    fn test_line_stops_at_the_first_unplayable_move() {
+        let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        let explanation = explain_best_line(start, &line(&["e2e4", "e2e4", "e7e5"])).unwrap();
+        assert_eq!(explanation.line.len(), 1);
+        assert!(explain_best_line(start, &line(&["e7e5"])).is_none());
+        assert!(explain_best_line(start, &[]).is_none());
+    }
+}
@@ -1,8 +1,24 @@
 pub mod advanced;
 pub mod board_analysis;
+pub mod endgame;
+pub mod explanation;
+pub mod opening;
+pub mod phase;
+pub mod review_diff;
 pub mod review_types;
+pub mod similarity;
+pub mod tablebase;
+pub mod time_analysis;
 
 pub use advanced::*;
 pub use board_analysis::*;
 pub use chess::{is_white_ply, AnalysisScore};
+pub use endgame::{classify_endgame, dominant_endgame, EndgameType};
+pub use explanation::{explain_best_line, LineStep, MoveExplanation};
+pub use opening::{classify_opening, Opening};
+pub use phase::{Phase, PhaseBoundaries};
+pub use review_diff::{diff_reviews, PlyChange, ReviewDiff, ReviewRevision};
 pub use review_types::*;
+pub use similarity::PositionSignature;
+pub use tablebase::{Tablebase, TablebaseError, TablebaseProbe, TablebaseWdl};
+pub use time_analysis::{simulate_clock, ClockSimulation, TimeControl};
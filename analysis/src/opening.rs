@@ -0,0 +1,457 @@
+//! ECO opening classification.
+//!
+//! The embedded tree is a list of named lines, each the moves that reach an
+//! opening's defining position. Lines are replayed once into a map keyed by
+//! position (placement, side to move and castling rights), so a game is
+//! classified by the positions it passes through and transpositions find the
+//! same name. A game's opening is that of the last position it reached that
+//! is in the tree.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use chess::parse_san;
+use cozy_chess::{Board, Move, Piece};
+use serde::{Deserialize, Serialize};
+
+/// The opening a game was classified as.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Opening {
+    /// ECO code, e.g. `C60`.
+    pub eco: String,
+    pub name: String,
+    /// Last ply (1-indexed) still in the opening tree.
+    pub ply: u32,
+}
+
+/// ECO code, name and SAN moves of each line in the tree. Where two lines
+/// reach the same position the first one names it.
+const OPENINGS: &[(&str, &str, &str)] = &[
+    ("A00", "Polish Opening", "b4"),
+    ("A00", "Grob Opening", "g4"),
+    ("A00", "Van't Kruijs Opening", "e3"),
+    ("A01", "Nimzo-Larsen Attack", "b3"),
+    ("A02", "Bird Opening", "f4"),
+    ("A03", "Bird Opening: Dutch Variation", "f4 d5"),
+    ("A04", "Zukertort Opening", "Nf3"),
+    ("A05", "Zukertort Opening", "Nf3 Nf6"),
+    ("A06", "Zukertort Opening", "Nf3 d5"),
+    ("A07", "King's Indian Attack", "Nf3 d5 g3"),
+    ("A09", "Réti Opening", "Nf3 d5 c4"),
+    ("A10", "English Opening", "c4"),
+    ("A13", "English Opening: Agincourt Defense", "c4 e6"),
+    ("A15", "English Opening: Anglo-Indian Defense", "c4 Nf6"),
+    ("A20", "English Opening: King's English Variation", "c4 e5"),
+    (
+        "A21",
+        "English Opening: King's English Variation, Reversed Sicilian",
+        "c4 e5 Nc3",
+    ),
+    ("A30", "English Opening: Symmetrical Variation", "c4 c5"),
+    ("A40", "Queen's Pawn Game", "d4"),
+    ("A40", "Englund Gambit", "d4 e5"),
+    ("A43", "Benoni Defense: Old Benoni", "d4 c5"),
+    ("A45", "Indian Defense", "d4 Nf6"),
+    ("A45", "Trompowsky Attack", "d4 Nf6 Bg5"),
+    ("A46", "Indian Defense: Knights Variation", "d4 Nf6 Nf3"),
+    ("A50", "Indian Defense: Normal Variation", "d4 Nf6 c4"),
+    ("A51", "Indian Defense: Budapest Defense", "d4 Nf6 c4 e5"),
+    ("A56", "Benoni Defense", "d4 Nf6 c4 c5"),
+    ("A57", "Benko Gambit", "d4 Nf6 c4 c5 d5 b5"),
+    (
+        "A60",
+        "Benoni Defense: Modern Variation",
+        "d4 Nf6 c4 c5 d5 e6",
+    ),
+    ("A80", "Dutch Defense", "d4 f5"),
+    ("B00", "King's Pawn Game", "e4"),
+    ("B00", "Nimzowitsch Defense", "e4 Nc6"),
+    ("B00", "Owen Defense", "e4 b6"),
+    ("B01", "Scandinavian Defense", "e4 d5"),
+    (
+        "B01",
+        "Scandinavian Defense: Modern Variation",
+        "e4 d5 exd5 Nf6",
+    ),
+    (
+        "B01",
+        "Scandinavian Defense: Main Line",
+        "e4 d5 exd5 Qxd5 Nc3 Qa5",
+    ),
+    ("B02", "Alekhine Defense", "e4 Nf6"),
+    (
+        "B03",
+        "Alekhine Defense: Four Pawns Attack",
+        "e4 Nf6 e5 Nd5 d4 d6 c4 Nb6 f4",
+    ),
+    ("B06", "Modern Defense", "e4 g6"),
+    ("B07", "Pirc Defense", "e4 d6 d4 Nf6"),
+    ("B10", "Caro-Kann Defense", "e4 c6"),
+    (
+        "B12",
+        "Caro-Kann Defense: Advance Variation",
+        "e4 c6 d4 d5 e5",
+    ),
+    (
+        "B13",
+        "Caro-Kann Defense: Exchange Variation",
+        "e4 c6 d4 d5 exd5 cxd5",
+    ),
+    ("B15", "Caro-Kann Defense", "e4 c6 d4 d5 Nc3"),
+    (
+        "B17",
+        "Caro-Kann Defense: Karpov Variation",
+        "e4 c6 d4 d5 Nc3 dxe4 Nxe4 Nd7",
+    ),
+    (
+        "B18",
+        "Caro-Kann Defense: Classical Variation",
+        "e4 c6 d4 d5 Nc3 dxe4 Nxe4 Bf5",
+    ),
+    ("B20", "Sicilian Defense", "e4 c5"),
+    (
+        "B21",
+        "Sicilian Defense: Smith-Morra Gambit",
+        "e4 c5 d4 cxd4 c3",
+    ),
+    ("B22", "Sicilian Defense: Alapin Variation", "e4 c5 c3"),
+    ("B23", "Sicilian Defense: Closed", "e4 c5 Nc3"),
+    ("B27", "Sicilian Defense", "e4 c5 Nf3"),
+    ("B30", "Sicilian Defense: Old Sicilian", "e4 c5 Nf3 Nc6"),
+    (
+        "B30",
+        "Sicilian Defense: Nyezhmetdinov-Rossolimo Attack",
+        "e4 c5 Nf3 Nc6 Bb5",
+    ),
+    (
+        "B32",
+        "Sicilian Defense: Open",
+        "e4 c5 Nf3 Nc6 d4 cxd4 Nxd4",
+    ),
+    (
+        "B34",
+        "Sicilian Defense: Accelerated Dragon",
+        "e4 c5 Nf3 Nc6 d4 cxd4 Nxd4 g6",
+    ),
+    (
+        "B33",
+        "Sicilian Defense: Lasker-Pelikan Variation",
+        "e4 c5 Nf3 Nc6 d4 cxd4 Nxd4 Nf6 Nc3 e5",
+    ),
+    ("B40", "Sicilian Defense: French Variation", "e4 c5 Nf3 e6"),
+    (
+        "B41",
+        "Sicilian Defense: Kan Variation",
+        "e4 c5 Nf3 e6 d4 cxd4 Nxd4 a6",
+    ),
+    (
+        "B44",
+        "Sicilian Defense: Taimanov Variation",
+        "e4 c5 Nf3 e6 d4 cxd4 Nxd4 Nc6",
+    ),
+    ("B50", "Sicilian Defense: Modern Variations", "e4 c5 Nf3 d6"),
+    (
+        "B51",
+        "Sicilian Defense: Moscow Variation",
+        "e4 c5 Nf3 d6 Bb5+",
+    ),
+    ("B54", "Sicilian Defense: Open", "e4 c5 Nf3 d6 d4 cxd4 Nxd4"),
+    (
+        "B56",
+        "Sicilian Defense: Classical Variation",
+        "e4 c5 Nf3 d6 d4 cxd4 Nxd4 Nf6 Nc3 Nc6",
+    ),
+    (
+        "B70",
+        "Sicilian Defense: Dragon Variation",
+        "e4 c5 Nf3 d6 d4 cxd4 Nxd4 Nf6 Nc3 g6",
+    ),
+    (
+        "B80",
+        "Sicilian Defense: Scheveningen Variation",
+        "e4 c5 Nf3 d6 d4 cxd4 Nxd4 Nf6 Nc3 e6",
+    ),
+    (
+        "B90",
+        "Sicilian Defense: Najdorf Variation",
+        "e4 c5 Nf3 d6 d4 cxd4 Nxd4 Nf6 Nc3 a6",
+    ),
+    ("C00", "French Defense", "e4 e6"),
+    (
+        "C01",
+        "French Defense: Exchange Variation",
+        "e4 e6 d4 d5 exd5",
+    ),
+    ("C02", "French Defense: Advance Variation", "e4 e6 d4 d5 e5"),
+    (
+        "C03",
+        "French Defense: Tarrasch Variation",
+        "e4 e6 d4 d5 Nd2",
+    ),
+    (
+        "C10",
+        "French Defense: Paulsen Variation",
+        "e4 e6 d4 d5 Nc3",
+    ),
+    (
+        "C10",
+        "French Defense: Rubinstein Variation",
+        "e4 e6 d4 d5 Nc3 dxe4",
+    ),
+    (
+        "C11",
+        "French Defense: Classical Variation",
+        "e4 e6 d4 d5 Nc3 Nf6",
+    ),
+    (
+        "C15",
+        "French Defense: Winawer Variation",
+        "e4 e6 d4 d5 Nc3 Bb4",
+    ),
+    ("C20", "King's Pawn Game", "e4 e5"),
+    ("C23", "Bishop's Opening", "e4 e5 Bc4"),
+    ("C25", "Vienna Game", "e4 e5 Nc3"),
+    ("C30", "King's Gambit", "e4 e5 f4"),
+    ("C33", "King's Gambit Accepted", "e4 e5 f4 exf4"),
+    ("C40", "King's Knight Opening", "e4 e5 Nf3"),
+    ("C40", "Latvian Gambit", "e4 e5 Nf3 f5"),
+    ("C41", "Philidor Defense", "e4 e5 Nf3 d6"),
+    ("C42", "Petrov's Defense", "e4 e5 Nf3 Nf6"),
+    (
+        "C44",
+        "King's Knight Opening: Normal Variation",
+        "e4 e5 Nf3 Nc6",
+    ),
+    ("C44", "Ponziani Opening", "e4 e5 Nf3 Nc6 c3"),
+    ("C44", "Scotch Game", "e4 e5 Nf3 Nc6 d4"),
+    ("C45", "Scotch Game", "e4 e5 Nf3 Nc6 d4 exd4 Nxd4"),
+    ("C46", "Three Knights Opening", "e4 e5 Nf3 Nc6 Nc3"),
+    ("C47", "Four Knights Game", "e4 e5 Nf3 Nc6 Nc3 Nf6"),
+    (
+        "C48",
+        "Four Knights Game: Spanish Variation",
+        "e4 e5 Nf3 Nc6 Nc3 Nf6 Bb5",
+    ),
+    ("C50", "Italian Game", "e4 e5 Nf3 Nc6 Bc4"),
+    (
+        "C50",
+        "Italian Game: Hungarian Defense",
+        "e4 e5 Nf3 Nc6 Bc4 Be7",
+    ),
+    ("C50", "Italian Game: Giuoco Piano", "e4 e5 Nf3 Nc6 Bc4 Bc5"),
+    (
+        "C51",
+        "Italian Game: Evans Gambit",
+        "e4 e5 Nf3 Nc6 Bc4 Bc5 b4",
+    ),
+    (
+        "C53",
+        "Italian Game: Classical Variation",
+        "e4 e5 Nf3 Nc6 Bc4 Bc5 c3",
+    ),
+    (
+        "C55",
+        "Italian Game: Two Knights Defense",
+        "e4 e5 Nf3 Nc6 Bc4 Nf6",
+    ),
+    (
+        "C57",
+        "Italian Game: Two Knights Defense, Knight Attack",
+        "e4 e5 Nf3 Nc6 Bc4 Nf6 Ng5",
+    ),
+    ("C60", "Ruy Lopez", "e4 e5 Nf3 Nc6 Bb5"),
+    ("C62", "Ruy Lopez: Steinitz Defense", "e4 e5 Nf3 Nc6 Bb5 d6"),
+    (
+        "C63",
+        "Ruy Lopez: Schliemann Defense",
+        "e4 e5 Nf3 Nc6 Bb5 f5",
+    ),
+    ("C65", "Ruy Lopez: Berlin Defense", "e4 e5 Nf3 Nc6 Bb5 Nf6"),
+    ("C70", "Ruy Lopez: Morphy Defense", "e4 e5 Nf3 Nc6 Bb5 a6"),
+    (
+        "C68",
+        "Ruy Lopez: Exchange Variation",
+        "e4 e5 Nf3 Nc6 Bb5 a6 Bxc6",
+    ),
+    (
+        "C78",
+        "Ruy Lopez: Morphy Defense",
+        "e4 e5 Nf3 Nc6 Bb5 a6 Ba4 Nf6 O-O",
+    ),
+    (
+        "C80",
+        "Ruy Lopez: Open",
+        "e4 e5 Nf3 Nc6 Bb5 a6 Ba4 Nf6 O-O Nxe4",
+    ),
+    (
+        "C84",
+        "Ruy Lopez: Closed",
+        "e4 e5 Nf3 Nc6 Bb5 a6 Ba4 Nf6 O-O Be7",
+    ),
+    ("D00", "Queen's Pawn Game", "d4 d5"),
+    ("D00", "Blackmar-Diemer Gambit", "d4 d5 e4"),
+    (
+        "D00",
+        "Queen's Pawn Game: Accelerated London System",
+        "d4 d5 Bf4",
+    ),
+    ("D06", "Queen's Gambit", "d4 d5 c4"),
+    (
+        "D07",
+        "Queen's Gambit Declined: Chigorin Defense",
+        "d4 d5 c4 Nc6",
+    ),
+    (
+        "D08",
+        "Queen's Gambit Declined: Albin Countergambit",
+        "d4 d5 c4 e5",
+    ),
+    ("D10", "Slav Defense", "d4 d5 c4 c6"),
+    ("D20", "Queen's Gambit Accepted", "d4 d5 c4 dxc4"),
+    ("D30", "Queen's Gambit Declined", "d4 d5 c4 e6"),
+    ("D43", "Semi-Slav Defense", "d4 d5 c4 e6 Nc3 Nf6 Nf3 c6"),
+    ("D80", "Grünfeld Defense", "d4 Nf6 c4 g6 Nc3 d5"),
+    (
+        "D85",
+        "Grünfeld Defense: Exchange Variation",
+        "d4 Nf6 c4 g6 Nc3 d5 cxd5 Nxd5",
+    ),
+    ("E00", "Indian Defense", "d4 Nf6 c4 e6"),
+    ("E01", "Catalan Opening", "d4 Nf6 c4 e6 g3"),
+    ("E11", "Bogo-Indian Defense", "d4 Nf6 c4 e6 Nf3 Bb4+"),
+    ("E12", "Queen's Indian Defense", "d4 Nf6 c4 e6 Nf3 b6"),
+    ("E20", "Nimzo-Indian Defense", "d4 Nf6 c4 e6 Nc3 Bb4"),
+    (
+        "E32",
+        "Nimzo-Indian Defense: Classical Variation",
+        "d4 Nf6 c4 e6 Nc3 Bb4 Qc2",
+    ),
+    ("E60", "King's Indian Defense", "d4 Nf6 c4 g6"),
+    ("E61", "King's Indian Defense", "d4 Nf6 c4 g6 Nc3 Bg7"),
+    (
+        "E70",
+        "King's Indian Defense: Normal Variation",
+        "d4 Nf6 c4 g6 Nc3 Bg7 e4 d6",
+    ),
+    (
+        "E80",
+        "King's Indian Defense: Sämisch Variation",
+        "d4 Nf6 c4 g6 Nc3 Bg7 e4 d6 f3",
+    ),
+];
+
+/// The part of a FEN that identifies a position for classification.
+fn position_key(fen: &str) -> String {
+    fen.split_whitespace().take(3).collect::<Vec<_>>().join(" ")
+}
+
+/// The move `san` stands for in `board`; `O-O` is the king taking its
+/// kingside rook.
+fn find_move(board: &Board, san: &str) -> Option<Move> {
+    if san != "O-O" {
+        return parse_san(board, san);
+    }
+    let mut found = None;
+    board.generate_moves(|moves| {
+        found = moves.into_iter().find(|mv| {
+            board.piece_on(mv.to) == Some(Piece::Rook)
+                && board.color_on(mv.to) == Some(board.side_to_move())
+                && mv.to.file() > mv.from.file()
+        });
+        found.is_some()
+    });
+    found
+}
+
+/// The position after each move of `sans` from the start position, or `None`
+/// if one of them is not legal.
+fn play_line(sans: &str) -> Option<Vec<Board>> {
+    let mut board = Board::default();
+    let mut boards = Vec::new();
+    for san in sans.split_whitespace() {
+        board.play_unchecked(find_move(&board, san)?);
+        boards.push(board.clone());
+    }
+    Some(boards)
+}
+
+/// Position key to index in [`OPENINGS`], built on first use.
+fn tree() -> &'static HashMap<String, usize> {
+    static TREE: OnceLock<HashMap<String, usize>> = OnceLock::new();
+    TREE.get_or_init(|| {
+        let mut tree = HashMap::new();
+        for (index, (_, _, moves)) in OPENINGS.iter().enumerate() {
+            if let Some(board) = play_line(moves).and_then(|boards| boards.last().cloned()) {
+                tree.entry(position_key(&board.to_string()))
+                    .or_insert(index);
+            }
+        }
+        tree
+    })
+}
+
+/// Classify a game from the FEN after each of its moves, in order.
+/// Returns `None` if not even its first move is in the tree.
+pub fn classify_opening<'a>(fens: impl IntoIterator<Item = &'a str>) -> Option<Opening> {
+    let tree = tree();
+    let mut opening = None;
+    for (i, fen) in fens.into_iter().enumerate() {
+        if let Some(&index) = tree.get(&position_key(fen)) {
+            let (eco, name, _) = OPENINGS[index];
+            opening = Some(Opening {
+                eco: eco.to_string(),
+                name: name.to_string(),
+                ply: i as u32 + 1,
+            });
+        }
+    }
+    opening
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classify(sans: &str) -> Option<Opening> {
+        let fens: Vec<String> = play_line(sans)
+            .unwrap()
+            .iter()
+            .map(|board| board.to_string())
+            .collect();
+        classify_opening(fens.iter().map(String::as_str))
+    }
+
+    #[test]
+    fn test_every_line_names_its_own_position() {
+        for (_, name, moves) in OPENINGS {
+            assert!(play_line(moves).is_some(), "{}: {}", name, moves);
+        }
+        assert_eq!(tree().len(), OPENINGS.len());
+    }
+
+    #[test]
+    fn test_classifies_deepest_known_position() {
+        let opening = classify("e4 e5 Nf3 Nc6 Bb5 a6 Ba4 Nf6 O-O Be7 Re1 b5").unwrap();
+        assert_eq!(opening.eco, "C84");
+        assert_eq!(opening.name, "Ruy Lopez: Closed");
+        assert_eq!(opening.ply, 10);
+
+        let opening = classify("e4 c5 Nf3 d6 d4 cxd4 Nxd4 Nf6 Nc3 a6 Be3").unwrap();
+        assert_eq!(opening.eco, "B90");
+        assert_eq!(opening.ply, 10);
+    }
+
+    #[test]
+    fn test_transpositions_find_the_same_opening() {
+        // The Four Knights reached with the knights in another order
+        let opening = classify("Nf3 Nc6 e4 Nf6 Nc3 e5").unwrap();
+        assert_eq!(opening.eco, "C47");
+        assert_eq!(opening.ply, 6);
+    }
+
+    #[test]
+    fn test_unknown_start() {
+        assert_eq!(classify("a3 a6"), None);
+        assert_eq!(classify_opening(std::iter::empty()), None);
+    }
+}
@@ -0,0 +1,176 @@
+//! Opening, middlegame and endgame.
+//!
+//! Where a game's phases change is read from its positions rather than its
+//! move number: the middlegame starts once either side has cleared its back
+//! rank or pieces have come off, the endgame once few pieces are left.
+//! Without positions to read, the phases fall back to fixed ply ranges.
+
+use cozy_chess::{Board, Color, Piece, Rank};
+use serde::{Deserialize, Serialize};
+
+use crate::review_types::PositionReview;
+
+/// Pieces other than kings and pawns, both sides together, at or below which
+/// the middlegame has started.
+const MIDDLEGAME_PIECES: usize = 10;
+/// Own pieces left on a back rank below which that side has developed.
+const DEVELOPED_BACK_RANK: usize = 4;
+/// Pieces other than kings and pawns at or below which it is an endgame.
+const ENDGAME_PIECES: usize = 6;
+/// First ply of each phase when there are no positions to read.
+const FALLBACK_MIDDLEGAME_PLY: u32 = 31;
+const FALLBACK_ENDGAME_PLY: u32 = 71;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Phase {
+    Opening,
+    Middlegame,
+    Endgame,
+}
+
+impl Phase {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Opening => "Opening",
+            Self::Middlegame => "Middlegame",
+            Self::Endgame => "Endgame",
+        }
+    }
+}
+
+/// First ply of the middlegame and of the endgame; `None` for a phase the
+/// game never reached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct PhaseBoundaries {
+    pub middlegame_from: Option<u32>,
+    pub endgame_from: Option<u32>,
+}
+
+impl PhaseBoundaries {
+    /// Fixed ply ranges: opening to ply 30, middlegame to ply 70.
+    pub fn by_ply() -> Self {
+        Self {
+            middlegame_from: Some(FALLBACK_MIDDLEGAME_PLY),
+            endgame_from: Some(FALLBACK_ENDGAME_PLY),
+        }
+    }
+
+    /// Boundaries from the FEN after each ply, in ply order. Falls back to
+    /// [`PhaseBoundaries::by_ply`] when none of the FENs parse.
+    pub fn detect<'a>(positions: impl IntoIterator<Item = (u32, &'a str)>) -> Self {
+        let mut boundaries = Self::default();
+        let mut any_board = false;
+        for (ply, fen) in positions {
+            let Ok(board) = fen.parse::<Board>() else {
+                continue;
+            };
+            any_board = true;
+            if boundaries.middlegame_from.is_none() && is_middlegame(&board) {
+                boundaries.middlegame_from = Some(ply);
+            }
+            if is_endgame(&board) {
+                boundaries.endgame_from = Some(ply);
+                break;
+            }
+        }
+        if !any_board {
+            return Self::by_ply();
+        }
+        // A game can go straight from the opening to the endgame
+        boundaries.middlegame_from = boundaries.middlegame_from.or(boundaries.endgame_from);
+        boundaries
+    }
+
+    /// Boundaries of a reviewed game.
+    pub fn of_review(positions: &[PositionReview]) -> Self {
+        Self::detect(positions.iter().map(|p| (p.ply, p.fen.as_str())))
+    }
+
+    /// Phase the move at `ply` was played in.
+    pub fn phase_of(&self, ply: u32) -> Phase {
+        if self.endgame_from.is_some_and(|from| ply >= from) {
+            Phase::Endgame
+        } else if self.middlegame_from.is_some_and(|from| ply >= from) {
+            Phase::Middlegame
+        } else {
+            Phase::Opening
+        }
+    }
+
+    /// Each phase the game reached with its first ply, in order. The opening
+    /// starts at ply 0, the starting position.
+    pub fn starts(&self) -> Vec<(Phase, u32)> {
+        let mut starts = vec![(Phase::Opening, 0)];
+        // An empty middlegame is no phase of its own
+        let middlegame = self
+            .middlegame_from
+            .filter(|&ply| Some(ply) != self.endgame_from);
+        starts.extend(middlegame.map(|ply| (Phase::Middlegame, ply)));
+        starts.extend(self.endgame_from.map(|ply| (Phase::Endgame, ply)));
+        starts
+    }
+}
+
+fn pieces_left(board: &Board) -> usize {
+    (board.occupied() & !board.pieces(Piece::Pawn) & !board.pieces(Piece::King)).len() as usize
+}
+
+fn is_middlegame(board: &Board) -> bool {
+    let back_rank = |color: Color| {
+        (board.colors(color) & Rank::First.relative_to(color).bitboard()).len() as usize
+    };
+    pieces_left(board) <= MIDDLEGAME_PIECES
+        || back_rank(Color::White) < DEVELOPED_BACK_RANK
+        || back_rank(Color::Black) < DEVELOPED_BACK_RANK
+}
+
+pub(crate) fn is_endgame(board: &Board) -> bool {
+    pieces_left(board) <= ENDGAME_PIECES
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phases_fall_back_to_ply_ranges_without_positions() {
+        let phases = PhaseBoundaries::detect([(1, ""), (2, "")]);
+        assert_eq!(phases, PhaseBoundaries::by_ply());
+        assert_eq!(phases.phase_of(30), Phase::Opening);
+        assert_eq!(phases.phase_of(31), Phase::Middlegame);
+        assert_eq!(phases.phase_of(71), Phase::Endgame);
+    }
+
+    #[test]
+    fn test_phases_follow_development_and_material() {
+        let positions = [
+            (
+                1,
+                "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1",
+            ),
+            // White's queen and minor pieces are out: three left on rank 1
+            (
+                2,
+                "r1bqkbnr/pppppppp/2n5/8/2BPP3/2N2N2/PPPQ1PPP/R3K2R b - - 0 1",
+            ),
+            (3, "4k3/5ppp/8/8/8/8/5PPP/3RK3 w - - 0 1"),
+        ];
+        let phases = PhaseBoundaries::detect(positions);
+        assert_eq!(phases.middlegame_from, Some(2));
+        assert_eq!(phases.endgame_from, Some(3));
+        assert_eq!(
+            phases.starts(),
+            vec![
+                (Phase::Opening, 0),
+                (Phase::Middlegame, 2),
+                (Phase::Endgame, 3)
+            ]
+        );
+
+        // A short game never leaves the opening
+        let phases = PhaseBoundaries::detect(positions[..1].iter().copied());
+        assert_eq!(phases, PhaseBoundaries::default());
+        assert_eq!(phases.phase_of(50), Phase::Opening);
+        assert_eq!(phases.starts(), vec![(Phase::Opening, 0)]);
+    }
+}
@@ -0,0 +1,183 @@
+//! Comparing two analysis passes over the same game.
+//!
+//! A game reviewed again, deeper or with another engine, keeps its earlier
+//! pass as a revision. Comparing a revision with the current review shows
+//! which moves the deeper look judged differently: a shallow review that
+//! agrees with a deep one on most plies can be trusted, one that does not
+//! cannot. Only plies analysed in both passes are compared.
+
+use serde::{Deserialize, Serialize};
+
+use crate::review_types::{GameReview, MoveClassification, PositionReview};
+
+/// An earlier pass of a game's review, kept when the game is analysed again.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewRevision {
+    pub game_id: String,
+    /// Numbered from 1, oldest first.
+    pub revision: u32,
+    /// Unix time in seconds when the pass was replaced.
+    pub archived_at: u64,
+    pub analysis_depth: u32,
+    pub completed_at: Option<u64>,
+    pub white_accuracy: Option<f64>,
+    pub black_accuracy: Option<f64>,
+    pub positions: Vec<PositionReview>,
+}
+
+impl ReviewRevision {
+    /// Snapshot `review` as revision number `revision`.
+    pub fn of(review: &GameReview, revision: u32, archived_at: u64) -> Self {
+        Self {
+            game_id: review.game_id.clone(),
+            revision,
+            archived_at,
+            analysis_depth: review.analysis_depth,
+            completed_at: review.completed_at,
+            white_accuracy: review.white_accuracy,
+            black_accuracy: review.black_accuracy,
+            positions: review.positions.clone(),
+        }
+    }
+}
+
+/// A ply judged differently by two passes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlyChange {
+    pub ply: u32,
+    pub played_san: String,
+    pub old_classification: MoveClassification,
+    pub new_classification: MoveClassification,
+    pub old_best_move_san: String,
+    pub new_best_move_san: String,
+    pub old_cp_loss: i32,
+    pub new_cp_loss: i32,
+    pub old_depth: u32,
+    pub new_depth: u32,
+}
+
+impl PlyChange {
+    pub fn classification_changed(&self) -> bool {
+        self.old_classification != self.new_classification
+    }
+
+    pub fn best_move_changed(&self) -> bool {
+        self.old_best_move_san != self.new_best_move_san
+    }
+}
+
+/// Where two passes over a game disagree.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReviewDiff {
+    /// Plies analysed in both passes.
+    pub compared_plies: u32,
+    pub classification_changes: u32,
+    pub best_move_changes: u32,
+    /// The plies that changed, in order.
+    pub changes: Vec<PlyChange>,
+}
+
+impl ReviewDiff {
+    /// Compared plies on which both passes agree.
+    pub fn agreeing_plies(&self) -> u32 {
+        self.compared_plies - self.changes.len() as u32
+    }
+}
+
+/// Compare the positions of an older pass with those of a newer one, both in
+/// ply order. A ply counts as changed when its classification or best move
+/// differs.
+pub fn diff_reviews(old: &[PositionReview], new: &[PositionReview]) -> ReviewDiff {
+    let mut diff = ReviewDiff::default();
+    for after in new {
+        let Some(before) = old.iter().find(|p| p.ply == after.ply) else {
+            continue;
+        };
+        diff.compared_plies += 1;
+        let change = PlyChange {
+            ply: after.ply,
+            played_san: after.played_san.clone(),
+            old_classification: before.classification,
+            new_classification: after.classification,
+            old_best_move_san: before.best_move_san.clone(),
+            new_best_move_san: after.best_move_san.clone(),
+            old_cp_loss: before.cp_loss,
+            new_cp_loss: after.cp_loss,
+            old_depth: before.depth,
+            new_depth: after.depth,
+        };
+        if !change.classification_changed() && !change.best_move_changed() {
+            continue;
+        }
+        if change.classification_changed() {
+            diff.classification_changes += 1;
+        }
+        if change.best_move_changed() {
+            diff.best_move_changes += 1;
+        }
+        diff.changes.push(change);
+    }
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chess::AnalysisScore;
+
+    fn position(ply: u32, best_san: &str, cp_loss: i32, depth: u32) -> PositionReview {
+        PositionReview {
+            ply,
+            fen: String::new(),
+            played_san: "Nf3".into(),
+            best_move_san: best_san.into(),
+            best_move_uci: String::new(),
+            eval_before: AnalysisScore::Centipawns(0),
+            eval_after: AnalysisScore::Centipawns(0),
+            eval_best: AnalysisScore::Centipawns(0),
+            classification: MoveClassification::from_cp_loss(cp_loss, false),
+            cp_loss,
+            pv: vec![],
+            depth,
+            clock_ms: None,
+            excluded: None,
+            only_move: false,
+        }
+    }
+
+    #[test]
+    fn test_diff_counts_classification_and_best_move_changes() {
+        let old = vec![
+            position(1, "Nf3", 0, 10),
+            position(2, "e5", 0, 10),
+            position(3, "d4", 40, 10),
+        ];
+        let new = vec![
+            position(1, "Nf3", 0, 22),
+            position(2, "c5", 0, 22),
+            position(3, "d4", 250, 22),
+        ];
+        let diff = diff_reviews(&old, &new);
+        assert_eq!(diff.compared_plies, 3);
+        assert_eq!(diff.agreeing_plies(), 1);
+        assert_eq!(diff.best_move_changes, 1);
+        assert_eq!(diff.classification_changes, 1);
+        assert_eq!(
+            diff.changes.iter().map(|c| c.ply).collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+        assert_eq!(
+            diff.changes[1].new_classification,
+            MoveClassification::Mistake
+        );
+    }
+
+    #[test]
+    fn test_diff_skips_plies_missing_from_either_pass() {
+        let old = vec![position(1, "Nf3", 0, 10)];
+        let new = vec![position(1, "Nf3", 0, 22), position(2, "c5", 300, 22)];
+        let diff = diff_reviews(&old, &new);
+        assert_eq!(diff.compared_plies, 1);
+        assert!(diff.changes.is_empty());
+    }
+}
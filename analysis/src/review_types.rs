@@ -1,3 +1,5 @@
+use crate::endgame::EndgameType;
+use crate::opening::Opening;
 use chess::is_white_ply;
 use chess::AnalysisScore;
 use serde::{Deserialize, Serialize};
@@ -5,8 +7,10 @@ use serde::{Deserialize, Serialize};
 /// Classification of a move's quality relative to the engine's best.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum MoveClassification {
-    /// Better than engine expected (rare tactical find).
+    /// Best move that is also a sound sacrifice.
     Brilliant,
+    /// Best move when every other move loses the advantage (only move).
+    Great,
     /// Matches or very close to engine's best move (0 cp loss).
     Best,
     /// Within 10 cp of best.
@@ -19,6 +23,9 @@ pub enum MoveClassification {
     Mistake,
     /// 300+ cp worse than best.
     Blunder,
+    /// A mistake or blunder right after the opponent's, letting their error
+    /// go unpunished.
+    Miss,
     /// Only one legal move available.
     Forced,
     /// Opening book move (first N plies, optional).
@@ -42,22 +49,147 @@ impl MoveClassification {
         }
     }
 
+    /// Classify from centipawn loss and what else is known about the move.
+    /// A best (or excellent) move that sacrifices is Brilliant, a best move
+    /// that is the only one holding is Great, and a mistake or blunder
+    /// answering the opponent's is a Miss.
+    pub fn classify(cp_loss: i32, context: MoveContext) -> Self {
+        match Self::from_cp_loss(cp_loss, context.is_forced) {
+            Self::Best | Self::Excellent if context.is_sacrifice => Self::Brilliant,
+            Self::Best if context.is_only_move => Self::Great,
+            Self::Mistake | Self::Blunder if context.opponent_erred => Self::Miss,
+            classification => classification,
+        }
+    }
+
+    /// Inaccuracies and worse.
+    pub fn is_error(self) -> bool {
+        matches!(
+            self,
+            Self::Inaccuracy | Self::Mistake | Self::Blunder | Self::Miss
+        )
+    }
+
     /// NAG (Numeric Annotation Glyph) for PGN export.
     pub fn to_nag(self) -> Option<u8> {
         match self {
             Self::Brilliant => Some(3), // !!
+            Self::Great => Some(8),     // singular move
             Self::Best => None,
             Self::Excellent => Some(1), // !
             Self::Good => None,
             Self::Inaccuracy => Some(6), // ?!
             Self::Mistake => Some(2),    // ?
             Self::Blunder => Some(4),    // ??
+            Self::Miss => Some(2),       // ?
             Self::Forced => None,
             Self::Book => None,
         }
     }
 }
 
+/// The second-best move losing this much more than the best makes the best
+/// the only move.
+pub const ONLY_MOVE_GAP_CP: i32 = 150;
+
+/// Below this the second-best move no longer keeps a clear advantage, so an
+/// only move still matters.
+const ONLY_MOVE_WINNING_CP: i32 = 200;
+
+/// What besides centipawn loss goes into a move's classification.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MoveContext {
+    /// Only one legal move.
+    pub is_forced: bool,
+    /// The move gives up material, per the sacrifice detector.
+    pub is_sacrifice: bool,
+    /// No other move keeps the evaluation, see [`is_only_move`].
+    pub is_only_move: bool,
+    /// The opponent's previous move was a mistake or blunder.
+    pub opponent_erred: bool,
+}
+
+/// Whether the engine's best move is the only one: its second-best, both
+/// from the mover's perspective, is [`ONLY_MOVE_GAP_CP`] worse and no longer
+/// clearly winning.
+pub fn is_only_move(best: &AnalysisScore, second: &AnalysisScore) -> bool {
+    let (best, second) = (best.to_cp(), second.to_cp());
+    best - second >= ONLY_MOVE_GAP_CP && second < ONLY_MOVE_WINNING_CP
+}
+
+/// Decisiveness threshold for [`AccuracyExclusions::decided_cp`] when none is
+/// given: eight pawns either way.
+pub const DEFAULT_DECIDED_CP: i32 = 800;
+
+/// Why a move was left out of its side's accuracy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AccuracyExclusion {
+    /// Still in the opening tree.
+    Book,
+    /// The only legal move.
+    Forced,
+    /// Played where the game was already decided either way.
+    Decided,
+}
+
+impl AccuracyExclusion {
+    pub const ALL: [Self; 3] = [Self::Book, Self::Forced, Self::Decided];
+
+    /// Stable name for storage.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::Book => "book",
+            Self::Forced => "forced",
+            Self::Decided => "decided",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|e| e.key() == key)
+    }
+}
+
+/// Which moves are left out of the accuracy. All off by default, so every
+/// analysed move counts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccuracyExclusions {
+    /// Moves up to the last ply in the opening tree.
+    pub book: bool,
+    /// Moves with no alternative.
+    pub forced: bool,
+    /// Moves played where the evaluation before was at least this many
+    /// centipawns either way, e.g. [`DEFAULT_DECIDED_CP`].
+    pub decided_cp: Option<i32>,
+}
+
+impl AccuracyExclusions {
+    /// Why `position` is left out, given the last book ply of its game.
+    pub fn exclusion(&self, position: &PositionReview, book_ply: u32) -> Option<AccuracyExclusion> {
+        if self.book && position.ply <= book_ply {
+            Some(AccuracyExclusion::Book)
+        } else if self.forced && position.classification == MoveClassification::Forced {
+            Some(AccuracyExclusion::Forced)
+        } else if self
+            .decided_cp
+            .is_some_and(|threshold| position.eval_before.to_cp().abs() >= threshold)
+        {
+            Some(AccuracyExclusion::Decided)
+        } else {
+            None
+        }
+    }
+}
+
+/// How many of a side's analysed moves its accuracy counts, and why the
+/// others were left out.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AccuracyCounts {
+    pub counted: u32,
+    pub book: u32,
+    pub forced: u32,
+    pub decided: u32,
+}
+
 /// Analysis result for a single position/ply.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PositionReview {
@@ -75,6 +207,15 @@ pub struct PositionReview {
     pub depth: u32,
     #[serde(default)]
     pub clock_ms: Option<u64>,
+    /// Set when the move is left out of the accuracy.
+    #[serde(default)]
+    pub excluded: Option<AccuracyExclusion>,
+    /// One move alone held the position, per the engine's second line (see
+    /// [`is_only_move`]), whether or not it was played. A move that misses
+    /// it loses a lot of centipawns from a position where every other move
+    /// would have too.
+    #[serde(default)]
+    pub only_move: bool,
 }
 
 /// Status of a review job.
@@ -100,6 +241,84 @@ pub struct GameReview {
     pub started_at: Option<u64>,
     pub completed_at: Option<u64>,
     pub winner: Option<String>, // "White", "Black", "Draw"
+    /// ECO classification, set once the review completes.
+    #[serde(default)]
+    pub opening: Option<Opening>,
+    /// Ending the game spent the most positions in, set once the review
+    /// completes.
+    #[serde(default)]
+    pub endgame: Option<EndgameType>,
+}
+
+/// A run of consecutive plies analysed at the same depth.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CoveredRange {
+    pub from_ply: u32,
+    pub to_ply: u32,
+    pub depth: u32,
+}
+
+impl GameReview {
+    /// The analysed plies as ranges, in order. A review of part of a game,
+    /// or one with a stretch re-analysed deeper, has several.
+    pub fn coverage(&self) -> Vec<CoveredRange> {
+        let mut plies: Vec<(u32, u32)> = self.positions.iter().map(|p| (p.ply, p.depth)).collect();
+        plies.sort_unstable();
+        let mut ranges: Vec<CoveredRange> = Vec::new();
+        for (ply, depth) in plies {
+            match ranges.last_mut() {
+                Some(last) if last.to_ply + 1 == ply && last.depth == depth => last.to_ply = ply,
+                _ => ranges.push(CoveredRange {
+                    from_ply: ply,
+                    to_ply: ply,
+                    depth,
+                }),
+            }
+        }
+        ranges
+    }
+
+    /// Add the analysis of a ply, replacing any earlier analysis of it and
+    /// keeping the positions in ply order.
+    pub fn set_position(&mut self, position: PositionReview) {
+        match self
+            .positions
+            .binary_search_by_key(&position.ply, |p| p.ply)
+        {
+            Ok(i) => self.positions[i] = position,
+            Err(i) => self.positions.insert(i, position),
+        }
+        self.analyzed_plies = self.positions.len() as u32;
+    }
+
+    /// Mark the moves `exclusions` leave out, book moves by the review's
+    /// opening, and compute both sides' accuracy over the rest.
+    pub fn apply_accuracy_exclusions(&mut self, exclusions: AccuracyExclusions) {
+        let book_ply = self.opening.as_ref().map_or(0, |o| o.ply);
+        for position in &mut self.positions {
+            position.excluded = exclusions.exclusion(position, book_ply);
+        }
+        self.white_accuracy = Some(compute_accuracy(&self.positions, true));
+        self.black_accuracy = Some(compute_accuracy(&self.positions, false));
+    }
+
+    /// What one side's accuracy was computed over.
+    pub fn accuracy_counts(&self, is_white: bool) -> AccuracyCounts {
+        let mut counts = AccuracyCounts::default();
+        for position in self
+            .positions
+            .iter()
+            .filter(|p| is_white_ply(p.ply) == is_white)
+        {
+            match position.excluded {
+                None => counts.counted += 1,
+                Some(AccuracyExclusion::Book) => counts.book += 1,
+                Some(AccuracyExclusion::Forced) => counts.forced += 1,
+                Some(AccuracyExclusion::Decided) => counts.decided += 1,
+            }
+        }
+        counts
+    }
 }
 
 /// Compute accuracy percentage for one side.
@@ -112,12 +331,14 @@ pub struct GameReview {
 ///
 /// Individual cp_loss values are capped at 1000 to prevent mate-related outliers
 /// (where to_cp() returns 20000+) from destroying the average.
+///
+/// Moves marked [`PositionReview::excluded`] are not counted.
 pub fn compute_accuracy(positions: &[PositionReview], is_white: bool) -> f64 {
     // Plies are 1-indexed: odd plies (1, 3, 5, ...) are white moves,
     // even plies (2, 4, 6, ...) are black moves.
     let side_positions: Vec<&PositionReview> = positions
         .iter()
-        .filter(|p| is_white_ply(p.ply) == is_white)
+        .filter(|p| is_white_ply(p.ply) == is_white && p.excluded.is_none())
         .collect();
 
     if side_positions.is_empty() {
@@ -134,6 +355,108 @@ pub fn compute_accuracy(positions: &[PositionReview], is_white: bool) -> f64 {
     accuracy.clamp(0.0, 100.0)
 }
 
+/// Minimum full-strength advantage (cp, mover's perspective) that counts as a win.
+pub const MISSED_WIN_THRESHOLD_CP: i32 = 200;
+
+/// A ply where full-strength analysis saw a winning move but the playing engine
+/// chose a move that no longer wins.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MissedWin {
+    pub ply: u32,
+    pub played_san: String,
+    pub best_move_san: String,
+    /// Eval of the best move, from the mover's perspective.
+    pub best_cp: i32,
+    /// Eval after the played move, from the mover's perspective.
+    pub played_cp: i32,
+}
+
+/// How far one side of an engine-vs-engine game fell short of full strength.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HandicapSide {
+    /// Percentage of moves that matched the full-strength best move.
+    pub best_move_rate: f64,
+    pub avg_cp_loss: f64,
+    /// Total centipawns given up versus full strength (per-move loss capped at 1000).
+    pub total_cp_cost: i32,
+    pub missed_wins: Vec<MissedWin>,
+}
+
+/// Cost of the skill limitation in an engine-vs-engine game.
+///
+/// Framed as divergence from full-strength analysis rather than human accuracy.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HandicapAnalysis {
+    pub skill_level: u8,
+    pub white: HandicapSide,
+    pub black: HandicapSide,
+}
+
+/// Compare the moves played at `skill_level` against full-strength review evals.
+pub fn compute_handicap_analysis(
+    positions: &[PositionReview],
+    skill_level: u8,
+) -> HandicapAnalysis {
+    HandicapAnalysis {
+        skill_level,
+        white: compute_handicap_side(positions, true),
+        black: compute_handicap_side(positions, false),
+    }
+}
+
+fn compute_handicap_side(positions: &[PositionReview], is_white: bool) -> HandicapSide {
+    let side_positions: Vec<&PositionReview> = positions
+        .iter()
+        .filter(|p| is_white_ply(p.ply) == is_white)
+        .collect();
+
+    if side_positions.is_empty() {
+        return HandicapSide {
+            best_move_rate: 100.0,
+            ..Default::default()
+        };
+    }
+
+    let best_moves = side_positions
+        .iter()
+        .filter(|p| p.cp_loss <= 0 || p.played_san == p.best_move_san)
+        .count();
+    let total_cp_cost: i32 = side_positions.iter().map(|p| p.cp_loss.min(1000)).sum();
+
+    // Review evals are stored from White's perspective
+    let to_mover = |score: &AnalysisScore| {
+        if is_white {
+            score.to_cp()
+        } else {
+            score.negate().to_cp()
+        }
+    };
+    let missed_wins = side_positions
+        .iter()
+        .filter_map(|p| {
+            let best_cp = to_mover(&p.eval_best);
+            let played_cp = to_mover(&p.eval_after);
+            (best_cp >= MISSED_WIN_THRESHOLD_CP && played_cp < MISSED_WIN_THRESHOLD_CP).then(|| {
+                MissedWin {
+                    ply: p.ply,
+                    played_san: p.played_san.clone(),
+                    best_move_san: p.best_move_san.clone(),
+                    best_cp,
+                    played_cp,
+                }
+            })
+        })
+        .collect();
+
+    let count = side_positions.len() as f64;
+    HandicapSide {
+        best_move_rate: best_moves as f64 / count * 100.0,
+        avg_cp_loss: total_cp_cost as f64 / count,
+        total_cp_cost,
+        missed_wins,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -222,6 +545,69 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_classify_with_context() {
+        let sacrifice = MoveContext {
+            is_sacrifice: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            MoveClassification::classify(5, sacrifice),
+            MoveClassification::Brilliant
+        );
+        // A sacrifice that loses is just an error
+        assert_eq!(
+            MoveClassification::classify(200, sacrifice),
+            MoveClassification::Mistake
+        );
+
+        let only_move = MoveContext {
+            is_only_move: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            MoveClassification::classify(0, only_move),
+            MoveClassification::Great
+        );
+        assert_eq!(
+            MoveClassification::classify(5, only_move),
+            MoveClassification::Excellent
+        );
+
+        let after_error = MoveContext {
+            opponent_erred: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            MoveClassification::classify(400, after_error),
+            MoveClassification::Miss
+        );
+        assert_eq!(
+            MoveClassification::classify(50, after_error),
+            MoveClassification::Inaccuracy
+        );
+        assert_eq!(
+            MoveClassification::classify(
+                400,
+                MoveContext {
+                    is_forced: true,
+                    ..after_error
+                }
+            ),
+            MoveClassification::Forced
+        );
+    }
+
+    #[test]
+    fn test_only_move_needs_a_gap_and_no_easy_win() {
+        let cp = AnalysisScore::Centipawns;
+        assert!(is_only_move(&cp(50), &cp(-150)));
+        assert!(!is_only_move(&cp(50), &cp(-50)));
+        // Both moves still win comfortably
+        assert!(!is_only_move(&cp(900), &cp(500)));
+        assert!(is_only_move(&AnalysisScore::Mate(2), &cp(0)));
+    }
+
     #[test]
     fn test_stored_score_to_cp() {
         assert_eq!(AnalysisScore::Centipawns(50).to_cp(), 50);
@@ -257,6 +643,8 @@ mod tests {
             pv: vec![],
             depth: 18,
             clock_ms: None,
+            excluded: None,
+            only_move: false,
         }];
         let accuracy = compute_accuracy(&positions, true);
         assert!(accuracy > 99.0);
@@ -279,6 +667,8 @@ mod tests {
             pv: vec![],
             depth: 18,
             clock_ms: None,
+            excluded: None,
+            only_move: false,
         }];
         let accuracy = compute_accuracy(&positions, true);
         assert!(accuracy < 50.0);
@@ -302,6 +692,8 @@ mod tests {
                 pv: vec![],
                 depth: 18,
                 clock_ms: None,
+                excluded: None,
+                only_move: false,
             },
             PositionReview {
                 ply: 2,
@@ -317,6 +709,8 @@ mod tests {
                 pv: vec![],
                 depth: 18,
                 clock_ms: None,
+                excluded: None,
+                only_move: false,
             },
         ];
         let white_accuracy = compute_accuracy(&positions, true);
@@ -325,4 +719,176 @@ mod tests {
         assert!(white_accuracy > 99.0);
         assert!(black_accuracy < 50.0);
     }
+
+    fn handicap_position(
+        ply: u32,
+        played: &str,
+        best: &str,
+        eval_best: i32,
+        eval_after: i32,
+    ) -> PositionReview {
+        let is_white = is_white_ply(ply);
+        let cp_loss = if is_white {
+            eval_best - eval_after
+        } else {
+            eval_after - eval_best
+        };
+        PositionReview {
+            ply,
+            fen: String::new(),
+            played_san: played.into(),
+            best_move_san: best.into(),
+            best_move_uci: String::new(),
+            eval_before: AnalysisScore::Centipawns(eval_best),
+            eval_after: AnalysisScore::Centipawns(eval_after),
+            eval_best: AnalysisScore::Centipawns(eval_best),
+            classification: MoveClassification::from_cp_loss(cp_loss, false),
+            cp_loss: cp_loss.max(0),
+            pv: vec![],
+            depth: 18,
+            clock_ms: None,
+            excluded: None,
+            only_move: false,
+        }
+    }
+
+    #[test]
+    fn test_handicap_detects_missed_win() {
+        let positions = vec![
+            // White had +350 with Qxf7 but played Nc3, dropping to +40
+            handicap_position(1, "Nc3", "Qxf7", 350, 40),
+            handicap_position(2, "e5", "e5", 40, 40),
+            // Already winning and stayed winning: not a missed win
+            handicap_position(3, "Bb5", "Bc4", 500, 420),
+        ];
+        let handicap = compute_handicap_analysis(&positions, 5);
+        assert_eq!(handicap.skill_level, 5);
+        assert_eq!(handicap.white.missed_wins.len(), 1);
+        let missed = &handicap.white.missed_wins[0];
+        assert_eq!(missed.ply, 1);
+        assert_eq!(missed.best_move_san, "Qxf7");
+        assert_eq!(missed.best_cp, 350);
+        assert_eq!(missed.played_cp, 40);
+        assert_eq!(handicap.white.total_cp_cost, 390);
+        assert!(handicap.black.missed_wins.is_empty());
+        assert_eq!(handicap.black.best_move_rate, 100.0);
+    }
+
+    #[test]
+    fn test_coverage_splits_on_gaps_and_depth() {
+        let mut review = GameReview {
+            game_id: "g".into(),
+            status: ReviewStatus::Complete,
+            positions: vec![],
+            white_accuracy: None,
+            black_accuracy: None,
+            total_plies: 10,
+            analyzed_plies: 0,
+            analysis_depth: 18,
+            started_at: None,
+            completed_at: None,
+            winner: None,
+            opening: None,
+            endgame: None,
+        };
+        for ply in [5, 3, 4, 8] {
+            review.set_position(handicap_position(ply, "e4", "e4", 0, 0));
+        }
+        let mut deeper = handicap_position(5, "e4", "e4", 0, 0);
+        deeper.depth = 24;
+        review.set_position(deeper);
+
+        assert_eq!(
+            review.positions.iter().map(|p| p.ply).collect::<Vec<_>>(),
+            vec![3, 4, 5, 8]
+        );
+        assert_eq!(review.analyzed_plies, 4);
+        let ranges: Vec<_> = review
+            .coverage()
+            .iter()
+            .map(|r| (r.from_ply, r.to_ply, r.depth))
+            .collect();
+        assert_eq!(ranges, vec![(3, 4, 18), (5, 5, 24), (8, 8, 18)]);
+    }
+
+    #[test]
+    fn test_accuracy_exclusions_are_recorded_and_not_counted() {
+        let mut review = GameReview {
+            game_id: "g".into(),
+            status: ReviewStatus::Complete,
+            positions: vec![],
+            white_accuracy: None,
+            black_accuracy: None,
+            total_plies: 7,
+            analyzed_plies: 0,
+            analysis_depth: 18,
+            started_at: None,
+            completed_at: None,
+            winner: None,
+            opening: Some(Opening {
+                eco: "C20".into(),
+                name: "King's Pawn Game".into(),
+                ply: 2,
+            }),
+            endgame: None,
+        };
+        // White: a book move, a blunder, a forced move, and a slip when
+        // already winning by a rook and more
+        review.set_position(handicap_position(1, "e4", "e4", 20, 20));
+        review.set_position(handicap_position(3, "f3", "Nf3", 20, -400));
+        let mut forced = handicap_position(5, "Kf2", "Kf2", 0, -200);
+        forced.classification = MoveClassification::Forced;
+        review.set_position(forced);
+        review.set_position(handicap_position(7, "Qd1", "Qh5", 900, 700));
+
+        review.apply_accuracy_exclusions(AccuracyExclusions::default());
+        assert!(review.positions.iter().all(|p| p.excluded.is_none()));
+        assert_eq!(review.accuracy_counts(true).counted, 4);
+        let all_counted = review.white_accuracy.unwrap();
+
+        review.apply_accuracy_exclusions(AccuracyExclusions {
+            book: true,
+            forced: true,
+            decided_cp: Some(DEFAULT_DECIDED_CP),
+        });
+        let excluded: Vec<_> = review.positions.iter().map(|p| p.excluded).collect();
+        assert_eq!(
+            excluded,
+            vec![
+                Some(AccuracyExclusion::Book),
+                None,
+                Some(AccuracyExclusion::Forced),
+                Some(AccuracyExclusion::Decided),
+            ]
+        );
+        assert_eq!(
+            review.accuracy_counts(true),
+            AccuracyCounts {
+                counted: 1,
+                book: 1,
+                forced: 1,
+                decided: 1,
+            }
+        );
+        // Only the blunder is left
+        assert!(review.white_accuracy.unwrap() < all_counted);
+    }
+
+    #[test]
+    fn test_handicap_uses_black_perspective() {
+        // Black had -300 (winning for Black) but played into -50
+        let positions = vec![handicap_position(2, "h6", "Qh4", -300, -50)];
+        let handicap = compute_handicap_analysis(&positions, 3);
+        assert_eq!(handicap.black.missed_wins.len(), 1);
+        assert_eq!(handicap.black.missed_wins[0].best_cp, 300);
+        assert_eq!(handicap.black.missed_wins[0].played_cp, 50);
+        assert_eq!(handicap.black.best_move_rate, 0.0);
+        assert_eq!(
+            handicap.white,
+            HandicapSide {
+                best_move_rate: 100.0,
+                ..Default::default()
+            }
+        );
+    }
 }
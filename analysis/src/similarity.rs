@@ -0,0 +1,163 @@
+//! Approximate position similarity.
+//!
+//! Two positions are alike when their pawn structures overlap, their
+//! material is close and their pieces stand on the same squares, roughly in
+//! that order of importance. A [`PositionSignature`] keeps only those
+//! features, so a library can be scanned without rebuilding boards.
+
+use cozy_chess::{BitBoard, Board, Color, Piece};
+
+/// Share of the score from the pawn structure.
+const PAWN_WEIGHT: f64 = 0.5;
+/// Share of the score from the material balance.
+const MATERIAL_WEIGHT: f64 = 0.3;
+/// Share of the score from where the other pieces stand.
+const PLACEMENT_WEIGHT: f64 = 0.2;
+
+/// Pieces counted in the material signature, with their values in pawns.
+const MATERIAL: [(Piece, u32); 5] = [
+    (Piece::Pawn, 1),
+    (Piece::Knight, 3),
+    (Piece::Bishop, 3),
+    (Piece::Rook, 5),
+    (Piece::Queen, 9),
+];
+
+const PIECES: [Piece; 5] = [
+    Piece::Knight,
+    Piece::Bishop,
+    Piece::Rook,
+    Piece::Queen,
+    Piece::King,
+];
+
+/// The features of a position that similarity is measured on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PositionSignature {
+    /// Pawns of each side, White first.
+    pawns: [BitBoard; 2],
+    /// Counts of the [`MATERIAL`] pieces of each side, White first.
+    material: [[u8; 5]; 2],
+    /// Squares of each non-pawn piece kind of each side, White first.
+    placement: [[BitBoard; 5]; 2],
+}
+
+impl PositionSignature {
+    pub fn new(board: &Board) -> Self {
+        let by_color = |color: Color| {
+            (
+                board.colored_pieces(color, Piece::Pawn),
+                MATERIAL.map(|(piece, _)| board.colored_pieces(color, piece).len() as u8),
+                PIECES.map(|piece| board.colored_pieces(color, piece)),
+            )
+        };
+        let (white_pawns, white_material, white_placement) = by_color(Color::White);
+        let (black_pawns, black_material, black_placement) = by_color(Color::Black);
+        Self {
+            pawns: [white_pawns, black_pawns],
+            material: [white_material, black_material],
+            placement: [white_placement, black_placement],
+        }
+    }
+
+    /// Signature of the position in `fen`, or `None` if it does not parse.
+    pub fn from_fen(fen: &str) -> Option<Self> {
+        fen.parse::<Board>().ok().map(|board| Self::new(&board))
+    }
+
+    /// Similarity from 0.0 (nothing in common) to 1.0 (same pawns,
+    /// material and piece squares). Symmetric.
+    pub fn similarity(&self, other: &Self) -> f64 {
+        let pawns = overlap(self.pawns.iter().zip(&other.pawns).map(|(a, b)| (*a, *b)));
+        let placement = overlap(
+            self.placement
+                .iter()
+                .flatten()
+                .zip(other.placement.iter().flatten())
+                .map(|(a, b)| (*a, *b)),
+        );
+        PAWN_WEIGHT * pawns
+            + MATERIAL_WEIGHT * self.material_similarity(other)
+            + PLACEMENT_WEIGHT * placement
+    }
+
+    /// 1.0 minus the material that differs, as a share of all material on
+    /// both boards.
+    fn material_similarity(&self, other: &Self) -> f64 {
+        let (mut differing, mut total) = (0, 0);
+        for side in 0..2 {
+            for (i, (_, value)) in MATERIAL.iter().enumerate() {
+                let (a, b) = (
+                    u32::from(self.material[side][i]),
+                    u32::from(other.material[side][i]),
+                );
+                differing += a.abs_diff(b) * value;
+                total += (a + b) * value;
+            }
+        }
+        if total == 0 {
+            1.0
+        } else {
+            1.0 - f64::from(differing) / f64::from(total)
+        }
+    }
+}
+
+/// Squares occupied in both boards of each pair, as a share of squares
+/// occupied in either. 1.0 when all boards are empty.
+fn overlap(pairs: impl Iterator<Item = (BitBoard, BitBoard)>) -> f64 {
+    let (shared, either) = pairs.fold((0, 0), |(shared, either), (a, b)| {
+        (shared + (a & b).len(), either + (a | b).len())
+    });
+    if either == 0 {
+        1.0
+    } else {
+        f64::from(shared) / f64::from(either)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signature(fen: &str) -> PositionSignature {
+        PositionSignature::from_fen(fen).unwrap()
+    }
+
+    #[test]
+    fn test_identical_positions_score_one() {
+        let start = signature("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        assert_eq!(start.similarity(&start), 1.0);
+    }
+
+    #[test]
+    fn test_pawn_structure_outweighs_piece_placement() {
+        let carlsbad = signature("r1bq1rk1/pp2bppp/2n1pn2/3p4/3P4/2NBPN2/PP3PPP/R2QK2R w KQ - 0 9");
+        // Same pawns, pieces developed differently
+        let redeveloped =
+            signature("r2q1rk1/pp1nbppp/4pn2/3p4/3P1B2/2N1PN2/PP2BPPP/R2QK2R w KQ - 0 9");
+        // Same pieces on the same squares, different pawns
+        let restructured =
+            signature("r1bq1rk1/1p2bpp1/p1n2n1p/4p3/2P5/2NB1N1P/PP3PP1/R2QK2R w KQ - 0 9");
+
+        let same_pawns = carlsbad.similarity(&redeveloped);
+        let same_pieces = carlsbad.similarity(&restructured);
+        assert!(
+            same_pawns > same_pieces,
+            "{} <= {}",
+            same_pawns,
+            same_pieces
+        );
+        assert_eq!(same_pawns, redeveloped.similarity(&carlsbad));
+    }
+
+    #[test]
+    fn test_material_similarity() {
+        let start = signature("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        let no_queens = signature("rnb1kbnr/pppppppp/8/8/8/8/PPPPPPPP/RNB1KBNR w KQkq - 0 1");
+        // 18 of the 138 points of material differ
+        let expected = 1.0 - 18.0 / 138.0;
+        assert!((start.material_similarity(&no_queens) - expected).abs() < 1e-9);
+        assert!(PositionSignature::from_fen("not a fen").is_none());
+    }
+}
@@ -0,0 +1,192 @@
+//! Syzygy endgame tablebases.
+//!
+//! Positions with few enough men are looked up in local Syzygy files
+//! (`.rtbw` for win/draw/loss, `.rtbz` for distance to zeroing) instead of
+//! trusting a depth-limited search. Only positions without castling rights
+//! are covered, as in the tables themselves.
+
+use std::path::{Path, PathBuf};
+
+use chess::AnalysisScore;
+use serde::{Deserialize, Serialize};
+use shakmaty::fen::Fen;
+use shakmaty::uci::UciMove;
+use shakmaty::{CastlingMode, Chess};
+use shakmaty_syzygy::{Tablebase as Tables, Wdl};
+
+/// The most men any Syzygy table covers.
+pub const MAX_TABLEBASE_MEN: usize = 7;
+
+/// Score of a tablebase win: short of any mate score, above any search eval.
+pub const TABLEBASE_WIN_CP: i32 = 20_000;
+
+#[derive(Debug, thiserror::Error)]
+pub enum TablebaseError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("No Syzygy tables in {0}")]
+    NoTables(PathBuf),
+}
+
+/// Game-theoretic result for the side to move. Cursed wins and blessed
+/// losses are wins and losses the fifty-move rule turns into draws.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TablebaseWdl {
+    Loss,
+    BlessedLoss,
+    Draw,
+    CursedWin,
+    Win,
+}
+
+/// What the tables say about one position.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TablebaseProbe {
+    pub wdl: TablebaseWdl,
+    /// Plies to the next capture or pawn move under best play, when the
+    /// `.rtbz` table is present. Negative when losing.
+    pub dtz: Option<i32>,
+    /// A move keeping the result with the best DTZ, in UCI notation.
+    pub best_move_uci: Option<String>,
+}
+
+impl TablebaseProbe {
+    /// Exact score for the side to move. Anything the fifty-move rule draws
+    /// scores as a draw, so every move that keeps a win loses nothing.
+    pub fn score(&self) -> AnalysisScore {
+        AnalysisScore::Centipawns(match self.wdl {
+            TablebaseWdl::Win => TABLEBASE_WIN_CP,
+            TablebaseWdl::Loss => -TABLEBASE_WIN_CP,
+            TablebaseWdl::CursedWin | TablebaseWdl::Draw | TablebaseWdl::BlessedLoss => 0,
+        })
+    }
+}
+
+/// Number of men in the position `fen`, or `None` if it does not parse.
+pub fn count_men(fen: &str) -> Option<usize> {
+    let placement = fen.split_whitespace().next()?;
+    Some(placement.chars().filter(char::is_ascii_alphabetic).count())
+}
+
+/// Syzygy tables loaded from one or more directories.
+pub struct Tablebase {
+    tables: Tables<Chess>,
+}
+
+impl Tablebase {
+    /// Load the tables in `dirs`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a directory cannot be read, or none of them holds a table.
+    pub fn open<P: AsRef<Path>>(dirs: impl IntoIterator<Item = P>) -> Result<Self, TablebaseError> {
+        let mut tables = Tables::new();
+        let mut found = 0;
+        let mut last = PathBuf::new();
+        for dir in dirs {
+            last = dir.as_ref().to_path_buf();
+            found += tables.add_directory(dir.as_ref())?;
+        }
+        if found == 0 {
+            return Err(TablebaseError::NoTables(last));
+        }
+        Ok(Self { tables })
+    }
+
+    /// Most men of any loaded table.
+    pub fn max_men(&self) -> usize {
+        self.tables.max_pieces()
+    }
+
+    /// Look up `fen`. `None` if the position has too many men, castling
+    /// rights, or its table is missing.
+    pub fn probe(&self, fen: &str) -> Option<TablebaseProbe> {
+        if count_men(fen)? > self.max_men().min(MAX_TABLEBASE_MEN) {
+            return None;
+        }
+        let position: Chess = fen
+            .parse::<Fen>()
+            .ok()?
+            .into_position(CastlingMode::Standard)
+            .ok()?;
+        // A review asks what the position is worth, so the fifty-move
+        // clock is taken as just reset
+        let wdl = match self.tables.probe_wdl_after_zeroing(&position).ok()? {
+            Wdl::Loss => TablebaseWdl::Loss,
+            Wdl::BlessedLoss => TablebaseWdl::BlessedLoss,
+            Wdl::Draw => TablebaseWdl::Draw,
+            Wdl::CursedWin => TablebaseWdl::CursedWin,
+            Wdl::Win => TablebaseWdl::Win,
+        };
+        let dtz = self
+            .tables
+            .probe_dtz(&position)
+            .ok()
+            .map(|dtz| dtz.ignore_rounding().0);
+        let best_move_uci = self
+            .tables
+            .best_move(&position)
+            .ok()
+            .flatten()
+            .map(|(mv, _)| UciMove::from_standard(&mv).to_string());
+        Some(TablebaseProbe {
+            wdl,
+            dtz,
+            best_move_uci,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn probe(wdl: TablebaseWdl) -> TablebaseProbe {
+        TablebaseProbe {
+            wdl,
+            dtz: None,
+            best_move_uci: None,
+        }
+    }
+
+    #[test]
+    fn test_count_men() {
+        assert_eq!(count_men("8/8/8/4k3/8/8/4P3/4K3 w - - 0 1"), Some(3));
+        assert_eq!(
+            count_men("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"),
+            Some(32)
+        );
+        assert_eq!(count_men(""), None);
+    }
+
+    #[test]
+    fn test_scores_follow_the_fifty_move_rule() {
+        assert_eq!(
+            probe(TablebaseWdl::Win).score(),
+            AnalysisScore::Centipawns(TABLEBASE_WIN_CP)
+        );
+        assert_eq!(
+            probe(TablebaseWdl::Loss).score(),
+            AnalysisScore::Centipawns(-TABLEBASE_WIN_CP)
+        );
+        for wdl in [
+            TablebaseWdl::CursedWin,
+            TablebaseWdl::Draw,
+            TablebaseWdl::BlessedLoss,
+        ] {
+            assert_eq!(probe(wdl).score(), AnalysisScore::Centipawns(0));
+        }
+        // Below any mate score
+        assert!(TABLEBASE_WIN_CP < AnalysisScore::Mate(99).to_cp());
+    }
+
+    #[test]
+    fn test_missing_tables() {
+        let dir = std::env::temp_dir().join("chesstty-no-syzygy");
+        std::fs::create_dir_all(&dir).unwrap();
+        assert!(matches!(
+            Tablebase::open([&dir]),
+            Err(TablebaseError::NoTables(_))
+        ));
+    }
+}
@@ -0,0 +1,105 @@
+//! Clock simulation over recorded think times.
+//!
+//! Games played without a clock still record how long each move took. Running
+//! those times against a time control after the fact shows whether the game
+//! would have been lost on time, and when: each move's think time comes off
+//! the mover's clock, the increment is added after the move, and the flag
+//! falls on the first move that takes longer than the time left.
+
+use serde::{Deserialize, Serialize};
+
+/// Starting time and increment per move of a time control.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeControl {
+    pub initial_ms: u64,
+    pub increment_ms: u64,
+}
+
+/// One side's clock over a game under a simulated time control.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClockSimulation {
+    pub control: TimeControl,
+    /// Ply (1-indexed) of the move during which the flag fell, if it did.
+    pub flagged_at_ply: Option<u32>,
+    /// Clock left after each simulated move, by ply; ends at the flag.
+    pub remaining_ms: Vec<(u32, u64)>,
+    /// Least time left after any move before the flag, or the starting time
+    /// if the side made no move.
+    pub lowest_ms: u64,
+    /// Moves without a recorded think time, counted as instant.
+    pub untimed_moves: u32,
+}
+
+impl ClockSimulation {
+    /// Clock left after the side's last simulated move.
+    pub fn final_ms(&self) -> u64 {
+        self.remaining_ms
+            .last()
+            .map_or(self.control.initial_ms, |&(_, ms)| ms)
+    }
+}
+
+/// Run `control` over one side's moves, given in order as the ply of each
+/// move and its think time.
+pub fn simulate_clock(moves: &[(u32, Option<u64>)], control: TimeControl) -> ClockSimulation {
+    let mut clock = control.initial_ms;
+    let mut simulation = ClockSimulation {
+        control,
+        flagged_at_ply: None,
+        remaining_ms: Vec::with_capacity(moves.len()),
+        lowest_ms: control.initial_ms,
+        untimed_moves: 0,
+    };
+    for &(ply, think_ms) in moves {
+        let think_ms = think_ms.unwrap_or_else(|| {
+            simulation.untimed_moves += 1;
+            0
+        });
+        if think_ms > clock {
+            simulation.flagged_at_ply = Some(ply);
+            simulation.remaining_ms.push((ply, 0));
+            simulation.lowest_ms = 0;
+            break;
+        }
+        clock = clock - think_ms + control.increment_ms;
+        simulation.remaining_ms.push((ply, clock));
+        simulation.lowest_ms = simulation.lowest_ms.min(clock);
+    }
+    simulation
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLITZ: TimeControl = TimeControl {
+        initial_ms: 180_000,
+        increment_ms: 0,
+    };
+
+    #[test]
+    fn test_flag_falls_on_the_move_that_runs_out() {
+        let moves = [(1, Some(60_000)), (3, Some(60_000)), (5, Some(70_000))];
+        let simulation = simulate_clock(&moves, BLITZ);
+        assert_eq!(simulation.flagged_at_ply, Some(5));
+        assert_eq!(
+            simulation.remaining_ms,
+            vec![(1, 120_000), (3, 60_000), (5, 0)]
+        );
+        assert_eq!(simulation.final_ms(), 0);
+    }
+
+    #[test]
+    fn test_increment_is_added_after_each_move() {
+        let control = TimeControl {
+            initial_ms: 10_000,
+            increment_ms: 5_000,
+        };
+        let moves = [(2, Some(9_000)), (4, Some(5_500)), (6, None)];
+        let simulation = simulate_clock(&moves, control);
+        assert_eq!(simulation.flagged_at_ply, None);
+        assert_eq!(simulation.final_ms(), 10_500);
+        assert_eq!(simulation.lowest_ms, 5_500);
+        assert_eq!(simulation.untimed_moves, 1);
+    }
+}
@@ -0,0 +1,75 @@
+//! Server addresses over either transport
+
+use std::fmt;
+use std::path::PathBuf;
+
+/// Where a chess server listens: its Unix Domain Socket on this machine, or
+/// a TCP endpoint for a server elsewhere.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ServerAddr {
+    /// Path to the server's Unix Domain Socket.
+    Unix(PathBuf),
+    /// Endpoint URI, e.g. `http://desktop.local:50051`.
+    Tcp(String),
+}
+
+impl ServerAddr {
+    /// Parse an address. `unix:/path` and absolute paths are sockets; a
+    /// URI is kept as is, and a bare `host:port` is taken as `http://`.
+    pub fn parse(addr: &str) -> Self {
+        let addr = addr.trim();
+        if let Some(path) = addr.strip_prefix("unix:") {
+            ServerAddr::Unix(PathBuf::from(path))
+        } else if addr.starts_with('/') {
+            ServerAddr::Unix(PathBuf::from(addr))
+        } else if addr.contains("://") {
+            ServerAddr::Tcp(addr.to_string())
+        } else {
+            ServerAddr::Tcp(format!("http://{}", addr))
+        }
+    }
+}
+
+impl fmt::Display for ServerAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ServerAddr::Unix(path) => write!(f, "unix:{}", path.display()),
+            ServerAddr::Tcp(uri) => f.write_str(uri),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_server_addr() {
+        assert_eq!(
+            ServerAddr::parse("unix:/tmp/chesstty.sock"),
+            ServerAddr::Unix(PathBuf::from("/tmp/chesstty.sock"))
+        );
+        assert_eq!(
+            ServerAddr::parse("/tmp/chesstty.sock"),
+            ServerAddr::Unix(PathBuf::from("/tmp/chesstty.sock"))
+        );
+        assert_eq!(
+            ServerAddr::parse("https://desktop.local:50051"),
+            ServerAddr::Tcp("https://desktop.local:50051".to_string())
+        );
+        assert_eq!(
+            ServerAddr::parse(" 192.168.1.20:50051 "),
+            ServerAddr::Tcp("http://192.168.1.20:50051".to_string())
+        );
+    }
+
+    #[test]
+    fn test_display_parses_back() {
+        for addr in [
+            ServerAddr::Unix(PathBuf::from("/tmp/chesstty.sock")),
+            ServerAddr::Tcp("http://[::1]:50051".to_string()),
+        ] {
+            assert_eq!(ServerAddr::parse(&addr.to_string()), addr);
+        }
+    }
+}
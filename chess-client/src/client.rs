@@ -1,5 +1,6 @@
 //! Chess gRPC client implementation
 
+use crate::addr::ServerAddr;
 use crate::error::{ClientError, ClientResult};
 use chess_proto::chess_service_client::ChessServiceClient;
 use chess_proto::*;
@@ -7,29 +8,124 @@ use std::path::Path;
 
 use hyper_util::rt::TokioIo;
 use tokio::net::UnixStream;
-use tonic::transport::{Channel, Endpoint, Uri};
+use tonic::transport::{Certificate, Channel, ClientTlsConfig, Endpoint, Uri};
+use tonic_health::pb::health_check_response::ServingStatus;
+use tonic_health::pb::health_client::HealthClient;
+use tonic_health::pb::HealthCheckRequest;
 use tower::service_fn;
 
 /// Network client for communicating with the chess server
+///
+/// Clones share the underlying channel and target the same session.
+#[derive(Clone)]
 pub struct ChessClient {
     client: ChessServiceClient<Channel>,
+    /// Standard `grpc.health.v1` checks on the same channel.
+    health: HealthClient<Channel>,
     session_id: Option<String>,
+    /// Player profile that new games and review reads are counted towards.
+    profile: Option<String>,
+}
+
+/// Client TLS settings that trust the PEM CA bundle at `path`, for servers
+/// whose certificate isn't signed by a system root (e.g. self-signed).
+pub fn tls_config_from_ca(path: &Path) -> ClientResult<ClientTlsConfig> {
+    let pem = std::fs::read(path)
+        .map_err(|e| ClientError::Tls(format!("Failed to read {}: {}", path.display(), e)))?;
+    Ok(ClientTlsConfig::new().ca_certificate(Certificate::from_pem(pem)))
+}
+
+/// Endpoint for a TCP server, set up for TLS when `tls` is given or the URI
+/// is `https://`.
+fn tcp_endpoint(uri: &str, tls: Option<ClientTlsConfig>) -> ClientResult<Endpoint> {
+    let (uri, tls) = match tls {
+        Some(tls) => match uri.strip_prefix("http://") {
+            Some(rest) => (format!("https://{}", rest), Some(tls)),
+            None => (uri.to_string(), Some(tls)),
+        },
+        None if uri.starts_with("https://") => (
+            uri.to_string(),
+            Some(ClientTlsConfig::new().with_native_roots()),
+        ),
+        None => (uri.to_string(), None),
+    };
+    let endpoint =
+        Endpoint::from_shared(uri).map_err(|e| ClientError::InvalidAddress(e.to_string()))?;
+    match tls {
+        Some(tls) => Ok(endpoint.tls_config(tls)?),
+        None => Ok(endpoint),
+    }
 }
 
 impl ChessClient {
-    /// Connect to the chess server
+    /// Connect to the chess server at `addr`, a TCP endpoint or a Unix
+    /// socket (see [`ServerAddr::parse`])
     pub async fn connect(addr: &str) -> ClientResult<Self> {
-        let channel = Channel::from_shared(addr.to_string())
-            .map_err(|e| ClientError::InvalidAddress(e.to_string()))?
-            .connect()
-            .await?;
+        Self::connect_to(&ServerAddr::parse(addr)).await
+    }
 
-        let client = ChessServiceClient::new(channel);
+    /// Connect to the chess server over whichever transport `addr` names
+    pub async fn connect_to(addr: &ServerAddr) -> ClientResult<Self> {
+        Self::connect_to_with_tls(addr, None).await
+    }
 
-        Ok(Self {
-            client,
-            session_id: None,
-        })
+    /// Connect like [`ChessClient::connect_to`], verifying a TCP server with
+    /// `tls` (see [`ChessClient::connect_tcp_with_tls`]). Unix sockets ignore it.
+    pub async fn connect_to_with_tls(
+        addr: &ServerAddr,
+        tls: Option<ClientTlsConfig>,
+    ) -> ClientResult<Self> {
+        match addr {
+            ServerAddr::Unix(path) => Self::connect_uds(path).await,
+            ServerAddr::Tcp(uri) => Self::connect_tcp_with_tls(uri, tls).await,
+        }
+    }
+
+    /// Set up a client for `addr` without connecting yet, like
+    /// [`ChessClient::connect_uds_lazy`] for either transport
+    pub fn connect_lazy(addr: &ServerAddr) -> ClientResult<Self> {
+        Self::connect_lazy_with_tls(addr, None)
+    }
+
+    /// Set up a client like [`ChessClient::connect_lazy`], verifying a TCP
+    /// server with `tls` once it connects
+    pub fn connect_lazy_with_tls(
+        addr: &ServerAddr,
+        tls: Option<ClientTlsConfig>,
+    ) -> ClientResult<Self> {
+        match addr {
+            ServerAddr::Unix(path) => Self::connect_uds_lazy(path),
+            ServerAddr::Tcp(uri) => {
+                let channel = tcp_endpoint(uri, tls)?.connect_lazy();
+                Ok(Self::from_channel(channel))
+            }
+        }
+    }
+
+    /// Connect to a chess server listening on TCP
+    ///
+    /// `https://` endpoints are verified against the system's root
+    /// certificates.
+    ///
+    /// # Arguments
+    /// * `uri` - Endpoint URI, e.g. `http://desktop.local:50051`
+    pub async fn connect_tcp(uri: &str) -> ClientResult<Self> {
+        Self::connect_tcp_with_tls(uri, None).await
+    }
+
+    /// Connect to a chess server listening on TCP with TLS
+    ///
+    /// # Arguments
+    /// * `uri` - Endpoint URI; `http://` is upgraded to `https://` when `tls` is given
+    /// * `tls` - How to verify the server, e.g. [`tls_config_from_ca`] for a
+    ///   self-signed certificate; `None` uses the system roots for `https://`
+    pub async fn connect_tcp_with_tls(
+        uri: &str,
+        tls: Option<ClientTlsConfig>,
+    ) -> ClientResult<Self> {
+        let channel = tcp_endpoint(uri, tls)?.connect().await?;
+
+        Ok(Self::from_channel(channel))
     }
 
     /// Connect to the chess server via Unix Domain Socket
@@ -49,12 +145,83 @@ impl ChessClient {
             }))
             .await?;
 
-        let client = ChessServiceClient::new(channel);
+        Ok(Self::from_channel(channel))
+    }
 
-        Ok(Self {
-            client,
+    /// Set up a client for the server's Unix Domain Socket without
+    /// connecting yet. The socket is dialled on the first request, so this
+    /// succeeds while the server is down.
+    pub fn connect_uds_lazy(socket_path: &Path) -> ClientResult<Self> {
+        let socket_path = socket_path.to_path_buf();
+        let channel = Endpoint::try_from("http://[::]:50051")
+            .map_err(|e| ClientError::InvalidAddress(e.to_string()))?
+            .connect_with_connector_lazy(service_fn(move |_: Uri| {
+                let path = socket_path.clone();
+                async move {
+                    let stream = UnixStream::connect(path).await?;
+                    Ok::<_, std::io::Error>(TokioIo::new(stream))
+                }
+            }));
+
+        Ok(Self::from_channel(channel))
+    }
+
+    fn from_channel(channel: Channel) -> Self {
+        Self {
+            client: ChessServiceClient::new(channel.clone()),
+            health: HealthClient::new(channel),
             session_id: None,
-        })
+            profile: None,
+        }
+    }
+
+    /// Count games created and reviews read by this client towards `profile`
+    pub fn with_profile(mut self, profile: impl Into<String>) -> Self {
+        self.profile = Some(profile.into());
+        self
+    }
+
+    /// Whether the chess service is ready, per the server's standard gRPC
+    /// health check. A server that predates health checks answers with an
+    /// `Unimplemented` status.
+    pub async fn health(&mut self) -> ClientResult<ServingStatus> {
+        let request = HealthCheckRequest {
+            service: chess_proto::chess_service_server::SERVICE_NAME.to_string(),
+        };
+        let response = self.health.check(request).await?;
+        Ok(ServingStatus::try_from(response.into_inner().status).unwrap_or(ServingStatus::Unknown))
+    }
+
+    /// Identify the running server binary (version, PID, executable)
+    pub async fn get_server_info(&mut self) -> ClientResult<GetServerInfoResponse> {
+        let response = self.client.get_server_info(GetServerInfoRequest {}).await?;
+        Ok(response.into_inner())
+    }
+
+    /// List the server's open sessions with their engine resource usage
+    pub async fn list_active_sessions(&mut self) -> ClientResult<ListActiveSessionsResponse> {
+        let response = self
+            .client
+            .list_active_sessions(ListActiveSessionsRequest {})
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    /// Close or suspend any open session, e.g. one a crashed client left
+    /// behind. A session that does not answer is aborted unsaved.
+    pub async fn admin_close_session(
+        &mut self,
+        session_id: &str,
+        suspend: bool,
+    ) -> ClientResult<AdminCloseSessionResponse> {
+        let response = self
+            .client
+            .admin_close_session(AdminCloseSessionRequest {
+                session_id: session_id.to_string(),
+                suspend,
+            })
+            .await?;
+        Ok(response.into_inner())
     }
 
     /// Create a new game session
@@ -63,11 +230,27 @@ impl ChessClient {
         fen: Option<String>,
         game_mode: Option<GameModeProto>,
         timer: Option<TimerState>,
+    ) -> ClientResult<SessionSnapshot> {
+        self.create_labeled_session(fen, game_mode, timer, None, None)
+            .await
+    }
+
+    /// Create a new game session tagged with a simul id and/or opponent name
+    pub async fn create_labeled_session(
+        &mut self,
+        fen: Option<String>,
+        game_mode: Option<GameModeProto>,
+        timer: Option<TimerState>,
+        simul_id: Option<String>,
+        opponent: Option<String>,
     ) -> ClientResult<SessionSnapshot> {
         let request = CreateSessionRequest {
             fen,
             game_mode,
             timer,
+            simul_id,
+            opponent,
+            profile: self.profile.clone(),
         };
         let response = self.client.create_session(request).await?;
         let snapshot = response.into_inner();
@@ -200,12 +383,148 @@ impl ChessClient {
             skill_level,
             threads,
             hash_mb,
+            multipv: None,
+            book_path: None,
+            book_max_plies: None,
+        };
+
+        self.client.set_engine(request).await?;
+        Ok(())
+    }
+
+    /// Set how many lines the running engine searches at once (MultiPV)
+    pub async fn set_engine_lines(&mut self, skill_level: u32, lines: u32) -> ClientResult<()> {
+        let session_id = self
+            .session_id
+            .as_ref()
+            .ok_or(ClientError::NoActiveSession)?;
+
+        let request = SetEngineRequest {
+            session_id: session_id.clone(),
+            enabled: true,
+            skill_level,
+            threads: None,
+            hash_mb: None,
+            multipv: Some(lines),
+            book_path: None,
+            book_max_plies: None,
         };
 
         self.client.set_engine(request).await?;
         Ok(())
     }
 
+    /// Have the running engine play from a polyglot opening book for the first
+    /// `max_plies` plies. `path` names a book in the server's books directory;
+    /// an empty name turns the book off.
+    pub async fn set_engine_book(
+        &mut self,
+        skill_level: u32,
+        path: &str,
+        max_plies: Option<u32>,
+    ) -> ClientResult<()> {
+        let session_id = self
+            .session_id
+            .as_ref()
+            .ok_or(ClientError::NoActiveSession)?;
+
+        let request = SetEngineRequest {
+            session_id: session_id.clone(),
+            enabled: true,
+            skill_level,
+            threads: None,
+            hash_mb: None,
+            multipv: None,
+            book_path: Some(path.to_string()),
+            book_max_plies: max_plies,
+        };
+
+        self.client.set_engine(request).await?;
+        Ok(())
+    }
+
+    /// Set the minimum delay between engine moves
+    pub async fn set_engine_move_delay(&mut self, delay_ms: u32) -> ClientResult<()> {
+        let session_id = self
+            .session_id
+            .as_ref()
+            .ok_or(ClientError::NoActiveSession)?;
+
+        let request = SetEngineMoveDelayRequest {
+            session_id: session_id.clone(),
+            delay_ms,
+        };
+
+        self.client.set_engine_move_delay(request).await?;
+        Ok(())
+    }
+
+    /// Enable or disable live tactical hints for the current session
+    pub async fn set_tactical_hints(&mut self, enabled: bool) -> ClientResult<()> {
+        let session_id = self
+            .session_id
+            .as_ref()
+            .ok_or(ClientError::NoActiveSession)?;
+
+        let request = SetTacticalHintsRequest {
+            session_id: session_id.clone(),
+            enabled,
+        };
+
+        self.client.set_tactical_hints(request).await?;
+        Ok(())
+    }
+
+    /// Switch who plays which side in the current session
+    pub async fn set_game_mode(
+        &mut self,
+        game_mode: GameModeProto,
+    ) -> ClientResult<SessionSnapshot> {
+        let session_id = self
+            .session_id
+            .as_ref()
+            .ok_or(ClientError::NoActiveSession)?;
+
+        let request = SetGameModeRequest {
+            session_id: session_id.clone(),
+            game_mode: Some(game_mode),
+        };
+
+        let response = self.client.set_game_mode(request).await?;
+        Ok(response.into_inner())
+    }
+
+    /// Enable or disable step-through mode for engine-vs-engine games
+    pub async fn set_step_mode(&mut self, enabled: bool) -> ClientResult<()> {
+        let session_id = self
+            .session_id
+            .as_ref()
+            .ok_or(ClientError::NoActiveSession)?;
+
+        let request = SetStepModeRequest {
+            session_id: session_id.clone(),
+            enabled,
+        };
+
+        self.client.set_step_mode(request).await?;
+        Ok(())
+    }
+
+    /// Let the engine make its next move in step-through mode
+    pub async fn step_engine(&mut self) -> ClientResult<()> {
+        let session_id = self
+            .session_id
+            .as_ref()
+            .ok_or(ClientError::NoActiveSession)?;
+
+        let request = StepEngineRequest {
+            session_id: session_id.clone(),
+        };
+
+        self.client.step_engine(request).await?;
+        Ok(())
+    }
+
     /// Pause the current session
     pub async fn pause(&mut self) -> ClientResult<()> {
         let session_id = self
@@ -245,12 +564,70 @@ impl ChessClient {
 
         let request = StreamEventsRequest {
             session_id: session_id.clone(),
+            watch: false,
         };
 
         let response = self.client.stream_events(request).await?;
         Ok(response.into_inner())
     }
 
+    /// Watch any open session's events alongside the client playing it.
+    /// Dropping the stream stops watching without closing the session.
+    pub async fn watch_events(
+        &mut self,
+        session_id: &str,
+    ) -> ClientResult<tonic::Streaming<SessionStreamEvent>> {
+        let request = StreamEventsRequest {
+            session_id: session_id.to_string(),
+            watch: true,
+        };
+
+        let response = self.client.stream_events(request).await?;
+        Ok(response.into_inner())
+    }
+
+    /// Send a chat message to every client of the current session
+    pub async fn send_chat(&mut self, author: &str, text: &str) -> ClientResult<ChatMessage> {
+        let session_id = self
+            .session_id
+            .as_ref()
+            .ok_or(ClientError::NoActiveSession)?;
+
+        let request = SendChatRequest {
+            session_id: session_id.clone(),
+            author: author.to_string(),
+            text: text.to_string(),
+        };
+
+        let response = self.client.send_chat(request).await?;
+        Ok(response.into_inner())
+    }
+
+    /// Get the saved chat of an open or closed session, oldest first
+    pub async fn get_chat_history(&mut self, session_id: &str) -> ClientResult<Vec<ChatMessage>> {
+        let request = GetChatHistoryRequest {
+            session_id: session_id.to_string(),
+        };
+
+        let response = self.client.get_chat_history(request).await?;
+        Ok(response.into_inner().messages)
+    }
+
+    /// Receive the raw engine output of any open session, e.g. to log evals
+    /// or collect training data. Dropping the stream detaches without
+    /// closing the session.
+    pub async fn attach_engine_tap(
+        &mut self,
+        session_id: &str,
+    ) -> ClientResult<tonic::Streaming<EngineTapEvent>> {
+        let request = AttachEngineTapRequest {
+            session_id: session_id.to_string(),
+        };
+
+        let response = self.client.attach_engine_tap(request).await?;
+        Ok(response.into_inner())
+    }
+
     /// Close the current session
     pub async fn close_session(&mut self) -> ClientResult<()> {
         if let Some(session_id) = self.session_id.take() {
@@ -297,6 +674,40 @@ impl ChessClient {
         Ok(snapshot)
     }
 
+    /// Ask for a code another client can take over the current session with.
+    /// Returns the code and the seconds left to claim it.
+    pub async fn create_session_transfer(&mut self) -> ClientResult<(String, u64)> {
+        let session_id = self
+            .session_id
+            .as_ref()
+            .ok_or(ClientError::NoActiveSession)?;
+
+        let request = CreateSessionTransferRequest {
+            session_id: session_id.clone(),
+        };
+        let response = self.client.create_session_transfer(request).await?;
+        let response = response.into_inner();
+        Ok((response.code, response.expires_in_secs))
+    }
+
+    /// Take over the session a transfer code was issued for, making it the
+    /// current session. The client that had it loses its event stream.
+    pub async fn claim_session_transfer(&mut self, code: &str) -> ClientResult<SessionSnapshot> {
+        let request = ClaimSessionTransferRequest {
+            code: code.to_string(),
+        };
+        let response = self.client.claim_session_transfer(request).await?;
+        let snapshot = response.into_inner();
+        self.session_id = Some(snapshot.session_id.clone());
+        Ok(snapshot)
+    }
+
+    /// Let go of the current session without closing it, e.g. once another
+    /// client has taken it over.
+    pub fn release_session(&mut self) {
+        self.session_id = None;
+    }
+
     /// Save a snapshot directly as a suspended session (from review mode).
     pub async fn save_snapshot(
         &mut self,
@@ -305,6 +716,7 @@ impl ChessClient {
         game_mode: Option<GameModeProto>,
         move_count: u32,
         skill_level: u8,
+        source_game: &str,
     ) -> ClientResult<String> {
         let request = SaveSnapshotRequest {
             fen: fen.to_string(),
@@ -312,11 +724,27 @@ impl ChessClient {
             game_mode,
             move_count,
             skill_level: skill_level as u32,
+            source_game: source_game.to_string(),
         };
         let response = self.client.save_snapshot(request).await?;
         Ok(response.into_inner().suspended_id)
     }
 
+    /// Save the current session's position as a named snapshot; the game
+    /// carries on.
+    pub async fn quick_snapshot(&mut self) -> ClientResult<QuickSnapshotResponse> {
+        let session_id = self
+            .session_id
+            .as_ref()
+            .ok_or(ClientError::NoActiveSession)?;
+
+        let request = QuickSnapshotRequest {
+            session_id: session_id.clone(),
+        };
+        let response = self.client.quick_snapshot(request).await?;
+        Ok(response.into_inner())
+    }
+
     /// Delete a suspended session
     pub async fn delete_suspended_session(&mut self, suspended_id: &str) -> ClientResult<()> {
         let request = DeleteSuspendedSessionRequest {
@@ -365,9 +793,42 @@ impl ChessClient {
 
     /// Enqueue a game for review analysis
     pub async fn enqueue_review(&mut self, game_id: &str) -> ClientResult<ReviewStatusInfo> {
-        let request = EnqueueReviewRequest {
+        self.enqueue_review_range(game_id, None, None, None, None, false)
+            .await
+    }
+
+    /// Enqueue analysis of plies `from_ply` to `to_ply` of a game, optionally
+    /// at another depth or number of engine lines, or without the advanced
+    /// analysis; reviewed games are re-analysed over the range. The status
+    /// carries the job id; asking again for a pending job returns it, so
+    /// this is safe to retry
+    pub async fn enqueue_review_range(
+        &mut self,
+        game_id: &str,
+        from_ply: Option<u32>,
+        to_ply: Option<u32>,
+        depth: Option<u32>,
+        multipv: Option<u32>,
+        skip_advanced: bool,
+    ) -> ClientResult<ReviewStatusInfo> {
+        self.enqueue_review_request(EnqueueReviewRequest {
             game_id: game_id.to_string(),
-        };
+            from_ply,
+            to_ply,
+            depth,
+            multipv,
+            skip_advanced,
+            ..Default::default()
+        })
+        .await
+    }
+
+    /// Enqueue a review with every option of the request, e.g. the moves
+    /// left out of the accuracy
+    pub async fn enqueue_review_request(
+        &mut self,
+        request: EnqueueReviewRequest,
+    ) -> ClientResult<ReviewStatusInfo> {
         let response = self.client.enqueue_review(request).await?;
         response
             .into_inner()
@@ -375,10 +836,29 @@ impl ChessClient {
             .ok_or_else(|| ClientError::InvalidData("missing review status".into()))
     }
 
-    /// Get review status for a game
-    pub async fn get_review_status(&mut self, game_id: &str) -> ClientResult<ReviewStatusInfo> {
-        let request = GetReviewStatusRequest {
+    /// Re-analyse the move at `ply` of a reviewed game, at `depth` or a few
+    /// plies deeper than before. Returns the depth it is re-analysed at; the
+    /// review is patched once its status is complete again
+    pub async fn reanalyze_position(
+        &mut self,
+        game_id: &str,
+        ply: u32,
+        depth: Option<u32>,
+    ) -> ClientResult<u32> {
+        let request = ReanalyzePositionRequest {
             game_id: game_id.to_string(),
+            ply,
+            depth,
+        };
+        let response = self.client.reanalyze_position(request).await?;
+        Ok(response.into_inner().depth)
+    }
+
+    /// Get review status for a game, by game id or the job id an enqueue
+    /// returned
+    pub async fn get_review_status(&mut self, id: &str) -> ClientResult<ReviewStatusInfo> {
+        let request = GetReviewStatusRequest {
+            game_id: id.to_string(),
         };
         let response = self.client.get_review_status(request).await?;
         response
@@ -387,10 +867,34 @@ impl ChessClient {
             .ok_or_else(|| ClientError::InvalidData("missing review status".into()))
     }
 
+    /// Cancel the pending review of a game, by game id or job id. Plies
+    /// analysed so far are kept. Returns the id of the job cancelled
+    pub async fn cancel_review(&mut self, id: &str) -> ClientResult<String> {
+        let request = CancelReviewRequest {
+            game_id: id.to_string(),
+        };
+        let response = self.client.cancel_review(request).await?;
+        Ok(response.into_inner().job_id)
+    }
+
+    /// Follow a review as it runs, by game id or job id: the current status
+    /// first, then one update per analysed ply until it completes or fails
+    pub async fn stream_review_progress(
+        &mut self,
+        id: &str,
+    ) -> ClientResult<tonic::Streaming<ReviewProgressUpdate>> {
+        let request = StreamReviewProgressRequest {
+            game_id: id.to_string(),
+        };
+        let response = self.client.stream_review_progress(request).await?;
+        Ok(response.into_inner())
+    }
+
     /// Get the full review for a game
     pub async fn get_game_review(&mut self, game_id: &str) -> ClientResult<GameReviewProto> {
         let request = GetGameReviewRequest {
             game_id: game_id.to_string(),
+            profile: self.profile.clone(),
         };
         let response = self.client.get_game_review(request).await?;
         response
@@ -417,6 +921,425 @@ impl ChessClient {
         Ok(())
     }
 
+    /// Import games played elsewhere from PGN text, optionally queueing
+    /// them for review
+    pub async fn import_pgn(&mut self, pgn: &str, review: bool) -> ClientResult<ImportPgnResponse> {
+        let request = ImportPgnRequest {
+            pgn: pgn.to_string(),
+            review,
+        };
+        let response = self.client.import_pgn(request).await?;
+        Ok(response.into_inner())
+    }
+
+    /// Get head-to-head stats against every named engine opponent
+    pub async fn get_opponent_stats(&mut self) -> ClientResult<Vec<OpponentStatsProto>> {
+        let request = GetOpponentStatsRequest {};
+        let response = self.client.get_opponent_stats(request).await?;
+        Ok(response.into_inner().opponents)
+    }
+
+    /// Find positions of finished games resembling `fen`, one per game,
+    /// with the games' results. `limit` of `None` returns up to 20.
+    pub async fn find_similar_positions(
+        &mut self,
+        fen: &str,
+        limit: Option<u32>,
+    ) -> ClientResult<FindSimilarPositionsResponse> {
+        let request = FindSimilarPositionsRequest {
+            fen: fen.to_string(),
+            limit,
+        };
+        let response = self.client.find_similar_positions(request).await?;
+        Ok(response.into_inner())
+    }
+
+    /// Run a time control over a finished game's recorded think times to
+    /// see whether either side would have lost on time
+    pub async fn simulate_clock(
+        &mut self,
+        game_id: &str,
+        initial_ms: u64,
+        increment_ms: u64,
+    ) -> ClientResult<SimulateClockResponse> {
+        let request = SimulateClockRequest {
+            game_id: game_id.to_string(),
+            initial_ms,
+            increment_ms,
+        };
+        let response = self.client.simulate_clock(request).await?;
+        Ok(response.into_inner())
+    }
+
+    /// Compare an earlier review pass of a game (the latest unless
+    /// `revision` is given) with its current review
+    pub async fn compare_reviews(
+        &mut self,
+        game_id: &str,
+        revision: Option<u32>,
+    ) -> ClientResult<CompareReviewsResponse> {
+        let request = CompareReviewsRequest {
+            game_id: game_id.to_string(),
+            revision,
+        };
+        let response = self.client.compare_reviews(request).await?;
+        Ok(response.into_inner())
+    }
+
+    /// Bookmark the position after `ply` of a finished game, replacing the
+    /// note of an earlier bookmark there
+    pub async fn set_review_bookmark(
+        &mut self,
+        game_id: &str,
+        ply: u32,
+        note: &str,
+    ) -> ClientResult<ReviewBookmark> {
+        let request = SetReviewBookmarkRequest {
+            game_id: game_id.to_string(),
+            ply,
+            note: note.to_string(),
+        };
+        let response = self.client.set_review_bookmark(request).await?;
+        Ok(response.into_inner())
+    }
+
+    /// A finished game's bookmarks, in ply order
+    pub async fn list_review_bookmarks(
+        &mut self,
+        game_id: &str,
+    ) -> ClientResult<Vec<ReviewBookmark>> {
+        let request = ListReviewBookmarksRequest {
+            game_id: game_id.to_string(),
+        };
+        let response = self.client.list_review_bookmarks(request).await?;
+        Ok(response.into_inner().bookmarks)
+    }
+
+    /// Remove the bookmark on `ply` of a finished game
+    pub async fn delete_review_bookmark(&mut self, game_id: &str, ply: u32) -> ClientResult<()> {
+        let request = DeleteReviewBookmarkRequest {
+            game_id: game_id.to_string(),
+            ply,
+        };
+        self.client.delete_review_bookmark(request).await?;
+        Ok(())
+    }
+
+    /// Analyse `fen` on the server's engine pool and return the best
+    /// `multipv` lines, best first. With neither depth nor movetime the
+    /// server searches to depth 18.
+    pub async fn analyze_position(
+        &mut self,
+        fen: &str,
+        depth: Option<u32>,
+        movetime_ms: Option<u64>,
+        multipv: u32,
+    ) -> ClientResult<Vec<AnalysisLine>> {
+        let request = AnalyzePositionRequest {
+            fen: fen.to_string(),
+            depth,
+            movetime_ms,
+            multipv,
+        };
+        let response = self.client.analyze_position(request).await?;
+        Ok(response.into_inner().lines)
+    }
+
+    /// Follow the best `multipv` lines of `fen` as an infinite search on the
+    /// server's engine pool deepens. The search runs until the stream is
+    /// dropped; it ends at once if the game is over in the position.
+    pub async fn stream_analysis(
+        &mut self,
+        fen: &str,
+        multipv: u32,
+    ) -> ClientResult<tonic::Streaming<AnalysisUpdate>> {
+        let request = StreamAnalysisRequest {
+            fen: fen.to_string(),
+            multipv,
+        };
+        let response = self.client.stream_analysis(request).await?;
+        Ok(response.into_inner())
+    }
+
+    /// Get a profile's daily activity over the last `days` days (0 for one year)
+    pub async fn get_activity_summary(
+        &mut self,
+        profile: &str,
+        days: u32,
+    ) -> ClientResult<GetActivitySummaryResponse> {
+        let request = GetActivitySummaryRequest {
+            profile: profile.to_string(),
+            days,
+        };
+        let response = self.client.get_activity_summary(request).await?;
+        Ok(response.into_inner())
+    }
+
+    /// Get the training digest for the last `days` days (0 for one week)
+    pub async fn get_training_digest(
+        &mut self,
+        days: u32,
+    ) -> ClientResult<GetTrainingDigestResponse> {
+        let request = GetTrainingDigestRequest { days };
+        let response = self.client.get_training_digest(request).await?;
+        Ok(response.into_inner())
+    }
+
+    /// Set a new goal for a profile
+    pub async fn add_goal(
+        &mut self,
+        profile: &str,
+        metric: GoalMetricType,
+        target: f64,
+        period: GoalPeriodType,
+    ) -> ClientResult<GoalProto> {
+        let request = AddGoalRequest {
+            profile: profile.to_string(),
+            metric: metric as i32,
+            target,
+            period: period as i32,
+        };
+        let response = self.client.add_goal(request).await?;
+        response
+            .into_inner()
+            .goal
+            .ok_or_else(|| ClientError::InvalidData("missing goal".into()))
+    }
+
+    /// Get a profile's goals with their progress in the current period
+    pub async fn list_goals(&mut self, profile: &str) -> ClientResult<Vec<GoalProgressProto>> {
+        let request = ListGoalsRequest {
+            profile: profile.to_string(),
+        };
+        let response = self.client.list_goals(request).await?;
+        Ok(response.into_inner().goals)
+    }
+
+    /// Delete one of a profile's goals
+    pub async fn delete_goal(&mut self, profile: &str, goal_id: &str) -> ClientResult<()> {
+        let request = DeleteGoalRequest {
+            profile: profile.to_string(),
+            goal_id: goal_id.to_string(),
+        };
+        self.client.delete_goal(request).await?;
+        Ok(())
+    }
+
+    /// Build a study from the finished games matching `filter`
+    pub async fn create_study(
+        &mut self,
+        name: &str,
+        filter: StudyFilterProto,
+    ) -> ClientResult<StudyInfo> {
+        let request = CreateStudyRequest {
+            name: name.to_string(),
+            filter: Some(filter),
+            blank: false,
+        };
+        let response = self.client.create_study(request).await?;
+        response
+            .into_inner()
+            .study
+            .ok_or_else(|| ClientError::InvalidData("missing study".into()))
+    }
+
+    /// Create a study built from no games, to hold chapters only
+    pub async fn create_blank_study(&mut self, name: &str) -> ClientResult<StudyInfo> {
+        let request = CreateStudyRequest {
+            name: name.to_string(),
+            filter: None,
+            blank: true,
+        };
+        let response = self.client.create_study(request).await?;
+        response
+            .into_inner()
+            .study
+            .ok_or_else(|| ClientError::InvalidData("missing study".into()))
+    }
+
+    /// List all studies, newest first
+    pub async fn list_studies(&mut self) -> ClientResult<Vec<StudyInfo>> {
+        let response = self.client.list_studies(ListStudiesRequest {}).await?;
+        Ok(response.into_inner().studies)
+    }
+
+    /// Get a study with its variation tree
+    pub async fn get_study(&mut self, study_id: &str) -> ClientResult<GetStudyResponse> {
+        let request = GetStudyRequest {
+            study_id: study_id.to_string(),
+        };
+        let response = self.client.get_study(request).await?;
+        Ok(response.into_inner())
+    }
+
+    /// Delete a study; its games are kept
+    pub async fn delete_study(&mut self, study_id: &str) -> ClientResult<()> {
+        let request = DeleteStudyRequest {
+            study_id: study_id.to_string(),
+        };
+        self.client.delete_study(request).await?;
+        Ok(())
+    }
+
+    /// Export a study as a multi-game PGN: its tree, its chapters, then each
+    /// of its games
+    pub async fn export_study_pgn(&mut self, study_id: &str) -> ClientResult<String> {
+        let request = ExportStudyPgnRequest {
+            study_id: study_id.to_string(),
+        };
+        let response = self.client.export_study_pgn(request).await?;
+        Ok(response.into_inner().pgn)
+    }
+
+    /// Add a chapter starting from `fen`, or with a finished game's moves as
+    /// its main line; with neither, from the standard position
+    pub async fn add_study_chapter(
+        &mut self,
+        study_id: &str,
+        name: &str,
+        fen: Option<String>,
+        game_id: Option<String>,
+    ) -> ClientResult<StudyChapterProto> {
+        let request = AddStudyChapterRequest {
+            study_id: study_id.to_string(),
+            name: name.to_string(),
+            fen,
+            game_id,
+        };
+        let response = self.client.add_study_chapter(request).await?;
+        response
+            .into_inner()
+            .chapter
+            .ok_or_else(|| ClientError::InvalidData("missing chapter".into()))
+    }
+
+    /// Delete a chapter of a study
+    pub async fn delete_study_chapter(
+        &mut self,
+        study_id: &str,
+        chapter_id: &str,
+    ) -> ClientResult<()> {
+        let request = DeleteStudyChapterRequest {
+            study_id: study_id.to_string(),
+            chapter_id: chapter_id.to_string(),
+        };
+        self.client.delete_study_chapter(request).await?;
+        Ok(())
+    }
+
+    /// Move a chapter to `index` among the study's chapters
+    pub async fn move_study_chapter(
+        &mut self,
+        study_id: &str,
+        chapter_id: &str,
+        index: u32,
+    ) -> ClientResult<()> {
+        let request = MoveStudyChapterRequest {
+            study_id: study_id.to_string(),
+            chapter_id: chapter_id.to_string(),
+            index,
+        };
+        self.client.move_study_chapter(request).await?;
+        Ok(())
+    }
+
+    /// Play a move, in UCI or SAN, from a node of a chapter
+    pub async fn add_chapter_move(
+        &mut self,
+        study_id: &str,
+        chapter_id: &str,
+        node_id: u32,
+        notation: &str,
+    ) -> ClientResult<AddChapterMoveResponse> {
+        let request = AddChapterMoveRequest {
+            study_id: study_id.to_string(),
+            chapter_id: chapter_id.to_string(),
+            node_id,
+            notation: notation.to_string(),
+        };
+        let response = self.client.add_chapter_move(request).await?;
+        Ok(response.into_inner())
+    }
+
+    /// Delete a move of a chapter with every move after it
+    pub async fn delete_chapter_move(
+        &mut self,
+        study_id: &str,
+        chapter_id: &str,
+        node_id: u32,
+    ) -> ClientResult<DeleteChapterMoveResponse> {
+        let request = DeleteChapterMoveRequest {
+            study_id: study_id.to_string(),
+            chapter_id: chapter_id.to_string(),
+            node_id,
+        };
+        let response = self.client.delete_chapter_move(request).await?;
+        Ok(response.into_inner())
+    }
+
+    /// Replace the comment, NAGs and arrows of a chapter's node
+    pub async fn annotate_chapter_node(
+        &mut self,
+        study_id: &str,
+        chapter_id: &str,
+        node_id: u32,
+        comment: Option<String>,
+        nags: Vec<u32>,
+        arrows: Vec<ChapterArrow>,
+    ) -> ClientResult<StudyChapterProto> {
+        let request = AnnotateChapterNodeRequest {
+            study_id: study_id.to_string(),
+            chapter_id: chapter_id.to_string(),
+            node_id,
+            comment,
+            nags,
+            arrows,
+        };
+        let response = self.client.annotate_chapter_node(request).await?;
+        response
+            .into_inner()
+            .chapter
+            .ok_or_else(|| ClientError::InvalidData("missing chapter".into()))
+    }
+
+    /// Ask the server to sync finished games and reviews with another server
+    pub async fn sync_with_peer(&mut self, peer: &str) -> ClientResult<SyncWithPeerResponse> {
+        let request = SyncWithPeerRequest {
+            peer: peer.to_string(),
+        };
+        let response = self.client.sync_with_peer(request).await?;
+        Ok(response.into_inner())
+    }
+
+    /// Get the most recent audit log entries, newest first
+    pub async fn list_audit_entries(&mut self, limit: u32) -> ClientResult<Vec<AuditEntryProto>> {
+        let response = self
+            .client
+            .list_audit_entries(ListAuditEntriesRequest { limit })
+            .await?;
+        Ok(response.into_inner().entries)
+    }
+
+    /// Get the state and UCI transcript of an open or recently closed
+    /// session, by session id or finished game id
+    pub async fn get_debug_report(&mut self, id: &str) -> ClientResult<GetDebugReportResponse> {
+        let request = GetDebugReportRequest { id: id.to_string() };
+        let response = self.client.get_debug_report(request).await?;
+        Ok(response.into_inner())
+    }
+
+    /// Get the combined report for all boards of a simul
+    pub async fn get_simul_report(
+        &mut self,
+        simul_id: &str,
+    ) -> ClientResult<GetSimulReportResponse> {
+        let request = GetSimulReportRequest {
+            simul_id: simul_id.to_string(),
+        };
+        let response = self.client.get_simul_report(request).await?;
+        Ok(response.into_inner())
+    }
+
     /// Get advanced analysis for a game (tactical patterns, king safety, tension, psychological profiles)
     pub async fn get_advanced_analysis(
         &mut self,
@@ -431,6 +1354,21 @@ impl ChessClient {
             .analysis
             .ok_or_else(|| ClientError::InvalidData("missing advanced analysis".into()))
     }
+
+    /// Why the best move at `ply` of a reviewed game is best: its line, the
+    /// tactics along it and what it wins
+    pub async fn explain_position(
+        &mut self,
+        game_id: &str,
+        ply: u32,
+    ) -> ClientResult<ExplainPositionResponse> {
+        let request = ExplainPositionRequest {
+            game_id: game_id.to_string(),
+            ply,
+        };
+        let response = self.client.explain_position(request).await?;
+        Ok(response.into_inner())
+    }
 }
 
 // ================================================================================
@@ -452,6 +1390,9 @@ impl ChessService for ChessClient {
             fen,
             game_mode,
             timer,
+            simul_id: None,
+            opponent: None,
+            profile: self.profile.clone(),
         };
         let response = self.client.create_session(request).await?;
         let snapshot = response.into_inner();
@@ -569,6 +1510,9 @@ impl ChessService for ChessClient {
             skill_level: skill_level as u32,
             threads: Some(threads),
             hash_mb: Some(hash_mb),
+            multipv: None,
+            book_path: None,
+            book_max_plies: None,
         };
 
         self.client.set_engine(request).await?;
@@ -585,6 +1529,7 @@ impl ChessService for ChessClient {
 
         let request = StreamEventsRequest {
             session_id: session_id.clone(),
+            watch: false,
         };
 
         let response = self.client.stream_events(request).await?;
@@ -9,6 +9,9 @@ pub enum ClientError {
     #[error("Invalid server address: {0}")]
     InvalidAddress(String),
 
+    #[error("TLS setup failed: {0}")]
+    Tls(String),
+
     #[error("Connection failed: {0}")]
     ConnectionFailed(#[from] tonic::transport::Error),
 
@@ -10,6 +10,7 @@
 //!
 //! #[tokio::main]
 //! async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     // A TCP endpoint, or `unix:/tmp/chesstty.sock` for the local socket
 //!     let mut client = ChessClient::connect("http://localhost:50051").await?;
 //!     let snapshot = client.create_session(None, None, None).await?;
 //!     println!("Created session: {}", snapshot.session_id);
@@ -17,14 +18,22 @@
 //! }
 //! ```
 
+mod addr;
 mod client;
 mod error;
 mod traits;
 
-pub use client::ChessClient;
+pub use addr::ServerAddr;
+pub use client::{tls_config_from_ca, ChessClient};
 pub use error::{ClientError, ClientResult};
 pub use traits::ChessService;
 
+// TLS settings for `ChessClient::connect_tcp_with_tls`, backed by rustls
+pub use tonic::transport::{Certificate, ClientTlsConfig};
+
+// Answer of `ChessClient::health`
+pub use tonic_health::pb::health_check_response::ServingStatus;
+
 // Re-export proto types for convenience
 pub use chess_proto::*;
 
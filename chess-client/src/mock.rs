@@ -119,6 +119,9 @@ impl MockChessService {
             move_count: 0,
             history: vec![],
             last_move: None,
+            undo_depth: 0,
+            redo_depth: 0,
+            next_redo: None,
             analysis: None,
             engine_config: None,
             game_mode: Some(GameModeProto {
@@ -128,6 +131,12 @@ impl MockChessService {
             engine_thinking: false,
             timer: None,
             start_fen: "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".to_string(),
+            engine_move_delay_ms: 0,
+            step_mode: false,
+            tactical_hints_enabled: false,
+            tactical_hints: vec![],
+            simul_id: None,
+            opponent: None,
         };
 
         let snapshot2 = snapshot.clone();
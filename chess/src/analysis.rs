@@ -13,6 +13,30 @@ pub struct EngineAnalysis {
     /// Principal variation as UCI move strings.
     pub pv: Vec<String>,
     pub nps: Option<u64>,
+    /// Line number when the engine searches several lines (MultiPV);
+    /// `None` or 1 is the best line.
+    pub multipv: Option<u32>,
+}
+
+/// What the engine saw when it played a move: the final search info before `bestmove`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EngineMoveTelemetry {
+    pub depth: Option<u32>,
+    /// Score from the perspective of the side that played the move.
+    pub score: Option<AnalysisScore>,
+    pub nodes: Option<u64>,
+    pub time_ms: Option<u64>,
+}
+
+impl From<&EngineAnalysis> for EngineMoveTelemetry {
+    fn from(analysis: &EngineAnalysis) -> Self {
+        Self {
+            depth: analysis.depth,
+            score: analysis.score.clone(),
+            nodes: analysis.nodes,
+            time_ms: analysis.time_ms,
+        }
+    }
 }
 
 /// Engine evaluation score.
@@ -20,7 +44,7 @@ pub struct EngineAnalysis {
 /// Centipawns: positive = side-to-move is better.
 /// Mate: positive N = side-to-move mates in N moves,
 /// negative N = side-to-move gets mated in N moves.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum AnalysisScore {
     Centipawns(i32),
     Mate(i32),
@@ -8,9 +8,10 @@ pub fn parse_square(s: &str) -> Option<Square> {
         return None;
     }
 
-    let chars: Vec<char> = s.chars().collect();
-    let file = parse_file(chars[0])?;
-    let rank = parse_rank(chars[1])?;
+    // Two bytes may still be a single multi-byte character
+    let mut chars = s.chars();
+    let file = parse_file(chars.next()?)?;
+    let rank = parse_rank(chars.next()?)?;
 
     Some(Square::new(file, rank))
 }
@@ -136,6 +137,8 @@ mod tests {
         let sq = parse_square("e2").unwrap();
         assert_eq!(sq.file(), File::E);
         assert_eq!(sq.rank(), Rank::Second);
+        assert_eq!(parse_square("é"), None);
+        assert_eq!(parse_square("e"), None);
     }
 
     #[test]
@@ -68,11 +68,15 @@ impl GamePhase {
     }
 }
 
-/// Determines who controls each side.
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// Determines who controls each side. The one definition of game modes
+/// shared by server, client and, through `chess-proto`, the wire format.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub enum GameMode {
+    #[default]
     HumanVsHuman,
-    HumanVsEngine { human_side: PlayerSide },
+    HumanVsEngine {
+        human_side: PlayerSide,
+    },
     EngineVsEngine,
     Analysis,
     Review,
@@ -85,6 +89,59 @@ pub enum PlayerSide {
     Black,
 }
 
+impl GameMode {
+    /// The mode as persisted: a name (`HumanVsEngine:White` for a human
+    /// against the engine) and the human's side (`white` or `black`).
+    pub fn to_stored(&self) -> (String, Option<String>) {
+        match self {
+            GameMode::HumanVsHuman => ("HumanVsHuman".to_string(), None),
+            GameMode::HumanVsEngine { human_side } => (
+                format!("HumanVsEngine:{:?}", human_side),
+                Some(human_side.as_str().to_string()),
+            ),
+            GameMode::EngineVsEngine => ("EngineVsEngine".to_string(), None),
+            GameMode::Analysis => ("Analysis".to_string(), None),
+            GameMode::Review => ("Review".to_string(), None),
+        }
+    }
+
+    /// Read back a persisted mode. The name may or may not carry the side;
+    /// the human plays White unless `human_side` says `black`. Unknown
+    /// names read as `HumanVsHuman`.
+    pub fn from_stored(game_mode: &str, human_side: Option<&str>) -> Self {
+        match game_mode.split(':').next().unwrap_or_default() {
+            "HumanVsEngine" => GameMode::HumanVsEngine {
+                human_side: match human_side {
+                    Some("black") => PlayerSide::Black,
+                    _ => PlayerSide::White,
+                },
+            },
+            "EngineVsEngine" => GameMode::EngineVsEngine,
+            "Analysis" => GameMode::Analysis,
+            "Review" => GameMode::Review,
+            _ => GameMode::HumanVsHuman,
+        }
+    }
+
+    /// The human's side when playing the engine.
+    pub fn human_side(&self) -> Option<PlayerSide> {
+        match self {
+            GameMode::HumanVsEngine { human_side } => Some(*human_side),
+            _ => None,
+        }
+    }
+}
+
+impl PlayerSide {
+    /// Lowercase name, as persisted.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PlayerSide::White => "white",
+            PlayerSide::Black => "black",
+        }
+    }
+}
+
 impl From<Color> for PlayerSide {
     fn from(c: Color) -> Self {
         match c {
@@ -133,14 +190,33 @@ impl Game {
         &self.history
     }
 
+    /// Number of undone moves that can be redone
+    pub fn redo_depth(&self) -> usize {
+        self.redo_stack.len()
+    }
+
+    /// The move the next redo plays, if any
+    pub fn next_redo(&self) -> Option<&HistoryEntry> {
+        self.redo_stack.last()
+    }
+
     /// Make a move on the board
     pub fn make_move(&mut self, mv: Move) -> Result<HistoryEntry, GameError> {
         // Validate move is legal
         if !self.legal_moves().contains(&mv) {
-            return Err(GameError::IllegalMove);
+            return Err(
+                match crate::legality::explain_illegal_move(&self.position, mv) {
+                    Some(reason) => GameError::IllegalMoveExplained(reason),
+                    None => GameError::IllegalMove,
+                },
+            );
         }
 
-        // Clear redo stack on new move (standard undo/redo behavior)
+        // Playing the move that would be redone keeps the rest of the undone
+        // line; any other move branches off and clears the redo stack
+        if self.redo_stack.last().is_some_and(|next| next.mv == mv) {
+            return self.redo();
+        }
         self.redo_stack.clear();
 
         // Clone the board before the move for O(1) undo
@@ -317,6 +393,39 @@ mod tests {
     use super::*;
     use cozy_chess::{File, Move, Piece, Rank, Square};
 
+    #[test]
+    fn test_game_mode_storage_round_trips() {
+        let modes = [
+            GameMode::HumanVsHuman,
+            GameMode::HumanVsEngine {
+                human_side: PlayerSide::White,
+            },
+            GameMode::HumanVsEngine {
+                human_side: PlayerSide::Black,
+            },
+            GameMode::EngineVsEngine,
+            GameMode::Analysis,
+            GameMode::Review,
+        ];
+        for mode in modes {
+            let (name, side) = mode.to_stored();
+            assert_eq!(GameMode::from_stored(&name, side.as_deref()), mode);
+        }
+        assert_eq!(
+            GameMode::HumanVsEngine {
+                human_side: PlayerSide::Black
+            }
+            .to_stored(),
+            ("HumanVsEngine:Black".to_string(), Some("black".to_string()))
+        );
+        // SQLite keeps the bare name, with the side in its own column
+        assert_eq!(
+            GameMode::from_stored("HumanVsEngine", Some("black")).human_side(),
+            Some(PlayerSide::Black)
+        );
+        assert_eq!(GameMode::from_stored("Bogus", None), GameMode::HumanVsHuman);
+    }
+
     fn mv(from_file: File, from_rank: Rank, to_file: File, to_rank: Rank) -> Move {
         Move {
             from: Square::new(from_file, from_rank),
@@ -325,6 +434,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_replaying_the_undone_move_keeps_redo_history() {
+        let mut game = Game::new();
+        let e4 = mv(File::E, Rank::Second, File::E, Rank::Fourth);
+        let e5 = mv(File::E, Rank::Seventh, File::E, Rank::Fifth);
+        game.make_move(e4).unwrap();
+        game.make_move(e5).unwrap();
+        game.undo().unwrap();
+        game.undo().unwrap();
+        assert_eq!(game.redo_depth(), 2);
+        assert_eq!(game.next_redo().map(|e| e.mv), Some(e4));
+
+        // The same move walks along the undone line
+        game.make_move(e4).unwrap();
+        assert_eq!(game.redo_depth(), 1);
+        assert_eq!(game.next_redo().map(|e| e.mv), Some(e5));
+
+        // A different one branches off and drops it
+        game.make_move(mv(File::C, Rank::Seventh, File::C, Rank::Fifth))
+            .unwrap();
+        assert_eq!(game.redo_depth(), 0);
+        assert!(game.next_redo().is_none());
+        assert_eq!(game.history().len(), 2);
+    }
+
     #[test]
     fn test_san_pawn_push() {
         let board: Board = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"
@@ -407,6 +541,19 @@ mod tests {
         assert_eq!(san, "Qh4");
     }
 
+    #[test]
+    fn test_illegal_move_is_explained() {
+        let mut game = Game::new();
+        let err = game
+            .make_move(mv(File::F, Rank::First, File::C, Rank::Fourth))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            GameError::IllegalMoveExplained(crate::IllegalMoveReason::BlockedPath { .. })
+        ));
+        assert!(game.history().is_empty());
+    }
+
     #[test]
     fn test_san_empty_square_fallback() {
         // Move from an empty square should fall back to UCI
@@ -421,6 +568,8 @@ mod tests {
 pub enum GameError {
     #[error("Illegal move")]
     IllegalMove,
+    #[error("Illegal move: {0}")]
+    IllegalMoveExplained(crate::legality::IllegalMoveReason),
     #[error("Nothing to undo")]
     NothingToUndo,
     #[error("Nothing to redo")]
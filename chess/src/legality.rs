@@ -0,0 +1,638 @@
+//! Explanations for why a move is illegal.
+//!
+//! `Board::is_legal` only answers yes or no. `explain_illegal_move` works out the
+//! most specific reason a learner would understand, along with the squares that
+//! matter (the pinning piece, the checker, the blocker, ...), so callers can show
+//! a message and highlight them.
+
+use std::fmt;
+
+use cozy_chess::{
+    get_between_rays, get_bishop_moves, get_king_moves, get_knight_moves, get_line_rays,
+    get_pawn_attacks, get_rook_moves, BitBoard, Board, Color, File, Move, Piece, Rank, Square,
+};
+
+/// Why a move cannot be played.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IllegalMoveReason {
+    /// There is no piece on the origin square.
+    NoPiece { square: Square },
+    /// The piece belongs to the side not on move.
+    NotYourTurn { square: Square },
+    /// The piece does not move that way.
+    InvalidPattern { piece: Piece, from: Square },
+    /// A pawn can only move diagonally when it captures.
+    PawnNeedsCapture { from: Square, to: Square },
+    /// A pawn is blocked by the piece directly in front of it.
+    PawnBlocked { from: Square, blocker: Square },
+    /// The destination holds one of the mover's own pieces.
+    OwnPieceOnTarget { square: Square },
+    /// Another piece stands between the origin and the destination.
+    BlockedPath { piece: Piece, blocker: Square },
+    /// Moving the piece would expose its king to the pinning piece.
+    Pinned {
+        piece: Piece,
+        square: Square,
+        pinner: Square,
+    },
+    /// The king is in check and the move does not deal with it.
+    InCheck { checkers: Vec<Square> },
+    /// The king would move onto an attacked square.
+    KingIntoCheck { to: Square, attackers: Vec<Square> },
+    /// The move would leave the king in check (e.g. an en passant discovery).
+    LeavesKingInCheck { attackers: Vec<Square> },
+    /// Castling on that side is no longer allowed.
+    NoCastlingRights { kingside: bool },
+    /// Castling is not allowed while in check.
+    CastleOutOfCheck { checkers: Vec<Square> },
+    /// Pieces stand between the king and the rook.
+    CastlingBlocked { blocker: Square },
+    /// The king would pass through or land on an attacked square.
+    CastleThroughCheck {
+        square: Square,
+        attackers: Vec<Square>,
+    },
+    /// The enemy pawn could only have been taken en passant right after it advanced.
+    EnPassantExpired { pawn: Square },
+    /// A pawn reaching the last rank must promote (and only pawns on the last rank may).
+    InvalidPromotion { to: Square },
+}
+
+impl IllegalMoveReason {
+    /// Squares worth highlighting to illustrate the reason.
+    pub fn squares(&self) -> Vec<Square> {
+        use IllegalMoveReason as R;
+        match self {
+            R::NoPiece { square } | R::NotYourTurn { square } | R::OwnPieceOnTarget { square } => {
+                vec![*square]
+            }
+            R::InvalidPattern { from, .. } => vec![*from],
+            R::PawnNeedsCapture { from, to } => vec![*from, *to],
+            R::PawnBlocked { from, blocker } => vec![*from, *blocker],
+            R::BlockedPath { blocker, .. } => vec![*blocker],
+            R::Pinned { square, pinner, .. } => vec![*square, *pinner],
+            R::InCheck { checkers } | R::CastleOutOfCheck { checkers } => checkers.clone(),
+            R::KingIntoCheck { to, attackers } => {
+                let mut squares = vec![*to];
+                squares.extend(attackers);
+                squares
+            }
+            R::LeavesKingInCheck { attackers } => attackers.clone(),
+            R::NoCastlingRights { .. } => vec![],
+            R::CastlingBlocked { blocker } => vec![*blocker],
+            R::CastleThroughCheck { square, attackers } => {
+                let mut squares = vec![*square];
+                squares.extend(attackers);
+                squares
+            }
+            R::EnPassantExpired { pawn } => vec![*pawn],
+            R::InvalidPromotion { to } => vec![*to],
+        }
+    }
+}
+
+impl fmt::Display for IllegalMoveReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use IllegalMoveReason as R;
+        match self {
+            R::NoPiece { square } => write!(f, "there is no piece on {}", square),
+            R::NotYourTurn { square } => write!(f, "the piece on {} is not yours to move", square),
+            R::InvalidPattern { piece, from } => {
+                write!(f, "the {} on {} doesn't move that way", name(*piece), from)
+            }
+            R::PawnNeedsCapture { to, .. } => {
+                write!(f, "pawns only move diagonally to capture, {} is empty", to)
+            }
+            R::PawnBlocked { blocker, .. } => write!(f, "the pawn is blocked by the piece on {}", blocker),
+            R::OwnPieceOnTarget { square } => write!(f, "your own piece is on {}", square),
+            R::BlockedPath { piece, blocker } => {
+                write!(f, "the {}'s path is blocked on {}", name(*piece), blocker)
+            }
+            R::Pinned {
+                piece,
+                square,
+                pinner,
+            } => write!(
+                f,
+                "the {} on {} is pinned to your king by the piece on {}",
+                name(*piece),
+                square,
+                pinner
+            ),
+            R::InCheck { checkers } => write!(
+                f,
+                "your king is in check from {} and must get out of it",
+                join(checkers)
+            ),
+            R::KingIntoCheck { to, attackers } => write!(
+                f,
+                "the king can't move to {}, it is attacked from {}",
+                to,
+                join(attackers)
+            ),
+            R::LeavesKingInCheck { attackers } => write!(
+                f,
+                "that would leave your king in check from {}",
+                join(attackers)
+            ),
+            R::NoCastlingRights { kingside } => write!(
+                f,
+                "you can no longer castle {}: the king or that rook has already moved",
+                if *kingside { "kingside" } else { "queenside" }
+            ),
+            R::CastleOutOfCheck { .. } => write!(f, "you can't castle while in check"),
+            R::CastlingBlocked { blocker } => {
+                write!(f, "you can't castle through the piece on {}", blocker)
+            }
+            R::CastleThroughCheck { square, .. } => write!(
+                f,
+                "you can't castle through or into check, {} is attacked",
+                square
+            ),
+            R::EnPassantExpired { pawn } => write!(
+                f,
+                "the pawn on {} could only be taken en passant right after it advanced",
+                pawn
+            ),
+            R::InvalidPromotion { to } => write!(
+                f,
+                "invalid promotion on {}: pawns promote to a queen, rook, bishop or knight exactly when they reach the last rank",
+                to
+            ),
+        }
+    }
+}
+
+/// Explain why `mv` is illegal on `board`, or `None` if it is legal.
+///
+/// Castling may be given either as the king's two-square move (`e1g1`) or in
+/// cozy-chess's king-takes-rook form (`e1h1`).
+pub fn explain_illegal_move(board: &Board, mv: Move) -> Option<IllegalMoveReason> {
+    use IllegalMoveReason as R;
+
+    if board.is_legal(mv) {
+        return None;
+    }
+
+    let us = board.side_to_move();
+    let Some(piece) = board.piece_on(mv.from) else {
+        return Some(R::NoPiece { square: mv.from });
+    };
+    if board.color_on(mv.from) != Some(us) {
+        return Some(R::NotYourTurn { square: mv.from });
+    }
+
+    if piece == Piece::King {
+        if let Some(kingside) = castling_side(board, mv) {
+            return explain_castling(board, mv, kingside);
+        }
+    }
+
+    if mv.from == mv.to {
+        return Some(R::InvalidPattern {
+            piece,
+            from: mv.from,
+        });
+    }
+    if board.colors(us).has(mv.to) {
+        return Some(R::OwnPieceOnTarget { square: mv.to });
+    }
+
+    let reason = match piece {
+        Piece::Pawn => explain_pawn(board, mv),
+        _ => explain_piece(board, mv, piece),
+    };
+    if reason.is_some() {
+        return reason;
+    }
+
+    if mv.promotion.is_some() || promotion_rank(us) == mv.to.rank() {
+        // The move geometry is fine; only the promotion piece is wrong or missing.
+        let mut fixed = mv;
+        fixed.promotion = if piece == Piece::Pawn && mv.to.rank() == promotion_rank(us) {
+            Some(Piece::Queen)
+        } else {
+            None
+        };
+        if board.is_legal(fixed) {
+            return Some(R::InvalidPromotion { to: mv.to });
+        }
+    }
+
+    Some(explain_king_safety(board, mv, piece))
+}
+
+/// Geometry and path checks for pieces other than pawns.
+fn explain_piece(board: &Board, mv: Move, piece: Piece) -> Option<IllegalMoveReason> {
+    let reachable = match piece {
+        Piece::Knight => get_knight_moves(mv.from),
+        Piece::King => get_king_moves(mv.from),
+        Piece::Bishop => get_bishop_moves(mv.from, BitBoard::EMPTY),
+        Piece::Rook => get_rook_moves(mv.from, BitBoard::EMPTY),
+        Piece::Queen => {
+            get_bishop_moves(mv.from, BitBoard::EMPTY) | get_rook_moves(mv.from, BitBoard::EMPTY)
+        }
+        Piece::Pawn => unreachable!("pawns are handled by explain_pawn"),
+    };
+    if !reachable.has(mv.to) {
+        return Some(IllegalMoveReason::InvalidPattern {
+            piece,
+            from: mv.from,
+        });
+    }
+
+    let blockers = get_between_rays(mv.from, mv.to) & board.occupied();
+    first_from(mv.from, blockers).map(|blocker| IllegalMoveReason::BlockedPath { piece, blocker })
+}
+
+/// Geometry checks for pawn pushes, captures and en passant.
+fn explain_pawn(board: &Board, mv: Move) -> Option<IllegalMoveReason> {
+    use IllegalMoveReason as R;
+
+    let us = board.side_to_move();
+    let forward: i8 = if us == Color::White { 1 } else { -1 };
+    let file_delta = mv.to.file() as i8 - mv.from.file() as i8;
+    let rank_delta = (mv.to.rank() as i8 - mv.from.rank() as i8) * forward;
+
+    match (file_delta.abs(), rank_delta) {
+        // Single push
+        (0, 1) => board.occupied().has(mv.to).then_some(R::PawnBlocked {
+            from: mv.from,
+            blocker: mv.to,
+        }),
+        // Double push from the starting rank
+        (0, 2) if mv.from.rank() == Rank::Second.relative_to(us) => {
+            let between = get_between_rays(mv.from, mv.to) | mv.to.bitboard();
+            first_from(mv.from, between & board.occupied()).map(|blocker| R::PawnBlocked {
+                from: mv.from,
+                blocker,
+            })
+        }
+        // Diagonal: only as a capture
+        (1, 1) => {
+            if board.colors(!us).has(mv.to) {
+                return None;
+            }
+            let passed = Square::new(mv.to.file(), mv.from.rank());
+            let beside_enemy_pawn = board.colored_pieces(!us, Piece::Pawn).has(passed);
+            let ep_rank = Rank::Fifth.relative_to(us);
+            if beside_enemy_pawn
+                && mv.from.rank() == ep_rank
+                && board.en_passant() != Some(mv.to.file())
+            {
+                Some(R::EnPassantExpired { pawn: passed })
+            } else if board.en_passant() == Some(mv.to.file()) && mv.from.rank() == ep_rank {
+                // En passant is available; any remaining problem is king safety
+                None
+            } else {
+                Some(R::PawnNeedsCapture {
+                    from: mv.from,
+                    to: mv.to,
+                })
+            }
+        }
+        _ => Some(R::InvalidPattern {
+            piece: Piece::Pawn,
+            from: mv.from,
+        }),
+    }
+}
+
+/// The move is geometrically fine, so it must expose the king.
+fn explain_king_safety(board: &Board, mv: Move, piece: Piece) -> IllegalMoveReason {
+    use IllegalMoveReason as R;
+
+    let us = board.side_to_move();
+    let king = board.king(us);
+
+    if piece == Piece::King {
+        // The king itself no longer blocks sliders along its line
+        let occupied = board.occupied() ^ mv.from.bitboard();
+        return R::KingIntoCheck {
+            to: mv.to,
+            attackers: squares(attackers(board, mv.to, !us, occupied)),
+        };
+    }
+
+    if board.pinned().has(mv.from) && !get_line_rays(king, mv.from).has(mv.to) {
+        if let Some(pinner) = find_pinner(board, king, mv.from) {
+            return R::Pinned {
+                piece,
+                square: mv.from,
+                pinner,
+            };
+        }
+    }
+
+    let checkers = board.checkers();
+    if !checkers.is_empty() {
+        return R::InCheck {
+            checkers: squares(checkers),
+        };
+    }
+
+    // E.g. an en passant capture that removes both pawns from the king's rank
+    let mut occupied = board.occupied() ^ mv.from.bitboard() | mv.to.bitboard();
+    if piece == Piece::Pawn && mv.from.file() != mv.to.file() && !board.occupied().has(mv.to) {
+        occupied ^= Square::new(mv.to.file(), mv.from.rank()).bitboard();
+    }
+    let attackers = attackers(board, king, !us, occupied) & !mv.to.bitboard();
+    R::LeavesKingInCheck {
+        attackers: squares(attackers),
+    }
+}
+
+fn explain_castling(board: &Board, mv: Move, kingside: bool) -> Option<IllegalMoveReason> {
+    use IllegalMoveReason as R;
+
+    let us = board.side_to_move();
+    let rights = board.castle_rights(us);
+    let Some(rook_file) = (if kingside { rights.short } else { rights.long }) else {
+        return Some(R::NoCastlingRights { kingside });
+    };
+
+    let back_rank = Rank::First.relative_to(us);
+    let rook = Square::new(rook_file, back_rank);
+    if board.is_legal(Move {
+        from: mv.from,
+        to: rook,
+        promotion: None,
+    }) {
+        return None;
+    }
+
+    let checkers = board.checkers();
+    if !checkers.is_empty() {
+        return Some(R::CastleOutOfCheck {
+            checkers: squares(checkers),
+        });
+    }
+
+    let (king_dest, rook_dest) = if kingside {
+        (
+            Square::new(File::G, back_rank),
+            Square::new(File::F, back_rank),
+        )
+    } else {
+        (
+            Square::new(File::C, back_rank),
+            Square::new(File::D, back_rank),
+        )
+    };
+    let must_be_empty = get_between_rays(mv.from, rook)
+        | get_between_rays(mv.from, king_dest)
+        | king_dest.bitboard()
+        | rook_dest.bitboard();
+    let occupied = board.occupied() ^ mv.from.bitboard() ^ rook.bitboard();
+    if let Some(blocker) = first_from(mv.from, must_be_empty & occupied) {
+        return Some(R::CastlingBlocked { blocker });
+    }
+
+    let king_path = get_between_rays(mv.from, king_dest) | king_dest.bitboard();
+    for square in ordered_from(mv.from, king_path) {
+        let attacking = attackers(board, square, !us, board.occupied());
+        if !attacking.is_empty() {
+            return Some(R::CastleThroughCheck {
+                square,
+                attackers: squares(attacking),
+            });
+        }
+    }
+
+    Some(explain_king_safety(board, mv, Piece::King))
+}
+
+/// Which side a king move is trying to castle to, if it looks like castling.
+fn castling_side(board: &Board, mv: Move) -> Option<bool> {
+    let us = board.side_to_move();
+    let back_rank = Rank::First.relative_to(us);
+    if mv.from.rank() != back_rank || mv.to.rank() != back_rank {
+        return None;
+    }
+    let two_squares = (mv.to.file() as i8 - mv.from.file() as i8).abs() == 2;
+    let onto_own_rook = board.colored_pieces(us, Piece::Rook).has(mv.to);
+    (two_squares || onto_own_rook).then_some(mv.to.file() > mv.from.file())
+}
+
+/// Pieces of `color` attacking `target`, given a custom occupancy.
+fn attackers(board: &Board, target: Square, color: Color, occupied: BitBoard) -> BitBoard {
+    let theirs = board.colors(color) & occupied;
+    let diagonal = board.pieces(Piece::Bishop) | board.pieces(Piece::Queen);
+    let orthogonal = board.pieces(Piece::Rook) | board.pieces(Piece::Queen);
+    theirs
+        & ((get_pawn_attacks(target, !color) & board.pieces(Piece::Pawn))
+            | (get_knight_moves(target) & board.pieces(Piece::Knight))
+            | (get_king_moves(target) & board.pieces(Piece::King))
+            | (get_bishop_moves(target, occupied) & diagonal)
+            | (get_rook_moves(target, occupied) & orthogonal))
+}
+
+/// The enemy slider pinning the piece on `pinned` to the king.
+fn find_pinner(board: &Board, king: Square, pinned: Square) -> Option<Square> {
+    let them = !board.side_to_move();
+    let without_pinned = board.occupied() ^ pinned.bitboard();
+    let line = get_line_rays(king, pinned);
+    (attackers(board, king, them, without_pinned) & line)
+        .into_iter()
+        .find(|&sq| get_between_rays(king, sq).has(pinned))
+}
+
+fn first_from(origin: Square, set: BitBoard) -> Option<Square> {
+    ordered_from(origin, set).into_iter().next()
+}
+
+/// Squares in `set`, nearest to `origin` first.
+fn ordered_from(origin: Square, set: BitBoard) -> Vec<Square> {
+    let mut squares = squares(set);
+    squares.sort_by_key(|sq| distance(origin, *sq));
+    squares
+}
+
+fn distance(a: Square, b: Square) -> u8 {
+    let files = (a.file() as i8 - b.file() as i8).unsigned_abs();
+    let ranks = (a.rank() as i8 - b.rank() as i8).unsigned_abs();
+    files.max(ranks)
+}
+
+fn squares(set: BitBoard) -> Vec<Square> {
+    set.into_iter().collect()
+}
+
+fn promotion_rank(color: Color) -> Rank {
+    Rank::Eighth.relative_to(color)
+}
+
+fn join(squares: &[Square]) -> String {
+    squares
+        .iter()
+        .map(|sq| sq.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn name(piece: Piece) -> &'static str {
+    match piece {
+        Piece::Pawn => "pawn",
+        Piece::Knight => "knight",
+        Piece::Bishop => "bishop",
+        Piece::Rook => "rook",
+        Piece::Queen => "queen",
+        Piece::King => "king",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use IllegalMoveReason as R;
+
+    fn board(fen: &str) -> Board {
+        fen.parse().unwrap()
+    }
+
+    fn mv(uci: &str) -> Move {
+        uci.parse().unwrap()
+    }
+
+    #[test]
+    fn legal_move_has_no_explanation() {
+        assert_eq!(explain_illegal_move(&Board::default(), mv("e2e4")), None);
+    }
+
+    #[test]
+    fn empty_and_enemy_squares() {
+        let b = Board::default();
+        assert_eq!(
+            explain_illegal_move(&b, mv("e4e5")),
+            Some(R::NoPiece {
+                square: "e4".parse().unwrap()
+            })
+        );
+        assert_eq!(
+            explain_illegal_move(&b, mv("e7e5")),
+            Some(R::NotYourTurn {
+                square: "e7".parse().unwrap()
+            })
+        );
+    }
+
+    #[test]
+    fn blocked_path_and_bad_pattern() {
+        let b = Board::default();
+        assert_eq!(
+            explain_illegal_move(&b, mv("f1c4")),
+            Some(R::BlockedPath {
+                piece: Piece::Bishop,
+                blocker: "e2".parse().unwrap()
+            })
+        );
+        assert_eq!(
+            explain_illegal_move(&b, mv("g1g3")),
+            Some(R::InvalidPattern {
+                piece: Piece::Knight,
+                from: "g1".parse().unwrap()
+            })
+        );
+        assert_eq!(
+            explain_illegal_move(&b, mv("e2d3")),
+            Some(R::PawnNeedsCapture {
+                from: "e2".parse().unwrap(),
+                to: "d3".parse().unwrap()
+            })
+        );
+        assert_eq!(
+            explain_illegal_move(&b, mv("d1d2")),
+            Some(R::OwnPieceOnTarget {
+                square: "d2".parse().unwrap()
+            })
+        );
+    }
+
+    #[test]
+    fn pinned_piece() {
+        // Knight on e2 pinned by the rook on e8
+        let b = board("4r1k1/8/8/8/8/8/4N3/4K3 w - - 0 1");
+        assert_eq!(
+            explain_illegal_move(&b, mv("e2c3")),
+            Some(R::Pinned {
+                piece: Piece::Knight,
+                square: "e2".parse().unwrap(),
+                pinner: "e8".parse().unwrap()
+            })
+        );
+    }
+
+    #[test]
+    fn check_must_be_answered() {
+        // Rook on e8 gives check; moving the a-pawn doesn't help
+        let b = board("4r1k1/8/8/8/8/8/P7/4K3 w - - 0 1");
+        assert_eq!(
+            explain_illegal_move(&b, mv("a2a3")),
+            Some(R::InCheck {
+                checkers: vec!["e8".parse().unwrap()]
+            })
+        );
+        assert_eq!(
+            explain_illegal_move(&b, mv("e1e2")),
+            Some(R::KingIntoCheck {
+                to: "e2".parse().unwrap(),
+                attackers: vec!["e8".parse().unwrap()]
+            })
+        );
+    }
+
+    #[test]
+    fn castling_reasons() {
+        let no_rights = board("4k3/8/8/8/8/8/8/4K2R w - - 0 1");
+        assert_eq!(
+            explain_illegal_move(&no_rights, mv("e1g1")),
+            Some(R::NoCastlingRights { kingside: true })
+        );
+
+        let blocked = board("4k3/8/8/8/8/8/8/4KB1R w K - 0 1");
+        assert_eq!(
+            explain_illegal_move(&blocked, mv("e1g1")),
+            Some(R::CastlingBlocked {
+                blocker: "f1".parse().unwrap()
+            })
+        );
+
+        let through_check = board("4kr2/8/8/8/8/8/8/4K2R w K - 0 1");
+        assert_eq!(
+            explain_illegal_move(&through_check, mv("e1g1")),
+            Some(R::CastleThroughCheck {
+                square: "f1".parse().unwrap(),
+                attackers: vec!["f8".parse().unwrap()]
+            })
+        );
+
+        let legal = board("4k3/8/8/8/8/8/8/4K2R w K - 0 1");
+        assert_eq!(explain_illegal_move(&legal, mv("e1g1")), None);
+    }
+
+    #[test]
+    fn en_passant_expired() {
+        // Black pawn on d5 sits beside the e5 pawn, but no en passant square is set
+        let b = board("4k3/8/8/3pP3/8/8/8/4K3 w - - 0 1");
+        assert_eq!(
+            explain_illegal_move(&b, mv("e5d6")),
+            Some(R::EnPassantExpired {
+                pawn: "d5".parse().unwrap()
+            })
+        );
+    }
+
+    #[test]
+    fn explanation_mentions_the_squares() {
+        let b = board("4r1k1/8/8/8/8/8/4N3/4K3 w - - 0 1");
+        let reason = explain_illegal_move(&b, mv("e2c3")).unwrap();
+        assert_eq!(
+            reason.to_string(),
+            "the knight on e2 is pinned to your king by the piece on e8"
+        );
+        assert_eq!(
+            reason.squares(),
+            vec!["e2".parse().unwrap(), "e8".parse().unwrap()]
+        );
+    }
+}
@@ -3,14 +3,23 @@ pub mod board_display;
 pub mod converters;
 pub mod fen;
 pub mod game;
+pub mod legality;
+pub mod notation;
+pub mod pgn;
 pub mod types;
 pub mod uci;
 
-pub use analysis::{is_white_ply, AnalysisScore, EngineAnalysis};
+#[cfg(all(test, feature = "property-tests"))]
+mod proptests;
+
+pub use analysis::{is_white_ply, AnalysisScore, EngineAnalysis, EngineMoveTelemetry};
 pub use board_display::{DisplayBoard, DisplayBoardError};
 pub use converters::*;
 pub use game::{
     format_move_as_san, Game, GameError, GameMode, GamePhase, GameResult, HistoryEntry, PlayerSide,
 };
+pub use legality::{explain_illegal_move, IllegalMoveReason};
+pub use notation::{parse_san, Notation, NotationStyle, PieceLanguage};
+pub use pgn::{PgnGame, PgnMove};
 pub use types::{PieceColor, PieceKind};
 pub use uci::{convert_uci_castling_to_cozy, format_uci_move};
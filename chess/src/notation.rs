@@ -0,0 +1,339 @@
+//! Display forms of standard algebraic notation.
+//!
+//! Moves are stored and exchanged as standard SAN (`Nf3`, `exd5`, `e8=Q+`);
+//! a [`Notation`] only changes how a move is shown: with figurines
+//! (`♘f3`), in long algebraic form (`Ng1-f3`) or with the piece letters of
+//! another language (`Sf3` in German). PGN and anything else read by other
+//! programs keep standard SAN.
+
+use crate::converters::{format_square, parse_file, parse_rank, parse_square};
+use cozy_chess::{Board, Move, Piece};
+
+/// How moves are written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NotationStyle {
+    /// Short algebraic, `Nf3`.
+    #[default]
+    San,
+    /// Short algebraic with piece figurines, `♘f3`.
+    Figurine,
+    /// Long algebraic with both squares, `Ng1-f3`.
+    Long,
+}
+
+impl NotationStyle {
+    pub const ALL: [NotationStyle; 3] = [Self::San, Self::Figurine, Self::Long];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::San => "SAN",
+            Self::Figurine => "Figurine SAN",
+            Self::Long => "Long algebraic",
+        }
+    }
+}
+
+/// Language of the piece letters, for the styles that use letters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PieceLanguage {
+    #[default]
+    English,
+    German,
+    French,
+    Spanish,
+    Dutch,
+}
+
+impl PieceLanguage {
+    pub const ALL: [PieceLanguage; 5] = [
+        Self::English,
+        Self::German,
+        Self::French,
+        Self::Spanish,
+        Self::Dutch,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::English => "English",
+            Self::German => "German",
+            Self::French => "French",
+            Self::Spanish => "Spanish",
+            Self::Dutch => "Dutch",
+        }
+    }
+
+    /// Letters for king, queen, rook, bishop and knight.
+    pub fn letters(self) -> [char; 5] {
+        match self {
+            Self::English => ['K', 'Q', 'R', 'B', 'N'],
+            Self::German => ['K', 'D', 'T', 'L', 'S'],
+            Self::French => ['R', 'D', 'T', 'F', 'C'],
+            Self::Spanish => ['R', 'D', 'T', 'A', 'C'],
+            Self::Dutch => ['K', 'D', 'T', 'L', 'P'],
+        }
+    }
+
+    pub fn letter(self, piece: Piece) -> char {
+        let [k, q, r, b, n] = self.letters();
+        match piece {
+            Piece::King => k,
+            Piece::Queen => q,
+            Piece::Rook => r,
+            Piece::Bishop => b,
+            Piece::Knight => n,
+            Piece::Pawn => 'P',
+        }
+    }
+}
+
+/// English SAN piece letter.
+fn san_piece(c: char) -> Option<Piece> {
+    match c {
+        'K' => Some(Piece::King),
+        'Q' => Some(Piece::Queen),
+        'R' => Some(Piece::Rook),
+        'B' => Some(Piece::Bishop),
+        'N' => Some(Piece::Knight),
+        _ => None,
+    }
+}
+
+fn figurine(piece: Piece) -> char {
+    match piece {
+        Piece::King => '♔',
+        Piece::Queen => '♕',
+        Piece::Rook => '♖',
+        Piece::Bishop => '♗',
+        Piece::Knight => '♘',
+        Piece::Pawn => '♙',
+    }
+}
+
+/// Split the check, mate and annotation marks off the end of a move.
+fn split_suffix(san: &str) -> (&str, &str) {
+    let core = san.trim_end_matches(['+', '#', '!', '?']);
+    (core, &san[core.len()..])
+}
+
+/// The move written by `san` in `board`, if exactly one legal move fits.
+/// Castling is left to the caller, since it is written the same in every
+/// style.
+pub fn parse_san(board: &Board, san: &str) -> Option<Move> {
+    let (core, _) = split_suffix(san.trim());
+    if core.starts_with("O-O") || core.starts_with("0-0") {
+        return None;
+    }
+
+    let (body, promotion) = match core.split_once('=') {
+        Some((body, promo)) => (body, Some(san_piece(promo.chars().next()?)?)),
+        None => (core, None),
+    };
+    let mut chars: Vec<char> = body.chars().filter(|&c| c != 'x' && c != '-').collect();
+    let piece = match chars.first().copied().and_then(san_piece) {
+        Some(piece) => {
+            chars.remove(0);
+            piece
+        }
+        None => Piece::Pawn,
+    };
+    if chars.len() < 2 {
+        return None;
+    }
+    let to: String = chars[chars.len() - 2..].iter().collect();
+    let to = parse_square(&to)?;
+    let hints = &chars[..chars.len() - 2];
+    let file = hints.iter().find_map(|&c| parse_file(c));
+    let rank = hints.iter().find_map(|&c| parse_rank(c));
+
+    let mut found = None;
+    let mut count = 0;
+    board.generate_moves(|moves| {
+        if moves.piece == piece {
+            for mv in moves {
+                if mv.to == to
+                    && mv.promotion == promotion
+                    && file.is_none_or(|f| mv.from.file() == f)
+                    && rank.is_none_or(|r| mv.from.rank() == r)
+                {
+                    found = Some(mv);
+                    count += 1;
+                }
+            }
+        }
+        false
+    });
+    found.filter(|_| count == 1)
+}
+
+/// A choice of how moves are shown.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Notation {
+    pub style: NotationStyle,
+    pub language: PieceLanguage,
+}
+
+impl Notation {
+    /// Symbol for a piece: a figurine, or the letter in the chosen language.
+    pub fn piece(&self, piece: Piece) -> char {
+        match self.style {
+            NotationStyle::Figurine => figurine(piece),
+            _ => self.language.letter(piece),
+        }
+    }
+
+    /// SAN with its piece letters replaced, keeping everything else.
+    fn short(&self, san: &str) -> String {
+        san.chars()
+            .map(|c| san_piece(c).map_or(c, |piece| self.piece(piece)))
+            .collect()
+    }
+
+    /// Show a standard SAN move. The long form needs `before`, the position
+    /// the move was played in, and falls back to the short form without it
+    /// or when the move cannot be found there.
+    pub fn format(&self, san: &str, before: Option<&Board>) -> String {
+        if self.style != NotationStyle::Long {
+            return self.short(san);
+        }
+        let mv = before.and_then(|board| Some((board, parse_san(board, san)?)));
+        match mv {
+            Some((board, mv)) => {
+                let piece = board.piece_on(mv.from);
+                let capture = board.color_on(mv.to).is_some()
+                    || (piece == Some(Piece::Pawn) && mv.from.file() != mv.to.file());
+                self.long(san, mv, piece, capture)
+            }
+            None => self.short(san),
+        }
+    }
+
+    /// Show a standard SAN move whose squares are already known, such as a
+    /// move from the game history.
+    pub fn format_with_squares(
+        &self,
+        san: &str,
+        from: &str,
+        to: &str,
+        piece: Option<Piece>,
+        capture: bool,
+    ) -> String {
+        if self.style != NotationStyle::Long {
+            return self.short(san);
+        }
+        match (parse_square(from), parse_square(to)) {
+            (Some(from), Some(to)) => {
+                let promotion = split_suffix(san)
+                    .0
+                    .split_once('=')
+                    .and_then(|(_, p)| p.chars().next())
+                    .and_then(san_piece);
+                let mv = Move {
+                    from,
+                    to,
+                    promotion,
+                };
+                self.long(san, mv, piece, capture)
+            }
+            _ => self.short(san),
+        }
+    }
+
+    /// `Ng1-f3`, `e4xd5`, `e7-e8=Q+`; castling stays `O-O`.
+    fn long(&self, san: &str, mv: Move, piece: Option<Piece>, capture: bool) -> String {
+        let (core, suffix) = split_suffix(san);
+        if core.starts_with("O-O") || core.starts_with("0-0") {
+            return san.to_string();
+        }
+        let mut text = String::new();
+        if let Some(piece) = piece.filter(|&p| p != Piece::Pawn) {
+            text.push(self.piece(piece));
+        }
+        text.push_str(&format_square(mv.from));
+        text.push(if capture { 'x' } else { '-' });
+        text.push_str(&format_square(mv.to));
+        if let Some(promotion) = mv.promotion {
+            text.push('=');
+            text.push(self.piece(promotion));
+        }
+        text.push_str(suffix);
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cozy_chess::Square;
+
+    fn notation(style: NotationStyle, language: PieceLanguage) -> Notation {
+        Notation { style, language }
+    }
+
+    #[test]
+    fn test_parse_san() {
+        let board = Board::default();
+        assert_eq!(
+            parse_san(&board, "Nf3"),
+            Some(Move {
+                from: Square::G1,
+                to: Square::F3,
+                promotion: None
+            })
+        );
+        assert_eq!(
+            parse_san(&board, "e4!?"),
+            Some(Move {
+                from: Square::E2,
+                to: Square::E4,
+                promotion: None
+            })
+        );
+        assert_eq!(parse_san(&board, "Nd4"), None);
+
+        // Both knights reach d2; the file picks one
+        let board: Board = "4k3/8/8/8/8/8/8/1N2KN2 w - - 0 1".parse().unwrap();
+        assert_eq!(parse_san(&board, "Nd2"), None);
+        let knight = parse_san(&board, "Nbd2").unwrap();
+        assert_eq!(knight.from, Square::B1);
+    }
+
+    #[test]
+    fn test_short_styles() {
+        let figurine = notation(NotationStyle::Figurine, PieceLanguage::German);
+        assert_eq!(figurine.format("Nxf3+", None), "♘xf3+");
+        assert_eq!(figurine.format("e8=Q#", None), "e8=♕#");
+        assert_eq!(figurine.format("O-O", None), "O-O");
+
+        let german = notation(NotationStyle::San, PieceLanguage::German);
+        assert_eq!(german.format("Nf3", None), "Sf3");
+        assert_eq!(german.format("Bxe5", None), "Lxe5");
+        assert_eq!(german.format("exd8=Q", None), "exd8=D");
+        // Lower-case file letters are never pieces
+        assert_eq!(german.format("bxc3", None), "bxc3");
+    }
+
+    #[test]
+    fn test_long_form() {
+        let long = notation(NotationStyle::Long, PieceLanguage::English);
+        let board = Board::default();
+        assert_eq!(long.format("Nf3", Some(&board)), "Ng1-f3");
+        assert_eq!(long.format("e4", Some(&board)), "e2-e4");
+        // Without the position the short form is kept
+        assert_eq!(long.format("Nf3", None), "Nf3");
+
+        let board: Board = "4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1".parse().unwrap();
+        assert_eq!(long.format("exd5+", Some(&board)), "e4xd5+");
+        assert_eq!(long.format("O-O", Some(&board)), "O-O");
+
+        let french = notation(NotationStyle::Long, PieceLanguage::French);
+        assert_eq!(
+            french.format_with_squares("exd8=Q", "e7", "d8", Some(Piece::Pawn), true),
+            "e7xd8=D"
+        );
+        assert_eq!(
+            french.format_with_squares("Nf3", "g1", "f3", Some(Piece::Knight), false),
+            "Cg1-f3"
+        );
+    }
+}
@@ -0,0 +1,536 @@
+//! Reading and writing PGN.
+//!
+//! The parser keeps what an annotated game carries: the tag pairs, the main
+//! line, nested variations, `{}` and `;` comments and NAGs, with the move
+//! suffixes `!`, `?`, `!!`, `??`, `!?` and `?!` read as their NAGs. Moves are
+//! kept as written; checking them against a position is up to the caller.
+//! The writer numbers moves from the `FEN` tag, writes NAGs as `$n` and
+//! wraps the movetext at 80 columns, as the PGN export format asks.
+
+use crate::game::GameResult;
+use cozy_chess::{Board, Color};
+
+/// Result tokens ending a game's movetext.
+pub const RESULTS: [&str; 4] = ["1-0", "0-1", "1/2-1/2", "*"];
+
+/// Column the writer wraps movetext at.
+const LINE_WIDTH: usize = 80;
+
+/// Move suffixes and the NAGs they stand for, longest first.
+const SUFFIX_NAGS: [(&str, u8); 6] = [
+    ("!!", 3),
+    ("??", 4),
+    ("!?", 5),
+    ("?!", 6),
+    ("!", 1),
+    ("?", 2),
+];
+
+/// One game as written in a PGN file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PgnGame {
+    pub tags: Vec<(String, String)>,
+    pub moves: Vec<PgnMove>,
+    /// Result token ending the movetext, `*` when missing.
+    pub result: String,
+}
+
+/// A move with its annotations and the variations that replace it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PgnMove {
+    /// SAN as written, without `!`/`?` suffixes.
+    pub san: String,
+    pub nags: Vec<u8>,
+    /// Comment before the move, e.g. at the start of the game or of a
+    /// variation.
+    pub comment_before: Option<String>,
+    pub comment: Option<String>,
+    /// Alternatives to this move, each played from the position before it.
+    pub variations: Vec<Vec<PgnMove>>,
+}
+
+impl PgnMove {
+    pub fn new(san: impl Into<String>) -> Self {
+        Self {
+            san: san.into(),
+            ..Default::default()
+        }
+    }
+}
+
+impl PgnGame {
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The result from the movetext, or the `Result` tag when the movetext
+    /// leaves it open. `None` for an unfinished game.
+    pub fn outcome(&self) -> Option<GameResult> {
+        let result = match self.result.as_str() {
+            "*" | "" => self.tag("Result").unwrap_or("*"),
+            result => result,
+        };
+        match result {
+            "1-0" => Some(GameResult::WhiteWins),
+            "0-1" => Some(GameResult::BlackWins),
+            "1/2-1/2" => Some(GameResult::Draw),
+            _ => None,
+        }
+    }
+
+    /// SAN of the main-line moves.
+    pub fn main_line(&self) -> impl Iterator<Item = &str> {
+        self.moves.iter().map(|mv| mv.san.as_str())
+    }
+
+    /// Position the game starts from: its `FEN` tag, or the standard one.
+    pub fn start_board(&self) -> Board {
+        self.tag("FEN")
+            .and_then(|fen| fen.parse().ok())
+            .unwrap_or_default()
+    }
+
+    /// The game as PGN: tag pairs, a blank line, then the movetext and the
+    /// result.
+    pub fn to_pgn(&self) -> String {
+        let mut pgn = String::new();
+        for (name, value) in &self.tags {
+            let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+            pgn.push_str(&format!("[{} \"{}\"]\n", name, value));
+        }
+        if !self.tags.is_empty() {
+            pgn.push('\n');
+        }
+
+        let mut tokens = Vec::new();
+        write_line(&self.moves, start_ply(&self.start_board()), &mut tokens);
+        tokens.push(match self.result.as_str() {
+            "" => "*".to_string(),
+            result => result.to_string(),
+        });
+        pgn.push_str(&wrap(&tokens));
+        pgn
+    }
+}
+
+/// Numbered movetext of `moves` played from `start` on one line, without a
+/// result: `1. e4 e5 2. Nf3`, or `12... Qd7 13. Re1` when Black moved first.
+pub fn movetext(start: &Board, moves: &[PgnMove]) -> String {
+    let mut tokens = Vec::new();
+    write_line(moves, start_ply(start), &mut tokens);
+    tokens.join(" ")
+}
+
+/// Plies played before `board`, counted from 1. e4.
+fn start_ply(board: &Board) -> u32 {
+    let moved = u32::from(board.side_to_move() == Color::Black);
+    (u32::from(board.fullmove_number()).max(1) - 1) * 2 + moved
+}
+
+/// Tokens of one line starting `ply` plies into the game, variations
+/// included.
+fn write_line(moves: &[PgnMove], mut ply: u32, tokens: &mut Vec<String>) {
+    // Black's moves need their number after anything but White's move
+    let mut numbered = false;
+    for mv in moves {
+        if let Some(ref comment) = mv.comment_before {
+            tokens.push(format!("{{{}}}", comment));
+            numbered = false;
+        }
+        // The number stays on the same line as its move
+        let number = ply / 2 + 1;
+        tokens.push(if ply % 2 == 0 {
+            format!("{}. {}", number, mv.san)
+        } else if !numbered {
+            format!("{}... {}", number, mv.san)
+        } else {
+            mv.san.clone()
+        });
+        tokens.extend(mv.nags.iter().map(|nag| format!("${}", nag)));
+        numbered = true;
+        if let Some(ref comment) = mv.comment {
+            tokens.push(format!("{{{}}}", comment));
+            numbered = false;
+        }
+        for variation in &mv.variations {
+            let mut inner = Vec::new();
+            write_line(variation, ply, &mut inner);
+            if let Some(first) = inner.first_mut() {
+                first.insert(0, '(');
+            }
+            if let Some(last) = inner.last_mut() {
+                last.push(')');
+            }
+            tokens.extend(inner);
+            numbered = false;
+        }
+        ply += 1;
+    }
+}
+
+/// `tokens` separated by spaces, broken into lines of at most
+/// [`LINE_WIDTH`] columns where possible.
+fn wrap(tokens: &[String]) -> String {
+    let mut text = String::new();
+    let mut column = 0;
+    for token in tokens {
+        let len = token.chars().count();
+        if column > 0 && column + 1 + len > LINE_WIDTH {
+            text.push('\n');
+            column = 0;
+        } else if column > 0 {
+            text.push(' ');
+            column += 1;
+        }
+        text.push_str(token);
+        column += len;
+    }
+    text
+}
+
+/// A piece of movetext.
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Move(String),
+    Nag(u8),
+    Comment(String),
+    Open,
+    Close,
+    Result(String),
+}
+
+/// Movetext scanner state carried from line to line.
+#[derive(Default)]
+struct Lexer {
+    /// Text of a `{}` comment still open at the end of the last line.
+    comment: Option<String>,
+    variation_depth: u32,
+}
+
+impl Lexer {
+    /// Whether the scanner is between tokens of the main line, where a tag
+    /// pair or an escape line may start.
+    fn at_top_level(&self) -> bool {
+        self.comment.is_none() && self.variation_depth == 0
+    }
+
+    /// Tokens on `line`. A result only counts on the main line.
+    fn tokens(&mut self, line: &str) -> Vec<Token> {
+        let mut tokens = Vec::new();
+        let mut word = String::new();
+        for (i, c) in line.char_indices() {
+            if let Some(ref mut text) = self.comment {
+                if c == '}' {
+                    let text = collapse(text);
+                    self.comment = None;
+                    if !text.is_empty() {
+                        tokens.push(Token::Comment(text));
+                    }
+                } else {
+                    text.push(c);
+                }
+                continue;
+            }
+            match c {
+                '{' => {
+                    self.flush(&mut word, &mut tokens);
+                    self.comment = Some(String::new());
+                }
+                ';' => {
+                    self.flush(&mut word, &mut tokens);
+                    let text = collapse(&line[i + 1..]);
+                    if !text.is_empty() {
+                        tokens.push(Token::Comment(text));
+                    }
+                    return tokens;
+                }
+                '(' => {
+                    self.flush(&mut word, &mut tokens);
+                    self.variation_depth += 1;
+                    tokens.push(Token::Open);
+                }
+                ')' => {
+                    self.flush(&mut word, &mut tokens);
+                    if self.variation_depth > 0 {
+                        self.variation_depth -= 1;
+                        tokens.push(Token::Close);
+                    }
+                }
+                c if c.is_whitespace() => self.flush(&mut word, &mut tokens),
+                c => word.push(c),
+            }
+        }
+        self.flush(&mut word, &mut tokens);
+        // A comment running on to the next line
+        if let Some(ref mut text) = self.comment {
+            text.push(' ');
+        }
+        tokens
+    }
+
+    fn flush(&self, word: &mut String, tokens: &mut Vec<Token>) {
+        let word = std::mem::take(word);
+        if word.is_empty() {
+            return;
+        }
+        if let Some(nag) = word.strip_prefix('$') {
+            if let Ok(nag) = nag.parse() {
+                tokens.push(Token::Nag(nag));
+            }
+            return;
+        }
+        if RESULTS.contains(&word.as_str()) {
+            if self.variation_depth == 0 {
+                tokens.push(Token::Result(word));
+            }
+            return;
+        }
+        // `12.`, `12...` and `12.e4` all carry a move number
+        let san = match word.rfind('.') {
+            Some(i) if word.starts_with(|c: char| c.is_ascii_digit()) => &word[i + 1..],
+            _ => &word,
+        };
+        if !san.is_empty() {
+            tokens.push(Token::Move(san.to_string()));
+        }
+    }
+}
+
+/// `text` trimmed, with runs of whitespace and line breaks made one space.
+fn collapse(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// `[Name "value"]`, with `\"` and `\\` escapes in the value.
+fn parse_tag(line: &str) -> Option<(String, String)> {
+    let inner = line.trim().strip_prefix('[')?.strip_suffix(']')?;
+    let (name, rest) = inner.split_once(char::is_whitespace)?;
+    let quoted = rest.trim().strip_prefix('"')?.strip_suffix('"')?;
+    let value = quoted.replace("\\\"", "\"").replace("\\\\", "\\");
+    Some((name.to_string(), value))
+}
+
+/// `word` without a `!`/`?` suffix, and the NAG the suffix stands for.
+fn split_suffix(word: &str) -> (&str, Option<u8>) {
+    for (suffix, nag) in SUFFIX_NAGS {
+        if let Some(san) = word.strip_suffix(suffix) {
+            if !san.is_empty() {
+                return (san, Some(nag));
+            }
+        }
+    }
+    (word, None)
+}
+
+/// Append `text` to a comment, joining several with a space.
+fn add_comment(comment: &mut Option<String>, text: String) {
+    match comment {
+        Some(existing) => {
+            existing.push(' ');
+            existing.push_str(&text);
+        }
+        None => *comment = Some(text),
+    }
+}
+
+/// A line being built: its moves and a comment waiting for the next move.
+type OpenLine = (Vec<PgnMove>, Option<String>);
+
+/// Attach the innermost open variation to the move it replaces.
+fn close_variation(lines: &mut Vec<OpenLine>) {
+    if lines.len() < 2 {
+        return;
+    }
+    let Some((variation, _)) = lines.pop() else {
+        return;
+    };
+    if let Some(mv) = lines.last_mut().and_then(|(line, _)| line.last_mut()) {
+        if !variation.is_empty() {
+            mv.variations.push(variation);
+        }
+    }
+}
+
+/// The main line, with variations and annotations, from its tokens.
+fn build_moves(tokens: Vec<Token>) -> Vec<PgnMove> {
+    let mut lines: Vec<OpenLine> = vec![OpenLine::default()];
+    for token in tokens {
+        match token {
+            Token::Move(word) => {
+                let (san, nag) = split_suffix(&word);
+                let mut mv = PgnMove::new(san);
+                mv.nags.extend(nag);
+                if let Some((line, pending)) = lines.last_mut() {
+                    mv.comment_before = pending.take();
+                    line.push(mv);
+                }
+            }
+            Token::Nag(nag) => {
+                if let Some(mv) = lines.last_mut().and_then(|(line, _)| line.last_mut()) {
+                    mv.nags.push(nag);
+                }
+            }
+            Token::Comment(text) => {
+                if let Some((line, pending)) = lines.last_mut() {
+                    match line.last_mut() {
+                        Some(mv) => add_comment(&mut mv.comment, text),
+                        None => add_comment(pending, text),
+                    }
+                }
+            }
+            Token::Open => lines.push(OpenLine::default()),
+            Token::Close => close_variation(&mut lines),
+            Token::Result(_) => {}
+        }
+    }
+    // Variations left open at the end of the game
+    while lines.len() > 1 {
+        close_variation(&mut lines);
+    }
+    lines.pop().map(|(line, _)| line).unwrap_or_default()
+}
+
+/// Every game in `text`, in file order.
+pub fn parse_games(text: &str) -> Vec<PgnGame> {
+    let mut games = Vec::new();
+    let mut tags = Vec::new();
+    let mut tokens = Vec::new();
+    let mut lexer = Lexer::default();
+
+    let mut finish = |tags: &mut Vec<_>, tokens: &mut Vec<_>, result: String| {
+        games.push(PgnGame {
+            tags: std::mem::take(tags),
+            moves: build_moves(std::mem::take(tokens)),
+            result,
+        });
+    };
+
+    for line in text.lines() {
+        let trimmed = line.trim_start_matches('\u{feff}').trim();
+        if lexer.at_top_level() && trimmed.starts_with('%') {
+            continue;
+        }
+        if lexer.at_top_level() && trimmed.starts_with('[') {
+            // A tag after movetext without a result starts the next game
+            if !tokens.is_empty() {
+                finish(&mut tags, &mut tokens, "*".to_string());
+            }
+            if let Some(tag) = parse_tag(trimmed) {
+                tags.push(tag);
+            }
+            continue;
+        }
+        for token in lexer.tokens(trimmed) {
+            match token {
+                Token::Result(result) => finish(&mut tags, &mut tokens, result),
+                token => tokens.push(token),
+            }
+        }
+    }
+    if !tokens.is_empty() || !tags.is_empty() {
+        finish(&mut tags, &mut tokens, "*".to_string());
+    }
+    games
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TWO_GAMES: &str = r#"[Event "Club night"]
+[White "Ann"]
+[Black "Bob"]
+[Result "1-0"]
+
+1. e4 {best by test} e5 2. Nf3 (2. f4 exf4) Nc6 3. Bc4 Nf6?! $6
+4. Ng5 d5 5. exd5 Na5 6. Bb5+ c6 7. dxc6 bxc6 8. Be2 h6 9. Nf3 e4
+10. Ne5 Bd6 11. O-O 1-0
+
+[Event "Blitz"]
+[Result "0-1"]
+
+1.f3 e5 2.g4 Qh4# 0-1
+"#;
+
+    #[test]
+    fn test_parse_games() {
+        let games = parse_games(TWO_GAMES);
+        assert_eq!(games.len(), 2);
+
+        assert_eq!(games[0].tag("White"), Some("Ann"));
+        let main: Vec<&str> = games[0].main_line().collect();
+        assert_eq!(main.len(), 21);
+        assert_eq!(main[..4], ["e4", "e5", "Nf3", "Nc6"]);
+        assert_eq!(main[5], "Nf6");
+        assert_eq!(games[0].moves[5].nags, [6, 6]);
+        assert_eq!(games[0].moves[0].comment.as_deref(), Some("best by test"));
+        assert_eq!(games[0].moves[2].variations.len(), 1);
+        assert_eq!(games[0].outcome(), Some(GameResult::WhiteWins));
+
+        let main: Vec<&str> = games[1].main_line().collect();
+        assert_eq!(main, ["f3", "e5", "g4", "Qh4#"]);
+        assert_eq!(games[1].outcome(), Some(GameResult::BlackWins));
+    }
+
+    #[test]
+    fn test_result_falls_back_to_tag() {
+        let games = parse_games("[Result \"1/2-1/2\"]\n\n1. d4 d5\n\n[Event \"Next\"]\n1. e4 *");
+        assert_eq!(games.len(), 2);
+        assert_eq!(games[0].outcome(), Some(GameResult::Draw));
+        assert_eq!(games[1].outcome(), None);
+    }
+
+    #[test]
+    fn test_nested_variations_and_comments() {
+        let text =
+            "{Opening notes} 1. e4 e5 (1... c5 {Sicilian} 2. Nf3 (2. c3 $1 d5) d6; the main line\n\
+                    3. d4) ({Or} 1... e6 2. d4 {multi\nline} d5) 2. Nf3 *";
+        let games = parse_games(text);
+        assert_eq!(games.len(), 1);
+        let moves = &games[0].moves;
+        assert_eq!(moves.len(), 3);
+        assert_eq!(moves[0].comment_before.as_deref(), Some("Opening notes"));
+
+        let [sicilian, french] = moves[1].variations.as_slice() else {
+            panic!("expected two variations, got {:?}", moves[1].variations);
+        };
+        assert_eq!(sicilian[0].comment.as_deref(), Some("Sicilian"));
+        assert_eq!(sicilian[1].variations[0][0].nags, [1]);
+        assert_eq!(sicilian[2].comment.as_deref(), Some("the main line"));
+        assert_eq!(sicilian.len(), 4);
+        assert_eq!(french[0].comment_before.as_deref(), Some("Or"));
+        assert_eq!(french[1].comment.as_deref(), Some("multi line"));
+        assert_eq!(games[0].result, "*");
+    }
+
+    #[test]
+    fn test_write_round_trips() {
+        let text = "[Event \"Club \\\"night\\\"\"]\n\n\
+                    {Opening notes} 1. e4 $1 e5 (1... c5 {Sicilian} 2. Nf3 (2. c3 d5) d6) 2. Nf3 {develops} \
+                    2... Nc6 1-0";
+        let game = &parse_games(text)[0];
+        let written = game.to_pgn();
+        assert_eq!(
+            written,
+            "[Event \"Club \\\"night\\\"\"]\n\n\
+             {Opening notes} 1. e4 $1 e5 (1... c5 {Sicilian} 2. Nf3 (2. c3 d5) 2... d6)\n\
+             2. Nf3 {develops} 2... Nc6 1-0"
+        );
+        assert_eq!(&parse_games(&written)[0], game);
+    }
+
+    #[test]
+    fn test_movetext_numbers_from_the_start() {
+        let moves: Vec<PgnMove> = ["Kd7", "Kd2", "Kc6"].map(PgnMove::new).into();
+        let start: Board = "4k3/8/8/8/8/8/8/4K3 b - - 0 12".parse().unwrap();
+        assert_eq!(movetext(&start, &moves), "12... Kd7 13. Kd2 Kc6");
+        assert_eq!(movetext(&Board::default(), &moves[..1]), "1. Kd7");
+
+        let mut one = PgnMove::new("e4");
+        one.variations.push(vec![PgnMove::new("d4")]);
+        assert_eq!(movetext(&Board::default(), &[one]), "1. e4 (1. d4)");
+    }
+}
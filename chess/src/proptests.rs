@@ -0,0 +1,203 @@
+//! Property tests for the FEN, SAN and PGN parsers, which read untrusted
+//! files on import. Opt in with `--features property-tests`.
+
+use cozy_chess::{Board, Move, Piece};
+use proptest::prelude::*;
+
+use crate::fen::{format_fen, parse_fen};
+use crate::pgn::{parse_games, RESULTS};
+use crate::{format_move_as_san, format_piece_upper, format_square, parse_san, PgnGame, PgnMove};
+
+/// Play from the start position, choosing each move by index among the
+/// legal ones. Returns every position with the move played from it, and the
+/// final position.
+fn playout(choices: &[usize]) -> (Vec<(Board, Move)>, Board) {
+    let mut board = Board::default();
+    let mut played = Vec::new();
+    for &choice in choices {
+        let mut moves = Vec::new();
+        board.generate_moves(|list| {
+            moves.extend(list);
+            false
+        });
+        if moves.is_empty() {
+            break;
+        }
+        let mv = moves[choice % moves.len()];
+        played.push((board.clone(), mv));
+        board.play_unchecked(mv);
+    }
+    (played, board)
+}
+
+fn choices() -> impl Strategy<Value = Vec<usize>> {
+    prop::collection::vec(any::<usize>(), 0..80)
+}
+
+/// SAN naming the moving piece and both squares, which no other move fits.
+fn qualified_san(board: &Board, mv: Move) -> String {
+    let mut san = String::new();
+    match board.piece_on(mv.from) {
+        Some(Piece::Pawn) | None => {}
+        Some(piece) => san.push(format_piece_upper(piece)),
+    }
+    san.push_str(&format_square(mv.from));
+    san.push_str(&format_square(mv.to));
+    if let Some(promotion) = mv.promotion {
+        san.push('=');
+        san.push(format_piece_upper(promotion));
+    }
+    san
+}
+
+/// Castling is the king capturing its own rook.
+fn is_castling(board: &Board, mv: Move) -> bool {
+    board.colors(board.side_to_move()).has(mv.to)
+}
+
+fn pgn_san() -> impl Strategy<Value = String> {
+    prop_oneof![
+        "[NBRQK]?[a-h][1-8]",
+        "[a-h]x[a-h][1-8]=[NBRQ]",
+        Just("O-O".to_string()),
+        Just("O-O-O".to_string()),
+    ]
+}
+
+/// Comment text as the parser leaves it: trimmed, single spaces, no braces.
+fn pgn_comment() -> impl Strategy<Value = String> {
+    "[a-z]{1,8}( [a-z]{1,8}){0,3}"
+}
+
+/// A non-empty line of moves with variations nested up to `depth` deep. Only
+/// a line's first move carries a comment before it; anywhere else such a
+/// comment reads back as the previous move's.
+fn pgn_line(depth: u32) -> BoxedStrategy<Vec<PgnMove>> {
+    let variations = if depth == 0 {
+        Just(Vec::new()).boxed()
+    } else {
+        prop::collection::vec(pgn_line(depth - 1), 0..3).boxed()
+    };
+    let mv = (
+        pgn_san(),
+        prop::collection::vec(any::<u8>(), 0..3),
+        prop::option::of(pgn_comment()),
+        variations,
+    )
+        .prop_map(|(san, nags, comment, variations)| PgnMove {
+            san,
+            nags,
+            comment_before: None,
+            comment,
+            variations,
+        });
+    (
+        prop::collection::vec(mv, 1..8),
+        prop::option::of(pgn_comment()),
+    )
+        .prop_map(|(mut moves, comment_before)| {
+            moves[0].comment_before = comment_before;
+            moves
+        })
+        .boxed()
+}
+
+fn pgn_game() -> impl Strategy<Value = PgnGame> {
+    (
+        prop::collection::vec(("[A-Z][A-Za-z]{0,8}", "[A-Za-z0-9 .,:?\"-]{0,20}"), 0..5),
+        prop::option::of(pgn_line(2)),
+        prop::sample::select(RESULTS.to_vec()),
+    )
+        .prop_map(|(tags, moves, result)| PgnGame {
+            tags,
+            moves: moves.unwrap_or_default(),
+            result: result.to_string(),
+        })
+}
+
+proptest! {
+    #[test]
+    fn fen_round_trips(choices in choices()) {
+        let (_, board) = playout(&choices);
+        let fen = format_fen(&board);
+        let parsed = parse_fen(&fen).unwrap();
+        prop_assert_eq!(format_fen(&parsed), fen);
+    }
+
+    #[test]
+    fn fen_parser_does_not_panic(fen in "\\PC{0,90}") {
+        let _ = parse_fen(&fen);
+    }
+
+    #[test]
+    fn fen_parser_does_not_panic_on_near_fens(
+        fen in "[pnbrqkPNBRQK1-9/]{1,70} [wb] (-|[KQkqA-Ha-h]{1,4}) (-|[a-h][1-8]) [0-9]{1,4} [0-9]{1,4}"
+    ) {
+        if let Ok(board) = parse_fen(&fen) {
+            prop_assert!(parse_fen(&format_fen(&board)).is_ok());
+        }
+    }
+
+    #[test]
+    fn qualified_san_finds_the_move(choices in choices()) {
+        let (played, _) = playout(&choices);
+        for (board, mv) in played {
+            if is_castling(&board, mv) {
+                continue;
+            }
+            let san = qualified_san(&board, mv);
+            prop_assert_eq!(parse_san(&board, &san), Some(mv), "{} in {}", san, board);
+        }
+    }
+
+    #[test]
+    fn written_san_never_reads_as_another_move(choices in choices()) {
+        let (played, _) = playout(&choices);
+        for (board, mv) in played {
+            let san = format_move_as_san(&board, mv);
+            let parsed = parse_san(&board, &san);
+            prop_assert!(parsed.is_none() || parsed == Some(mv), "{} in {}", san, board);
+        }
+    }
+
+    #[test]
+    fn san_parser_does_not_panic(choices in choices(), san in "\\PC{0,12}") {
+        let (_, board) = playout(&choices);
+        if let Some(mv) = parse_san(&board, &san) {
+            prop_assert!(board.is_legal(mv));
+        }
+    }
+
+    #[test]
+    fn pgn_round_trips(game in pgn_game()) {
+        let written = game.to_pgn();
+        let parsed = parse_games(&written);
+        prop_assert_eq!(parsed, vec![game], "{}", written);
+    }
+
+    #[test]
+    fn pgn_parser_does_not_panic(text in "\\PC{0,200}") {
+        for game in parse_games(&text) {
+            let _ = parse_games(&game.to_pgn());
+        }
+    }
+
+    #[test]
+    fn pgn_parser_does_not_panic_on_movetext(
+        tokens in prop::collection::vec(
+            prop_oneof![
+                "[NBRQK]?[a-h]x?[a-h][1-8][+#!?]{0,2}",
+                "[0-9]{1,3}\\.{1,3}",
+                "\\$[0-9]{1,4}",
+                "[{}();\\[\\]%\"\n]",
+                "[a-z ]{0,10}",
+            ],
+            0..60,
+        )
+    ) {
+        let text = tokens.join(" ");
+        for game in parse_games(&text) {
+            let _ = parse_games(&game.to_pgn());
+        }
+    }
+}
@@ -0,0 +1,103 @@
+//! Printing the server's audit log for `chesstty audit`.
+//!
+//! The server records deletions, imports and engine config changes along
+//! with who made them; the shim only fetches and formats the entries.
+
+use chess_client::{AuditActionType, AuditEntryProto};
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// `YYYY-MM-DD HH:MM:SS` (UTC) for a unix timestamp in seconds.
+pub fn format_timestamp(secs: u64) -> String {
+    let days = (secs / SECONDS_PER_DAY) as i64;
+    let rem = secs % SECONDS_PER_DAY;
+
+    // Civil date from a day count, in the proleptic Gregorian calendar
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + i64::from(m <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        y,
+        m,
+        d,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
+
+fn action_name(action: i32) -> &'static str {
+    match AuditActionType::try_from(action) {
+        Ok(AuditActionType::AuditActionDelete) => "delete",
+        Ok(AuditActionType::AuditActionImport) => "import",
+        Ok(AuditActionType::AuditActionConfigChange) => "config",
+        Ok(AuditActionType::AuditActionSessionClose) => "close",
+        Err(_) => "unknown",
+    }
+}
+
+/// One line per entry, in the order given.
+pub fn format_entries(entries: &[AuditEntryProto]) -> String {
+    let actor_width = entries.iter().map(|e| e.actor.len()).max().unwrap_or(0);
+    let mut out = String::new();
+    for entry in entries {
+        let mut line = format!(
+            "{}  {:<actor_width$}  {:<6}  {}",
+            format_timestamp(entry.at),
+            entry.actor,
+            action_name(entry.action),
+            entry.target,
+        );
+        if !entry.detail.is_empty() {
+            line.push_str(&format!(" ({})", entry.detail));
+        }
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00:00");
+        // 2024-02-29 13:45:07 UTC
+        assert_eq!(format_timestamp(1_709_214_307), "2024-02-29 13:45:07");
+    }
+
+    #[test]
+    fn test_format_entries() {
+        let entries = vec![
+            AuditEntryProto {
+                at: 1_709_214_307,
+                actor: "uid 1000 (pid 42)".to_string(),
+                action: AuditActionType::AuditActionDelete as i32,
+                target: "position pos_1".to_string(),
+                detail: String::new(),
+            },
+            AuditEntryProto {
+                at: 1_709_214_000,
+                actor: "server".to_string(),
+                action: AuditActionType::AuditActionImport as i32,
+                target: "peer desktop".to_string(),
+                detail: "2 received, 0 sent, 0 conflicts".to_string(),
+            },
+        ];
+        assert_eq!(
+            format_entries(&entries),
+            "2024-02-29 13:45:07  uid 1000 (pid 42)  delete  position pos_1\n\
+             2024-02-29 13:40:00  server             import  peer desktop (2 received, 0 sent, 0 conflicts)\n"
+        );
+    }
+}
@@ -1,9 +1,10 @@
 //! Configuration for ChessTTY runtime.
 //!
 //! Centralises all runtime tunables for the shim: socket path, PID file path,
-//! server log path, and timeout/poll-interval values for the socket-readiness
-//! wait. Every value has a compile-time default and can be overridden at runtime
-//! via a dedicated environment variable.
+//! server log path, timeout/poll-interval values for the socket-readiness
+//! wait, and how a server version mismatch is handled. Every value has a
+//! compile-time default and can be overridden at runtime via a dedicated
+//! environment variable.
 
 use std::path::PathBuf;
 
@@ -86,6 +87,38 @@ pub fn get_server_log_path() -> PathBuf {
     PathBuf::from(DEFAULT_SERVER_LOG_PATH)
 }
 
+/// What the shim does when the running server's version differs from its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionCheckMode {
+    /// Ask whether to restart the server; warn instead without a terminal.
+    Prompt,
+    /// Print a warning and carry on with the running server.
+    Warn,
+    /// Restart the server without asking.
+    Restart,
+    /// Skip the check.
+    Off,
+}
+
+/// Get how a server version mismatch is handled.
+///
+/// Priority:
+/// 1. `CHESSTTY_VERSION_CHECK` env variable if set to `prompt`, `warn`,
+///    `restart` or `off`
+/// 2. `prompt` as fallback
+pub fn get_version_check_mode() -> VersionCheckMode {
+    parse_version_check_mode(std::env::var("CHESSTTY_VERSION_CHECK").ok().as_deref())
+}
+
+fn parse_version_check_mode(value: Option<&str>) -> VersionCheckMode {
+    match value.map(|v| v.trim().to_ascii_lowercase()).as_deref() {
+        Some("warn") => VersionCheckMode::Warn,
+        Some("restart") => VersionCheckMode::Restart,
+        Some("off") => VersionCheckMode::Off,
+        _ => VersionCheckMode::Prompt,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -120,6 +153,24 @@ mod tests {
         assert_eq!(interval, DEFAULT_SOCKET_POLL_INTERVAL_MS);
     }
 
+    #[test]
+    fn test_parse_version_check_mode() {
+        assert_eq!(parse_version_check_mode(None), VersionCheckMode::Prompt);
+        assert_eq!(
+            parse_version_check_mode(Some("Warn")),
+            VersionCheckMode::Warn
+        );
+        assert_eq!(
+            parse_version_check_mode(Some(" restart ")),
+            VersionCheckMode::Restart
+        );
+        assert_eq!(parse_version_check_mode(Some("off")), VersionCheckMode::Off);
+        assert_eq!(
+            parse_version_check_mode(Some("sometimes")),
+            VersionCheckMode::Prompt
+        );
+    }
+
     #[test]
     fn test_get_server_log_path_default() {
         let path = get_server_log_path();
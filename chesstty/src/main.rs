@@ -7,7 +7,24 @@
 //! 1. **Default mode (no args)**: Launches the gRPC server as a background
 //!    daemon (if not already running) and then launches the TUI in the foreground.
 //! 2. **`engine stop` subcommand**: Signals the background server to shut down
-//!    gracefully (SIGTERM) or immediately (`--force` → SIGKILL).
+//!    gracefully (SIGTERM) or immediately (`--force` → SIGKILL); `engine
+//!    status` shows its version, uptime and power mode.
+//! 3. **`sync <peer>` subcommand**: Asks the running server to exchange finished
+//!    games and reviews with another chesstty server.
+//! 4. **`audit` subcommand**: Prints the server's audit log of deletions,
+//!    imports and engine config changes (see [`audit`]).
+//! 5. **`report <id>` subcommand**: Bundles a session or finished game, its UCI
+//!    transcript, a server log excerpt, the redacted config and the versions
+//!    into one archive to attach to a bug report (see [`report`]).
+//! 6. **`sessions` subcommand**: Lists the server's open sessions with their
+//!    age, mode, move count and engine (see [`sessions`]); `sessions close`
+//!    closes or suspends one without restarting the server.
+//! 7. **`review <game>` subcommand**: Queues a finished game for review,
+//!    optionally only a range of plies or deeper than the server's default.
+//!
+//! When a server from an earlier run is found, the shim compares its version
+//! with its own (see [`version`]) and warns about a mismatch or offers to
+//! restart the server with the co-located binary.
 //!
 //! # Architecture
 //!
@@ -30,15 +47,20 @@
 //! is created manually *after* the fork boundary, only in the parent path.
 
 use std::fs::File;
+use std::io::IsTerminal;
 use std::path::PathBuf;
 use std::process::Command;
 use std::time::Duration;
 
 use clap::{Parser, Subcommand};
 
+mod audit;
 mod config;
 mod daemon;
 mod process;
+mod report;
+mod sessions;
+mod version;
 mod wait;
 
 /// Top-level CLI arguments for ChessTTY.
@@ -51,6 +73,12 @@ struct Cli {
     /// Optional subcommand. When omitted, runs the default server + TUI flow.
     #[command(subcommand)]
     command: Option<Commands>,
+
+    /// Start the server in low-power mode: engines run with one thread and a
+    /// small hash and background reviews wait. Only applies to a server this
+    /// run starts; `CHESSTTY_LOW_POWER=auto` does so only while on battery.
+    #[arg(long)]
+    low_power: bool,
 }
 
 /// Top-level subcommands for managing ChessTTY components.
@@ -62,6 +90,97 @@ enum Commands {
         #[command(subcommand)]
         action: EngineAction,
     },
+    /// Sync finished games and reviews with another chesstty server.
+    ///
+    /// Both servers must share `CHESSTTY_SYNC_TOKEN`, and the peer must accept
+    /// sync connections (`CHESSTTY_SYNC_LISTEN`).
+    Sync {
+        /// Peer address, e.g. `desktop.local` or `10.0.0.2:50151`.
+        peer: String,
+    },
+    /// Show who deleted, imported or reconfigured what on the server.
+    Audit {
+        /// Number of most recent entries to show.
+        #[arg(short = 'n', long, default_value_t = 50)]
+        limit: u32,
+    },
+    /// Bundle a session or finished game into an archive for a bug report.
+    ///
+    /// The archive holds the session and game as the server knows them, the
+    /// engine's UCI transcript, an excerpt of the server log, the
+    /// `CHESSTTY_*` configuration with secrets redacted, and the versions.
+    Report {
+        /// Session id, or the id of a finished game.
+        id: String,
+        /// Where to write the archive; defaults to
+        /// `chesstty-report-<id>.tar` in the current directory.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// List the sessions open on the server, oldest first.
+    Sessions {
+        #[command(subcommand)]
+        action: Option<SessionsAction>,
+    },
+    /// Queue a finished game for review.
+    ///
+    /// With a ply range or depth, only those plies are analysed, and a game
+    /// already reviewed has them re-analysed; plies analysed at least that
+    /// deep before are kept.
+    Review {
+        /// Id of the finished game, or with `--cancel` a job id.
+        game_id: String,
+        /// Cancel the game's pending review instead, keeping the plies
+        /// analysed so far.
+        #[arg(long, conflicts_with_all = [
+            "from", "to", "depth", "multipv", "skip_advanced",
+            "exclude_book", "exclude_forced", "exclude_decided",
+        ])]
+        cancel: bool,
+        /// First ply to analyse (1 = White's first move).
+        #[arg(long)]
+        from: Option<u32>,
+        /// Last ply to analyse.
+        #[arg(long)]
+        to: Option<u32>,
+        /// Engine depth, instead of the server's configured one.
+        #[arg(long)]
+        depth: Option<u32>,
+        /// Lines the engine searches per position (1-5, default 2); 1 is
+        /// quickest but cannot tell an only move.
+        #[arg(long)]
+        multipv: Option<u32>,
+        /// Leave out the advanced analysis, for a quick review.
+        #[arg(long)]
+        skip_advanced: bool,
+        /// Leave moves still in the opening tree out of the accuracy.
+        #[arg(long)]
+        exclude_book: bool,
+        /// Leave only legal moves out of the accuracy.
+        #[arg(long)]
+        exclude_forced: bool,
+        /// Leave out of the accuracy moves played at least this many
+        /// centipawns ahead or behind (800 when no value is given).
+        #[arg(long, value_name = "CP", num_args = 0..=1, default_missing_value = "800")]
+        exclude_decided: Option<i32>,
+    },
+}
+
+/// Actions on the server's open sessions.
+#[derive(Subcommand)]
+enum SessionsAction {
+    /// Close a session, e.g. one a crashed TUI left behind.
+    ///
+    /// A game that has ended is saved and queued for review as usual. A
+    /// session that does not answer within a few seconds is aborted without
+    /// saving anything.
+    Close {
+        /// Session id, as listed by `chesstty sessions`.
+        id: String,
+        /// Save the session as a suspended session first, to resume later.
+        #[arg(short, long)]
+        suspend: bool,
+    },
 }
 
 /// Actions that can be performed on the background engine server.
@@ -76,6 +195,8 @@ enum EngineAction {
         #[arg(short, long)]
         force: bool,
     },
+    /// Show the running server's version, uptime and power mode.
+    Status,
 }
 
 /// Error type for CLI operations.
@@ -96,6 +217,26 @@ enum CliError {
     /// A general process-management error (spawn failure, PID I/O, signal delivery).
     #[error("server process error: {0}")]
     ProcessError(String),
+
+    /// The server could not be reached or the sync with the peer failed.
+    #[error("sync failed: {0}")]
+    SyncFailed(String),
+
+    /// The server could not be reached or the audit log could not be read.
+    #[error("failed to read audit log: {0}")]
+    AuditFailed(String),
+
+    /// The report could not be gathered or written.
+    #[error("failed to write report: {0}")]
+    ReportFailed(String),
+
+    /// The server could not be reached, or did not list or close its sessions.
+    #[error("session command failed: {0}")]
+    SessionsFailed(String),
+
+    /// The server could not be reached or refused to queue the review.
+    #[error("failed to queue review: {0}")]
+    ReviewFailed(String),
 }
 
 /// Resolve the path to a sibling binary distributed alongside this executable.
@@ -145,7 +286,7 @@ fn resolve_sibling_binary(name: &str) -> PathBuf {
 /// # Errors
 ///
 /// Returns [`CliError::ProcessError`] if `fork()` fails.
-fn spawn_server() -> Result<(), CliError> {
+fn spawn_server(low_power: bool) -> Result<(), CliError> {
     let pid_path = config::get_pid_path();
     let log_path = config::get_server_log_path();
 
@@ -188,12 +329,14 @@ fn spawn_server() -> Result<(), CliError> {
             // We are now the daemon (grandchild). Exec into server binary.
             use std::os::unix::process::CommandExt;
             let server_bin = resolve_sibling_binary("chesstty-server");
-            let err = Command::new(&server_bin).exec();
+            let server_args: &[&str] = if low_power { &["--low-power"] } else { &[] };
+            let err = Command::new(&server_bin).args(server_args).exec();
 
             // exec() only returns on failure — try cargo fallback
             if err.kind() == std::io::ErrorKind::NotFound {
                 let err = Command::new("cargo")
-                    .args(["run", "-p", "chesstty-server"])
+                    .args(["run", "-p", "chesstty-server", "--"])
+                    .args(server_args)
                     .exec();
                 eprintln!("Failed to exec server (cargo fallback): {}", err);
             } else {
@@ -213,27 +356,127 @@ fn spawn_server() -> Result<(), CliError> {
     }
 }
 
-/// Wait for the server's Unix domain socket to become connectable.
+/// Wait for the server's Unix domain socket to become connectable and the
+/// server behind it to report ready.
 ///
 /// Polls the socket path (from [`config::get_socket_path`]) at the configured
-/// interval until either a connection succeeds or the timeout elapses. Both the
-/// timeout and poll interval are read from [`config`] and can be overridden via
+/// interval until either a connection succeeds or the timeout elapses, then
+/// polls the server's gRPC health check the same way. Both the timeout and
+/// poll interval are read from [`config`] and can be overridden via
 /// environment variables.
 ///
 /// # Errors
 ///
 /// Returns [`CliError::SocketWait`] wrapping a [`wait::WaitError`] if the socket
-/// does not become available within the configured timeout.
+/// does not become available, or the server ready, within the configured timeout.
 async fn wait_for_server_socket() -> Result<(), CliError> {
     let socket_path = config::get_socket_path();
     let timeout = Duration::from_secs(config::get_socket_timeout_secs());
     let poll_interval = Duration::from_millis(config::get_socket_poll_interval_ms());
 
-    wait::wait_for_socket(&socket_path, timeout, poll_interval)
+    wait::wait_for_socket(&socket_path, timeout, poll_interval).await?;
+    wait::wait_for_serving(&socket_path, timeout, poll_interval)
         .await
         .map_err(CliError::from)
 }
 
+/// Compare the already-running server's version with the shim's and decide,
+/// per [`config::get_version_check_mode`], whether to restart the server.
+///
+/// A mismatch is only warned about when the mode says so or no terminal is
+/// attached to ask on. Failing to query the server is logged and never fatal.
+fn should_restart_server(rt: &tokio::runtime::Runtime) -> bool {
+    let mode = config::get_version_check_mode();
+    if mode == config::VersionCheckMode::Off {
+        return false;
+    }
+
+    let server = match rt.block_on(version::query_server_version(&config::get_socket_path())) {
+        Ok(server) => server,
+        Err(e) => {
+            tracing::warn!("Could not check the server version: {}", e);
+            return false;
+        }
+    };
+    if server.matches(version::SHIM_VERSION) {
+        return false;
+    }
+
+    let message = server.mismatch_message(version::SHIM_VERSION);
+    let server_bin = resolve_sibling_binary("chesstty-server");
+    match mode {
+        config::VersionCheckMode::Restart => {
+            eprintln!(
+                "chesstty: {}; restarting it with {}.",
+                message,
+                server_bin.display()
+            );
+            true
+        }
+        config::VersionCheckMode::Prompt if std::io::stdin().is_terminal() => {
+            version::confirm_restart(&message, &server_bin)
+        }
+        _ => {
+            eprintln!(
+                "chesstty: warning: {}. Run `chesstty engine stop` and start chesstty again to upgrade it.",
+                message
+            );
+            false
+        }
+    }
+}
+
+/// Stop the running server, waiting for it to exit, and re-exec the shim,
+/// which then starts the co-located server binary afresh.
+///
+/// Re-exec'ing instead of calling [`spawn_server`] again keeps the
+/// fork-before-runtime invariant: the caller already created a tokio runtime,
+/// and the new process image starts without any threads.
+///
+/// # Errors
+///
+/// Returns [`CliError::ProcessError`] if the server cannot be signalled, does
+/// not exit, or the shim cannot be re-executed. On success it does not return.
+fn restart_server() -> Result<(), CliError> {
+    let pid_path = config::get_pid_path();
+    let pid = process::read_pid(&pid_path)
+        .map_err(|e| CliError::ProcessError(format!("failed to read PID: {}", e)))?;
+    let timeout = Duration::from_secs(config::get_socket_timeout_secs());
+    let poll_interval = Duration::from_millis(config::get_socket_poll_interval_ms());
+
+    // Graceful first, then forceful if the server does not go away
+    for signal in [libc::SIGTERM, libc::SIGKILL] {
+        // SAFETY: kill is safe when sending signals to our own spawned processes
+        if unsafe { libc::kill(pid, signal) } != 0 {
+            return Err(CliError::ProcessError(format!(
+                "failed to send signal to process {}: {}",
+                pid,
+                std::io::Error::last_os_error()
+            )));
+        }
+        if process::wait_for_exit(pid, timeout, poll_interval) {
+            break;
+        }
+        if signal == libc::SIGKILL {
+            return Err(CliError::ProcessError(format!(
+                "server process {} did not exit",
+                pid
+            )));
+        }
+    }
+    let _ = std::fs::remove_file(&pid_path);
+    tracing::info!("Server stopped, restarting chesstty...");
+
+    use std::os::unix::process::CommandExt;
+    let exe = std::env::current_exe()
+        .map_err(|e| CliError::ProcessError(format!("failed to locate chesstty: {}", e)))?;
+    let err = Command::new(exe).args(std::env::args_os().skip(1)).exec();
+    Err(CliError::ProcessError(format!(
+        "failed to restart chesstty: {}",
+        err
+    )))
+}
+
 /// Spawn the TUI client and block until it exits.
 ///
 /// Resolution strategy mirrors [`spawn_server`]:
@@ -348,6 +591,280 @@ fn handle_engine_stop(force: bool) -> Result<(), CliError> {
     Ok(())
 }
 
+/// Print the running server's version, uptime and power mode.
+///
+/// # Errors
+///
+/// Returns [`CliError::ProcessError`] if the server is running but cannot
+/// be reached.
+fn handle_engine_status() -> Result<(), CliError> {
+    if !matches!(
+        process::is_server_running(&config::get_pid_path()),
+        Ok(true)
+    ) {
+        println!("Server is not running.");
+        return Ok(());
+    }
+
+    let rt = tokio::runtime::Runtime::new()
+        .map_err(|e| CliError::ProcessError(format!("failed to create runtime: {}", e)))?;
+    let info = rt
+        .block_on(async {
+            let mut client =
+                chess_client::ChessClient::connect_uds(&config::get_socket_path()).await?;
+            client.get_server_info().await
+        })
+        .map_err(|e| CliError::ProcessError(format!("failed to query server: {}", e)))?;
+
+    print!("{}", version::format_status(&info));
+    Ok(())
+}
+
+/// Ask the running server to sync with `peer` and print what was exchanged.
+///
+/// The sync itself runs in the server, which connects to the peer's sync
+/// listener; the shim only triggers it over the local Unix socket.
+///
+/// # Errors
+///
+/// Returns [`CliError::SyncFailed`] if the server is not running, cannot be
+/// reached, or reports that the sync failed.
+fn handle_sync(peer: &str) -> Result<(), CliError> {
+    if !matches!(
+        process::is_server_running(&config::get_pid_path()),
+        Ok(true)
+    ) {
+        return Err(CliError::SyncFailed(
+            "server is not running; start chesstty first".to_string(),
+        ));
+    }
+
+    let rt = tokio::runtime::Runtime::new()
+        .map_err(|e| CliError::ProcessError(format!("failed to create runtime: {}", e)))?;
+    let report = rt
+        .block_on(async {
+            let mut client =
+                chess_client::ChessClient::connect_uds(&config::get_socket_path()).await?;
+            client.sync_with_peer(peer).await
+        })
+        .map_err(|e| CliError::SyncFailed(e.to_string()))?;
+
+    println!(
+        "Synced with {} ({}): {} received, {} sent, {} conflicts resolved.",
+        peer, report.peer_node_id, report.received, report.sent, report.conflicts
+    );
+    Ok(())
+}
+
+/// Print the running server's most recent audit log entries, newest first.
+///
+/// # Errors
+///
+/// Returns [`CliError::AuditFailed`] if the server is not running, cannot be
+/// reached, or fails to read its audit log.
+fn handle_audit(limit: u32) -> Result<(), CliError> {
+    if !matches!(
+        process::is_server_running(&config::get_pid_path()),
+        Ok(true)
+    ) {
+        return Err(CliError::AuditFailed(
+            "server is not running; start chesstty first".to_string(),
+        ));
+    }
+
+    let rt = tokio::runtime::Runtime::new()
+        .map_err(|e| CliError::ProcessError(format!("failed to create runtime: {}", e)))?;
+    let entries = rt
+        .block_on(async {
+            let mut client =
+                chess_client::ChessClient::connect_uds(&config::get_socket_path()).await?;
+            client.list_audit_entries(limit).await
+        })
+        .map_err(|e| CliError::AuditFailed(e.to_string()))?;
+
+    if entries.is_empty() {
+        println!("The audit log is empty.");
+    } else {
+        print!("{}", audit::format_entries(&entries));
+    }
+    Ok(())
+}
+
+/// Queue a review of a game on the running server, optionally of a ply
+/// range, at another depth or number of engine lines, without the advanced
+/// analysis, or with some moves left out of the accuracy.
+///
+/// # Errors
+///
+/// Returns [`CliError::ReviewFailed`] if the server is not running, cannot be
+/// reached, or refuses the range (e.g. one past the end of the game).
+fn handle_review(request: chess_client::EnqueueReviewRequest) -> Result<(), CliError> {
+    if !matches!(
+        process::is_server_running(&config::get_pid_path()),
+        Ok(true)
+    ) {
+        return Err(CliError::ReviewFailed(
+            "server is not running; start chesstty first".to_string(),
+        ));
+    }
+
+    let rt = tokio::runtime::Runtime::new()
+        .map_err(|e| CliError::ProcessError(format!("failed to create runtime: {}", e)))?;
+    let game_id = request.game_id.clone();
+    let (from, to) = (request.from_ply, request.to_ply);
+    let status = rt
+        .block_on(async {
+            let mut client =
+                chess_client::ChessClient::connect_uds(&config::get_socket_path()).await?;
+            client.enqueue_review_request(request).await
+        })
+        .map_err(|e| CliError::ReviewFailed(e.to_string()))?;
+
+    let plies = match (from, to) {
+        (None, None) => "the whole game".to_string(),
+        (from, to) => format!(
+            "plies {}-{}",
+            from.unwrap_or(1),
+            to.map_or("end".to_string(), |t| t.to_string())
+        ),
+    };
+    match status.job_id {
+        Some(job_id) => println!(
+            "Queued {} of {} for review (job {}).",
+            plies, game_id, job_id
+        ),
+        None => println!("Queued {} of {} for review.", plies, game_id),
+    }
+    Ok(())
+}
+
+/// Cancel the pending review of `game_id`, or of the job with that id, on
+/// the running server.
+///
+/// # Errors
+///
+/// Returns [`CliError::ReviewFailed`] if the server is not running, cannot be
+/// reached, or has no pending review for the game.
+fn handle_cancel_review(id: &str) -> Result<(), CliError> {
+    if !matches!(
+        process::is_server_running(&config::get_pid_path()),
+        Ok(true)
+    ) {
+        return Err(CliError::ReviewFailed(
+            "server is not running; start chesstty first".to_string(),
+        ));
+    }
+
+    let rt = tokio::runtime::Runtime::new()
+        .map_err(|e| CliError::ProcessError(format!("failed to create runtime: {}", e)))?;
+    let job_id = rt
+        .block_on(async {
+            let mut client =
+                chess_client::ChessClient::connect_uds(&config::get_socket_path()).await?;
+            client.cancel_review(id).await
+        })
+        .map_err(|e| CliError::ReviewFailed(e.to_string()))?;
+    println!("Cancelled review job {}.", job_id);
+    Ok(())
+}
+
+/// Print the sessions open on the running server.
+///
+/// # Errors
+///
+/// Returns [`CliError::SessionsFailed`] if the server is not running or
+/// cannot be reached.
+fn handle_sessions() -> Result<(), CliError> {
+    if !matches!(
+        process::is_server_running(&config::get_pid_path()),
+        Ok(true)
+    ) {
+        return Err(CliError::SessionsFailed(
+            "server is not running; start chesstty first".to_string(),
+        ));
+    }
+
+    let rt = tokio::runtime::Runtime::new()
+        .map_err(|e| CliError::ProcessError(format!("failed to create runtime: {}", e)))?;
+    let response = rt
+        .block_on(async {
+            let mut client =
+                chess_client::ChessClient::connect_uds(&config::get_socket_path()).await?;
+            client.list_active_sessions().await
+        })
+        .map_err(|e| CliError::SessionsFailed(e.to_string()))?;
+
+    print!("{}", sessions::format_sessions(&response));
+    Ok(())
+}
+
+/// Close or suspend the session `id` on the running server.
+///
+/// # Errors
+///
+/// Returns [`CliError::SessionsFailed`] if the server is not running, cannot
+/// be reached, or has no session `id`.
+fn handle_close_session(id: &str, suspend: bool) -> Result<(), CliError> {
+    if !matches!(
+        process::is_server_running(&config::get_pid_path()),
+        Ok(true)
+    ) {
+        return Err(CliError::SessionsFailed(
+            "server is not running; start chesstty first".to_string(),
+        ));
+    }
+
+    let rt = tokio::runtime::Runtime::new()
+        .map_err(|e| CliError::ProcessError(format!("failed to create runtime: {}", e)))?;
+    let response = rt
+        .block_on(async {
+            let mut client =
+                chess_client::ChessClient::connect_uds(&config::get_socket_path()).await?;
+            client.admin_close_session(id, suspend).await
+        })
+        .map_err(|e| CliError::SessionsFailed(e.to_string()))?;
+
+    println!("{}", sessions::format_close(id, &response));
+    Ok(())
+}
+
+/// Write a bug report archive for the session or finished game `id`.
+///
+/// Unlike the other subcommands this works without a running server: the
+/// archive then holds the log excerpt, config and versions, and notes that
+/// the server was not reachable.
+///
+/// # Errors
+///
+/// Returns [`CliError::ReportFailed`] if the server knows no session or game
+/// called `id`, or the archive cannot be written.
+fn handle_report(id: &str, output: Option<PathBuf>) -> Result<(), CliError> {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+
+    let rt = tokio::runtime::Runtime::new()
+        .map_err(|e| CliError::ProcessError(format!("failed to create runtime: {}", e)))?;
+    let files = rt
+        .block_on(report::gather(&config::get_socket_path(), id, now))
+        .map_err(CliError::ReportFailed)?;
+
+    let stem = report::file_stem(id);
+    let path = output.unwrap_or_else(|| PathBuf::from(format!("{}.tar", stem)));
+    let mut file = File::create(&path)
+        .map_err(|e| CliError::ReportFailed(format!("{}: {}", path.display(), e)))?;
+    report::write_tar(&mut file, &stem, &files, now)
+        .map_err(|e| CliError::ReportFailed(format!("{}: {}", path.display(), e)))?;
+
+    println!(
+        "Wrote {}. Look it over, then attach it to an issue at \
+         https://github.com/benediktms/chesstty/issues",
+        path.display()
+    );
+    Ok(())
+}
+
 /// Entry point for the ChessTTY shim.
 ///
 /// This function is intentionally **sync** — no `#[tokio::main]`. All
@@ -360,10 +877,15 @@ fn handle_engine_stop(force: bool) -> Result<(), CliError> {
 /// 1. Parse the command line with [`Cli`].
 /// 2. If the server is not already running, call [`spawn_server`] (fork + daemon + exec).
 /// 3. Create a tokio runtime and wait for the server's Unix socket ([`wait_for_server_socket`]).
-/// 4. Launch the TUI with [`spawn_tui_client`] and block until it exits.
+/// 4. If the server was already running, check its version ([`should_restart_server`])
+///    and, if asked to, restart it ([`restart_server`]).
+/// 5. Launch the TUI with [`spawn_tui_client`] and block until it exits.
 ///
 /// When the `engine stop` subcommand is given, delegates directly to
-/// [`handle_engine_stop`] — no runtime needed.
+/// [`handle_engine_stop`] — no runtime needed; `engine status` is handled by
+/// [`handle_engine_status`]. The `sync`, `audit`,
+/// `report` and `sessions` subcommands are handled by [`handle_sync`],
+/// [`handle_audit`], [`handle_report`] and [`handle_sessions`].
 ///
 /// # Errors
 ///
@@ -382,7 +904,54 @@ fn main() -> Result<(), CliError> {
             EngineAction::Stop { force } => {
                 handle_engine_stop(force)?;
             }
+            EngineAction::Status => {
+                handle_engine_status()?;
+            }
         },
+        Some(Commands::Sync { peer }) => {
+            handle_sync(&peer)?;
+        }
+        Some(Commands::Audit { limit }) => {
+            handle_audit(limit)?;
+        }
+        Some(Commands::Report { id, output }) => {
+            handle_report(&id, output)?;
+        }
+        Some(Commands::Sessions { action }) => match action {
+            None => handle_sessions()?,
+            Some(SessionsAction::Close { id, suspend }) => handle_close_session(&id, suspend)?,
+        },
+        Some(Commands::Review {
+            game_id,
+            cancel: true,
+            ..
+        }) => {
+            handle_cancel_review(&game_id)?;
+        }
+        Some(Commands::Review {
+            game_id,
+            from,
+            to,
+            depth,
+            multipv,
+            skip_advanced,
+            exclude_book,
+            exclude_forced,
+            exclude_decided,
+            ..
+        }) => {
+            handle_review(chess_client::EnqueueReviewRequest {
+                game_id,
+                from_ply: from,
+                to_ply: to,
+                depth,
+                multipv,
+                skip_advanced,
+                exclude_book,
+                exclude_forced,
+                exclude_decided_cp: exclude_decided,
+            })?;
+        }
         None => {
             let socket_path = config::get_socket_path();
             let pid_path = config::get_pid_path();
@@ -405,7 +974,7 @@ fn main() -> Result<(), CliError> {
 
             if !server_running {
                 tracing::info!("Server not running, starting...");
-                spawn_server()?;
+                spawn_server(cli.low_power)?;
                 tracing::info!("Server spawned.");
             } else {
                 tracing::info!("Server already running.");
@@ -421,6 +990,13 @@ fn main() -> Result<(), CliError> {
             rt.block_on(wait_for_server_socket())?;
             tracing::info!("Server socket ready.");
 
+            // A server we just spawned is the co-located binary; one left over
+            // from an earlier run may predate an upgrade.
+            if server_running && should_restart_server(&rt) {
+                drop(rt);
+                restart_server()?;
+            }
+
             // Spawn TUI client (sync — doesn't need tokio)
             tracing::info!("Starting TUI client...");
             spawn_tui_client()?;
@@ -2,6 +2,7 @@
 
 use std::fs;
 use std::path::Path;
+use std::time::{Duration, Instant};
 
 /// Error type for PID file and process-existence operations.
 #[derive(Debug, thiserror::Error)]
@@ -54,6 +55,24 @@ pub fn is_server_running(pid_path: &Path) -> Result<bool, ProcessError> {
     Ok(true)
 }
 
+/// Wait until the process with `pid` has exited, polling every `poll_interval`.
+///
+/// Returns `true` once the process is gone, or `false` if it is still running
+/// after `timeout`.
+pub fn wait_for_exit(pid: i32, timeout: Duration, poll_interval: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    loop {
+        // SAFETY: kill(pid, 0) only checks if the process exists.
+        if unsafe { libc::kill(pid, 0) } != 0 {
+            return true;
+        }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        std::thread::sleep(poll_interval);
+    }
+}
+
 /// Remove a stale PID file if the process is no longer running.
 ///
 /// A stale PID file contains a PID that no longer corresponds to a running process.
@@ -141,6 +160,15 @@ mod tests {
         assert!(matches!(result, Err(ProcessError::ProcessNotFound(999999))));
     }
 
+    #[test]
+    fn test_wait_for_exit() {
+        let poll = Duration::from_millis(10);
+        assert!(wait_for_exit(999999, Duration::ZERO, poll));
+
+        let pid = std::process::id() as i32;
+        assert!(!wait_for_exit(pid, Duration::from_millis(30), poll));
+    }
+
     #[test]
     fn test_remove_stale_pid() {
         let temp_dir = TempDir::new().unwrap();
@@ -0,0 +1,352 @@
+//! Bug report archives for `chesstty report`.
+//!
+//! A report is a tar archive with one text file per source: a summary with
+//! the shim and server versions, the session and finished game as the
+//! server knows them, the engine's UCI transcript, an excerpt of the server
+//! log and the `CHESSTTY_*` configuration with secrets redacted. Whatever
+//! the server cannot provide (it is down, or the session closed too long
+//! ago) is noted in the summary instead, so a report can still be made
+//! after a crash.
+
+use std::io::{self, Write};
+use std::path::Path;
+
+use chess_client::{ChessClient, UciDirection, UciMessageEvent};
+
+use crate::audit::format_timestamp;
+use crate::config;
+use crate::version::SHIM_VERSION;
+
+/// Lines kept from the end of the server log, besides those naming the
+/// session or game.
+pub const LOG_TAIL_LINES: usize = 200;
+
+/// Configuration names containing any of these have their values redacted.
+const SECRET_MARKERS: [&str; 4] = ["TOKEN", "SECRET", "PASSWORD", "KEY"];
+
+const REDACTED: &str = "<redacted>";
+
+const TAR_BLOCK: usize = 512;
+
+/// One text file of the archive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReportFile {
+    pub name: &'static str,
+    pub contents: String,
+}
+
+/// `id` with anything but letters, digits, `-` and `_` replaced, for use in
+/// file names.
+pub fn file_stem(id: &str) -> String {
+    let id: String = id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("chesstty-report-{}", id)
+}
+
+/// `NAME=value` for every `CHESSTTY_*` variable and `RUST_LOG`, sorted, with
+/// tokens and other secrets redacted.
+pub fn redacted_config(vars: impl IntoIterator<Item = (String, String)>) -> String {
+    let mut lines: Vec<String> = vars
+        .into_iter()
+        .filter(|(name, _)| name.starts_with("CHESSTTY_") || name == "RUST_LOG")
+        .map(|(name, value)| {
+            let secret = SECRET_MARKERS.iter().any(|marker| name.contains(marker));
+            let value = if secret { REDACTED.to_string() } else { value };
+            format!("{}={}", name, value)
+        })
+        .collect();
+    lines.sort();
+    if lines.is_empty() {
+        return "No CHESSTTY_* variables set; all defaults.\n".to_string();
+    }
+    lines.join("\n") + "\n"
+}
+
+/// The lines of `log` that mention one of `ids`, plus its last `tail` lines,
+/// in file order.
+pub fn log_excerpt(log: &str, ids: &[&str], tail: usize) -> String {
+    let lines: Vec<&str> = log.lines().collect();
+    let tail_start = lines.len().saturating_sub(tail);
+    let mut excerpt = String::new();
+    let mut skipped = false;
+    for (i, line) in lines.iter().enumerate() {
+        if i >= tail_start || ids.iter().any(|id| !id.is_empty() && line.contains(id)) {
+            if skipped {
+                excerpt.push_str("...\n");
+                skipped = false;
+            }
+            excerpt.push_str(line);
+            excerpt.push('\n');
+        } else {
+            skipped = true;
+        }
+    }
+    excerpt
+}
+
+/// One line per UCI message: `>>` to the engine, `<<` from it, `!!` for its
+/// stderr.
+pub fn format_transcript(entries: &[UciMessageEvent]) -> String {
+    let mut out = String::new();
+    for entry in entries {
+        let arrow = match UciDirection::try_from(entry.direction) {
+            Ok(UciDirection::ToEngine) => ">>",
+            Ok(UciDirection::FromEngine) => "<<",
+            Ok(UciDirection::EngineStderr) | Err(_) => "!!",
+        };
+        out.push_str(&format!("{} {}\n", arrow, entry.message));
+    }
+    out
+}
+
+/// Collect everything known about `id`, a session or finished game id.
+///
+/// # Errors
+///
+/// Returns an error if the server is reachable but knows neither a session
+/// nor a game by that id, which is most likely a typo.
+pub async fn gather(socket_path: &Path, id: &str, now: u64) -> Result<Vec<ReportFile>, String> {
+    let mut summary = vec![
+        format!("chesstty report for {}", id),
+        format!("Created: {} UTC", format_timestamp(now)),
+        format!("chesstty: {}", SHIM_VERSION),
+        format!(
+            "Platform: {} {}",
+            std::env::consts::OS,
+            std::env::consts::ARCH
+        ),
+    ];
+    let mut files = Vec::new();
+    let mut ids = vec![id.to_string()];
+
+    match ChessClient::connect_uds(socket_path).await {
+        Ok(mut client) => {
+            match client.get_server_info().await {
+                Ok(info) => summary.push(format!(
+                    "Server: {} (pid {}, {}), up since {} UTC",
+                    info.version,
+                    info.pid,
+                    info.executable,
+                    format_timestamp(info.started_at)
+                )),
+                Err(e) => summary.push(format!("Server: version unknown ({})", e)),
+            }
+
+            let mut game_id = id.to_string();
+            match client.get_debug_report(id).await {
+                Ok(report) => {
+                    if let Some(session) = &report.session {
+                        ids.push(session.session_id.clone());
+                        files.push(ReportFile {
+                            name: "session.txt",
+                            contents: format!("{:#?}\n", session),
+                        });
+                    }
+                    if let Some(saved) = report.game_id {
+                        game_id = saved;
+                        ids.push(game_id.clone());
+                    }
+                    files.push(ReportFile {
+                        name: "uci.log",
+                        contents: format_transcript(&report.uci_transcript),
+                    });
+                }
+                Err(e) => summary.push(format!("Session: not available ({})", e)),
+            }
+
+            match client.list_finished_games().await {
+                Ok(games) => {
+                    if let Some(game) = games.iter().find(|g| g.game_id == game_id) {
+                        files.push(ReportFile {
+                            name: "game.txt",
+                            contents: format!("{:#?}\n", game),
+                        });
+                        if let Ok(review) = client.get_game_review(&game_id).await {
+                            files.push(ReportFile {
+                                name: "review.txt",
+                                contents: format!("{:#?}\n", review),
+                            });
+                        }
+                    }
+                }
+                Err(e) => summary.push(format!("Finished games: not available ({})", e)),
+            }
+
+            if files.is_empty() {
+                return Err(format!("no session or finished game with id {}", id));
+            }
+        }
+        Err(e) => summary.push(format!(
+            "Server: not reachable at {} ({})",
+            socket_path.display(),
+            e
+        )),
+    }
+
+    let log_path = config::get_server_log_path();
+    let log = if log_path == Path::new("/dev/null") {
+        "Server output is discarded. Set CHESSTTY_SERVER_LOG_PATH to a file and \
+         reproduce the problem to capture it.\n"
+            .to_string()
+    } else {
+        match std::fs::read_to_string(&log_path) {
+            Ok(text) => {
+                let ids: Vec<&str> = ids.iter().map(String::as_str).collect();
+                log_excerpt(&text, &ids, LOG_TAIL_LINES)
+            }
+            Err(e) => format!("Could not read {}: {}\n", log_path.display(), e),
+        }
+    };
+    files.push(ReportFile {
+        name: "server.log",
+        contents: log,
+    });
+    files.push(ReportFile {
+        name: "config.txt",
+        contents: redacted_config(std::env::vars()),
+    });
+
+    files.insert(
+        0,
+        ReportFile {
+            name: "summary.txt",
+            contents: summary.join("\n") + "\n",
+        },
+    );
+    Ok(files)
+}
+
+fn put(header: &mut [u8; TAR_BLOCK], offset: usize, value: &[u8]) {
+    header[offset..offset + value.len()].copy_from_slice(value);
+}
+
+/// A ustar header for a regular file.
+fn tar_header(path: &str, size: usize, mtime: u64) -> [u8; TAR_BLOCK] {
+    let mut header = [0u8; TAR_BLOCK];
+    put(&mut header, 0, &path.as_bytes()[..path.len().min(100)]);
+    put(&mut header, 100, b"0000644\0");
+    put(&mut header, 108, b"0000000\0");
+    put(&mut header, 116, b"0000000\0");
+    put(&mut header, 124, format!("{:011o}\0", size).as_bytes());
+    put(&mut header, 136, format!("{:011o}\0", mtime).as_bytes());
+    // The checksum is summed with its own field as spaces
+    put(&mut header, 148, b"        ");
+    header[156] = b'0';
+    put(&mut header, 257, b"ustar\0");
+    put(&mut header, 263, b"00");
+    put(&mut header, 265, b"chesstty");
+    put(&mut header, 297, b"chesstty");
+    let checksum: u32 = header.iter().map(|&b| u32::from(b)).sum();
+    put(&mut header, 148, format!("{:06o}\0 ", checksum).as_bytes());
+    header
+}
+
+/// Write `files` as a tar archive, all under the directory `dir`.
+pub fn write_tar(
+    out: &mut impl Write,
+    dir: &str,
+    files: &[ReportFile],
+    mtime: u64,
+) -> io::Result<()> {
+    for file in files {
+        let data = file.contents.as_bytes();
+        out.write_all(&tar_header(
+            &format!("{}/{}", dir, file.name),
+            data.len(),
+            mtime,
+        ))?;
+        out.write_all(data)?;
+        let padding = (TAR_BLOCK - data.len() % TAR_BLOCK) % TAR_BLOCK;
+        out.write_all(&vec![0u8; padding])?;
+    }
+    // Two empty blocks end the archive
+    out.write_all(&[0u8; TAR_BLOCK * 2])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_redacted_config() {
+        let config = redacted_config(vars(&[
+            ("HOME", "/home/ann"),
+            ("CHESSTTY_SYNC_TOKEN", "hunter2"),
+            ("RUST_LOG", "debug"),
+            ("CHESSTTY_SOCKET_PATH", "/tmp/c.sock"),
+        ]));
+        assert_eq!(
+            config,
+            "CHESSTTY_SOCKET_PATH=/tmp/c.sock\nCHESSTTY_SYNC_TOKEN=<redacted>\nRUST_LOG=debug\n"
+        );
+        assert!(redacted_config(vars(&[("HOME", "/")])).starts_with("No CHESSTTY_"));
+    }
+
+    #[test]
+    fn test_log_excerpt_keeps_matches_and_tail() {
+        let log = "start\nsession abc created\nnoise 1\nnoise 2\nnoise 3\nlast";
+        assert_eq!(
+            log_excerpt(log, &["abc"], 2),
+            "...\nsession abc created\n...\nnoise 3\nlast\n"
+        );
+        assert_eq!(log_excerpt("a\nb", &[""], 5), "a\nb\n");
+    }
+
+    #[test]
+    fn test_format_transcript() {
+        let entries = vec![
+            UciMessageEvent {
+                direction: UciDirection::ToEngine as i32,
+                message: "go depth 10".into(),
+                ..Default::default()
+            },
+            UciMessageEvent {
+                direction: UciDirection::FromEngine as i32,
+                message: "bestmove e2e4".into(),
+                ..Default::default()
+            },
+        ];
+        assert_eq!(
+            format_transcript(&entries),
+            ">> go depth 10\n<< bestmove e2e4\n"
+        );
+    }
+
+    #[test]
+    fn test_write_tar() {
+        let files = vec![ReportFile {
+            name: "summary.txt",
+            contents: "hello\n".to_string(),
+        }];
+        let mut archive = Vec::new();
+        write_tar(&mut archive, &file_stem("a/b"), &files, 0).unwrap();
+
+        assert_eq!(archive.len(), TAR_BLOCK * 4);
+        assert!(archive.starts_with(b"chesstty-report-a_b/summary.txt\0"));
+        assert_eq!(&archive[124..136], b"00000000006\0");
+        assert_eq!(&archive[257..263], b"ustar\0");
+        assert_eq!(&archive[TAR_BLOCK..TAR_BLOCK + 6], b"hello\n");
+
+        let mut header = [0u8; TAR_BLOCK];
+        header.copy_from_slice(&archive[..TAR_BLOCK]);
+        let stored = std::str::from_utf8(&archive[148..154]).unwrap();
+        header[148..156].copy_from_slice(b"        ");
+        let sum: u32 = header.iter().map(|&b| u32::from(b)).sum();
+        assert_eq!(u32::from_str_radix(stored, 8).unwrap(), sum);
+    }
+}
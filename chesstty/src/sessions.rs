@@ -0,0 +1,172 @@
+//! Printing the server's open sessions for `chesstty sessions`.
+//!
+//! A daemon left running for days collects sessions from TUIs that were
+//! closed or crashed; this lists what is still open and what it holds, and
+//! reports on `chesstty sessions close`.
+
+use chess_client::{
+    ActiveSessionInfo, AdminCloseSessionResponse, GameModeProto, GameModeType,
+    ListActiveSessionsResponse, PlayerSideProto,
+};
+
+fn mode_name(mode: Option<&GameModeProto>) -> &'static str {
+    let Some(mode) = mode else {
+        return "unknown";
+    };
+    match GameModeType::try_from(mode.mode) {
+        Ok(GameModeType::HumanVsHuman) => "human vs human",
+        Ok(GameModeType::HumanVsEngine) => match mode.human_side.map(PlayerSideProto::try_from) {
+            Some(Ok(PlayerSideProto::Black)) => "human (black) vs engine",
+            _ => "human (white) vs engine",
+        },
+        Ok(GameModeType::EngineVsEngine) => "engine vs engine",
+        Ok(GameModeType::Analysis) => "analysis",
+        Ok(GameModeType::Review) => "review",
+        Err(_) => "unknown",
+    }
+}
+
+/// Largest two units of a duration, e.g. `3d 4h`, `12m 5s`.
+pub fn format_age(secs: u64) -> String {
+    let (days, hours, mins) = (secs / 86_400, secs % 86_400 / 3600, secs % 3600 / 60);
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, mins)
+    } else if mins > 0 {
+        format!("{}m {}s", mins, secs % 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+fn format_session(session: &ActiveSessionInfo) -> String {
+    let mut line = format!(
+        "{}  {:>7}  {:>4} moves  {}",
+        session.session_id,
+        format_age(session.age_secs),
+        session.move_count,
+        mode_name(session.game_mode.as_ref()),
+    );
+    if session.engine_running {
+        line.push_str(&format!(
+            " (engine: {} threads, {} MB)",
+            session.threads, session.hash_mb
+        ));
+    }
+    line
+}
+
+/// One line per session, oldest first, followed by the engine totals.
+pub fn format_sessions(response: &ListActiveSessionsResponse) -> String {
+    let mut sessions: Vec<&ActiveSessionInfo> = response.sessions.iter().collect();
+    sessions.sort_by(|a, b| b.age_secs.cmp(&a.age_secs));
+    let mut out = String::new();
+    for session in sessions {
+        out.push_str(&format_session(session));
+        out.push('\n');
+    }
+    out.push_str(&format!(
+        "{} open, {} engines running, {} MB hash\n",
+        response.sessions.len(),
+        response.engines_running,
+        response.total_hash_mb
+    ));
+    out
+}
+
+/// What `chesstty sessions close` did to session `id`.
+pub fn format_close(id: &str, response: &AdminCloseSessionResponse) -> String {
+    if response.aborted {
+        format!(
+            "Session {} did not answer and was aborted; nothing was saved.",
+            id
+        )
+    } else if let Some(suspended_id) = &response.suspended_id {
+        format!("Suspended session {} as {}.", id, suspended_id)
+    } else if let Some(game_id) = &response.game_id {
+        format!(
+            "Closed session {}; its game was saved as {} and queued for review.",
+            id, game_id
+        )
+    } else {
+        format!("Closed session {}.", id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_age() {
+        assert_eq!(format_age(42), "42s");
+        assert_eq!(format_age(125), "2m 5s");
+        assert_eq!(format_age(3 * 3600 + 120), "3h 2m");
+        assert_eq!(format_age(2 * 86_400 + 5 * 3600 + 59), "2d 5h");
+    }
+
+    #[test]
+    fn test_format_close() {
+        let aborted = AdminCloseSessionResponse {
+            aborted: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            format_close("a", &aborted),
+            "Session a did not answer and was aborted; nothing was saved."
+        );
+        let suspended = AdminCloseSessionResponse {
+            suspended_id: Some("suspended_1".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            format_close("a", &suspended),
+            "Suspended session a as suspended_1."
+        );
+        assert_eq!(
+            format_close("a", &AdminCloseSessionResponse::default()),
+            "Closed session a."
+        );
+    }
+
+    #[test]
+    fn test_format_sessions() {
+        let response = ListActiveSessionsResponse {
+            sessions: vec![
+                ActiveSessionInfo {
+                    session_id: "b".to_string(),
+                    game_mode: Some(GameModeProto {
+                        mode: GameModeType::Analysis as i32,
+                        human_side: None,
+                    }),
+                    move_count: 0,
+                    age_secs: 30,
+                    ..Default::default()
+                },
+                ActiveSessionInfo {
+                    session_id: "a".to_string(),
+                    game_mode: Some(GameModeProto {
+                        mode: GameModeType::HumanVsEngine as i32,
+                        human_side: Some(PlayerSideProto::Black as i32),
+                    }),
+                    move_count: 12,
+                    age_secs: 90_000,
+                    engine_running: true,
+                    threads: 2,
+                    hash_mb: 64,
+                    ..Default::default()
+                },
+            ],
+            engines_running: 1,
+            total_hash_mb: 64,
+            ..Default::default()
+        };
+        assert_eq!(
+            format_sessions(&response),
+            "a    1d 1h    12 moves  human (black) vs engine (engine: 2 threads, 64 MB)\n\
+             b      30s     0 moves  analysis\n\
+             2 open, 1 engines running, 64 MB hash\n"
+        );
+    }
+}
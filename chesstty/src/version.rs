@@ -0,0 +1,243 @@
+//! Server version check for the ChessTTY shim.
+//!
+//! After an upgrade the daemon from the previous install may still be
+//! running. Once the socket is ready the shim asks the server which version
+//! it is (`GetServerInfo`) and compares it with its own, so a new TUI is not
+//! silently run against an old server. `chesstty engine status` prints the
+//! same answer, with the server's power mode, disk usage and engine, via
+//! [`format_status`].
+
+use std::io::{BufRead, Write};
+use std::path::Path;
+
+use chess_client::{ChessClient, ClientError, GetServerInfoResponse};
+
+use crate::audit::format_timestamp;
+
+/// Version of this shim, shared by every binary of the workspace.
+pub const SHIM_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// What the running server reported about itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ServerVersion {
+    /// The server's version and the binary it runs from.
+    Known { version: String, executable: String },
+    /// The server predates `GetServerInfo`, so is older than this shim.
+    Unknown,
+}
+
+impl ServerVersion {
+    /// Whether the server was built from the same version as `shim_version`.
+    pub fn matches(&self, shim_version: &str) -> bool {
+        matches!(self, ServerVersion::Known { version, .. } if version == shim_version)
+    }
+
+    /// Warning shown when the versions differ.
+    pub fn mismatch_message(&self, shim_version: &str) -> String {
+        match self {
+            ServerVersion::Known {
+                version,
+                executable,
+            } if !executable.is_empty() => format!(
+                "the running server is version {} ({}) but chesstty is version {}",
+                version, executable, shim_version
+            ),
+            ServerVersion::Known { version, .. } => format!(
+                "the running server is version {} but chesstty is version {}",
+                version, shim_version
+            ),
+            ServerVersion::Unknown => format!(
+                "the running server is older than chesstty version {}",
+                shim_version
+            ),
+        }
+    }
+}
+
+/// Ask the server on `socket_path` for its version.
+///
+/// # Errors
+///
+/// Returns a [`ClientError`] if the server cannot be reached or the request
+/// fails for any reason other than the server not knowing `GetServerInfo`.
+pub async fn query_server_version(socket_path: &Path) -> Result<ServerVersion, ClientError> {
+    let mut client = ChessClient::connect_uds(socket_path).await?;
+    match client.get_server_info().await {
+        Ok(info) => Ok(ServerVersion::Known {
+            version: info.version,
+            executable: info.executable,
+        }),
+        Err(ClientError::RpcError(status)) if status.code() == tonic::Code::Unimplemented => {
+            Ok(ServerVersion::Unknown)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Ask on the terminal whether to restart the server with `server_bin`.
+/// Anything but an explicit yes keeps the running server.
+pub fn confirm_restart(message: &str, server_bin: &Path) -> bool {
+    eprint!(
+        "chesstty: {}.\nRestart the server with {}? Other open clients are disconnected. [y/N] ",
+        message,
+        server_bin.display()
+    );
+    let _ = std::io::stderr().flush();
+
+    let mut answer = String::new();
+    if std::io::stdin().lock().read_line(&mut answer).is_err() {
+        return false;
+    }
+    is_yes(&answer)
+}
+
+/// What `chesstty engine status` prints about the running server.
+pub fn format_status(info: &GetServerInfoResponse) -> String {
+    let mut out = format!("Server {} (pid {})\n", info.version, info.pid);
+    if !info.executable.is_empty() {
+        out.push_str(&format!("Binary: {}\n", info.executable));
+    }
+    out.push_str(&format!(
+        "Up since: {} UTC\n",
+        format_timestamp(info.started_at)
+    ));
+    let source = match info.power_source.as_str() {
+        "" => "unknown",
+        source => source,
+    };
+    let mode = if info.low_power {
+        "low-power (engines reduced, reviews paused)"
+    } else {
+        "full"
+    };
+    out.push_str(&format!("Power: {}, {}\n", source, mode));
+    let free = match info.disk_free_bytes {
+        Some(bytes) => format!("{} free", format_bytes(bytes)),
+        None => "free space unknown".to_string(),
+    };
+    out.push_str(&format!(
+        "Disk: {}{}; database {}, logs {}\n",
+        free,
+        if info.disk_low { " (low)" } else { "" },
+        format_bytes(info.db_size_bytes),
+        format_bytes(info.log_size_bytes)
+    ));
+    if info.engine_missing {
+        out.push_str("Engine: Stockfish not found (engine modes unavailable, reviews waiting)\n");
+    } else if !info.engine_path.is_empty() {
+        out.push_str(&format!("Engine: {}\n", info.engine_path));
+    }
+    // Unset while recovery runs, and from servers that predate it
+    if let Some(r) = &info.recovery {
+        let failed = if r.failed > 0 {
+            format!(", {} failed", r.failed)
+        } else {
+            String::new()
+        };
+        out.push_str(&format!(
+            "Recovery: {} interrupted and {} unreviewed games queued{} in {} ms\n",
+            r.interrupted, r.unreviewed, failed, r.elapsed_ms
+        ));
+    }
+    out
+}
+
+/// `bytes` in the largest unit that keeps it at or above 1, to one decimal.
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+fn is_yes(answer: &str) -> bool {
+    matches!(answer.trim().to_ascii_lowercase().as_str(), "y" | "yes")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chess_client::RecoveryInfo;
+
+    fn known(version: &str) -> ServerVersion {
+        ServerVersion::Known {
+            version: version.to_string(),
+            executable: "/opt/chesstty/chesstty-server".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_matches() {
+        assert!(known(SHIM_VERSION).matches(SHIM_VERSION));
+        assert!(!known("0.0.1").matches("0.1.2"));
+        assert!(!ServerVersion::Unknown.matches(SHIM_VERSION));
+    }
+
+    #[test]
+    fn test_mismatch_message() {
+        assert_eq!(
+            known("0.0.1").mismatch_message("0.1.2"),
+            "the running server is version 0.0.1 (/opt/chesstty/chesstty-server) \
+             but chesstty is version 0.1.2"
+        );
+        assert_eq!(
+            ServerVersion::Unknown.mismatch_message("0.1.2"),
+            "the running server is older than chesstty version 0.1.2"
+        );
+    }
+
+    #[test]
+    fn test_format_status() {
+        let info = GetServerInfoResponse {
+            version: "0.1.2".to_string(),
+            pid: 42,
+            executable: String::new(),
+            started_at: 0,
+            power_source: "battery".to_string(),
+            low_power: true,
+            disk_free_bytes: Some(300 << 20),
+            db_size_bytes: 4 << 20,
+            log_size_bytes: 0,
+            disk_low: true,
+            engine_missing: true,
+            engine_path: String::new(),
+            recovery: Some(RecoveryInfo {
+                interrupted: 1,
+                unreviewed: 12,
+                failed: 2,
+                elapsed_ms: 48,
+            }),
+        };
+        assert_eq!(
+            format_status(&info),
+            "Server 0.1.2 (pid 42)\n\
+             Up since: 1970-01-01 00:00:00 UTC\n\
+             Power: battery, low-power (engines reduced, reviews paused)\n\
+             Disk: 300.0 MB free (low); database 4.0 MB, logs 0 B\n\
+             Engine: Stockfish not found (engine modes unavailable, reviews waiting)\n\
+             Recovery: 1 interrupted and 12 unreviewed games queued, 2 failed in 48 ms\n"
+        );
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KB");
+        assert_eq!(format_bytes(5 << 30), "5.0 GB");
+    }
+
+    #[test]
+    fn test_is_yes() {
+        assert!(is_yes("y\n"));
+        assert!(is_yes(" YES "));
+        assert!(!is_yes("\n"));
+        assert!(!is_yes("no"));
+    }
+}
@@ -1,10 +1,13 @@
 //! Socket wait utilities for ChessTTY shim.
 //!
-//! Provides waiting for Unix domain sockets to become available.
+//! Provides waiting for Unix domain sockets to become available, and for the
+//! server behind one to report itself ready.
 
 use std::path::Path;
 use std::time::{Duration, Instant};
 
+use chess_client::{ChessClient, ClientError, ServingStatus};
+
 /// Error type for socket wait operations.
 #[derive(Debug, thiserror::Error)]
 pub enum WaitError {
@@ -13,6 +16,9 @@ pub enum WaitError {
 
     #[error("failed to connect to socket {0}: {1}")]
     Connect(String, #[source] std::io::Error),
+
+    #[error("server on socket {0} not ready after {1:?}")]
+    NotServing(String, Duration),
 }
 
 /// Wait for a Unix domain socket to become available.
@@ -89,6 +95,49 @@ pub async fn wait_for_socket(
     }
 }
 
+/// Wait for the server on `socket_path` to report its chess service as
+/// serving through the standard gRPC health check.
+///
+/// A connectable socket only means the server has bound it; the health check
+/// answers once the service is actually registered. A server that predates
+/// health checks counts as ready as soon as it answers at all.
+///
+/// # Errors
+///
+/// Returns `WaitError::NotServing` if the server doesn't report serving
+/// within the timeout period.
+pub async fn wait_for_serving(
+    socket_path: impl AsRef<Path>,
+    timeout: Duration,
+    poll_interval: Duration,
+) -> Result<(), WaitError> {
+    let socket_path = socket_path.as_ref();
+    let deadline = Instant::now() + timeout;
+
+    loop {
+        let status = match ChessClient::connect_uds(socket_path).await {
+            Ok(mut client) => client.health().await,
+            Err(e) => Err(e),
+        };
+        match status {
+            Ok(ServingStatus::Serving) => return Ok(()),
+            Err(ClientError::RpcError(status)) if status.code() == tonic::Code::Unimplemented => {
+                return Ok(())
+            }
+            Ok(status) => tracing::debug!("Server not serving yet: {:?}", status),
+            Err(e) => tracing::debug!("Server health check failed: {}", e),
+        }
+
+        if Instant::now() > deadline {
+            return Err(WaitError::NotServing(
+                socket_path.display().to_string(),
+                timeout,
+            ));
+        }
+        tokio::time::sleep(poll_interval).await;
+    }
+}
+
 /// Wait for a socket with default settings (5s timeout, 100ms poll interval).
 ///
 /// Convenience function that uses sensible defaults.
@@ -139,4 +188,19 @@ mod tests {
 
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_wait_for_serving_without_server() {
+        let temp_dir = TempDir::new().unwrap();
+        let socket_path = temp_dir.path().join("nonexistent.sock");
+
+        let result = wait_for_serving(
+            &socket_path,
+            Duration::from_millis(50),
+            Duration::from_millis(10),
+        )
+        .await;
+
+        assert!(matches!(result.unwrap_err(), WaitError::NotServing(_, _)));
+    }
 }
@@ -0,0 +1,139 @@
+//! Games shown by the demo ("attract") mode.
+//!
+//! The demo alternates famous games, replayed move by move with short
+//! annotations, with engine-vs-engine games played by the server. It is
+//! opened from the menu, or on its own once the menu has been left alone
+//! for `CHESSTTY_ATTRACT_IDLE_SECS` seconds, e.g. on a club display
+//! terminal.
+
+use crate::external_input::parse_san_move;
+use cozy_chess::{Board, Move};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+const FAMOUS_GAMES: &str = include_str!("../demo/famous_games.json");
+
+/// Environment variable with the menu idle time that starts the demo.
+pub const IDLE_ENV: &str = "CHESSTTY_ATTRACT_IDLE_SECS";
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FamousGame {
+    pub title: String,
+    pub white: String,
+    pub black: String,
+    pub event: String,
+    /// Movetext in SAN, move numbers included.
+    pub moves: String,
+    pub result: String,
+    /// Annotations by ply, counted from 1.
+    #[serde(default)]
+    pub notes: BTreeMap<usize, String>,
+}
+
+/// One move of a replayed game.
+#[derive(Debug, Clone)]
+pub struct DemoPly {
+    pub san: String,
+    pub mv: Move,
+    /// Position after the move.
+    pub board: Board,
+}
+
+impl FamousGame {
+    /// Replay the movetext from the starting position.
+    pub fn plies(&self) -> Result<Vec<DemoPly>, String> {
+        let mut board = Board::default();
+        let mut plies = Vec::new();
+        let sans = self
+            .moves
+            .split_whitespace()
+            .filter(|token| !token.ends_with('.'));
+        for san in sans {
+            let mv = parse_san_move(&board, san).ok_or_else(|| {
+                format!(
+                    "{}: illegal move {} at ply {}",
+                    self.title,
+                    san,
+                    plies.len() + 1
+                )
+            })?;
+            board.play(mv);
+            plies.push(DemoPly {
+                san: san.to_string(),
+                mv,
+                board: board.clone(),
+            });
+        }
+        Ok(plies)
+    }
+
+    /// The annotation shown after `ply` moves: the latest one up to that
+    /// move, so it stays up until the next.
+    pub fn note(&self, ply: usize) -> Option<&str> {
+        self.notes
+            .range(..=ply)
+            .next_back()
+            .map(|(_, note)| note.as_str())
+    }
+}
+
+/// The built-in famous games.
+pub fn famous_games() -> Result<Vec<FamousGame>, serde_json::Error> {
+    serde_json::from_str(FAMOUS_GAMES)
+}
+
+/// Menu idle time after which the demo starts, if configured.
+pub fn idle_timeout() -> Option<Duration> {
+    let value = std::env::var(IDLE_ENV).ok()?;
+    parse_idle(&value).or_else(|| {
+        tracing::warn!("Ignoring {}: expected seconds, got {}", IDLE_ENV, value);
+        None
+    })
+}
+
+fn parse_idle(value: &str) -> Option<Duration> {
+    match value.trim().parse::<u64>() {
+        Ok(0) | Err(_) => None,
+        Ok(secs) => Some(Duration::from_secs(secs)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_famous_games_replay() {
+        let games = famous_games().unwrap();
+        assert!(games.len() >= 3);
+        for game in &games {
+            let plies = game.plies().unwrap();
+            let last = plies.last().unwrap();
+            assert!(last.san.ends_with('#'), "{} should end in mate", game.title);
+            assert_eq!(last.board.status(), cozy_chess::GameStatus::Won);
+            assert!(game.notes.keys().all(|ply| (1..=plies.len()).contains(ply)));
+        }
+    }
+
+    #[test]
+    fn test_opera_game_castles_long() {
+        let games = famous_games().unwrap();
+        let plies = games[0].plies().unwrap();
+        // 12. O-O-O: cozy-chess writes castling as the king taking its rook
+        let castle = &plies[22];
+        assert_eq!(castle.san, "O-O-O");
+        assert_eq!(castle.mv.from, cozy_chess::Square::E1);
+        assert_eq!(castle.mv.to, cozy_chess::Square::A1);
+        assert_eq!(games[0].note(5), None);
+        assert_eq!(games[0].note(23), Some(games[0].notes[&23].as_str()));
+        assert_eq!(games[0].note(24), games[0].note(23));
+    }
+
+    #[test]
+    fn test_parse_idle() {
+        assert_eq!(parse_idle("90"), Some(Duration::from_secs(90)));
+        assert_eq!(parse_idle("0"), None);
+        assert_eq!(parse_idle("soon"), None);
+    }
+}
@@ -0,0 +1,163 @@
+//! Smoothing for the best-move arrow drawn while the engine searches.
+//!
+//! The head of the PV changes often in the first plies of a search, which
+//! made the arrow flicker. A new best move only replaces the arrow once it
+//! has led the PV for a few updates in a row, or once it is reported at a
+//! depth deep enough to trust, and the last accepted arrow stays up while a
+//! new search of the same position warms up.
+
+use cozy_chess::Square;
+
+/// Environment variable with the number of updates a new best move must
+/// lead the PV before the arrow follows it.
+pub const STABLE_UPDATES_ENV: &str = "CHESSTTY_ARROW_STABLE_UPDATES";
+
+/// Environment variable with the depth from which a new best move moves the
+/// arrow straight away.
+pub const MIN_DEPTH_ENV: &str = "CHESSTTY_ARROW_MIN_DEPTH";
+
+const DEFAULT_STABLE_UPDATES: u32 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArrowSmoothing {
+    /// Updates in a row a new best move needs; 0 or 1 follows every change.
+    pub stable_updates: u32,
+    /// Depth at which a new best move is taken at once, if set.
+    pub min_depth: Option<u32>,
+}
+
+impl Default for ArrowSmoothing {
+    fn default() -> Self {
+        Self {
+            stable_updates: DEFAULT_STABLE_UPDATES,
+            min_depth: None,
+        }
+    }
+}
+
+impl ArrowSmoothing {
+    /// Settings from `CHESSTTY_ARROW_STABLE_UPDATES` and
+    /// `CHESSTTY_ARROW_MIN_DEPTH`, falling back to the defaults.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            stable_updates: read_env(STABLE_UPDATES_ENV).unwrap_or(defaults.stable_updates),
+            min_depth: read_env(MIN_DEPTH_ENV).or(defaults.min_depth),
+        }
+    }
+}
+
+fn read_env(name: &str) -> Option<u32> {
+    let value = std::env::var(name).ok()?;
+    value.trim().parse().ok().or_else(|| {
+        tracing::warn!("Ignoring {}: expected a number, got {}", name, value);
+        None
+    })
+}
+
+/// The arrow currently shown and the move challenging it.
+#[derive(Debug, Clone, Default)]
+pub struct BestMoveArrow {
+    settings: ArrowSmoothing,
+    shown: Option<(Square, Square)>,
+    /// A different best move and how many updates in a row it has led.
+    candidate: Option<((Square, Square), u32)>,
+}
+
+impl BestMoveArrow {
+    pub fn new(settings: ArrowSmoothing) -> Self {
+        Self {
+            settings,
+            ..Default::default()
+        }
+    }
+
+    pub fn shown(&self) -> Option<(Square, Square)> {
+        self.shown
+    }
+
+    /// Take the head of the PV from one engine update and return the arrow
+    /// to draw.
+    pub fn observe(
+        &mut self,
+        best: Option<(Square, Square)>,
+        depth: Option<u32>,
+    ) -> Option<(Square, Square)> {
+        let Some(best) = best else {
+            return self.shown;
+        };
+        if self.shown == Some(best) {
+            self.candidate = None;
+            return self.shown;
+        }
+
+        let streak = match self.candidate {
+            Some((mv, n)) if mv == best => n + 1,
+            _ => 1,
+        };
+        let deep_enough = self
+            .settings
+            .min_depth
+            .is_some_and(|min| depth.is_some_and(|d| d >= min));
+        if streak >= self.settings.stable_updates || deep_enough {
+            self.shown = Some(best);
+            self.candidate = None;
+        } else {
+            self.candidate = Some((best, streak));
+        }
+        self.shown
+    }
+
+    /// Forget the arrow once the position it was for is gone.
+    pub fn reset(&mut self) {
+        self.shown = None;
+        self.candidate = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const E4: (Square, Square) = (Square::E2, Square::E4);
+    const D4: (Square, Square) = (Square::D2, Square::D4);
+
+    #[test]
+    fn test_new_best_move_waits_for_stable_updates() {
+        let mut arrow = BestMoveArrow::new(ArrowSmoothing::default());
+        assert_eq!(arrow.observe(Some(E4), Some(1)), None);
+        assert_eq!(arrow.observe(Some(E4), Some(2)), None);
+        assert_eq!(arrow.observe(Some(E4), Some(3)), Some(E4));
+
+        // A challenger that drops back does not move the arrow
+        assert_eq!(arrow.observe(Some(D4), Some(4)), Some(E4));
+        assert_eq!(arrow.observe(Some(D4), Some(5)), Some(E4));
+        assert_eq!(arrow.observe(Some(E4), Some(6)), Some(E4));
+        assert_eq!(arrow.observe(Some(D4), Some(7)), Some(E4));
+        assert_eq!(arrow.observe(Some(D4), Some(8)), Some(E4));
+        assert_eq!(arrow.observe(Some(D4), Some(9)), Some(D4));
+    }
+
+    #[test]
+    fn test_min_depth_takes_best_move_at_once() {
+        let mut arrow = BestMoveArrow::new(ArrowSmoothing {
+            stable_updates: 5,
+            min_depth: Some(12),
+        });
+        assert_eq!(arrow.observe(Some(E4), Some(11)), None);
+        assert_eq!(arrow.observe(Some(D4), Some(12)), Some(D4));
+    }
+
+    #[test]
+    fn test_arrow_persists_until_reset() {
+        let mut arrow = BestMoveArrow::new(ArrowSmoothing {
+            stable_updates: 1,
+            min_depth: None,
+        });
+        assert_eq!(arrow.observe(Some(E4), Some(20)), Some(E4));
+        // A new search of the same position, or an update without a PV
+        assert_eq!(arrow.observe(None, Some(1)), Some(E4));
+        arrow.reset();
+        assert_eq!(arrow.shown(), None);
+    }
+}
@@ -0,0 +1,167 @@
+//! Exporting review bookmarks as saved positions or EPD puzzles.
+
+use std::path::{Path, PathBuf};
+
+use crate::review_state::ReviewState;
+use crate::ui::widgets::bookmark_dialog::ply_label;
+
+/// Default directory for exported puzzles.
+pub fn default_export_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|d| d.join("chesstty").join("puzzles"))
+}
+
+/// File name for a game's puzzles, e.g. `game_1700000000-bookmarks.epd`.
+pub fn file_name(game_id: &str) -> String {
+    format!("{}-bookmarks.epd", game_id)
+}
+
+/// Each bookmarked position as a name and FEN for the saved positions. The
+/// name is the bookmark's note, or the move the position came from.
+pub fn saved_positions(review: &ReviewState) -> Vec<(String, String)> {
+    review
+        .bookmarks
+        .iter()
+        .filter_map(|bookmark| {
+            let fen = review.fen_after(bookmark.ply)?;
+            let name = if bookmark.note.is_empty() {
+                ply_label(bookmark.ply, &played_san(review, bookmark.ply))
+            } else {
+                bookmark.note.clone()
+            };
+            Some((format!("{} - {}", name, review.review.game_id), fen))
+        })
+        .collect()
+}
+
+/// One EPD record per bookmarked move the review analysed: the position the
+/// move was played in, with the engine's best move there as the solution.
+/// The starting position has no move to solve and is left out.
+pub fn puzzles(review: &ReviewState) -> Vec<String> {
+    review
+        .bookmarks
+        .iter()
+        .filter(|bookmark| bookmark.ply > 0)
+        .filter_map(|bookmark| {
+            let position = review
+                .review
+                .positions
+                .iter()
+                .find(|p| p.ply == bookmark.ply && !p.best_move_san.is_empty())?;
+            let board = review.board_before(bookmark.ply)?.to_string();
+            // EPD keeps the first four FEN fields and drops the move counters
+            let fields: Vec<&str> = board.split_whitespace().take(4).collect();
+            let mut record = format!(
+                "{} bm {}; id \"{} ply {}\";",
+                fields.join(" "),
+                position.best_move_san,
+                review.review.game_id,
+                bookmark.ply
+            );
+            if !bookmark.note.is_empty() {
+                record.push_str(&format!(" c0 \"{}\";", bookmark.note.replace('"', "'")));
+            }
+            Some(record)
+        })
+        .collect()
+}
+
+/// Write a game's puzzles into `dir`, one record per line, replacing an
+/// earlier export of the same game. Returns the written file's path.
+pub fn export(dir: &Path, game_id: &str, puzzles: &[String]) -> std::io::Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(file_name(game_id));
+    let mut text = puzzles.join("\n");
+    text.push('\n');
+    std::fs::write(&path, text)?;
+    Ok(path)
+}
+
+fn played_san(review: &ReviewState, ply: u32) -> String {
+    review
+        .review
+        .positions
+        .iter()
+        .find(|p| p.ply == ply)
+        .map(|p| p.played_san.clone())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chess_client::{GameReviewProto, PositionReview, ReviewBookmark};
+
+    fn review_with_bookmarks(bookmarks: Vec<(u32, &str)>) -> ReviewState {
+        let review = GameReviewProto {
+            game_id: "game_1".into(),
+            total_plies: 2,
+            analyzed_plies: 2,
+            positions: vec![
+                PositionReview {
+                    ply: 1,
+                    fen: "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1".into(),
+                    played_san: "e4".into(),
+                    best_move_san: "e4".into(),
+                    ..Default::default()
+                },
+                PositionReview {
+                    ply: 2,
+                    fen: "rnbqkbnr/pppppp1p/6p1/8/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2".into(),
+                    played_san: "g6".into(),
+                    best_move_san: "c5".into(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let mut state = ReviewState::new(review);
+        state.bookmarks = bookmarks
+            .into_iter()
+            .map(|(ply, note)| ReviewBookmark {
+                game_id: "game_1".into(),
+                ply,
+                note: note.into(),
+                created_at: 0,
+            })
+            .collect();
+        state
+    }
+
+    #[test]
+    fn test_saved_positions_are_named_by_note_or_move() {
+        let review = review_with_bookmarks(vec![(0, ""), (2, "Sicilian instead?")]);
+        let positions = saved_positions(&review);
+        assert_eq!(positions.len(), 2);
+        assert_eq!(positions[0].0, "Starting position - game_1");
+        assert_eq!(positions[1].0, "Sicilian instead? - game_1");
+        assert!(positions[1].1.starts_with("rnbqkbnr/pppppp1p/6p1"));
+
+        let review = review_with_bookmarks(vec![(2, "")]);
+        assert_eq!(saved_positions(&review)[0].0, "1... g6 - game_1");
+    }
+
+    #[test]
+    fn test_puzzles_ask_for_the_best_move_before_the_bookmark() {
+        let review = review_with_bookmarks(vec![(0, "start"), (2, "say \"c5\"")]);
+        assert_eq!(
+            puzzles(&review),
+            vec![
+                "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - bm c5; \
+                 id \"game_1 ply 2\"; c0 \"say 'c5'\";"
+                    .to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_export_writes_one_record_per_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let records = vec!["a bm e4;".to_string(), "b bm d4;".to_string()];
+        let path = export(&dir.path().join("puzzles"), "game_1", &records).unwrap();
+        assert_eq!(path.file_name().unwrap(), "game_1-bookmarks.epd");
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "a bm e4;\nb bm d4;\n"
+        );
+    }
+}
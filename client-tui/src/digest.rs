@@ -0,0 +1,53 @@
+//! Exporting training digests as Markdown files.
+
+use std::path::{Path, PathBuf};
+
+use chess_client::GetTrainingDigestResponse;
+
+/// Default directory for exported digests.
+pub fn default_export_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|d| d.join("chesstty").join("digests"))
+}
+
+/// File name for a digest, from the period it covers.
+pub fn file_name(digest: &GetTrainingDigestResponse) -> String {
+    format!(
+        "training-digest-{}-to-{}.md",
+        digest.first_date, digest.last_date
+    )
+}
+
+/// Write the digest's Markdown into `dir`, replacing an earlier export of
+/// the same period. Returns the written file's path.
+pub fn export(dir: &Path, digest: &GetTrainingDigestResponse) -> std::io::Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(file_name(digest));
+    std::fs::write(&path, &digest.markdown)?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_writes_markdown() {
+        let dir = tempfile::tempdir().unwrap();
+        let digest = GetTrainingDigestResponse {
+            first_date: "2026-10-10".to_string(),
+            last_date: "2026-10-16".to_string(),
+            markdown: "# Training Digest\n".to_string(),
+            ..Default::default()
+        };
+
+        let path = export(&dir.path().join("digests"), &digest).unwrap();
+        assert_eq!(
+            path.file_name().unwrap(),
+            "training-digest-2026-10-10-to-2026-10-16.md"
+        );
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "# Training Digest\n"
+        );
+    }
+}
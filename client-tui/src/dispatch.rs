@@ -0,0 +1,521 @@
+//! Server calls triggered by input, run off the render loop.
+//!
+//! Key handlers turn what the player asked for into an [`Action`] and return
+//! at once. A background task owns a clone of the board's client and runs the
+//! actions one at a time, in the order they were sent, so a slow server never
+//! stalls drawing. Each finished call comes back as a [`Completion`] that the
+//! render loop applies to the [`GameSession`](crate::state::GameSession).
+//! A re-analysis only holds the queue until the server accepts it; its result
+//! is waited for by a task of its own.
+
+use chess_client::{
+    AdvancedGameAnalysisProto, ChessClient, CompareReviewsResponse, ExplainPositionResponse,
+    GameModeProto, GameReviewProto, MoveDetail, ReviewBookmark, ReviewStatusType, SessionSnapshot,
+    SimulateClockResponse,
+};
+use cozy_chess::{Piece, Square};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tracing::Instrument;
+
+/// How long a re-analysis is waited for, e.g. while reviews are held on
+/// battery, before giving up on showing its result.
+const REANALYSIS_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// A server call requested by the player.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Action {
+    MakeMove {
+        from: Square,
+        to: Square,
+        promotion: Option<Piece>,
+    },
+    Undo,
+    Redo,
+    Reset,
+    Pause,
+    Resume,
+    SetEngine {
+        skill_level: u8,
+    },
+    /// Number of lines the engine searches at once (MultiPV).
+    SetEngineLines {
+        skill_level: u8,
+        lines: u32,
+    },
+    SetMoveDelay(u32),
+    SetTacticalHints(bool),
+    SetStepMode(bool),
+    StepEngine,
+    /// Fetch the legal moves after the position changed.
+    LegalMoves,
+    /// Re-read the session after a call failed.
+    Refresh,
+    SaveSnapshot {
+        fen: String,
+        name: String,
+        game_mode: Option<GameModeProto>,
+        move_count: u32,
+        skill_level: u8,
+        /// Finished game the position comes from.
+        source_game: String,
+    },
+    /// Snapshot the live position under a name the server makes up.
+    QuickSnapshot,
+    /// Ask for a code another client can take the session over with.
+    CreateTransfer,
+    SendChat {
+        author: String,
+        text: String,
+    },
+    /// Run a time control over a finished game's think times.
+    SimulateClock {
+        game_id: String,
+        initial_ms: u64,
+        increment_ms: u64,
+    },
+    /// Compare a game's latest earlier review pass with its current review.
+    CompareReviews {
+        game_id: String,
+    },
+    /// Explain why the best move at a reviewed ply is best.
+    ExplainPosition {
+        game_id: String,
+        ply: u32,
+    },
+    /// Re-analyse the move at a reviewed ply deeper, and fetch the patched
+    /// review once it is done.
+    ReanalyzePosition {
+        game_id: String,
+        ply: u32,
+    },
+    /// Fetch a reviewed game's bookmarks.
+    LoadBookmarks {
+        game_id: String,
+    },
+    /// Bookmark a ply, or change the note of its bookmark.
+    SetBookmark {
+        game_id: String,
+        ply: u32,
+        note: String,
+    },
+    DeleteBookmark {
+        game_id: String,
+        ply: u32,
+    },
+    /// Save bookmarked positions, as names and FENs, to the saved positions.
+    SaveBookmarkPositions {
+        positions: Vec<(String, String)>,
+    },
+}
+
+impl Action {
+    /// Prefix of the error notification when the call fails.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Action::MakeMove {
+                promotion: None, ..
+            } => "Move",
+            Action::MakeMove { .. } => "Promotion",
+            Action::Undo => "Undo",
+            Action::Redo => "Redo",
+            Action::Reset => "Reset",
+            Action::Pause => "Pause",
+            Action::Resume => "Resume",
+            Action::SetEngine { .. } => "Engine",
+            Action::SetEngineLines { .. } => "Engine lines",
+            Action::SetMoveDelay(_) => "Speed",
+            Action::SetTacticalHints(_) => "Hints",
+            Action::SetStepMode(_) => "Step mode",
+            Action::StepEngine => "Step",
+            Action::LegalMoves | Action::Refresh => "Sync",
+            Action::SaveSnapshot { .. } | Action::QuickSnapshot => "Snapshot",
+            Action::CreateTransfer => "Transfer",
+            Action::SendChat { .. } => "Chat",
+            Action::SimulateClock { .. } => "Clock",
+            Action::CompareReviews { .. } => "Compare",
+            Action::ExplainPosition { .. } => "Explain",
+            Action::ReanalyzePosition { .. } => "Re-analyse",
+            Action::LoadBookmarks { .. } => "Bookmarks",
+            Action::SetBookmark { .. } | Action::DeleteBookmark { .. } => "Bookmark",
+            Action::SaveBookmarkPositions { .. } => "Save positions",
+        }
+    }
+
+    /// Whether the session was updated locally before the call was made, so
+    /// a failure has to be followed by a [`Action::Refresh`].
+    pub fn is_optimistic(&self) -> bool {
+        matches!(
+            self,
+            Action::Pause
+                | Action::Resume
+                | Action::SetMoveDelay(_)
+                | Action::SetTacticalHints(_)
+                | Action::SetEngineLines { .. }
+                | Action::SetStepMode(_)
+        )
+    }
+}
+
+/// What a successful call returned.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    Done,
+    /// The position changed; the legal moves are those of the new position.
+    Position {
+        snapshot: SessionSnapshot,
+        legal_moves: Vec<MoveDetail>,
+    },
+    LegalMoves(Vec<MoveDetail>),
+    ClockSimulation(SimulateClockResponse),
+    ReviewComparison(CompareReviewsResponse),
+    PositionExplanation(ExplainPositionResponse),
+    /// The server took a re-analysis at `depth`. The patched review follows
+    /// as a second completion of the same action.
+    ReanalysisQueued {
+        depth: u32,
+    },
+    /// The review and advanced analysis after a re-analysis, and the depth
+    /// the move was re-analysed at.
+    ReviewPatched {
+        review: GameReviewProto,
+        advanced: Option<AdvancedGameAnalysisProto>,
+        depth: u32,
+    },
+    /// A game's bookmarks after loading or changing them, in ply order.
+    Bookmarks(Vec<ReviewBookmark>),
+    /// Name a quick snapshot was saved under.
+    Snapshot(String),
+    /// Code another client can take the session over with, and how long it
+    /// can be used.
+    TransferCode {
+        code: String,
+        expires_in_secs: u64,
+    },
+}
+
+/// A finished action and its result.
+#[derive(Debug)]
+pub struct Completion {
+    pub action: Action,
+    pub result: Result<Outcome, String>,
+    /// How long the call took, for the profiling overlay.
+    pub elapsed: Duration,
+}
+
+/// Handle to the background task running a board's actions.
+pub struct Dispatcher {
+    actions: mpsc::UnboundedSender<Action>,
+    completions: mpsc::UnboundedReceiver<Completion>,
+}
+
+impl Dispatcher {
+    /// Start the task. It ends once the dispatcher is dropped and the action
+    /// in progress, if any, has finished.
+    pub fn spawn(mut client: ChessClient) -> Self {
+        let (actions, mut action_rx) = mpsc::unbounded_channel::<Action>();
+        let (completion_tx, completions) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            while let Some(action) = action_rx.recv().await {
+                let started = Instant::now();
+                let result = execute(&mut client, &action)
+                    .instrument(tracing::debug_span!("rpc", action = action.label()))
+                    .await;
+                if let (
+                    Action::ReanalyzePosition { game_id, .. },
+                    Ok(Outcome::ReanalysisQueued { depth }),
+                ) = (&action, &result)
+                {
+                    tokio::spawn(follow_reanalysis(
+                        client.clone(),
+                        game_id.clone(),
+                        *depth,
+                        action.clone(),
+                        completion_tx.clone(),
+                    ));
+                }
+                let completion = Completion {
+                    action,
+                    result,
+                    elapsed: started.elapsed(),
+                };
+                if completion_tx.send(completion).is_err() {
+                    break;
+                }
+            }
+        });
+        Self {
+            actions,
+            completions,
+        }
+    }
+
+    pub fn send(&mut self, action: Action) {
+        if let Err(e) = self.actions.send(action) {
+            tracing::warn!("Action dropped, dispatcher stopped: {:?}", e.0);
+        }
+    }
+
+    /// Wait for the next finished action. Cancel-safe, so it can be raced
+    /// against other events.
+    pub async fn recv(&mut self) -> Option<Completion> {
+        self.completions.recv().await
+    }
+
+    /// The next finished action, if one is waiting.
+    pub fn try_recv(&mut self) -> Option<Completion> {
+        self.completions.try_recv().ok()
+    }
+}
+
+async fn execute(client: &mut ChessClient, action: &Action) -> Result<Outcome, String> {
+    use ::chess::{format_piece, format_square};
+
+    match action {
+        Action::MakeMove {
+            from,
+            to,
+            promotion,
+        } => {
+            let snapshot = client
+                .make_move(
+                    &format_square(*from),
+                    &format_square(*to),
+                    promotion.map(|p| format_piece(p).to_string()),
+                )
+                .await
+                .map_err(|e| e.to_string())?;
+            position(client, snapshot).await
+        }
+        Action::Undo => {
+            let snapshot = client.undo_move().await.map_err(|e| e.to_string())?;
+            position(client, snapshot).await
+        }
+        Action::Redo => {
+            let snapshot = client.redo_move().await.map_err(|e| e.to_string())?;
+            position(client, snapshot).await
+        }
+        Action::Reset => {
+            let snapshot = client.reset_game(None).await.map_err(|e| e.to_string())?;
+            position(client, snapshot).await
+        }
+        Action::Refresh => {
+            let snapshot = client.get_session().await.map_err(|e| e.to_string())?;
+            position(client, snapshot).await
+        }
+        Action::LegalMoves => client
+            .get_legal_moves(None)
+            .await
+            .map(Outcome::LegalMoves)
+            .map_err(|e| e.to_string()),
+        Action::Pause => done(client.pause().await),
+        Action::Resume => done(client.resume().await),
+        Action::SetEngine { skill_level } => done(
+            client
+                .set_engine(true, *skill_level as u32, None, None)
+                .await,
+        ),
+        Action::SetEngineLines { skill_level, lines } => {
+            done(client.set_engine_lines(*skill_level as u32, *lines).await)
+        }
+        Action::SetMoveDelay(delay_ms) => done(client.set_engine_move_delay(*delay_ms).await),
+        Action::SetTacticalHints(enabled) => done(client.set_tactical_hints(*enabled).await),
+        Action::SetStepMode(enabled) => done(client.set_step_mode(*enabled).await),
+        Action::StepEngine => done(client.step_engine().await),
+        Action::SaveSnapshot {
+            fen,
+            name,
+            game_mode,
+            move_count,
+            skill_level,
+            source_game,
+        } => done(
+            client
+                .save_snapshot(
+                    fen,
+                    name,
+                    game_mode.clone(),
+                    *move_count,
+                    *skill_level,
+                    source_game,
+                )
+                .await,
+        ),
+        Action::QuickSnapshot => client
+            .quick_snapshot()
+            .await
+            .map(|snapshot| Outcome::Snapshot(snapshot.name))
+            .map_err(|e| e.to_string()),
+        Action::CreateTransfer => client
+            .create_session_transfer()
+            .await
+            .map(|(code, expires_in_secs)| Outcome::TransferCode {
+                code,
+                expires_in_secs,
+            })
+            .map_err(|e| e.to_string()),
+        Action::SendChat { author, text } => done(client.send_chat(author, text).await),
+        Action::SimulateClock {
+            game_id,
+            initial_ms,
+            increment_ms,
+        } => client
+            .simulate_clock(game_id, *initial_ms, *increment_ms)
+            .await
+            .map(Outcome::ClockSimulation)
+            .map_err(|e| e.to_string()),
+        Action::CompareReviews { game_id } => client
+            .compare_reviews(game_id, None)
+            .await
+            .map(Outcome::ReviewComparison)
+            .map_err(|e| e.to_string()),
+        Action::ExplainPosition { game_id, ply } => client
+            .explain_position(game_id, *ply)
+            .await
+            .map(Outcome::PositionExplanation)
+            .map_err(|e| e.to_string()),
+        Action::ReanalyzePosition { game_id, ply } => client
+            .reanalyze_position(game_id, *ply, None)
+            .await
+            .map(|depth| Outcome::ReanalysisQueued { depth })
+            .map_err(|e| e.to_string()),
+        Action::LoadBookmarks { game_id } => bookmarks(client, game_id).await,
+        Action::SetBookmark { game_id, ply, note } => {
+            client
+                .set_review_bookmark(game_id, *ply, note)
+                .await
+                .map_err(|e| e.to_string())?;
+            bookmarks(client, game_id).await
+        }
+        Action::DeleteBookmark { game_id, ply } => {
+            client
+                .delete_review_bookmark(game_id, *ply)
+                .await
+                .map_err(|e| e.to_string())?;
+            bookmarks(client, game_id).await
+        }
+        Action::SaveBookmarkPositions { positions } => {
+            for (name, fen) in positions {
+                client
+                    .save_position(name, fen)
+                    .await
+                    .map_err(|e| e.to_string())?;
+            }
+            Ok(Outcome::Done)
+        }
+    }
+}
+
+/// Wait for an accepted re-analysis to finish, then report the patched
+/// review as a completion of `action`.
+async fn follow_reanalysis(
+    mut client: ChessClient,
+    game_id: String,
+    depth: u32,
+    action: Action,
+    completions: mpsc::UnboundedSender<Completion>,
+) {
+    let started = Instant::now();
+    let result = async {
+        wait_for_review(&mut client, &game_id).await?;
+        let review = client
+            .get_game_review(&game_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        let advanced = client.get_advanced_analysis(&game_id).await.ok();
+        Ok(Outcome::ReviewPatched {
+            review,
+            advanced,
+            depth,
+        })
+    }
+    .instrument(tracing::debug_span!("rpc", action = action.label()))
+    .await;
+    // Nobody is listening once the board is closed
+    let _ = completions.send(Completion {
+        action,
+        result,
+        elapsed: started.elapsed(),
+    });
+}
+
+/// Wait until a game's review is complete again, following its progress
+/// stream.
+async fn wait_for_review(client: &mut ChessClient, game_id: &str) -> Result<(), String> {
+    let mut updates = client
+        .stream_review_progress(game_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    let finished = async {
+        while let Some(update) = updates.message().await.map_err(|e| e.to_string())? {
+            let Some(status) = update.status else {
+                continue;
+            };
+            match ReviewStatusType::try_from(status.status) {
+                Ok(ReviewStatusType::ReviewStatusComplete) => return Ok(()),
+                Ok(ReviewStatusType::ReviewStatusFailed) => {
+                    return Err(status
+                        .error
+                        .unwrap_or_else(|| "analysis failed".to_string()))
+                }
+                _ => {}
+            }
+        }
+        Err("the server stopped reporting the analysis".to_string())
+    };
+    tokio::time::timeout(REANALYSIS_TIMEOUT, finished)
+        .await
+        .unwrap_or_else(|_| Err("still queued, reopen the review later to see it".to_string()))
+}
+
+async fn bookmarks(client: &mut ChessClient, game_id: &str) -> Result<Outcome, String> {
+    client
+        .list_review_bookmarks(game_id)
+        .await
+        .map(Outcome::Bookmarks)
+        .map_err(|e| e.to_string())
+}
+
+async fn position(client: &mut ChessClient, snapshot: SessionSnapshot) -> Result<Outcome, String> {
+    let legal_moves = client
+        .get_legal_moves(None)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(Outcome::Position {
+        snapshot,
+        legal_moves,
+    })
+}
+
+fn done<T, E: std::fmt::Display>(result: Result<T, E>) -> Result<Outcome, String> {
+    result.map(|_| Outcome::Done).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_labels_distinguish_promotions() {
+        let mv = Action::MakeMove {
+            from: Square::E7,
+            to: Square::E8,
+            promotion: None,
+        };
+        assert_eq!(mv.label(), "Move");
+        let promotion = Action::MakeMove {
+            from: Square::E7,
+            to: Square::E8,
+            promotion: Some(Piece::Queen),
+        };
+        assert_eq!(promotion.label(), "Promotion");
+    }
+
+    #[test]
+    fn test_only_local_settings_are_optimistic() {
+        assert!(Action::Pause.is_optimistic());
+        assert!(Action::SetMoveDelay(500).is_optimistic());
+        assert!(!Action::Undo.is_optimistic());
+        assert!(!Action::Refresh.is_optimistic());
+    }
+}
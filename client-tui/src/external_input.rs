@@ -0,0 +1,455 @@
+//! Moves and commands sent by other programs.
+//!
+//! Voice control, macro pads and similar tools drive the game through a
+//! small line protocol instead of being linked into the client. Each line
+//! is one JSON request:
+//!
+//! ```text
+//! {"move": "e2e4"}           a move as two squares, or in SAN ("Nf3", "O-O")
+//! {"keys": ["^", "y"]}       key presses, as if typed
+//! {"query": "status"}        a screen-reader command, answered in `lines`
+//! ```
+//!
+//! Requests come from a Unix socket at `CHESSTTY_INPUT_SOCKET`, where every
+//! request is answered with one JSON line such as
+//! `{"ok":true,"lines":["White to move."]}`, and from standard input when it
+//! is piped rather than a terminal. Replies to standard input are only
+//! logged, since standard output belongs to the board. Requests are handled
+//! by the render loop, one per frame, while a game is open.
+
+use crate::narration;
+use chess::parse_san;
+use cozy_chess::{Board, Move, Piece, Square};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
+use std::io::IsTerminal;
+use std::os::unix::fs::FileTypeExt;
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+
+/// Environment variable naming the socket to listen on.
+pub const SOCKET_ENV: &str = "CHESSTTY_INPUT_SOCKET";
+
+/// Requests waiting for the render loop.
+const QUEUE_LENGTH: usize = 32;
+
+/// One request line.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum Request {
+    Move {
+        #[serde(rename = "move")]
+        text: String,
+    },
+    Keys {
+        keys: Vec<String>,
+    },
+    Query {
+        query: String,
+    },
+}
+
+/// The answer to one request.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Reply {
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub lines: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl Reply {
+    pub fn ok(lines: Vec<String>) -> Self {
+        Self {
+            ok: true,
+            lines,
+            error: None,
+        }
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            ok: false,
+            lines: Vec::new(),
+            error: Some(message.into()),
+        }
+    }
+}
+
+/// A request on its way to the render loop.
+pub struct Pending {
+    pub request: Request,
+    reply: Option<oneshot::Sender<Reply>>,
+}
+
+impl Pending {
+    /// Answer the request. Replies to standard input go to the log.
+    pub fn respond(self, reply: Reply) {
+        match self.reply {
+            Some(sender) => {
+                let _ = sender.send(reply);
+            }
+            None if !reply.ok => {
+                tracing::warn!(request = ?self.request, "External input rejected: {:?}", reply.error)
+            }
+            None => {}
+        }
+    }
+}
+
+/// Listens for external requests for as long as the client runs.
+pub struct ExternalInput {
+    requests: mpsc::Receiver<Pending>,
+    // Kept so `recv` waits rather than ends when no source is open
+    _sender: mpsc::Sender<Pending>,
+    socket: Option<PathBuf>,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl ExternalInput {
+    /// Open the socket named by [`SOCKET_ENV`], if set, and read standard
+    /// input if it is piped. Failing to bind is logged, not fatal.
+    pub fn start() -> Self {
+        let (sender, requests) = mpsc::channel(QUEUE_LENGTH);
+        let mut input = Self {
+            requests,
+            _sender: sender.clone(),
+            socket: None,
+            tasks: Vec::new(),
+        };
+
+        if let Some(path) = std::env::var_os(SOCKET_ENV).map(PathBuf::from) {
+            match bind_socket(&path) {
+                Ok(listener) => {
+                    tracing::info!("Accepting external input on {}", path.display());
+                    input
+                        .tasks
+                        .push(tokio::spawn(accept(listener, sender.clone())));
+                    input.socket = Some(path);
+                }
+                Err(e) => tracing::warn!(
+                    "Failed to open external input socket {}: {}",
+                    path.display(),
+                    e
+                ),
+            }
+        }
+
+        if !std::io::stdin().is_terminal() {
+            tracing::info!("Accepting external input on stdin");
+            input.tasks.push(tokio::spawn(async move {
+                let mut lines = BufReader::new(tokio::io::stdin()).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    match parse_request(&line) {
+                        Ok(Some(request)) => {
+                            let pending = Pending {
+                                request,
+                                reply: None,
+                            };
+                            if sender.send(pending).await.is_err() {
+                                break;
+                            }
+                        }
+                        Ok(None) => {}
+                        Err(e) => tracing::warn!("Bad external input on stdin: {}", e),
+                    }
+                }
+            }));
+        }
+
+        input
+    }
+
+    /// Wait for the next request. Cancel-safe, so it can be raced against
+    /// other events.
+    pub async fn recv(&mut self) -> Option<Pending> {
+        self.requests.recv().await
+    }
+
+    /// Turn away requests that arrived while no game was open.
+    pub fn reject_queued(&mut self, reason: &str) {
+        while let Ok(pending) = self.requests.try_recv() {
+            pending.respond(Reply::error(reason));
+        }
+    }
+}
+
+impl Drop for ExternalInput {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+        if let Some(ref path) = self.socket {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// Bind the socket at `path`. A socket left behind by a crashed client is
+/// replaced; a live one, or anything that isn't a socket, is left alone.
+fn bind_socket(path: &Path) -> std::io::Result<UnixListener> {
+    match std::fs::symlink_metadata(path) {
+        Ok(metadata) if !metadata.file_type().is_socket() => {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::AlreadyExists,
+                "path exists and is not a socket",
+            ));
+        }
+        Ok(_) => match std::os::unix::net::UnixStream::connect(path) {
+            Ok(_) => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::AddrInUse,
+                    "another client is listening on it",
+                ));
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::ConnectionRefused => {
+                std::fs::remove_file(path)?;
+            }
+            Err(e) => return Err(e),
+        },
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e),
+    }
+    UnixListener::bind(path)
+}
+
+/// Parse one request line. Blank lines are `None`.
+pub fn parse_request(line: &str) -> Result<Option<Request>, String> {
+    let line = line.trim();
+    if line.is_empty() {
+        return Ok(None);
+    }
+    serde_json::from_str(line).map(Some).map_err(|_| {
+        format!(
+            "Expected {{\"move\"}}, {{\"keys\"}} or {{\"query\"}}: {}",
+            line
+        )
+    })
+}
+
+async fn accept(listener: UnixListener, sender: mpsc::Sender<Pending>) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                tokio::spawn(serve(stream, sender.clone()));
+            }
+            Err(e) => {
+                tracing::warn!("External input socket error: {}", e);
+                return;
+            }
+        }
+    }
+}
+
+/// Answer the requests of one connection, in order.
+async fn serve(stream: UnixStream, sender: mpsc::Sender<Pending>) {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        let reply = match parse_request(&line) {
+            Ok(None) => continue,
+            Ok(Some(request)) => {
+                let (reply_tx, reply_rx) = oneshot::channel();
+                let pending = Pending {
+                    request,
+                    reply: Some(reply_tx),
+                };
+                if sender.send(pending).await.is_err() {
+                    return;
+                }
+                reply_rx
+                    .await
+                    .unwrap_or_else(|_| Reply::error("Request dropped"))
+            }
+            Err(e) => Reply::error(e),
+        };
+        let mut json = serde_json::to_string(&reply).unwrap_or_default();
+        json.push('\n');
+        if writer.write_all(json.as_bytes()).await.is_err() {
+            return;
+        }
+    }
+}
+
+/// A key press by name: a single character, or `Enter`, `Esc`, `Tab`,
+/// `Backspace`, `Space`, the arrows (`Up`...), `Home`, `End`, `PageUp`,
+/// `PageDown` or `F1`-`F12`, optionally prefixed with `Ctrl+`.
+pub fn parse_key(name: &str) -> Option<KeyEvent> {
+    let (modifiers, name) = match name.strip_prefix("Ctrl+") {
+        Some(rest) if !rest.is_empty() => (KeyModifiers::CONTROL, rest),
+        _ => (KeyModifiers::NONE, name),
+    };
+    let mut chars = name.chars();
+    let code = match (chars.next(), chars.next()) {
+        (Some(c), None) => KeyCode::Char(c),
+        _ => match name.to_ascii_lowercase().as_str() {
+            "enter" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "tab" => KeyCode::Tab,
+            "backspace" => KeyCode::Backspace,
+            "space" => KeyCode::Char(' '),
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            other => {
+                let n: u8 = other.strip_prefix('f')?.parse().ok()?;
+                if !(1..=12).contains(&n) {
+                    return None;
+                }
+                KeyCode::F(n)
+            }
+        },
+    };
+    Some(KeyEvent::new(code, modifiers))
+}
+
+/// The squares of a move given as two squares (`e2e4`, `e7e8q`) or in SAN.
+/// Castling is the king moving onto its own rook, as the board expects.
+pub fn parse_move(board: &Board, text: &str) -> Option<(Square, Square, Option<Piece>)> {
+    if let Some(typed) = narration::parse_typed_move(text) {
+        return Some(typed);
+    }
+    let mv = parse_san_move(board, text)?;
+    Some((mv.from, mv.to, mv.promotion))
+}
+
+/// The legal move `san` stands for in `board`, castling included.
+pub fn parse_san_move(board: &Board, san: &str) -> Option<Move> {
+    let san = san.trim();
+    match san.trim_end_matches(['+', '#', '!', '?']) {
+        "O-O" | "0-0" => castling_move(board, true),
+        "O-O-O" | "0-0-0" => castling_move(board, false),
+        _ => parse_san(board, san),
+    }
+}
+
+fn castling_move(board: &Board, kingside: bool) -> Option<Move> {
+    let side = board.side_to_move();
+    let mut found = None;
+    board.generate_moves(|moves| {
+        found = moves.into_iter().find(|mv| {
+            board.piece_on(mv.from) == Some(Piece::King)
+                && board.piece_on(mv.to) == Some(Piece::Rook)
+                && board.color_on(mv.to) == Some(side)
+                && (mv.to.file() > mv.from.file()) == kingside
+        });
+        found.is_some()
+    });
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_request() {
+        assert_eq!(
+            parse_request(r#"{"move": "Nf3"}"#),
+            Ok(Some(Request::Move { text: "Nf3".into() }))
+        );
+        assert_eq!(
+            parse_request(r#"{"keys": ["^", "y"]}"#),
+            Ok(Some(Request::Keys {
+                keys: vec!["^".into(), "y".into()]
+            }))
+        );
+        assert_eq!(
+            parse_request(r#" {"query":"status"} "#),
+            Ok(Some(Request::Query {
+                query: "status".into()
+            }))
+        );
+        assert_eq!(parse_request("  "), Ok(None));
+        assert!(parse_request(r#"{"jump": 3}"#).is_err());
+        assert!(parse_request("e2e4").is_err());
+    }
+
+    #[test]
+    fn test_reply_json() {
+        let ok = serde_json::to_string(&Reply::ok(vec!["White to move.".into()])).unwrap();
+        assert_eq!(ok, r#"{"ok":true,"lines":["White to move."]}"#);
+        let error = serde_json::to_string(&Reply::error("Not a move")).unwrap();
+        assert_eq!(error, r#"{"ok":false,"error":"Not a move"}"#);
+    }
+
+    #[test]
+    fn test_parse_key() {
+        assert_eq!(
+            parse_key("Y"),
+            Some(KeyEvent::new(KeyCode::Char('Y'), KeyModifiers::NONE))
+        );
+        assert_eq!(
+            parse_key("Esc"),
+            Some(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE))
+        );
+        assert_eq!(
+            parse_key("Ctrl+c"),
+            Some(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL))
+        );
+        assert_eq!(
+            parse_key("F5"),
+            Some(KeyEvent::new(KeyCode::F(5), KeyModifiers::NONE))
+        );
+        assert_eq!(parse_key("F13"), None);
+        assert_eq!(parse_key("Hyper"), None);
+    }
+
+    #[test]
+    fn test_parse_move() {
+        let board = Board::default();
+        let square = |s: &str| s.parse::<Square>().unwrap();
+        assert_eq!(
+            parse_move(&board, "e2e4"),
+            Some((square("e2"), square("e4"), None))
+        );
+        assert_eq!(
+            parse_move(&board, "Nf3"),
+            Some((square("g1"), square("f3"), None))
+        );
+        assert_eq!(parse_move(&board, "Ke2"), None);
+
+        let castling: Board = "r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1".parse().unwrap();
+        assert_eq!(
+            parse_move(&castling, "O-O-O"),
+            Some((square("e8"), square("a8"), None))
+        );
+        assert_eq!(
+            parse_move(&castling, "0-0+"),
+            Some((square("e8"), square("h8"), None))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_bind_socket_only_replaces_stale_sockets() {
+        let dir = tempfile::tempdir().unwrap();
+
+        // A stale socket is replaced
+        let stale = dir.path().join("stale.sock");
+        drop(std::os::unix::net::UnixListener::bind(&stale).unwrap());
+        assert!(bind_socket(&stale).is_ok());
+
+        // A live one is not
+        let live = dir.path().join("live.sock");
+        let _listener = bind_socket(&live).unwrap();
+        assert_eq!(
+            bind_socket(&live).unwrap_err().kind(),
+            std::io::ErrorKind::AddrInUse
+        );
+
+        // Nor is anything else
+        let file = dir.path().join("notes.txt");
+        std::fs::write(&file, "keep me").unwrap();
+        assert!(bind_socket(&file).is_err());
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "keep me");
+    }
+}
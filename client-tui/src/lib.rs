@@ -1,10 +1,27 @@
+mod attract;
+mod best_move;
+mod bookmarks;
+mod digest;
+mod dispatch;
+mod external_input;
+mod material;
+mod narration;
+mod notifications;
+mod offline;
+mod opponents;
 pub mod prelude;
+mod premove;
+mod profiling;
+mod review_progress;
 mod review_state;
+mod simul;
 mod state;
+mod study;
+mod tutorial;
 pub mod ui;
 
 pub use review_state::ReviewState;
-pub use state::{GameMode, PlayerColor, UciDirection, UciLogEntry};
+pub use state::{GameMode, PlayerSide, UciDirection, UciLogEntry};
 
 pub use ui::fsm;
 pub use ui::menu_app;
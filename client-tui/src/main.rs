@@ -1,14 +1,31 @@
+mod attract;
+mod best_move;
+mod bookmarks;
+mod digest;
+mod dispatch;
+mod external_input;
+mod material;
+mod narration;
+mod notifications;
+mod offline;
+mod opponents;
+mod premove;
+mod profiling;
+mod review_progress;
 mod review_state;
+mod simul;
 mod state;
+mod study;
+mod tutorial;
 mod ui;
 
 // Re-export app types for compatibility
 pub mod app {
-    pub use crate::state::{GameMode, PlayerColor, UciDirection, UciLogEntry};
+    pub use crate::state::{GameMode, PlayerSide, UciDirection, UciLogEntry};
     pub use crate::ui::fsm::render_spec::InputPhase;
 }
 
-use tracing_subscriber::{fmt, prelude::*, EnvFilter};
+use tracing_subscriber::{fmt, fmt::format::FmtSpan, prelude::*, EnvFilter};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -24,18 +41,39 @@ async fn main() -> anyhow::Result<()> {
                 .with_writer(non_blocking)
                 .with_ansi(false)
                 .with_target(true)
-                .with_line_number(true),
+                .with_line_number(true)
+                // Closing spans log their busy time, so `RUST_LOG=debug`
+                // shows how long each draw and server call took
+                .with_span_events(FmtSpan::CLOSE),
         )
         .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")))
         .init();
 
     tracing::info!("ChessTTY Client starting up");
 
+    // `--driver <script>` runs a script headless instead of the menu
+    let mut args = std::env::args().skip(1);
+    match args.next().as_deref() {
+        Some("--driver") => {
+            let script = args
+                .next()
+                .ok_or_else(|| anyhow::anyhow!("--driver needs a script path"))?;
+            // Plain text at fixed widths, so screenshots match on every terminal
+            ui::glyphs::init(ui::glyphs::GlyphWidths::NARROW);
+            ui::graphics::init(None);
+            return ui::driver::run_script(std::path::Path::new(&script)).await;
+        }
+        Some(other) => anyhow::bail!("Unknown argument: {} (expected --driver <script>)", other),
+        None => {}
+    }
+
     println!("ChessTTY - Terminal Chess Client");
     println!("Connecting to server via UDS");
     println!();
     println!("ChessTTY - Starting menu...");
     println!("Debug logs: logs/chesstty-client-tui.YYYY-MM-DD");
+    ui::glyphs::init(ui::glyphs::detect());
+    ui::graphics::init(ui::graphics::detect());
     ui::run_app().await?;
 
     tracing::info!("ChessTTY Client shutting down");
@@ -0,0 +1,121 @@
+//! Captured pieces and material balance for the game info panel.
+//!
+//! Captures are read off consecutive positions of the move history rather
+//! than the move records, so the same code serves live games (where undo
+//! shortens the server's history) and review navigation (where only FENs are
+//! available).
+
+use cozy_chess::{Board, Color, Piece};
+
+/// Piece types that can be captured, in display order.
+pub const CAPTURABLE: [Piece; 5] = [
+    Piece::Pawn,
+    Piece::Knight,
+    Piece::Bishop,
+    Piece::Rook,
+    Piece::Queen,
+];
+
+/// Conventional piece values in pawns.
+pub fn piece_value(piece: Piece) -> i32 {
+    match piece {
+        Piece::Pawn => 1,
+        Piece::Knight | Piece::Bishop => 3,
+        Piece::Rook => 5,
+        Piece::Queen => 9,
+        Piece::King => 0,
+    }
+}
+
+/// Pieces each side has taken, counted per type in [`CAPTURABLE`] order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CapturedMaterial {
+    pub by_white: [u8; 5],
+    pub by_black: [u8; 5],
+}
+
+impl CapturedMaterial {
+    /// Captures made between `start` and the last of `fens`, the positions
+    /// after each move in order. Unparseable positions are skipped.
+    pub fn from_history<'a>(start: &Board, fens: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut captured = Self::default();
+        let mut prev = start.clone();
+        for fen in fens {
+            let Ok(next) = fen.parse::<Board>() else {
+                continue;
+            };
+            let mover = prev.side_to_move();
+            let taken = match mover {
+                Color::White => &mut captured.by_white,
+                Color::Black => &mut captured.by_black,
+            };
+            for (slot, piece) in taken.iter_mut().zip(CAPTURABLE) {
+                let before = prev.colored_pieces(!mover, piece).len();
+                let after = next.colored_pieces(!mover, piece).len();
+                *slot += before.saturating_sub(after) as u8;
+            }
+            prev = next;
+        }
+        captured
+    }
+}
+
+/// Material on the board, White's minus Black's, in pawns.
+pub fn material_balance(board: &Board) -> i32 {
+    CAPTURABLE
+        .iter()
+        .map(|&piece| {
+            let white = board.colored_pieces(Color::White, piece).len() as i32;
+            let black = board.colored_pieces(Color::Black, piece).len() as i32;
+            (white - black) * piece_value(piece)
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 1. e4 d5 2. exd5 Qxd5
+    const SCANDINAVIAN: [&str; 4] = [
+        "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1",
+        "rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2",
+        "rnbqkbnr/ppp1pppp/8/3P4/8/8/PPPP1PPP/RNBQKBNR b KQkq - 0 2",
+        "rnb1kbnr/ppp1pppp/8/3q4/8/8/PPPP1PPP/RNBQKBNR w KQkq - 0 3",
+    ];
+
+    #[test]
+    fn test_captures_follow_history_length() {
+        let start = Board::default();
+
+        let after_exd5 = CapturedMaterial::from_history(&start, SCANDINAVIAN[..3].iter().copied());
+        assert_eq!(after_exd5.by_white, [1, 0, 0, 0, 0]);
+        assert_eq!(after_exd5.by_black, [0; 5]);
+
+        // Stepping forward (or the server replaying after an undo) recomputes
+        let after_qxd5 = CapturedMaterial::from_history(&start, SCANDINAVIAN.iter().copied());
+        assert_eq!(after_qxd5.by_white, [1, 0, 0, 0, 0]);
+        assert_eq!(after_qxd5.by_black, [1, 0, 0, 0, 0]);
+
+        assert_eq!(
+            CapturedMaterial::from_history(&start, std::iter::empty()),
+            CapturedMaterial::default()
+        );
+    }
+
+    #[test]
+    fn test_promotion_is_not_a_capture() {
+        let start: Board = "8/P7/8/8/8/8/8/k6K w - - 0 1".parse().unwrap();
+        let promoted = "Q7/8/8/8/8/8/8/k6K b - - 0 1";
+        let captured = CapturedMaterial::from_history(&start, [promoted]);
+        assert_eq!(captured, CapturedMaterial::default());
+        assert_eq!(material_balance(&promoted.parse().unwrap()), 9);
+    }
+
+    #[test]
+    fn test_material_balance() {
+        assert_eq!(material_balance(&Board::default()), 0);
+        assert_eq!(material_balance(&SCANDINAVIAN[2].parse().unwrap()), 1);
+        assert_eq!(material_balance(&SCANDINAVIAN[3].parse().unwrap()), 0);
+    }
+}
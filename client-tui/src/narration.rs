@@ -0,0 +1,566 @@
+//! Plain-text narration for the screen-reader mode.
+//!
+//! Instead of the board, the screen-reader mode shows a transcript of what
+//! happened, one sentence per line: moves as they are played, checks, the
+//! end of the game, pauses, clock warnings and any warning or error that
+//! would otherwise only flash past as a coloured toast. The render loop
+//! feeds the session to [`Narration::observe`] every frame and only changes
+//! are written out, so a screen reader reviewing the bottom of the panel
+//! reads the latest events. The position itself is inspected with typed
+//! commands (see [`run_command`]) rather than by reading the board.
+
+use crate::notifications::Severity;
+use crate::state::GameSession;
+use chess::parse_square;
+use chess_client::MoveRecord;
+use cozy_chess::{Board, Color, Piece, Square};
+use std::collections::VecDeque;
+use std::time::Instant;
+
+/// Most lines kept in the transcript.
+pub const TRANSCRIPT_LIMIT: usize = 500;
+
+/// Remaining clock times that are announced once crossed.
+pub const CLOCK_WARNINGS_MS: [u64; 3] = [60_000, 30_000, 10_000];
+
+/// Whether the menu starts with the screen-reader mode on, from
+/// `CHESSTTY_SCREEN_READER` (`1`, `true` or `on`).
+pub fn enabled_by_default() -> bool {
+    std::env::var("CHESSTTY_SCREEN_READER")
+        .is_ok_and(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "on"))
+}
+
+/// What the narration compares between frames.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GameFacts {
+    pub plies: usize,
+    /// Server game status: 0 ongoing, 1 checkmate, 2 stalemate, 3 draw.
+    pub status: i32,
+    pub white_to_move: bool,
+    pub in_check: bool,
+    pub paused: bool,
+    /// Remaining (white, black) clock time, when the game is timed.
+    pub clocks: Option<(u64, u64)>,
+    pub awaiting_human: bool,
+}
+
+impl GameFacts {
+    pub fn from_session(session: &GameSession) -> Self {
+        Self {
+            plies: session.history().len(),
+            status: session.status(),
+            white_to_move: session.board().side_to_move() == Color::White,
+            in_check: !session.board().checkers().is_empty(),
+            paused: session.paused,
+            clocks: session
+                .snapshot
+                .timer
+                .as_ref()
+                .map(|t| (t.white_remaining_ms, t.black_remaining_ms)),
+            awaiting_human: session.awaiting_human_move(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Narration {
+    facts: Option<GameFacts>,
+    /// When the last notification mirrored into the transcript was raised.
+    last_notification: Option<Instant>,
+    lines: VecDeque<String>,
+}
+
+impl Narration {
+    pub fn say(&mut self, line: impl Into<String>) {
+        self.lines.push_back(line.into());
+        while self.lines.len() > TRANSCRIPT_LIMIT {
+            self.lines.pop_front();
+        }
+    }
+
+    /// Start over on another simul board: the next observation describes
+    /// its position instead of narrating the difference.
+    pub fn switch_board(&mut self, label: impl Into<String>) {
+        self.facts = None;
+        self.say(label);
+    }
+
+    /// Transcript, oldest first.
+    pub fn lines(&self) -> impl DoubleEndedIterator<Item = &str> + ExactSizeIterator {
+        self.lines.iter().map(String::as_str)
+    }
+
+    /// Narrate what changed since the last call. Review mode is not
+    /// narrated; stepping through a finished game is driven by the player.
+    pub fn observe(&mut self, session: &GameSession) {
+        if session.review_state.is_some() {
+            return;
+        }
+        let now = GameFacts::from_session(session);
+        let lines = match &self.facts {
+            Some(prev) if *prev == now => Vec::new(),
+            Some(prev) => {
+                let new_moves = session.history().get(prev.plies..).unwrap_or_default();
+                narrate(prev, &now, new_moves)
+            }
+            None if now.plies == 0 => vec![format!(
+                "Game started. {}. Press colon for commands.",
+                turn(&now)
+            )],
+            None => vec![format!(
+                "Move {}. {}. Press colon for commands.",
+                now.plies / 2 + 1,
+                turn(&now)
+            )],
+        };
+        lines.into_iter().for_each(|line| self.say(line));
+        self.facts = Some(now);
+
+        // Warnings and errors are only shown as coloured toasts otherwise
+        if let Some(latest) = session.notifications.latest() {
+            if self.last_notification != Some(latest.created) {
+                self.last_notification = Some(latest.created);
+                if matches!(latest.severity, Severity::Warning | Severity::Error) {
+                    self.say(format!(
+                        "{}: {}",
+                        severity_word(latest.severity),
+                        latest.message
+                    ));
+                }
+            }
+        }
+    }
+}
+
+fn severity_word(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Info => "Info",
+        Severity::Success => "Done",
+        Severity::Warning => "Warning",
+        Severity::Error => "Error",
+    }
+}
+
+fn side_name(white: bool) -> &'static str {
+    if white {
+        "White"
+    } else {
+        "Black"
+    }
+}
+
+fn turn(facts: &GameFacts) -> String {
+    if facts.awaiting_human {
+        "Your move".to_string()
+    } else {
+        format!("{} to move", side_name(facts.white_to_move))
+    }
+}
+
+/// Sentences for everything that changed between two frames.
+pub fn narrate(prev: &GameFacts, now: &GameFacts, new_moves: &[MoveRecord]) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    if now.plies < prev.plies {
+        lines.push(if now.plies == 0 {
+            "Board reset to the starting position.".to_string()
+        } else {
+            format!("Move taken back. {}.", turn(now))
+        });
+    }
+
+    // The side that made the first new move is the opposite of the one to
+    // move now, shifted by the number of moves
+    let mut white_moved = now.white_to_move == (new_moves.len() % 2 == 0);
+    for (i, record) in new_moves.iter().enumerate() {
+        let mut line = format!("{}: {}", side_name(white_moved), describe_move(record));
+        let last = i + 1 == new_moves.len();
+        if last && now.in_check && now.status == 0 {
+            line.push_str(", check");
+        }
+        line.push('.');
+        lines.push(line);
+        white_moved = !white_moved;
+    }
+
+    if now.status != prev.status {
+        match now.status {
+            1 => lines.push(format!(
+                "Checkmate. {} wins.",
+                side_name(!now.white_to_move)
+            )),
+            2 => lines.push("Stalemate. The game is drawn.".to_string()),
+            3 => lines.push("The game is drawn.".to_string()),
+            _ => {}
+        }
+    }
+
+    if now.paused != prev.paused {
+        lines.push(if now.paused {
+            "Game paused.".to_string()
+        } else {
+            "Game resumed.".to_string()
+        });
+    }
+
+    if let (Some((pw, pb)), Some((nw, nb))) = (prev.clocks, now.clocks) {
+        for (white, before, after) in [(true, pw, nw), (false, pb, nb)] {
+            if let Some(&mark) = CLOCK_WARNINGS_MS
+                .iter()
+                .filter(|&&mark| before > mark && after <= mark)
+                .last()
+            {
+                lines.push(format!(
+                    "{} has {} seconds left.",
+                    side_name(white),
+                    mark / 1000
+                ));
+            }
+        }
+    }
+
+    // A take-back already said whose move it is
+    if now.awaiting_human && !prev.awaiting_human && now.status == 0 && now.plies >= prev.plies {
+        lines.push("Your move.".to_string());
+    }
+    lines
+}
+
+pub(crate) fn piece_word(piece: Piece) -> &'static str {
+    match piece {
+        Piece::Pawn => "pawn",
+        Piece::Knight => "knight",
+        Piece::Bishop => "bishop",
+        Piece::Rook => "rook",
+        Piece::Queen => "queen",
+        Piece::King => "king",
+    }
+}
+
+fn plural(piece: Piece) -> &'static str {
+    match piece {
+        Piece::Pawn => "pawns",
+        Piece::Knight => "knights",
+        Piece::Bishop => "bishops",
+        Piece::Rook => "rooks",
+        Piece::Queen => "queens",
+        Piece::King => "kings",
+    }
+}
+
+fn piece_from_letter(letter: &str) -> Option<Piece> {
+    match letter.to_ascii_uppercase().as_str() {
+        "P" => Some(Piece::Pawn),
+        "N" => Some(Piece::Knight),
+        "B" => Some(Piece::Bishop),
+        "R" => Some(Piece::Rook),
+        "Q" => Some(Piece::Queen),
+        "K" => Some(Piece::King),
+        _ => None,
+    }
+}
+
+/// `knight g1 to f3`, `pawn e5 takes pawn on d6`, `castles kingside`,
+/// `pawn e7 to e8, promotes to queen`.
+pub fn describe_move(record: &MoveRecord) -> String {
+    let san = record.san.trim_end_matches(['+', '#']);
+    if san == "O-O" {
+        return "castles kingside".to_string();
+    }
+    if san == "O-O-O" {
+        return "castles queenside".to_string();
+    }
+
+    let piece = piece_from_letter(&record.piece).map_or("piece", piece_word);
+    let mut text = match record.captured.as_deref().and_then(piece_from_letter) {
+        Some(captured) => format!(
+            "{} {} takes {} on {}",
+            piece,
+            record.from,
+            piece_word(captured),
+            record.to
+        ),
+        None => format!("{} {} to {}", piece, record.from, record.to),
+    };
+    if let Some(promotion) = record.promotion.as_deref().and_then(piece_from_letter) {
+        text.push_str(&format!(", promotes to {}", piece_word(promotion)));
+    }
+    text
+}
+
+/// `White pieces: king e1; queen d1; rooks a1, h1; ...`, most valuable
+/// first.
+pub fn list_pieces(board: &Board, color: Color) -> String {
+    let groups: Vec<String> = [
+        Piece::King,
+        Piece::Queen,
+        Piece::Rook,
+        Piece::Bishop,
+        Piece::Knight,
+        Piece::Pawn,
+    ]
+    .into_iter()
+    .filter_map(|piece| {
+        let squares: Vec<String> = board
+            .colored_pieces(color, piece)
+            .into_iter()
+            .map(|sq| sq.to_string())
+            .collect();
+        match squares.len() {
+            0 => None,
+            1 => Some(format!("{} {}", piece_word(piece), squares[0])),
+            _ => Some(format!("{} {}", plural(piece), squares.join(", "))),
+        }
+    })
+    .collect();
+    format!(
+        "{} pieces: {}.",
+        side_name(color == Color::White),
+        groups.join("; ")
+    )
+}
+
+/// `e4: white pawn` or `e4: empty`.
+pub fn describe_square(board: &Board, square: Square) -> String {
+    match board.piece_on(square).zip(board.color_on(square)) {
+        Some((piece, color)) => format!(
+            "{}: {} {}.",
+            square,
+            if color == Color::White {
+                "white"
+            } else {
+                "black"
+            },
+            piece_word(piece)
+        ),
+        None => format!("{}: empty.", square),
+    }
+}
+
+pub const HELP: &str =
+    "Commands: white, black (list pieces); a square such as e4 (what is on it); \
+moves e2 (where the piece on e2 can go); last (last move); status; help. \
+Play a move by typing its squares, such as e2e4, or e7e8q to promote.";
+
+/// A move typed as two squares, `e2e4` or `e2 e4`, with an optional
+/// promotion letter.
+pub fn parse_typed_move(command: &str) -> Option<(Square, Square, Option<Piece>)> {
+    let compact: String = command
+        .trim()
+        .to_lowercase()
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .collect();
+    if !compact.is_ascii() || !(4..=5).contains(&compact.len()) {
+        return None;
+    }
+    let from = parse_square(&compact[0..2])?;
+    let to = parse_square(&compact[2..4])?;
+    let promotion = match compact.get(4..) {
+        Some("") | None => None,
+        Some(letter) => Some(piece_from_letter(letter).filter(|p| {
+            matches!(
+                p,
+                Piece::Knight | Piece::Bishop | Piece::Rook | Piece::Queen
+            )
+        })?),
+    };
+    Some((from, to, promotion))
+}
+
+/// Answer a command typed at the screen-reader prompt.
+pub fn run_command(session: &GameSession, command: &str) -> Vec<String> {
+    let command = command.trim().to_lowercase();
+    let board = session.board();
+    let words: Vec<&str> = command.split_whitespace().collect();
+
+    match words.as_slice() {
+        [] => Vec::new(),
+        ["white" | "w"] | ["list", "white"] => vec![list_pieces(board, Color::White)],
+        ["black" | "b"] | ["list", "black"] => vec![list_pieces(board, Color::Black)],
+        ["last" | "l"] => vec![match session.history().last() {
+            Some(record) => format!("Last move: {}.", describe_move(record)),
+            None => "No moves yet.".to_string(),
+        }],
+        ["status" | "s"] => vec![status_line(session)],
+        ["help" | "h" | "?"] => vec![HELP.to_string()],
+        ["moves" | "m", square] => match parse_square(square) {
+            Some(from) => vec![match session.legal_moves_from(from) {
+                Some(moves) if !moves.is_empty() => {
+                    // Promotions list the same target once per piece
+                    let mut targets: Vec<&str> = moves.iter().map(|m| m.to.as_str()).collect();
+                    targets.dedup();
+                    format!("{} can go to {}.", from, targets.join(", "))
+                }
+                _ => format!("No legal moves from {}.", from),
+            }],
+            None => vec![format!("{} is not a square.", square)],
+        },
+        [square] if parse_square(square).is_some() => {
+            vec![parse_square(square)
+                .map(|sq| describe_square(board, sq))
+                .unwrap_or_default()]
+        }
+        _ => vec![format!(
+            "Unknown command: {}. Type help for the list of commands.",
+            command
+        )],
+    }
+}
+
+/// Move number, whose turn it is, check, clocks.
+fn status_line(session: &GameSession) -> String {
+    let facts = GameFacts::from_session(session);
+    let mut parts = vec![format!("Move {}", facts.plies / 2 + 1), turn(&facts)];
+    if facts.in_check {
+        parts.push("in check".to_string());
+    }
+    if facts.paused {
+        parts.push("paused".to_string());
+    }
+    if let Some((white, black)) = facts.clocks {
+        parts.push(format!(
+            "white has {} seconds, black has {} seconds",
+            white / 1000,
+            black / 1000
+        ));
+    }
+    format!("{}.", parts.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(piece: &str, from: &str, to: &str, san: &str) -> MoveRecord {
+        MoveRecord {
+            piece: piece.into(),
+            from: from.into(),
+            to: to.into(),
+            san: san.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_describe_move() {
+        assert_eq!(
+            describe_move(&record("N", "g1", "f3", "Nf3")),
+            "knight g1 to f3"
+        );
+        let capture = MoveRecord {
+            captured: Some("p".into()),
+            ..record("P", "e5", "d6", "exd6")
+        };
+        assert_eq!(describe_move(&capture), "pawn e5 takes pawn on d6");
+        let promotion = MoveRecord {
+            promotion: Some("q".into()),
+            ..record("P", "e7", "e8", "e8=Q+")
+        };
+        assert_eq!(
+            describe_move(&promotion),
+            "pawn e7 to e8, promotes to queen"
+        );
+        assert_eq!(
+            describe_move(&record("K", "e1", "g1", "O-O")),
+            "castles kingside"
+        );
+    }
+
+    #[test]
+    fn test_narrate_moves_check_and_mate() {
+        let start = GameFacts {
+            white_to_move: true,
+            ..Default::default()
+        };
+        let after_two = GameFacts {
+            plies: 2,
+            white_to_move: true,
+            in_check: true,
+            ..Default::default()
+        };
+        let moves = [
+            record("P", "e2", "e4", "e4"),
+            record("Q", "d8", "h4", "Qh4+"),
+        ];
+        assert_eq!(
+            narrate(&start, &after_two, &moves),
+            ["White: pawn e2 to e4.", "Black: queen d8 to h4, check."]
+        );
+
+        let mate = GameFacts {
+            plies: 3,
+            status: 1,
+            white_to_move: false,
+            in_check: true,
+            ..Default::default()
+        };
+        let lines = narrate(&after_two, &mate, &[record("Q", "h5", "f7", "Qxf7#")]);
+        assert_eq!(lines, ["White: queen h5 to f7.", "Checkmate. White wins."]);
+    }
+
+    #[test]
+    fn test_narrate_clock_warnings_once_per_mark() {
+        let facts = |white, black| GameFacts {
+            clocks: Some((white, black)),
+            ..Default::default()
+        };
+        assert_eq!(
+            narrate(&facts(61_000, 90_000), &facts(59_000, 90_000), &[]),
+            ["White has 60 seconds left."]
+        );
+        assert!(narrate(&facts(59_000, 90_000), &facts(58_000, 90_000), &[]).is_empty());
+        // Skipping past two marks at once only announces the lower one
+        assert_eq!(
+            narrate(&facts(59_000, 35_000), &facts(59_000, 9_000), &[]),
+            ["Black has 10 seconds left."]
+        );
+    }
+
+    #[test]
+    fn test_narrate_takeback_and_pause() {
+        let prev = GameFacts {
+            plies: 4,
+            white_to_move: true,
+            ..Default::default()
+        };
+        let now = GameFacts {
+            plies: 2,
+            white_to_move: true,
+            paused: true,
+            awaiting_human: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            narrate(&prev, &now, &[]),
+            ["Move taken back. Your move.", "Game paused."]
+        );
+    }
+
+    #[test]
+    fn test_parse_typed_move() {
+        assert_eq!(
+            parse_typed_move("e2e4"),
+            Some((Square::E2, Square::E4, None))
+        );
+        assert_eq!(
+            parse_typed_move(" E7 e8 q"),
+            Some((Square::E7, Square::E8, Some(Piece::Queen)))
+        );
+        assert_eq!(parse_typed_move("e7e8k"), None);
+        assert_eq!(parse_typed_move("e4"), None);
+        assert_eq!(parse_typed_move("white"), None);
+    }
+
+    #[test]
+    fn test_list_pieces_and_squares() {
+        let board = Board::default();
+        assert_eq!(
+            list_pieces(&board, Color::Black),
+            "Black pieces: king e8; queen d8; rooks a8, h8; bishops c8, f8; knights b8, g8; \
+             pawns a7, b7, c7, d7, e7, f7, g7, h7."
+        );
+        assert_eq!(describe_square(&board, Square::E2), "e2: white pawn.");
+        assert_eq!(describe_square(&board, Square::E4), "e4: empty.");
+    }
+}
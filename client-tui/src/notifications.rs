@@ -0,0 +1,181 @@
+//! Toast notifications with a scrollable history.
+//!
+//! Every message is shown as a toast that expires after a delay depending on
+//! its severity, and is also kept in a bounded history so errors that
+//! flashed past can be read later from the notification overlay.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Most toasts stacked on screen at once; older ones are dropped early.
+pub const MAX_VISIBLE_TOASTS: usize = 4;
+
+/// Most notifications kept in the history overlay.
+pub const HISTORY_LIMIT: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    /// How long a toast of this severity stays on screen.
+    pub fn lifetime(self) -> Duration {
+        match self {
+            Severity::Info | Severity::Success => Duration::from_secs(3),
+            Severity::Warning => Duration::from_secs(6),
+            Severity::Error => Duration::from_secs(10),
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Success => "ok",
+            Severity::Warning => "warn",
+            Severity::Error => "error",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notification {
+    pub message: String,
+    pub severity: Severity,
+    pub created: Instant,
+}
+
+impl Notification {
+    fn expired(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.created) >= self.severity.lifetime()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Notifications {
+    /// Toasts on screen, oldest first.
+    toasts: VecDeque<Notification>,
+    /// Every notification, oldest first, capped at [`HISTORY_LIMIT`].
+    history: VecDeque<Notification>,
+}
+
+impl Notifications {
+    pub fn push(&mut self, severity: Severity, message: impl Into<String>) {
+        self.push_at(severity, message.into(), Instant::now());
+    }
+
+    pub fn info(&mut self, message: impl Into<String>) {
+        self.push(Severity::Info, message);
+    }
+
+    pub fn success(&mut self, message: impl Into<String>) {
+        self.push(Severity::Success, message);
+    }
+
+    pub fn warn(&mut self, message: impl Into<String>) {
+        self.push(Severity::Warning, message);
+    }
+
+    pub fn error(&mut self, message: impl Into<String>) {
+        self.push(Severity::Error, message);
+    }
+
+    fn push_at(&mut self, severity: Severity, message: String, now: Instant) {
+        let notification = Notification {
+            message,
+            severity,
+            created: now,
+        };
+
+        // A repeated message refreshes its toast instead of stacking a copy
+        self.toasts.retain(|t| t.message != notification.message);
+        self.toasts.push_back(notification.clone());
+        while self.toasts.len() > MAX_VISIBLE_TOASTS {
+            self.toasts.pop_front();
+        }
+
+        self.history.push_back(notification);
+        while self.history.len() > HISTORY_LIMIT {
+            self.history.pop_front();
+        }
+    }
+
+    /// Drop toasts that have been shown for their full lifetime.
+    pub fn expire(&mut self, now: Instant) {
+        self.toasts.retain(|t| !t.expired(now));
+    }
+
+    /// Hide all toasts; they stay in the history.
+    pub fn dismiss(&mut self) {
+        self.toasts.clear();
+    }
+
+    /// Toasts currently on screen, oldest first.
+    pub fn toasts(&self) -> impl DoubleEndedIterator<Item = &Notification> {
+        self.toasts.iter()
+    }
+
+    /// All kept notifications, oldest first.
+    pub fn history(&self) -> impl DoubleEndedIterator<Item = &Notification> + ExactSizeIterator {
+        self.history.iter()
+    }
+
+    /// The most recent notification, whether or not it is still on screen.
+    pub fn latest(&self) -> Option<&Notification> {
+        self.history.back()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toasts_expire_by_severity() {
+        let start = Instant::now();
+        let mut n = Notifications::default();
+        n.push_at(Severity::Info, "Paused".into(), start);
+        n.push_at(Severity::Error, "Move failed".into(), start);
+
+        n.expire(start + Duration::from_secs(4));
+        let left: Vec<_> = n.toasts().map(|t| t.message.as_str()).collect();
+        assert_eq!(left, ["Move failed"]);
+
+        n.expire(start + Duration::from_secs(11));
+        assert_eq!(n.toasts().count(), 0);
+        assert_eq!(n.history().len(), 2);
+        assert_eq!(n.latest().unwrap().message, "Move failed");
+    }
+
+    #[test]
+    fn test_stack_is_capped_and_deduplicated() {
+        let start = Instant::now();
+        let mut n = Notifications::default();
+        for i in 0..6 {
+            n.push_at(Severity::Info, format!("message {}", i), start);
+        }
+        n.push_at(Severity::Info, "message 3".into(), start);
+
+        let shown: Vec<_> = n.toasts().map(|t| t.message.as_str()).collect();
+        assert_eq!(shown, ["message 2", "message 4", "message 5", "message 3"]);
+        assert_eq!(n.history().len(), 7);
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        let start = Instant::now();
+        let mut n = Notifications::default();
+        for i in 0..HISTORY_LIMIT + 5 {
+            n.push_at(Severity::Warning, format!("warning {}", i), start);
+        }
+        assert_eq!(n.history().len(), HISTORY_LIMIT);
+        assert_eq!(n.history().next().unwrap().message, "warning 5");
+
+        n.dismiss();
+        assert_eq!(n.toasts().count(), 0);
+        assert_eq!(n.history().len(), HISTORY_LIMIT);
+    }
+}
@@ -0,0 +1,448 @@
+//! Read-only access to the server's game library while the server is down.
+//!
+//! The client opens the server's SQLite file directly, but only ever reads
+//! from it: the connection is opened read-only, so it can never race the
+//! server's writes, and each lookup runs in one transaction so a server that
+//! starts up mid-read cannot hand back half a review.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chess::GameMode;
+use chess_client::{
+    review_score, FinishedGameInfo, GameModeProto, GameReviewProto, MoveClassification,
+    OpeningProto, PositionReview, ReviewScore, ReviewStatusInfo, ReviewStatusType,
+};
+use sqlx::sqlite::SqliteRow;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+use sqlx::Row;
+
+#[derive(Debug, thiserror::Error)]
+pub enum OfflineError {
+    #[error("No game library at {0}")]
+    NotFound(PathBuf),
+
+    #[error("The game library is encrypted; start the server to unlock it")]
+    Encrypted,
+
+    #[error("Review not found: {0}")]
+    ReviewNotFound(String),
+
+    #[error("The review of {0} is not complete yet")]
+    ReviewIncomplete(String),
+
+    #[error("Failed to read the game library: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// Path of the server's database, resolved the same way the server does.
+///
+/// Priority:
+/// 1. CHESSTTY_DB_PATH env variable if set
+/// 2. The platform data directory (e.g. ~/.local/share/chesstty/chesstty.db)
+/// 3. ./data/chesstty.db as fallback
+pub fn default_db_path() -> PathBuf {
+    if let Ok(path) = std::env::var("CHESSTTY_DB_PATH") {
+        return PathBuf::from(path);
+    }
+
+    match dirs::data_dir() {
+        Some(dir) => dir.join("chesstty").join("chesstty.db"),
+        None => PathBuf::from("./data/chesstty.db"),
+    }
+}
+
+/// Whether the server is set up to encrypt its database. The client cannot
+/// unlock such a file on its own.
+fn encryption_configured() -> bool {
+    std::env::var_os("CHESSTTY_DB_KEY_FILE").is_some()
+        || std::env::var("CHESSTTY_DB_ENCRYPTION")
+            .is_ok_and(|mode| mode.eq_ignore_ascii_case("prompt"))
+}
+
+/// Finished games and their reviews, read straight from the database.
+pub struct OfflineLibrary {
+    pool: SqlitePool,
+}
+
+impl OfflineLibrary {
+    /// Open the library at the default database path.
+    pub async fn open_default() -> Result<Self, OfflineError> {
+        if encryption_configured() {
+            return Err(OfflineError::Encrypted);
+        }
+        Self::open(&default_db_path()).await
+    }
+
+    /// Open the database at `path` read-only.
+    pub async fn open(path: &Path) -> Result<Self, OfflineError> {
+        if !path.exists() {
+            return Err(OfflineError::NotFound(path.to_path_buf()));
+        }
+
+        let options = SqliteConnectOptions::new()
+            .filename(path)
+            .read_only(true)
+            // Wait out a server that is writing as it comes back up
+            .busy_timeout(Duration::from_secs(5));
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await?;
+
+        Ok(Self { pool })
+    }
+
+    /// Finished games, newest first, with the status of their reviews.
+    pub async fn list_finished_games(&self) -> Result<Vec<FinishedGameInfo>, OfflineError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT g.game_id, g.result, g.result_reason, g.game_mode, g.human_side,
+                   g.move_count, g.created_at, g.simul_id, g.opponent, r.status,
+                   r.opening_eco, r.opening_name, r.opening_ply
+            FROM finished_games g
+            LEFT JOIN game_reviews r ON r.game_id = g.game_id
+            ORDER BY g.created_at DESC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let game_mode: String = row.get("game_mode");
+                let human_side: Option<String> = row.get("human_side");
+                let move_count: i64 = row.get("move_count");
+                let created_at: i64 = row.get("created_at");
+                let review_status: Option<String> = row.get("status");
+                FinishedGameInfo {
+                    game_id: row.get("game_id"),
+                    result: row.get("result"),
+                    result_reason: row.get("result_reason"),
+                    game_mode: Some(decode_game_mode(&game_mode, human_side.as_deref())),
+                    move_count: move_count as u32,
+                    created_at: created_at as u64,
+                    review_status: review_status.map(|s| decode_status_type(&s) as i32),
+                    simul_id: row.get("simul_id"),
+                    opponent: row.get("opponent"),
+                    opening: decode_opening(&row),
+                    // Older libraries have no derived results
+                    derived_result: None,
+                    // Nor endgame types
+                    endgame: None,
+                }
+            })
+            .collect())
+    }
+
+    /// The completed review of `game_id`.
+    pub async fn get_game_review(&self, game_id: &str) -> Result<GameReviewProto, OfflineError> {
+        let mut tx = self.pool.begin().await?;
+
+        let header = sqlx::query(
+            r#"
+            SELECT status, white_accuracy, black_accuracy, total_plies, analyzed_plies,
+                   analysis_depth, started_at, completed_at, winner,
+                   opening_eco, opening_name, opening_ply
+            FROM game_reviews
+            WHERE game_id = ?
+            "#,
+        )
+        .bind(game_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| OfflineError::ReviewNotFound(game_id.to_string()))?;
+
+        let status: String = header.get("status");
+        if decode_status_type(&status) != ReviewStatusType::ReviewStatusComplete {
+            return Err(OfflineError::ReviewIncomplete(game_id.to_string()));
+        }
+
+        let rows = sqlx::query(
+            r#"
+            SELECT ply, fen, played_san, best_move_san, best_move_uci,
+                   eval_before_type, eval_before_value,
+                   eval_after_type, eval_after_value,
+                   eval_best_type, eval_best_value,
+                   classification, cp_loss, pv, depth, clock_ms
+            FROM position_reviews
+            WHERE game_id = ?
+            ORDER BY ply ASC
+            "#,
+        )
+        .bind(game_id)
+        .fetch_all(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        let positions = rows
+            .into_iter()
+            .map(|row| {
+                let ply: i64 = row.get("ply");
+                let classification: String = row.get("classification");
+                let cp_loss: i64 = row.get("cp_loss");
+                let pv: String = row.get("pv");
+                let depth: i64 = row.get("depth");
+                let clock_ms: Option<i64> = row.get("clock_ms");
+                PositionReview {
+                    ply: ply as u32,
+                    fen: row.get("fen"),
+                    played_san: row.get("played_san"),
+                    best_move_san: row.get("best_move_san"),
+                    best_move_uci: row.get("best_move_uci"),
+                    eval_before: Some(decode_score(
+                        row.get("eval_before_type"),
+                        row.get("eval_before_value"),
+                    )),
+                    eval_after: Some(decode_score(
+                        row.get("eval_after_type"),
+                        row.get("eval_after_value"),
+                    )),
+                    eval_best: Some(decode_score(
+                        row.get("eval_best_type"),
+                        row.get("eval_best_value"),
+                    )),
+                    classification: decode_classification(&classification) as i32,
+                    cp_loss: cp_loss as i32,
+                    pv: serde_json::from_str(&pv).unwrap_or_default(),
+                    depth: depth as u32,
+                    clock_ms: clock_ms.map(|v| v as u64),
+                    // Exclusions and only moves are not read from older
+                    // libraries
+                    excluded: None,
+                    only_move: false,
+                }
+            })
+            .collect();
+
+        let total_plies: i64 = header.get("total_plies");
+        let analyzed_plies: i64 = header.get("analyzed_plies");
+        let analysis_depth: i64 = header.get("analysis_depth");
+        let started_at: Option<i64> = header.get("started_at");
+        let completed_at: Option<i64> = header.get("completed_at");
+        Ok(GameReviewProto {
+            game_id: game_id.to_string(),
+            status: Some(ReviewStatusInfo {
+                status: ReviewStatusType::ReviewStatusComplete as i32,
+                current_ply: None,
+                total_plies: None,
+                error: None,
+                job_id: None,
+            }),
+            positions,
+            white_accuracy: header.get("white_accuracy"),
+            black_accuracy: header.get("black_accuracy"),
+            total_plies: total_plies as u32,
+            analyzed_plies: analyzed_plies as u32,
+            analysis_depth: analysis_depth as u32,
+            started_at: started_at.map(|v| v as u64),
+            completed_at: completed_at.map(|v| v as u64),
+            winner: header.get("winner"),
+            opening: decode_opening(&header),
+            // Computed by the server on request, not stored
+            handicap: None,
+            // Only the server groups the analysed plies into ranges; the
+            // library lists analysed plies only
+            coverage: Vec::new(),
+            // Older libraries have no endgame types
+            endgame: None,
+        })
+    }
+}
+
+fn decode_game_mode(mode: &str, human_side: Option<&str>) -> GameModeProto {
+    GameModeProto::from(GameMode::from_stored(mode, human_side))
+}
+
+/// The opening stored with a review, if it was classified.
+fn decode_opening(row: &SqliteRow) -> Option<OpeningProto> {
+    let eco: Option<String> = row.get("opening_eco");
+    let name: Option<String> = row.get("opening_name");
+    let ply: Option<i64> = row.get("opening_ply");
+    Some(OpeningProto {
+        eco: eco?,
+        name: name?,
+        ply: ply? as u32,
+    })
+}
+
+fn decode_status_type(status: &str) -> ReviewStatusType {
+    match status {
+        "Analyzing" => ReviewStatusType::ReviewStatusAnalyzing,
+        "Complete" => ReviewStatusType::ReviewStatusComplete,
+        "Failed" => ReviewStatusType::ReviewStatusFailed,
+        _ => ReviewStatusType::ReviewStatusQueued,
+    }
+}
+
+fn decode_score(type_str: &str, value: i64) -> ReviewScore {
+    let score = match type_str {
+        "mate" => review_score::Score::Mate(value as i32),
+        _ => review_score::Score::Centipawns(value as i32),
+    };
+    ReviewScore { score: Some(score) }
+}
+
+fn decode_classification(classification: &str) -> MoveClassification {
+    match classification {
+        "Brilliant" => MoveClassification::ClassificationBrilliant,
+        "Great" => MoveClassification::ClassificationGreat,
+        "Best" => MoveClassification::ClassificationBest,
+        "Excellent" => MoveClassification::ClassificationExcellent,
+        "Good" => MoveClassification::ClassificationGood,
+        "Inaccuracy" => MoveClassification::ClassificationInaccuracy,
+        "Mistake" => MoveClassification::ClassificationMistake,
+        "Blunder" => MoveClassification::ClassificationBlunder,
+        "Miss" => MoveClassification::ClassificationMiss,
+        "Forced" => MoveClassification::ClassificationForced,
+        _ => MoveClassification::ClassificationBook,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chess_client::{GameModeType, PlayerSideProto};
+
+    /// The columns of the server's schema that the library reads.
+    const SCHEMA: &str = r#"
+        CREATE TABLE finished_games (
+            game_id TEXT PRIMARY KEY NOT NULL, result TEXT NOT NULL,
+            result_reason TEXT NOT NULL, game_mode TEXT NOT NULL, human_side TEXT,
+            move_count INTEGER NOT NULL, created_at INTEGER NOT NULL,
+            simul_id TEXT, opponent TEXT
+        );
+        CREATE TABLE game_reviews (
+            game_id TEXT PRIMARY KEY NOT NULL, status TEXT NOT NULL,
+            white_accuracy REAL, black_accuracy REAL, total_plies INTEGER NOT NULL,
+            analyzed_plies INTEGER NOT NULL, analysis_depth INTEGER NOT NULL,
+            started_at INTEGER, completed_at INTEGER, winner TEXT,
+            opening_eco TEXT, opening_name TEXT, opening_ply INTEGER
+        );
+        CREATE TABLE position_reviews (
+            game_id TEXT NOT NULL, ply INTEGER NOT NULL, fen TEXT NOT NULL,
+            played_san TEXT NOT NULL, best_move_san TEXT NOT NULL,
+            best_move_uci TEXT NOT NULL,
+            eval_before_type TEXT NOT NULL, eval_before_value INTEGER NOT NULL,
+            eval_after_type TEXT NOT NULL, eval_after_value INTEGER NOT NULL,
+            eval_best_type TEXT NOT NULL, eval_best_value INTEGER NOT NULL,
+            classification TEXT NOT NULL, cp_loss INTEGER NOT NULL,
+            pv TEXT NOT NULL, depth INTEGER NOT NULL, clock_ms INTEGER
+        );
+        INSERT INTO finished_games VALUES
+            ('old', 'WhiteWins', 'Checkmate', 'HumanVsEngine', 'black', 40, 100, NULL, 'Magnus'),
+            ('new', 'Draw', 'Stalemate', 'HumanVsHuman', NULL, 60, 200, NULL, NULL);
+        INSERT INTO game_reviews VALUES
+            ('old', 'Complete', 91.5, 78.0, 2, 2, 18, 110, 120, 'White',
+             'C20', 'King''s Pawn Game', 2),
+            ('new', 'Queued', NULL, NULL, 60, 0, 18, NULL, NULL, NULL, NULL, NULL, NULL);
+        INSERT INTO position_reviews VALUES
+            ('old', 2, 'fen2', 'e5', 'e5', 'e7e5', 'cp', 30, 'cp', 25, 'cp', 25,
+             'Best', 0, '["e7e5"]', 18, NULL),
+            ('old', 1, 'fen1', 'e4', 'd4', 'd2d4', 'cp', 20, 'cp', 30, 'mate', 3,
+             'Good', 10, '["d2d4","d7d5"]', 18, 59000);
+    "#;
+
+    async fn library() -> (tempfile::TempDir, OfflineLibrary) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("chesstty.db");
+        let options = SqliteConnectOptions::new()
+            .filename(&path)
+            .create_if_missing(true);
+        let pool = SqlitePool::connect_with(options).await.unwrap();
+        sqlx::raw_sql(SCHEMA).execute(&pool).await.unwrap();
+        pool.close().await;
+
+        let library = OfflineLibrary::open(&path).await.unwrap();
+        (dir, library)
+    }
+
+    #[tokio::test]
+    async fn test_list_finished_games() {
+        let (_dir, library) = library().await;
+        let games = library.list_finished_games().await.unwrap();
+
+        assert_eq!(games.len(), 2);
+        assert_eq!(games[0].game_id, "new");
+        assert_eq!(
+            games[0].review_status,
+            Some(ReviewStatusType::ReviewStatusQueued as i32)
+        );
+        assert_eq!(games[0].opening, None);
+        let old = &games[1];
+        assert_eq!(old.opening.as_ref().map(|o| o.eco.as_str()), Some("C20"));
+        assert_eq!(old.opponent.as_deref(), Some("Magnus"));
+        assert_eq!(
+            old.game_mode,
+            Some(GameModeProto {
+                mode: GameModeType::HumanVsEngine as i32,
+                human_side: Some(PlayerSideProto::Black as i32),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_completed_review() {
+        let (_dir, library) = library().await;
+        let review = library.get_game_review("old").await.unwrap();
+
+        assert_eq!(review.white_accuracy, Some(91.5));
+        assert_eq!(review.winner.as_deref(), Some("White"));
+        assert_eq!(
+            review.opening,
+            Some(OpeningProto {
+                eco: "C20".into(),
+                name: "King's Pawn Game".into(),
+                ply: 2,
+            })
+        );
+        assert_eq!(
+            review.positions.iter().map(|p| p.ply).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        let first = &review.positions[0];
+        assert_eq!(first.pv, vec!["d2d4", "d7d5"]);
+        assert_eq!(first.clock_ms, Some(59_000));
+        assert_eq!(
+            first.eval_best,
+            Some(ReviewScore {
+                score: Some(review_score::Score::Mate(3))
+            })
+        );
+        assert_eq!(
+            first.classification,
+            MoveClassification::ClassificationGood as i32
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unfinished_or_missing_reviews_are_refused() {
+        let (_dir, library) = library().await;
+        assert!(matches!(
+            library.get_game_review("new").await,
+            Err(OfflineError::ReviewIncomplete(_))
+        ));
+        assert!(matches!(
+            library.get_game_review("gone").await,
+            Err(OfflineError::ReviewNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_library_is_read_only() {
+        let (_dir, library) = library().await;
+        let result = sqlx::query("DELETE FROM finished_games")
+            .execute(&library.pool)
+            .await;
+        assert!(result.is_err());
+        assert_eq!(library.list_finished_games().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_missing_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let result = OfflineLibrary::open(&dir.path().join("absent.db")).await;
+        assert!(matches!(result, Err(OfflineError::NotFound(_))));
+    }
+}
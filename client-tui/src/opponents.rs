@@ -0,0 +1,118 @@
+//! Named engine opponents, stored as JSON in the user's config directory.
+//!
+//! An opponent is a label for an engine configuration. Games started against
+//! one carry its name to the server, which keeps head-to-head stats per name.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, thiserror::Error)]
+pub enum OpponentError {
+    #[error("Malformed opponents file: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Failed to access opponents file: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Opponent {
+    pub name: String,
+    /// Stockfish skill level (0-20).
+    pub skill_level: u8,
+}
+
+/// The user's list of named opponents.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OpponentRoster {
+    #[serde(default)]
+    pub opponents: Vec<Opponent>,
+}
+
+impl Default for OpponentRoster {
+    /// Starter opponents written on first use, so there is something to edit.
+    fn default() -> Self {
+        let opponent = |name: &str, skill_level| Opponent {
+            name: name.to_string(),
+            skill_level,
+        };
+        Self {
+            opponents: vec![
+                opponent("Stocky 1500", 2),
+                opponent("Club Grinder", 8),
+                opponent("Aggro Bot", 12),
+            ],
+        }
+    }
+}
+
+/// Default location of the roster file.
+pub fn default_roster_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join("chesstty").join("opponents.json"))
+}
+
+impl OpponentRoster {
+    /// Load the roster from `path`, writing the starter roster if there is none yet.
+    pub fn load_or_init(path: &Path) -> Result<Self, OpponentError> {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => Ok(serde_json::from_str(&contents)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                let roster = Self::default();
+                roster.save(path)?;
+                Ok(roster)
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), OpponentError> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// The roster at the default path; falls back to the starter roster on error.
+    pub fn load_default() -> Self {
+        let Some(path) = default_roster_path() else {
+            return Self::default();
+        };
+        Self::load_or_init(&path).unwrap_or_else(|e| {
+            tracing::warn!("Failed to load opponents: {}", e);
+            Self::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_roster_is_initialized_on_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("opponents.json");
+
+        let roster = OpponentRoster::load_or_init(&path).unwrap();
+        assert_eq!(roster, OpponentRoster::default());
+        assert!(path.exists());
+    }
+
+    #[test]
+    fn edited_roster_is_loaded() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("opponents.json");
+        std::fs::write(
+            &path,
+            r#"{"opponents": [{"name": "Sparring Partner", "skill_level": 5}]}"#,
+        )
+        .unwrap();
+
+        let roster = OpponentRoster::load_or_init(&path).unwrap();
+        assert_eq!(roster.opponents.len(), 1);
+        assert_eq!(roster.opponents[0].name, "Sparring Partner");
+        assert_eq!(roster.opponents[0].skill_level, 5);
+    }
+}
@@ -1,5 +1,5 @@
 // Re-export state types
-pub use crate::state::{GameMode, PlayerColor, UciDirection, UciLogEntry};
+pub use crate::state::{GameMode, PlayerSide, UciDirection, UciLogEntry};
 
 // Re-export FSM types
 pub use crate::ui::fsm::{
@@ -0,0 +1,252 @@
+//! Premoves: moves queued while the engine is thinking, played one at a
+//! time as the turn comes back.
+//!
+//! The queue is planned on the board as it will be once the queued moves are
+//! played, with the engine's replies unknown. Each premove remembers what it
+//! found on its destination square and only fires if the same piece (or
+//! emptiness) is still there; otherwise, or if it is no longer legal, the
+//! whole queue is dropped, since the moves after it were planned on top of
+//! it.
+
+use chess::{convert_uci_castling_to_cozy, format_square};
+use cozy_chess::{Board, Color, File, Move, Piece, Rank, Square};
+use std::collections::VecDeque;
+
+/// Most premoves queued at once.
+pub const MAX_PREMOVES: usize = 5;
+
+/// A queued move and the occupant of its destination when it was queued.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Premove {
+    pub from: Square,
+    pub to: Square,
+    pub promotion: Option<Piece>,
+    /// Piece on `to` when queued, counting earlier premoves as played.
+    pub target: Option<(Color, Piece)>,
+}
+
+impl Premove {
+    /// The move in coordinates, e.g. `e7-e8=Q`.
+    pub fn describe(&self) -> String {
+        let promotion = self
+            .promotion
+            .map(|p| format!("={}", chess::format_piece_upper(p)))
+            .unwrap_or_default();
+        format!(
+            "{}-{}{}",
+            format_square(self.from),
+            format_square(self.to),
+            promotion
+        )
+    }
+}
+
+/// Premoves in the order they are played.
+#[derive(Debug, Default)]
+pub struct PremoveQueue {
+    moves: VecDeque<Premove>,
+    /// Hash of the position the last premove was played in, so a repeated
+    /// snapshot of it does not fire the next one too.
+    fired_in: Option<u64>,
+}
+
+/// Occupants of every square, by square index.
+type Occupants = [Option<(Color, Piece)>; Square::NUM];
+
+impl PremoveQueue {
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Premove> {
+        self.moves.iter()
+    }
+
+    pub fn clear(&mut self) {
+        self.moves.clear();
+        self.fired_in = None;
+    }
+
+    /// `board` with the queued moves played in order.
+    fn projected(&self, board: &Board) -> Occupants {
+        let mut squares: Occupants = [None; Square::NUM];
+        for &square in &Square::ALL {
+            if let (Some(color), Some(piece)) = (board.color_on(square), board.piece_on(square)) {
+                squares[square as usize] = Some((color, piece));
+            }
+        }
+        for mv in &self.moves {
+            let Some((color, piece)) = squares[mv.from as usize].take() else {
+                continue;
+            };
+            // Castling is queued as the king's two-square step
+            if piece == Piece::King && mv.from.file() == File::E {
+                let rook = match mv.to.file() {
+                    File::G => Some((File::H, File::F)),
+                    File::C => Some((File::A, File::D)),
+                    _ => None,
+                };
+                if let Some((rook_from, rook_to)) = rook {
+                    let rank = mv.from.rank();
+                    let rook = squares[Square::new(rook_from, rank) as usize].take();
+                    squares[Square::new(rook_to, rank) as usize] = rook;
+                }
+            }
+            squares[mv.to as usize] = Some((color, mv.promotion.unwrap_or(piece)));
+        }
+        squares
+    }
+
+    /// Occupant of `square` once the queued moves are played.
+    pub fn piece_at(&self, board: &Board, square: Square) -> Option<(Color, Piece)> {
+        self.projected(board)[square as usize]
+    }
+
+    /// Queue a move of `side` from `from` to `to`. A pawn reaching the last
+    /// rank promotes to a queen.
+    pub fn push(
+        &mut self,
+        board: &Board,
+        side: Color,
+        from: Square,
+        to: Square,
+    ) -> Result<Premove, String> {
+        if self.moves.len() >= MAX_PREMOVES {
+            return Err(format!("At most {} premoves can be queued", MAX_PREMOVES));
+        }
+        let squares = self.projected(board);
+        let Some((color, piece)) = squares[from as usize] else {
+            return Err(format!("No piece on {}", format_square(from)));
+        };
+        if color != side {
+            return Err("Premoves are for your own pieces".to_string());
+        }
+        let target = squares[to as usize];
+        if from == to || target.is_some_and(|(color, _)| color == side) {
+            return Err(format!("Cannot premove to {}", format_square(to)));
+        }
+        let last_rank = match side {
+            Color::White => Rank::Eighth,
+            Color::Black => Rank::First,
+        };
+        let premove = Premove {
+            from,
+            to,
+            promotion: (piece == Piece::Pawn && to.rank() == last_rank).then_some(Piece::Queen),
+            target,
+        };
+        self.moves.push_back(premove);
+        Ok(premove)
+    }
+
+    /// The next premove to play in `board`, with the player to move. It is
+    /// taken off the queue if its destination still holds what it did when
+    /// queued and it is legal; otherwise the queue is cleared and the reason
+    /// returned.
+    pub fn take_next(&mut self, board: &Board) -> Result<Option<Premove>, String> {
+        if self.fired_in == Some(board.hash()) {
+            return Ok(None);
+        }
+        let Some(next) = self.moves.pop_front() else {
+            return Ok(None);
+        };
+        let found = match (board.color_on(next.to), board.piece_on(next.to)) {
+            (Some(color), Some(piece)) => Some((color, piece)),
+            _ => None,
+        };
+        if found != next.target {
+            self.clear();
+            return Err(format!("{} changed", format_square(next.to)));
+        }
+        let mut legal = Vec::new();
+        board.generate_moves(|moves| {
+            legal.extend(moves);
+            false
+        });
+        let mv = convert_uci_castling_to_cozy(
+            Move {
+                from: next.from,
+                to: next.to,
+                promotion: next.promotion,
+            },
+            &legal,
+        );
+        if !legal.contains(&mv) {
+            self.clear();
+            return Err(format!("{} is not legal", next.describe()));
+        }
+        self.fired_in = Some(board.hash());
+        Ok(Some(next))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// After 1. e4 e5, White to move.
+    fn open_game() -> Board {
+        "rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2"
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_queue_is_planned_on_the_queued_moves() {
+        let board = open_game();
+        let mut queue = PremoveQueue::default();
+        queue
+            .push(&board, Color::White, Square::G1, Square::F3)
+            .unwrap();
+        // The knight is on f3 once the first premove is played
+        let second = queue
+            .push(&board, Color::White, Square::F3, Square::E5)
+            .unwrap();
+        assert_eq!(second.target, Some((Color::Black, Piece::Pawn)));
+        assert!(queue
+            .push(&board, Color::White, Square::G1, Square::F3)
+            .is_err());
+        assert!(queue
+            .push(&board, Color::White, Square::D7, Square::D5)
+            .is_err());
+    }
+
+    #[test]
+    fn test_premove_fires_once_per_position() {
+        let board = open_game();
+        let mut queue = PremoveQueue::default();
+        queue
+            .push(&board, Color::White, Square::G1, Square::F3)
+            .unwrap();
+        queue
+            .push(&board, Color::White, Square::F1, Square::C4)
+            .unwrap();
+
+        let first = queue.take_next(&board).unwrap().unwrap();
+        assert_eq!((first.from, first.to), (Square::G1, Square::F3));
+        assert_eq!(queue.take_next(&board), Ok(None));
+        assert!(!queue.is_empty());
+    }
+
+    #[test]
+    fn test_changed_destination_clears_the_queue() {
+        let mut board = open_game();
+        let mut queue = PremoveQueue::default();
+        queue
+            .push(&board, Color::White, Square::D2, Square::D4)
+            .unwrap();
+        queue
+            .push(&board, Color::White, Square::D4, Square::E5)
+            .unwrap();
+        // White plays Nf3 itself, Black answers ...d5
+        board.play("g1f3".parse().unwrap());
+        board.play("d7d5".parse().unwrap());
+
+        assert!(queue.take_next(&board).unwrap().is_some());
+        board.play("d2d4".parse().unwrap());
+        // ...exd4 takes the pawn the second premove was to move
+        board.play("e5d4".parse().unwrap());
+        assert!(queue.take_next(&board).is_err());
+        assert!(queue.is_empty());
+    }
+}
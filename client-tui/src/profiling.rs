@@ -0,0 +1,118 @@
+//! Timings behind the debug profiling overlay.
+//!
+//! The render loop records how long each frame took to draw and how long the
+//! loop spent between waking up and finishing the frame; the dispatcher's
+//! completions carry how long each server call took. Only the most recent
+//! samples are kept, so the overlay reflects the last few seconds.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::time::Duration;
+
+/// Samples kept per measurement, about four seconds of frames at 30fps.
+pub const WINDOW: usize = 120;
+
+/// Time available per frame at the render loop's ~30fps tick.
+pub const FRAME_BUDGET: Duration = Duration::from_millis(33);
+
+/// Rolling window of durations for one measurement.
+#[derive(Debug, Clone, Default)]
+pub struct Timings {
+    samples: VecDeque<Duration>,
+}
+
+impl Timings {
+    pub fn record(&mut self, sample: Duration) {
+        self.samples.push_back(sample);
+        while self.samples.len() > WINDOW {
+            self.samples.pop_front();
+        }
+    }
+
+    pub fn last(&self) -> Option<Duration> {
+        self.samples.back().copied()
+    }
+
+    pub fn average(&self) -> Option<Duration> {
+        let count = u32::try_from(self.samples.len()).ok().filter(|&n| n > 0)?;
+        Some(self.samples.iter().sum::<Duration>() / count)
+    }
+
+    pub fn max(&self) -> Option<Duration> {
+        self.samples.iter().max().copied()
+    }
+
+    /// Samples in the window that took longer than `budget`.
+    pub fn over(&self, budget: Duration) -> usize {
+        self.samples.iter().filter(|&&s| s > budget).count()
+    }
+}
+
+/// Per-frame measurements taken by the render loop.
+#[derive(Debug, Clone, Default)]
+pub struct FrameProfile {
+    /// Time spent in `terminal.draw`.
+    pub draw: Timings,
+    /// Time from the loop waking up to the frame being drawn, including
+    /// applying server events and finished calls.
+    pub loop_latency: Timings,
+    /// Terminal size at the last frame, in cells.
+    pub area: (u16, u16),
+}
+
+/// Duration of finished server calls, by action.
+#[derive(Debug, Clone, Default)]
+pub struct RpcTimings {
+    by_action: BTreeMap<&'static str, Timings>,
+}
+
+impl RpcTimings {
+    pub fn record(&mut self, action: &'static str, elapsed: Duration) {
+        self.by_action.entry(action).or_default().record(elapsed);
+    }
+
+    /// Timings of every action seen so far, by name.
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, &Timings)> {
+        self.by_action.iter().map(|(name, t)| (*name, t))
+    }
+}
+
+/// `0.4ms`, `12ms`, `1.20s`.
+pub fn format_duration(d: Duration) -> String {
+    let ms = d.as_secs_f64() * 1000.0;
+    if ms < 10.0 {
+        format!("{:.1}ms", ms)
+    } else if ms < 1000.0 {
+        format!("{:.0}ms", ms)
+    } else {
+        format!("{:.2}s", ms / 1000.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_keeps_recent_samples() {
+        let mut t = Timings::default();
+        assert_eq!(t.average(), None);
+        for ms in 0..WINDOW as u64 + 10 {
+            t.record(Duration::from_millis(ms));
+        }
+        assert_eq!(t.last(), Some(Duration::from_millis(WINDOW as u64 + 9)));
+        assert_eq!(t.max(), t.last());
+        // The oldest ten samples were dropped
+        assert_eq!(
+            t.average(),
+            Some(Duration::from_micros(((10 + WINDOW as u64 + 9) * 1000) / 2))
+        );
+        assert_eq!(t.over(FRAME_BUDGET), WINDOW + 10 - 34);
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_micros(400)), "0.4ms");
+        assert_eq!(format_duration(Duration::from_millis(12)), "12ms");
+        assert_eq!(format_duration(Duration::from_millis(1200)), "1.20s");
+    }
+}
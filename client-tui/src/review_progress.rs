@@ -0,0 +1,161 @@
+//! Live progress of reviews queued from the menu.
+//!
+//! Queueing a review from the game table starts a task that follows the
+//! server's progress stream for that game. The menu reads the latest update
+//! every frame, so the table shows a bar and the time left as the workers
+//! go, without polling.
+
+use chess_client::{ChessClient, ReviewProgressUpdate, ReviewStatusType};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Cells of the progress bar in the review table.
+const BAR_WIDTH: u32 = 6;
+
+/// Latest update of each followed review, by game id. Clones share it.
+#[derive(Debug, Clone, Default)]
+pub struct ReviewProgressTracker {
+    latest: Arc<Mutex<HashMap<String, ReviewProgressUpdate>>>,
+}
+
+impl ReviewProgressTracker {
+    /// Follow a game's review in the background until it completes or fails.
+    pub fn follow(&self, mut client: ChessClient, game_id: String) {
+        let tracker = self.clone();
+        tokio::spawn(async move {
+            let mut updates = match client.stream_review_progress(&game_id).await {
+                Ok(updates) => updates,
+                Err(e) => {
+                    tracing::warn!(game_id = %game_id, "Failed to follow review: {}", e);
+                    return;
+                }
+            };
+            loop {
+                match updates.message().await {
+                    Ok(Some(update)) => tracker.record(update),
+                    Ok(None) => break,
+                    Err(e) => {
+                        tracing::warn!(game_id = %game_id, "Review progress stream failed: {}", e);
+                        break;
+                    }
+                }
+            }
+        });
+    }
+
+    /// Keep an update as the latest for its game.
+    pub fn record(&self, update: ReviewProgressUpdate) {
+        self.latest
+            .lock()
+            .unwrap()
+            .insert(update.game_id.clone(), update);
+    }
+
+    /// The latest update for a game, if its review is followed.
+    pub fn get(&self, game_id: &str) -> Option<ReviewProgressUpdate> {
+        self.latest.lock().unwrap().get(game_id).cloned()
+    }
+}
+
+/// The status an update reports.
+pub fn status_of(update: &ReviewProgressUpdate) -> Option<ReviewStatusType> {
+    update
+        .status
+        .as_ref()
+        .and_then(|s| ReviewStatusType::try_from(s.status).ok())
+}
+
+/// Bar and time left of a review being analysed, e.g. `███░░░ 1m05s`; the
+/// analysed share of the game stands in until the pace is known.
+pub fn progress_label(update: &ReviewProgressUpdate) -> Option<String> {
+    let status = update.status.as_ref()?;
+    if status_of(update) != Some(ReviewStatusType::ReviewStatusAnalyzing) {
+        return None;
+    }
+    let (current, total) = (status.current_ply?, status.total_plies?);
+    if total == 0 {
+        return None;
+    }
+    let filled = (current.min(total) * BAR_WIDTH / total) as usize;
+    let bar = format!(
+        "{}{}",
+        "█".repeat(filled),
+        "░".repeat(BAR_WIDTH as usize - filled)
+    );
+    let tail = match update.eta_ms {
+        Some(eta_ms) => format_eta(eta_ms),
+        None => format!("{}%", current.min(total) * 100 / total),
+    };
+    Some(format!("{} {}", bar, tail))
+}
+
+/// `45s`, `2m05s`.
+fn format_eta(ms: u64) -> String {
+    let secs = ms.div_ceil(1000);
+    if secs < 60 {
+        format!("{}s", secs)
+    } else {
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chess_client::ReviewStatusInfo;
+
+    fn update(
+        status: ReviewStatusType,
+        plies: Option<(u32, u32)>,
+        eta_ms: Option<u64>,
+    ) -> ReviewProgressUpdate {
+        ReviewProgressUpdate {
+            game_id: "game_1".to_string(),
+            status: Some(ReviewStatusInfo {
+                status: status as i32,
+                current_ply: plies.map(|(current, _)| current),
+                total_plies: plies.map(|(_, total)| total),
+                error: None,
+                job_id: None,
+            }),
+            eta_ms,
+        }
+    }
+
+    #[test]
+    fn test_progress_label_shows_bar_and_time_left() {
+        let analysing = update(
+            ReviewStatusType::ReviewStatusAnalyzing,
+            Some((20, 40)),
+            Some(65_000),
+        );
+        assert_eq!(progress_label(&analysing).unwrap(), "███░░░ 1m05s");
+
+        // Before the pace is known
+        let started = update(
+            ReviewStatusType::ReviewStatusAnalyzing,
+            Some((10, 40)),
+            None,
+        );
+        assert_eq!(progress_label(&started).unwrap(), "█░░░░░ 25%");
+    }
+
+    #[test]
+    fn test_progress_label_only_while_analysing() {
+        let queued = update(ReviewStatusType::ReviewStatusQueued, None, None);
+        assert_eq!(progress_label(&queued), None);
+        let complete = update(ReviewStatusType::ReviewStatusComplete, None, None);
+        assert_eq!(progress_label(&complete), None);
+        assert_eq!(
+            status_of(&complete),
+            Some(ReviewStatusType::ReviewStatusComplete)
+        );
+    }
+
+    #[test]
+    fn test_format_eta_rounds_up() {
+        assert_eq!(format_eta(400), "1s");
+        assert_eq!(format_eta(45_000), "45s");
+        assert_eq!(format_eta(125_000), "2m05s");
+    }
+}
@@ -1,8 +1,26 @@
+use analysis::{Phase, PhaseBoundaries};
+use chess::{Notation, NotationStyle};
 use chess_client::{
-    AdvancedGameAnalysisProto, GameModeProto, GameReviewProto, MoveClassification, MoveRecord,
-    PositionReview,
+    AdvancedGameAnalysisProto, ClockSimulationSide, CompareReviewsResponse,
+    ExplainPositionResponse, GameModeProto, GameReviewProto, MobilityMetricsProto,
+    MoveClassification, MoveRecord, PositionReview, ReviewBookmark, ReviewRevisionInfo,
+    SimulateClockResponse,
 };
 use cozy_chess::{Board, Square};
+use std::time::Duration;
+
+/// Auto-play speeds, as multiples of [`AUTO_PLAY_BASE`].
+pub const AUTO_PLAY_SPEEDS: [f32; 6] = [0.5, 1.0, 1.5, 2.0, 3.0, 4.0];
+
+/// Time per move at 1x.
+const AUTO_PLAY_BASE: Duration = Duration::from_millis(750);
+
+/// Index of 1x in [`AUTO_PLAY_SPEEDS`].
+const DEFAULT_SPEED: usize = 1;
+
+/// Time controls the clock simulation cycles through, as minutes and
+/// increment seconds.
+pub const CLOCK_PRESETS: [(u64, u64); 5] = [(1, 0), (3, 0), (3, 2), (5, 0), (10, 0)];
 
 /// Local review navigation state. All data is fetched once from the server
 /// and then navigated entirely client-side (no further server calls).
@@ -18,6 +36,10 @@ pub struct ReviewState {
     pub fen_at_ply: String,
     /// Whether auto-play is active.
     pub auto_play: bool,
+    /// Index into [`AUTO_PLAY_SPEEDS`].
+    pub auto_play_speed: usize,
+    /// Stop auto-play on blunders and mistakes.
+    pub pause_on_critical: bool,
     /// Move history reconstructed from review positions for the MoveHistoryPanel.
     pub move_history: Vec<MoveRecord>,
     /// Original game mode (for creating snapshots that preserve engine settings).
@@ -26,6 +48,18 @@ pub struct ReviewState {
     pub skill_level: u8,
     /// Advanced analysis data (tactical patterns, king safety, tension, psychological profiles).
     pub advanced: Option<AdvancedGameAnalysisProto>,
+    /// Index into [`CLOCK_PRESETS`] of the simulated time control, if any.
+    pub clock_preset: Option<usize>,
+    /// Both clocks under the simulated time control, once the server ran it.
+    pub clock_simulation: Option<SimulateClockResponse>,
+    /// The game's earlier review pass compared with this one, toggled with `d`.
+    pub comparison: Option<CompareReviewsResponse>,
+    /// Why the best move is best at the ply it was asked for, with `w`.
+    pub explanation: Option<ExplainPositionResponse>,
+    /// Where the game moves into the middlegame and the endgame.
+    pub phases: PhaseBoundaries,
+    /// Plies the player bookmarked, in ply order, once fetched.
+    pub bookmarks: Vec<ReviewBookmark>,
 }
 
 impl ReviewState {
@@ -79,6 +113,38 @@ impl ReviewState {
         self.go_to_ply(self.review.total_plies);
     }
 
+    /// Time between auto-play moves at the current speed.
+    pub fn auto_play_interval(&self) -> Duration {
+        AUTO_PLAY_BASE.div_f32(AUTO_PLAY_SPEEDS[self.auto_play_speed])
+    }
+
+    /// The current speed, e.g. `1.5x`.
+    pub fn speed_label(&self) -> String {
+        format!("{}x", AUTO_PLAY_SPEEDS[self.auto_play_speed])
+    }
+
+    /// Step to the next faster or slower speed, stopping at either end.
+    pub fn change_speed(&mut self, faster: bool) {
+        self.auto_play_speed = if faster {
+            (self.auto_play_speed + 1).min(AUTO_PLAY_SPEEDS.len() - 1)
+        } else {
+            self.auto_play_speed.saturating_sub(1)
+        };
+    }
+
+    /// One auto-play step: stops at the end of the game and, when
+    /// `pause_on_critical` is set, right after a blunder or mistake.
+    pub fn auto_advance(&mut self) {
+        if self.current_ply >= self.review.total_plies {
+            self.auto_play = false;
+            return;
+        }
+        self.next_ply();
+        if self.pause_on_critical && self.critical_moments().contains(&self.current_ply) {
+            self.auto_play = false;
+        }
+    }
+
     /// Get the side to move at the current review position.
     pub fn side_to_move(&self) -> &str {
         match self.board_at_ply.side_to_move() {
@@ -114,23 +180,194 @@ impl ReviewState {
                 clock_ms: pos.clock_ms,
             })
             .collect();
+        let phases =
+            PhaseBoundaries::detect(review.positions.iter().map(|p| (p.ply, p.fen.as_str())));
 
         Self {
             current_ply: 0,
             board_at_ply: Board::default(),
             fen_at_ply: Board::default().to_string(),
             auto_play: false,
+            auto_play_speed: DEFAULT_SPEED,
+            pause_on_critical: false,
             move_history,
             review,
             game_mode,
             skill_level,
             advanced,
+            clock_preset: None,
+            clock_simulation: None,
+            comparison: None,
+            explanation: None,
+            phases,
+            bookmarks: Vec::new(),
+        }
+    }
+
+    /// Take a review patched on the server, e.g. after re-analysing a move,
+    /// staying on the current ply. Comparisons and explanations were of the
+    /// old judgements, so they are dropped.
+    pub fn replace_review(
+        &mut self,
+        review: GameReviewProto,
+        advanced: Option<AdvancedGameAnalysisProto>,
+    ) {
+        let advanced = advanced.or_else(|| self.advanced.take());
+        let mut patched =
+            Self::with_metadata(review, self.game_mode.clone(), self.skill_level, advanced);
+        patched.auto_play_speed = self.auto_play_speed;
+        patched.pause_on_critical = self.pause_on_critical;
+        patched.clock_preset = self.clock_preset;
+        patched.clock_simulation = self.clock_simulation.take();
+        patched.bookmarks = std::mem::take(&mut self.bookmarks);
+        patched.go_to_ply(self.current_ply);
+        *self = patched;
+    }
+
+    /// Move on to the next simulated time control, turning the simulation
+    /// off after the last one. Returns the new control as starting time and
+    /// increment in milliseconds.
+    pub fn cycle_clock_preset(&mut self) -> Option<(u64, u64)> {
+        self.clock_simulation = None;
+        self.clock_preset = match self.clock_preset {
+            None => Some(0),
+            Some(i) if i + 1 < CLOCK_PRESETS.len() => Some(i + 1),
+            Some(_) => None,
+        };
+        self.clock_control()
+    }
+
+    /// The simulated time control as starting time and increment in
+    /// milliseconds.
+    pub fn clock_control(&self) -> Option<(u64, u64)> {
+        let (minutes, increment) = CLOCK_PRESETS[self.clock_preset?];
+        Some((minutes * 60_000, increment * 1_000))
+    }
+
+    /// The simulated time control as it is usually written, e.g. `3+2`.
+    pub fn clock_label(&self) -> Option<String> {
+        let (minutes, increment) = CLOCK_PRESETS[self.clock_preset?];
+        Some(format!("{}+{}", minutes, increment))
+    }
+
+    /// One line on who would have lost on time under the simulated control.
+    pub fn clock_verdict(&self) -> Option<String> {
+        let label = self.clock_label()?;
+        let simulation = self.clock_simulation.as_ref()?;
+        let flag = |side: &Option<ClockSimulationSide>| side.as_ref()?.flagged_at_ply;
+        // Only the first flag counts: the game ends there
+        let verdict = match (flag(&simulation.white), flag(&simulation.black)) {
+            (Some(w), Some(b)) if b < w => format!("Black flags on move {}", b.div_ceil(2)),
+            (Some(w), _) => format!("White flags on move {}", w.div_ceil(2)),
+            (None, Some(b)) => format!("Black flags on move {}", b.div_ceil(2)),
+            (None, None) => "nobody flags".to_string(),
+        };
+        Some(format!("At {} {}", label, verdict))
+    }
+
+    /// The earlier pass the comparison is against.
+    pub fn compared_revision(&self) -> Option<&ReviewRevisionInfo> {
+        let comparison = self.comparison.as_ref()?;
+        let revision = comparison.compared_revision?;
+        comparison.revisions.iter().find(|r| r.revision == revision)
+    }
+
+    /// One line on how far the earlier pass agrees with this review.
+    pub fn comparison_summary(&self) -> Option<String> {
+        let comparison = self.comparison.as_ref()?;
+        let revision = self.compared_revision()?;
+        let agreeing = comparison
+            .compared_plies
+            .saturating_sub(comparison.changes.len() as u32);
+        Some(format!(
+            "Pass {} (depth {}) agrees on {} of {} moves",
+            revision.revision, revision.analysis_depth, agreeing, comparison.compared_plies
+        ))
+    }
+
+    /// The explanation of the current ply's best move, if one was fetched
+    /// for it.
+    pub fn current_explanation(&self) -> Option<&ExplainPositionResponse> {
+        self.explanation
+            .as_ref()
+            .filter(|e| e.ply == self.current_ply)
+    }
+
+    /// Phase of the game at the current ply.
+    pub fn current_phase(&self) -> Phase {
+        self.phases.phase_of(self.current_ply)
+    }
+
+    /// Jump to the start of the next phase, or back to the start of the
+    /// current one (then the one before). Returns the phase landed in.
+    pub fn jump_to_phase(&mut self, forward: bool) -> Option<Phase> {
+        let current = self.current_ply;
+        let starts = self.phases.starts();
+        let &(phase, ply) = if forward {
+            starts.iter().find(|(_, ply)| *ply > current)
+        } else {
+            starts.iter().rev().find(|(_, ply)| *ply < current)
+        }?;
+        self.go_to_ply(ply);
+        Some(phase)
+    }
+
+    /// The bookmark on the current ply, if any.
+    pub fn current_bookmark(&self) -> Option<&ReviewBookmark> {
+        self.bookmarks.iter().find(|b| b.ply == self.current_ply)
+    }
+
+    /// Jump to the next or previous bookmarked ply. Returns the ply landed on.
+    pub fn jump_to_bookmark(&mut self, forward: bool) -> Option<u32> {
+        let current = self.current_ply;
+        let mut plies = self.bookmarks.iter().map(|b| b.ply);
+        let ply = if forward {
+            plies.find(|&ply| ply > current)
+        } else {
+            plies.rev().find(|&ply| ply < current)
+        }?;
+        self.go_to_ply(ply);
+        Some(ply)
+    }
+
+    /// FEN of the position after `ply`; the standard starting position at
+    /// ply 0.
+    pub fn fen_after(&self, ply: u32) -> Option<String> {
+        if ply == 0 {
+            return Some(Board::default().to_string());
         }
+        self.review
+            .positions
+            .iter()
+            .find(|p| p.ply == ply)
+            .map(|p| p.fen.clone())
     }
 
     /// Get the PositionReview for the current ply (None at ply 0).
+    /// Board the move at `ply` was played in. Reviews start from the
+    /// standard position.
+    pub fn board_before(&self, ply: u32) -> Option<Board> {
+        if ply <= 1 {
+            return Some(Board::default());
+        }
+        self.review
+            .positions
+            .iter()
+            .find(|p| p.ply == ply - 1)
+            .and_then(|p| p.fen.parse().ok())
+    }
+
+    /// A move played, or suggested, at `ply`, written in `notation`.
+    pub fn show_san(&self, notation: &Notation, ply: u32, san: &str) -> String {
+        let before = match notation.style {
+            NotationStyle::Long => self.board_before(ply),
+            _ => None,
+        };
+        notation.format(san, before.as_ref())
+    }
+
     pub fn current_position(&self) -> Option<&PositionReview> {
-        if self.current_ply == 0 {
+        if self.current_ply == 0 || !self.is_analyzed(self.current_ply) {
             return None;
         }
         self.review
@@ -139,6 +376,45 @@ impl ReviewState {
             .find(|p| p.ply == self.current_ply)
     }
 
+    /// Whether the review analysed `ply`. Reviews without coverage, from
+    /// older servers or the offline library, list only analysed plies.
+    pub fn is_analyzed(&self, ply: u32) -> bool {
+        let coverage = &self.review.coverage;
+        coverage.is_empty()
+            || coverage
+                .iter()
+                .any(|r| (r.from_ply..=r.to_ply).contains(&ply))
+    }
+
+    /// The analysed positions, for totals and graphs over the game.
+    pub fn analyzed_positions(&self) -> Vec<PositionReview> {
+        self.review
+            .positions
+            .iter()
+            .filter(|p| self.is_analyzed(p.ply))
+            .cloned()
+            .collect()
+    }
+
+    /// The analysed plies when the review covers only part of the game,
+    /// e.g. `41-80 (depth 24)`.
+    pub fn coverage_label(&self) -> Option<String> {
+        let coverage = &self.review.coverage;
+        let whole = match coverage.as_slice() {
+            [] => true,
+            [only] => only.from_ply <= 1 && only.to_ply >= self.review.total_plies,
+            _ => false,
+        };
+        if whole {
+            return None;
+        }
+        let ranges: Vec<String> = coverage
+            .iter()
+            .map(|r| format!("{}-{} (depth {})", r.from_ply, r.to_ply, r.depth))
+            .collect();
+        Some(ranges.join(", "))
+    }
+
     /// Get the AdvancedPositionAnalysis for the current ply (None if no advanced data or at ply 0).
     pub fn advanced_position(&self) -> Option<&chess_client::AdvancedPositionAnalysisProto> {
         self.advanced
@@ -213,7 +489,7 @@ impl ReviewState {
         Some((from, to))
     }
 
-    /// Get plies of critical moments (blunders and mistakes) sorted by ply.
+    /// Get plies of critical moments (blunders, misses and mistakes) sorted by ply.
     pub fn critical_moments(&self) -> Vec<u32> {
         self.review
             .positions
@@ -223,12 +499,62 @@ impl ReviewState {
                 matches!(
                     class,
                     Ok(MoveClassification::ClassificationBlunder)
+                        | Ok(MoveClassification::ClassificationMiss)
                         | Ok(MoveClassification::ClassificationMistake)
                 )
             })
             .map(|p| p.ply)
             .collect()
     }
+
+    /// Average mobility and space of each side in every phase the advanced
+    /// analysis has positions for, in phase order.
+    pub fn mobility_by_phase(&self) -> Vec<PhaseMobility> {
+        let Some(advanced) = &self.advanced else {
+            return vec![];
+        };
+        let mut trend = Vec::new();
+        for phase in [Phase::Opening, Phase::Middlegame, Phase::Endgame] {
+            let sides: Vec<_> = advanced
+                .positions
+                .iter()
+                .filter(|p| self.phases.phase_of(p.ply) == phase)
+                .filter_map(|p| p.mobility.as_ref())
+                .map(|m| (side_mobility(&m.white), side_mobility(&m.black)))
+                .collect();
+            if sides.is_empty() {
+                continue;
+            }
+            trend.push(PhaseMobility {
+                phase,
+                white_mobility: average(sides.iter().map(|(w, _)| w.0)),
+                black_mobility: average(sides.iter().map(|(_, b)| b.0)),
+                white_space: average(sides.iter().map(|(w, _)| w.1)),
+                black_space: average(sides.iter().map(|(_, b)| b.1)),
+            });
+        }
+        trend
+    }
+}
+
+/// Average mobility and space of both sides over one phase of the game.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PhaseMobility {
+    pub phase: Phase,
+    pub white_mobility: f32,
+    pub black_mobility: f32,
+    pub white_space: f32,
+    pub black_space: f32,
+}
+
+/// A side's mobility and space, zero when the analysis has none.
+fn side_mobility(metrics: &Option<MobilityMetricsProto>) -> (u32, u32) {
+    metrics.as_ref().map_or((0, 0), |m| (m.mobility, m.space))
+}
+
+fn average(values: impl ExactSizeIterator<Item = u32>) -> f32 {
+    let count = values.len() as f32;
+    values.sum::<u32>() as f32 / count
 }
 
 /// Parse a UCI square string like "e2" into a cozy_chess Square.
@@ -251,6 +577,7 @@ fn parse_uci_square(s: &str) -> Option<Square> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chess_client::CoveredRangeProto;
 
     /// Build a minimal `GameReviewProto` with positions for the first few moves
     /// of a game (1. e4 e5 2. Nf3).
@@ -452,4 +779,247 @@ mod tests {
         rs.go_to_ply(0);
         assert_eq!(rs.fen_at_ply, Board::default().to_string());
     }
+
+    #[test]
+    fn test_auto_play_speed_and_critical_pause() {
+        let mut review = sample_review();
+        review.positions[1].classification = MoveClassification::ClassificationMistake as i32;
+        let mut rs = new_review_state(review);
+        assert_eq!(rs.auto_play_interval(), Duration::from_millis(750));
+
+        rs.change_speed(true);
+        assert_eq!(rs.speed_label(), "1.5x");
+        assert_eq!(rs.auto_play_interval(), Duration::from_millis(500));
+        for _ in 0..AUTO_PLAY_SPEEDS.len() {
+            rs.change_speed(false);
+        }
+        assert_eq!(rs.speed_label(), "0.5x");
+
+        rs.auto_play = true;
+        rs.pause_on_critical = true;
+        rs.auto_advance();
+        assert!(rs.auto_play);
+        rs.auto_advance();
+        assert_eq!(rs.current_ply, 2);
+        assert!(!rs.auto_play);
+
+        // Without the pause it runs on to the end, then stops
+        rs.auto_play = true;
+        rs.pause_on_critical = false;
+        rs.auto_advance();
+        rs.auto_advance();
+        assert_eq!(rs.current_ply, 3);
+        assert!(!rs.auto_play);
+    }
+
+    #[test]
+    fn test_show_san_uses_position_before_the_ply() {
+        use chess::PieceLanguage;
+
+        let rs = new_review_state(sample_review());
+        let long = Notation {
+            style: NotationStyle::Long,
+            language: PieceLanguage::German,
+        };
+        assert_eq!(rs.show_san(&long, 1, "e4"), "e2-e4");
+        assert_eq!(rs.show_san(&long, 3, "Nf3"), "Sg1-f3");
+        // Unknown plies keep the short form
+        assert_eq!(rs.show_san(&long, 9, "Nf3"), "Sf3");
+    }
+
+    #[test]
+    fn test_clock_presets_cycle_back_to_off() {
+        let mut rs = new_review_state(sample_review());
+        assert_eq!(rs.cycle_clock_preset(), Some((60_000, 0)));
+        rs.clock_simulation = Some(SimulateClockResponse {
+            white: Some(ClockSimulationSide {
+                flagged_at_ply: Some(41),
+                ..Default::default()
+            }),
+            black: Some(ClockSimulationSide {
+                flagged_at_ply: Some(38),
+                ..Default::default()
+            }),
+        });
+        assert_eq!(
+            rs.clock_verdict().as_deref(),
+            Some("At 1+0 Black flags on move 19")
+        );
+
+        assert_eq!(rs.cycle_clock_preset(), Some((180_000, 0)));
+        assert!(rs.clock_simulation.is_none());
+        for _ in 1..CLOCK_PRESETS.len() {
+            rs.cycle_clock_preset();
+        }
+        assert_eq!(rs.clock_preset, None);
+        assert_eq!(rs.clock_verdict(), None);
+    }
+
+    #[test]
+    fn test_comparison_summary() {
+        let mut rs = new_review_state(sample_review());
+        assert_eq!(rs.comparison_summary(), None);
+        rs.comparison = Some(CompareReviewsResponse {
+            revisions: vec![ReviewRevisionInfo {
+                revision: 1,
+                analysis_depth: 10,
+                ..Default::default()
+            }],
+            compared_revision: Some(1),
+            compared_plies: 40,
+            classification_changes: 2,
+            best_move_changes: 3,
+            changes: vec![Default::default(); 4],
+        });
+        assert_eq!(
+            rs.comparison_summary().as_deref(),
+            Some("Pass 1 (depth 10) agrees on 36 of 40 moves")
+        );
+    }
+
+    #[test]
+    fn test_explanation_shows_only_on_its_ply() {
+        let mut rs = new_review_state(sample_review());
+        rs.go_to_ply(2);
+        rs.explanation = Some(ExplainPositionResponse {
+            ply: 2,
+            best_move_san: "Nf6".to_string(),
+            ..Default::default()
+        });
+        assert_eq!(
+            rs.current_explanation().map(|e| e.best_move_san.as_str()),
+            Some("Nf6")
+        );
+        rs.go_to_ply(3);
+        assert!(rs.current_explanation().is_none());
+    }
+
+    #[test]
+    fn test_replace_review_stays_on_the_ply() {
+        let mut rs = new_review_state(sample_review());
+        rs.go_to_ply(2);
+        rs.explanation = Some(ExplainPositionResponse {
+            ply: 2,
+            ..Default::default()
+        });
+        let mut patched = sample_review();
+        patched.positions[1].depth = 26;
+        rs.replace_review(patched, None);
+        assert_eq!(rs.current_ply, 2);
+        assert_eq!(rs.current_position().map(|p| p.depth), Some(26));
+        assert!(rs.explanation.is_none());
+    }
+
+    #[test]
+    fn test_partial_review_coverage() {
+        let mut review = sample_review();
+        review.coverage = vec![CoveredRangeProto {
+            from_ply: 2,
+            to_ply: 3,
+            depth: 24,
+        }];
+        let mut rs = new_review_state(review);
+        assert_eq!(rs.coverage_label().as_deref(), Some("2-3 (depth 24)"));
+        assert_eq!(
+            rs.analyzed_positions()
+                .iter()
+                .map(|p| p.ply)
+                .collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+
+        // Unanalysed plies can still be stepped through, without analysis
+        rs.go_to_ply(1);
+        assert_eq!(rs.current_ply, 1);
+        assert!(rs.current_position().is_none());
+        rs.go_to_ply(2);
+        assert!(rs.current_position().is_some());
+
+        assert_eq!(new_review_state(sample_review()).coverage_label(), None);
+    }
+
+    #[test]
+    fn test_jump_to_phase_steps_between_phase_starts() {
+        let mut rs = new_review_state(sample_review());
+        // Three moves in, the game is still in the opening
+        assert_eq!(rs.phases, PhaseBoundaries::default());
+        assert_eq!(rs.jump_to_phase(true), None);
+
+        rs.phases = PhaseBoundaries {
+            middlegame_from: Some(2),
+            endgame_from: Some(3),
+        };
+        assert_eq!(rs.jump_to_phase(true), Some(Phase::Middlegame));
+        assert_eq!(rs.current_ply, 2);
+        assert_eq!(rs.jump_to_phase(true), Some(Phase::Endgame));
+        assert_eq!(rs.jump_to_phase(true), None);
+        assert_eq!(rs.current_phase(), Phase::Endgame);
+
+        assert_eq!(rs.jump_to_phase(false), Some(Phase::Middlegame));
+        assert_eq!(rs.jump_to_phase(false), Some(Phase::Opening));
+        assert_eq!(rs.current_ply, 0);
+    }
+
+    #[test]
+    fn test_jump_to_bookmark_skips_to_marked_plies() {
+        let mut rs = new_review_state(sample_review());
+        assert_eq!(rs.jump_to_bookmark(true), None);
+
+        let bookmark = |ply: u32| ReviewBookmark {
+            game_id: "test".into(),
+            ply,
+            ..Default::default()
+        };
+        rs.bookmarks = vec![bookmark(1), bookmark(3)];
+        assert_eq!(rs.jump_to_bookmark(true), Some(1));
+        assert_eq!(rs.current_bookmark(), Some(&bookmark(1)));
+        assert_eq!(rs.jump_to_bookmark(true), Some(3));
+        assert_eq!(rs.jump_to_bookmark(true), None);
+
+        rs.go_to_ply(2);
+        assert_eq!(rs.current_bookmark(), None);
+        assert_eq!(rs.jump_to_bookmark(false), Some(1));
+        assert_eq!(rs.jump_to_bookmark(false), None);
+    }
+
+    #[test]
+    fn test_mobility_by_phase_averages_each_phase() {
+        use chess_client::{AdvancedPositionAnalysisProto, PositionMobilityProto};
+
+        let mut rs = new_review_state(sample_review());
+        assert!(rs.mobility_by_phase().is_empty());
+
+        let position = |ply: u32, white: u32, black: u32| AdvancedPositionAnalysisProto {
+            ply,
+            mobility: Some(PositionMobilityProto {
+                white: Some(MobilityMetricsProto {
+                    mobility: white,
+                    space: white / 2,
+                }),
+                black: Some(MobilityMetricsProto {
+                    mobility: black,
+                    space: black / 2,
+                }),
+            }),
+            ..Default::default()
+        };
+        rs.advanced = Some(AdvancedGameAnalysisProto {
+            positions: vec![position(1, 4, 4), position(2, 8, 6), position(3, 20, 10)],
+            ..Default::default()
+        });
+        rs.phases = PhaseBoundaries {
+            middlegame_from: Some(3),
+            endgame_from: None,
+        };
+
+        let trend = rs.mobility_by_phase();
+        assert_eq!(trend.len(), 2);
+        assert_eq!(trend[0].phase, Phase::Opening);
+        assert_eq!(trend[0].white_mobility, 6.0);
+        assert_eq!(trend[0].black_mobility, 5.0);
+        assert_eq!(trend[0].white_space, 3.0);
+        assert_eq!(trend[1].phase, Phase::Middlegame);
+        assert_eq!(trend[1].white_mobility, 20.0);
+        assert_eq!(trend[1].black_space, 5.0);
+    }
 }
@@ -0,0 +1,82 @@
+//! Simul mode: one player against several engine boards at once.
+//!
+//! Each board is its own server session. The boards share a simul id so the
+//! finished games are saved as a linked set and can be reported on together.
+
+use crate::state::GameSession;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Most boards a simul can be started with.
+pub const MAX_SIMUL_BOARDS: u8 = 6;
+
+/// What the board strip shows for one simul board.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulBoardStatus {
+    pub awaiting_move: bool,
+    pub finished: bool,
+    pub move_count: u32,
+    /// The player's remaining time on this board, if timed.
+    pub clock_ms: Option<u64>,
+}
+
+/// Overview of every board in the simul, attached to the board being shown.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulStatus {
+    pub active: usize,
+    pub boards: Vec<SimulBoardStatus>,
+}
+
+impl SimulStatus {
+    pub fn from_boards(boards: &[GameSession], active: usize) -> Self {
+        Self {
+            active,
+            boards: boards
+                .iter()
+                .map(|b| SimulBoardStatus {
+                    awaiting_move: b.awaiting_human_move(),
+                    finished: b.is_finished(),
+                    move_count: b.snapshot.move_count,
+                    clock_ms: b.human_clock_ms(),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn all_finished(&self) -> bool {
+        self.boards.iter().all(|b| b.finished)
+    }
+}
+
+/// Generate an id linking the boards of a new simul.
+pub fn new_simul_id() -> String {
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    format!("simul_{}", millis)
+}
+
+/// The next board after `active` (wrapping) that is waiting on the player.
+pub fn next_waiting_board(waiting: &[bool], active: usize) -> Option<usize> {
+    let n = waiting.len();
+    (1..=n)
+        .map(|offset| (active + offset) % n)
+        .find(|&i| waiting[i])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_waiting_board_wraps_around() {
+        assert_eq!(next_waiting_board(&[true, false, false, true], 3), Some(0));
+        assert_eq!(next_waiting_board(&[true, false, true, false], 0), Some(2));
+    }
+
+    #[test]
+    fn next_waiting_board_returns_active_when_only_it_waits() {
+        assert_eq!(next_waiting_board(&[false, true, false], 1), Some(1));
+        assert_eq!(next_waiting_board(&[false, false, false], 1), None);
+    }
+}
@@ -1,37 +1,110 @@
+use crate::best_move::{ArrowSmoothing, BestMoveArrow};
+use crate::dispatch::{Action, Completion, Dispatcher, Outcome};
+use crate::material::CapturedMaterial;
+use crate::notifications::Notifications;
+use crate::premove::PremoveQueue;
+use crate::profiling::RpcTimings;
 use crate::review_state::ReviewState;
+use crate::simul::SimulStatus;
 use chess_client::ChessClient;
 use chess_client::*;
 use cozy_chess::{Board, Piece, Square};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 use tonic::Streaming;
 
-/// Get the socket path for server communication.
-fn get_socket_path() -> PathBuf {
+/// Game modes are shared with the server through the `chess` crate.
+pub use ::chess::{GameMode, PlayerSide};
+
+/// Get the server address to connect to.
+///
+/// Priority:
+/// 1. CHESSTTY_SERVER_ADDR env variable if set, e.g. `http://desktop.local:50051`
+///    for a server listening on TCP
+/// 2. The Unix socket at CHESSTTY_SOCKET_PATH if set
+/// 3. /tmp/chesstty.sock as fallback
+pub(crate) fn get_server_addr() -> ServerAddr {
+    if let Ok(addr) = std::env::var("CHESSTTY_SERVER_ADDR") {
+        return ServerAddr::parse(&addr);
+    }
     if let Ok(path) = std::env::var("CHESSTTY_SOCKET_PATH") {
-        return PathBuf::from(path);
+        return ServerAddr::Unix(PathBuf::from(path));
     }
-    PathBuf::from("/tmp/chesstty.sock")
+    ServerAddr::Unix(PathBuf::from("/tmp/chesstty.sock"))
 }
 
-/// Convert a proto GameModeProto to the client's local GameMode.
-pub fn game_mode_from_proto(proto: &GameModeProto) -> GameMode {
-    match GameModeType::try_from(proto.mode) {
-        Ok(GameModeType::HumanVsHuman) => GameMode::HumanVsHuman,
-        Ok(GameModeType::HumanVsEngine) => {
-            let human_side = match proto
-                .human_side
-                .and_then(|v| PlayerSideProto::try_from(v).ok())
-            {
-                Some(PlayerSideProto::Black) => PlayerColor::Black,
-                _ => PlayerColor::White,
-            };
-            GameMode::HumanVsEngine { human_side }
-        }
-        Ok(GameModeType::EngineVsEngine) => GameMode::EngineVsEngine,
-        Ok(GameModeType::Analysis) => GameMode::AnalysisMode,
-        Ok(GameModeType::Review) => GameMode::ReviewMode,
-        Err(_) => GameMode::HumanVsHuman,
+/// Get the TLS settings for a TCP server.
+///
+/// `CHESSTTY_TLS_CA` names a PEM CA bundle to verify the server with (e.g.
+/// its self-signed certificate). Unset, `https://` servers are verified
+/// against the system roots.
+fn get_client_tls() -> ClientResult<Option<ClientTlsConfig>> {
+    match std::env::var_os("CHESSTTY_TLS_CA") {
+        Some(path) => tls_config_from_ca(&PathBuf::from(path)).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Connect to the server at `get_server_addr()` with `get_client_tls()`.
+pub(crate) async fn connect_server() -> ClientResult<ChessClient> {
+    ChessClient::connect_to_with_tls(&get_server_addr(), get_client_tls()?).await
+}
+
+/// How long the squares behind an illegal-move explanation stay highlighted.
+const ILLEGAL_MOVE_HINT_DURATION: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Engine move delay presets (ms) cycled by the speed keys.
+const MOVE_DELAY_STEPS_MS: [u32; 7] = [0, 250, 500, 1000, 2000, 4000, 8000];
+
+/// Most engine lines cycled through by the lines key or the menu.
+pub const MAX_ENGINE_LINES: u32 = 3;
+
+/// Chat messages kept for the chat pane, as many as the server replays.
+const MAX_CHAT_MESSAGES: usize = 200;
+
+/// Chat messages younger than this raise a toast when they arrive.
+const CHAT_TOAST_SECS: u64 = 10;
+
+/// A move picked on the board but not yet sent, in move confirmation mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PendingMove {
+    pub from: Square,
+    pub to: Square,
+    pub promotion: Option<Piece>,
+}
+
+impl PendingMove {
+    /// The move in coordinates, e.g. `e7-e8=Q`.
+    pub fn describe(&self) -> String {
+        use ::chess::{format_piece_upper, format_square};
+        let promotion = self
+            .promotion
+            .map(|p| format!("={}", format_piece_upper(p)))
+            .unwrap_or_default();
+        format!(
+            "{}-{}{}",
+            format_square(self.from),
+            format_square(self.to),
+            promotion
+        )
+    }
+}
+
+/// The next delay preset above (`slower`) or below the current delay.
+fn next_move_delay(current: u32, slower: bool) -> u32 {
+    if slower {
+        MOVE_DELAY_STEPS_MS
+            .iter()
+            .copied()
+            .find(|&d| d > current)
+            .unwrap_or(MOVE_DELAY_STEPS_MS[MOVE_DELAY_STEPS_MS.len() - 1])
+    } else {
+        MOVE_DELAY_STEPS_MS
+            .iter()
+            .rev()
+            .copied()
+            .find(|&d| d < current)
+            .unwrap_or(0)
     }
 }
 
@@ -45,6 +118,12 @@ pub struct GameSession {
 
     /// Engine analysis info from the server
     pub engine_info: Option<EngineInfo>,
+    /// Latest info of each line after the best one when the engine searches
+    /// several at once, by line number (2 and up).
+    pub alt_lines: BTreeMap<u32, EngineInfo>,
+    /// Latest engine score seen after each move, keyed by the number of
+    /// moves played and turned to White's side, for the live eval trail.
+    pub live_evals: HashMap<usize, String>,
     /// Whether the engine is currently thinking
     pub is_engine_thinking: bool,
     /// UCI log entries
@@ -64,10 +143,21 @@ pub struct GameSession {
     pub last_move: Option<(Square, Square)>,
     /// Best move squares (from engine analysis)
     pub best_move_squares: Option<(Square, Square)>,
+    /// Smooths the best move from the engine's PV into the arrow.
+    best_move_arrow: BestMoveArrow,
     /// Selected promotion piece
     pub selected_promotion_piece: Piece,
-    /// Status message to display
-    pub status_message: Option<String>,
+    /// Hold each move until it is confirmed with a second key press.
+    pub confirm_moves: bool,
+    /// Move waiting for confirmation when `confirm_moves` is on, or when it
+    /// would drop the moves that can be redone.
+    pub pending_move: Option<PendingMove>,
+    /// Moves queued during the engine's turn, played as the turn comes back.
+    pub premoves: PremoveQueue,
+    /// Toasts on screen and the history of past notifications
+    pub notifications: Notifications,
+    /// Squares explaining the last rejected move, highlighted until the deadline.
+    pub illegal_move_hint: Option<(Vec<Square>, std::time::Instant)>,
 
     /// The latest snapshot from the server — single source of truth.
     pub snapshot: SessionSnapshot,
@@ -78,6 +168,11 @@ pub struct GameSession {
 
     /// Event streaming
     event_stream: Option<Streaming<SessionStreamEvent>>,
+    /// Runs server calls off the render loop. Spawned on first use so it
+    /// clones the client after a suspended session has been resumed.
+    dispatcher: Option<Dispatcher>,
+    /// How long finished server calls took, for the profiling overlay.
+    pub rpc_timings: RpcTimings,
 
     /// Review mode state (populated when viewing a post-game review).
     pub review_state: Option<ReviewState>,
@@ -85,25 +180,16 @@ pub struct GameSession {
     /// Pre-history moves from a snapshot (moves played before the snapshot position).
     /// Displayed before the current game's move history in the move history panel.
     pub pre_history: Vec<MoveRecord>,
-}
 
-/// Game mode determines how the app behaves
-#[derive(Debug, Clone, PartialEq, Default)]
-pub enum GameMode {
-    #[default]
-    HumanVsHuman,
-    HumanVsEngine {
-        human_side: PlayerColor,
-    },
-    EngineVsEngine,
-    AnalysisMode,
-    ReviewMode,
-}
+    /// Overview of all boards when this session is one board of a simul.
+    pub simul: Option<SimulStatus>,
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum PlayerColor {
-    White,
-    Black,
+    /// Chat between the clients of the session, oldest first.
+    pub chat: Vec<ChatMessage>,
+
+    /// Another client took the session over with a transfer code; this
+    /// client no longer has it.
+    pub transferred: bool,
 }
 
 /// Render state - all UI state needed to render the interface.
@@ -126,19 +212,52 @@ pub struct UciLogEntry {
 pub enum UciDirection {
     ToEngine,
     FromEngine,
+    EngineStderr,
+}
+
+/// What woke [`GameSession::poll_event_async`].
+enum Wakeup {
+    Completion(Completion),
+    Event(Option<Result<SessionStreamEvent, tonic::Status>>),
 }
 
 impl GameSession {
-    /// Create a new client state and session on the server.
-    pub async fn new(
+    /// Create a new client state and session on the server, optionally tagged
+    /// with a simul id and/or a named opponent.
+    pub async fn new_labeled(
         _server_addr: &str,
         fen: Option<String>,
         game_mode_proto: Option<GameModeProto>,
         timer: Option<TimerState>,
+        simul_id: Option<String>,
+        opponent: Option<String>,
     ) -> Result<Self, Box<dyn std::error::Error>> {
-        let mut client = ChessClient::connect_uds(&get_socket_path()).await?;
-        let snapshot = client.create_session(fen, game_mode_proto, timer).await?;
+        let mut client = connect_server()
+            .await?
+            .with_profile(crate::tutorial::current_profile());
+        let snapshot = client
+            .create_labeled_session(fen, game_mode_proto, timer, simul_id, opponent)
+            .await?;
+        Self::from_snapshot(client, snapshot).await
+    }
+
+    /// Take over the session another client issued `code` for. The session
+    /// keeps its game mode, engine and clocks.
+    pub async fn claim_transfer(code: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut client = connect_server()
+            .await?
+            .with_profile(crate::tutorial::current_profile());
+        let snapshot = client.claim_session_transfer(code).await?;
+        let skill_level = snapshot.engine_config.as_ref().map(|c| c.skill_level as u8);
+        let mut state = Self::from_snapshot(client, snapshot).await?;
+        state.skill_level = skill_level.unwrap_or(state.skill_level);
+        Ok(state)
+    }
 
+    async fn from_snapshot(
+        client: ChessClient,
+        snapshot: SessionSnapshot,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
         let board = snapshot
             .fen
             .parse::<Board>()
@@ -147,8 +266,8 @@ impl GameSession {
         let mode = snapshot
             .game_mode
             .as_ref()
-            .map(game_mode_from_proto)
-            .unwrap_or(GameMode::HumanVsHuman);
+            .map(GameMode::from)
+            .unwrap_or_default();
 
         let mut state = Self {
             client,
@@ -156,6 +275,8 @@ impl GameSession {
             skill_level: 10,
             // Engine state
             engine_info: None,
+            alt_lines: BTreeMap::new(),
+            live_evals: HashMap::new(),
             is_engine_thinking: false,
             uci_log: Vec::new(),
             paused: false,
@@ -166,15 +287,25 @@ impl GameSession {
             selectable_squares: Vec::new(),
             last_move: None,
             best_move_squares: None,
+            best_move_arrow: BestMoveArrow::new(ArrowSmoothing::from_env()),
             selected_promotion_piece: Piece::Queen,
-            status_message: None,
+            confirm_moves: false,
+            pending_move: None,
+            premoves: PremoveQueue::default(),
+            notifications: Notifications::default(),
+            illegal_move_hint: None,
             // Snapshot and board
             snapshot,
             board,
             legal_moves_cache: HashMap::new(),
             event_stream: None,
+            dispatcher: None,
+            rpc_timings: RpcTimings::default(),
             review_state: None,
             pre_history: Vec::new(),
+            simul: None,
+            chat: Vec::new(),
+            transferred: false,
         };
 
         state.update_selectable_squares().await?;
@@ -182,6 +313,9 @@ impl GameSession {
     }
 
     /// Create a client state for review mode (no server session created).
+    ///
+    /// Navigation is local, so the client connects lazily and a review read
+    /// from the offline library works without a running server.
     pub async fn new_review(
         _server_addr: &str,
         review: GameReviewProto,
@@ -189,17 +323,19 @@ impl GameSession {
         review_skill_level: u8,
         advanced: Option<AdvancedGameAnalysisProto>,
     ) -> Result<Self, Box<dyn std::error::Error>> {
-        let client = ChessClient::connect_uds(&get_socket_path()).await?;
+        let client = ChessClient::connect_lazy_with_tls(&get_server_addr(), get_client_tls()?)?;
 
         let board = Board::default();
         let snapshot = SessionSnapshot::default();
 
         Ok(Self {
             client,
-            mode: GameMode::ReviewMode,
+            mode: GameMode::Review,
             skill_level: 0,
             // Engine state - not used in review
             engine_info: None,
+            alt_lines: BTreeMap::new(),
+            live_evals: HashMap::new(),
             is_engine_thinking: false,
             uci_log: Vec::new(),
             paused: false,
@@ -210,13 +346,24 @@ impl GameSession {
             selectable_squares: Vec::new(),
             last_move: None,
             best_move_squares: None,
+            best_move_arrow: BestMoveArrow::new(ArrowSmoothing::from_env()),
             selected_promotion_piece: Piece::Queen,
-            status_message: Some("Review mode - use arrow keys to navigate".to_string()),
+            confirm_moves: false,
+            pending_move: None,
+            premoves: PremoveQueue::default(),
+            notifications: {
+                let mut notifications = Notifications::default();
+                notifications.info("Review mode - use arrow keys to navigate");
+                notifications
+            },
+            illegal_move_hint: None,
             // Snapshot and board
             snapshot,
             board,
             legal_moves_cache: HashMap::new(),
             event_stream: None,
+            dispatcher: None,
+            rpc_timings: RpcTimings::default(),
             review_state: Some(ReviewState::with_metadata(
                 review,
                 review_game_mode,
@@ -224,6 +371,9 @@ impl GameSession {
                 advanced,
             )),
             pre_history: Vec::new(),
+            simul: None,
+            chat: Vec::new(),
+            transferred: false,
         })
     }
 
@@ -257,6 +407,55 @@ impl GameSession {
         }
     }
 
+    /// Pieces each side has captured up to the displayed position: the
+    /// current ply when reviewing, the latest move otherwise.
+    pub fn captured_material(&self) -> CapturedMaterial {
+        let (start, shown) = match self.review_state {
+            Some(ref rs) => (Board::default(), rs.current_ply as usize),
+            None => (
+                self.snapshot.start_fen.parse().unwrap_or_default(),
+                self.snapshot.history.len(),
+            ),
+        };
+        let fens = self
+            .history()
+            .iter()
+            .take(shown)
+            .map(|m| m.fen_after.as_str());
+        CapturedMaterial::from_history(&start, fens)
+    }
+
+    /// Whether the game on this board has ended.
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            GamePhase::try_from(self.snapshot.phase).ok(),
+            Some(GamePhase::Ended)
+        )
+    }
+
+    /// Whether the engine is waiting on the human's move.
+    pub fn awaiting_human_move(&self) -> bool {
+        let GameMode::HumanVsEngine { human_side } = self.mode else {
+            return false;
+        };
+        let human = match human_side {
+            PlayerSide::White => "white",
+            PlayerSide::Black => "black",
+        };
+        !self.is_finished() && self.snapshot.side_to_move == human
+    }
+
+    /// Remaining clock time for the human side, when the game is timed.
+    pub fn human_clock_ms(&self) -> Option<u64> {
+        let GameMode::HumanVsEngine { human_side } = self.mode else {
+            return None;
+        };
+        self.snapshot.timer.as_ref().map(|t| match human_side {
+            PlayerSide::White => t.white_remaining_ms,
+            PlayerSide::Black => t.black_remaining_ms,
+        })
+    }
+
     pub fn is_undo_allowed(&self) -> bool {
         matches!(self.mode, GameMode::HumanVsEngine { .. }) && self.skill_level <= 3
     }
@@ -273,9 +472,14 @@ impl GameSession {
     }
 
     pub async fn update_selectable_squares(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        use ::chess::parse_square;
-
         let moves = self.client.get_legal_moves(None).await?;
+        self.set_legal_moves(moves);
+        Ok(())
+    }
+
+    /// Replace the cached legal moves and the squares that can be selected.
+    fn set_legal_moves(&mut self, moves: Vec<MoveDetail>) {
+        use ::chess::parse_square;
 
         let mut from_squares: Vec<Square> =
             moves.iter().filter_map(|m| parse_square(&m.from)).collect();
@@ -292,7 +496,16 @@ impl GameSession {
                 .push(mv);
         }
 
-        Ok(())
+        // A piece picked for a premove stays selected once the turn is back
+        if let Some(square) = self.selected_square.filter(|_| !self.can_premove()) {
+            match self.legal_moves_cache.get(&::chess::format_square(square)) {
+                Some(moves) => {
+                    self.highlighted_squares =
+                        moves.iter().filter_map(|m| parse_square(&m.to)).collect();
+                }
+                None => self.clear_selection(),
+            }
+        }
     }
 
     pub fn filter_selectable_by_input(&self, input: &str) -> Vec<Square> {
@@ -316,8 +529,14 @@ impl GameSession {
     pub fn select_square(&mut self, square: Square) {
         use ::chess::{format_square, parse_square};
 
+        if self.can_premove() {
+            self.select_premove_square(square);
+            return;
+        }
+
         if !self.selectable_squares.contains(&square) {
-            self.status_message = Some("No piece on that square or not your turn".to_string());
+            self.notifications
+                .warn("No piece on that square or not your turn");
             return;
         }
 
@@ -326,19 +545,39 @@ impl GameSession {
             self.selected_square = Some(square);
             self.highlighted_squares = moves.iter().filter_map(|m| parse_square(&m.to)).collect();
             // input_phase now handled by FSM
-            self.status_message = Some(format!("Selected {}", square_str));
+            self.notifications.info(format!("Selected {}", square_str));
         } else {
-            self.status_message = Some("No legal moves from that square".to_string());
+            self.notifications.warn("No legal moves from that square");
         }
     }
 
-    pub async fn try_move_to(&mut self, to_square: Square) -> Result<(), String> {
+    /// Check the move locally and send it to the server. A promotion first
+    /// asks for the piece; the result arrives as a [`Completion`].
+    pub fn try_move_to(&mut self, to_square: Square) -> Result<(), String> {
         use ::chess::format_square;
 
         let from_square = self.selected_square.ok_or("No piece selected")?;
 
+        if self.can_premove() {
+            return self.queue_premove(from_square, to_square);
+        }
+
         if !self.highlighted_squares.contains(&to_square) {
-            return Err("Illegal move".to_string());
+            let mv = cozy_chess::Move {
+                from: from_square,
+                to: to_square,
+                promotion: None,
+            };
+            return Err(match ::chess::explain_illegal_move(&self.board, mv) {
+                Some(reason) => {
+                    self.illegal_move_hint = Some((
+                        reason.squares(),
+                        std::time::Instant::now() + ILLEGAL_MOVE_HINT_DURATION,
+                    ));
+                    reason.to_string()
+                }
+                None => "Illegal move".to_string(),
+            });
         }
 
         let from_str = format_square(from_square);
@@ -357,69 +596,173 @@ impl GameSession {
         if needs_promotion {
             // input_phase now handled by FSM - select promotion piece in FSM
             self.selected_promotion_piece = Piece::Queen;
-            self.status_message = Some("Select promotion piece".to_string());
+            self.notifications.info("Select promotion piece");
             return Ok(());
         }
 
-        let snapshot = self
-            .client
-            .make_move(&from_str, &to_str, None)
-            .await
-            .map_err(|e| e.to_string())?;
-
-        self.apply_snapshot(snapshot);
+        self.send_move(PendingMove {
+            from: from_square,
+            to: to_square,
+            promotion: None,
+        });
+        Ok(())
+    }
 
-        self.last_move = Some((from_square, to_square));
-        self.selected_square = None;
-        self.highlighted_squares.clear();
-        // input_phase now handled by FSM
-        self.status_message = Some(format!("Moved {} to {}", from_str, to_str));
+    pub fn execute_promotion(&mut self, from: Square, to: Square, piece: Piece) {
+        self.send_move(PendingMove {
+            from,
+            to,
+            promotion: Some(piece),
+        });
+    }
 
-        self.update_selectable_squares()
-            .await
-            .map_err(|e| e.to_string())?;
+    /// Send a chosen move, or hold it for confirmation when `confirm_moves`
+    /// is on or it would drop the undone moves. Only one move is held; a new
+    /// one replaces it.
+    fn send_move(&mut self, mv: PendingMove) {
+        self.clear_selection();
+        if self.discards_redo(&mv) {
+            let count = self.snapshot.redo_depth;
+            self.notifications.warn(format!(
+                "Play {}? It drops {} undone move{}. Enter to confirm, Esc to cancel",
+                mv.describe(),
+                count,
+                if count == 1 { "" } else { "s" }
+            ));
+            self.pending_move = Some(mv);
+            return;
+        }
+        if self.confirm_moves {
+            self.notifications.info(format!(
+                "Play {}? Enter to confirm, Esc to cancel",
+                mv.describe()
+            ));
+            self.pending_move = Some(mv);
+            return;
+        }
+        self.dispatch(Action::MakeMove {
+            from: mv.from,
+            to: mv.to,
+            promotion: mv.promotion,
+        });
+    }
 
-        Ok(())
+    /// Send the move waiting for confirmation, if any.
+    pub fn confirm_pending_move(&mut self) {
+        if let Some(mv) = self.pending_move.take() {
+            self.dispatch(Action::MakeMove {
+                from: mv.from,
+                to: mv.to,
+                promotion: mv.promotion,
+            });
+        }
     }
 
-    pub async fn execute_promotion(
-        &mut self,
-        from: Square,
-        to: Square,
-        piece: Piece,
-    ) -> Result<(), String> {
-        use ::chess::{format_piece, format_square};
+    /// Whether playing `mv` drops the undone moves: any move but the one the
+    /// next redo plays branches off the undone line.
+    fn discards_redo(&self, mv: &PendingMove) -> bool {
+        use ::chess::format_square;
+        let replays_redo = self.snapshot.next_redo.as_ref().is_some_and(|next| {
+            next.from == format_square(mv.from) && next.to == format_square(mv.to)
+        });
+        self.snapshot.redo_depth > 0 && !replays_redo
+    }
 
-        let from_str = format_square(from);
-        let to_str = format_square(to);
-        let piece_str = format_piece(piece).to_string();
+    /// Drop the move waiting for confirmation, if any.
+    pub fn cancel_pending_move(&mut self) {
+        if let Some(mv) = self.pending_move.take() {
+            self.notifications
+                .info(format!("Cancelled {}", mv.describe()));
+        }
+    }
 
-        let snapshot = self
-            .client
-            .make_move(&from_str, &to_str, Some(piece_str))
-            .await
-            .map_err(|e| e.to_string())?;
+    /// Whether a move picked now is queued as a premove: against the engine,
+    /// while it is the engine's turn.
+    pub fn can_premove(&self) -> bool {
+        matches!(self.mode, GameMode::HumanVsEngine { .. })
+            && !self.is_finished()
+            && !self.awaiting_human_move()
+    }
 
-        self.apply_snapshot(snapshot);
+    /// Colour of the human's pieces in a game against the engine.
+    fn human_color(&self) -> Option<cozy_chess::Color> {
+        match self.mode {
+            GameMode::HumanVsEngine {
+                human_side: PlayerSide::White,
+            } => Some(cozy_chess::Color::White),
+            GameMode::HumanVsEngine {
+                human_side: PlayerSide::Black,
+            } => Some(cozy_chess::Color::Black),
+            _ => None,
+        }
+    }
 
-        self.last_move = Some((from, to));
-        self.selected_square = None;
+    /// Select a piece to premove, where it stands once the queued premoves
+    /// are played. Any other square is a possible destination.
+    fn select_premove_square(&mut self, square: Square) {
+        let side = self.human_color();
+        let own = self
+            .premoves
+            .piece_at(&self.board, square)
+            .is_some_and(|(color, _)| Some(color) == side);
+        if !own {
+            self.notifications.warn("No piece of yours on that square");
+            return;
+        }
+        self.selected_square = Some(square);
         self.highlighted_squares.clear();
-        // input_phase now handled by FSM
-        self.status_message = Some(format!("Promoted to {:?}", piece));
-
-        self.update_selectable_squares()
-            .await
-            .map_err(|e| e.to_string())?;
+        self.notifications
+            .info(format!("Premove from {}", ::chess::format_square(square)));
+    }
 
+    fn queue_premove(&mut self, from: Square, to: Square) -> Result<(), String> {
+        let side = self
+            .human_color()
+            .ok_or("Premoves are only for games against the engine")?;
+        let premove = self.premoves.push(&self.board, side, from, to)?;
+        self.clear_selection();
+        self.notifications
+            .info(format!("Premove {} queued", premove.describe()));
         Ok(())
     }
 
+    /// Drop every queued premove.
+    pub fn clear_premoves(&mut self) {
+        self.premoves.clear();
+        self.notifications.info("Premoves cleared");
+    }
+
+    /// Play the next premove once the turn is back, or drop the queue when
+    /// it no longer applies. Premoves skip move confirmation.
+    fn play_premove(&mut self) {
+        if self.premoves.is_empty() || self.paused || !self.awaiting_human_move() {
+            return;
+        }
+        match self.premoves.take_next(&self.board) {
+            Ok(Some(premove)) => self.dispatch(Action::MakeMove {
+                from: premove.from,
+                to: premove.to,
+                promotion: premove.promotion,
+            }),
+            Ok(None) => {}
+            Err(reason) => self
+                .notifications
+                .warn(format!("Premoves cleared: {}", reason)),
+        }
+    }
+
+    /// Squares explaining the last rejected move, while the highlight lasts.
+    pub fn illegal_move_squares(&self) -> &[Square] {
+        match self.illegal_move_hint {
+            Some((ref squares, until)) if std::time::Instant::now() < until => squares,
+            _ => &[],
+        }
+    }
+
     pub fn clear_selection(&mut self) {
         self.selected_square = None;
         self.highlighted_squares.clear();
         // input_phase now handled by FSM
-        self.status_message = None;
     }
 
     // --- Event streaming ---
@@ -432,43 +775,61 @@ impl GameSession {
         Ok(())
     }
 
+    /// Wait for the next server event or finished server call and apply it.
     pub async fn poll_event_async(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         use futures::StreamExt;
 
-        if let Some(stream) = &mut self.event_stream {
-            match stream.next().await {
-                Some(Ok(event)) => {
-                    self.handle_event(event).await;
-                    Ok(())
-                }
-                Some(Err(e)) => {
-                    self.status_message = Some(format!("Stream error: {}", e));
-                    self.event_stream = None;
-                    Err(e.into())
-                }
-                None => {
-                    self.event_stream = None;
-                    Err("Event stream ended".into())
+        let (dispatcher, stream) = (&mut self.dispatcher, &mut self.event_stream);
+        let wakeup = tokio::select! {
+            completion = Self::next_completion(dispatcher) => Wakeup::Completion(completion),
+            event = async {
+                match stream.as_mut() {
+                    Some(stream) => stream.next().await,
+                    None => std::future::pending().await,
                 }
+            } => Wakeup::Event(event),
+        };
+
+        match wakeup {
+            Wakeup::Completion(completion) => {
+                self.apply_completion(completion);
+                Ok(())
+            }
+            Wakeup::Event(Some(Ok(event))) => {
+                self.handle_event(event);
+                Ok(())
+            }
+            Wakeup::Event(Some(Err(e))) => {
+                self.notifications.error(format!("Stream error: {}", e));
+                self.event_stream = None;
+                Err(e.into())
+            }
+            Wakeup::Event(None) => {
+                self.event_stream = None;
+                Err("Event stream ended".into())
             }
-        } else {
-            std::future::pending::<()>().await;
-            Ok(())
         }
     }
 
+    /// Apply one buffered server event or finished server call without
+    /// waiting. Returns whether there was one.
     pub async fn poll_events(&mut self) -> Result<bool, Box<dyn std::error::Error>> {
         use futures::StreamExt;
 
+        if let Some(completion) = self.dispatcher.as_mut().and_then(Dispatcher::try_recv) {
+            self.apply_completion(completion);
+            return Ok(true);
+        }
+
         if let Some(stream) = &mut self.event_stream {
             match futures::poll!(stream.next()) {
                 std::task::Poll::Ready(Some(result)) => match result {
                     Ok(event) => {
-                        self.handle_event(event).await;
+                        self.handle_event(event);
                         Ok(true)
                     }
                     Err(e) => {
-                        self.status_message = Some(format!("Stream error: {}", e));
+                        self.notifications.error(format!("Stream error: {}", e));
                         self.event_stream = None;
                         Err(e.into())
                     }
@@ -484,7 +845,7 @@ impl GameSession {
         }
     }
 
-    async fn handle_event(&mut self, event: SessionStreamEvent) {
+    fn handle_event(&mut self, event: SessionStreamEvent) {
         if let Some(event_type) = event.event {
             match event_type {
                 session_stream_event::Event::StateChanged(snapshot) => {
@@ -501,13 +862,8 @@ impl GameSession {
 
                     self.is_engine_thinking = snapshot.engine_thinking;
                     self.apply_snapshot(snapshot);
-
-                    if let Err(e) = self.update_selectable_squares().await {
-                        tracing::warn!(
-                            "Failed to update selectable squares after state change: {}",
-                            e
-                        );
-                    }
+                    self.dispatch(Action::LegalMoves);
+                    self.play_premove();
                 }
                 session_stream_event::Event::EngineThinking(analysis) => {
                     let info = EngineInfo {
@@ -519,6 +875,21 @@ impl GameSession {
                         pv: analysis.pv.clone(),
                         nps: analysis.nps,
                     };
+                    if let Some(line) = analysis.multipv.filter(|&n| n > 1) {
+                        if line <= self.engine_lines() {
+                            self.alt_lines.insert(line, info);
+                        }
+                        return;
+                    }
+                    let best = info.pv.first().and_then(|uci| self.pv_move_squares(uci));
+                    self.best_move_squares = self.best_move_arrow.observe(best, info.depth);
+                    if let Some(ref score) = info.score {
+                        let white_to_move = self.snapshot.side_to_move == "white";
+                        self.live_evals.insert(
+                            self.snapshot.history.len(),
+                            white_relative(score, white_to_move),
+                        );
+                    }
                     self.engine_info = Some(info);
                     self.is_engine_thinking = true;
                 }
@@ -526,20 +897,57 @@ impl GameSession {
                     let direction = match uci_msg.direction {
                         0 => UciDirection::ToEngine,
                         1 => UciDirection::FromEngine,
+                        2 => UciDirection::EngineStderr,
                         _ => UciDirection::FromEngine,
                     };
+                    if matches!(direction, UciDirection::EngineStderr) {
+                        self.notifications
+                            .warn(format!("Engine: {}", uci_msg.message.trim()));
+                    }
                     self.log_uci_message(direction, uci_msg.message, uci_msg.context);
                 }
                 session_stream_event::Event::Error(err_string) => {
                     let error_msg = format!("Server error: {}", err_string);
                     tracing::error!("{}", error_msg);
-                    self.status_message = Some(error_msg);
+                    self.notifications.error(error_msg);
                     self.is_engine_thinking = false;
                 }
+                session_stream_event::Event::Chat(message) => self.receive_chat(message),
+                session_stream_event::Event::Transferred(_) => {
+                    // The session carries on in the other client; leaving
+                    // must not close it, and queued calls must not reach it
+                    self.client.release_session();
+                    self.dispatcher = None;
+                    self.event_stream = None;
+                    self.transferred = true;
+                    self.notifications.warn("Session moved to another client");
+                }
             }
         }
     }
 
+    /// Keep a chat message, once, in seq order. A reconnected stream
+    /// replays messages already seen.
+    fn receive_chat(&mut self, message: ChatMessage) {
+        if self.chat.last().is_some_and(|last| message.seq <= last.seq) {
+            return;
+        }
+        // Replayed history is old news; only fresh messages are toasted
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        if message.author != crate::tutorial::current_profile()
+            && message.sent_at + CHAT_TOAST_SECS >= now
+        {
+            self.notifications
+                .info(format!("{}: {}", message.author, message.text));
+        }
+        self.chat.push(message);
+        if self.chat.len() > MAX_CHAT_MESSAGES {
+            self.chat.remove(0);
+        }
+    }
+
     pub fn log_uci_message(
         &mut self,
         direction: UciDirection,
@@ -559,29 +967,34 @@ impl GameSession {
 
     // --- Game actions ---
 
-    pub async fn undo(&mut self) -> Result<(), String> {
-        let snapshot = self.client.undo_move().await.map_err(|e| e.to_string())?;
-        self.apply_snapshot(snapshot);
-        self.update_selectable_squares()
-            .await
-            .map_err(|e| e.to_string())?;
-        self.status_message = Some("Move undone".to_string());
-        Ok(())
+    pub fn undo(&mut self) {
+        self.dispatch(Action::Undo);
     }
 
-    pub async fn reset(&mut self, fen: Option<String>) -> Result<(), String> {
-        let snapshot = self
-            .client
-            .reset_game(fen)
-            .await
-            .map_err(|e| e.to_string())?;
-        self.apply_snapshot(snapshot);
-        self.update_selectable_squares()
-            .await
-            .map_err(|e| e.to_string())?;
-        self.clear_selection();
-        self.status_message = Some("Game reset".to_string());
-        Ok(())
+    pub fn redo(&mut self) {
+        self.dispatch(Action::Redo);
+    }
+
+    pub fn reset(&mut self) {
+        self.dispatch(Action::Reset);
+    }
+
+    /// Pause or resume the engine. The flag flips straight away so the menu
+    /// can restore it before the server has answered.
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+        self.notifications
+            .info(if paused { "Paused" } else { "Playing" });
+        self.dispatch(if paused {
+            Action::Pause
+        } else {
+            Action::Resume
+        });
+    }
+
+    /// Change the engine strength of a running game.
+    pub fn change_skill_level(&mut self, skill_level: u8) {
+        self.dispatch(Action::SetEngine { skill_level });
     }
 
     pub async fn set_engine(&mut self, enabled: bool, skill_level: u8) -> Result<(), String> {
@@ -603,9 +1016,376 @@ impl GameSession {
         Ok(())
     }
 
+    /// Have the engine search `lines` lines at once (MultiPV), for a game
+    /// set up with several from the menu.
+    pub async fn set_engine_lines(&mut self, lines: u32) -> Result<(), String> {
+        self.client
+            .set_engine_lines(self.skill_level as u32, lines)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Step the server-side engine move delay one notch slower or faster.
+    pub fn adjust_move_delay(&mut self, slower: bool) {
+        let delay_ms = next_move_delay(self.snapshot.engine_move_delay_ms, slower);
+        self.snapshot.engine_move_delay_ms = delay_ms;
+        self.notifications
+            .info(format!("Move delay: {}ms", delay_ms));
+        self.dispatch(Action::SetMoveDelay(delay_ms));
+    }
+
+    /// Toggle live tactical hints for the current position.
+    pub fn toggle_tactical_hints(&mut self) {
+        let enabled = !self.snapshot.tactical_hints_enabled;
+        self.snapshot.tactical_hints_enabled = enabled;
+        self.notifications.info(if enabled {
+            "Tactical hints on"
+        } else {
+            "Tactical hints off"
+        });
+        self.dispatch(Action::SetTacticalHints(enabled));
+    }
+
+    /// Lines the engine searches at once; 1 unless MultiPV is on.
+    pub fn engine_lines(&self) -> u32 {
+        self.snapshot
+            .engine_config
+            .as_ref()
+            .map_or(1, |config| config.multipv.max(1))
+    }
+
+    /// Whether the engine's lines can be changed: while it analyses or
+    /// plays itself, not while it is someone's opponent.
+    pub fn can_change_engine_lines(&self) -> bool {
+        matches!(self.mode, GameMode::EngineVsEngine | GameMode::Analysis)
+            && self
+                .snapshot
+                .engine_config
+                .as_ref()
+                .is_some_and(|config| config.enabled)
+    }
+
+    /// Search one more line at once, wrapping back to a single line.
+    pub fn cycle_engine_lines(&mut self) {
+        let Some(ref mut config) = self.snapshot.engine_config else {
+            return;
+        };
+        let lines = config.multipv.max(1) % MAX_ENGINE_LINES + 1;
+        config.multipv = lines;
+        self.alt_lines.retain(|&line, _| line <= lines);
+        self.notifications.info(if lines == 1 {
+            "Engine lines: best only".to_string()
+        } else {
+            format!("Engine lines: {}", lines)
+        });
+        self.dispatch(Action::SetEngineLines {
+            skill_level: self.skill_level,
+            lines,
+        });
+    }
+
+    /// First moves of the other lines, for their arrows.
+    pub fn alt_line_arrows(&self) -> Vec<(u32, (Square, Square))> {
+        self.alt_lines
+            .iter()
+            .filter_map(|(&line, info)| Some((line, self.pv_move_squares(info.pv.first()?)?)))
+            .collect()
+    }
+
+    /// Toggle step-through mode (engine-vs-engine only moves on `step_engine`).
+    pub fn toggle_step_mode(&mut self) {
+        let enabled = !self.snapshot.step_mode;
+        self.snapshot.step_mode = enabled;
+        self.notifications.info(if enabled {
+            "Step mode: press n for the next move"
+        } else {
+            "Step mode off"
+        });
+        self.dispatch(Action::SetStepMode(enabled));
+    }
+
+    /// Let the engine play its next move in step-through mode.
+    pub fn step_engine(&mut self) {
+        self.dispatch(Action::StepEngine);
+    }
+
+    pub fn save_snapshot(
+        &mut self,
+        fen: String,
+        name: String,
+        game_mode: Option<GameModeProto>,
+        move_count: u32,
+        skill_level: u8,
+        source_game: String,
+    ) {
+        self.dispatch(Action::SaveSnapshot {
+            fen,
+            name,
+            game_mode,
+            move_count,
+            skill_level,
+            source_game,
+        });
+    }
+
+    /// Send a chat message to the other clients of the session, under the
+    /// current profile's name. It is shown once the server echoes it back.
+    pub fn send_chat(&mut self, text: String) {
+        self.dispatch(Action::SendChat {
+            author: crate::tutorial::current_profile(),
+            text,
+        });
+    }
+
+    // --- Action dispatch ---
+
+    /// Queue a server call; its result is applied by [`Self::apply_completion`].
+    pub fn dispatch(&mut self, action: Action) {
+        let client = &self.client;
+        self.dispatcher
+            .get_or_insert_with(|| Dispatcher::spawn(client.clone()))
+            .send(action);
+    }
+
+    /// Apply the result of a finished server call.
+    pub fn apply_completion(&mut self, completion: Completion) {
+        let Completion {
+            action,
+            result,
+            elapsed,
+        } = completion;
+        self.rpc_timings.record(action.label(), elapsed);
+        let outcome = match result {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                self.notifications
+                    .error(format!("{} error: {}", action.label(), e));
+                if action.is_optimistic() {
+                    self.dispatch(Action::Refresh);
+                }
+                return;
+            }
+        };
+
+        match outcome {
+            Outcome::Done => {}
+            Outcome::Position {
+                snapshot,
+                legal_moves,
+            } => {
+                self.apply_snapshot(snapshot);
+                self.set_legal_moves(legal_moves);
+            }
+            Outcome::LegalMoves(legal_moves) => self.set_legal_moves(legal_moves),
+            Outcome::ClockSimulation(simulation) => {
+                self.apply_clock_simulation(&action, simulation)
+            }
+            Outcome::ReviewComparison(comparison) => {
+                self.apply_review_comparison(&action, comparison)
+            }
+            Outcome::PositionExplanation(explanation) => {
+                self.apply_position_explanation(&action, explanation)
+            }
+            // Announced when the key was pressed; the result comes later
+            Outcome::ReanalysisQueued { .. } => {}
+            Outcome::ReviewPatched {
+                review,
+                advanced,
+                depth,
+            } => self.apply_patched_review(&action, review, advanced, depth),
+            Outcome::Bookmarks(bookmarks) => self.apply_bookmarks(&action, bookmarks),
+            Outcome::Snapshot(name) => self
+                .notifications
+                .success(format!("Snapshot saved: {}", name)),
+            Outcome::TransferCode {
+                code,
+                expires_in_secs,
+            } => self.notifications.success(format!(
+                "Transfer code {}: choose Join From Another Client in the other client within {} min",
+                code,
+                expires_in_secs.div_ceil(60)
+            )),
+        }
+
+        match action {
+            Action::MakeMove {
+                from,
+                to,
+                promotion,
+            } => {
+                use ::chess::format_square;
+                self.last_move = Some((from, to));
+                match promotion {
+                    Some(piece) => self
+                        .notifications
+                        .success(format!("Promoted to {:?}", piece)),
+                    None => self.notifications.info(format!(
+                        "Moved {} to {}",
+                        format_square(from),
+                        format_square(to)
+                    )),
+                }
+            }
+            Action::Undo => self.notifications.success("Move undone"),
+            Action::Redo => self.notifications.success("Move redone"),
+            Action::Reset => {
+                self.clear_selection();
+                self.notifications.success("Game reset");
+            }
+            Action::SetEngine { skill_level } => {
+                self.skill_level = skill_level;
+                let label = match skill_level {
+                    3 => "Beginner",
+                    10 => "Intermediate",
+                    15 => "Advanced",
+                    20 => "Master",
+                    _ => "Custom",
+                };
+                self.notifications
+                    .success(format!("Difficulty set to {}", label));
+            }
+            Action::SaveSnapshot { .. } => self.notifications.success("Snapshot saved"),
+            Action::SetBookmark { .. } => self.notifications.success("Bookmark saved"),
+            Action::DeleteBookmark { .. } => self.notifications.info("Bookmark removed"),
+            Action::SaveBookmarkPositions { positions } => self.notifications.success(format!(
+                "Saved {} bookmarked position{}",
+                positions.len(),
+                if positions.len() == 1 { "" } else { "s" }
+            )),
+            _ => {}
+        }
+    }
+
+    /// Show a simulated clock in the review, unless another time control
+    /// was picked while it ran.
+    fn apply_clock_simulation(&mut self, action: &Action, simulation: SimulateClockResponse) {
+        let Action::SimulateClock {
+            initial_ms,
+            increment_ms,
+            ..
+        } = *action
+        else {
+            return;
+        };
+        let Some(review) = self.review_state.as_mut() else {
+            return;
+        };
+        if review.clock_control() != Some((initial_ms, increment_ms)) {
+            return;
+        }
+        review.clock_simulation = Some(simulation);
+        if let Some(verdict) = review.clock_verdict() {
+            self.notifications.info(verdict);
+        }
+    }
+
+    /// Show how the current review differs from the game's earlier pass,
+    /// unless another game is under review by now.
+    fn apply_review_comparison(&mut self, action: &Action, comparison: CompareReviewsResponse) {
+        let Action::CompareReviews { game_id } = action else {
+            return;
+        };
+        let Some(review) = self.review_state.as_mut() else {
+            return;
+        };
+        if review.review.game_id != *game_id {
+            return;
+        }
+        if comparison.compared_revision.is_none() {
+            self.notifications
+                .info("This game was only reviewed once, nothing to compare");
+            return;
+        }
+        review.comparison = Some(comparison);
+        if let Some(summary) = review.comparison_summary() {
+            self.notifications.info(summary);
+        }
+    }
+
+    /// Show why the best move is best, unless another game is under review
+    /// by now. The panel shows it while the review stays on its ply.
+    fn apply_position_explanation(
+        &mut self,
+        action: &Action,
+        explanation: ExplainPositionResponse,
+    ) {
+        let Action::ExplainPosition { game_id, .. } = action else {
+            return;
+        };
+        let Some(review) = self.review_state.as_mut() else {
+            return;
+        };
+        if review.review.game_id != *game_id {
+            return;
+        }
+        review.explanation = Some(explanation);
+    }
+
+    /// Take the review patched by a re-analysis, unless another game is
+    /// under review by now.
+    fn apply_patched_review(
+        &mut self,
+        action: &Action,
+        patched: GameReviewProto,
+        advanced: Option<AdvancedGameAnalysisProto>,
+        depth: u32,
+    ) {
+        let Action::ReanalyzePosition { game_id, ply } = action else {
+            return;
+        };
+        let Some(review) = self.review_state.as_mut() else {
+            return;
+        };
+        if review.review.game_id != *game_id {
+            return;
+        }
+        review.replace_review(patched, advanced);
+        self.notifications
+            .success(format!("Move {} re-analysed at depth {}", ply, depth));
+    }
+
+    /// Take a game's bookmarks, unless another game is under review by now.
+    fn apply_bookmarks(&mut self, action: &Action, bookmarks: Vec<ReviewBookmark>) {
+        let (Action::LoadBookmarks { game_id }
+        | Action::SetBookmark { game_id, .. }
+        | Action::DeleteBookmark { game_id, .. }) = action
+        else {
+            return;
+        };
+        let Some(review) = self.review_state.as_mut() else {
+            return;
+        };
+        if review.review.game_id != *game_id {
+            return;
+        }
+        review.bookmarks = bookmarks;
+    }
+
+    /// Wait for the next finished server call; pending forever while none
+    /// has been dispatched.
+    async fn next_completion(dispatcher: &mut Option<Dispatcher>) -> Completion {
+        match dispatcher {
+            Some(dispatcher) => match dispatcher.recv().await {
+                Some(completion) => completion,
+                None => std::future::pending().await,
+            },
+            None => std::future::pending().await,
+        }
+    }
+
     // --- Internal ---
 
     /// Apply a snapshot from the server — the single update path.
+    /// Squares of a UCI move, if the side to move has a piece on its
+    /// origin; analysis still arriving from the previous position is
+    /// dropped this way.
+    fn pv_move_squares(&self, uci: &str) -> Option<(Square, Square)> {
+        use ::chess::parse_square;
+        let from = parse_square(uci.get(0..2)?)?;
+        let to = parse_square(uci.get(2..4)?)?;
+        (self.board.color_on(from) == Some(self.board.side_to_move())).then_some((from, to))
+    }
+
     fn apply_snapshot(&mut self, snapshot: SessionSnapshot) {
         if let Ok(board) = snapshot.fen.parse::<Board>() {
             self.board = board;
@@ -615,7 +1395,7 @@ impl GameSession {
 
         // Update mode from snapshot if present
         if let Some(ref gm) = snapshot.game_mode {
-            self.mode = game_mode_from_proto(gm);
+            self.mode = GameMode::from(gm);
         }
 
         // Update pause state from phase
@@ -624,10 +1404,48 @@ impl GameSession {
             Some(GamePhase::Paused)
         );
 
+        // The arrow was for the previous position
+        if snapshot.fen != self.snapshot.fen {
+            self.best_move_arrow.reset();
+            self.best_move_squares = None;
+            self.alt_lines.clear();
+        }
+
+        // A held move was picked for a position or phase that is gone
+        // (undo, reset, a fallen flag)
+        if snapshot.fen != self.snapshot.fen || snapshot.phase != self.snapshot.phase {
+            self.pending_move = None;
+        }
+
+        // Premoves were planned on moves that were taken back, or the game is over
+        if snapshot.history.len() < self.snapshot.history.len()
+            || GamePhase::try_from(snapshot.phase).ok() == Some(GamePhase::Ended)
+        {
+            self.premoves.clear();
+        }
+
+        // Evals of undone moves no longer belong to the game
+        let plies = snapshot.history.len();
+        self.live_evals.retain(|&ply, _| ply <= plies);
+
         self.snapshot = snapshot;
     }
 }
 
+/// A UCI score (`cp 25`, `mate -3`) from the side to move, from White's side.
+fn white_relative(score: &str, white_to_move: bool) -> String {
+    if white_to_move {
+        return score.to_string();
+    }
+    match score.split_once(' ') {
+        Some((kind, value)) => match value.parse::<i32>() {
+            Ok(value) => format!("{} {}", kind, -value),
+            Err(_) => score.to_string(),
+        },
+        None => score.to_string(),
+    }
+}
+
 impl Drop for GameSession {
     fn drop(&mut self) {
         // Best effort — can't await in drop
@@ -0,0 +1,51 @@
+//! Exporting studies as PGN files.
+
+use std::path::{Path, PathBuf};
+
+/// Default directory for exported studies.
+pub fn default_export_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|d| d.join("chesstty").join("studies"))
+}
+
+/// File name for a study, from its name, e.g. `sicilian-losses.pgn`.
+pub fn file_name(name: &str) -> String {
+    let slug: Vec<String> = name
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    if slug.is_empty() {
+        "study.pgn".to_string()
+    } else {
+        format!("{}.pgn", slug.join("-"))
+    }
+}
+
+/// Write the study's PGN into `dir`, replacing an earlier export of a study
+/// with the same name. Returns the written file's path.
+pub fn export(dir: &Path, name: &str, pgn: &str) -> std::io::Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(file_name(name));
+    std::fs::write(&path, pgn)?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_name() {
+        assert_eq!(file_name("Sicilian losses"), "sicilian-losses.pgn");
+        assert_eq!(file_name(" B20: as White! "), "b20-as-white.pgn");
+        assert_eq!(file_name("?"), "study.pgn");
+    }
+
+    #[test]
+    fn test_export_writes_pgn() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = export(&dir.path().join("studies"), "Sicilian", "1. e4 c5 *\n").unwrap();
+        assert_eq!(path.file_name().unwrap(), "sicilian.pgn");
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "1. e4 c5 *\n");
+    }
+}
@@ -0,0 +1,302 @@
+//! Lesson definitions and move validation for the beginner tutorial.
+//!
+//! Lessons are plain JSON: each lesson is a list of steps, and each step is a
+//! position, a prompt, an optional set of squares the learner may move from
+//! and a goal the move has to reach. The learner picks one of those pieces,
+//! then one of its legal destinations, so only moves the step allows can be
+//! entered.
+
+use chess::convert_uci_castling_to_cozy;
+use cozy_chess::{Board, File, GameStatus, Move, Square};
+use serde::Deserialize;
+
+use super::TutorialError;
+
+/// Lessons shipped with the client.
+const BUILTIN_LESSONS: &str = include_str!("../../lessons/basics.json");
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Lesson {
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub summary: String,
+    pub steps: Vec<LessonStep>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LessonStep {
+    pub fen: String,
+    pub prompt: String,
+    /// Squares the learner may move from; empty means any piece of the side to move.
+    #[serde(default)]
+    pub movable: Vec<String>,
+    pub goal: StepGoal,
+}
+
+/// What a move has to achieve for a step to count as solved.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StepGoal {
+    /// One of the listed moves (UCI notation, castling as king moves).
+    Moves { moves: Vec<String> },
+    /// Any move that gives check.
+    Check,
+    /// Any move that checkmates.
+    Checkmate,
+}
+
+/// Result of checking a learner's move against a step.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StepOutcome {
+    /// The move solves the step; carries the position after the move.
+    Solved { board: Board, mv: Move },
+    /// The text could not be read as a move.
+    Unreadable,
+    /// The move is not legal in this position.
+    Illegal,
+    /// The move uses a piece the step does not allow.
+    NotAllowed,
+    /// A legal move that does not reach the goal.
+    Incorrect,
+}
+
+/// Parse and validate the lessons bundled with the client.
+pub fn builtin_lessons() -> Result<Vec<Lesson>, TutorialError> {
+    parse_lessons(BUILTIN_LESSONS)
+}
+
+/// Parse a lesson file, rejecting lessons whose steps cannot be played.
+pub fn parse_lessons(json: &str) -> Result<Vec<Lesson>, TutorialError> {
+    let lessons: Vec<Lesson> = serde_json::from_str(json)?;
+    for lesson in &lessons {
+        lesson.validate()?;
+    }
+    Ok(lessons)
+}
+
+impl Lesson {
+    fn validate(&self) -> Result<(), TutorialError> {
+        if self.steps.is_empty() {
+            return Err(TutorialError::InvalidLesson {
+                lesson: self.id.clone(),
+                reason: "lesson has no steps".to_string(),
+            });
+        }
+        for (i, step) in self.steps.iter().enumerate() {
+            let invalid = |reason: String| TutorialError::InvalidLesson {
+                lesson: self.id.clone(),
+                reason: format!("step {}: {}", i + 1, reason),
+            };
+            let board = step.board().map_err(|e| invalid(e.to_string()))?;
+            for sq in &step.movable {
+                sq.parse::<Square>()
+                    .map_err(|_| invalid(format!("invalid square '{}'", sq)))?;
+            }
+            if let StepGoal::Moves { moves } = &step.goal {
+                for uci in moves {
+                    let legal = parse_move(&board, uci).is_some_and(|mv| board.is_legal(mv));
+                    if !legal {
+                        return Err(invalid(format!("expected move '{}' is not legal", uci)));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl LessonStep {
+    pub fn board(&self) -> Result<Board, TutorialError> {
+        self.fen
+            .parse::<Board>()
+            .map_err(|_| TutorialError::InvalidFen(self.fen.clone()))
+    }
+
+    /// Squares the learner may move from.
+    pub fn movable_squares(&self) -> Vec<Square> {
+        self.movable.iter().filter_map(|s| s.parse().ok()).collect()
+    }
+
+    /// Squares the learner can pick a piece on: the allowed ones, or every
+    /// piece of the side to move, that have a legal move.
+    pub fn selectable_squares(&self, board: &Board) -> Vec<Square> {
+        let movable = self.movable_squares();
+        let mut squares = Vec::new();
+        for mv in legal_moves(board) {
+            if (movable.is_empty() || movable.contains(&mv.from)) && !squares.contains(&mv.from) {
+                squares.push(mv.from);
+            }
+        }
+        squares
+    }
+
+    /// Check a move typed by the learner (UCI notation, e.g. `e2e4`).
+    pub fn check(&self, input: &str) -> StepOutcome {
+        let Ok(board) = self.board() else {
+            return StepOutcome::Unreadable;
+        };
+        let Some(mv) = parse_move(&board, input.trim()) else {
+            return StepOutcome::Unreadable;
+        };
+        if !board.is_legal(mv) {
+            return StepOutcome::Illegal;
+        }
+        let movable = self.movable_squares();
+        if !movable.is_empty() && !movable.contains(&mv.from) {
+            return StepOutcome::NotAllowed;
+        }
+
+        let mut after = board.clone();
+        after.play_unchecked(mv);
+        let solved = match &self.goal {
+            StepGoal::Moves { moves } => moves
+                .iter()
+                .filter_map(|uci| parse_move(&board, uci))
+                .any(|expected| expected == mv),
+            StepGoal::Check => !after.checkers().is_empty(),
+            StepGoal::Checkmate => after.status() == GameStatus::Won,
+        };
+        if solved {
+            StepOutcome::Solved { board: after, mv }
+        } else {
+            StepOutcome::Incorrect
+        }
+    }
+}
+
+/// Squares the piece on `from` can move to, with castling shown as the
+/// king's two-square move.
+pub fn legal_targets(board: &Board, from: Square) -> Vec<Square> {
+    let own = board.colors(board.side_to_move());
+    let mut targets = Vec::new();
+    for mv in legal_moves(board).into_iter().filter(|mv| mv.from == from) {
+        let to = if own.has(mv.to) {
+            let file = if mv.to.file() > mv.from.file() {
+                File::G
+            } else {
+                File::C
+            };
+            Square::new(file, mv.from.rank())
+        } else {
+            mv.to
+        };
+        if !targets.contains(&to) {
+            targets.push(to);
+        }
+    }
+    targets
+}
+
+fn legal_moves(board: &Board) -> Vec<Move> {
+    let mut legal = Vec::new();
+    board.generate_moves(|moves| {
+        legal.extend(moves);
+        false
+    });
+    legal
+}
+
+/// Parse a UCI move, mapping standard castling notation to cozy-chess's king-takes-rook form.
+fn parse_move(board: &Board, uci: &str) -> Option<Move> {
+    let mv = uci.parse::<Move>().ok()?;
+    Some(convert_uci_castling_to_cozy(mv, &legal_moves(board)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(fen: &str, movable: &[&str], goal: StepGoal) -> LessonStep {
+        LessonStep {
+            fen: fen.to_string(),
+            prompt: String::new(),
+            movable: movable.iter().map(|s| s.to_string()).collect(),
+            goal,
+        }
+    }
+
+    #[test]
+    fn builtin_lessons_are_valid() {
+        let lessons = builtin_lessons().unwrap();
+        assert!(!lessons.is_empty());
+        for lesson in &lessons {
+            assert!(!lesson.steps.is_empty(), "{} has no steps", lesson.id);
+        }
+    }
+
+    #[test]
+    fn expected_move_is_solved() {
+        let s = step(
+            "4k3/8/8/8/8/8/8/R3K3 w - - 0 1",
+            &["a1"],
+            StepGoal::Moves {
+                moves: vec!["a1a8".to_string()],
+            },
+        );
+        assert!(matches!(s.check("a1a8"), StepOutcome::Solved { .. }));
+        assert_eq!(s.check("a1a5"), StepOutcome::Incorrect);
+        assert_eq!(s.check("a1b2"), StepOutcome::Illegal);
+        assert_eq!(s.check("e1e2"), StepOutcome::NotAllowed);
+        assert_eq!(s.check("hello"), StepOutcome::Unreadable);
+    }
+
+    #[test]
+    fn castling_accepts_standard_notation() {
+        let s = step(
+            "4k3/8/8/8/8/8/5PPP/4K2R w K - 0 1",
+            &["e1"],
+            StepGoal::Moves {
+                moves: vec!["e1g1".to_string()],
+            },
+        );
+        assert!(matches!(s.check("e1g1"), StepOutcome::Solved { .. }));
+    }
+
+    #[test]
+    fn selection_is_limited_to_allowed_pieces_and_their_moves() {
+        let s = step(
+            "4k3/8/8/8/8/8/5PPP/4K2R w K - 0 1",
+            &["e1"],
+            StepGoal::Moves {
+                moves: vec!["e1g1".to_string()],
+            },
+        );
+        let board = s.board().unwrap();
+        assert_eq!(s.selectable_squares(&board), vec![Square::E1]);
+
+        let targets = legal_targets(&board, Square::E1);
+        assert!(targets.contains(&Square::G1));
+        assert!(!targets.contains(&Square::H1));
+        // Own pieces block
+        assert!(!targets.contains(&Square::F2));
+    }
+
+    #[test]
+    fn check_and_checkmate_goals() {
+        let check = step("4k3/8/8/8/8/8/8/3QK3 w - - 0 1", &[], StepGoal::Check);
+        assert!(matches!(check.check("d1h5"), StepOutcome::Solved { .. }));
+        assert_eq!(check.check("d1d2"), StepOutcome::Incorrect);
+
+        let mate = step(
+            "6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1",
+            &[],
+            StepGoal::Checkmate,
+        );
+        assert!(matches!(mate.check("a1a8"), StepOutcome::Solved { .. }));
+        assert_eq!(mate.check("a1a7"), StepOutcome::Incorrect);
+    }
+
+    #[test]
+    fn lessons_with_illegal_expected_moves_are_rejected() {
+        let json = r#"[{"id": "bad", "title": "Bad", "steps": [{
+            "fen": "4k3/8/8/8/8/8/8/R3K3 w - - 0 1",
+            "prompt": "",
+            "goal": {"type": "moves", "moves": ["a1b2"]}
+        }]}]"#;
+        assert!(matches!(
+            parse_lessons(json),
+            Err(TutorialError::InvalidLesson { .. })
+        ));
+    }
+}
@@ -0,0 +1,22 @@
+//! Scripted lessons for absolute beginners.
+
+pub mod lesson;
+pub mod progress;
+
+pub use lesson::{builtin_lessons, legal_targets, Lesson, LessonStep, StepGoal, StepOutcome};
+pub use progress::{current_profile, default_progress_path, TutorialProgress};
+
+#[derive(Debug, thiserror::Error)]
+pub enum TutorialError {
+    #[error("Invalid lesson '{lesson}': {reason}")]
+    InvalidLesson { lesson: String, reason: String },
+
+    #[error("Invalid FEN: {0}")]
+    InvalidFen(String),
+
+    #[error("Malformed tutorial data: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Failed to access tutorial progress: {0}")]
+    Io(#[from] std::io::Error),
+}
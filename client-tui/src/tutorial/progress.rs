@@ -0,0 +1,103 @@
+//! Per-profile tutorial progress, stored as JSON in the user's config directory.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::TutorialError;
+
+/// Profile used when `CHESSTTY_PROFILE` is not set.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Name of the active profile.
+pub fn current_profile() -> String {
+    std::env::var("CHESSTTY_PROFILE")
+        .ok()
+        .filter(|p| !p.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_PROFILE.to_string())
+}
+
+/// Default location of the progress file.
+pub fn default_progress_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join("chesstty").join("tutorial_progress.json"))
+}
+
+/// Completed lessons, keyed by profile name.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TutorialProgress {
+    #[serde(default)]
+    profiles: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl TutorialProgress {
+    /// Load progress from `path`; a missing file means no progress yet.
+    pub fn load(path: &Path) -> Result<Self, TutorialError> {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => Ok(serde_json::from_str(&contents)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), TutorialError> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn is_completed(&self, profile: &str, lesson_id: &str) -> bool {
+        self.profiles
+            .get(profile)
+            .is_some_and(|done| done.contains(lesson_id))
+    }
+
+    /// Record a finished lesson. Returns false if it was already completed.
+    pub fn mark_completed(&mut self, profile: &str, lesson_id: &str) -> bool {
+        self.profiles
+            .entry(profile.to_string())
+            .or_default()
+            .insert(lesson_id.to_string())
+    }
+
+    pub fn completed_count(&self, profile: &str) -> usize {
+        self.profiles.get(profile).map_or(0, |done| done.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn progress_is_tracked_per_profile() {
+        let mut progress = TutorialProgress::default();
+        assert!(progress.mark_completed("alice", "rook"));
+        assert!(!progress.mark_completed("alice", "rook"));
+
+        assert!(progress.is_completed("alice", "rook"));
+        assert!(!progress.is_completed("bob", "rook"));
+        assert_eq!(progress.completed_count("alice"), 1);
+        assert_eq!(progress.completed_count("bob"), 0);
+    }
+
+    #[test]
+    fn progress_round_trips_through_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("progress.json");
+
+        assert_eq!(
+            TutorialProgress::load(&path).unwrap(),
+            TutorialProgress::default()
+        );
+
+        let mut progress = TutorialProgress::default();
+        progress.mark_completed(DEFAULT_PROFILE, "knight");
+        progress.save(&path).unwrap();
+
+        let loaded = TutorialProgress::load(&path).unwrap();
+        assert!(loaded.is_completed(DEFAULT_PROFILE, "knight"));
+    }
+}
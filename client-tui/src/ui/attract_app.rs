@@ -0,0 +1,404 @@
+use crate::attract::{famous_games, DemoPly, FamousGame};
+use crate::dispatch::Action;
+use crate::state::{GameMode, GameSession};
+use crate::ui::widgets::board_overlay::{BoardOverlay, OverlayColor};
+use crate::ui::widgets::engine_panel::parse_score;
+use crate::ui::widgets::BoardWidget;
+use chess_client::{GameModeProto, GameModeType};
+use cozy_chess::Board;
+use crossterm::{
+    event::{self, Event},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame, Terminal,
+};
+use std::io;
+use std::time::{Duration, Instant};
+
+/// Time each famous-game move stays on screen.
+const MOVE_PAUSE: Duration = Duration::from_millis(2000);
+
+/// Extra time for a move with an annotation, to read it.
+const NOTE_PAUSE: Duration = Duration::from_millis(3000);
+
+/// Time the final position stays on screen before the next game.
+const END_PAUSE: Duration = Duration::from_secs(8);
+
+/// Engine strength and speed for the engine-vs-engine showings.
+const ENGINE_SKILL: u8 = 12;
+const ENGINE_MOVE_DELAY_MS: u32 = 1500;
+
+/// Engine games are cut short after this many plies.
+const ENGINE_PLY_LIMIT: usize = 200;
+
+/// Run the demo until any key is pressed. Engine games are skipped while
+/// the server is offline.
+pub async fn run_attract(offline: bool) -> anyhow::Result<()> {
+    let games = famous_games()?;
+    let mut app = AttractApp::new(games, offline)?;
+
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+
+    let result = loop {
+        if let Err(e) = terminal.draw(|f| app.render(f)) {
+            break Err(e.into());
+        }
+        match event::poll(Duration::from_millis(100)) {
+            // Any key ends the demo
+            Ok(true) => match event::read() {
+                Ok(Event::Key(_)) => break Ok(()),
+                Ok(_) => {}
+                Err(e) => break Err(e.into()),
+            },
+            Ok(false) => {}
+            Err(e) => break Err(e.into()),
+        }
+        app.advance().await;
+    };
+    app.stop().await;
+
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+
+    result
+}
+
+/// A famous game being replayed.
+struct Replay {
+    game: usize,
+    plies: Vec<DemoPly>,
+    /// Moves shown so far.
+    shown: usize,
+    next_at: Instant,
+}
+
+/// What is on screen.
+enum Showing {
+    Famous(Replay),
+    Engine {
+        session: Box<GameSession>,
+        /// Set once the game ended or hit the ply limit.
+        ended_at: Option<Instant>,
+    },
+}
+
+struct AttractApp {
+    games: Vec<FamousGame>,
+    offline: bool,
+    /// Famous game to show next.
+    next_game: usize,
+    showing: Showing,
+}
+
+impl AttractApp {
+    fn new(games: Vec<FamousGame>, offline: bool) -> anyhow::Result<Self> {
+        if games.is_empty() {
+            anyhow::bail!("No demo games");
+        }
+        let showing = Showing::Famous(Self::replay(&games, 0)?);
+        Ok(Self {
+            games,
+            offline,
+            next_game: 1,
+            showing,
+        })
+    }
+
+    fn replay(games: &[FamousGame], game: usize) -> anyhow::Result<Replay> {
+        Ok(Replay {
+            game,
+            plies: games[game].plies().map_err(anyhow::Error::msg)?,
+            shown: 0,
+            next_at: Instant::now() + MOVE_PAUSE,
+        })
+    }
+
+    /// Step the current showing, moving on once it is over.
+    async fn advance(&mut self) {
+        let now = Instant::now();
+        let finished = match self.showing {
+            Showing::Famous(ref mut replay) => {
+                if now < replay.next_at {
+                    return;
+                }
+                if replay.shown == replay.plies.len() {
+                    true
+                } else {
+                    replay.shown += 1;
+                    let game = &self.games[replay.game];
+                    let pause = if game.notes.contains_key(&replay.shown) {
+                        MOVE_PAUSE + NOTE_PAUSE
+                    } else {
+                        MOVE_PAUSE
+                    };
+                    replay.next_at = now
+                        + if replay.shown == replay.plies.len() {
+                            END_PAUSE
+                        } else {
+                            pause
+                        };
+                    false
+                }
+            }
+            Showing::Engine {
+                ref mut session,
+                ref mut ended_at,
+            } => {
+                while let Ok(true) = session.poll_events().await {}
+                if ended_at.is_none()
+                    && (session.is_finished() || session.history().len() >= ENGINE_PLY_LIMIT)
+                {
+                    *ended_at = Some(now);
+                }
+                ended_at.is_some_and(|at| now >= at + END_PAUSE)
+            }
+        };
+        if finished {
+            self.next().await;
+        }
+    }
+
+    /// Show the next game: every famous game is followed by an engine game
+    /// while the server is reachable.
+    async fn next(&mut self) {
+        let after_famous = matches!(self.showing, Showing::Famous(_));
+        self.stop().await;
+        if after_famous && !self.offline {
+            match start_engine_game().await {
+                Ok(session) => {
+                    self.showing = Showing::Engine {
+                        session: Box::new(session),
+                        ended_at: None,
+                    };
+                    return;
+                }
+                Err(e) => tracing::warn!("Demo engine game unavailable: {}", e),
+            }
+        }
+        let game = self.next_game;
+        self.next_game = (game + 1) % self.games.len();
+        match Self::replay(&self.games, game) {
+            Ok(replay) => self.showing = Showing::Famous(replay),
+            Err(e) => tracing::warn!("Skipping demo game: {}", e),
+        }
+    }
+
+    /// Close the engine game's session, if one is running.
+    async fn stop(&mut self) {
+        if let Showing::Engine {
+            ref mut session, ..
+        } = self.showing
+        {
+            if let Err(e) = session.client.close_session().await {
+                tracing::warn!("Failed to close demo session: {}", e);
+            }
+        }
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .split(frame.area());
+
+        let label = Style::default()
+            .fg(Color::Cyan)
+            .add_modifier(Modifier::BOLD);
+        let title = Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD);
+        let (board, last_move, mut lines) = match self.showing {
+            Showing::Famous(ref replay) => {
+                let game = &self.games[replay.game];
+                let (board, last_move) = match replay.shown.checked_sub(1) {
+                    Some(i) => {
+                        let ply = &replay.plies[i];
+                        (ply.board.clone(), Some((ply.mv.from, ply.mv.to)))
+                    }
+                    None => (Board::default(), None),
+                };
+                let mut lines = vec![
+                    Line::from(Span::styled(game.title.clone(), title)),
+                    Line::from(Span::styled(
+                        game.event.clone(),
+                        Style::default().fg(Color::Gray),
+                    )),
+                    Line::raw(""),
+                    Line::from(vec![
+                        Span::styled("White: ", label),
+                        Span::raw(game.white.clone()),
+                    ]),
+                    Line::from(vec![
+                        Span::styled("Black: ", label),
+                        Span::raw(game.black.clone()),
+                    ]),
+                    Line::raw(""),
+                    move_line(
+                        replay.shown,
+                        replay
+                            .shown
+                            .checked_sub(1)
+                            .map(|i| replay.plies[i].san.as_str()),
+                        label,
+                    ),
+                ];
+                if let Some(note) = game.note(replay.shown) {
+                    lines.push(Line::raw(""));
+                    lines.push(Line::from(Span::styled(
+                        note.to_string(),
+                        Style::default().add_modifier(Modifier::ITALIC),
+                    )));
+                }
+                if replay.shown == replay.plies.len() {
+                    lines.push(Line::raw(""));
+                    lines.push(Line::from(Span::styled(game.result.clone(), title)));
+                }
+                (board, last_move, lines)
+            }
+            Showing::Engine {
+                ref session,
+                ended_at,
+            } => {
+                let history = session.history();
+                let mut lines = vec![
+                    Line::from(Span::styled("Engine vs Engine", title)),
+                    Line::from(Span::styled(
+                        format!("Both sides at skill {}", ENGINE_SKILL),
+                        Style::default().fg(Color::Gray),
+                    )),
+                    Line::raw(""),
+                    move_line(history.len(), history.last().map(|m| m.san.as_str()), label),
+                ];
+                if let Some(score) = session
+                    .engine_info
+                    .as_ref()
+                    .and_then(|i| i.score.as_deref())
+                {
+                    let (text, color) = parse_score(score);
+                    lines.push(Line::from(vec![
+                        Span::styled("Eval: ", label),
+                        Span::styled(text, Style::default().fg(color)),
+                    ]));
+                }
+                if ended_at.is_some() {
+                    lines.push(Line::raw(""));
+                    lines.push(Line::from(Span::styled("Game over", title)));
+                }
+                (session.board().clone(), session.last_move, lines)
+            }
+        };
+
+        let mut overlay = BoardOverlay::new();
+        if let Some((from, to)) = last_move {
+            overlay.tint(from, OverlayColor::LastMove);
+            overlay.tint(to, OverlayColor::LastMove);
+        }
+        frame.render_widget(
+            BoardWidget {
+                board: &board,
+                overlay: &overlay,
+                flipped: false,
+            },
+            chunks[0],
+        );
+
+        lines.push(Line::raw(""));
+        lines.push(Line::from(Span::styled(
+            "Press any key to return to the menu",
+            Style::default().fg(Color::DarkGray),
+        )));
+        let block = Block::default()
+            .title("♔ ChessTTY - Demo ♔")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan));
+        frame.render_widget(
+            Paragraph::new(lines)
+                .block(block)
+                .wrap(Wrap { trim: false }),
+            chunks[1],
+        );
+    }
+}
+
+/// `Move: 12. O-O-O` or `Move: 12... Rd8` after `plies` moves.
+fn move_line(plies: usize, san: Option<&str>, label: Style) -> Line<'static> {
+    let text = match san {
+        Some(san) if plies % 2 == 1 => format!("{}. {}", plies.div_ceil(2), san),
+        Some(san) => format!("{}... {}", plies / 2, san),
+        None => "Starting position".to_string(),
+    };
+    Line::from(vec![Span::styled("Move: ", label), Span::raw(text)])
+}
+
+/// Start an engine-vs-engine game on the server.
+async fn start_engine_game() -> Result<GameSession, Box<dyn std::error::Error>> {
+    let mode = GameModeProto {
+        mode: GameModeType::EngineVsEngine as i32,
+        human_side: None,
+    };
+    let mut session =
+        GameSession::new_labeled("http://[::1]:50051", None, Some(mode), None, None, None).await?;
+    session.mode = GameMode::EngineVsEngine;
+    session.skill_level = ENGINE_SKILL;
+    session.start_event_stream().await?;
+    session.dispatch(Action::SetMoveDelay(ENGINE_MOVE_DELAY_MS));
+    session
+        .set_engine_full(true, ENGINE_SKILL, Some(1), Some(16))
+        .await?;
+    Ok(session)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_move_line() {
+        let label = Style::default();
+        assert_eq!(
+            move_line(0, None, label).to_string(),
+            "Move: Starting position"
+        );
+        assert_eq!(
+            move_line(23, Some("O-O-O"), label).to_string(),
+            "Move: 12. O-O-O"
+        );
+        assert_eq!(
+            move_line(24, Some("Rd8"), label).to_string(),
+            "Move: 12... Rd8"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_offline_demo_cycles_famous_games() {
+        let mut app = AttractApp::new(famous_games().unwrap(), true).unwrap();
+        let plies = match app.showing {
+            Showing::Famous(ref replay) => replay.plies.len(),
+            Showing::Engine { .. } => panic!("demo should open with a famous game"),
+        };
+        // Every move, then the final pause
+        for _ in 0..=plies {
+            if let Showing::Famous(ref mut replay) = app.showing {
+                replay.next_at = Instant::now();
+            }
+            app.advance().await;
+        }
+        let Showing::Famous(ref replay) = app.showing else {
+            panic!("offline demo should only show famous games");
+        };
+        assert_eq!(replay.game, 1);
+        assert_eq!(replay.shown, 0);
+    }
+}
@@ -0,0 +1,587 @@
+//! Headless driver for end-to-end tests and demo recordings.
+//!
+//! `client-tui --driver <script>` plays a script against the server without
+//! a terminal: the game is drawn into an in-memory buffer and input goes
+//! through the same handlers as typed keys. Each line is one step:
+//!
+//! ```text
+//! # Two moves, then the FEN/PGN panel
+//! start mode=hve side=white skill=3
+//! move e4
+//! wait turn
+//! expect moves 2
+//! keys ^
+//! screenshot out/opening.txt
+//! quit
+//! ```
+//!
+//! `start` takes `mode=hvh|hve|eve|analysis`, `side=white|black`,
+//! `skill=N`, `fen="..."` and `time=SECONDS`. `move` plays a move in either
+//! of the forms external input accepts and waits until it is in the
+//! history; `keys` presses keys and `query` prints the answer to a
+//! screen-reader command, also as external input does. `wait` waits until
+//! the server has gone quiet, `wait turn` until the human is to move or the
+//! game is over and `wait 500` for that many milliseconds; the first two
+//! fail after 30 seconds, so use a fixed time while engines play each
+//! other. `expect` checks `fen`, `side`, `moves`, `last` (SAN of the last
+//! move), `status`, `phase`, `paused` or `notification` (the latest
+//! message contains the text). `screenshot` writes the screen as text, at
+//! the size set by `size 120x40`. The first failing step stops the script
+//! with an error naming its line.
+
+use super::fsm::renderer::Renderer;
+use super::fsm::{UiMode, UiStateMachine};
+use super::input::{self, AppAction};
+use super::menu_app::GameConfig;
+use super::render_loop::start_session;
+use crate::external_input::Request;
+use crate::state::{GameMode, GameSession, PlayerSide};
+use anyhow::{anyhow, bail, Context};
+use ratatui::backend::TestBackend;
+use ratatui::buffer::Buffer;
+use ratatui::Terminal;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Screen size until a `size` step changes it.
+const DEFAULT_SIZE: (u16, u16) = (120, 40);
+
+/// How long the server must stay silent for `wait` to finish.
+const QUIET_PERIOD: Duration = Duration::from_millis(250);
+
+/// Longest any wait may take before the step fails.
+const WAIT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// What a `wait` step waits for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaitFor {
+    /// No server event for [`QUIET_PERIOD`].
+    Quiet,
+    /// The human is to move, or the game is over.
+    Turn,
+    Millis(u64),
+}
+
+/// One line of a script.
+#[derive(Debug, Clone)]
+pub enum Step {
+    Start(Box<GameConfig>),
+    Move(String),
+    Keys(Vec<String>),
+    Query(String),
+    Wait(WaitFor),
+    Expect { field: String, value: String },
+    Size(u16, u16),
+    Screenshot(PathBuf),
+    Quit,
+}
+
+const FIELDS: [&str; 8] = [
+    "fen",
+    "side",
+    "moves",
+    "last",
+    "status",
+    "phase",
+    "paused",
+    "notification",
+];
+
+/// Split a line into words; double quotes group words with spaces, as in
+/// `fen="8/8/8/8/8/8/8/K1k5 w - - 0 1"`.
+fn split_words(line: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut started = false;
+    for c in line.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                started = true;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if started {
+                    words.push(std::mem::take(&mut current));
+                    started = false;
+                }
+            }
+            c => {
+                current.push(c);
+                started = true;
+            }
+        }
+    }
+    if quoted {
+        return Err("Unclosed quote".to_string());
+    }
+    if started {
+        words.push(current);
+    }
+    Ok(words)
+}
+
+fn parse_start(options: &[String]) -> Result<GameConfig, String> {
+    let mut config = GameConfig {
+        mode: GameMode::HumanVsHuman,
+        skill_level: 10,
+        start_fen: None,
+        time_control_seconds: None,
+        engine_threads: None,
+        engine_hash_mb: None,
+        engine_lines: 1,
+        resume_session_id: None,
+        resume_game_mode: None,
+        resume_human_side: None,
+        resume_skill_level: None,
+        transfer_code: None,
+        review_data: None,
+        review_game_mode: None,
+        review_skill_level: None,
+        review_start_ply: None,
+        pre_history: None,
+        advanced_data: None,
+        simul_boards: 1,
+        opponent: None,
+        screen_reader: false,
+        notation: chess::Notation::default(),
+        live_evals: false,
+        confirm_moves: false,
+    };
+    let mut mode = "hvh";
+    let mut side = PlayerSide::White;
+    for option in options {
+        let (key, value) = option
+            .split_once('=')
+            .ok_or_else(|| format!("Expected key=value, got {}", option))?;
+        let number = || {
+            value
+                .parse::<u64>()
+                .map_err(|_| format!("Bad {}: {}", key, value))
+        };
+        match key {
+            "mode" => mode = value,
+            "side" => {
+                side = match value {
+                    "white" => PlayerSide::White,
+                    "black" => PlayerSide::Black,
+                    _ => return Err(format!("Bad side: {}", value)),
+                }
+            }
+            "skill" => config.skill_level = number()?.min(20) as u8,
+            "time" => config.time_control_seconds = Some(number()?),
+            "lines" => {
+                config.engine_lines =
+                    number()?.clamp(1, crate::state::MAX_ENGINE_LINES as u64) as u32
+            }
+            "fen" => config.start_fen = Some(value.to_string()),
+            _ => return Err(format!("Unknown start option: {}", key)),
+        }
+    }
+    config.mode = match mode {
+        "hvh" => GameMode::HumanVsHuman,
+        "hve" => GameMode::HumanVsEngine { human_side: side },
+        "eve" => GameMode::EngineVsEngine,
+        "analysis" => GameMode::Analysis,
+        _ => return Err(format!("Bad mode: {}", mode)),
+    };
+    Ok(config)
+}
+
+fn parse_step(words: &[String]) -> Result<Step, String> {
+    let rest = &words[1..];
+    let joined = || rest.join(" ");
+    let step = match words[0].as_str() {
+        "start" => Step::Start(Box::new(parse_start(rest)?)),
+        "move" if !rest.is_empty() => Step::Move(joined()),
+        "keys" if !rest.is_empty() => Step::Keys(rest.to_vec()),
+        "query" if !rest.is_empty() => Step::Query(joined()),
+        "wait" => Step::Wait(match rest.first().map(String::as_str) {
+            None => WaitFor::Quiet,
+            Some("turn") => WaitFor::Turn,
+            Some(ms) => WaitFor::Millis(ms.parse().map_err(|_| format!("Bad wait: {}", ms))?),
+        }),
+        "expect" if rest.len() >= 2 => {
+            if !FIELDS.contains(&rest[0].as_str()) {
+                return Err(format!(
+                    "Unknown field {}, expected one of {}",
+                    rest[0],
+                    FIELDS.join(", ")
+                ));
+            }
+            Step::Expect {
+                field: rest[0].clone(),
+                value: rest[1..].join(" "),
+            }
+        }
+        "size" if rest.len() == 1 => {
+            let (width, height) = rest[0]
+                .split_once('x')
+                .and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?)))
+                .ok_or_else(|| format!("Bad size: {}", rest[0]))?;
+            Step::Size(width, height)
+        }
+        "screenshot" if rest.len() == 1 => Step::Screenshot(PathBuf::from(&rest[0])),
+        "quit" if rest.is_empty() => Step::Quit,
+        other => return Err(format!("Bad step: {}", other)),
+    };
+    Ok(step)
+}
+
+/// Parse a script into its steps, each with its line number.
+pub fn parse_script(text: &str) -> Result<Vec<(usize, Step)>, String> {
+    let mut steps = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line_no = index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let step = split_words(line)
+            .and_then(|words| parse_step(&words))
+            .map_err(|e| format!("line {}: {}", line_no, e))?;
+        steps.push((line_no, step));
+    }
+    Ok(steps)
+}
+
+/// The screen as text, one line per row without trailing spaces.
+pub fn buffer_text(buffer: &Buffer) -> String {
+    let width = buffer.area.width as usize;
+    let mut text = String::new();
+    for row in buffer.content.chunks(width.max(1)) {
+        let line: String = row.iter().map(|cell| cell.symbol()).collect();
+        text.push_str(line.trim_end());
+        text.push('\n');
+    }
+    text
+}
+
+/// A game started by the script.
+struct Game {
+    state: GameSession,
+    fsm: UiStateMachine,
+    input_buffer: String,
+}
+
+struct Driver {
+    terminal: Terminal<TestBackend>,
+    game: Option<Game>,
+}
+
+impl Driver {
+    fn game(&mut self) -> anyhow::Result<&mut Game> {
+        self.game
+            .as_mut()
+            .ok_or_else(|| anyhow!("No game started; use start first"))
+    }
+
+    /// Close the current game's server session, if there is one.
+    async fn close(&mut self) {
+        if let Some(mut game) = self.game.take() {
+            if let Err(e) = game.state.client.close_session().await {
+                tracing::warn!("Failed to close driver session: {}", e);
+            }
+        }
+    }
+
+    /// Apply an action returned by the input handlers.
+    async fn apply(&mut self, action: AppAction) -> anyhow::Result<()> {
+        match action {
+            AppAction::Continue | AppAction::NextBoard => Ok(()),
+            AppAction::Quit | AppAction::ReturnToMenu | AppAction::SuspendAndReturnToMenu => {
+                self.close().await;
+                Ok(())
+            }
+            AppAction::PlaySnapshot(_) => {
+                bail!("Playing a snapshot is not supported by the driver")
+            }
+            AppAction::BranchAnalysis { .. } => {
+                bail!("Branching from a review is not supported by the driver")
+            }
+        }
+    }
+
+    /// Run one step. Returns lines to print below the step.
+    async fn run(&mut self, step: Step) -> anyhow::Result<Vec<String>> {
+        match step {
+            Step::Start(config) => {
+                self.close().await;
+                let state = start_session(&config).await?;
+                let mut fsm = UiStateMachine::default();
+                fsm.notation = config.notation;
+                fsm.transition_to(UiMode::GameBoard);
+                let mut game = Game {
+                    state,
+                    fsm,
+                    input_buffer: String::new(),
+                };
+                while let Ok(true) = game.state.poll_events().await {}
+                self.game = Some(game);
+            }
+            Step::Move(text) => {
+                let game = self.game()?;
+                let before = game.state.history().len();
+                let request = Request::Move { text: text.clone() };
+                let (_, reply) = input::handle_external(
+                    &mut game.state,
+                    &mut game.fsm,
+                    &mut game.input_buffer,
+                    &request,
+                );
+                if let Some(error) = reply.error {
+                    bail!(error);
+                }
+                let deadline = Instant::now() + WAIT_TIMEOUT;
+                while self.game()?.state.history().len() == before {
+                    let game = self.game()?;
+                    if Instant::now() >= deadline {
+                        let reason = game
+                            .state
+                            .notifications
+                            .latest()
+                            .map(|n| n.message.clone())
+                            .unwrap_or_else(|| "timed out".to_string());
+                        bail!("{} was not played: {}", text, reason);
+                    }
+                    poll_once(&mut game.state, deadline - Instant::now()).await;
+                }
+            }
+            Step::Keys(keys) => {
+                let game = self.game()?;
+                let (action, reply) = input::handle_external(
+                    &mut game.state,
+                    &mut game.fsm,
+                    &mut game.input_buffer,
+                    &Request::Keys { keys },
+                );
+                if let Some(error) = reply.error {
+                    bail!(error);
+                }
+                self.apply(action).await?;
+            }
+            Step::Query(query) => {
+                let game = self.game()?;
+                let (_, reply) = input::handle_external(
+                    &mut game.state,
+                    &mut game.fsm,
+                    &mut game.input_buffer,
+                    &Request::Query { query },
+                );
+                return Ok(reply.lines);
+            }
+            Step::Wait(until) => self.settle(until).await?,
+            Step::Expect { field, value } => {
+                let actual = field_value(&self.game()?.state, &field);
+                let matched = match field.as_str() {
+                    "notification" => actual.contains(&value),
+                    _ => actual == value,
+                };
+                if !matched {
+                    bail!("expected {} {:?}, found {:?}", field, value, actual);
+                }
+            }
+            Step::Size(width, height) => self.terminal.backend_mut().resize(width, height),
+            Step::Screenshot(path) => {
+                let text = self.draw()?;
+                write_screenshot(&path, &text)?;
+                return Ok(vec![format!("wrote {}", path.display())]);
+            }
+            Step::Quit => self.close().await,
+        }
+        Ok(Vec::new())
+    }
+
+    /// Wait as `until` asks, applying server events meanwhile.
+    async fn settle(&mut self, until: WaitFor) -> anyhow::Result<()> {
+        let game = self.game()?;
+        let started = Instant::now();
+        loop {
+            let elapsed = started.elapsed();
+            let remaining = match until {
+                WaitFor::Millis(ms) => match Duration::from_millis(ms).checked_sub(elapsed) {
+                    Some(remaining) if !remaining.is_zero() => remaining,
+                    _ => return Ok(()),
+                },
+                _ if elapsed >= WAIT_TIMEOUT => bail!("wait timed out"),
+                WaitFor::Turn => {
+                    if game.state.awaiting_human_move() || game.state.is_finished() {
+                        return Ok(());
+                    }
+                    WAIT_TIMEOUT - elapsed
+                }
+                WaitFor::Quiet => QUIET_PERIOD,
+            };
+            if !poll_once(&mut game.state, remaining).await && until == WaitFor::Quiet {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Draw the current game and return the screen as text.
+    fn draw(&mut self) -> anyhow::Result<String> {
+        let game = self
+            .game
+            .as_mut()
+            .ok_or_else(|| anyhow!("No game started"))?;
+        let (state, fsm) = (&mut game.state, &game.fsm);
+        state.notifications.expire(Instant::now());
+        self.terminal.draw(|f| {
+            let layout = fsm.layout(state);
+            Renderer::render(f, f.area(), &layout, state, fsm);
+        })?;
+        Ok(buffer_text(self.terminal.backend().buffer()))
+    }
+}
+
+/// Apply the next server event, waiting at most `timeout`. Returns whether
+/// one arrived.
+async fn poll_once(state: &mut GameSession, timeout: Duration) -> bool {
+    match tokio::time::timeout(timeout, state.poll_event_async()).await {
+        Ok(Ok(())) => true,
+        Ok(Err(e)) => {
+            tracing::warn!("Error polling server events: {}", e);
+            // A closed stream would otherwise return at once, forever
+            tokio::time::sleep(timeout.min(QUIET_PERIOD)).await;
+            false
+        }
+        Err(_) => false,
+    }
+}
+
+/// The value `expect` compares for `field`.
+fn field_value(state: &GameSession, field: &str) -> String {
+    match field {
+        "fen" => state.snapshot.fen.clone(),
+        "side" => state.side_to_move().to_string(),
+        "moves" => state.history().len().to_string(),
+        "last" => state
+            .history()
+            .last()
+            .map(|m| m.san.clone())
+            .unwrap_or_default(),
+        "status" => chess_client::GameStatus::try_from(state.status())
+            .map(|s| s.as_str_name().to_lowercase())
+            .unwrap_or_default(),
+        "phase" => chess_client::GamePhase::try_from(state.snapshot.phase)
+            .map(|p| {
+                p.as_str_name()
+                    .trim_start_matches("GAME_PHASE_")
+                    .to_lowercase()
+            })
+            .unwrap_or_default(),
+        "paused" => state.paused.to_string(),
+        "notification" => state
+            .notifications
+            .latest()
+            .map(|n| n.message.clone())
+            .unwrap_or_default(),
+        _ => String::new(),
+    }
+}
+
+fn write_screenshot(path: &Path, text: &str) -> anyhow::Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, text).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Run the script at `path`, printing each step as it passes.
+pub async fn run_script(path: &Path) -> anyhow::Result<()> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let steps = parse_script(&text).map_err(|e| anyhow!("{}: {}", path.display(), e))?;
+    let source: Vec<&str> = text.lines().collect();
+
+    let (width, height) = DEFAULT_SIZE;
+    let mut driver = Driver {
+        terminal: Terminal::new(TestBackend::new(width, height))?,
+        game: None,
+    };
+    let count = steps.len();
+    for (line_no, step) in steps {
+        let quit = matches!(step, Step::Quit);
+        let line = source[line_no - 1].trim();
+        match driver.run(step).await {
+            Ok(output) => {
+                println!("ok    {:>3}: {}", line_no, line);
+                for out in output {
+                    println!("           {}", out);
+                }
+            }
+            Err(e) => {
+                println!("FAIL  {:>3}: {}", line_no, line);
+                driver.close().await;
+                bail!("{}:{}: {}", path.display(), line_no, e);
+            }
+        }
+        if quit {
+            break;
+        }
+    }
+    driver.close().await;
+    println!("{} steps passed", count);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_words() {
+        assert_eq!(
+            split_words(r#"start mode=hve fen="8/8 w - - 0 1"  skill=3"#).unwrap(),
+            ["start", "mode=hve", "fen=8/8 w - - 0 1", "skill=3"]
+        );
+        assert_eq!(
+            split_words(r#"expect last """#).unwrap(),
+            ["expect", "last", ""]
+        );
+        assert!(split_words(r#"expect fen "8/8"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_script() {
+        let script = "# demo\nstart mode=hve side=black skill=3 time=300\n\nmove Nf3\nkeys ^ y\nwait turn\nexpect moves 2\nsize 80x24\nquit\n";
+        let steps = parse_script(script).unwrap();
+        assert_eq!(steps.len(), 7);
+        assert_eq!(steps[0].0, 2);
+        let Step::Start(ref config) = steps[0].1 else {
+            panic!("expected start");
+        };
+        assert_eq!(
+            config.mode,
+            GameMode::HumanVsEngine {
+                human_side: PlayerSide::Black
+            }
+        );
+        assert_eq!(config.skill_level, 3);
+        assert_eq!(config.time_control_seconds, Some(300));
+        assert!(matches!(steps[3].1, Step::Wait(WaitFor::Turn)));
+        assert!(matches!(steps[5].1, Step::Size(80, 24)));
+    }
+
+    #[test]
+    fn test_parse_errors_name_line() {
+        assert_eq!(
+            parse_script("start\njump 3").unwrap_err(),
+            "line 2: Bad step: jump"
+        );
+        assert!(parse_script("start mode=chess")
+            .unwrap_err()
+            .contains("Bad mode"));
+        assert!(parse_script("expect colour white")
+            .unwrap_err()
+            .contains("Unknown field"));
+    }
+
+    #[test]
+    fn test_buffer_text() {
+        let mut buffer = Buffer::empty(ratatui::layout::Rect::new(0, 0, 6, 2));
+        buffer.set_string(0, 0, "ab", ratatui::style::Style::default());
+        buffer.set_string(2, 1, "cd", ratatui::style::Style::default());
+        assert_eq!(buffer_text(&buffer), "ab\n  cd\n");
+    }
+}
@@ -12,6 +12,11 @@ pub enum Component {
     ReviewTabs,
     ReviewSummary,
     AdvancedAnalysis,
+    AdvancedTabs,
+    TimelineScrubber,
+    EvalSparkline,
+    MoveCommentary,
+    PositionPanel,
 }
 
 pub struct ComponentProperties {
@@ -86,6 +91,36 @@ impl ComponentProperties {
                 is_selectable: true,
                 is_expandable: true,
             },
+            Component::AdvancedTabs => ComponentProperties {
+                component: Component::AdvancedTabs,
+                title: "Advanced Tabs",
+                is_selectable: false,
+                is_expandable: false,
+            },
+            Component::TimelineScrubber => ComponentProperties {
+                component: Component::TimelineScrubber,
+                title: "Timeline",
+                is_selectable: false,
+                is_expandable: false,
+            },
+            Component::EvalSparkline => ComponentProperties {
+                component: Component::EvalSparkline,
+                title: "Eval Graph",
+                is_selectable: false,
+                is_expandable: false,
+            },
+            Component::MoveCommentary => ComponentProperties {
+                component: Component::MoveCommentary,
+                title: "Commentary",
+                is_selectable: false,
+                is_expandable: false,
+            },
+            Component::PositionPanel => ComponentProperties {
+                component: Component::PositionPanel,
+                title: "FEN / PGN",
+                is_selectable: true,
+                is_expandable: true,
+            },
         }
     }
 }
@@ -174,6 +209,8 @@ mod tests {
         assert_eq!(Component::TabInput.number_key(&mode), None);
         assert_eq!(Component::Controls.number_key(&mode), None);
         assert_eq!(Component::ReviewTabs.number_key(&mode), None);
+        assert_eq!(Component::AdvancedTabs.number_key(&mode), None);
+        assert_eq!(Component::TimelineScrubber.number_key(&mode), None);
     }
 
     #[test]
@@ -8,8 +8,17 @@ pub mod hooks;
 pub mod render_spec;
 pub mod renderer;
 
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
 
+use ratatui::layout::Rect;
+
+use crate::narration::Narration;
+use crate::profiling::FrameProfile;
+use crate::ui::widgets::advanced_analysis_panel::AdvancedTab;
+use crate::ui::widgets::board::BoardCache;
+use crate::ui::widgets::board_overlay::Heatmap;
+use crate::ui::widgets::timeline_scrubber::KeyAcceleration;
 use render_spec::{Control, InputPhase, Layout, Section, SectionContent, TabInputState};
 
 #[derive(Default)]
@@ -33,7 +42,17 @@ pub struct UiStateMachine {
     pub input_phase: InputPhase,
     pub popup_menu: Option<crate::ui::widgets::popup_menu::PopupMenuState>,
     pub snapshot_dialog: Option<crate::ui::widgets::snapshot_dialog::SnapshotDialogState>,
+    /// Note being written for a review bookmark, while the dialog is open.
+    pub bookmark_dialog: Option<crate::ui::widgets::bookmark_dialog::BookmarkDialogState>,
+    /// Control of the square examined on the analysis board, while shown.
+    pub square_control: Option<analysis::board_analysis::SquareControl>,
+    pub notification_history: Option<crate::ui::widgets::toast::NotificationHistoryState>,
+    /// Chat pane of the session, while it is open.
+    pub chat: Option<crate::ui::widgets::chat_pane::ChatPaneState>,
     pub review_tab: u8,
+    pub advanced_tab: AdvancedTab,
+    /// Scroll offset of each advanced analysis tab, kept across tab switches.
+    pub advanced_tab_scroll: HashMap<AdvancedTab, u16>,
     #[allow(dead_code)] // used once review board navigation is complete
     pub review_moves_selection: Option<u32>,
     pub selected_promotion_piece: cozy_chess::Piece,
@@ -42,6 +61,37 @@ pub struct UiStateMachine {
     pub visibility: HashMap<Component, bool>,
     pub scroll_state: HashMap<Component, u16>,
     pub typeahead_squares: Vec<cozy_chess::Square>,
+    /// Track of the review timeline as last drawn, for mapping mouse clicks.
+    pub scrubber_track: Cell<Option<Rect>>,
+    /// Track of the review eval graph as last drawn, for mapping mouse clicks.
+    pub sparkline_track: Cell<Option<Rect>>,
+    /// The timeline or eval graph the left button went down on, while it is
+    /// still held.
+    pub dragging: Option<Component>,
+    pub review_key_accel: KeyAcceleration,
+    /// Whether the frame timing overlay is drawn.
+    pub show_profiler: bool,
+    /// Frame timings recorded by the render loop.
+    pub frame_profile: FrameProfile,
+    /// The board as last drawn, so unchanged squares are not drawn again.
+    pub board_cache: RefCell<BoardCache>,
+    /// Screen-reader mode: the board is replaced by a narrated transcript.
+    pub screen_reader: bool,
+    pub narration: Narration,
+    /// Command typed at the screen-reader prompt, while it is open.
+    pub command_line: Option<String>,
+    /// How moves are written in the history and review panels.
+    pub notation: chess::Notation,
+    /// Show the engine eval after each move in the history of a live game.
+    pub live_evals: bool,
+    /// Show the engine's principal variation in raw UCI instead of SAN.
+    pub raw_pv: bool,
+    /// Describe the current move under the board while reviewing.
+    pub review_commentary: bool,
+    /// Draw the evaluation over the whole game under the review timeline.
+    pub review_eval_graph: bool,
+    /// Heatmap under the board in analysis and review.
+    pub heatmap: Heatmap,
 }
 
 impl Default for UiStateMachine {
@@ -53,6 +103,7 @@ impl Default for UiStateMachine {
         visibility.insert(Component::ReviewSummary, false);
         visibility.insert(Component::AdvancedAnalysis, false);
         visibility.insert(Component::DebugPanel, false);
+        visibility.insert(Component::PositionPanel, false);
 
         let mut scroll_state = HashMap::new();
         scroll_state.insert(Component::InfoPanel, 0);
@@ -60,7 +111,7 @@ impl Default for UiStateMachine {
         scroll_state.insert(Component::EnginePanel, 0);
         scroll_state.insert(Component::DebugPanel, 0);
         scroll_state.insert(Component::ReviewSummary, 0);
-        scroll_state.insert(Component::AdvancedAnalysis, 0);
+        scroll_state.insert(Component::PositionPanel, 0);
 
         Self {
             context: AppContext::default(),
@@ -69,7 +120,13 @@ impl Default for UiStateMachine {
             input_phase: InputPhase::default(),
             popup_menu: None,
             snapshot_dialog: None,
+            bookmark_dialog: None,
+            square_control: None,
+            notification_history: None,
+            chat: None,
             review_tab: 0,
+            advanced_tab: AdvancedTab::default(),
+            advanced_tab_scroll: HashMap::new(),
             review_moves_selection: None,
             selected_promotion_piece: cozy_chess::Piece::Queen,
             focused_component: None,
@@ -77,6 +134,22 @@ impl Default for UiStateMachine {
             visibility,
             scroll_state,
             typeahead_squares: Vec::new(),
+            scrubber_track: Cell::new(None),
+            sparkline_track: Cell::new(None),
+            dragging: None,
+            review_key_accel: KeyAcceleration::default(),
+            show_profiler: false,
+            frame_profile: FrameProfile::default(),
+            board_cache: RefCell::new(BoardCache::new(crate::ui::graphics::current())),
+            screen_reader: false,
+            narration: Narration::default(),
+            command_line: None,
+            notation: chess::Notation::default(),
+            live_evals: false,
+            raw_pv: false,
+            review_commentary: false,
+            review_eval_graph: false,
+            heatmap: Heatmap::Off,
         }
     }
 }
@@ -91,6 +164,13 @@ impl UiStateMachine {
             _ => {}
         }
     }
+
+    /// Replace the board with the narrated transcript. The board is never
+    /// drawn, so no picture of it is sent either.
+    pub fn enable_screen_reader(&mut self) {
+        self.screen_reader = true;
+        *self.board_cache.get_mut() = BoardCache::new(None);
+    }
 }
 
 impl UiStateMachine {
@@ -135,6 +215,16 @@ impl UiStateMachine {
     fn derive_overlay(&self) -> render_spec::Overlay {
         use render_spec::Overlay;
 
+        // Notification history is opened over everything else
+        if self.notification_history.is_some() {
+            return Overlay::NotificationHistory;
+        }
+
+        // The chat pane takes the keyboard while it is open
+        if self.chat.is_some() {
+            return Overlay::Chat;
+        }
+
         // Check for promotion dialog first
         if let InputPhase::SelectPromotion { from, to } = &self.input_phase {
             return Overlay::PromotionDialog {
@@ -153,6 +243,14 @@ impl UiStateMachine {
             return Overlay::SnapshotDialog;
         }
 
+        if self.bookmark_dialog.is_some() {
+            return Overlay::BookmarkDialog;
+        }
+
+        if self.square_control.is_some() {
+            return Overlay::SquareControl;
+        }
+
         Overlay::None
     }
 
@@ -176,17 +274,84 @@ impl UiStateMachine {
                 let mut controls = vec![
                     Control::new("1-4", "Panels"),
                     Control::new("j/k", "Moves"),
+                    Control::new("\u{2190}/\u{2192}", "Step"),
                     Control::new("Space", "Auto"),
+                    Control::new("[/]", "Speed"),
                     Control::new("Home/End", "Jump"),
+                    Control::new("(/)", "Phases"),
+                    Control::new("b", "Bookmark"),
+                    Control::new("</>", "Bookmarks"),
+                    Control::new("x/X", "Export"),
+                    Control::new("a", "Analyse"),
                     Control::new("Esc", "Menu"),
                 ];
+                let stops = game_session
+                    .review_state
+                    .as_ref()
+                    .is_some_and(|review| review.pause_on_critical);
+                controls.push(Control::new(
+                    "c",
+                    if stops {
+                        "Don't Stop"
+                    } else {
+                        "Stop on Errors"
+                    },
+                ));
+                let label = if self.review_commentary {
+                    "Hide Notes"
+                } else {
+                    "Notes"
+                };
+                controls.push(Control::new("v", label));
+                if self.review_eval_graph {
+                    controls.push(Control::new("g", "Hide Graph"));
+                    controls.push(Control::new("{/}", "Swings"));
+                } else {
+                    controls.push(Control::new("g", "Graph"));
+                }
+                controls.push(Control::new("t", "Clock"));
+                let label = if game_session
+                    .review_state
+                    .as_ref()
+                    .is_some_and(|r| r.comparison.is_some())
+                {
+                    "Hide Diff"
+                } else {
+                    "Diff"
+                };
+                controls.push(Control::new("d", label));
+                let label = if game_session
+                    .review_state
+                    .as_ref()
+                    .is_some_and(|r| r.current_explanation().is_some())
+                {
+                    "Hide Why"
+                } else {
+                    "Why"
+                };
+                controls.push(Control::new("w", label));
+                controls.push(Control::new("r", "Deeper"));
+                controls.push(Control::new("*", self.heatmap_control_label()));
                 if self.focused_component.is_some() {
                     controls.push(Control::new("J/K", "Scroll"));
                 }
                 controls
             }
             UiMode::GameBoard => {
-                let mut controls = vec![Control::new("i", "Input")];
+                // Only the answer to a held move is read until it is given
+                if game_session.pending_move.is_some() {
+                    return vec![
+                        Control::new("Enter/y", "Play Move"),
+                        Control::new("Esc/n", "Cancel"),
+                        Control::new("Ctrl+C", "Quit"),
+                    ];
+                }
+
+                let mut controls = if self.screen_reader {
+                    vec![Control::new(":", "Command")]
+                } else {
+                    vec![Control::new("i", "Input")]
+                };
 
                 if matches!(
                     game_session.mode,
@@ -198,11 +363,42 @@ impl UiStateMachine {
                     controls.push(Control::new("p", "Pause"));
                 }
 
+                if matches!(game_session.mode, GameMode::EngineVsEngine) {
+                    controls.push(Control::new("[/]", "Speed"));
+                    controls.push(Control::new("t", "Hints"));
+                    if game_session.snapshot.step_mode {
+                        controls.push(Control::new("n", "Step"));
+                        controls.push(Control::new("s", "Step Off"));
+                    } else {
+                        controls.push(Control::new("s", "Step Mode"));
+                    }
+                }
+
+                if game_session.simul.is_some() {
+                    controls.push(Control::new(">", "Next Board"));
+                }
+
+                if game_session.can_change_engine_lines() {
+                    controls.push(Control::new("m", "Lines"));
+                }
+
+                if matches!(game_session.mode, GameMode::Analysis) {
+                    controls.push(Control::new("*", self.heatmap_control_label()));
+                    controls.push(Control::new("?", "Square"));
+                }
+
                 if game_session.is_undo_allowed() {
                     controls.push(Control::new("u", "Undo"));
+                    if game_session.snapshot.redo_depth > 0 {
+                        controls.push(Control::new("r", "Redo"));
+                    }
                 }
 
-                controls.push(Control::new("Esc", "Menu"));
+                if game_session.premoves.is_empty() {
+                    controls.push(Control::new("Esc", "Menu"));
+                } else {
+                    controls.push(Control::new("Esc", "Clear Premoves"));
+                }
                 let panel_hint = if self.is_component_visible(&Component::DebugPanel) {
                     "1-4"
                 } else {
@@ -210,50 +406,102 @@ impl UiStateMachine {
                 };
                 controls.push(Control::new(panel_hint, "Panels"));
                 controls.push(Control::new("@", "UCI"));
+                controls.push(Control::new("^", "FEN/PGN"));
                 controls.push(Control::new("Ctrl+C", "Quit"));
 
                 if self.focused_component.is_some() {
                     controls.push(Control::new("J/K", "Scroll"));
                 }
+                if self.focused_component == Some(Component::PositionPanel) {
+                    controls.push(Control::new("y/Y", "Copy FEN/PGN"));
+                }
+                if self.focused_component == Some(Component::EnginePanel) {
+                    let label = if self.raw_pv { "SAN PV" } else { "UCI PV" };
+                    controls.push(Control::new("r", label));
+                }
 
                 controls
             }
         }
     }
 
+    fn heatmap_control_label(&self) -> &'static str {
+        match self.heatmap {
+            Heatmap::Off => "Heatmap",
+            _ => self.heatmap.label(),
+        }
+    }
+
     /// Build board overlay from game session (for game mode)
     pub fn board_overlay(
         &self,
         game_session: &crate::state::GameSession,
     ) -> crate::ui::widgets::board_overlay::BoardOverlay {
-        use crate::ui::widgets::board_overlay::{BoardOverlay, OverlayColor};
+        use crate::state::GameMode;
+        use crate::ui::widgets::board_overlay::{
+            add_heatmap, line_color, BoardOverlay, OverlayColor,
+        };
 
         let mut overlay = BoardOverlay::new();
 
+        // Layer 0: Heatmap while analysing, under everything else
+        if matches!(game_session.mode, GameMode::Analysis) {
+            add_heatmap(&mut overlay, game_session.board(), self.heatmap);
+        }
+
         // Layer 1: Last move (lowest priority)
         if let Some((from, to)) = game_session.last_move {
             overlay.tint(from, OverlayColor::LastMove);
             overlay.tint(to, OverlayColor::LastMove);
         }
 
-        // Layer 2: Best move (engine recommendation) - arrow and outline squares
+        // Layer 2: Best move (engine recommendation) - arrow and outline squares,
+        // over lighter arrows for the engine's other lines
+        for (line, (from, to)) in game_session.alt_line_arrows() {
+            if game_session.best_move_squares != Some((from, to)) {
+                overlay.arrow(from, to, line_color(line));
+            }
+        }
         if let Some((from, to)) = game_session.best_move_squares {
             overlay.arrow(from, to, OverlayColor::BestMove);
             overlay.outline(from, OverlayColor::BestMove);
             overlay.outline(to, OverlayColor::BestMove);
         }
 
-        // Layer 3: Legal move destinations (highlighted squares)
+        // Layer 3: Live tactical hints (when enabled on the session)
+        if !game_session.snapshot.tactical_hints.is_empty() {
+            crate::ui::widgets::board_overlay::add_tactical_tag_overlays(
+                &mut overlay,
+                &game_session.snapshot.tactical_hints,
+            );
+        }
+
+        // Layer 4: Legal move destinations (highlighted squares)
         for &sq in &game_session.highlighted_squares {
             overlay.tint(sq, OverlayColor::LegalMove);
         }
 
-        // Layer 4: Selected piece (highest priority)
+        // Layer 5: Squares explaining a rejected move
+        for &sq in game_session.illegal_move_squares() {
+            overlay.tint(sq, OverlayColor::Danger);
+        }
+
+        // Layer 6: Selected piece (highest priority)
         if let Some(sq) = game_session.selected_square {
             overlay.tint(sq, OverlayColor::Selected);
         }
 
-        // Layer 5: Typeahead squares (pieces matching user input) - outline only
+        // Layer 7: Queued premoves, faint so the position stays readable
+        for premove in game_session.premoves.iter() {
+            overlay.arrow(premove.from, premove.to, OverlayColor::Premove);
+        }
+
+        // Layer 8: Move waiting for confirmation, over the selection it came from
+        if let Some(pending) = game_session.pending_move {
+            overlay.arrow(pending.from, pending.to, OverlayColor::PendingMove);
+        }
+
+        // Layer 9: Typeahead squares (pieces matching user input) - outline only
         for &sq in &self.typeahead_squares {
             overlay.outline(sq, OverlayColor::Typeahead);
         }
@@ -317,11 +565,26 @@ impl UiStateMachine {
         self.visibility.insert(component, !current);
     }
 
+    /// Scroll offset of `component`. The advanced analysis panel scrolls
+    /// each of its tabs separately.
     pub fn component_scroll(&self, component: &Component) -> u16 {
+        if *component == Component::AdvancedAnalysis {
+            return self
+                .advanced_tab_scroll
+                .get(&self.advanced_tab)
+                .copied()
+                .unwrap_or(0);
+        }
         self.scroll_state.get(component).copied().unwrap_or(0)
     }
 
     pub fn component_scroll_mut(&mut self, component: &Component) -> &mut u16 {
+        if *component == Component::AdvancedAnalysis {
+            return self
+                .advanced_tab_scroll
+                .entry(self.advanced_tab)
+                .or_insert(0);
+        }
         self.scroll_state.entry(*component).or_insert(0)
     }
 
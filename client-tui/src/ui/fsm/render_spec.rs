@@ -89,6 +89,10 @@ pub enum Overlay {
     None,
     PopupMenu,
     SnapshotDialog,
+    BookmarkDialog,
+    SquareControl,
+    NotificationHistory,
+    Chat,
     PromotionDialog {
         from: Square,
         to: Square,
@@ -1,9 +1,15 @@
-use crate::state::{GameMode, GameSession, PlayerColor};
+use crate::state::{GameMode, GameSession, PlayerSide};
 use crate::ui::fsm::render_spec::{Component, Constraint, Layout, Overlay, Row};
 use crate::ui::fsm::UiStateMachine;
+use crate::ui::glyphs;
 use crate::ui::widgets::{
-    advanced_analysis_panel::AdvancedAnalysisPanel, board_overlay::build_review_overlay,
-    review_summary_panel::ReviewSummaryPanel, review_tabs_panel::ReviewTabsPanel, BoardWidget,
+    advanced_analysis_panel::{AdvancedAnalysisPanel, AdvancedTabsHeader},
+    board_overlay::build_review_overlay,
+    position_panel,
+    review_summary_panel::ReviewSummaryPanel,
+    review_tabs_panel::ReviewTabsPanel,
+    BoardWidget, EvalSparkline, MoveCommentary, NarrationPanel, PositionPanel, ProfilerOverlay,
+    TimelineScrubber, ToastStack,
 };
 use ratatui::{layout::Rect, Frame};
 
@@ -17,6 +23,13 @@ impl Renderer {
         game_session: &GameSession,
         fsm: &UiStateMachine,
     ) {
+        // Only set again when the timeline is drawn, e.g. not while a pane is expanded
+        fsm.scrubber_track.set(None);
+        fsm.sparkline_track.set(None);
+        fsm.board_cache
+            .borrow_mut()
+            .begin_frame(!matches!(fsm.overlay(), Overlay::None));
+
         let row_areas = Self::split_vertical(area, &layout.rows);
 
         for (row, row_area) in layout.rows.iter().zip(row_areas.iter()) {
@@ -33,10 +46,32 @@ impl Renderer {
             }
         }
 
+        frame.render_widget(
+            ToastStack {
+                notifications: &game_session.notifications,
+                labelled: fsm.screen_reader,
+            },
+            area,
+        );
+
+        if fsm.show_profiler {
+            frame.render_widget(
+                ProfilerOverlay {
+                    frames: &fsm.frame_profile,
+                    rpc: &game_session.rpc_timings,
+                    board_redrawn: fsm.board_cache.borrow().last_redrawn,
+                },
+                area,
+            );
+        }
+
         let overlay = fsm.overlay();
         if !matches!(overlay, Overlay::None) {
             Self::render_overlay(frame, area, overlay, game_session, fsm);
         }
+
+        // Keep text after any wide glyph in place, wherever it was drawn
+        glyphs::current().claim_padding(frame.buffer_mut(), area);
     }
 
     fn split_vertical(area: Rect, rows: &[Row]) -> Vec<Rect> {
@@ -114,6 +149,7 @@ impl Renderer {
     ) {
         match content {
             crate::ui::fsm::render_spec::SectionContent::Component(component) => {
+                let _span = tracing::trace_span!("component", ?component).entered();
                 Self::render_component(frame, area, component, game_session, fsm);
             }
             crate::ui::fsm::render_spec::SectionContent::Nested(sections) => {
@@ -143,15 +179,22 @@ impl Renderer {
         };
 
         match component {
+            Component::Board if fsm.screen_reader => {
+                let panel = NarrationPanel {
+                    narration: &fsm.narration,
+                    command_line: fsm.command_line.as_deref(),
+                };
+                frame.render_widget(panel, area);
+            }
             Component::Board => {
                 let is_flipped = matches!(
                     game_session.mode,
                     GameMode::HumanVsEngine {
-                        human_side: PlayerColor::Black
+                        human_side: PlayerSide::Black
                     }
                 );
                 let board_overlay = if let Some(ref review) = game_session.review_state {
-                    build_review_overlay(review)
+                    build_review_overlay(review, fsm.heatmap)
                 } else {
                     fsm.board_overlay(game_session)
                 };
@@ -160,7 +203,7 @@ impl Renderer {
                     overlay: &board_overlay,
                     flipped: is_flipped,
                 };
-                frame.render_widget(board_widget, area);
+                frame.render_stateful_widget(board_widget, area, &mut fsm.board_cache.borrow_mut());
             }
             Component::TabInput => {
                 let widget = TabInputWidget::new(game_session, fsm);
@@ -216,7 +259,13 @@ impl Renderer {
                 let current_ply = game_session.review_state.as_ref().map(|rs| rs.current_ply);
                 let widget = MoveHistoryPanel::new(game_session.history(), scroll, is_selected)
                     .with_review_positions(review_positions)
-                    .with_current_ply(current_ply);
+                    .with_current_ply(current_ply)
+                    .with_phases(game_session.review_state.as_ref().map(|rs| rs.phases))
+                    .with_notation(fsm.notation)
+                    .with_live_evals(
+                        (fsm.live_evals && game_session.review_state.is_none())
+                            .then_some(&game_session.live_evals),
+                    );
                 frame.render_widget(widget, area);
             }
             Component::EnginePanel => {
@@ -227,7 +276,24 @@ impl Renderer {
                     game_session.is_engine_thinking,
                     scroll,
                     is_selected,
-                );
+                )
+                .with_position(Some(game_session.board()))
+                .with_raw_pv(fsm.raw_pv)
+                .with_notation(fsm.notation)
+                .with_alt_lines(Some(&game_session.alt_lines));
+                frame.render_widget(widget, area);
+            }
+            Component::PositionPanel => {
+                let start: cozy_chess::Board =
+                    game_session.snapshot.start_fen.parse().unwrap_or_default();
+                let fen = game_session.board().to_string();
+                let movetext = position_panel::movetext(&start, game_session.history());
+                let widget = PositionPanel {
+                    fen: &fen,
+                    movetext: &movetext,
+                    scroll: fsm.component_scroll(&Component::PositionPanel),
+                    is_selected: fsm.selected_component() == Some(Component::PositionPanel),
+                };
                 frame.render_widget(widget, area);
             }
             Component::DebugPanel => {
@@ -247,6 +313,7 @@ impl Renderer {
                         expanded: false,
                         is_selected,
                         moves_selection: None,
+                        notation: fsm.notation,
                     };
                     frame.render_widget(widget, area);
                 }
@@ -260,6 +327,37 @@ impl Renderer {
                         scroll,
                         is_selected,
                         expanded: false,
+                        notation: fsm.notation,
+                    };
+                    frame.render_widget(widget, area);
+                }
+            }
+            Component::AdvancedTabs => {
+                let widget = AdvancedTabsHeader {
+                    current: fsm.advanced_tab,
+                    is_selected: fsm.selected_component() == Some(Component::AdvancedAnalysis),
+                };
+                frame.render_widget(widget, area);
+            }
+            Component::TimelineScrubber => {
+                if let Some(ref review_state) = game_session.review_state {
+                    fsm.scrubber_track
+                        .set(Some(TimelineScrubber::track_area(area)));
+                    frame.render_widget(TimelineScrubber { review_state }, area);
+                }
+            }
+            Component::EvalSparkline => {
+                if let Some(ref review_state) = game_session.review_state {
+                    fsm.sparkline_track
+                        .set(Some(EvalSparkline::track_area(area)));
+                    frame.render_widget(EvalSparkline { review_state }, area);
+                }
+            }
+            Component::MoveCommentary => {
+                if let Some(ref review_state) = game_session.review_state {
+                    let widget = MoveCommentary {
+                        review_state,
+                        notation: fsm.notation,
                     };
                     frame.render_widget(widget, area);
                 }
@@ -270,6 +368,7 @@ impl Renderer {
                     let scroll = fsm.component_scroll(&Component::AdvancedAnalysis);
                     let widget = AdvancedAnalysisPanel {
                         review_state,
+                        tab: fsm.advanced_tab,
                         scroll,
                         is_selected,
                         expanded: false,
@@ -284,10 +383,13 @@ impl Renderer {
         frame: &mut Frame,
         area: Rect,
         overlay: Overlay,
-        _game_session: &GameSession,
+        game_session: &GameSession,
         fsm: &UiStateMachine,
     ) {
-        use crate::ui::widgets::{PopupMenuWidget, PromotionWidget, SnapshotDialogWidget};
+        use crate::ui::widgets::{
+            BookmarkDialogWidget, ChatPaneWidget, NotificationHistoryWidget, PopupMenuWidget,
+            PromotionWidget, SnapshotDialogWidget, SquareControlWidget,
+        };
 
         match overlay {
             Overlay::None => {}
@@ -303,6 +405,36 @@ impl Renderer {
                     frame.render_widget(widget, area);
                 }
             }
+            Overlay::BookmarkDialog => {
+                if let Some(ref state) = fsm.bookmark_dialog {
+                    frame.render_widget(BookmarkDialogWidget { state }, area);
+                }
+            }
+            Overlay::SquareControl => {
+                if let Some(ref control) = fsm.square_control {
+                    frame.render_widget(SquareControlWidget { control }, area);
+                }
+            }
+            Overlay::NotificationHistory => {
+                if let Some(ref state) = fsm.notification_history {
+                    let widget = NotificationHistoryWidget {
+                        notifications: &game_session.notifications,
+                        state,
+                    };
+                    frame.render_widget(widget, area);
+                }
+            }
+            Overlay::Chat => {
+                if let Some(ref state) = fsm.chat {
+                    let own_author = crate::tutorial::current_profile();
+                    let widget = ChatPaneWidget {
+                        messages: &game_session.chat,
+                        state,
+                        own_author: &own_author,
+                    };
+                    frame.render_widget(widget, area);
+                }
+            }
             Overlay::PromotionDialog { .. } => {
                 let widget = PromotionWidget {
                     selected_piece: fsm.selected_promotion_piece,
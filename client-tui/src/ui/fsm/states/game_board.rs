@@ -22,7 +22,7 @@ impl GameBoardState {
             ));
         }
 
-        // Build right column - stacked: GameInfo → EngineAnalysis → MoveHistory
+        // Build right column - stacked: GameInfo → EngineAnalysis → FEN/PGN → MoveHistory
         let mut right_columns = vec![Section::component(
             Constraint::Length(8),
             Component::InfoPanel,
@@ -36,6 +36,13 @@ impl GameBoardState {
             ));
         }
 
+        if shared.is_component_visible(&Component::PositionPanel) {
+            right_columns.push(Section::component(
+                Constraint::Length(10),
+                Component::PositionPanel,
+            ));
+        }
+
         // Move history takes remaining space
         right_columns.push(Section::component(
             Constraint::Min(10),
@@ -83,7 +90,7 @@ impl GameBoardState {
             ));
         }
 
-        // Build right column - stacked: GameInfo → EngineAnalysis → MoveHistory
+        // Build right column - stacked: GameInfo → EngineAnalysis → FEN/PGN → MoveHistory
         let mut right_columns = vec![Section::component(
             Constraint::Length(8),
             Component::InfoPanel,
@@ -97,6 +104,13 @@ impl GameBoardState {
             ));
         }
 
+        if shared.is_component_visible(&Component::PositionPanel) {
+            right_columns.push(Section::component(
+                Constraint::Length(10),
+                Component::PositionPanel,
+            ));
+        }
+
         // Move history takes remaining space
         right_columns.push(Section::component(
             Constraint::Min(10),
@@ -1,4 +1,4 @@
-use crate::state::{GameMode, PlayerColor};
+use crate::state::{GameMode, PlayerSide};
 
 #[allow(dead_code)]
 #[derive(Clone, Debug)]
@@ -6,7 +6,7 @@ pub struct MatchSummaryState {
     pub game_result: Option<(i32, String)>,
     pub move_count: u32,
     pub game_mode: GameMode,
-    pub winner: Option<PlayerColor>,
+    pub winner: Option<PlayerSide>,
 }
 
 impl Default for MatchSummaryState {
@@ -25,7 +25,7 @@ impl MatchSummaryState {
     pub fn new(result: Option<(i32, String)>, move_count: u32, game_mode: GameMode) -> Self {
         let winner = result.as_ref().and_then(|(status, _)| {
             if *status == 1 {
-                Some(PlayerColor::Black)
+                Some(PlayerSide::Black)
             } else {
                 None
             }
@@ -1,4 +1,6 @@
 use crate::ui::fsm::render_spec::{Component, Constraint, Layout, Overlay, Row, Section};
+use crate::ui::widgets::eval_sparkline::SPARKLINE_HEIGHT;
+use crate::ui::widgets::timeline_scrubber::SCRUBBER_HEIGHT;
 
 #[derive(Clone, Debug, Default)]
 pub struct ReviewBoardState;
@@ -13,7 +15,7 @@ impl ReviewBoardState {
     ) -> Layout {
         // Left column: Advanced Analysis (35%) on top, Review Summary below
         let left_columns = vec![
-            Section::component(Constraint::Percentage(35), Component::AdvancedAnalysis),
+            Section::nested(Constraint::Percentage(35), advanced_with_tabs()),
             Section::component(Constraint::Min(10), Component::ReviewSummary),
         ];
 
@@ -23,13 +25,20 @@ impl ReviewBoardState {
             Section::component(Constraint::Min(10), Component::HistoryPanel),
         ];
 
+        // The expanded advanced panel keeps its tab strip
+        let center = if component == Component::AdvancedAnalysis {
+            Section::nested(Constraint::Percentage(55), advanced_with_tabs())
+        } else {
+            Section::component(Constraint::Percentage(55), component)
+        };
+
         Layout {
             rows: vec![
                 Row::new(
                     Constraint::Percentage(95),
                     vec![
                         Section::nested(Constraint::Percentage(20), left_columns),
-                        Section::component(Constraint::Percentage(55), component),
+                        center,
                         Section::nested(Constraint::Percentage(25), right_columns),
                     ],
                 ),
@@ -55,7 +64,7 @@ impl ReviewBoardState {
 
         // Left column: Advanced Analysis (35%) on top, Review Summary below
         let left_columns = vec![
-            Section::component(Constraint::Percentage(35), Component::AdvancedAnalysis),
+            Section::nested(Constraint::Percentage(35), advanced_with_tabs()),
             Section::component(Constraint::Min(10), Component::ReviewSummary),
         ];
 
@@ -65,6 +74,28 @@ impl ReviewBoardState {
             Section::component(Constraint::Min(10), Component::HistoryPanel),
         ];
 
+        // Centre column: Board with the timeline scrubber underneath, then
+        // the eval graph and the move commentary when they are on
+        let mut board_column = vec![
+            Section::component(Constraint::Min(10), Component::Board),
+            Section::component(
+                Constraint::Length(SCRUBBER_HEIGHT),
+                Component::TimelineScrubber,
+            ),
+        ];
+        if shared.review_eval_graph {
+            board_column.push(Section::component(
+                Constraint::Length(SPARKLINE_HEIGHT),
+                Component::EvalSparkline,
+            ));
+        }
+        if shared.review_commentary {
+            board_column.push(Section::component(
+                Constraint::Length(1),
+                Component::MoveCommentary,
+            ));
+        }
+
         // Overlay is now set by UiStateMachine::layout()
         Layout {
             rows: vec![
@@ -72,7 +103,7 @@ impl ReviewBoardState {
                     Constraint::Percentage(95),
                     vec![
                         Section::nested(Constraint::Percentage(20), left_columns),
-                        Section::component(Constraint::Percentage(55), Component::Board),
+                        Section::nested(Constraint::Percentage(55), board_column),
                         Section::nested(Constraint::Percentage(25), right_columns),
                     ],
                 ),
@@ -88,3 +119,11 @@ impl ReviewBoardState {
         }
     }
 }
+
+/// Advanced analysis panel with its tab strip above it.
+fn advanced_with_tabs() -> Vec<Section> {
+    vec![
+        Section::component(Constraint::Length(1), Component::AdvancedTabs),
+        Section::component(Constraint::Min(3), Component::AdvancedAnalysis),
+    ]
+}
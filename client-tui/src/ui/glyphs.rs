@@ -0,0 +1,394 @@
+//! Display width of the symbols the boards are drawn with.
+//!
+//! Ratatui lays text out with Unicode widths, which count chess glyphs
+//! (`♔`), dingbats (`✺`) and geometric shapes (`●`) as one cell. Plenty of
+//! terminals and fonts draw them two cells wide instead, which pushes the
+//! rest of the row to the right and breaks board alignment. The widths the
+//! terminal actually uses are probed once at startup, or taken from
+//! `CHESSTTY_GLYPH_WIDTHS`, and the board widgets lay squares out from them:
+//! a wide glyph claims the cell after it, and piece art that would no longer
+//! fit its square falls back to ASCII stand-ins.
+//!
+//! Everything outside these classes (ASCII, box drawing, block elements) is
+//! assumed narrow; the rest of the UI's borders depend on that anyway.
+
+use ratatui::{buffer::Buffer, layout::Rect, style::Style};
+use std::borrow::Cow;
+use std::fmt;
+use std::io::{self, IsTerminal, Write};
+use std::sync::OnceLock;
+
+/// Groups of symbols that terminals tend to agree on the width of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GlyphClass {
+    /// Unicode chess pieces, used by the mini board and piece lists.
+    Pieces,
+    /// Dingbats such as `✺ ✦ ❂`, used in the board's piece art.
+    Dingbats,
+    /// Geometric shapes such as `▲ ◣ ●`, used in piece art and arrow heads.
+    Shapes,
+}
+
+impl GlyphClass {
+    pub const ALL: [GlyphClass; 3] = [GlyphClass::Pieces, GlyphClass::Dingbats, GlyphClass::Shapes];
+
+    pub fn of(c: char) -> Option<GlyphClass> {
+        match c {
+            '\u{2654}'..='\u{265f}' => Some(GlyphClass::Pieces),
+            '\u{2700}'..='\u{27bf}' => Some(GlyphClass::Dingbats),
+            '\u{25a0}'..='\u{25ff}' => Some(GlyphClass::Shapes),
+            _ => None,
+        }
+    }
+
+    /// Name used in `CHESSTTY_GLYPH_WIDTHS`.
+    pub fn name(self) -> &'static str {
+        match self {
+            GlyphClass::Pieces => "pieces",
+            GlyphClass::Dingbats => "dingbats",
+            GlyphClass::Shapes => "shapes",
+        }
+    }
+
+    /// Symbol printed to measure the class.
+    fn sample(self) -> char {
+        match self {
+            GlyphClass::Pieces => '\u{2654}',
+            GlyphClass::Dingbats => '\u{273a}',
+            GlyphClass::Shapes => '\u{25cf}',
+        }
+    }
+}
+
+/// Cells each glyph class takes up in this terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GlyphWidths {
+    pub pieces: u16,
+    pub dingbats: u16,
+    pub shapes: u16,
+}
+
+impl Default for GlyphWidths {
+    fn default() -> Self {
+        Self::NARROW
+    }
+}
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum GlyphWidthsError {
+    #[error("unknown glyph class '{0}', expected pieces, dingbats or shapes")]
+    UnknownClass(String),
+    #[error("invalid width '{0}', expected 1 or 2")]
+    InvalidWidth(String),
+    #[error("expected 'auto', 'narrow', 'wide' or 'class=width,...', got '{0}'")]
+    Malformed(String),
+}
+
+impl GlyphWidths {
+    pub const NARROW: Self = Self {
+        pieces: 1,
+        dingbats: 1,
+        shapes: 1,
+    };
+
+    pub const WIDE: Self = Self {
+        pieces: 2,
+        dingbats: 2,
+        shapes: 2,
+    };
+
+    pub fn class_width(&self, class: GlyphClass) -> u16 {
+        match class {
+            GlyphClass::Pieces => self.pieces,
+            GlyphClass::Dingbats => self.dingbats,
+            GlyphClass::Shapes => self.shapes,
+        }
+    }
+
+    fn set(&mut self, class: GlyphClass, width: u16) {
+        match class {
+            GlyphClass::Pieces => self.pieces = width,
+            GlyphClass::Dingbats => self.dingbats = width,
+            GlyphClass::Shapes => self.shapes = width,
+        }
+    }
+
+    pub fn char_width(&self, c: char) -> u16 {
+        GlyphClass::of(c).map_or(1, |class| self.class_width(class))
+    }
+
+    pub fn is_wide(&self, c: char) -> bool {
+        self.char_width(c) > 1
+    }
+
+    /// Cells `s` takes up on screen.
+    pub fn str_width(&self, s: &str) -> u16 {
+        s.chars().map(|c| self.char_width(c)).sum()
+    }
+
+    /// `line` made to fit in `width` cells: padding is trimmed first, then
+    /// wide glyphs are swapped for narrow stand-ins.
+    pub fn fit<'a>(&self, line: &'a str, width: u16) -> Cow<'a, str> {
+        if self.str_width(line) <= width {
+            return Cow::Borrowed(line);
+        }
+        let trimmed = line.trim_matches(' ');
+        if self.str_width(trimmed) <= width {
+            return Cow::Borrowed(trimmed);
+        }
+        Cow::Owned(self.narrowed(trimmed))
+    }
+
+    /// A single-cell symbol, or its narrow stand-in if it would take two.
+    pub fn narrow(&self, symbol: &'static str) -> Cow<'static, str> {
+        if symbol.chars().any(|c| self.is_wide(c)) {
+            Cow::Owned(self.narrowed(symbol))
+        } else {
+            Cow::Borrowed(symbol)
+        }
+    }
+
+    fn narrowed(&self, s: &str) -> String {
+        s.chars()
+            .map(|c| {
+                if self.is_wide(c) {
+                    narrow_fallback(c)
+                } else {
+                    c
+                }
+            })
+            .collect()
+    }
+
+    /// Write `s` starting at `(x, y)`, stopping before `right`. A wide glyph
+    /// takes its cell and the next one, which is marked as skipped so the
+    /// terminal's own advance is not overwritten.
+    pub fn set_str(&self, buf: &mut Buffer, x: u16, y: u16, s: &str, style: Style, right: u16) {
+        let mut x = x;
+        for c in s.chars() {
+            let width = self.char_width(c);
+            if x + width > right {
+                break;
+            }
+            buf[(x, y)].set_char(c).set_style(style);
+            if width > 1 {
+                buf[(x + 1, y)].reset();
+                buf[(x + 1, y)].set_style(style).set_skip(true);
+            }
+            x += width;
+        }
+    }
+
+    /// Let wide glyphs already in `area` spill into the blank cell after
+    /// them. For text laid out with a space after each glyph, such as the
+    /// mini board's squares, this is all it takes to keep columns aligned.
+    pub fn claim_padding(&self, buf: &mut Buffer, area: Rect) {
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right().saturating_sub(1) {
+                let mut chars = buf[(x, y)].symbol().chars();
+                let wide = match (chars.next(), chars.next()) {
+                    (Some(c), None) => self.is_wide(c),
+                    _ => false,
+                };
+                if wide && buf[(x + 1, y)].symbol() == " " {
+                    buf[(x + 1, y)].set_skip(true);
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for GlyphWidths {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = GlyphClass::ALL
+            .iter()
+            .map(|&class| format!("{}={}", class.name(), self.class_width(class)))
+            .collect();
+        write!(f, "{}", parts.join(","))
+    }
+}
+
+/// ASCII stand-in for a glyph that does not fit.
+fn narrow_fallback(c: char) -> char {
+    match c {
+        '\u{2654}' => 'K',
+        '\u{2655}' => 'Q',
+        '\u{2656}' => 'R',
+        '\u{2657}' => 'B',
+        '\u{2658}' => 'N',
+        '\u{2659}' => 'P',
+        '\u{265a}' => 'k',
+        '\u{265b}' => 'q',
+        '\u{265c}' => 'r',
+        '\u{265d}' => 'b',
+        '\u{265e}' => 'n',
+        '\u{265f}' => 'p',
+        '\u{273a}' | '\u{2726}' => '*',
+        '\u{2742}' | '\u{25c9}' | '\u{25cf}' => 'o',
+        '\u{25b2}' => '^',
+        '\u{25bc}' => 'v',
+        '\u{25b6}' => '>',
+        '\u{25c0}' => '<',
+        '\u{25e3}' | '\u{25e5}' => '/',
+        '\u{25e2}' | '\u{25e4}' => '\\',
+        _ => '#',
+    }
+}
+
+/// What `CHESSTTY_GLYPH_WIDTHS` asks for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GlyphSetting {
+    /// Measure the terminal.
+    Auto,
+    Fixed(GlyphWidths),
+}
+
+/// Parse `auto`, `narrow`, `wide`, or a list such as `pieces=2,shapes=1`;
+/// classes left out of a list are narrow.
+pub fn parse_setting(value: &str) -> Result<GlyphSetting, GlyphWidthsError> {
+    match value.trim() {
+        "" | "auto" => return Ok(GlyphSetting::Auto),
+        "narrow" => return Ok(GlyphSetting::Fixed(GlyphWidths::NARROW)),
+        "wide" => return Ok(GlyphSetting::Fixed(GlyphWidths::WIDE)),
+        _ => {}
+    }
+
+    let mut widths = GlyphWidths::NARROW;
+    for entry in value.split(',') {
+        let (name, width) = entry
+            .split_once('=')
+            .ok_or_else(|| GlyphWidthsError::Malformed(value.to_string()))?;
+        let class = GlyphClass::ALL
+            .into_iter()
+            .find(|class| class.name() == name.trim())
+            .ok_or_else(|| GlyphWidthsError::UnknownClass(name.trim().to_string()))?;
+        let width = match width.trim() {
+            "1" => 1,
+            "2" => 2,
+            other => return Err(GlyphWidthsError::InvalidWidth(other.to_string())),
+        };
+        widths.set(class, width);
+    }
+    Ok(GlyphSetting::Fixed(widths))
+}
+
+/// Measure each class by printing its sample at the start of the line and
+/// asking the terminal where the cursor ended up. The line is cleared again
+/// afterwards.
+pub fn probe() -> io::Result<GlyphWidths> {
+    use crossterm::{
+        cursor::{self, MoveToColumn},
+        queue,
+        style::Print,
+        terminal::{Clear, ClearType},
+    };
+
+    let mut stdout = io::stdout();
+    let mut widths = GlyphWidths::NARROW;
+    let result = GlyphClass::ALL.into_iter().try_for_each(|class| {
+        queue!(stdout, MoveToColumn(0), Print(class.sample()))?;
+        stdout.flush()?;
+        let (column, _) = cursor::position()?;
+        widths.set(class, column.clamp(1, 2));
+        Ok(())
+    });
+    queue!(stdout, MoveToColumn(0), Clear(ClearType::CurrentLine))?;
+    stdout.flush()?;
+    result.map(|()| widths)
+}
+
+/// Widths from `CHESSTTY_GLYPH_WIDTHS`, probing the terminal when it is
+/// unset or `auto`. Falls back to narrow when the terminal does not answer.
+pub fn detect() -> GlyphWidths {
+    let setting = match std::env::var("CHESSTTY_GLYPH_WIDTHS") {
+        Ok(value) => parse_setting(&value).unwrap_or_else(|e| {
+            tracing::warn!("Ignoring CHESSTTY_GLYPH_WIDTHS: {}", e);
+            GlyphSetting::Auto
+        }),
+        Err(_) => GlyphSetting::Auto,
+    };
+
+    match setting {
+        GlyphSetting::Fixed(widths) => widths,
+        GlyphSetting::Auto if !io::stdout().is_terminal() => GlyphWidths::NARROW,
+        GlyphSetting::Auto => probe().unwrap_or_else(|e| {
+            tracing::warn!("Glyph width probe failed, assuming narrow glyphs: {}", e);
+            GlyphWidths::NARROW
+        }),
+    }
+}
+
+static WIDTHS: OnceLock<GlyphWidths> = OnceLock::new();
+
+/// Record the widths for the rest of the run. Only the first call counts.
+pub fn init(widths: GlyphWidths) {
+    if WIDTHS.set(widths).is_ok() {
+        tracing::info!("Glyph widths: {}", widths);
+    }
+}
+
+/// Widths recorded by [`init`], narrow if it was never called.
+pub fn current() -> GlyphWidths {
+    WIDTHS.get().copied().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_setting() {
+        assert_eq!(parse_setting("auto"), Ok(GlyphSetting::Auto));
+        assert_eq!(
+            parse_setting("wide"),
+            Ok(GlyphSetting::Fixed(GlyphWidths::WIDE))
+        );
+        assert_eq!(
+            parse_setting("pieces=2, shapes=1"),
+            Ok(GlyphSetting::Fixed(GlyphWidths {
+                pieces: 2,
+                dingbats: 1,
+                shapes: 1,
+            }))
+        );
+        assert_eq!(
+            parse_setting("runes=2"),
+            Err(GlyphWidthsError::UnknownClass("runes".into()))
+        );
+        assert_eq!(
+            parse_setting("pieces=3"),
+            Err(GlyphWidthsError::InvalidWidth("3".into()))
+        );
+        assert!(matches!(
+            parse_setting("2"),
+            Err(GlyphWidthsError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn test_fit_trims_then_falls_back() {
+        let line = "  \u{273a}\u{25b2}\u{273a}  ";
+        assert_eq!(GlyphWidths::NARROW.fit(line, 7), line);
+
+        let wide = GlyphWidths {
+            pieces: 1,
+            dingbats: 2,
+            shapes: 1,
+        };
+        assert_eq!(wide.str_width(line), 9);
+        assert_eq!(wide.fit(line, 5), "\u{273a}\u{25b2}\u{273a}");
+        assert_eq!(wide.fit(line, 4), "*\u{25b2}*");
+    }
+
+    #[test]
+    fn test_set_str_skips_cell_after_wide_glyph() {
+        let mut buf = Buffer::empty(Rect::new(0, 0, 6, 1));
+        GlyphWidths::WIDE.set_str(&mut buf, 0, 0, "\u{2654}a\u{265a}", Style::default(), 6);
+        assert_eq!(buf[(0, 0)].symbol(), "\u{2654}");
+        assert!(buf[(1, 0)].skip);
+        assert_eq!(buf[(2, 0)].symbol(), "a");
+        assert_eq!(buf[(3, 0)].symbol(), "\u{265a}");
+        assert!(buf[(4, 0)].skip);
+        assert!(!buf[(5, 0)].skip);
+    }
+}
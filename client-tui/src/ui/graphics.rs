@@ -0,0 +1,498 @@
+//! Terminal image protocols for the high-fidelity board.
+//!
+//! Kitty, iTerm2 and sixel terminals can show the board as a picture instead
+//! of cell art. Which protocol to use is decided once at startup from
+//! `CHESSTTY_GRAPHICS` or, by default, from the environment the terminal
+//! advertises itself with. Anything unknown, including tmux (which needs
+//! passthrough escapes), keeps the text board. This module only knows how to
+//! turn an RGB [`Image`] into the escape sequence of each protocol; the board
+//! widget decides what to draw and when.
+
+use base64::Engine;
+use std::fmt::Write as _;
+use std::io::{self, IsTerminal, Write};
+use std::sync::OnceLock;
+
+/// Image id the board is transmitted under with the kitty protocol, so the
+/// previous picture can be deleted before the next one is placed.
+const KITTY_IMAGE_ID: u32 = 7411;
+
+/// Largest base64 payload per kitty escape sequence.
+const KITTY_CHUNK: usize = 4096;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphicsProtocol {
+    Kitty,
+    Iterm2,
+    Sixel,
+}
+
+impl GraphicsProtocol {
+    pub fn name(self) -> &'static str {
+        match self {
+            GraphicsProtocol::Kitty => "kitty",
+            GraphicsProtocol::Iterm2 => "iterm2",
+            GraphicsProtocol::Sixel => "sixel",
+        }
+    }
+
+    /// Whether the terminal scales the image to the cells it is placed in.
+    /// Sixel images are drawn pixel for pixel.
+    pub fn scales(self) -> bool {
+        !matches!(self, GraphicsProtocol::Sixel)
+    }
+
+    /// Escape sequence showing `image` stretched over `cols` x `rows` cells
+    /// from the cursor position, leaving the cursor where it was.
+    pub fn encode(self, image: &Image, cols: u16, rows: u16) -> Result<String, GraphicsError> {
+        match self {
+            GraphicsProtocol::Kitty => Ok(kitty(image, cols, rows)),
+            GraphicsProtocol::Iterm2 => Ok(iterm2(image, cols, rows)),
+            GraphicsProtocol::Sixel => sixel(image),
+        }
+    }
+
+    /// Escape sequence removing the board image, for protocols whose images
+    /// sit above the text rather than in it.
+    pub fn delete(self) -> Option<String> {
+        match self {
+            GraphicsProtocol::Kitty => {
+                Some(format!("\x1b_Ga=d,d=I,i={},q=2\x1b\\", KITTY_IMAGE_ID))
+            }
+            GraphicsProtocol::Iterm2 | GraphicsProtocol::Sixel => None,
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum GraphicsError {
+    #[error("unknown graphics setting '{0}', expected auto, off, kitty, iterm2 or sixel")]
+    UnknownSetting(String),
+    #[error("image has {0} colours, sixel output supports at most 256")]
+    TooManyColors(usize),
+}
+
+/// What `CHESSTTY_GRAPHICS` asks for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphicsSetting {
+    Auto,
+    Off,
+    Force(GraphicsProtocol),
+}
+
+pub fn parse_setting(value: &str) -> Result<GraphicsSetting, GraphicsError> {
+    match value.trim() {
+        "" | "auto" => Ok(GraphicsSetting::Auto),
+        "off" | "text" => Ok(GraphicsSetting::Off),
+        "kitty" => Ok(GraphicsSetting::Force(GraphicsProtocol::Kitty)),
+        "iterm2" => Ok(GraphicsSetting::Force(GraphicsProtocol::Iterm2)),
+        "sixel" => Ok(GraphicsSetting::Force(GraphicsProtocol::Sixel)),
+        other => Err(GraphicsError::UnknownSetting(other.to_string())),
+    }
+}
+
+/// The terminal's environment variables that identify it.
+#[derive(Debug, Default)]
+pub struct TerminalEnv<'a> {
+    pub term: Option<&'a str>,
+    pub term_program: Option<&'a str>,
+    pub kitty_window: bool,
+    pub tmux: bool,
+}
+
+/// Protocol a terminal is known to support, judging by its environment.
+pub fn protocol_for(env: &TerminalEnv<'_>) -> Option<GraphicsProtocol> {
+    if env.tmux {
+        return None;
+    }
+    let term = env.term.unwrap_or_default();
+    match env.term_program.unwrap_or_default() {
+        "iTerm.app" | "WezTerm" => return Some(GraphicsProtocol::Iterm2),
+        "ghostty" => return Some(GraphicsProtocol::Kitty),
+        _ => {}
+    }
+    if env.kitty_window || term == "xterm-kitty" {
+        Some(GraphicsProtocol::Kitty)
+    } else if term.starts_with("foot") || term.starts_with("mlterm") || term.contains("sixel") {
+        Some(GraphicsProtocol::Sixel)
+    } else {
+        None
+    }
+}
+
+/// Protocol from `CHESSTTY_GRAPHICS`, or from the terminal's environment
+/// when it is unset or `auto`. `None` keeps the text board.
+pub fn detect() -> Option<GraphicsProtocol> {
+    let setting = match std::env::var("CHESSTTY_GRAPHICS") {
+        Ok(value) => parse_setting(&value).unwrap_or_else(|e| {
+            tracing::warn!("Ignoring CHESSTTY_GRAPHICS: {}", e);
+            GraphicsSetting::Auto
+        }),
+        Err(_) => GraphicsSetting::Auto,
+    };
+
+    match setting {
+        GraphicsSetting::Off => None,
+        GraphicsSetting::Force(protocol) => Some(protocol),
+        GraphicsSetting::Auto if !io::stdout().is_terminal() => None,
+        GraphicsSetting::Auto => {
+            let term = std::env::var("TERM").ok();
+            let term_program = std::env::var("TERM_PROGRAM").ok();
+            protocol_for(&TerminalEnv {
+                term: term.as_deref(),
+                term_program: term_program.as_deref(),
+                kitty_window: std::env::var_os("KITTY_WINDOW_ID").is_some(),
+                tmux: std::env::var_os("TMUX").is_some(),
+            })
+        }
+    }
+}
+
+static PROTOCOL: OnceLock<Option<GraphicsProtocol>> = OnceLock::new();
+
+/// Record the protocol for the rest of the run. Only the first call counts.
+pub fn init(protocol: Option<GraphicsProtocol>) {
+    if PROTOCOL.set(protocol).is_ok() {
+        tracing::info!(
+            "Board renderer: {}",
+            protocol.map_or("text", GraphicsProtocol::name)
+        );
+    }
+}
+
+/// Protocol recorded by [`init`]; `None` if there is none or it was never
+/// called.
+pub fn current() -> Option<GraphicsProtocol> {
+    PROTOCOL.get().copied().flatten()
+}
+
+/// Size of one cell in pixels, if the terminal reports it.
+pub fn cell_size() -> Option<(u16, u16)> {
+    let size = crossterm::terminal::window_size().ok()?;
+    if size.columns == 0 || size.rows == 0 {
+        return None;
+    }
+    let cell = (size.width / size.columns, size.height / size.rows);
+    (cell.0 > 0 && cell.1 > 0).then_some(cell)
+}
+
+/// Remove any image left by the board, e.g. before leaving the game screen.
+pub fn clear_images(out: &mut impl Write) -> io::Result<()> {
+    if let Some(delete) = current().and_then(GraphicsProtocol::delete) {
+        out.write_all(delete.as_bytes())?;
+        out.flush()?;
+    }
+    Ok(())
+}
+
+/// An RGB picture, row-major from the top-left corner.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    pixels: Vec<[u8; 3]>,
+}
+
+impl Image {
+    pub fn new(width: u32, height: u32, fill: [u8; 3]) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![fill; (width * height) as usize],
+        }
+    }
+
+    pub fn get(&self, x: u32, y: u32) -> [u8; 3] {
+        self.pixels[(y * self.width + x) as usize]
+    }
+
+    /// Set a pixel; anything outside the image is ignored.
+    pub fn put(&mut self, x: i64, y: i64, color: [u8; 3]) {
+        if x >= 0 && y >= 0 && (x as u32) < self.width && (y as u32) < self.height {
+            let idx = (y as u32 * self.width + x as u32) as usize;
+            self.pixels[idx] = color;
+        }
+    }
+
+    pub fn fill_rect(&mut self, x: u32, y: u32, width: u32, height: u32, color: [u8; 3]) {
+        for py in y..(y + height).min(self.height) {
+            for px in x..(x + width).min(self.width) {
+                self.pixels[(py * self.width + px) as usize] = color;
+            }
+        }
+    }
+
+    fn rgb_bytes(&self) -> Vec<u8> {
+        self.pixels.iter().flatten().copied().collect()
+    }
+}
+
+fn kitty(image: &Image, cols: u16, rows: u16) -> String {
+    let payload = base64::engine::general_purpose::STANDARD.encode(image.rgb_bytes());
+    let chunks: Vec<&[u8]> = payload.as_bytes().chunks(KITTY_CHUNK).collect();
+
+    let mut out = String::with_capacity(payload.len() + chunks.len() * 16 + 64);
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(i + 1 < chunks.len());
+        // Base64 output is ASCII, so every chunk is valid UTF-8
+        let chunk = std::str::from_utf8(chunk).unwrap_or_default();
+        if i == 0 {
+            let _ = write!(
+                out,
+                "\x1b_Ga=T,f=24,s={},v={},c={},r={},i={},C=1,q=2,m={};{}\x1b\\",
+                image.width, image.height, cols, rows, KITTY_IMAGE_ID, more, chunk
+            );
+        } else {
+            let _ = write!(out, "\x1b_Gm={};{}\x1b\\", more, chunk);
+        }
+    }
+    out
+}
+
+fn iterm2(image: &Image, cols: u16, rows: u16) -> String {
+    let png = png(image);
+    format!(
+        "\x1b]1337;File=inline=1;size={};width={};height={};preserveAspectRatio=0:{}\x07",
+        png.len(),
+        cols,
+        rows,
+        base64::engine::general_purpose::STANDARD.encode(&png)
+    )
+}
+
+/// Sixel data with a palette of the image's colours, six rows per band and
+/// each colour's row run-length encoded.
+fn sixel(image: &Image) -> Result<String, GraphicsError> {
+    let mut palette: Vec<[u8; 3]> = Vec::new();
+    let mut indexed = Vec::with_capacity(image.pixels.len());
+    for pixel in &image.pixels {
+        let idx = match palette.iter().position(|c| c == pixel) {
+            Some(idx) => idx,
+            None => {
+                palette.push(*pixel);
+                palette.len() - 1
+            }
+        };
+        indexed.push(idx);
+    }
+    if palette.len() > 256 {
+        return Err(GraphicsError::TooManyColors(palette.len()));
+    }
+
+    let mut out = format!("\x1bP0;1;0q\"1;1;{};{}", image.width, image.height);
+    for (i, [r, g, b]) in palette.iter().enumerate() {
+        let pct = |v: u8| u32::from(v) * 100 / 255;
+        let _ = write!(out, "#{};2;{};{};{}", i, pct(*r), pct(*g), pct(*b));
+    }
+
+    let width = image.width as usize;
+    let mut bands = vec![0u8; palette.len() * width];
+    for band_top in (0..image.height as usize).step_by(6) {
+        bands.iter_mut().for_each(|b| *b = 0);
+        let mut present = vec![false; palette.len()];
+        for dy in 0..6.min(image.height as usize - band_top) {
+            let row = &indexed[(band_top + dy) * width..(band_top + dy + 1) * width];
+            for (x, &color) in row.iter().enumerate() {
+                bands[color * width + x] |= 1 << dy;
+                present[color] = true;
+            }
+        }
+
+        let mut first = true;
+        for color in (0..palette.len()).filter(|&c| present[c]) {
+            if !first {
+                out.push('$');
+            }
+            first = false;
+            let _ = write!(out, "#{}", color);
+            push_sixel_run(&mut out, &bands[color * width..(color + 1) * width]);
+        }
+        out.push('-');
+    }
+    out.push_str("\x1b\\");
+    Ok(out)
+}
+
+/// One colour's row of a band, with repeats of the same sixel collapsed.
+fn push_sixel_run(out: &mut String, row: &[u8]) {
+    let mut i = 0;
+    while i < row.len() {
+        let bits = row[i];
+        let run = row[i..].iter().take_while(|&&b| b == bits).count();
+        let ch = char::from(b'?' + bits);
+        if run > 3 {
+            let _ = write!(out, "!{}{}", run, ch);
+        } else {
+            (0..run).for_each(|_| out.push(ch));
+        }
+        i += run;
+    }
+}
+
+/// Uncompressed PNG: zlib stored blocks, which every decoder accepts.
+fn png(image: &Image) -> Vec<u8> {
+    let mut raw = Vec::with_capacity((image.width * 3 + 1) as usize * image.height as usize);
+    for row in image.pixels.chunks(image.width.max(1) as usize) {
+        raw.push(0); // no filter
+        raw.extend(row.iter().flatten());
+    }
+
+    let mut zlib = vec![0x78, 0x01];
+    let mut blocks = raw.chunks(u16::MAX as usize).peekable();
+    if blocks.peek().is_none() {
+        zlib.extend([1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let len = block.len() as u16;
+        zlib.push(u8::from(blocks.peek().is_none()));
+        zlib.extend(len.to_le_bytes());
+        zlib.extend((!len).to_le_bytes());
+        zlib.extend(block);
+    }
+    zlib.extend(adler32(&raw).to_be_bytes());
+
+    let mut ihdr = Vec::with_capacity(13);
+    ihdr.extend(image.width.to_be_bytes());
+    ihdr.extend(image.height.to_be_bytes());
+    ihdr.extend([8, 2, 0, 0, 0]); // 8-bit RGB, default compression, no interlace
+
+    let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+    png_chunk(&mut png, b"IHDR", &ihdr);
+    png_chunk(&mut png, b"IDAT", &zlib);
+    png_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+fn png_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend((data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend(kind);
+    png.extend(data);
+    let crc = crc32(&png[start..]);
+    png.extend(crc.to_be_bytes());
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffffu32;
+    for &byte in data {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + u32::from(byte)) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_protocol_for_environment() {
+        let kitty = TerminalEnv {
+            term: Some("xterm-kitty"),
+            ..Default::default()
+        };
+        assert_eq!(protocol_for(&kitty), Some(GraphicsProtocol::Kitty));
+
+        let iterm = TerminalEnv {
+            term: Some("xterm-256color"),
+            term_program: Some("iTerm.app"),
+            ..Default::default()
+        };
+        assert_eq!(protocol_for(&iterm), Some(GraphicsProtocol::Iterm2));
+
+        let foot = TerminalEnv {
+            term: Some("foot"),
+            ..Default::default()
+        };
+        assert_eq!(protocol_for(&foot), Some(GraphicsProtocol::Sixel));
+
+        let in_tmux = TerminalEnv {
+            tmux: true,
+            ..kitty
+        };
+        assert_eq!(protocol_for(&in_tmux), None);
+        assert_eq!(
+            protocol_for(&TerminalEnv {
+                term: Some("xterm-256color"),
+                ..Default::default()
+            }),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_setting() {
+        assert_eq!(parse_setting("auto"), Ok(GraphicsSetting::Auto));
+        assert_eq!(parse_setting("off"), Ok(GraphicsSetting::Off));
+        assert_eq!(
+            parse_setting("sixel"),
+            Ok(GraphicsSetting::Force(GraphicsProtocol::Sixel))
+        );
+        assert_eq!(
+            parse_setting("png"),
+            Err(GraphicsError::UnknownSetting("png".into()))
+        );
+    }
+
+    #[test]
+    fn test_kitty_payload_is_chunked() {
+        let image = Image::new(64, 64, [10, 20, 30]);
+        let out = kitty(&image, 8, 4);
+        let sequences: Vec<&str> = out.split("\x1b\\").filter(|s| !s.is_empty()).collect();
+        // 64*64*3 bytes is 16384 base64 characters, four full chunks
+        assert_eq!(sequences.len(), 4);
+        assert!(sequences[0].starts_with("\x1b_Ga=T,f=24,s=64,v=64,c=8,r=4,"));
+        assert!(sequences[0].contains("m=1;"));
+        assert!(sequences[3].starts_with("\x1b_Gm=0;"));
+    }
+
+    #[test]
+    fn test_png_structure() {
+        let mut image = Image::new(3, 2, [255, 0, 0]);
+        image.put(1, 1, [0, 0, 255]);
+        let png = png(&image);
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(&png[16..24], &[0, 0, 0, 3, 0, 0, 0, 2]);
+        assert_eq!(&png[png.len() - 8..png.len() - 4], b"IEND");
+        // The well-known CRC of an empty IEND chunk
+        assert_eq!(&png[png.len() - 4..], &[0xae, 0x42, 0x60, 0x82]);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    }
+
+    #[test]
+    fn test_sixel_palette_and_runs() {
+        let mut image = Image::new(10, 6, [0, 0, 0]);
+        image.fill_rect(0, 0, 5, 6, [255, 255, 255]);
+        let out = sixel(&image).unwrap();
+        assert!(out.starts_with("\x1bP0;1;0q\"1;1;10;6"));
+        assert!(out.contains("#0;2;100;100;100#1;2;0;0;0"));
+        // One band: white fills the first five columns, black the rest
+        assert!(out.contains("#0!5~!5?$#1!5?!5~-"));
+        assert!(out.ends_with("\x1b\\"));
+
+        let noisy = Image {
+            width: 300,
+            height: 1,
+            pixels: (0..300u32)
+                .map(|i| [(i % 256) as u8, (i / 256) as u8, 0])
+                .collect(),
+        };
+        assert_eq!(sixel(&noisy), Err(GraphicsError::TooManyColors(300)));
+    }
+}
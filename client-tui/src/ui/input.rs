@@ -1,18 +1,36 @@
+use crate::dispatch::Action;
+use crate::external_input;
+use crate::narration;
 use crate::review_state::ReviewState;
 use crate::state::{GameMode, GameSession};
+use crate::ui::clipboard;
 use crate::ui::fsm::render_spec::InputPhase;
-use crate::ui::fsm::{Component, UiStateMachine};
+use crate::ui::fsm::{Component, UiMode, UiStateMachine};
 use crate::ui::menu_app::GameConfig;
+use crate::ui::widgets::advanced_analysis_panel::AdvancedTab;
+use crate::ui::widgets::bookmark_dialog::BookmarkDialogState;
+use crate::ui::widgets::chat_pane::ChatPaneState;
+use crate::ui::widgets::eval_sparkline::{eval_swings, ply_at_cell};
 use crate::ui::widgets::popup_menu::{PopupMenuItem, PopupMenuState};
+use crate::ui::widgets::position_panel;
 use crate::ui::widgets::snapshot_dialog::{SnapshotDialogFocus, SnapshotDialogState};
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crate::ui::widgets::timeline_scrubber::ply_at_column;
+use crate::ui::widgets::toast::NotificationHistoryState;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::Position;
+use std::time::Instant;
 
 /// Scroll increment for component scroll (lines per scroll step).
 const SCROLL_INCREMENT: u16 = 5;
 
-/// Handle review navigation keys shared across all contexts (n/p/Space/Home/End).
-/// Returns true if the key was consumed.
-fn handle_review_navigation(review: &mut ReviewState, key: KeyCode) -> bool {
+/// Handle review navigation and auto-play keys shared across all contexts
+/// (n/p/Space/[/]/c/v/g/{/}/(/)/</>/Home/End). Returns true if the key was
+/// consumed.
+fn handle_review_navigation(
+    review: &mut ReviewState,
+    fsm: &mut UiStateMachine,
+    key: KeyCode,
+) -> bool {
     match key {
         KeyCode::Char('n') => {
             let current = review.current_ply;
@@ -37,6 +55,44 @@ fn handle_review_navigation(review: &mut ReviewState, key: KeyCode) -> bool {
             review.auto_play = !review.auto_play;
             true
         }
+        KeyCode::Char(c @ ('[' | ']')) => {
+            review.change_speed(c == ']');
+            true
+        }
+        KeyCode::Char('c') => {
+            review.pause_on_critical = !review.pause_on_critical;
+            true
+        }
+        KeyCode::Char('v') => {
+            fsm.review_commentary = !fsm.review_commentary;
+            true
+        }
+        KeyCode::Char('g') => {
+            fsm.review_eval_graph = !fsm.review_eval_graph;
+            true
+        }
+        KeyCode::Char('}') => {
+            let current = review.current_ply;
+            if let Some(&next) = eval_swings(review).iter().find(|&&p| p > current) {
+                review.go_to_ply(next);
+            }
+            true
+        }
+        KeyCode::Char('{') => {
+            let current = review.current_ply;
+            if let Some(&prev) = eval_swings(review).iter().rev().find(|&&p| p < current) {
+                review.go_to_ply(prev);
+            }
+            true
+        }
+        KeyCode::Char(c @ ('(' | ')')) => {
+            review.jump_to_phase(c == ')');
+            true
+        }
+        KeyCode::Char(c @ ('<' | '>')) => {
+            review.jump_to_bookmark(c == '>');
+            true
+        }
         KeyCode::Home => {
             review.go_to_start();
             true
@@ -61,33 +117,67 @@ pub enum AppAction {
     SuspendAndReturnToMenu,
     /// Play from a snapshot — exit review and start a new game with the given config.
     PlaySnapshot(Box<GameConfig>),
+    /// Leave review for an analysis session from the current position, then
+    /// come back to the review.
+    BranchAnalysis {
+        analysis: Box<GameConfig>,
+        review: Box<GameConfig>,
+    },
+    /// Switch to the next simul board waiting on the player.
+    NextBoard,
 }
 
 /// Returns true if character input should be disabled for the given game mode.
 pub fn should_disable_input(mode: &GameMode) -> bool {
-    matches!(mode, GameMode::EngineVsEngine | GameMode::ReviewMode)
+    matches!(mode, GameMode::EngineVsEngine | GameMode::Review)
 }
 
 /// Main key dispatch function. Routes input to the appropriate context handler.
-pub async fn handle_key(
+pub fn handle_key(
     state: &mut GameSession,
     fsm: &mut UiStateMachine,
     input_buffer: &mut String,
     key: KeyEvent,
 ) -> AppAction {
+    // Notification history sits above every other overlay
+    if fsm.notification_history.is_some() {
+        return handle_notification_history_input(fsm, key);
+    }
+
+    // The chat pane takes typed text, so it sits above the other modals
+    if fsm.chat.is_some() {
+        return handle_chat_input(state, fsm, key);
+    }
+
+    // A move held for confirmation takes the next key
+    if state.pending_move.is_some() {
+        return handle_pending_move_input(state, key);
+    }
+
     // Tab input mode takes priority (modal overlay)
     if fsm.tab_input.active {
-        return handle_tab_input(state, fsm, key).await;
+        return handle_tab_input(state, fsm, key);
     }
 
     // Popup menu takes highest priority (modal overlay)
     if fsm.popup_menu.is_some() {
-        return handle_popup_input(state, fsm, key).await;
+        return handle_popup_input(state, fsm, key);
     }
 
     // Snapshot dialog takes priority (modal overlay)
     if fsm.snapshot_dialog.is_some() {
-        return handle_snapshot_dialog_input(state, fsm, key).await;
+        return handle_snapshot_dialog_input(state, fsm, key);
+    }
+
+    // Bookmark dialog takes typed text (modal overlay)
+    if fsm.bookmark_dialog.is_some() {
+        return handle_bookmark_dialog_input(state, fsm, key);
+    }
+
+    // Any key closes the square control popup
+    if fsm.square_control.is_some() {
+        fsm.square_control = None;
+        return AppAction::Continue;
     }
 
     // Promotion dialog takes priority (modal overlay)
@@ -100,6 +190,11 @@ pub async fn handle_key(
         return AppAction::Quit;
     }
 
+    // Screen-reader prompt takes every other key while open
+    if fsm.command_line.is_some() {
+        return handle_command_line_input(state, fsm, key);
+    }
+
     // Global toggles that work in any context
     match key.code {
         KeyCode::Char('@') => {
@@ -114,40 +209,193 @@ pub async fn handle_key(
             fsm.toggle_component_visibility(Component::AdvancedAnalysis);
             return AppAction::Continue;
         }
+        KeyCode::Char('%') => {
+            fsm.show_profiler = !fsm.show_profiler;
+            return AppAction::Continue;
+        }
+        KeyCode::Char('^') if fsm.mode == UiMode::GameBoard => {
+            // Selected as it appears, so y/Y copy straight away
+            if fsm.is_component_visible(&Component::PositionPanel) {
+                fsm.set_component_visible(Component::PositionPanel, false);
+                if fsm.focused_component == Some(Component::PositionPanel) {
+                    fsm.clear_focus();
+                }
+            } else {
+                fsm.set_component_visible(Component::PositionPanel, true);
+                fsm.select_component(Component::PositionPanel);
+            }
+            return AppAction::Continue;
+        }
+        KeyCode::Char('!') => {
+            state.notifications.dismiss();
+            fsm.notification_history = Some(NotificationHistoryState::default());
+            return AppAction::Continue;
+        }
+        // Board heatmaps while analysing or reviewing
+        KeyCode::Char('*') if matches!(state.mode, GameMode::Analysis | GameMode::Review) => {
+            fsm.heatmap = fsm.heatmap.next();
+            state.notifications.info(fsm.heatmap.label());
+            return AppAction::Continue;
+        }
+        // Quick snapshot of the live position; the game carries on
+        KeyCode::Char('~') if !matches!(state.mode, GameMode::Review) => {
+            state.dispatch(Action::QuickSnapshot);
+            return AppAction::Continue;
+        }
+        // Review mode has no session to chat in
+        KeyCode::Char('&') if !matches!(state.mode, GameMode::Review) => {
+            fsm.chat = Some(ChatPaneState::default());
+            return AppAction::Continue;
+        }
         _ => {}
     }
 
     // Dispatch by context
     match (fsm.focused_component, fsm.expanded) {
-        (None, _) => handle_board_context(state, fsm, input_buffer, key).await,
+        (None, _) => handle_board_context(state, fsm, input_buffer, key),
         (Some(component), false) => handle_component_selected_context(state, fsm, component, key),
         (Some(component), true) => handle_component_expanded_context(state, fsm, component, key),
     }
 }
 
+/// Mouse handling. Pressing the left button on the review timeline or eval
+/// graph and dragging along it moves the review to the ply under the pointer.
+pub fn handle_mouse(state: &mut GameSession, fsm: &mut UiStateMachine, mouse: MouseEvent) {
+    let Some(review) = state.review_state.as_mut() else {
+        return;
+    };
+    let tracks = [
+        (Component::TimelineScrubber, fsm.scrubber_track.get()),
+        (Component::EvalSparkline, fsm.sparkline_track.get()),
+    ];
+    if tracks.iter().all(|(_, track)| track.is_none()) {
+        fsm.dragging = None;
+        return;
+    }
+    if fsm.popup_menu.is_some()
+        || fsm.snapshot_dialog.is_some()
+        || fsm.bookmark_dialog.is_some()
+        || fsm.notification_history.is_some()
+        || fsm.chat.is_some()
+    {
+        return;
+    }
+
+    match mouse.kind {
+        MouseEventKind::Down(MouseButton::Left) => {
+            let pointer = Position::new(mouse.column, mouse.row);
+            fsm.dragging = tracks
+                .iter()
+                .find(|(_, track)| track.is_some_and(|t| t.contains(pointer)))
+                .map(|&(component, _)| component);
+        }
+        MouseEventKind::Up(MouseButton::Left) => fsm.dragging = None,
+        MouseEventKind::Drag(MouseButton::Left) => {}
+        _ => return,
+    }
+    let Some((component, Some(track))) = tracks
+        .into_iter()
+        .find(|&(component, _)| Some(component) == fsm.dragging)
+    else {
+        return;
+    };
+
+    // Dragging past either end of the track pins to the start or end of the game
+    let col = mouse.column.saturating_sub(track.x);
+    let total = review.review.total_plies;
+    review.auto_play = false;
+    review.go_to_ply(match component {
+        Component::EvalSparkline => ply_at_cell(col, track.width, total),
+        _ => ply_at_column(col, track.width, total),
+    });
+}
+
 /// Handle keys in Board context (default — user is interacting with the chess board).
-async fn handle_board_context(
+fn handle_board_context(
     state: &mut GameSession,
     fsm: &mut UiStateMachine,
     input_buffer: &mut String,
     key: KeyEvent,
 ) -> AppAction {
+    if fsm.screen_reader && key.code == KeyCode::Char(':') {
+        fsm.command_line = Some(String::new());
+        return AppAction::Continue;
+    }
+
     // Review mode: navigation keys instead of move input
-    if matches!(state.mode, GameMode::ReviewMode) {
+    if matches!(state.mode, GameMode::Review) {
         if let Some(ref mut review) = state.review_state {
-            // Shared review navigation (n/p/Space/Home/End)
-            if handle_review_navigation(review, key.code) {
+            // Shared review navigation and auto-play keys
+            if handle_review_navigation(review, fsm, key.code) {
                 return AppAction::Continue;
             }
             match key.code {
                 KeyCode::Right | KeyCode::Char('l') => {
-                    review.next_ply();
+                    let step = fsm.review_key_accel.step(true, Instant::now());
+                    review.go_to_ply(review.current_ply.saturating_add(step));
                     return AppAction::Continue;
                 }
                 KeyCode::Left | KeyCode::Char('h') => {
-                    review.prev_ply();
+                    let step = fsm.review_key_accel.step(false, Instant::now());
+                    review.go_to_ply(review.current_ply.saturating_sub(step));
                     return AppAction::Continue;
                 }
+                KeyCode::Char('a') => {
+                    // Branch into a live analysis session from this position
+                    if review.board_at_ply.status() != cozy_chess::GameStatus::Ongoing {
+                        state.notifications.warn("The game is over at this move");
+                        return AppAction::Continue;
+                    }
+                    let analysis = GameConfig {
+                        mode: GameMode::Analysis,
+                        skill_level: review.skill_level,
+                        start_fen: Some(review.fen_at_ply.clone()),
+                        time_control_seconds: None,
+                        engine_threads: None,
+                        engine_hash_mb: None,
+                        engine_lines: 1,
+                        resume_session_id: None,
+                        resume_game_mode: None,
+                        resume_human_side: None,
+                        resume_skill_level: None,
+                        transfer_code: None,
+                        review_data: None,
+                        review_game_mode: None,
+                        review_skill_level: None,
+                        review_start_ply: None,
+                        pre_history: Some(
+                            review
+                                .move_history
+                                .iter()
+                                .take(review.current_ply as usize)
+                                .cloned()
+                                .collect(),
+                        ),
+                        advanced_data: None,
+                        simul_boards: 1,
+                        opponent: None,
+                        screen_reader: fsm.screen_reader,
+                        notation: fsm.notation,
+                        live_evals: fsm.live_evals,
+                        confirm_moves: state.confirm_moves,
+                    };
+                    // Reopened at the same ply once the analysis ends
+                    let back = GameConfig {
+                        mode: GameMode::Review,
+                        start_fen: None,
+                        pre_history: None,
+                        review_data: Some(review.review.clone()),
+                        review_game_mode: review.game_mode,
+                        review_skill_level: Some(review.skill_level),
+                        review_start_ply: Some(review.current_ply),
+                        advanced_data: review.advanced.clone(),
+                        ..analysis.clone()
+                    };
+                    return AppAction::BranchAnalysis {
+                        analysis: Box::new(analysis),
+                        review: Box::new(back),
+                    };
+                }
                 KeyCode::Char('s') => {
                     // Open snapshot dialog
                     let current_ply = review.current_ply;
@@ -157,6 +405,76 @@ async fn handle_board_context(
                         Some(SnapshotDialogState::new(current_ply, &game_id, positions));
                     return AppAction::Continue;
                 }
+                KeyCode::Char('t') => {
+                    // Cycle the simulated time control: 1+0 .. 10+0, then off
+                    let game_id = review.review.game_id.clone();
+                    match review.cycle_clock_preset() {
+                        Some((initial_ms, increment_ms)) => state.dispatch(Action::SimulateClock {
+                            game_id,
+                            initial_ms,
+                            increment_ms,
+                        }),
+                        None => state.notifications.info("Clock simulation off"),
+                    }
+                    return AppAction::Continue;
+                }
+                KeyCode::Char('d') => {
+                    // Compare with the game's earlier review pass, or hide it
+                    if review.comparison.take().is_some() {
+                        state.notifications.info("Review comparison off");
+                    } else {
+                        let game_id = review.review.game_id.clone();
+                        state.dispatch(Action::CompareReviews { game_id });
+                    }
+                    return AppAction::Continue;
+                }
+                KeyCode::Char('w') => {
+                    // Explain why the engine's move is best here, or hide it
+                    if review.current_explanation().is_some() {
+                        review.explanation = None;
+                    } else if review.current_position().is_some() {
+                        let game_id = review.review.game_id.clone();
+                        let ply = review.current_ply;
+                        state.dispatch(Action::ExplainPosition { game_id, ply });
+                    } else {
+                        state.notifications.info("This move was not analysed");
+                    }
+                    return AppAction::Continue;
+                }
+                KeyCode::Char('r') => {
+                    // Have the engine look at this move again, deeper
+                    if review.current_position().is_some() {
+                        let game_id = review.review.game_id.clone();
+                        let ply = review.current_ply;
+                        state.notifications.info("Re-analysing this move deeper...");
+                        state.dispatch(Action::ReanalyzePosition { game_id, ply });
+                    } else {
+                        state.notifications.info("This move was not analysed");
+                    }
+                    return AppAction::Continue;
+                }
+                KeyCode::Char('b') => {
+                    // Bookmark this position, or edit the note of its bookmark
+                    fsm.bookmark_dialog = Some(BookmarkDialogState::for_current_ply(review));
+                    return AppAction::Continue;
+                }
+                KeyCode::Char('x') => {
+                    // Keep the bookmarked positions as saved positions
+                    let positions = crate::bookmarks::saved_positions(review);
+                    if positions.is_empty() {
+                        state.notifications.info("No bookmarks to save");
+                    } else {
+                        state.dispatch(Action::SaveBookmarkPositions { positions });
+                    }
+                    return AppAction::Continue;
+                }
+                KeyCode::Char('X') => {
+                    // Write the bookmarked moves out as puzzles
+                    let puzzles = crate::bookmarks::puzzles(review);
+                    let game_id = review.review.game_id.clone();
+                    export_puzzles(state, &game_id, &puzzles);
+                    return AppAction::Continue;
+                }
                 KeyCode::Char(c) if ('1'..='4').contains(&c) => {
                     if let Some(target) = Component::from_number_key(c, &fsm.mode) {
                         if fsm.is_component_visible(&target) {
@@ -194,27 +512,36 @@ async fn handle_board_context(
                 GameMode::HumanVsEngine { .. } | GameMode::EngineVsEngine
             ) =>
         {
-            if state.paused {
-                match state.client.resume().await {
-                    Ok(()) => {
-                        state.paused = false;
-                        state.status_message = Some("Playing".to_string());
-                    }
-                    Err(e) => {
-                        state.status_message = Some(format!("Resume error: {}", e));
-                    }
-                }
-            } else {
-                match state.client.pause().await {
-                    Ok(()) => {
-                        state.paused = true;
-                        state.status_message = Some("Paused".to_string());
-                    }
-                    Err(e) => {
-                        state.status_message = Some(format!("Pause error: {}", e));
-                    }
-                }
-            }
+            state.set_paused(!state.paused);
+        }
+        // Engine move speed while spectating
+        KeyCode::Char(c @ ('[' | ']')) if matches!(state.mode, GameMode::EngineVsEngine) => {
+            state.adjust_move_delay(c == ']');
+        }
+        // More engine lines while analysing or spectating
+        KeyCode::Char('m') if state.can_change_engine_lines() => {
+            state.cycle_engine_lines();
+        }
+        // Tactical hints overlay while spectating
+        KeyCode::Char('t') if matches!(state.mode, GameMode::EngineVsEngine) => {
+            state.toggle_tactical_hints();
+        }
+        // Step-through mode while spectating
+        KeyCode::Char('s') if matches!(state.mode, GameMode::EngineVsEngine) => {
+            state.toggle_step_mode();
+        }
+        KeyCode::Char('n')
+            if matches!(state.mode, GameMode::EngineVsEngine) && state.snapshot.step_mode =>
+        {
+            state.step_engine();
+        }
+        // Who controls the typed or selected square while analysing
+        KeyCode::Char('?') if matches!(state.mode, GameMode::Analysis) => {
+            examine_square(state, fsm, input_buffer);
+        }
+        KeyCode::Char('>') if state.simul.is_some() => {
+            input_buffer.clear();
+            return AppAction::NextBoard;
         }
         KeyCode::Char(c) => {
             if !should_disable_input(&state.mode) {
@@ -226,7 +553,7 @@ async fn handle_board_context(
         }
         KeyCode::Enter => {
             if !input_buffer.is_empty() {
-                crate::ui::render_loop::handle_input(state, fsm, input_buffer).await;
+                crate::ui::render_loop::handle_input(state, fsm, input_buffer);
                 input_buffer.clear();
             }
         }
@@ -234,6 +561,9 @@ async fn handle_board_context(
             if state.selected_square.is_some() {
                 state.clear_selection();
                 input_buffer.clear();
+            } else if !state.premoves.is_empty() {
+                state.clear_premoves();
+                input_buffer.clear();
             } else {
                 input_buffer.clear();
                 // Auto-pause on server when opening menu (any mode with an engine)
@@ -244,9 +574,7 @@ async fn handle_board_context(
                 if has_engine {
                     state.paused_before_menu = state.paused;
                     if !state.paused {
-                        let _ = state.client.pause().await;
-                        state.paused = true;
-                        state.status_message = Some("Paused".to_string());
+                        state.set_paused(true);
                     }
                 }
                 fsm.popup_menu = Some(PopupMenuState::new(&state.mode));
@@ -257,21 +585,37 @@ async fn handle_board_context(
     AppAction::Continue
 }
 
+/// Show who controls the square typed into the move input, or else the
+/// selected square, with whether it is an outpost.
+fn examine_square(state: &mut GameSession, fsm: &mut UiStateMachine, input_buffer: &mut String) {
+    use analysis::board_analysis::{square_control, AttackMap};
+
+    let typed = chess::parse_square(input_buffer.trim());
+    let Some(square) = typed.or(state.selected_square) else {
+        state
+            .notifications
+            .info("Type a square (e.g. 'd5') or select a piece, then press ?");
+        return;
+    };
+    input_buffer.clear();
+    let board = state.board();
+    fsm.square_control = Some(square_control(board, &AttackMap::compute(board), square));
+}
+
 /// Restore pause state after popup menu is dismissed.
-async fn restore_pause_state(state: &mut GameSession) {
+fn restore_pause_state(state: &mut GameSession) {
     let has_engine = matches!(
         state.mode,
         GameMode::HumanVsEngine { .. } | GameMode::EngineVsEngine
     );
     if has_engine && !state.paused_before_menu && state.paused {
         // Was not paused before menu — resume on server
-        let _ = state.client.resume().await;
-        state.paused = false;
+        state.set_paused(false);
     }
 }
 
 /// Handle keys when the popup menu is active.
-async fn handle_popup_input(
+fn handle_popup_input(
     state: &mut GameSession,
     fsm: &mut UiStateMachine,
     key: KeyEvent,
@@ -291,14 +635,12 @@ async fn handle_popup_input(
             let selected = fsm.popup_menu.as_ref().map(|m| m.selected_item().clone());
 
             fsm.popup_menu = None;
-            restore_pause_state(state).await;
+            restore_pause_state(state);
 
             if let Some(item) = selected {
                 match item {
                     PopupMenuItem::Restart => {
-                        if let Err(e) = state.reset(None).await {
-                            state.status_message = Some(format!("Reset error: {}", e));
-                        }
+                        state.reset();
                     }
                     PopupMenuItem::AdjustDifficulty => {
                         let new_level = match state.skill_level {
@@ -307,18 +649,7 @@ async fn handle_popup_input(
                             13..=18 => 20,
                             _ => 3,
                         };
-                        if let Err(e) = state.set_engine(true, new_level).await {
-                            state.status_message = Some(format!("Engine error: {}", e));
-                        } else {
-                            let label = match new_level {
-                                3 => "Beginner",
-                                10 => "Intermediate",
-                                15 => "Advanced",
-                                20 => "Master",
-                                _ => "Custom",
-                            };
-                            state.status_message = Some(format!("Difficulty set to {}", label));
-                        }
+                        state.change_skill_level(new_level);
                     }
                     PopupMenuItem::SuspendSession => {
                         return AppAction::SuspendAndReturnToMenu;
@@ -331,15 +662,79 @@ async fn handle_popup_input(
         }
         KeyCode::Esc => {
             fsm.popup_menu = None;
-            restore_pause_state(state).await;
+            restore_pause_state(state);
         }
         _ => {}
     }
     AppAction::Continue
 }
 
+/// Handle keys while the bookmark dialog is open (modal overlay).
+fn handle_bookmark_dialog_input(
+    state: &mut GameSession,
+    fsm: &mut UiStateMachine,
+    key: KeyEvent,
+) -> AppAction {
+    let Some(dialog) = fsm.bookmark_dialog.as_mut() else {
+        return AppAction::Continue;
+    };
+
+    match key.code {
+        KeyCode::Esc => {
+            fsm.bookmark_dialog = None;
+        }
+        KeyCode::Char(c) => {
+            dialog.note.push(c);
+        }
+        KeyCode::Backspace => {
+            dialog.note.pop();
+        }
+        KeyCode::Delete if dialog.existing => {
+            let dialog = fsm.bookmark_dialog.take().unwrap();
+            state.dispatch(Action::DeleteBookmark {
+                game_id: dialog.game_id,
+                ply: dialog.ply,
+            });
+        }
+        KeyCode::Enter => {
+            let dialog = fsm.bookmark_dialog.take().unwrap();
+            state.dispatch(Action::SetBookmark {
+                game_id: dialog.game_id,
+                ply: dialog.ply,
+                note: dialog.note,
+            });
+        }
+        _ => {}
+    }
+    AppAction::Continue
+}
+
+/// Write a reviewed game's bookmarked moves as EPD puzzles under the data
+/// directory.
+fn export_puzzles(state: &mut GameSession, game_id: &str, puzzles: &[String]) {
+    if puzzles.is_empty() {
+        state
+            .notifications
+            .info("No analysed bookmarked moves to export");
+        return;
+    }
+    let Some(dir) = crate::bookmarks::default_export_dir() else {
+        state.notifications.error("No data directory to export to");
+        return;
+    };
+    match crate::bookmarks::export(&dir, game_id, puzzles) {
+        Ok(path) => state.notifications.success(format!(
+            "Exported {} puzzle{} to {}",
+            puzzles.len(),
+            if puzzles.len() == 1 { "" } else { "s" },
+            path.display()
+        )),
+        Err(e) => state.notifications.error(format!("Export failed: {}", e)),
+    }
+}
+
 /// Handle keys when the snapshot dialog is active (modal overlay).
-async fn handle_snapshot_dialog_input(
+fn handle_snapshot_dialog_input(
     state: &mut GameSession,
     fsm: &mut UiStateMachine,
     key: KeyEvent,
@@ -390,8 +785,9 @@ async fn handle_snapshot_dialog_input(
         KeyCode::Enter => {
             // Block confirm if target position is terminal
             if dialog.is_target_terminal {
-                state.status_message =
-                    Some("Cannot create snapshot at a terminal position".to_string());
+                state
+                    .notifications
+                    .warn("Cannot create snapshot at a terminal position");
                 return AppAction::Continue;
             }
 
@@ -431,12 +827,13 @@ async fn handle_snapshot_dialog_input(
                 let skill_level = review.skill_level;
 
                 // Determine local GameMode from proto
-                let mode = game_mode
-                    .as_ref()
-                    .map(crate::state::game_mode_from_proto)
-                    .unwrap_or(GameMode::HumanVsEngine {
-                        human_side: crate::state::PlayerColor::White,
-                    });
+                let mode =
+                    game_mode
+                        .as_ref()
+                        .map(GameMode::from)
+                        .unwrap_or(GameMode::HumanVsEngine {
+                            human_side: crate::state::PlayerSide::White,
+                        });
 
                 let config = GameConfig {
                     mode,
@@ -445,15 +842,24 @@ async fn handle_snapshot_dialog_input(
                     time_control_seconds: None,
                     engine_threads: None,
                     engine_hash_mb: None,
+                    engine_lines: 1,
                     resume_session_id: None,
                     resume_game_mode: None,
                     resume_human_side: None,
                     resume_skill_level: None,
+                    transfer_code: None,
                     review_data: None,
                     review_game_mode: None,
                     review_skill_level: None,
+                    review_start_ply: None,
                     pre_history: Some(pre_history),
                     advanced_data: None,
+                    simul_boards: 1,
+                    opponent: None,
+                    screen_reader: fsm.screen_reader,
+                    notation: fsm.notation,
+                    live_evals: fsm.live_evals,
+                    confirm_moves: state.confirm_moves,
                 };
                 return AppAction::PlaySnapshot(Box::new(config));
             } else {
@@ -462,19 +868,9 @@ async fn handle_snapshot_dialog_input(
                 let skill_level = review.skill_level;
                 let move_count = target_ply;
                 let name = dialog.effective_name();
+                let source_game = review.review.game_id.clone();
 
-                match state
-                    .client
-                    .save_snapshot(&fen, &name, game_mode, move_count, skill_level)
-                    .await
-                {
-                    Ok(_) => {
-                        state.status_message = Some("Snapshot saved".to_string());
-                    }
-                    Err(e) => {
-                        state.status_message = Some(format!("Failed to save snapshot: {}", e));
-                    }
-                }
+                state.save_snapshot(fen, name, game_mode, move_count, skill_level, source_game);
             }
         }
         _ => {}
@@ -489,10 +885,10 @@ fn handle_component_selected_context(
     component: Component,
     key: KeyEvent,
 ) -> AppAction {
-    // Forward review navigation keys (n/p/Space/Home/End) from component context
-    if matches!(state.mode, GameMode::ReviewMode) {
+    // Forward review navigation keys from component context
+    if matches!(state.mode, GameMode::Review) {
         if let Some(ref mut review) = state.review_state {
-            if handle_review_navigation(review, key.code) {
+            if handle_review_navigation(review, fsm, key.code) {
                 return AppAction::Continue;
             }
         }
@@ -500,6 +896,17 @@ fn handle_component_selected_context(
 
     let layout = fsm.layout(state);
     match key.code {
+        KeyCode::Char(c @ ('y' | 'Y')) if component == Component::PositionPanel => {
+            copy_position(state, c == 'Y');
+        }
+        KeyCode::Char('r') if component == Component::EnginePanel => {
+            fsm.raw_pv = !fsm.raw_pv;
+        }
+        KeyCode::Char(c) if component == Component::AdvancedAnalysis && c.is_ascii_digit() => {
+            if let Some(tab) = AdvancedTab::from_number_key(c) {
+                fsm.advanced_tab = tab;
+            }
+        }
         KeyCode::Left | KeyCode::Char('h') => {
             if let Some(prev) = fsm.prev_section(component, &layout) {
                 fsm.select_component(prev);
@@ -565,6 +972,27 @@ fn handle_component_selected_context(
     AppAction::Continue
 }
 
+/// Copy the current position's FEN, or the game so far as PGN, to the
+/// clipboard.
+fn copy_position(state: &mut GameSession, as_pgn: bool) {
+    let (what, text) = if as_pgn {
+        (
+            "PGN",
+            position_panel::pgn(&state.snapshot.start_fen, state.history()),
+        )
+    } else {
+        ("FEN", state.board().to_string())
+    };
+    match clipboard::copy(&text) {
+        Ok(()) => state
+            .notifications
+            .success(format!("Copied {} to clipboard", what)),
+        Err(e) => state
+            .notifications
+            .warn(format!("Could not copy {}: {}", what, e)),
+    }
+}
+
 /// Handle keys in ComponentExpanded context (a component fills the board area).
 fn handle_component_expanded_context(
     state: &mut GameSession,
@@ -572,16 +1000,27 @@ fn handle_component_expanded_context(
     component: Component,
     key: KeyEvent,
 ) -> AppAction {
-    // Forward review navigation keys (n/p/Space/Home/End) from expanded pane
-    if matches!(state.mode, GameMode::ReviewMode) {
+    // Forward review navigation keys from expanded pane
+    if matches!(state.mode, GameMode::Review) {
         if let Some(ref mut review) = state.review_state {
-            if handle_review_navigation(review, key.code) {
+            if handle_review_navigation(review, fsm, key.code) {
                 return AppAction::Continue;
             }
         }
     }
 
     match key.code {
+        KeyCode::Char(c @ ('y' | 'Y')) if component == Component::PositionPanel => {
+            copy_position(state, c == 'Y');
+        }
+        KeyCode::Char('r') if component == Component::EnginePanel => {
+            fsm.raw_pv = !fsm.raw_pv;
+        }
+        KeyCode::Char(c) if component == Component::AdvancedAnalysis && c.is_ascii_digit() => {
+            if let Some(tab) = AdvancedTab::from_number_key(c) {
+                fsm.advanced_tab = tab;
+            }
+        }
         KeyCode::Up | KeyCode::Char('k') => {
             let scroll = fsm.component_scroll_mut(&component);
             *scroll = scroll.saturating_sub(SCROLL_INCREMENT);
@@ -604,6 +1043,92 @@ fn handle_component_expanded_context(
     AppAction::Continue
 }
 
+/// Handle keys while the notification history overlay is open.
+fn handle_notification_history_input(fsm: &mut UiStateMachine, key: KeyEvent) -> AppAction {
+    if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+        return AppAction::Quit;
+    }
+    let Some(history) = fsm.notification_history.as_mut() else {
+        return AppAction::Continue;
+    };
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('!') | KeyCode::Char('q') => {
+            fsm.notification_history = None;
+        }
+        KeyCode::Down | KeyCode::Char('j') => {
+            history.scroll = history.scroll.saturating_add(1);
+        }
+        KeyCode::Up | KeyCode::Char('k') => {
+            history.scroll = history.scroll.saturating_sub(1);
+        }
+        KeyCode::PageDown => {
+            history.scroll = history.scroll.saturating_add(SCROLL_INCREMENT * 2);
+        }
+        KeyCode::PageUp => {
+            history.scroll = history.scroll.saturating_sub(SCROLL_INCREMENT * 2);
+        }
+        KeyCode::Home => history.scroll = 0,
+        _ => {}
+    }
+    AppAction::Continue
+}
+
+/// Handle keys while the chat pane is open. Printable keys go to the
+/// draft; Enter sends it.
+fn handle_chat_input(
+    state: &mut GameSession,
+    fsm: &mut UiStateMachine,
+    key: KeyEvent,
+) -> AppAction {
+    if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+        return AppAction::Quit;
+    }
+    let Some(chat) = fsm.chat.as_mut() else {
+        return AppAction::Continue;
+    };
+    match key.code {
+        KeyCode::Esc => fsm.chat = None,
+        KeyCode::Enter => {
+            if let Some(text) = chat.take_draft() {
+                state.send_chat(text);
+            }
+        }
+        KeyCode::Backspace => {
+            chat.draft.pop();
+        }
+        KeyCode::Up => chat.scroll = chat.scroll.saturating_add(1),
+        KeyCode::Down => chat.scroll = chat.scroll.saturating_sub(1),
+        KeyCode::PageUp => chat.scroll = chat.scroll.saturating_add(SCROLL_INCREMENT * 2),
+        KeyCode::PageDown => chat.scroll = chat.scroll.saturating_sub(SCROLL_INCREMENT * 2),
+        KeyCode::Char(c) => chat.push_char(c),
+        _ => {}
+    }
+    AppAction::Continue
+}
+
+/// The answer a key gives to a move waiting for confirmation: `Some(true)`
+/// plays it, `Some(false)` drops it, other keys leave it waiting.
+fn confirm_answer(code: KeyCode) -> Option<bool> {
+    match code {
+        KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char(' ') => Some(true),
+        KeyCode::Esc | KeyCode::Char('n') | KeyCode::Backspace => Some(false),
+        _ => None,
+    }
+}
+
+/// Handle keys while a move waits for confirmation.
+fn handle_pending_move_input(state: &mut GameSession, key: KeyEvent) -> AppAction {
+    if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+        return AppAction::Quit;
+    }
+    match confirm_answer(key.code) {
+        Some(true) => state.confirm_pending_move(),
+        Some(false) => state.cancel_pending_move(),
+        None => {}
+    }
+    AppAction::Continue
+}
+
 /// Handle keys when the promotion dialog is active (modal overlay).
 fn handle_promotion_input(
     state: &mut GameSession,
@@ -627,12 +1152,124 @@ fn handle_promotion_input(
     AppAction::Continue
 }
 
-/// Handle keys when tab input mode is active (modal overlay).
-async fn handle_tab_input(
+/// Handle keys at the screen-reader prompt. Enter plays a typed move or
+/// answers a command into the transcript.
+fn handle_command_line_input(
     state: &mut GameSession,
     fsm: &mut UiStateMachine,
     key: KeyEvent,
 ) -> AppAction {
+    let Some(line) = fsm.command_line.as_mut() else {
+        return AppAction::Continue;
+    };
+    match key.code {
+        KeyCode::Esc => fsm.command_line = None,
+        KeyCode::Backspace => {
+            line.pop();
+        }
+        KeyCode::Char(c) => line.push(c),
+        KeyCode::Enter => {
+            let command = std::mem::take(line);
+            fsm.command_line = None;
+            if command.trim().is_empty() {
+                return AppAction::Continue;
+            }
+            fsm.narration.say(format!("> {}", command.trim()));
+            match narration::parse_typed_move(&command) {
+                Some(_) if should_disable_input(&state.mode) => {
+                    fsm.narration.say("Moves cannot be played here.");
+                }
+                Some((from, to, promotion)) => {
+                    // Rejections arrive as warnings, which are narrated
+                    let _ = play_move(state, from, to, promotion);
+                }
+                None => {
+                    for reply in narration::run_command(state, &command) {
+                        fsm.narration.say(reply);
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+    AppAction::Continue
+}
+
+/// Play a move by its squares, as if they were clicked, promoting to
+/// `promotion` or a queen. Rejections are shown as notifications and
+/// returned.
+fn play_move(
+    state: &mut GameSession,
+    from: cozy_chess::Square,
+    to: cozy_chess::Square,
+    promotion: Option<cozy_chess::Piece>,
+) -> Result<(), String> {
+    state.select_square(from);
+    if state.selected_square != Some(from) {
+        return Err("No piece on that square or not your turn".to_string());
+    }
+    if let Err(e) = state.try_move_to(to) {
+        state.notifications.error(format!("Move error: {}", e));
+        state.clear_selection();
+        return Err(e);
+    }
+    if state.selected_square.is_some() {
+        // Still selected: the move promotes
+        state.execute_promotion(from, to, promotion.unwrap_or(cozy_chess::Piece::Queen));
+    }
+    Ok(())
+}
+
+/// Handle a request from an external input tool. Key presses go through
+/// [`handle_key`], so they can quit or leave the game like typed keys.
+pub fn handle_external(
+    state: &mut GameSession,
+    fsm: &mut UiStateMachine,
+    input_buffer: &mut String,
+    request: &external_input::Request,
+) -> (AppAction, external_input::Reply) {
+    use external_input::{Reply, Request};
+
+    let reply = match request {
+        Request::Move { text } if should_disable_input(&state.mode) => {
+            Reply::error(format!("Moves cannot be played here: {}", text))
+        }
+        Request::Move { text } => match external_input::parse_move(state.board(), text) {
+            Some((from, to, promotion)) => {
+                if fsm.screen_reader {
+                    fsm.narration.say(format!("> {}", text.trim()));
+                }
+                match play_move(state, from, to, promotion) {
+                    Ok(()) => Reply::ok(Vec::new()),
+                    Err(e) => Reply::error(e),
+                }
+            }
+            None => Reply::error(format!("Not a legal move: {}", text)),
+        },
+        Request::Keys { keys } => {
+            let events: Option<Vec<KeyEvent>> =
+                keys.iter().map(|k| external_input::parse_key(k)).collect();
+            let Some(events) = events else {
+                return (
+                    AppAction::Continue,
+                    Reply::error(format!("Unknown key in {:?}", keys)),
+                );
+            };
+            for key in events {
+                let action = handle_key(state, fsm, input_buffer, key);
+                if !matches!(action, AppAction::Continue) {
+                    return (action, Reply::ok(Vec::new()));
+                }
+            }
+            Reply::ok(Vec::new())
+        }
+        Request::Query { query } => Reply::ok(narration::run_command(state, query)),
+    };
+    (AppAction::Continue, reply)
+}
+
+/// Handle keys when tab input mode is active (modal overlay).
+fn handle_tab_input(state: &mut GameSession, fsm: &mut UiStateMachine, key: KeyEvent) -> AppAction {
     use chess::parse_square;
 
     match key.code {
@@ -672,8 +1309,10 @@ async fn handle_tab_input(
             // Tab 1: auto-advance on valid 2-char piece square
             if fsm.tab_input.current_tab == 0 && fsm.tab_input.typeahead_buffer.len() == 2 {
                 if let Some(from_square) = parse_square(&fsm.tab_input.typeahead_buffer) {
-                    if state.selectable_squares.contains(&from_square) {
+                    if state.selectable_squares.contains(&from_square) || state.can_premove() {
                         state.select_square(from_square);
+                    }
+                    if state.selected_square == Some(from_square) {
                         fsm.tab_input.advance_to_destination(from_square);
                     } else {
                         // Invalid piece — clear buffer
@@ -693,12 +1332,20 @@ async fn handle_tab_input(
                     if let Some(to_square) = parse_square(&typeahead) {
                         // Verify it's a legal destination
                         if let Some(from_square) = fsm.tab_input.from_square {
+                            // Any destination can be premoved to; it is checked when played
+                            if state.can_premove() {
+                                fsm.tab_input.deactivate();
+                                if let Err(e) = state.try_move_to(to_square) {
+                                    state.notifications.error(format!("Premove failed: {}", e));
+                                }
+                                return AppAction::Continue;
+                            }
                             if let Some(moves) = state.legal_moves_from(from_square) {
                                 let to_str = chess::format_square(to_square);
                                 if moves.iter().any(|m| m.to == to_str) {
                                     fsm.tab_input.deactivate();
-                                    if let Err(e) = state.try_move_to(to_square).await {
-                                        state.status_message = Some(format!("Move failed: {}", e));
+                                    if let Err(e) = state.try_move_to(to_square) {
+                                        state.notifications.error(format!("Move failed: {}", e));
                                     }
                                     return AppAction::Continue;
                                 }
@@ -729,7 +1376,7 @@ mod tests {
     #[test]
     fn test_input_enabled_human_vs_engine() {
         assert!(!should_disable_input(&GameMode::HumanVsEngine {
-            human_side: crate::state::PlayerColor::White,
+            human_side: crate::state::PlayerSide::White,
         }));
     }
 
@@ -737,4 +1384,14 @@ mod tests {
     fn test_input_enabled_human_vs_human() {
         assert!(!should_disable_input(&GameMode::HumanVsHuman));
     }
+
+    #[test]
+    fn test_confirm_answer_keys() {
+        assert_eq!(confirm_answer(KeyCode::Enter), Some(true));
+        assert_eq!(confirm_answer(KeyCode::Char('y')), Some(true));
+        assert_eq!(confirm_answer(KeyCode::Esc), Some(false));
+        assert_eq!(confirm_answer(KeyCode::Char('n')), Some(false));
+        // A stray key leaves the move waiting
+        assert_eq!(confirm_answer(KeyCode::Char('e')), None);
+    }
 }
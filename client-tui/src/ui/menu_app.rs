@@ -1,5 +1,14 @@
-use crate::state::{GameMode, PlayerColor};
+use crate::review_progress::{progress_label, status_of, ReviewProgressTracker};
+use crate::state::{GameMode, PlayerSide};
+use crate::ui::widgets::activity_heatmap::{ActivityHeatmap, HEATMAP_HEIGHT};
+use crate::ui::widgets::chapter_view::{
+    self, ChapterDialog, ChapterDraft, ChapterInput, ChapterView,
+};
+use crate::ui::widgets::goals_panel::{GoalDialog, GoalDraft, GoalsPanel};
 use crate::ui::widgets::selectable_table::SelectableTableState;
+use crate::ui::widgets::study_browser::{self, StudyDialog, StudyDraft, StudyTreeView};
+use crate::ui::widgets::training_digest::TrainingDigestView;
+use crate::ui::widgets::transfer_dialog::{push_code_char, TransferDialogWidget};
 use crate::ui::widgets::{
     render_table_overlay, FenDialogState, FenDialogWidget, MenuState, MenuWidget,
     TableOverlayParams,
@@ -9,7 +18,11 @@ use crossterm::{
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
-use ratatui::{backend::CrosstermBackend, layout::Constraint, Terminal};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Constraint, Direction, Layout, Rect},
+    Terminal,
+};
 use std::io;
 use std::time::Duration;
 
@@ -21,23 +34,44 @@ pub struct GameConfig {
     pub time_control_seconds: Option<u64>,
     pub engine_threads: Option<u32>,
     pub engine_hash_mb: Option<u32>,
+    /// Lines the engine searches at once from the start (MultiPV); 1 for
+    /// the best move only.
+    pub engine_lines: u32,
     /// If set, resume this suspended session by ID instead of starting a new game.
     pub resume_session_id: Option<String>,
     /// Metadata from the suspended session (game mode, skill level etc.)
     pub resume_game_mode: Option<i32>,
     pub resume_human_side: Option<i32>,
     pub resume_skill_level: Option<u8>,
+    /// If set, take over the session another client issued this transfer
+    /// code for instead of starting a new game.
+    pub transfer_code: Option<String>,
     /// If set, enter review mode with this pre-fetched review data.
     pub review_data: Option<chess_client::GameReviewProto>,
     /// Original game mode from a finished game (for snapshot creation during review).
     pub review_game_mode: Option<chess_client::GameModeProto>,
     /// Original skill level from a finished game (for snapshot creation during review).
     pub review_skill_level: Option<u8>,
+    /// Ply a review opens at, e.g. on coming back from a branch; the start
+    /// of the game when unset.
+    pub review_start_ply: Option<u32>,
     /// Pre-history moves from a snapshot (moves played before the snapshot position).
     /// `Some(...)` indicates a snapshot game, which implies paused start for engine modes.
     pub pre_history: Option<Vec<chess_client::MoveRecord>>,
     /// Advanced analysis data (tactical patterns, king safety, tension, psychological profiles).
     pub advanced_data: Option<chess_client::AdvancedGameAnalysisProto>,
+    /// Number of engine boards to play at once; 1 is a regular game.
+    pub simul_boards: u8,
+    /// Name of the engine opponent profile, recorded with the finished game.
+    pub opponent: Option<String>,
+    /// Narrate the game as text instead of drawing the board.
+    pub screen_reader: bool,
+    /// How moves are written in the history and review panels.
+    pub notation: chess::Notation,
+    /// Show the engine eval after each move in the history of a live game.
+    pub live_evals: bool,
+    /// Hold each move until it is confirmed with a second key press.
+    pub confirm_moves: bool,
 }
 
 /// Actions returned from the menu.
@@ -46,16 +80,122 @@ pub enum MenuAction {
     StartGame(Box<GameConfig>),
     /// Enqueue a game for review analysis, then return to menu.
     EnqueueReview(String),
+    /// Open the beginner tutorial, then return to menu.
+    Tutorial,
+    /// Run the demo until a key is pressed, then return to menu.
+    Demo,
+    /// Fetch the combined report for a simul, then return to menu showing it.
+    SimulReport(String),
+    /// Fetch activity and head-to-head stats, then return to menu showing them.
+    Stats,
+    /// Fetch the weekly training digest, then return to menu showing it.
+    Digest,
+    /// Save a new goal for the current profile, then return to the Stats screen.
+    AddGoal {
+        metric: chess_client::GoalMetricType,
+        target: f64,
+        period: chess_client::GoalPeriodType,
+    },
+    /// Delete one of the current profile's goals, then return to the Stats screen.
+    DeleteGoal(String),
+    /// Fetch the list of studies, then return to menu showing it.
+    Studies,
+    /// Build a study from the matching finished games, then return to the list.
+    CreateStudy {
+        name: String,
+        filter: chess_client::StudyFilterProto,
+    },
+    /// Create a study without games, then open its chapter list.
+    CreateBlankStudy(String),
+    /// Fetch a study's tree, then return to menu browsing it.
+    OpenStudy(String),
+    /// Delete a study, then return to the list.
+    DeleteStudy(String),
+    /// Export a study as a PGN file, then return to the list.
+    ExportStudy(String),
+    /// Add a chapter from a FEN, a finished game or the start position.
+    AddChapter {
+        study_id: String,
+        name: String,
+        fen: Option<String>,
+        game_id: Option<String>,
+    },
+    DeleteChapter {
+        study_id: String,
+        chapter_id: String,
+    },
+    /// Move a chapter to `index` in the study's list.
+    MoveChapter {
+        study_id: String,
+        chapter_id: String,
+        index: u32,
+    },
+    /// Play a move from a chapter node, then return to the chapter at it.
+    AddChapterMove {
+        study_id: String,
+        chapter_id: String,
+        node_id: u32,
+        notation: String,
+    },
+    /// Delete a chapter node and its moves, then return to its parent.
+    DeleteChapterMove {
+        study_id: String,
+        chapter_id: String,
+        node_id: u32,
+    },
+    /// Replace a chapter node's comment, NAGs and arrows.
+    AnnotateChapterNode {
+        study_id: String,
+        chapter_id: String,
+        node_id: u32,
+        comment: Option<String>,
+        nags: Vec<u32>,
+        arrows: Vec<chess_client::ChapterArrow>,
+    },
     /// User chose to quit.
     Quit,
 }
 
+/// A server report shown as an overlay when the menu reopens.
+pub enum MenuReport {
+    Simul(chess_client::GetSimulReportResponse),
+    Stats {
+        opponents: Vec<chess_client::OpponentStatsProto>,
+        activity: Option<chess_client::GetActivitySummaryResponse>,
+        goals: Vec<chess_client::GoalProgressProto>,
+    },
+    Digest(chess_client::GetTrainingDigestResponse),
+    Studies {
+        studies: Vec<chess_client::StudyInfo>,
+        /// Outcome of the action that led back to the list.
+        status: Option<String>,
+    },
+    Study(chess_client::GetStudyResponse),
+    Chapters {
+        study: chess_client::GetStudyResponse,
+        /// Chapter to highlight, if it is still in the study.
+        selected: Option<String>,
+        status: Option<String>,
+    },
+    Chapter {
+        study_id: String,
+        chapter: chess_client::StudyChapterProto,
+        node_id: u32,
+        status: Option<String>,
+    },
+}
+
 /// Show menu and get game configuration.
 /// Pre-fetched data from the server is passed in to avoid async calls during menu rendering.
+/// A report, if given, is shown as an overlay when the menu opens.
 pub async fn show_menu(
     suspended_sessions: Vec<chess_client::SuspendedSessionInfo>,
     saved_positions: Vec<chess_client::SavedPosition>,
     finished_games: Vec<chess_client::FinishedGameInfo>,
+    report: Option<MenuReport>,
+    offline: bool,
+    engine_missing: bool,
+    review_progress: ReviewProgressTracker,
 ) -> anyhow::Result<MenuAction> {
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -72,10 +212,100 @@ pub async fn show_menu(
         has_saved_session,
         has_finished_games,
         finished_games,
+        opponents: crate::opponents::OpponentRoster::load_default().opponents,
+        offline,
+        engine_missing,
+        review_progress,
         ..Default::default()
     };
+    if engine_missing {
+        menu_state.game_mode = crate::ui::widgets::menu::GameModeOption::HumanVsHuman;
+    }
+    match report {
+        Some(MenuReport::Simul(report)) => {
+            use crate::ui::widgets::menu::SimulReportContext;
+            menu_state.simul_report = Some(SimulReportContext {
+                table_state: SelectableTableState::new(report.boards.len()),
+                report,
+            });
+        }
+        Some(MenuReport::Stats {
+            opponents,
+            activity,
+            goals,
+        }) => {
+            use crate::ui::widgets::menu::StatsContext;
+            menu_state.stats = Some(StatsContext {
+                table_state: SelectableTableState::new(opponents.len()),
+                opponents,
+                activity,
+                goals_focused: !goals.is_empty(),
+                goals,
+                selected_goal: 0,
+                goal_draft: None,
+            });
+        }
+        Some(MenuReport::Digest(digest)) => {
+            use crate::ui::widgets::menu::DigestContext;
+            menu_state.digest = Some(DigestContext {
+                digest,
+                scroll: 0,
+                status: None,
+            });
+        }
+        Some(MenuReport::Studies { studies, status }) => {
+            use crate::ui::widgets::menu::StudiesContext;
+            menu_state.studies = Some(StudiesContext {
+                table_state: SelectableTableState::new(studies.len()),
+                studies,
+                draft: None,
+                status,
+            });
+        }
+        Some(MenuReport::Study(study)) => {
+            use crate::ui::widgets::menu::StudyContext;
+            if study.game_ids.is_empty() {
+                // Nothing to browse but chapters
+                menu_state.chapters = Some(chapters_context(study, None, None));
+            } else {
+                menu_state.study = Some(StudyContext {
+                    study,
+                    path: vec![],
+                    selected: 0,
+                });
+            }
+        }
+        Some(MenuReport::Chapters {
+            study,
+            selected,
+            status,
+        }) => {
+            menu_state.chapters = Some(chapters_context(study, selected, status));
+        }
+        Some(MenuReport::Chapter {
+            study_id,
+            chapter,
+            node_id,
+            status,
+        }) => {
+            use crate::ui::widgets::menu::ChapterContext;
+            menu_state.chapter = Some(ChapterContext {
+                study_id,
+                chapter,
+                node_id,
+                selected: 0,
+                input: None,
+                status,
+            });
+        }
+        None => {}
+    }
+
+    let idle_timeout = crate::attract::idle_timeout();
+    let mut last_input = std::time::Instant::now();
 
     let result = loop {
+        apply_review_progress(&mut menu_state);
         terminal.draw(|f| {
             let menu_widget = MenuWidget {
                 menu_state: &menu_state,
@@ -88,6 +318,11 @@ pub async fn show_menu(
                 f.render_widget(fen_dialog, f.area());
             }
 
+            // Render the transfer code dialog if active
+            if let Some(ref code) = menu_state.transfer_code {
+                f.render_widget(TransferDialogWidget { code }, f.area());
+            }
+
             // Render review game selection table if active
             if let Some(ref mut ctx) = menu_state.review_table {
                 let rows: Vec<Vec<String>> = ctx
@@ -95,7 +330,10 @@ pub async fn show_menu(
                     .iter()
                     .map(|g| {
                         let result = &g.result;
-                        let reason = if g.result_reason.len() > 15 {
+                        let reason = if let Some(ref derived) = g.derived_result {
+                            // The PGN claimed another result than the game shows
+                            format!("Shows {}", derived)
+                        } else if g.result_reason.len() > 15 {
                             format!("{}...", &g.result_reason[..12])
                         } else {
                             g.result_reason.clone()
@@ -113,27 +351,269 @@ pub async fn show_menu(
                                 chess_client::ReviewStatusType::ReviewStatusFailed => "Failed",
                             })
                             .unwrap_or("Not reviewed");
-                        vec![result.clone(), reason, moves, status.to_string()]
+                        // A bar and the time left while a followed review runs
+                        let status = menu_state
+                            .review_progress
+                            .get(&g.game_id)
+                            .as_ref()
+                            .and_then(progress_label)
+                            .unwrap_or_else(|| status.to_string());
+                        let eco = g
+                            .opening
+                            .as_ref()
+                            .map(|o| o.eco.clone())
+                            .unwrap_or_default();
+                        let endgame = game_endgame(g).map(endgame_name).unwrap_or_default();
+                        vec![
+                            result.clone(),
+                            reason,
+                            moves,
+                            eco,
+                            endgame.to_string(),
+                            status,
+                        ]
                     })
                     .collect();
+                let title = match ctx.endgame_filter {
+                    Some(endgame) => format!("Review Game · {} endgames", endgame_name(endgame)),
+                    None => "Review Game".to_string(),
+                };
 
                 render_table_overlay(
                     f.area(),
                     f.buffer_mut(),
                     TableOverlayParams {
-                        title: "Review Game",
-                        headers: &["Result", "Reason", "Moves", "Status"],
+                        title: &title,
+                        headers: &["Result", "Reason", "Moves", "ECO", "Endgame", "Status"],
                         rows: &rows,
                         column_widths: &[
                             Constraint::Length(12),
                             Constraint::Length(16),
                             Constraint::Length(10),
+                            Constraint::Length(5),
+                            Constraint::Length(16),
                             Constraint::Length(14),
                         ],
                         state: &mut ctx.table_state,
-                        width: 65,
+                        width: 88,
                         height: (ctx.games.len() as u16 + 6).min(20),
-                        footer: Some("Enter: View reviewed | a: Analyze | Esc: Back"),
+                        footer: Some(if menu_state.offline {
+                            "Enter: View reviewed | e: Endgame | Esc: Back (offline, read-only)"
+                        } else {
+                            "Enter: View reviewed | a: Analyze | s: Simul report | e: Endgame | Esc: Back"
+                        }),
+                    },
+                );
+            }
+
+            // Render simul report if active
+            if let Some(ref mut ctx) = menu_state.simul_report {
+                let rows = simul_report_rows(&ctx.report);
+                let title = simul_report_title(&ctx.report);
+                render_table_overlay(
+                    f.area(),
+                    f.buffer_mut(),
+                    TableOverlayParams {
+                        title: &title,
+                        headers: &["Board", "Result", "Reason", "Moves", "Accuracy"],
+                        rows: &rows,
+                        column_widths: &[
+                            Constraint::Length(7),
+                            Constraint::Length(8),
+                            Constraint::Length(16),
+                            Constraint::Length(7),
+                            Constraint::Length(14),
+                        ],
+                        state: &mut ctx.table_state,
+                        width: 65,
+                        height: (ctx.report.boards.len() as u16 + 6).min(20),
+                        footer: Some("Esc: Back"),
+                    },
+                );
+            }
+
+            // Render the Stats screen if active: heat-map, goals, then the head-to-head table
+            if let Some(ref mut ctx) = menu_state.stats {
+                let chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([
+                        Constraint::Length(HEATMAP_HEIGHT + 1),
+                        Constraint::Length(GoalsPanel::height(&ctx.goals)),
+                        Constraint::Min(0),
+                    ])
+                    .split(f.area());
+                let (heatmap_area, goals_area, table_area) = (chunks[0], chunks[1], chunks[2]);
+                if let Some(ref activity) = ctx.activity {
+                    let width = heatmap_area.width.min(80);
+                    let area = Rect {
+                        x: heatmap_area.x + (heatmap_area.width - width) / 2,
+                        y: heatmap_area.y + 1,
+                        width,
+                        height: HEATMAP_HEIGHT.min(heatmap_area.height.saturating_sub(1)),
+                    };
+                    f.render_widget(ActivityHeatmap { summary: activity }, area);
+                }
+
+                let width = goals_area.width.min(80);
+                f.render_widget(
+                    GoalsPanel {
+                        goals: &ctx.goals,
+                        selected: ctx.goals_focused.then_some(ctx.selected_goal),
+                    },
+                    Rect {
+                        x: goals_area.x + (goals_area.width - width) / 2,
+                        width,
+                        ..goals_area
+                    },
+                );
+
+                let rows = opponent_stats_rows(&ctx.opponents);
+                render_table_overlay(
+                    table_area,
+                    f.buffer_mut(),
+                    TableOverlayParams {
+                        title: "Head-to-Head",
+                        headers: &["Opponent", "Games", "W-D-L", "Perf", "Accuracy", "Skill"],
+                        rows: &rows,
+                        column_widths: &[
+                            Constraint::Min(14),
+                            Constraint::Length(6),
+                            Constraint::Length(9),
+                            Constraint::Length(6),
+                            Constraint::Length(13),
+                            Constraint::Length(6),
+                        ],
+                        state: &mut ctx.table_state,
+                        width: 70,
+                        height: (ctx.opponents.len() as u16 + 6).min(table_area.height),
+                        footer: Some("Esc: Back"),
+                    },
+                );
+
+                if let Some(ref draft) = ctx.goal_draft {
+                    f.render_widget(GoalDialog { draft }, f.area());
+                }
+            }
+
+            // Render the training digest if active
+            if let Some(ref ctx) = menu_state.digest {
+                f.render_widget(
+                    TrainingDigestView {
+                        digest: &ctx.digest,
+                        scroll: ctx.scroll,
+                        status: ctx.status.as_deref(),
+                    },
+                    f.area(),
+                );
+            }
+
+            // Render the list of studies if active
+            if let Some(ref mut ctx) = menu_state.studies {
+                let rows = study_browser::study_rows(&ctx.studies);
+                let footer = ctx
+                    .status
+                    .as_deref()
+                    .unwrap_or("Enter: Browse | n: New | d: Delete | e: Export PGN | Esc: Back");
+                render_table_overlay(
+                    f.area(),
+                    f.buffer_mut(),
+                    TableOverlayParams {
+                        title: "Studies",
+                        headers: &["Name", "Games", "Results", "Built from"],
+                        rows: &rows,
+                        column_widths: &[
+                            Constraint::Min(18),
+                            Constraint::Length(6),
+                            Constraint::Length(12),
+                            Constraint::Min(24),
+                        ],
+                        state: &mut ctx.table_state,
+                        width: 76,
+                        height: (ctx.studies.len() as u16 + 6).min(20),
+                        footer: Some(footer),
+                    },
+                );
+
+                if let Some(ref draft) = ctx.draft {
+                    f.render_widget(StudyDialog { draft }, f.area());
+                }
+            }
+
+            // Render a study's tree if one is open
+            if let Some(ref ctx) = menu_state.study {
+                let area = f.area();
+                let width = area.width.min(90);
+                f.render_widget(
+                    StudyTreeView {
+                        study: &ctx.study,
+                        path: &ctx.path,
+                        selected: ctx.selected,
+                    },
+                    Rect {
+                        x: area.x + (area.width - width) / 2,
+                        width,
+                        ..area
+                    },
+                );
+            }
+
+            // Render a study's chapter list if active
+            if let Some(ref mut ctx) = menu_state.chapters {
+                let rows = chapter_view::chapter_rows(&ctx.chapters);
+                let footer = ctx
+                    .status
+                    .as_deref()
+                    .unwrap_or("Enter: Open | n: New | d: Delete | [/]: Reorder | Esc: Back");
+                let title = format!("Chapters of {}", ctx.study_name);
+                render_table_overlay(
+                    f.area(),
+                    f.buffer_mut(),
+                    TableOverlayParams {
+                        title: &title,
+                        headers: &["#", "Name", "Moves", "From"],
+                        rows: &rows,
+                        column_widths: &[
+                            Constraint::Length(3),
+                            Constraint::Min(24),
+                            Constraint::Length(6),
+                            Constraint::Length(9),
+                        ],
+                        state: &mut ctx.table_state,
+                        width: 64,
+                        height: (ctx.chapters.len() as u16 + 6).min(20),
+                        footer: Some(footer),
+                    },
+                );
+
+                if let Some(ref draft) = ctx.draft {
+                    f.render_widget(
+                        ChapterDialog {
+                            draft,
+                            games: &menu_state.finished_games,
+                        },
+                        f.area(),
+                    );
+                }
+            }
+
+            // Render a chapter if one is open
+            if let Some(ref ctx) = menu_state.chapter {
+                let area = f.area();
+                let width = area.width.min(90);
+                let height = area.height.min(22);
+                f.render_widget(
+                    ChapterView {
+                        chapter: &ctx.chapter,
+                        node_id: ctx.node_id,
+                        selected: ctx.selected,
+                        input: ctx.input.as_ref(),
+                        status: ctx.status.as_deref(),
+                    },
+                    Rect {
+                        x: area.x + (area.width - width) / 2,
+                        y: area.y + (area.height - height) / 2,
+                        width,
+                        height,
                     },
                 );
             }
@@ -143,7 +623,8 @@ pub async fn show_menu(
                 let rows: Vec<Vec<String>> = ctx
                     .sessions
                     .iter()
-                    .map(|s| {
+                    .enumerate()
+                    .map(|(i, s)| {
                         let mode = s
                             .game_mode
                             .as_ref()
@@ -156,14 +637,22 @@ pub async fn show_menu(
                                 chess_client::GameModeType::Review => "Review",
                             })
                             .unwrap_or("Unknown");
-                        let moves = format!("{} moves", s.move_count);
-                        let side = s.side_to_move.clone();
-                        let fen_preview = if s.fen.len() > 30 {
-                            format!("{}...", &s.fen[..27])
+                        // The game is named on the first of its snapshots only
+                        let starts_group =
+                            i == 0 || ctx.sessions[i - 1].source_game != s.source_game;
+                        let game = if starts_group {
+                            source_label(&s.source_game)
+                        } else {
+                            String::new()
+                        };
+                        let name = if s.name.is_empty() {
+                            mode.to_string()
                         } else {
-                            s.fen.clone()
+                            s.name.clone()
                         };
-                        vec![mode.to_string(), moves, side, fen_preview]
+                        let moves = format!("{} moves", s.move_count);
+                        let side = s.side_to_move.clone();
+                        vec![game, name, moves, side]
                     })
                     .collect();
 
@@ -171,17 +660,17 @@ pub async fn show_menu(
                     f.area(),
                     f.buffer_mut(),
                     TableOverlayParams {
-                        title: "Resume Session",
-                        headers: &["Mode", "Moves", "Turn", "Position"],
+                        title: "Resume Session or Snapshot",
+                        headers: &["Game", "Name", "Moves", "Turn"],
                         rows: &rows,
                         column_widths: &[
-                            Constraint::Length(16),
+                            Constraint::Length(18),
+                            Constraint::Min(30),
                             Constraint::Length(10),
-                            Constraint::Length(8),
-                            Constraint::Min(20),
+                            Constraint::Length(6),
                         ],
                         state: &mut ctx.table_state,
-                        width: 70,
+                        width: 80,
                         height: (ctx.sessions.len() as u16 + 5).min(20),
                         footer: None,
                     },
@@ -189,8 +678,74 @@ pub async fn show_menu(
             }
         })?;
 
+        // Nobody at the keyboard: start the demo, e.g. on a club display
+        if idle_timeout.is_some_and(|idle| last_input.elapsed() >= idle) {
+            break MenuAction::Demo;
+        }
+
         if event::poll(Duration::from_millis(100))? {
             if let Event::Key(key) = event::read()? {
+                last_input = std::time::Instant::now();
+
+                // Simul report sits on top of everything else
+                if let Some(ref mut ctx) = menu_state.simul_report {
+                    match key.code {
+                        KeyCode::Up | KeyCode::Char('k') => ctx.table_state.move_up(),
+                        KeyCode::Down | KeyCode::Char('j') => ctx.table_state.move_down(),
+                        KeyCode::Esc | KeyCode::Enter => menu_state.simul_report = None,
+                        _ => {}
+                    }
+                    continue;
+                }
+
+                if menu_state.stats.is_some() {
+                    if let Some(action) = handle_stats_input(&mut menu_state, key.code) {
+                        break action;
+                    }
+                    continue;
+                }
+
+                if let Some(ref mut ctx) = menu_state.digest {
+                    match key.code {
+                        KeyCode::Up | KeyCode::Char('k') => {
+                            ctx.scroll = ctx.scroll.saturating_sub(1)
+                        }
+                        KeyCode::Down | KeyCode::Char('j') => ctx.scroll += 1,
+                        KeyCode::Char('e') => ctx.status = Some(export_digest(&ctx.digest)),
+                        KeyCode::Esc | KeyCode::Enter => menu_state.digest = None,
+                        _ => {}
+                    }
+                    continue;
+                }
+
+                if menu_state.chapter.is_some() {
+                    if let Some(action) = handle_chapter_input(&mut menu_state, key.code) {
+                        break action;
+                    }
+                    continue;
+                }
+
+                if menu_state.chapters.is_some() {
+                    if let Some(action) = handle_chapters_input(&mut menu_state, key.code) {
+                        break action;
+                    }
+                    continue;
+                }
+
+                if menu_state.study.is_some() {
+                    if let Some(action) = handle_study_input(&mut menu_state, key.code) {
+                        break action;
+                    }
+                    continue;
+                }
+
+                if menu_state.studies.is_some() {
+                    if let Some(action) = handle_studies_input(&mut menu_state, key.code) {
+                        break action;
+                    }
+                    continue;
+                }
+
                 // Review table takes highest priority
                 if menu_state.review_table.is_some() {
                     let action = handle_review_table_input(&mut menu_state, key.code);
@@ -209,6 +764,13 @@ pub async fn show_menu(
                     continue;
                 }
 
+                if menu_state.transfer_code.is_some() {
+                    if let Some(config) = handle_transfer_code_input(&mut menu_state, key.code) {
+                        break MenuAction::StartGame(Box::new(config));
+                    }
+                    continue;
+                }
+
                 // Handle FEN dialog input if active
                 if menu_state.fen_dialog_state.is_some() {
                     handle_fen_dialog_input(&mut menu_state, key.code);
@@ -240,7 +802,11 @@ pub async fn show_menu(
                         cycle_option(&mut menu_state, &selected_item, 1);
                     }
                     KeyCode::Enter => match selected_item {
-                        Some(MenuItem::StartGame) => {
+                        // Engine modes stay greyed out until the server has Stockfish
+                        Some(MenuItem::StartGame) | Some(MenuItem::Opponent(Some(_)))
+                            if menu_state.needs_missing_engine() => {}
+                        // Enter on a named opponent quick-starts a game against it
+                        Some(MenuItem::StartGame) | Some(MenuItem::Opponent(Some(_))) => {
                             use crate::ui::widgets::menu::StartPositionOption;
                             if menu_state.start_position == StartPositionOption::CustomFen
                                 && menu_state.selected_fen.is_none()
@@ -253,7 +819,7 @@ pub async fn show_menu(
                             }
                         }
                         Some(MenuItem::ResumeSession) => {
-                            let sessions = menu_state.suspended_sessions.clone();
+                            let sessions = group_by_source(menu_state.suspended_sessions.clone());
                             if !sessions.is_empty() {
                                 use crate::ui::widgets::menu::SessionTableContext;
                                 let count = sessions.len();
@@ -263,6 +829,9 @@ pub async fn show_menu(
                                 });
                             }
                         }
+                        Some(MenuItem::JoinSession) => {
+                            menu_state.transfer_code = Some(String::new());
+                        }
                         Some(MenuItem::ReviewGame) => {
                             let games = menu_state.finished_games.clone();
                             if !games.is_empty() {
@@ -271,9 +840,25 @@ pub async fn show_menu(
                                 menu_state.review_table = Some(ReviewTableContext {
                                     table_state: SelectableTableState::new(count),
                                     games,
+                                    endgame_filter: None,
                                 });
                             }
                         }
+                        Some(MenuItem::Stats) => {
+                            break MenuAction::Stats;
+                        }
+                        Some(MenuItem::Digest) => {
+                            break MenuAction::Digest;
+                        }
+                        Some(MenuItem::Studies) => {
+                            break MenuAction::Studies;
+                        }
+                        Some(MenuItem::Tutorial) => {
+                            break MenuAction::Tutorial;
+                        }
+                        Some(MenuItem::Demo) => {
+                            break MenuAction::Demo;
+                        }
                         Some(MenuItem::Quit) => {
                             break MenuAction::Quit;
                         }
@@ -342,6 +927,12 @@ fn cycle_option(
         MenuItem::PlayAs(_) => {
             menu_state.cycle_play_as();
         }
+        MenuItem::SimulBoards(_) => {
+            menu_state.cycle_simul_boards(_direction);
+        }
+        MenuItem::Opponent(_) => {
+            menu_state.cycle_opponent(_direction);
+        }
         MenuItem::Difficulty(_) => {
             menu_state.difficulty = match menu_state.difficulty {
                 DifficultyOption::Beginner => {
@@ -465,16 +1056,58 @@ fn cycle_option(
                 }
             };
         }
+        MenuItem::EngineLines(_) => {
+            let max = crate::state::MAX_ENGINE_LINES;
+            menu_state.engine_lines = if _direction > 0 {
+                menu_state.engine_lines % max + 1
+            } else {
+                (menu_state.engine_lines + max - 2) % max + 1
+            };
+        }
         MenuItem::StartPosition(_) => {
             menu_state.start_position = match menu_state.start_position {
                 StartPositionOption::Standard => StartPositionOption::CustomFen,
                 StartPositionOption::CustomFen => StartPositionOption::Standard,
             };
         }
+        MenuItem::Notation(_) => {
+            menu_state.notation.style = cycle_in(
+                &chess::NotationStyle::ALL,
+                menu_state.notation.style,
+                _direction,
+            );
+        }
+        MenuItem::PieceLetters(_) => {
+            menu_state.notation.language = cycle_in(
+                &chess::PieceLanguage::ALL,
+                menu_state.notation.language,
+                _direction,
+            );
+        }
+        MenuItem::LiveEvals(_) => {
+            menu_state.live_evals = !menu_state.live_evals;
+        }
+        MenuItem::ConfirmMoves(_) => {
+            menu_state.confirm_moves = !menu_state.confirm_moves;
+        }
+        MenuItem::ScreenReader(_) => {
+            menu_state.screen_reader = !menu_state.screen_reader;
+        }
         _ => {}
     }
 }
 
+/// The entry after (or before) `current` in `all`, wrapping.
+fn cycle_in<T: Copy + PartialEq>(all: &[T], current: T, direction: i32) -> T {
+    let index = all.iter().position(|&v| v == current).unwrap_or(0);
+    let next = if direction > 0 {
+        (index + 1) % all.len()
+    } else {
+        (index + all.len() - 1) % all.len()
+    };
+    all[next]
+}
+
 fn handle_fen_dialog_input(menu_state: &mut MenuState, key_code: KeyCode) {
     use crate::ui::widgets::fen_dialog::FenDialogFocus;
 
@@ -601,19 +1234,21 @@ fn create_game_config(menu_state: &MenuState) -> GameConfig {
         GameModeOption::HumanVsHuman => GameMode::HumanVsHuman,
         GameModeOption::HumanVsEngine => {
             let human_side = match menu_state.play_as {
-                PlayAsOption::White => PlayerColor::White,
-                PlayAsOption::Black => PlayerColor::Black,
+                PlayAsOption::White => PlayerSide::White,
+                PlayAsOption::Black => PlayerSide::Black,
             };
             GameMode::HumanVsEngine { human_side }
         }
         GameModeOption::EngineVsEngine => GameMode::EngineVsEngine,
     };
 
-    let skill_level = match menu_state.difficulty {
-        DifficultyOption::Beginner => 3,
-        DifficultyOption::Intermediate => 10,
-        DifficultyOption::Advanced => 15,
-        DifficultyOption::Master => 20,
+    let opponent = menu_state.selected_opponent();
+    let skill_level = match (opponent, menu_state.difficulty) {
+        (Some(o), _) => o.skill_level,
+        (None, DifficultyOption::Beginner) => 3,
+        (None, DifficultyOption::Intermediate) => 10,
+        (None, DifficultyOption::Advanced) => 15,
+        (None, DifficultyOption::Master) => 20,
     };
 
     let start_fen = menu_state.selected_fen.clone();
@@ -633,6 +1268,11 @@ fn create_game_config(menu_state: &MenuState) -> GameConfig {
     } else {
         None
     };
+    let engine_lines = if menu_state.game_mode == GameModeOption::EngineVsEngine {
+        menu_state.engine_lines
+    } else {
+        1
+    };
 
     GameConfig {
         mode,
@@ -641,16 +1281,81 @@ fn create_game_config(menu_state: &MenuState) -> GameConfig {
         time_control_seconds,
         engine_threads,
         engine_hash_mb,
+        engine_lines,
         resume_session_id: None,
         resume_game_mode: None,
         resume_human_side: None,
         resume_skill_level: None,
+        transfer_code: None,
         review_data: None,
         review_game_mode: None,
         review_skill_level: None,
+        review_start_ply: None,
         pre_history: None,
         advanced_data: None,
+        simul_boards: if menu_state.game_mode == GameModeOption::HumanVsEngine {
+            menu_state.simul_boards
+        } else {
+            1
+        },
+        opponent: opponent.map(|o| o.name.clone()),
+        screen_reader: menu_state.screen_reader,
+        notation: menu_state.notation,
+        live_evals: menu_state.live_evals,
+        confirm_moves: menu_state.confirm_moves,
+    }
+}
+
+/// Snapshots of the same game next to each other, the game with the newest
+/// snapshot first. Suspended sessions, which come from no other game, are
+/// listed last. Sessions arrive newest first and keep that order in a group.
+fn group_by_source(
+    mut sessions: Vec<chess_client::SuspendedSessionInfo>,
+) -> Vec<chess_client::SuspendedSessionInfo> {
+    let mut first_seen: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
+    for (i, session) in sessions.iter().enumerate() {
+        first_seen.entry(session.source_game.clone()).or_insert(i);
     }
+    sessions.sort_by_key(|s| (s.source_game.is_empty(), first_seen[&s.source_game]));
+    sessions
+}
+
+/// Short name of the game a snapshot came from for the session table.
+fn source_label(source_game: &str) -> String {
+    const MAX_LEN: usize = 16;
+    if source_game.is_empty() {
+        "Suspended".to_string()
+    } else if source_game.chars().count() > MAX_LEN {
+        let short: String = source_game.chars().take(MAX_LEN - 1).collect();
+        format!("{}…", short)
+    } else {
+        source_game.to_string()
+    }
+}
+
+/// Handle input for the transfer code dialog.
+/// Returns Some(GameConfig) to take over the session once a code is entered.
+fn handle_transfer_code_input(menu_state: &mut MenuState, key_code: KeyCode) -> Option<GameConfig> {
+    let code = menu_state.transfer_code.as_mut()?;
+
+    match key_code {
+        KeyCode::Char(c) => push_code_char(code, c),
+        KeyCode::Backspace => {
+            code.pop();
+        }
+        KeyCode::Enter if !code.is_empty() => {
+            let code = menu_state.transfer_code.take();
+            let mut config = create_game_config(menu_state);
+            config.transfer_code = code;
+            return Some(config);
+        }
+        KeyCode::Esc => {
+            menu_state.transfer_code = None;
+        }
+        _ => {}
+    }
+
+    None
 }
 
 /// Handle input for the session selection table.
@@ -706,6 +1411,21 @@ fn handle_session_table_input(menu_state: &mut MenuState, key_code: KeyCode) ->
 
 /// Handle input for the review game selection table.
 /// Returns Some(MenuAction) when user picks a game or enqueues analysis.
+/// Carry the status of followed reviews into the game lists, so a review
+/// that completes while the menu is open can be opened at once.
+fn apply_review_progress(menu_state: &mut MenuState) {
+    let tracker = &menu_state.review_progress;
+    let table_games = menu_state
+        .review_table
+        .iter_mut()
+        .flat_map(|ctx| ctx.games.iter_mut());
+    for game in menu_state.finished_games.iter_mut().chain(table_games) {
+        if let Some(status) = tracker.get(&game.game_id).as_ref().and_then(status_of) {
+            game.review_status = Some(status as i32);
+        }
+    }
+}
+
 fn handle_review_table_input(menu_state: &mut MenuState, key_code: KeyCode) -> Option<MenuAction> {
     let ctx = menu_state.review_table.as_mut()?;
 
@@ -728,27 +1448,37 @@ fn handle_review_table_input(menu_state: &mut MenuState, key_code: KeyCode) -> O
                         let review_game_mode = game.game_mode;
                         menu_state.review_table = None;
                         return Some(MenuAction::StartGame(Box::new(GameConfig {
-                            mode: GameMode::ReviewMode,
+                            mode: GameMode::Review,
                             skill_level: 0,
                             start_fen: None,
                             time_control_seconds: None,
                             engine_threads: None,
                             engine_hash_mb: None,
+                            engine_lines: 1,
                             resume_session_id: Some(game_id),
                             resume_game_mode: None,
                             resume_human_side: None,
                             resume_skill_level: None,
+                            transfer_code: None,
                             review_data: None,
                             review_game_mode,
                             review_skill_level: None,
+                            review_start_ply: None,
                             pre_history: None,
                             advanced_data: None,
+                            simul_boards: 1,
+                            opponent: None,
+                            screen_reader: menu_state.screen_reader,
+                            notation: menu_state.notation,
+                            live_evals: menu_state.live_evals,
+                            confirm_moves: menu_state.confirm_moves,
                         })));
                     }
                 }
             }
         }
-        KeyCode::Char('a') => {
+        // Analysis and simul reports need the server
+        KeyCode::Char('a') if !menu_state.offline => {
             // Enqueue analysis for the selected game (only if not reviewed and not in-flight)
             if let Some(idx) = ctx.table_state.selected_index() {
                 if let Some(game) = ctx.games.get(idx) {
@@ -767,6 +1497,27 @@ fn handle_review_table_input(menu_state: &mut MenuState, key_code: KeyCode) -> O
                 }
             }
         }
+        KeyCode::Char('s') if !menu_state.offline => {
+            // Combined report for the simul the selected game was played in
+            if let Some(idx) = ctx.table_state.selected_index() {
+                if let Some(simul_id) = ctx.games.get(idx).and_then(|g| g.simul_id.clone()) {
+                    menu_state.review_table = None;
+                    return Some(MenuAction::SimulReport(simul_id));
+                }
+            }
+        }
+        KeyCode::Char('e') => {
+            // Narrow the table to the next endgame type among the games
+            let filter = next_endgame_filter(&menu_state.finished_games, ctx.endgame_filter);
+            ctx.games = menu_state
+                .finished_games
+                .iter()
+                .filter(|g| filter.is_none() || game_endgame(g) == filter)
+                .cloned()
+                .collect();
+            ctx.table_state = SelectableTableState::new(ctx.games.len());
+            ctx.endgame_filter = filter;
+        }
         KeyCode::Esc => {
             menu_state.review_table = None;
         }
@@ -776,10 +1527,484 @@ fn handle_review_table_input(menu_state: &mut MenuState, key_code: KeyCode) -> O
     None
 }
 
+/// The dominant endgame of a game's completed review.
+fn game_endgame(game: &chess_client::FinishedGameInfo) -> Option<chess_client::EndgameTypeProto> {
+    game.endgame
+        .and_then(|e| chess_client::EndgameTypeProto::try_from(e).ok())
+        .filter(|e| *e != chess_client::EndgameTypeProto::EndgameTypeUnspecified)
+}
+
+/// The endgame filter after `current`: each type the games reached in turn,
+/// then no filter again.
+fn next_endgame_filter(
+    games: &[chess_client::FinishedGameInfo],
+    current: Option<chess_client::EndgameTypeProto>,
+) -> Option<chess_client::EndgameTypeProto> {
+    let mut present: Vec<_> = games.iter().filter_map(game_endgame).collect();
+    present.sort_by_key(|e| *e as i32);
+    present.dedup();
+    match current {
+        None => present.first().copied(),
+        Some(current) => present.into_iter().find(|e| *e as i32 > current as i32),
+    }
+}
+
+fn endgame_name(endgame: chess_client::EndgameTypeProto) -> &'static str {
+    use chess_client::EndgameTypeProto as E;
+    match endgame {
+        E::EndgameTypeUnspecified => "",
+        E::EndgameTypePawn => "King and pawn",
+        E::EndgameTypeKnight => "Knight",
+        E::EndgameTypeBishop => "Bishop",
+        E::EndgameTypeOppositeBishops => "Opposite bishops",
+        E::EndgameTypeMinor => "Minor piece",
+        E::EndgameTypeRook => "Rook",
+        E::EndgameTypeRookAndMinor => "Rook and minor",
+        E::EndgameTypeQueen => "Queen",
+        E::EndgameTypeMixed => "Mixed",
+    }
+}
+
+/// Outcome of a simul board from the player's side.
+fn simul_board_outcome(board: &chess_client::SimulBoardResult) -> &'static str {
+    let human_white = board.human_side.as_deref() != Some("black");
+    match (board.result.as_str(), human_white) {
+        ("Draw", _) => "Draw",
+        ("WhiteWins", true) | ("BlackWins", false) => "Win",
+        _ => "Loss",
+    }
+}
+
+fn simul_report_title(report: &chess_client::GetSimulReportResponse) -> String {
+    let accuracy = report
+        .average_accuracy
+        .map(|a| format!(" \u{00b7} {:.1}% avg", a))
+        .unwrap_or_default();
+    format!(
+        "Simul: +{} ={} -{}{}",
+        report.wins, report.draws, report.losses, accuracy
+    )
+}
+
+fn simul_report_rows(report: &chess_client::GetSimulReportResponse) -> Vec<Vec<String>> {
+    report
+        .boards
+        .iter()
+        .enumerate()
+        .map(|(i, b)| {
+            let accuracy = match b.human_accuracy {
+                Some(a) => format!("{:.1}%", a),
+                None => "Not reviewed".to_string(),
+            };
+            vec![
+                format!("{}", i + 1),
+                simul_board_outcome(b).to_string(),
+                b.result_reason.clone(),
+                b.move_count.to_string(),
+                accuracy,
+            ]
+        })
+        .collect()
+}
+
+/// Handle a key on the Stats screen. Saving or deleting a goal leaves the menu
+/// so the server can be called; everything else stays on the screen.
+fn handle_stats_input(menu_state: &mut MenuState, key: KeyCode) -> Option<MenuAction> {
+    let ctx = menu_state.stats.as_mut()?;
+
+    if let Some(ref mut draft) = ctx.goal_draft {
+        match key {
+            KeyCode::Esc => ctx.goal_draft = None,
+            KeyCode::Up => draft.previous_field(),
+            KeyCode::Down | KeyCode::Tab => draft.next_field(),
+            KeyCode::Left => draft.cycle(-1),
+            KeyCode::Right => draft.cycle(1),
+            KeyCode::Backspace => draft.pop_char(),
+            KeyCode::Char(c) => draft.push_char(c),
+            KeyCode::Enter => match draft.parse_target() {
+                Ok(target) => {
+                    return Some(MenuAction::AddGoal {
+                        metric: draft.metric,
+                        target,
+                        period: draft.period,
+                    })
+                }
+                Err(e) => draft.error = Some(e),
+            },
+            _ => {}
+        }
+        return None;
+    }
+
+    match key {
+        KeyCode::Char('n') => ctx.goal_draft = Some(GoalDraft::default()),
+        KeyCode::Tab => ctx.goals_focused = !ctx.goals_focused && !ctx.goals.is_empty(),
+        KeyCode::Up | KeyCode::Char('k') if ctx.goals_focused => {
+            ctx.selected_goal = ctx.selected_goal.saturating_sub(1)
+        }
+        KeyCode::Down | KeyCode::Char('j') if ctx.goals_focused => {
+            ctx.selected_goal = (ctx.selected_goal + 1).min(ctx.goals.len().saturating_sub(1))
+        }
+        KeyCode::Char('d') if ctx.goals_focused => {
+            let goal = ctx.goals.get(ctx.selected_goal)?.goal.as_ref()?;
+            return Some(MenuAction::DeleteGoal(goal.goal_id.clone()));
+        }
+        KeyCode::Up | KeyCode::Char('k') => ctx.table_state.move_up(),
+        KeyCode::Down | KeyCode::Char('j') => ctx.table_state.move_down(),
+        KeyCode::Esc | KeyCode::Enter => menu_state.stats = None,
+        _ => {}
+    }
+    None
+}
+
+/// Handle a key on the list of studies. Building, opening, deleting or
+/// exporting a study leaves the menu so the server can be called.
+fn handle_studies_input(menu_state: &mut MenuState, key: KeyCode) -> Option<MenuAction> {
+    let ctx = menu_state.studies.as_mut()?;
+
+    if let Some(ref mut draft) = ctx.draft {
+        match key {
+            KeyCode::Esc => ctx.draft = None,
+            KeyCode::Up => draft.previous_field(),
+            KeyCode::Down | KeyCode::Tab => draft.next_field(),
+            KeyCode::Left => draft.cycle(-1),
+            KeyCode::Right => draft.cycle(1),
+            KeyCode::Backspace => draft.pop_char(),
+            KeyCode::Char(c) => draft.push_char(c),
+            KeyCode::Enter => match draft.parse_name() {
+                Ok(name) if draft.blank => return Some(MenuAction::CreateBlankStudy(name)),
+                Ok(name) => {
+                    return Some(MenuAction::CreateStudy {
+                        name,
+                        filter: draft.filter(),
+                    })
+                }
+                Err(e) => draft.error = Some(e),
+            },
+            _ => {}
+        }
+        return None;
+    }
+
+    let selected = ctx
+        .table_state
+        .selected_index()
+        .and_then(|i| ctx.studies.get(i))
+        .map(|s| s.study_id.clone());
+    match key {
+        KeyCode::Char('n') => ctx.draft = Some(StudyDraft::default()),
+        KeyCode::Up | KeyCode::Char('k') => ctx.table_state.move_up(),
+        KeyCode::Down | KeyCode::Char('j') => ctx.table_state.move_down(),
+        KeyCode::Enter => return selected.map(MenuAction::OpenStudy),
+        KeyCode::Char('d') => return selected.map(MenuAction::DeleteStudy),
+        KeyCode::Char('e') => return selected.map(MenuAction::ExportStudy),
+        KeyCode::Esc => menu_state.studies = None,
+        _ => {}
+    }
+    None
+}
+
+/// Handle a key while browsing a study's tree: move between the moves played
+/// from the shown position, follow one, or step back.
+fn handle_study_input(menu_state: &mut MenuState, key: KeyCode) -> Option<MenuAction> {
+    let ctx = menu_state.study.as_mut()?;
+    let root = ctx.study.root.as_ref()?;
+    let children = study_browser::node_at(root, &ctx.path).map_or(0, |n| n.children.len());
+
+    match key {
+        KeyCode::Up | KeyCode::Char('k') => ctx.selected = ctx.selected.saturating_sub(1),
+        KeyCode::Down | KeyCode::Char('j') => {
+            ctx.selected = (ctx.selected + 1).min(children.saturating_sub(1))
+        }
+        KeyCode::Right | KeyCode::Char('l') | KeyCode::Enter if ctx.selected < children => {
+            ctx.path.push(ctx.selected);
+            ctx.selected = 0;
+        }
+        KeyCode::Left | KeyCode::Char('h') | KeyCode::Backspace => {
+            if let Some(index) = ctx.path.pop() {
+                ctx.selected = index;
+            }
+        }
+        KeyCode::Char('e') => {
+            let study_id = ctx.study.study.as_ref()?.study_id.clone();
+            return Some(MenuAction::ExportStudy(study_id));
+        }
+        KeyCode::Char('c') => {
+            let study = menu_state.study.take()?.study;
+            menu_state.chapters = Some(chapters_context(study, None, None));
+        }
+        KeyCode::Esc => return Some(MenuAction::Studies),
+        _ => {}
+    }
+    None
+}
+
+fn chapters_context(
+    study: chess_client::GetStudyResponse,
+    selected: Option<String>,
+    status: Option<String>,
+) -> crate::ui::widgets::menu::ChaptersContext {
+    let info = study.study.unwrap_or_default();
+    let has_games = !study.game_ids.is_empty();
+    let mut table_state = SelectableTableState::new(study.chapters.len());
+    if let Some(index) =
+        selected.and_then(|id| study.chapters.iter().position(|c| c.chapter_id == id))
+    {
+        table_state.table_state.select(Some(index));
+    }
+    crate::ui::widgets::menu::ChaptersContext {
+        table_state,
+        study_id: info.study_id,
+        study_name: info.name,
+        has_games,
+        chapters: study.chapters,
+        draft: None,
+        status,
+    }
+}
+
+/// Handle a key on a study's chapter list. Changes to the list leave the
+/// menu so the server can be called; opening a chapter does not.
+fn handle_chapters_input(menu_state: &mut MenuState, key: KeyCode) -> Option<MenuAction> {
+    let ctx = menu_state.chapters.as_mut()?;
+
+    if let Some(ref mut draft) = ctx.draft {
+        let games = menu_state.finished_games.len();
+        match key {
+            KeyCode::Esc => ctx.draft = None,
+            KeyCode::Up => draft.previous_field(),
+            KeyCode::Down | KeyCode::Tab => draft.next_field(),
+            KeyCode::Left => draft.cycle(-1, games),
+            KeyCode::Right => draft.cycle(1, games),
+            KeyCode::Backspace => draft.pop_char(),
+            KeyCode::Char(c) => draft.push_char(c),
+            KeyCode::Enter => match draft.parse(&menu_state.finished_games) {
+                Ok((name, fen, game_id)) => {
+                    return Some(MenuAction::AddChapter {
+                        study_id: ctx.study_id.clone(),
+                        name,
+                        fen,
+                        game_id,
+                    })
+                }
+                Err(e) => draft.error = Some(e),
+            },
+            _ => {}
+        }
+        return None;
+    }
+
+    let index = ctx.table_state.selected_index();
+    let selected = index
+        .and_then(|i| ctx.chapters.get(i))
+        .map(|c| c.chapter_id.clone());
+    let reorder = |to: Option<usize>| {
+        Some(MenuAction::MoveChapter {
+            study_id: ctx.study_id.clone(),
+            chapter_id: selected.clone()?,
+            index: to? as u32,
+        })
+    };
+    match key {
+        KeyCode::Char('n') => ctx.draft = Some(ChapterDraft::default()),
+        KeyCode::Up | KeyCode::Char('k') => ctx.table_state.move_up(),
+        KeyCode::Down | KeyCode::Char('j') => ctx.table_state.move_down(),
+        KeyCode::Char('[') | KeyCode::Char('K') => {
+            return reorder(index.and_then(|i| i.checked_sub(1)))
+        }
+        KeyCode::Char(']') | KeyCode::Char('J') => {
+            return reorder(index.map(|i| i + 1).filter(|&i| i < ctx.chapters.len()))
+        }
+        KeyCode::Char('d') => {
+            return Some(MenuAction::DeleteChapter {
+                study_id: ctx.study_id.clone(),
+                chapter_id: selected?,
+            })
+        }
+        KeyCode::Enter => {
+            use crate::ui::widgets::menu::ChapterContext;
+            let chapter = ctx.chapters.get(index?)?.clone();
+            let node_id = chapter.nodes.first()?.id;
+            menu_state.chapter = Some(ChapterContext {
+                study_id: ctx.study_id.clone(),
+                chapter,
+                node_id,
+                selected: 0,
+                input: None,
+                status: None,
+            });
+        }
+        KeyCode::Esc if ctx.has_games => {
+            let study_id = ctx.study_id.clone();
+            menu_state.chapters = None;
+            return Some(MenuAction::OpenStudy(study_id));
+        }
+        KeyCode::Esc => return Some(MenuAction::Studies),
+        _ => {}
+    }
+    None
+}
+
+/// Handle a key while browsing a chapter: step through its moves, or type
+/// a move, comment or arrow for the shown position. Edits leave the menu so
+/// the server can be called.
+fn handle_chapter_input(menu_state: &mut MenuState, key: KeyCode) -> Option<MenuAction> {
+    let ctx = menu_state.chapter.as_mut()?;
+    let current = chapter_view::node(&ctx.chapter, ctx.node_id)?.clone();
+    let study_id = ctx.study_id.clone();
+    let chapter_id = ctx.chapter.chapter_id.clone();
+    let annotate = |comment: Option<String>, nags: Vec<u32>, arrows| {
+        Some(MenuAction::AnnotateChapterNode {
+            study_id: study_id.clone(),
+            chapter_id: chapter_id.clone(),
+            node_id: current.id,
+            comment,
+            nags,
+            arrows,
+        })
+    };
+
+    if let Some(ref mut input) = ctx.input {
+        match key {
+            KeyCode::Esc => ctx.input = None,
+            KeyCode::Backspace => {
+                input.text_mut().pop();
+            }
+            KeyCode::Char(c) => {
+                let text = input.text_mut();
+                if text.len() < 200 {
+                    text.push(c);
+                }
+            }
+            KeyCode::Enter => {
+                let input = ctx.input.take()?;
+                match input {
+                    ChapterInput::Move(text) if !text.trim().is_empty() => {
+                        return Some(MenuAction::AddChapterMove {
+                            study_id,
+                            chapter_id,
+                            node_id: current.id,
+                            notation: text.trim().to_string(),
+                        })
+                    }
+                    ChapterInput::Comment(text) => {
+                        let text = text.trim();
+                        let comment = (!text.is_empty()).then(|| text.to_string());
+                        return annotate(comment, current.nags, current.arrows);
+                    }
+                    ChapterInput::Arrow(text) => match chapter_view::parse_arrow(&text) {
+                        Some(arrow) => {
+                            let mut arrows = current.arrows;
+                            arrows.retain(|a| (&a.from, &a.to) != (&arrow.from, &arrow.to));
+                            arrows.push(arrow);
+                            return annotate(current.comment, current.nags, arrows);
+                        }
+                        None => ctx.status = Some(format!("Not an arrow: {}", text)),
+                    },
+                    ChapterInput::Move(_) => {}
+                }
+            }
+            _ => {}
+        }
+        return None;
+    }
+
+    let children = current.children.len();
+    match key {
+        KeyCode::Up | KeyCode::Char('k') => ctx.selected = ctx.selected.saturating_sub(1),
+        KeyCode::Down | KeyCode::Char('j') => {
+            ctx.selected = (ctx.selected + 1).min(children.saturating_sub(1))
+        }
+        KeyCode::Right | KeyCode::Char('l') | KeyCode::Enter if ctx.selected < children => {
+            ctx.node_id = current.children[ctx.selected];
+            ctx.selected = 0;
+            ctx.status = None;
+        }
+        KeyCode::Left | KeyCode::Char('h') | KeyCode::Backspace => {
+            if let Some(parent) = current
+                .parent
+                .and_then(|p| chapter_view::node(&ctx.chapter, p))
+            {
+                ctx.selected = parent
+                    .children
+                    .iter()
+                    .position(|&c| c == current.id)
+                    .unwrap_or(0);
+                ctx.node_id = parent.id;
+                ctx.status = None;
+            }
+        }
+        KeyCode::Char('m') => ctx.input = Some(ChapterInput::Move(String::new())),
+        KeyCode::Char('c') => {
+            ctx.input = Some(ChapterInput::Comment(
+                current.comment.clone().unwrap_or_default(),
+            ))
+        }
+        KeyCode::Char('a') => ctx.input = Some(ChapterInput::Arrow(String::new())),
+        KeyCode::Char('A') if !current.arrows.is_empty() => {
+            return annotate(current.comment.clone(), current.nags.clone(), vec![])
+        }
+        KeyCode::Char(c @ '1'..='6') => {
+            let (nag, _) = chapter_view::NAG_KEYS[c as usize - '1' as usize];
+            let mut nags = current.nags.clone();
+            if nags.contains(&nag) {
+                nags.retain(|&n| n != nag);
+            } else {
+                nags.push(nag);
+            }
+            return annotate(current.comment.clone(), nags, current.arrows.clone());
+        }
+        KeyCode::Char('x') if current.parent.is_some() => {
+            return Some(MenuAction::DeleteChapterMove {
+                study_id,
+                chapter_id,
+                node_id: current.id,
+            })
+        }
+        KeyCode::Esc => menu_state.chapter = None,
+        _ => {}
+    }
+    None
+}
+
+/// Export a digest as Markdown, returning a status line for the report screen.
+fn export_digest(digest: &chess_client::GetTrainingDigestResponse) -> String {
+    let Some(dir) = crate::digest::default_export_dir() else {
+        return "No data directory to export to".to_string();
+    };
+    match crate::digest::export(&dir, digest) {
+        Ok(path) => format!("Exported to {}", path.display()),
+        Err(e) => {
+            tracing::warn!("Failed to export training digest: {}", e);
+            format!("Export failed: {}", e)
+        }
+    }
+}
+
+fn opponent_stats_rows(stats: &[chess_client::OpponentStatsProto]) -> Vec<Vec<String>> {
+    stats
+        .iter()
+        .map(|s| {
+            let accuracy = match s.average_accuracy {
+                Some(a) => format!("{:.1}%", a),
+                None => "Not reviewed".to_string(),
+            };
+            vec![
+                s.name.clone(),
+                s.games.to_string(),
+                format!("{}-{}-{}", s.wins, s.draws, s.losses),
+                s.performance_rating.to_string(),
+                accuracy,
+                s.skill_level.to_string(),
+            ]
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ui::widgets::menu::ReviewTableContext;
+    use crate::ui::widgets::menu::{MenuItem, ReviewTableContext};
 
     fn sample_game(game_id: &str, review_status: Option<i32>) -> chess_client::FinishedGameInfo {
         chess_client::FinishedGameInfo {
@@ -790,6 +2015,11 @@ mod tests {
             move_count: 4,
             created_at: 1000,
             review_status,
+            simul_id: None,
+            opponent: None,
+            opening: None,
+            derived_result: None,
+            endgame: None,
         }
     }
 
@@ -803,10 +2033,74 @@ mod tests {
         state.review_table = Some(ReviewTableContext {
             table_state: SelectableTableState::new(count),
             games,
+            endgame_filter: None,
         });
         state
     }
 
+    #[test]
+    fn test_followed_review_can_be_opened_once_complete() {
+        let games = vec![sample_game(
+            "game_1",
+            Some(chess_client::ReviewStatusType::ReviewStatusQueued as i32),
+        )];
+        let mut state = menu_with_review_table(games);
+        let update = |status: chess_client::ReviewStatusType| chess_client::ReviewProgressUpdate {
+            game_id: "game_1".to_string(),
+            status: Some(chess_client::ReviewStatusInfo {
+                status: status as i32,
+                ..Default::default()
+            }),
+            eta_ms: None,
+        };
+
+        state.review_progress.record(update(
+            chess_client::ReviewStatusType::ReviewStatusAnalyzing,
+        ));
+        apply_review_progress(&mut state);
+        assert!(handle_review_table_input(&mut state, KeyCode::Enter).is_none());
+
+        state
+            .review_progress
+            .record(update(chess_client::ReviewStatusType::ReviewStatusComplete));
+        apply_review_progress(&mut state);
+        assert_eq!(
+            state.finished_games[0].review_status,
+            Some(chess_client::ReviewStatusType::ReviewStatusComplete as i32)
+        );
+        let action = handle_review_table_input(&mut state, KeyCode::Enter);
+        assert!(matches!(action, Some(MenuAction::StartGame(_))));
+    }
+
+    #[test]
+    fn test_snapshots_are_grouped_by_source_game() {
+        let session = |id: &str, source: &str| chess_client::SuspendedSessionInfo {
+            suspended_id: id.to_string(),
+            source_game: source.to_string(),
+            ..Default::default()
+        };
+        // Newest first, as the server lists them
+        let sessions = vec![
+            session("s5", "game_a"),
+            session("s4", ""),
+            session("s3", "game_b"),
+            session("s2", "game_a"),
+            session("s1", "game_b"),
+        ];
+        let ids: Vec<String> = group_by_source(sessions)
+            .into_iter()
+            .map(|s| s.suspended_id)
+            .collect();
+        assert_eq!(ids, vec!["s5", "s2", "s3", "s1", "s4"]);
+
+        assert_eq!(source_label(""), "Suspended");
+        assert_eq!(source_label("game_1"), "game_1");
+        assert_eq!(
+            source_label("0b5e8f3a-1c2d-4e5f-8a9b-0c1d2e3f4a5b"),
+            "0b5e8f3a-1c2d-4…"
+        );
+    }
+
     #[test]
     fn test_enter_on_reviewed_game_returns_start_game() {
         let games = vec![sample_game(
@@ -819,7 +2113,7 @@ mod tests {
         assert!(action.is_some());
         match action.unwrap() {
             MenuAction::StartGame(config) => {
-                assert_eq!(config.mode, GameMode::ReviewMode);
+                assert_eq!(config.mode, GameMode::Review);
                 assert_eq!(config.resume_session_id, Some("game_1".to_string()));
             }
             _ => panic!("Expected StartGame"),
@@ -882,6 +2176,62 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_transfer_code_dialog_joins_with_the_typed_code() {
+        let mut state = MenuState {
+            transfer_code: Some(String::new()),
+            ..Default::default()
+        };
+        for c in "k7qx".chars() {
+            assert!(handle_transfer_code_input(&mut state, KeyCode::Char(c)).is_none());
+        }
+        handle_transfer_code_input(&mut state, KeyCode::Backspace);
+        assert_eq!(state.transfer_code.as_deref(), Some("K7Q"));
+
+        let config = handle_transfer_code_input(&mut state, KeyCode::Enter).unwrap();
+        assert_eq!(config.transfer_code.as_deref(), Some("K7Q"));
+        assert!(state.transfer_code.is_none());
+
+        // Esc closes the dialog, and an empty code joins nothing
+        state.transfer_code = Some(String::new());
+        assert!(handle_transfer_code_input(&mut state, KeyCode::Enter).is_none());
+        handle_transfer_code_input(&mut state, KeyCode::Esc);
+        assert!(state.transfer_code.is_none());
+    }
+
+    #[test]
+    fn test_offline_menu_only_browses() {
+        let games = vec![sample_game("game_1", None)];
+        let mut state = menu_with_review_table(games);
+        state.offline = true;
+
+        assert!(handle_review_table_input(&mut state, KeyCode::Char('a')).is_none());
+        assert!(state.review_table.is_some());
+        assert_eq!(
+            state.items(),
+            vec![
+                MenuItem::ReviewGame,
+                MenuItem::Tutorial,
+                MenuItem::Demo,
+                MenuItem::Quit
+            ]
+        );
+    }
+
+    #[test]
+    fn test_engine_modes_need_stockfish() {
+        use crate::ui::widgets::menu::GameModeOption;
+
+        let mut state = MenuState::default();
+        assert!(!state.needs_missing_engine());
+        state.engine_missing = true;
+        assert!(state.needs_missing_engine());
+        state.game_mode = GameModeOption::EngineVsEngine;
+        assert!(state.needs_missing_engine());
+        state.game_mode = GameModeOption::HumanVsHuman;
+        assert!(!state.needs_missing_engine());
+    }
+
     #[test]
     fn test_a_on_completed_review_does_nothing() {
         let games = vec![sample_game(
@@ -931,6 +2281,49 @@ mod tests {
         assert!(state.review_table.is_none());
     }
 
+    #[test]
+    fn test_e_cycles_the_endgame_filter() {
+        use chess_client::EndgameTypeProto;
+        let with_endgame = |game_id: &str, endgame: EndgameTypeProto| {
+            let mut game = sample_game(game_id, Some(2));
+            game.endgame = Some(endgame as i32);
+            game
+        };
+        let games = vec![
+            with_endgame("rook_1", EndgameTypeProto::EndgameTypeRook),
+            sample_game("no_endgame", None),
+            with_endgame("pawn", EndgameTypeProto::EndgameTypePawn),
+            with_endgame("rook_2", EndgameTypeProto::EndgameTypeRook),
+        ];
+        let mut state = menu_with_review_table(games);
+        let shown = |state: &MenuState| {
+            let ctx = state.review_table.as_ref().unwrap();
+            let ids: Vec<String> = ctx.games.iter().map(|g| g.game_id.clone()).collect();
+            (ctx.endgame_filter, ids)
+        };
+
+        assert!(handle_review_table_input(&mut state, KeyCode::Char('e')).is_none());
+        assert_eq!(
+            shown(&state),
+            (
+                Some(EndgameTypeProto::EndgameTypePawn),
+                vec!["pawn".to_string()]
+            )
+        );
+        handle_review_table_input(&mut state, KeyCode::Char('e'));
+        assert_eq!(
+            shown(&state),
+            (
+                Some(EndgameTypeProto::EndgameTypeRook),
+                vec!["rook_1".to_string(), "rook_2".to_string()]
+            )
+        );
+        handle_review_table_input(&mut state, KeyCode::Char('e'));
+        let (filter, ids) = shown(&state);
+        assert_eq!(filter, None);
+        assert_eq!(ids.len(), 4);
+    }
+
     #[test]
     fn test_navigation_in_review_table() {
         let games = vec![
@@ -987,4 +2380,456 @@ mod tests {
             _ => panic!("Expected StartGame"),
         }
     }
+
+    #[test]
+    fn test_s_on_simul_game_requests_report() {
+        let mut simul_game = sample_game("game_1", None);
+        simul_game.simul_id = Some("simul_1".to_string());
+        let mut state = menu_with_review_table(vec![simul_game]);
+
+        match handle_review_table_input(&mut state, KeyCode::Char('s')) {
+            Some(MenuAction::SimulReport(id)) => assert_eq!(id, "simul_1"),
+            _ => panic!("Expected SimulReport"),
+        }
+        assert!(state.review_table.is_none());
+    }
+
+    #[test]
+    fn test_s_on_regular_game_does_nothing() {
+        let mut state = menu_with_review_table(vec![sample_game("game_1", None)]);
+        assert!(handle_review_table_input(&mut state, KeyCode::Char('s')).is_none());
+        assert!(state.review_table.is_some());
+    }
+
+    fn menu_with_opponents() -> MenuState {
+        MenuState {
+            opponents: crate::opponents::OpponentRoster::default().opponents,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_named_opponent_sets_skill_and_name() {
+        let mut state = menu_with_opponents();
+        state.cycle_opponent(1);
+        let config = create_game_config(&state);
+        assert_eq!(config.opponent.as_deref(), Some("Stocky 1500"));
+        assert_eq!(config.skill_level, state.opponents[0].skill_level);
+    }
+
+    #[test]
+    fn test_opponent_cycles_back_to_none() {
+        let mut state = menu_with_opponents();
+        state.cycle_opponent(-1);
+        assert_eq!(state.opponent, Some(state.opponents.len() - 1));
+        state.cycle_opponent(1);
+        assert_eq!(state.opponent, None);
+        assert_eq!(create_game_config(&state).opponent, None);
+    }
+
+    #[test]
+    fn test_opponent_ignored_outside_human_vs_engine() {
+        use crate::ui::widgets::menu::GameModeOption;
+        let mut state = menu_with_opponents();
+        state.cycle_opponent(1);
+        state.game_mode = GameModeOption::EngineVsEngine;
+        assert_eq!(create_game_config(&state).opponent, None);
+    }
+
+    #[test]
+    fn test_engine_lines_only_reach_engine_vs_engine() {
+        use crate::ui::widgets::menu::GameModeOption;
+
+        let mut state = MenuState::default();
+        assert!(!state.items().contains(&MenuItem::EngineLines(1)));
+
+        state.game_mode = GameModeOption::EngineVsEngine;
+        let lines = Some(MenuItem::EngineLines(state.engine_lines));
+        cycle_option(&mut state, &lines, -1);
+        assert_eq!(state.engine_lines, crate::state::MAX_ENGINE_LINES);
+        cycle_option(&mut state, &lines, 1);
+        assert_eq!(state.engine_lines, 1);
+        cycle_option(&mut state, &lines, 1);
+        assert_eq!(create_game_config(&state).engine_lines, 2);
+
+        state.game_mode = GameModeOption::HumanVsEngine;
+        assert_eq!(create_game_config(&state).engine_lines, 1);
+    }
+
+    #[test]
+    fn test_notation_cycles_and_reaches_config() {
+        use chess::{NotationStyle, PieceLanguage};
+
+        let mut state = MenuState::default();
+        let notation = Some(MenuItem::Notation(state.notation.style));
+        cycle_option(&mut state, &notation, 1);
+        assert_eq!(state.notation.style, NotationStyle::Figurine);
+        assert!(!state
+            .items()
+            .contains(&MenuItem::PieceLetters(PieceLanguage::English)));
+
+        cycle_option(&mut state, &notation, 1);
+        let letters = Some(MenuItem::PieceLetters(state.notation.language));
+        cycle_option(&mut state, &letters, -1);
+        assert_eq!(state.notation.language, PieceLanguage::Dutch);
+        let config = create_game_config(&state);
+        assert_eq!(config.notation.style, NotationStyle::Long);
+        assert_eq!(config.notation.language, PieceLanguage::Dutch);
+    }
+
+    #[test]
+    fn test_opponent_stats_rows() {
+        let rows = opponent_stats_rows(&[chess_client::OpponentStatsProto {
+            name: "Aggro Bot".to_string(),
+            games: 5,
+            wins: 2,
+            draws: 1,
+            losses: 2,
+            skill_level: 12,
+            average_accuracy: None,
+            performance_rating: 2250,
+            last_played_at: 1000,
+        }]);
+        assert_eq!(
+            rows[0],
+            ["Aggro Bot", "5", "2-1-2", "2250", "Not reviewed", "12"]
+        );
+    }
+
+    fn menu_with_goals(goal_ids: &[&str]) -> MenuState {
+        use crate::ui::widgets::menu::StatsContext;
+        let goals: Vec<_> = goal_ids
+            .iter()
+            .map(|id| chess_client::GoalProgressProto {
+                goal: Some(chess_client::GoalProto {
+                    goal_id: id.to_string(),
+                    ..Default::default()
+                }),
+                current: None,
+                achieved: false,
+            })
+            .collect();
+        MenuState {
+            stats: Some(StatsContext {
+                table_state: SelectableTableState::new(0),
+                opponents: vec![],
+                activity: None,
+                goals_focused: !goals.is_empty(),
+                goals,
+                selected_goal: 0,
+                goal_draft: None,
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_new_goal_dialog_saves_goal() {
+        let mut state = menu_with_goals(&[]);
+        assert!(handle_stats_input(&mut state, KeyCode::Char('n')).is_none());
+        handle_stats_input(&mut state, KeyCode::Down);
+        handle_stats_input(&mut state, KeyCode::Char('5'));
+
+        match handle_stats_input(&mut state, KeyCode::Enter) {
+            Some(MenuAction::AddGoal {
+                metric,
+                target,
+                period,
+            }) => {
+                assert_eq!(metric, chess_client::GoalMetricType::GoalMetricGamesPlayed);
+                assert_eq!(target, 205.0);
+                assert_eq!(period, chess_client::GoalPeriodType::GoalPeriodMonth);
+            }
+            _ => panic!("expected AddGoal"),
+        }
+    }
+
+    #[test]
+    fn test_new_goal_rejects_bad_target() {
+        let mut state = menu_with_goals(&[]);
+        handle_stats_input(&mut state, KeyCode::Char('n'));
+        handle_stats_input(&mut state, KeyCode::Down);
+        handle_stats_input(&mut state, KeyCode::Backspace);
+        handle_stats_input(&mut state, KeyCode::Backspace);
+
+        assert!(handle_stats_input(&mut state, KeyCode::Enter).is_none());
+        let draft = state.stats.unwrap().goal_draft.unwrap();
+        assert!(draft.error.is_some());
+    }
+
+    #[test]
+    fn test_delete_selected_goal() {
+        let mut state = menu_with_goals(&["g1", "g2"]);
+        handle_stats_input(&mut state, KeyCode::Down);
+        match handle_stats_input(&mut state, KeyCode::Char('d')) {
+            Some(MenuAction::DeleteGoal(id)) => assert_eq!(id, "g2"),
+            _ => panic!("expected DeleteGoal"),
+        }
+
+        // With focus on the head-to-head table, d does nothing
+        handle_stats_input(&mut state, KeyCode::Tab);
+        assert!(handle_stats_input(&mut state, KeyCode::Char('d')).is_none());
+    }
+
+    fn menu_with_studies(ids: &[&str]) -> MenuState {
+        use crate::ui::widgets::menu::StudiesContext;
+        let studies: Vec<_> = ids
+            .iter()
+            .map(|id| chess_client::StudyInfo {
+                study_id: id.to_string(),
+                ..Default::default()
+            })
+            .collect();
+        MenuState {
+            studies: Some(StudiesContext {
+                table_state: SelectableTableState::new(studies.len()),
+                studies,
+                draft: None,
+                status: None,
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_new_study_dialog_builds_study() {
+        let mut state = menu_with_studies(&[]);
+        handle_studies_input(&mut state, KeyCode::Char('n'));
+        // A study needs a name
+        assert!(handle_studies_input(&mut state, KeyCode::Enter).is_none());
+        for c in "Sicilian".chars() {
+            handle_studies_input(&mut state, KeyCode::Char(c));
+        }
+        handle_studies_input(&mut state, KeyCode::Down);
+        handle_studies_input(&mut state, KeyCode::Char('B'));
+
+        match handle_studies_input(&mut state, KeyCode::Enter) {
+            Some(MenuAction::CreateStudy { name, filter }) => {
+                assert_eq!(name, "Sicilian");
+                assert_eq!(filter.opening.as_deref(), Some("B"));
+                assert_eq!(
+                    filter.outcome,
+                    chess_client::StudyOutcomeType::StudyOutcomeLoss as i32
+                );
+            }
+            _ => panic!("expected CreateStudy"),
+        }
+    }
+
+    #[test]
+    fn test_studies_list_actions() {
+        let mut state = menu_with_studies(&["s1", "s2"]);
+        handle_studies_input(&mut state, KeyCode::Down);
+        match handle_studies_input(&mut state, KeyCode::Enter) {
+            Some(MenuAction::OpenStudy(id)) => assert_eq!(id, "s2"),
+            _ => panic!("expected OpenStudy"),
+        }
+        match handle_studies_input(&mut state, KeyCode::Char('d')) {
+            Some(MenuAction::DeleteStudy(id)) => assert_eq!(id, "s2"),
+            _ => panic!("expected DeleteStudy"),
+        }
+        assert!(handle_studies_input(&mut state, KeyCode::Esc).is_none());
+        assert!(state.studies.is_none());
+    }
+
+    #[test]
+    fn test_study_tree_navigation() {
+        use crate::ui::widgets::menu::StudyContext;
+        let node = |san: &str, children| chess_client::StudyNodeProto {
+            san: san.to_string(),
+            children,
+            ..Default::default()
+        };
+        let root = node(
+            "",
+            vec![node("e4", vec![node("c5", vec![]), node("e5", vec![])])],
+        );
+        let mut state = MenuState {
+            study: Some(StudyContext {
+                study: chess_client::GetStudyResponse {
+                    study: Some(chess_client::StudyInfo {
+                        study_id: "s1".to_string(),
+                        ..Default::default()
+                    }),
+                    root: Some(root),
+                    game_ids: vec![],
+                    chapters: vec![],
+                },
+                path: vec![],
+                selected: 0,
+            }),
+            ..Default::default()
+        };
+
+        handle_study_input(&mut state, KeyCode::Enter);
+        handle_study_input(&mut state, KeyCode::Down);
+        handle_study_input(&mut state, KeyCode::Down);
+        handle_study_input(&mut state, KeyCode::Right);
+        let ctx = state.study.as_ref().unwrap();
+        assert_eq!((ctx.path.as_slice(), ctx.selected), (&[0, 1][..], 0));
+
+        // e5 has no replies to follow
+        handle_study_input(&mut state, KeyCode::Right);
+        assert_eq!(state.study.as_ref().unwrap().path, [0, 1]);
+
+        handle_study_input(&mut state, KeyCode::Left);
+        let ctx = state.study.as_ref().unwrap();
+        assert_eq!((ctx.path.as_slice(), ctx.selected), (&[0][..], 1));
+
+        match handle_study_input(&mut state, KeyCode::Char('e')) {
+            Some(MenuAction::ExportStudy(id)) => assert_eq!(id, "s1"),
+            _ => panic!("expected ExportStudy"),
+        }
+        assert!(matches!(
+            handle_study_input(&mut state, KeyCode::Esc),
+            Some(MenuAction::Studies)
+        ));
+    }
+
+    fn chapter(id: &str) -> chess_client::StudyChapterProto {
+        let node = |id, parent, san: &str, children| chess_client::ChapterNodeProto {
+            id,
+            parent,
+            san: san.to_string(),
+            fen: "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".to_string(),
+            children,
+            ..Default::default()
+        };
+        chess_client::StudyChapterProto {
+            chapter_id: id.to_string(),
+            name: id.to_string(),
+            game_id: None,
+            nodes: vec![
+                node(0, None, "", vec![1]),
+                node(1, Some(0), "e4", vec![2, 3]),
+                node(2, Some(1), "c5", vec![]),
+                node(3, Some(1), "e5", vec![]),
+            ],
+        }
+    }
+
+    fn menu_with_chapters(ids: &[&str]) -> MenuState {
+        let study = chess_client::GetStudyResponse {
+            study: Some(chess_client::StudyInfo {
+                study_id: "s1".to_string(),
+                name: "Endgames".to_string(),
+                ..Default::default()
+            }),
+            root: None,
+            game_ids: vec![],
+            chapters: ids.iter().map(|id| chapter(id)).collect(),
+        };
+        MenuState {
+            chapters: Some(chapters_context(
+                study,
+                Some(ids[ids.len() - 1].to_string()),
+                None,
+            )),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_chapter_list_actions() {
+        let mut state = menu_with_chapters(&["c1", "c2"]);
+        // The last chapter starts selected and cannot move further down
+        assert!(handle_chapters_input(&mut state, KeyCode::Char(']')).is_none());
+        match handle_chapters_input(&mut state, KeyCode::Char('[')) {
+            Some(MenuAction::MoveChapter {
+                chapter_id, index, ..
+            }) => assert_eq!((chapter_id.as_str(), index), ("c2", 0)),
+            _ => panic!("expected MoveChapter"),
+        }
+        match handle_chapters_input(&mut state, KeyCode::Char('d')) {
+            Some(MenuAction::DeleteChapter { chapter_id, .. }) => assert_eq!(chapter_id, "c2"),
+            _ => panic!("expected DeleteChapter"),
+        }
+
+        handle_chapters_input(&mut state, KeyCode::Char('n'));
+        for c in "Lucena".chars() {
+            handle_chapters_input(&mut state, KeyCode::Char(c));
+        }
+        match handle_chapters_input(&mut state, KeyCode::Enter) {
+            Some(MenuAction::AddChapter {
+                study_id,
+                name,
+                fen,
+                game_id,
+            }) => {
+                assert_eq!((study_id.as_str(), name.as_str()), ("s1", "Lucena"));
+                assert_eq!((fen, game_id), (None, None));
+            }
+            _ => panic!("expected AddChapter"),
+        }
+
+        // A study without games goes back to the list of studies
+        handle_chapters_input(&mut state, KeyCode::Esc);
+        assert!(matches!(
+            handle_chapters_input(&mut state, KeyCode::Esc),
+            Some(MenuAction::Studies)
+        ));
+
+        handle_chapters_input(&mut state, KeyCode::Enter);
+        let ctx = state.chapter.as_ref().unwrap();
+        assert_eq!((ctx.chapter.chapter_id.as_str(), ctx.node_id), ("c2", 0));
+    }
+
+    #[test]
+    fn test_chapter_navigation_and_edits() {
+        let mut state = menu_with_chapters(&["c1"]);
+        handle_chapters_input(&mut state, KeyCode::Enter);
+
+        handle_chapter_input(&mut state, KeyCode::Right);
+        handle_chapter_input(&mut state, KeyCode::Down);
+        handle_chapter_input(&mut state, KeyCode::Right);
+        assert_eq!(state.chapter.as_ref().unwrap().node_id, 3);
+        handle_chapter_input(&mut state, KeyCode::Left);
+        let ctx = state.chapter.as_ref().unwrap();
+        assert_eq!((ctx.node_id, ctx.selected), (1, 1));
+
+        handle_chapter_input(&mut state, KeyCode::Char('m'));
+        for c in "Nf3".chars() {
+            handle_chapter_input(&mut state, KeyCode::Char(c));
+        }
+        match handle_chapter_input(&mut state, KeyCode::Enter) {
+            Some(MenuAction::AddChapterMove {
+                node_id, notation, ..
+            }) => assert_eq!((node_id, notation.as_str()), (1, "Nf3")),
+            _ => panic!("expected AddChapterMove"),
+        }
+
+        match handle_chapter_input(&mut state, KeyCode::Char('5')) {
+            Some(MenuAction::AnnotateChapterNode { node_id, nags, .. }) => {
+                assert_eq!((node_id, nags), (1, vec![5]))
+            }
+            _ => panic!("expected AnnotateChapterNode"),
+        }
+
+        handle_chapter_input(&mut state, KeyCode::Char('a'));
+        for c in "Re7e5".chars() {
+            handle_chapter_input(&mut state, KeyCode::Char(c));
+        }
+        match handle_chapter_input(&mut state, KeyCode::Enter) {
+            Some(MenuAction::AnnotateChapterNode { arrows, .. }) => {
+                assert_eq!(arrows.len(), 1);
+                assert_eq!(
+                    arrows[0].color,
+                    chess_client::ArrowColorType::ArrowColorRed as i32
+                );
+            }
+            _ => panic!("expected AnnotateChapterNode"),
+        }
+
+        match handle_chapter_input(&mut state, KeyCode::Char('x')) {
+            Some(MenuAction::DeleteChapterMove { node_id, .. }) => assert_eq!(node_id, 1),
+            _ => panic!("expected DeleteChapterMove"),
+        }
+        // The start position cannot be deleted
+        handle_chapter_input(&mut state, KeyCode::Left);
+        assert!(handle_chapter_input(&mut state, KeyCode::Char('x')).is_none());
+
+        handle_chapter_input(&mut state, KeyCode::Esc);
+        assert!(state.chapter.is_none());
+        assert!(state.chapters.is_some());
+    }
 }
@@ -1,9 +1,15 @@
 // UI modules
+pub mod attract_app;
+pub mod clipboard;
 pub mod fsm;
+pub mod glyphs;
+pub mod graphics;
 pub mod menu_app;
+pub mod tutorial_app;
 pub mod widgets;
 
 // Main entry points
+pub mod driver;
 pub mod input;
 pub mod render_loop;
 
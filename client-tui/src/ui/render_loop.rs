@@ -1,7 +1,14 @@
-use crate::state::{GameMode, GameSession, PlayerColor};
+use crate::dispatch::Action;
+use crate::external_input::ExternalInput;
+use crate::offline::{OfflineError, OfflineLibrary};
+use crate::profiling::FRAME_BUDGET;
+use crate::review_progress::ReviewProgressTracker;
+use crate::simul::{self, SimulStatus};
+use crate::state::{connect_server, GameMode, GameSession};
+use crate::tutorial;
 use crate::ui::fsm::render_spec::InputPhase;
-use crate::ui::menu_app;
-use chess_client::{GameModeProto, GameModeType, PlayerSideProto};
+use crate::ui::{attract_app, graphics, menu_app, tutorial_app};
+use chess_client::GameModeProto;
 use crossterm::{
     event::{DisableMouseCapture, EnableMouseCapture, Event},
     execute,
@@ -9,48 +16,152 @@ use crossterm::{
 };
 use ratatui::{backend::CrosstermBackend, Terminal};
 use std::io;
-use std::path::PathBuf;
 use std::time::Duration;
 
-/// Get the socket path for server communication.
-///
-/// Priority:
-/// 1. CHESSTTY_SOCKET_PATH env variable if set
-/// 2. /tmp/chesstty.sock as fallback
-fn get_socket_path() -> PathBuf {
-    if let Ok(path) = std::env::var("CHESSTTY_SOCKET_PATH") {
-        return PathBuf::from(path);
+/// Finished games from the read-only offline library, for when the server
+/// is unreachable.
+async fn list_offline_games() -> Vec<chess_client::FinishedGameInfo> {
+    let games = match OfflineLibrary::open_default().await {
+        Ok(library) => library.list_finished_games().await,
+        Err(e) => Err(e),
+    };
+    games.unwrap_or_else(|e| {
+        tracing::warn!("Offline library unavailable: {}", e);
+        vec![]
+    })
+}
+
+/// A completed review from the read-only offline library.
+async fn read_offline_review(game_id: &str) -> Result<chess_client::GameReviewProto, OfflineError> {
+    OfflineLibrary::open_default()
+        .await?
+        .get_game_review(game_id)
+        .await
+}
+
+/// Fetch everything shown on the Stats screen for the current profile.
+/// Parts that fail to load are logged and left empty.
+async fn fetch_stats_report(client: &mut chess_client::ChessClient) -> menu_app::MenuReport {
+    let profile = tutorial::current_profile();
+    let opponents = client.get_opponent_stats().await.unwrap_or_else(|e| {
+        tracing::warn!("Failed to fetch opponent stats: {}", e);
+        vec![]
+    });
+    let activity = client
+        .get_activity_summary(&profile, 0)
+        .await
+        .map_err(|e| tracing::warn!("Failed to fetch activity summary: {}", e))
+        .ok();
+    let goals = client.list_goals(&profile).await.unwrap_or_else(|e| {
+        tracing::warn!("Failed to fetch goals: {}", e);
+        vec![]
+    });
+    menu_app::MenuReport::Stats {
+        opponents,
+        activity,
+        goals,
     }
+}
 
-    PathBuf::from("/tmp/chesstty.sock")
+/// The list of studies, with the outcome of the action that led back to it.
+async fn fetch_studies_report(
+    client: &mut chess_client::ChessClient,
+    status: Option<String>,
+) -> menu_app::MenuReport {
+    let studies = client.list_studies().await.unwrap_or_else(|e| {
+        tracing::warn!("Failed to fetch studies: {}", e);
+        vec![]
+    });
+    menu_app::MenuReport::Studies { studies, status }
 }
 
-/// Convert client-side GameMode to proto representation.
-fn game_mode_to_proto(mode: &GameMode) -> GameModeProto {
-    match mode {
-        GameMode::HumanVsHuman => GameModeProto {
-            mode: GameModeType::HumanVsHuman as i32,
-            human_side: None,
-        },
-        GameMode::HumanVsEngine { human_side } => GameModeProto {
-            mode: GameModeType::HumanVsEngine as i32,
-            human_side: Some(match human_side {
-                PlayerColor::White => PlayerSideProto::White as i32,
-                PlayerColor::Black => PlayerSideProto::Black as i32,
-            }),
-        },
-        GameMode::EngineVsEngine => GameModeProto {
-            mode: GameModeType::EngineVsEngine as i32,
-            human_side: None,
-        },
-        GameMode::AnalysisMode => GameModeProto {
-            mode: GameModeType::Analysis as i32,
-            human_side: None,
-        },
-        GameMode::ReviewMode => GameModeProto {
-            mode: GameModeType::Review as i32,
-            human_side: None,
+/// A study's chapter list, falling back to the list of studies if the study
+/// cannot be fetched.
+async fn fetch_chapters_report(
+    client: &mut chess_client::ChessClient,
+    study_id: &str,
+    selected: Option<String>,
+    status: Option<String>,
+) -> menu_app::MenuReport {
+    match client.get_study(study_id).await {
+        Ok(study) => menu_app::MenuReport::Chapters {
+            study,
+            selected,
+            status,
         },
+        Err(e) => {
+            tracing::warn!(study_id = %study_id, "Failed to fetch study: {}", e);
+            fetch_studies_report(client, Some(format!("Could not open the study: {}", e))).await
+        }
+    }
+}
+
+/// A chapter after an edit: the edited chapter at `node_id`, or on error the
+/// chapter as stored with the error as status.
+async fn chapter_report(
+    client: &mut chess_client::ChessClient,
+    study_id: &str,
+    chapter_id: &str,
+    edited: chess_client::ClientResult<(chess_client::StudyChapterProto, u32)>,
+    node_id: u32,
+) -> menu_app::MenuReport {
+    let error = match edited {
+        Ok((chapter, node_id)) => {
+            return menu_app::MenuReport::Chapter {
+                study_id: study_id.to_string(),
+                chapter,
+                node_id,
+                status: None,
+            }
+        }
+        Err(e) => e,
+    };
+    tracing::warn!(study_id = %study_id, chapter_id = %chapter_id, "Failed to edit chapter: {}", error);
+    let stored = client.get_study(study_id).await.ok().and_then(|study| {
+        study
+            .chapters
+            .into_iter()
+            .find(|c| c.chapter_id == chapter_id)
+    });
+    match stored {
+        Some(chapter) => {
+            // The node may be gone if another client deleted it
+            let node_id = if chapter.nodes.iter().any(|n| n.id == node_id) {
+                node_id
+            } else {
+                chapter.nodes.first().map_or(0, |n| n.id)
+            };
+            menu_app::MenuReport::Chapter {
+                study_id: study_id.to_string(),
+                chapter,
+                node_id,
+                status: Some(error.to_string()),
+            }
+        }
+        None => fetch_chapters_report(client, study_id, None, Some(error.to_string())).await,
+    }
+}
+
+/// Fetch a study's PGN and write it under the data directory, returning a
+/// status line for the list of studies.
+async fn export_study(client: &mut chess_client::ChessClient, study_id: &str) -> String {
+    let Some(dir) = crate::study::default_export_dir() else {
+        return "No data directory to export to".to_string();
+    };
+    let name = match client.get_study(study_id).await {
+        Ok(study) => study.study.map(|s| s.name).unwrap_or_default(),
+        Err(e) => return format!("Export failed: {}", e),
+    };
+    let pgn = match client.export_study_pgn(study_id).await {
+        Ok(pgn) => pgn,
+        Err(e) => return format!("Export failed: {}", e),
+    };
+    match crate::study::export(&dir, &name, &pgn) {
+        Ok(path) => format!("Exported to {}", path.display()),
+        Err(e) => {
+            tracing::warn!(study_id = %study_id, "Failed to export study: {}", e);
+            format!("Export failed: {}", e)
+        }
     }
 }
 
@@ -59,14 +170,27 @@ enum ExitReason {
     Quit,
     ReturnToMenu,
     PlaySnapshot(Box<menu_app::GameConfig>),
+    /// Analyse from a review position, then reopen the review.
+    BranchAnalysis {
+        analysis: Box<menu_app::GameConfig>,
+        review: Box<menu_app::GameConfig>,
+    },
 }
 
 pub async fn run_app() -> anyhow::Result<()> {
+    // Report fetched on request, shown when the menu reopens
+    let mut report = None;
+    // Opened once so tools stay connected from game to game
+    let mut external = ExternalInput::start();
+    // Reviews queued from the menu, followed from one menu visit to the next
+    let review_progress = ReviewProgressTracker::default();
+
     // Outer loop: menu → game → menu → game → ...
     loop {
-        // Pre-fetch data from server for the menu
-        let (suspended, positions, finished_games) =
-            match chess_client::ChessClient::connect_uds(&get_socket_path()).await {
+        // Pre-fetch data from server for the menu, falling back to the
+        // read-only offline library when the server is down
+        let (suspended, positions, finished_games, offline, engine_missing) =
+            match connect_server().await {
                 Ok(mut client) => {
                     let sessions = client.list_suspended_sessions().await.unwrap_or_else(|e| {
                         tracing::warn!("Failed to list suspended sessions: {}", e);
@@ -80,37 +204,303 @@ pub async fn run_app() -> anyhow::Result<()> {
                         tracing::warn!("Failed to list finished games: {}", e);
                         vec![]
                     });
-                    (sessions, positions, finished)
+                    // Servers that predate the check report an engine
+                    let engine_missing = client
+                        .get_server_info()
+                        .await
+                        .is_ok_and(|info| info.engine_missing);
+                    (sessions, positions, finished, false, engine_missing)
                 }
                 Err(e) => {
                     tracing::warn!("Failed to connect to server: {}", e);
-                    (vec![], vec![], vec![])
+                    (vec![], vec![], list_offline_games().await, true, false)
                 }
             };
 
         // Show menu and get game configuration
-        let menu_action = menu_app::show_menu(suspended, positions, finished_games).await?;
+        let menu_action = menu_app::show_menu(
+            suspended,
+            positions,
+            finished_games,
+            report.take(),
+            offline,
+            engine_missing,
+            review_progress.clone(),
+        )
+        .await?;
 
         let config = match menu_action {
             menu_app::MenuAction::Quit => return Ok(()),
+            menu_app::MenuAction::Tutorial => {
+                tutorial_app::run_tutorial().await?;
+                continue;
+            }
+            menu_app::MenuAction::Demo => {
+                // Without an engine the demo shows only the famous games
+                attract_app::run_attract(offline || engine_missing).await?;
+                continue;
+            }
             menu_app::MenuAction::EnqueueReview(game_id) => {
-                // Enqueue analysis and return to menu
-                if let Ok(mut client) =
-                    chess_client::ChessClient::connect_uds(&get_socket_path()).await
-                {
+                // Enqueue analysis, follow it, and return to menu
+                if let Ok(mut client) = connect_server().await {
                     match client.enqueue_review(&game_id).await {
-                        Ok(_) => tracing::info!(game_id = %game_id, "Review enqueued"),
+                        Ok(_) => {
+                            tracing::info!(game_id = %game_id, "Review enqueued");
+                            review_progress.follow(client, game_id);
+                        }
                         Err(e) => tracing::warn!(game_id = %game_id, "Failed to enqueue: {}", e),
                     }
                 }
                 continue;
             }
+            menu_app::MenuAction::SimulReport(simul_id) => {
+                if let Ok(mut client) = connect_server().await {
+                    match client.get_simul_report(&simul_id).await {
+                        Ok(simul) => report = Some(menu_app::MenuReport::Simul(simul)),
+                        Err(e) => {
+                            tracing::warn!(simul_id = %simul_id, "Failed to fetch simul report: {}", e)
+                        }
+                    }
+                }
+                continue;
+            }
+            menu_app::MenuAction::Stats => {
+                if let Ok(mut client) = connect_server().await {
+                    report = Some(fetch_stats_report(&mut client).await);
+                }
+                continue;
+            }
+            menu_app::MenuAction::AddGoal {
+                metric,
+                target,
+                period,
+            } => {
+                if let Ok(mut client) = connect_server().await {
+                    if let Err(e) = client
+                        .add_goal(&tutorial::current_profile(), metric, target, period)
+                        .await
+                    {
+                        tracing::warn!("Failed to add goal: {}", e);
+                    }
+                    report = Some(fetch_stats_report(&mut client).await);
+                }
+                continue;
+            }
+            menu_app::MenuAction::DeleteGoal(goal_id) => {
+                if let Ok(mut client) = connect_server().await {
+                    if let Err(e) = client
+                        .delete_goal(&tutorial::current_profile(), &goal_id)
+                        .await
+                    {
+                        tracing::warn!("Failed to delete goal: {}", e);
+                    }
+                    report = Some(fetch_stats_report(&mut client).await);
+                }
+                continue;
+            }
+            menu_app::MenuAction::Digest => {
+                if let Ok(mut client) = connect_server().await {
+                    match client.get_training_digest(0).await {
+                        Ok(digest) => report = Some(menu_app::MenuReport::Digest(digest)),
+                        Err(e) => tracing::warn!("Failed to fetch training digest: {}", e),
+                    }
+                }
+                continue;
+            }
+            menu_app::MenuAction::Studies => {
+                if let Ok(mut client) = connect_server().await {
+                    report = Some(fetch_studies_report(&mut client, None).await);
+                }
+                continue;
+            }
+            menu_app::MenuAction::CreateStudy { name, filter } => {
+                if let Ok(mut client) = connect_server().await {
+                    let status = match client.create_study(&name, filter).await {
+                        Ok(study) => format!("Built {} from {} games", study.name, study.games),
+                        Err(e) => {
+                            tracing::warn!("Failed to create study: {}", e);
+                            format!("Could not build the study: {}", e)
+                        }
+                    };
+                    report = Some(fetch_studies_report(&mut client, Some(status)).await);
+                }
+                continue;
+            }
+            menu_app::MenuAction::CreateBlankStudy(name) => {
+                if let Ok(mut client) = connect_server().await {
+                    report = Some(match client.create_blank_study(&name).await {
+                        Ok(study) => {
+                            fetch_chapters_report(&mut client, &study.study_id, None, None).await
+                        }
+                        Err(e) => {
+                            tracing::warn!("Failed to create study: {}", e);
+                            let status = format!("Could not create the study: {}", e);
+                            fetch_studies_report(&mut client, Some(status)).await
+                        }
+                    });
+                }
+                continue;
+            }
+            menu_app::MenuAction::OpenStudy(study_id) => {
+                if let Ok(mut client) = connect_server().await {
+                    match client.get_study(&study_id).await {
+                        Ok(study) => report = Some(menu_app::MenuReport::Study(study)),
+                        Err(e) => {
+                            tracing::warn!(study_id = %study_id, "Failed to fetch study: {}", e);
+                            report = Some(fetch_studies_report(&mut client, None).await);
+                        }
+                    }
+                }
+                continue;
+            }
+            menu_app::MenuAction::DeleteStudy(study_id) => {
+                if let Ok(mut client) = connect_server().await {
+                    if let Err(e) = client.delete_study(&study_id).await {
+                        tracing::warn!(study_id = %study_id, "Failed to delete study: {}", e);
+                    }
+                    report = Some(fetch_studies_report(&mut client, None).await);
+                }
+                continue;
+            }
+            menu_app::MenuAction::ExportStudy(study_id) => {
+                if let Ok(mut client) = connect_server().await {
+                    let status = export_study(&mut client, &study_id).await;
+                    report = Some(fetch_studies_report(&mut client, Some(status)).await);
+                }
+                continue;
+            }
+            menu_app::MenuAction::AddChapter {
+                study_id,
+                name,
+                fen,
+                game_id,
+            } => {
+                if let Ok(mut client) = connect_server().await {
+                    let (selected, status) = match client
+                        .add_study_chapter(&study_id, &name, fen, game_id)
+                        .await
+                    {
+                        Ok(chapter) => (Some(chapter.chapter_id), None),
+                        Err(e) => (None, Some(format!("Could not add the chapter: {}", e))),
+                    };
+                    report =
+                        Some(fetch_chapters_report(&mut client, &study_id, selected, status).await);
+                }
+                continue;
+            }
+            menu_app::MenuAction::DeleteChapter {
+                study_id,
+                chapter_id,
+            } => {
+                if let Ok(mut client) = connect_server().await {
+                    let status = match client.delete_study_chapter(&study_id, &chapter_id).await {
+                        Ok(()) => None,
+                        Err(e) => Some(format!("Could not delete the chapter: {}", e)),
+                    };
+                    report =
+                        Some(fetch_chapters_report(&mut client, &study_id, None, status).await);
+                }
+                continue;
+            }
+            menu_app::MenuAction::MoveChapter {
+                study_id,
+                chapter_id,
+                index,
+            } => {
+                if let Ok(mut client) = connect_server().await {
+                    let status = match client
+                        .move_study_chapter(&study_id, &chapter_id, index)
+                        .await
+                    {
+                        Ok(()) => None,
+                        Err(e) => Some(format!("Could not move the chapter: {}", e)),
+                    };
+                    report = Some(
+                        fetch_chapters_report(&mut client, &study_id, Some(chapter_id), status)
+                            .await,
+                    );
+                }
+                continue;
+            }
+            menu_app::MenuAction::AddChapterMove {
+                study_id,
+                chapter_id,
+                node_id,
+                notation,
+            } => {
+                if let Ok(mut client) = connect_server().await {
+                    let edited = client
+                        .add_chapter_move(&study_id, &chapter_id, node_id, &notation)
+                        .await
+                        .and_then(|r| {
+                            let chapter = r.chapter.ok_or_else(|| {
+                                chess_client::ClientError::InvalidData("missing chapter".into())
+                            })?;
+                            Ok((chapter, r.node_id))
+                        });
+                    report = Some(
+                        chapter_report(&mut client, &study_id, &chapter_id, edited, node_id).await,
+                    );
+                }
+                continue;
+            }
+            menu_app::MenuAction::DeleteChapterMove {
+                study_id,
+                chapter_id,
+                node_id,
+            } => {
+                if let Ok(mut client) = connect_server().await {
+                    let edited = client
+                        .delete_chapter_move(&study_id, &chapter_id, node_id)
+                        .await
+                        .and_then(|r| {
+                            let chapter = r.chapter.ok_or_else(|| {
+                                chess_client::ClientError::InvalidData("missing chapter".into())
+                            })?;
+                            Ok((chapter, r.parent_id))
+                        });
+                    report = Some(
+                        chapter_report(&mut client, &study_id, &chapter_id, edited, node_id).await,
+                    );
+                }
+                continue;
+            }
+            menu_app::MenuAction::AnnotateChapterNode {
+                study_id,
+                chapter_id,
+                node_id,
+                comment,
+                nags,
+                arrows,
+            } => {
+                if let Ok(mut client) = connect_server().await {
+                    let edited = client
+                        .annotate_chapter_node(
+                            &study_id,
+                            &chapter_id,
+                            node_id,
+                            comment,
+                            nags,
+                            arrows,
+                        )
+                        .await
+                        .map(|chapter| (chapter, node_id));
+                    report = Some(
+                        chapter_report(&mut client, &study_id, &chapter_id, edited, node_id).await,
+                    );
+                }
+                continue;
+            }
             menu_app::MenuAction::StartGame(mut cfg) => {
                 // If review mode, fetch the review data before entering game
-                if cfg.mode == crate::state::GameMode::ReviewMode {
+                if cfg.mode == crate::state::GameMode::Review {
                     if let Some(ref game_id) = cfg.resume_session_id {
                         tracing::info!(game_id = %game_id, "Fetching review data");
-                        match chess_client::ChessClient::connect_uds(&get_socket_path()).await {
+                        // The profile counts the review as read in the activity log
+                        match connect_server()
+                            .await
+                            .map(|c| c.with_profile(tutorial::current_profile()))
+                        {
                             Ok(mut client) => match client.get_game_review(game_id).await {
                                 Ok(review) => {
                                     tracing::info!(
@@ -144,8 +534,14 @@ pub async fn run_app() -> anyhow::Result<()> {
                                 }
                             },
                             Err(e) => {
-                                tracing::error!("Failed to connect to server for review: {}", e);
-                                continue;
+                                tracing::warn!("Server unavailable, reading review offline: {}", e);
+                                match read_offline_review(game_id).await {
+                                    Ok(review) => cfg.review_data = Some(review),
+                                    Err(e) => {
+                                        tracing::error!(game_id = %game_id, "Failed to read review: {}", e);
+                                        continue;
+                                    }
+                                }
                             }
                         }
                     } else {
@@ -157,57 +553,40 @@ pub async fn run_app() -> anyhow::Result<()> {
             }
         };
 
-        // Setup terminal for game
-        enable_raw_mode()?;
-        let mut stdout = io::stdout();
-        execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-        let backend = CrosstermBackend::new(stdout);
-        let mut terminal = Terminal::new(backend)?;
-
-        let result = run_game(&mut terminal, config).await;
-
-        // Restore terminal
-        disable_raw_mode()?;
-        execute!(
-            terminal.backend_mut(),
-            LeaveAlternateScreen,
-            DisableMouseCapture
-        )?;
-        terminal.show_cursor()?;
-
-        match result {
-            Ok(ExitReason::Quit) => return Ok(()),
-            Ok(ExitReason::ReturnToMenu) => continue, // Loop back to menu
-            Ok(ExitReason::PlaySnapshot(config)) => {
-                // Re-enter game directly with the snapshot config (skip menu)
-                enable_raw_mode()?;
-                let mut stdout = io::stdout();
-                execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-                let backend = CrosstermBackend::new(stdout);
-                let mut terminal = Terminal::new(backend)?;
-
-                let result = run_game(&mut terminal, *config).await;
-
-                disable_raw_mode()?;
-                execute!(
-                    terminal.backend_mut(),
-                    LeaveAlternateScreen,
-                    DisableMouseCapture
-                )?;
-                terminal.show_cursor()?;
-
-                match result {
-                    Ok(ExitReason::Quit) => return Ok(()),
-                    Ok(ExitReason::ReturnToMenu) => continue,
-                    Ok(ExitReason::PlaySnapshot(_inner_config)) => {
-                        // Nested snapshot — not expected but handle gracefully
-                        tracing::warn!("Nested PlaySnapshot, returning to menu");
-                        continue;
-                    }
-                    Err(e) => return Err(e),
+        // Games opened from a game, a snapshot or a branch, follow without
+        // the menu in between
+        let mut next = Some(config);
+        // Review to reopen once a branched analysis ends
+        let mut return_to = None;
+        while let Some(config) = next.take() {
+            // Setup terminal for game
+            enable_raw_mode()?;
+            let mut stdout = io::stdout();
+            execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+            let backend = CrosstermBackend::new(stdout);
+            let mut terminal = Terminal::new(backend)?;
+
+            let result = run_game(&mut terminal, config, &mut external).await;
+
+            // Restore terminal
+            graphics::clear_images(terminal.backend_mut())?;
+            disable_raw_mode()?;
+            execute!(
+                terminal.backend_mut(),
+                LeaveAlternateScreen,
+                DisableMouseCapture
+            )?;
+            terminal.show_cursor()?;
+
+            match result? {
+                ExitReason::Quit => return Ok(()),
+                ExitReason::ReturnToMenu => next = return_to.take(),
+                ExitReason::PlaySnapshot(config) => next = Some(*config),
+                ExitReason::BranchAnalysis { analysis, review } => {
+                    return_to = Some(*review);
+                    next = Some(*analysis);
                 }
             }
-            Err(e) => return Err(e),
         }
     }
 }
@@ -216,13 +595,19 @@ pub async fn run_app() -> anyhow::Result<()> {
 async fn run_game<B: ratatui::backend::Backend>(
     terminal: &mut Terminal<B>,
     config: menu_app::GameConfig,
+    external: &mut ExternalInput,
 ) -> anyhow::Result<ExitReason> {
     // Create FSM
     use crate::ui::fsm::{UiMode, UiStateMachine};
     let mut fsm = UiStateMachine::default();
+    fsm.notation = config.notation;
+    fsm.live_evals = config.live_evals;
+    if config.screen_reader {
+        fsm.enable_screen_reader();
+    }
 
     // Review mode: no server session, just local navigation
-    if config.mode == GameMode::ReviewMode {
+    if config.mode == GameMode::Review {
         if let Some(review_data) = config.review_data {
             let mut state = GameSession::new_review(
                 "http://[::1]:50051",
@@ -233,32 +618,75 @@ async fn run_game<B: ratatui::backend::Backend>(
             )
             .await
             .map_err(|e| anyhow::anyhow!("Failed to connect: {}", e))?;
+            if let Some(game_id) = state
+                .review_state
+                .as_ref()
+                .map(|r| r.review.game_id.clone())
+            {
+                state.dispatch(Action::LoadBookmarks { game_id });
+            }
+            if let (Some(review), Some(ply)) =
+                (state.review_state.as_mut(), config.review_start_ply)
+            {
+                review.go_to_ply(ply);
+            }
             // Transition FSM to review board
             fsm.transition_to(UiMode::ReviewBoard);
-            return run_ui_loop(terminal, &mut state, fsm).await;
+            return run_ui_loop(terminal, std::slice::from_mut(&mut state), fsm, external).await;
         }
         return Ok(ExitReason::ReturnToMenu);
     }
 
+    if config.simul_boards > 1 {
+        return run_simul(terminal, config, external).await;
+    }
+
+    let mut state = start_session(&config).await?;
+
+    // Transition FSM to game board
+    fsm.transition_to(UiMode::GameBoard);
+
+    run_ui_loop(terminal, std::slice::from_mut(&mut state), fsm, external).await
+}
+
+/// Take over a session from another client with its transfer code. The
+/// server already runs its engine and clocks, so only the event stream is
+/// started.
+async fn claim_session(code: &str, config: &menu_app::GameConfig) -> anyhow::Result<GameSession> {
+    let mut state = GameSession::claim_transfer(code)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to join session: {}", e))?;
+    state.confirm_moves = config.confirm_moves;
+    if let Err(e) = state.start_event_stream().await {
+        state
+            .notifications
+            .error(format!("Failed to start event stream: {}", e));
+    }
+    state.notifications.success("Session taken over");
+    Ok(state)
+}
+
+/// Connect a single game's session and set up its engine and event stream,
+/// ready for input.
+pub(super) async fn start_session(config: &menu_app::GameConfig) -> anyhow::Result<GameSession> {
+    if let Some(ref code) = config.transfer_code {
+        return claim_session(code, config).await;
+    }
+
     // Convert game mode to proto for the server
-    let game_mode_proto = game_mode_to_proto(&config.mode);
+    let game_mode_proto = GameModeProto::from(&config.mode);
 
     // Convert timer config to proto (server owns all timer state)
-    let timer_proto = config.time_control_seconds.map(|seconds| {
-        let ms = seconds * 1000;
-        chess_client::TimerState {
-            white_remaining_ms: ms,
-            black_remaining_ms: ms,
-            active_side: None,
-        }
-    });
+    let timer_proto = timer_to_proto(config.time_control_seconds);
 
     // Connect to server and create client state with game mode, FEN, and timer
-    let mut state = GameSession::new(
+    let mut state = GameSession::new_labeled(
         "http://[::1]:50051",
         config.start_fen.clone(),
         Some(game_mode_proto),
         timer_proto,
+        None,
+        config.opponent.clone(),
     )
     .await
     .map_err(|e| anyhow::anyhow!("Failed to connect to server: {}", e))?;
@@ -270,37 +698,25 @@ async fn run_game<B: ratatui::backend::Backend>(
         match state.client.resume_suspended_session(suspended_id).await {
             Ok(_snapshot) => {
                 if let Err(e) = state.refresh_from_server().await {
-                    state.status_message = Some(format!("Failed to sync state: {}", e));
+                    state
+                        .notifications
+                        .error(format!("Failed to sync state: {}", e));
                 }
 
                 // Restore local game mode from config metadata (for UI rendering)
-                let game_mode_type = config
-                    .resume_game_mode
-                    .and_then(|v| GameModeType::try_from(v).ok())
-                    .unwrap_or(GameModeType::HumanVsHuman);
-                state.mode = match game_mode_type {
-                    GameModeType::HumanVsHuman => GameMode::HumanVsHuman,
-                    GameModeType::HumanVsEngine => {
-                        let side = match config
-                            .resume_human_side
-                            .and_then(|v| PlayerSideProto::try_from(v).ok())
-                        {
-                            Some(PlayerSideProto::Black) => PlayerColor::Black,
-                            _ => PlayerColor::White,
-                        };
-                        GameMode::HumanVsEngine { human_side: side }
-                    }
-                    GameModeType::EngineVsEngine => GameMode::EngineVsEngine,
-                    GameModeType::Analysis => GameMode::AnalysisMode,
-                    GameModeType::Review => GameMode::ReviewMode,
-                };
+                state.mode = GameMode::from(GameModeProto {
+                    mode: config.resume_game_mode.unwrap_or_default(),
+                    human_side: config.resume_human_side,
+                });
 
                 state.skill_level = config.resume_skill_level.unwrap_or(10);
 
-                state.status_message = Some("Session resumed".to_string());
+                state.notifications.success("Session resumed");
             }
             Err(e) => {
-                state.status_message = Some(format!("Failed to resume session: {}", e));
+                state
+                    .notifications
+                    .error(format!("Failed to resume session: {}", e));
             }
         }
     } else {
@@ -308,17 +724,20 @@ async fn run_game<B: ratatui::backend::Backend>(
         state.skill_level = config.skill_level;
         state.mode = config.mode.clone();
     }
+    state.confirm_moves = config.confirm_moves;
 
     // Apply pre-history if starting from a snapshot.
     let is_snapshot = config.pre_history.is_some();
-    if let Some(pre_history) = config.pre_history {
-        state.pre_history = pre_history;
+    if let Some(ref pre_history) = config.pre_history {
+        state.pre_history = pre_history.clone();
     }
 
     // Start event stream BEFORE engine config so we don't miss auto-triggered moves
     // (e.g., when it's the engine's turn at the snapshot position)
     if let Err(e) = state.start_event_stream().await {
-        state.status_message = Some(format!("Failed to start event stream: {}", e));
+        state
+            .notifications
+            .error(format!("Failed to start event stream: {}", e));
     }
 
     // Configure engine after event stream is active so we don't miss
@@ -333,14 +752,16 @@ async fn run_game<B: ratatui::backend::Backend>(
     if is_snapshot && needs_engine {
         let _ = state.client.pause().await;
         state.paused = true;
-        state.status_message = Some("Paused \u{2014} press p to start".to_string());
+        state.notifications.info("Paused \u{2014} press p to start");
     }
 
     if needs_engine {
         if config.resume_session_id.is_some() {
             // Resume: re-enable engine with stored skill level
             if let Err(e) = state.set_engine(true, state.skill_level).await {
-                state.status_message = Some(format!("Failed to enable engine: {}", e));
+                state
+                    .notifications
+                    .error(format!("Failed to enable engine: {}", e));
             }
         } else {
             // New game: full engine configuration
@@ -353,26 +774,127 @@ async fn run_game<B: ratatui::backend::Backend>(
                 )
                 .await
             {
-                state.status_message = Some(format!("Failed to enable engine: {}", e));
+                state
+                    .notifications
+                    .error(format!("Failed to enable engine: {}", e));
+            } else if config.engine_lines > 1 {
+                if let Err(e) = state.set_engine_lines(config.engine_lines).await {
+                    state
+                        .notifications
+                        .error(format!("Failed to set engine lines: {}", e));
+                }
             }
         }
     }
 
-    // Transition FSM to game board
+    Ok(state)
+}
+
+/// Starting clocks for a time control (server owns all timer state).
+fn timer_to_proto(time_control_seconds: Option<u64>) -> Option<chess_client::TimerState> {
+    time_control_seconds.map(|seconds| {
+        let ms = seconds * 1000;
+        chess_client::TimerState {
+            white_remaining_ms: ms,
+            black_remaining_ms: ms,
+            active_side: None,
+        }
+    })
+}
+
+/// Start every board of a simul and run the UI loop over all of them.
+async fn run_simul<B: ratatui::backend::Backend>(
+    terminal: &mut Terminal<B>,
+    config: menu_app::GameConfig,
+    external: &mut ExternalInput,
+) -> anyhow::Result<ExitReason> {
+    use crate::ui::fsm::{UiMode, UiStateMachine};
+
+    let simul_id = simul::new_simul_id();
+    let game_mode_proto = GameModeProto::from(&config.mode);
+    let timer_proto = timer_to_proto(config.time_control_seconds);
+
+    let mut boards = Vec::with_capacity(config.simul_boards as usize);
+    for _ in 0..config.simul_boards {
+        let mut board = GameSession::new_labeled(
+            "http://[::1]:50051",
+            config.start_fen.clone(),
+            Some(game_mode_proto.clone()),
+            timer_proto.clone(),
+            Some(simul_id.clone()),
+            config.opponent.clone(),
+        )
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to connect to server: {}", e))?;
+        board.skill_level = config.skill_level;
+        board.mode = config.mode.clone();
+        board.confirm_moves = config.confirm_moves;
+
+        // Event stream first so engine moves triggered by set_engine are not missed
+        if let Err(e) = board.start_event_stream().await {
+            board
+                .notifications
+                .error(format!("Failed to start event stream: {}", e));
+        }
+        if let Err(e) = board
+            .set_engine_full(
+                true,
+                config.skill_level,
+                config.engine_threads,
+                config.engine_hash_mb,
+            )
+            .await
+        {
+            board
+                .notifications
+                .error(format!("Failed to enable engine: {}", e));
+        }
+        boards.push(board);
+    }
+    tracing::info!(simul_id = %simul_id, boards = boards.len(), "Simul started");
+
+    let mut fsm = UiStateMachine::default();
+    fsm.notation = config.notation;
+    fsm.live_evals = config.live_evals;
+    if config.screen_reader {
+        fsm.enable_screen_reader();
+    }
     fsm.transition_to(UiMode::GameBoard);
+    run_ui_loop(terminal, &mut boards, fsm, external).await
+}
 
-    run_ui_loop(terminal, &mut state, fsm).await
+/// Close the server session of every board.
+async fn close_boards(boards: &mut [GameSession], context: &str) {
+    for board in boards.iter_mut() {
+        // Review mode has no server session to close
+        if board.review_state.is_none() {
+            if let Err(e) = board.client.close_session().await {
+                tracing::warn!("Failed to close session on {}: {}", context, e);
+            }
+        }
+    }
 }
 
+/// Run the game UI over one board, or over several for a simul. Only the
+/// active board is drawn and receives input, typed or external; every board's
+/// events are drained.
 async fn run_ui_loop<B: ratatui::backend::Backend>(
     terminal: &mut Terminal<B>,
-    state: &mut GameSession,
+    boards: &mut [GameSession],
     mut fsm: crate::ui::fsm::UiStateMachine,
+    external: &mut ExternalInput,
 ) -> anyhow::Result<ExitReason> {
     use super::input::{self, AppAction};
     use crossterm::event::EventStream;
     use futures::StreamExt;
 
+    let board_count = boards.len();
+    let is_simul = board_count > 1;
+    let mut active = 0;
+    let mut simul_complete = false;
+    // Board the narration last described
+    let mut narrated = None;
+
     let mut input_buffer = String::new();
     let mut term_events = EventStream::new();
 
@@ -383,8 +905,15 @@ async fn run_ui_loop<B: ratatui::backend::Backend>(
     // Auto-play tracking for review mode
     let mut last_auto_advance = std::time::Instant::now();
 
+    // Requests sent from the menu were meant for a game that no longer exists
+    external.reject_queued("No game was open");
+
     loop {
-        // Wait for whichever comes first: keyboard, server event, or UI tick.
+        let state = &mut boards[active];
+        let mut external_request = None;
+
+        // Wait for whichever comes first: keyboard, external input, server
+        // event, or UI tick.
         let term_event = tokio::select! {
             biased;
 
@@ -400,7 +929,13 @@ async fn run_ui_loop<B: ratatui::backend::Backend>(
                 }
             }
 
-            // Server event from gRPC stream
+            // Move or command from a voice or macro tool
+            Some(pending) = external.recv() => {
+                external_request = Some(pending);
+                None
+            }
+
+            // Server event from gRPC stream, or a finished server call
             consumed = async {
                 state.poll_event_async().await
             } => {
@@ -415,16 +950,13 @@ async fn run_ui_loop<B: ratatui::backend::Backend>(
                 None
             }
         };
+        let woke_at = std::time::Instant::now();
 
-        // Auto-play: advance review ply every 750ms when active
+        // Auto-play: advance the review ply at the chosen speed when active
         if let Some(ref mut review) = state.review_state {
-            if review.auto_play && last_auto_advance.elapsed() >= Duration::from_millis(750) {
-                if review.current_ply >= review.review.total_plies {
-                    review.auto_play = false;
-                } else {
-                    review.next_ply();
-                    last_auto_advance = std::time::Instant::now();
-                }
+            if review.auto_play && last_auto_advance.elapsed() >= review.auto_play_interval() {
+                review.auto_advance();
+                last_auto_advance = std::time::Instant::now();
             }
         }
 
@@ -432,10 +964,42 @@ async fn run_ui_loop<B: ratatui::backend::Backend>(
         // The server ticks the timer and sends updated snapshots.
 
         // Drain any additional buffered server events (non-blocking)
-        while let Ok(true) = state.poll_events().await {
-            continue;
+        for board in boards.iter_mut() {
+            while let Ok(true) = board.poll_events().await {
+                continue;
+            }
         }
 
+        // Another client took the game over; its session stays open
+        if !is_simul && boards[active].transferred {
+            tracing::info!("Session transferred to another client");
+            return Ok(ExitReason::ReturnToMenu);
+        }
+
+        if is_simul {
+            // Once the shown board no longer needs the player, move on to one that does
+            let waiting: Vec<bool> = boards.iter().map(|b| b.awaiting_human_move()).collect();
+            let idle = input_buffer.is_empty()
+                && boards[active].selected_square.is_none()
+                && fsm.popup_menu.is_none();
+            if !waiting[active] && idle {
+                if let Some(next) = simul::next_waiting_board(&waiting, active) {
+                    boards[active].simul = None;
+                    active = next;
+                }
+            }
+
+            let status = SimulStatus::from_boards(boards, active);
+            if status.all_finished() && !simul_complete {
+                simul_complete = true;
+                boards[active]
+                    .notifications
+                    .success("Simul complete \u{2014} return to the menu to review the boards");
+            }
+            boards[active].simul = Some(status);
+        }
+        let state = &mut boards[active];
+
         // Calculate typeahead squares based on current input and store on FSM
         fsm.typeahead_squares = if fsm.tab_input.active
             && fsm.tab_input.current_tab == 0
@@ -449,35 +1013,82 @@ async fn run_ui_loop<B: ratatui::backend::Backend>(
         };
 
         // Snapshot pane state for rendering (avoids borrow conflicts)
-        let _is_review_mode = matches!(state.mode, GameMode::ReviewMode);
+        let _is_review_mode = matches!(state.mode, GameMode::Review);
+
+        state.notifications.expire(std::time::Instant::now());
+
+        if fsm.screen_reader {
+            if is_simul && narrated != Some(active) {
+                fsm.narration
+                    .switch_board(format!("Board {} of {}.", active + 1, board_count));
+            }
+            narrated = Some(active);
+            fsm.narration.observe(state);
+        }
 
         // Draw UI using FSM-based renderer
-        let _ = terminal.draw(|f| {
-            use crate::ui::fsm::renderer::Renderer;
-            // Get layout from FSM
-            let layout = fsm.layout(state);
-            Renderer::render(f, f.area(), &layout, state, &fsm);
-        });
-
-        // Handle keyboard event if one arrived
-        if let Some(Event::Key(key)) = term_event {
-            match input::handle_key(state, &mut fsm, &mut input_buffer, key).await {
+        let draw_started = std::time::Instant::now();
+        {
+            let _span = tracing::debug_span!("draw", simul_board = active).entered();
+            let drawn = terminal.draw(|f| {
+                use crate::ui::fsm::renderer::Renderer;
+                // Get layout from FSM
+                let layout = fsm.layout(state);
+                Renderer::render(f, f.area(), &layout, state, &fsm);
+            });
+            if let Ok(frame) = drawn {
+                fsm.frame_profile.area = (frame.area.width, frame.area.height);
+            }
+            if let Err(e) = fsm
+                .board_cache
+                .borrow_mut()
+                .flush_graphics(&mut io::stdout())
+            {
+                tracing::warn!("Failed to send board image: {}", e);
+            }
+        }
+        let draw_time = draw_started.elapsed();
+        fsm.frame_profile.draw.record(draw_time);
+        fsm.frame_profile.loop_latency.record(woke_at.elapsed());
+        if draw_time > FRAME_BUDGET {
+            tracing::debug!(
+                draw_ms = draw_time.as_millis() as u64,
+                engine_thinking = state.is_engine_thinking,
+                "Frame over budget"
+            );
+        }
+
+        if let Some(Event::Mouse(mouse)) = term_event {
+            input::handle_mouse(state, &mut fsm, mouse);
+        }
+
+        // Handle keyboard event or external request if one arrived
+        let action = match term_event {
+            Some(Event::Key(key)) => {
+                Some(input::handle_key(state, &mut fsm, &mut input_buffer, key))
+            }
+            _ => external_request.map(|pending| {
+                let (action, reply) =
+                    input::handle_external(state, &mut fsm, &mut input_buffer, &pending.request);
+                pending.respond(reply);
+                action
+            }),
+        };
+        if let Some(action) = action {
+            match action {
                 AppAction::Continue => {}
                 AppAction::Quit => {
-                    // Review mode has no server session to close
-                    if state.review_state.is_none() {
-                        if let Err(e) = state.client.close_session().await {
-                            tracing::warn!("Failed to close session on qrender_statet: {}", e);
-                        }
-                    }
+                    close_boards(boards, "quit").await;
                     return Ok(ExitReason::Quit);
                 }
                 AppAction::ReturnToMenu => {
-                    if state.review_state.is_none() {
-                        if let Err(e) = state.client.close_session().await {
-                            tracing::warn!("Failed to close session on return to menu: {}", e);
-                        }
-                    }
+                    close_boards(boards, "return to menu").await;
+                    return Ok(ExitReason::ReturnToMenu);
+                }
+                AppAction::SuspendAndReturnToMenu if is_simul => {
+                    // Suspended sessions resume as single games, so a simul
+                    // is closed instead; finished boards are still saved.
+                    close_boards(boards, "leaving simul").await;
                     return Ok(ExitReason::ReturnToMenu);
                 }
                 AppAction::SuspendAndReturnToMenu => {
@@ -490,12 +1101,25 @@ async fn run_ui_loop<B: ratatui::backend::Backend>(
                 AppAction::PlaySnapshot(config) => {
                     return Ok(ExitReason::PlaySnapshot(config));
                 }
+                AppAction::BranchAnalysis { analysis, review } => {
+                    return Ok(ExitReason::BranchAnalysis { analysis, review });
+                }
+                AppAction::NextBoard => {
+                    let waiting: Vec<bool> =
+                        boards.iter().map(|b| b.awaiting_human_move()).collect();
+                    let next = simul::next_waiting_board(&waiting, active)
+                        .filter(|&i| i != active)
+                        .unwrap_or((active + 1) % boards.len());
+                    boards[active].clear_selection();
+                    boards[active].simul = None;
+                    active = next;
+                }
             }
         }
     }
 }
 
-pub(super) async fn handle_input(
+pub(super) fn handle_input(
     state: &mut GameSession,
     fsm: &mut crate::ui::fsm::UiStateMachine,
     input: &str,
@@ -506,15 +1130,34 @@ pub(super) async fn handle_input(
     match input.as_str() {
         "undo" | "u" => {
             if !state.is_undo_allowed() {
-                state.status_message = Some(
+                state.notifications.warn(
                     "Undo is only available in Human vs Engine mode with Beginner difficulty"
                         .to_string(),
                 );
                 return;
             }
-            if let Err(e) = state.undo().await {
-                state.status_message = Some(format!("Undo error: {}", e));
+            state.undo();
+            return;
+        }
+        "redo" | "r" => {
+            if !state.is_undo_allowed() {
+                state.notifications.warn(
+                    "Redo is only available in Human vs Engine mode with Beginner difficulty"
+                        .to_string(),
+                );
+                return;
+            }
+            state.redo();
+            return;
+        }
+        "transfer" => {
+            if matches!(state.mode, GameMode::Review) || state.simul.is_some() {
+                state
+                    .notifications
+                    .warn("Only a single live game can be transferred");
+                return;
             }
+            state.dispatch(Action::CreateTransfer);
             return;
         }
         _ => {}
@@ -528,23 +1171,19 @@ pub(super) async fn handle_input(
         match fsm.input_phase {
             InputPhase::SelectPiece => {
                 if let Some(square) = parse_square(&input) {
-                    if state.selectable_squares.contains(&square) {
-                        state.select_square(square);
-                    } else {
-                        state.status_message =
-                            Some("No piece on that square or not your turn".to_string());
-                    }
+                    // Checks the square itself, premoves included
+                    state.select_square(square);
                 } else {
-                    state.status_message = Some("Invalid square".to_string());
+                    state.notifications.warn("Invalid square");
                 }
             }
             InputPhase::SelectDestination => {
                 if let Some(square) = parse_square(&input) {
-                    if let Err(e) = state.try_move_to(square).await {
-                        state.status_message = Some(format!("Move error: {}", e));
+                    if let Err(e) = state.try_move_to(square) {
+                        state.notifications.error(format!("Move error: {}", e));
                     }
                 } else {
-                    state.status_message = Some("Invalid square".to_string());
+                    state.notifications.warn("Invalid square");
                 }
             }
             InputPhase::SelectPromotion { from, to } => {
@@ -554,7 +1193,7 @@ pub(super) async fn handle_input(
                     "b" | "bishop" => Piece::Bishop,
                     "n" | "knight" => Piece::Knight,
                     _ => {
-                        state.status_message = Some(
+                        state.notifications.warn(
                             "Invalid promotion piece. Use q/r/b/n for queen/rook/bishop/knight"
                                 .to_string(),
                         );
@@ -562,13 +1201,12 @@ pub(super) async fn handle_input(
                     }
                 };
 
-                if let Err(e) = state.execute_promotion(from, to, piece).await {
-                    state.status_message = Some(format!("Promotion error: {}", e));
-                }
+                state.execute_promotion(from, to, piece);
             }
         }
     } else {
-        state.status_message =
-            Some("Enter a square (e.g., 'e2'). Use 'undo' for special commands".to_string());
+        state
+            .notifications
+            .warn("Enter a square (e.g., 'e2'). Use 'undo' or 'redo' for special commands");
     }
 }
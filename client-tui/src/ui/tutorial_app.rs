@@ -0,0 +1,531 @@
+use crate::tutorial::{
+    builtin_lessons, current_profile, default_progress_path, legal_targets, Lesson, StepOutcome,
+    TutorialProgress,
+};
+use crate::ui::widgets::board_overlay::{BoardOverlay, OverlayColor};
+use crate::ui::widgets::BoardWidget;
+use cozy_chess::{Board, Move, Piece, Rank, Square};
+use crossterm::{
+    event::{self, Event, KeyCode},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame, Terminal,
+};
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Run the tutorial until the learner goes back to the main menu.
+pub async fn run_tutorial() -> anyhow::Result<()> {
+    let lessons = builtin_lessons()?;
+    let progress_path = default_progress_path();
+    let progress = match progress_path.as_deref().map(TutorialProgress::load) {
+        Some(Ok(progress)) => progress,
+        Some(Err(e)) => {
+            tracing::warn!("Failed to load tutorial progress: {}", e);
+            TutorialProgress::default()
+        }
+        None => TutorialProgress::default(),
+    };
+    let mut app = TutorialApp::new(lessons, progress, progress_path, current_profile());
+
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+
+    let result = loop {
+        if let Err(e) = terminal.draw(|f| app.render(f)) {
+            break Err(e.into());
+        }
+        match event::poll(Duration::from_millis(100)) {
+            Ok(true) => {
+                if let Ok(Event::Key(key)) = event::read() {
+                    if app.handle_key(key.code) {
+                        break Ok(());
+                    }
+                }
+            }
+            Ok(false) => {}
+            Err(e) => break Err(e.into()),
+        }
+    };
+
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+
+    result
+}
+
+/// A lesson being played through.
+struct ActiveLesson {
+    lesson_idx: usize,
+    step_idx: usize,
+    board: Board,
+    /// Piece picked for the move; its destination is typed next.
+    from: Option<Square>,
+    /// Square being typed, e.g. `e` then `e2`.
+    input: String,
+    feedback: Option<(String, Color)>,
+    /// The solving move of the current step, shown until the learner continues.
+    solved: Option<Move>,
+}
+
+impl ActiveLesson {
+    /// Take a typed square as the piece to move or, once one is picked, as
+    /// its destination.
+    fn choose(&mut self, step: &crate::tutorial::LessonStep, square: Square) {
+        let Some(from) = self.from else {
+            if step.selectable_squares(&self.board).contains(&square) {
+                self.from = Some(square);
+                self.feedback = None;
+            } else {
+                self.feedback = Some((
+                    "Pick one of the highlighted pieces.".to_string(),
+                    Color::Yellow,
+                ));
+            }
+            return;
+        };
+        if !legal_targets(&self.board, from).contains(&square) {
+            self.feedback = Some(("That piece can't move there.".to_string(), Color::Red));
+            return;
+        }
+
+        self.from = None;
+        let mut uci = format!("{}{}", from, square);
+        // Promotions are always to a queen
+        if self.board.piece_on(from) == Some(Piece::Pawn)
+            && matches!(square.rank(), Rank::First | Rank::Eighth)
+        {
+            uci.push('q');
+        }
+        self.feedback = Some(match step.check(&uci) {
+            StepOutcome::Solved { board, mv } => {
+                self.board = board;
+                self.solved = Some(mv);
+                (
+                    "Correct! Press Enter to continue.".to_string(),
+                    Color::Green,
+                )
+            }
+            StepOutcome::Incorrect => (
+                "Legal, but not what this exercise asks. Try again.".to_string(),
+                Color::Yellow,
+            ),
+            // Selection only offers allowed, legal moves
+            StepOutcome::Unreadable | StepOutcome::Illegal | StepOutcome::NotAllowed => {
+                ("That piece can't move there.".to_string(), Color::Red)
+            }
+        });
+    }
+}
+
+struct TutorialApp {
+    lessons: Vec<Lesson>,
+    progress: TutorialProgress,
+    progress_path: Option<PathBuf>,
+    profile: String,
+    selected: usize,
+    active: Option<ActiveLesson>,
+    status: Option<String>,
+}
+
+impl TutorialApp {
+    fn new(
+        lessons: Vec<Lesson>,
+        progress: TutorialProgress,
+        progress_path: Option<PathBuf>,
+        profile: String,
+    ) -> Self {
+        Self {
+            lessons,
+            progress,
+            progress_path,
+            profile,
+            selected: 0,
+            active: None,
+            status: None,
+        }
+    }
+
+    /// Handle a key press. Returns true when the tutorial should exit.
+    fn handle_key(&mut self, code: KeyCode) -> bool {
+        if self.active.is_some() {
+            self.handle_lesson_key(code);
+            return false;
+        }
+
+        match code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.selected = self.selected.saturating_sub(1);
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                if self.selected + 1 < self.lessons.len() {
+                    self.selected += 1;
+                }
+            }
+            KeyCode::Enter => self.start_lesson(self.selected),
+            KeyCode::Esc | KeyCode::Char('q') => return true,
+            _ => {}
+        }
+        false
+    }
+
+    /// Moves are entered by picking one of the step's pieces, then one of
+    /// its legal destinations, a square at a time. Anything else is ignored.
+    fn handle_lesson_key(&mut self, code: KeyCode) {
+        let Some(active) = self.active.as_mut() else {
+            return;
+        };
+
+        match code {
+            KeyCode::Esc if active.from.is_some() => {
+                active.from = None;
+                active.input.clear();
+            }
+            KeyCode::Esc => self.active = None,
+            KeyCode::Enter if active.solved.is_some() => self.next_step(),
+            KeyCode::Backspace => {
+                if active.input.pop().is_none() {
+                    active.from = None;
+                }
+            }
+            KeyCode::Char(c) if active.solved.is_none() => {
+                let c = c.to_ascii_lowercase();
+                let valid = match active.input.len() {
+                    0 => ('a'..='h').contains(&c),
+                    1 => ('1'..='8').contains(&c),
+                    _ => false,
+                };
+                if !valid {
+                    return;
+                }
+                active.input.push(c);
+                if let Ok(square) = active.input.parse::<Square>() {
+                    active.input.clear();
+                    let step = &self.lessons[active.lesson_idx].steps[active.step_idx];
+                    active.choose(step, square);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn start_lesson(&mut self, lesson_idx: usize) {
+        let Some(lesson) = self.lessons.get(lesson_idx) else {
+            return;
+        };
+        self.status = None;
+        self.active = lesson.steps[0].board().ok().map(|board| ActiveLesson {
+            lesson_idx,
+            step_idx: 0,
+            board,
+            from: None,
+            input: String::new(),
+            feedback: None,
+            solved: None,
+        });
+    }
+
+    fn next_step(&mut self) {
+        let Some(active) = self.active.as_mut() else {
+            return;
+        };
+        let lesson = &self.lessons[active.lesson_idx];
+
+        if active.step_idx + 1 < lesson.steps.len() {
+            active.step_idx += 1;
+            if let Ok(board) = lesson.steps[active.step_idx].board() {
+                active.board = board;
+            }
+            active.from = None;
+            active.input.clear();
+            active.feedback = None;
+            active.solved = None;
+            return;
+        }
+
+        // Lesson finished
+        let lesson_id = lesson.id.clone();
+        let title = lesson.title.clone();
+        let lesson_idx = active.lesson_idx;
+        self.active = None;
+        self.progress.mark_completed(&self.profile, &lesson_id);
+        if let Some(ref path) = self.progress_path {
+            if let Err(e) = self.progress.save(path) {
+                tracing::warn!("Failed to save tutorial progress: {}", e);
+            }
+        }
+        self.status = Some(format!("Lesson complete: {}", title));
+        if lesson_idx + 1 < self.lessons.len() {
+            self.selected = lesson_idx + 1;
+        }
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        match self.active {
+            Some(ref active) => self.render_lesson(frame, active),
+            None => self.render_lesson_list(frame),
+        }
+    }
+
+    fn render_lesson_list(&self, frame: &mut Frame) {
+        let area = centered(frame.area(), 60, self.lessons.len() as u16 + 10);
+        let block = Block::default()
+            .title("♔ ChessTTY - Tutorial ♔")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan));
+
+        let mut lines = vec![
+            Line::raw(""),
+            Line::from(Span::styled(
+                format!(
+                    "Profile: {}  ({}/{} lessons complete)",
+                    self.profile,
+                    self.progress.completed_count(&self.profile),
+                    self.lessons.len()
+                ),
+                Style::default().fg(Color::Yellow),
+            )),
+            Line::raw(""),
+        ];
+
+        for (idx, lesson) in self.lessons.iter().enumerate() {
+            let is_selected = idx == self.selected;
+            let prefix = if is_selected { "► " } else { "  " };
+            let done = if self.progress.is_completed(&self.profile, &lesson.id) {
+                "✓ "
+            } else {
+                "  "
+            };
+            let style = if is_selected {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            lines.push(Line::from(vec![
+                Span::styled(prefix, style),
+                Span::styled(done, Style::default().fg(Color::Green)),
+                Span::styled(lesson.title.clone(), style),
+            ]));
+        }
+
+        lines.push(Line::raw(""));
+        if let Some(ref status) = self.status {
+            lines.push(Line::from(Span::styled(
+                status.clone(),
+                Style::default().fg(Color::Green),
+            )));
+        }
+        lines.push(Line::from(Span::styled(
+            "↑/↓: Navigate  Enter: Start lesson  Esc: Back",
+            Style::default().fg(Color::DarkGray),
+        )));
+
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+
+    fn render_lesson(&self, frame: &mut Frame, active: &ActiveLesson) {
+        let lesson = &self.lessons[active.lesson_idx];
+        let step = &lesson.steps[active.step_idx];
+
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .split(frame.area());
+
+        let mut overlay = BoardOverlay::new();
+        match active.solved {
+            Some(mv) => {
+                overlay.tint(mv.from, OverlayColor::LastMove);
+                overlay.tint(mv.to, OverlayColor::LastMove);
+            }
+            None => match active.from {
+                Some(from) => {
+                    overlay.tint(from, OverlayColor::Selected);
+                    for sq in legal_targets(&active.board, from) {
+                        overlay.tint(sq, OverlayColor::LegalMove);
+                    }
+                }
+                None => {
+                    for sq in step.selectable_squares(&active.board) {
+                        overlay.outline(sq, OverlayColor::Typeahead);
+                    }
+                }
+            },
+        }
+        frame.render_widget(
+            BoardWidget {
+                board: &active.board,
+                overlay: &overlay,
+                flipped: false,
+            },
+            chunks[0],
+        );
+
+        let label = Style::default()
+            .fg(Color::Cyan)
+            .add_modifier(Modifier::BOLD);
+        let mut lines = vec![
+            Line::from(Span::styled(
+                lesson.title.clone(),
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )),
+            Line::from(Span::styled(
+                lesson.summary.clone(),
+                Style::default().fg(Color::Gray),
+            )),
+            Line::raw(""),
+            Line::from(vec![
+                Span::styled("Step: ", label),
+                Span::raw(format!("{}/{}", active.step_idx + 1, lesson.steps.len())),
+            ]),
+            Line::raw(""),
+            Line::raw(step.prompt.clone()),
+            Line::raw(""),
+            match active.from {
+                Some(from) => Line::from(vec![
+                    Span::styled("Move: ", label),
+                    Span::raw(format!("{} to {}_", from, active.input)),
+                ]),
+                None => Line::from(vec![
+                    Span::styled("Piece: ", label),
+                    Span::raw(format!("{}_", active.input)),
+                ]),
+            },
+        ];
+        if let Some((ref text, color)) = active.feedback {
+            lines.push(Line::raw(""));
+            lines.push(Line::from(Span::styled(
+                text.clone(),
+                Style::default().fg(color).add_modifier(Modifier::BOLD),
+            )));
+        }
+        lines.push(Line::raw(""));
+        lines.push(Line::from(Span::styled(
+            "Type a highlighted piece's square, then where it goes  Backspace: Back  Esc: Lessons",
+            Style::default().fg(Color::DarkGray),
+        )));
+
+        let block = Block::default()
+            .title("Lesson")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan));
+        frame.render_widget(
+            Paragraph::new(lines)
+                .block(block)
+                .wrap(Wrap { trim: false }),
+            chunks[1],
+        );
+    }
+}
+
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    Rect {
+        x: area.x + area.width.saturating_sub(width) / 2,
+        y: area.y + area.height.saturating_sub(height) / 2,
+        width: width.min(area.width),
+        height: height.min(area.height),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app() -> TutorialApp {
+        TutorialApp::new(
+            builtin_lessons().unwrap(),
+            TutorialProgress::default(),
+            None,
+            "test".to_string(),
+        )
+    }
+
+    fn type_move(app: &mut TutorialApp, mv: &str) {
+        for c in mv.chars() {
+            app.handle_key(KeyCode::Char(c));
+        }
+    }
+
+    #[test]
+    fn completing_a_lesson_records_progress() {
+        let mut app = app();
+        let first_id = app.lessons[0].id.clone();
+        app.handle_key(KeyCode::Enter);
+        assert!(app.active.is_some());
+
+        // Play every step with its first expected move
+        let steps = app.lessons[0].steps.clone();
+        for step in &steps {
+            let crate::tutorial::StepGoal::Moves { ref moves } = step.goal else {
+                panic!("first lesson should only use move goals");
+            };
+            type_move(&mut app, &moves[0]);
+            assert!(app.active.as_ref().unwrap().solved.is_some());
+            app.handle_key(KeyCode::Enter);
+        }
+
+        assert!(app.active.is_none());
+        assert!(app.progress.is_completed("test", &first_id));
+        assert_eq!(app.selected, 1);
+    }
+
+    #[test]
+    fn wrong_move_keeps_the_step() {
+        let mut app = app();
+        app.handle_key(KeyCode::Enter);
+        type_move(&mut app, "a1a5");
+
+        let active = app.active.as_ref().unwrap();
+        assert_eq!(active.step_idx, 0);
+        assert!(active.solved.is_none());
+        assert!(active.feedback.is_some());
+        assert!(active.input.is_empty());
+    }
+
+    #[test]
+    fn only_allowed_pieces_and_their_moves_can_be_entered() {
+        let mut app = app();
+        app.handle_key(KeyCode::Enter);
+
+        // Not a square character: ignored
+        type_move(&mut app, "x9");
+        assert!(app.active.as_ref().unwrap().input.is_empty());
+
+        // The rook step only lets the a1 rook move
+        type_move(&mut app, "a1");
+        assert_eq!(app.active.as_ref().unwrap().from, Some(Square::A1));
+        type_move(&mut app, "b2");
+        let active = app.active.as_ref().unwrap();
+        assert_eq!(active.from, Some(Square::A1));
+        assert!(active.solved.is_none());
+
+        // Backspace on an empty square puts the piece back
+        app.handle_key(KeyCode::Backspace);
+        assert_eq!(app.active.as_ref().unwrap().from, None);
+    }
+
+    #[test]
+    fn escape_returns_to_lessons_then_menu() {
+        let mut app = app();
+        app.handle_key(KeyCode::Enter);
+        assert!(!app.handle_key(KeyCode::Esc));
+        assert!(app.active.is_none());
+        assert!(app.handle_key(KeyCode::Esc));
+    }
+}
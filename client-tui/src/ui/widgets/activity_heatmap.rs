@@ -0,0 +1,179 @@
+use chess_client::{ActivityDayProto, GetActivitySummaryResponse};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+use std::collections::HashMap;
+
+/// Height of the heat-map including its border.
+pub const HEATMAP_HEIGHT: u16 = 11;
+
+/// Most weeks shown, about a year.
+const MAX_WEEKS: usize = 53;
+
+/// Cell colours from no activity to the busiest days.
+const SHADES: [Color; 5] = [
+    Color::DarkGray,
+    Color::Rgb(14, 68, 41),
+    Color::Rgb(0, 109, 50),
+    Color::Rgb(38, 166, 65),
+    Color::Rgb(57, 211, 83),
+];
+
+/// Weekday of a day number, Monday = 0. Day 0 (1970-01-01) was a Thursday.
+pub fn weekday(day: u32) -> usize {
+    ((day + 3) % 7) as usize
+}
+
+/// Shade bucket for a day's activity total, 0 (none) to 4 (busiest).
+pub fn intensity(total: u32) -> usize {
+    match total {
+        0 => 0,
+        1 => 1,
+        2..=3 => 2,
+        4..=5 => 3,
+        _ => 4,
+    }
+}
+
+/// Activity totals as week columns of Monday-first days, oldest week first.
+/// The last column is the current week; days after `today` are `None`.
+pub fn heatmap_grid(days: &[ActivityDayProto], today: u32, weeks: usize) -> Vec<[Option<u32>; 7]> {
+    let totals: HashMap<u32, u32> = days
+        .iter()
+        .map(|d| (d.day, d.games_played + d.puzzles_solved + d.reviews_read))
+        .collect();
+    let current_monday = today - weekday(today) as u32;
+
+    (0..weeks)
+        .map(|w| {
+            let weeks_back = (weeks - 1 - w) as u32;
+            let mut column = [None; 7];
+            for (offset, cell) in column.iter_mut().enumerate() {
+                let Some(day) = (current_monday + offset as u32).checked_sub(weeks_back * 7) else {
+                    continue;
+                };
+                if day <= today {
+                    *cell = Some(totals.get(&day).copied().unwrap_or(0));
+                }
+            }
+            column
+        })
+        .collect()
+}
+
+/// GitHub-style calendar of daily activity (games, puzzles, reviews read).
+pub struct ActivityHeatmap<'a> {
+    pub summary: &'a GetActivitySummaryResponse,
+}
+
+impl Widget for ActivityHeatmap<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let block = Block::default()
+            .title(" Activity ")
+            .borders(Borders::ALL)
+            .border_style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .style(Style::default().bg(Color::Black));
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        // Weekday labels take 4 columns; each day is 2 wide
+        let weeks = ((inner.width.saturating_sub(4)) / 2).min(MAX_WEEKS as u16) as usize;
+        if weeks == 0 || inner.height < 9 {
+            return;
+        }
+        let grid = heatmap_grid(&self.summary.days, self.summary.today, weeks);
+
+        let summary = self.summary;
+        let mut lines = vec![Line::from(vec![
+            Span::styled(
+                format!("{} active days", summary.days.len()),
+                Style::default().fg(Color::White),
+            ),
+            Span::styled(
+                format!(
+                    "  \u{00b7}  streak {} (best {})",
+                    summary.current_streak, summary.longest_streak
+                ),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ])];
+
+        const LABELS: [&str; 7] = ["Mon ", "    ", "Wed ", "    ", "Fri ", "    ", "Sun "];
+        for (row, label) in LABELS.iter().enumerate() {
+            let mut spans = vec![Span::styled(*label, Style::default().fg(Color::DarkGray))];
+            for column in &grid {
+                spans.push(match column[row] {
+                    Some(total) => {
+                        Span::styled("\u{25a0} ", Style::default().fg(SHADES[intensity(total)]))
+                    }
+                    None => Span::raw("  "),
+                });
+            }
+            lines.push(Line::from(spans));
+        }
+
+        let mut legend = vec![Span::styled("Less ", Style::default().fg(Color::DarkGray))];
+        legend.extend(
+            SHADES
+                .iter()
+                .map(|&shade| Span::styled("\u{25a0} ", Style::default().fg(shade))),
+        );
+        legend.push(Span::styled("More", Style::default().fg(Color::DarkGray)));
+        lines.push(Line::from(legend));
+
+        Paragraph::new(lines).render(inner, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(day: u32, games_played: u32, reviews_read: u32) -> ActivityDayProto {
+        ActivityDayProto {
+            day,
+            games_played,
+            puzzles_solved: 0,
+            reviews_read,
+        }
+    }
+
+    #[test]
+    fn test_weekday_epoch_is_thursday() {
+        assert_eq!(weekday(0), 3);
+        // 1970-01-05 was a Monday
+        assert_eq!(weekday(4), 0);
+    }
+
+    #[test]
+    fn test_grid_ends_at_today() {
+        // Day 20 is a Wednesday; its week starts on Monday, day 18
+        let grid = heatmap_grid(&[day(18, 1, 2), day(12, 1, 0)], 20, 2);
+        assert_eq!(grid.len(), 2);
+        assert_eq!(grid[1][0], Some(3));
+        assert_eq!(grid[1][2], Some(0));
+        assert_eq!(grid[1][3], None);
+        // Previous week: Monday 11 .. Sunday 17
+        assert_eq!(grid[0][1], Some(1));
+        assert_eq!(grid[0][6], Some(0));
+    }
+
+    #[test]
+    fn test_intensity_buckets() {
+        assert_eq!(intensity(0), 0);
+        assert_eq!(intensity(1), 1);
+        assert_eq!(intensity(3), 2);
+        assert_eq!(intensity(5), 3);
+        assert_eq!(intensity(40), 4);
+    }
+}
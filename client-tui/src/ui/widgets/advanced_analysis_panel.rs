@@ -1,6 +1,9 @@
-use crate::review_state::ReviewState;
+use crate::review_state::{PhaseMobility, ReviewState};
+use crate::ui::widgets::bookmark_dialog::ply_label;
+use crate::ui::widgets::format_clock;
 use chess_client::{
-    PositionKingSafetyProto, PositionTensionMetricsProto, TacticalTagKindProto, TacticalTagProto,
+    AdvancedGameAnalysisProto, PositionKingSafetyProto, PositionReview,
+    PositionTensionMetricsProto, TacticalTagKindProto, TacticalTagProto,
 };
 use ratatui::{
     buffer::Buffer,
@@ -11,8 +14,82 @@ use ratatui::{
     widgets::{Block, Borders, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState, Widget},
 };
 
+/// Tabs of the advanced analysis panel, switched with 1-7 while it is focused.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum AdvancedTab {
+    #[default]
+    Tactics,
+    KingSafety,
+    Tension,
+    Psychology,
+    Time,
+    Mobility,
+    Bookmarks,
+}
+
+impl AdvancedTab {
+    pub const ALL: [AdvancedTab; 7] = [
+        AdvancedTab::Tactics,
+        AdvancedTab::KingSafety,
+        AdvancedTab::Tension,
+        AdvancedTab::Psychology,
+        AdvancedTab::Time,
+        AdvancedTab::Mobility,
+        AdvancedTab::Bookmarks,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            AdvancedTab::Tactics => "Tactics",
+            AdvancedTab::KingSafety => "King Safety",
+            AdvancedTab::Tension => "Tension",
+            AdvancedTab::Psychology => "Psychology",
+            AdvancedTab::Time => "Time",
+            AdvancedTab::Mobility => "Mobility",
+            AdvancedTab::Bookmarks => "Bookmarks",
+        }
+    }
+
+    /// The tab selected by number key `key` ('1' = Tactics ... '7' = Bookmarks).
+    pub fn from_number_key(key: char) -> Option<Self> {
+        let index = key.to_digit(10)?.checked_sub(1)?;
+        Self::ALL.get(index as usize).copied()
+    }
+}
+
+/// One-line tab strip shown above the advanced analysis panel.
+pub struct AdvancedTabsHeader {
+    pub current: AdvancedTab,
+    pub is_selected: bool,
+}
+
+impl Widget for AdvancedTabsHeader {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let active_bg = if self.is_selected {
+            Color::Yellow
+        } else {
+            Color::Magenta
+        };
+
+        let mut spans = Vec::new();
+        for (i, tab) in AdvancedTab::ALL.iter().enumerate() {
+            let style = if *tab == self.current {
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(active_bg)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::DarkGray)
+            };
+            spans.push(Span::styled(format!(" {} {} ", i + 1, tab.title()), style));
+        }
+        Paragraph::new(Line::from(spans)).render(area, buf);
+    }
+}
+
 pub struct AdvancedAnalysisPanel<'a> {
     pub review_state: &'a ReviewState,
+    pub tab: AdvancedTab,
     pub scroll: u16,
     pub is_selected: bool,
     pub expanded: bool,
@@ -21,11 +98,11 @@ pub struct AdvancedAnalysisPanel<'a> {
 impl Widget for AdvancedAnalysisPanel<'_> {
     fn render(self, area: Rect, buf: &mut Buffer) {
         let title = if self.expanded {
-            "Advanced Analysis (Expanded)"
+            format!("Advanced Analysis: {} (Expanded)", self.tab.title())
         } else if self.is_selected {
-            "Advanced Analysis [SELECTED]"
+            format!("Advanced Analysis: {} [SELECTED]", self.tab.title())
         } else {
-            "[3] Advanced Analysis"
+            format!("[3] Advanced Analysis: {}", self.tab.title())
         };
 
         let border_style = if self.is_selected || self.expanded {
@@ -44,206 +121,394 @@ impl Widget for AdvancedAnalysisPanel<'_> {
         let inner = block.inner(area);
         block.render(area, buf);
 
-        let mut lines: Vec<Line<'static>> = vec![];
-
-        let advanced = match &self.review_state.advanced {
-            Some(a) => a,
-            None => {
-                lines.push(Line::from(Span::styled(
-                    "No advanced analysis available",
-                    Style::default().fg(Color::DarkGray),
-                )));
-                let paragraph = Paragraph::new(lines).scroll((self.scroll, 0));
-                paragraph.render(inner, buf);
-                return;
-            }
+        let lines = match &self.review_state.advanced {
+            // Bookmarks are the player's own and need no analysis
+            _ if self.tab == AdvancedTab::Bookmarks => self.bookmark_lines(),
+            Some(advanced) => match self.tab {
+                AdvancedTab::Tactics => self.tactics_lines(advanced),
+                AdvancedTab::KingSafety => self.king_safety_lines(),
+                AdvancedTab::Tension => self.tension_lines(),
+                AdvancedTab::Psychology => psychology_lines(advanced),
+                AdvancedTab::Time => self.time_lines(advanced),
+                AdvancedTab::Mobility => self.mobility_lines(),
+                AdvancedTab::Bookmarks => self.bookmark_lines(),
+            },
+            None => vec![dim_line("No advanced analysis available")],
         };
 
-        // Per-position analysis (changes with ply navigation)
-        if let Some(adv_pos) = self.review_state.advanced_position() {
-            lines.push(Line::from(Span::styled(
-                "Position Analysis",
-                Style::default()
-                    .fg(Color::White)
-                    .add_modifier(Modifier::BOLD),
-            )));
+        let content_height = lines.len() as u16;
+        let paragraph = Paragraph::new(lines).scroll((self.scroll, 0));
+        paragraph.render(inner, buf);
 
-            // Critical position badge
-            if adv_pos.is_critical {
-                lines.push(Line::from(Span::styled(
-                    "  \u{26A0} CRITICAL POSITION \u{26A0}",
-                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
-                )));
-            }
+        if content_height > inner.height {
+            let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+                .thumb_style(Style::default().fg(Color::Cyan).bg(Color::DarkGray));
+            let mut scrollbar_state =
+                ScrollbarState::new(content_height as usize).position(self.scroll as usize);
+            scrollbar.render(inner, buf, &mut scrollbar_state);
+        }
+    }
+}
 
-            // Tactical tags
-            if !adv_pos.tactical_tags_after.is_empty() {
+impl AdvancedAnalysisPanel<'_> {
+    /// Tactical tags at the current ply, plus the game's critical position count.
+    fn tactics_lines(&self, advanced: &AdvancedGameAnalysisProto) -> Vec<Line<'static>> {
+        let mut lines = vec![];
+        match self.review_state.advanced_position() {
+            Some(adv_pos) => {
+                if adv_pos.is_critical {
+                    lines.push(Line::from(Span::styled(
+                        "  \u{26A0} CRITICAL POSITION \u{26A0}",
+                        Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                    )));
+                }
                 render_tactical_tags(&mut lines, &adv_pos.tactical_tags_after);
             }
+            None => lines.push(dim_line(NO_POSITION)),
+        }
 
-            // King safety
-            if let Some(ref ks) = adv_pos.king_safety {
-                render_king_safety(&mut lines, ks);
-            }
+        lines.push(Line::raw(""));
+        lines.push(Line::from(vec![
+            Span::styled("Critical positions: ", Style::default().fg(Color::DarkGray)),
+            Span::raw(format!("{}", advanced.critical_positions_count)),
+        ]));
+        lines
+    }
 
-            // Tension metrics
-            if let Some(ref tension) = adv_pos.tension {
-                render_tension(&mut lines, tension);
-            }
+    fn king_safety_lines(&self) -> Vec<Line<'static>> {
+        let mut lines = vec![];
+        match self
+            .review_state
+            .advanced_position()
+            .and_then(|p| p.king_safety.as_ref())
+        {
+            Some(ks) => render_king_safety(&mut lines, ks),
+            None => lines.push(dim_line(NO_POSITION)),
+        }
+        lines
+    }
 
-            lines.push(Line::raw(""));
+    fn tension_lines(&self) -> Vec<Line<'static>> {
+        let mut lines = vec![];
+        match self
+            .review_state
+            .advanced_position()
+            .and_then(|p| p.tension.as_ref())
+        {
+            Some(tension) => render_tension(&mut lines, tension),
+            None => lines.push(dim_line(NO_POSITION)),
         }
+        lines
+    }
 
-        // Game-wide analysis (static)
+    /// Clock use for the current move, then how each side's thinking time
+    /// related to the quality of their moves.
+    fn time_lines(&self, advanced: &AdvancedGameAnalysisProto) -> Vec<Line<'static>> {
+        let positions = &self.review_state.review.positions;
+        if positions.iter().all(|p| p.clock_ms.is_none()) {
+            return vec![dim_line("No clock data recorded for this game")];
+        }
+
+        let mut lines = vec![Line::from(Span::styled(
+            "This Move",
+            Style::default().fg(Color::Cyan),
+        ))];
+        let ply = self.review_state.current_ply;
+        match self
+            .review_state
+            .current_position()
+            .and_then(|p| p.clock_ms)
+        {
+            Some(clock) => {
+                lines.push(Line::from(vec![
+                    Span::raw("  Clock after move: "),
+                    Span::styled(format_clock(clock), Style::default().fg(Color::White)),
+                ]));
+                if let Some(used) = time_used(positions, ply) {
+                    lines.push(Line::from(vec![
+                        Span::raw("  Time used: "),
+                        Span::styled(format_clock(used), Style::default().fg(Color::LightCyan)),
+                    ]));
+                }
+            }
+            None => lines.push(dim_line("  No clock reading at this ply")),
+        }
+
+        lines.push(Line::raw(""));
         lines.push(Line::from(Span::styled(
-            "Game-wide Analysis",
-            Style::default()
-                .fg(Color::White)
-                .add_modifier(Modifier::BOLD),
+            "Time vs. Quality",
+            Style::default().fg(Color::Cyan),
         )));
+        for (side, psy) in [
+            ("White", advanced.white_psychology.as_ref()),
+            ("Black", advanced.black_psychology.as_ref()),
+        ] {
+            let avg = |ms: Option<u64>| ms.map(format_clock).unwrap_or_else(|| "-".to_string());
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {}: ", side), Style::default().fg(Color::White)),
+                Span::raw("good moves "),
+                Span::styled(
+                    avg(psy.and_then(|p| p.avg_good_move_time_ms)),
+                    Style::default().fg(Color::Green),
+                ),
+                Span::raw("  blunders "),
+                Span::styled(
+                    avg(psy.and_then(|p| p.avg_blunder_time_ms)),
+                    Style::default().fg(Color::Red),
+                ),
+            ]));
+            if let Some(corr) = psy.and_then(|p| p.time_quality_correlation) {
+                lines.push(Line::from(vec![
+                    Span::raw("    correlation "),
+                    Span::styled(format!("{:+.2}", corr), Style::default().fg(Color::Yellow)),
+                ]));
+            }
+        }
+        lines
+    }
+
+    /// Mobility and space of both sides in the current position, then their
+    /// average over each phase of the game with the change from the phase
+    /// before.
+    fn mobility_lines(&self) -> Vec<Line<'static>> {
+        let mut lines = vec![Line::from(Span::styled(
+            "This Position",
+            Style::default().fg(Color::Cyan),
+        ))];
+        match self
+            .review_state
+            .advanced_position()
+            .and_then(|p| p.mobility.as_ref())
+        {
+            Some(mobility) => {
+                for (side, metrics) in [("White", &mobility.white), ("Black", &mobility.black)] {
+                    let (moves, space) = metrics.as_ref().map_or((0, 0), |m| (m.mobility, m.space));
+                    lines.push(Line::from(vec![
+                        Span::styled(format!("  {}: ", side), Style::default().fg(Color::White)),
+                        Span::raw("mobility "),
+                        Span::styled(format!("{}", moves), Style::default().fg(Color::LightCyan)),
+                        Span::raw("  space "),
+                        Span::styled(format!("{}", space), Style::default().fg(Color::LightCyan)),
+                    ]));
+                }
+            }
+            None => lines.push(dim_line(NO_POSITION)),
+        }
 
-        // Phase performance
+        lines.push(Line::raw(""));
         lines.push(Line::from(Span::styled(
-            "Phase Performance (avg cp_loss)",
+            "Trend by Phase (avg mobility / space)",
             Style::default().fg(Color::Cyan),
         )));
+        let trend = self.review_state.mobility_by_phase();
+        if trend.is_empty() {
+            lines.push(dim_line("  No mobility data for this game"));
+        }
+        let mut previous: Option<PhaseMobility> = None;
+        for phase in trend {
+            let mut spans = vec![Span::styled(
+                format!("  {:<11}", phase.phase.name()),
+                Style::default().fg(Color::White),
+            )];
+            for (side, mobility, space, before) in [
+                (
+                    "W",
+                    phase.white_mobility,
+                    phase.white_space,
+                    previous.map(|p| p.white_mobility),
+                ),
+                (
+                    "B",
+                    phase.black_mobility,
+                    phase.black_space,
+                    previous.map(|p| p.black_mobility),
+                ),
+            ] {
+                spans.push(Span::raw(format!(" {} {:.1}/{:.1}", side, mobility, space)));
+                spans.push(trend_arrow(before, mobility));
+            }
+            lines.push(Line::from(spans));
+            previous = Some(phase);
+        }
+        lines
+    }
+}
 
-        let white_psy = advanced.white_psychology.as_ref();
-        let black_psy = advanced.black_psychology.as_ref();
+impl AdvancedAnalysisPanel<'_> {
+    /// The game's bookmarks in ply order, the one at the current ply
+    /// highlighted.
+    fn bookmark_lines(&self) -> Vec<Line<'static>> {
+        let review = self.review_state;
+        if review.bookmarks.is_empty() {
+            return vec![dim_line("No bookmarks yet: press b to bookmark a position")];
+        }
+        review
+            .bookmarks
+            .iter()
+            .map(|bookmark| {
+                let san = review
+                    .review
+                    .positions
+                    .iter()
+                    .find(|p| p.ply == bookmark.ply)
+                    .map(|p| p.played_san.as_str())
+                    .unwrap_or_default();
+                let style = if bookmark.ply == review.current_ply {
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(Color::White)
+                };
+                Line::from(vec![
+                    Span::styled(format!("  {:<14}", ply_label(bookmark.ply, san)), style),
+                    Span::styled(bookmark.note.clone(), Style::default().fg(Color::Gray)),
+                ])
+            })
+            .collect()
+    }
+}
 
-        let w_opening = white_psy.map(|p| p.opening_avg_cp_loss).unwrap_or(0.0);
-        let b_opening = black_psy.map(|p| p.opening_avg_cp_loss).unwrap_or(0.0);
-        let w_mid = white_psy.map(|p| p.middlegame_avg_cp_loss).unwrap_or(0.0);
-        let b_mid = black_psy.map(|p| p.middlegame_avg_cp_loss).unwrap_or(0.0);
-        let w_end = white_psy.map(|p| p.endgame_avg_cp_loss).unwrap_or(0.0);
-        let b_end = black_psy.map(|p| p.endgame_avg_cp_loss).unwrap_or(0.0);
+const NO_POSITION: &str = "Step to a move to see this position's analysis";
 
-        let w_max_err = white_psy.map(|p| p.max_consecutive_errors).unwrap_or(0);
-        let b_max_err = black_psy.map(|p| p.max_consecutive_errors).unwrap_or(0);
-        let w_blunder = white_psy.map(|p| p.blunder_cluster_density).unwrap_or(0);
-        let b_blunder = black_psy.map(|p| p.blunder_cluster_density).unwrap_or(0);
+fn dim_line(text: &'static str) -> Line<'static> {
+    Line::from(Span::styled(text, Style::default().fg(Color::DarkGray)))
+}
 
-        let w_fav = white_psy.map(|p| p.favorable_swings).unwrap_or(0);
-        let b_fav = black_psy.map(|p| p.favorable_swings).unwrap_or(0);
-        let w_unfav = white_psy.map(|p| p.unfavorable_swings).unwrap_or(0);
-        let b_unfav = black_psy.map(|p| p.unfavorable_swings).unwrap_or(0);
-        let w_streak = white_psy.map(|p| p.max_momentum_streak).unwrap_or(0);
-        let b_streak = black_psy.map(|p| p.max_momentum_streak).unwrap_or(0);
+/// Game-wide phase performance, error patterns and momentum for both sides.
+fn psychology_lines(advanced: &AdvancedGameAnalysisProto) -> Vec<Line<'static>> {
+    let mut lines: Vec<Line<'static>> = vec![];
+    // Phase performance
+    lines.push(Line::from(Span::styled(
+        "Phase Performance (avg cp_loss)",
+        Style::default().fg(Color::Cyan),
+    )));
 
-        lines.push(Line::from(vec![
-            Span::raw("  "),
-            Span::styled("Opening", Style::default().fg(Color::White)),
-            Span::raw(": W "),
-            Span::styled(
-                format!("{:.1}", w_opening),
-                Style::default().fg(Color::LightCyan),
-            ),
-            Span::raw("  B "),
-            Span::styled(
-                format!("{:.1}", b_opening),
-                Style::default().fg(Color::LightCyan),
-            ),
-        ]));
-        lines.push(Line::from(vec![
-            Span::raw("  "),
-            Span::styled("Middlegame", Style::default().fg(Color::White)),
-            Span::raw(": W "),
-            Span::styled(
-                format!("{:.1}", w_mid),
-                Style::default().fg(Color::LightCyan),
-            ),
-            Span::raw("  B "),
-            Span::styled(
-                format!("{:.1}", b_mid),
-                Style::default().fg(Color::LightCyan),
-            ),
-        ]));
-        lines.push(Line::from(vec![
-            Span::raw("  "),
-            Span::styled("Endgame", Style::default().fg(Color::White)),
-            Span::raw(": W "),
-            Span::styled(
-                format!("{:.1}", w_end),
-                Style::default().fg(Color::LightCyan),
-            ),
-            Span::raw("  B "),
-            Span::styled(
-                format!("{:.1}", b_end),
-                Style::default().fg(Color::LightCyan),
-            ),
-        ]));
+    let white_psy = advanced.white_psychology.as_ref();
+    let black_psy = advanced.black_psychology.as_ref();
 
-        lines.push(Line::raw(""));
+    let w_opening = white_psy.map(|p| p.opening_avg_cp_loss).unwrap_or(0.0);
+    let b_opening = black_psy.map(|p| p.opening_avg_cp_loss).unwrap_or(0.0);
+    let w_mid = white_psy.map(|p| p.middlegame_avg_cp_loss).unwrap_or(0.0);
+    let b_mid = black_psy.map(|p| p.middlegame_avg_cp_loss).unwrap_or(0.0);
+    let w_end = white_psy.map(|p| p.endgame_avg_cp_loss).unwrap_or(0.0);
+    let b_end = black_psy.map(|p| p.endgame_avg_cp_loss).unwrap_or(0.0);
 
-        // Error patterns
-        lines.push(Line::from(Span::styled(
-            "Error Patterns",
-            Style::default().fg(Color::Cyan),
-        )));
-        lines.push(Line::from(vec![
-            Span::raw("  Max consecutive: "),
-            Span::styled(
-                format!("W:{}  B:{}", w_max_err, b_max_err),
-                Style::default().fg(Color::LightRed),
-            ),
-        ]));
-        lines.push(Line::from(vec![
-            Span::raw("  Blunder cluster: "),
-            Span::styled(
-                format!("W:{}  B:{}", w_blunder, b_blunder),
-                Style::default().fg(Color::LightMagenta),
-            ),
-        ]));
+    let w_max_err = white_psy.map(|p| p.max_consecutive_errors).unwrap_or(0);
+    let b_max_err = black_psy.map(|p| p.max_consecutive_errors).unwrap_or(0);
+    let w_blunder = white_psy.map(|p| p.blunder_cluster_density).unwrap_or(0);
+    let b_blunder = black_psy.map(|p| p.blunder_cluster_density).unwrap_or(0);
 
-        lines.push(Line::raw(""));
+    let w_fav = white_psy.map(|p| p.favorable_swings).unwrap_or(0);
+    let b_fav = black_psy.map(|p| p.favorable_swings).unwrap_or(0);
+    let w_unfav = white_psy.map(|p| p.unfavorable_swings).unwrap_or(0);
+    let b_unfav = black_psy.map(|p| p.unfavorable_swings).unwrap_or(0);
+    let w_streak = white_psy.map(|p| p.max_momentum_streak).unwrap_or(0);
+    let b_streak = black_psy.map(|p| p.max_momentum_streak).unwrap_or(0);
 
-        // Momentum
-        lines.push(Line::from(Span::styled(
-            "Momentum",
-            Style::default().fg(Color::Cyan),
-        )));
-        lines.push(Line::from(vec![
-            Span::raw("  Favorable swings: "),
-            Span::styled(
-                format!("W:{}  B:{}", w_fav, b_fav),
-                Style::default().fg(Color::Green),
-            ),
-        ]));
-        lines.push(Line::from(vec![
-            Span::raw("  Unfavorable swings: "),
-            Span::styled(
-                format!("W:{}  B:{}", w_unfav, b_unfav),
-                Style::default().fg(Color::Red),
-            ),
-        ]));
-        lines.push(Line::from(vec![
-            Span::raw("  Max streak: "),
-            Span::styled(
-                format!("W:{}  B:{}", w_streak, b_streak),
-                Style::default().fg(Color::Yellow),
-            ),
-        ]));
+    lines.push(Line::from(vec![
+        Span::raw("  "),
+        Span::styled("Opening", Style::default().fg(Color::White)),
+        Span::raw(": W "),
+        Span::styled(
+            format!("{:.1}", w_opening),
+            Style::default().fg(Color::LightCyan),
+        ),
+        Span::raw("  B "),
+        Span::styled(
+            format!("{:.1}", b_opening),
+            Style::default().fg(Color::LightCyan),
+        ),
+    ]));
+    lines.push(Line::from(vec![
+        Span::raw("  "),
+        Span::styled("Middlegame", Style::default().fg(Color::White)),
+        Span::raw(": W "),
+        Span::styled(
+            format!("{:.1}", w_mid),
+            Style::default().fg(Color::LightCyan),
+        ),
+        Span::raw("  B "),
+        Span::styled(
+            format!("{:.1}", b_mid),
+            Style::default().fg(Color::LightCyan),
+        ),
+    ]));
+    lines.push(Line::from(vec![
+        Span::raw("  "),
+        Span::styled("Endgame", Style::default().fg(Color::White)),
+        Span::raw(": W "),
+        Span::styled(
+            format!("{:.1}", w_end),
+            Style::default().fg(Color::LightCyan),
+        ),
+        Span::raw("  B "),
+        Span::styled(
+            format!("{:.1}", b_end),
+            Style::default().fg(Color::LightCyan),
+        ),
+    ]));
 
-        lines.push(Line::raw(""));
+    lines.push(Line::raw(""));
 
-        // Info
-        lines.push(Line::from(vec![
-            Span::styled("Critical positions: ", Style::default().fg(Color::DarkGray)),
-            Span::raw(format!("{}", advanced.critical_positions_count)),
-        ]));
+    // Error patterns
+    lines.push(Line::from(Span::styled(
+        "Error Patterns",
+        Style::default().fg(Color::Cyan),
+    )));
+    lines.push(Line::from(vec![
+        Span::raw("  Max consecutive: "),
+        Span::styled(
+            format!("W:{}  B:{}", w_max_err, b_max_err),
+            Style::default().fg(Color::LightRed),
+        ),
+    ]));
+    lines.push(Line::from(vec![
+        Span::raw("  Blunder cluster: "),
+        Span::styled(
+            format!("W:{}  B:{}", w_blunder, b_blunder),
+            Style::default().fg(Color::LightMagenta),
+        ),
+    ]));
 
-        let content_height = lines.len() as u16;
-        let paragraph = Paragraph::new(lines).scroll((self.scroll, 0));
-        paragraph.render(inner, buf);
+    lines.push(Line::raw(""));
 
-        if content_height > inner.height {
-            let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
-                .thumb_style(Style::default().fg(Color::Cyan).bg(Color::DarkGray));
-            let mut scrollbar_state =
-                ScrollbarState::new(content_height as usize).position(self.scroll as usize);
-            scrollbar.render(inner, buf, &mut scrollbar_state);
-        }
-    }
+    // Momentum
+    lines.push(Line::from(Span::styled(
+        "Momentum",
+        Style::default().fg(Color::Cyan),
+    )));
+    lines.push(Line::from(vec![
+        Span::raw("  Favorable swings: "),
+        Span::styled(
+            format!("W:{}  B:{}", w_fav, b_fav),
+            Style::default().fg(Color::Green),
+        ),
+    ]));
+    lines.push(Line::from(vec![
+        Span::raw("  Unfavorable swings: "),
+        Span::styled(
+            format!("W:{}  B:{}", w_unfav, b_unfav),
+            Style::default().fg(Color::Red),
+        ),
+    ]));
+    lines.push(Line::from(vec![
+        Span::raw("  Max streak: "),
+        Span::styled(
+            format!("W:{}  B:{}", w_streak, b_streak),
+            Style::default().fg(Color::Yellow),
+        ),
+    ]));
+
+    lines
+}
+
+/// Time the mover spent on `ply`: their clock after their previous move
+/// minus their clock after this one. Increments make this an underestimate.
+fn time_used(positions: &[PositionReview], ply: u32) -> Option<u64> {
+    let clock_at = |ply: u32| positions.iter().find(|p| p.ply == ply)?.clock_ms;
+    let before = clock_at(ply.checked_sub(2)?)?;
+    Some(before.saturating_sub(clock_at(ply)?))
 }
 
 fn render_tactical_tags(lines: &mut Vec<Line<'_>>, tags: &[TacticalTagProto]) {
@@ -328,6 +593,8 @@ fn tactical_tag_kind_name(kind: i32) -> &'static str {
         Ok(TacticalTagKindProto::TacticalTagKindZwischenzug) => "Zwischenzug",
         Ok(TacticalTagKindProto::TacticalTagKindBackRankWeakness) => "Back Rank",
         Ok(TacticalTagKindProto::TacticalTagKindMateThreat) => "Mate Threat",
+        Ok(TacticalTagKindProto::TacticalTagKindDeflection) => "Deflection",
+        Ok(TacticalTagKindProto::TacticalTagKindDecoy) => "Decoy",
         _ => "Unknown",
     }
 }
@@ -482,6 +749,20 @@ fn render_tension(lines: &mut Vec<Line<'_>>, tension: &PositionTensionMetricsPro
     ]));
 }
 
+/// Whether a side's mobility rose or fell since the phase before, if any.
+fn trend_arrow(before: Option<f32>, now: f32) -> Span<'static> {
+    match before {
+        Some(before) if now > before + 0.5 => {
+            Span::styled(" \u{2191}", Style::default().fg(Color::Green))
+        }
+        Some(before) if now < before - 0.5 => {
+            Span::styled(" \u{2193}", Style::default().fg(Color::Red))
+        }
+        Some(_) => Span::styled(" =", Style::default().fg(Color::DarkGray)),
+        None => Span::raw("  "),
+    }
+}
+
 fn volatility_bar(score: f32, width: usize) -> String {
     let filled = ((score.clamp(0.0, 1.0)) * width as f32).round() as usize;
     let empty = width.saturating_sub(filled);
@@ -491,3 +772,46 @@ fn volatility_bar(score: f32, width: usize) -> String {
         "\u{2591}".repeat(empty)
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tab_number_keys() {
+        assert_eq!(
+            AdvancedTab::from_number_key('1'),
+            Some(AdvancedTab::Tactics)
+        );
+        assert_eq!(AdvancedTab::from_number_key('5'), Some(AdvancedTab::Time));
+        assert_eq!(AdvancedTab::from_number_key('0'), None);
+        assert_eq!(
+            AdvancedTab::from_number_key('6'),
+            Some(AdvancedTab::Mobility)
+        );
+        assert_eq!(
+            AdvancedTab::from_number_key('7'),
+            Some(AdvancedTab::Bookmarks)
+        );
+        assert_eq!(AdvancedTab::from_number_key('8'), None);
+        assert_eq!(AdvancedTab::from_number_key('x'), None);
+    }
+
+    #[test]
+    fn test_time_used_compares_the_movers_clocks() {
+        let position = |ply, clock_ms| PositionReview {
+            ply,
+            clock_ms,
+            ..Default::default()
+        };
+        let positions = vec![
+            position(1, Some(598_000)),
+            position(2, Some(597_000)),
+            position(3, Some(585_500)),
+            position(4, None),
+        ];
+        assert_eq!(time_used(&positions, 3), Some(12_500));
+        assert_eq!(time_used(&positions, 1), None);
+        assert_eq!(time_used(&positions, 4), None);
+    }
+}
@@ -1,11 +1,16 @@
+use super::board_image::{rasterize, ImageArrow};
 use super::board_overlay::{BoardOverlay, OverlayColor, OverlayElement};
+use crate::ui::glyphs::{self, GlyphWidths};
+use crate::ui::graphics::{self, GraphicsProtocol};
 use cozy_chess::{Board, Color as ChessColor, File, Piece, Rank, Square};
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
     style::{Color, Modifier, Style},
-    widgets::{Block, Borders, Widget},
+    widgets::{Block, Borders, StatefulWidget, Widget},
 };
+use std::borrow::Cow;
+use std::io::{self, Write};
 // Default board square colors (tan/brown)
 const LIGHT_SQUARE: Color = Color::Rgb(240, 217, 181);
 const DARK_SQUARE: Color = Color::Rgb(181, 136, 99);
@@ -65,39 +70,318 @@ impl BoardSize {
     }
 }
 
-pub struct BoardWidget<'a> {
-    pub board: &'a Board,
-    pub overlay: &'a BoardOverlay,
-    pub flipped: bool,
+/// Where the board sits inside the widget area.
+#[derive(Clone, Copy)]
+struct Geometry {
+    inner: Rect,
+    board_size: BoardSize,
+    start_x: u16,
+    start_y: u16,
 }
 
-impl Widget for BoardWidget<'_> {
-    fn render(self, area: Rect, buf: &mut Buffer) {
-        let block = Block::default()
-            .title("♟ Chess Board ♟")
-            .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::Cyan));
-        let inner = block.inner(area);
-        block.render(area, buf);
+impl Geometry {
+    fn for_area(area: Rect) -> Self {
+        let inner = Block::default().borders(Borders::ALL).inner(area);
 
         // Calculate the best board size for available space
         let board_size = BoardSize::for_area(inner);
 
-        // Calculate actual board dimensions (including space for labels)
-        let board_width = board_size.square_width * 8;
-        let board_height = board_size.square_height * 8;
-
         // Account for rank labels on the left (need 3 chars) and file labels below (need 2 lines)
-        let total_width = board_width + 3; // board + rank labels
-        let total_height = board_height + 2; // board + file labels
+        let total_width = board_size.square_width * 8 + 3;
+        let total_height = board_size.square_height * 8 + 2;
 
         // Center the board within the available area
         let offset_x = (inner.width.saturating_sub(total_width)) / 2;
         let offset_y = (inner.height.saturating_sub(total_height)) / 2;
 
-        // Add space for rank labels on the left
-        let board_start_x = inner.x + offset_x + 3;
-        let board_start_y = inner.y + offset_y;
+        Self {
+            inner,
+            board_size,
+            // Add space for rank labels on the left
+            start_x: inner.x + offset_x + 3,
+            start_y: inner.y + offset_y,
+        }
+    }
+
+    /// Top-left cell of the square at grid position `idx` (row-major from
+    /// the top-left corner as drawn).
+    fn square_origin(&self, idx: usize) -> (u16, u16) {
+        let (rank_idx, file_idx) = (idx / 8, idx % 8);
+        (
+            self.start_x + file_idx as u16 * self.board_size.square_width,
+            self.start_y + rank_idx as u16 * self.board_size.square_height,
+        )
+    }
+}
+
+/// Everything drawn on one square, compared between frames to find the
+/// squares that have to be drawn again.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct SquareLook {
+    pub(crate) bg: Color,
+    pub(crate) piece: Option<(Piece, ChessColor)>,
+    pub(crate) outline: Option<Color>,
+}
+
+/// The board as drawn on the previous frame, without arrows.
+struct CachedBoard {
+    area: Rect,
+    flipped: bool,
+    buffer: Buffer,
+    looks: [SquareLook; 64],
+}
+
+/// Second buffer for [`BoardWidget`]. Squares that look the same as on the
+/// previous frame are copied from it rather than drawn again; the border,
+/// labels and every square are only redrawn when the area or orientation
+/// changes.
+#[derive(Default)]
+pub struct BoardCache {
+    board: Option<CachedBoard>,
+    /// Squares drawn on the last frame; 64 after a full redraw.
+    pub last_redrawn: usize,
+    /// Image backend, when the terminal can show pictures.
+    graphics: Option<BoardGraphics>,
+}
+
+impl BoardCache {
+    /// A cache that draws the board as a picture with `protocol`, falling
+    /// back to text whenever that is not possible.
+    pub fn new(protocol: Option<GraphicsProtocol>) -> Self {
+        Self {
+            graphics: protocol.map(BoardGraphics::new),
+            ..Self::default()
+        }
+    }
+
+    /// Start a frame. While `covered`, e.g. when a popup is open, the board
+    /// is drawn as text, since images would hide what is drawn over them.
+    pub fn begin_frame(&mut self, covered: bool) {
+        if let Some(graphics) = self.graphics.as_mut() {
+            graphics.pending = None;
+            graphics.covered = covered;
+            graphics.cell_px = graphics::cell_size();
+        }
+    }
+
+    /// After the frame was drawn: place the board image if it changed, or
+    /// remove it if the board was drawn as text.
+    pub fn flush_graphics(&mut self, out: &mut impl Write) -> io::Result<()> {
+        match self.graphics.as_mut() {
+            Some(graphics) => graphics.flush(out),
+            None => Ok(()),
+        }
+    }
+
+    /// Whether the last frame drew the board as an image.
+    pub fn showing_image(&self) -> bool {
+        self.graphics
+            .as_ref()
+            .is_some_and(|graphics| graphics.pending.is_some())
+    }
+}
+
+/// What the board image shows and where, compared between frames so the
+/// image is only sent again when something changed.
+#[derive(Clone, PartialEq)]
+struct BoardImage {
+    /// Cells the squares cover.
+    cells: Rect,
+    /// Size of one square in image pixels.
+    square_px: (u32, u32),
+    looks: [SquareLook; 64],
+    arrows: Vec<ImageArrow>,
+}
+
+/// Image backend for [`BoardWidget`]. The widget records the picture it
+/// wants during the draw, and the render loop sends it to the terminal
+/// afterwards, since images cannot go through ratatui's cell buffer.
+struct BoardGraphics {
+    protocol: GraphicsProtocol,
+    pending: Option<BoardImage>,
+    shown: Option<BoardImage>,
+    covered: bool,
+    /// Size of a terminal cell in pixels, read at the start of each frame.
+    cell_px: Option<(u16, u16)>,
+    /// Set once an image could not be encoded; the board stays text.
+    failed: bool,
+}
+
+impl BoardGraphics {
+    fn new(protocol: GraphicsProtocol) -> Self {
+        Self {
+            protocol,
+            pending: None,
+            shown: None,
+            covered: false,
+            cell_px: None,
+            failed: false,
+        }
+    }
+
+    /// Largest square, in pixels, sent to terminals that scale images; they
+    /// stretch it over the cells, so more would only cost bandwidth.
+    const SCALED_SQUARE_PX: u32 = 48;
+
+    /// The image for this frame, or `None` when the board has to be text:
+    /// something covers it, the terminal does not report its cell size, or
+    /// the squares do not fit the area.
+    fn plan(
+        &self,
+        widget: &BoardWidget,
+        geometry: &Geometry,
+        looks: [SquareLook; 64],
+    ) -> Option<BoardImage> {
+        if self.covered || self.failed {
+            return None;
+        }
+        let size = geometry.board_size;
+        let cells = Rect::new(
+            geometry.start_x,
+            geometry.start_y,
+            size.square_width * 8,
+            size.square_height * 8,
+        );
+        if cells.right() > geometry.inner.right() || cells.bottom() > geometry.inner.bottom() {
+            return None;
+        }
+
+        let (cell_w, cell_h) = self.cell_px?;
+        let mut square_px = (
+            u32::from(size.square_width * cell_w),
+            u32::from(size.square_height * cell_h),
+        );
+        if self.protocol.scales() {
+            let longest = square_px.0.max(square_px.1);
+            if longest > Self::SCALED_SQUARE_PX {
+                square_px = (
+                    (square_px.0 * Self::SCALED_SQUARE_PX / longest).max(1),
+                    (square_px.1 * Self::SCALED_SQUARE_PX / longest).max(1),
+                );
+            }
+        }
+
+        let arrows = widget
+            .overlay
+            .elements()
+            .into_iter()
+            .filter_map(|element| match element {
+                OverlayElement::Arrow {
+                    from, to, color, ..
+                } => Some(ImageArrow {
+                    from: square_to_grid_idx(*from, widget.flipped),
+                    to: square_to_grid_idx(*to, widget.flipped),
+                    color: color.resolve(false),
+                    thin: color.is_secondary(),
+                }),
+                _ => None,
+            })
+            .collect();
+
+        Some(BoardImage {
+            cells,
+            square_px,
+            looks,
+            arrows,
+        })
+    }
+
+    fn flush(&mut self, out: &mut impl Write) -> io::Result<()> {
+        if self.pending == self.shown {
+            return Ok(());
+        }
+        if self.shown.take().is_some() {
+            if let Some(delete) = self.protocol.delete() {
+                out.write_all(delete.as_bytes())?;
+            }
+        }
+        if let Some(image) = &self.pending {
+            let picture = rasterize(&image.looks, &image.arrows, image.square_px);
+            match self
+                .protocol
+                .encode(&picture, image.cells.width, image.cells.height)
+            {
+                Ok(escape) => {
+                    crossterm::queue!(
+                        out,
+                        crossterm::cursor::SavePosition,
+                        crossterm::cursor::MoveTo(image.cells.x, image.cells.y)
+                    )?;
+                    out.write_all(escape.as_bytes())?;
+                    crossterm::queue!(out, crossterm::cursor::RestorePosition)?;
+                    self.shown = Some(image.clone());
+                }
+                Err(e) => {
+                    tracing::warn!("Board image failed, drawing the board as text: {}", e);
+                    self.failed = true;
+                }
+            }
+        }
+        out.flush()
+    }
+}
+
+pub struct BoardWidget<'a> {
+    pub board: &'a Board,
+    pub overlay: &'a BoardOverlay,
+    pub flipped: bool,
+}
+
+impl BoardWidget<'_> {
+    /// Looks of all squares by grid position (row-major from the top-left
+    /// corner as drawn).
+    fn square_looks(&self) -> [SquareLook; 64] {
+        std::array::from_fn(|idx| {
+            let (rank_idx, file_idx) = (idx / 8, idx % 8);
+            let file = if self.flipped {
+                File::index(7 - file_idx)
+            } else {
+                File::index(file_idx)
+            };
+            let rank = if self.flipped {
+                Rank::index(rank_idx)
+            } else {
+                Rank::index(7 - rank_idx)
+            };
+            let square = Square::new(file, rank);
+            let is_light_square = (file_idx + rank_idx) % 2 == 0;
+
+            // Resolve background color from overlay (or default board color)
+            let bg = match self.overlay.square_tint(square) {
+                Some(color) => color.resolve(is_light_square),
+                None => {
+                    if is_light_square {
+                        LIGHT_SQUARE
+                    } else {
+                        DARK_SQUARE
+                    }
+                }
+            };
+            SquareLook {
+                bg,
+                piece: self.board.piece_on(square).zip(self.board.color_on(square)),
+                outline: self
+                    .overlay
+                    .square_outline(square)
+                    .map(|c| c.resolve(is_light_square)),
+            }
+        })
+    }
+
+    /// Border and rank/file labels.
+    fn render_frame(&self, area: Rect, buf: &mut Buffer, geometry: &Geometry) {
+        let Geometry {
+            inner,
+            board_size,
+            start_x: board_start_x,
+            start_y: board_start_y,
+        } = *geometry;
+
+        Block::default()
+            .title("♟ Chess Board ♟")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .render(area, buf);
 
         // Draw rank labels on the left
         for rank_idx in 0..8 {
@@ -132,93 +416,137 @@ impl Widget for BoardWidget<'_> {
                 buf.set_string(x, y, &file_label, Style::default().fg(Color::Yellow));
             }
         }
+    }
+}
 
-        // Pre-compute arrow paths for BFS rendering
-        let arrow_paths = compute_arrow_paths(self.overlay, board_size, self.flipped);
+/// Draw one square: background, piece and outline.
+fn render_square_look(buf: &mut Buffer, geometry: &Geometry, idx: usize, look: SquareLook) {
+    let (x, y) = geometry.square_origin(idx);
+    let (board_size, inner) = (geometry.board_size, geometry.inner);
+
+    // Draw the square background
+    render_square(buf, x, y, look.bg, board_size, inner);
+
+    // Draw piece
+    if let Some((piece, color)) = look.piece {
+        render_piece(
+            buf,
+            &PieceRenderParams {
+                x,
+                y,
+                piece,
+                color,
+                bg_color: look.bg,
+                board_size,
+                bounds: inner,
+                glyphs: glyphs::current(),
+            },
+        );
+    }
 
-        // Draw each square
-        for rank_idx in 0..8 {
-            for file_idx in 0..8 {
-                let file = if self.flipped {
-                    File::index(7 - file_idx)
-                } else {
-                    File::index(file_idx)
-                };
-                let rank = if self.flipped {
-                    Rank::index(rank_idx)
+    // Draw outline (border) around square if present
+    if let Some(outline_color) = look.outline {
+        draw_square_outline(buf, x, y, outline_color, board_size, inner);
+    }
+}
+
+/// Reset the cells of one square before it is drawn again, so nothing of
+/// the previous piece or outline is left behind.
+fn clear_square(buf: &mut Buffer, geometry: &Geometry, idx: usize) {
+    let (x, y) = geometry.square_origin(idx);
+    let size = geometry.board_size;
+    for py in y..(y + size.square_height).min(geometry.inner.bottom()) {
+        for px in x..(x + size.square_width).min(geometry.inner.right()) {
+            if let Some(cell) = buf.cell_mut((px, py)) {
+                cell.reset();
+            }
+        }
+    }
+}
+
+impl StatefulWidget for BoardWidget<'_> {
+    type State = BoardCache;
+
+    fn render(self, area: Rect, buf: &mut Buffer, cache: &mut BoardCache) {
+        let geometry = Geometry::for_area(area);
+        let looks = self.square_looks();
+
+        // As a picture, only the border and labels go through the buffer;
+        // the squares' cells stay blank for the image to cover
+        if let Some(graphics) = cache.graphics.as_mut() {
+            graphics.pending = graphics.plan(&self, &geometry, looks);
+            if let Some(image) = &graphics.pending {
+                cache.last_redrawn = if graphics.shown.as_ref() == Some(image) {
+                    0
                 } else {
-                    Rank::index(7 - rank_idx)
-                };
-                let square = Square::new(file, rank);
-
-                let x = board_start_x + (file_idx as u16 * board_size.square_width);
-                let y = board_start_y + (rank_idx as u16 * board_size.square_height);
-
-                let is_light_square = (file_idx + rank_idx) % 2 == 0;
-
-                // Resolve background color from overlay (or default board color)
-                let bg_color = match self.overlay.square_tint(square) {
-                    Some(color) => color.resolve(is_light_square),
-                    None => {
-                        if is_light_square {
-                            LIGHT_SQUARE
-                        } else {
-                            DARK_SQUARE
-                        }
-                    }
+                    looks.len()
                 };
+                self.render_frame(area, buf, &geometry);
+                return;
+            }
+        }
 
-                // Draw the square background
-                render_square(buf, x, y, bg_color, board_size, inner);
-
-                // Get piece at this square
-                let piece = self.board.piece_on(square);
-                let piece_color = self.board.color_on(square);
-
-                // Draw piece
-                if let (Some(piece), Some(piece_color)) = (piece, piece_color) {
-                    render_piece(
-                        buf,
-                        &PieceRenderParams {
-                            x,
-                            y,
-                            piece,
-                            color: piece_color,
-                            bg_color,
-                            board_size,
-                            bounds: inner,
-                        },
-                    );
+        let board = match cache.board.take() {
+            Some(mut cached) if cached.area == area && cached.flipped == self.flipped => {
+                let mut redrawn = 0;
+                for (idx, look) in looks.iter().enumerate() {
+                    if cached.looks[idx] != *look {
+                        clear_square(&mut cached.buffer, &geometry, idx);
+                        render_square_look(&mut cached.buffer, &geometry, idx, *look);
+                        redrawn += 1;
+                    }
+                }
+                cached.looks = looks;
+                cache.last_redrawn = redrawn;
+                cached
+            }
+            _ => {
+                let mut buffer = Buffer::empty(area);
+                self.render_frame(area, &mut buffer, &geometry);
+                for (idx, look) in looks.iter().enumerate() {
+                    render_square_look(&mut buffer, &geometry, idx, *look);
+                }
+                cache.last_redrawn = looks.len();
+                CachedBoard {
+                    area,
+                    flipped: self.flipped,
+                    buffer,
+                    looks,
                 }
+            }
+        };
 
-                // Draw outline (border) around square if present
-                if let Some(outline_color) = self.overlay.square_outline(square) {
-                    draw_square_outline(
-                        buf,
-                        x,
-                        y,
-                        outline_color.resolve(is_light_square),
-                        board_size,
-                        inner,
-                    );
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                if let (Some(src), Some(dst)) = (board.buffer.cell((x, y)), buf.cell_mut((x, y))) {
+                    *dst = src.clone();
                 }
             }
         }
+        cache.board = Some(board);
 
-        // Draw arrow paths on top of everything
+        // Arrows are drawn on top of the copy every frame so the cached
+        // squares underneath stay clean
+        let arrow_paths = compute_arrow_paths(self.overlay, geometry.board_size, self.flipped);
         for arrow_path in &arrow_paths {
             render_arrow_path(
                 buf,
                 arrow_path,
-                board_start_x,
-                board_start_y,
-                board_size,
-                inner,
+                geometry.start_x,
+                geometry.start_y,
+                geometry.board_size,
+                geometry.inner,
             );
         }
     }
 }
 
+impl Widget for BoardWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        StatefulWidget::render(self, area, buf, &mut BoardCache::default());
+    }
+}
+
 fn render_square(
     buf: &mut Buffer,
     x: u16,
@@ -248,6 +576,7 @@ struct PieceRenderParams {
     bg_color: Color,
     board_size: BoardSize,
     bounds: Rect,
+    glyphs: GlyphWidths,
 }
 
 fn render_piece(buf: &mut Buffer, params: &PieceRenderParams) {
@@ -264,16 +593,26 @@ fn render_piece(buf: &mut Buffer, params: &PieceRenderParams) {
         .fg(fg_color)
         .add_modifier(Modifier::BOLD);
 
+    let square_width = params.board_size.square_width;
+    let right = (params.x + square_width).min(params.bounds.right());
+
     // Render each line of piece art, centered
     for (i, line) in lines.iter().enumerate() {
         let py = params.y + i as u16;
         if py < params.bounds.bottom() {
+            // Art drawn with wide glyphs can overflow the square; refit it
+            // clear of the outline columns
+            let line = if params.glyphs.str_width(line) <= square_width {
+                Cow::Borrowed(*line)
+            } else {
+                params.glyphs.fit(line, square_width.saturating_sub(2))
+            };
             // Center the text in the square
-            let line_width = line.chars().count() as u16;
-            let offset = (params.board_size.square_width.saturating_sub(line_width)) / 2;
+            let line_width = params.glyphs.str_width(&line);
+            let offset = (square_width.saturating_sub(line_width)) / 2;
             let px = params.x + offset;
             if px < params.bounds.right() {
-                buf.set_string(px, py, line, style);
+                params.glyphs.set_str(buf, px, py, &line, style, right);
             }
         }
     }
@@ -646,7 +985,14 @@ fn render_arrow_path(
 
     // Use the overlay color resolved for a neutral context (dark)
     let arrow_fg = path.color.resolve(false);
-    let style = Style::default().fg(arrow_fg).add_modifier(Modifier::BOLD);
+    // Other engine lines are drawn lighter, with dashed bodies
+    let secondary = path.color.is_secondary();
+    let weight = if secondary {
+        Modifier::empty()
+    } else {
+        Modifier::BOLD
+    };
+    let style = Style::default().fg(arrow_fg).add_modifier(weight);
 
     // Draw arrow body
     for &(px, py) in &path.cells[skip_start..total.saturating_sub(skip_end)] {
@@ -658,7 +1004,7 @@ fn render_arrow_path(
             && screen_y >= bounds.y
         {
             let (dx, dy) = path.head_direction;
-            let symbol = arrow_body_symbol(dx, dy);
+            let symbol = arrow_body_symbol(dx, dy, secondary);
             buf[(screen_x, screen_y)]
                 .set_symbol(symbol)
                 .set_style(style);
@@ -675,20 +1021,23 @@ fn render_arrow_path(
             && screen_y >= bounds.y
         {
             let (dx, dy) = path.head_direction;
-            let symbol = arrow_head_symbol(dx, dy);
-            let head_style = Style::default().fg(arrow_fg).add_modifier(Modifier::BOLD);
+            // Heads sit one cell wide among the body cells
+            let symbol = glyphs::current().narrow(arrow_head_symbol(dx, dy));
+            let head_style = Style::default().fg(arrow_fg).add_modifier(weight);
             buf[(screen_x, screen_y)]
-                .set_symbol(symbol)
+                .set_symbol(&symbol)
                 .set_style(head_style);
         }
     }
 }
 
 /// Choose an arrow body character based on the overall direction.
-fn arrow_body_symbol(dx: i16, dy: i16) -> &'static str {
+fn arrow_body_symbol(dx: i16, dy: i16, dashed: bool) -> &'static str {
     let dx = dx.signum();
     let dy = dy.signum();
     match (dx, dy) {
+        (0, _) if dashed => "┆",
+        (_, 0) if dashed => "┄",
         (0, _) => "│",            // vertical
         (_, 0) => "─",            // horizontal
         (1, -1) | (-1, 1) => "╱", // diagonal /
@@ -713,3 +1062,157 @@ fn arrow_head_symbol(dx: i16, dy: i16) -> &'static str {
         _ => "●",
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::widgets::board_overlay::OverlayColor;
+    use ratatui::buffer::Cell;
+
+    fn area() -> Rect {
+        Rect::new(0, 0, 80, 50)
+    }
+
+    fn draw(board: &Board, overlay: &BoardOverlay, cache: &mut BoardCache) -> Buffer {
+        let mut buf = Buffer::empty(area());
+        let widget = BoardWidget {
+            board,
+            overlay,
+            flipped: false,
+        };
+        StatefulWidget::render(widget, area(), &mut buf, cache);
+        buf
+    }
+
+    #[test]
+    fn test_move_redraws_only_changed_squares() {
+        let start = Board::default();
+        let after_e4: Board = "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1"
+            .parse()
+            .unwrap();
+        let empty = BoardOverlay::new();
+        let mut cache = BoardCache::default();
+
+        let before = draw(&start, &empty, &mut cache);
+        assert_eq!(cache.last_redrawn, 64);
+        draw(&start, &empty, &mut cache);
+        assert_eq!(cache.last_redrawn, 0);
+
+        let after = draw(&after_e4, &empty, &mut cache);
+        assert_eq!(cache.last_redrawn, 2);
+        assert_eq!(after, draw(&after_e4, &empty, &mut BoardCache::default()));
+
+        // Only cells of e2 and e4 differ, so only they are written to the terminal
+        let size = Geometry::for_area(area()).board_size;
+        let square_cells = (size.square_width * size.square_height) as usize;
+        let writes = before.diff(&after).len();
+        assert!(
+            writes > 0 && writes <= 2 * square_cells,
+            "{} writes",
+            writes
+        );
+    }
+
+    #[test]
+    fn test_overlay_changes_leave_no_trace() {
+        let board = Board::default();
+        let mut highlighted = BoardOverlay::new();
+        highlighted
+            .tint(Square::E2, OverlayColor::Selected)
+            .outline(Square::E4, OverlayColor::LegalMove)
+            .arrow(Square::G1, Square::F3, OverlayColor::BestMove);
+        let plain = BoardOverlay::new();
+        let mut cache = BoardCache::default();
+
+        draw(&board, &plain, &mut cache);
+        let with_overlay = draw(&board, &highlighted, &mut cache);
+        assert_eq!(cache.last_redrawn, 2);
+        assert_eq!(
+            with_overlay,
+            draw(&board, &highlighted, &mut BoardCache::default())
+        );
+
+        let cleared = draw(&board, &plain, &mut cache);
+        assert_eq!(cleared, draw(&board, &plain, &mut BoardCache::default()));
+    }
+
+    #[test]
+    fn test_image_backend_falls_back_to_text() {
+        let board = Board::default();
+        let overlay = BoardOverlay::new();
+        let text = draw(&board, &overlay, &mut BoardCache::default());
+
+        let mut cache = BoardCache::new(Some(GraphicsProtocol::Kitty));
+        cache.begin_frame(false);
+        // No cell size reported, e.g. not a real terminal
+        assert_eq!(draw(&board, &overlay, &mut cache), text);
+        assert!(!cache.showing_image());
+
+        let mut out = Vec::new();
+        cache.begin_frame(false);
+        cache.graphics.as_mut().unwrap().cell_px = Some((10, 20));
+        let framed = draw(&board, &overlay, &mut cache);
+        assert!(cache.showing_image());
+        assert_eq!(cache.last_redrawn, 64);
+        // Squares are left blank for the picture
+        let origin = Geometry::for_area(area());
+        assert_eq!(
+            framed[(origin.start_x + 2, origin.start_y + 2)],
+            Cell::default()
+        );
+        cache.flush_graphics(&mut out).unwrap();
+        assert!(String::from_utf8_lossy(&out).contains("\x1b_Ga=T,f=24"));
+
+        // Unchanged board: nothing is sent again
+        out.clear();
+        let cell_px = cache.graphics.as_ref().unwrap().cell_px;
+        cache.begin_frame(false);
+        cache.graphics.as_mut().unwrap().cell_px = cell_px;
+        draw(&board, &overlay, &mut cache);
+        assert_eq!(cache.last_redrawn, 0);
+        cache.flush_graphics(&mut out).unwrap();
+        assert!(out.is_empty());
+
+        // A popup over the board: text again, and the picture is removed
+        cache.begin_frame(true);
+        assert_eq!(draw(&board, &overlay, &mut cache), text);
+        cache.flush_graphics(&mut out).unwrap();
+        assert!(String::from_utf8_lossy(&out).contains("a=d,d=I"));
+    }
+
+    #[test]
+    fn test_wide_piece_art_stays_inside_its_square() {
+        let size = BoardSize::SMALL;
+        let bounds = Rect::new(0, 0, size.square_width * 2, size.square_height);
+        let mut buf = Buffer::empty(bounds);
+        for piece in [Piece::King, Piece::Queen, Piece::Knight, Piece::Pawn] {
+            buf.reset();
+            render_piece(
+                &mut buf,
+                &PieceRenderParams {
+                    x: 0,
+                    y: 0,
+                    piece,
+                    color: ChessColor::White,
+                    bg_color: LIGHT_SQUARE,
+                    board_size: size,
+                    bounds,
+                    glyphs: GlyphWidths::WIDE,
+                },
+            );
+            // Nothing spills into the neighbouring square, and every wide
+            // glyph has the cell after it to itself
+            for y in 0..size.square_height {
+                for x in size.square_width..bounds.width {
+                    assert_eq!(buf[(x, y)].symbol(), " ", "{:?} at ({}, {})", piece, x, y);
+                }
+                for x in 0..size.square_width - 1 {
+                    let c = buf[(x, y)].symbol().chars().next().unwrap();
+                    if GlyphWidths::WIDE.is_wide(c) {
+                        assert!(buf[(x + 1, y)].skip);
+                    }
+                }
+            }
+        }
+    }
+}
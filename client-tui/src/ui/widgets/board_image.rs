@@ -0,0 +1,378 @@
+use super::board::SquareLook;
+use crate::ui::graphics::Image;
+use cozy_chess::{Color as ChessColor, Piece};
+use ratatui::style::Color;
+
+/// Side of the piece sprites in sprite pixels.
+const SPRITE_SIZE: usize = 16;
+
+#[rustfmt::skip]
+const PAWN: [&str; SPRITE_SIZE] = [
+    "................",
+    "................",
+    "................",
+    "......####......",
+    ".....######.....",
+    ".....######.....",
+    "......####......",
+    ".....######.....",
+    "......####......",
+    "......####......",
+    ".....######.....",
+    "....########....",
+    "...##########...",
+    "...##########...",
+    "................",
+    "................",
+];
+
+#[rustfmt::skip]
+const KNIGHT: [&str; SPRITE_SIZE] = [
+    "................",
+    "................",
+    ".......#.#......",
+    "......######....",
+    ".....########...",
+    "....####.#####..",
+    "...##########...",
+    "...####.#####...",
+    ".......######...",
+    "......######....",
+    ".....######.....",
+    "....########....",
+    "...##########...",
+    "...##########...",
+    "................",
+    "................",
+];
+
+#[rustfmt::skip]
+const BISHOP: [&str; SPRITE_SIZE] = [
+    "................",
+    ".......##.......",
+    "......####......",
+    ".....###.##.....",
+    ".....##.###.....",
+    ".....######.....",
+    "......####......",
+    ".......##.......",
+    "......####......",
+    ".....######.....",
+    "......####......",
+    "....########....",
+    "...##########...",
+    "...##########...",
+    "................",
+    "................",
+];
+
+#[rustfmt::skip]
+const ROOK: [&str; SPRITE_SIZE] = [
+    "................",
+    "................",
+    "...##.####.##...",
+    "...##.####.##...",
+    "...##########...",
+    "....########....",
+    ".....######.....",
+    ".....######.....",
+    ".....######.....",
+    ".....######.....",
+    ".....######.....",
+    "....########....",
+    "...##########...",
+    "...##########...",
+    "................",
+    "................",
+];
+
+#[rustfmt::skip]
+const QUEEN: [&str; SPRITE_SIZE] = [
+    "................",
+    "..#....##....#..",
+    "..##..####..##..",
+    "..###.####.###..",
+    "...##########...",
+    "...##########...",
+    "....########....",
+    ".....######.....",
+    ".....######.....",
+    "....########....",
+    ".....######.....",
+    "....########....",
+    "...##########...",
+    "...##########...",
+    "................",
+    "................",
+];
+
+#[rustfmt::skip]
+const KING: [&str; SPRITE_SIZE] = [
+    ".......##.......",
+    "......####......",
+    ".......##.......",
+    "....##.##.##....",
+    "...##########...",
+    "...##########...",
+    "....########....",
+    ".....######.....",
+    ".....######.....",
+    "....########....",
+    ".....######.....",
+    "....########....",
+    "...##########...",
+    "...##########...",
+    "................",
+    "................",
+];
+
+fn sprite(piece: Piece) -> &'static [&'static str; SPRITE_SIZE] {
+    match piece {
+        Piece::Pawn => &PAWN,
+        Piece::Knight => &KNIGHT,
+        Piece::Bishop => &BISHOP,
+        Piece::Rook => &ROOK,
+        Piece::Queen => &QUEEN,
+        Piece::King => &KING,
+    }
+}
+
+fn sprite_filled(sprite: &[&str; SPRITE_SIZE], x: i32, y: i32) -> bool {
+    (0..SPRITE_SIZE as i32).contains(&x)
+        && (0..SPRITE_SIZE as i32).contains(&y)
+        && sprite[y as usize].as_bytes()[x as usize] == b'#'
+}
+
+/// Fill and outline colour of a side's pieces.
+fn piece_colors(color: ChessColor) -> ([u8; 3], [u8; 3]) {
+    match color {
+        ChessColor::White => ([245, 245, 240], [25, 25, 25]),
+        ChessColor::Black => ([35, 35, 35], [225, 225, 225]),
+    }
+}
+
+/// RGB for a terminal colour, using xterm's defaults for named colours.
+pub fn rgb(color: Color) -> [u8; 3] {
+    match color {
+        Color::Rgb(r, g, b) => [r, g, b],
+        Color::Black => [0, 0, 0],
+        Color::Red => [205, 0, 0],
+        Color::Green => [0, 205, 0],
+        Color::Yellow => [205, 205, 0],
+        Color::Blue => [0, 0, 238],
+        Color::Magenta => [205, 0, 205],
+        Color::Cyan => [0, 205, 205],
+        Color::Gray => [229, 229, 229],
+        Color::DarkGray => [127, 127, 127],
+        Color::LightRed => [255, 0, 0],
+        Color::LightGreen => [0, 255, 0],
+        Color::LightYellow => [255, 255, 0],
+        Color::LightBlue => [92, 92, 255],
+        Color::LightMagenta => [255, 0, 255],
+        Color::LightCyan => [0, 255, 255],
+        Color::White => [255, 255, 255],
+        Color::Indexed(_) | Color::Reset => [128, 128, 128],
+    }
+}
+
+/// An arrow between two squares by grid position (column, row from the
+/// top-left corner as drawn).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImageArrow {
+    pub from: (usize, usize),
+    pub to: (usize, usize),
+    pub color: Color,
+    /// Drawn narrower, for the engine's lines after the best one.
+    pub thin: bool,
+}
+
+/// Paint the 64 squares, their pieces and outlines, and the arrows on top,
+/// with each square `square` pixels in size.
+pub fn rasterize(looks: &[SquareLook; 64], arrows: &[ImageArrow], square: (u32, u32)) -> Image {
+    let (sw, sh) = square;
+    let mut image = Image::new(sw * 8, sh * 8, [0, 0, 0]);
+
+    for (idx, look) in looks.iter().enumerate() {
+        let x0 = (idx % 8) as u32 * sw;
+        let y0 = (idx / 8) as u32 * sh;
+        image.fill_rect(x0, y0, sw, sh, rgb(look.bg));
+
+        if let Some((piece, color)) = look.piece {
+            draw_sprite(&mut image, sprite(piece), color, x0, y0, square);
+        }
+
+        if let Some(outline) = look.outline {
+            let t = (sw.min(sh) / 16).max(1);
+            let c = rgb(outline);
+            image.fill_rect(x0, y0, sw, t, c);
+            image.fill_rect(x0, y0 + sh - t, sw, t, c);
+            image.fill_rect(x0, y0, t, sh, c);
+            image.fill_rect(x0 + sw - t, y0, t, sh, c);
+        }
+    }
+
+    for arrow in arrows {
+        draw_arrow(&mut image, arrow, square);
+    }
+    image
+}
+
+/// Scale a sprite to the largest square that fits, centred, with a one
+/// sprite-pixel outline where the fill meets the background.
+fn draw_sprite(
+    image: &mut Image,
+    sprite: &[&str; SPRITE_SIZE],
+    color: ChessColor,
+    x0: u32,
+    y0: u32,
+    square: (u32, u32),
+) {
+    let (fill, outline) = piece_colors(color);
+    let side = square.0.min(square.1);
+    let left = i64::from(x0 + (square.0 - side) / 2);
+    let top = i64::from(y0 + (square.1 - side) / 2);
+
+    for py in 0..side {
+        for px in 0..side {
+            let sx = (px as usize * SPRITE_SIZE / side as usize) as i32;
+            let sy = (py as usize * SPRITE_SIZE / side as usize) as i32;
+            if !sprite_filled(sprite, sx, sy) {
+                continue;
+            }
+            let edge = [(-1, 0), (1, 0), (0, -1), (0, 1)]
+                .iter()
+                .any(|(dx, dy)| !sprite_filled(sprite, sx + dx, sy + dy));
+            let color = if edge { outline } else { fill };
+            image.put(left + i64::from(px), top + i64::from(py), color);
+        }
+    }
+}
+
+/// A thick line between the square centres and a triangular head that ends
+/// at the target's centre.
+fn draw_arrow(image: &mut Image, arrow: &ImageArrow, square: (u32, u32)) {
+    let (sw, sh) = (f64::from(square.0), f64::from(square.1));
+    let centre =
+        |(col, row): (usize, usize)| (col as f64 * sw + sw / 2.0, row as f64 * sh + sh / 2.0);
+    let (x0, y0) = centre(arrow.from);
+    let (x1, y1) = centre(arrow.to);
+    let (dx, dy) = (x1 - x0, y1 - y0);
+    let len = (dx * dx + dy * dy).sqrt();
+    if len < 1.0 {
+        return;
+    }
+    let (ux, uy) = (dx / len, dy / len);
+    let unit = sw.min(sh);
+    let scale = if arrow.thin { 0.5 } else { 1.0 };
+    let half_width = (unit / 12.0 * scale).max(1.0);
+    let head_len = (unit / 2.5).min(len);
+    let head_half = unit / 4.0 * scale.max(0.75);
+    let color = rgb(arrow.color);
+
+    // Shaft up to the base of the head
+    let (bx, by) = (x1 - ux * head_len, y1 - uy * head_len);
+    let shaft = [
+        (x0 - uy * half_width, y0 + ux * half_width),
+        (bx - uy * half_width, by + ux * half_width),
+        (bx + uy * half_width, by - ux * half_width),
+        (x0 + uy * half_width, y0 - ux * half_width),
+    ];
+    fill_polygon(image, &shaft, color);
+
+    let head = [
+        (bx - uy * head_half, by + ux * head_half),
+        (x1, y1),
+        (bx + uy * head_half, by - ux * head_half),
+    ];
+    fill_polygon(image, &head, color);
+}
+
+/// Fill a convex polygon by testing pixel centres inside its bounding box.
+fn fill_polygon(image: &mut Image, points: &[(f64, f64)], color: [u8; 3]) {
+    let min_x = points.iter().map(|p| p.0).fold(f64::MAX, f64::min).floor() as i64;
+    let max_x = points.iter().map(|p| p.0).fold(f64::MIN, f64::max).ceil() as i64;
+    let min_y = points.iter().map(|p| p.1).fold(f64::MAX, f64::min).floor() as i64;
+    let max_y = points.iter().map(|p| p.1).fold(f64::MIN, f64::max).ceil() as i64;
+
+    for y in min_y..=max_y {
+        for x in min_x..=max_x {
+            let (cx, cy) = (x as f64 + 0.5, y as f64 + 0.5);
+            // Inside when on the same side of every edge
+            let mut sign = 0.0;
+            let inside = points
+                .iter()
+                .zip(points.iter().cycle().skip(1))
+                .all(|(a, b)| {
+                    let cross = (b.0 - a.0) * (cy - a.1) - (b.1 - a.1) * (cx - a.0);
+                    if cross == 0.0 {
+                        true
+                    } else if sign == 0.0 {
+                        sign = cross.signum();
+                        true
+                    } else {
+                        cross.signum() == sign
+                    }
+                });
+            if inside {
+                image.put(x, y, color);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn empty_looks() -> [SquareLook; 64] {
+        std::array::from_fn(|idx| SquareLook {
+            bg: if (idx % 8 + idx / 8) % 2 == 0 {
+                Color::Rgb(240, 217, 181)
+            } else {
+                Color::Rgb(181, 136, 99)
+            },
+            piece: None,
+            outline: None,
+        })
+    }
+
+    #[test]
+    fn test_sprites_are_square() {
+        for piece in Piece::ALL {
+            assert!(sprite(piece).iter().all(|row| row.len() == SPRITE_SIZE));
+        }
+    }
+
+    #[test]
+    fn test_rasterize_squares_pieces_and_outlines() {
+        let mut looks = empty_looks();
+        looks[0].piece = Some((Piece::Rook, ChessColor::Black));
+        looks[63].outline = Some(Color::Blue);
+        let image = rasterize(&looks, &[], (32, 32));
+
+        assert_eq!((image.width, image.height), (256, 256));
+        assert_eq!(image.get(32 + 16, 16), [181, 136, 99]);
+        // Middle of the rook's body is filled, its corner is background
+        assert_eq!(image.get(16, 16), [35, 35, 35]);
+        assert_eq!(image.get(1, 1), [240, 217, 181]);
+        assert_eq!(image.get(255, 255), rgb(Color::Blue));
+        assert_eq!(image.get(255 - 16, 255 - 16), [240, 217, 181]);
+    }
+
+    #[test]
+    fn test_arrow_runs_between_square_centres() {
+        let arrow = ImageArrow {
+            from: (0, 0),
+            to: (0, 3),
+            color: Color::Green,
+            thin: false,
+        };
+        let image = rasterize(&empty_looks(), &[arrow], (32, 32));
+        let green = rgb(Color::Green);
+        assert_eq!(image.get(16, 40), green);
+        assert_eq!(image.get(16, 3 * 32 + 10), green);
+        // Nothing beyond the target centre or beside the shaft
+        assert_ne!(image.get(16, 3 * 32 + 20), green);
+        assert_ne!(image.get(28, 40), green);
+    }
+}
@@ -1,6 +1,7 @@
 use crate::review_state::ReviewState;
+use analysis::board_analysis::{attack_density, piece_activity, AttackMap};
 use chess_client;
-use cozy_chess::Square;
+use cozy_chess::{Board, Color as Side, Square};
 use ratatui::style::Color;
 use std::collections::BTreeMap;
 
@@ -26,6 +27,9 @@ pub enum OverlayColor {
     LastMove,
     /// Green — engine best move recommendation
     BestMove,
+    /// Engine's other lines when several are searched, by line number
+    /// (2 and up); drawn lighter than the best move
+    AltLine(u8),
     /// Cyan — typeahead input match
     Typeahead,
     /// Red — blunder highlight
@@ -36,6 +40,12 @@ pub enum OverlayColor {
     Danger,
     /// Yellow/orange — tactical pattern
     Tactical,
+    /// Orange — move waiting for confirmation
+    PendingMove,
+    /// Faint violet — premoves queued during the engine's turn
+    Premove,
+    /// Heatmap shading, graded from 1 (faint) to 3 (strong)
+    Heat(HeatTone, u8),
     /// Escape hatch for arbitrary colors (light_square, dark_square)
     Custom(Color, Color),
 }
@@ -48,11 +58,17 @@ impl OverlayColor {
             Self::LegalMove => (Color::LightBlue, Color::Blue),
             Self::LastMove => (Color::LightYellow, Color::Yellow),
             Self::BestMove => (Color::LightGreen, Color::Green),
+            Self::AltLine(2) => (Color::Rgb(150, 190, 240), Color::Rgb(90, 140, 200)),
+            Self::AltLine(3) => (Color::Rgb(215, 165, 235), Color::Rgb(160, 110, 190)),
+            Self::AltLine(_) => (Color::Gray, Color::DarkGray),
             Self::Typeahead => (Color::LightCyan, Color::Cyan),
             Self::Blunder => (Color::LightRed, Color::Red),
             Self::Brilliant => (Color::LightMagenta, Color::Magenta),
             Self::Danger => (Color::LightRed, Color::Red),
             Self::Tactical => (Color::Rgb(255, 200, 100), Color::Rgb(200, 150, 50)),
+            Self::PendingMove => (Color::Rgb(255, 170, 90), Color::Rgb(215, 110, 30)),
+            Self::Premove => (Color::Rgb(185, 175, 215), Color::Rgb(130, 120, 165)),
+            Self::Heat(tone, level) => tone.shades()[level.clamp(1, 3) as usize - 1],
             Self::Custom(l, d) => (l, d),
         };
         if is_light_square {
@@ -61,6 +77,136 @@ impl OverlayColor {
             dark
         }
     }
+
+    /// Whether arrows in this color are drawn thinner, behind the best move.
+    pub fn is_secondary(self) -> bool {
+        matches!(self, Self::AltLine(_) | Self::Premove)
+    }
+}
+
+/// Hue of a heatmap square.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeatTone {
+    /// Blue — White's squares
+    White,
+    /// Red — Black's squares
+    Black,
+    /// Violet — squares both sides fight over
+    Contested,
+    /// Green — active pieces
+    Activity,
+}
+
+impl HeatTone {
+    /// (light square, dark square) shades, faintest first.
+    fn shades(self) -> [(Color, Color); 3] {
+        match self {
+            Self::White => [
+                (Color::Rgb(205, 215, 235), Color::Rgb(145, 150, 180)),
+                (Color::Rgb(170, 195, 240), Color::Rgb(110, 130, 195)),
+                (Color::Rgb(130, 170, 245), Color::Rgb(70, 105, 205)),
+            ],
+            Self::Black => [
+                (Color::Rgb(240, 200, 185), Color::Rgb(190, 130, 110)),
+                (Color::Rgb(245, 170, 150), Color::Rgb(200, 105, 85)),
+                (Color::Rgb(245, 135, 115), Color::Rgb(205, 75, 60)),
+            ],
+            Self::Contested => [
+                (Color::Rgb(225, 205, 225), Color::Rgb(170, 130, 160)),
+                (Color::Rgb(215, 175, 230), Color::Rgb(155, 105, 175)),
+                (Color::Rgb(200, 145, 230), Color::Rgb(140, 75, 175)),
+            ],
+            Self::Activity => [
+                (Color::Rgb(220, 230, 180), Color::Rgb(160, 165, 95)),
+                (Color::Rgb(195, 230, 160), Color::Rgb(130, 170, 80)),
+                (Color::Rgb(160, 225, 130), Color::Rgb(95, 165, 55)),
+            ],
+        }
+    }
+}
+
+/// Board heatmap drawn under the other highlights, cycled with `*`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Heatmap {
+    #[default]
+    Off,
+    /// Squares attacked by each side, in the colour of the side with more
+    /// attackers; shaded by how many.
+    Attacks,
+    /// Only squares attacked by both sides.
+    Contested,
+    /// Pieces shaded by how many squares they reach.
+    Activity,
+}
+
+impl Heatmap {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Off => Self::Attacks,
+            Self::Attacks => Self::Contested,
+            Self::Contested => Self::Activity,
+            Self::Activity => Self::Off,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Off => "Heatmap off",
+            Self::Attacks => "Attack density",
+            Self::Contested => "Contested squares",
+            Self::Activity => "Piece activity",
+        }
+    }
+}
+
+/// Tint `board`'s squares for `heatmap`. Call before adding highlights, so
+/// the move and selection tints are drawn over it.
+pub fn add_heatmap(overlay: &mut BoardOverlay, board: &Board, heatmap: Heatmap) {
+    match heatmap {
+        Heatmap::Off => {}
+        Heatmap::Attacks | Heatmap::Contested => {
+            let density = attack_density(&AttackMap::compute(board));
+            for sq in Square::ALL {
+                let (white, black) = (density.get(sq, Side::White), density.get(sq, Side::Black));
+                let heat = match (heatmap, white.cmp(&black)) {
+                    (Heatmap::Contested, _) if density.is_contested(sq) => {
+                        Some((HeatTone::Contested, white.min(black)))
+                    }
+                    (Heatmap::Contested, _) => None,
+                    (_, std::cmp::Ordering::Greater) => Some((HeatTone::White, white)),
+                    (_, std::cmp::Ordering::Less) => Some((HeatTone::Black, black)),
+                    (_, std::cmp::Ordering::Equal) if white > 0 => {
+                        Some((HeatTone::Contested, white))
+                    }
+                    _ => None,
+                };
+                if let Some((tone, level)) = heat {
+                    overlay.tint(sq, OverlayColor::Heat(tone, level));
+                }
+            }
+        }
+        Heatmap::Activity => {
+            let activity = piece_activity(board);
+            for sq in board.occupied() {
+                let reach = activity.get(sq, Side::White) + activity.get(sq, Side::Black);
+                let level = match reach {
+                    0 => continue,
+                    1..=3 => 1,
+                    4..=7 => 2,
+                    _ => 3,
+                };
+                overlay.tint(sq, OverlayColor::Heat(HeatTone::Activity, level));
+            }
+        }
+    }
+}
+
+/// Arrow color of engine line `line`, where line 1 is the best move.
+pub fn line_color(line: u32) -> OverlayColor {
+    match line {
+        0 | 1 => OverlayColor::BestMove,
+        n => OverlayColor::AltLine(n.min(u8::MAX as u32) as u8),
+    }
 }
 
 /// A single visual element to draw on the board.
@@ -250,10 +396,14 @@ impl BoardOverlay {
 
 /// Build a board overlay for review mode.
 ///
-/// Shows the played move as last-move tints and the engine's best move as outlined squares.
-pub fn build_review_overlay(review: &ReviewState) -> BoardOverlay {
+/// Shows the played move as last-move tints and the engine's best move as
+/// outlined squares, over `heatmap`.
+pub fn build_review_overlay(review: &ReviewState, heatmap: Heatmap) -> BoardOverlay {
     let mut overlay = BoardOverlay::new();
 
+    // Layer 0: Heatmap, under everything else
+    add_heatmap(&mut overlay, &review.board_at_ply, heatmap);
+
     // Layer 1: Played move highlights (from/to of the actual move played)
     if let Some((from, to)) = review.played_move_squares() {
         overlay.tint(from, OverlayColor::LastMove);
@@ -295,7 +445,10 @@ fn parse_square_str(sq_str: &str) -> Option<Square> {
 }
 
 /// Add tactical tag overlays to the board using the new TacticalTagProto model.
-fn add_tactical_tag_overlays(overlay: &mut BoardOverlay, tags: &[chess_client::TacticalTagProto]) {
+pub(crate) fn add_tactical_tag_overlays(
+    overlay: &mut BoardOverlay,
+    tags: &[chess_client::TacticalTagProto],
+) {
     use chess_client::TacticalTagKindProto;
 
     for tag in tags {
@@ -402,6 +555,37 @@ mod tests {
         assert_eq!(OverlayColor::BestMove.resolve(false), Color::Green);
     }
 
+    #[test]
+    fn test_attack_heatmap_shades_by_side_and_count() {
+        let mut overlay = BoardOverlay::new();
+        add_heatmap(&mut overlay, &Board::default(), Heatmap::Attacks);
+        assert_eq!(
+            overlay.square_tint(sq(File::F, Rank::Third)),
+            Some(OverlayColor::Heat(HeatTone::White, 3))
+        );
+        assert_eq!(
+            overlay.square_tint(sq(File::F, Rank::Sixth)),
+            Some(OverlayColor::Heat(HeatTone::Black, 3))
+        );
+        assert_eq!(overlay.square_tint(sq(File::E, Rank::Fourth)), None);
+
+        let mut overlay = BoardOverlay::new();
+        add_heatmap(&mut overlay, &Board::default(), Heatmap::Contested);
+        assert!(overlay.elements().is_empty());
+    }
+
+    #[test]
+    fn test_activity_heatmap_tints_pieces_that_reach_squares() {
+        let mut overlay = BoardOverlay::new();
+        add_heatmap(&mut overlay, &Board::default(), Heatmap::Activity);
+        assert_eq!(
+            overlay.square_tint(sq(File::G, Rank::First)),
+            Some(OverlayColor::Heat(HeatTone::Activity, 1))
+        );
+        assert_eq!(overlay.square_tint(sq(File::A, Rank::First)), None);
+        assert_eq!(Heatmap::Activity.next(), Heatmap::Off);
+    }
+
     #[test]
     fn test_outline() {
         let mut overlay = BoardOverlay::new();
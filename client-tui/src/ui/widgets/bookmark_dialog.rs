@@ -0,0 +1,131 @@
+use crate::review_state::ReviewState;
+use chess::is_white_ply;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget, Wrap},
+};
+
+/// State of the dialog that bookmarks the current review ply with a note.
+#[derive(Debug, Clone)]
+pub struct BookmarkDialogState {
+    pub game_id: String,
+    pub ply: u32,
+    /// The move the position came from, e.g. `12... Nf6`.
+    pub label: String,
+    pub note: String,
+    /// Whether the ply is bookmarked already, so Delete can remove it.
+    pub existing: bool,
+}
+
+impl BookmarkDialogState {
+    /// Dialog for the review's current ply, holding the note of its bookmark
+    /// if it has one.
+    pub fn for_current_ply(review: &ReviewState) -> Self {
+        let ply = review.current_ply;
+        let san = ply
+            .checked_sub(1)
+            .and_then(|i| review.move_history.get(i as usize))
+            .map(|m| m.san.as_str())
+            .unwrap_or_default();
+        let existing = review.current_bookmark();
+        Self {
+            game_id: review.review.game_id.clone(),
+            ply,
+            label: ply_label(ply, san),
+            note: existing.map(|b| b.note.clone()).unwrap_or_default(),
+            existing: existing.is_some(),
+        }
+    }
+}
+
+/// A ply as a numbered move, e.g. `12. Nf3` or `12... Nf6`; the starting
+/// position at ply 0.
+pub fn ply_label(ply: u32, san: &str) -> String {
+    if ply == 0 {
+        return "Starting position".to_string();
+    }
+    let dots = if is_white_ply(ply) { "." } else { "..." };
+    format!("{}{} {}", ply.div_ceil(2), dots, san)
+}
+
+/// Widget for the bookmark dialog, centred over the board.
+pub struct BookmarkDialogWidget<'a> {
+    pub state: &'a BookmarkDialogState,
+}
+
+impl Widget for BookmarkDialogWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let dialog_width = 50;
+        let dialog_height = 8;
+        let x = (area.width.saturating_sub(dialog_width)) / 2;
+        let y = (area.height.saturating_sub(dialog_height)) / 2;
+
+        let dialog_area = Rect {
+            x: area.x + x,
+            y: area.y + y,
+            width: dialog_width.min(area.width),
+            height: dialog_height.min(area.height),
+        };
+        Clear.render(dialog_area, buf);
+
+        let title = if self.state.existing {
+            " Edit Bookmark "
+        } else {
+            " Bookmark "
+        };
+        let block = Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .style(Style::default().bg(Color::Black));
+        let inner = block.inner(dialog_area);
+        block.render(dialog_area, buf);
+
+        let label = Style::default().fg(Color::DarkGray);
+        let footer = if self.state.existing {
+            "Enter save · Del remove · Esc cancel"
+        } else {
+            "Enter save · Esc cancel"
+        };
+        let lines = vec![
+            Line::from(vec![
+                Span::styled("After  ", label),
+                Span::raw(self.state.label.clone()),
+            ]),
+            Line::raw(""),
+            Line::from(vec![
+                Span::styled("Note   ", label),
+                Span::styled(
+                    format!("{}_", self.state.note),
+                    Style::default().fg(Color::White),
+                ),
+            ]),
+            Line::raw(""),
+            Line::from(Span::styled(footer, label)),
+        ];
+
+        Paragraph::new(lines)
+            .alignment(Alignment::Left)
+            .wrap(Wrap { trim: true })
+            .render(inner, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ply_label_numbers_moves() {
+        assert_eq!(ply_label(0, ""), "Starting position");
+        assert_eq!(ply_label(1, "e4"), "1. e4");
+        assert_eq!(ply_label(24, "Nf6"), "12... Nf6");
+    }
+}
@@ -0,0 +1,616 @@
+use super::mini_board::MiniBoardWidget;
+use chess_client::{
+    ArrowColorType, ChapterArrow, ChapterNodeProto, FinishedGameInfo, StudyChapterProto,
+};
+use cozy_chess::{Board, Color as ChessColor};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget, Wrap},
+};
+
+/// NAGs the number keys toggle, with the move suffixes they stand for.
+pub const NAG_KEYS: [(u32, &str); 6] = [
+    (1, "!"),
+    (2, "?"),
+    (3, "!!"),
+    (4, "??"),
+    (5, "!?"),
+    (6, "?!"),
+];
+
+const ARROW_COLORS: [(char, ArrowColorType, &str); 4] = [
+    ('G', ArrowColorType::ArrowColorGreen, "green"),
+    ('R', ArrowColorType::ArrowColorRed, "red"),
+    ('Y', ArrowColorType::ArrowColorYellow, "yellow"),
+    ('B', ArrowColorType::ArrowColorBlue, "blue"),
+];
+
+const CHAPTER_KEYS: &str =
+    " \u{2191}/\u{2193}: Select | \u{2192}: Play | \u{2190}: Back | m: Move | c: Comment | a/A: Arrow/Clear | 1-6: NAG | x: Delete | Esc: Chapters ";
+
+pub fn node(chapter: &StudyChapterProto, id: u32) -> Option<&ChapterNodeProto> {
+    chapter.nodes.iter().find(|n| n.id == id)
+}
+
+/// The moves from the start of the chapter to node `id`, the root left out.
+pub fn line_to(chapter: &StudyChapterProto, id: u32) -> Vec<&ChapterNodeProto> {
+    let mut line = vec![];
+    let mut next = node(chapter, id);
+    while let Some(n) = next {
+        if n.parent.is_none() || line.len() > chapter.nodes.len() {
+            break;
+        }
+        line.push(n);
+        next = n.parent.and_then(|p| node(chapter, p));
+    }
+    line.reverse();
+    line
+}
+
+/// Plies played before `fen`, counted from 1. e4.
+fn start_ply(fen: &str) -> u32 {
+    let fields: Vec<&str> = fen.split_whitespace().collect();
+    let black = fields.get(1) == Some(&"b");
+    let number = fields
+        .get(5)
+        .and_then(|n| n.parse::<u32>().ok())
+        .unwrap_or(1)
+        .max(1);
+    (number - 1) * 2 + u32::from(black)
+}
+
+/// Move suffixes for NAGs 1-6, `$n` for the others, e.g. "!? $14".
+pub fn nag_suffix(nags: &[u32]) -> String {
+    let mut suffix = String::new();
+    for &nag in nags {
+        match NAG_KEYS.iter().find(|(n, _)| *n == nag) {
+            Some((_, glyph)) => suffix.push_str(glyph),
+            None => suffix.push_str(&format!(" ${}", nag)),
+        }
+    }
+    suffix
+}
+
+/// The moves leading to node `id` with move numbers and NAGs, e.g.
+/// "1. e4 c5 2. Nf3!", or "12... Qd7" for a chapter starting with Black.
+pub fn line_label(chapter: &StudyChapterProto, id: u32) -> String {
+    let start = chapter.nodes.first().map_or(0, |root| start_ply(&root.fen));
+    let mut words = vec![];
+    for (i, n) in line_to(chapter, id).into_iter().enumerate() {
+        let ply = start + i as u32;
+        if ply % 2 == 0 {
+            words.push(format!("{}.", ply / 2 + 1));
+        } else if i == 0 {
+            words.push(format!("{}...", ply / 2 + 1));
+        }
+        words.push(format!("{}{}", n.san, nag_suffix(&n.nags)));
+    }
+    words.join(" ")
+}
+
+/// An arrow typed as two squares, with an optional colour letter first:
+/// "e2e4" is green, "Re2e4" red.
+pub fn parse_arrow(text: &str) -> Option<ChapterArrow> {
+    let text = text.trim();
+    if !text.is_ascii() {
+        return None;
+    }
+    let (color, squares) = match text.len() {
+        4 => (ArrowColorType::ArrowColorGreen, text),
+        5 => {
+            let letter = text.chars().next()?.to_ascii_uppercase();
+            let (_, color, _) = ARROW_COLORS.iter().find(|(c, _, _)| *c == letter)?;
+            (*color, &text[1..])
+        }
+        _ => return None,
+    };
+    let squares = squares.to_lowercase();
+    let is_square = |s: &str| {
+        let mut chars = s.chars();
+        matches!(
+            (chars.next(), chars.next()),
+            (Some('a'..='h'), Some('1'..='8'))
+        )
+    };
+    let (from, to) = squares.split_at(2);
+    if !is_square(from) || !is_square(to) || from == to {
+        return None;
+    }
+    Some(ChapterArrow {
+        from: from.to_string(),
+        to: to.to_string(),
+        color: color as i32,
+    })
+}
+
+/// An arrow as shown in the viewer, e.g. "e2-e4 red".
+pub fn arrow_label(arrow: &ChapterArrow) -> String {
+    let color = ARROW_COLORS
+        .iter()
+        .find(|(_, c, _)| *c as i32 == arrow.color)
+        .map_or("green", |(_, _, name)| name);
+    format!("{}-{} {}", arrow.from, arrow.to, color)
+}
+
+/// Rows of the chapter list: number, name, moves, source.
+pub fn chapter_rows(chapters: &[StudyChapterProto]) -> Vec<Vec<String>> {
+    chapters
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            vec![
+                (i + 1).to_string(),
+                c.name.clone(),
+                c.nodes.len().saturating_sub(1).to_string(),
+                if c.game_id.is_some() {
+                    "game".to_string()
+                } else {
+                    "position".to_string()
+                },
+            ]
+        })
+        .collect()
+}
+
+/// Text being typed in the chapter viewer.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChapterInput {
+    Move(String),
+    Comment(String),
+    Arrow(String),
+}
+
+impl ChapterInput {
+    pub fn text_mut(&mut self) -> &mut String {
+        match self {
+            Self::Move(text) | Self::Comment(text) | Self::Arrow(text) => text,
+        }
+    }
+
+    fn prompt(&self) -> (&'static str, &str) {
+        match self {
+            Self::Move(text) => ("Move (UCI or SAN): ", text),
+            Self::Comment(text) => ("Comment: ", text),
+            Self::Arrow(text) => ("Arrow (e2e4, R/Y/B first for a colour): ", text),
+        }
+    }
+}
+
+/// A chapter's move tree, one position at a time: the board, the line
+/// leading to it, what was written about it and the moves from it.
+pub struct ChapterView<'a> {
+    pub chapter: &'a StudyChapterProto,
+    pub node_id: u32,
+    pub selected: usize,
+    pub input: Option<&'a ChapterInput>,
+    pub status: Option<&'a str>,
+}
+
+impl Widget for ChapterView<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let block = Block::default()
+            .title(format!(" {} ", self.chapter.name))
+            .title_bottom(Line::from(CHAPTER_KEYS).centered())
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .style(Style::default().bg(Color::Black));
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let Some(current) = node(self.chapter, self.node_id) else {
+            return;
+        };
+
+        // Board on the left, from the side that moves first in the chapter
+        let board_width = 20.min(inner.width);
+        if let Ok(board) = current.fen.parse::<Board>() {
+            let flipped = self
+                .chapter
+                .nodes
+                .first()
+                .and_then(|root| root.fen.parse::<Board>().ok())
+                .is_some_and(|root| root.side_to_move() == ChessColor::Black);
+            MiniBoardWidget {
+                board: &board,
+                flipped,
+            }
+            .render(
+                Rect {
+                    width: board_width,
+                    height: 11.min(inner.height),
+                    ..inner
+                },
+                buf,
+            );
+        }
+        let text_area = Rect {
+            x: inner.x + board_width + 1,
+            width: inner.width.saturating_sub(board_width + 1),
+            ..inner
+        };
+
+        let dim = Style::default().fg(Color::DarkGray);
+        let line = line_label(self.chapter, self.node_id);
+        let mut lines = vec![
+            Line::from(vec![
+                Span::styled("Line: ", dim),
+                Span::styled(
+                    if line.is_empty() {
+                        "start position".to_string()
+                    } else {
+                        line
+                    },
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                ),
+            ]),
+            Line::raw(""),
+        ];
+        if let Some(comment) = &current.comment {
+            lines.push(Line::from(Span::styled(
+                comment.clone(),
+                Style::default().fg(Color::White),
+            )));
+        }
+        if !current.arrows.is_empty() {
+            let arrows: Vec<String> = current.arrows.iter().map(arrow_label).collect();
+            lines.push(Line::from(vec![
+                Span::styled("Arrows: ", dim),
+                Span::styled(arrows.join(", "), Style::default().fg(Color::Magenta)),
+            ]));
+        }
+        lines.push(Line::raw(""));
+
+        if current.children.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "No moves from here yet; m adds one.",
+                dim,
+            )));
+        }
+        for (i, &child_id) in current.children.iter().enumerate() {
+            let Some(child) = node(self.chapter, child_id) else {
+                continue;
+            };
+            let mut style = Style::default().fg(Color::White);
+            if i == self.selected {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+            let mut spans = vec![Span::styled(
+                format!(
+                    "{:<10}",
+                    format!("{}{}", child.san, nag_suffix(&child.nags))
+                ),
+                style,
+            )];
+            if i == 0 {
+                spans.push(Span::styled(" main line", dim));
+            }
+            if child.comment.is_some() {
+                spans.push(Span::styled(" (comment)", dim));
+            }
+            lines.push(Line::from(spans));
+        }
+
+        lines.push(Line::raw(""));
+        if let Some(input) = self.input {
+            let (prompt, text) = input.prompt();
+            lines.push(Line::from(vec![
+                Span::styled(prompt, Style::default().fg(Color::Yellow)),
+                Span::styled(format!("{}_", text), Style::default().fg(Color::Green)),
+            ]));
+        } else if let Some(status) = self.status {
+            lines.push(Line::from(Span::styled(
+                status.to_string(),
+                Style::default().fg(Color::Red),
+            )));
+        }
+
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .render(text_area, buf);
+    }
+}
+
+/// Where a new chapter starts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChapterSourceOption {
+    StartPosition,
+    Fen,
+    Game,
+}
+
+const SOURCES: [ChapterSourceOption; 3] = [
+    ChapterSourceOption::StartPosition,
+    ChapterSourceOption::Fen,
+    ChapterSourceOption::Game,
+];
+
+/// A chapter being set up in the New Chapter dialog.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChapterDraft {
+    pub name: String,
+    pub source: ChapterSourceOption,
+    pub fen: String,
+    /// Index into the finished games when starting from a game.
+    pub game: usize,
+    /// Field being edited: 0 name, 1 source, 2 FEN or game.
+    pub field: usize,
+    pub error: Option<String>,
+}
+
+impl Default for ChapterDraft {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            source: ChapterSourceOption::StartPosition,
+            fen: String::new(),
+            game: 0,
+            field: 0,
+            error: None,
+        }
+    }
+}
+
+/// A finished game as the dialog offers it, e.g. "WhiteWins in 34 moves".
+fn game_label(game: &FinishedGameInfo) -> String {
+    match &game.opponent {
+        Some(opponent) => format!(
+            "{} in {} moves vs {}",
+            game.result, game.move_count, opponent
+        ),
+        None => format!("{} in {} moves", game.result, game.move_count),
+    }
+}
+
+impl ChapterDraft {
+    /// The start position has no third field to edit.
+    fn fields(&self) -> usize {
+        if self.source == ChapterSourceOption::StartPosition {
+            2
+        } else {
+            3
+        }
+    }
+
+    pub fn next_field(&mut self) {
+        self.field = (self.field + 1) % self.fields();
+    }
+
+    pub fn previous_field(&mut self) {
+        self.field = (self.field + self.fields() - 1) % self.fields();
+    }
+
+    /// Cycle the source, or the game when starting from one.
+    pub fn cycle(&mut self, direction: i32, games: usize) {
+        match self.field {
+            1 => {
+                let index = SOURCES.iter().position(|&s| s == self.source).unwrap_or(0) as i32;
+                self.source =
+                    SOURCES[(index + direction).rem_euclid(SOURCES.len() as i32) as usize];
+            }
+            2 if self.source == ChapterSourceOption::Game && games > 0 => {
+                self.game = (self.game as i32 + direction).rem_euclid(games as i32) as usize;
+            }
+            _ => {}
+        }
+    }
+
+    /// Type into the name, or the FEN when starting from one.
+    pub fn push_char(&mut self, c: char) {
+        match self.field {
+            0 if self.name.len() < 40 => self.name.push(c),
+            2 if self.source == ChapterSourceOption::Fen && self.fen.len() < 100 => {
+                self.fen.push(c)
+            }
+            _ => {}
+        }
+    }
+
+    pub fn pop_char(&mut self) {
+        match self.field {
+            0 => self.name.pop(),
+            2 => self.fen.pop(),
+            _ => None,
+        };
+    }
+
+    /// The name, FEN and game id to add the chapter with.
+    pub fn parse(
+        &self,
+        games: &[FinishedGameInfo],
+    ) -> Result<(String, Option<String>, Option<String>), String> {
+        let name = self.name.trim();
+        if name.is_empty() {
+            return Err("Enter a name for the chapter".to_string());
+        }
+        match self.source {
+            ChapterSourceOption::StartPosition => Ok((name.to_string(), None, None)),
+            ChapterSourceOption::Fen => {
+                let fen = self.fen.trim();
+                if fen.parse::<Board>().is_err() {
+                    return Err("Enter a valid FEN".to_string());
+                }
+                Ok((name.to_string(), Some(fen.to_string()), None))
+            }
+            ChapterSourceOption::Game => match games.get(self.game) {
+                Some(game) => Ok((name.to_string(), None, Some(game.game_id.clone()))),
+                None => Err("No finished games to start from".to_string()),
+            },
+        }
+    }
+}
+
+/// Dialog for adding a chapter.
+pub struct ChapterDialog<'a> {
+    pub draft: &'a ChapterDraft,
+    pub games: &'a [FinishedGameInfo],
+}
+
+impl Widget for ChapterDialog<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let width = area.width.min(64);
+        let height = area.height.min(9);
+        let area = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        };
+        Clear.render(area, buf);
+
+        let block = Block::default()
+            .title(" New Chapter ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow))
+            .style(Style::default().bg(Color::Black));
+
+        let draft = self.draft;
+        let field = |index: usize, name: &'static str, value: String, cycles: bool| {
+            let style = if draft.field == index {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            Line::from(vec![
+                Span::styled(if draft.field == index { "> " } else { "  " }, style),
+                Span::styled(format!("{:<8}", name), style),
+                Span::styled(value, style.fg(Color::Green)),
+                Span::styled(
+                    if cycles { " [\u{2190}/\u{2192}]" } else { "" },
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ])
+        };
+
+        let source = match draft.source {
+            ChapterSourceOption::StartPosition => "start position",
+            ChapterSourceOption::Fen => "FEN",
+            ChapterSourceOption::Game => "finished game",
+        };
+        let mut lines = vec![
+            field(0, "Name", draft.name.clone(), false),
+            field(1, "From", source.to_string(), true),
+        ];
+        match draft.source {
+            ChapterSourceOption::StartPosition => lines.push(Line::raw("")),
+            ChapterSourceOption::Fen => lines.push(field(2, "FEN", draft.fen.clone(), false)),
+            ChapterSourceOption::Game => {
+                let game = self
+                    .games
+                    .get(draft.game)
+                    .map_or("no finished games".to_string(), game_label);
+                lines.push(field(2, "Game", game, true));
+            }
+        }
+        lines.push(Line::raw(""));
+        lines.push(match &draft.error {
+            Some(error) => Line::from(Span::styled(error.clone(), Style::default().fg(Color::Red))),
+            None => Line::from(Span::styled(
+                "Enter: Add | Esc: Cancel",
+                Style::default().fg(Color::DarkGray),
+            )),
+        });
+
+        Paragraph::new(lines).block(block).render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chapter(start_fen: &str, moves: &[(u32, u32, &str)]) -> StudyChapterProto {
+        let mut nodes = vec![ChapterNodeProto {
+            fen: start_fen.to_string(),
+            ..Default::default()
+        }];
+        for &(id, parent, san) in moves {
+            nodes[parent as usize].children.push(id);
+            nodes.push(ChapterNodeProto {
+                id,
+                parent: Some(parent),
+                san: san.to_string(),
+                ..Default::default()
+            });
+        }
+        StudyChapterProto {
+            chapter_id: "c1".to_string(),
+            name: "Test".to_string(),
+            game_id: None,
+            nodes,
+        }
+    }
+
+    #[test]
+    fn test_line_label() {
+        let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        let mut c = chapter(
+            start,
+            &[(1, 0, "e4"), (2, 1, "c5"), (3, 2, "Nf3"), (4, 1, "e5")],
+        );
+        c.nodes[3].nags = vec![1, 14];
+        assert_eq!(line_label(&c, 3), "1. e4 c5 2. Nf3! $14");
+        assert_eq!(line_label(&c, 4), "1. e4 e5");
+        assert_eq!(line_label(&c, 0), "");
+
+        let black = chapter(
+            "4k3/8/8/8/8/8/8/4K3 b - - 3 12",
+            &[(1, 0, "Kd7"), (2, 1, "Kd2")],
+        );
+        assert_eq!(line_label(&black, 2), "12... Kd7 13. Kd2");
+    }
+
+    #[test]
+    fn test_parse_arrow() {
+        let arrow = parse_arrow("e2e4").unwrap();
+        assert_eq!((arrow.from.as_str(), arrow.to.as_str()), ("e2", "e4"));
+        assert_eq!(arrow.color, ArrowColorType::ArrowColorGreen as i32);
+        let red = parse_arrow(" rD7D5 ").unwrap();
+        assert_eq!(red.color, ArrowColorType::ArrowColorRed as i32);
+        assert_eq!(arrow_label(&red), "d7-d5 red");
+        for bad in ["e2e2", "e2e9", "Xe2e4", "e4", ""] {
+            assert!(parse_arrow(bad).is_none(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn test_chapter_draft() {
+        let games = vec![FinishedGameInfo {
+            game_id: "g1".to_string(),
+            result: "Draw".to_string(),
+            move_count: 30,
+            ..Default::default()
+        }];
+        let mut draft = ChapterDraft::default();
+        assert!(draft.parse(&games).is_err(), "a chapter needs a name");
+        for c in "Lucena".chars() {
+            draft.push_char(c);
+        }
+        assert_eq!(draft.parse(&games), Ok(("Lucena".to_string(), None, None)));
+
+        draft.next_field();
+        draft.cycle(1, games.len());
+        assert_eq!(draft.source, ChapterSourceOption::Fen);
+        draft.next_field();
+        for c in "not a fen".chars() {
+            draft.push_char(c);
+        }
+        assert!(draft.parse(&games).is_err());
+
+        draft.previous_field();
+        draft.cycle(1, games.len());
+        assert_eq!(
+            draft.parse(&games),
+            Ok(("Lucena".to_string(), None, Some("g1".to_string())))
+        );
+        assert!(draft.parse(&[]).is_err());
+    }
+}
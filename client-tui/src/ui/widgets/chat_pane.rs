@@ -0,0 +1,148 @@
+use chess_client::ChatMessage;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+
+/// Longest message the server accepts, in characters.
+pub const MAX_DRAFT_CHARS: usize = 500;
+
+/// Message being typed and how far the chat is scrolled back.
+#[derive(Debug, Clone, Default)]
+pub struct ChatPaneState {
+    pub draft: String,
+    /// Lines scrolled up from the newest message.
+    pub scroll: u16,
+}
+
+impl ChatPaneState {
+    /// Take the draft to send, leaving it empty. A blank draft is kept.
+    pub fn take_draft(&mut self) -> Option<String> {
+        if self.draft.trim().is_empty() {
+            return None;
+        }
+        self.scroll = 0;
+        Some(std::mem::take(&mut self.draft))
+    }
+
+    pub fn push_char(&mut self, c: char) {
+        if self.draft.chars().count() < MAX_DRAFT_CHARS {
+            self.draft.push(c);
+        }
+    }
+}
+
+/// Author colour; your own messages stand out from the others'.
+fn author_style(own: bool) -> Style {
+    let color = if own { Color::Green } else { Color::Cyan };
+    Style::default().fg(color).add_modifier(Modifier::BOLD)
+}
+
+/// Chat of the session docked at the bottom right, newest message last,
+/// with the draft on its bottom line.
+pub struct ChatPaneWidget<'a> {
+    pub messages: &'a [ChatMessage],
+    pub state: &'a ChatPaneState,
+    /// Author name this client sends as.
+    pub own_author: &'a str,
+}
+
+impl Widget for ChatPaneWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let width = (area.width * 4 / 10).max(36).min(area.width);
+        let height = (area.height / 2).max(8).min(area.height);
+        let pane_area = Rect::new(area.right() - width, area.bottom() - height, width, height);
+
+        Clear.render(pane_area, buf);
+
+        let block = Block::default()
+            .title(" Chat ")
+            .title_bottom(" Enter: Send  \u{2191}/\u{2193}: Scroll  Esc: Close ")
+            .borders(Borders::ALL)
+            .border_style(
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .style(Style::default().bg(Color::Black));
+
+        let inner = block.inner(pane_area);
+        block.render(pane_area, buf);
+        if inner.height < 2 {
+            return;
+        }
+
+        let log_area = Rect::new(inner.x, inner.y, inner.width, inner.height - 1);
+        let lines: Vec<Line<'static>> = if self.messages.is_empty() {
+            vec![Line::from(Span::styled(
+                "No messages yet",
+                Style::default().fg(Color::DarkGray),
+            ))]
+        } else {
+            self.messages
+                .iter()
+                .map(|m| {
+                    Line::from(vec![
+                        Span::styled(
+                            format!("{}: ", m.author),
+                            author_style(m.author == self.own_author),
+                        ),
+                        Span::raw(m.text.clone()),
+                    ])
+                })
+                .collect()
+        };
+        // Wrapped lines are not counted, so a long message may push the
+        // newest one out of view until scrolled
+        let bottom = (lines.len() as u16).saturating_sub(log_area.height);
+        Paragraph::new(lines)
+            .scroll((bottom.saturating_sub(self.state.scroll), 0))
+            .render(log_area, buf);
+
+        let prompt_area = Rect::new(inner.x, inner.bottom() - 1, inner.width, 1);
+        // Show the end of a draft wider than the pane
+        let visible = (inner.width as usize).saturating_sub(3);
+        let skip = self.state.draft.chars().count().saturating_sub(visible);
+        let draft: String = self.state.draft.chars().skip(skip).collect();
+        Paragraph::new(Line::from(vec![
+            Span::styled("> ", Style::default().fg(Color::Yellow)),
+            Span::raw(draft),
+            Span::styled("_", Style::default().add_modifier(Modifier::SLOW_BLINK)),
+        ]))
+        .render(prompt_area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_take_draft_skips_blank_messages() {
+        let mut state = ChatPaneState {
+            draft: "  ".to_string(),
+            scroll: 3,
+        };
+        assert_eq!(state.take_draft(), None);
+        assert_eq!(state.scroll, 3);
+
+        for c in "gg".chars() {
+            state.push_char(c);
+        }
+        assert_eq!(state.take_draft().as_deref(), Some("  gg"));
+        assert!(state.draft.is_empty());
+        assert_eq!(state.scroll, 0);
+    }
+
+    #[test]
+    fn test_draft_stops_at_the_server_limit() {
+        let mut state = ChatPaneState::default();
+        for _ in 0..MAX_DRAFT_CHARS + 5 {
+            state.push_char('x');
+        }
+        assert_eq!(state.draft.chars().count(), MAX_DRAFT_CHARS);
+    }
+}
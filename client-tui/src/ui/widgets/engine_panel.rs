@@ -1,4 +1,7 @@
+use super::board_overlay::line_color;
+use chess::{convert_uci_castling_to_cozy, format_move_as_san, format_square, Notation};
 use chess_client::EngineInfo;
+use cozy_chess::{Board, Move};
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
@@ -6,12 +9,23 @@ use ratatui::{
     text::{Line, Span},
     widgets::{Block, Borders, Paragraph, Widget},
 };
+use std::collections::BTreeMap;
+
+/// Plies of each line shown in the lines legend.
+const LEGEND_PLIES: usize = 3;
 
 pub struct EngineAnalysisPanel<'a> {
     pub engine_info: Option<&'a EngineInfo>,
     pub is_thinking: bool,
     pub scroll: u16,
     pub is_selected: bool,
+    /// Position the engine is searching, for writing the PV as SAN.
+    pub position: Option<&'a Board>,
+    /// Show the PV in raw UCI, as the engine sent it.
+    pub raw_pv: bool,
+    pub notation: Notation,
+    /// The engine's other lines when it searches several, by line number.
+    pub alt_lines: Option<&'a BTreeMap<u32, EngineInfo>>,
 }
 
 impl<'a> EngineAnalysisPanel<'a> {
@@ -26,6 +40,70 @@ impl<'a> EngineAnalysisPanel<'a> {
             is_thinking,
             scroll,
             is_selected,
+            position: None,
+            raw_pv: false,
+            notation: Notation::default(),
+            alt_lines: None,
+        }
+    }
+
+    pub fn with_alt_lines(mut self, lines: Option<&'a BTreeMap<u32, EngineInfo>>) -> Self {
+        self.alt_lines = lines.filter(|lines| !lines.is_empty());
+        self
+    }
+
+    /// One legend row: the line's arrow, number, score and first moves.
+    fn line_row(&self, line: u32, info: &EngineInfo) -> Line<'static> {
+        let color = line_color(line);
+        let arrow = if color.is_secondary() {
+            "┄▶"
+        } else {
+            "━▶"
+        };
+        let (score, score_color) = info
+            .score
+            .as_deref()
+            .map_or(("?".to_string(), Color::DarkGray), parse_score);
+        let start = &info.pv[..info.pv.len().min(LEGEND_PLIES)];
+        let moves = match self.position {
+            Some(board) if !self.raw_pv => pv_to_san(board, start, &self.notation),
+            _ => None,
+        }
+        .unwrap_or_else(|| start.join(" "));
+        Line::from(vec![
+            Span::styled(arrow, Style::default().fg(color.resolve(false))),
+            Span::styled(format!(" {} ", line), Style::default().fg(Color::DarkGray)),
+            Span::styled(format!("{:>6} ", score), Style::default().fg(score_color)),
+            Span::styled(moves, Style::default().fg(Color::White)),
+        ])
+    }
+
+    pub fn with_position(mut self, position: Option<&'a Board>) -> Self {
+        self.position = position;
+        self
+    }
+
+    pub fn with_raw_pv(mut self, raw_pv: bool) -> Self {
+        self.raw_pv = raw_pv;
+        self
+    }
+
+    pub fn with_notation(mut self, notation: Notation) -> Self {
+        self.notation = notation;
+        self
+    }
+
+    /// The PV as SAN from the current position, or raw UCI when asked for
+    /// or when it does not fit the position (the engine info can still be
+    /// from before the last move).
+    fn pv_text(&self, pv: &[String]) -> (String, &'static str) {
+        let san = match self.position {
+            Some(board) if !self.raw_pv => pv_to_san(board, pv, &self.notation),
+            _ => None,
+        };
+        match san {
+            Some(san) => (san, "Principal Variation:"),
+            None => (pv.join(" "), "Principal Variation (UCI):"),
         }
     }
 }
@@ -118,18 +196,33 @@ impl Widget for EngineAnalysisPanel<'_> {
                 ]));
             }
 
+            // Legend for the arrows of several lines
+            if let Some(alt_lines) = self.alt_lines {
+                lines.push(Line::from(""));
+                lines.push(Line::from(Span::styled(
+                    "Lines:",
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                )));
+                lines.push(self.line_row(1, info));
+                for (&line, alt) in alt_lines {
+                    lines.push(self.line_row(line, alt));
+                }
+            }
+
             // Principal Variation
             if !info.pv.is_empty() {
+                let (pv_text, pv_title) = self.pv_text(&info.pv);
                 lines.push(Line::from(""));
                 lines.push(Line::from(Span::styled(
-                    "Principal Variation:",
+                    pv_title,
                     Style::default()
                         .fg(Color::Yellow)
                         .add_modifier(Modifier::BOLD),
                 )));
 
                 // Display PV moves (wrap if too long)
-                let pv_text = info.pv.join(" ");
                 let max_width = (inner.width as usize).saturating_sub(2);
 
                 for chunk in wrap_text(&pv_text, max_width) {
@@ -163,7 +256,7 @@ impl Widget for EngineAnalysisPanel<'_> {
 
 // Helper functions
 
-fn parse_score(score: &str) -> (String, Color) {
+pub fn parse_score(score: &str) -> (String, Color) {
     // Score format: "cp 25" (centipawns) or "mate 5" (mate in 5)
     let parts: Vec<&str> = score.split_whitespace().collect();
     if parts.len() < 2 {
@@ -205,6 +298,45 @@ fn parse_score(score: &str) -> (String, Color) {
     }
 }
 
+/// Play a UCI PV out from `board` and write it as numbered SAN
+/// (`12. Nf3 Nc6 13. d4`, or `12... Nc6 13. d4` with Black to move).
+/// `None` if a move is not legal where it is played.
+pub fn pv_to_san(board: &Board, pv: &[String], notation: &Notation) -> Option<String> {
+    let mut board = board.clone();
+    let mut words = Vec::new();
+    for (i, uci) in pv.iter().enumerate() {
+        let mut legal = Vec::new();
+        board.generate_moves(|moves| {
+            legal.extend(moves);
+            false
+        });
+        let mv = convert_uci_castling_to_cozy(uci.parse::<Move>().ok()?, &legal);
+        if !legal.contains(&mv) {
+            return None;
+        }
+
+        let number = board.fullmove_number();
+        if board.side_to_move() == cozy_chess::Color::White {
+            words.push(format!("{}.", number));
+        } else if i == 0 {
+            words.push(format!("{}...", number));
+        }
+        let piece = board.piece_on(mv.from);
+        let capture = board.color_on(mv.to) == Some(!board.side_to_move())
+            || (piece == Some(cozy_chess::Piece::Pawn) && mv.from.file() != mv.to.file());
+        let san = format_move_as_san(&board, mv);
+        words.push(notation.format_with_squares(
+            &san,
+            &format_square(mv.from),
+            &format_square(mv.to),
+            piece,
+            capture,
+        ));
+        board.play_unchecked(mv);
+    }
+    Some(words.join(" "))
+}
+
 fn format_number(n: u64) -> String {
     // Format large numbers with thousands separators
     let s = n.to_string();
@@ -262,3 +394,81 @@ fn wrap_text(text: &str, max_width: usize) -> Vec<String> {
 
     lines
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chess::{NotationStyle, PieceLanguage};
+
+    fn pv(moves: &[&str]) -> Vec<String> {
+        moves.iter().map(|m| m.to_string()).collect()
+    }
+
+    #[test]
+    fn test_pv_to_san_numbers_moves() {
+        let notation = Notation::default();
+        let board = Board::default();
+        assert_eq!(
+            pv_to_san(&board, &pv(&["e2e4", "e7e5", "g1f3"]), &notation).as_deref(),
+            Some("1. e4 e5 2. Nf3")
+        );
+
+        let black: Board = "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            pv_to_san(&black, &pv(&["e7e5", "g1f3"]), &notation).as_deref(),
+            Some("1... e5 2. Nf3")
+        );
+    }
+
+    #[test]
+    fn test_pv_to_san_castles_and_notation() {
+        let board: Board = "r3k2r/pppq1ppp/2npbn2/4p3/4P3/2NPBN2/PPPQ1PPP/R3K2R w KQkq - 0 8"
+            .parse()
+            .unwrap();
+        let figurine = Notation {
+            style: NotationStyle::Figurine,
+            language: PieceLanguage::English,
+        };
+        assert_eq!(
+            pv_to_san(&board, &pv(&["e1g1", "e8c8", "f3g5"]), &figurine).as_deref(),
+            Some("8. O-O O-O-O 9. ♘g5")
+        );
+    }
+
+    #[test]
+    fn test_line_row_shows_first_moves() {
+        let info = EngineInfo {
+            score: Some("cp 35".to_string()),
+            pv: pv(&["d2d4", "d7d5", "c2c4", "e7e6"]),
+            ..Default::default()
+        };
+        let board = Board::default();
+        let panel = EngineAnalysisPanel::new(None, true, 0, false).with_position(Some(&board));
+        let row: String = panel
+            .line_row(2, &info)
+            .spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect();
+        assert!(row.starts_with("┄▶ 2 "), "{}", row);
+        assert!(row.ends_with("1. d4 d5 2. c4"), "{}", row);
+    }
+
+    #[test]
+    fn test_pv_to_san_rejects_stale_pv() {
+        // A PV from before 1. e4 no longer fits the position
+        let board: Board = "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            pv_to_san(&board, &pv(&["e2e4"]), &Notation::default()),
+            None
+        );
+        assert_eq!(
+            pv_to_san(&board, &pv(&["junk"]), &Notation::default()),
+            None
+        );
+    }
+}
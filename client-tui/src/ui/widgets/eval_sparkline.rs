@@ -0,0 +1,156 @@
+use super::timeline_scrubber::{column_for_ply, critical_color, eval_at_ply, ply_at_column};
+use crate::review_state::ReviewState;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Widget},
+};
+
+/// Height of the graph including its border.
+pub const SPARKLINE_HEIGHT: u16 = 4;
+
+/// Change in evaluation over one move, in centipawns, that counts as a swing
+/// for `{`/`}`.
+const SWING_CP: i32 = 150;
+
+/// Braille dots are two columns of four; bit of the dot at (column, row).
+const BRAILLE_DOTS: [[u8; 4]; 2] = [[0x01, 0x02, 0x04, 0x40], [0x08, 0x10, 0x20, 0x80]];
+
+/// Dot row of an evaluation on a graph `dot_rows` dots high: +500 at the
+/// top, -500 at the bottom.
+fn dot_row(cp: i32, dot_rows: u16) -> u16 {
+    let last = i32::from(dot_rows.saturating_sub(1));
+    let from_top = (500 - cp.clamp(-500, 500)) * last;
+    ((from_top + 500) / 1000) as u16
+}
+
+/// Plies whose move swung the evaluation by at least [`SWING_CP`], in order.
+pub fn eval_swings(review_state: &ReviewState) -> Vec<u32> {
+    (1..=review_state.review.total_plies)
+        .filter(|&ply| {
+            match (
+                eval_at_ply(review_state, ply - 1),
+                eval_at_ply(review_state, ply),
+            ) {
+                (Some(before), Some(after)) => (after - before).abs() >= SWING_CP,
+                _ => false,
+            }
+        })
+        .collect()
+}
+
+/// Ply shown by the graph cell at `col` of a track `width` cells wide.
+pub fn ply_at_cell(col: u16, width: u16, total_plies: u32) -> u32 {
+    ply_at_column(col.saturating_mul(2), width.saturating_mul(2), total_plies)
+}
+
+/// Evaluation over the whole reviewed game as a braille sparkline, filled
+/// from the level line, with the current ply highlighted.
+pub struct EvalSparkline<'a> {
+    pub review_state: &'a ReviewState,
+}
+
+impl EvalSparkline<'_> {
+    /// Area of the track inside the border, for mapping mouse positions.
+    pub fn track_area(area: Rect) -> Rect {
+        Block::default().borders(Borders::ALL).inner(area)
+    }
+}
+
+impl Widget for EvalSparkline<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let total = self.review_state.review.total_plies;
+        let current = self.review_state.current_ply;
+
+        let block = Block::default()
+            .title(" Eval ")
+            .title_bottom(" {/}: Swings ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::DarkGray));
+        let track = block.inner(area);
+        block.render(area, buf);
+        if track.width == 0 || track.height == 0 {
+            return;
+        }
+
+        let (dot_cols, dot_rows) = (track.width * 2, track.height * 4);
+        let level = dot_row(0, dot_rows);
+        let mut cells = vec![0u8; usize::from(track.width) * usize::from(track.height)];
+        for x in 0..dot_cols {
+            let ply = ply_at_column(x, dot_cols, total);
+            // Unanalysed plies only keep the level line
+            let y = eval_at_ply(self.review_state, ply).map_or(level, |cp| dot_row(cp, dot_rows));
+            for dy in y.min(level)..=y.max(level) {
+                let cell = usize::from(dy / 4) * usize::from(track.width) + usize::from(x / 2);
+                cells[cell] |= BRAILLE_DOTS[usize::from(x % 2)][usize::from(dy % 4)];
+            }
+        }
+
+        let cursor = column_for_ply(current, dot_cols, total) / 2;
+        for col in 0..track.width {
+            let style = if col == cursor {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .bg(Color::DarkGray)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                let fg = [col * 2, col * 2 + 1]
+                    .into_iter()
+                    .find_map(|x| {
+                        critical_color(self.review_state, ply_at_column(x, dot_cols, total))
+                    })
+                    .unwrap_or(Color::Gray);
+                Style::default().fg(fg)
+            };
+            for row in 0..track.height {
+                let bits = cells[usize::from(row) * usize::from(track.width) + usize::from(col)];
+                let symbol = char::from_u32(0x2800 + u32::from(bits)).unwrap_or(' ');
+                buf[(track.x + col, track.y + row)]
+                    .set_char(symbol)
+                    .set_style(style);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chess_client::{review_score, GameReviewProto, PositionReview, ReviewScore};
+
+    fn cp(cp: i32) -> Option<ReviewScore> {
+        Some(ReviewScore {
+            score: Some(review_score::Score::Centipawns(cp)),
+        })
+    }
+
+    #[test]
+    fn test_dot_rows_span_the_graph() {
+        assert_eq!(dot_row(500, 8), 0);
+        assert_eq!(dot_row(900, 8), 0);
+        assert_eq!(dot_row(-500, 8), 7);
+        assert_eq!(dot_row(0, 8), 4);
+    }
+
+    #[test]
+    fn test_swings_are_large_changes_over_one_move() {
+        let evals = [20, 30, -200, -180, 300];
+        let positions = evals
+            .iter()
+            .enumerate()
+            .map(|(i, &after)| PositionReview {
+                ply: i as u32 + 1,
+                eval_before: cp(if i == 0 { 15 } else { evals[i - 1] }),
+                eval_after: cp(after),
+                ..Default::default()
+            })
+            .collect();
+        let review_state = ReviewState::new(GameReviewProto {
+            total_plies: evals.len() as u32,
+            positions,
+            ..Default::default()
+        });
+        assert_eq!(eval_swings(&review_state), vec![3, 5]);
+    }
+}
@@ -1,6 +1,11 @@
+use crate::material::material_balance;
 use crate::state::GameSession;
 use crate::ui::fsm::UiStateMachine;
-use chess_client::{review_score, MoveClassification, ReviewScore};
+use crate::ui::glyphs::{self, GlyphWidths};
+use crate::ui::widgets::format_clock;
+use chess_client::{
+    review_score, MoveClassification, ReviewScore, TacticalTagKindProto, TacticalTagProto,
+};
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
@@ -111,11 +116,13 @@ impl GameInfoPanel<'_> {
                 ),
             ]));
 
+            lines.extend(self.material_lines());
+
             // Auto-play indicator
             if rs.auto_play {
                 lines.push(Line::raw(""));
                 lines.push(Line::from(Span::styled(
-                    "AUTO-PLAY".to_string(),
+                    format!("AUTO-PLAY {}", rs.speed_label()),
                     Style::default()
                         .fg(Color::Green)
                         .add_modifier(Modifier::BOLD),
@@ -157,6 +164,56 @@ impl GameInfoPanel<'_> {
         lines
     }
 
+    /// Captured pieces per side and who is ahead on material, for the
+    /// position on the board.
+    fn material_lines(&self) -> Vec<Line<'static>> {
+        use ratatui::text::Span;
+
+        let captured = self.client_state.captured_material();
+        let glyphs = glyphs::current();
+        let balance = material_balance(self.client_state.board());
+        let (leader, color) = match balance.signum() {
+            1 => ("White", Color::White),
+            -1 => ("Black", Color::Gray),
+            _ => ("Even", Color::DarkGray),
+        };
+        let balance_text = if balance == 0 {
+            leader.to_string()
+        } else {
+            format!("{} +{}", leader, balance.abs())
+        };
+
+        vec![
+            Line::from(vec![
+                Span::styled(
+                    "Material: ",
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    balance_text,
+                    Style::default().fg(color).add_modifier(Modifier::BOLD),
+                ),
+            ]),
+            Line::from(vec![
+                Span::styled("\u{2654} ", Style::default().fg(Color::White)),
+                Span::styled(
+                    format!(
+                        "{:<12}",
+                        captured_icons(&captured.by_white, &BLACK_ICONS, &glyphs)
+                    ),
+                    Style::default().fg(Color::Gray),
+                ),
+                Span::styled("\u{265a} ", Style::default().fg(Color::Gray)),
+                Span::styled(
+                    captured_icons(&captured.by_black, &WHITE_ICONS, &glyphs),
+                    Style::default().fg(Color::White),
+                ),
+            ]),
+        ]
+    }
+
     fn brender_stateld_game_lines(&self) -> Vec<Line<'static>> {
         use ratatui::text::Span;
 
@@ -232,6 +289,27 @@ impl GameInfoPanel<'_> {
             ),
         ]));
 
+        // Undone moves still to redo
+        let redo_depth = self.client_state.snapshot.redo_depth;
+        if redo_depth > 0 {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    "Redo: ",
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    format!(
+                        "{} move{} (type 'redo')",
+                        redo_depth,
+                        if redo_depth == 1 { "" } else { "s" }
+                    ),
+                    Style::default().fg(Color::LightCyan),
+                ),
+            ]));
+        }
+
         // Timer display — read from server snapshot
         if let Some(ref timer) = self.client_state.snapshot.timer {
             let white_ms = timer.white_remaining_ms;
@@ -251,18 +329,6 @@ impl GameInfoPanel<'_> {
                 }
             };
 
-            let format_ms = |ms: u64| -> String {
-                let secs = ms / 1000;
-                let mins = secs / 60;
-                let rem_secs = secs % 60;
-                if secs < 10 {
-                    let tenths = (ms % 1000) / 100;
-                    format!("0:{:02}.{}", rem_secs, tenths)
-                } else {
-                    format!("{}:{:02}", mins, rem_secs)
-                }
-            };
-
             let white_indicator = if white_active { "\u{25b6} " } else { "  " };
             let black_indicator = if black_active { "\u{25b6} " } else { "  " };
 
@@ -270,7 +336,7 @@ impl GameInfoPanel<'_> {
                 Span::styled(white_indicator, Style::default().fg(Color::White)),
                 Span::styled("\u{2654} ", Style::default().fg(Color::White)),
                 Span::styled(
-                    format_ms(white_ms),
+                    format_clock(white_ms),
                     Style::default()
                         .fg(timer_color(white_ms, white_active))
                         .add_modifier(Modifier::BOLD),
@@ -279,7 +345,7 @@ impl GameInfoPanel<'_> {
                 Span::styled(black_indicator, Style::default().fg(Color::Gray)),
                 Span::styled("\u{265a} ", Style::default().fg(Color::Gray)),
                 Span::styled(
-                    format_ms(black_ms),
+                    format_clock(black_ms),
                     Style::default()
                         .fg(timer_color(black_ms, black_active))
                         .add_modifier(Modifier::BOLD),
@@ -287,6 +353,72 @@ impl GameInfoPanel<'_> {
             ]));
         }
 
+        lines.extend(self.material_lines());
+
+        // Live tactical hints callouts
+        if !self.client_state.snapshot.tactical_hints.is_empty() {
+            lines.push(Line::raw(""));
+            lines.push(Line::from(Span::styled(
+                "Hints:",
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )));
+            for tag in &self.client_state.snapshot.tactical_hints {
+                lines.push(Line::from(Span::styled(
+                    format!("  {}", hint_callout(tag)),
+                    Style::default().fg(Color::Rgb(255, 200, 100)),
+                )));
+            }
+        }
+
+        // Simul board strip: every board with its state and the player's clock
+        if let Some(ref simul) = self.client_state.simul {
+            lines.push(Line::raw(""));
+            lines.push(Line::from(Span::styled(
+                format!(
+                    "Simul: board {} of {}",
+                    simul.active + 1,
+                    simul.boards.len()
+                ),
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )));
+            for (i, board) in simul.boards.iter().enumerate() {
+                let marker = if i == simul.active { "\u{25b6}" } else { " " };
+                let (state, color) = if board.finished {
+                    ("finished", Color::DarkGray)
+                } else if board.awaiting_move {
+                    ("your move", Color::Yellow)
+                } else {
+                    ("engine", Color::Gray)
+                };
+                let mut spans = vec![
+                    Span::styled(
+                        format!("{} {}  ", marker, i + 1),
+                        Style::default().fg(Color::White),
+                    ),
+                    Span::styled(format!("{:<10}", state), Style::default().fg(color)),
+                    Span::styled(
+                        format!("{:>3} mv", board.move_count),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                ];
+                if let Some(ms) = board.clock_ms {
+                    spans.push(Span::styled(
+                        format!("  {}", format_clock(ms)),
+                        Style::default().fg(if ms < 60_000 {
+                            Color::Red
+                        } else {
+                            Color::White
+                        }),
+                    ));
+                }
+                lines.push(Line::from(spans));
+            }
+        }
+
         // Add selection indicator
         if let Some(selected) = self.client_state.selected_square {
             lines.push(Line::raw(""));
@@ -330,20 +462,6 @@ impl GameInfoPanel<'_> {
             }
         }
 
-        // Add status message
-        if let Some(ref msg) = self.client_state.status_message {
-            lines.push(Line::raw(""));
-            lines.push(Line::from(vec![
-                Span::styled(
-                    "Status: ",
-                    Style::default()
-                        .fg(Color::Cyan)
-                        .add_modifier(Modifier::BOLD),
-                ),
-                Span::raw(msg.clone()),
-            ]));
-        }
-
         // Add game status
         let status = self.client_state.status();
         if status != 0 {
@@ -413,13 +531,53 @@ impl GameInfoPanel<'_> {
     }
 }
 
+/// Piece glyphs in `CAPTURABLE` order (pawn, knight, bishop, rook, queen).
+const WHITE_ICONS: [char; 5] = ['\u{2659}', '\u{2658}', '\u{2657}', '\u{2656}', '\u{2655}'];
+const BLACK_ICONS: [char; 5] = ['\u{265f}', '\u{265e}', '\u{265d}', '\u{265c}', '\u{265b}'];
+
+/// Captured pieces as glyphs grouped by type, e.g. `♟♟♟ ♞ ♛`. Wide glyphs
+/// each get a space to spill into.
+fn captured_icons(counts: &[u8; 5], icons: &[char; 5], glyphs: &GlyphWidths) -> String {
+    let pad = if glyphs.pieces > 1 { " " } else { "" };
+    counts
+        .iter()
+        .zip(icons)
+        .filter(|(&n, _)| n > 0)
+        .map(|(&n, &icon)| format!("{}{}", icon, pad).repeat(n as usize))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 fn format_game_mode(mode: &crate::state::GameMode) -> &'static str {
     match mode {
         crate::state::GameMode::HumanVsHuman => "Human vs Human",
         crate::state::GameMode::HumanVsEngine { .. } => "Human vs Engine",
         crate::state::GameMode::EngineVsEngine => "Engine vs Engine",
-        crate::state::GameMode::AnalysisMode => "Analysis",
-        crate::state::GameMode::ReviewMode => "Review",
+        crate::state::GameMode::Analysis => "Analysis",
+        crate::state::GameMode::Review => "Review",
+    }
+}
+
+/// Short spectator-facing callout for a live tactical hint, e.g. "Fork available (e5)".
+fn hint_callout(tag: &TacticalTagProto) -> String {
+    let text = match TacticalTagKindProto::try_from(tag.kind) {
+        Ok(TacticalTagKindProto::TacticalTagKindFork) => "Fork available",
+        Ok(TacticalTagKindProto::TacticalTagKindPin) => "Pin",
+        Ok(TacticalTagKindProto::TacticalTagKindSkewer) => "Skewer available",
+        Ok(TacticalTagKindProto::TacticalTagKindDiscoveredAttack) => "Discovered attack",
+        Ok(TacticalTagKindProto::TacticalTagKindDoubleAttack) => "Double attack",
+        Ok(TacticalTagKindProto::TacticalTagKindHangingPiece) => "Hanging piece",
+        Ok(TacticalTagKindProto::TacticalTagKindSacrifice) => "Sacrifice possible",
+        Ok(TacticalTagKindProto::TacticalTagKindZwischenzug) => "Zwischenzug",
+        Ok(TacticalTagKindProto::TacticalTagKindBackRankWeakness) => "Back rank weak",
+        Ok(TacticalTagKindProto::TacticalTagKindMateThreat) => "Mate threat",
+        Ok(TacticalTagKindProto::TacticalTagKindDeflection) => "Deflection",
+        Ok(TacticalTagKindProto::TacticalTagKindDecoy) => "Decoy",
+        _ => "Tactic",
+    };
+    match tag.target_square.as_ref().or(tag.victims.first()) {
+        Some(sq) => format!("{} ({})", text, sq),
+        None => text.to_string(),
     }
 }
 
@@ -456,12 +614,14 @@ pub(crate) fn format_review_score(score: &ReviewScore) -> (String, Color) {
 pub(crate) fn classification_color(classification: i32) -> Color {
     match MoveClassification::try_from(classification) {
         Ok(MoveClassification::ClassificationBrilliant) => Color::Cyan,
+        Ok(MoveClassification::ClassificationGreat) => Color::LightBlue,
         Ok(MoveClassification::ClassificationBest) => Color::LightGreen,
         Ok(MoveClassification::ClassificationExcellent) => Color::Cyan,
         Ok(MoveClassification::ClassificationGood) => Color::White,
         Ok(MoveClassification::ClassificationInaccuracy) => Color::Yellow,
         Ok(MoveClassification::ClassificationMistake) => Color::Magenta,
         Ok(MoveClassification::ClassificationBlunder) => Color::Red,
+        Ok(MoveClassification::ClassificationMiss) => Color::LightRed,
         Ok(MoveClassification::ClassificationForced) => Color::DarkGray,
         Ok(MoveClassification::ClassificationBook) => Color::DarkGray,
         _ => Color::White,
@@ -0,0 +1,365 @@
+use chess_client::{GoalMetricType, GoalPeriodType, GoalProgressProto, GoalProto};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    symbols,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, LineGauge, Paragraph, Widget},
+};
+
+/// Metrics a new goal can track. Puzzle goals are left out until something
+/// records solved puzzles.
+const METRICS: [GoalMetricType; 3] = [
+    GoalMetricType::GoalMetricAverageAccuracy,
+    GoalMetricType::GoalMetricGamesPlayed,
+    GoalMetricType::GoalMetricReviewsRead,
+];
+
+const PERIODS: [GoalPeriodType; 3] = [
+    GoalPeriodType::GoalPeriodWeek,
+    GoalPeriodType::GoalPeriodMonth,
+    GoalPeriodType::GoalPeriodAllTime,
+];
+
+fn metric_name(metric: GoalMetricType) -> &'static str {
+    match metric {
+        GoalMetricType::GoalMetricAverageAccuracy => "Average accuracy",
+        GoalMetricType::GoalMetricGamesPlayed => "Games played",
+        GoalMetricType::GoalMetricPuzzlesSolved => "Puzzles solved",
+        GoalMetricType::GoalMetricReviewsRead => "Reviews read",
+    }
+}
+
+fn period_name(period: GoalPeriodType) -> &'static str {
+    match period {
+        GoalPeriodType::GoalPeriodWeek => "this week",
+        GoalPeriodType::GoalPeriodMonth => "this month",
+        GoalPeriodType::GoalPeriodAllTime => "all time",
+    }
+}
+
+fn format_value(metric: GoalMetricType, value: f64) -> String {
+    match metric {
+        GoalMetricType::GoalMetricAverageAccuracy => format!("{:.1}%", value),
+        _ => format!("{:.0}", value),
+    }
+}
+
+/// Description of a goal, e.g. "Games played: 20 this month".
+pub fn goal_label(goal: &GoalProto) -> String {
+    let metric = GoalMetricType::try_from(goal.metric).unwrap_or_default();
+    let period = GoalPeriodType::try_from(goal.period).unwrap_or_default();
+    format!(
+        "{}: {} {}",
+        metric_name(metric),
+        format_value(metric, goal.target),
+        period_name(period)
+    )
+}
+
+/// Current value against the target, e.g. "5 / 20".
+pub fn progress_label(progress: &GoalProgressProto) -> String {
+    let Some(goal) = &progress.goal else {
+        return String::new();
+    };
+    let metric = GoalMetricType::try_from(goal.metric).unwrap_or_default();
+    let current = match progress.current {
+        Some(current) => format_value(metric, current),
+        None => "-".to_string(),
+    };
+    format!("{} / {}", current, format_value(metric, goal.target))
+}
+
+/// Share of a goal's target reached, from 0 to 1.
+pub fn progress_ratio(progress: &GoalProgressProto) -> f64 {
+    match (progress.current, &progress.goal) {
+        (Some(current), Some(goal)) if goal.target > 0.0 => (current / goal.target).clamp(0.0, 1.0),
+        _ => 0.0,
+    }
+}
+
+/// Goals with a progress bar each.
+pub struct GoalsPanel<'a> {
+    pub goals: &'a [GoalProgressProto],
+    /// Highlighted goal when the panel has focus.
+    pub selected: Option<usize>,
+}
+
+impl GoalsPanel<'_> {
+    /// Height of the panel including its border.
+    pub fn height(goals: &[GoalProgressProto]) -> u16 {
+        goals.len().max(1) as u16 + 2
+    }
+}
+
+impl Widget for GoalsPanel<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let border_color = if self.selected.is_some() {
+            Color::Yellow
+        } else {
+            Color::DarkGray
+        };
+        let block = Block::default()
+            .title(" Goals ")
+            .title_bottom(Line::from(" n: New goal | d: Delete | Tab: Switch focus ").centered())
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(border_color))
+            .style(Style::default().bg(Color::Black));
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        if self.goals.is_empty() {
+            Paragraph::new(Span::styled(
+                "No goals yet. Press n to set one.",
+                Style::default().fg(Color::DarkGray),
+            ))
+            .render(inner, buf);
+            return;
+        }
+
+        let label_width = inner.width / 2;
+        for (i, progress) in self.goals.iter().enumerate().take(inner.height as usize) {
+            let y = inner.y + i as u16;
+            let selected = self.selected == Some(i);
+            let mut label_style = Style::default().fg(Color::White);
+            if selected {
+                label_style = label_style.add_modifier(Modifier::REVERSED);
+            }
+            let label = progress.goal.as_ref().map(goal_label).unwrap_or_default();
+            Paragraph::new(Line::from(Span::styled(label, label_style))).render(
+                Rect {
+                    x: inner.x,
+                    y,
+                    width: label_width,
+                    height: 1,
+                },
+                buf,
+            );
+
+            let color = if progress.achieved {
+                Color::Green
+            } else {
+                Color::Cyan
+            };
+            LineGauge::default()
+                .ratio(progress_ratio(progress))
+                .label(progress_label(progress))
+                .line_set(symbols::line::THICK)
+                .filled_style(Style::default().fg(color))
+                .unfilled_style(Style::default().fg(Color::DarkGray))
+                .render(
+                    Rect {
+                        x: inner.x + label_width,
+                        y,
+                        width: inner.width - label_width,
+                        height: 1,
+                    },
+                    buf,
+                );
+        }
+    }
+}
+
+/// A goal being set up in the New Goal dialog.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoalDraft {
+    pub metric: GoalMetricType,
+    pub target: String,
+    pub period: GoalPeriodType,
+    /// Field being edited: 0 metric, 1 target, 2 period.
+    pub field: usize,
+    pub error: Option<String>,
+}
+
+impl Default for GoalDraft {
+    fn default() -> Self {
+        Self {
+            metric: GoalMetricType::GoalMetricGamesPlayed,
+            target: "20".to_string(),
+            period: GoalPeriodType::GoalPeriodMonth,
+            field: 0,
+            error: None,
+        }
+    }
+}
+
+fn cycle<T: Copy + PartialEq>(options: &[T], current: T, direction: i32) -> T {
+    let index = options.iter().position(|&o| o == current).unwrap_or(0) as i32;
+    options[(index + direction).rem_euclid(options.len() as i32) as usize]
+}
+
+impl GoalDraft {
+    pub fn next_field(&mut self) {
+        self.field = (self.field + 1) % 3;
+    }
+
+    pub fn previous_field(&mut self) {
+        self.field = (self.field + 2) % 3;
+    }
+
+    /// Cycle the metric or period, whichever field is selected.
+    pub fn cycle(&mut self, direction: i32) {
+        match self.field {
+            0 => self.metric = cycle(&METRICS, self.metric, direction),
+            2 => self.period = cycle(&PERIODS, self.period, direction),
+            _ => {}
+        }
+    }
+
+    /// Type into the target field; only digits and one decimal point are accepted.
+    pub fn push_char(&mut self, c: char) {
+        if self.field == 1
+            && (c.is_ascii_digit() || (c == '.' && !self.target.contains('.')))
+            && self.target.len() < 6
+        {
+            self.target.push(c);
+        }
+    }
+
+    pub fn pop_char(&mut self) {
+        if self.field == 1 {
+            self.target.pop();
+        }
+    }
+
+    /// The target as a number, if it is one the server will accept.
+    pub fn parse_target(&self) -> Result<f64, String> {
+        let target: f64 = self
+            .target
+            .parse()
+            .map_err(|_| "Enter a target number".to_string())?;
+        if target <= 0.0 {
+            return Err("Target must be positive".to_string());
+        }
+        if self.metric == GoalMetricType::GoalMetricAverageAccuracy && target > 100.0 {
+            return Err("Accuracy target cannot exceed 100%".to_string());
+        }
+        Ok(target)
+    }
+}
+
+/// Dialog for setting a new goal.
+pub struct GoalDialog<'a> {
+    pub draft: &'a GoalDraft,
+}
+
+impl Widget for GoalDialog<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let width = area.width.min(50);
+        let height = area.height.min(9);
+        let area = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        };
+        Clear.render(area, buf);
+
+        let block = Block::default()
+            .title(" New Goal ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow))
+            .style(Style::default().bg(Color::Black));
+
+        let draft = self.draft;
+        let field = |index: usize, name: &'static str, value: String| {
+            let style = if draft.field == index {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            let hint = if index == 1 {
+                ""
+            } else {
+                " [\u{2190}/\u{2192}]"
+            };
+            Line::from(vec![
+                Span::styled(if draft.field == index { "> " } else { "  " }, style),
+                Span::styled(format!("{:<8}", name), style),
+                Span::styled(value, style.fg(Color::Green)),
+                Span::styled(hint, Style::default().fg(Color::DarkGray)),
+            ])
+        };
+
+        let mut lines = vec![
+            field(0, "Metric", metric_name(draft.metric).to_string()),
+            field(1, "Target", draft.target.clone()),
+            field(2, "Period", period_name(draft.period).to_string()),
+            Line::raw(""),
+        ];
+        lines.push(match &draft.error {
+            Some(error) => Line::from(Span::styled(error.clone(), Style::default().fg(Color::Red))),
+            None => Line::from(Span::styled(
+                "Enter: Save | Esc: Cancel",
+                Style::default().fg(Color::DarkGray),
+            )),
+        });
+
+        Paragraph::new(lines).block(block).render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress(metric: GoalMetricType, target: f64, current: Option<f64>) -> GoalProgressProto {
+        GoalProgressProto {
+            goal: Some(GoalProto {
+                metric: metric as i32,
+                target,
+                period: GoalPeriodType::GoalPeriodMonth as i32,
+                ..Default::default()
+            }),
+            current,
+            achieved: false,
+        }
+    }
+
+    #[test]
+    fn test_labels() {
+        let games = progress(GoalMetricType::GoalMetricGamesPlayed, 20.0, Some(5.0));
+        assert_eq!(
+            goal_label(games.goal.as_ref().unwrap()),
+            "Games played: 20 this month"
+        );
+        assert_eq!(progress_label(&games), "5 / 20");
+        assert_eq!(progress_ratio(&games), 0.25);
+
+        let accuracy = progress(GoalMetricType::GoalMetricAverageAccuracy, 85.0, None);
+        assert_eq!(progress_label(&accuracy), "- / 85.0%");
+        assert_eq!(progress_ratio(&accuracy), 0.0);
+    }
+
+    #[test]
+    fn test_draft_editing() {
+        let mut draft = GoalDraft::default();
+        draft.cycle(1);
+        assert_eq!(draft.metric, GoalMetricType::GoalMetricReviewsRead);
+        draft.cycle(-1);
+        draft.cycle(-1);
+        assert_eq!(draft.metric, GoalMetricType::GoalMetricAverageAccuracy);
+
+        draft.next_field();
+        draft.pop_char();
+        draft.pop_char();
+        for c in "85.5.x".chars() {
+            draft.push_char(c);
+        }
+        assert_eq!(draft.target, "85.5");
+        assert_eq!(draft.parse_target(), Ok(85.5));
+
+        draft.target = "120".to_string();
+        assert!(draft.parse_target().is_err());
+
+        draft.previous_field();
+        draft.previous_field();
+        draft.cycle(1);
+        assert_eq!(draft.period, GoalPeriodType::GoalPeriodAllTime);
+    }
+}
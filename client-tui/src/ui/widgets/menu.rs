@@ -1,5 +1,8 @@
+use crate::opponents::Opponent;
+use crate::review_progress::ReviewProgressTracker;
 use crate::ui::widgets::fen_dialog::FenDialogState;
 use crate::ui::widgets::selectable_table::SelectableTableState;
+use chess::{Notation, NotationStyle, PieceLanguage};
 use ratatui::{
     buffer::Buffer,
     layout::{Alignment, Rect},
@@ -12,14 +15,30 @@ use ratatui::{
 pub enum MenuItem {
     GameMode(GameModeOption),
     PlayAs(PlayAsOption),
+    SimulBoards(u8),
+    /// Index into the opponent roster; `None` plays an unnamed engine.
+    Opponent(Option<usize>),
     Difficulty(DifficultyOption),
     EngineThreads(ThreadsOption),
     EngineHash(HashOption),
+    EngineLines(u32),
     TimeControl(TimeControlOption),
     StartPosition(StartPositionOption),
+    Notation(NotationStyle),
+    PieceLetters(PieceLanguage),
+    LiveEvals(bool),
+    ConfirmMoves(bool),
+    ScreenReader(bool),
     ResumeSession,
+    /// Take over a session from another client with a transfer code.
+    JoinSession,
     ReviewGame,
+    Stats,
+    Digest,
+    Studies,
     StartGame,
+    Tutorial,
+    Demo,
     Quit,
 }
 
@@ -102,26 +121,133 @@ pub struct MenuState {
     pub selected_index: usize,
     pub game_mode: GameModeOption,
     pub play_as: PlayAsOption,
+    /// Number of engine boards played at once; 1 is a regular game.
+    pub simul_boards: u8,
+    /// Named engine opponents the player can pick instead of a difficulty.
+    pub opponents: Vec<Opponent>,
+    pub opponent: Option<usize>,
     pub difficulty: DifficultyOption,
     pub engine_threads: ThreadsOption,
     pub engine_hash: HashOption,
+    /// Lines the engine searches at once in engine-vs-engine games (MultiPV).
+    pub engine_lines: u32,
     pub time_control: TimeControlOption,
     pub start_position: StartPositionOption,
+    /// How moves are written in the history and review panels.
+    pub notation: Notation,
+    /// Show engine evals next to the moves of a live game.
+    pub live_evals: bool,
+    /// Hold each move until it is confirmed with a second key press.
+    pub confirm_moves: bool,
+    /// Narrate the game as text instead of drawing the board.
+    pub screen_reader: bool,
     pub fen_dialog_state: Option<FenDialogState>,
     pub saved_positions: Vec<chess_client::SavedPosition>,
     pub selected_fen: Option<String>,
     pub has_saved_session: bool,
     pub suspended_sessions: Vec<chess_client::SuspendedSessionInfo>,
     pub session_table: Option<SessionTableContext>,
+    /// Transfer code being typed, while the join dialog is open.
+    pub transfer_code: Option<String>,
     pub has_finished_games: bool,
     pub finished_games: Vec<chess_client::FinishedGameInfo>,
     pub review_table: Option<ReviewTableContext>,
+    /// Reviews queued from the table, followed as the server analyses them.
+    pub review_progress: ReviewProgressTracker,
+    pub simul_report: Option<SimulReportContext>,
+    pub stats: Option<StatsContext>,
+    pub digest: Option<DigestContext>,
+    pub studies: Option<StudiesContext>,
+    pub study: Option<StudyContext>,
+    pub chapters: Option<ChaptersContext>,
+    pub chapter: Option<ChapterContext>,
+    /// The server is unreachable and finished games come from the read-only
+    /// offline library; only browsing reviews is possible.
+    pub offline: bool,
+    /// The server has no Stockfish binary, so engine modes cannot start.
+    pub engine_missing: bool,
 }
 
 /// Context for the review game selection table dialog.
 pub struct ReviewTableContext {
     pub table_state: SelectableTableState,
     pub games: Vec<chess_client::FinishedGameInfo>,
+    /// Endgame type the table is narrowed to, if any.
+    pub endgame_filter: Option<chess_client::EndgameTypeProto>,
+}
+
+/// Context for the combined report of a simul's boards.
+pub struct SimulReportContext {
+    pub table_state: SelectableTableState,
+    pub report: chess_client::GetSimulReportResponse,
+}
+
+/// Context for the Stats screen: activity heat-map, goals and head-to-head records.
+pub struct StatsContext {
+    pub table_state: SelectableTableState,
+    pub opponents: Vec<chess_client::OpponentStatsProto>,
+    pub activity: Option<chess_client::GetActivitySummaryResponse>,
+    pub goals: Vec<chess_client::GoalProgressProto>,
+    /// Whether arrow keys move through the goals rather than the head-to-head table.
+    pub goals_focused: bool,
+    pub selected_goal: usize,
+    /// New goal being set up, if the dialog is open.
+    pub goal_draft: Option<super::goals_panel::GoalDraft>,
+}
+
+/// Context for the training digest report screen.
+pub struct DigestContext {
+    pub digest: chess_client::GetTrainingDigestResponse,
+    pub scroll: u16,
+    /// Outcome of the last Markdown export.
+    pub status: Option<String>,
+}
+
+/// Context for the list of studies.
+pub struct StudiesContext {
+    pub table_state: SelectableTableState,
+    pub studies: Vec<chess_client::StudyInfo>,
+    /// New study being set up, if the dialog is open.
+    pub draft: Option<super::study_browser::StudyDraft>,
+    /// Outcome of the last build or PGN export.
+    pub status: Option<String>,
+}
+
+/// Context for browsing a study's tree.
+pub struct StudyContext {
+    pub study: chess_client::GetStudyResponse,
+    /// Child index per move from the start position to the shown one.
+    pub path: Vec<usize>,
+    /// Highlighted move among the shown position's children.
+    pub selected: usize,
+}
+
+/// Context for the chapter list of a study.
+pub struct ChaptersContext {
+    pub table_state: SelectableTableState,
+    pub study_id: String,
+    pub study_name: String,
+    /// Whether the study has a game tree to go back to.
+    pub has_games: bool,
+    pub chapters: Vec<chess_client::StudyChapterProto>,
+    /// New chapter being set up, if the dialog is open.
+    pub draft: Option<super::chapter_view::ChapterDraft>,
+    /// Outcome of the last change.
+    pub status: Option<String>,
+}
+
+/// Context for browsing and editing one chapter.
+pub struct ChapterContext {
+    pub study_id: String,
+    pub chapter: chess_client::StudyChapterProto,
+    /// Node whose position is shown.
+    pub node_id: u32,
+    /// Highlighted move among the shown node's children.
+    pub selected: usize,
+    /// Move, comment or arrow being typed.
+    pub input: Option<super::chapter_view::ChapterInput>,
+    /// Outcome of the last change.
+    pub status: Option<String>,
 }
 
 /// Context for the session selection table dialog.
@@ -136,60 +262,124 @@ impl Default for MenuState {
             selected_index: 0,
             game_mode: GameModeOption::HumanVsEngine,
             play_as: PlayAsOption::White,
+            simul_boards: 1,
+            opponents: vec![],
+            opponent: None,
             difficulty: DifficultyOption::Intermediate,
             engine_threads: ThreadsOption::Auto,
             engine_hash: HashOption::Medium,
+            engine_lines: 1,
             time_control: TimeControlOption::None,
             start_position: StartPositionOption::Standard,
+            notation: Notation::default(),
+            live_evals: false,
+            confirm_moves: false,
+            screen_reader: crate::narration::enabled_by_default(),
             fen_dialog_state: None,
             saved_positions: vec![],
             selected_fen: None,
             has_saved_session: false,
             suspended_sessions: vec![],
             session_table: None,
+            transfer_code: None,
             has_finished_games: false,
             finished_games: vec![],
             review_table: None,
+            review_progress: ReviewProgressTracker::default(),
+            simul_report: None,
+            stats: None,
+            digest: None,
+            studies: None,
+            study: None,
+            chapters: None,
+            chapter: None,
+            offline: false,
+            engine_missing: false,
         }
     }
 }
 
 impl MenuState {
+    /// An engine mode is picked while the server has no engine to run it.
+    pub fn needs_missing_engine(&self) -> bool {
+        self.engine_missing
+            && matches!(
+                self.game_mode,
+                GameModeOption::HumanVsEngine | GameModeOption::EngineVsEngine
+            )
+    }
+
     pub fn items(&self) -> Vec<MenuItem> {
         let has_engine = matches!(
             self.game_mode,
             GameModeOption::HumanVsEngine | GameModeOption::EngineVsEngine
         );
 
+        if self.offline {
+            let mut items = vec![];
+            if self.has_finished_games {
+                items.push(MenuItem::ReviewGame);
+            }
+            items.push(MenuItem::Tutorial);
+            items.push(MenuItem::Demo);
+            items.push(MenuItem::Quit);
+            return items;
+        }
+
         let mut items = vec![MenuItem::GameMode(self.game_mode.clone())];
 
         // Show Play As only for Human vs Engine
         if self.game_mode == GameModeOption::HumanVsEngine {
             items.push(MenuItem::PlayAs(self.play_as));
+            items.push(MenuItem::SimulBoards(self.simul_boards));
+            if !self.opponents.is_empty() {
+                items.push(MenuItem::Opponent(self.opponent));
+            }
         }
 
-        items.push(MenuItem::Difficulty(self.difficulty));
+        // A named opponent brings its own skill level
+        if self.selected_opponent().is_none() {
+            items.push(MenuItem::Difficulty(self.difficulty));
+        }
 
         // Show engine tuning options when an engine is involved
         if has_engine {
             items.push(MenuItem::EngineThreads(self.engine_threads));
             items.push(MenuItem::EngineHash(self.engine_hash));
         }
+        // Extra lines are drawn as arrows, which would give hints to a human
+        if self.game_mode == GameModeOption::EngineVsEngine {
+            items.push(MenuItem::EngineLines(self.engine_lines));
+        }
 
         items.push(MenuItem::TimeControl(self.time_control));
         items.push(MenuItem::StartPosition(self.start_position));
+        items.push(MenuItem::Notation(self.notation.style));
+        // Figurines replace the letters
+        if self.notation.style != NotationStyle::Figurine {
+            items.push(MenuItem::PieceLetters(self.notation.language));
+        }
+        items.push(MenuItem::LiveEvals(self.live_evals));
+        items.push(MenuItem::ConfirmMoves(self.confirm_moves));
+        items.push(MenuItem::ScreenReader(self.screen_reader));
 
         // Show Resume Session if a saved session exists
         if self.has_saved_session {
             items.push(MenuItem::ResumeSession);
         }
+        items.push(MenuItem::JoinSession);
 
         // Show Review Game if finished games exist
         if self.has_finished_games {
             items.push(MenuItem::ReviewGame);
+            items.push(MenuItem::Stats);
+            items.push(MenuItem::Digest);
+            items.push(MenuItem::Studies);
         }
 
         items.push(MenuItem::StartGame);
+        items.push(MenuItem::Tutorial);
+        items.push(MenuItem::Demo);
         items.push(MenuItem::Quit);
         items
     }
@@ -200,6 +390,51 @@ impl MenuState {
             PlayAsOption::Black => PlayAsOption::White,
         };
     }
+
+    /// The chosen named opponent, if playing against one.
+    pub fn selected_opponent(&self) -> Option<&Opponent> {
+        if self.game_mode != GameModeOption::HumanVsEngine {
+            return None;
+        }
+        self.opponent.and_then(|i| self.opponents.get(i))
+    }
+
+    /// Step through no opponent, then each roster entry, wrapping.
+    pub fn cycle_opponent(&mut self, direction: i32) {
+        let count = self.opponents.len();
+        if count == 0 {
+            return;
+        }
+        self.opponent = if direction > 0 {
+            match self.opponent {
+                None => Some(0),
+                Some(i) if i + 1 < count => Some(i + 1),
+                Some(_) => None,
+            }
+        } else {
+            match self.opponent {
+                None => Some(count - 1),
+                Some(0) => None,
+                Some(i) => Some(i - 1),
+            }
+        };
+    }
+
+    /// Step the simul board count through 1 (off) ..= MAX_SIMUL_BOARDS, wrapping.
+    pub fn cycle_simul_boards(&mut self, direction: i32) {
+        let max = crate::simul::MAX_SIMUL_BOARDS;
+        self.simul_boards = if direction > 0 {
+            if self.simul_boards >= max {
+                1
+            } else {
+                self.simul_boards + 1
+            }
+        } else if self.simul_boards <= 1 {
+            max
+        } else {
+            self.simul_boards - 1
+        };
+    }
 }
 
 pub struct MenuWidget<'a> {
@@ -213,7 +448,7 @@ impl Widget for MenuWidget<'_> {
 
         // Calculate centered menu area
         let menu_width = 60;
-        let menu_height = 18;
+        let menu_height = 24;
         let x = (area.width.saturating_sub(menu_width)) / 2;
         let y = (area.height.saturating_sub(menu_height)) / 2;
 
@@ -234,16 +469,27 @@ impl Widget for MenuWidget<'_> {
         block.render(menu_area, buf);
 
         let items = self.menu_state.items();
-        let mut lines = vec![
-            Line::raw(""),
-            Line::from(vec![Span::styled(
+        let heading = if self.menu_state.offline {
+            Span::styled(
+                "Server offline - browsing the library read-only",
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            )
+        } else if self.menu_state.engine_missing {
+            Span::styled(
+                "Stockfish not found - install it to play the engine",
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )
+        } else {
+            Span::styled(
                 "Welcome to ChessTTY!",
                 Style::default()
                     .fg(Color::Yellow)
                     .add_modifier(Modifier::BOLD),
-            )]),
-            Line::raw(""),
-        ];
+            )
+        };
+        let mut lines = vec![Line::raw(""), Line::from(vec![heading]), Line::raw("")];
 
         for (idx, item) in items.iter().enumerate() {
             let is_selected = idx == self.menu_state.selected_index;
@@ -264,12 +510,21 @@ impl Widget for MenuWidget<'_> {
                         GameModeOption::HumanVsEngine => "Human vs Engine",
                         GameModeOption::EngineVsEngine => "Engine vs Engine",
                     };
-                    Line::from(vec![
+                    let mut spans = vec![
                         Span::styled(prefix, style),
                         Span::styled("Game Mode: ", style),
-                        Span::styled(mode_str, style.fg(Color::Cyan)),
-                        Span::styled(" [←/→]", Style::default().fg(Color::DarkGray)),
-                    ])
+                    ];
+                    if self.menu_state.needs_missing_engine() {
+                        spans.push(Span::styled(mode_str, style.fg(Color::DarkGray)));
+                        spans.push(Span::styled(
+                            " (needs Stockfish)",
+                            Style::default().fg(Color::DarkGray),
+                        ));
+                    } else {
+                        spans.push(Span::styled(mode_str, style.fg(Color::Cyan)));
+                    }
+                    spans.push(Span::styled(" [←/→]", Style::default().fg(Color::DarkGray)));
+                    Line::from(spans)
                 }
                 MenuItem::Difficulty(diff) => {
                     let diff_str = match diff {
@@ -329,6 +584,19 @@ impl Widget for MenuWidget<'_> {
                         Span::styled(" [\u{2190}/\u{2192}]", Style::default().fg(Color::DarkGray)),
                     ])
                 }
+                MenuItem::EngineLines(lines) => {
+                    let lines_str = if *lines > 1 {
+                        lines.to_string()
+                    } else {
+                        "Best only".to_string()
+                    };
+                    Line::from(vec![
+                        Span::styled(prefix, style),
+                        Span::styled("Engine Lines: ", style),
+                        Span::styled(lines_str, style.fg(Color::Cyan)),
+                        Span::styled(" [\u{2190}/\u{2192}]", Style::default().fg(Color::DarkGray)),
+                    ])
+                }
                 MenuItem::StartPosition(pos) => {
                     let pos_str = match pos {
                         StartPositionOption::Standard => "Standard",
@@ -341,6 +609,42 @@ impl Widget for MenuWidget<'_> {
                         Span::styled(" [←/→]", Style::default().fg(Color::DarkGray)),
                     ])
                 }
+                MenuItem::Notation(notation_style) => Line::from(vec![
+                    Span::styled(prefix, style),
+                    Span::styled("Notation: ", style),
+                    Span::styled(notation_style.name(), style.fg(Color::Cyan)),
+                    Span::styled(" [\u{2190}/\u{2192}]", Style::default().fg(Color::DarkGray)),
+                ]),
+                MenuItem::PieceLetters(language) => {
+                    let letters: String = language.letters().iter().collect();
+                    Line::from(vec![
+                        Span::styled(prefix, style),
+                        Span::styled("Piece Letters: ", style),
+                        Span::styled(
+                            format!("{} ({})", language.name(), letters),
+                            style.fg(Color::Cyan),
+                        ),
+                        Span::styled(" [\u{2190}/\u{2192}]", Style::default().fg(Color::DarkGray)),
+                    ])
+                }
+                MenuItem::LiveEvals(on) => Line::from(vec![
+                    Span::styled(prefix, style),
+                    Span::styled("Live Evals: ", style),
+                    Span::styled(if *on { "On" } else { "Off" }, style.fg(Color::Yellow)),
+                    Span::styled(" [\u{2190}/\u{2192}]", Style::default().fg(Color::DarkGray)),
+                ]),
+                MenuItem::ConfirmMoves(on) => Line::from(vec![
+                    Span::styled(prefix, style),
+                    Span::styled("Confirm Moves: ", style),
+                    Span::styled(if *on { "On" } else { "Off" }, style.fg(Color::Yellow)),
+                    Span::styled(" [\u{2190}/\u{2192}]", Style::default().fg(Color::DarkGray)),
+                ]),
+                MenuItem::ScreenReader(on) => Line::from(vec![
+                    Span::styled(prefix, style),
+                    Span::styled("Screen Reader: ", style),
+                    Span::styled(if *on { "On" } else { "Off" }, style.fg(Color::Yellow)),
+                    Span::styled(" [\u{2190}/\u{2192}]", Style::default().fg(Color::DarkGray)),
+                ]),
                 MenuItem::PlayAs(play_as) => {
                     let play_as_str = match play_as {
                         PlayAsOption::White => "White",
@@ -353,17 +657,73 @@ impl Widget for MenuWidget<'_> {
                         Span::styled(" [\u{2190}/\u{2192}]", Style::default().fg(Color::DarkGray)),
                     ])
                 }
+                MenuItem::SimulBoards(count) => {
+                    let count_str = if *count > 1 {
+                        format!("{} boards", count)
+                    } else {
+                        "Off".to_string()
+                    };
+                    Line::from(vec![
+                        Span::styled(prefix, style),
+                        Span::styled("Simul: ", style),
+                        Span::styled(count_str, style.fg(Color::Yellow)),
+                        Span::styled(" [\u{2190}/\u{2192}]", Style::default().fg(Color::DarkGray)),
+                    ])
+                }
+                MenuItem::Opponent(index) => {
+                    let opponent_str = match index.and_then(|i| self.menu_state.opponents.get(i)) {
+                        Some(o) => format!("{} (skill {})", o.name, o.skill_level),
+                        None => "None".to_string(),
+                    };
+                    Line::from(vec![
+                        Span::styled(prefix, style),
+                        Span::styled("Opponent: ", style),
+                        Span::styled(opponent_str, style.fg(Color::Green)),
+                        Span::styled(" [\u{2190}/\u{2192}]", Style::default().fg(Color::DarkGray)),
+                    ])
+                }
                 MenuItem::ResumeSession => Line::from(vec![
                     Span::styled(prefix, style),
                     Span::styled("\u{25b6} Resume Session", style.fg(Color::Cyan)),
                 ]),
+                MenuItem::JoinSession => Line::from(vec![
+                    Span::styled(prefix, style),
+                    Span::styled("\u{25b6} Join From Another Client", style.fg(Color::Cyan)),
+                ]),
                 MenuItem::ReviewGame => Line::from(vec![
                     Span::styled(prefix, style),
                     Span::styled("\u{25b6} Review Game", style.fg(Color::Green)),
                 ]),
-                MenuItem::StartGame => Line::from(vec![
+                MenuItem::Stats => Line::from(vec![
+                    Span::styled(prefix, style),
+                    Span::styled("\u{25b6} Stats", style.fg(Color::Green)),
+                ]),
+                MenuItem::Digest => Line::from(vec![
+                    Span::styled(prefix, style),
+                    Span::styled("\u{25b6} Training Digest", style.fg(Color::Green)),
+                ]),
+                MenuItem::Studies => Line::from(vec![
+                    Span::styled(prefix, style),
+                    Span::styled("\u{25b6} Studies", style.fg(Color::Green)),
+                ]),
+                MenuItem::StartGame => {
+                    let color = if self.menu_state.needs_missing_engine() {
+                        Color::DarkGray
+                    } else {
+                        Color::Green
+                    };
+                    Line::from(vec![
+                        Span::styled(prefix, style),
+                        Span::styled("\u{25b6} Start Game", style.fg(color)),
+                    ])
+                }
+                MenuItem::Tutorial => Line::from(vec![
+                    Span::styled(prefix, style),
+                    Span::styled("\u{25b6} Tutorial", style.fg(Color::Cyan)),
+                ]),
+                MenuItem::Demo => Line::from(vec![
                     Span::styled(prefix, style),
-                    Span::styled("\u{25b6} Start Game", style.fg(Color::Green)),
+                    Span::styled("\u{25b6} Demo", style.fg(Color::Magenta)),
                 ]),
                 MenuItem::Quit => Line::from(vec![
                     Span::styled(prefix, style),
@@ -1,3 +1,4 @@
+use crate::ui::glyphs::{self, GlyphWidths};
 use cozy_chess::{Board, Color as ChessColor, File, Piece, Rank, Square};
 use ratatui::{
     buffer::Buffer,
@@ -41,6 +42,14 @@ pub struct MiniBoardWidget<'a> {
 
 impl Widget for MiniBoardWidget<'_> {
     fn render(self, area: Rect, buf: &mut Buffer) {
+        self.render_with(area, buf, &glyphs::current());
+    }
+}
+
+impl MiniBoardWidget<'_> {
+    /// Each square is two cells, the symbol and a space; a wide symbol
+    /// takes both.
+    fn render_with(self, area: Rect, buf: &mut Buffer, glyphs: &GlyphWidths) {
         let block = Block::default()
             .borders(Borders::ALL)
             .border_style(Style::default().fg(Color::DarkGray))
@@ -49,7 +58,9 @@ impl Widget for MiniBoardWidget<'_> {
         let inner = block.inner(area);
         block.render(area, buf);
 
-        if inner.width < 17 || inner.height < 9 {
+        // The last file's symbol needs its padding cell when it is wide
+        let min_width = if glyphs.pieces > 1 { 18 } else { 17 };
+        if inner.width < min_width || inner.height < 9 {
             return; // Not enough space
         }
 
@@ -172,6 +183,7 @@ impl Widget for MiniBoardWidget<'_> {
 
         let paragraph = Paragraph::new(lines).style(Style::default().bg(Color::White));
         paragraph.render(inner, buf);
+        glyphs.claim_padding(buf, inner);
     }
 }
 
@@ -221,4 +233,33 @@ mod tests {
     fn test_empty_square_symbol() {
         assert_eq!(empty_square_symbol(), "\u{00b7}");
     }
+
+    #[test]
+    fn test_wide_symbols_keep_files_aligned() {
+        let board = Board::default();
+        let area = Rect::new(0, 0, 20, 11);
+        let render = |glyphs: &GlyphWidths| {
+            let mut buf = Buffer::empty(area);
+            MiniBoardWidget {
+                board: &board,
+                flipped: false,
+            }
+            .render_with(area, &mut buf, glyphs);
+            buf
+        };
+
+        let narrow = render(&GlyphWidths::NARROW);
+        let wide = render(&GlyphWidths::WIDE);
+        // Same cells either way; the wide layout only marks the padding
+        // after each piece as covered by it
+        for x in 0..area.width {
+            assert_eq!(narrow[(x, 1)].symbol(), wide[(x, 1)].symbol());
+            assert!(!narrow[(x, 1)].skip);
+        }
+        assert_eq!(wide[(3, 1)].symbol(), "\u{265c}");
+        assert!(wide[(4, 1)].skip);
+        assert_eq!(wide[(17, 1)].symbol(), "\u{265c}");
+        assert!(wide[(18, 1)].skip);
+        assert!(!wide[(19, 1)].skip);
+    }
 }
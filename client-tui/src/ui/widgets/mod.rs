@@ -1,33 +1,82 @@
+pub mod activity_heatmap;
 pub mod advanced_analysis_panel;
 pub mod board;
+pub mod board_image;
 pub mod board_overlay;
+pub mod bookmark_dialog;
+pub mod chapter_view;
+pub mod chat_pane;
 pub mod engine_panel;
+pub mod eval_sparkline;
 pub mod fen_dialog;
 pub mod game_info_panel;
+pub mod goals_panel;
 pub mod menu;
 pub mod mini_board;
 pub mod move_analysis_panel;
+pub mod move_commentary;
 pub mod move_history_panel;
+pub mod narration_panel;
 pub mod popup_menu;
+pub mod position_panel;
+pub mod profiler_overlay;
 pub mod promotion_dialog;
 pub mod review_summary_panel;
 pub mod review_tabs_panel;
 pub mod selectable_table;
 pub mod snapshot_dialog;
+pub mod square_control_popup;
+pub mod study_browser;
 pub mod tab_input;
+pub mod timeline_scrubber;
+pub mod toast;
+pub mod training_digest;
+pub mod transfer_dialog;
 pub mod uci_debug_panel;
 
 pub use board::BoardWidget;
 #[allow(unused_imports)]
 pub use board_overlay::{build_review_overlay, BoardOverlay};
+pub use bookmark_dialog::BookmarkDialogWidget;
+pub use chat_pane::ChatPaneWidget;
 pub use engine_panel::EngineAnalysisPanel;
+pub use eval_sparkline::EvalSparkline;
 pub use fen_dialog::{FenDialogState, FenDialogWidget};
 pub use game_info_panel::GameInfoPanel;
 pub use menu::{MenuState, MenuWidget};
+pub use move_commentary::MoveCommentary;
 pub use move_history_panel::MoveHistoryPanel;
+pub use narration_panel::NarrationPanel;
 pub use popup_menu::PopupMenuWidget;
+pub use position_panel::PositionPanel;
+pub use profiler_overlay::ProfilerOverlay;
 pub use promotion_dialog::PromotionWidget;
 pub use selectable_table::{render_table_overlay, TableOverlayParams};
 pub use snapshot_dialog::SnapshotDialogWidget;
+pub use square_control_popup::SquareControlWidget;
 pub use tab_input::TabInputWidget;
+pub use timeline_scrubber::TimelineScrubber;
+pub use toast::{NotificationHistoryWidget, ToastStack};
 pub use uci_debug_panel::UciDebugPanel;
+
+/// Clock display as `m:ss`; tenths of a second are shown below ten seconds.
+pub fn format_clock(ms: u64) -> String {
+    let secs = ms / 1000;
+    if secs < 10 {
+        format!("0:{:02}.{}", secs, (ms % 1000) / 100)
+    } else {
+        format!("{}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_clock() {
+        assert_eq!(format_clock(5_300), "0:05.3");
+        assert_eq!(format_clock(12_500), "0:12");
+        assert_eq!(format_clock(585_500), "9:45");
+    }
+}
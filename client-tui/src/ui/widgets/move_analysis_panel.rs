@@ -1,8 +1,10 @@
 use crate::review_state::ReviewState;
 use crate::ui::widgets::game_info_panel::{classification_color, format_review_score};
+use crate::ui::widgets::review_tabs_panel::only_move_badge;
+use chess::Notation;
 use chess_client::{
-    review_score, MoveClassification, PositionKingSafetyProto, PositionTensionMetricsProto,
-    TacticalTagKindProto, TacticalTagProto,
+    review_score, ExplainPositionResponse, MoveClassification, PositionKingSafetyProto,
+    PositionTensionMetricsProto, TacticalTagKindProto, TacticalTagProto,
 };
 use ratatui::{
     buffer::Buffer,
@@ -18,6 +20,7 @@ pub struct MoveAnalysisPanel<'a> {
     pub scroll: u16,
     pub is_selected: bool,
     pub expanded: bool,
+    pub notation: Notation,
 }
 
 #[allow(dead_code)]
@@ -28,6 +31,7 @@ impl<'a> MoveAnalysisPanel<'a> {
             scroll,
             is_selected,
             expanded: false,
+            notation: Notation::default(),
         }
     }
 }
@@ -60,7 +64,11 @@ impl Widget for MoveAnalysisPanel<'_> {
         block.render(area, buf);
 
         let Some(pos) = self.review_state.current_position() else {
-            let text = "Navigate to a move to see analysis";
+            let text = if self.review_state.current_ply == 0 {
+                "Navigate to a move to see analysis"
+            } else {
+                "This move was not analysed"
+            };
             let paragraph = Paragraph::new(Line::from(Span::styled(
                 text,
                 Style::default()
@@ -77,13 +85,18 @@ impl Widget for MoveAnalysisPanel<'_> {
         let class_name = classification_display_name(pos.classification);
         let class_color = classification_color(pos.classification);
         let badge_text = format!(" {} ", class_name);
-        lines.push(Line::from(Span::styled(
+        let mut badge = vec![Span::styled(
             badge_text,
             Style::default()
                 .fg(Color::Black)
                 .bg(class_color)
                 .add_modifier(Modifier::BOLD),
-        )));
+        )];
+        if let Some(only_move) = only_move_badge(pos) {
+            badge.push(Span::raw(" "));
+            badge.push(only_move);
+        }
+        lines.push(Line::from(badge));
         lines.push(Line::raw(""));
 
         // Played move with marker
@@ -91,7 +104,12 @@ impl Widget for MoveAnalysisPanel<'_> {
         lines.push(Line::from(vec![
             Span::styled("Played: ", Style::default().fg(Color::DarkGray)),
             Span::styled(
-                format!("{}{}", pos.played_san, marker),
+                format!(
+                    "{}{}",
+                    self.review_state
+                        .show_san(&self.notation, pos.ply, &pos.played_san),
+                    marker
+                ),
                 Style::default()
                     .fg(class_color)
                     .add_modifier(Modifier::BOLD),
@@ -103,7 +121,8 @@ impl Widget for MoveAnalysisPanel<'_> {
             lines.push(Line::from(vec![
                 Span::styled("Best:   ", Style::default().fg(Color::DarkGray)),
                 Span::styled(
-                    pos.best_move_san.clone(),
+                    self.review_state
+                        .show_san(&self.notation, pos.ply, &pos.best_move_san),
                     Style::default()
                         .fg(Color::Green)
                         .add_modifier(Modifier::BOLD),
@@ -192,6 +211,13 @@ impl Widget for MoveAnalysisPanel<'_> {
             }
         }
 
+        // Why the best move is best, once asked for with `w`
+        if let Some(explanation) = self.review_state.current_explanation() {
+            lines.push(Line::raw(""));
+            let max_width = (inner.width as usize).saturating_sub(4);
+            render_explanation_inline(&mut lines, explanation, max_width);
+        }
+
         // Advanced analysis (tactics, king safety, tension) - only if we have a position
         if let Some(adv_pos) = self.review_state.advanced_position() {
             lines.push(Line::raw(""));
@@ -221,12 +247,14 @@ impl Widget for MoveAnalysisPanel<'_> {
 fn classification_display_name(classification: i32) -> &'static str {
     match MoveClassification::try_from(classification) {
         Ok(MoveClassification::ClassificationBrilliant) => "BRILLIANT",
+        Ok(MoveClassification::ClassificationGreat) => "GREAT",
         Ok(MoveClassification::ClassificationBest) => "BEST",
         Ok(MoveClassification::ClassificationExcellent) => "EXCELLENT",
         Ok(MoveClassification::ClassificationGood) => "GOOD",
         Ok(MoveClassification::ClassificationInaccuracy) => "INACCURACY",
         Ok(MoveClassification::ClassificationMistake) => "MISTAKE",
         Ok(MoveClassification::ClassificationBlunder) => "BLUNDER",
+        Ok(MoveClassification::ClassificationMiss) => "MISS",
         Ok(MoveClassification::ClassificationForced) => "FORCED",
         Ok(MoveClassification::ClassificationBook) => "BOOK",
         _ => "UNKNOWN",
@@ -237,10 +265,12 @@ fn classification_display_name(classification: i32) -> &'static str {
 fn classification_marker_str(classification: i32) -> &'static str {
     match MoveClassification::try_from(classification) {
         Ok(MoveClassification::ClassificationBrilliant) => "!!",
+        Ok(MoveClassification::ClassificationGreat) => "!",
         Ok(MoveClassification::ClassificationExcellent) => "!",
         Ok(MoveClassification::ClassificationInaccuracy) => "?!",
         Ok(MoveClassification::ClassificationMistake) => "?",
         Ok(MoveClassification::ClassificationBlunder) => "??",
+        Ok(MoveClassification::ClassificationMiss) => "×",
         Ok(MoveClassification::ClassificationForced) => "[]",
         _ => "",
     }
@@ -341,6 +371,83 @@ fn render_tactical_tags_inline(lines: &mut Vec<Line<'_>>, tags: &[TacticalTagPro
     }
 }
 
+fn render_explanation_inline(
+    lines: &mut Vec<Line<'_>>,
+    explanation: &ExplainPositionResponse,
+    max_width: usize,
+) {
+    lines.push(Line::from(Span::styled(
+        format!("Why {}", explanation.best_move_san),
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD),
+    )));
+
+    for point in &explanation.points {
+        for (i, chunk) in wrap_text(point, max_width).into_iter().enumerate() {
+            let bullet = if i == 0 { "  \u{2022} " } else { "    " };
+            lines.push(Line::from(vec![
+                Span::styled(bullet, Style::default().fg(Color::DarkGray)),
+                Span::styled(chunk, Style::default().fg(Color::White)),
+            ]));
+        }
+    }
+
+    // The line itself, each move with the tactics it leaves on the board
+    for step in &explanation.line {
+        let color = if step.by_mover {
+            Color::Green
+        } else {
+            Color::LightRed
+        };
+        let mut spans = vec![
+            Span::raw("  "),
+            Span::styled(format!("{:<7}", step.san), Style::default().fg(color)),
+        ];
+        let tags: Vec<_> = step
+            .tags
+            .iter()
+            .map(|tag| tactical_tag_kind_name_inline(tag.kind))
+            .collect();
+        if !tags.is_empty() {
+            spans.push(Span::styled(
+                tags.join(", "),
+                Style::default().fg(Color::LightCyan),
+            ));
+        }
+        lines.push(Line::from(spans));
+    }
+
+    let pawns = |cp: i32| format!("{:+.1}", cp as f64 / 100.0);
+    lines.push(Line::from(vec![
+        Span::styled("  Material ", Style::default().fg(Color::DarkGray)),
+        Span::raw(format!(
+            "{} -> {}",
+            pawns(explanation.material_before),
+            pawns(explanation.material_after)
+        )),
+    ]));
+    lines.push(Line::from(vec![
+        Span::styled("  King exp ", Style::default().fg(Color::DarkGray)),
+        Span::raw("own "),
+        Span::styled(
+            format!(
+                "{:.1}->{:.1}",
+                explanation.own_king_before, explanation.own_king_after
+            ),
+            Style::default().fg(exposure_color(explanation.own_king_after)),
+        ),
+        Span::raw(" theirs "),
+        Span::styled(
+            format!(
+                "{:.1}->{:.1}",
+                explanation.their_king_before, explanation.their_king_after
+            ),
+            Style::default().fg(exposure_color(explanation.their_king_after)),
+        ),
+    ]));
+}
+
 #[allow(dead_code)]
 fn tactical_tag_kind_name_inline(kind: i32) -> &'static str {
     match TacticalTagKindProto::try_from(kind) {
@@ -354,6 +461,8 @@ fn tactical_tag_kind_name_inline(kind: i32) -> &'static str {
         Ok(TacticalTagKindProto::TacticalTagKindZwischenzug) => "Zwischenzug",
         Ok(TacticalTagKindProto::TacticalTagKindBackRankWeakness) => "Back Rank",
         Ok(TacticalTagKindProto::TacticalTagKindMateThreat) => "Mate Threat",
+        Ok(TacticalTagKindProto::TacticalTagKindDeflection) => "Deflection",
+        Ok(TacticalTagKindProto::TacticalTagKindDecoy) => "Decoy",
         _ => "Unknown",
     }
 }
@@ -0,0 +1,145 @@
+use super::review_tabs_panel::{
+    classification_color, classification_display_name, tactical_tag_kind_name,
+};
+use crate::review_state::ReviewState;
+use chess::Notation;
+use chess_client::MoveClassification;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Paragraph, Widget},
+};
+
+/// One line under the review board describing the move just played: its
+/// classification, the better move for errors, and the tactics it created.
+pub struct MoveCommentary<'a> {
+    pub review_state: &'a ReviewState,
+    pub notation: Notation,
+}
+
+impl MoveCommentary<'_> {
+    fn spans(&self) -> Vec<Span<'static>> {
+        let rs = self.review_state;
+        if rs.current_ply == 0 {
+            return vec![Span::styled(
+                "Starting position",
+                Style::default().fg(Color::DarkGray),
+            )];
+        }
+        let Some(pos) = rs.current_position() else {
+            return Vec::new();
+        };
+
+        let number = pos.ply.div_ceil(2);
+        let dots = if pos.ply % 2 == 1 { "." } else { "..." };
+        let mut spans = vec![Span::styled(
+            format!(
+                "{}{} {}",
+                number,
+                dots,
+                rs.show_san(&self.notation, pos.ply, &pos.played_san)
+            ),
+            Style::default()
+                .fg(Color::White)
+                .add_modifier(Modifier::BOLD),
+        )];
+        if rs.review.analyzed_plies < pos.ply {
+            return spans;
+        }
+
+        spans.push(Span::styled(
+            format!("  {}", classification_display_name(pos.classification)),
+            Style::default().fg(classification_color(pos.classification)),
+        ));
+        let is_error = matches!(
+            MoveClassification::try_from(pos.classification),
+            Ok(MoveClassification::ClassificationInaccuracy
+                | MoveClassification::ClassificationMistake
+                | MoveClassification::ClassificationBlunder
+                | MoveClassification::ClassificationMiss)
+        );
+        if is_error && !pos.best_move_san.is_empty() && pos.best_move_san != pos.played_san {
+            spans.push(Span::styled(
+                format!(
+                    "  best was {}",
+                    rs.show_san(&self.notation, pos.ply, &pos.best_move_san)
+                ),
+                Style::default().fg(Color::Gray),
+            ));
+        }
+
+        let mut tags: Vec<&str> = Vec::new();
+        if let Some(adv) = rs.advanced_position() {
+            for tag in &adv.tactical_tags_after {
+                let name = tactical_tag_kind_name(tag.kind);
+                if !tags.contains(&name) {
+                    tags.push(name);
+                }
+            }
+        }
+        if !tags.is_empty() {
+            spans.push(Span::styled(
+                format!("  · {}", tags.join(", ")),
+                Style::default().fg(Color::Cyan),
+            ));
+        }
+        spans
+    }
+}
+
+impl Widget for MoveCommentary<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Paragraph::new(Line::from(self.spans())).render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chess_client::{GameReviewProto, PositionReview};
+
+    fn text(review_state: &ReviewState) -> String {
+        let widget = MoveCommentary {
+            review_state,
+            notation: Notation::default(),
+        };
+        widget.spans().iter().map(|s| s.content.as_ref()).collect()
+    }
+
+    #[test]
+    fn test_commentary_describes_errors() {
+        let review = GameReviewProto {
+            total_plies: 2,
+            analyzed_plies: 2,
+            positions: vec![
+                PositionReview {
+                    ply: 1,
+                    fen: "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1".into(),
+                    played_san: "e4".into(),
+                    best_move_san: "e4".into(),
+                    classification: MoveClassification::ClassificationBest as i32,
+                    ..Default::default()
+                },
+                PositionReview {
+                    ply: 2,
+                    fen: "rnbqkbnr/ppppp1pp/8/5p2/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2".into(),
+                    played_san: "f5".into(),
+                    best_move_san: "e5".into(),
+                    classification: MoveClassification::ClassificationMistake as i32,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let mut rs = ReviewState::new(review);
+        assert_eq!(text(&rs), "Starting position");
+
+        rs.go_to_ply(1);
+        assert_eq!(text(&rs), "1. e4  BEST");
+
+        rs.go_to_ply(2);
+        assert_eq!(text(&rs), "1... f5  MISTAKE  best was e5");
+    }
+}
@@ -1,3 +1,6 @@
+use super::engine_panel::parse_score;
+use analysis::{Phase, PhaseBoundaries};
+use chess::{parse_piece, Notation, NotationStyle};
 use chess_client::{MoveClassification, MoveRecord, PositionReview};
 use ratatui::{
     buffer::Buffer,
@@ -7,6 +10,7 @@ use ratatui::{
     widgets::StatefulWidget,
     widgets::{Block, Borders, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState, Widget},
 };
+use std::collections::HashMap;
 
 pub struct MoveHistoryPanel<'a> {
     pub history: &'a [MoveRecord],
@@ -17,6 +21,11 @@ pub struct MoveHistoryPanel<'a> {
     pub review_positions: Option<&'a [PositionReview]>,
     /// When set (review mode), highlight the move at this 1-indexed ply.
     pub current_ply: Option<u32>,
+    pub notation: Notation,
+    /// Engine evals seen during a live game, keyed by ply (1-indexed).
+    pub live_evals: Option<&'a HashMap<usize, String>>,
+    /// Where a reviewed game enters the middlegame and the endgame.
+    pub phases: Option<PhaseBoundaries>,
 }
 
 impl<'a> MoveHistoryPanel<'a> {
@@ -28,6 +37,9 @@ impl<'a> MoveHistoryPanel<'a> {
             expanded: false,
             review_positions: None,
             current_ply: None,
+            notation: Notation::default(),
+            live_evals: None,
+            phases: None,
         }
     }
 
@@ -41,6 +53,64 @@ impl<'a> MoveHistoryPanel<'a> {
         self
     }
 
+    pub fn with_notation(mut self, notation: Notation) -> Self {
+        self.notation = notation;
+        self
+    }
+
+    pub fn with_live_evals(mut self, evals: Option<&'a HashMap<usize, String>>) -> Self {
+        self.live_evals = evals;
+        self
+    }
+
+    pub fn with_phases(mut self, phases: Option<PhaseBoundaries>) -> Self {
+        self.phases = phases;
+        self
+    }
+
+    /// The phase that starts with the move at 1-indexed `ply`, if any.
+    fn phase_starting_at(&self, ply: u32) -> Option<Phase> {
+        self.phases?
+            .starts()
+            .into_iter()
+            .find(|&(_, from)| from == ply)
+            .map(|(phase, _)| phase)
+    }
+
+    /// The live eval after the move at 1-indexed `ply`, e.g. ` +0.35`.
+    fn live_eval_span(&self, ply: usize, bg: Color) -> Option<Span<'static>> {
+        let score = self.live_evals?.get(&ply)?;
+        let (text, color) = parse_score(score);
+        Some(Span::styled(
+            format!(" {}", text),
+            Style::default().fg(color).bg(bg),
+        ))
+    }
+
+    /// The SAN of the move at `index` in the chosen notation. Moves played
+    /// in this session carry their squares; reviewed moves only have the
+    /// position after them, so the long form looks at the previous one.
+    fn show_san(&self, index: usize) -> String {
+        let record = &self.history[index];
+        if !record.from.is_empty() {
+            let piece = record.piece.chars().next().and_then(parse_piece);
+            let capture = record.captured.as_deref().is_some_and(|c| !c.is_empty());
+            return self.notation.format_with_squares(
+                &record.san,
+                &record.from,
+                &record.to,
+                piece,
+                capture,
+            );
+        }
+        let before = match (self.notation.style, index) {
+            (NotationStyle::Long, 0) => Some(cozy_chess::Board::default()),
+            (NotationStyle::Long, _) => self.history[index - 1].fen_after.parse().ok(),
+            _ => None,
+        };
+        self.notation.format(&record.san, before.as_ref())
+    }
+
     /// Calculate scroll position to keep current_ply visible.
     /// Centers the current ply in the visible area when possible.
     #[allow(dead_code)]
@@ -62,6 +132,14 @@ impl<'a> MoveHistoryPanel<'a> {
     }
 }
 
+/// The line drawn above the first move of a phase.
+fn phase_separator(phase: Phase) -> Line<'static> {
+    Line::from(Span::styled(
+        format!("\u{2500}\u{2500} {} \u{2500}\u{2500}", phase.name()),
+        Style::default().fg(Color::DarkGray),
+    ))
+}
+
 /// Format clock_ms as `[M:SS]` for display in the move history.
 fn format_clock_span(positions: &[PositionReview], ply: usize) -> Option<String> {
     positions
@@ -84,10 +162,12 @@ fn classification_marker(
     positions.iter().find(|p| p.ply as usize == ply).and_then(
         |p| match MoveClassification::try_from(p.classification) {
             Ok(MoveClassification::ClassificationBrilliant) => Some(("!!", Color::Cyan)),
+            Ok(MoveClassification::ClassificationGreat) => Some(("!", Color::LightBlue)),
             Ok(MoveClassification::ClassificationExcellent) => Some(("!", Color::Cyan)),
             Ok(MoveClassification::ClassificationInaccuracy) => Some(("?!", Color::Yellow)),
             Ok(MoveClassification::ClassificationMistake) => Some(("?", Color::Magenta)),
             Ok(MoveClassification::ClassificationBlunder) => Some(("??", Color::Red)),
+            Ok(MoveClassification::ClassificationMiss) => Some(("×", Color::LightRed)),
             Ok(MoveClassification::ClassificationForced) => Some(("[]", Color::DarkGray)),
             _ => None,
         },
@@ -133,10 +213,10 @@ impl Widget for MoveHistoryPanel<'_> {
             self.build_compact_lines()
         };
 
+        let total_rows = lines.len();
         let paragraph = Paragraph::new(lines).scroll((self.scroll, 0));
         paragraph.render(inner, buf);
 
-        let total_rows = self.history.len().div_ceil(2);
         if total_rows > inner.height as usize {
             let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
                 .thumb_style(Style::default().fg(Color::Cyan).bg(Color::DarkGray));
@@ -158,7 +238,7 @@ impl MoveHistoryPanel<'_> {
             let move_color = if is_white { Color::White } else { Color::Gray };
 
             let move_str = if !record.san.is_empty() {
-                record.san.clone()
+                self.show_san(i)
             } else {
                 let capture =
                     if record.captured.is_some() && !record.captured.as_ref().unwrap().is_empty() {
@@ -199,12 +279,23 @@ impl MoveHistoryPanel<'_> {
                     ));
                 }
             }
+            if let Some(eval) = self.live_eval_span(i + 1, Color::Reset) {
+                move_spans.push(eval);
+            }
+
+            // A new phase breaks the row, even between White's and Black's move
+            let phase = self.phase_starting_at(ply);
+            if let Some(phase) = phase {
+                lines.push(phase_separator(phase));
+            }
 
-            if is_white {
-                let mut spans = vec![Span::styled(
-                    format!("{}. ", move_number),
-                    Style::default().fg(Color::Yellow),
-                )];
+            if is_white || phase.is_some() {
+                let prefix = if is_white {
+                    format!("{}. ", move_number)
+                } else {
+                    format!("{}... ", move_number)
+                };
+                let mut spans = vec![Span::styled(prefix, Style::default().fg(Color::Yellow))];
                 spans.extend(move_spans);
                 lines.push(Line::from(spans));
             } else if let Some(last_line) = lines.last_mut() {
@@ -234,7 +325,7 @@ impl MoveHistoryPanel<'_> {
             };
 
             let san = if !record.san.is_empty() {
-                record.san.clone()
+                self.show_san(i)
             } else {
                 format!("{}-{}", record.from, record.to)
             };
@@ -270,7 +361,11 @@ impl MoveHistoryPanel<'_> {
                     ));
                 }
             }
+            spans.extend(self.live_eval_span(i + 1, bg));
 
+            if let Some(phase) = self.phase_starting_at(ply) {
+                lines.push(phase_separator(phase));
+            }
             lines.push(Line::from(spans));
         }
 
@@ -542,6 +637,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_history_uses_chosen_notation() {
+        use chess::PieceLanguage;
+
+        let history = vec![
+            make_record("P", "e2", "e4", None, "e4", None),
+            make_record("N", "g8", "f6", None, "Nf6", None),
+        ];
+        let panel = MoveHistoryPanel::new(&history, 0, false).with_notation(Notation {
+            style: NotationStyle::Figurine,
+            language: PieceLanguage::English,
+        });
+        assert_eq!(panel.show_san(1), "♘f6");
+
+        let panel = panel.with_notation(Notation {
+            style: NotationStyle::Long,
+            language: PieceLanguage::German,
+        });
+        assert_eq!(panel.show_san(0), "e2-e4");
+        assert_eq!(panel.show_san(1), "Sg8-f6");
+
+        // Reviewed moves have no squares; the previous position is used
+        let mut reviewed = history.clone();
+        for record in &mut reviewed {
+            record.from.clear();
+            record.to.clear();
+        }
+        reviewed[0].fen_after = "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1".into();
+        let panel = MoveHistoryPanel::new(&reviewed, 0, false).with_notation(panel.notation);
+        assert_eq!(panel.show_san(0), "e2-e4");
+        assert_eq!(panel.show_san(1), "Sg8-f6");
+    }
+
     #[test]
     fn test_compact_lines_without_review_omit_markers() {
         let history = vec![make_record("P", "e2", "e4", None, "e4", None)];
@@ -593,6 +721,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_compact_lines_include_live_evals() {
+        let history = vec![
+            make_record("P", "e2", "e4", None, "e4", None),
+            make_record("P", "e7", "e5", None, "e5", None),
+        ];
+        // Only the first move has been evaluated so far
+        let evals = HashMap::from([(1, "cp 31".to_string())]);
+
+        let panel = MoveHistoryPanel::new(&history, 0, false);
+        assert_eq!(panel.build_compact_lines()[0].spans.len(), 4);
+
+        let panel = panel.with_live_evals(Some(&evals));
+        let lines = panel.build_compact_lines();
+        let spans: Vec<&str> = lines[0].spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(spans, vec!["1. ", "e4", " +0.31", "  ", "e5"]);
+        assert_eq!(lines[0].spans[2].style.fg, Some(Color::Green));
+
+        let expanded = panel.build_expanded_lines();
+        assert_eq!(expanded[0].spans.last().unwrap().content, " +0.31");
+        assert_eq!(expanded[1].spans.len(), 3);
+    }
+
     #[test]
     fn test_compact_lines_no_clock_when_none() {
         let history = vec![make_record("P", "e2", "e4", None, "e4", None)];
@@ -611,4 +762,76 @@ mod tests {
         assert_eq!(lines.len(), 1);
         assert_eq!(lines[0].spans.len(), 2); // "1. " and "e4"
     }
+
+    #[test]
+    fn test_great_and_miss_markers_are_distinct() {
+        let positions: Vec<PositionReview> = [
+            MoveClassification::ClassificationBrilliant,
+            MoveClassification::ClassificationGreat,
+            MoveClassification::ClassificationExcellent,
+            MoveClassification::ClassificationBlunder,
+            MoveClassification::ClassificationMiss,
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, c)| PositionReview {
+            ply: i as u32 + 1,
+            classification: *c as i32,
+            ..Default::default()
+        })
+        .collect();
+
+        assert_eq!(
+            classification_marker(&positions, 2),
+            Some(("!", Color::LightBlue))
+        );
+        assert_eq!(
+            classification_marker(&positions, 5),
+            Some(("\u{d7}", Color::LightRed))
+        );
+        let markers: Vec<_> = (1..=5)
+            .map(|ply| classification_marker(&positions, ply).unwrap())
+            .collect();
+        for (i, a) in markers.iter().enumerate() {
+            for b in &markers[i + 1..] {
+                assert_ne!(a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_phase_separators_break_the_move_rows() {
+        let history = vec![
+            make_record("P", "e2", "e4", None, "e4", None),
+            make_record("P", "e7", "e5", None, "e5", None),
+            make_record("N", "g1", "f3", None, "Nf3", None),
+            make_record("N", "b8", "c6", None, "Nc6", None),
+        ];
+        let phases = PhaseBoundaries {
+            middlegame_from: Some(4),
+            endgame_from: None,
+        };
+        let panel = MoveHistoryPanel::new(&history, 0, false).with_phases(Some(phases));
+        let text: Vec<String> = panel
+            .build_compact_lines()
+            .iter()
+            .map(|line| line.spans.iter().map(|s| s.content.as_ref()).collect())
+            .collect();
+        assert_eq!(
+            text,
+            vec![
+                "1. e4  e5",
+                "2. Nf3",
+                "\u{2500}\u{2500} Middlegame \u{2500}\u{2500}",
+                "2... Nc6"
+            ]
+        );
+
+        let expanded = panel.build_expanded_lines();
+        assert_eq!(expanded.len(), 5);
+        assert_eq!(
+            expanded[3].spans[0].content,
+            "\u{2500}\u{2500} Middlegame \u{2500}\u{2500}"
+        );
+    }
 }
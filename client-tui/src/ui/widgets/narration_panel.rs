@@ -0,0 +1,91 @@
+use crate::narration::Narration;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    text::Line,
+    widgets::{Block, Borders, Paragraph, Widget, Wrap},
+};
+
+/// The screen-reader transcript in place of the board, newest line last and
+/// the prompt on the bottom row. Everything is plain unstyled text, so
+/// nothing depends on colour.
+pub struct NarrationPanel<'a> {
+    pub narration: &'a Narration,
+    /// Text typed at the prompt, while it is open.
+    pub command_line: Option<&'a str>,
+}
+
+impl NarrationPanel<'_> {
+    fn prompt(&self) -> String {
+        match self.command_line {
+            Some(command) => format!("> {}", command),
+            None => "Press colon to type a command or move.".to_string(),
+        }
+    }
+}
+
+impl Widget for NarrationPanel<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = Block::default().title(" Game ").borders(Borders::ALL);
+        let inner = block.inner(area);
+        block.render(area, buf);
+        if inner.height == 0 {
+            return;
+        }
+
+        // Take lines from the end until the panel is full, counting wrapped
+        // rows, so the latest events are always visible
+        let width = usize::from(inner.width.max(1));
+        let rows = |text: &str| text.chars().count().max(1).div_ceil(width);
+        let mut budget = usize::from(inner.height).saturating_sub(rows(&self.prompt()));
+        let mut shown: Vec<&str> = Vec::new();
+        for line in self.narration.lines().rev() {
+            let needed = rows(line);
+            if needed > budget {
+                break;
+            }
+            budget -= needed;
+            shown.push(line);
+        }
+
+        let mut lines: Vec<Line> = shown.into_iter().rev().map(Line::from).collect();
+        lines.extend((0..budget).map(|_| Line::default()));
+        lines.push(Line::from(self.prompt()));
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .render(inner, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(buf: &Buffer, y: u16) -> String {
+        // Inside the borders
+        (1..buf.area.width - 1)
+            .map(|x| buf[(x, y)].symbol())
+            .collect::<String>()
+            .trim_end()
+            .to_string()
+    }
+
+    #[test]
+    fn test_latest_lines_and_prompt_are_shown() {
+        let mut narration = Narration::default();
+        for i in 0..10 {
+            narration.say(format!("line {}", i));
+        }
+        let area = Rect::new(0, 0, 30, 6);
+        let mut buf = Buffer::empty(area);
+        NarrationPanel {
+            narration: &narration,
+            command_line: Some("white"),
+        }
+        .render(area, &mut buf);
+
+        assert_eq!(row(&buf, 1), "line 7");
+        assert_eq!(row(&buf, 3), "line 9");
+        assert_eq!(row(&buf, 4), "> white");
+    }
+}
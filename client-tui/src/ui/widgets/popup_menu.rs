@@ -166,7 +166,7 @@ mod tests {
     #[test]
     fn test_menu_items_with_engine() {
         let state = PopupMenuState::new(&GameMode::HumanVsEngine {
-            human_side: crate::state::PlayerColor::White,
+            human_side: crate::state::PlayerSide::White,
         });
         assert!(state.items.contains(&PopupMenuItem::Restart));
         assert!(state.items.contains(&PopupMenuItem::AdjustDifficulty));
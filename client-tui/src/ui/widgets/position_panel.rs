@@ -0,0 +1,130 @@
+use chess::pgn::{PgnGame, PgnMove};
+use chess_client::MoveRecord;
+use cozy_chess::Board;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Widget, Wrap},
+};
+
+/// Numbered movetext of `history` played from `start`: `1. e4 e5 2. Nf3`,
+/// or `12... Qd7 13. Re1` when Black moved first.
+pub fn movetext(start: &Board, history: &[MoveRecord]) -> String {
+    chess::pgn::movetext(start, &pgn_moves(history))
+}
+
+/// PGN of a game in progress, with the starting position as a `FEN` tag
+/// when it is not the standard one. The result is left open (`*`).
+pub fn pgn(start_fen: &str, history: &[MoveRecord]) -> String {
+    let start: Board = start_fen.parse().unwrap_or_default();
+    let mut tags = Vec::new();
+    if !start_fen.is_empty() && start.to_string() != Board::default().to_string() {
+        tags.push(("SetUp".to_string(), "1".to_string()));
+        tags.push(("FEN".to_string(), start.to_string()));
+    }
+    PgnGame {
+        tags,
+        moves: pgn_moves(history),
+        result: "*".to_string(),
+    }
+    .to_pgn()
+}
+
+fn pgn_moves(history: &[MoveRecord]) -> Vec<PgnMove> {
+    history
+        .iter()
+        .map(|record| PgnMove::new(record.san.as_str()))
+        .collect()
+}
+
+/// The current position as FEN and the game so far as PGN movetext, for
+/// pasting into other tools. `y` and `Y` copy them while the panel is
+/// selected.
+pub struct PositionPanel<'a> {
+    pub fen: &'a str,
+    pub movetext: &'a str,
+    pub scroll: u16,
+    pub is_selected: bool,
+}
+
+impl Widget for PositionPanel<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let title = if self.is_selected {
+            "FEN / PGN [SELECTED] (y FEN, Y PGN)"
+        } else {
+            "FEN / PGN (^ to toggle)"
+        };
+        let border_style = if self.is_selected {
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::Cyan)
+        };
+        let block = Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(border_style);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let label = Style::default().fg(Color::DarkGray);
+        let moves = if self.movetext.is_empty() {
+            "No moves yet"
+        } else {
+            self.movetext
+        };
+        let lines = vec![
+            Line::from(Span::styled("FEN", label)),
+            Line::from(self.fen),
+            Line::default(),
+            Line::from(Span::styled("PGN", label)),
+            Line::from(moves),
+        ];
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll, 0))
+            .render(inner, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(san: &str) -> MoveRecord {
+        MoveRecord {
+            san: san.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_movetext_numbers_moves() {
+        let history = [record("e4"), record("e5"), record("Nf3")];
+        assert_eq!(movetext(&Board::default(), &history), "1. e4 e5 2. Nf3");
+        assert_eq!(movetext(&Board::default(), &[]), "");
+    }
+
+    #[test]
+    fn test_movetext_from_black_to_move() {
+        let start: Board = "4k3/8/8/8/8/8/8/4K3 b - - 0 12".parse().unwrap();
+        let history = [record("Kd7"), record("Kd2"), record("Kc6")];
+        assert_eq!(movetext(&start, &history), "12... Kd7 13. Kd2 Kc6");
+    }
+
+    #[test]
+    fn test_pgn_tags_a_custom_start() {
+        let standard = Board::default().to_string();
+        assert_eq!(pgn(&standard, &[record("d4")]), "1. d4 *");
+        assert_eq!(pgn("", &[]), "*");
+
+        let fen = "4k3/8/8/8/8/8/8/4K3 w - - 0 1";
+        assert_eq!(
+            pgn(fen, &[record("Kd2")]),
+            format!("[SetUp \"1\"]\n[FEN \"{}\"]\n\n1. Kd2 *", fen)
+        );
+    }
+}
@@ -0,0 +1,132 @@
+use crate::profiling::{format_duration, FrameProfile, RpcTimings, Timings, FRAME_BUDGET, WINDOW};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+use std::time::Duration;
+
+const OVERLAY_WIDTH: u16 = 44;
+
+/// Frame and server call timings in the bottom-right corner, toggled with `%`.
+pub struct ProfilerOverlay<'a> {
+    pub frames: &'a FrameProfile,
+    pub rpc: &'a RpcTimings,
+    /// Squares the board widget drew on this frame.
+    pub board_redrawn: usize,
+}
+
+/// Colour for a duration measured against a budget.
+fn budget_color(d: Option<Duration>, budget: Duration) -> Color {
+    match d {
+        Some(d) if d > budget => Color::Red,
+        Some(d) if d > budget / 2 => Color::Yellow,
+        Some(_) => Color::Green,
+        None => Color::DarkGray,
+    }
+}
+
+fn timing_line(label: &str, timings: &Timings, budget: Duration) -> Line<'static> {
+    let show = |d: Option<Duration>| d.map(format_duration).unwrap_or_else(|| "-".to_string());
+    Line::from(vec![
+        Span::styled(format!("{:<10}", label), Style::default().fg(Color::Gray)),
+        Span::styled(
+            format!("{:>7}", show(timings.last())),
+            Style::default().fg(budget_color(timings.last(), budget)),
+        ),
+        Span::styled(
+            format!(" avg {:>7}", show(timings.average())),
+            Style::default().fg(budget_color(timings.average(), budget)),
+        ),
+        Span::styled(
+            format!(" max {:>7}", show(timings.max())),
+            Style::default().fg(budget_color(timings.max(), budget)),
+        ),
+    ])
+}
+
+impl Widget for ProfilerOverlay<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let (cols, rows) = self.frames.area;
+        let mut lines = vec![
+            Line::from(Span::styled(
+                format!(
+                    "{}x{} ({} cells)",
+                    cols,
+                    rows,
+                    u32::from(cols) * u32::from(rows)
+                ),
+                Style::default().fg(Color::DarkGray),
+            )),
+            timing_line("draw", &self.frames.draw, FRAME_BUDGET),
+            timing_line("loop", &self.frames.loop_latency, FRAME_BUDGET),
+            Line::from(Span::styled(
+                format!(
+                    "over budget: {} of last {} frames",
+                    self.frames.draw.over(FRAME_BUDGET),
+                    WINDOW
+                ),
+                Style::default().fg(Color::DarkGray),
+            )),
+            Line::from(Span::styled(
+                format!("board: {}/64 squares redrawn", self.board_redrawn),
+                Style::default().fg(Color::DarkGray),
+            )),
+        ];
+
+        let mut rpc = self.rpc.iter().peekable();
+        if rpc.peek().is_some() {
+            lines.push(Line::from(Span::styled(
+                "rpc",
+                Style::default().add_modifier(Modifier::BOLD),
+            )));
+            for (action, timings) in rpc {
+                // Server calls are off the render loop, so a slow one only
+                // delays its own result; flag anything over a quarter second
+                lines.push(timing_line(action, timings, Duration::from_millis(250)));
+            }
+        }
+
+        let width = OVERLAY_WIDTH.min(area.width);
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let overlay_area = Rect::new(
+            area.right().saturating_sub(width),
+            area.bottom().saturating_sub(height),
+            width,
+            height,
+        );
+
+        Clear.render(overlay_area, buf);
+        let block = Block::default()
+            .title(" Profiler ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Magenta))
+            .style(Style::default().bg(Color::Black));
+        Paragraph::new(lines).block(block).render(overlay_area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget_color() {
+        let budget = Duration::from_millis(30);
+        assert_eq!(budget_color(None, budget), Color::DarkGray);
+        assert_eq!(
+            budget_color(Some(Duration::from_millis(5)), budget),
+            Color::Green
+        );
+        assert_eq!(
+            budget_color(Some(Duration::from_millis(20)), budget),
+            Color::Yellow
+        );
+        assert_eq!(
+            budget_color(Some(Duration::from_millis(40)), budget),
+            Color::Red
+        );
+    }
+}
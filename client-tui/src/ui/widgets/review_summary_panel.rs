@@ -1,6 +1,11 @@
 use crate::review_state::ReviewState;
-use chess::is_white_ply;
-use chess_client::{review_score, MoveClassification, PositionReview};
+use crate::ui::widgets::format_clock;
+use analysis::{Phase, PhaseBoundaries};
+use chess::{is_white_ply, Notation};
+use chess_client::{
+    review_score, AccuracyExclusionProto, HandicapAnalysisProto, HandicapSideProto,
+    MoveClassification, PositionReview,
+};
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
@@ -15,6 +20,7 @@ pub struct ReviewSummaryPanel<'a> {
     pub scroll: u16,
     pub is_selected: bool,
     pub expanded: bool,
+    pub notation: Notation,
 }
 
 impl Widget for ReviewSummaryPanel<'_> {
@@ -70,6 +76,39 @@ impl Widget for ReviewSummaryPanel<'_> {
             lines.push(Line::raw(""));
         }
 
+        if let Some(ref opening) = review.opening {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("{} ", opening.eco),
+                    Style::default().fg(Color::Cyan),
+                ),
+                Span::raw(opening.name.clone()),
+            ]));
+            lines.push(Line::from(Span::styled(
+                format!("  in theory until move {}", opening.ply.div_ceil(2)),
+                Style::default().fg(Color::DarkGray),
+            )));
+            lines.push(Line::raw(""));
+        }
+
+        lines.push(build_phase_line(
+            &self.review_state.phases,
+            review.total_plies,
+            self.review_state.current_phase(),
+        ));
+        lines.push(Line::raw(""));
+
+        // Reviews of part of the game say which part, and leave the other
+        // plies out of the totals
+        let analyzed = self.review_state.analyzed_positions();
+        if let Some(coverage) = self.review_state.coverage_label() {
+            lines.push(Line::from(vec![
+                Span::styled("Analysed: ", Style::default().fg(Color::DarkGray)),
+                Span::raw(coverage),
+            ]));
+            lines.push(Line::raw(""));
+        }
+
         // === CURRENT POSITION ANALYSIS (TOP) ===
         if self.review_state.current_ply > 0 {
             lines.push(Line::from(Span::styled(
@@ -110,6 +149,11 @@ impl Widget for ReviewSummaryPanel<'_> {
                         ),
                     ]));
                 }
+            } else {
+                lines.push(Line::from(Span::styled(
+                    "  Not analysed",
+                    Style::default().fg(Color::DarkGray),
+                )));
             }
 
             // Check if there's advanced position analysis
@@ -125,39 +169,33 @@ impl Widget for ReviewSummaryPanel<'_> {
             lines.push(Line::raw(""));
         }
 
-        // Accuracy section
-        lines.push(Line::from(Span::styled(
-            "Accuracy",
-            Style::default()
-                .fg(Color::White)
-                .add_modifier(Modifier::BOLD),
-        )));
+        // Engine-vs-engine games: cost of the skill limitation instead of accuracy
+        if let Some(ref handicap) = review.handicap {
+            lines.extend(build_handicap_lines(handicap, |ply, san| {
+                self.review_state.show_san(&self.notation, ply, san)
+            }));
+        } else {
+            lines.extend(build_accuracy_lines(
+                review.white_accuracy,
+                review.black_accuracy,
+                &review.positions,
+            ));
+        }
 
-        if let Some(white_acc) = review.white_accuracy {
-            lines.push(Line::from(vec![
-                Span::raw("  White: "),
-                Span::styled(
-                    format!("{:.1}%", white_acc),
-                    Style::default().fg(accuracy_color(white_acc)),
-                ),
-                Span::raw("  "),
-                Span::raw(accuracy_bar(white_acc, 20)),
-            ]));
+        if let Some(label) = self.review_state.clock_label() {
+            lines.push(Line::raw(""));
+            lines.extend(build_clock_lines(&label, self.review_state));
         }
-        if let Some(black_acc) = review.black_accuracy {
-            lines.push(Line::from(vec![
-                Span::raw("  Black: "),
-                Span::styled(
-                    format!("{:.1}%", black_acc),
-                    Style::default().fg(accuracy_color(black_acc)),
-                ),
-                Span::raw("  "),
-                Span::raw(accuracy_bar(black_acc, 20)),
-            ]));
+
+        if self.review_state.comparison.is_some() {
+            lines.push(Line::raw(""));
+            lines.extend(build_comparison_lines(self.review_state, |ply, san| {
+                self.review_state.show_san(&self.notation, ply, san)
+            }));
         }
 
         // Eval graph
-        if !review.positions.is_empty() {
+        if !analyzed.is_empty() {
             lines.push(Line::raw(""));
             lines.push(Line::from(Span::styled(
                 "Evaluation",
@@ -166,7 +204,8 @@ impl Widget for ReviewSummaryPanel<'_> {
                     .add_modifier(Modifier::BOLD),
             )));
             let graph_width = (inner.width as usize).saturating_sub(4).min(60);
-            let graph_lines = build_eval_graph(&review.positions, graph_width);
+            let graph_lines =
+                build_eval_graph(&analyzed, graph_width, self.review_state.current_ply);
             lines.extend(graph_lines);
         }
 
@@ -180,7 +219,7 @@ impl Widget for ReviewSummaryPanel<'_> {
                 .add_modifier(Modifier::BOLD),
         )));
 
-        let (white_counts, black_counts) = count_classifications(&review.positions);
+        let (white_counts, black_counts) = count_classifications(&analyzed);
         // Categories with symbols - show all even if count is 0 (legend purposes)
         let categories = [
             (
@@ -188,6 +227,11 @@ impl Widget for ReviewSummaryPanel<'_> {
                 MoveClassification::ClassificationBrilliant as i32,
                 Color::Cyan,
             ),
+            (
+                "!  Great",
+                MoveClassification::ClassificationGreat as i32,
+                Color::LightBlue,
+            ),
             (
                 "!  Excellent",
                 MoveClassification::ClassificationExcellent as i32,
@@ -213,6 +257,11 @@ impl Widget for ReviewSummaryPanel<'_> {
                 MoveClassification::ClassificationBlunder as i32,
                 Color::Red,
             ),
+            (
+                "×  Miss",
+                MoveClassification::ClassificationMiss as i32,
+                Color::LightRed,
+            ),
             (
                 "[] Forced",
                 MoveClassification::ClassificationForced as i32,
@@ -232,6 +281,12 @@ impl Widget for ReviewSummaryPanel<'_> {
 
         lines.push(Line::raw(""));
 
+        // One blunder or a slow bleed: how the loss is spread over the moves
+        if !analyzed.is_empty() {
+            lines.extend(build_cp_loss_lines(&analyzed));
+            lines.push(Line::raw(""));
+        }
+
         // Critical moments
         let critical: Vec<_> = review
             .positions
@@ -240,6 +295,7 @@ impl Widget for ReviewSummaryPanel<'_> {
                 matches!(
                     MoveClassification::try_from(p.classification),
                     Ok(MoveClassification::ClassificationBlunder)
+                        | Ok(MoveClassification::ClassificationMiss)
                         | Ok(MoveClassification::ClassificationMistake)
                 )
             })
@@ -259,11 +315,13 @@ impl Widget for ReviewSummaryPanel<'_> {
                 let side = if is_white { "W" } else { "B" };
                 let class_str = match MoveClassification::try_from(pos.classification) {
                     Ok(MoveClassification::ClassificationBlunder) => "??",
+                    Ok(MoveClassification::ClassificationMiss) => "×",
                     Ok(MoveClassification::ClassificationMistake) => "?",
                     _ => "",
                 };
                 let color = match MoveClassification::try_from(pos.classification) {
                     Ok(MoveClassification::ClassificationBlunder) => Color::Red,
+                    Ok(MoveClassification::ClassificationMiss) => Color::LightRed,
                     _ => Color::Magenta,
                 };
 
@@ -271,7 +329,12 @@ impl Widget for ReviewSummaryPanel<'_> {
                     Span::raw(format!("  {}. ", move_num)),
                     Span::raw(format!("[{}] ", side)),
                     Span::styled(
-                        format!("{}{}", pos.played_san, class_str),
+                        format!(
+                            "{}{}",
+                            self.review_state
+                                .show_san(&self.notation, pos.ply, &pos.played_san),
+                            class_str
+                        ),
                         Style::default().fg(color),
                     ),
                     Span::raw(format!(" ({}cp)", pos.cp_loss)),
@@ -303,6 +366,406 @@ impl Widget for ReviewSummaryPanel<'_> {
     }
 }
 
+/// The moves each phase of the game spans, the current one highlighted,
+/// e.g. `Opening 1-9 · Middlegame 10-31 · Endgame 32-40`.
+fn build_phase_line(phases: &PhaseBoundaries, total_plies: u32, current: Phase) -> Line<'static> {
+    let starts = phases.starts();
+    let mut spans = vec![];
+    for (i, &(phase, from)) in starts.iter().enumerate() {
+        let to = starts
+            .get(i + 1)
+            .map_or(total_plies, |&(_, next)| next.saturating_sub(1));
+        if to < from.max(1) {
+            continue;
+        }
+        if !spans.is_empty() {
+            spans.push(Span::styled(" · ", Style::default().fg(Color::DarkGray)));
+        }
+        let style = if phase == current {
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::Gray)
+        };
+        spans.push(Span::styled(
+            format!(
+                "{} {}-{}",
+                phase.name(),
+                from.max(1).div_ceil(2),
+                to.div_ceil(2)
+            ),
+            style,
+        ));
+    }
+    Line::from(spans)
+}
+
+fn build_accuracy_lines(
+    white: Option<f64>,
+    black: Option<f64>,
+    positions: &[PositionReview],
+) -> Vec<Line<'static>> {
+    let mut lines = vec![Line::from(Span::styled(
+        "Accuracy",
+        Style::default()
+            .fg(Color::White)
+            .add_modifier(Modifier::BOLD),
+    ))];
+
+    for (label, accuracy, is_white) in [("  White: ", white, true), ("  Black: ", black, false)] {
+        if let Some(acc) = accuracy {
+            lines.push(Line::from(vec![
+                Span::raw(label),
+                Span::styled(
+                    format!("{:.1}%", acc),
+                    Style::default().fg(accuracy_color(acc)),
+                ),
+                Span::raw("  "),
+                Span::raw(accuracy_bar(acc, 20)),
+            ]));
+            if let Some(note) = exclusion_note(positions, is_white) {
+                lines.push(Line::from(Span::styled(
+                    format!("         {}", note),
+                    Style::default().fg(Color::DarkGray),
+                )));
+            }
+        }
+    }
+    lines
+}
+
+/// What one side's accuracy counted, when the server left moves out of it,
+/// e.g. "28 moves counted; 6 book, 2 forced left out".
+fn exclusion_note(positions: &[PositionReview], is_white: bool) -> Option<String> {
+    let mut counted = 0;
+    let mut excluded = [0u32; 3];
+    for p in positions
+        .iter()
+        .filter(|p| p.depth > 0 && is_white_ply(p.ply) == is_white)
+    {
+        match p.excluded.map(AccuracyExclusionProto::try_from) {
+            Some(Ok(AccuracyExclusionProto::AccuracyExclusionBook)) => excluded[0] += 1,
+            Some(Ok(AccuracyExclusionProto::AccuracyExclusionForced)) => excluded[1] += 1,
+            Some(Ok(AccuracyExclusionProto::AccuracyExclusionDecided)) => excluded[2] += 1,
+            _ => counted += 1,
+        }
+    }
+    let left_out: Vec<String> = excluded
+        .iter()
+        .zip(["book", "forced", "decided"])
+        .filter(|(count, _)| **count > 0)
+        .map(|(count, reason)| format!("{} {}", count, reason))
+        .collect();
+    if left_out.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "{} move{} counted; {} left out",
+        counted,
+        if counted == 1 { "" } else { "s" },
+        left_out.join(", ")
+    ))
+}
+
+/// Upper bounds of the centipawn-loss buckets; the last bucket is open.
+const CP_LOSS_BUCKETS: [(i32, &str); 4] = [
+    (20, "0-20"),
+    (50, "20-50"),
+    (100, "50-100"),
+    (i32::MAX, "100+"),
+];
+
+/// Width of each side's histogram bar at the largest bucket.
+const CP_LOSS_BAR_WIDTH: usize = 6;
+
+/// Moves per centipawn-loss bucket, and the loss summed over each bucket,
+/// for White and Black.
+fn cp_loss_distribution(positions: &[PositionReview]) -> [([u32; 4], [i64; 4]); 2] {
+    let mut sides = [([0u32; 4], [0i64; 4]); 2];
+    for p in positions {
+        let side = &mut sides[usize::from(!is_white_ply(p.ply))];
+        let loss = p.cp_loss.max(0);
+        let bucket = CP_LOSS_BUCKETS
+            .iter()
+            .position(|&(upper, _)| loss < upper)
+            .unwrap_or(CP_LOSS_BUCKETS.len() - 1);
+        side.0[bucket] += 1;
+        side.1[bucket] += i64::from(loss);
+    }
+    sides
+}
+
+/// Per-side histogram of centipawn loss, with the share of each side's
+/// total loss that came from its 100+ moves.
+fn build_cp_loss_lines(positions: &[PositionReview]) -> Vec<Line<'static>> {
+    let sides = cp_loss_distribution(positions);
+    let mut lines = vec![Line::from(vec![
+        Span::styled(
+            "Centipawn Loss",
+            Style::default()
+                .fg(Color::White)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled("  White / Black", Style::default().fg(Color::DarkGray)),
+    ])];
+
+    let max = sides
+        .iter()
+        .flat_map(|(counts, _)| counts.iter().copied())
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let bar = |count: u32| {
+        let filled = (count as usize * CP_LOSS_BAR_WIDTH).div_ceil(max as usize);
+        format!(
+            "{}{}",
+            "\u{2588}".repeat(filled),
+            " ".repeat(CP_LOSS_BAR_WIDTH - filled)
+        )
+    };
+    let colors = [Color::Green, Color::Yellow, Color::Magenta, Color::Red];
+    for (i, (&(_, label), color)) in CP_LOSS_BUCKETS.iter().zip(colors).enumerate() {
+        let style = Style::default().fg(color);
+        let (white, black) = (sides[0].0[i], sides[1].0[i]);
+        lines.push(Line::from(vec![
+            Span::raw(format!("  {:7}", label)),
+            Span::styled(bar(white), style),
+            Span::raw(format!(" {:<3} ", white)),
+            Span::styled(bar(black), style),
+            Span::raw(format!(" {}", black)),
+        ]));
+    }
+
+    let share = |(_, losses): &([u32; 4], [i64; 4])| {
+        let total: i64 = losses.iter().sum();
+        match total {
+            0 => "-".to_string(),
+            total => format!("{}%", losses[3] * 100 / total),
+        }
+    };
+    lines.push(Line::from(Span::styled(
+        format!(
+            "  Loss from 100+: W {}  B {}",
+            share(&sides[0]),
+            share(&sides[1])
+        ),
+        Style::default().fg(Color::DarkGray),
+    )));
+    lines
+}
+
+/// Simulated clocks of both sides under the time control cycled with `t`.
+fn build_clock_lines(label: &str, review_state: &ReviewState) -> Vec<Line<'static>> {
+    let mut lines = vec![Line::from(Span::styled(
+        format!("Clock {}", label),
+        Style::default()
+            .fg(Color::White)
+            .add_modifier(Modifier::BOLD),
+    ))];
+    let Some(ref simulation) = review_state.clock_simulation else {
+        lines.push(Line::from(Span::styled(
+            "  Simulating...",
+            Style::default().fg(Color::DarkGray),
+        )));
+        return lines;
+    };
+
+    for (label, side) in [("White", &simulation.white), ("Black", &simulation.black)] {
+        let Some(side) = side else { continue };
+        let clock = match side.flagged_at_ply {
+            Some(ply) => Span::styled(
+                format!("flags on move {}", ply.div_ceil(2)),
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            ),
+            None => Span::styled(
+                format!(
+                    "{} left, lowest {}",
+                    format_clock(side.final_ms),
+                    format_clock(side.lowest_ms)
+                ),
+                Style::default().fg(Color::Green),
+            ),
+        };
+        let mut spans = vec![Span::raw(format!("  {}: ", label)), clock];
+        if side.untimed_moves > 0 {
+            spans.push(Span::styled(
+                format!("  ({} untimed)", side.untimed_moves),
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
+        lines.push(Line::from(spans));
+    }
+    if let Some(verdict) = review_state.clock_verdict() {
+        lines.push(Line::from(Span::styled(
+            format!("  {}", verdict),
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+    lines
+}
+
+/// Where the game's earlier review pass, compared with `d`, judged moves
+/// differently. `show_san` writes a move at a ply in the chosen notation.
+fn build_comparison_lines(
+    review_state: &ReviewState,
+    show_san: impl Fn(u32, &str) -> String,
+) -> Vec<Line<'static>> {
+    let (Some(comparison), Some(revision)) = (
+        review_state.comparison.as_ref(),
+        review_state.compared_revision(),
+    ) else {
+        return vec![];
+    };
+    let mut lines = vec![Line::from(vec![
+        Span::styled(
+            format!("Compared with pass {}", revision.revision),
+            Style::default()
+                .fg(Color::White)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            format!(
+                "  (depth {} vs {})",
+                revision.analysis_depth, review_state.review.analysis_depth
+            ),
+            Style::default().fg(Color::DarkGray),
+        ),
+    ])];
+    if let Some(summary) = review_state.comparison_summary() {
+        lines.push(Line::raw(format!("  {}", summary)));
+    }
+    lines.push(Line::from(Span::styled(
+        format!(
+            "  Judged differently: {}  Best move changed: {}",
+            comparison.classification_changes, comparison.best_move_changes
+        ),
+        Style::default().fg(Color::DarkGray),
+    )));
+
+    for change in comparison.changes.iter().take(12) {
+        let side = if is_white_ply(change.ply) { "W" } else { "B" };
+        let mut spans = vec![
+            Span::raw(format!("  {}. [{}] ", change.ply.div_ceil(2), side)),
+            Span::raw(show_san(change.ply, &change.played_san)),
+        ];
+        if change.old_classification != change.new_classification {
+            let (old, old_color) = classification_word(change.old_classification);
+            let (new, new_color) = classification_word(change.new_classification);
+            spans.push(Span::raw(" "));
+            spans.push(Span::styled(old, Style::default().fg(old_color)));
+            spans.push(Span::raw(" \u{2192} "));
+            spans.push(Span::styled(new, Style::default().fg(new_color)));
+        }
+        if change.old_best_move_san != change.new_best_move_san {
+            spans.push(Span::styled(
+                format!(
+                    "  best {} \u{2192} {}",
+                    show_san(change.ply, &change.old_best_move_san),
+                    show_san(change.ply, &change.new_best_move_san)
+                ),
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
+        lines.push(Line::from(spans));
+    }
+    if comparison.changes.len() > 12 {
+        lines.push(Line::from(Span::styled(
+            format!("  and {} more", comparison.changes.len() - 12),
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+    lines
+}
+
+fn classification_word(classification: i32) -> (&'static str, Color) {
+    match MoveClassification::try_from(classification) {
+        Ok(MoveClassification::ClassificationBrilliant) => ("brilliant", Color::Cyan),
+        Ok(MoveClassification::ClassificationGreat) => ("great", Color::LightBlue),
+        Ok(MoveClassification::ClassificationBest) => ("best", Color::Green),
+        Ok(MoveClassification::ClassificationExcellent) => ("excellent", Color::Cyan),
+        Ok(MoveClassification::ClassificationGood) => ("good", Color::White),
+        Ok(MoveClassification::ClassificationInaccuracy) => ("inaccuracy", Color::Yellow),
+        Ok(MoveClassification::ClassificationMistake) => ("mistake", Color::Magenta),
+        Ok(MoveClassification::ClassificationBlunder) => ("blunder", Color::Red),
+        Ok(MoveClassification::ClassificationMiss) => ("miss", Color::LightRed),
+        Ok(MoveClassification::ClassificationForced) => ("forced", Color::DarkGray),
+        Ok(MoveClassification::ClassificationBook) => ("book", Color::DarkGray),
+        Err(_) => ("?", Color::DarkGray),
+    }
+}
+
+/// Handicap analysis: how far each engine strayed from full strength at its skill level.
+/// `show_san` writes a move at a ply in the chosen notation.
+fn build_handicap_lines(
+    handicap: &HandicapAnalysisProto,
+    show_san: impl Fn(u32, &str) -> String,
+) -> Vec<Line<'static>> {
+    let mut lines = vec![Line::from(vec![
+        Span::styled(
+            "Handicap Analysis",
+            Style::default()
+                .fg(Color::White)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            format!("  (skill {} vs full strength)", handicap.skill_level),
+            Style::default().fg(Color::DarkGray),
+        ),
+    ])];
+
+    let sides = [("White", &handicap.white), ("Black", &handicap.black)];
+    for (label, side) in sides {
+        let Some(side) = side else { continue };
+        lines.push(Line::from(vec![
+            Span::raw(format!("  {}: ", label)),
+            Span::styled(
+                format!("{:.0}% best", side.best_move_rate),
+                Style::default().fg(accuracy_color(side.best_move_rate)),
+            ),
+            Span::raw(format!(
+                "  avg -{:.0}cp  cost {}cp",
+                side.avg_cp_loss, side.total_cp_cost
+            )),
+        ]));
+    }
+
+    let mut missed: Vec<_> = sides
+        .iter()
+        .filter_map(|(_, side)| side.as_ref())
+        .flat_map(|side: &HandicapSideProto| side.missed_wins.iter())
+        .collect();
+    missed.sort_by_key(|m| m.ply);
+    if !missed.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "  Missed wins",
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )));
+        for m in missed.iter().take(10) {
+            let side = if is_white_ply(m.ply) { "W" } else { "B" };
+            lines.push(Line::from(vec![
+                Span::raw(format!("  {}. [{}] ", m.ply.div_ceil(2), side)),
+                Span::styled(
+                    show_san(m.ply, &m.played_san),
+                    Style::default().fg(Color::Red),
+                ),
+                Span::raw(" instead of "),
+                Span::styled(
+                    show_san(m.ply, &m.best_move_san),
+                    Style::default().fg(Color::Green),
+                ),
+                Span::styled(
+                    format!(" ({:+} \u{2192} {:+})", m.best_cp, m.played_cp),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]));
+        }
+    }
+    lines
+}
+
 fn accuracy_color(accuracy: f64) -> Color {
     if accuracy >= 90.0 {
         Color::Green
@@ -343,7 +806,11 @@ fn score_to_cp_clamped(pos: &PositionReview) -> i32 {
 /// Renders a sparkline chart: positive = white advantage (above midline),
 /// negative = black advantage (below midline).
 /// Uses 5 rows of height. The midline is row 2 (0-indexed).
-fn build_eval_graph(positions: &[PositionReview], width: usize) -> Vec<Line<'static>> {
+fn build_eval_graph(
+    positions: &[PositionReview],
+    width: usize,
+    current_ply: u32,
+) -> Vec<Line<'static>> {
     if positions.is_empty() || width == 0 {
         return vec![];
     }
@@ -363,6 +830,7 @@ fn build_eval_graph(positions: &[PositionReview], width: usize) -> Vec<Line<'sta
             // Color critical moments
             let col_color = match MoveClassification::try_from(pos.classification) {
                 Ok(MoveClassification::ClassificationBlunder) => Some(Color::Red),
+                Ok(MoveClassification::ClassificationMiss) => Some(Color::LightRed),
                 Ok(MoveClassification::ClassificationMistake) => Some(Color::Yellow),
                 _ => None,
             };
@@ -377,10 +845,16 @@ fn build_eval_graph(positions: &[PositionReview], width: usize) -> Vec<Line<'sta
     ];
 
     // Build rows top to bottom
+    // Column showing the current ply, highlighted to follow review navigation
+    let cursor = positions
+        .iter()
+        .position(|p| p.ply == current_ply)
+        .map(|idx| idx * width / total);
+
     let mut rows: Vec<Line<'static>> = Vec::with_capacity(height);
     for row in 0..height {
         let mut spans: Vec<Span<'static>> = vec![Span::raw("  ")];
-        for &(cp, critical_color) in &cols {
+        for (col, &(cp, critical_color)) in cols.iter().enumerate() {
             // Map cp [-500, 500] to a fill level [0.0, height*8] sub-cells
             // midline at mid*8 sub-cells from bottom
             let max_sub = (height * 8) as f64;
@@ -408,10 +882,11 @@ fn build_eval_graph(positions: &[PositionReview], width: usize) -> Vec<Line<'sta
                 critical_color.unwrap_or(Color::Gray)
             };
 
-            spans.push(Span::styled(
-                block_char.to_string(),
-                Style::default().fg(fg),
-            ));
+            let mut style = Style::default().fg(fg);
+            if cursor == Some(col) {
+                style = style.bg(Color::DarkGray);
+            }
+            spans.push(Span::styled(block_char.to_string(), style));
         }
         rows.push(Line::from(spans));
     }
@@ -441,10 +916,12 @@ fn count_classifications(
 fn classification_marker(classification: &i32) -> Option<(&'static str, Color)> {
     match MoveClassification::try_from(*classification) {
         Ok(MoveClassification::ClassificationBrilliant) => Some(("!!", Color::Cyan)),
+        Ok(MoveClassification::ClassificationGreat) => Some(("!", Color::LightBlue)),
         Ok(MoveClassification::ClassificationExcellent) => Some(("!", Color::Cyan)),
         Ok(MoveClassification::ClassificationInaccuracy) => Some(("?!", Color::Yellow)),
         Ok(MoveClassification::ClassificationMistake) => Some(("?", Color::Magenta)),
         Ok(MoveClassification::ClassificationBlunder) => Some(("??", Color::Red)),
+        Ok(MoveClassification::ClassificationMiss) => Some(("×", Color::LightRed)),
         Ok(MoveClassification::ClassificationForced) => Some(("[]", Color::DarkGray)),
         _ => None,
     }
@@ -461,3 +938,76 @@ fn cp_loss_color(cp_loss: i32) -> Color {
         Color::Red
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cp_loss_buckets_per_side() {
+        let positions: Vec<PositionReview> = [5, 0, 30, 150, 60, 20, 400, 19]
+            .iter()
+            .enumerate()
+            .map(|(i, &cp_loss)| PositionReview {
+                ply: i as u32 + 1,
+                cp_loss,
+                ..Default::default()
+            })
+            .collect();
+        let [white, black] = cp_loss_distribution(&positions);
+        assert_eq!(white.0, [1, 1, 1, 1]);
+        assert_eq!(white.1, [5, 30, 60, 400]);
+        assert_eq!(black.0, [2, 1, 0, 1]);
+        assert_eq!(black.1[3], 150);
+    }
+
+    #[test]
+    fn test_exclusion_note_explains_what_was_counted() {
+        let position = |ply: u32, excluded: Option<AccuracyExclusionProto>| PositionReview {
+            ply,
+            depth: 18,
+            excluded: excluded.map(|e| e as i32),
+            ..Default::default()
+        };
+        let mut positions = vec![
+            position(1, Some(AccuracyExclusionProto::AccuracyExclusionBook)),
+            position(2, Some(AccuracyExclusionProto::AccuracyExclusionBook)),
+            position(3, None),
+            position(4, None),
+            position(5, Some(AccuracyExclusionProto::AccuracyExclusionDecided)),
+            position(7, None),
+        ];
+        assert_eq!(
+            exclusion_note(&positions, true).as_deref(),
+            Some("2 moves counted; 1 book, 1 decided left out")
+        );
+        assert_eq!(
+            exclusion_note(&positions, false).as_deref(),
+            Some("1 move counted; 1 book left out")
+        );
+
+        // Nothing left out, or only plies outside the analysis
+        positions.retain(|p| p.excluded.is_none());
+        positions.push(PositionReview {
+            ply: 9,
+            ..Default::default()
+        });
+        assert_eq!(exclusion_note(&positions, true), None);
+    }
+
+    #[test]
+    fn test_phase_line_spans_moves_and_highlights_current() {
+        let phases = PhaseBoundaries {
+            middlegame_from: Some(19),
+            endgame_from: Some(63),
+        };
+        let line = build_phase_line(&phases, 80, Phase::Middlegame);
+        let text: String = line.spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(text, "Opening 1-9 · Middlegame 10-31 · Endgame 32-40");
+        assert_eq!(line.spans[2].style.fg, Some(Color::Yellow));
+
+        let line = build_phase_line(&PhaseBoundaries::default(), 12, Phase::Opening);
+        let text: String = line.spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(text, "Opening 1-6");
+    }
+}
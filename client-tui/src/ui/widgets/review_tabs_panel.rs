@@ -1,7 +1,7 @@
 #![allow(dead_code)]
 
 use crate::review_state::ReviewState;
-use chess::is_white_ply;
+use chess::{is_white_ply, Notation};
 use chess_client::{
     review_score, MoveClassification, PositionReview, TacticalTagKindProto, TacticalTagProto,
 };
@@ -26,6 +26,7 @@ pub struct ReviewTabsPanel<'a> {
     pub is_selected: bool,
     pub expanded: bool,
     pub moves_selection: Option<u32>,
+    pub notation: Notation,
 }
 
 impl Widget for ReviewTabsPanel<'_> {
@@ -141,8 +142,9 @@ impl ReviewTabsPanel<'_> {
             ]));
         }
 
-        // Eval graph
-        if !review.positions.is_empty() {
+        // Eval graph, over the analysed plies only
+        let analyzed = self.review_state.analyzed_positions();
+        if !analyzed.is_empty() {
             lines.push(Line::raw(""));
             lines.push(Line::from(Span::styled(
                 "Evaluation",
@@ -151,7 +153,8 @@ impl ReviewTabsPanel<'_> {
                     .add_modifier(Modifier::BOLD),
             )));
             let graph_width = (inner_width as usize).saturating_sub(4).min(60);
-            let graph_lines = build_eval_graph(&review.positions, graph_width);
+            let graph_lines =
+                build_eval_graph(&analyzed, graph_width, self.review_state.current_ply);
             lines.extend(graph_lines);
         }
 
@@ -164,7 +167,7 @@ impl ReviewTabsPanel<'_> {
                 .add_modifier(Modifier::BOLD),
         )));
 
-        let (white_counts, black_counts) = count_classifications(&review.positions);
+        let (white_counts, black_counts) = count_classifications(&analyzed);
         // Categories with symbols - show all even if count is 0 (legend purposes)
         let categories = [
             (
@@ -172,6 +175,11 @@ impl ReviewTabsPanel<'_> {
                 MoveClassification::ClassificationBrilliant as i32,
                 Color::Cyan,
             ),
+            (
+                "!  Great",
+                MoveClassification::ClassificationGreat as i32,
+                Color::LightBlue,
+            ),
             (
                 "!  Excellent",
                 MoveClassification::ClassificationExcellent as i32,
@@ -197,6 +205,11 @@ impl ReviewTabsPanel<'_> {
                 MoveClassification::ClassificationBlunder as i32,
                 Color::Red,
             ),
+            (
+                "×  Miss",
+                MoveClassification::ClassificationMiss as i32,
+                Color::LightRed,
+            ),
             (
                 "[] Forced",
                 MoveClassification::ClassificationForced as i32,
@@ -223,6 +236,7 @@ impl ReviewTabsPanel<'_> {
                 matches!(
                     MoveClassification::try_from(p.classification),
                     Ok(MoveClassification::ClassificationBlunder)
+                        | Ok(MoveClassification::ClassificationMiss)
                         | Ok(MoveClassification::ClassificationMistake)
                 )
             })
@@ -242,11 +256,13 @@ impl ReviewTabsPanel<'_> {
                 let side = if is_white { "W" } else { "B" };
                 let class_str = match MoveClassification::try_from(pos.classification) {
                     Ok(MoveClassification::ClassificationBlunder) => "??",
+                    Ok(MoveClassification::ClassificationMiss) => "×",
                     Ok(MoveClassification::ClassificationMistake) => "?",
                     _ => "",
                 };
                 let color = match MoveClassification::try_from(pos.classification) {
                     Ok(MoveClassification::ClassificationBlunder) => Color::Red,
+                    Ok(MoveClassification::ClassificationMiss) => Color::LightRed,
                     _ => Color::Magenta,
                 };
 
@@ -254,7 +270,12 @@ impl ReviewTabsPanel<'_> {
                     Span::raw(format!("  {}. ", move_num)),
                     Span::raw(format!("[{}] ", side)),
                     Span::styled(
-                        format!("{}{}", pos.played_san, class_str),
+                        format!(
+                            "{}{}",
+                            self.review_state
+                                .show_san(&self.notation, pos.ply, &pos.played_san),
+                            class_str
+                        ),
                         Style::default().fg(color),
                     ),
                 ]));
@@ -385,13 +406,18 @@ impl ReviewTabsPanel<'_> {
             // Classification badge (colored header)
             let class_name = classification_display_name(pos.classification);
             let class_color = classification_color(pos.classification);
-            lines.push(Line::from(Span::styled(
+            let mut badge = vec![Span::styled(
                 format!(" {} ", class_name),
                 Style::default()
                     .fg(Color::Black)
                     .bg(class_color)
                     .add_modifier(Modifier::BOLD),
-            )));
+            )];
+            if let Some(only_move) = only_move_badge(pos) {
+                badge.push(Span::raw(" "));
+                badge.push(only_move);
+            }
+            lines.push(Line::from(badge));
             lines.push(Line::raw(""));
 
             // Move info
@@ -415,7 +441,12 @@ impl ReviewTabsPanel<'_> {
             lines.push(Line::from(vec![
                 Span::styled("Played: ", Style::default().fg(Color::DarkGray)),
                 Span::styled(
-                    format!("{}{}", pos.played_san, marker),
+                    format!(
+                        "{}{}",
+                        self.review_state
+                            .show_san(&self.notation, pos.ply, &pos.played_san),
+                        marker
+                    ),
                     Style::default()
                         .fg(class_color)
                         .add_modifier(Modifier::BOLD),
@@ -427,7 +458,8 @@ impl ReviewTabsPanel<'_> {
                 lines.push(Line::from(vec![
                     Span::styled("Best:   ", Style::default().fg(Color::DarkGray)),
                     Span::styled(
-                        pos.best_move_san.clone(),
+                        self.review_state
+                            .show_san(&self.notation, pos.ply, &pos.best_move_san),
                         Style::default()
                             .fg(Color::Green)
                             .add_modifier(Modifier::BOLD),
@@ -567,10 +599,12 @@ fn cp_loss_color(cp_loss: i32) -> Color {
 fn classification_marker(classification: &i32) -> Option<(&'static str, Color)> {
     match MoveClassification::try_from(*classification) {
         Ok(MoveClassification::ClassificationBrilliant) => Some(("!!", Color::Cyan)),
+        Ok(MoveClassification::ClassificationGreat) => Some(("!", Color::LightBlue)),
         Ok(MoveClassification::ClassificationExcellent) => Some(("!", Color::Cyan)),
         Ok(MoveClassification::ClassificationInaccuracy) => Some(("?!", Color::Yellow)),
         Ok(MoveClassification::ClassificationMistake) => Some(("?", Color::Magenta)),
         Ok(MoveClassification::ClassificationBlunder) => Some(("??", Color::Red)),
+        Ok(MoveClassification::ClassificationMiss) => Some(("×", Color::LightRed)),
         Ok(MoveClassification::ClassificationForced) => Some(("[]", Color::DarkGray)),
         _ => None,
     }
@@ -636,7 +670,7 @@ fn render_tactical_tags(lines: &mut Vec<Line<'_>>, tags: &[TacticalTagProto]) {
     }
 }
 
-fn tactical_tag_kind_name(kind: i32) -> &'static str {
+pub(crate) fn tactical_tag_kind_name(kind: i32) -> &'static str {
     match TacticalTagKindProto::try_from(kind) {
         Ok(TacticalTagKindProto::TacticalTagKindFork) => "Fork",
         Ok(TacticalTagKindProto::TacticalTagKindPin) => "Pin",
@@ -648,6 +682,8 @@ fn tactical_tag_kind_name(kind: i32) -> &'static str {
         Ok(TacticalTagKindProto::TacticalTagKindZwischenzug) => "Zwischenzug",
         Ok(TacticalTagKindProto::TacticalTagKindBackRankWeakness) => "Back Rank",
         Ok(TacticalTagKindProto::TacticalTagKindMateThreat) => "Mate Threat",
+        Ok(TacticalTagKindProto::TacticalTagKindDeflection) => "Deflection",
+        Ok(TacticalTagKindProto::TacticalTagKindDecoy) => "Decoy",
         _ => "Unknown",
     }
 }
@@ -698,30 +734,49 @@ fn render_tension(lines: &mut Vec<Line<'_>>, tension: &chess_client::PositionTen
     ]));
 }
 
-fn classification_display_name(classification: i32) -> &'static str {
+/// Badge for a position where one move alone held the evaluation, shown next
+/// to the classification so a large cp loss reads as a missed only move
+/// rather than a plain blunder.
+pub(crate) fn only_move_badge(pos: &PositionReview) -> Option<Span<'static>> {
+    pos.only_move.then(|| {
+        Span::styled(
+            " ONLY MOVE ",
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::LightMagenta)
+                .add_modifier(Modifier::BOLD),
+        )
+    })
+}
+
+pub(crate) fn classification_display_name(classification: i32) -> &'static str {
     match MoveClassification::try_from(classification) {
         Ok(MoveClassification::ClassificationBrilliant) => "BRILLIANT",
+        Ok(MoveClassification::ClassificationGreat) => "GREAT",
         Ok(MoveClassification::ClassificationBest) => "BEST",
         Ok(MoveClassification::ClassificationExcellent) => "EXCELLENT",
         Ok(MoveClassification::ClassificationGood) => "GOOD",
         Ok(MoveClassification::ClassificationInaccuracy) => "INACCURACY",
         Ok(MoveClassification::ClassificationMistake) => "MISTAKE",
         Ok(MoveClassification::ClassificationBlunder) => "BLUNDER",
+        Ok(MoveClassification::ClassificationMiss) => "MISS",
         Ok(MoveClassification::ClassificationForced) => "FORCED",
         Ok(MoveClassification::ClassificationBook) => "BOOK",
         _ => "UNKNOWN",
     }
 }
 
-fn classification_color(classification: i32) -> Color {
+pub(crate) fn classification_color(classification: i32) -> Color {
     match MoveClassification::try_from(classification) {
         Ok(MoveClassification::ClassificationBrilliant) => Color::Cyan,
+        Ok(MoveClassification::ClassificationGreat) => Color::LightBlue,
         Ok(MoveClassification::ClassificationBest) => Color::LightGreen,
         Ok(MoveClassification::ClassificationExcellent) => Color::Cyan,
         Ok(MoveClassification::ClassificationGood) => Color::White,
         Ok(MoveClassification::ClassificationInaccuracy) => Color::Yellow,
         Ok(MoveClassification::ClassificationMistake) => Color::Magenta,
         Ok(MoveClassification::ClassificationBlunder) => Color::Red,
+        Ok(MoveClassification::ClassificationMiss) => Color::LightRed,
         Ok(MoveClassification::ClassificationForced) => Color::DarkGray,
         _ => Color::White,
     }
@@ -730,10 +785,12 @@ fn classification_color(classification: i32) -> Color {
 fn classification_marker_str(classification: i32) -> &'static str {
     match MoveClassification::try_from(classification) {
         Ok(MoveClassification::ClassificationBrilliant) => "!!",
+        Ok(MoveClassification::ClassificationGreat) => "!",
         Ok(MoveClassification::ClassificationExcellent) => "!",
         Ok(MoveClassification::ClassificationInaccuracy) => "?!",
         Ok(MoveClassification::ClassificationMistake) => "?",
         Ok(MoveClassification::ClassificationBlunder) => "??",
+        Ok(MoveClassification::ClassificationMiss) => "×",
         Ok(MoveClassification::ClassificationForced) => "[]",
         _ => "",
     }
@@ -807,7 +864,11 @@ fn score_to_cp_clamped(pos: &PositionReview) -> i32 {
     cp.clamp(-500, 500)
 }
 
-fn build_eval_graph(positions: &[PositionReview], width: usize) -> Vec<Line<'static>> {
+fn build_eval_graph(
+    positions: &[PositionReview],
+    width: usize,
+    current_ply: u32,
+) -> Vec<Line<'static>> {
     if positions.is_empty() || width == 0 {
         return vec![];
     }
@@ -825,6 +886,7 @@ fn build_eval_graph(positions: &[PositionReview], width: usize) -> Vec<Line<'sta
 
             let col_color = match MoveClassification::try_from(pos.classification) {
                 Ok(MoveClassification::ClassificationBlunder) => Some(Color::Red),
+                Ok(MoveClassification::ClassificationMiss) => Some(Color::LightRed),
                 Ok(MoveClassification::ClassificationMistake) => Some(Color::Yellow),
                 _ => None,
             };
@@ -837,10 +899,16 @@ fn build_eval_graph(positions: &[PositionReview], width: usize) -> Vec<Line<'sta
         '\u{2588}',
     ];
 
+    // Column showing the current ply, highlighted to follow review navigation
+    let cursor = positions
+        .iter()
+        .position(|p| p.ply == current_ply)
+        .map(|idx| idx * width / total);
+
     let mut rows: Vec<Line<'static>> = Vec::with_capacity(height);
     for row in 0..height {
         let mut spans: Vec<Span<'static>> = vec![Span::raw("  ")];
-        for &(cp, critical_color) in &cols {
+        for (col, &(cp, critical_color)) in cols.iter().enumerate() {
             let max_sub = (height * 8) as f64;
             let mid_sub = (mid * 8) as f64;
             let fill_sub = mid_sub + (cp as f64 / 500.0) * mid_sub;
@@ -863,10 +931,11 @@ fn build_eval_graph(positions: &[PositionReview], width: usize) -> Vec<Line<'sta
                 critical_color.unwrap_or(Color::Gray)
             };
 
-            spans.push(Span::styled(
-                block_char.to_string(),
-                Style::default().fg(fg),
-            ));
+            let mut style = Style::default().fg(fg);
+            if cursor == Some(col) {
+                style = style.bg(Color::DarkGray);
+            }
+            spans.push(Span::styled(block_char.to_string(), style));
         }
         rows.push(Line::from(spans));
     }
@@ -0,0 +1,164 @@
+use analysis::board_analysis::helpers::piece_value;
+use analysis::board_analysis::{Attacker, SquareControl};
+use cozy_chess::{Color as Side, Piece};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget, Wrap},
+};
+
+/// Who controls a square of the analysis board, and whether it is an outpost.
+pub struct SquareControlWidget<'a> {
+    pub control: &'a SquareControl,
+}
+
+impl Widget for SquareControlWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let control = self.control;
+        let dialog_width = 44;
+        let dialog_height = 12;
+        let x = (area.width.saturating_sub(dialog_width)) / 2;
+        let y = (area.height.saturating_sub(dialog_height)) / 2;
+
+        let dialog_area = Rect {
+            x: area.x + x,
+            y: area.y + y,
+            width: dialog_width.min(area.width),
+            height: dialog_height.min(area.height),
+        };
+        Clear.render(dialog_area, buf);
+
+        let block = Block::default()
+            .title(format!(
+                " {} · square control ",
+                chess::format_square(control.square)
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .style(Style::default().bg(Color::Black));
+        let inner = block.inner(dialog_area);
+        block.render(dialog_area, buf);
+
+        let label = Style::default().fg(Color::DarkGray);
+        let mut lines = Vec::new();
+
+        // With a piece on the square, its own side defends and the other attacks
+        let sides = match control.occupant {
+            Some((color, piece)) => {
+                lines.push(Line::from(vec![
+                    Span::styled("Piece      ", label),
+                    Span::raw(format!(
+                        "{} {}",
+                        side_name(color),
+                        crate::narration::piece_word(piece)
+                    )),
+                ]));
+                [("Defenders  ", color), ("Attackers  ", !color)]
+            }
+            None => {
+                lines.push(Line::from(vec![
+                    Span::styled("Piece      ", label),
+                    Span::raw("empty"),
+                ]));
+                [("White      ", Side::White), ("Black      ", Side::Black)]
+            }
+        };
+        for (title, side) in sides {
+            lines.push(Line::from(vec![
+                Span::styled(title, label),
+                Span::raw(attacker_list(control.attackers(side))),
+            ]));
+        }
+
+        let counts = format!("{} v {}", control.white.len(), control.black.len());
+        let verdict = match control.controller {
+            Some(side) => Span::styled(
+                format!("{} ({})", side_name(side), counts),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            None if control.white.is_empty() => Span::raw("nobody"),
+            None => Span::styled(
+                format!("contested ({})", counts),
+                Style::default().fg(Color::Yellow),
+            ),
+        };
+        lines.push(Line::from(vec![
+            Span::styled("Control    ", label),
+            verdict,
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled("Outpost    ", label),
+            match control.outpost_for {
+                Some(side) => Span::styled(
+                    format!("yes, for {}", side_name(side)),
+                    Style::default().fg(Color::Green),
+                ),
+                None => Span::raw("no"),
+            },
+        ]));
+
+        lines.push(Line::raw(""));
+        lines.push(Line::from(Span::styled("Any key to close", label)));
+
+        Paragraph::new(lines)
+            .alignment(Alignment::Left)
+            .wrap(Wrap { trim: true })
+            .render(inner, buf);
+    }
+}
+
+fn side_name(side: Side) -> &'static str {
+    match side {
+        Side::White => "White",
+        Side::Black => "Black",
+    }
+}
+
+/// "P c4 (1), N c3 (3)": each piece with its square and value in pawns.
+fn attacker_list(attackers: &[Attacker]) -> String {
+    if attackers.is_empty() {
+        return "none".to_string();
+    }
+    attackers
+        .iter()
+        .map(|a| {
+            let piece = char::from(a.piece).to_ascii_uppercase();
+            let square = chess::format_square(a.from);
+            // The king has no trade value
+            match a.piece {
+                Piece::King => format!("{} {}", piece, square),
+                _ => format!("{} {} ({})", piece, square, piece_value(a.piece) / 100),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cozy_chess::Square;
+
+    #[test]
+    fn test_attacker_list_shows_piece_square_and_value() {
+        let attackers = [
+            Attacker {
+                from: Square::C4,
+                piece: Piece::Pawn,
+            },
+            Attacker {
+                from: Square::C3,
+                piece: Piece::Knight,
+            },
+        ];
+        assert_eq!(attacker_list(&attackers), "P c4 (1), N c3 (3)");
+        let king = [Attacker {
+            from: Square::E1,
+            piece: Piece::King,
+        }];
+        assert_eq!(attacker_list(&king), "K e1");
+        assert_eq!(attacker_list(&[]), "none");
+    }
+}
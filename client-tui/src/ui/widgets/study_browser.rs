@@ -0,0 +1,494 @@
+use super::game_info_panel::format_review_score;
+use chess_client::{
+    GetStudyResponse, PlayerSideProto, StudyFilterProto, StudyInfo, StudyNodeProto,
+    StudyOutcomeType,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+
+const OUTCOMES: [StudyOutcomeType; 4] = [
+    StudyOutcomeType::StudyOutcomeAny,
+    StudyOutcomeType::StudyOutcomeWin,
+    StudyOutcomeType::StudyOutcomeDraw,
+    StudyOutcomeType::StudyOutcomeLoss,
+];
+
+const SIDES: [Option<PlayerSideProto>; 3] = [
+    None,
+    Some(PlayerSideProto::White),
+    Some(PlayerSideProto::Black),
+];
+
+fn outcome_name(outcome: StudyOutcomeType) -> &'static str {
+    match outcome {
+        StudyOutcomeType::StudyOutcomeAny => "any",
+        StudyOutcomeType::StudyOutcomeWin => "wins",
+        StudyOutcomeType::StudyOutcomeDraw => "draws",
+        StudyOutcomeType::StudyOutcomeLoss => "losses",
+    }
+}
+
+fn side_name(side: Option<PlayerSideProto>) -> &'static str {
+    match side {
+        None => "either",
+        Some(PlayerSideProto::White) => "white",
+        Some(PlayerSideProto::Black) => "black",
+    }
+}
+
+/// What a study was built from, e.g. "Sicilian, losses, as white".
+pub fn filter_label(filter: &StudyFilterProto) -> String {
+    let mut parts = vec![];
+    if let Some(opening) = &filter.opening {
+        parts.push(opening.clone());
+    }
+    let outcome = StudyOutcomeType::try_from(filter.outcome).unwrap_or_default();
+    if outcome != StudyOutcomeType::StudyOutcomeAny {
+        parts.push(outcome_name(outcome).to_string());
+    }
+    if let Some(opponent) = &filter.opponent {
+        parts.push(format!("vs {}", opponent));
+    }
+    if let Some(side) = filter
+        .human_side
+        .and_then(|s| PlayerSideProto::try_from(s).ok())
+    {
+        parts.push(format!("as {}", side_name(Some(side))));
+    }
+    if parts.is_empty() {
+        "all games".to_string()
+    } else {
+        parts.join(", ")
+    }
+}
+
+/// Results from White's side, e.g. "+3 =1 -2".
+pub fn score_label(white_wins: u32, draws: u32, black_wins: u32) -> String {
+    format!("+{} ={} -{}", white_wins, draws, black_wins)
+}
+
+/// Rows of the study list: name, games, results, filter.
+pub fn study_rows(studies: &[StudyInfo]) -> Vec<Vec<String>> {
+    studies
+        .iter()
+        .map(|s| {
+            vec![
+                s.name.clone(),
+                s.games.to_string(),
+                score_label(s.white_wins, s.draws, s.black_wins),
+                s.filter.as_ref().map(filter_label).unwrap_or_default(),
+            ]
+        })
+        .collect()
+}
+
+/// The node reached by following `path`, a child index per move.
+pub fn node_at<'a>(root: &'a StudyNodeProto, path: &[usize]) -> Option<&'a StudyNodeProto> {
+    path.iter()
+        .try_fold(root, |node, &index| node.children.get(index))
+}
+
+/// The moves along `path` with move numbers, e.g. "1. e4 c5 2. Nf3".
+pub fn line_label(root: &StudyNodeProto, path: &[usize]) -> String {
+    let mut words = vec![];
+    let mut node = root;
+    for (ply, &index) in path.iter().enumerate() {
+        let Some(child) = node.children.get(index) else {
+            break;
+        };
+        if ply % 2 == 0 {
+            words.push(format!("{}.", ply / 2 + 1));
+        }
+        words.push(child.san.clone());
+        node = child;
+    }
+    words.join(" ")
+}
+
+/// What the reviews said about a move, e.g. "avg loss 48cp, 1 mistake, best Nf3".
+pub fn review_label(node: &StudyNodeProto) -> String {
+    let mut parts = vec![];
+    if let Some(loss) = node.average_cp_loss {
+        parts.push(format!("avg loss {:.0}cp", loss));
+    }
+    for (count, singular, plural) in [
+        (node.inaccuracies, "inaccuracy", "inaccuracies"),
+        (node.mistakes, "mistake", "mistakes"),
+        (node.blunders, "blunder", "blunders"),
+    ] {
+        match count {
+            0 => {}
+            1 => parts.push(format!("1 {}", singular)),
+            n => parts.push(format!("{} {}", n, plural)),
+        }
+    }
+    if let Some(best) = &node.best_san {
+        parts.push(format!("best {}", best));
+    }
+    parts.join(", ")
+}
+
+const TREE_KEYS: &str =
+    " \u{2191}/\u{2193}: Select | \u{2192}/Enter: Play | \u{2190}: Back | c: Chapters | e: Export PGN | Esc: Studies ";
+
+/// A study's tree, one position at a time: the moves played from it with
+/// how often, how they ended and what the reviews said.
+pub struct StudyTreeView<'a> {
+    pub study: &'a GetStudyResponse,
+    pub path: &'a [usize],
+    pub selected: usize,
+}
+
+impl Widget for StudyTreeView<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let name = self
+            .study
+            .study
+            .as_ref()
+            .map(|s| s.name.as_str())
+            .unwrap_or("Study");
+        let block = Block::default()
+            .title(format!(" {} ", name))
+            .title_bottom(Line::from(TREE_KEYS).centered())
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .style(Style::default().bg(Color::Black));
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let Some(root) = self.study.root.as_ref() else {
+            return;
+        };
+        let Some(node) = node_at(root, self.path) else {
+            return;
+        };
+
+        let line = line_label(root, self.path);
+        let mut lines = vec![
+            Line::from(vec![
+                Span::styled("Line: ", Style::default().fg(Color::DarkGray)),
+                Span::styled(
+                    if line.is_empty() {
+                        "start position".to_string()
+                    } else {
+                        line
+                    },
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                ),
+            ]),
+            Line::from(Span::styled(
+                format!(
+                    "{} games ({})",
+                    node.games,
+                    score_label(node.white_wins, node.draws, node.black_wins)
+                ),
+                Style::default().fg(Color::DarkGray),
+            )),
+            Line::raw(""),
+        ];
+
+        if node.children.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "No game continued past this move in the study.",
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+        for (i, child) in node.children.iter().enumerate() {
+            let selected = i == self.selected;
+            let mut style = Style::default().fg(Color::White);
+            if selected {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+            let mut spans = vec![
+                Span::styled(format!("{:<8}", child.san), style),
+                Span::styled(format!("{:>4} games  ", child.games), style),
+                Span::styled(
+                    format!(
+                        "{:<14}",
+                        score_label(child.white_wins, child.draws, child.black_wins)
+                    ),
+                    style,
+                ),
+            ];
+            if let Some(eval) = &child.eval {
+                let (text, color) = format_review_score(eval);
+                spans.push(Span::styled(format!("{:>7}  ", text), style.fg(color)));
+            }
+            let review = review_label(child);
+            if !review.is_empty() {
+                spans.push(Span::styled(review, Style::default().fg(Color::Magenta)));
+            }
+            lines.push(Line::from(spans));
+        }
+
+        Paragraph::new(lines).render(inner, buf);
+    }
+}
+
+/// A study being set up in the New Study dialog.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StudyDraft {
+    pub name: String,
+    pub opening: String,
+    pub outcome: StudyOutcomeType,
+    pub side: Option<PlayerSideProto>,
+    /// Start without games, to fill with chapters instead.
+    pub blank: bool,
+    /// Field being edited: 0 name, 1 opening, 2 outcome, 3 side, 4 games.
+    pub field: usize,
+    pub error: Option<String>,
+}
+
+impl Default for StudyDraft {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            opening: String::new(),
+            outcome: StudyOutcomeType::StudyOutcomeLoss,
+            side: None,
+            blank: false,
+            field: 0,
+            error: None,
+        }
+    }
+}
+
+fn cycle<T: Copy + PartialEq>(options: &[T], current: T, direction: i32) -> T {
+    let index = options.iter().position(|&o| o == current).unwrap_or(0) as i32;
+    options[(index + direction).rem_euclid(options.len() as i32) as usize]
+}
+
+impl StudyDraft {
+    pub fn next_field(&mut self) {
+        self.field = (self.field + 1) % 5;
+    }
+
+    pub fn previous_field(&mut self) {
+        self.field = (self.field + 4) % 5;
+    }
+
+    /// Cycle the outcome, side or games, whichever field is selected.
+    pub fn cycle(&mut self, direction: i32) {
+        match self.field {
+            2 => self.outcome = cycle(&OUTCOMES, self.outcome, direction),
+            3 => self.side = cycle(&SIDES, self.side, direction),
+            4 => self.blank = !self.blank,
+            _ => {}
+        }
+    }
+
+    /// Type into the name or opening field.
+    pub fn push_char(&mut self, c: char) {
+        let text = match self.field {
+            0 => &mut self.name,
+            1 => &mut self.opening,
+            _ => return,
+        };
+        if text.len() < 40 {
+            text.push(c);
+        }
+    }
+
+    pub fn pop_char(&mut self) {
+        match self.field {
+            0 => self.name.pop(),
+            1 => self.opening.pop(),
+            _ => None,
+        };
+    }
+
+    /// The name to save the study under, if one was entered.
+    pub fn parse_name(&self) -> Result<String, String> {
+        let name = self.name.trim();
+        if name.is_empty() {
+            return Err("Enter a name for the study".to_string());
+        }
+        Ok(name.to_string())
+    }
+
+    pub fn filter(&self) -> StudyFilterProto {
+        let opening = self.opening.trim();
+        StudyFilterProto {
+            opening: (!opening.is_empty()).then(|| opening.to_string()),
+            outcome: self.outcome as i32,
+            opponent: None,
+            human_side: self.side.map(|s| s as i32),
+        }
+    }
+}
+
+/// Dialog for building a new study.
+pub struct StudyDialog<'a> {
+    pub draft: &'a StudyDraft,
+}
+
+impl Widget for StudyDialog<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let width = area.width.min(56);
+        let height = area.height.min(11);
+        let area = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        };
+        Clear.render(area, buf);
+
+        let block = Block::default()
+            .title(" New Study ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow))
+            .style(Style::default().bg(Color::Black));
+
+        let draft = self.draft;
+        let field = |index: usize, name: &'static str, value: String| {
+            let style = if draft.field == index {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            let hint = if index < 2 {
+                ""
+            } else {
+                " [\u{2190}/\u{2192}]"
+            };
+            Line::from(vec![
+                Span::styled(if draft.field == index { "> " } else { "  " }, style),
+                Span::styled(format!("{:<9}", name), style),
+                Span::styled(value, style.fg(Color::Green)),
+                Span::styled(hint, Style::default().fg(Color::DarkGray)),
+            ])
+        };
+
+        let opening = if draft.opening.is_empty() && draft.field != 1 {
+            "any (ECO or name)".to_string()
+        } else {
+            draft.opening.clone()
+        };
+        let mut lines = vec![
+            field(0, "Name", draft.name.clone()),
+            field(1, "Opening", opening),
+            field(2, "Result", outcome_name(draft.outcome).to_string()),
+            field(3, "Played", side_name(draft.side).to_string()),
+            field(
+                4,
+                "Games",
+                if draft.blank {
+                    "none, chapters only"
+                } else {
+                    "matching"
+                }
+                .to_string(),
+            ),
+            Line::raw(""),
+        ];
+        lines.push(match &draft.error {
+            Some(error) => Line::from(Span::styled(error.clone(), Style::default().fg(Color::Red))),
+            None => Line::from(Span::styled(
+                "Enter: Build | Esc: Cancel",
+                Style::default().fg(Color::DarkGray),
+            )),
+        });
+
+        Paragraph::new(lines).block(block).render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(san: &str, children: Vec<StudyNodeProto>) -> StudyNodeProto {
+        StudyNodeProto {
+            san: san.to_string(),
+            children,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_path_and_line() {
+        let root = node(
+            "",
+            vec![
+                node(
+                    "e4",
+                    vec![node("e5", vec![]), node("c5", vec![node("Nf3", vec![])])],
+                ),
+                node("d4", vec![]),
+            ],
+        );
+        assert_eq!(node_at(&root, &[0, 1, 0]).unwrap().san, "Nf3");
+        assert!(node_at(&root, &[1, 0]).is_none());
+        assert_eq!(line_label(&root, &[0, 1, 0]), "1. e4 c5 2. Nf3");
+        assert_eq!(line_label(&root, &[]), "");
+    }
+
+    #[test]
+    fn test_labels() {
+        let filter = StudyFilterProto {
+            opening: Some("Sicilian".to_string()),
+            outcome: StudyOutcomeType::StudyOutcomeLoss as i32,
+            opponent: None,
+            human_side: Some(PlayerSideProto::White as i32),
+        };
+        assert_eq!(filter_label(&filter), "Sicilian, losses, as white");
+        assert_eq!(filter_label(&StudyFilterProto::default()), "all games");
+
+        let qh5 = StudyNodeProto {
+            average_cp_loss: Some(250.0),
+            mistakes: 1,
+            blunders: 2,
+            best_san: Some("Nf3".to_string()),
+            ..node("Qh5", vec![])
+        };
+        assert_eq!(
+            review_label(&qh5),
+            "avg loss 250cp, 1 mistake, 2 blunders, best Nf3"
+        );
+    }
+
+    #[test]
+    fn test_draft_editing() {
+        let mut draft = StudyDraft::default();
+        for c in "Sicilian losses".chars() {
+            draft.push_char(c);
+        }
+        draft.next_field();
+        for c in "B2x".chars() {
+            draft.push_char(c);
+        }
+        draft.pop_char();
+        draft.next_field();
+        draft.cycle(1);
+        assert_eq!(draft.outcome, StudyOutcomeType::StudyOutcomeAny);
+        draft.next_field();
+        draft.cycle(-1);
+        assert_eq!(draft.side, Some(PlayerSideProto::Black));
+        draft.previous_field();
+        draft.previous_field();
+        draft.previous_field();
+        draft.previous_field();
+        draft.cycle(1);
+        assert!(draft.blank, "field 4 wraps around from the name");
+
+        assert_eq!(draft.parse_name(), Ok("Sicilian losses".to_string()));
+        let filter = draft.filter();
+        assert_eq!(filter.opening.as_deref(), Some("B2"));
+        assert_eq!(filter.outcome, StudyOutcomeType::StudyOutcomeAny as i32);
+        assert_eq!(filter.human_side, Some(PlayerSideProto::Black as i32));
+
+        assert!(StudyDraft::default().parse_name().is_err());
+    }
+}
@@ -1,5 +1,6 @@
 use crate::state::GameSession;
 use crate::ui::fsm::UiStateMachine;
+use crate::ui::glyphs;
 use crate::ui::widgets::mini_board::piece_to_unicode;
 use chess::{format_square, parse_square};
 use ratatui::{
@@ -86,6 +87,12 @@ fn render_piece_content(state: &GameSession, fsm: &UiStateMachine, buf: &mut Buf
 
     let typeahead = &fsm.tab_input.typeahead_buffer;
     let selectable_squares = &state.selectable_squares;
+    // A wide piece glyph needs a space to spill into before the square name
+    let gap = if glyphs::current().pieces > 1 {
+        " "
+    } else {
+        ""
+    };
 
     let mut spans: Vec<Span> = vec![
         Span::styled("> ", Style::default().fg(Color::Yellow)),
@@ -104,7 +111,7 @@ fn render_piece_content(state: &GameSession, fsm: &UiStateMachine, buf: &mut Buf
             state.board().color_on(*square),
         ) {
             let symbol = piece_to_unicode(piece, color);
-            let label = format!("{}{}", symbol, square_str);
+            let label = format!("{}{}{}", symbol, gap, square_str);
             spans.push(Span::styled(label, Style::default().fg(Color::White)));
             spans.push(Span::raw("  "));
         }
@@ -0,0 +1,207 @@
+use crate::review_state::ReviewState;
+use chess_client::{review_score, MoveClassification, ReviewScore};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Widget},
+};
+use std::time::{Duration, Instant};
+
+/// Height of the scrubber including its border.
+pub const SCRUBBER_HEIGHT: u16 = 3;
+
+/// Presses closer together than this continue an acceleration streak.
+const REPEAT_WINDOW: Duration = Duration::from_millis(180);
+
+/// Ply of the timeline cell at `col`, for a track `width` cells wide showing
+/// plies `0..=total_plies`. The first and last cells are always the start and
+/// end of the game.
+pub fn ply_at_column(col: u16, width: u16, total_plies: u32) -> u32 {
+    if width <= 1 || total_plies == 0 {
+        return 0;
+    }
+    let last = u64::from(width - 1);
+    let col = u64::from(col).min(last);
+    ((col * u64::from(total_plies) + last / 2) / last) as u32
+}
+
+/// Cell showing `ply` on a track `width` cells wide. The inverse of
+/// [`ply_at_column`] whenever the track has a cell per ply.
+pub fn column_for_ply(ply: u32, width: u16, total_plies: u32) -> u16 {
+    if width <= 1 || total_plies == 0 {
+        return 0;
+    }
+    let last = u64::from(width - 1);
+    let ply = u64::from(ply.min(total_plies));
+    ((ply * last + u64::from(total_plies) / 2) / u64::from(total_plies)) as u16
+}
+
+/// Evaluation after `ply` in centipawns from White's side, clamped to ±500.
+/// Ply 0 uses the evaluation before the first move.
+pub fn eval_at_ply(review_state: &ReviewState, ply: u32) -> Option<i32> {
+    let positions = &review_state.review.positions;
+    let score = if ply == 0 {
+        positions.iter().find(|p| p.ply == 1)?.eval_before.as_ref()
+    } else {
+        positions.iter().find(|p| p.ply == ply)?.eval_after.as_ref()
+    };
+    score.and_then(score_to_cp).map(|cp| cp.clamp(-500, 500))
+}
+
+fn score_to_cp(score: &ReviewScore) -> Option<i32> {
+    match score.score.as_ref()? {
+        review_score::Score::Centipawns(cp) => Some(*cp),
+        review_score::Score::Mate(m) if *m > 0 => Some(500),
+        review_score::Score::Mate(_) => Some(-500),
+    }
+}
+
+/// Cell colour for an evaluation: light when White is better, dark when
+/// Black is, grey when level or not yet analysed.
+fn eval_color(cp: Option<i32>) -> Color {
+    match cp {
+        Some(cp) if cp >= 200 => Color::White,
+        Some(cp) if cp >= 50 => Color::Gray,
+        Some(cp) if cp <= -200 => Color::Blue,
+        Some(cp) if cp <= -50 => Color::LightBlue,
+        _ => Color::DarkGray,
+    }
+}
+
+/// Blunders, misses and mistakes stand out on the track like on the eval graph.
+pub fn critical_color(review_state: &ReviewState, ply: u32) -> Option<Color> {
+    let pos = review_state
+        .review
+        .positions
+        .iter()
+        .find(|p| p.ply == ply)?;
+    match MoveClassification::try_from(pos.classification) {
+        Ok(MoveClassification::ClassificationBlunder) => Some(Color::Red),
+        Ok(MoveClassification::ClassificationMiss) => Some(Color::LightRed),
+        Ok(MoveClassification::ClassificationMistake) => Some(Color::Yellow),
+        _ => None,
+    }
+}
+
+/// Step size for held or rapidly repeated arrow keys. Each press within
+/// [`REPEAT_WINDOW`] of the last one in the same direction extends the
+/// streak, and longer streaks move further per press.
+#[derive(Clone, Debug, Default)]
+pub struct KeyAcceleration {
+    last: Option<(Instant, bool)>,
+    streak: u32,
+}
+
+impl KeyAcceleration {
+    /// Plies to move for a press at `now`; `forward` is the direction.
+    pub fn step(&mut self, forward: bool, now: Instant) -> u32 {
+        let continues = matches!(
+            self.last,
+            Some((at, dir)) if dir == forward && now.saturating_duration_since(at) <= REPEAT_WINDOW
+        );
+        self.streak = if continues { self.streak + 1 } else { 0 };
+        self.last = Some((now, forward));
+        match self.streak {
+            0..=3 => 1,
+            4..=9 => 2,
+            10..=19 => 4,
+            _ => 8,
+        }
+    }
+}
+
+/// Horizontal timeline of the reviewed game under the board. Each cell is
+/// coloured by the evaluation at that point and the current ply is marked.
+pub struct TimelineScrubber<'a> {
+    pub review_state: &'a ReviewState,
+}
+
+impl TimelineScrubber<'_> {
+    /// Area of the track inside the border, for mapping mouse positions.
+    pub fn track_area(area: Rect) -> Rect {
+        Block::default().borders(Borders::ALL).inner(area)
+    }
+}
+
+impl Widget for TimelineScrubber<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let total = self.review_state.review.total_plies;
+        let current = self.review_state.current_ply;
+
+        let block = Block::default()
+            .title(format!(" Timeline {}/{} ", current, total))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::DarkGray));
+        let track = block.inner(area);
+        block.render(area, buf);
+        if track.width == 0 || track.height == 0 {
+            return;
+        }
+
+        let cursor = column_for_ply(current, track.width, total);
+        for col in 0..track.width {
+            let ply = ply_at_column(col, track.width, total);
+            let (symbol, style) = if col == cursor {
+                (
+                    "\u{2503}",
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                )
+            } else {
+                let fg = critical_color(self.review_state, ply)
+                    .unwrap_or_else(|| eval_color(eval_at_ply(self.review_state, ply)));
+                ("\u{2586}", Style::default().fg(fg))
+            };
+            for row in track.y..track.y + track.height {
+                buf[(track.x + col, row)]
+                    .set_symbol(symbol)
+                    .set_style(style);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_track_ends_map_to_game_ends() {
+        assert_eq!(ply_at_column(0, 40, 80), 0);
+        assert_eq!(ply_at_column(39, 40, 80), 80);
+        assert_eq!(ply_at_column(100, 40, 80), 80);
+        assert_eq!(column_for_ply(0, 40, 80), 0);
+        assert_eq!(column_for_ply(80, 40, 80), 39);
+        assert_eq!(ply_at_column(5, 1, 80), 0);
+        assert_eq!(column_for_ply(3, 40, 0), 0);
+    }
+
+    #[test]
+    fn test_column_round_trip_on_wide_track() {
+        let (width, total) = (60, 20);
+        for ply in 0..=total {
+            assert_eq!(
+                ply_at_column(column_for_ply(ply, width, total), width, total),
+                ply
+            );
+        }
+    }
+
+    #[test]
+    fn test_acceleration_grows_with_streak() {
+        let mut accel = KeyAcceleration::default();
+        let start = Instant::now();
+        let steps: Vec<u32> = (0..12)
+            .map(|i| accel.step(true, start + Duration::from_millis(50 * i)))
+            .collect();
+        assert_eq!(steps[0], 1);
+        assert_eq!(steps[4], 2);
+        assert_eq!(steps[11], 4);
+
+        // A pause or a change of direction starts over
+        assert_eq!(accel.step(true, start + Duration::from_secs(5)), 1);
+        assert_eq!(accel.step(false, start + Duration::from_millis(5050)), 1);
+    }
+}
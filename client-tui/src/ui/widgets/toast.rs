@@ -0,0 +1,182 @@
+use crate::notifications::{Notification, Notifications, Severity};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{
+        Block, Borders, Clear, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState,
+        StatefulWidget, Widget,
+    },
+};
+use std::time::{Duration, Instant};
+
+/// Widest a toast gets; longer messages are cut off.
+const MAX_TOAST_WIDTH: u16 = 60;
+
+fn severity_color(severity: Severity) -> Color {
+    match severity {
+        Severity::Info => Color::Cyan,
+        Severity::Success => Color::Green,
+        Severity::Warning => Color::Yellow,
+        Severity::Error => Color::Red,
+    }
+}
+
+/// Toasts stacked in the top-right corner, oldest at the top.
+pub struct ToastStack<'a> {
+    pub notifications: &'a Notifications,
+    /// Spell out the severity before each message instead of relying on
+    /// the colour of the marker alone.
+    pub labelled: bool,
+}
+
+impl Widget for ToastStack<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        for (row, toast) in self.notifications.toasts().enumerate() {
+            let y = area.y + 1 + row as u16;
+            if y >= area.bottom() {
+                break;
+            }
+            let text = if self.labelled {
+                format!(" {}: {} ", toast.severity.label(), toast.message)
+            } else {
+                format!(" {} ", toast.message)
+            };
+            let width = (text.chars().count() as u16 + 1)
+                .min(MAX_TOAST_WIDTH)
+                .min(area.width);
+            let toast_area = Rect::new(area.right().saturating_sub(width + 1), y, width, 1);
+
+            Clear.render(toast_area, buf);
+            let color = severity_color(toast.severity);
+            let line = Line::from(vec![
+                Span::styled("\u{258c}", Style::default().fg(color).bg(Color::Black)),
+                Span::styled(
+                    text,
+                    Style::default()
+                        .fg(Color::White)
+                        .bg(Color::Black)
+                        .add_modifier(if toast.severity == Severity::Error {
+                            Modifier::BOLD
+                        } else {
+                            Modifier::empty()
+                        }),
+                ),
+            ]);
+            Paragraph::new(line).render(toast_area, buf);
+        }
+    }
+}
+
+/// Scroll position of the notification history overlay.
+#[derive(Debug, Clone, Default)]
+pub struct NotificationHistoryState {
+    pub scroll: u16,
+}
+
+/// `5s`, `3m`, `2h` since a notification was raised.
+pub fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    if secs < 60 {
+        format!("{}s", secs)
+    } else if secs < 3600 {
+        format!("{}m", secs / 60)
+    } else {
+        format!("{}h", secs / 3600)
+    }
+}
+
+/// Centered overlay listing past notifications, newest first.
+pub struct NotificationHistoryWidget<'a> {
+    pub notifications: &'a Notifications,
+    pub state: &'a NotificationHistoryState,
+}
+
+impl NotificationHistoryWidget<'_> {
+    fn line(notification: &Notification, now: Instant) -> Line<'static> {
+        let color = severity_color(notification.severity);
+        Line::from(vec![
+            Span::styled(
+                format!("{:>4} ", format_age(now - notification.created)),
+                Style::default().fg(Color::DarkGray),
+            ),
+            Span::styled(
+                format!("{:<5} ", notification.severity.label()),
+                Style::default().fg(color).add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(notification.message.clone()),
+        ])
+    }
+}
+
+impl Widget for NotificationHistoryWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let width = (area.width * 7 / 10).max(40).min(area.width);
+        let height = (area.height * 6 / 10).max(8).min(area.height);
+        let popup_area = Rect::new(
+            area.x + (area.width - width) / 2,
+            area.y + (area.height - height) / 2,
+            width,
+            height,
+        );
+
+        Clear.render(popup_area, buf);
+
+        let block = Block::default()
+            .title(" Notifications ")
+            .title_bottom(" j/k: Scroll  Esc: Close ")
+            .borders(Borders::ALL)
+            .border_style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .style(Style::default().bg(Color::Black));
+
+        let inner = block.inner(popup_area);
+        block.render(popup_area, buf);
+
+        let now = Instant::now();
+        let lines: Vec<Line<'static>> = if self.notifications.latest().is_none() {
+            vec![Line::from(Span::styled(
+                "No notifications yet",
+                Style::default().fg(Color::DarkGray),
+            ))]
+        } else {
+            self.notifications
+                .history()
+                .rev()
+                .map(|n| Self::line(n, now))
+                .collect()
+        };
+
+        let content_height = lines.len() as u16;
+        Paragraph::new(lines)
+            .scroll((self.state.scroll, 0))
+            .render(inner, buf);
+
+        if content_height > inner.height {
+            let mut scrollbar_state =
+                ScrollbarState::new(content_height as usize).position(self.state.scroll as usize);
+            Scrollbar::new(ScrollbarOrientation::VerticalRight).render(
+                inner,
+                buf,
+                &mut scrollbar_state,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_age() {
+        assert_eq!(format_age(Duration::from_secs(0)), "0s");
+        assert_eq!(format_age(Duration::from_secs(59)), "59s");
+        assert_eq!(format_age(Duration::from_secs(150)), "2m");
+        assert_eq!(format_age(Duration::from_secs(7300)), "2h");
+    }
+}
@@ -0,0 +1,207 @@
+use chess_client::GetTrainingDigestResponse;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+
+/// Width of the accuracy bars in the trend section.
+const BAR_WIDTH: usize = 20;
+
+/// Bar of `width` cells, filled in proportion to an accuracy percentage.
+pub fn accuracy_bar(accuracy: f64, width: usize) -> String {
+    let filled = ((accuracy.clamp(0.0, 100.0) / 100.0) * width as f64).round() as usize;
+    format!(
+        "{}{}",
+        "\u{2588}".repeat(filled),
+        "\u{2591}".repeat(width - filled)
+    )
+}
+
+/// Label for a day relative to the end of the digest period.
+pub fn days_ago(day: u32, last_day: u32) -> String {
+    match last_day.saturating_sub(day) {
+        0 => "today".to_string(),
+        n => format!("{}d ago", n),
+    }
+}
+
+fn heading(text: &'static str) -> Line<'static> {
+    Line::from(Span::styled(
+        text,
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD),
+    ))
+}
+
+/// The digest's sections as styled lines.
+pub fn digest_lines(digest: &GetTrainingDigestResponse) -> Vec<Line<'static>> {
+    let dim = Style::default().fg(Color::DarkGray);
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled(
+                format!("{} games", digest.games_played),
+                Style::default().fg(Color::White),
+            ),
+            Span::styled(
+                format!(
+                    "  {}W / {}D / {}L  \u{00b7}  {} reviewed",
+                    digest.wins, digest.draws, digest.losses, digest.reviewed_games
+                ),
+                dim,
+            ),
+        ]),
+        match (digest.average_accuracy, digest.previous_average_accuracy) {
+            (Some(now), previous) => {
+                let mut spans = vec![Span::styled(
+                    format!("Accuracy {:.1}%", now),
+                    Style::default().fg(Color::White),
+                )];
+                if let Some(before) = previous {
+                    let delta = now - before;
+                    let color = if delta >= 0.0 {
+                        Color::Green
+                    } else {
+                        Color::Red
+                    };
+                    spans.push(Span::styled(
+                        format!("  {:+.1} vs previous period", delta),
+                        Style::default().fg(color),
+                    ));
+                }
+                Line::from(spans)
+            }
+            (None, _) => Line::from(Span::styled("No reviewed games", dim)),
+        },
+        Line::raw(""),
+    ];
+
+    if !digest.accuracy_trend.is_empty() {
+        lines.push(heading("Accuracy Trend"));
+        for point in &digest.accuracy_trend {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("{:>8}  ", days_ago(point.day, digest.last_day)),
+                    dim,
+                ),
+                Span::styled(
+                    accuracy_bar(point.accuracy, BAR_WIDTH),
+                    Style::default().fg(Color::Cyan),
+                ),
+                Span::raw(format!(" {:5.1}%", point.accuracy)),
+                Span::styled(format!("  ({} games)", point.games), dim),
+            ]));
+        }
+        lines.push(Line::raw(""));
+    }
+
+    lines.push(heading("Top Recurring Mistakes"));
+    if digest.top_mistakes.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "No mistakes or blunders in reviewed games",
+            dim,
+        )));
+    }
+    for (i, mistake) in digest.top_mistakes.iter().enumerate() {
+        lines.push(Line::from(vec![
+            Span::raw(format!("{}. {}", i + 1, mistake.theme)),
+            Span::styled(format!("  \u{00d7}{}", mistake.count), dim),
+        ]));
+    }
+    lines.push(Line::raw(""));
+
+    lines.push(heading("Recommended Focus"));
+    for area in &digest.focus_areas {
+        lines.push(Line::from(vec![
+            Span::styled("\u{2022} ", Style::default().fg(Color::Green)),
+            Span::raw(area.clone()),
+        ]));
+    }
+    lines
+}
+
+/// Report screen for a training digest.
+pub struct TrainingDigestView<'a> {
+    pub digest: &'a GetTrainingDigestResponse,
+    pub scroll: u16,
+    /// Result of the last export, shown in place of the key hints.
+    pub status: Option<&'a str>,
+}
+
+impl Widget for TrainingDigestView<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let width = area.width.min(80);
+        let height = area.height.min(30);
+        let area = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        };
+        Clear.render(area, buf);
+
+        let footer = self
+            .status
+            .unwrap_or("\u{2191}/\u{2193}: Scroll | e: Export Markdown | Esc: Back");
+        let block = Block::default()
+            .title(format!(
+                " Training Digest: {} to {} ",
+                self.digest.first_date, self.digest.last_date
+            ))
+            .title_bottom(Line::from(format!(" {} ", footer)).centered())
+            .borders(Borders::ALL)
+            .border_style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .style(Style::default().bg(Color::Black));
+
+        Paragraph::new(digest_lines(self.digest))
+            .block(block)
+            .scroll((self.scroll, 0))
+            .render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chess_client::RecurringMistakeProto;
+
+    #[test]
+    fn test_accuracy_bar_fills_proportionally() {
+        assert_eq!(accuracy_bar(50.0, 4), "\u{2588}\u{2588}\u{2591}\u{2591}");
+        assert_eq!(accuracy_bar(0.0, 2), "\u{2591}\u{2591}");
+        assert_eq!(accuracy_bar(120.0, 2), "\u{2588}\u{2588}");
+    }
+
+    #[test]
+    fn test_days_ago() {
+        assert_eq!(days_ago(100, 100), "today");
+        assert_eq!(days_ago(97, 100), "3d ago");
+    }
+
+    #[test]
+    fn test_digest_lines_list_mistakes_and_focus() {
+        let digest = GetTrainingDigestResponse {
+            games_played: 3,
+            top_mistakes: vec![RecurringMistakeProto {
+                theme: "Endgame errors".to_string(),
+                count: 4,
+            }],
+            focus_areas: vec!["Endgame play: 45 cp lost per move on average".to_string()],
+            ..Default::default()
+        };
+        let text: Vec<String> = digest_lines(&digest)
+            .iter()
+            .map(|l| l.spans.iter().map(|s| s.content.as_ref()).collect())
+            .collect();
+        assert!(text.contains(&"1. Endgame errors  \u{00d7}4".to_string()));
+        assert!(text.contains(&"\u{2022} Endgame play: 45 cp lost per move on average".to_string()));
+        assert!(!text.contains(&"Accuracy Trend".to_string()));
+    }
+}
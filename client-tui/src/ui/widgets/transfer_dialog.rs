@@ -0,0 +1,96 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+
+/// Characters in a transfer code, as the server issues them.
+pub const TRANSFER_CODE_LEN: usize = 6;
+
+/// Add a typed character to a transfer code. Codes are letters and digits,
+/// shown in upper case; anything else, or a character past the code's
+/// length, is ignored.
+pub fn push_code_char(code: &mut String, c: char) {
+    if c.is_ascii_alphanumeric() && code.len() < TRANSFER_CODE_LEN {
+        code.push(c.to_ascii_uppercase());
+    }
+}
+
+/// Dialog for typing the transfer code another client showed, centred over
+/// the menu.
+pub struct TransferDialogWidget<'a> {
+    pub code: &'a str,
+}
+
+impl Widget for TransferDialogWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let dialog_width = 50;
+        let dialog_height = 8;
+        let x = (area.width.saturating_sub(dialog_width)) / 2;
+        let y = (area.height.saturating_sub(dialog_height)) / 2;
+
+        let dialog_area = Rect {
+            x: area.x + x,
+            y: area.y + y,
+            width: dialog_width.min(area.width),
+            height: dialog_height.min(area.height),
+        };
+        Clear.render(dialog_area, buf);
+
+        let block = Block::default()
+            .title(" Join From Another Client ")
+            .borders(Borders::ALL)
+            .border_style(
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .style(Style::default().bg(Color::Black));
+        let inner = block.inner(dialog_area);
+        block.render(dialog_area, buf);
+
+        let label = Style::default().fg(Color::DarkGray);
+        let lines = vec![
+            Line::from(Span::styled(
+                "Type 'transfer' in the other client's game",
+                label,
+            )),
+            Line::raw(""),
+            Line::from(vec![
+                Span::styled("Code   ", label),
+                Span::styled(
+                    format!("{}_", self.code),
+                    Style::default()
+                        .fg(Color::White)
+                        .add_modifier(Modifier::BOLD),
+                ),
+            ]),
+            Line::raw(""),
+            Line::from(Span::styled("Enter join · Esc cancel", label)),
+        ];
+
+        Paragraph::new(lines)
+            .alignment(Alignment::Left)
+            .render(inner, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_code_char_keeps_letters_and_digits() {
+        let mut code = String::new();
+        for c in "k7-q x".chars() {
+            push_code_char(&mut code, c);
+        }
+        assert_eq!(code, "K7QX");
+        for c in "mdz".chars() {
+            push_code_char(&mut code, c);
+        }
+        assert_eq!(code, "K7QXMD");
+    }
+}
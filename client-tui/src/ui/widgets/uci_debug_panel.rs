@@ -70,6 +70,7 @@ impl Widget for UciDebugPanel<'_> {
             let (prefix, color) = match entry.direction {
                 UciDirection::ToEngine => ("→ OUT: ", Color::Cyan),
                 UciDirection::FromEngine => ("← IN:  ", Color::Green),
+                UciDirection::EngineStderr => ("! ERR: ", Color::Red),
             };
 
             // Parse message for syntax highlighting
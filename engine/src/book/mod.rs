@@ -0,0 +1,304 @@
+//! Polyglot opening books.
+//!
+//! A polyglot `.bin` book is a flat array of 16-byte big-endian entries
+//! (position key, move, weight, learn), sorted by key. The key is a Zobrist
+//! hash of the position over the fixed table in [`random64`]. Moves encode
+//! castling as the king capturing its rook, the same convention cozy-chess
+//! uses, so decoded moves compare directly with generated ones.
+
+mod random64;
+
+use cozy_chess::{Board, Color, File, Move, Piece, Rank, Square};
+use std::path::Path;
+
+use random64::RANDOM64;
+
+const ENTRY_BYTES: usize = 16;
+const CASTLE_OFFSET: usize = 768;
+const EN_PASSANT_OFFSET: usize = 772;
+const TURN_OFFSET: usize = 780;
+
+#[derive(Debug, thiserror::Error)]
+pub enum BookError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Book size {0} is not a whole number of 16-byte entries")]
+    Truncated(usize),
+}
+
+/// One book entry: a move for the position with hash `key`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BookEntry {
+    pub key: u64,
+    /// Polyglot move encoding; see [`decode_move`].
+    pub raw_move: u16,
+    /// Relative frequency the move should be played with.
+    pub weight: u16,
+    pub learn: u32,
+}
+
+/// An opening book loaded into memory.
+#[derive(Debug, Clone, Default)]
+pub struct PolyglotBook {
+    entries: Vec<BookEntry>,
+}
+
+impl PolyglotBook {
+    /// Load a `.bin` book from disk.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, BookError> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BookError> {
+        if bytes.len() % ENTRY_BYTES != 0 {
+            return Err(BookError::Truncated(bytes.len()));
+        }
+        let mut entries: Vec<BookEntry> = bytes
+            .chunks_exact(ENTRY_BYTES)
+            .map(|chunk| BookEntry {
+                key: u64::from_be_bytes(chunk[0..8].try_into().unwrap()),
+                raw_move: u16::from_be_bytes([chunk[8], chunk[9]]),
+                weight: u16::from_be_bytes([chunk[10], chunk[11]]),
+                learn: u32::from_be_bytes(chunk[12..16].try_into().unwrap()),
+            })
+            .collect();
+        // Books are written sorted, but lookups depend on it
+        entries.sort_by_key(|entry| entry.key);
+        Ok(Self { entries })
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The legal book moves for `board` with their weights, in book order.
+    /// Entries whose move is not legal here (a hash collision) are skipped.
+    pub fn moves(&self, board: &Board) -> Vec<(Move, u16)> {
+        let key = polyglot_key(board);
+        let start = self.entries.partition_point(|entry| entry.key < key);
+        let mut legal = Vec::new();
+        board.generate_moves(|moves| {
+            legal.extend(moves);
+            false
+        });
+
+        self.entries[start..]
+            .iter()
+            .take_while(|entry| entry.key == key)
+            .filter_map(|entry| {
+                let mv = decode_move(entry.raw_move);
+                legal.contains(&mv).then_some((mv, entry.weight))
+            })
+            .collect()
+    }
+
+    /// Pick a book move for `board`, each with a chance proportional to its
+    /// weight. `seed` selects among them; moves of weight 0 are never chosen.
+    pub fn pick(&self, board: &Board, seed: u64) -> Option<Move> {
+        let moves = self.moves(board);
+        let total: u64 = moves.iter().map(|&(_, weight)| u64::from(weight)).sum();
+        if total == 0 {
+            return None;
+        }
+        let mut target = seed % total;
+        for (mv, weight) in moves {
+            let weight = u64::from(weight);
+            if target < weight {
+                return Some(mv);
+            }
+            target -= weight;
+        }
+        None
+    }
+}
+
+/// Decode a polyglot move: to-file in bits 0-2, to-rank 3-5, from-file
+/// 6-8, from-rank 9-11 and promotion piece 12-14.
+pub fn decode_move(raw: u16) -> Move {
+    let square = |bits: u16| {
+        Square::new(
+            File::index(usize::from(bits & 7)),
+            Rank::index(usize::from((bits >> 3) & 7)),
+        )
+    };
+    let promotion = match (raw >> 12) & 7 {
+        1 => Some(Piece::Knight),
+        2 => Some(Piece::Bishop),
+        3 => Some(Piece::Rook),
+        4 => Some(Piece::Queen),
+        _ => None,
+    };
+    Move {
+        from: square(raw >> 6),
+        to: square(raw),
+        promotion,
+    }
+}
+
+/// The polyglot hash of `board`.
+pub fn polyglot_key(board: &Board) -> u64 {
+    let mut key = 0;
+    for color in Color::ALL {
+        for piece in Piece::ALL {
+            // Black pawn, white pawn, black knight, ... white king
+            let kind = 2 * piece as usize + usize::from(color == Color::White);
+            for square in board.colored_pieces(color, piece) {
+                key ^= RANDOM64[64 * kind + 8 * square.rank() as usize + square.file() as usize];
+            }
+        }
+    }
+
+    let castle_keys = [(Color::White, 0), (Color::Black, 2)];
+    for (color, offset) in castle_keys {
+        let rights = board.castle_rights(color);
+        if rights.short.is_some() {
+            key ^= RANDOM64[CASTLE_OFFSET + offset];
+        }
+        if rights.long.is_some() {
+            key ^= RANDOM64[CASTLE_OFFSET + offset + 1];
+        }
+    }
+
+    // The en passant file only counts when a pawn could actually take there
+    let side = board.side_to_move();
+    if let Some(file) = board.en_passant() {
+        let rank = match side {
+            Color::White => Rank::Fifth,
+            Color::Black => Rank::Fourth,
+        };
+        let pawns = board.colored_pieces(side, Piece::Pawn);
+        let neighbours = [(file as usize).checked_sub(1), Some(file as usize + 1)];
+        let can_capture = neighbours
+            .into_iter()
+            .filter_map(|f| File::try_index(f?))
+            .any(|f| pawns.has(Square::new(f, rank)));
+        if can_capture {
+            key ^= RANDOM64[EN_PASSANT_OFFSET + file as usize];
+        }
+    }
+
+    if side == Color::White {
+        key ^= RANDOM64[TURN_OFFSET];
+    }
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board(fen: &str) -> Board {
+        Board::from_fen(fen, false).unwrap()
+    }
+
+    fn entry(key: u64, raw_move: u16, weight: u16) -> [u8; ENTRY_BYTES] {
+        let mut bytes = [0u8; ENTRY_BYTES];
+        bytes[0..8].copy_from_slice(&key.to_be_bytes());
+        bytes[8..10].copy_from_slice(&raw_move.to_be_bytes());
+        bytes[10..12].copy_from_slice(&weight.to_be_bytes());
+        bytes
+    }
+
+    /// The positions and keys published with the polyglot format.
+    #[test]
+    fn test_polyglot_key_matches_reference() {
+        let cases = [
+            (
+                "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+                0x463b96181691fc9c,
+            ),
+            (
+                "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+                0x823c9b50fd114196,
+            ),
+            (
+                "rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 2",
+                0x0756b94461c50fb0,
+            ),
+            (
+                "rnbqkbnr/ppp1pppp/8/3pP3/8/8/PPPP1PPP/RNBQKBNR b KQkq - 0 2",
+                0x662fafb965db29d4,
+            ),
+            (
+                "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+                0x22a48b5a8e47ff78,
+            ),
+            (
+                "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPPKPPP/RNBQ1BNR b kq - 0 3",
+                0x652a607ca3f242c1,
+            ),
+            (
+                "rnbq1bnr/ppp1pkpp/8/3pPp2/8/8/PPPPKPPP/RNBQ1BNR w - - 0 4",
+                0x00fdd303c946bdd9,
+            ),
+            (
+                "rnbqkbnr/p1pppppp/8/8/PpP4P/8/1P1PPPP1/RNBQKBNR b KQkq c3 0 3",
+                0x3c8123ea7b067637,
+            ),
+            (
+                "rnbqkbnr/p1pppppp/8/8/P6P/R1p5/1P1PPPP1/1NBQKBNR b Kkq - 0 4",
+                0x5c3f9b829b279560,
+            ),
+        ];
+        for (fen, key) in cases {
+            assert_eq!(polyglot_key(&board(fen)), key, "{}", fen);
+        }
+    }
+
+    #[test]
+    fn test_decode_move() {
+        // e2e4: from e2 (file 4, rank 1), to e4 (file 4, rank 3)
+        let mv = decode_move((1 << 9) | (4 << 6) | (3 << 3) | 4);
+        assert_eq!(mv.to_string(), "e2e4");
+        // a7a8=Q
+        let mv = decode_move((4 << 12) | (6 << 9) | (7 << 3));
+        assert_eq!(mv.to_string(), "a7a8q");
+        // White short castling is stored as e1h1, the king taking its rook
+        let mv = decode_move((4 << 6) | 7);
+        assert_eq!(mv.to_string(), "e1h1");
+    }
+
+    #[test]
+    fn test_book_lookup_and_pick() {
+        let start = Board::default();
+        let key = polyglot_key(&start);
+        let e4 = (1 << 9) | (4 << 6) | (3 << 3) | 4;
+        let d4 = (1 << 9) | (3 << 6) | (3 << 3) | 3;
+        // A colliding entry whose move is not legal in the start position
+        let illegal = (4 << 6) | 7;
+        let mut bytes = Vec::new();
+        bytes.extend(entry(key, d4, 1));
+        bytes.extend(entry(key + 1, e4, 50));
+        bytes.extend(entry(key, e4, 3));
+        bytes.extend(entry(key, illegal, 100));
+        let book = PolyglotBook::from_bytes(&bytes).unwrap();
+        assert_eq!(book.len(), 4);
+
+        let moves: Vec<(String, u16)> = book
+            .moves(&start)
+            .into_iter()
+            .map(|(mv, weight)| (mv.to_string(), weight))
+            .collect();
+        assert_eq!(moves, vec![("d2d4".into(), 1), ("e2e4".into(), 3)]);
+
+        assert_eq!(book.pick(&start, 0).unwrap().to_string(), "d2d4");
+        assert_eq!(book.pick(&start, 1).unwrap().to_string(), "e2e4");
+        assert_eq!(book.pick(&start, 7).unwrap().to_string(), "e2e4");
+        assert!(book
+            .pick(&board("8/8/8/8/8/8/8/K6k w - - 0 1"), 0)
+            .is_none());
+    }
+
+    #[test]
+    fn test_truncated_book_is_rejected() {
+        assert!(matches!(
+            PolyglotBook::from_bytes(&[0; 20]),
+            Err(BookError::Truncated(20))
+        ));
+        assert!(PolyglotBook::from_bytes(&[]).unwrap().is_empty());
+    }
+}
@@ -1,10 +1,18 @@
+pub mod book;
 pub mod stockfish;
 pub mod uci;
 
-pub use stockfish::{EngineConfig as StockfishConfig, StockfishEngine};
+#[cfg(all(test, feature = "property-tests"))]
+mod proptests;
+
+pub use book::{BookError, PolyglotBook};
+pub use stockfish::{
+    find_stockfish_path, EngineConfig as StockfishConfig, StockfishEngine, STOCKFISH_NOT_FOUND,
+};
 pub use uci::{UciError, UciMessage};
 
 use cozy_chess::Move;
+use std::collections::BTreeMap;
 use tokio::sync::mpsc;
 
 /// Handle for communicating with a chess engine
@@ -37,6 +45,8 @@ pub enum EngineEvent {
     Ready,
     BestMove(Move),
     Info(EngineInfo),
+    /// Free-form `info string` text, surfaced as an engine log line.
+    InfoString(String),
     Error(String),
     RawUciMessage {
         direction: UciMessageDirection,
@@ -48,6 +58,8 @@ pub enum EngineEvent {
 pub enum UciMessageDirection {
     ToEngine,
     FromEngine,
+    /// A line the engine wrote to stderr (diagnostics, NNUE load errors).
+    EngineStderr,
 }
 
 /// Engine analysis information
@@ -63,6 +75,14 @@ pub struct EngineInfo {
     pub currmove: Option<Move>,
     pub hashfull: Option<u16>,
     pub nps: Option<u64>,
+    /// Set when `score` is only a bound from an aspiration window fail.
+    pub score_bound: Option<ScoreBound>,
+    /// Win/draw/loss expectation in permille (lc0, Stockfish with `UCI_ShowWDL`).
+    pub wdl: Option<Wdl>,
+    pub tbhits: Option<u64>,
+    pub cpuload: Option<u16>,
+    /// Unrecognised key/value pairs, kept verbatim instead of being dropped.
+    pub extras: BTreeMap<String, String>,
 }
 
 #[derive(Debug, Clone)]
@@ -70,3 +90,18 @@ pub enum Score {
     Centipawns(i32),
     Mate(i8), // Negative for being mated
 }
+
+/// Marks a reported score as a lower or upper bound rather than exact.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScoreBound {
+    Lower,
+    Upper,
+}
+
+/// Win/draw/loss probabilities in permille, from the side to move's view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Wdl {
+    pub win: u16,
+    pub draw: u16,
+    pub loss: u16,
+}
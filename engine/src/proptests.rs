@@ -0,0 +1,88 @@
+//! Property tests for the UCI parser, which reads whatever the engine
+//! binary prints. Opt in with `--features property-tests`.
+
+use cozy_chess::{Move, Piece, Square};
+use proptest::prelude::*;
+
+use crate::uci::{format_uci_move, parse_uci_message, parse_uci_move, UciMessage};
+
+fn any_move() -> impl Strategy<Value = Move> {
+    let promotion = prop::option::of(prop::sample::select(vec![
+        Piece::Knight,
+        Piece::Bishop,
+        Piece::Rook,
+        Piece::Queen,
+    ]));
+    (0..64usize, 0..64usize, promotion).prop_map(|(from, to, promotion)| Move {
+        from: Square::index(from),
+        to: Square::index(to),
+        promotion,
+    })
+}
+
+/// Tokens an `info` line is made of, valid or not.
+fn info_token() -> impl Strategy<Value = String> {
+    prop_oneof![
+        prop::sample::select(vec![
+            "depth",
+            "seldepth",
+            "time",
+            "nodes",
+            "score",
+            "cp",
+            "mate",
+            "pv",
+            "multipv",
+            "currmove",
+            "hashfull",
+            "nps",
+            "tbhits",
+            "cpuload",
+            "string",
+            "lowerbound",
+            "upperbound",
+            "wdl",
+            "refutation",
+        ])
+        .prop_map(str::to_string),
+        "[a-h][1-8][a-h][1-8][qrbn]?",
+        "-?[0-9]{1,12}",
+        "\\PC{1,4}",
+    ]
+}
+
+proptest! {
+    #[test]
+    fn uci_move_round_trips(mv in any_move()) {
+        prop_assert_eq!(parse_uci_move(&format_uci_move(&mv)).unwrap(), mv);
+    }
+
+    #[test]
+    fn bestmove_round_trips(mv in any_move(), ponder in any_move()) {
+        let line = format!("bestmove {} ponder {}", format_uci_move(&mv), format_uci_move(&ponder));
+        match parse_uci_message(&line) {
+            Ok(UciMessage::BestMove { mv: parsed, ponder: parsed_ponder }) => {
+                prop_assert_eq!(parsed, mv);
+                prop_assert_eq!(parsed_ponder, Some(ponder));
+            }
+            other => prop_assert!(false, "{} parsed as {:?}", line, other),
+        }
+    }
+
+    #[test]
+    fn info_lines_always_parse(tokens in prop::collection::vec(info_token(), 0..24)) {
+        let line = format!("info {}", tokens.join(" "));
+        prop_assert!(parse_uci_message(&line).is_ok(), "{}", line);
+    }
+
+    #[test]
+    fn parser_does_not_panic(line in "\\PC{0,80}") {
+        let _ = parse_uci_message(&line);
+        let _ = parse_uci_move(&line);
+    }
+
+    #[test]
+    fn bestmove_does_not_panic(mv in "\\PC{0,8}", ponder in "\\PC{0,8}") {
+        let _ = parse_uci_message(&format!("bestmove {} ponder {}", mv, ponder));
+    }
+}
@@ -1,15 +1,73 @@
 use crate::uci::{format_uci_move, parse_uci_message, UciMessage};
 use crate::{EngineCommand, EngineEvent, UciMessageDirection};
+use std::collections::VecDeque;
 use std::path::{Path, PathBuf};
 use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
-use tokio::process::Child;
+use tokio::process::{Child, ChildStdin};
 use tokio::sync::mpsc;
 
+/// Error from [`StockfishEngine::spawn_with_config`] when no usable
+/// Stockfish binary is installed.
+pub const STOCKFISH_NOT_FOUND: &str =
+    "Stockfish not found; install it (`brew install stockfish` or `apt install stockfish`) so `stockfish` is on PATH";
+
+/// Number of stderr lines retained for crash reports.
+const STDERR_TAIL_LINES: usize = 20;
+
+/// How long to wait for `uciok`/`readyok` before retrying.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How many times each handshake command is sent before giving up.
+const HANDSHAKE_ATTEMPTS: u32 = 3;
+
+/// Phases of the UCI initialisation handshake, in order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum InitPhase {
+    Uci,
+    Options,
+    IsReady,
+    Ready,
+}
+
+impl InitPhase {
+    fn next(self) -> Self {
+        match self {
+            InitPhase::Uci => InitPhase::Options,
+            InitPhase::Options => InitPhase::IsReady,
+            InitPhase::IsReady | InitPhase::Ready => InitPhase::Ready,
+        }
+    }
+}
+
+/// Handshake replies routed from the stdout reader to the init state machine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HandshakeSignal {
+    UciOk,
+    ReadyOk,
+}
+
+#[derive(Debug, thiserror::Error)]
+enum HandshakeFailure {
+    #[error("timed out waiting for engine")]
+    Timeout,
+    #[error("engine closed before completing the handshake")]
+    Closed,
+}
+
 pub struct StockfishEngine {
     process: Child,
     command_tx: mpsc::Sender<EngineCommand>,
     event_rx: mpsc::Receiver<EngineEvent>,
+    /// Engine name from `id name`, falling back to the binary name.
+    name: Arc<Mutex<String>>,
+    /// Most recent stderr lines, oldest first.
+    stderr_tail: Arc<Mutex<VecDeque<String>>>,
+    /// Set once `quit` has been sent, so EOF is not reported as a crash.
+    quitting: Arc<AtomicBool>,
 }
 
 /// Configuration for engine performance tuning.
@@ -26,45 +84,46 @@ impl StockfishEngine {
     /// Spawn a new Stockfish instance with full configuration.
     #[tracing::instrument(level = "info", skip(config))]
     pub async fn spawn_with_config(config: EngineConfig) -> Result<Self, String> {
-        let skill_level = config.skill_level;
         tracing::info!("Starting Stockfish engine spawn (config: {:?})", config);
-        let path = find_stockfish_path().ok_or("Stockfish not found")?;
+        let path = find_stockfish_path().ok_or(STOCKFISH_NOT_FOUND)?;
         tracing::info!("Found Stockfish at: {:?}", path);
 
         tracing::debug!("Spawning Stockfish process");
         let mut process = tokio::process::Command::new(&path)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
-            .stderr(Stdio::null())
+            .stderr(Stdio::piped())
             .spawn()
             .map_err(|e| {
                 tracing::error!("Failed to spawn Stockfish: {}", e);
                 format!("Failed to spawn Stockfish: {}", e)
             })?;
 
-        tracing::debug!("Stockfish process spawned, getting stdin/stdout");
+        tracing::debug!("Stockfish process spawned, getting stdin/stdout/stderr");
         let mut stdin = process.stdin.take().ok_or("Failed to get stdin")?;
         let stdout = process.stdout.take().ok_or("Failed to get stdout")?;
+        let stderr = process.stderr.take().ok_or("Failed to get stderr")?;
 
-        // Initialize UCI
-        tracing::debug!("Sending 'uci' command");
-        stdin.write_all(b"uci\n").await.map_err(|e| {
-            tracing::error!("Failed to write 'uci' to stdin: {}", e);
-            format!("Failed to write to stdin: {}", e)
-        })?;
-        stdin.flush().await.map_err(|e| {
-            tracing::error!("Failed to flush stdin: {}", e);
-            format!("Failed to flush: {}", e)
-        })?;
-        tracing::debug!("'uci' command sent successfully");
+        let binary_name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "stockfish".to_string());
+        let name = Arc::new(Mutex::new(binary_name));
+        let stderr_tail = Arc::new(Mutex::new(VecDeque::with_capacity(STDERR_TAIL_LINES)));
+        let quitting = Arc::new(AtomicBool::new(false));
 
         // Create channels for communication
         let (command_tx, mut command_rx) = mpsc::channel::<EngineCommand>(32);
-        let (event_tx, event_rx) = mpsc::channel::<EngineEvent>(32);
+        let (event_tx, mut event_rx) = mpsc::channel::<EngineEvent>(32);
+        // uciok/readyok are routed to the handshake until it completes
+        let (handshake_tx, mut handshake_rx) = mpsc::unbounded_channel::<HandshakeSignal>();
 
         // Spawn output reader task
         tracing::debug!("Spawning output reader task");
         let event_tx_clone = event_tx.clone();
+        let reader_name = name.clone();
+        let reader_stderr_tail = stderr_tail.clone();
+        let reader_quitting = quitting.clone();
         let label = config.label.clone().unwrap_or_default();
         let reader_span = tracing::info_span!("stockfish", session = %label);
         tokio::spawn(tracing::Instrument::instrument(
@@ -76,7 +135,16 @@ impl StockfishEngine {
                     line.clear();
                     match reader.read_line(&mut line).await {
                         Ok(0) => {
+                            if reader_quitting.load(Ordering::SeqCst) {
+                                tracing::debug!("Stockfish stdout EOF after quit");
+                                break;
+                            }
                             tracing::warn!("Stockfish stdout EOF - engine closed");
+                            // Give the stderr reader a moment to drain the last lines
+                            tokio::time::sleep(Duration::from_millis(50)).await;
+                            let report = crash_report(&reader_name, &reader_stderr_tail);
+                            tracing::error!("{}", report);
+                            let _ = event_tx_clone.send(EngineEvent::Error(report)).await;
                             break;
                         }
                         Ok(_) => {
@@ -95,10 +163,15 @@ impl StockfishEngine {
                                 let event = match msg {
                                     UciMessage::UciOk => {
                                         tracing::debug!("Received uciok");
-                                        EngineEvent::Ready
+                                        let _ = handshake_tx.send(HandshakeSignal::UciOk);
+                                        continue;
                                     }
                                     UciMessage::ReadyOk => {
                                         tracing::debug!("Received readyok");
+                                        // Once the handshake is done, readyok is a plain Ready
+                                        if handshake_tx.send(HandshakeSignal::ReadyOk).is_ok() {
+                                            continue;
+                                        }
                                         EngineEvent::Ready
                                     }
                                     UciMessage::BestMove { mv, .. } => {
@@ -109,6 +182,17 @@ impl StockfishEngine {
                                         tracing::trace!("Received info: {:?}", info);
                                         EngineEvent::Info(info)
                                     }
+                                    UciMessage::InfoString(text) => {
+                                        tracing::debug!("Engine says: {}", text);
+                                        EngineEvent::InfoString(text)
+                                    }
+                                    UciMessage::Id { name, value } if name == "name" => {
+                                        tracing::info!("Engine identified as {}", value);
+                                        if let Ok(mut engine_name) = reader_name.lock() {
+                                            *engine_name = value;
+                                        }
+                                        continue;
+                                    }
                                     _ => {
                                         tracing::trace!("Ignoring UCI message: {:?}", msg);
                                         continue;
@@ -133,77 +217,84 @@ impl StockfishEngine {
             reader_span,
         ));
 
-        // Wait for uciok
-        tracing::debug!("Waiting for uciok from engine");
-        let mut temp_rx = event_rx;
-        let wait_result = tokio::time::timeout(std::time::Duration::from_secs(10), async {
-            while let Some(event) = temp_rx.recv().await {
-                if matches!(event, EngineEvent::Ready) {
-                    tracing::debug!("Received uciok, engine ready");
-                    return Ok(());
+        // Spawn stderr reader task
+        tracing::debug!("Spawning stderr reader task");
+        let event_tx_for_stderr = event_tx.clone();
+        let stderr_name = name.clone();
+        let stderr_tail_for_reader = stderr_tail.clone();
+        let stderr_span = tracing::info_span!("stockfish", session = %label);
+        tokio::spawn(tracing::Instrument::instrument(
+            async move {
+                let mut lines = BufReader::new(stderr).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    let engine_name = stderr_name.lock().map(|n| n.clone()).unwrap_or_default();
+                    tracing::warn!(engine = %engine_name, "stderr: {}", line);
+                    if let Ok(mut tail) = stderr_tail_for_reader.lock() {
+                        if tail.len() == STDERR_TAIL_LINES {
+                            tail.pop_front();
+                        }
+                        tail.push_back(line.clone());
+                    }
+                    let _ = event_tx_for_stderr
+                        .send(EngineEvent::RawUciMessage {
+                            direction: UciMessageDirection::EngineStderr,
+                            message: line,
+                        })
+                        .await;
                 }
-            }
-            Err("Engine closed before sending uciok")
-        })
-        .await;
+                tracing::debug!("Stderr reader task exiting");
+            },
+            stderr_span,
+        ));
 
-        match wait_result {
-            Ok(Ok(())) => {}
-            Ok(Err(e)) => {
-                tracing::error!("Engine initialization failed: {}", e);
-                return Err(format!("Engine initialization failed: {}", e));
-            }
-            Err(_) => {
-                tracing::error!("Timeout waiting for uciok");
-                return Err("Timeout waiting for engine to respond".to_string());
-            }
-        }
-        let event_rx = temp_rx;
-
-        // Set skill level if provided
-        if let Some(level) = skill_level {
-            tracing::info!("Setting skill level to {}", level);
-            stdin
-                .write_all(format!("setoption name Skill Level value {}\n", level).as_bytes())
-                .await
-                .map_err(|e| {
-                    tracing::error!("Failed to set skill level: {}", e);
-                    format!("Failed to set skill level: {}", e)
-                })?;
-            stdin.flush().await.map_err(|e| {
-                tracing::error!("Failed to flush after skill level: {}", e);
-                format!("Failed to flush: {}", e)
-            })?;
-            tracing::debug!("Skill level set successfully");
-        }
+        // UCI handshake: uci -> options -> isready -> ready
+        let options = startup_options(&config);
+        let mut phase = InitPhase::Uci;
+        let mut attempt = 1;
+        while phase != InitPhase::Ready {
+            let (command, expected) = match phase {
+                InitPhase::Uci => ("uci", HandshakeSignal::UciOk),
+                InitPhase::Options => {
+                    for (name, value) in &options {
+                        tracing::info!("Setting {} to {}", name, value);
+                        write_line(
+                            &mut stdin,
+                            &format!("setoption name {} value {}", name, value),
+                        )
+                        .await?;
+                    }
+                    phase = InitPhase::IsReady;
+                    continue;
+                }
+                InitPhase::IsReady => ("isready", HandshakeSignal::ReadyOk),
+                InitPhase::Ready => unreachable!(),
+            };
 
-        // Set Threads if provided
-        if let Some(threads) = config.threads {
-            let threads = threads.clamp(1, 16);
-            tracing::info!("Setting Threads to {}", threads);
-            stdin
-                .write_all(format!("setoption name Threads value {}\n", threads).as_bytes())
-                .await
-                .map_err(|e| format!("Failed to set Threads: {}", e))?;
-            stdin
-                .flush()
-                .await
-                .map_err(|e| format!("Failed to flush: {}", e))?;
-        }
+            tracing::debug!(?phase, attempt, "Sending '{}'", command);
+            write_line(&mut stdin, command).await?;
 
-        // Set Hash if provided
-        if let Some(hash_mb) = config.hash_mb {
-            let hash_mb = hash_mb.clamp(1, 2048);
-            tracing::info!("Setting Hash to {} MB", hash_mb);
-            stdin
-                .write_all(format!("setoption name Hash value {}\n", hash_mb).as_bytes())
-                .await
-                .map_err(|e| format!("Failed to set Hash: {}", e))?;
-            stdin
-                .flush()
-                .await
-                .map_err(|e| format!("Failed to flush: {}", e))?;
+            match await_handshake(&mut event_rx, &mut handshake_rx, expected).await {
+                Ok(()) => {
+                    phase = phase.next();
+                    attempt = 1;
+                }
+                Err(HandshakeFailure::Timeout) if attempt < HANDSHAKE_ATTEMPTS => {
+                    tracing::warn!(?phase, attempt, "Engine handshake timed out, retrying");
+                    attempt += 1;
+                }
+                Err(failure) => {
+                    tracing::error!(?phase, "Engine initialization failed: {}", failure);
+                    return Err(format!(
+                        "Engine initialization failed during {:?}: {}{}",
+                        phase,
+                        failure,
+                        stderr_suffix(&stderr_tail)
+                    ));
+                }
+            }
         }
+        drop(handshake_rx);
+        tracing::debug!("Engine handshake complete");
 
         // Clone stdin for the command processor task
         let (stdin_tx, mut stdin_rx) = mpsc::channel::<String>(32);
@@ -238,14 +329,11 @@ impl StockfishEngine {
             writer_span,
         ));
 
-        // Send isready
-        tracing::debug!("Sending 'isready' command");
-        let _ = stdin_tx.send("isready\n".to_string()).await;
-
         // Spawn command processor task
         tracing::debug!("Spawning command processor task");
         let _event_tx_for_commands = event_tx.clone();
         let stdin_tx_for_commands = stdin_tx.clone();
+        let quitting_for_commands = quitting.clone();
         let cmd_span = tracing::info_span!("stockfish", session = %label);
         tokio::spawn(tracing::Instrument::instrument(
             async move {
@@ -302,6 +390,7 @@ impl StockfishEngine {
                         }
                         EngineCommand::Quit => {
                             tracing::info!("Sending quit command to engine");
+                            quitting_for_commands.store(true, Ordering::SeqCst);
                             let _ = stdin_tx_for_commands.send("quit\n".to_string()).await;
                             break;
                         }
@@ -316,14 +405,31 @@ impl StockfishEngine {
             cmd_span,
         ));
 
+        let _ = event_tx.send(EngineEvent::Ready).await;
         tracing::info!("Stockfish engine spawned and initialized successfully");
         Ok(Self {
             process,
             command_tx,
             event_rx,
+            name,
+            stderr_tail,
+            quitting,
         })
     }
 
+    /// Engine name as reported by `id name`, or the binary name before that.
+    pub fn name(&self) -> String {
+        self.name.lock().map(|n| n.clone()).unwrap_or_default()
+    }
+
+    /// The most recent lines the engine wrote to stderr, oldest first.
+    pub fn recent_stderr(&self) -> Vec<String> {
+        self.stderr_tail
+            .lock()
+            .map(|tail| tail.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
     /// Send a command to the engine
     #[tracing::instrument(level = "debug", skip(self))]
     pub async fn send_command(&self, cmd: EngineCommand) -> Result<(), String> {
@@ -352,14 +458,93 @@ impl StockfishEngine {
 
     /// Shutdown the engine
     pub async fn shutdown(mut self) {
+        self.quitting.store(true, Ordering::SeqCst);
         let _ = self.send_command(EngineCommand::Quit).await;
-        let _ = tokio::time::timeout(std::time::Duration::from_secs(1), self.process.wait()).await;
+        let _ = tokio::time::timeout(Duration::from_secs(1), self.process.wait()).await;
         let _ = self.process.kill().await;
     }
 }
 
-/// Find Stockfish executable in common locations
-fn find_stockfish_path() -> Option<PathBuf> {
+/// `setoption` pairs sent during the handshake, clamped to sane ranges.
+fn startup_options(config: &EngineConfig) -> Vec<(&'static str, String)> {
+    let mut options = Vec::new();
+    if let Some(level) = config.skill_level {
+        options.push(("Skill Level", level.min(20).to_string()));
+    }
+    if let Some(threads) = config.threads {
+        options.push(("Threads", threads.clamp(1, 16).to_string()));
+    }
+    if let Some(hash_mb) = config.hash_mb {
+        options.push(("Hash", hash_mb.clamp(1, 2048).to_string()));
+    }
+    options
+}
+
+/// Write a single command line to the engine and flush it.
+async fn write_line(stdin: &mut ChildStdin, command: &str) -> Result<(), String> {
+    tracing::trace!("UCI >> {}", command);
+    stdin
+        .write_all(format!("{}\n", command).as_bytes())
+        .await
+        .map_err(|e| format!("Failed to write '{}' to engine: {}", command, e))?;
+    stdin
+        .flush()
+        .await
+        .map_err(|e| format!("Failed to flush engine stdin: {}", e))
+}
+
+/// Wait for a handshake reply, draining engine output meanwhile so the reader
+/// task never blocks on a full event channel.
+async fn await_handshake(
+    events: &mut mpsc::Receiver<EngineEvent>,
+    signals: &mut mpsc::UnboundedReceiver<HandshakeSignal>,
+    expected: HandshakeSignal,
+) -> Result<(), HandshakeFailure> {
+    let wait = async {
+        loop {
+            tokio::select! {
+                signal = signals.recv() => match signal {
+                    Some(signal) if signal == expected => return Ok(()),
+                    Some(_) => continue,
+                    None => return Err(HandshakeFailure::Closed),
+                },
+                event = events.recv() => {
+                    if event.is_none() {
+                        return Err(HandshakeFailure::Closed);
+                    }
+                }
+            }
+        }
+    };
+    tokio::time::timeout(HANDSHAKE_TIMEOUT, wait)
+        .await
+        .unwrap_or(Err(HandshakeFailure::Timeout))
+}
+
+/// Build the error reported when the engine exits without being asked to.
+fn crash_report(name: &Mutex<String>, stderr_tail: &Mutex<VecDeque<String>>) -> String {
+    let engine_name = name.lock().map(|n| n.clone()).unwrap_or_default();
+    format!(
+        "{} exited unexpectedly{}",
+        engine_name,
+        stderr_suffix(stderr_tail)
+    )
+}
+
+/// Format recent stderr output as a suffix for error messages (empty if none).
+fn stderr_suffix(stderr_tail: &Mutex<VecDeque<String>>) -> String {
+    match stderr_tail.lock() {
+        Ok(tail) if !tail.is_empty() => {
+            let lines: Vec<&str> = tail.iter().map(String::as_str).collect();
+            format!("; recent stderr:\n{}", lines.join("\n"))
+        }
+        _ => String::new(),
+    }
+}
+
+/// Find Stockfish executable in common locations. A binary that cannot be
+/// run, say built for another architecture, does not count.
+pub fn find_stockfish_path() -> Option<PathBuf> {
     // Common paths to check
     let paths = vec![
         "/usr/local/bin/stockfish",
@@ -385,3 +570,77 @@ fn find_stockfish_path() -> Option<PathBuf> {
 
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crash_report_includes_name_and_stderr() {
+        let name = Mutex::new("Stockfish 16".to_string());
+        let tail = Mutex::new(VecDeque::from(vec![
+            "ERROR: Network evaluation parameters incompatible".to_string(),
+            "ERROR: The network file nn-x.nnue was not loaded successfully".to_string(),
+        ]));
+        let report = crash_report(&name, &tail);
+        assert!(report.starts_with("Stockfish 16 exited unexpectedly"));
+        assert!(report.contains("nn-x.nnue was not loaded"));
+    }
+
+    #[test]
+    fn test_init_phase_order() {
+        assert_eq!(InitPhase::Uci.next(), InitPhase::Options);
+        assert_eq!(InitPhase::Options.next(), InitPhase::IsReady);
+        assert_eq!(InitPhase::IsReady.next(), InitPhase::Ready);
+        assert_eq!(InitPhase::Ready.next(), InitPhase::Ready);
+    }
+
+    #[test]
+    fn test_startup_options_clamped() {
+        let config = EngineConfig {
+            skill_level: Some(25),
+            threads: Some(64),
+            hash_mb: Some(0),
+            label: None,
+        };
+        let options = startup_options(&config);
+        assert_eq!(
+            options,
+            vec![
+                ("Skill Level", "20".to_string()),
+                ("Threads", "16".to_string()),
+                ("Hash", "1".to_string()),
+            ]
+        );
+        assert!(startup_options(&EngineConfig::default()).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_await_handshake_ignores_other_signals() {
+        let (event_tx, mut event_rx) = mpsc::channel(4);
+        let (signal_tx, mut signal_rx) = mpsc::unbounded_channel();
+        event_tx
+            .send(EngineEvent::InfoString("loading".to_string()))
+            .await
+            .unwrap();
+        signal_tx.send(HandshakeSignal::UciOk).unwrap();
+        signal_tx.send(HandshakeSignal::ReadyOk).unwrap();
+        let result = await_handshake(&mut event_rx, &mut signal_rx, HandshakeSignal::ReadyOk).await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_await_handshake_reports_closed_engine() {
+        let (_event_tx, mut event_rx) = mpsc::channel(4);
+        let (signal_tx, mut signal_rx) = mpsc::unbounded_channel();
+        drop(signal_tx);
+        let result = await_handshake(&mut event_rx, &mut signal_rx, HandshakeSignal::UciOk).await;
+        assert!(matches!(result, Err(HandshakeFailure::Closed)));
+    }
+
+    #[test]
+    fn test_stderr_suffix_empty_without_output() {
+        let tail = Mutex::new(VecDeque::new());
+        assert_eq!(stderr_suffix(&tail), "");
+    }
+}
@@ -1,15 +1,23 @@
-use crate::{EngineInfo, Score};
+use crate::{EngineInfo, Score, ScoreBound, Wdl};
 use chess::converters::{format_piece, format_square, parse_piece, parse_square};
 use cozy_chess::{Move, Piece};
 
 /// Incoming message from UCI engine
 #[derive(Debug, Clone)]
 pub enum UciMessage {
-    Id { name: String, value: String },
+    Id {
+        name: String,
+        value: String,
+    },
     UciOk,
     ReadyOk,
-    BestMove { mv: Move, ponder: Option<Move> },
+    BestMove {
+        mv: Move,
+        ponder: Option<Move>,
+    },
     Info(EngineInfo),
+    /// `info string ...` — free-form text from the engine.
+    InfoString(String),
 }
 
 /// Parse a UCI message line
@@ -42,6 +50,10 @@ pub fn parse_uci_message(line: &str) -> Result<UciMessage, crate::UciError> {
             Ok(UciMessage::BestMove { mv, ponder })
         }
 
+        Some(&"info") if tokens.get(1) == Some(&"string") => {
+            Ok(UciMessage::InfoString(tokens[2..].join(" ")))
+        }
+
         Some(&"info") => Ok(UciMessage::Info(parse_info_line(&tokens[1..])?)),
 
         _ => Err(crate::UciError::UnknownMessage(line.to_string())),
@@ -88,6 +100,34 @@ fn parse_info_line(tokens: &[&str]) -> Result<EngineInfo, crate::UciError> {
                     }
                 }
             }
+            "lowerbound" => info.score_bound = Some(ScoreBound::Lower),
+            "upperbound" => info.score_bound = Some(ScoreBound::Upper),
+            "wdl" => {
+                let values: Vec<u16> = tokens
+                    .iter()
+                    .skip(i + 1)
+                    .take(3)
+                    .map_while(|s| s.parse().ok())
+                    .collect();
+                if let [win, draw, loss] = values[..] {
+                    info.wdl = Some(Wdl { win, draw, loss });
+                }
+                i += values.len();
+            }
+            "tbhits" => {
+                i += 1;
+                info.tbhits = tokens.get(i).and_then(|s| s.parse().ok());
+            }
+            "cpuload" => {
+                i += 1;
+                info.cpuload = tokens.get(i).and_then(|s| s.parse().ok());
+            }
+            "string" => {
+                // `string` swallows the rest of the line
+                info.extras
+                    .insert("string".to_string(), tokens[i + 1..].join(" "));
+                break;
+            }
             "pv" => {
                 // Collect all moves until next keyword
                 i += 1;
@@ -111,8 +151,17 @@ fn parse_info_line(tokens: &[&str]) -> Result<EngineInfo, crate::UciError> {
                 i += 1;
                 info.hashfull = tokens.get(i).and_then(|s| s.parse().ok());
             }
-            _ => {
-                // Unknown keyword, skip
+            key => {
+                // Unknown keyword: keep its value (if any) so vendor extensions survive
+                let mut value = Vec::new();
+                while let Some(&token) = tokens.get(i + 1) {
+                    if is_keyword(token) {
+                        break;
+                    }
+                    value.push(token);
+                    i += 1;
+                }
+                info.extras.insert(key.to_string(), value.join(" "));
             }
         }
         i += 1;
@@ -135,8 +184,15 @@ fn is_keyword(token: &str) -> bool {
             | "hashfull"
             | "nps"
             | "tbhits"
+            | "sbhits"
             | "cpuload"
             | "string"
+            | "lowerbound"
+            | "upperbound"
+            | "wdl"
+            | "refutation"
+            | "currline"
+            | "currmovenumber"
     )
 }
 
@@ -146,9 +202,15 @@ pub fn parse_uci_move(s: &str) -> Result<Move, crate::UciError> {
         return Err(crate::UciError::InvalidMove(s.to_string()));
     }
 
-    let from =
-        parse_square(&s[0..2]).ok_or_else(|| crate::UciError::InvalidSquare(s.to_string()))?;
-    let to = parse_square(&s[2..4]).ok_or_else(|| crate::UciError::InvalidSquare(s.to_string()))?;
+    // `get` rather than slicing: the engine may send non-ASCII bytes
+    let from = s
+        .get(0..2)
+        .and_then(parse_square)
+        .ok_or_else(|| crate::UciError::InvalidSquare(s.to_string()))?;
+    let to = s
+        .get(2..4)
+        .and_then(parse_square)
+        .ok_or_else(|| crate::UciError::InvalidSquare(s.to_string()))?;
 
     let promotion = if s.len() == 5 {
         let promo_char = s.chars().nth(4).unwrap();
@@ -207,4 +269,83 @@ mod tests {
             _ => panic!("Wrong message type"),
         }
     }
+
+    #[test]
+    fn test_parse_info_string() {
+        let msg = parse_uci_message("info string NNUE evaluation using nn-1111.nnue").unwrap();
+        match msg {
+            UciMessage::InfoString(text) => {
+                assert_eq!(text, "NNUE evaluation using nn-1111.nnue");
+            }
+            _ => panic!("Wrong message type"),
+        }
+    }
+
+    #[test]
+    fn test_parse_score_bounds() {
+        let msg = parse_uci_message("info depth 20 score cp 41 lowerbound nodes 100").unwrap();
+        match msg {
+            UciMessage::Info(info) => {
+                assert!(matches!(info.score, Some(Score::Centipawns(41))));
+                assert_eq!(info.score_bound, Some(ScoreBound::Lower));
+                assert_eq!(info.nodes, Some(100));
+            }
+            _ => panic!("Wrong message type"),
+        }
+
+        let msg = parse_uci_message("info depth 20 score mate 3 upperbound").unwrap();
+        match msg {
+            UciMessage::Info(info) => {
+                assert!(matches!(info.score, Some(Score::Mate(3))));
+                assert_eq!(info.score_bound, Some(ScoreBound::Upper));
+            }
+            _ => panic!("Wrong message type"),
+        }
+    }
+
+    #[test]
+    fn test_parse_wdl_and_tbhits() {
+        let msg =
+            parse_uci_message("info depth 9 score cp 20 wdl 120 840 40 tbhits 7 pv d2d4").unwrap();
+        match msg {
+            UciMessage::Info(info) => {
+                assert_eq!(
+                    info.wdl,
+                    Some(Wdl {
+                        win: 120,
+                        draw: 840,
+                        loss: 40
+                    })
+                );
+                assert_eq!(info.tbhits, Some(7));
+                assert_eq!(info.pv.len(), 1);
+                assert!(info.extras.is_empty());
+            }
+            _ => panic!("Wrong message type"),
+        }
+    }
+
+    #[test]
+    fn test_parse_unknown_keys_kept_in_extras() {
+        let msg = parse_uci_message("info depth 5 movesleft 42 qhits 3 4 pv e2e4").unwrap();
+        match msg {
+            UciMessage::Info(info) => {
+                assert_eq!(info.depth, Some(5));
+                assert_eq!(info.extras.get("movesleft").map(String::as_str), Some("42"));
+                assert_eq!(info.extras.get("qhits").map(String::as_str), Some("3 4"));
+                assert_eq!(info.pv.len(), 1);
+            }
+            _ => panic!("Wrong message type"),
+        }
+    }
+
+    #[test]
+    fn test_parse_uci_move_rejects_non_ascii() {
+        assert!(parse_uci_move("aé34").is_err());
+        assert!(parse_uci_move("e2é4").is_err());
+        assert!(matches!(
+            parse_uci_message("bestmove ë2e4"),
+            Err(crate::UciError::InvalidSquare(_))
+        ));
+    }
 }
@@ -0,0 +1,13 @@
+#![no_main]
+
+use chess::fen::{format_fen, parse_fen};
+use libfuzzer_sys::fuzz_target;
+
+// Whatever parses must write out as a FEN that parses to the same position
+fuzz_target!(|data: &str| {
+    if let Ok(board) = parse_fen(data) {
+        let fen = format_fen(&board);
+        let reparsed = parse_fen(&fen).expect("written FEN does not parse");
+        assert_eq!(format_fen(&reparsed), fen);
+    }
+});
@@ -0,0 +1,11 @@
+#![no_main]
+
+use chess::pgn::parse_games;
+use libfuzzer_sys::fuzz_target;
+
+// Parsing, and writing back what was parsed, must not panic
+fuzz_target!(|data: &str| {
+    for game in parse_games(data) {
+        let _ = parse_games(&game.to_pgn());
+    }
+});
@@ -0,0 +1,16 @@
+#![no_main]
+
+use chess::fen::parse_fen;
+use chess::parse_san;
+use cozy_chess::Board;
+use libfuzzer_sys::fuzz_target;
+
+// A position as FEN on the first line, SAN on the second; positions that do
+// not parse fall back to the start position
+fuzz_target!(|data: &str| {
+    let (fen, san) = data.split_once('\n').unwrap_or(("", data));
+    let board = parse_fen(fen).unwrap_or_else(|_| Board::default());
+    if let Some(mv) = parse_san(&board, san) {
+        assert!(board.is_legal(mv), "{} read as illegal {}", san, mv);
+    }
+});
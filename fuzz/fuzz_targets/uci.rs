@@ -0,0 +1,12 @@
+#![no_main]
+
+use engine::uci::{parse_uci_message, parse_uci_move};
+use libfuzzer_sys::fuzz_target;
+
+// Engine output, one message per line
+fuzz_target!(|data: &str| {
+    for line in data.lines() {
+        let _ = parse_uci_message(line);
+    }
+    let _ = parse_uci_move(data);
+});
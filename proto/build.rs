@@ -14,6 +14,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 "proto/positions.proto",
                 "proto/review.proto",
                 "proto/advanced_review.proto",
+                "proto/sync.proto",
                 "proto/chess_service.proto",
             ],
             &["proto"],
@@ -0,0 +1,128 @@
+//! Conversions between the wire types and the domain types of the `chess`
+//! crate, so every crate converts game modes the same way.
+
+use ::chess::{GameMode, PlayerSide};
+
+use crate::chess::{GameModeProto, GameModeType, PlayerSideProto};
+
+impl From<PlayerSide> for PlayerSideProto {
+    fn from(side: PlayerSide) -> Self {
+        match side {
+            PlayerSide::White => PlayerSideProto::White,
+            PlayerSide::Black => PlayerSideProto::Black,
+        }
+    }
+}
+
+impl From<PlayerSideProto> for PlayerSide {
+    fn from(side: PlayerSideProto) -> Self {
+        match side {
+            PlayerSideProto::White => PlayerSide::White,
+            PlayerSideProto::Black => PlayerSide::Black,
+        }
+    }
+}
+
+impl From<&GameMode> for GameModeProto {
+    fn from(mode: &GameMode) -> Self {
+        let mode_type = match mode {
+            GameMode::HumanVsHuman => GameModeType::HumanVsHuman,
+            GameMode::HumanVsEngine { .. } => GameModeType::HumanVsEngine,
+            GameMode::EngineVsEngine => GameModeType::EngineVsEngine,
+            GameMode::Analysis => GameModeType::Analysis,
+            GameMode::Review => GameModeType::Review,
+        };
+        GameModeProto {
+            mode: mode_type as i32,
+            human_side: mode
+                .human_side()
+                .map(|side| PlayerSideProto::from(side) as i32),
+        }
+    }
+}
+
+impl From<GameMode> for GameModeProto {
+    fn from(mode: GameMode) -> Self {
+        Self::from(&mode)
+    }
+}
+
+/// Unknown modes read as human vs human, and a human playing the engine
+/// without a side plays White.
+impl From<&GameModeProto> for GameMode {
+    fn from(proto: &GameModeProto) -> Self {
+        match GameModeType::try_from(proto.mode) {
+            Ok(GameModeType::HumanVsEngine) => GameMode::HumanVsEngine {
+                human_side: proto
+                    .human_side
+                    .and_then(|side| PlayerSideProto::try_from(side).ok())
+                    .map_or(PlayerSide::White, PlayerSide::from),
+            },
+            Ok(GameModeType::EngineVsEngine) => GameMode::EngineVsEngine,
+            Ok(GameModeType::Analysis) => GameMode::Analysis,
+            Ok(GameModeType::Review) => GameMode::Review,
+            Ok(GameModeType::HumanVsHuman) | Err(_) => GameMode::HumanVsHuman,
+        }
+    }
+}
+
+impl From<GameModeProto> for GameMode {
+    fn from(proto: GameModeProto) -> Self {
+        Self::from(&proto)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_game_mode_round_trips() {
+        let modes = [
+            GameMode::HumanVsHuman,
+            GameMode::HumanVsEngine {
+                human_side: PlayerSide::White,
+            },
+            GameMode::HumanVsEngine {
+                human_side: PlayerSide::Black,
+            },
+            GameMode::EngineVsEngine,
+            GameMode::Analysis,
+            GameMode::Review,
+        ];
+        for mode in modes {
+            let proto = GameModeProto::from(&mode);
+            assert_eq!(GameMode::from(&proto), mode);
+        }
+    }
+
+    #[test]
+    fn test_human_side_only_against_the_engine() {
+        let proto = GameModeProto::from(GameMode::HumanVsEngine {
+            human_side: PlayerSide::Black,
+        });
+        assert_eq!(proto.mode, GameModeType::HumanVsEngine as i32);
+        assert_eq!(proto.human_side, Some(PlayerSideProto::Black as i32));
+        assert_eq!(GameModeProto::from(GameMode::Analysis).human_side, None);
+    }
+
+    #[test]
+    fn test_lenient_decoding() {
+        let unknown = GameModeProto {
+            mode: 99,
+            human_side: None,
+        };
+        assert_eq!(GameMode::from(unknown), GameMode::HumanVsHuman);
+
+        let sideless = GameModeProto {
+            mode: GameModeType::HumanVsEngine as i32,
+            human_side: None,
+        };
+        assert_eq!(
+            GameMode::from(sideless),
+            GameMode::HumanVsEngine {
+                human_side: PlayerSide::White
+            }
+        );
+    }
+}
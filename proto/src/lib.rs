@@ -7,5 +7,7 @@ pub mod chess {
     tonic::include_proto!("chess");
 }
 
+mod convert;
+
 // Re-export commonly used types
-pub use chess::*;
+pub use self::chess::*;
@@ -0,0 +1,7 @@
+-- Record what the engine saw when it played a move: the final search info
+-- before bestmove. All columns are NULL for human moves.
+ALTER TABLE stored_moves ADD COLUMN engine_depth INTEGER;
+ALTER TABLE stored_moves ADD COLUMN engine_eval_type TEXT CHECK(engine_eval_type IN ('cp', 'mate'));
+ALTER TABLE stored_moves ADD COLUMN engine_eval_value INTEGER;
+ALTER TABLE stored_moves ADD COLUMN engine_nodes INTEGER;
+ALTER TABLE stored_moves ADD COLUMN engine_time_ms INTEGER;
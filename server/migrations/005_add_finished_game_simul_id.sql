@@ -0,0 +1,4 @@
+-- Games played as boards of one simul share a simul_id so they can be
+-- reviewed together. NULL for regular games.
+ALTER TABLE finished_games ADD COLUMN simul_id TEXT;
+CREATE INDEX idx_finished_games_simul_id ON finished_games(simul_id);
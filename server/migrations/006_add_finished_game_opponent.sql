@@ -0,0 +1,4 @@
+-- Name of the engine opponent profile a game was played against, for
+-- head-to-head stats. NULL for games without a named opponent.
+ALTER TABLE finished_games ADD COLUMN opponent TEXT;
+CREATE INDEX idx_finished_games_opponent ON finished_games(opponent);
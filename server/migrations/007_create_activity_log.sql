@@ -0,0 +1,10 @@
+-- Daily activity counts per player profile, for the Stats heat-map.
+-- `day` is the number of days since the Unix epoch (UTC).
+CREATE TABLE activity_log (
+    profile        TEXT NOT NULL,
+    day            INTEGER NOT NULL,
+    games_played   INTEGER NOT NULL DEFAULT 0,
+    puzzles_solved INTEGER NOT NULL DEFAULT 0,
+    reviews_read   INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (profile, day)
+) STRICT;
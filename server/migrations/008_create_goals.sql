@@ -0,0 +1,12 @@
+-- Goals set by player profiles, evaluated against the stats on demand.
+-- `metric` and `period` hold the names from GoalMetric / GoalPeriod.
+CREATE TABLE goals (
+    goal_id    TEXT PRIMARY KEY NOT NULL,
+    profile    TEXT NOT NULL,
+    metric     TEXT NOT NULL,
+    target     REAL NOT NULL,
+    period     TEXT NOT NULL,
+    created_at INTEGER NOT NULL
+) STRICT;
+
+CREATE INDEX idx_goals_profile ON goals(profile);
@@ -0,0 +1,16 @@
+-- State for server-to-server sync.
+-- `sync_node` holds this server's stable node id (a single row).
+CREATE TABLE sync_node (
+    singleton INTEGER PRIMARY KEY NOT NULL CHECK (singleton = 1),
+    node_id   TEXT NOT NULL
+) STRICT;
+
+-- Version vector of every synced record. `clock` is a JSON object mapping
+-- node ids to counters; `content_hash` is NULL once the record was deleted.
+CREATE TABLE sync_clocks (
+    kind         TEXT NOT NULL,
+    record_id    TEXT NOT NULL,
+    clock        TEXT NOT NULL,
+    content_hash INTEGER,
+    PRIMARY KEY (kind, record_id)
+) STRICT;
@@ -0,0 +1,10 @@
+-- Destructive operations, imports and config changes, newest last.
+-- `action` holds the name from AuditAction; `actor` identifies the client.
+CREATE TABLE audit_log (
+    entry_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    at       INTEGER NOT NULL,
+    actor    TEXT NOT NULL,
+    action   TEXT NOT NULL,
+    target   TEXT NOT NULL,
+    detail   TEXT NOT NULL
+) STRICT;
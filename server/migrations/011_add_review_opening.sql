@@ -0,0 +1,6 @@
+-- ECO classification of a reviewed game: code, name and the last ply still
+-- in the opening tree. NULL until the review completes, and for games that
+-- leave the tree on the first move.
+ALTER TABLE game_reviews ADD COLUMN opening_eco TEXT;
+ALTER TABLE game_reviews ADD COLUMN opening_name TEXT;
+ALTER TABLE game_reviews ADD COLUMN opening_ply INTEGER;
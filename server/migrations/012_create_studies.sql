@@ -0,0 +1,12 @@
+-- Studies: merged variation trees built from the finished games matching a
+-- filter. `filter`, `game_ids` and `tree` hold the StudyFilter, the list of
+-- game ids and the root StudyNode as JSON. A study is a snapshot and keeps
+-- no reference to the games it was built from.
+CREATE TABLE studies (
+    study_id   TEXT PRIMARY KEY NOT NULL,
+    name       TEXT NOT NULL,
+    filter     TEXT NOT NULL,
+    game_ids   TEXT NOT NULL,
+    tree       TEXT NOT NULL,
+    created_at INTEGER NOT NULL
+) STRICT;
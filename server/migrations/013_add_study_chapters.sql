@@ -0,0 +1,5 @@
+-- Chapters of a study, in order, as a JSON list of StudyChapter: each a
+-- move tree from a position or a finished game, with the comments, NAGs
+-- and arrows added to its moves. Studies built before chapters start with
+-- none.
+ALTER TABLE studies ADD COLUMN chapters TEXT NOT NULL DEFAULT '[]';
@@ -0,0 +1,10 @@
+-- Chat between the clients watching a session, in the order it was sent.
+-- `seq` counts a session's messages from 1. Kept after the session closes.
+CREATE TABLE chat_messages (
+    session_id TEXT NOT NULL,
+    seq        INTEGER NOT NULL,
+    author     TEXT NOT NULL,
+    text       TEXT NOT NULL,
+    sent_at    INTEGER NOT NULL,
+    PRIMARY KEY (session_id, seq)
+) STRICT;
@@ -0,0 +1,3 @@
+-- Time the player took over each move, pauses excluded, so games played
+-- without a clock can be replayed against one. NULL when it was not measured.
+ALTER TABLE stored_moves ADD COLUMN think_ms INTEGER;
@@ -0,0 +1,4 @@
+-- Result of an imported game as found from its final position or closing
+-- comment, kept next to the claimed result so a wrong Result tag shows.
+-- NULL when it could not be determined or for games played here.
+ALTER TABLE finished_games ADD COLUMN derived_result TEXT;
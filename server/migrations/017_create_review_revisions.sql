@@ -0,0 +1,15 @@
+-- Earlier passes of a game's review, kept when the game is analysed again.
+-- `revision` counts a game's passes from 1. `positions` holds the pass's
+-- PositionReviews as JSON: a revision is only ever read whole, to compare it
+-- with the current review.
+CREATE TABLE review_revisions (
+    game_id        TEXT NOT NULL REFERENCES finished_games(game_id) ON DELETE CASCADE,
+    revision       INTEGER NOT NULL,
+    archived_at    INTEGER NOT NULL,
+    analysis_depth INTEGER NOT NULL,
+    completed_at   INTEGER,
+    white_accuracy REAL,
+    black_accuracy REAL,
+    positions      TEXT NOT NULL,
+    PRIMARY KEY (game_id, revision)
+) STRICT;
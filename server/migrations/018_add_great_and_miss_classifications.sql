@@ -0,0 +1,39 @@
+-- Allow the Great (only move) and Miss (unpunished opponent error)
+-- classifications. SQLite can't change a CHECK constraint in place, so the
+-- table is recreated.
+CREATE TABLE position_reviews_new (
+    id               INTEGER PRIMARY KEY,
+    game_id          TEXT NOT NULL REFERENCES game_reviews(game_id) ON DELETE CASCADE,
+    ply              INTEGER NOT NULL,
+    fen              TEXT NOT NULL,
+    played_san       TEXT NOT NULL,
+    best_move_san    TEXT NOT NULL,
+    best_move_uci    TEXT NOT NULL,
+    eval_before_type  TEXT NOT NULL CHECK(eval_before_type IN ('cp', 'mate')),
+    eval_before_value INTEGER NOT NULL,
+    eval_after_type   TEXT NOT NULL CHECK(eval_after_type IN ('cp', 'mate')),
+    eval_after_value  INTEGER NOT NULL,
+    eval_best_type    TEXT NOT NULL CHECK(eval_best_type IN ('cp', 'mate')),
+    eval_best_value   INTEGER NOT NULL,
+    classification   TEXT NOT NULL CHECK(classification IN (
+                        'Brilliant','Great','Best','Excellent','Good',
+                        'Inaccuracy','Mistake','Blunder','Miss','Forced','Book'
+                     )),
+    cp_loss          INTEGER NOT NULL CHECK(cp_loss >= 0),
+    pv               TEXT NOT NULL DEFAULT '[]' CHECK(json_valid(pv)),
+    depth            INTEGER NOT NULL,
+    clock_ms         INTEGER,
+    UNIQUE(game_id, ply)
+) STRICT;
+
+INSERT INTO position_reviews_new
+    (id, game_id, ply, fen, played_san, best_move_san, best_move_uci,
+     eval_before_type, eval_before_value, eval_after_type, eval_after_value,
+     eval_best_type, eval_best_value, classification, cp_loss, pv, depth, clock_ms)
+SELECT id, game_id, ply, fen, played_san, best_move_san, best_move_uci,
+       eval_before_type, eval_before_value, eval_after_type, eval_after_value,
+       eval_best_type, eval_best_value, classification, cp_loss, pv, depth, clock_ms
+FROM position_reviews;
+
+DROP TABLE position_reviews;
+ALTER TABLE position_reviews_new RENAME TO position_reviews;
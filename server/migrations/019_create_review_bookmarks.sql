@@ -0,0 +1,9 @@
+-- Positions of a finished game the player marked during review, one per
+-- ply. `ply` 0 is the starting position; `note` is empty for a bare bookmark.
+CREATE TABLE review_bookmarks (
+    game_id    TEXT NOT NULL REFERENCES finished_games(game_id) ON DELETE CASCADE,
+    ply        INTEGER NOT NULL,
+    note       TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    PRIMARY KEY (game_id, ply)
+) STRICT;
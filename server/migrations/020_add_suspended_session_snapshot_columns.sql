@@ -0,0 +1,5 @@
+-- Name of a snapshot and the session or finished game it was taken from, so
+-- the snapshots of one game can be listed together. NULL for suspended
+-- sessions and for snapshots saved before these columns existed.
+ALTER TABLE suspended_sessions ADD COLUMN name TEXT;
+ALTER TABLE suspended_sessions ADD COLUMN source_game TEXT;
@@ -0,0 +1,6 @@
+-- Mobility and space of each side in an analysed position. 0 for positions
+-- analysed before these columns existed.
+ALTER TABLE advanced_position_analyses ADD COLUMN mobility_white INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE advanced_position_analyses ADD COLUMN space_white INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE advanced_position_analyses ADD COLUMN mobility_black INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE advanced_position_analyses ADD COLUMN space_black INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,4 @@
+-- The ending a reviewed game spent the most positions in, e.g. 'rook'.
+-- NULL for games that never reached an endgame and for reviews completed
+-- before this column existed.
+ALTER TABLE game_reviews ADD COLUMN endgame TEXT;
@@ -0,0 +1,5 @@
+-- Startup recovery and engine retries look up the reviews that are not
+-- complete, failed ones included, instead of loading every review.
+DROP INDEX idx_game_reviews_pending;
+CREATE INDEX idx_game_reviews_unfinished ON game_reviews(status)
+    WHERE status IN ('Queued', 'Analyzing', 'Failed');
@@ -0,0 +1,5 @@
+-- Why a move was left out of its side's accuracy: 'book', 'forced' or
+-- 'decided'. NULL for moves that count, and for reviews completed before
+-- this column existed.
+ALTER TABLE position_reviews ADD COLUMN excluded TEXT
+    CHECK(excluded IN ('book', 'forced', 'decided'));
@@ -0,0 +1,5 @@
+-- Whether the position had one move that alone held it, per the engine's
+-- second line; an only legal move is classified Forced instead. 0 for
+-- reviews completed before this column existed.
+ALTER TABLE position_reviews ADD COLUMN only_move INTEGER NOT NULL DEFAULT 0
+    CHECK(only_move IN (0, 1));
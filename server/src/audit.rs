@@ -0,0 +1,169 @@
+//! Audit log of destructive operations, imports and config changes.
+//!
+//! Entries name the client that caused them: the uid and pid of a process on
+//! the Unix socket, or the address of a TCP peer. Recording never fails the
+//! operation being audited; a failed write is logged instead.
+
+use tonic::transport::server::{TcpConnectInfo, TlsConnectInfo, UdsConnectInfo};
+use tonic::Request;
+
+use crate::persistence::{now_timestamp, AuditAction, AuditEntry, AuditRepository, Persistence};
+
+/// Actor recorded for operations the server starts on its own.
+pub const SERVER_ACTOR: &str = "server";
+
+pub struct AuditLog<D: Persistence> {
+    store: D::Audit,
+}
+
+impl<D: Persistence> AuditLog<D> {
+    pub fn new(store: D::Audit) -> Self {
+        Self { store }
+    }
+
+    pub async fn record(
+        &self,
+        actor: &str,
+        action: AuditAction,
+        target: impl Into<String>,
+        detail: impl Into<String>,
+    ) {
+        let entry = AuditEntry {
+            at: now_timestamp(),
+            actor: actor.to_string(),
+            action,
+            target: target.into(),
+            detail: detail.into(),
+        };
+        tracing::info!(
+            actor = %entry.actor,
+            action = entry.action.as_str(),
+            target = %entry.target,
+            "Audit: {}",
+            entry.detail
+        );
+        if let Err(e) = self.store.record_audit(&entry).await {
+            tracing::warn!("Failed to write audit entry: {}", e);
+        }
+    }
+
+    /// The `limit` most recent entries, newest first.
+    pub async fn list(&self, limit: usize) -> Result<Vec<AuditEntry>, String> {
+        self.store
+            .list_audit(limit)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Who sent `request`, as recorded in the audit log.
+pub fn client_identity<T>(request: &Request<T>) -> String {
+    if let Some(info) = request.extensions().get::<UdsConnectInfo>() {
+        if let Some(cred) = &info.peer_cred {
+            return match cred.pid() {
+                Some(pid) => format!("uid {} (pid {})", cred.uid(), pid),
+                None => format!("uid {}", cred.uid()),
+            };
+        }
+    }
+    let extensions = request.extensions();
+    if let Some(addr) = extensions
+        .get::<TcpConnectInfo>()
+        .or_else(|| {
+            extensions
+                .get::<TlsConnectInfo<TcpConnectInfo>>()
+                .map(TlsConnectInfo::get_ref)
+        })
+        .and_then(TcpConnectInfo::remote_addr)
+    {
+        return addr.to_string();
+    }
+    "unknown".to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::{AuditStore, JsonPersistence};
+
+    #[test]
+    fn test_client_identity() {
+        assert_eq!(client_identity(&Request::new(())), "unknown");
+
+        let mut request = Request::new(());
+        request.extensions_mut().insert(TcpConnectInfo {
+            local_addr: None,
+            remote_addr: Some("192.0.2.1:50000".parse().unwrap()),
+        });
+        assert_eq!(client_identity(&request), "192.0.2.1:50000");
+    }
+
+    #[tokio::test]
+    async fn test_client_identity_over_tls() {
+        use std::sync::Arc;
+        use tokio_rustls::rustls::{ClientConfig, RootCertStore, ServerConfig};
+        use tokio_rustls::{TlsAcceptor, TlsConnector};
+        use tonic::transport::server::Connected;
+
+        let cert_pem = include_bytes!("../testdata/tls-cert.pem");
+        let key_pem = include_bytes!("../testdata/tls-key.pem");
+        let certs: Vec<_> = rustls_pemfile::certs(&mut &cert_pem[..])
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let key = rustls_pemfile::private_key(&mut &key_pem[..])
+            .unwrap()
+            .unwrap();
+        let mut roots = RootCertStore::empty();
+        roots.add(certs[0].clone()).unwrap();
+        let acceptor = TlsAcceptor::from(Arc::new(
+            ServerConfig::builder()
+                .with_no_client_auth()
+                .with_single_cert(certs, key)
+                .unwrap(),
+        ));
+        let connector = TlsConnector::from(Arc::new(
+            ClientConfig::builder()
+                .with_root_certificates(roots)
+                .with_no_client_auth(),
+        ));
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let client = tokio::spawn(async move {
+            let tcp = tokio::net::TcpStream::connect(addr).await.unwrap();
+            let name = "localhost".try_into().unwrap();
+            let tls = connector.connect(name, tcp).await.unwrap();
+            tls.get_ref().0.local_addr().unwrap()
+        });
+        let (tcp, _) = listener.accept().await.unwrap();
+        let tls = acceptor.accept(tcp).await.unwrap();
+        let client_addr = client.await.unwrap();
+
+        // What tonic attaches to requests on a TLS listener
+        let mut request = Request::new(());
+        request.extensions_mut().insert(tls.connect_info());
+        assert_eq!(client_identity(&request), client_addr.to_string());
+    }
+
+    #[tokio::test]
+    async fn test_record_and_list() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = AuditLog::<JsonPersistence>::new(AuditStore::new(dir.path().to_path_buf()));
+
+        log.record("uid 1000", AuditAction::Delete, "position pos_1", "Opening")
+            .await;
+        log.record(
+            SERVER_ACTOR,
+            AuditAction::Import,
+            "peer desktop",
+            "3 received",
+        )
+        .await;
+
+        let entries = log.list(10).await.unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].actor, SERVER_ACTOR);
+        assert_eq!(entries[1].target, "position pos_1");
+        assert!(entries[1].at > 0);
+    }
+}
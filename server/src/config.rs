@@ -4,8 +4,33 @@
 //! - Legacy JSON data directory (for migration): `get_legacy_data_dir()`
 //! - SQLite database path: `get_db_path()`
 //! - Unix Domain Socket path: `get_socket_path()`
+//! - Optional TCP address for remote clients: `get_listen_tcp_addr()`, served
+//!   over TLS with `get_tls_files()`
+//! - Database encryption key source: `get_db_key_source()`
+//! - Minimum delay between engine moves: `get_engine_move_delay_ms()`
+//! - Caps on session engines: `get_engine_limits()`
+//! - Engines kept for `AnalyzePosition`: `get_analysis_engines()`
+//! - Abuse protection for TCP listeners: `get_tcp_guard_limits()`
+//! - Server-to-server sync: `get_sync_listen_addr()`, `get_sync_token()`,
+//!   `get_sync_peers()`, `get_sync_interval_secs()` and `get_sync_ca()`
+//! - PGN watch folder: `get_import_dir()`, `get_import_interval_secs()` and
+//!   `get_import_review()`
+//! - Syzygy tablebases for reviews: `get_syzygy_paths()`
+//! - Opening books sessions may load: `get_books_dir()`
+//! - Moves left out of review accuracy: `get_accuracy_exclusions()`
+//! - Low-power mode on battery: `get_power_policy()`
+//! - Free disk space guard: `get_disk_thresholds()`, with the log sized from
+//!   `get_server_log_path()`
 
-use std::path::PathBuf;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+
+use analysis::{AccuracyExclusions, DEFAULT_DECIDED_CP};
+
+use crate::disk::DiskThresholds;
+use crate::guard::{BanRule, GuardLimits};
+use crate::power::PowerPolicy;
+use crate::session::resources::EngineLimits;
 
 const DEFAULT_CONFIG_DIR: &str = ".config/chesstty/data";
 const DEV_DATA_DIR: &str = "./data";
@@ -13,6 +38,12 @@ const DEV_DATA_DIR: &str = "./data";
 /// Default socket path for server communication.
 const DEFAULT_SOCKET_PATH: &str = "/tmp/chesstty.sock";
 
+/// Default minimum delay between engine moves, so fast games stay watchable.
+const DEFAULT_ENGINE_MOVE_DELAY_MS: u64 = 500;
+
+/// Upper bound for the engine move delay.
+pub const MAX_ENGINE_MOVE_DELAY_MS: u64 = 10_000;
+
 /// Get the data directory for JSON file migration only.
 ///
 /// Priority:
@@ -51,6 +82,37 @@ pub fn get_db_path() -> PathBuf {
     PathBuf::from("./data/chesstty.db")
 }
 
+/// Where the passphrase for an encrypted (SQLCipher) database comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DbKeySource {
+    /// The database is stored unencrypted.
+    Unencrypted,
+    /// Read the passphrase from a key file.
+    KeyFile(PathBuf),
+    /// Prompt for the passphrase on the terminal at server start.
+    Prompt,
+}
+
+/// Get the key source used to unlock the SQLite database.
+///
+/// The key is tied to the database file, so each `CHESSTTY_DB_PATH` profile
+/// can use its own key file (or none at all).
+///
+/// Priority:
+/// 1. CHESSTTY_DB_KEY_FILE env variable if set
+/// 2. CHESSTTY_DB_ENCRYPTION=prompt
+/// 3. Unencrypted as fallback
+pub fn get_db_key_source() -> DbKeySource {
+    if let Ok(path) = std::env::var("CHESSTTY_DB_KEY_FILE") {
+        return DbKeySource::KeyFile(PathBuf::from(path));
+    }
+
+    match std::env::var("CHESSTTY_DB_ENCRYPTION") {
+        Ok(mode) if mode.eq_ignore_ascii_case("prompt") => DbKeySource::Prompt,
+        _ => DbKeySource::Unencrypted,
+    }
+}
+
 /// Get the Unix Domain Socket path for server communication.
 ///
 /// Priority:
@@ -64,6 +126,400 @@ pub fn get_socket_path() -> PathBuf {
     PathBuf::from(DEFAULT_SOCKET_PATH)
 }
 
+/// Get the TCP address to serve the chess service on, besides the Unix
+/// socket, so a TUI on another machine can connect.
+///
+/// `CHESSTTY_LISTEN_TCP` (e.g. `0.0.0.0:50051`); off when unset or invalid.
+/// Clients are held to the `get_tcp_guard_limits()` limits.
+pub fn get_listen_tcp_addr() -> Option<SocketAddr> {
+    let value = std::env::var("CHESSTTY_LISTEN_TCP").ok()?;
+    match value.trim().parse() {
+        Ok(addr) => Some(addr),
+        Err(e) => {
+            tracing::warn!("Ignoring invalid CHESSTTY_LISTEN_TCP {:?}: {}", value, e);
+            None
+        }
+    }
+}
+
+/// PEM files the TCP listener proves its identity with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TlsFiles {
+    pub cert: PathBuf,
+    pub key: PathBuf,
+}
+
+/// Get the certificate and key to serve TCP clients and sync peers over TLS
+/// with.
+///
+/// `CHESSTTY_TLS_CERT` (certificate chain) and `CHESSTTY_TLS_KEY` (private
+/// key), both PEM. When set, the TCP and sync listeners only accept TLS;
+/// setting just one of them is ignored with a warning.
+pub fn get_tls_files() -> Option<TlsFiles> {
+    parse_tls_files(
+        std::env::var_os("CHESSTTY_TLS_CERT").map(PathBuf::from),
+        std::env::var_os("CHESSTTY_TLS_KEY").map(PathBuf::from),
+    )
+}
+
+fn parse_tls_files(cert: Option<PathBuf>, key: Option<PathBuf>) -> Option<TlsFiles> {
+    match (cert, key) {
+        (Some(cert), Some(key)) => Some(TlsFiles { cert, key }),
+        (None, None) => None,
+        _ => {
+            tracing::warn!(
+                "CHESSTTY_TLS_CERT and CHESSTTY_TLS_KEY must be set together; not using TLS"
+            );
+            None
+        }
+    }
+}
+
+/// Get the default minimum delay between engine moves for new sessions.
+///
+/// Priority:
+/// 1. CHESSTTY_ENGINE_MOVE_DELAY_MS env variable if set and valid
+/// 2. 500ms fallback
+///
+/// The value is capped at `MAX_ENGINE_MOVE_DELAY_MS`.
+pub fn get_engine_move_delay_ms() -> u64 {
+    parse_engine_move_delay(
+        std::env::var("CHESSTTY_ENGINE_MOVE_DELAY_MS")
+            .ok()
+            .as_deref(),
+    )
+}
+
+fn parse_engine_move_delay(value: Option<&str>) -> u64 {
+    value
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .unwrap_or(DEFAULT_ENGINE_MOVE_DELAY_MS)
+        .min(MAX_ENGINE_MOVE_DELAY_MS)
+}
+
+/// Get the caps on engines run by sessions.
+///
+/// `CHESSTTY_MAX_ENGINE_SESSIONS` limits how many sessions may run an engine
+/// at once and `CHESSTTY_MAX_ENGINE_HASH_MB` the hash all engines may hold
+/// together. Either is unlimited when unset, zero or invalid.
+pub fn get_engine_limits() -> EngineLimits {
+    EngineLimits {
+        max_engine_sessions: parse_limit(
+            std::env::var("CHESSTTY_MAX_ENGINE_SESSIONS")
+                .ok()
+                .as_deref(),
+        ),
+        max_total_hash_mb: parse_limit(
+            std::env::var("CHESSTTY_MAX_ENGINE_HASH_MB").ok().as_deref(),
+        ),
+    }
+}
+
+/// Default number of engines kept for `AnalyzePosition`.
+const DEFAULT_ANALYSIS_ENGINES: usize = 2;
+
+/// Get how many engines `AnalyzePosition` may run at once.
+///
+/// `CHESSTTY_ANALYSIS_ENGINES`; 2 when unset, zero or invalid. These engines
+/// are owned by the server and not counted against the session caps.
+pub fn get_analysis_engines() -> usize {
+    parse_analysis_engines(std::env::var("CHESSTTY_ANALYSIS_ENGINES").ok().as_deref())
+}
+
+fn parse_analysis_engines(value: Option<&str>) -> usize {
+    value
+        .and_then(|v| v.trim().parse::<usize>().ok())
+        .filter(|&count| count > 0)
+        .unwrap_or(DEFAULT_ANALYSIS_ENGINES)
+}
+
+fn parse_limit(value: Option<&str>) -> Option<u32> {
+    value
+        .and_then(|v| v.trim().parse::<u32>().ok())
+        .filter(|&limit| limit > 0)
+}
+
+/// Get the abuse protection limits for TCP listeners.
+///
+/// Each `CHESSTTY_TCP_*` variable overrides one default; `0` lifts the limit:
+/// - `CHESSTTY_TCP_REQUESTS_PER_SEC` (50), `CHESSTTY_TCP_MOVES_PER_SEC` (5) and
+///   `CHESSTTY_TCP_SESSIONS_PER_MIN` (20), per client
+/// - `CHESSTTY_TCP_MAX_CONNECTIONS` (64) in total and
+///   `CHESSTTY_TCP_MAX_CONNECTIONS_PER_CLIENT` (8)
+/// - `CHESSTTY_TCP_BANNED`, a comma-separated list of addresses and networks
+pub fn get_tcp_guard_limits() -> GuardLimits {
+    let var = |name: &str| std::env::var(name).ok();
+    GuardLimits {
+        requests_per_sec: parse_tcp_limit(var("CHESSTTY_TCP_REQUESTS_PER_SEC").as_deref(), 50),
+        moves_per_sec: parse_tcp_limit(var("CHESSTTY_TCP_MOVES_PER_SEC").as_deref(), 5),
+        sessions_per_min: parse_tcp_limit(var("CHESSTTY_TCP_SESSIONS_PER_MIN").as_deref(), 20),
+        max_connections: parse_tcp_limit(var("CHESSTTY_TCP_MAX_CONNECTIONS").as_deref(), 64),
+        max_connections_per_client: parse_tcp_limit(
+            var("CHESSTTY_TCP_MAX_CONNECTIONS_PER_CLIENT").as_deref(),
+            8,
+        ),
+        banned: parse_ban_list(var("CHESSTTY_TCP_BANNED").as_deref()),
+    }
+}
+
+fn parse_tcp_limit(value: Option<&str>, default: u32) -> Option<u32> {
+    match value.map(str::trim) {
+        None | Some("") => Some(default),
+        Some(v) => match v.parse::<u32>() {
+            Ok(0) => None,
+            Ok(limit) => Some(limit),
+            Err(_) => {
+                tracing::warn!("Ignoring invalid TCP limit {:?}, using {}", v, default);
+                Some(default)
+            }
+        },
+    }
+}
+
+fn parse_ban_list(value: Option<&str>) -> Vec<BanRule> {
+    value
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .filter_map(|entry| {
+            let rule = BanRule::parse(entry);
+            if rule.is_none() {
+                tracing::warn!("Ignoring invalid ban entry {:?}", entry);
+            }
+            rule
+        })
+        .collect()
+}
+
+/// Port sync peers listen on when an address leaves it out.
+pub const DEFAULT_SYNC_PORT: u16 = 50151;
+
+/// Get the TCP address to accept sync peers on.
+///
+/// `CHESSTTY_SYNC_LISTEN` (e.g. `0.0.0.0:50151`); sync listening is off when
+/// unset or invalid.
+pub fn get_sync_listen_addr() -> Option<SocketAddr> {
+    let value = std::env::var("CHESSTTY_SYNC_LISTEN").ok()?;
+    match value.trim().parse() {
+        Ok(addr) => Some(addr),
+        Err(e) => {
+            tracing::warn!("Ignoring invalid CHESSTTY_SYNC_LISTEN {:?}: {}", value, e);
+            None
+        }
+    }
+}
+
+/// Get the shared secret sync peers authenticate with.
+///
+/// `CHESSTTY_SYNC_TOKEN`; both servers must use the same value.
+pub fn get_sync_token() -> Option<String> {
+    std::env::var("CHESSTTY_SYNC_TOKEN")
+        .ok()
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+}
+
+/// Get the CA bundle `https://` sync peers are verified against.
+///
+/// `CHESSTTY_SYNC_CA`, a PEM file, e.g. for peers with self-signed
+/// certificates; the system roots are used when unset.
+pub fn get_sync_ca() -> Option<PathBuf> {
+    std::env::var_os("CHESSTTY_SYNC_CA")
+        .map(PathBuf::from)
+        .filter(|p| !p.as_os_str().is_empty())
+}
+
+/// Get the peers to sync with on a schedule.
+///
+/// `CHESSTTY_SYNC_PEERS`, a comma-separated list of addresses.
+pub fn get_sync_peers() -> Vec<String> {
+    parse_sync_peers(std::env::var("CHESSTTY_SYNC_PEERS").ok().as_deref())
+}
+
+fn parse_sync_peers(value: Option<&str>) -> Vec<String> {
+    value
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Get the interval between scheduled syncs.
+///
+/// `CHESSTTY_SYNC_INTERVAL_SECS`; scheduled sync is off when unset or 0.
+pub fn get_sync_interval_secs() -> Option<u64> {
+    parse_sync_interval(std::env::var("CHESSTTY_SYNC_INTERVAL_SECS").ok().as_deref())
+}
+
+fn parse_sync_interval(value: Option<&str>) -> Option<u64> {
+    value
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .filter(|&secs| secs > 0)
+}
+
+/// Default time between scans of the PGN watch folder.
+const DEFAULT_IMPORT_INTERVAL_SECS: u64 = 5;
+
+/// Get the folder watched for PGN files to import.
+///
+/// `CHESSTTY_IMPORT_DIR`; importing is off when unset.
+pub fn get_import_dir() -> Option<PathBuf> {
+    std::env::var("CHESSTTY_IMPORT_DIR")
+        .ok()
+        .map(|d| d.trim().to_string())
+        .filter(|d| !d.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Get the time between scans of the PGN watch folder.
+///
+/// `CHESSTTY_IMPORT_INTERVAL_SECS`; 5 seconds when unset, zero or invalid.
+pub fn get_import_interval_secs() -> u64 {
+    parse_sync_interval(
+        std::env::var("CHESSTTY_IMPORT_INTERVAL_SECS")
+            .ok()
+            .as_deref(),
+    )
+    .unwrap_or(DEFAULT_IMPORT_INTERVAL_SECS)
+}
+
+/// Get whether imported games are queued for review.
+///
+/// `CHESSTTY_IMPORT_REVIEW` set to `1`, `true` or `on`; off otherwise.
+pub fn get_import_review() -> bool {
+    parse_flag(std::env::var("CHESSTTY_IMPORT_REVIEW").ok().as_deref())
+}
+
+/// Get when the server runs in low-power mode.
+///
+/// Priority:
+/// 1. The `--low-power` command line flag: always
+/// 2. CHESSTTY_LOW_POWER set to `auto` (while on battery), or `1`, `true` or
+///    `on` (always)
+/// 3. Never as fallback
+pub fn get_power_policy() -> PowerPolicy {
+    if std::env::args().skip(1).any(|arg| arg == "--low-power") {
+        return PowerPolicy::On;
+    }
+    parse_power_policy(std::env::var("CHESSTTY_LOW_POWER").ok().as_deref())
+}
+
+fn parse_power_policy(value: Option<&str>) -> PowerPolicy {
+    if value.is_some_and(|v| v.trim().eq_ignore_ascii_case("auto")) {
+        PowerPolicy::Auto
+    } else if parse_flag(value) {
+        PowerPolicy::On
+    } else {
+        PowerPolicy::Off
+    }
+}
+
+fn parse_flag(value: Option<&str>) -> bool {
+    value.is_some_and(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "on"))
+}
+
+/// Get the free space below which imports and reviews warn or are refused.
+///
+/// `CHESSTTY_DISK_WARN_MB` (1024) and `CHESSTTY_DISK_MIN_MB` (256); `0` turns
+/// a check off, and the warning threshold is never below the minimum.
+pub fn get_disk_thresholds() -> DiskThresholds {
+    parse_disk_thresholds(
+        std::env::var("CHESSTTY_DISK_WARN_MB").ok().as_deref(),
+        std::env::var("CHESSTTY_DISK_MIN_MB").ok().as_deref(),
+    )
+}
+
+fn parse_disk_thresholds(warn: Option<&str>, min: Option<&str>) -> DiskThresholds {
+    let parse = |value: Option<&str>, default: u64| {
+        value
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .unwrap_or(default)
+    };
+    let min_mb = parse(min, DiskThresholds::default().min_mb);
+    let warn_mb = parse(warn, DiskThresholds::default().warn_mb);
+    DiskThresholds {
+        warn_mb: warn_mb.max(min_mb),
+        min_mb,
+    }
+}
+
+/// Get the file the server's output is written to, for its size in the
+/// server info.
+///
+/// `CHESSTTY_SERVER_LOG_PATH`, as the `chesstty` shim redirects it; `None`
+/// when unset or `/dev/null`.
+pub fn get_server_log_path() -> Option<PathBuf> {
+    std::env::var("CHESSTTY_SERVER_LOG_PATH")
+        .ok()
+        .map(PathBuf::from)
+        .filter(|p| !p.as_os_str().is_empty() && p.as_path() != Path::new("/dev/null"))
+}
+
+/// Get the directories holding Syzygy tables for reviews.
+///
+/// `CHESSTTY_SYZYGY_PATH`, several separated like `PATH`; no tablebase
+/// probing when unset.
+pub fn get_syzygy_paths() -> Vec<PathBuf> {
+    std::env::var_os("CHESSTTY_SYZYGY_PATH")
+        .map(|paths| {
+            std::env::split_paths(&paths)
+                .filter(|p| !p.as_os_str().is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Get the directory opening books are loaded from.
+///
+/// `CHESSTTY_BOOKS_DIR`, or a `books` directory next to the database; clients
+/// name a book in it and can't reach files elsewhere.
+pub fn get_books_dir() -> PathBuf {
+    if let Ok(dir) = std::env::var("CHESSTTY_BOOKS_DIR") {
+        return PathBuf::from(dir);
+    }
+
+    get_db_path()
+        .parent()
+        .map(|dir| dir.join("books"))
+        .unwrap_or_else(|| PathBuf::from("./data/books"))
+}
+
+/// Get the moves reviews leave out of the accuracy.
+///
+/// `CHESSTTY_ACCURACY_EXCLUDE`, a comma-separated list of `book`, `forced`
+/// and `decided` or `decided=<cp>` (800 when no threshold is given); unknown
+/// entries are ignored and every move counts when unset.
+pub fn get_accuracy_exclusions() -> AccuracyExclusions {
+    parse_accuracy_exclusions(std::env::var("CHESSTTY_ACCURACY_EXCLUDE").ok().as_deref())
+}
+
+fn parse_accuracy_exclusions(value: Option<&str>) -> AccuracyExclusions {
+    let mut exclusions = AccuracyExclusions::default();
+    for entry in value.unwrap_or_default().split(',').map(str::trim) {
+        let (name, threshold) = match entry.split_once('=') {
+            Some((name, threshold)) => (name.trim(), Some(threshold.trim())),
+            None => (entry, None),
+        };
+        match name.to_lowercase().as_str() {
+            "book" => exclusions.book = true,
+            "forced" => exclusions.forced = true,
+            "decided" => {
+                exclusions.decided_cp = Some(
+                    threshold
+                        .and_then(|t| t.parse::<i32>().ok())
+                        .filter(|cp| *cp > 0)
+                        .unwrap_or(DEFAULT_DECIDED_CP),
+                )
+            }
+            _ => {}
+        }
+    }
+    exclusions
+}
+
 /// Get the directory containing default positions (version controlled).
 ///
 /// This is always relative to the server binary location, not configurable.
@@ -92,6 +548,152 @@ mod tests {
         assert!(path.to_string_lossy().ends_with("chesstty.db"));
     }
 
+    #[test]
+    fn test_get_db_key_source_default() {
+        if std::env::var("CHESSTTY_DB_KEY_FILE").is_err()
+            && std::env::var("CHESSTTY_DB_ENCRYPTION").is_err()
+        {
+            assert_eq!(get_db_key_source(), DbKeySource::Unencrypted);
+        }
+    }
+
+    #[test]
+    fn test_parse_engine_move_delay() {
+        assert_eq!(parse_engine_move_delay(None), DEFAULT_ENGINE_MOVE_DELAY_MS);
+        assert_eq!(parse_engine_move_delay(Some("0")), 0);
+        assert_eq!(parse_engine_move_delay(Some(" 1200 ")), 1200);
+        assert_eq!(
+            parse_engine_move_delay(Some("fast")),
+            DEFAULT_ENGINE_MOVE_DELAY_MS
+        );
+        assert_eq!(
+            parse_engine_move_delay(Some("999999")),
+            MAX_ENGINE_MOVE_DELAY_MS
+        );
+    }
+
+    #[test]
+    fn test_parse_limit() {
+        assert_eq!(parse_limit(None), None);
+        assert_eq!(parse_limit(Some(" 4 ")), Some(4));
+        assert_eq!(parse_limit(Some("0")), None);
+        assert_eq!(parse_limit(Some("lots")), None);
+    }
+
+    #[test]
+    fn test_parse_analysis_engines() {
+        assert_eq!(parse_analysis_engines(None), DEFAULT_ANALYSIS_ENGINES);
+        assert_eq!(parse_analysis_engines(Some(" 4 ")), 4);
+        assert_eq!(parse_analysis_engines(Some("0")), DEFAULT_ANALYSIS_ENGINES);
+    }
+
+    #[test]
+    fn test_parse_tls_files() {
+        let cert = PathBuf::from("/etc/chesstty/cert.pem");
+        let key = PathBuf::from("/etc/chesstty/key.pem");
+        assert_eq!(
+            parse_tls_files(Some(cert.clone()), Some(key.clone())),
+            Some(TlsFiles {
+                cert: cert.clone(),
+                key
+            })
+        );
+        assert_eq!(parse_tls_files(Some(cert), None), None);
+        assert_eq!(parse_tls_files(None, None), None);
+    }
+
+    #[test]
+    fn test_parse_tcp_limit() {
+        assert_eq!(parse_tcp_limit(None, 5), Some(5));
+        assert_eq!(parse_tcp_limit(Some("12"), 5), Some(12));
+        assert_eq!(parse_tcp_limit(Some("0"), 5), None);
+        assert_eq!(parse_tcp_limit(Some("many"), 5), Some(5));
+    }
+
+    #[test]
+    fn test_parse_ban_list() {
+        let rules = parse_ban_list(Some("203.0.113.7, 10.0.0.0/8,,bogus"));
+        assert_eq!(rules.len(), 2);
+        assert!(rules[1].matches("10.20.30.40".parse().unwrap()));
+        assert!(parse_ban_list(None).is_empty());
+    }
+
+    #[test]
+    fn test_parse_sync_peers() {
+        assert!(parse_sync_peers(None).is_empty());
+        assert_eq!(
+            parse_sync_peers(Some(" desktop.local , ,10.0.0.2:50151")),
+            vec!["desktop.local", "10.0.0.2:50151"]
+        );
+    }
+
+    #[test]
+    fn test_parse_sync_interval() {
+        assert_eq!(parse_sync_interval(None), None);
+        assert_eq!(parse_sync_interval(Some("0")), None);
+        assert_eq!(parse_sync_interval(Some("hourly")), None);
+        assert_eq!(parse_sync_interval(Some(" 3600 ")), Some(3600));
+    }
+
+    #[test]
+    fn test_parse_power_policy() {
+        assert_eq!(parse_power_policy(None), PowerPolicy::Off);
+        assert_eq!(parse_power_policy(Some(" Auto ")), PowerPolicy::Auto);
+        assert_eq!(parse_power_policy(Some("on")), PowerPolicy::On);
+        assert_eq!(parse_power_policy(Some("battery")), PowerPolicy::Off);
+    }
+
+    #[test]
+    fn test_parse_disk_thresholds() {
+        assert_eq!(parse_disk_thresholds(None, None), DiskThresholds::default());
+        assert_eq!(
+            parse_disk_thresholds(Some("0"), Some(" 512 ")),
+            DiskThresholds {
+                warn_mb: 512,
+                min_mb: 512
+            }
+        );
+        assert_eq!(
+            parse_disk_thresholds(Some("lots"), Some("0")),
+            DiskThresholds {
+                warn_mb: 1024,
+                min_mb: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_accuracy_exclusions() {
+        assert_eq!(
+            parse_accuracy_exclusions(None),
+            AccuracyExclusions::default()
+        );
+        assert_eq!(
+            parse_accuracy_exclusions(Some("Book, decided")),
+            AccuracyExclusions {
+                book: true,
+                forced: false,
+                decided_cp: Some(DEFAULT_DECIDED_CP),
+            }
+        );
+        assert_eq!(
+            parse_accuracy_exclusions(Some("forced,decided=600,openings")),
+            AccuracyExclusions {
+                book: false,
+                forced: true,
+                decided_cp: Some(600),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_flag() {
+        assert!(!parse_flag(None));
+        assert!(parse_flag(Some(" ON ")));
+        assert!(parse_flag(Some("1")));
+        assert!(!parse_flag(Some("no")));
+    }
+
     #[test]
     fn test_get_defaults_dir() {
         let dir = get_defaults_dir();
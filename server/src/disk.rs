@@ -0,0 +1,211 @@
+//! Disk-space guard.
+//!
+//! Imports and reviews write to the database, so before one starts the
+//! server checks the space left on the disk holding it: below the warning
+//! threshold it warns, below the minimum it refuses, so a full disk never
+//! leaves the database half-written. The free space is read with `df`, and
+//! reported with the database and log sizes by `GetServerInfo`.
+
+use std::path::{Path, PathBuf};
+
+/// Free space, in MB, below which imports and reviews warn.
+pub const DEFAULT_DISK_WARN_MB: u64 = 1024;
+
+/// Free space, in MB, below which imports and reviews are refused.
+pub const DEFAULT_DISK_MIN_MB: u64 = 256;
+
+const MB: u64 = 1024 * 1024;
+
+/// Free space thresholds, in MB; 0 turns a check off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiskThresholds {
+    pub warn_mb: u64,
+    pub min_mb: u64,
+}
+
+impl Default for DiskThresholds {
+    fn default() -> Self {
+        Self {
+            warn_mb: DEFAULT_DISK_WARN_MB,
+            min_mb: DEFAULT_DISK_MIN_MB,
+        }
+    }
+}
+
+/// How the free space compares with the thresholds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiskSpace {
+    /// Above the warning threshold, or the free space is unknown.
+    Ok,
+    /// Under the warning threshold, with the free bytes.
+    Low(u64),
+    /// Under the minimum, with the free bytes.
+    Full(u64),
+}
+
+impl DiskThresholds {
+    pub fn classify(&self, free_bytes: u64) -> DiskSpace {
+        if free_bytes < self.min_mb * MB {
+            DiskSpace::Full(free_bytes)
+        } else if free_bytes < self.warn_mb * MB {
+            DiskSpace::Low(free_bytes)
+        } else {
+            DiskSpace::Ok
+        }
+    }
+}
+
+/// Sizes reported with the server info.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DiskUsage {
+    /// Space left on the disk holding the database, if `df` could tell.
+    pub free_bytes: Option<u64>,
+    /// The database with its WAL and shared-memory files.
+    pub db_bytes: u64,
+    /// The server log; 0 when it is not written to a file.
+    pub log_bytes: u64,
+    /// Free space is under the warning threshold.
+    pub low: bool,
+}
+
+pub struct DiskGuard {
+    db_path: PathBuf,
+    log_path: Option<PathBuf>,
+    thresholds: DiskThresholds,
+}
+
+impl DiskGuard {
+    pub fn new(db_path: PathBuf, log_path: Option<PathBuf>, thresholds: DiskThresholds) -> Self {
+        Self {
+            db_path,
+            log_path,
+            thresholds,
+        }
+    }
+
+    /// Space left on the disk holding the database.
+    pub async fn free_bytes(&self) -> Option<u64> {
+        let dir = match self.db_path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        let output = tokio::process::Command::new("df")
+            .arg("-Pk")
+            .arg(dir)
+            .output()
+            .await
+            .ok()?;
+        parse_df(&String::from_utf8_lossy(&output.stdout))
+    }
+
+    pub async fn check(&self) -> DiskSpace {
+        match self.free_bytes().await {
+            Some(free) => self.thresholds.classify(free),
+            None => DiskSpace::Ok,
+        }
+    }
+
+    /// Check before writing `what` to the database. Returns a warning when
+    /// space is low.
+    ///
+    /// # Errors
+    ///
+    /// Returns why `what` is refused when less than the minimum is free.
+    pub async fn ensure_room(&self, what: &str) -> Result<Option<String>, String> {
+        match self.check().await {
+            DiskSpace::Ok => Ok(None),
+            DiskSpace::Low(free) => {
+                let warning = format!("Low disk space: {} MB left", free / MB);
+                tracing::warn!(free_mb = free / MB, "{} with low disk space", what);
+                Ok(Some(warning))
+            }
+            DiskSpace::Full(free) => {
+                tracing::error!(free_mb = free / MB, "Refusing {}: disk nearly full", what);
+                Err(format!(
+                    "Refusing {}: only {} MB left on disk, {} MB needed",
+                    what,
+                    free / MB,
+                    self.thresholds.min_mb
+                ))
+            }
+        }
+    }
+
+    pub async fn usage(&self) -> DiskUsage {
+        let free_bytes = self.free_bytes().await;
+        let mut db_bytes = file_size(&self.db_path).await;
+        for suffix in ["-wal", "-shm"] {
+            let mut path = self.db_path.clone().into_os_string();
+            path.push(suffix);
+            db_bytes += file_size(Path::new(&path)).await;
+        }
+        let log_bytes = match &self.log_path {
+            Some(path) => file_size(path).await,
+            None => 0,
+        };
+        DiskUsage {
+            free_bytes,
+            db_bytes,
+            log_bytes,
+            low: free_bytes.is_some_and(|free| self.thresholds.classify(free) != DiskSpace::Ok),
+        }
+    }
+}
+
+async fn file_size(path: &Path) -> u64 {
+    tokio::fs::metadata(path)
+        .await
+        .map(|meta| meta.len())
+        .unwrap_or(0)
+}
+
+/// Available bytes from `df -Pk`: a header, then one line whose fourth
+/// column is the free space in 1024-byte blocks.
+fn parse_df(output: &str) -> Option<u64> {
+    let line = output.lines().nth(1)?;
+    let available: u64 = line.split_whitespace().nth(3)?.parse().ok()?;
+    Some(available * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_df() {
+        let output = "Filesystem     1024-blocks      Used Available Capacity Mounted on\n\
+                      /dev/nvme0n1p2   490617784 301234560 164383224      65% /\n";
+        assert_eq!(parse_df(output), Some(164383224 * 1024));
+        assert_eq!(parse_df(""), None);
+        assert_eq!(parse_df("Filesystem\n/dev/sda1 - - -\n"), None);
+    }
+
+    #[test]
+    fn test_thresholds_classify_free_space() {
+        let thresholds = DiskThresholds::default();
+        assert_eq!(thresholds.classify(2048 * MB), DiskSpace::Ok);
+        assert_eq!(thresholds.classify(512 * MB), DiskSpace::Low(512 * MB));
+        assert_eq!(thresholds.classify(100 * MB), DiskSpace::Full(100 * MB));
+
+        let off = DiskThresholds {
+            warn_mb: 0,
+            min_mb: 0,
+        };
+        assert_eq!(off.classify(0), DiskSpace::Ok);
+    }
+
+    #[tokio::test]
+    async fn test_usage_counts_database_files_and_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("chesstty.db");
+        std::fs::write(&db_path, [0u8; 100]).unwrap();
+        std::fs::write(dir.path().join("chesstty.db-wal"), [0u8; 20]).unwrap();
+        let log_path = dir.path().join("server.log");
+        std::fs::write(&log_path, [0u8; 7]).unwrap();
+
+        let guard = DiskGuard::new(db_path, Some(log_path), DiskThresholds::default());
+        let usage = guard.usage().await;
+        assert_eq!(usage.db_bytes, 120);
+        assert_eq!(usage.log_bytes, 7);
+    }
+}
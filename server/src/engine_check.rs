@@ -0,0 +1,66 @@
+//! Whether a Stockfish binary is installed.
+//!
+//! Checked at start and every [`ENGINE_CHECK_INTERVAL`] after, so Stockfish
+//! installed while the server runs is picked up without a restart. Without
+//! it background reviews are held rather than failed, and reviews that
+//! failed for want of an engine are queued again once it is found. Clients
+//! read the answer from `GetServerInfo` to grey out engine modes.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::watch;
+
+use crate::persistence::Persistence;
+use crate::review::ReviewManager;
+
+/// How often the engine binary is looked for again.
+pub const ENGINE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Look for Stockfish now and keep looking. The receiver holds the binary
+/// found, or `None` while there is none.
+pub fn spawn_engine_monitor<D: Persistence>(
+    review_manager: Arc<ReviewManager<D>>,
+) -> watch::Receiver<Option<PathBuf>> {
+    let initial = engine::find_stockfish_path();
+    match &initial {
+        Some(path) => tracing::info!(path = %path.display(), "Stockfish found"),
+        None => tracing::warn!(
+            "{}; engine modes and reviews wait for it",
+            engine::STOCKFISH_NOT_FOUND
+        ),
+    }
+    // Hold reviews before any recovered job reaches a worker
+    review_manager.set_engine_available(initial.is_some());
+    let (path_tx, path_rx) = watch::channel(initial.clone());
+
+    tokio::spawn(async move {
+        if initial.is_some() {
+            // Reviews that failed without an engine on an earlier run
+            review_manager.retry_engine_failures().await;
+        }
+        let mut interval = tokio::time::interval(ENGINE_CHECK_INTERVAL);
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            let path = tokio::task::spawn_blocking(engine::find_stockfish_path)
+                .await
+                .unwrap_or(None);
+            let found = path.is_some();
+            if found == path_tx.borrow().is_some() {
+                continue;
+            }
+            match &path {
+                Some(path) => tracing::info!(path = %path.display(), "Stockfish found"),
+                None => tracing::warn!("Stockfish is gone; engine modes and reviews wait for it"),
+            }
+            review_manager.set_engine_available(found);
+            path_tx.send_replace(path);
+            if found {
+                review_manager.retry_engine_failures().await;
+            }
+        }
+    });
+    path_rx
+}
@@ -0,0 +1,503 @@
+//! Server-owned Stockfish processes for analysing arbitrary positions.
+//!
+//! `AnalyzePosition` asks for engine lines without creating a game session,
+//! and `StreamAnalysis` follows them while an infinite search runs, holding
+//! its engine until the client cancels. Engines are spawned on first use, up
+//! to the configured count, and kept between requests; a request waits for a
+//! free engine for a while and then gives up. An engine that errors or
+//! closes mid-search is dropped and a new one is spawned for the next request.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use cozy_chess::{Board, GameStatus, Move};
+use engine::{
+    EngineCommand, EngineEvent, EngineInfo, GoParams, Score, StockfishConfig, StockfishEngine,
+};
+use tokio::sync::{mpsc, OwnedSemaphorePermit, Semaphore};
+
+/// Depth searched when a request sets neither depth nor movetime.
+pub const DEFAULT_ANALYSIS_DEPTH: u32 = 18;
+pub const MAX_ANALYSIS_DEPTH: u32 = 40;
+pub const MAX_ANALYSIS_MOVETIME_MS: u64 = 60_000;
+pub const MAX_ANALYSIS_LINES: u32 = 10;
+
+/// How long a request waits for a free engine.
+const CHECKOUT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long a cancelled stream waits for its engine to stop searching.
+const STOP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Updates buffered for a slow stream consumer; later ones are dropped until
+/// it catches up.
+const STREAM_BUFFER: usize = 64;
+
+/// Threads and hash of each pooled engine.
+const POOL_ENGINE_THREADS: u32 = 1;
+const POOL_ENGINE_HASH_MB: u32 = 64;
+
+#[derive(Debug, thiserror::Error)]
+pub enum PoolError {
+    #[error("Invalid FEN: {0}")]
+    InvalidFen(String),
+    #[error("All {0} analysis engines are busy")]
+    Busy(usize),
+    #[error("Analysis engine failed: {0}")]
+    Engine(String),
+}
+
+/// What to search: one of depth or movetime, or both (whichever ends first).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnalysisRequest {
+    pub fen: String,
+    pub depth: Option<u32>,
+    pub movetime_ms: Option<u64>,
+    pub lines: u32,
+}
+
+impl AnalysisRequest {
+    /// Clamp the limits to what the pool serves, falling back to
+    /// [`DEFAULT_ANALYSIS_DEPTH`] when no limit is set.
+    pub fn new(fen: String, depth: Option<u32>, movetime_ms: Option<u64>, lines: u32) -> Self {
+        let depth = depth.map(|d| d.clamp(1, MAX_ANALYSIS_DEPTH));
+        let movetime_ms = movetime_ms.map(|ms| ms.clamp(1, MAX_ANALYSIS_MOVETIME_MS));
+        Self {
+            fen,
+            depth: if depth.is_none() && movetime_ms.is_none() {
+                Some(DEFAULT_ANALYSIS_DEPTH)
+            } else {
+                depth
+            },
+            movetime_ms,
+            lines: lines.clamp(1, MAX_ANALYSIS_LINES),
+        }
+    }
+}
+
+/// One principal variation, scored from the side to move's view.
+#[derive(Debug, Clone)]
+pub struct AnalysisLine {
+    /// 1 for the best line.
+    pub multipv: u32,
+    pub depth: u32,
+    pub score: Score,
+    pub pv_uci: Vec<String>,
+    pub pv_san: Vec<String>,
+}
+
+/// A line from a running infinite search, with the search's progress.
+#[derive(Debug, Clone)]
+pub struct AnalysisUpdate {
+    pub line: AnalysisLine,
+    pub seldepth: Option<u32>,
+    pub nodes: Option<u64>,
+    pub nps: Option<u64>,
+    pub time_ms: Option<u64>,
+}
+
+/// The MultiPV index, depth, score and PV of an info line, if it reports an
+/// exact line. Bounded scores come from aspiration-window fails.
+fn exact_line(info: &EngineInfo) -> Option<(u32, u32, Score, &[Move])> {
+    if info.score_bound.is_some() || info.pv.is_empty() {
+        return None;
+    }
+    let score = info.score.clone()?;
+    let multipv = info.multipv.map_or(1, u32::from);
+    let depth = info.depth.map_or(0, u32::from);
+    Some((multipv, depth, score, &info.pv))
+}
+
+fn stream_update(board: &Board, info: &EngineInfo) -> Option<AnalysisUpdate> {
+    let (multipv, depth, score, pv) = exact_line(info)?;
+    Some(AnalysisUpdate {
+        line: AnalysisLine {
+            multipv,
+            depth,
+            score,
+            pv_uci: pv.iter().map(|&mv| chess::format_uci_move(mv)).collect(),
+            pv_san: pv_to_san(board, pv),
+        },
+        seldepth: info.seldepth.map(u32::from),
+        nodes: info.nodes,
+        nps: info.nps,
+        time_ms: info.time_ms,
+    })
+}
+
+/// The latest exact line per MultiPV index of one search.
+#[derive(Debug, Default)]
+struct LineCollector {
+    lines: BTreeMap<u32, (u32, Score, Vec<Move>)>,
+}
+
+impl LineCollector {
+    fn observe(&mut self, info: &EngineInfo) {
+        if let Some((multipv, depth, score, pv)) = exact_line(info) {
+            self.lines.insert(multipv, (depth, score, pv.to_vec()));
+        }
+    }
+
+    fn finish(self, board: &Board) -> Vec<AnalysisLine> {
+        self.lines
+            .into_iter()
+            .map(|(multipv, (depth, score, pv))| AnalysisLine {
+                multipv,
+                depth,
+                score,
+                pv_uci: pv.iter().map(|&mv| chess::format_uci_move(mv)).collect(),
+                pv_san: pv_to_san(board, &pv),
+            })
+            .collect()
+    }
+}
+
+/// The PV in SAN, up to the first move that is not legal where it is played.
+fn pv_to_san(board: &Board, pv: &[Move]) -> Vec<String> {
+    let mut board = board.clone();
+    let mut sans = Vec::with_capacity(pv.len());
+    for &mv in pv {
+        let mut legal = Vec::new();
+        board.generate_moves(|moves| {
+            legal.extend(moves);
+            false
+        });
+        let mv = chess::convert_uci_castling_to_cozy(mv, &legal);
+        if !legal.contains(&mv) {
+            break;
+        }
+        sans.push(chess::format_move_as_san(&board, mv));
+        board.play_unchecked(mv);
+    }
+    sans
+}
+
+/// A fixed number of engines shared by all `AnalyzePosition` and
+/// `StreamAnalysis` calls.
+pub struct EnginePool {
+    size: usize,
+    permits: Arc<Semaphore>,
+    idle: Mutex<Vec<StockfishEngine>>,
+}
+
+impl EnginePool {
+    pub fn new(size: usize) -> Self {
+        let size = size.max(1);
+        Self {
+            size,
+            permits: Arc::new(Semaphore::new(size)),
+            idle: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Wait for a free engine, spawning one if none is idle.
+    async fn checkout(&self) -> Result<(OwnedSemaphorePermit, StockfishEngine), PoolError> {
+        let permit = tokio::time::timeout(CHECKOUT_TIMEOUT, self.permits.clone().acquire_owned())
+            .await
+            .map_err(|_| PoolError::Busy(self.size))?
+            .map_err(|_| PoolError::Busy(self.size))?;
+        let idle = self.idle.lock().unwrap().pop();
+        let engine = match idle {
+            Some(engine) => engine,
+            None => StockfishEngine::spawn_with_config(StockfishConfig {
+                skill_level: None,
+                threads: Some(POOL_ENGINE_THREADS),
+                hash_mb: Some(POOL_ENGINE_HASH_MB),
+                label: Some("analysis-pool".to_string()),
+            })
+            .await
+            .map_err(PoolError::Engine)?,
+        };
+        Ok((permit, engine))
+    }
+
+    /// Keep an engine for the next request, or quit it after an error.
+    async fn checkin(&self, engine: StockfishEngine, result: &Result<(), String>) {
+        match result {
+            Ok(()) => self.idle.lock().unwrap().push(engine),
+            Err(e) => {
+                tracing::warn!("Dropping analysis engine after error: {}", e);
+                engine.shutdown().await;
+            }
+        }
+    }
+
+    /// Search `request.fen` and return its lines, best first. A position
+    /// where the game is over has no lines.
+    pub async fn analyze(&self, request: &AnalysisRequest) -> Result<Vec<AnalysisLine>, PoolError> {
+        let board: Board = request
+            .fen
+            .parse()
+            .map_err(|_| PoolError::InvalidFen(request.fen.clone()))?;
+        if board.status() != GameStatus::Ongoing {
+            return Ok(vec![]);
+        }
+
+        let (_permit, mut engine) = self.checkout().await?;
+        let result = search(&mut engine, &board, request).await;
+        let (lines, status) = match result {
+            Ok(lines) => (Ok(lines), Ok(())),
+            Err(e) => (Err(PoolError::Engine(e.clone())), Err(e)),
+        };
+        self.checkin(engine, &status).await;
+        lines
+    }
+
+    /// Search `fen` with `go infinite` until the returned receiver is
+    /// dropped, sending each exact line the engine reports. The engine stays
+    /// checked out meanwhile. A position where the game is over ends the
+    /// stream at once; an engine failure ends it with an error.
+    pub async fn stream(
+        self: &Arc<Self>,
+        fen: &str,
+        lines: u32,
+    ) -> Result<mpsc::Receiver<Result<AnalysisUpdate, PoolError>>, PoolError> {
+        let board: Board = fen
+            .parse()
+            .map_err(|_| PoolError::InvalidFen(fen.to_string()))?;
+        let (tx, rx) = mpsc::channel(STREAM_BUFFER);
+        if board.status() != GameStatus::Ongoing {
+            return Ok(rx);
+        }
+
+        let (permit, mut engine) = self.checkout().await?;
+        let pool = Arc::clone(self);
+        let fen = fen.to_string();
+        let lines = lines.clamp(1, MAX_ANALYSIS_LINES);
+        tokio::spawn(async move {
+            let result = stream_search(&mut engine, &board, &fen, lines, &tx).await;
+            if let Err(e) = &result {
+                let _ = tx.try_send(Err(PoolError::Engine(e.clone())));
+            }
+            pool.checkin(engine, &result).await;
+            drop(permit);
+        });
+        Ok(rx)
+    }
+
+    /// Quit the idle engines, e.g. at server shutdown.
+    pub async fn shutdown(&self) {
+        let engines: Vec<_> = self.idle.lock().unwrap().drain(..).collect();
+        for engine in engines {
+            engine.shutdown().await;
+        }
+    }
+}
+
+async fn search(
+    engine: &mut StockfishEngine,
+    board: &Board,
+    request: &AnalysisRequest,
+) -> Result<Vec<AnalysisLine>, String> {
+    // Output left over from the engine's previous search
+    while engine.try_recv_event().is_some() {}
+
+    engine
+        .send_command(EngineCommand::SetOption {
+            name: "MultiPV".to_string(),
+            value: Some(request.lines.to_string()),
+        })
+        .await?;
+    engine
+        .send_command(EngineCommand::SetPosition {
+            fen: request.fen.clone(),
+            moves: vec![],
+        })
+        .await?;
+    engine
+        .send_command(EngineCommand::Go(GoParams {
+            depth: request.depth.map(|d| d as u8),
+            movetime: request.movetime_ms,
+            infinite: false,
+        }))
+        .await?;
+
+    let mut collector = LineCollector::default();
+    loop {
+        match engine.recv_event().await {
+            Some(EngineEvent::Info(info)) => collector.observe(&info),
+            Some(EngineEvent::BestMove(_)) => return Ok(collector.finish(board)),
+            Some(EngineEvent::Error(e)) => return Err(e),
+            Some(_) => continue,
+            None => return Err("Engine channel closed during analysis".to_string()),
+        }
+    }
+}
+
+async fn stream_search(
+    engine: &mut StockfishEngine,
+    board: &Board,
+    fen: &str,
+    lines: u32,
+    tx: &mpsc::Sender<Result<AnalysisUpdate, PoolError>>,
+) -> Result<(), String> {
+    while engine.try_recv_event().is_some() {}
+
+    engine
+        .send_command(EngineCommand::SetOption {
+            name: "MultiPV".to_string(),
+            value: Some(lines.to_string()),
+        })
+        .await?;
+    engine
+        .send_command(EngineCommand::SetPosition {
+            fen: fen.to_string(),
+            moves: vec![],
+        })
+        .await?;
+    engine
+        .send_command(EngineCommand::Go(GoParams {
+            depth: None,
+            movetime: None,
+            infinite: true,
+        }))
+        .await?;
+
+    loop {
+        tokio::select! {
+            _ = tx.closed() => break,
+            event = engine.recv_event() => match event {
+                Some(EngineEvent::Info(info)) => {
+                    let Some(update) = stream_update(board, &info) else {
+                        continue;
+                    };
+                    match tx.try_send(Ok(update)) {
+                        Ok(()) | Err(mpsc::error::TrySendError::Full(_)) => {}
+                        Err(mpsc::error::TrySendError::Closed(_)) => break,
+                    }
+                }
+                // Only sent after `stop`, but the engine is idle either way
+                Some(EngineEvent::BestMove(_)) => return Ok(()),
+                Some(EngineEvent::Error(e)) => return Err(e),
+                Some(_) => continue,
+                None => return Err("Engine channel closed during analysis".to_string()),
+            },
+        }
+    }
+
+    // Wait for the search to end so the next request starts on an idle engine
+    engine.send_command(EngineCommand::Stop).await?;
+    tokio::time::timeout(STOP_TIMEOUT, async {
+        loop {
+            match engine.recv_event().await {
+                Some(EngineEvent::BestMove(_)) => return Ok(()),
+                Some(EngineEvent::Error(e)) => return Err(e),
+                Some(_) => continue,
+                None => return Err("Engine channel closed while stopping".to_string()),
+            }
+        }
+    })
+    .await
+    .map_err(|_| "Engine did not stop searching".to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const START: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+    fn info(multipv: u8, depth: u8, cp: i32, pv: &[&str]) -> EngineInfo {
+        EngineInfo {
+            multipv: Some(multipv),
+            depth: Some(depth),
+            score: Some(Score::Centipawns(cp)),
+            pv: pv.iter().map(|m| m.parse().unwrap()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_request_limits() {
+        let default = AnalysisRequest::new(START.to_string(), None, None, 0);
+        assert_eq!(default.depth, Some(DEFAULT_ANALYSIS_DEPTH));
+        assert_eq!(default.lines, 1);
+
+        let timed = AnalysisRequest::new(START.to_string(), None, Some(1_000_000), 50);
+        assert_eq!(timed.depth, None);
+        assert_eq!(timed.movetime_ms, Some(MAX_ANALYSIS_MOVETIME_MS));
+        assert_eq!(timed.lines, MAX_ANALYSIS_LINES);
+
+        let deep = AnalysisRequest::new(START.to_string(), Some(99), None, 3);
+        assert_eq!(deep.depth, Some(MAX_ANALYSIS_DEPTH));
+    }
+
+    #[test]
+    fn test_collector_keeps_latest_exact_line_per_index() {
+        let board: Board = START.parse().unwrap();
+        let mut collector = LineCollector::default();
+        collector.observe(&info(1, 10, 30, &["e2e4", "e7e5"]));
+        collector.observe(&info(2, 10, 20, &["d2d4"]));
+        collector.observe(&info(1, 11, 35, &["g1f3", "g8f6"]));
+        // A fail-high bound does not replace the exact line
+        collector.observe(&EngineInfo {
+            score_bound: Some(engine::ScoreBound::Lower),
+            ..info(2, 12, 90, &["c2c4"])
+        });
+
+        let lines = collector.finish(&board);
+        assert_eq!(lines.len(), 2);
+        assert_eq!((lines[0].multipv, lines[0].depth), (1, 11));
+        assert_eq!(lines[0].pv_uci, ["g1f3", "g8f6"]);
+        assert_eq!(lines[0].pv_san, ["Nf3", "Nf6"]);
+        assert_eq!(lines[1].pv_san, ["d4"]);
+        assert!(matches!(lines[1].score, Score::Centipawns(20)));
+    }
+
+    #[test]
+    fn test_pv_to_san_castles_and_stops_at_illegal_move() {
+        let board: Board = "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1".parse().unwrap();
+        let pv: Vec<Move> = ["e1g1", "e8c8", "h1h8"]
+            .iter()
+            .map(|m| m.parse().unwrap())
+            .collect();
+        assert_eq!(pv_to_san(&board, &pv), ["O-O", "O-O-O"]);
+    }
+
+    #[test]
+    fn test_stream_update_carries_progress() {
+        let board: Board = START.parse().unwrap();
+        let update = stream_update(
+            &board,
+            &EngineInfo {
+                seldepth: Some(24),
+                nodes: Some(1_500_000),
+                nps: Some(900_000),
+                ..info(2, 20, -15, &["d2d4", "g8f6"])
+            },
+        )
+        .unwrap();
+        assert_eq!((update.line.multipv, update.line.depth), (2, 20));
+        assert_eq!(update.line.pv_san, ["d4", "Nf6"]);
+        assert_eq!((update.seldepth, update.nps), (Some(24), Some(900_000)));
+
+        // currmove lines carry no PV
+        assert!(stream_update(&board, &EngineInfo::default()).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_stream_of_finished_position_ends_at_once() {
+        let pool = Arc::new(EnginePool::new(1));
+        let stalemate = "7k/5Q2/6K1/8/8/8/8/8 b - - 0 1";
+        let mut rx = pool.stream(stalemate, 1).await.unwrap();
+        assert!(rx.recv().await.is_none());
+        assert!(matches!(
+            pool.stream("not a fen", 1).await,
+            Err(PoolError::InvalidFen(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_finished_position_has_no_lines() {
+        let pool = EnginePool::new(1);
+        let mate = "rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3";
+        let request = AnalysisRequest::new(mate.to_string(), None, None, 1);
+        assert!(pool.analyze(&request).await.unwrap().is_empty());
+
+        let bad = AnalysisRequest::new("not a fen".to_string(), None, None, 1);
+        assert!(matches!(
+            pool.analyze(&bad).await,
+            Err(PoolError::InvalidFen(_))
+        ));
+    }
+}
@@ -0,0 +1,300 @@
+//! Export of reviewed positions as training data.
+//!
+//! Every analyzed ply of a completed review becomes one row: the position
+//! before the move, the engine's evaluation of it, the best and the played
+//! move in UCI, the move's classification and the tactical motifs on the
+//! board. `chesstty-server export-training <dir> [val_percent]` writes the
+//! rows to `train.csv` and `val.csv` in `dir`. Games are split as a whole by
+//! a hash of their id, so no game has positions on both sides and repeated
+//! exports of a growing library keep earlier games where they were.
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use analysis::advanced::types::AdvancedGameAnalysis;
+use analysis::board_analysis::TacticalTag;
+use analysis::{AnalysisScore, GameReview, ReviewStatus};
+
+use crate::persistence::{
+    AdvancedAnalysisRepository, FinishedGameData, FinishedGameRepository, PersistenceError,
+    ReviewRepository, StoredMoveRecord,
+};
+use crate::sync::content_hash;
+
+/// Share of games, in percent, written to the validation split by default.
+pub const DEFAULT_VAL_PERCENT: u8 = 10;
+
+const HEADER: &str =
+    "game_id,ply,fen,eval_cp,eval_mate,best_move,played_move,classification,tactical_tags";
+
+#[derive(Debug, thiserror::Error)]
+pub enum ExportError {
+    #[error(transparent)]
+    Persistence(#[from] PersistenceError),
+    #[error("Failed to write training data: {0}")]
+    Io(#[from] io::Error),
+}
+
+/// One analyzed ply.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrainingRow {
+    pub game_id: String,
+    pub ply: u32,
+    /// Position before the move.
+    pub fen: String,
+    /// Engine evaluation of `fen`, from White's point of view.
+    pub eval: AnalysisScore,
+    pub best_move: String,
+    pub played_move: String,
+    pub classification: String,
+    /// Tactical motifs present in `fen`, separated by `;`.
+    pub tactical_tags: String,
+}
+
+impl fmt::Display for TrainingRow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (cp, mate) = match self.eval {
+            AnalysisScore::Centipawns(cp) => (cp.to_string(), String::new()),
+            AnalysisScore::Mate(m) => (String::new(), m.to_string()),
+        };
+        write!(
+            f,
+            "{},{},{},{},{},{},{},{},{}",
+            csv_field(&self.game_id),
+            self.ply,
+            csv_field(&self.fen),
+            cp,
+            mate,
+            csv_field(&self.best_move),
+            csv_field(&self.played_move),
+            csv_field(&self.classification),
+            csv_field(&self.tactical_tags)
+        )
+    }
+}
+
+/// Quote `value` if it would otherwise break the CSV row.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn tag_names(tags: &[TacticalTag]) -> String {
+    tags.iter()
+        .map(|tag| format!("{:?}", tag.kind))
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+fn uci_move(mv: &StoredMoveRecord) -> String {
+    let promotion = mv.promotion.as_deref().unwrap_or_default();
+    format!("{}{}{}", mv.from, mv.to, promotion.to_ascii_lowercase())
+}
+
+/// Rows for every analyzed ply of `review` whose move is in `game`.
+pub fn training_rows(
+    game: &FinishedGameData,
+    review: &GameReview,
+    advanced: Option<&AdvancedGameAnalysis>,
+) -> Vec<TrainingRow> {
+    review
+        .positions
+        .iter()
+        .filter_map(|pos| {
+            let played = game.moves.get((pos.ply as usize).checked_sub(1)?)?;
+            let tags = advanced
+                .and_then(|a| a.positions.iter().find(|p| p.ply == pos.ply))
+                .map(|p| tag_names(&p.tactical_tags_before))
+                .unwrap_or_default();
+            Some(TrainingRow {
+                game_id: game.game_id.clone(),
+                ply: pos.ply,
+                fen: pos.fen.clone(),
+                eval: pos.eval_before.clone(),
+                best_move: pos.best_move_uci.clone(),
+                played_move: uci_move(played),
+                classification: format!("{:?}", pos.classification),
+                tactical_tags: tags,
+            })
+        })
+        .collect()
+}
+
+/// Whether `game_id` belongs to the validation split.
+pub fn is_validation(game_id: &str, val_percent: u8) -> bool {
+    content_hash(game_id.as_bytes()) % 100 < u64::from(val_percent)
+}
+
+/// Rows written to each split.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ExportSummary {
+    pub games: usize,
+    pub train_rows: usize,
+    pub val_rows: usize,
+}
+
+/// Export every game with a completed review to `train.csv` and `val.csv`
+/// in `dir`, creating it if needed.
+pub async fn run<G, R, A>(
+    games: &G,
+    reviews: &R,
+    analyses: &A,
+    dir: &Path,
+    val_percent: u8,
+) -> Result<ExportSummary, ExportError>
+where
+    G: FinishedGameRepository,
+    R: ReviewRepository,
+    A: AdvancedAnalysisRepository,
+{
+    std::fs::create_dir_all(dir)?;
+    let mut train = BufWriter::new(File::create(dir.join("train.csv"))?);
+    let mut val = BufWriter::new(File::create(dir.join("val.csv"))?);
+    writeln!(train, "{}", HEADER)?;
+    writeln!(val, "{}", HEADER)?;
+
+    let mut summary = ExportSummary::default();
+    for review in reviews.list_reviews().await? {
+        if review.status != ReviewStatus::Complete {
+            continue;
+        }
+        let Some(game) = games.load_game(&review.game_id).await? else {
+            continue;
+        };
+        let advanced = analyses.load_analysis(&review.game_id).await?;
+        let rows = training_rows(&game, &review, advanced.as_ref());
+        let (out, count) = if is_validation(&game.game_id, val_percent) {
+            (&mut val, &mut summary.val_rows)
+        } else {
+            (&mut train, &mut summary.train_rows)
+        };
+        for row in &rows {
+            writeln!(out, "{}", row)?;
+        }
+        *count += rows.len();
+        summary.games += 1;
+    }
+    train.flush()?;
+    val.flush()?;
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use analysis::board_analysis::{TacticalEvidence, TacticalTagKind};
+    use analysis::{MoveClassification, PositionReview};
+
+    fn mv(from: &str, to: &str, promotion: Option<&str>) -> StoredMoveRecord {
+        StoredMoveRecord {
+            from: from.to_string(),
+            to: to.to_string(),
+            piece: "P".to_string(),
+            promotion: promotion.map(str::to_string),
+            ..StoredMoveRecord::sample("")
+        }
+    }
+
+    fn position(ply: u32, eval: AnalysisScore) -> PositionReview {
+        PositionReview {
+            ply,
+            fen: format!("fen {}", ply),
+            played_san: String::new(),
+            best_move_san: String::new(),
+            best_move_uci: "d2d4".to_string(),
+            eval_before: eval.clone(),
+            eval_after: eval.clone(),
+            eval_best: eval,
+            classification: MoveClassification::Inaccuracy,
+            cp_loss: 40,
+            pv: vec![],
+            depth: 18,
+            clock_ms: None,
+            excluded: None,
+            only_move: false,
+        }
+    }
+
+    fn tag(kind: TacticalTagKind) -> TacticalTag {
+        TacticalTag {
+            kind,
+            attacker: None,
+            victims: vec![],
+            target_square: None,
+            confidence: 1.0,
+            note: None,
+            evidence: TacticalEvidence::default(),
+        }
+    }
+
+    #[test]
+    fn test_rows_pair_positions_with_played_moves() {
+        let game = FinishedGameData {
+            start_fen: String::new(),
+            result: "Draw".to_string(),
+            result_reason: String::new(),
+            game_mode: "HumanVsHuman".to_string(),
+            human_side: None,
+            skill_level: 0,
+            move_count: 2,
+            moves: vec![mv("e2", "e4", None), mv("a2", "a1", Some("Q"))],
+            ..FinishedGameData::sample("g")
+        };
+        let review = GameReview {
+            game_id: "g".to_string(),
+            status: ReviewStatus::Complete,
+            positions: vec![
+                position(1, AnalysisScore::Centipawns(25)),
+                position(2, AnalysisScore::Mate(-3)),
+                position(3, AnalysisScore::Centipawns(0)),
+            ],
+            white_accuracy: None,
+            black_accuracy: None,
+            total_plies: 3,
+            analyzed_plies: 3,
+            analysis_depth: 18,
+            started_at: None,
+            completed_at: None,
+            winner: None,
+            opening: None,
+            endgame: None,
+        };
+        let rows = training_rows(&game, &review, None);
+        assert_eq!(rows.len(), 2, "ply 3 has no recorded move");
+        assert_eq!(rows[0].played_move, "e2e4");
+        assert_eq!(rows[0].to_string(), "g,1,fen 1,25,,d2d4,e2e4,Inaccuracy,");
+        assert_eq!(rows[1].played_move, "a2a1q");
+        assert_eq!(rows[1].to_string(), "g,2,fen 2,,-3,d2d4,a2a1q,Inaccuracy,");
+    }
+
+    #[test]
+    fn test_tag_names_join_kinds() {
+        assert_eq!(tag_names(&[]), "");
+        assert_eq!(
+            tag_names(&[tag(TacticalTagKind::Fork), tag(TacticalTagKind::MateThreat)]),
+            "Fork;MateThreat"
+        );
+    }
+
+    #[test]
+    fn test_csv_field_quotes_separators() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn test_split_is_stable_and_proportional() {
+        let ids: Vec<String> = (0..1000).map(|i| format!("game_{}", i)).collect();
+        let val = ids.iter().filter(|id| is_validation(id, 10)).count();
+        assert!((50..150).contains(&val), "{} of 1000 in validation", val);
+        assert!(ids.iter().all(|id| !is_validation(id, 0)));
+        assert!(ids.iter().all(|id| is_validation(id, 100)));
+        assert_eq!(is_validation("game_7", 10), is_validation("game_7", 10));
+    }
+}
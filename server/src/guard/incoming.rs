@@ -0,0 +1,103 @@
+//! TCP accept loop that refuses banned clients and enforces connection caps.
+
+use std::io;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_stream::Stream;
+use tonic::transport::server::{Connected, TcpConnectInfo};
+
+use super::{ClientGuard, ConnectionPermit};
+
+/// Pause after a failed accept, e.g. when out of file descriptors.
+const ACCEPT_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// A TCP connection admitted by the guard. Its slot is given back when the
+/// connection closes.
+pub struct GuardedStream {
+    stream: TcpStream,
+    _permit: ConnectionPermit,
+}
+
+/// Accept connections on `addr`, closing those the guard refuses.
+pub async fn guarded_incoming(
+    addr: SocketAddr,
+    guard: Arc<ClientGuard>,
+) -> io::Result<impl Stream<Item = io::Result<GuardedStream>>> {
+    let listener = TcpListener::bind(addr).await?;
+    Ok(async_stream::stream! {
+        loop {
+            let (stream, peer) = match listener.accept().await {
+                Ok(accepted) => accepted,
+                Err(e) => {
+                    tracing::warn!("Failed to accept connection: {}", e);
+                    tokio::time::sleep(ACCEPT_RETRY_DELAY).await;
+                    continue;
+                }
+            };
+            match guard.connect(peer.ip()) {
+                Ok(permit) => {
+                    let _ = stream.set_nodelay(true);
+                    yield Ok(GuardedStream { stream, _permit: permit });
+                }
+                Err(reason) => {
+                    tracing::warn!(peer = %peer, "Refused connection: {}", reason);
+                    drop(stream);
+                }
+            }
+        }
+    })
+}
+
+impl Connected for GuardedStream {
+    type ConnectInfo = TcpConnectInfo;
+
+    fn connect_info(&self) -> Self::ConnectInfo {
+        self.stream.connect_info()
+    }
+}
+
+impl AsyncRead for GuardedStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for GuardedStream {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.stream).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_shutdown(cx)
+    }
+
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.stream).poll_write_vectored(cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.stream.is_write_vectored()
+    }
+}
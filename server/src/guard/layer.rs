@@ -0,0 +1,78 @@
+//! Tower layer rejecting requests from banned or rate-limited clients.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use http::{Request, Response};
+use tonic::body::BoxBody;
+use tonic::transport::server::{TcpConnectInfo, TlsConnectInfo};
+use tonic::Status;
+use tower_layer::Layer;
+use tower_service::Service;
+
+use super::ClientGuard;
+
+#[derive(Clone)]
+pub struct GuardLayer {
+    guard: Arc<ClientGuard>,
+}
+
+impl GuardLayer {
+    pub fn new(guard: Arc<ClientGuard>) -> Self {
+        Self { guard }
+    }
+}
+
+impl<S> Layer<S> for GuardLayer {
+    type Service = GuardService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        GuardService {
+            inner,
+            guard: Arc::clone(&self.guard),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct GuardService<S> {
+    inner: S,
+    guard: Arc<ClientGuard>,
+}
+
+impl<S, B> Service<Request<B>> for GuardService<S>
+where
+    S: Service<Request<B>, Response = Response<BoxBody>>,
+    S::Future: Send + 'static,
+{
+    type Response = Response<BoxBody>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<B>) -> Self::Future {
+        // Requests without a TCP peer (e.g. over the Unix socket) are not limited
+        let extensions = request.extensions();
+        let peer = extensions
+            .get::<TcpConnectInfo>()
+            .or_else(|| {
+                extensions
+                    .get::<TlsConnectInfo<TcpConnectInfo>>()
+                    .map(TlsConnectInfo::get_ref)
+            })
+            .and_then(TcpConnectInfo::remote_addr);
+        if let Some(peer) = peer {
+            if let Err(e) = self.guard.check_request(peer.ip(), request.uri().path()) {
+                tracing::debug!(peer = %peer, path = request.uri().path(), "Rejected request: {}", e);
+                let response = Status::from(e).into_http();
+                return Box::pin(async move { Ok(response) });
+            }
+        }
+        Box::pin(self.inner.call(request))
+    }
+}
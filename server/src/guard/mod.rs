@@ -0,0 +1,547 @@
+//! Abuse protection for TCP listeners.
+//!
+//! Clients are told apart by IP address, IPv6 clients by their /64 network
+//! since each one usually holds a whole one. Banned addresses are dropped on
+//! accept and each client may hold only so many connections
+//! ([`incoming::guarded_incoming`]). Requests on an accepted connection pass
+//! through [`layer::GuardLayer`], which rate-limits them per client with token
+//! buckets: one for all requests and tighter ones for moves and session
+//! creations. The Unix socket is not guarded; anyone who can reach it already
+//! has a shell on the machine.
+
+pub mod incoming;
+pub mod layer;
+
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv6Addr};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use tonic::Status;
+
+/// Buckets kept before idle, refilled ones are pruned. If that is not
+/// enough, the least recently used go until a quarter is free again.
+const MAX_TRACKED_BUCKETS: usize = 4096;
+
+/// Limits applied to clients of a TCP listener. `None` means unlimited.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GuardLimits {
+    /// Requests of any kind per second, per client.
+    pub requests_per_sec: Option<u32>,
+    /// `MakeMove` calls per second, per client.
+    pub moves_per_sec: Option<u32>,
+    /// `CreateSession` calls per minute, per client.
+    pub sessions_per_min: Option<u32>,
+    /// Open connections across all clients.
+    pub max_connections: Option<u32>,
+    /// Open connections per client.
+    pub max_connections_per_client: Option<u32>,
+    /// Addresses refused outright.
+    pub banned: Vec<BanRule>,
+}
+
+/// A banned address or network, e.g. `203.0.113.7` or `203.0.113.0/24`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BanRule {
+    network: IpAddr,
+    prefix_len: u8,
+}
+
+impl BanRule {
+    pub fn parse(s: &str) -> Option<Self> {
+        let (addr, prefix_len) = match s.trim().split_once('/') {
+            Some((addr, len)) => (addr.parse::<IpAddr>().ok()?, Some(len.parse::<u8>().ok()?)),
+            None => (s.trim().parse::<IpAddr>().ok()?, None),
+        };
+        let max_len = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix_len = prefix_len.unwrap_or(max_len);
+        if prefix_len > max_len {
+            return None;
+        }
+        Some(Self {
+            network: addr,
+            prefix_len,
+        })
+    }
+
+    pub fn matches(&self, ip: IpAddr) -> bool {
+        // IPv4 clients of a dual-stack listener show up as mapped IPv6 addresses
+        let ip = match ip {
+            IpAddr::V6(v6) => v6.to_ipv4_mapped().map_or(ip, IpAddr::V4),
+            ip => ip,
+        };
+        match (self.network, ip) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => prefix_eq(
+                u32::from(net).into(),
+                u32::from(ip).into(),
+                32,
+                self.prefix_len,
+            ),
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                prefix_eq(u128::from(net), u128::from(ip), 128, self.prefix_len)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// The client `ip` counts as: an IPv4 address, or an IPv6 /64 network.
+fn client_key(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+            Some(v4) => IpAddr::V4(v4),
+            None => IpAddr::V6(Ipv6Addr::from(u128::from(v6) & !u128::from(u64::MAX))),
+        },
+        ip => ip,
+    }
+}
+
+fn prefix_eq(a: u128, b: u128, bits: u8, prefix_len: u8) -> bool {
+    if prefix_len == 0 {
+        return true;
+    }
+    let shift = u32::from(bits - prefix_len);
+    (a >> shift) == (b >> shift)
+}
+
+/// Why a client's connection or request was refused.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum GuardError {
+    #[error("Address is banned")]
+    Banned,
+    #[error("Server connection limit ({0}) reached")]
+    ServerConnectionLimit(u32),
+    #[error("Client connection limit ({0}) reached")]
+    ClientConnectionLimit(u32),
+    #[error("Rate limit exceeded: at most {limit} {unit}")]
+    RateLimited { limit: u32, unit: &'static str },
+}
+
+impl From<GuardError> for Status {
+    fn from(e: GuardError) -> Self {
+        match e {
+            GuardError::Banned => Status::permission_denied(e.to_string()),
+            _ => Status::resource_exhausted(e.to_string()),
+        }
+    }
+}
+
+/// What a rate-limited request counts against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum RateKind {
+    Request,
+    Move,
+    SessionCreation,
+}
+
+/// Requests allowed per `per_secs`, refilled continuously, with bursts up to
+/// the full allowance.
+#[derive(Debug, Clone, Copy)]
+struct Rate {
+    allowance: u32,
+    per_secs: f64,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+    /// Last request from the client, refused or not. Unlike `updated`,
+    /// pruning leaves it alone.
+    last_seen: Instant,
+}
+
+impl Bucket {
+    fn full(rate: Rate, now: Instant) -> Self {
+        Self {
+            tokens: f64::from(rate.allowance),
+            updated: now,
+            last_seen: now,
+        }
+    }
+
+    /// Tokens the bucket would hold at `now`.
+    fn tokens_at(&self, rate: Rate, now: Instant) -> f64 {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        let capacity = f64::from(rate.allowance);
+        (self.tokens + elapsed * capacity / rate.per_secs).min(capacity)
+    }
+
+    fn refill(&mut self, rate: Rate, now: Instant) {
+        self.tokens = self.tokens_at(rate, now);
+        self.updated = now;
+    }
+
+    fn take(&mut self, rate: Rate, now: Instant) -> bool {
+        self.refill(rate, now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct GuardState {
+    buckets: HashMap<(IpAddr, RateKind), Bucket>,
+    connections: HashMap<IpAddr, u32>,
+    total_connections: u32,
+}
+
+/// Per-client rate limits, connection counts and bans, shared by a
+/// listener's accept loop and its request layer.
+#[derive(Debug)]
+pub struct ClientGuard {
+    limits: GuardLimits,
+    state: Mutex<GuardState>,
+}
+
+impl ClientGuard {
+    pub fn new(limits: GuardLimits) -> Arc<Self> {
+        Arc::new(Self {
+            limits,
+            state: Mutex::new(GuardState::default()),
+        })
+    }
+
+    pub fn is_banned(&self, ip: IpAddr) -> bool {
+        self.limits.banned.iter().any(|rule| rule.matches(ip))
+    }
+
+    /// Admit a new connection from `ip`, or refuse it with the reason.
+    /// The connection counts against the caps until the permit is dropped.
+    pub fn connect(self: &Arc<Self>, ip: IpAddr) -> Result<ConnectionPermit, GuardError> {
+        if self.is_banned(ip) {
+            return Err(GuardError::Banned);
+        }
+        let client = client_key(ip);
+        let mut state = self.state.lock().unwrap();
+        if let Some(max) = self.limits.max_connections {
+            if state.total_connections >= max {
+                return Err(GuardError::ServerConnectionLimit(max));
+            }
+        }
+        let open = state.connections.get(&client).copied().unwrap_or(0);
+        if let Some(max) = self.limits.max_connections_per_client {
+            if open >= max {
+                return Err(GuardError::ClientConnectionLimit(max));
+            }
+        }
+        state.connections.insert(client, open + 1);
+        state.total_connections += 1;
+        Ok(ConnectionPermit {
+            guard: Arc::clone(self),
+            client,
+        })
+    }
+
+    /// Check a request for the gRPC method at `path` against `ip`'s limits.
+    pub fn check_request(&self, ip: IpAddr, path: &str) -> Result<(), GuardError> {
+        if self.is_banned(ip) {
+            return Err(GuardError::Banned);
+        }
+
+        let method = path.rsplit('/').next().unwrap_or_default();
+        let mut limits = vec![(
+            RateKind::Request,
+            self.limits.requests_per_sec,
+            1.0,
+            "requests per second",
+        )];
+        match method {
+            "MakeMove" => limits.push((
+                RateKind::Move,
+                self.limits.moves_per_sec,
+                1.0,
+                "moves per second",
+            )),
+            "CreateSession" => limits.push((
+                RateKind::SessionCreation,
+                self.limits.sessions_per_min,
+                60.0,
+                "session creations per minute",
+            )),
+            _ => {}
+        }
+
+        let client = client_key(ip);
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap();
+        let limits: Vec<_> = limits
+            .into_iter()
+            .filter_map(|(kind, allowance, per_secs, unit)| {
+                let rate = Rate {
+                    allowance: allowance?,
+                    per_secs,
+                };
+                Some((kind, rate, unit))
+            })
+            .collect();
+        let new_buckets = limits
+            .iter()
+            .filter(|(kind, _, _)| !state.buckets.contains_key(&(client, *kind)))
+            .count();
+        if state.buckets.len() + new_buckets > MAX_TRACKED_BUCKETS {
+            self.prune(&mut state, now);
+        }
+
+        // Check every bucket before taking from any, so a request refused by
+        // one limit doesn't use up another
+        for &(kind, rate, unit) in &limits {
+            let bucket = state
+                .buckets
+                .entry((client, kind))
+                .or_insert_with(|| Bucket::full(rate, now));
+            bucket.refill(rate, now);
+            bucket.last_seen = now;
+            if bucket.tokens < 1.0 {
+                return Err(GuardError::RateLimited {
+                    limit: rate.allowance,
+                    unit,
+                });
+            }
+        }
+        for &(kind, rate, _) in &limits {
+            if let Some(bucket) = state.buckets.get_mut(&(client, kind)) {
+                bucket.take(rate, now);
+            }
+        }
+        Ok(())
+    }
+
+    /// Drop buckets that have refilled completely; they are recreated full.
+    /// Then, if too many are left, drop the least recently used.
+    fn prune(&self, state: &mut GuardState, now: Instant) {
+        let limits = &self.limits;
+        state.buckets.retain(|(_, kind), bucket| {
+            let (allowance, per_secs) = match kind {
+                RateKind::Request => (limits.requests_per_sec, 1.0),
+                RateKind::Move => (limits.moves_per_sec, 1.0),
+                RateKind::SessionCreation => (limits.sessions_per_min, 60.0),
+            };
+            let Some(allowance) = allowance else {
+                return false;
+            };
+            let rate = Rate {
+                allowance,
+                per_secs,
+            };
+            bucket.tokens_at(rate, now) < f64::from(allowance)
+        });
+
+        // Clients still draining their buckets: forget the least recently seen
+        if state.buckets.len() > MAX_TRACKED_BUCKETS * 3 / 4 {
+            let mut seen: Vec<_> = state
+                .buckets
+                .iter()
+                .map(|(key, bucket)| (bucket.last_seen, *key))
+                .collect();
+            let excess = seen.len() - MAX_TRACKED_BUCKETS * 3 / 4;
+            seen.select_nth_unstable_by_key(excess - 1, |(last_seen, _)| *last_seen);
+            for (_, key) in &seen[..excess] {
+                state.buckets.remove(key);
+            }
+        }
+    }
+
+    fn disconnect(&self, client: IpAddr) {
+        let mut state = self.state.lock().unwrap();
+        state.total_connections = state.total_connections.saturating_sub(1);
+        if let Some(open) = state.connections.get_mut(&client) {
+            *open -= 1;
+            if *open == 0 {
+                state.connections.remove(&client);
+            }
+        }
+    }
+}
+
+/// An admitted connection, counted against the caps until dropped.
+#[derive(Debug)]
+pub struct ConnectionPermit {
+    guard: Arc<ClientGuard>,
+    client: IpAddr,
+}
+
+impl Drop for ConnectionPermit {
+    fn drop(&mut self) {
+        self.guard.disconnect(self.client);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_ban_rules() {
+        let single = BanRule::parse("203.0.113.7").unwrap();
+        assert!(single.matches(ip("203.0.113.7")));
+        assert!(!single.matches(ip("203.0.113.8")));
+        assert!(single.matches(ip("::ffff:203.0.113.7")));
+
+        let net = BanRule::parse("10.1.0.0/16").unwrap();
+        assert!(net.matches(ip("10.1.200.3")));
+        assert!(!net.matches(ip("10.2.0.1")));
+
+        let v6 = BanRule::parse("2001:db8::/32").unwrap();
+        assert!(v6.matches(ip("2001:db8:1::1")));
+        assert!(!v6.matches(ip("10.1.0.1")));
+
+        assert!(BanRule::parse("0.0.0.0/0").unwrap().matches(ip("1.2.3.4")));
+        assert_eq!(BanRule::parse("10.0.0.0/33"), None);
+        assert_eq!(BanRule::parse("example.com"), None);
+    }
+
+    #[test]
+    fn test_rate_limits_per_client_and_method() {
+        let guard = ClientGuard::new(GuardLimits {
+            moves_per_sec: Some(2),
+            sessions_per_min: Some(1),
+            ..Default::default()
+        });
+        let a = ip("192.0.2.1");
+        let b = ip("192.0.2.2");
+
+        assert!(guard
+            .check_request(a, "/chess.ChessService/MakeMove")
+            .is_ok());
+        assert!(guard
+            .check_request(a, "/chess.ChessService/MakeMove")
+            .is_ok());
+        let err = guard
+            .check_request(a, "/chess.ChessService/MakeMove")
+            .unwrap_err();
+        assert_eq!(Status::from(err).code(), tonic::Code::ResourceExhausted);
+
+        // Other methods and other clients are unaffected
+        assert!(guard
+            .check_request(a, "/chess.ChessService/GetSession")
+            .is_ok());
+        assert!(guard
+            .check_request(b, "/chess.ChessService/MakeMove")
+            .is_ok());
+
+        assert!(guard
+            .check_request(a, "/chess.ChessService/CreateSession")
+            .is_ok());
+        assert!(guard
+            .check_request(a, "/chess.ChessService/CreateSession")
+            .is_err());
+    }
+
+    #[test]
+    fn test_bucket_refills() {
+        let rate = Rate {
+            allowance: 2,
+            per_secs: 1.0,
+        };
+        let start = Instant::now();
+        let mut bucket = Bucket::full(rate, start);
+        assert!(bucket.take(rate, start));
+        assert!(bucket.take(rate, start));
+        assert!(!bucket.take(rate, start));
+        assert!(bucket.take(rate, start + std::time::Duration::from_millis(500)));
+        assert!(!bucket.take(rate, start + std::time::Duration::from_millis(500)));
+    }
+
+    #[test]
+    fn test_connection_caps() {
+        let guard = ClientGuard::new(GuardLimits {
+            max_connections: Some(3),
+            max_connections_per_client: Some(2),
+            banned: vec![BanRule::parse("198.51.100.0/24").unwrap()],
+            ..Default::default()
+        });
+        let a = ip("192.0.2.1");
+
+        let first = guard.connect(a).unwrap();
+        let _second = guard.connect(a).unwrap();
+        assert!(guard.connect(a).is_err());
+        let _third = guard.connect(ip("192.0.2.2")).unwrap();
+        assert!(guard.connect(ip("192.0.2.3")).is_err());
+        assert!(guard.connect(ip("198.51.100.9")).is_err());
+
+        drop(first);
+        assert!(guard.connect(a).is_ok());
+    }
+
+    #[test]
+    fn test_ipv6_clients_share_their_64() {
+        let guard = ClientGuard::new(GuardLimits {
+            moves_per_sec: Some(1),
+            max_connections_per_client: Some(1),
+            ..Default::default()
+        });
+
+        assert!(guard
+            .check_request(ip("2001:db8:0:1::1"), "/chess.ChessService/MakeMove")
+            .is_ok());
+        assert!(guard
+            .check_request(ip("2001:db8:0:1::2"), "/chess.ChessService/MakeMove")
+            .is_err());
+        assert!(guard
+            .check_request(ip("2001:db8:0:2::1"), "/chess.ChessService/MakeMove")
+            .is_ok());
+
+        let _permit = guard.connect(ip("2001:db8:0:1::1")).unwrap();
+        assert!(guard.connect(ip("2001:db8:0:1:ffff::9")).is_err());
+        assert_eq!(client_key(ip("::ffff:192.0.2.1")), ip("192.0.2.1"));
+    }
+
+    #[test]
+    fn test_refused_request_keeps_other_tokens() {
+        let guard = ClientGuard::new(GuardLimits {
+            requests_per_sec: Some(2),
+            sessions_per_min: Some(1),
+            ..Default::default()
+        });
+        let a = ip("192.0.2.1");
+
+        assert!(guard
+            .check_request(a, "/chess.ChessService/CreateSession")
+            .is_ok());
+        // Refused by the session bucket, without spending a request token
+        assert!(guard
+            .check_request(a, "/chess.ChessService/CreateSession")
+            .is_err());
+        assert!(guard
+            .check_request(a, "/chess.ChessService/GetSession")
+            .is_ok());
+    }
+
+    #[test]
+    fn test_buckets_are_bounded() {
+        let guard = ClientGuard::new(GuardLimits {
+            requests_per_sec: Some(1),
+            ..Default::default()
+        });
+        let drained: IpAddr = "192.0.2.1".parse().unwrap();
+        assert!(guard
+            .check_request(drained, "/chess.ChessService/GetSession")
+            .is_ok());
+        for i in 0..(MAX_TRACKED_BUCKETS as u32 * 2) {
+            let client = IpAddr::V4(std::net::Ipv4Addr::from(0x0a00_0000 + i));
+            assert!(guard
+                .check_request(client, "/chess.ChessService/GetSession")
+                .is_ok());
+            // Still active, so pruning must not hand it a fresh bucket
+            if i % 256 == 0 {
+                assert!(guard
+                    .check_request(drained, "/chess.ChessService/GetSession")
+                    .is_err());
+            }
+        }
+        let buckets = guard.state.lock().unwrap().buckets.len();
+        assert!(buckets > MAX_TRACKED_BUCKETS / 2 && buckets <= MAX_TRACKED_BUCKETS);
+        assert!(guard
+            .check_request(drained, "/chess.ChessService/GetSession")
+            .is_err());
+    }
+}
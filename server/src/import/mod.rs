@@ -0,0 +1,481 @@
+//! Import of PGN games, from files dropped into a watch directory or sent
+//! with the `ImportPgn` RPC.
+//!
+//! The directory is polled; a `.pgn` file is imported once its size and
+//! modification time have stayed the same for one poll, so a file still
+//! being copied in is left alone. Every finished game in it is stored as a
+//! finished game, optionally queued for review, and the file is moved to
+//! the `archive` subfolder. Files without a single readable game go to
+//! `failed` instead, so they are not retried forever. While the disk is
+//! nearly full (see [`crate::disk`]) files wait in the folder.
+//!
+//! Imported games get an id derived from their tags and moves, so dropping
+//! the same game twice, or importing it on two synced servers, stores it
+//! once.
+//!
+//! A game keeps the result its PGN claims. When the replay shows another
+//! one, from the final position or a closing comment, it is stored
+//! alongside and the game is reported as a result mismatch.
+
+pub mod pgn;
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use crate::audit::{AuditLog, SERVER_ACTOR};
+use crate::disk::DiskGuard;
+use crate::persistence::{
+    self, AuditAction, FinishedGameData, FinishedGameRepository, Persistence,
+};
+use crate::review::ReviewManager;
+use crate::sync::content_hash;
+use chess::pgn::PgnGame;
+use chess::GameResult;
+use pgn::PgnError;
+
+/// `game_mode` of imported games.
+pub const IMPORTED_GAME_MODE: &str = "Imported";
+
+/// Subfolder imported files are moved to.
+pub const ARCHIVE_DIR: &str = "archive";
+
+/// Subfolder for files without a single importable game.
+pub const FAILED_DIR: &str = "failed";
+
+/// Tags that tell two games with the same moves apart.
+const IDENTITY_TAGS: [&str; 6] = ["Event", "Site", "Date", "Round", "White", "Black"];
+
+/// Counts from importing one file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImportReport {
+    pub imported: u32,
+    /// Games already in the library.
+    pub duplicates: u32,
+    /// Unfinished games and games that could not be replayed.
+    pub skipped: u32,
+    /// Imported games whose claimed result the game itself contradicts.
+    pub result_mismatches: u32,
+}
+
+impl ImportReport {
+    pub fn tally(games: &[GameImport]) -> Self {
+        let mut report = Self::default();
+        for game in games {
+            match game {
+                GameImport::Imported(_) => report.imported += 1,
+                GameImport::ResultMismatch { .. } => {
+                    report.imported += 1;
+                    report.result_mismatches += 1;
+                }
+                GameImport::Duplicate(_) => report.duplicates += 1,
+                GameImport::Skipped(_) => report.skipped += 1,
+            }
+        }
+        report
+    }
+}
+
+/// What became of one game of an import.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GameImport {
+    Imported(String),
+    /// Imported, though the game shows another result than it claims.
+    ResultMismatch {
+        game_id: String,
+        claimed: String,
+        derived: String,
+    },
+    /// Already in the library under this id.
+    Duplicate(String),
+    Skipped(PgnError),
+}
+
+/// Id of an imported game, stable across imports and servers.
+fn imported_game_id(game: &PgnGame, start_fen: &str) -> String {
+    let mut key = String::from(start_fen);
+    for name in IDENTITY_TAGS {
+        key.push('\n');
+        key.push_str(game.tag(name).unwrap_or_default());
+    }
+    for san in game.main_line() {
+        key.push('\n');
+        key.push_str(san);
+    }
+    format!("pgn_{:016x}", content_hash(key.as_bytes()))
+}
+
+/// How a result is stored.
+fn stored_result(result: &GameResult) -> &'static str {
+    match result {
+        GameResult::WhiteWins => "WhiteWins",
+        GameResult::BlackWins => "BlackWins",
+        GameResult::Draw => "Draw",
+    }
+}
+
+/// Turn a PGN game into a finished game, ready to store.
+fn to_finished_game(game: &PgnGame) -> Result<FinishedGameData, PgnError> {
+    let result = game.outcome().ok_or(PgnError::Unfinished)?;
+    let replayed = pgn::replay(game)?;
+    Ok(FinishedGameData {
+        game_id: imported_game_id(game, &replayed.start_fen),
+        start_fen: replayed.start_fen,
+        result: stored_result(&result).to_string(),
+        result_reason: game.tag("Termination").unwrap_or("Imported").to_string(),
+        game_mode: IMPORTED_GAME_MODE.to_string(),
+        human_side: None,
+        skill_level: 0,
+        move_count: replayed.moves.len() as u32,
+        moves: replayed.moves,
+        created_at: persistence::now_timestamp(),
+        simul_id: None,
+        opponent: None,
+        derived_result: replayed
+            .derived_result
+            .as_ref()
+            .map(|r| stored_result(r).to_string()),
+    })
+}
+
+/// Stores the games of PGN files and queues their reviews.
+pub struct PgnImporter<D: Persistence> {
+    finished_game_store: Arc<D::FinishedGames>,
+    /// Set when imported games are reviewed straight away.
+    review_manager: Option<Arc<ReviewManager<D>>>,
+    audit_log: Arc<AuditLog<D>>,
+}
+
+impl<D: Persistence> PgnImporter<D> {
+    pub fn new(
+        finished_game_store: Arc<D::FinishedGames>,
+        review_manager: Option<Arc<ReviewManager<D>>>,
+        audit_log: Arc<AuditLog<D>>,
+    ) -> Self {
+        Self {
+            finished_game_store,
+            review_manager,
+            audit_log,
+        }
+    }
+
+    /// Import every finished game in `text`.
+    pub async fn import_text(&self, text: &str) -> Result<ImportReport, String> {
+        Ok(ImportReport::tally(&self.import_games(text).await?))
+    }
+
+    /// Import every finished game in `text`, reporting on each game in
+    /// file order.
+    pub async fn import_games(&self, text: &str) -> Result<Vec<GameImport>, String> {
+        let mut results = Vec::new();
+        for (index, game) in chess::pgn::parse_games(text).iter().enumerate() {
+            let data = match to_finished_game(game) {
+                Ok(data) => data,
+                Err(e) => {
+                    tracing::warn!(game = index + 1, "Skipping PGN game: {}", e);
+                    results.push(GameImport::Skipped(e));
+                    continue;
+                }
+            };
+            let existing = self
+                .finished_game_store
+                .load_game(&data.game_id)
+                .await
+                .map_err(|e| e.to_string())?;
+            if existing.is_some() {
+                results.push(GameImport::Duplicate(data.game_id));
+                continue;
+            }
+            self.finished_game_store
+                .save_game(&data)
+                .await
+                .map_err(|e| e.to_string())?;
+
+            if let Some(ref review_manager) = self.review_manager {
+                if let Err(e) = review_manager.enqueue(&data.game_id).await {
+                    tracing::warn!(game_id = %data.game_id, "Failed to queue imported game for review: {}", e);
+                }
+            }
+            match data.derived_result {
+                Some(derived) if derived != data.result => {
+                    tracing::warn!(
+                        game_id = %data.game_id,
+                        claimed = %data.result,
+                        derived = %derived,
+                        "Imported PGN game claims another result than it shows"
+                    );
+                    results.push(GameImport::ResultMismatch {
+                        game_id: data.game_id,
+                        claimed: data.result,
+                        derived,
+                    });
+                }
+                _ => results.push(GameImport::Imported(data.game_id)),
+            }
+        }
+        Ok(results)
+    }
+
+    /// Import `path` and move it out of the watch directory.
+    async fn import_file(&self, dir: &Path, path: &Path) -> Result<(), String> {
+        let bytes = tokio::fs::read(path)
+            .await
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        // Exports from older tools are often Latin-1
+        let text = String::from_utf8_lossy(&bytes);
+        let report = self.import_text(&text).await?;
+
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let found = report.imported + report.duplicates + report.skipped;
+        let target = if found > 0 && report.skipped < found {
+            ARCHIVE_DIR
+        } else {
+            FAILED_DIR
+        };
+        tracing::info!(
+            file = %name,
+            imported = report.imported,
+            duplicates = report.duplicates,
+            skipped = report.skipped,
+            result_mismatches = report.result_mismatches,
+            "Imported PGN file"
+        );
+        if report.imported > 0 {
+            self.audit_log
+                .record(
+                    SERVER_ACTOR,
+                    AuditAction::Import,
+                    format!("PGN file {}", name),
+                    format!(
+                        "{} games, {} duplicates, {} skipped, {} with a wrong result",
+                        report.imported,
+                        report.duplicates,
+                        report.skipped,
+                        report.result_mismatches
+                    ),
+                )
+                .await;
+        }
+
+        let destination = free_path(&dir.join(target), &name).await;
+        tokio::fs::rename(path, &destination)
+            .await
+            .map_err(|e| format!("Failed to move {} to {}: {}", name, target, e))
+    }
+}
+
+/// `dir/name`, or `dir/stem-N.ext` when a file of that name exists.
+async fn free_path(dir: &Path, name: &str) -> PathBuf {
+    let candidate = dir.join(name);
+    if !tokio::fs::try_exists(&candidate).await.unwrap_or(false) {
+        return candidate;
+    }
+    let (stem, ext) = name.rsplit_once('.').unwrap_or((name, "pgn"));
+    let mut n = 1;
+    loop {
+        let candidate = dir.join(format!("{}-{}.{}", stem, n, ext));
+        if !tokio::fs::try_exists(&candidate).await.unwrap_or(false) {
+            return candidate;
+        }
+        n += 1;
+    }
+}
+
+fn is_pgn(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("pgn"))
+}
+
+/// Watch `dir` for PGN files, once per `interval`, for as long as the
+/// server runs.
+pub async fn run_watch<D: Persistence>(
+    importer: PgnImporter<D>,
+    dir: PathBuf,
+    interval: Duration,
+    disk: Arc<DiskGuard>,
+) {
+    for sub in [ARCHIVE_DIR, FAILED_DIR] {
+        if let Err(e) = tokio::fs::create_dir_all(dir.join(sub)).await {
+            tracing::error!("Failed to create {} in PGN watch directory: {}", sub, e);
+            return;
+        }
+    }
+
+    // Size and modification time seen on the previous poll
+    let mut seen: HashMap<PathBuf, (u64, Option<SystemTime>)> = HashMap::new();
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        ticker.tick().await;
+        let mut entries = match tokio::fs::read_dir(&dir).await {
+            Ok(entries) => entries,
+            Err(e) => {
+                tracing::warn!("Failed to read PGN watch directory: {}", e);
+                continue;
+            }
+        };
+
+        let mut current = HashMap::new();
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let path = entry.path();
+            let Ok(meta) = entry.metadata().await else {
+                continue;
+            };
+            if meta.is_file() && is_pgn(&path) {
+                current.insert(path, (meta.len(), meta.modified().ok()));
+            }
+        }
+
+        for (path, state) in &current {
+            if seen.get(path) != Some(state) {
+                continue;
+            }
+            // Files stay in the folder until there is room for them
+            if disk.ensure_room("PGN import").await.is_err() {
+                break;
+            }
+            if let Err(e) = importer.import_file(&dir, path).await {
+                tracing::warn!("PGN import failed: {}", e);
+            }
+        }
+        seen = current;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::AuditLog;
+    use crate::persistence::{AuditStore, FinishedGameStore, JsonPersistence};
+
+    const GAME: &str = "[White \"Ann\"]\n[Black \"Bob\"]\n\n1. f3 e5 2. g4 Qh4# 0-1\n";
+
+    fn importer(dir: &Path) -> PgnImporter<JsonPersistence> {
+        PgnImporter::new(
+            Arc::new(FinishedGameStore::new(dir.to_path_buf())),
+            None,
+            Arc::new(AuditLog::new(AuditStore::new(dir.to_path_buf()))),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_import_text_dedups() {
+        let dir = tempfile::tempdir().unwrap();
+        let importer = importer(dir.path());
+
+        let text = format!("{}\n[White \"Cy\"]\n\n1. e4 *\n", GAME);
+        let report = importer.import_text(&text).await.unwrap();
+        assert_eq!(
+            report,
+            ImportReport {
+                imported: 1,
+                duplicates: 0,
+                skipped: 1,
+                result_mismatches: 0,
+            }
+        );
+
+        let again = importer.import_text(GAME).await.unwrap();
+        assert_eq!(again.imported, 0);
+        assert_eq!(again.duplicates, 1);
+
+        let games = importer.finished_game_store.list_games().await.unwrap();
+        assert_eq!(games.len(), 1);
+        assert_eq!(games[0].result, "BlackWins");
+        assert_eq!(games[0].game_mode, IMPORTED_GAME_MODE);
+        assert_eq!(games[0].move_count, 4);
+    }
+
+    #[tokio::test]
+    async fn test_import_games_reports_each_game() {
+        let dir = tempfile::tempdir().unwrap();
+        let importer = importer(dir.path());
+
+        let text = format!("{}\n[White \"Cy\"]\n\n1. e4 e5 2. Ke3 1-0\n", GAME);
+        let games = importer.import_games(&text).await.unwrap();
+        let GameImport::Imported(ref id) = games[0] else {
+            panic!("expected an import, got {:?}", games[0]);
+        };
+        assert_eq!(
+            games[1],
+            GameImport::Skipped(PgnError::IllegalMove {
+                ply: 3,
+                san: "Ke3".into()
+            })
+        );
+
+        let again = importer.import_games(GAME).await.unwrap();
+        assert_eq!(again, vec![GameImport::Duplicate(id.clone())]);
+    }
+
+    #[tokio::test]
+    async fn test_import_flags_wrong_result_tag() {
+        let dir = tempfile::tempdir().unwrap();
+        let importer = importer(dir.path());
+
+        let games = importer
+            .import_games("1. f3 e5 2. g4 Qh4# 1-0\n")
+            .await
+            .unwrap();
+        let GameImport::ResultMismatch {
+            ref claimed,
+            ref derived,
+            ..
+        } = games[0]
+        else {
+            panic!("expected a result mismatch, got {:?}", games[0]);
+        };
+        assert_eq!(
+            (claimed.as_str(), derived.as_str()),
+            ("WhiteWins", "BlackWins")
+        );
+        assert_eq!(ImportReport::tally(&games).result_mismatches, 1);
+
+        let stored = importer.finished_game_store.list_games().await.unwrap();
+        assert_eq!(stored[0].result, "WhiteWins");
+        assert_eq!(stored[0].derived_result.as_deref(), Some("BlackWins"));
+    }
+
+    #[test]
+    fn test_game_id_depends_on_tags_and_moves() {
+        let games = chess::pgn::parse_games(GAME);
+        let start = chess::Game::new().to_fen();
+        let id = imported_game_id(&games[0], &start);
+        assert!(id.starts_with("pgn_"));
+
+        let mut renamed = games[0].clone();
+        renamed.tags[0].1 = "Dee".into();
+        assert_ne!(imported_game_id(&renamed, &start), id);
+
+        let mut annotated = games[0].clone();
+        annotated.moves[2].nags.push(4);
+        assert_eq!(imported_game_id(&annotated, &start), id);
+    }
+
+    #[tokio::test]
+    async fn test_import_file_archives() {
+        let dir = tempfile::tempdir().unwrap();
+        let watch = dir.path().join("watch");
+        for sub in [ARCHIVE_DIR, FAILED_DIR] {
+            std::fs::create_dir_all(watch.join(sub)).unwrap();
+        }
+        let importer = importer(dir.path());
+
+        let good = watch.join("dgt.pgn");
+        std::fs::write(&good, GAME).unwrap();
+        importer.import_file(&watch, &good).await.unwrap();
+        assert!(!good.exists());
+        assert!(watch.join(ARCHIVE_DIR).join("dgt.pgn").exists());
+
+        // Same name again: kept next to the first, not overwritten
+        std::fs::write(&good, GAME).unwrap();
+        importer.import_file(&watch, &good).await.unwrap();
+        assert!(watch.join(ARCHIVE_DIR).join("dgt-1.pgn").exists());
+
+        let bad = watch.join("notes.pgn");
+        std::fs::write(&bad, "not a game").unwrap();
+        importer.import_file(&watch, &bad).await.unwrap();
+        assert!(watch.join(FAILED_DIR).join("notes.pgn").exists());
+    }
+}
@@ -0,0 +1,244 @@
+//! Replaying imported PGN games into stored moves.
+//!
+//! Parsing lives in [`chess::pgn`]; only the main line is replayed here.
+//! Moves are replayed with [`chess::Game`], so every move is checked for
+//! legality and stored with the same squares, pieces and FENs as a move
+//! played on this server; the SAN is kept from the file, without its
+//! annotation marks.
+//!
+//! Result tags are not always right, so the replay also works out the
+//! result where it can: from a mate, stalemate or bare material on the
+//! board at the end, or else from a closing comment such as
+//! `{White resigns}`.
+
+use chess::converters::{format_piece_upper, format_square};
+use chess::pgn::PgnGame;
+use chess::{Game, GameResult};
+use cozy_chess::{Board, Color, GameStatus, Move, Piece};
+
+use crate::persistence::StoredMoveRecord;
+
+/// Why a game could not be imported.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PgnError {
+    #[error("Invalid FEN tag: {0}")]
+    InvalidFen(String),
+    #[error("Illegal or ambiguous move {san} at ply {ply}")]
+    IllegalMove { ply: usize, san: String },
+    #[error("Game has no moves")]
+    NoMoves,
+    #[error("Game has no result")]
+    Unfinished,
+}
+
+/// A game replayed from its starting position.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayedGame {
+    pub start_fen: String,
+    pub moves: Vec<StoredMoveRecord>,
+    /// Result the final position or closing comment shows, if either does.
+    pub derived_result: Option<GameResult>,
+}
+
+/// `san` without `!`/`?` annotations, castling written with letters.
+fn clean_san(san: &str) -> String {
+    let san = san.trim_end_matches(['!', '?']);
+    match san.trim_end_matches(['+', '#']) {
+        "0-0" | "0-0-0" => san.replace('0', "O"),
+        _ => san.to_string(),
+    }
+}
+
+/// Neither side can mate: bare kings, or a single knight or bishop left.
+fn insufficient_material(board: &Board) -> bool {
+    let heavy = board.pieces(Piece::Pawn) | board.pieces(Piece::Rook) | board.pieces(Piece::Queen);
+    let minors = board.pieces(Piece::Knight) | board.pieces(Piece::Bishop);
+    heavy.is_empty() && minors.len() <= 1
+}
+
+/// Result of a game ending in `board`, if the position decides it.
+fn result_on_board(board: &Board) -> Option<GameResult> {
+    match board.status() {
+        GameStatus::Won => Some(match board.side_to_move() {
+            Color::White => GameResult::BlackWins,
+            Color::Black => GameResult::WhiteWins,
+        }),
+        // A stalemate; the fifty-move rule has to be claimed, so a game
+        // going on past it shows nothing
+        GameStatus::Drawn if !board.generate_moves(|_| true) => Some(GameResult::Draw),
+        _ if insufficient_material(board) => Some(GameResult::Draw),
+        _ => None,
+    }
+}
+
+/// Result a closing comment such as `{Black wins on time}` or
+/// `{Draw by repetition}` states.
+fn result_in_comment(comment: &str) -> Option<GameResult> {
+    let comment = comment.to_lowercase();
+    if comment.contains("white resigns") || comment.contains("black wins") {
+        Some(GameResult::BlackWins)
+    } else if comment.contains("black resigns") || comment.contains("white wins") {
+        Some(GameResult::WhiteWins)
+    } else if ["draw", "stalemate", "repetition", "agreed"]
+        .iter()
+        .any(|word| comment.contains(word))
+    {
+        Some(GameResult::Draw)
+    } else {
+        None
+    }
+}
+
+/// The move `san` stands for in `board`.
+pub fn find_move(board: &Board, legal: &[Move], san: &str) -> Option<Move> {
+    let core = san.trim_end_matches(['+', '#', '!', '?']);
+    let kingside = match core {
+        "O-O" | "0-0" => true,
+        "O-O-O" | "0-0-0" => false,
+        _ => return chess::parse_san(board, san),
+    };
+    // Castling is the king taking its own rook
+    let side = board.side_to_move();
+    legal.iter().copied().find(|mv| {
+        board.piece_on(mv.from) == Some(Piece::King)
+            && board.piece_on(mv.to) == Some(Piece::Rook)
+            && board.color_on(mv.to) == Some(side)
+            && (mv.to.file() > mv.from.file()) == kingside
+    })
+}
+
+/// Replay the main line of `game` from its `FEN` tag, or the standard
+/// starting position.
+pub fn replay(game: &PgnGame) -> Result<ReplayedGame, PgnError> {
+    if game.moves.is_empty() {
+        return Err(PgnError::NoMoves);
+    }
+    let mut chess_game = match game.tag("FEN") {
+        Some(fen) => Game::from_fen(fen).map_err(|_| PgnError::InvalidFen(fen.to_string()))?,
+        None => Game::new(),
+    };
+    let start_fen = chess_game.to_fen();
+
+    let mut moves = Vec::with_capacity(game.moves.len());
+    for (ply, san) in game.main_line().enumerate() {
+        let illegal = || PgnError::IllegalMove {
+            ply: ply + 1,
+            san: san.to_string(),
+        };
+        let legal = chess_game.legal_moves();
+        let mv = find_move(chess_game.position(), &legal, san).ok_or_else(illegal)?;
+        let entry = chess_game.make_move(mv).map_err(|_| illegal())?;
+        // The castling rook is not a capture
+        let captured = entry
+            .captured
+            .filter(|_| entry.board_before.color_on(entry.to) != Some(entry.piece_color));
+        moves.push(StoredMoveRecord {
+            from: format_square(entry.from),
+            to: format_square(entry.to),
+            piece: format_piece_upper(entry.piece).to_string(),
+            captured: captured.map(|p| format_piece_upper(p).to_string()),
+            promotion: entry.promotion.map(|p| format_piece_upper(p).to_string()),
+            san: clean_san(san),
+            fen_after: entry.fen,
+            clock_ms: None,
+            engine_telemetry: None,
+            think_ms: None,
+        });
+    }
+
+    let derived_result = result_on_board(chess_game.position()).or_else(|| {
+        game.moves
+            .last()
+            .and_then(|mv| mv.comment.as_deref())
+            .and_then(result_in_comment)
+    });
+    Ok(ReplayedGame {
+        start_fen,
+        moves,
+        derived_result,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chess::pgn::{parse_games, PgnMove};
+
+    const TWO_GAMES: &str = r#"[Event "Club night"]
+[White "Ann"]
+[Black "Bob"]
+[Result "1-0"]
+
+1. e4 {best by test} e5 2. Nf3 (2. f4 exf4) Nc6 3. Bc4 Nf6?! $6
+4. Ng5 d5 5. exd5 Na5 6. Bb5+ c6 7. dxc6 bxc6 8. Be2 h6 9. Nf3 e4
+10. Ne5 Bd6 11. O-O 1-0
+
+[Event "Blitz"]
+[Result "0-1"]
+
+1.f3 e5 2.g4 Qh4# 0-1
+"#;
+
+    #[test]
+    fn test_replay_regenerates_records() {
+        let games = parse_games(TWO_GAMES);
+        let replayed = replay(&games[0]).unwrap();
+        assert_eq!(replayed.moves.len(), 21);
+        assert_eq!(replayed.moves[5].san, "Nf6");
+
+        let castle = replayed.moves.last().unwrap();
+        assert_eq!(castle.san, "O-O");
+        assert_eq!(castle.piece, "K");
+        assert_eq!(castle.from, "e1");
+        assert_eq!(castle.captured, None);
+
+        let mate = replay(&games[1]).unwrap();
+        assert_eq!(mate.moves[3].san, "Qh4#");
+        assert_eq!(mate.moves[3].piece, "Q");
+        assert_eq!(mate.derived_result, Some(GameResult::BlackWins));
+    }
+
+    #[test]
+    fn test_result_from_position_or_comment() {
+        let games = parse_games(
+            "[FEN \"k7/8/8/8/8/8/1q6/K7 w - - 0 1\"]\n\n1. Kxb2 0-1\n\n\
+             1. e4 e5 2. Qh5 {White resigns, annoyed} 1-0\n\n\
+             1. d4 d5 1/2-1/2\n",
+        );
+        // Queen taken, bare kings left
+        assert_eq!(
+            replay(&games[0]).unwrap().derived_result,
+            Some(GameResult::Draw)
+        );
+        assert_eq!(
+            replay(&games[1]).unwrap().derived_result,
+            Some(GameResult::BlackWins)
+        );
+        assert_eq!(replay(&games[2]).unwrap().derived_result, None);
+    }
+
+    #[test]
+    fn test_replay_from_fen_and_errors() {
+        let game = PgnGame {
+            tags: vec![("FEN".into(), "4k3/8/8/8/8/8/8/4K2R w K - 0 1".into())],
+            moves: vec![PgnMove::new("0-0+"), PgnMove::new("Kd7")],
+            result: "*".into(),
+        };
+        let replayed = replay(&game).unwrap();
+        assert_eq!(replayed.start_fen, "4k3/8/8/8/8/8/8/4K2R w K - 0 1");
+        assert_eq!(replayed.moves[0].san, "O-O+");
+
+        let illegal = PgnGame {
+            moves: vec![PgnMove::new("e4"), PgnMove::new("e4")],
+            ..Default::default()
+        };
+        assert_eq!(
+            replay(&illegal),
+            Err(PgnError::IllegalMove {
+                ply: 2,
+                san: "e4".into()
+            })
+        );
+        assert_eq!(replay(&PgnGame::default()), Err(PgnError::NoMoves));
+    }
+}
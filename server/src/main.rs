@@ -1,22 +1,38 @@
+mod audit;
 mod config;
+mod disk;
+mod engine_check;
+mod engine_pool;
+mod export;
+mod guard;
+mod import;
 mod persistence;
+mod power;
+mod replay;
 mod review;
 mod service;
 mod session;
+mod study;
+mod sync;
 
 use chess_proto::chess_service_server::ChessServiceServer;
+use chess_proto::sync_service_server::SyncServiceServer;
 use persistence::sqlite::{
-    migrate_json_to_sqlite, Database, SqliteAdvancedAnalysisRepository,
-    SqliteFinishedGameRepository, SqlitePersistence, SqlitePositionRepository,
-    SqliteReviewRepository, SqliteSessionRepository,
+    migrate_json_to_sqlite, Database, DatabaseKey, SqliteActivityRepository,
+    SqliteAdvancedAnalysisRepository, SqliteAuditRepository, SqliteChatRepository,
+    SqliteFinishedGameRepository, SqliteGoalRepository, SqlitePersistence,
+    SqlitePositionRepository, SqliteReviewRepository, SqliteSessionRepository,
+    SqliteStudyRepository, SqliteSyncRepository,
 };
-use service::ChessServiceImpl;
+use service::{require_sync_token, ChessServiceImpl, SyncServiceImpl};
 use session::SessionManager;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::net::UnixListener;
 use tokio::signal::unix::{signal, SignalKind};
 use tokio_stream::wrappers::UnixListenerStream;
-use tonic::transport::Server;
+use tonic::service::interceptor::InterceptedService;
+use tonic::transport::{Identity, Server, ServerTlsConfig};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -38,7 +54,21 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     tracing::info!("Using legacy data directory: {}", data_dir.display());
     tracing::info!("Using SQLite database: {}", db_path.display());
 
-    let database = Database::open(&db_path).await?;
+    let database = match config::get_db_key_source() {
+        config::DbKeySource::Unencrypted => Database::open(&db_path).await?,
+        config::DbKeySource::KeyFile(key_path) => {
+            tracing::info!(
+                "Unlocking encrypted database with key file: {}",
+                key_path.display()
+            );
+            let key = DatabaseKey::from_file(&key_path)?;
+            Database::open_encrypted(&db_path, &key).await?
+        }
+        config::DbKeySource::Prompt => {
+            let key = DatabaseKey::prompt()?;
+            Database::open_encrypted(&db_path, &key).await?
+        }
+    };
     let migration_report = migrate_json_to_sqlite(database.pool(), &data_dir).await?;
     tracing::info!(
         skipped = migration_report.skipped,
@@ -50,6 +80,65 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         "SQLite migration check complete"
     );
 
+    // `chesstty-server replay [game_id...]` checks recorded games and exits
+    let mut args = std::env::args().skip(1);
+    match args.next().as_deref() {
+        Some("replay") => {
+            let ids: Vec<String> = args.collect();
+            let store = SqliteFinishedGameRepository::new(database.pool().clone());
+            let consistent = replay::run(&store, &ids).await?;
+            std::process::exit(if consistent { 0 } else { 1 });
+        }
+        // `chesstty-server export-training <dir> [val_percent]` writes the
+        // reviewed positions as train/val CSV files and exits
+        Some("export-training") => {
+            let dir = args
+                .next()
+                .ok_or("usage: export-training <dir> [val_percent]")?;
+            let val_percent = match args.next() {
+                Some(value) => value
+                    .parse::<u8>()
+                    .ok()
+                    .filter(|p| *p <= 100)
+                    .ok_or("val_percent must be between 0 and 100")?,
+                None => export::DEFAULT_VAL_PERCENT,
+            };
+            let summary = export::run(
+                &SqliteFinishedGameRepository::new(database.pool().clone()),
+                &SqliteReviewRepository::new(database.pool().clone()),
+                &SqliteAdvancedAnalysisRepository::new(database.pool().clone()),
+                std::path::Path::new(&dir),
+                val_percent,
+            )
+            .await?;
+            println!(
+                "Exported {} games: {} training rows, {} validation rows.",
+                summary.games, summary.train_rows, summary.val_rows
+            );
+            std::process::exit(0);
+        }
+        _ => {}
+    }
+
+    let audit_log = Arc::new(audit::AuditLog::<SqlitePersistence>::new(
+        SqliteAuditRepository::new(database.pool().clone()),
+    ));
+    let imported = migration_report.sessions
+        + migration_report.positions
+        + migration_report.finished_games
+        + migration_report.reviews
+        + migration_report.advanced_analyses;
+    if imported > 0 {
+        audit_log
+            .record(
+                audit::SERVER_ACTOR,
+                persistence::AuditAction::Import,
+                format!("legacy JSON data in {}", data_dir.display()),
+                format!("{} records", imported),
+            )
+            .await;
+    }
+
     let session_store = SqliteSessionRepository::new(database.pool().clone());
     let position_store = SqlitePositionRepository::new(database.pool().clone());
     let finished_game_store = Arc::new(SqliteFinishedGameRepository::new(database.pool().clone()));
@@ -57,27 +146,218 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let advanced_store = Arc::new(SqliteAdvancedAnalysisRepository::new(
         database.pool().clone(),
     ));
+    let activity_store = SqliteActivityRepository::new(database.pool().clone());
+    let goal_store = SqliteGoalRepository::new(database.pool().clone());
+    let sync_store = SqliteSyncRepository::new(database.pool().clone());
+    let study_store = SqliteStudyRepository::new(database.pool().clone());
+    let chat_store = SqliteChatRepository::new(database.pool().clone());
 
     // Create session manager
-    let session_manager = Arc::new(SessionManager::<SqlitePersistence>::new(
-        session_store,
-        position_store,
+    let engine_limits = config::get_engine_limits();
+    let session_manager = Arc::new(
+        SessionManager::<SqlitePersistence>::new(
+            session_store,
+            position_store,
+            finished_game_store.clone(),
+            activity_store,
+            goal_store,
+            chat_store,
+        )
+        .with_engine_limits(engine_limits),
+    );
+
+    // Create sync manager
+    let sync_manager = Arc::new(sync::SyncManager::<SqlitePersistence>::new(
+        finished_game_store.clone(),
+        review_store.clone(),
+        sync_store,
+    ));
+
+    // Create study manager
+    let study_manager = Arc::new(study::StudyManager::<SqlitePersistence>::new(
         finished_game_store.clone(),
+        review_store.clone(),
+        study_store,
     ));
 
     // Create review manager
+    let import_dir = config::get_import_dir();
+    let import_review = config::get_import_review();
     let review_manager = Arc::new(review::ReviewManager::<SqlitePersistence>::new(
-        finished_game_store,
+        finished_game_store.clone(),
         review_store,
         advanced_store,
-        review::ReviewConfig::default(),
+        review::ReviewConfig {
+            review_imports: import_review,
+            tablebase_paths: config::get_syzygy_paths(),
+            accuracy_exclusions: config::get_accuracy_exclusions(),
+            ..Default::default()
+        },
     ));
 
-    // Recover any pending reviews from previous runs
-    review_manager.recover_pending_reviews().await;
+    // Hold reviews while Stockfish is missing and retry them once it is found
+    let engine_path = engine_check::spawn_engine_monitor(review_manager.clone());
+
+    // Recover pending reviews from previous runs while the server starts
+    let recovery = review_manager.spawn_recovery();
+
+    // Reduce engines and hold reviews in low-power mode, e.g. on battery
+    let power = power::spawn_power_monitor(
+        config::get_power_policy(),
+        session_manager.clone(),
+        review_manager.clone(),
+    );
+
+    // Refuse imports and reviews before they fill the disk
+    let disk = Arc::new(disk::DiskGuard::new(
+        db_path.clone(),
+        config::get_server_log_path(),
+        config::get_disk_thresholds(),
+    ));
+
+    // Engines for AnalyzePosition, spawned on first use
+    let engine_pool = Arc::new(engine_pool::EnginePool::new(config::get_analysis_engines()));
 
     // Create service
-    let service = ChessServiceImpl::new(session_manager.clone(), review_manager.clone());
+    let service = ChessServiceServer::new(ChessServiceImpl::new(
+        session_manager.clone(),
+        review_manager.clone(),
+        sync_manager.clone(),
+        audit_log.clone(),
+        study_manager,
+        engine_pool.clone(),
+        power,
+        disk.clone(),
+        engine_path,
+        recovery,
+    ));
+
+    // Standard gRPC health checks, so clients can tell a ready server from an open socket
+    let (mut health_reporter, health_service) = tonic_health::server::health_reporter();
+    health_reporter
+        .set_serving::<ChessServiceServer<ChessServiceImpl<SqlitePersistence>>>()
+        .await;
+
+    // Serve remote clients over TCP as well, if configured
+    if let Some(addr) = config::get_listen_tcp_addr() {
+        let tcp_service = service.clone();
+        let tcp_health_service = health_service.clone();
+        let guard = guard::ClientGuard::new(config::get_tcp_guard_limits());
+        // Refuse to start rather than fall back to plaintext when TLS is misconfigured
+        let mut builder = Server::builder();
+        match config::get_tls_files() {
+            Some(files) => {
+                builder = builder.tls_config(load_tls_config(&files)?)?;
+                tracing::info!("Accepting clients over TCP with TLS on {}", addr);
+            }
+            None => tracing::info!("Accepting clients over TCP on {}", addr),
+        }
+        tokio::spawn(async move {
+            let incoming = match guard::incoming::guarded_incoming(addr, guard.clone()).await {
+                Ok(incoming) => incoming,
+                Err(e) => {
+                    tracing::error!("TCP listener error: {}", e);
+                    return;
+                }
+            };
+            if let Err(e) = builder
+                .layer(guard::layer::GuardLayer::new(guard))
+                .add_service(tcp_health_service)
+                .add_service(tcp_service)
+                .serve_with_incoming(incoming)
+                .await
+            {
+                tracing::error!("TCP listener error: {}", e);
+            }
+        });
+    }
+
+    // Accept sync peers over TCP and sync on a schedule, if configured
+    let sync_token = config::get_sync_token();
+    if let Some(addr) = config::get_sync_listen_addr() {
+        match &sync_token {
+            Some(token) => {
+                // The token is checked on the headers, before a message is decoded
+                let sync_service = InterceptedService::new(
+                    SyncServiceServer::new(SyncServiceImpl::new(
+                        sync_manager.clone(),
+                        audit_log.clone(),
+                    ))
+                    .max_decoding_message_size(sync::peer::MAX_SYNC_MESSAGE_SIZE)
+                    .max_encoding_message_size(sync::peer::MAX_SYNC_MESSAGE_SIZE),
+                    require_sync_token(token.clone()),
+                );
+                let guard = guard::ClientGuard::new(config::get_tcp_guard_limits());
+                let mut builder = Server::builder();
+                match config::get_tls_files() {
+                    Some(files) => {
+                        builder = builder.tls_config(load_tls_config(&files)?)?;
+                        tracing::info!("Accepting sync peers with TLS on {}", addr);
+                    }
+                    None => tracing::warn!(
+                        "Accepting sync peers on {} without TLS; the sync token and records travel in plain text",
+                        addr
+                    ),
+                }
+                tokio::spawn(async move {
+                    let incoming =
+                        match guard::incoming::guarded_incoming(addr, guard.clone()).await {
+                            Ok(incoming) => incoming,
+                            Err(e) => {
+                                tracing::error!("Sync listener error: {}", e);
+                                return;
+                            }
+                        };
+                    if let Err(e) = builder
+                        .layer(guard::layer::GuardLayer::new(guard))
+                        .add_service(sync_service)
+                        .serve_with_incoming(incoming)
+                        .await
+                    {
+                        tracing::error!("Sync listener error: {}", e);
+                    }
+                });
+            }
+            None => tracing::warn!(
+                "CHESSTTY_SYNC_LISTEN is set without CHESSTTY_SYNC_TOKEN; not accepting sync peers"
+            ),
+        }
+    }
+    let sync_peers = config::get_sync_peers();
+    if let (Some(secs), Some(token)) = (config::get_sync_interval_secs(), &sync_token) {
+        if !sync_peers.is_empty() {
+            tracing::info!(peers = ?sync_peers, interval_secs = secs, "Scheduled sync enabled");
+            tokio::spawn(sync::peer::run_scheduled_sync(
+                sync_manager.clone(),
+                audit_log.clone(),
+                sync_peers,
+                token.clone(),
+                Duration::from_secs(secs),
+            ));
+        }
+    }
+
+    // Import PGN files dropped into the watch folder, if configured
+    if let Some(dir) = import_dir {
+        let secs = config::get_import_interval_secs();
+        tracing::info!(
+            dir = %dir.display(),
+            interval_secs = secs,
+            review = import_review,
+            "Watching for PGN files"
+        );
+        let importer = import::PgnImporter::<SqlitePersistence>::new(
+            finished_game_store,
+            import_review.then(|| review_manager.clone()),
+            audit_log.clone(),
+        );
+        tokio::spawn(import::run_watch(
+            importer,
+            dir,
+            Duration::from_secs(secs),
+            disk,
+        ));
+    }
 
     // Server address (Unix Domain Socket)
     let socket_path = config::get_socket_path();
@@ -98,7 +378,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Start server with signal handling
     let server_future = Server::builder()
-        .add_service(ChessServiceServer::new(service))
+        .add_service(health_service)
+        .add_service(service)
         .serve_with_incoming(uds_stream);
 
     tokio::select! {
@@ -117,6 +398,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Cleanup socket file
     tracing::info!("Cleaning up...");
+    health_reporter
+        .set_not_serving::<ChessServiceServer<ChessServiceImpl<SqlitePersistence>>>()
+        .await;
+    engine_pool.shutdown().await;
     if socket_path.exists() {
         if let Err(e) = std::fs::remove_file(&socket_path) {
             tracing::warn!("Failed to remove socket file: {}", e);
@@ -126,3 +411,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+/// Read the PEM certificate chain and private key the TCP and sync listeners
+/// serve TLS with.
+fn load_tls_config(
+    files: &config::TlsFiles,
+) -> Result<ServerTlsConfig, Box<dyn std::error::Error>> {
+    let read = |path: &std::path::Path| {
+        std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+    };
+    let identity = Identity::from_pem(read(&files.cert)?, read(&files.key)?);
+    Ok(ServerTlsConfig::new().identity(identity))
+}
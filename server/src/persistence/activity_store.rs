@@ -0,0 +1,218 @@
+use super::json_store::{JsonStore, Storable};
+use super::PersistenceError;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// Day number (days since the Unix epoch, UTC) of a unix timestamp in seconds.
+pub fn day_of(timestamp: u64) -> u32 {
+    (timestamp / SECONDS_PER_DAY) as u32
+}
+
+/// Year, month and day of the month of a day number, in the proleptic
+/// Gregorian calendar.
+pub fn civil_date(day: u32) -> (i64, u32, u32) {
+    let z = i64::from(day) + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    (yoe + era * 400 + i64::from(m <= 2), m as u32, d as u32)
+}
+
+/// `YYYY-MM-DD` for a day number.
+pub fn iso_date(day: u32) -> String {
+    let (y, m, d) = civil_date(day);
+    format!("{:04}-{:02}-{:02}", y, m, d)
+}
+
+/// First day of the calendar month containing `day`.
+pub fn month_start(day: u32) -> u32 {
+    day - (civil_date(day).2 - 1)
+}
+
+/// Monday of the week containing `day`. Day 0 (1970-01-01) was a Thursday.
+pub fn week_start(day: u32) -> u32 {
+    day - (day + 3) % 7
+}
+
+/// Something a player did that counts towards their daily activity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActivityKind {
+    GamePlayed,
+    /// Not recorded by anything yet; counted once there is a puzzle trainer.
+    #[allow(dead_code)]
+    PuzzleSolved,
+    ReviewRead,
+}
+
+/// Activity counts for one profile on one day.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ActivityDay {
+    /// Days since the Unix epoch (UTC).
+    pub day: u32,
+    pub games_played: u32,
+    pub puzzles_solved: u32,
+    pub reviews_read: u32,
+}
+
+impl ActivityDay {
+    pub fn new(day: u32) -> Self {
+        Self {
+            day,
+            ..Default::default()
+        }
+    }
+
+    pub fn add(&mut self, kind: ActivityKind) {
+        match kind {
+            ActivityKind::GamePlayed => self.games_played += 1,
+            ActivityKind::PuzzleSolved => self.puzzles_solved += 1,
+            ActivityKind::ReviewRead => self.reviews_read += 1,
+        }
+    }
+
+    pub fn total(&self) -> u32 {
+        self.games_played + self.puzzles_solved + self.reviews_read
+    }
+}
+
+/// A profile's whole activity log, stored as one JSON file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ActivityLogData {
+    profile: String,
+    days: Vec<ActivityDay>,
+}
+
+impl Storable for ActivityLogData {
+    fn id(&self) -> &str {
+        &self.profile
+    }
+}
+
+/// Persistence layer for activity logs. Uses JSON files in a directory.
+/// Kept as a fallback trait implementation; production uses SqliteActivityRepository.
+#[allow(dead_code)]
+pub struct ActivityStore {
+    inner: JsonStore<ActivityLogData>,
+}
+
+#[allow(dead_code)]
+impl ActivityStore {
+    pub fn new(data_dir: PathBuf) -> Self {
+        let dir = data_dir.join("activity");
+        Self {
+            inner: JsonStore::new(dir),
+        }
+    }
+
+    /// Count one activity for `profile` on `day`.
+    pub fn record(
+        &self,
+        profile: &str,
+        day: u32,
+        kind: ActivityKind,
+    ) -> Result<(), PersistenceError> {
+        let mut log = self
+            .inner
+            .load(profile)?
+            .unwrap_or_else(|| ActivityLogData {
+                profile: profile.to_string(),
+                days: vec![],
+            });
+        match log.days.iter_mut().find(|d| d.day == day) {
+            Some(entry) => entry.add(kind),
+            None => {
+                let mut entry = ActivityDay::new(day);
+                entry.add(kind);
+                log.days.push(entry);
+                log.days.sort_by_key(|d| d.day);
+            }
+        }
+        self.inner.save(&log)?;
+        Ok(())
+    }
+
+    /// Activity of `profile` from `since_day` onwards, oldest first.
+    pub fn list(
+        &self,
+        profile: &str,
+        since_day: u32,
+    ) -> Result<Vec<ActivityDay>, PersistenceError> {
+        let days = self
+            .inner
+            .load(profile)?
+            .map(|log| log.days)
+            .unwrap_or_default();
+        Ok(days.into_iter().filter(|d| d.day >= since_day).collect())
+    }
+}
+
+impl super::traits::ActivityRepository for ActivityStore {
+    async fn record_activity(
+        &self,
+        profile: &str,
+        day: u32,
+        kind: ActivityKind,
+    ) -> Result<(), PersistenceError> {
+        self.record(profile, day, kind)
+    }
+
+    async fn list_activity(
+        &self,
+        profile: &str,
+        since_day: u32,
+    ) -> Result<Vec<ActivityDay>, PersistenceError> {
+        self.list(profile, since_day)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_day_of_rounds_down_to_utc_day() {
+        assert_eq!(day_of(0), 0);
+        assert_eq!(day_of(SECONDS_PER_DAY - 1), 0);
+        assert_eq!(day_of(SECONDS_PER_DAY), 1);
+    }
+
+    #[test]
+    fn test_calendar_days() {
+        assert_eq!(iso_date(0), "1970-01-01");
+        assert_eq!(iso_date(59), "1970-03-01");
+        assert_eq!(iso_date(19_723), "2024-01-01");
+        // 2024-02-29 is day 19_782
+        assert_eq!(iso_date(19_782), "2024-02-29");
+        assert_eq!(month_start(19_782), 19_754);
+        // 1970-01-05 was a Monday
+        assert_eq!(week_start(10), 4);
+        assert_eq!(week_start(4), 4);
+    }
+
+    #[test]
+    fn test_record_accumulates_per_day() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ActivityStore::new(dir.path().to_path_buf());
+
+        store.record("alice", 12, ActivityKind::GamePlayed).unwrap();
+        store.record("alice", 10, ActivityKind::ReviewRead).unwrap();
+        store.record("alice", 12, ActivityKind::GamePlayed).unwrap();
+        store.record("bob", 12, ActivityKind::PuzzleSolved).unwrap();
+
+        let days = store.list("alice", 0).unwrap();
+        assert_eq!(days.len(), 2);
+        assert_eq!(days[0].day, 10);
+        assert_eq!(days[0].reviews_read, 1);
+        assert_eq!(days[1].games_played, 2);
+        assert_eq!(days[1].total(), 2);
+
+        assert_eq!(store.list("alice", 11).unwrap().len(), 1);
+        assert!(store.list("carol", 0).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,162 @@
+use super::json_store::{JsonStore, Storable};
+use super::PersistenceError;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Kind of operation recorded in the audit log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AuditAction {
+    /// A finished game (with its review), position, suspended session or goal was deleted.
+    Delete,
+    /// Records were taken over from elsewhere: a sync peer or legacy JSON files.
+    Import,
+    /// A session's engine settings were changed.
+    ConfigChange,
+    /// An operator closed or suspended a session that was not theirs.
+    SessionClose,
+}
+
+impl AuditAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AuditAction::Delete => "delete",
+            AuditAction::Import => "import",
+            AuditAction::ConfigChange => "config_change",
+            AuditAction::SessionClose => "session_close",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "delete" => Some(AuditAction::Delete),
+            "import" => Some(AuditAction::Import),
+            "config_change" => Some(AuditAction::ConfigChange),
+            "session_close" => Some(AuditAction::SessionClose),
+            _ => None,
+        }
+    }
+}
+
+/// One audited operation.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AuditEntry {
+    /// Unix time in seconds.
+    pub at: u64,
+    /// Who did it, e.g. `uid 1000 (pid 4242)` or a peer address.
+    pub actor: String,
+    pub action: AuditAction,
+    /// What it was done to, e.g. `finished_game game_1700000000000`.
+    pub target: String,
+    pub detail: String,
+}
+
+/// The whole audit log, stored as one JSON file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct AuditLogData {
+    id: String,
+    entries: Vec<AuditEntry>,
+}
+
+impl Storable for AuditLogData {
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+const AUDIT_LOG_ID: &str = "log";
+
+/// Persistence layer for the audit log. Uses a JSON file in a directory.
+/// Kept as a fallback trait implementation; production uses SqliteAuditRepository.
+#[allow(dead_code)]
+pub struct AuditStore {
+    inner: JsonStore<AuditLogData>,
+}
+
+#[allow(dead_code)]
+impl AuditStore {
+    pub fn new(data_dir: PathBuf) -> Self {
+        let dir = data_dir.join("audit");
+        Self {
+            inner: JsonStore::new(dir),
+        }
+    }
+
+    fn load_log(&self) -> Result<AuditLogData, PersistenceError> {
+        Ok(self
+            .inner
+            .load(AUDIT_LOG_ID)?
+            .unwrap_or_else(|| AuditLogData {
+                id: AUDIT_LOG_ID.to_string(),
+                entries: vec![],
+            }))
+    }
+
+    pub fn record(&self, entry: &AuditEntry) -> Result<(), PersistenceError> {
+        let mut log = self.load_log()?;
+        log.entries.push(entry.clone());
+        self.inner.save(&log)?;
+        Ok(())
+    }
+
+    /// The `limit` most recent entries, newest first.
+    pub fn list(&self, limit: usize) -> Result<Vec<AuditEntry>, PersistenceError> {
+        Ok(self
+            .load_log()?
+            .entries
+            .into_iter()
+            .rev()
+            .take(limit)
+            .collect())
+    }
+}
+
+impl super::traits::AuditRepository for AuditStore {
+    async fn record_audit(&self, entry: &AuditEntry) -> Result<(), PersistenceError> {
+        self.record(entry)
+    }
+
+    async fn list_audit(&self, limit: usize) -> Result<Vec<AuditEntry>, PersistenceError> {
+        self.list(limit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(at: u64, action: AuditAction) -> AuditEntry {
+        AuditEntry {
+            at,
+            actor: "uid 1000".to_string(),
+            action,
+            target: format!("position pos_{}", at),
+            detail: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_action_round_trip() {
+        for action in [
+            AuditAction::Delete,
+            AuditAction::Import,
+            AuditAction::ConfigChange,
+            AuditAction::SessionClose,
+        ] {
+            assert_eq!(AuditAction::parse(action.as_str()), Some(action));
+        }
+        assert_eq!(AuditAction::parse("shutdown"), None);
+    }
+
+    #[test]
+    fn test_list_is_newest_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = AuditStore::new(dir.path().to_path_buf());
+        assert!(store.list(10).unwrap().is_empty());
+
+        for at in 1..=3 {
+            store.record(&entry(at, AuditAction::Delete)).unwrap();
+        }
+        let entries = store.list(2).unwrap();
+        assert_eq!(entries.iter().map(|e| e.at).collect::<Vec<_>>(), vec![3, 2]);
+    }
+}
@@ -0,0 +1,110 @@
+use super::json_store::{JsonStore, Storable};
+use super::PersistenceError;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// One chat message sent in a session.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChatMessageData {
+    pub session_id: String,
+    /// Position in the session's chat, from 1.
+    pub seq: u32,
+    pub author: String,
+    pub text: String,
+    /// Unix time in seconds.
+    pub sent_at: u64,
+}
+
+/// A session's whole chat, stored as one JSON file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ChatLogData {
+    session_id: String,
+    messages: Vec<ChatMessageData>,
+}
+
+impl Storable for ChatLogData {
+    fn id(&self) -> &str {
+        &self.session_id
+    }
+}
+
+/// Persistence layer for session chat. Uses a JSON file per session.
+/// Kept as a fallback trait implementation; production uses SqliteChatRepository.
+#[allow(dead_code)]
+pub struct ChatStore {
+    inner: JsonStore<ChatLogData>,
+}
+
+#[allow(dead_code)]
+impl ChatStore {
+    pub fn new(data_dir: PathBuf) -> Self {
+        let dir = data_dir.join("chat");
+        Self {
+            inner: JsonStore::new(dir),
+        }
+    }
+
+    pub fn record(&self, message: &ChatMessageData) -> Result<(), PersistenceError> {
+        let mut log = self
+            .inner
+            .load(&message.session_id)?
+            .unwrap_or_else(|| ChatLogData {
+                session_id: message.session_id.clone(),
+                messages: vec![],
+            });
+        log.messages.retain(|m| m.seq != message.seq);
+        log.messages.push(message.clone());
+        log.messages.sort_by_key(|m| m.seq);
+        self.inner.save(&log)?;
+        Ok(())
+    }
+
+    /// A session's messages, oldest first.
+    pub fn list(&self, session_id: &str) -> Result<Vec<ChatMessageData>, PersistenceError> {
+        Ok(self
+            .inner
+            .load(session_id)?
+            .map(|log| log.messages)
+            .unwrap_or_default())
+    }
+}
+
+impl super::traits::ChatRepository for ChatStore {
+    async fn save_chat_message(&self, message: &ChatMessageData) -> Result<(), PersistenceError> {
+        self.record(message)
+    }
+
+    async fn list_chat_messages(
+        &self,
+        session_id: &str,
+    ) -> Result<Vec<ChatMessageData>, PersistenceError> {
+        self.list(session_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_messages_are_kept_per_session_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ChatStore::new(dir.path().to_path_buf());
+        assert!(store.list("s1").unwrap().is_empty());
+
+        for (session_id, seq) in [("s1", 2), ("s1", 1), ("s2", 1)] {
+            store
+                .record(&ChatMessageData {
+                    session_id: session_id.to_string(),
+                    seq,
+                    author: "ana".to_string(),
+                    text: format!("message {}", seq),
+                    sent_at: 100,
+                })
+                .unwrap();
+        }
+        let messages = store.list("s1").unwrap();
+        assert_eq!(messages.iter().map(|m| m.seq).collect::<Vec<_>>(), [1, 2]);
+        assert_eq!(store.list("s2").unwrap().len(), 1);
+    }
+}
@@ -1,5 +1,6 @@
 use super::json_store::{JsonStore, Storable};
 use super::PersistenceError;
+use chess::EngineMoveTelemetry;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
@@ -15,6 +16,12 @@ pub struct StoredMoveRecord {
     pub fen_after: String,
     #[serde(default)]
     pub clock_ms: Option<u64>,
+    /// Search depth, eval, nodes and time the engine used, for engine-played moves.
+    #[serde(default)]
+    pub engine_telemetry: Option<EngineMoveTelemetry>,
+    /// Time the player took over the move, pauses excluded.
+    #[serde(default)]
+    pub think_ms: Option<u64>,
 }
 
 /// Data stored for a completed game eligible for review.
@@ -30,6 +37,17 @@ pub struct FinishedGameData {
     pub move_count: u32,
     pub moves: Vec<StoredMoveRecord>,
     pub created_at: u64,
+    /// Simul the game was played in; games sharing an id form one simul.
+    #[serde(default)]
+    pub simul_id: Option<String>,
+    /// Name of the engine opponent profile the game was played against.
+    #[serde(default)]
+    pub opponent: Option<String>,
+    /// For imported games, the result found from the final position or the
+    /// closing comment, when one could be; differs from `result` when the
+    /// file's Result tag is wrong.
+    #[serde(default)]
+    pub derived_result: Option<String>,
 }
 
 impl Storable for FinishedGameData {
@@ -87,6 +105,15 @@ impl super::traits::FinishedGameRepository for FinishedGameStore {
         self.list()
     }
 
+    async fn list_games_by_simul(
+        &self,
+        simul_id: &str,
+    ) -> Result<Vec<FinishedGameData>, super::PersistenceError> {
+        let mut games = self.list()?;
+        games.retain(|g| g.simul_id.as_deref() == Some(simul_id));
+        Ok(games)
+    }
+
     async fn load_game(
         &self,
         id: &str,
@@ -113,17 +140,53 @@ impl FinishedGameStore {
 }
 
 #[cfg(test)]
-mod tests {
-    use super::*;
-
-    fn sample_finished_game(id: &str, ts: u64) -> FinishedGameData {
-        FinishedGameData {
+impl FinishedGameData {
+    /// A human-vs-engine game from the starting position that White won,
+    /// with no moves. Tests override the fields they care about.
+    pub fn sample(id: &str) -> Self {
+        Self {
             game_id: id.to_string(),
             start_fen: "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".to_string(),
             result: "WhiteWins".to_string(),
             result_reason: "Checkmate".to_string(),
             game_mode: "HumanVsEngine".to_string(),
             human_side: Some("white".to_string()),
+            skill_level: 5,
+            move_count: 0,
+            moves: vec![],
+            created_at: 0,
+            simul_id: None,
+            opponent: None,
+            derived_result: None,
+        }
+    }
+}
+
+#[cfg(test)]
+impl StoredMoveRecord {
+    /// A move with only its SAN filled in.
+    pub fn sample(san: &str) -> Self {
+        Self {
+            from: String::new(),
+            to: String::new(),
+            piece: String::new(),
+            captured: None,
+            promotion: None,
+            san: san.to_string(),
+            fen_after: String::new(),
+            clock_ms: None,
+            engine_telemetry: None,
+            think_ms: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_finished_game(id: &str, ts: u64) -> FinishedGameData {
+        FinishedGameData {
             skill_level: 10,
             move_count: 2,
             moves: vec![
@@ -131,26 +194,21 @@ mod tests {
                     from: "e2".to_string(),
                     to: "e4".to_string(),
                     piece: "P".to_string(),
-                    captured: None,
-                    promotion: None,
-                    san: "e4".to_string(),
                     fen_after: "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"
                         .to_string(),
-                    clock_ms: None,
+                    ..StoredMoveRecord::sample("e4")
                 },
                 StoredMoveRecord {
                     from: "e7".to_string(),
                     to: "e5".to_string(),
                     piece: "P".to_string(),
-                    captured: None,
-                    promotion: None,
-                    san: "e5".to_string(),
                     fen_after: "rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq e6 0 2"
                         .to_string(),
-                    clock_ms: None,
+                    ..StoredMoveRecord::sample("e5")
                 },
             ],
             created_at: ts,
+            ..FinishedGameData::sample(id)
         }
     }
 
@@ -0,0 +1,219 @@
+use super::json_store::{JsonStore, Storable};
+use super::{month_start, week_start, PersistenceError};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// What a goal measures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GoalMetric {
+    /// Mean human accuracy (percent) over reviewed games.
+    AverageAccuracy,
+    GamesPlayed,
+    PuzzlesSolved,
+    ReviewsRead,
+}
+
+impl GoalMetric {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            GoalMetric::AverageAccuracy => "average_accuracy",
+            GoalMetric::GamesPlayed => "games_played",
+            GoalMetric::PuzzlesSolved => "puzzles_solved",
+            GoalMetric::ReviewsRead => "reviews_read",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "average_accuracy" => Some(GoalMetric::AverageAccuracy),
+            "games_played" => Some(GoalMetric::GamesPlayed),
+            "puzzles_solved" => Some(GoalMetric::PuzzlesSolved),
+            "reviews_read" => Some(GoalMetric::ReviewsRead),
+            _ => None,
+        }
+    }
+}
+
+/// The stretch of time a goal's progress is measured over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GoalPeriod {
+    /// The current calendar week, from Monday.
+    Week,
+    /// The current calendar month.
+    Month,
+    AllTime,
+}
+
+impl GoalPeriod {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            GoalPeriod::Week => "week",
+            GoalPeriod::Month => "month",
+            GoalPeriod::AllTime => "all_time",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "week" => Some(GoalPeriod::Week),
+            "month" => Some(GoalPeriod::Month),
+            "all_time" => Some(GoalPeriod::AllTime),
+            _ => None,
+        }
+    }
+
+    /// First day counted towards the period that contains `today`.
+    pub fn start_day(self, today: u32) -> u32 {
+        match self {
+            GoalPeriod::Week => week_start(today),
+            GoalPeriod::Month => month_start(today),
+            GoalPeriod::AllTime => 0,
+        }
+    }
+}
+
+/// A goal set by a player profile.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GoalData {
+    pub goal_id: String,
+    pub profile: String,
+    pub metric: GoalMetric,
+    /// Accuracy percentage or count to reach.
+    pub target: f64,
+    pub period: GoalPeriod,
+    pub created_at: u64,
+}
+
+/// A profile's goals, stored as one JSON file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct GoalListData {
+    profile: String,
+    goals: Vec<GoalData>,
+}
+
+impl Storable for GoalListData {
+    fn id(&self) -> &str {
+        &self.profile
+    }
+}
+
+/// Persistence layer for goals. Uses JSON files in a directory.
+/// Kept as a fallback trait implementation; production uses SqliteGoalRepository.
+#[allow(dead_code)]
+pub struct GoalStore {
+    inner: JsonStore<GoalListData>,
+}
+
+#[allow(dead_code)]
+impl GoalStore {
+    pub fn new(data_dir: PathBuf) -> Self {
+        let dir = data_dir.join("goals");
+        Self {
+            inner: JsonStore::new(dir),
+        }
+    }
+
+    fn load_list(&self, profile: &str) -> Result<GoalListData, PersistenceError> {
+        Ok(self.inner.load(profile)?.unwrap_or_else(|| GoalListData {
+            profile: profile.to_string(),
+            goals: vec![],
+        }))
+    }
+
+    /// Save a goal, replacing one with the same ID.
+    pub fn save(&self, goal: &GoalData) -> Result<(), PersistenceError> {
+        let mut list = self.load_list(&goal.profile)?;
+        list.goals.retain(|g| g.goal_id != goal.goal_id);
+        list.goals.push(goal.clone());
+        list.goals.sort_by_key(|g| g.created_at);
+        self.inner.save(&list)?;
+        Ok(())
+    }
+
+    /// Goals of `profile`, oldest first.
+    pub fn list(&self, profile: &str) -> Result<Vec<GoalData>, PersistenceError> {
+        Ok(self.load_list(profile)?.goals)
+    }
+
+    pub fn delete(&self, profile: &str, goal_id: &str) -> Result<(), PersistenceError> {
+        let mut list = self.load_list(profile)?;
+        list.goals.retain(|g| g.goal_id != goal_id);
+        self.inner.save(&list)?;
+        Ok(())
+    }
+}
+
+impl super::traits::GoalRepository for GoalStore {
+    async fn save_goal(&self, goal: &GoalData) -> Result<(), PersistenceError> {
+        self.save(goal)
+    }
+
+    async fn list_goals(&self, profile: &str) -> Result<Vec<GoalData>, PersistenceError> {
+        self.list(profile)
+    }
+
+    async fn delete_goal(&self, profile: &str, goal_id: &str) -> Result<(), PersistenceError> {
+        self.delete(profile, goal_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn goal(id: &str, profile: &str, created_at: u64) -> GoalData {
+        GoalData {
+            goal_id: id.to_string(),
+            profile: profile.to_string(),
+            metric: GoalMetric::GamesPlayed,
+            target: 20.0,
+            period: GoalPeriod::Month,
+            created_at,
+        }
+    }
+
+    #[test]
+    fn test_metric_and_period_round_trip() {
+        for metric in [
+            GoalMetric::AverageAccuracy,
+            GoalMetric::GamesPlayed,
+            GoalMetric::PuzzlesSolved,
+            GoalMetric::ReviewsRead,
+        ] {
+            assert_eq!(GoalMetric::parse(metric.as_str()), Some(metric));
+        }
+        for period in [GoalPeriod::Week, GoalPeriod::Month, GoalPeriod::AllTime] {
+            assert_eq!(GoalPeriod::parse(period.as_str()), Some(period));
+        }
+        assert_eq!(GoalMetric::parse("elo"), None);
+    }
+
+    #[test]
+    fn test_period_start_day() {
+        // 2024-02-29 (a Thursday) is day 19_782
+        assert_eq!(GoalPeriod::Week.start_day(19_782), 19_779);
+        assert_eq!(GoalPeriod::Month.start_day(19_782), 19_754);
+        assert_eq!(GoalPeriod::AllTime.start_day(19_782), 0);
+    }
+
+    #[test]
+    fn test_goals_are_kept_per_profile() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = GoalStore::new(dir.path().to_path_buf());
+
+        store.save(&goal("g2", "alice", 2)).unwrap();
+        store.save(&goal("g1", "alice", 1)).unwrap();
+        store.save(&goal("g3", "bob", 3)).unwrap();
+
+        let goals = store.list("alice").unwrap();
+        assert_eq!(
+            goals.iter().map(|g| g.goal_id.as_str()).collect::<Vec<_>>(),
+            vec!["g1", "g2"]
+        );
+
+        store.delete("alice", "g1").unwrap();
+        assert_eq!(store.list("alice").unwrap().len(), 1);
+        assert_eq!(store.list("bob").unwrap().len(), 1);
+        assert!(store.list("carol").unwrap().is_empty());
+    }
+}
@@ -7,13 +7,14 @@
 //! finished games, reviews, advanced analyses).
 //!
 //! **Production backend**: SQLite via `sqlx` (see [`sqlite`]). A single database
-//! file holds nine STRICT tables with foreign-key constraints and WAL mode for
+//! file holds sixteen STRICT tables with foreign-key constraints and WAL mode for
 //! concurrent reads. The [`sqlite::Database`] type owns the connection pool and
 //! runs embedded migrations on startup.
 //!
 //! **Test backend**: The original JSON-file stores (`SessionStore`, `PositionStore`,
-//! `FinishedGameStore`) are still compiled under `#[cfg(test)]`. They satisfy the
-//! same traits, keeping unit tests fast and filesystem-isolated.
+//! `FinishedGameStore`, `ActivityStore`, `GoalStore`, `SyncStore`, `AuditStore`, `StudyStore`,
+//! `ChatStore`) are still compiled under `#[cfg(test)]`. They satisfy the same traits,
+//! keeping unit tests fast and filesystem-isolated.
 //!
 //! **Data migration**: [`sqlite::migrate_json_to_sqlite`] is called once on startup.
 //! It reads any existing JSON records from the legacy data directory and inserts
@@ -26,30 +27,57 @@
 //! Concrete type parameters are resolved in `main.rs`, keeping the managers
 //! independent of any specific backend.
 
+mod activity_store;
+mod audit_store;
+mod chat_store;
 mod finished_game_store;
+mod goal_store;
 mod json_store;
 mod position_store;
 mod session_store;
+mod study_store;
+mod sync_store;
 
 pub mod sqlite;
 pub mod traits;
 
 pub(crate) use json_store::{JsonStore, Storable};
 pub use traits::{
-    AdvancedAnalysisRepository, FinishedGameRepository, Persistence, PositionRepository,
-    ReviewRepository, SessionRepository,
+    ActivityRepository, AdvancedAnalysisRepository, AuditRepository, ChatRepository,
+    FinishedGameRepository, GoalRepository, Persistence, PositionRepository, ReviewRepository,
+    SessionRepository, StudyRepository, SyncRepository,
 };
 
+pub use activity_store::{day_of, iso_date, month_start, week_start, ActivityDay, ActivityKind};
+pub use audit_store::{AuditAction, AuditEntry};
+pub use chat_store::ChatMessageData;
 pub use finished_game_store::{FinishedGameData, StoredMoveRecord};
+pub use goal_store::{GoalData, GoalMetric, GoalPeriod};
 pub use position_store::SavedPositionData;
 pub use session_store::SuspendedSessionData;
+pub use study_store::{
+    Arrow, ArrowColor, ChapterNode, StudyChapter, StudyData, StudyFilter, StudyNode,
+};
+pub use sync_store::{SyncKind, SyncRecord};
 
+#[cfg(test)]
+pub use activity_store::ActivityStore;
+#[cfg(test)]
+pub use audit_store::AuditStore;
+#[cfg(test)]
+pub use chat_store::ChatStore;
 #[cfg(test)]
 pub use finished_game_store::FinishedGameStore;
 #[cfg(test)]
+pub use goal_store::GoalStore;
+#[cfg(test)]
 pub use position_store::PositionStore;
 #[cfg(test)]
 pub use session_store::SessionStore;
+#[cfg(test)]
+pub use study_store::StudyStore;
+#[cfg(test)]
+pub use sync_store::SyncStore;
 
 /// Test persistence provider backed by JSON file stores.
 #[cfg(test)]
@@ -62,6 +90,12 @@ impl Persistence for JsonPersistence {
     type FinishedGames = FinishedGameStore;
     type Reviews = crate::review::store::ReviewStore;
     type Advanced = crate::review::advanced::store::AdvancedAnalysisStore;
+    type Activity = ActivityStore;
+    type Goals = GoalStore;
+    type Sync = SyncStore;
+    type Audit = AuditStore;
+    type Studies = StudyStore;
+    type Chat = ChatStore;
 }
 
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -79,6 +113,8 @@ pub enum PersistenceError {
     DefaultPositionProtected,
     #[error("Migration error: {0}")]
     Migration(String),
+    #[error("Encryption error: {0}")]
+    Encryption(String),
 }
 
 /// Generate a unique suspended session ID using timestamp + random suffix.
@@ -108,6 +144,38 @@ pub fn generate_position_id() -> String {
     format!("pos_{}", ts)
 }
 
+/// Generate a unique goal ID.
+pub fn generate_goal_id() -> String {
+    let ts = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    format!("goal_{}", ts)
+}
+
+/// Generate a unique study ID.
+pub fn generate_study_id() -> String {
+    let ts = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    format!("study_{}", ts)
+}
+
+/// Generate a unique study chapter ID.
+pub fn generate_chapter_id() -> String {
+    let ts = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    format!("chapter_{}", ts)
+}
+
+/// Generate the node id a server identifies itself with to sync peers.
+pub fn generate_node_id() -> String {
+    format!("node_{}", uuid::Uuid::new_v4().simple())
+}
+
 /// Get the current unix timestamp in seconds.
 pub fn now_timestamp() -> u64 {
     SystemTime::now()
@@ -14,6 +14,13 @@ pub struct SuspendedSessionData {
     pub human_side: Option<String>,
     pub skill_level: u8,
     pub created_at: u64,
+    /// Name given to a snapshot; suspended sessions have none.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Session or finished game a snapshot was taken from, so snapshots of
+    /// the same game can be listed together.
+    #[serde(default)]
+    pub source_game: Option<String>,
 }
 
 impl Storable for SuspendedSessionData {
@@ -114,6 +121,8 @@ mod tests {
             human_side: Some("white".to_string()),
             skill_level: 10,
             created_at: ts,
+            name: None,
+            source_game: None,
         }
     }
 
@@ -0,0 +1,132 @@
+//! SQLite-backed implementation of [`ActivityRepository`].
+
+use sqlx::SqlitePool;
+
+use crate::persistence::traits::ActivityRepository;
+use crate::persistence::{ActivityDay, ActivityKind, PersistenceError};
+
+/// Row type for activity queries, mapped via `sqlx::FromRow`.
+#[derive(sqlx::FromRow)]
+struct ActivityRow {
+    day: i64,
+    games_played: i64,
+    puzzles_solved: i64,
+    reviews_read: i64,
+}
+
+impl From<ActivityRow> for ActivityDay {
+    fn from(r: ActivityRow) -> Self {
+        Self {
+            day: r.day as u32,
+            games_played: r.games_played as u32,
+            puzzles_solved: r.puzzles_solved as u32,
+            reviews_read: r.reviews_read as u32,
+        }
+    }
+}
+
+pub struct SqliteActivityRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteActivityRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+impl ActivityRepository for SqliteActivityRepository {
+    async fn record_activity(
+        &self,
+        profile: &str,
+        day: u32,
+        kind: ActivityKind,
+    ) -> Result<(), PersistenceError> {
+        // Column names come from this fixed match, never from input.
+        let column = match kind {
+            ActivityKind::GamePlayed => "games_played",
+            ActivityKind::PuzzleSolved => "puzzles_solved",
+            ActivityKind::ReviewRead => "reviews_read",
+        };
+        let sql = format!(
+            "INSERT INTO activity_log (profile, day, {column}) VALUES (?, ?, 1) \
+             ON CONFLICT(profile, day) DO UPDATE SET {column} = {column} + 1"
+        );
+
+        sqlx::query(&sql)
+            .bind(profile)
+            .bind(i64::from(day))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn list_activity(
+        &self,
+        profile: &str,
+        since_day: u32,
+    ) -> Result<Vec<ActivityDay>, PersistenceError> {
+        let rows: Vec<ActivityRow> = sqlx::query_as(
+            "SELECT day, games_played, puzzles_solved, reviews_read \
+             FROM activity_log WHERE profile = ? AND day >= ? ORDER BY day",
+        )
+        .bind(profile)
+        .bind(i64::from(since_day))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(ActivityDay::from).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::sqlite::Database;
+
+    #[tokio::test]
+    async fn test_record_upserts_daily_counts() {
+        let db = Database::new_in_memory().await.unwrap();
+        let repo = SqliteActivityRepository::new(db.pool().clone());
+
+        repo.record_activity("alice", 20, ActivityKind::GamePlayed)
+            .await
+            .unwrap();
+        repo.record_activity("alice", 20, ActivityKind::GamePlayed)
+            .await
+            .unwrap();
+        repo.record_activity("alice", 20, ActivityKind::ReviewRead)
+            .await
+            .unwrap();
+        repo.record_activity("alice", 18, ActivityKind::PuzzleSolved)
+            .await
+            .unwrap();
+        repo.record_activity("bob", 20, ActivityKind::GamePlayed)
+            .await
+            .unwrap();
+
+        let days = repo.list_activity("alice", 0).await.unwrap();
+        assert_eq!(
+            days,
+            vec![
+                ActivityDay {
+                    day: 18,
+                    games_played: 0,
+                    puzzles_solved: 1,
+                    reviews_read: 0,
+                },
+                ActivityDay {
+                    day: 20,
+                    games_played: 2,
+                    puzzles_solved: 0,
+                    reviews_read: 1,
+                },
+            ]
+        );
+
+        let recent = repo.list_activity("alice", 19).await.unwrap();
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].day, 20);
+    }
+}
@@ -8,7 +8,8 @@ use analysis::advanced::types::{
     AdvancedGameAnalysis, AdvancedPositionAnalysis, PsychologicalProfile,
 };
 use analysis::board_analysis::{
-    KingSafetyMetrics, PositionKingSafety, PositionTensionMetrics, TacticalTag,
+    KingSafetyMetrics, MobilityMetrics, PositionKingSafety, PositionMobility,
+    PositionTensionMetrics, TacticalTag,
 };
 
 /// SQLite implementation of [`AdvancedAnalysisRepository`].
@@ -133,6 +134,7 @@ impl AdvancedAnalysisRepository for SqliteAdvancedAnalysisRepository {
                     ks_black_attacker_count, ks_black_attack_weight, \
                     ks_black_attacked_king_zone_sq, ks_black_king_zone_size, \
                     ks_black_exposure_score, \
+                    mobility_white, space_white, mobility_black, space_black, \
                     tactics_before_tags, tactics_after_tags \
              FROM advanced_position_analyses WHERE game_id = ? ORDER BY ply",
         )
@@ -255,6 +257,12 @@ async fn insert_position(
     let ksb_zone_size = pos.king_safety.black.king_zone_size as i32;
     let ksb_exposure = pos.king_safety.black.exposure_score as f64;
 
+    // Mobility
+    let mob_white = pos.mobility.white.mobility as i32;
+    let space_white = pos.mobility.white.space as i32;
+    let mob_black = pos.mobility.black.mobility as i32;
+    let space_black = pos.mobility.black.space as i32;
+
     let tb_tags =
         serde_json::to_string(&pos.tactical_tags_before).unwrap_or_else(|_| "[]".to_string());
     let ta_tags =
@@ -275,8 +283,10 @@ async fn insert_position(
           ks_black_attacker_count, ks_black_attack_weight, \
           ks_black_attacked_king_zone_sq, ks_black_king_zone_size, \
           ks_black_exposure_score, \
+          mobility_white, space_white, mobility_black, space_black, \
           tactics_before_tags, tactics_after_tags) \
-          VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+          VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, \
+                  ?, ?, ?, ?)",
     )
     .bind(game_id)
     .bind(ply)
@@ -303,6 +313,10 @@ async fn insert_position(
     .bind(ksb_zone_sq)
     .bind(ksb_zone_size)
     .bind(ksb_exposure)
+    .bind(mob_white)
+    .bind(space_white)
+    .bind(mob_black)
+    .bind(space_black)
     .bind(tb_tags)
     .bind(ta_tags)
     .execute(&mut **tx)
@@ -424,6 +438,11 @@ struct PositionRow {
     ks_black_attacked_king_zone_sq: i64,
     ks_black_king_zone_size: i64,
     ks_black_exposure_score: f64,
+    // Mobility
+    mobility_white: i64,
+    space_white: i64,
+    mobility_black: i64,
+    space_black: i64,
     // Tactical tags (JSON)
     tactics_before_tags: String,
     tactics_after_tags: String,
@@ -473,6 +492,16 @@ impl PositionRow {
                     exposure_score: self.ks_black_exposure_score as f32,
                 },
             },
+            mobility: PositionMobility {
+                white: MobilityMetrics {
+                    mobility: self.mobility_white as u16,
+                    space: self.space_white as u8,
+                },
+                black: MobilityMetrics {
+                    mobility: self.mobility_black as u16,
+                    space: self.space_black as u8,
+                },
+            },
             tactical_tags_before,
             tactical_tags_after,
         })
@@ -527,6 +556,19 @@ mod tests {
         }
     }
 
+    fn sample_mobility() -> PositionMobility {
+        PositionMobility {
+            white: MobilityMetrics {
+                mobility: 31,
+                space: 9,
+            },
+            black: MobilityMetrics {
+                mobility: 24,
+                space: 5,
+            },
+        }
+    }
+
     fn sample_profile(color: char) -> PsychologicalProfile {
         PsychologicalProfile {
             color,
@@ -556,6 +598,7 @@ mod tests {
                     tactical_tags_after: vec![],
                     king_safety: sample_king_safety(),
                     tension: sample_tension(),
+                    mobility: sample_mobility(),
                     is_critical: false,
                     deep_depth: None,
                 },
@@ -565,6 +608,7 @@ mod tests {
                     tactical_tags_after: vec![],
                     king_safety: sample_king_safety(),
                     tension: sample_tension(),
+                    mobility: sample_mobility(),
                     is_critical: true,
                     deep_depth: Some(22),
                 },
@@ -643,6 +687,9 @@ mod tests {
         let exp = loaded.positions[0].king_safety.black.exposure_score;
         assert!((exp - 0.55).abs() < 0.01, "exposure_score mismatch: {exp}");
 
+        // Mobility
+        assert_eq!(loaded.positions[0].mobility, sample_mobility());
+
         // Tactical tags (empty in sample data)
         assert!(loaded.positions[0].tactical_tags_before.is_empty());
         assert!(loaded.positions[0].tactical_tags_after.is_empty());
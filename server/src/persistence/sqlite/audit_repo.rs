@@ -0,0 +1,104 @@
+//! SQLite-backed implementation of [`AuditRepository`].
+
+use sqlx::SqlitePool;
+
+use crate::persistence::traits::AuditRepository;
+use crate::persistence::{AuditAction, AuditEntry, PersistenceError};
+
+/// Row type for audit log queries, mapped via `sqlx::FromRow`.
+#[derive(sqlx::FromRow)]
+struct AuditRow {
+    at: i64,
+    actor: String,
+    action: String,
+    target: String,
+    detail: String,
+}
+
+impl AuditRow {
+    /// `None` for an action this build does not know.
+    fn into_domain(self) -> Option<AuditEntry> {
+        Some(AuditEntry {
+            action: AuditAction::parse(&self.action)?,
+            at: self.at as u64,
+            actor: self.actor,
+            target: self.target,
+            detail: self.detail,
+        })
+    }
+}
+
+pub struct SqliteAuditRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteAuditRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+impl AuditRepository for SqliteAuditRepository {
+    async fn record_audit(&self, entry: &AuditEntry) -> Result<(), PersistenceError> {
+        sqlx::query(
+            "INSERT INTO audit_log (at, actor, action, target, detail) VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(entry.at as i64)
+        .bind(&entry.actor)
+        .bind(entry.action.as_str())
+        .bind(&entry.target)
+        .bind(&entry.detail)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn list_audit(&self, limit: usize) -> Result<Vec<AuditEntry>, PersistenceError> {
+        let rows: Vec<AuditRow> = sqlx::query_as(
+            "SELECT at, actor, action, target, detail FROM audit_log \
+             ORDER BY entry_id DESC LIMIT ?",
+        )
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().filter_map(AuditRow::into_domain).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::sqlite::Database;
+
+    fn entry(at: u64, action: AuditAction, target: &str) -> AuditEntry {
+        AuditEntry {
+            at,
+            actor: "uid 1000 (pid 42)".to_string(),
+            action,
+            target: target.to_string(),
+            detail: "detail".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_record_and_list() {
+        let db = Database::new_in_memory().await.unwrap();
+        let repo = SqliteAuditRepository::new(db.pool().clone());
+
+        let first = entry(100, AuditAction::Delete, "position pos_1");
+        let second = entry(100, AuditAction::Import, "peer desktop.local");
+        let third = entry(200, AuditAction::ConfigChange, "session abc");
+        for e in [&first, &second, &third] {
+            repo.record_audit(e).await.unwrap();
+        }
+
+        // Entries with the same timestamp keep their insertion order
+        assert_eq!(
+            repo.list_audit(10).await.unwrap(),
+            vec![third.clone(), second.clone(), first]
+        );
+        assert_eq!(repo.list_audit(2).await.unwrap(), vec![third, second]);
+    }
+}
@@ -0,0 +1,107 @@
+//! SQLite-backed implementation of [`ChatRepository`].
+
+use sqlx::SqlitePool;
+
+use crate::persistence::traits::ChatRepository;
+use crate::persistence::{ChatMessageData, PersistenceError};
+
+/// Row type for chat queries, mapped via `sqlx::FromRow`.
+#[derive(sqlx::FromRow)]
+struct ChatRow {
+    session_id: String,
+    seq: i64,
+    author: String,
+    text: String,
+    sent_at: i64,
+}
+
+impl ChatRow {
+    fn into_domain(self) -> ChatMessageData {
+        ChatMessageData {
+            session_id: self.session_id,
+            seq: self.seq as u32,
+            author: self.author,
+            text: self.text,
+            sent_at: self.sent_at as u64,
+        }
+    }
+}
+
+pub struct SqliteChatRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteChatRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+impl ChatRepository for SqliteChatRepository {
+    async fn save_chat_message(&self, message: &ChatMessageData) -> Result<(), PersistenceError> {
+        sqlx::query(
+            "INSERT OR REPLACE INTO chat_messages (session_id, seq, author, text, sent_at) \
+             VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(&message.session_id)
+        .bind(message.seq as i64)
+        .bind(&message.author)
+        .bind(&message.text)
+        .bind(message.sent_at as i64)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn list_chat_messages(
+        &self,
+        session_id: &str,
+    ) -> Result<Vec<ChatMessageData>, PersistenceError> {
+        let rows: Vec<ChatRow> = sqlx::query_as(
+            "SELECT session_id, seq, author, text, sent_at FROM chat_messages \
+             WHERE session_id = ? ORDER BY seq",
+        )
+        .bind(session_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(ChatRow::into_domain).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::sqlite::Database;
+
+    fn message(session_id: &str, seq: u32, text: &str) -> ChatMessageData {
+        ChatMessageData {
+            session_id: session_id.to_string(),
+            seq,
+            author: "ana".to_string(),
+            text: text.to_string(),
+            sent_at: 1_700_000_000,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_save_and_list() {
+        let db = Database::new_in_memory().await.unwrap();
+        let repo = SqliteChatRepository::new(db.pool().clone());
+
+        let second = message("s1", 2, "good move");
+        let first = message("s1", 1, "hello");
+        let other = message("s2", 1, "elsewhere");
+        for m in [&second, &first, &other] {
+            repo.save_chat_message(m).await.unwrap();
+        }
+
+        assert_eq!(
+            repo.list_chat_messages("s1").await.unwrap(),
+            vec![first, second]
+        );
+        assert_eq!(repo.list_chat_messages("s2").await.unwrap(), vec![other]);
+        assert!(repo.list_chat_messages("s3").await.unwrap().is_empty());
+    }
+}
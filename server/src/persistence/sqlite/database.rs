@@ -1,12 +1,73 @@
 //! SQLite database connection pool and migration runner.
+//!
+//! Encrypted databases are supported when the server is built with the
+//! `sqlcipher` feature: [`Database::open_encrypted`] issues `PRAGMA key`
+//! before any other statement on every pooled connection.
 
 use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteSynchronous};
 use sqlx::SqlitePool;
+use std::fmt;
+use std::io::IsTerminal;
 use std::path::Path;
 use std::str::FromStr;
+use zeroize::Zeroizing;
 
 use crate::persistence::PersistenceError;
 
+/// Passphrase used to unlock an encrypted (SQLCipher) database. Wiped from
+/// memory when dropped.
+#[derive(Clone, PartialEq, Eq)]
+pub struct DatabaseKey(Zeroizing<String>);
+
+impl DatabaseKey {
+    /// Create a key from a passphrase. Returns `None` for an empty passphrase.
+    pub fn new(passphrase: impl Into<String>) -> Option<Self> {
+        let passphrase = passphrase.into();
+        if passphrase.is_empty() {
+            None
+        } else {
+            Some(Self(Zeroizing::new(passphrase)))
+        }
+    }
+
+    /// Read the passphrase from a key file. Surrounding whitespace is ignored.
+    pub fn from_file(path: &Path) -> Result<Self, PersistenceError> {
+        let contents = Zeroizing::new(std::fs::read_to_string(path)?);
+        Self::new(contents.trim()).ok_or_else(|| {
+            PersistenceError::Encryption(format!("key file {} is empty", path.display()))
+        })
+    }
+
+    /// Prompt for the passphrase on the controlling terminal, without echoing
+    /// it.
+    ///
+    /// Fails when stdin is not a terminal (e.g. when the server was started
+    /// as a daemon), in which case a key file must be used instead.
+    pub fn prompt() -> Result<Self, PersistenceError> {
+        let stdin = std::io::stdin();
+        if !stdin.is_terminal() {
+            return Err(PersistenceError::Encryption(
+                "cannot prompt for database passphrase: stdin is not a terminal".to_string(),
+            ));
+        }
+
+        let line = Zeroizing::new(rpassword::prompt_password("Database passphrase: ")?);
+        Self::new(line.as_str())
+            .ok_or_else(|| PersistenceError::Encryption("empty database passphrase".to_string()))
+    }
+
+    /// Render the key as a quoted SQL string literal for `PRAGMA key`.
+    fn pragma_value(&self) -> String {
+        format!("'{}'", self.0.replace('\'', "''"))
+    }
+}
+
+impl fmt::Debug for DatabaseKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("DatabaseKey(<redacted>)")
+    }
+}
+
 /// Holds a connection pool to the SQLite database.
 #[derive(Clone)]
 pub struct Database {
@@ -17,6 +78,21 @@ impl Database {
     /// Open (or create) the database at `path`, run migrations, and return
     /// a ready-to-use `Database`.
     pub async fn open(path: &Path) -> Result<Self, PersistenceError> {
+        Self::open_with_key(path, None).await
+    }
+
+    /// Open (or create) an encrypted database at `path`, unlocking it with `key`.
+    ///
+    /// Requires the `sqlcipher` feature; against plain SQLite this returns
+    /// [`PersistenceError::Encryption`] instead of silently writing plaintext.
+    pub async fn open_encrypted(path: &Path, key: &DatabaseKey) -> Result<Self, PersistenceError> {
+        Self::open_with_key(path, Some(key)).await
+    }
+
+    async fn open_with_key(
+        path: &Path,
+        key: Option<&DatabaseKey>,
+    ) -> Result<Self, PersistenceError> {
         // Ensure parent directory exists
         if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent).map_err(PersistenceError::Io)?;
@@ -27,17 +103,56 @@ impl Database {
             .journal_mode(SqliteJournalMode::Wal)
             .synchronous(SqliteSynchronous::Normal)
             .foreign_keys(true);
+        // sqlx always sends `key` before any other pragma, as SQLCipher requires.
+        let options = match key {
+            Some(key) => options.pragma("key", key.pragma_value()),
+            None => options,
+        };
 
         let pool = SqlitePoolOptions::new()
             .max_connections(5)
             .connect_with(options)
-            .await?;
+            .await
+            .map_err(|e| match key {
+                Some(_) => PersistenceError::Encryption(format!(
+                    "failed to unlock database (wrong key?): {}",
+                    e
+                )),
+                None => PersistenceError::Sqlx(e),
+            })?;
 
         let db = Self { pool };
+        if key.is_some() {
+            db.verify_encryption().await?;
+        }
         db.run_migrations().await?;
         Ok(db)
     }
 
+    /// Ensure SQLCipher is linked and the key actually unlocks the file.
+    async fn verify_encryption(&self) -> Result<(), PersistenceError> {
+        let cipher_version: Option<(String,)> = sqlx::query_as("PRAGMA cipher_version")
+            .fetch_optional(&self.pool)
+            .await?;
+        if cipher_version.is_none() {
+            return Err(PersistenceError::Encryption(
+                "server was built without SQLCipher; rebuild with `--features sqlcipher`"
+                    .to_string(),
+            ));
+        }
+
+        sqlx::query("SELECT count(*) FROM sqlite_master")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| {
+                PersistenceError::Encryption(format!(
+                    "failed to unlock database (wrong key?): {}",
+                    e
+                ))
+            })?;
+        Ok(())
+    }
+
     /// Create an in-memory database for testing. Migrations are applied.
     #[cfg(test)]
     pub async fn new_in_memory() -> Result<Self, PersistenceError> {
@@ -101,6 +216,13 @@ mod tests {
         assert!(names.contains(&"stored_moves"));
         assert!(names.contains(&"game_reviews"));
         assert!(names.contains(&"position_reviews"));
+        assert!(names.contains(&"activity_log"));
+        assert!(names.contains(&"goals"));
+        assert!(names.contains(&"sync_node"));
+        assert!(names.contains(&"sync_clocks"));
+        assert!(names.contains(&"audit_log"));
+        assert!(names.contains(&"studies"));
+        assert!(names.contains(&"review_bookmarks"));
     }
 
     #[tokio::test]
@@ -115,4 +237,40 @@ mod tests {
         assert_eq!(row.0, 1);
         assert!(db_path.exists());
     }
+
+    #[test]
+    fn test_database_key_rejects_empty() {
+        assert!(DatabaseKey::new("").is_none());
+    }
+
+    #[test]
+    fn test_database_key_pragma_value_escapes_quotes() {
+        let key = DatabaseKey::new("it's secret").unwrap();
+        assert_eq!(key.pragma_value(), "'it''s secret'");
+    }
+
+    #[test]
+    fn test_database_key_debug_is_redacted() {
+        let key = DatabaseKey::new("hunter2").unwrap();
+        assert!(!format!("{:?}", key).contains("hunter2"));
+    }
+
+    #[test]
+    fn test_database_key_from_file_trims() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("db.key");
+        std::fs::write(&path, "passphrase\n").unwrap();
+        let key = DatabaseKey::from_file(&path).unwrap();
+        assert_eq!(key, DatabaseKey::new("passphrase").unwrap());
+    }
+
+    #[cfg(not(feature = "sqlcipher"))]
+    #[tokio::test]
+    async fn test_open_encrypted_without_sqlcipher_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("encrypted.db");
+        let key = DatabaseKey::new("secret").unwrap();
+        let result = Database::open_encrypted(&db_path, &key).await;
+        assert!(matches!(result, Err(PersistenceError::Encryption(_))));
+    }
 }
@@ -2,7 +2,9 @@
 
 use sqlx::SqlitePool;
 
-use super::helpers::normalize_game_mode;
+use chess::EngineMoveTelemetry;
+
+use super::helpers::{decode_score, encode_score, normalize_game_mode};
 use crate::persistence::traits::FinishedGameRepository;
 use crate::persistence::{FinishedGameData, PersistenceError, StoredMoveRecord};
 
@@ -18,6 +20,9 @@ struct GameRow {
     skill_level: i64,
     move_count: i64,
     created_at: i64,
+    simul_id: Option<String>,
+    opponent: Option<String>,
+    derived_result: Option<String>,
 }
 
 impl GameRow {
@@ -33,6 +38,9 @@ impl GameRow {
             move_count: self.move_count as u32,
             moves,
             created_at: self.created_at as u64,
+            simul_id: self.simul_id,
+            opponent: self.opponent,
+            derived_result: self.derived_result,
         }
     }
 }
@@ -48,10 +56,39 @@ struct MoveRow {
     san: String,
     fen_after: String,
     clock_ms: Option<i64>,
+    engine_depth: Option<i64>,
+    engine_eval_type: Option<String>,
+    engine_eval_value: Option<i64>,
+    engine_nodes: Option<i64>,
+    engine_time_ms: Option<i64>,
+    think_ms: Option<i64>,
+}
+
+impl MoveRow {
+    fn engine_telemetry(&self) -> Option<EngineMoveTelemetry> {
+        let score = match (&self.engine_eval_type, self.engine_eval_value) {
+            (Some(t), Some(v)) => Some(decode_score(t, v as i32)),
+            _ => None,
+        };
+        if self.engine_depth.is_none()
+            && score.is_none()
+            && self.engine_nodes.is_none()
+            && self.engine_time_ms.is_none()
+        {
+            return None;
+        }
+        Some(EngineMoveTelemetry {
+            depth: self.engine_depth.map(|v| v as u32),
+            score,
+            nodes: self.engine_nodes.map(|v| v as u64),
+            time_ms: self.engine_time_ms.map(|v| v as u64),
+        })
+    }
 }
 
 impl From<MoveRow> for StoredMoveRecord {
     fn from(r: MoveRow) -> Self {
+        let engine_telemetry = r.engine_telemetry();
         Self {
             from: r.mv_from,
             to: r.mv_to,
@@ -61,6 +98,8 @@ impl From<MoveRow> for StoredMoveRecord {
             san: r.san,
             fen_after: r.fen_after,
             clock_ms: r.clock_ms.map(|v| v as u64),
+            engine_telemetry,
+            think_ms: r.think_ms.map(|v| v as u64),
         }
     }
 }
@@ -89,8 +128,9 @@ impl FinishedGameRepository for SqliteFinishedGameRepository {
             r#"
             INSERT OR REPLACE INTO finished_games
                 (game_id, start_fen, result, result_reason, game_mode,
-                 human_side, skill_level, move_count, created_at)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+                 human_side, skill_level, move_count, created_at, simul_id, opponent,
+                 derived_result)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(&data.game_id)
@@ -102,6 +142,9 @@ impl FinishedGameRepository for SqliteFinishedGameRepository {
         .bind(skill_level)
         .bind(move_count)
         .bind(created_at)
+        .bind(&data.simul_id)
+        .bind(&data.opponent)
+        .bind(&data.derived_result)
         .execute(&mut *tx)
         .await?;
 
@@ -114,12 +157,22 @@ impl FinishedGameRepository for SqliteFinishedGameRepository {
         for (ply, mv) in data.moves.iter().enumerate() {
             let ply = ply as i64;
             let clock_ms = mv.clock_ms.map(|v| v as i64);
+            let telemetry = mv.engine_telemetry.as_ref();
+            let (eval_type, eval_value) = match telemetry.and_then(|t| t.score.as_ref()) {
+                Some(score) => {
+                    let (t, v) = encode_score(score);
+                    (Some(t), Some(v as i64))
+                }
+                None => (None, None),
+            };
             sqlx::query(
                 r#"
                 INSERT INTO stored_moves
                     (game_id, ply, mv_from, mv_to, piece, captured,
-                     promotion, san, fen_after, clock_ms)
-                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                     promotion, san, fen_after, clock_ms,
+                     engine_depth, engine_eval_type, engine_eval_value,
+                     engine_nodes, engine_time_ms, think_ms)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
                 "#,
             )
             .bind(&data.game_id)
@@ -132,6 +185,12 @@ impl FinishedGameRepository for SqliteFinishedGameRepository {
             .bind(&mv.san)
             .bind(&mv.fen_after)
             .bind(clock_ms)
+            .bind(telemetry.and_then(|t| t.depth).map(|v| v as i64))
+            .bind(eval_type)
+            .bind(eval_value)
+            .bind(telemetry.and_then(|t| t.nodes).map(|v| v as i64))
+            .bind(telemetry.and_then(|t| t.time_ms).map(|v| v as i64))
+            .bind(mv.think_ms.map(|v| v as i64))
             .execute(&mut *tx)
             .await?;
         }
@@ -144,7 +203,8 @@ impl FinishedGameRepository for SqliteFinishedGameRepository {
         let game_rows: Vec<GameRow> = sqlx::query_as(
             r#"
                 SELECT game_id, start_fen, result, result_reason, game_mode,
-                       human_side, skill_level, move_count, created_at
+                       human_side, skill_level, move_count, created_at, simul_id,
+                       opponent, derived_result
                 FROM finished_games
                 ORDER BY created_at DESC
                 "#,
@@ -152,20 +212,36 @@ impl FinishedGameRepository for SqliteFinishedGameRepository {
         .fetch_all(&self.pool)
         .await?;
 
-        let mut games = Vec::with_capacity(game_rows.len());
-        for row in game_rows {
-            let moves = load_moves_for_game(&self.pool, &row.game_id).await?;
-            games.push(row.into_finished_game(moves));
-        }
+        with_moves(&self.pool, game_rows).await
+    }
+
+    async fn list_games_by_simul(
+        &self,
+        simul_id: &str,
+    ) -> Result<Vec<FinishedGameData>, PersistenceError> {
+        let game_rows: Vec<GameRow> = sqlx::query_as(
+            r#"
+                SELECT game_id, start_fen, result, result_reason, game_mode,
+                       human_side, skill_level, move_count, created_at, simul_id,
+                       opponent, derived_result
+                FROM finished_games
+                WHERE simul_id = ?
+                ORDER BY created_at DESC
+                "#,
+        )
+        .bind(simul_id)
+        .fetch_all(&self.pool)
+        .await?;
 
-        Ok(games)
+        with_moves(&self.pool, game_rows).await
     }
 
     async fn load_game(&self, id: &str) -> Result<Option<FinishedGameData>, PersistenceError> {
         let row: Option<GameRow> = sqlx::query_as(
             r#"
                 SELECT game_id, start_fen, result, result_reason, game_mode,
-                       human_side, skill_level, move_count, created_at
+                       human_side, skill_level, move_count, created_at, simul_id,
+                       opponent, derived_result
                 FROM finished_games
                 WHERE game_id = ?
                 "#,
@@ -192,6 +268,19 @@ impl FinishedGameRepository for SqliteFinishedGameRepository {
     }
 }
 
+/// Complete game rows with their moves.
+async fn with_moves(
+    pool: &SqlitePool,
+    rows: Vec<GameRow>,
+) -> Result<Vec<FinishedGameData>, PersistenceError> {
+    let mut games = Vec::with_capacity(rows.len());
+    for row in rows {
+        let moves = load_moves_for_game(pool, &row.game_id).await?;
+        games.push(row.into_finished_game(moves));
+    }
+    Ok(games)
+}
+
 /// Load all moves for a game ordered by ply.
 async fn load_moves_for_game(
     pool: &SqlitePool,
@@ -199,7 +288,9 @@ async fn load_moves_for_game(
 ) -> Result<Vec<StoredMoveRecord>, PersistenceError> {
     let rows: Vec<MoveRow> = sqlx::query_as(
         r#"
-            SELECT mv_from, mv_to, piece, captured, promotion, san, fen_after, clock_ms
+            SELECT mv_from, mv_to, piece, captured, promotion, san, fen_after, clock_ms,
+                   engine_depth, engine_eval_type, engine_eval_value,
+                   engine_nodes, engine_time_ms, think_ms
             FROM stored_moves
             WHERE game_id = ?
             ORDER BY ply
@@ -225,12 +316,6 @@ mod tests {
 
     fn sample_game(id: &str, ts: u64) -> FinishedGameData {
         FinishedGameData {
-            game_id: id.to_string(),
-            start_fen: "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".to_string(),
-            result: "WhiteWins".to_string(),
-            result_reason: "Checkmate".to_string(),
-            game_mode: "HumanVsEngine".to_string(),
-            human_side: Some("white".to_string()),
             skill_level: 10,
             move_count: 2,
             moves: vec![
@@ -238,26 +323,29 @@ mod tests {
                     from: "e2".to_string(),
                     to: "e4".to_string(),
                     piece: "P".to_string(),
-                    captured: None,
-                    promotion: None,
-                    san: "e4".to_string(),
                     fen_after: "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"
                         .to_string(),
                     clock_ms: Some(5000),
+                    ..StoredMoveRecord::sample("e4")
                 },
                 StoredMoveRecord {
                     from: "e7".to_string(),
                     to: "e5".to_string(),
                     piece: "P".to_string(),
-                    captured: None,
-                    promotion: None,
-                    san: "e5".to_string(),
                     fen_after: "rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq e6 0 2"
                         .to_string(),
-                    clock_ms: None,
+                    engine_telemetry: Some(EngineMoveTelemetry {
+                        depth: Some(12),
+                        score: Some(chess::AnalysisScore::Centipawns(-18)),
+                        nodes: Some(84_000),
+                        time_ms: Some(500),
+                    }),
+                    think_ms: Some(2_300),
+                    ..StoredMoveRecord::sample("e5")
                 },
             ],
             created_at: ts,
+            ..FinishedGameData::sample(id)
         }
     }
 
@@ -281,6 +369,11 @@ mod tests {
         assert_eq!(loaded.moves[0].clock_ms, Some(5000));
         assert_eq!(loaded.moves[1].from, "e7");
         assert_eq!(loaded.moves[1].clock_ms, None);
+        assert_eq!(loaded.moves[0].engine_telemetry, None);
+        let telemetry = loaded.moves[1].engine_telemetry.as_ref().unwrap();
+        assert_eq!(telemetry.depth, Some(12));
+        assert_eq!(telemetry.score, Some(chess::AnalysisScore::Centipawns(-18)));
+        assert_eq!(loaded.moves[1].think_ms, Some(2_300));
     }
 
     #[tokio::test]
@@ -330,6 +423,31 @@ mod tests {
         assert_eq!(move_count.0, 0);
     }
 
+    #[tokio::test]
+    async fn test_list_games_by_simul() {
+        let (_db, repo) = test_db().await;
+        for (id, ts, simul) in [
+            ("board_1", 100, Some("simul_a")),
+            ("board_2", 200, Some("simul_a")),
+            ("other", 300, Some("simul_b")),
+            ("casual", 400, None),
+        ] {
+            let mut game = sample_game(id, ts);
+            game.simul_id = simul.map(str::to_string);
+            repo.save_game(&game).await.unwrap();
+        }
+
+        let boards = repo.list_games_by_simul("simul_a").await.unwrap();
+        let ids: Vec<&str> = boards.iter().map(|g| g.game_id.as_str()).collect();
+        assert_eq!(ids, vec!["board_2", "board_1"]);
+        assert_eq!(boards[0].moves.len(), 2);
+        assert!(repo
+            .list_games_by_simul("simul_c")
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
     #[tokio::test]
     async fn test_list_empty() {
         let (_db, repo) = test_db().await;
@@ -347,6 +465,20 @@ mod tests {
         assert_eq!(loaded.game_mode, "HumanVsEngine");
     }
 
+    #[tokio::test]
+    async fn test_labels_roundtrip() {
+        let (_db, repo) = test_db().await;
+        let mut data = sample_game("simul_board", 100);
+        data.simul_id = Some("simul_1".to_string());
+        data.opponent = Some("Stocky 1500".to_string());
+        data.derived_result = Some("Draw".to_string());
+        repo.save_game(&data).await.unwrap();
+        let loaded = repo.load_game("simul_board").await.unwrap().unwrap();
+        assert_eq!(loaded.simul_id.as_deref(), Some("simul_1"));
+        assert_eq!(loaded.opponent.as_deref(), Some("Stocky 1500"));
+        assert_eq!(loaded.derived_result.as_deref(), Some("Draw"));
+    }
+
     #[tokio::test]
     async fn test_save_replace_updates_moves() {
         let (_db, repo) = test_db().await;
@@ -358,11 +490,8 @@ mod tests {
             from: "d2".to_string(),
             to: "d4".to_string(),
             piece: "P".to_string(),
-            captured: None,
-            promotion: None,
-            san: "d4".to_string(),
             fen_after: "rnbqkbnr/pppppppp/8/8/3P4/8/PPP1PPPP/RNBQKBNR b KQkq d3 0 1".to_string(),
-            clock_ms: None,
+            ..StoredMoveRecord::sample("d4")
         }];
         data.move_count = 1;
         repo.save_game(&data).await.unwrap();
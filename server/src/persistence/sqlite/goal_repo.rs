@@ -0,0 +1,151 @@
+//! SQLite-backed implementation of [`GoalRepository`].
+
+use sqlx::SqlitePool;
+
+use crate::persistence::traits::GoalRepository;
+use crate::persistence::{GoalData, GoalMetric, GoalPeriod, PersistenceError};
+
+/// Row type for goal queries, mapped via `sqlx::FromRow`.
+#[derive(sqlx::FromRow)]
+struct GoalRow {
+    goal_id: String,
+    profile: String,
+    metric: String,
+    target: f64,
+    period: String,
+    created_at: i64,
+}
+
+impl GoalRow {
+    /// `None` for a metric or period this build does not know.
+    fn into_domain(self) -> Option<GoalData> {
+        Some(GoalData {
+            metric: GoalMetric::parse(&self.metric)?,
+            period: GoalPeriod::parse(&self.period)?,
+            goal_id: self.goal_id,
+            profile: self.profile,
+            target: self.target,
+            created_at: self.created_at as u64,
+        })
+    }
+}
+
+pub struct SqliteGoalRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteGoalRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+impl GoalRepository for SqliteGoalRepository {
+    async fn save_goal(&self, goal: &GoalData) -> Result<(), PersistenceError> {
+        sqlx::query(
+            "INSERT OR REPLACE INTO goals \
+             (goal_id, profile, metric, target, period, created_at) \
+             VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&goal.goal_id)
+        .bind(&goal.profile)
+        .bind(goal.metric.as_str())
+        .bind(goal.target)
+        .bind(goal.period.as_str())
+        .bind(goal.created_at as i64)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn list_goals(&self, profile: &str) -> Result<Vec<GoalData>, PersistenceError> {
+        let rows: Vec<GoalRow> = sqlx::query_as(
+            "SELECT goal_id, profile, metric, target, period, created_at \
+             FROM goals WHERE profile = ? ORDER BY created_at",
+        )
+        .bind(profile)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                let goal_id = row.goal_id.clone();
+                let goal = row.into_domain();
+                if goal.is_none() {
+                    tracing::warn!(goal_id = %goal_id, "Skipping goal with unknown metric or period");
+                }
+                goal
+            })
+            .collect())
+    }
+
+    async fn delete_goal(&self, profile: &str, goal_id: &str) -> Result<(), PersistenceError> {
+        sqlx::query("DELETE FROM goals WHERE profile = ? AND goal_id = ?")
+            .bind(profile)
+            .bind(goal_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::sqlite::Database;
+
+    fn goal(id: &str, profile: &str, metric: GoalMetric, created_at: u64) -> GoalData {
+        GoalData {
+            goal_id: id.to_string(),
+            profile: profile.to_string(),
+            metric,
+            target: 85.0,
+            period: GoalPeriod::Week,
+            created_at,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_save_list_delete() {
+        let db = Database::new_in_memory().await.unwrap();
+        let repo = SqliteGoalRepository::new(db.pool().clone());
+
+        let accuracy = goal("g1", "alice", GoalMetric::AverageAccuracy, 1);
+        let games = goal("g2", "alice", GoalMetric::GamesPlayed, 2);
+        repo.save_goal(&games).await.unwrap();
+        repo.save_goal(&accuracy).await.unwrap();
+        repo.save_goal(&goal("g3", "bob", GoalMetric::ReviewsRead, 3))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            repo.list_goals("alice").await.unwrap(),
+            vec![accuracy, games.clone()]
+        );
+
+        // Deleting needs the owning profile
+        repo.delete_goal("bob", "g1").await.unwrap();
+        assert_eq!(repo.list_goals("alice").await.unwrap().len(), 2);
+        repo.delete_goal("alice", "g1").await.unwrap();
+        assert_eq!(repo.list_goals("alice").await.unwrap(), vec![games]);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_metric_is_skipped() {
+        let db = Database::new_in_memory().await.unwrap();
+        let repo = SqliteGoalRepository::new(db.pool().clone());
+
+        sqlx::query(
+            "INSERT INTO goals (goal_id, profile, metric, target, period, created_at) \
+             VALUES ('g1', 'alice', 'elo', 1800.0, 'week', 1)",
+        )
+        .execute(db.pool())
+        .await
+        .unwrap();
+
+        assert!(repo.list_goals("alice").await.unwrap().is_empty());
+    }
+}
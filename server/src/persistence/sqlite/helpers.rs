@@ -68,12 +68,14 @@ pub fn decode_status(
 pub fn encode_classification(c: &MoveClassification) -> &'static str {
     match c {
         MoveClassification::Brilliant => "Brilliant",
+        MoveClassification::Great => "Great",
         MoveClassification::Best => "Best",
         MoveClassification::Excellent => "Excellent",
         MoveClassification::Good => "Good",
         MoveClassification::Inaccuracy => "Inaccuracy",
         MoveClassification::Mistake => "Mistake",
         MoveClassification::Blunder => "Blunder",
+        MoveClassification::Miss => "Miss",
         MoveClassification::Forced => "Forced",
         MoveClassification::Book => "Book",
     }
@@ -83,12 +85,14 @@ pub fn encode_classification(c: &MoveClassification) -> &'static str {
 pub fn decode_classification(s: &str) -> MoveClassification {
     match s {
         "Brilliant" => MoveClassification::Brilliant,
+        "Great" => MoveClassification::Great,
         "Best" => MoveClassification::Best,
         "Excellent" => MoveClassification::Excellent,
         "Good" => MoveClassification::Good,
         "Inaccuracy" => MoveClassification::Inaccuracy,
         "Mistake" => MoveClassification::Mistake,
         "Blunder" => MoveClassification::Blunder,
+        "Miss" => MoveClassification::Miss,
         "Forced" => MoveClassification::Forced,
         "Book" => MoveClassification::Book,
         _ => MoveClassification::Good, // safe fallback
@@ -153,12 +157,14 @@ mod tests {
     fn classification_roundtrip() {
         let all = vec![
             MoveClassification::Brilliant,
+            MoveClassification::Great,
             MoveClassification::Best,
             MoveClassification::Excellent,
             MoveClassification::Good,
             MoveClassification::Inaccuracy,
             MoveClassification::Mistake,
             MoveClassification::Blunder,
+            MoveClassification::Miss,
             MoveClassification::Forced,
             MoveClassification::Book,
         ];
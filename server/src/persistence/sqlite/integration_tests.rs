@@ -26,6 +26,8 @@ fn sample_session(id: &str, ts: u64) -> SuspendedSessionData {
         human_side: Some("white".to_string()),
         skill_level: 10,
         created_at: ts,
+        name: None,
+        source_game: None,
     }
 }
 
@@ -45,42 +47,34 @@ fn sample_moves() -> Vec<StoredMoveRecord> {
             from: "e2".to_string(),
             to: "e4".to_string(),
             piece: "P".to_string(),
-            captured: None,
-            promotion: None,
-            san: "e4".to_string(),
             fen_after: "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1".to_string(),
             clock_ms: Some(15_000),
+            ..StoredMoveRecord::sample("e4")
         },
         StoredMoveRecord {
             from: "c7".to_string(),
             to: "c5".to_string(),
             piece: "P".to_string(),
-            captured: None,
-            promotion: None,
-            san: "c5".to_string(),
             fen_after: "rnbqkbnr/pp1ppppp/8/2p5/4P3/8/PPPP1PPP/RNBQKBNR w KQkq c6 0 2".to_string(),
             clock_ms: Some(14_000),
+            ..StoredMoveRecord::sample("c5")
         },
         StoredMoveRecord {
             from: "g1".to_string(),
             to: "f3".to_string(),
             piece: "N".to_string(),
-            captured: None,
-            promotion: None,
-            san: "Nf3".to_string(),
             fen_after: "rnbqkbnr/pp1ppppp/8/2p5/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2".to_string(),
             clock_ms: Some(13_500),
+            ..StoredMoveRecord::sample("Nf3")
         },
         StoredMoveRecord {
             from: "d7".to_string(),
             to: "d6".to_string(),
             piece: "P".to_string(),
-            captured: None,
-            promotion: None,
-            san: "d6".to_string(),
             fen_after: "rnbqkbnr/pp2pppp/3p4/2p5/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 0 3"
                 .to_string(),
             clock_ms: Some(13_000),
+            ..StoredMoveRecord::sample("d6")
         },
     ]
 }
@@ -88,16 +82,11 @@ fn sample_moves() -> Vec<StoredMoveRecord> {
 fn sample_finished_game(id: &str, ts: u64) -> FinishedGameData {
     let moves = sample_moves();
     FinishedGameData {
-        game_id: id.to_string(),
-        start_fen: "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".to_string(),
-        result: "WhiteWins".to_string(),
-        result_reason: "Checkmate".to_string(),
-        game_mode: "HumanVsEngine".to_string(),
-        human_side: Some("white".to_string()),
         skill_level: 12,
         move_count: moves.len() as u32,
         moves,
         created_at: ts,
+        ..FinishedGameData::sample(id)
     }
 }
 
@@ -119,6 +108,8 @@ fn sample_review(game_id: &str) -> GameReview {
             pv: vec!["c5".to_string(), "Nf3".to_string()],
             depth: 18,
             clock_ms: Some(15_000),
+            excluded: None,
+            only_move: false,
         }],
         white_accuracy: Some(96.0),
         black_accuracy: Some(92.0),
@@ -128,6 +119,8 @@ fn sample_review(game_id: &str) -> GameReview {
         started_at: Some(1_000),
         completed_at: Some(2_000),
         winner: Some("White".to_string()),
+        opening: None,
+        endgame: None,
     }
 }
 
@@ -198,6 +191,7 @@ fn sample_analysis(game_id: &str, ts: u64) -> AdvancedGameAnalysis {
             tactical_tags_after: vec![],
             king_safety: sample_king_safety(),
             tension: sample_tension(),
+            mobility: Default::default(),
             is_critical: true,
             deep_depth: Some(22),
         }],
@@ -481,6 +481,8 @@ mod tests {
             human_side: Some("white".to_string()),
             skill_level: 10,
             created_at: ts,
+            name: None,
+            source_game: None,
         }
     }
 
@@ -496,12 +498,6 @@ mod tests {
 
     fn sample_game(game_id: &str, ts: u64) -> FinishedGameData {
         FinishedGameData {
-            game_id: game_id.to_string(),
-            start_fen: "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".to_string(),
-            result: "WhiteWins".to_string(),
-            result_reason: "Checkmate".to_string(),
-            game_mode: "HumanVsEngine".to_string(),
-            human_side: Some("white".to_string()),
             skill_level: 12,
             move_count: 2,
             moves: vec![
@@ -509,26 +505,23 @@ mod tests {
                     from: "e2".to_string(),
                     to: "e4".to_string(),
                     piece: "P".to_string(),
-                    captured: None,
-                    promotion: None,
-                    san: "e4".to_string(),
                     fen_after: "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"
                         .to_string(),
                     clock_ms: Some(15_000),
+                    ..StoredMoveRecord::sample("e4")
                 },
                 StoredMoveRecord {
                     from: "e7".to_string(),
                     to: "e5".to_string(),
                     piece: "P".to_string(),
-                    captured: None,
-                    promotion: None,
-                    san: "e5".to_string(),
                     fen_after: "rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq e6 0 2"
                         .to_string(),
                     clock_ms: Some(14_000),
+                    ..StoredMoveRecord::sample("e5")
                 },
             ],
             created_at: ts,
+            ..FinishedGameData::sample(game_id)
         }
     }
 
@@ -550,6 +543,8 @@ mod tests {
                 pv: vec!["e5".to_string(), "Nf3".to_string()],
                 depth: 18,
                 clock_ms: Some(15_000),
+                excluded: None,
+                only_move: false,
             }],
             white_accuracy: Some(95.0),
             black_accuracy: Some(90.0),
@@ -559,6 +554,8 @@ mod tests {
             started_at: Some(1_000),
             completed_at: Some(2_000),
             winner: Some("White".to_string()),
+            opening: None,
+            endgame: None,
         }
     }
 
@@ -629,6 +626,7 @@ mod tests {
                 tactical_tags_after: vec![],
                 king_safety: sample_king_safety(),
                 tension: sample_tension(),
+                mobility: Default::default(),
                 is_critical: true,
                 deep_depth: Some(22),
             }],
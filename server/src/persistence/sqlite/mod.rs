@@ -7,6 +7,8 @@
 //! - **Foreign keys enabled** — enforced at the connection level.
 //! - **Embedded migrations** — `sqlx::migrate!` runs `migrations/001_initial_schema.sql`
 //!   automatically when [`Database::open`] is called. The schema is idempotent.
+//! - **Optional encryption** — [`Database::open_encrypted`] unlocks a SQLCipher
+//!   database with a [`DatabaseKey`] (requires the `sqlcipher` feature).
 //!
 //! ## Repository types
 //!
@@ -20,6 +22,12 @@
 //! | [`SqliteFinishedGameRepository`] | `FinishedGameRepository` |
 //! | [`SqliteReviewRepository`] | `ReviewRepository` |
 //! | [`SqliteAdvancedAnalysisRepository`] | `AdvancedAnalysisRepository` |
+//! | [`SqliteActivityRepository`] | `ActivityRepository` |
+//! | [`SqliteGoalRepository`] | `GoalRepository` |
+//! | [`SqliteSyncRepository`] | `SyncRepository` |
+//! | [`SqliteAuditRepository`] | `AuditRepository` |
+//! | [`SqliteStudyRepository`] | `StudyRepository` |
+//! | [`SqliteChatRepository`] | `ChatRepository` |
 //!
 //! Enum columns (game status, score classification, move classification) are stored
 //! as `TEXT` and round-tripped through shared encode/decode helpers in [`helpers`].
@@ -30,9 +38,13 @@
 //! JSON records. It is called from `main.rs` before the service starts accepting
 //! requests. Original JSON files are not deleted.
 
+mod activity_repo;
 mod advanced_repo;
+mod audit_repo;
+mod chat_repo;
 mod database;
 mod finished_game_repo;
+mod goal_repo;
 pub(crate) mod helpers;
 #[cfg(test)]
 mod integration_tests;
@@ -40,14 +52,22 @@ mod migrate_json;
 mod position_repo;
 mod review_repo;
 mod session_repo;
+mod study_repo;
+mod sync_repo;
 
+pub use activity_repo::SqliteActivityRepository;
 pub use advanced_repo::SqliteAdvancedAnalysisRepository;
-pub use database::Database;
+pub use audit_repo::SqliteAuditRepository;
+pub use chat_repo::SqliteChatRepository;
+pub use database::{Database, DatabaseKey};
 pub use finished_game_repo::SqliteFinishedGameRepository;
+pub use goal_repo::SqliteGoalRepository;
 pub use migrate_json::migrate_json_to_sqlite;
 pub use position_repo::SqlitePositionRepository;
 pub use review_repo::SqliteReviewRepository;
 pub use session_repo::SqliteSessionRepository;
+pub use study_repo::SqliteStudyRepository;
+pub use sync_repo::SqliteSyncRepository;
 
 /// Production persistence provider backed by SQLite.
 ///
@@ -61,4 +81,10 @@ impl crate::persistence::Persistence for SqlitePersistence {
     type FinishedGames = SqliteFinishedGameRepository;
     type Reviews = SqliteReviewRepository;
     type Advanced = SqliteAdvancedAnalysisRepository;
+    type Activity = SqliteActivityRepository;
+    type Goals = SqliteGoalRepository;
+    type Sync = SqliteSyncRepository;
+    type Audit = SqliteAuditRepository;
+    type Studies = SqliteStudyRepository;
+    type Chat = SqliteChatRepository;
 }
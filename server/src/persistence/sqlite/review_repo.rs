@@ -6,10 +6,15 @@ use super::helpers::{
     decode_classification, decode_score, decode_status, encode_classification, encode_score,
     encode_status,
 };
+use crate::import::IMPORTED_GAME_MODE;
 use crate::persistence::now_timestamp;
 use crate::persistence::traits::ReviewRepository;
 use crate::persistence::PersistenceError;
-use analysis::{GameReview, PositionReview};
+use crate::review::types::ReviewBookmark;
+use analysis::{
+    AccuracyExclusion, EndgameType, GameReview, Opening, PositionReview, ReviewRevision,
+    ReviewStatus,
+};
 
 /// SQLite implementation of [`ReviewRepository`].
 pub struct SqliteReviewRepository {
@@ -38,14 +43,16 @@ impl ReviewRepository for SqliteReviewRepository {
         let created_at = now_timestamp() as i64;
         let started_at = review.started_at.map(|v| v as i64);
         let completed_at = review.completed_at.map(|v| v as i64);
+        let opening = review.opening.as_ref();
 
         sqlx::query(
             r#"
             INSERT OR REPLACE INTO game_reviews
                 (game_id, status, status_current_ply, status_total_plies, status_error,
                  white_accuracy, black_accuracy, total_plies, analyzed_plies, analysis_depth,
-                 created_at, started_at, completed_at, winner)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                 created_at, started_at, completed_at, winner,
+                 opening_eco, opening_name, opening_ply, endgame)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(&review.game_id)
@@ -62,6 +69,10 @@ impl ReviewRepository for SqliteReviewRepository {
         .bind(started_at)
         .bind(completed_at)
         .bind(&review.winner)
+        .bind(opening.map(|o| o.eco.as_str()))
+        .bind(opening.map(|o| o.name.as_str()))
+        .bind(opening.map(|o| o.ply as i64))
+        .bind(review.endgame.map(|e| e.key()))
         .execute(&mut *tx)
         .await?;
 
@@ -75,16 +86,18 @@ impl ReviewRepository for SqliteReviewRepository {
             let pv_json = serde_json::to_string(&position.pv)?;
             let depth = position.depth as i64;
             let clock_ms = position.clock_ms.map(|v| v as i64);
+            let excluded = position.excluded.map(|e| e.key());
+            let only_move = position.only_move as i64;
 
             sqlx::query(
                 r#"
-                INSERT OR IGNORE INTO position_reviews
+                INSERT OR REPLACE INTO position_reviews
                     (game_id, ply, fen, played_san, best_move_san, best_move_uci,
                      eval_before_type, eval_before_value,
                      eval_after_type, eval_after_value,
                      eval_best_type, eval_best_value,
-                     classification, cp_loss, pv, depth, clock_ms)
-                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                     classification, cp_loss, pv, depth, clock_ms, excluded, only_move)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
                 "#,
             )
             .bind(&review.game_id)
@@ -104,6 +117,8 @@ impl ReviewRepository for SqliteReviewRepository {
             .bind(&pv_json)
             .bind(depth)
             .bind(clock_ms)
+            .bind(excluded)
+            .bind(only_move)
             .execute(&mut *tx)
             .await?;
         }
@@ -117,7 +132,8 @@ impl ReviewRepository for SqliteReviewRepository {
             r#"
             SELECT status, status_current_ply, status_total_plies, status_error,
                    white_accuracy, black_accuracy, total_plies, analyzed_plies,
-                   analysis_depth, started_at, completed_at, winner
+                   analysis_depth, started_at, completed_at, winner,
+                   opening_eco, opening_name, opening_ply, endgame
             FROM game_reviews
             WHERE game_id = ?
             "#,
@@ -144,6 +160,19 @@ impl ReviewRepository for SqliteReviewRepository {
         let started_at: Option<i64> = row.get("started_at");
         let completed_at: Option<i64> = row.get("completed_at");
         let winner: Option<String> = row.get("winner");
+        let opening_eco: Option<String> = row.get("opening_eco");
+        let opening_name: Option<String> = row.get("opening_name");
+        let opening_ply: Option<i64> = row.get("opening_ply");
+        let opening = match (opening_eco, opening_name, opening_ply) {
+            (Some(eco), Some(name), Some(ply)) => Some(Opening {
+                eco,
+                name,
+                ply: ply as u32,
+            }),
+            _ => None,
+        };
+        let endgame: Option<String> = row.get("endgame");
+        let endgame = endgame.as_deref().and_then(EndgameType::from_key);
 
         let status = decode_status(
             &status_str,
@@ -158,7 +187,7 @@ impl ReviewRepository for SqliteReviewRepository {
                    eval_before_type, eval_before_value,
                    eval_after_type, eval_after_value,
                    eval_best_type, eval_best_value,
-                   classification, cp_loss, pv, depth, clock_ms
+                   classification, cp_loss, pv, depth, clock_ms, excluded, only_move
             FROM position_reviews
             WHERE game_id = ?
             ORDER BY ply ASC
@@ -186,6 +215,8 @@ impl ReviewRepository for SqliteReviewRepository {
             let pv_json: String = pr.get("pv");
             let depth: i64 = pr.get("depth");
             let clock_ms: Option<i64> = pr.get("clock_ms");
+            let excluded: Option<String> = pr.get("excluded");
+            let only_move: i64 = pr.get("only_move");
 
             let pv: Vec<String> = serde_json::from_str(&pv_json).unwrap_or_default();
 
@@ -203,6 +234,8 @@ impl ReviewRepository for SqliteReviewRepository {
                 pv,
                 depth: depth as u32,
                 clock_ms: clock_ms.map(|v| v as u64),
+                excluded: excluded.as_deref().and_then(AccuracyExclusion::from_key),
+                only_move: only_move != 0,
             });
         }
 
@@ -218,6 +251,8 @@ impl ReviewRepository for SqliteReviewRepository {
             started_at: started_at.map(|v| v as u64),
             completed_at: completed_at.map(|v| v as u64),
             winner,
+            opening,
+            endgame,
         }))
     }
 
@@ -238,8 +273,167 @@ impl ReviewRepository for SqliteReviewRepository {
     }
 
     async fn delete_review(&self, game_id: &str) -> Result<(), PersistenceError> {
+        let mut tx = self.pool.begin().await?;
         sqlx::query("DELETE FROM game_reviews WHERE game_id = ?")
             .bind(game_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM review_revisions WHERE game_id = ?")
+            .bind(game_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM review_bookmarks WHERE game_id = ?")
+            .bind(game_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn list_unfinished_reviews(
+        &self,
+    ) -> Result<Vec<(String, ReviewStatus)>, PersistenceError> {
+        // Matches the partial index on unfinished statuses
+        let rows: Vec<(String, String, Option<i64>, Option<i64>, Option<String>)> = sqlx::query_as(
+            r#"
+                SELECT game_id, status, status_current_ply, status_total_plies, status_error
+                FROM game_reviews
+                WHERE status IN ('Queued', 'Analyzing', 'Failed')
+                "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|(game_id, status, current_ply, total_plies, error)| {
+                let status = decode_status(
+                    &status,
+                    current_ply.map(|v| v as u32),
+                    total_plies.map(|v| v as u32),
+                    error,
+                );
+                (game_id, status)
+            })
+            .collect())
+    }
+
+    async fn list_unreviewed_games(
+        &self,
+        include_imports: bool,
+    ) -> Result<Vec<String>, PersistenceError> {
+        let rows: Vec<(String,)> = sqlx::query_as(
+            r#"
+            SELECT g.game_id
+            FROM finished_games g
+            LEFT JOIN game_reviews r ON r.game_id = g.game_id
+            WHERE r.game_id IS NULL AND (? OR g.game_mode != ?)
+            ORDER BY g.created_at DESC
+            "#,
+        )
+        .bind(include_imports)
+        .bind(IMPORTED_GAME_MODE)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(|(game_id,)| game_id).collect())
+    }
+
+    async fn save_revision(&self, revision: &ReviewRevision) -> Result<(), PersistenceError> {
+        let positions_json = serde_json::to_string(&revision.positions)?;
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO review_revisions
+                (game_id, revision, archived_at, analysis_depth, completed_at,
+                 white_accuracy, black_accuracy, positions)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&revision.game_id)
+        .bind(revision.revision as i64)
+        .bind(revision.archived_at as i64)
+        .bind(revision.analysis_depth as i64)
+        .bind(revision.completed_at.map(|v| v as i64))
+        .bind(revision.white_accuracy)
+        .bind(revision.black_accuracy)
+        .bind(&positions_json)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn list_revisions(&self, game_id: &str) -> Result<Vec<ReviewRevision>, PersistenceError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT revision, archived_at, analysis_depth, completed_at,
+                   white_accuracy, black_accuracy, positions
+            FROM review_revisions
+            WHERE game_id = ?
+            ORDER BY revision ASC
+            "#,
+        )
+        .bind(game_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        use sqlx::Row;
+        let mut revisions = Vec::with_capacity(rows.len());
+        for row in rows {
+            let revision: i64 = row.get("revision");
+            let archived_at: i64 = row.get("archived_at");
+            let analysis_depth: i64 = row.get("analysis_depth");
+            let completed_at: Option<i64> = row.get("completed_at");
+            let positions_json: String = row.get("positions");
+            revisions.push(ReviewRevision {
+                game_id: game_id.to_string(),
+                revision: revision as u32,
+                archived_at: archived_at as u64,
+                analysis_depth: analysis_depth as u32,
+                completed_at: completed_at.map(|v| v as u64),
+                white_accuracy: row.get("white_accuracy"),
+                black_accuracy: row.get("black_accuracy"),
+                positions: serde_json::from_str(&positions_json)?,
+            });
+        }
+        Ok(revisions)
+    }
+
+    async fn save_bookmark(&self, bookmark: &ReviewBookmark) -> Result<(), PersistenceError> {
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO review_bookmarks (game_id, ply, note, created_at)
+            VALUES (?, ?, ?, ?)
+            "#,
+        )
+        .bind(&bookmark.game_id)
+        .bind(bookmark.ply as i64)
+        .bind(&bookmark.note)
+        .bind(bookmark.created_at as i64)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn list_bookmarks(&self, game_id: &str) -> Result<Vec<ReviewBookmark>, PersistenceError> {
+        let rows: Vec<(i64, String, i64)> = sqlx::query_as(
+            "SELECT ply, note, created_at FROM review_bookmarks WHERE game_id = ? ORDER BY ply ASC",
+        )
+        .bind(game_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|(ply, note, created_at)| ReviewBookmark {
+                game_id: game_id.to_string(),
+                ply: ply as u32,
+                note,
+                created_at: created_at as u64,
+            })
+            .collect())
+    }
+
+    async fn delete_bookmark(&self, game_id: &str, ply: u32) -> Result<(), PersistenceError> {
+        sqlx::query("DELETE FROM review_bookmarks WHERE game_id = ? AND ply = ?")
+            .bind(game_id)
+            .bind(ply as i64)
             .execute(&self.pool)
             .await?;
         Ok(())
@@ -287,6 +481,8 @@ mod tests {
             pv: vec!["e5".to_string(), "Nf3".to_string()],
             depth: 18,
             clock_ms: Some(60000),
+            excluded: None,
+            only_move: false,
         }
     }
 
@@ -294,7 +490,16 @@ mod tests {
         GameReview {
             game_id: game_id.to_string(),
             status: ReviewStatus::Complete,
-            positions: vec![sample_position(1), sample_position(2)],
+            positions: vec![
+                PositionReview {
+                    excluded: Some(AccuracyExclusion::Book),
+                    ..sample_position(1)
+                },
+                PositionReview {
+                    only_move: true,
+                    ..sample_position(2)
+                },
+            ],
             white_accuracy: Some(92.5),
             black_accuracy: Some(88.3),
             total_plies: 2,
@@ -303,6 +508,12 @@ mod tests {
             started_at: Some(1000),
             completed_at: Some(2000),
             winner: Some("White".to_string()),
+            opening: Some(Opening {
+                eco: "B00".to_string(),
+                name: "King's Pawn Game".to_string(),
+                ply: 1,
+            }),
+            endgame: Some(EndgameType::Rook),
         }
     }
 
@@ -326,6 +537,8 @@ mod tests {
         assert_eq!(loaded.started_at, review.started_at);
         assert_eq!(loaded.completed_at, review.completed_at);
         assert_eq!(loaded.winner, review.winner);
+        assert_eq!(loaded.opening, review.opening);
+        assert_eq!(loaded.endgame, review.endgame);
 
         let pos = &loaded.positions[0];
         assert_eq!(pos.ply, 1);
@@ -335,6 +548,10 @@ mod tests {
         assert_eq!(pos.cp_loss, 0);
         assert_eq!(pos.pv, vec!["e5".to_string(), "Nf3".to_string()]);
         assert_eq!(pos.clock_ms, Some(60000));
+        assert_eq!(pos.excluded, Some(AccuracyExclusion::Book));
+        assert_eq!(loaded.positions[1].excluded, None);
+        assert!(!pos.only_move);
+        assert!(loaded.positions[1].only_move);
     }
 
     #[tokio::test]
@@ -356,6 +573,8 @@ mod tests {
             started_at: Some(5000),
             completed_at: None,
             winner: None,
+            opening: None,
+            endgame: None,
         };
 
         repo.save_review(&review).await.unwrap();
@@ -435,7 +654,69 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_insert_or_ignore_positions_on_resave() {
+    async fn test_revisions_roundtrip_and_delete_with_review() {
+        let (db, repo) = test_db().await;
+        insert_parent_game(&db, "game_rev").await;
+
+        let review = complete_review("game_rev");
+        repo.save_review(&review).await.unwrap();
+        repo.save_revision(&ReviewRevision::of(&review, 2, 3000))
+            .await
+            .unwrap();
+        repo.save_revision(&ReviewRevision::of(&review, 1, 2500))
+            .await
+            .unwrap();
+
+        let revisions = repo.list_revisions("game_rev").await.unwrap();
+        assert_eq!(
+            revisions.iter().map(|r| r.revision).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        assert_eq!(revisions[1].archived_at, 3000);
+        assert_eq!(revisions[1].positions.len(), 2);
+        assert_eq!(revisions[1].positions[0].pv, vec!["e5", "Nf3"]);
+
+        repo.delete_review("game_rev").await.unwrap();
+        assert!(repo.list_revisions("game_rev").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_bookmarks_roundtrip_and_delete_with_review() {
+        let (db, repo) = test_db().await;
+        insert_parent_game(&db, "game_mark").await;
+        repo.save_review(&complete_review("game_mark"))
+            .await
+            .unwrap();
+
+        let bookmark = |ply: u32, note: &str| ReviewBookmark {
+            game_id: "game_mark".to_string(),
+            ply,
+            note: note.to_string(),
+            created_at: 4000,
+        };
+        repo.save_bookmark(&bookmark(2, "")).await.unwrap();
+        repo.save_bookmark(&bookmark(0, "opening trap"))
+            .await
+            .unwrap();
+        repo.save_bookmark(&bookmark(2, "should take"))
+            .await
+            .unwrap();
+
+        let bookmarks = repo.list_bookmarks("game_mark").await.unwrap();
+        assert_eq!(
+            bookmarks,
+            vec![bookmark(0, "opening trap"), bookmark(2, "should take")]
+        );
+
+        repo.delete_bookmark("game_mark", 0).await.unwrap();
+        assert_eq!(repo.list_bookmarks("game_mark").await.unwrap().len(), 1);
+
+        repo.delete_review("game_mark").await.unwrap();
+        assert!(repo.list_bookmarks("game_mark").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_positions_replaced_on_resave() {
         let (db, repo) = test_db().await;
         insert_parent_game(&db, "game_resave").await;
 
@@ -443,15 +724,17 @@ mod tests {
         let mut review = complete_review("game_resave");
         repo.save_review(&review).await.unwrap();
 
-        // Second save (header update) with same positions + 1 new
+        // Second save (header update) with same positions + 1 new, and
+        // ply 2 re-analysed deeper
         review.analyzed_plies = 3;
         review.positions.push(sample_position(3));
+        review.positions[1].depth = 30;
         repo.save_review(&review).await.unwrap();
 
         let loaded = repo.load_review("game_resave").await.unwrap().unwrap();
-        // The 2 existing positions were ignored (INSERT OR IGNORE), new one added
         assert_eq!(loaded.positions.len(), 3);
         assert_eq!(loaded.analyzed_plies, 3);
+        assert_eq!(loaded.positions[1].depth, 30);
     }
 
     #[tokio::test]
@@ -472,6 +755,8 @@ mod tests {
             started_at: Some(9000),
             completed_at: None,
             winner: None,
+            opening: None,
+            endgame: None,
         };
 
         repo.save_review(&review).await.unwrap();
@@ -507,6 +792,8 @@ mod tests {
             started_at: None,
             completed_at: None,
             winner: Some("White".to_string()),
+            opening: None,
+            endgame: None,
         };
 
         repo.save_review(&review).await.unwrap();
@@ -517,4 +804,63 @@ mod tests {
         assert!(matches!(p.eval_after, AnalysisScore::Mate(-2)));
         assert!(matches!(p.classification, MoveClassification::Brilliant));
     }
+
+    #[tokio::test]
+    async fn test_great_and_miss_classifications_stored() {
+        let (db, repo) = test_db().await;
+        insert_parent_game(&db, "game_classes").await;
+        let mut review = complete_review("game_classes");
+        review.positions[0].classification = MoveClassification::Great;
+        review.positions[1].classification = MoveClassification::Miss;
+
+        repo.save_review(&review).await.unwrap();
+        let loaded = repo.load_review("game_classes").await.unwrap().unwrap();
+        assert_eq!(
+            loaded.positions[0].classification,
+            MoveClassification::Great
+        );
+        assert_eq!(loaded.positions[1].classification, MoveClassification::Miss);
+    }
+
+    #[tokio::test]
+    async fn test_recovery_queries() {
+        let (db, repo) = test_db().await;
+        for game_id in ["done", "running", "broken", "bare"] {
+            insert_parent_game(&db, game_id).await;
+        }
+        sqlx::query("UPDATE finished_games SET game_mode = ? WHERE game_id = 'bare'")
+            .bind(IMPORTED_GAME_MODE)
+            .execute(db.pool())
+            .await
+            .unwrap();
+        insert_parent_game(&db, "unseen").await;
+
+        repo.save_review(&complete_review("done")).await.unwrap();
+        let mut running = complete_review("running");
+        running.status = ReviewStatus::Analyzing {
+            current_ply: 1,
+            total_plies: 2,
+        };
+        repo.save_review(&running).await.unwrap();
+        let mut broken = complete_review("broken");
+        broken.status = ReviewStatus::Failed {
+            error: "engine crashed".to_string(),
+        };
+        repo.save_review(&broken).await.unwrap();
+
+        let mut unfinished = repo.list_unfinished_reviews().await.unwrap();
+        unfinished.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            unfinished,
+            vec![
+                ("broken".to_string(), broken.status),
+                ("running".to_string(), running.status),
+            ]
+        );
+
+        assert_eq!(repo.list_unreviewed_games(false).await.unwrap(), ["unseen"]);
+        let mut unreviewed = repo.list_unreviewed_games(true).await.unwrap();
+        unreviewed.sort();
+        assert_eq!(unreviewed, ["bare", "unseen"]);
+    }
 }
@@ -17,6 +17,8 @@ struct SessionRow {
     human_side: Option<String>,
     skill_level: i64,
     created_at: i64,
+    name: Option<String>,
+    source_game: Option<String>,
 }
 
 impl From<SessionRow> for SuspendedSessionData {
@@ -30,6 +32,8 @@ impl From<SessionRow> for SuspendedSessionData {
             human_side: r.human_side,
             skill_level: r.skill_level as u8,
             created_at: r.created_at as u64,
+            name: r.name,
+            source_game: r.source_game,
         }
     }
 }
@@ -56,8 +60,8 @@ impl SessionRepository for SqliteSessionRepository {
             r#"
             INSERT OR REPLACE INTO suspended_sessions
                 (suspended_id, fen, side_to_move, move_count, game_mode,
-                 human_side, skill_level, created_at)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                 human_side, skill_level, created_at, name, source_game)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(&data.suspended_id)
@@ -68,6 +72,8 @@ impl SessionRepository for SqliteSessionRepository {
         .bind(&data.human_side)
         .bind(skill_level)
         .bind(created_at)
+        .bind(&data.name)
+        .bind(&data.source_game)
         .execute(&self.pool)
         .await?;
 
@@ -78,7 +84,7 @@ impl SessionRepository for SqliteSessionRepository {
         let rows: Vec<SessionRow> = sqlx::query_as(
             r#"
                 SELECT suspended_id, fen, side_to_move, move_count, game_mode,
-                       human_side, skill_level, created_at
+                       human_side, skill_level, created_at, name, source_game
                 FROM suspended_sessions
                 ORDER BY created_at DESC
                 "#,
@@ -96,7 +102,7 @@ impl SessionRepository for SqliteSessionRepository {
         let row: Option<SessionRow> = sqlx::query_as(
             r#"
                 SELECT suspended_id, fen, side_to_move, move_count, game_mode,
-                       human_side, skill_level, created_at
+                       human_side, skill_level, created_at, name, source_game
                 FROM suspended_sessions
                 WHERE suspended_id = ?
                 "#,
@@ -139,6 +145,8 @@ mod tests {
             human_side: Some("white".to_string()),
             skill_level: 10,
             created_at: ts,
+            name: None,
+            source_game: None,
         }
     }
 
@@ -151,6 +159,17 @@ mod tests {
         assert_eq!(loaded, Some(data));
     }
 
+    #[tokio::test]
+    async fn test_snapshot_name_and_source_roundtrip() {
+        let (_db, repo) = test_db().await;
+        let mut data = sample_session("sess_snap", 1000);
+        data.name = Some("vs Stocky 1500, move 12".to_string());
+        data.source_game = Some("sess_live".to_string());
+        repo.save_session(&data).await.unwrap();
+        let loaded = repo.load_session("sess_snap").await.unwrap();
+        assert_eq!(loaded, Some(data));
+    }
+
     #[tokio::test]
     async fn test_load_nonexistent() {
         let (_db, repo) = test_db().await;
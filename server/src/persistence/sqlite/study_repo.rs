@@ -0,0 +1,167 @@
+//! SQLite-backed implementation of [`StudyRepository`].
+
+use sqlx::SqlitePool;
+
+use crate::persistence::traits::StudyRepository;
+use crate::persistence::{PersistenceError, StudyData};
+
+/// Row type for study queries, mapped via `sqlx::FromRow`.
+#[derive(sqlx::FromRow)]
+struct StudyRow {
+    study_id: String,
+    name: String,
+    filter: String,
+    game_ids: String,
+    tree: String,
+    chapters: String,
+    created_at: i64,
+}
+
+impl StudyRow {
+    fn into_domain(self) -> Result<StudyData, PersistenceError> {
+        Ok(StudyData {
+            filter: serde_json::from_str(&self.filter)?,
+            game_ids: serde_json::from_str(&self.game_ids)?,
+            root: serde_json::from_str(&self.tree)?,
+            chapters: serde_json::from_str(&self.chapters)?,
+            study_id: self.study_id,
+            name: self.name,
+            created_at: self.created_at as u64,
+        })
+    }
+}
+
+pub struct SqliteStudyRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteStudyRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+impl StudyRepository for SqliteStudyRepository {
+    async fn save_study(&self, study: &StudyData) -> Result<(), PersistenceError> {
+        sqlx::query(
+            "INSERT OR REPLACE INTO studies \
+             (study_id, name, filter, game_ids, tree, chapters, created_at) \
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&study.study_id)
+        .bind(&study.name)
+        .bind(serde_json::to_string(&study.filter)?)
+        .bind(serde_json::to_string(&study.game_ids)?)
+        .bind(serde_json::to_string(&study.root)?)
+        .bind(serde_json::to_string(&study.chapters)?)
+        .bind(study.created_at as i64)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn load_study(&self, study_id: &str) -> Result<Option<StudyData>, PersistenceError> {
+        let row: Option<StudyRow> = sqlx::query_as(
+            "SELECT study_id, name, filter, game_ids, tree, chapters, created_at \
+             FROM studies WHERE study_id = ?",
+        )
+        .bind(study_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.map(StudyRow::into_domain).transpose()
+    }
+
+    async fn list_studies(&self) -> Result<Vec<StudyData>, PersistenceError> {
+        let rows: Vec<StudyRow> = sqlx::query_as(
+            "SELECT study_id, name, filter, game_ids, tree, chapters, created_at \
+             FROM studies ORDER BY created_at DESC",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter().map(StudyRow::into_domain).collect()
+    }
+
+    async fn delete_study(&self, study_id: &str) -> Result<(), PersistenceError> {
+        sqlx::query("DELETE FROM studies WHERE study_id = ?")
+            .bind(study_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::sqlite::Database;
+    use crate::persistence::{ChapterNode, StudyChapter, StudyFilter, StudyNode};
+
+    fn study(id: &str, created_at: u64) -> StudyData {
+        StudyData {
+            study_id: id.to_string(),
+            name: "French wins".to_string(),
+            filter: StudyFilter {
+                opening: Some("C0".to_string()),
+                ..Default::default()
+            },
+            game_ids: vec!["g1".to_string(), "g2".to_string()],
+            root: StudyNode {
+                games: 2,
+                children: vec![StudyNode {
+                    san: "e4".to_string(),
+                    games: 2,
+                    white_wins: 2,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+            chapters: vec![StudyChapter {
+                chapter_id: "c1".to_string(),
+                name: "From the game".to_string(),
+                game_id: Some("g1".to_string()),
+                nodes: vec![
+                    ChapterNode {
+                        children: vec![1],
+                        ..Default::default()
+                    },
+                    ChapterNode {
+                        id: 1,
+                        parent: Some(0),
+                        san: "e4".to_string(),
+                        uci: "e2e4".to_string(),
+                        nags: vec![1],
+                        ..Default::default()
+                    },
+                ],
+            }],
+            created_at,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_save_load_list_delete() {
+        let db = Database::new_in_memory().await.unwrap();
+        let repo = SqliteStudyRepository::new(db.pool().clone());
+
+        repo.save_study(&study("s1", 1)).await.unwrap();
+        repo.save_study(&study("s2", 2)).await.unwrap();
+
+        assert_eq!(repo.load_study("s1").await.unwrap(), Some(study("s1", 1)));
+        assert!(repo.load_study("missing").await.unwrap().is_none());
+        let ids: Vec<String> = repo
+            .list_studies()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|s| s.study_id)
+            .collect();
+        assert_eq!(ids, vec!["s2", "s1"]);
+
+        repo.delete_study("s2").await.unwrap();
+        assert_eq!(repo.list_studies().await.unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,132 @@
+//! SQLite-backed implementation of [`SyncRepository`].
+
+use sqlx::SqlitePool;
+
+use crate::persistence::traits::SyncRepository;
+use crate::persistence::{generate_node_id, PersistenceError, SyncKind, SyncRecord};
+
+/// Row type for sync clock queries, mapped via `sqlx::FromRow`.
+#[derive(sqlx::FromRow)]
+struct SyncClockRow {
+    kind: String,
+    record_id: String,
+    clock: String,
+    content_hash: Option<i64>,
+}
+
+impl SyncClockRow {
+    /// `None` for a kind this build does not know.
+    fn into_domain(self) -> Result<Option<SyncRecord>, PersistenceError> {
+        let Some(kind) = SyncKind::parse(&self.kind) else {
+            return Ok(None);
+        };
+        Ok(Some(SyncRecord {
+            kind,
+            record_id: self.record_id,
+            clock: serde_json::from_str(&self.clock)?,
+            content_hash: self.content_hash.map(|h| h as u64),
+        }))
+    }
+}
+
+pub struct SqliteSyncRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteSyncRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+impl SyncRepository for SqliteSyncRepository {
+    async fn node_id(&self) -> Result<String, PersistenceError> {
+        sqlx::query("INSERT OR IGNORE INTO sync_node (singleton, node_id) VALUES (1, ?)")
+            .bind(generate_node_id())
+            .execute(&self.pool)
+            .await?;
+
+        let (node_id,): (String,) = sqlx::query_as("SELECT node_id FROM sync_node")
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(node_id)
+    }
+
+    async fn list_sync_records(&self) -> Result<Vec<SyncRecord>, PersistenceError> {
+        let rows: Vec<SyncClockRow> = sqlx::query_as(
+            "SELECT kind, record_id, clock, content_hash FROM sync_clocks ORDER BY kind, record_id",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut records = Vec::with_capacity(rows.len());
+        for row in rows {
+            let kind = row.kind.clone();
+            match row.into_domain()? {
+                Some(record) => records.push(record),
+                None => tracing::warn!(kind = %kind, "Skipping sync record of unknown kind"),
+            }
+        }
+        Ok(records)
+    }
+
+    async fn save_sync_record(&self, record: &SyncRecord) -> Result<(), PersistenceError> {
+        sqlx::query(
+            "INSERT OR REPLACE INTO sync_clocks (kind, record_id, clock, content_hash) \
+             VALUES (?, ?, ?, ?)",
+        )
+        .bind(record.kind.as_str())
+        .bind(&record.record_id)
+        .bind(serde_json::to_string(&record.clock)?)
+        .bind(record.content_hash.map(|h| h as i64))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::sqlite::Database;
+    use std::collections::BTreeMap;
+
+    #[tokio::test]
+    async fn test_node_id_is_stable() {
+        let db = Database::new_in_memory().await.unwrap();
+        let repo = SqliteSyncRepository::new(db.pool().clone());
+
+        let node_id = repo.node_id().await.unwrap();
+        assert!(node_id.starts_with("node_"));
+        assert_eq!(repo.node_id().await.unwrap(), node_id);
+    }
+
+    #[tokio::test]
+    async fn test_save_and_list_records() {
+        let db = Database::new_in_memory().await.unwrap();
+        let repo = SqliteSyncRepository::new(db.pool().clone());
+
+        let game = SyncRecord {
+            kind: SyncKind::FinishedGame,
+            record_id: "game_1".to_string(),
+            clock: BTreeMap::from([("node_a".to_string(), 2), ("node_b".to_string(), 1)]),
+            // Hashes use the full u64 range
+            content_hash: Some(u64::MAX - 1),
+        };
+        let mut review = SyncRecord {
+            kind: SyncKind::Review,
+            record_id: "game_1".to_string(),
+            clock: BTreeMap::from([("node_a".to_string(), 1)]),
+            content_hash: Some(3),
+        };
+        repo.save_sync_record(&game).await.unwrap();
+        repo.save_sync_record(&review).await.unwrap();
+
+        review.content_hash = None;
+        review.clock.insert("node_a".to_string(), 2);
+        repo.save_sync_record(&review).await.unwrap();
+
+        assert_eq!(repo.list_sync_records().await.unwrap(), vec![game, review]);
+    }
+}
@@ -0,0 +1,244 @@
+use super::json_store::{JsonStore, Storable};
+use super::PersistenceError;
+use crate::review::outcome::HumanOutcome;
+use analysis::AnalysisScore;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Which finished games a study is built from. Unset fields match any game.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StudyFilter {
+    /// ECO code prefix (`B2`) or part of the opening name (`Sicilian`),
+    /// ignoring case. Only reviewed games have an opening.
+    pub opening: Option<String>,
+    /// Result for the human player.
+    pub outcome: Option<HumanOutcome>,
+    /// Name of the engine opponent profile.
+    pub opponent: Option<String>,
+    /// Side the human played, `"white"` or `"black"` as finished games
+    /// store it.
+    pub human_side: Option<String>,
+}
+
+/// A move of a study's tree, with the games that played it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StudyNode {
+    /// SAN of the move; empty for the root.
+    pub san: String,
+    /// Position after the move.
+    pub fen: String,
+    pub games: u32,
+    pub white_wins: u32,
+    pub draws: u32,
+    pub black_wins: u32,
+    /// Games whose review covers the move.
+    pub reviewed: u32,
+    /// Average centipawn loss of the move over the reviewed games.
+    pub average_cp_loss: Option<f64>,
+    pub inaccuracies: u32,
+    pub mistakes: u32,
+    pub blunders: u32,
+    /// The engine's choice where the move was an inaccuracy or worse, the
+    /// most suggested one if reviews differ.
+    pub best_san: Option<String>,
+    /// Engine evaluation after the move, from the first review covering it.
+    pub eval: Option<AnalysisScore>,
+    /// Moves played next, most played first.
+    pub children: Vec<StudyNode>,
+}
+
+/// Colours an arrow can be drawn in, as Lichess offers them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ArrowColor {
+    #[default]
+    Green,
+    Red,
+    Yellow,
+    Blue,
+}
+
+/// An arrow drawn on the board of a chapter's position.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Arrow {
+    /// Squares in algebraic notation, e.g. `e2`.
+    pub from: String,
+    pub to: String,
+    pub color: ArrowColor,
+}
+
+/// A position of a chapter's move tree with what the author wrote about it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChapterNode {
+    /// Unique within the chapter and kept when other nodes are deleted;
+    /// the root is 0.
+    pub id: u32,
+    /// `None` for the root.
+    pub parent: Option<u32>,
+    /// SAN and UCI of the move leading here; empty for the root.
+    pub san: String,
+    pub uci: String,
+    /// Position after the move.
+    pub fen: String,
+    pub comment: Option<String>,
+    pub nags: Vec<u8>,
+    pub arrows: Vec<Arrow>,
+    /// Ids of the moves from here; the first continues the main line.
+    pub children: Vec<u32>,
+}
+
+/// A chapter of a study: a move tree from a position or a finished game.
+/// Nodes are kept flat, so a long game is not a deeply nested document.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StudyChapter {
+    pub chapter_id: String,
+    pub name: String,
+    /// The finished game the chapter was copied from, if any.
+    pub game_id: Option<String>,
+    /// The root first, then the other nodes in the order they were added.
+    pub nodes: Vec<ChapterNode>,
+}
+
+/// A merged variation tree of the games matching a filter, with chapters
+/// written by hand. The tree is a snapshot: games finished or deleted later
+/// do not change it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StudyData {
+    pub study_id: String,
+    pub name: String,
+    pub filter: StudyFilter,
+    /// Games merged into the tree, oldest first.
+    pub game_ids: Vec<String>,
+    /// The start position; its children are the games' first moves.
+    pub root: StudyNode,
+    /// In the order they are shown and exported.
+    #[serde(default)]
+    pub chapters: Vec<StudyChapter>,
+    pub created_at: u64,
+}
+
+impl Storable for StudyData {
+    fn id(&self) -> &str {
+        &self.study_id
+    }
+}
+
+/// Persistence layer for studies. Uses JSON files in a directory.
+/// Kept as a fallback trait implementation; production uses SqliteStudyRepository.
+#[allow(dead_code)]
+pub struct StudyStore {
+    inner: JsonStore<StudyData>,
+}
+
+#[allow(dead_code)]
+impl StudyStore {
+    pub fn new(data_dir: PathBuf) -> Self {
+        let dir = data_dir.join("studies");
+        Self {
+            inner: JsonStore::new(dir),
+        }
+    }
+
+    pub fn save(&self, study: &StudyData) -> Result<(), PersistenceError> {
+        self.inner.save(study)?;
+        Ok(())
+    }
+
+    pub fn load(&self, study_id: &str) -> Result<Option<StudyData>, PersistenceError> {
+        self.inner.load(study_id)
+    }
+
+    /// All studies, newest first.
+    pub fn list(&self) -> Result<Vec<StudyData>, PersistenceError> {
+        let mut studies = self.inner.load_all()?;
+        studies.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        Ok(studies)
+    }
+
+    pub fn delete(&self, study_id: &str) -> Result<(), PersistenceError> {
+        self.inner.delete(study_id)
+    }
+}
+
+impl super::traits::StudyRepository for StudyStore {
+    async fn save_study(&self, study: &StudyData) -> Result<(), PersistenceError> {
+        self.save(study)
+    }
+
+    async fn load_study(&self, study_id: &str) -> Result<Option<StudyData>, PersistenceError> {
+        self.load(study_id)
+    }
+
+    async fn list_studies(&self) -> Result<Vec<StudyData>, PersistenceError> {
+        self.list()
+    }
+
+    async fn delete_study(&self, study_id: &str) -> Result<(), PersistenceError> {
+        self.delete(study_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn study(id: &str, created_at: u64) -> StudyData {
+        StudyData {
+            study_id: id.to_string(),
+            name: "Sicilian losses".to_string(),
+            filter: StudyFilter {
+                opening: Some("Sicilian".to_string()),
+                outcome: Some(HumanOutcome::Loss),
+                ..Default::default()
+            },
+            game_ids: vec!["g1".to_string()],
+            root: StudyNode {
+                games: 1,
+                children: vec![StudyNode {
+                    san: "e4".to_string(),
+                    games: 1,
+                    eval: Some(AnalysisScore::Centipawns(30)),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+            chapters: vec![StudyChapter {
+                chapter_id: "chapter_1".to_string(),
+                name: "Main line".to_string(),
+                game_id: None,
+                nodes: vec![ChapterNode {
+                    comment: Some("Open Sicilian".to_string()),
+                    arrows: vec![Arrow {
+                        from: "e2".to_string(),
+                        to: "e4".to_string(),
+                        color: ArrowColor::Red,
+                    }],
+                    ..Default::default()
+                }],
+            }],
+            created_at,
+        }
+    }
+
+    #[test]
+    fn test_save_list_delete() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = StudyStore::new(dir.path().to_path_buf());
+
+        store.save(&study("s1", 1)).unwrap();
+        store.save(&study("s2", 2)).unwrap();
+
+        let studies = store.list().unwrap();
+        assert_eq!(
+            studies
+                .iter()
+                .map(|s| s.study_id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["s2", "s1"]
+        );
+        assert_eq!(store.load("s1").unwrap(), Some(study("s1", 1)));
+
+        store.delete("s1").unwrap();
+        assert!(store.load("s1").unwrap().is_none());
+        assert_eq!(store.list().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,157 @@
+use super::json_store::{JsonStore, Storable};
+use super::{generate_node_id, PersistenceError};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Kind of record exchanged with sync peers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum SyncKind {
+    FinishedGame,
+    Review,
+}
+
+impl SyncKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SyncKind::FinishedGame => "finished_game",
+            SyncKind::Review => "review",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "finished_game" => Some(SyncKind::FinishedGame),
+            "review" => Some(SyncKind::Review),
+            _ => None,
+        }
+    }
+}
+
+/// Sync bookkeeping for one record.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SyncRecord {
+    pub kind: SyncKind,
+    pub record_id: String,
+    /// Version vector: node id to the number of changes made on that node.
+    pub clock: BTreeMap<String, u64>,
+    /// Hash of the content the clock describes; `None` once deleted.
+    pub content_hash: Option<u64>,
+}
+
+/// Node id and all sync records, stored as one JSON file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SyncStateData {
+    id: String,
+    node_id: String,
+    records: Vec<SyncRecord>,
+}
+
+impl Storable for SyncStateData {
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+const SYNC_STATE_ID: &str = "state";
+
+/// Persistence layer for sync state. Uses a JSON file in a directory.
+/// Kept as a fallback trait implementation; production uses SqliteSyncRepository.
+#[allow(dead_code)]
+pub struct SyncStore {
+    inner: JsonStore<SyncStateData>,
+}
+
+#[allow(dead_code)]
+impl SyncStore {
+    pub fn new(data_dir: PathBuf) -> Self {
+        let dir = data_dir.join("sync");
+        Self {
+            inner: JsonStore::new(dir),
+        }
+    }
+
+    fn load_state(&self) -> Result<SyncStateData, PersistenceError> {
+        match self.inner.load(SYNC_STATE_ID)? {
+            Some(state) => Ok(state),
+            None => {
+                let state = SyncStateData {
+                    id: SYNC_STATE_ID.to_string(),
+                    node_id: generate_node_id(),
+                    records: vec![],
+                };
+                self.inner.save(&state)?;
+                Ok(state)
+            }
+        }
+    }
+
+    /// This server's node id, created on first use.
+    pub fn node_id(&self) -> Result<String, PersistenceError> {
+        Ok(self.load_state()?.node_id)
+    }
+
+    pub fn list(&self) -> Result<Vec<SyncRecord>, PersistenceError> {
+        Ok(self.load_state()?.records)
+    }
+
+    /// Save a record, replacing the one for the same kind and id.
+    pub fn save(&self, record: &SyncRecord) -> Result<(), PersistenceError> {
+        let mut state = self.load_state()?;
+        state
+            .records
+            .retain(|r| r.kind != record.kind || r.record_id != record.record_id);
+        state.records.push(record.clone());
+        self.inner.save(&state)?;
+        Ok(())
+    }
+}
+
+impl super::traits::SyncRepository for SyncStore {
+    async fn node_id(&self) -> Result<String, PersistenceError> {
+        SyncStore::node_id(self)
+    }
+
+    async fn list_sync_records(&self) -> Result<Vec<SyncRecord>, PersistenceError> {
+        self.list()
+    }
+
+    async fn save_sync_record(&self, record: &SyncRecord) -> Result<(), PersistenceError> {
+        self.save(record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kind_round_trip() {
+        for kind in [SyncKind::FinishedGame, SyncKind::Review] {
+            assert_eq!(SyncKind::parse(kind.as_str()), Some(kind));
+        }
+        assert_eq!(SyncKind::parse("puzzle"), None);
+    }
+
+    #[test]
+    fn test_node_id_is_stable_and_records_replace() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SyncStore::new(dir.path().to_path_buf());
+
+        let node_id = store.node_id().unwrap();
+        assert_eq!(store.node_id().unwrap(), node_id);
+
+        let mut record = SyncRecord {
+            kind: SyncKind::Review,
+            record_id: "game_1".to_string(),
+            clock: BTreeMap::from([(node_id.clone(), 1)]),
+            content_hash: Some(7),
+        };
+        store.save(&record).unwrap();
+        record.clock.insert(node_id.clone(), 2);
+        record.content_hash = None;
+        store.save(&record).unwrap();
+
+        assert_eq!(store.list().unwrap(), vec![record]);
+    }
+}
@@ -8,8 +8,12 @@
 //! the futures are guaranteed `Send` — required by tonic's `#[async_trait]`
 //! and `tokio::spawn`.
 
-use super::{FinishedGameData, PersistenceError, SavedPositionData, SuspendedSessionData};
-use analysis::{AdvancedGameAnalysis, GameReview};
+use super::{
+    ActivityDay, ActivityKind, AuditEntry, ChatMessageData, FinishedGameData, GoalData,
+    PersistenceError, SavedPositionData, StudyData, SuspendedSessionData, SyncRecord,
+};
+use crate::review::types::ReviewBookmark;
+use analysis::{AdvancedGameAnalysis, GameReview, ReviewRevision, ReviewStatus};
 use std::future::Future;
 
 /// Repository for suspended chess sessions.
@@ -61,6 +65,11 @@ pub trait FinishedGameRepository: Send + Sync {
     fn list_games(
         &self,
     ) -> impl Future<Output = Result<Vec<FinishedGameData>, PersistenceError>> + Send;
+    /// The boards of one simul, most recent first.
+    fn list_games_by_simul(
+        &self,
+        simul_id: &str,
+    ) -> impl Future<Output = Result<Vec<FinishedGameData>, PersistenceError>> + Send;
     fn load_game(
         &self,
         id: &str,
@@ -72,6 +81,8 @@ pub trait FinishedGameRepository: Send + Sync {
 ///
 /// Stores both the review header and per-position analysis data.
 /// Implementations must guarantee atomic save/load of the full aggregate.
+/// Earlier passes of a review are kept as revisions, and the player's
+/// bookmarks on the game's positions alongside; both are deleted with it.
 pub trait ReviewRepository: Send + Sync {
     fn save_review(
         &self,
@@ -84,10 +95,45 @@ pub trait ReviewRepository: Send + Sync {
     fn list_reviews(
         &self,
     ) -> impl Future<Output = Result<Vec<GameReview>, PersistenceError>> + Send;
+    /// Game ids and statuses of the reviews that are queued, being analysed
+    /// or failed, without their positions.
+    fn list_unfinished_reviews(
+        &self,
+    ) -> impl Future<Output = Result<Vec<(String, ReviewStatus)>, PersistenceError>> + Send;
+    /// Ids of the finished games with no review, most recent first.
+    /// Imported games are left out unless `include_imports`.
+    fn list_unreviewed_games(
+        &self,
+        include_imports: bool,
+    ) -> impl Future<Output = Result<Vec<String>, PersistenceError>> + Send;
     fn delete_review(
         &self,
         game_id: &str,
     ) -> impl Future<Output = Result<(), PersistenceError>> + Send;
+    fn save_revision(
+        &self,
+        revision: &ReviewRevision,
+    ) -> impl Future<Output = Result<(), PersistenceError>> + Send;
+    /// A game's revisions, oldest first.
+    fn list_revisions(
+        &self,
+        game_id: &str,
+    ) -> impl Future<Output = Result<Vec<ReviewRevision>, PersistenceError>> + Send;
+    /// Save a bookmark, replacing the one on the same ply.
+    fn save_bookmark(
+        &self,
+        bookmark: &ReviewBookmark,
+    ) -> impl Future<Output = Result<(), PersistenceError>> + Send;
+    /// A game's bookmarks, in ply order.
+    fn list_bookmarks(
+        &self,
+        game_id: &str,
+    ) -> impl Future<Output = Result<Vec<ReviewBookmark>, PersistenceError>> + Send;
+    fn delete_bookmark(
+        &self,
+        game_id: &str,
+        ply: u32,
+    ) -> impl Future<Output = Result<(), PersistenceError>> + Send;
 }
 
 /// Repository for advanced game analyses (tension, king safety, tactics).
@@ -109,6 +155,106 @@ pub trait AdvancedAnalysisRepository: Send + Sync {
     ) -> impl Future<Output = Result<(), PersistenceError>> + Send;
 }
 
+/// Repository for per-profile daily activity counts.
+///
+/// Recording is an upsert: the first activity of a day creates its row.
+pub trait ActivityRepository: Send + Sync {
+    fn record_activity(
+        &self,
+        profile: &str,
+        day: u32,
+        kind: ActivityKind,
+    ) -> impl Future<Output = Result<(), PersistenceError>> + Send;
+    /// Days with activity from `since_day` onwards, oldest first.
+    fn list_activity(
+        &self,
+        profile: &str,
+        since_day: u32,
+    ) -> impl Future<Output = Result<Vec<ActivityDay>, PersistenceError>> + Send;
+}
+
+/// Repository for goals set by player profiles.
+pub trait GoalRepository: Send + Sync {
+    /// Insert a goal, or replace the one with the same ID.
+    fn save_goal(
+        &self,
+        goal: &GoalData,
+    ) -> impl Future<Output = Result<(), PersistenceError>> + Send;
+    /// Goals of `profile`, oldest first.
+    fn list_goals(
+        &self,
+        profile: &str,
+    ) -> impl Future<Output = Result<Vec<GoalData>, PersistenceError>> + Send;
+    fn delete_goal(
+        &self,
+        profile: &str,
+        goal_id: &str,
+    ) -> impl Future<Output = Result<(), PersistenceError>> + Send;
+}
+
+/// Repository for server-to-server sync state: this node's id and the
+/// version vector of every synced record.
+pub trait SyncRepository: Send + Sync {
+    /// This server's node id, created on first use.
+    fn node_id(&self) -> impl Future<Output = Result<String, PersistenceError>> + Send;
+    fn list_sync_records(
+        &self,
+    ) -> impl Future<Output = Result<Vec<SyncRecord>, PersistenceError>> + Send;
+    /// Insert a record, or replace the one for the same kind and id.
+    fn save_sync_record(
+        &self,
+        record: &SyncRecord,
+    ) -> impl Future<Output = Result<(), PersistenceError>> + Send;
+}
+
+/// Repository for the audit log of destructive operations, imports and
+/// config changes.
+pub trait AuditRepository: Send + Sync {
+    fn record_audit(
+        &self,
+        entry: &AuditEntry,
+    ) -> impl Future<Output = Result<(), PersistenceError>> + Send;
+    /// The `limit` most recent entries, newest first.
+    fn list_audit(
+        &self,
+        limit: usize,
+    ) -> impl Future<Output = Result<Vec<AuditEntry>, PersistenceError>> + Send;
+}
+
+/// Repository for studies built from finished games.
+pub trait StudyRepository: Send + Sync {
+    /// Insert a study, or replace the one with the same ID.
+    fn save_study(
+        &self,
+        study: &StudyData,
+    ) -> impl Future<Output = Result<(), PersistenceError>> + Send;
+    fn load_study(
+        &self,
+        study_id: &str,
+    ) -> impl Future<Output = Result<Option<StudyData>, PersistenceError>> + Send;
+    /// All studies, newest first.
+    fn list_studies(&self)
+        -> impl Future<Output = Result<Vec<StudyData>, PersistenceError>> + Send;
+    fn delete_study(
+        &self,
+        study_id: &str,
+    ) -> impl Future<Output = Result<(), PersistenceError>> + Send;
+}
+
+/// Repository for the chat of sessions, kept after they close.
+pub trait ChatRepository: Send + Sync {
+    /// Insert a message, or replace the one with the same session and seq.
+    fn save_chat_message(
+        &self,
+        message: &ChatMessageData,
+    ) -> impl Future<Output = Result<(), PersistenceError>> + Send;
+    /// A session's messages, oldest first.
+    fn list_chat_messages(
+        &self,
+        session_id: &str,
+    ) -> impl Future<Output = Result<Vec<ChatMessageData>, PersistenceError>> + Send;
+}
+
 /// Bundles all repository types into a single generic parameter.
 ///
 /// Instead of `Foo<S, P, F, R, A>` with 5 type params and repeated where-clauses,
@@ -123,4 +269,10 @@ pub trait Persistence: Send + Sync + 'static {
     type FinishedGames: FinishedGameRepository + Send + Sync + 'static;
     type Reviews: ReviewRepository + Send + Sync + 'static;
     type Advanced: AdvancedAnalysisRepository + Send + Sync + 'static;
+    type Activity: ActivityRepository + Send + Sync + 'static;
+    type Goals: GoalRepository + Send + Sync + 'static;
+    type Sync: SyncRepository + Send + Sync + 'static;
+    type Audit: AuditRepository + Send + Sync + 'static;
+    type Studies: StudyRepository + Send + Sync + 'static;
+    type Chat: ChatRepository + Send + Sync + 'static;
 }
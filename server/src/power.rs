@@ -0,0 +1,258 @@
+//! Power awareness.
+//!
+//! On battery the server runs session engines with one thread and a small
+//! hash and holds background reviews; back on mains power both return to
+//! their full settings. Whether the machine is on battery is read from
+//! `/sys/class/power_supply` on Linux and `pmset` on macOS, polled every
+//! [`POWER_POLL_INTERVAL`].
+
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::watch;
+
+use crate::persistence::Persistence;
+use crate::review::ReviewManager;
+use crate::session::SessionManager;
+
+/// Threads a session engine runs with in low-power mode.
+pub const LOW_POWER_THREADS: u32 = 1;
+
+/// Hash, in MB, a session engine holds in low-power mode.
+pub const LOW_POWER_HASH_MB: u32 = 16;
+
+/// How often the power source is checked in [`PowerPolicy::Auto`].
+pub const POWER_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+const POWER_SUPPLY_DIR: &str = "/sys/class/power_supply";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerSource {
+    Ac,
+    Battery,
+    /// No battery found, or the platform cannot tell.
+    Unknown,
+}
+
+impl PowerSource {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Ac => "ac",
+            Self::Battery => "battery",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+/// When the server runs in low-power mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerPolicy {
+    /// Never; the power source is only shown.
+    Off,
+    /// While the machine is on battery.
+    Auto,
+    /// Always, e.g. with `--low-power`.
+    On,
+}
+
+impl PowerPolicy {
+    pub fn is_low_power(&self, source: PowerSource) -> bool {
+        match self {
+            Self::Off => false,
+            Self::Auto => source == PowerSource::Battery,
+            Self::On => true,
+        }
+    }
+}
+
+/// The power source last seen and whether the server runs in low-power
+/// mode because of it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PowerState {
+    pub source: PowerSource,
+    pub low_power: bool,
+}
+
+impl PowerState {
+    pub fn new(policy: PowerPolicy, source: PowerSource) -> Self {
+        Self {
+            source,
+            low_power: policy.is_low_power(source),
+        }
+    }
+}
+
+/// Threads and hash a session engine runs with, from the ones it was
+/// configured with.
+pub fn engine_resources(threads: u32, hash_mb: u32, low_power: bool) -> (u32, u32) {
+    if low_power {
+        (
+            threads.min(LOW_POWER_THREADS),
+            hash_mb.min(LOW_POWER_HASH_MB),
+        )
+    } else {
+        (threads, hash_mb)
+    }
+}
+
+/// Read where the machine draws its power from.
+pub fn detect_power_source() -> PowerSource {
+    if cfg!(target_os = "macos") {
+        return std::process::Command::new("pmset")
+            .args(["-g", "batt"])
+            .output()
+            .map(|out| parse_pmset(&String::from_utf8_lossy(&out.stdout)))
+            .unwrap_or(PowerSource::Unknown);
+    }
+    read_power_supplies(Path::new(POWER_SUPPLY_DIR))
+}
+
+/// The source `pmset -g batt` names on its first line, e.g.
+/// `Now drawing from 'Battery Power'`.
+fn parse_pmset(output: &str) -> PowerSource {
+    let first = output.lines().next().unwrap_or_default();
+    if first.contains("'AC Power'") {
+        PowerSource::Ac
+    } else if first.contains("'Battery Power'") {
+        PowerSource::Battery
+    } else {
+        PowerSource::Unknown
+    }
+}
+
+/// Each supply under `dir` has a `type` file; a mains supply reports
+/// whether it is plugged in with `online`, a battery whether it is running
+/// down with `status`. A plugged-in mains supply wins over a battery.
+fn read_power_supplies(dir: &Path) -> PowerSource {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return PowerSource::Unknown;
+    };
+    let read = |path: &Path, name: &str| {
+        std::fs::read_to_string(path.join(name))
+            .map(|s| s.trim().to_string())
+            .unwrap_or_default()
+    };
+    let mut source = PowerSource::Unknown;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        match read(&path, "type").as_str() {
+            "Mains" if read(&path, "online") == "1" => return PowerSource::Ac,
+            "Battery" => {
+                source = match read(&path, "status").as_str() {
+                    "Discharging" => PowerSource::Battery,
+                    _ => PowerSource::Ac,
+                };
+            }
+            _ => {}
+        }
+    }
+    source
+}
+
+/// Apply `policy` now and, in [`PowerPolicy::Auto`], again whenever the
+/// power source changes. The receiver follows the state for the server
+/// status.
+pub fn spawn_power_monitor<D: Persistence>(
+    policy: PowerPolicy,
+    session_manager: Arc<SessionManager<D>>,
+    review_manager: Arc<ReviewManager<D>>,
+) -> watch::Receiver<PowerState> {
+    let source = detect_power_source();
+    let initial = PowerState::new(policy, source);
+    let (state_tx, state_rx) = watch::channel(initial);
+    tracing::info!(
+        ?policy,
+        source = source.name(),
+        low_power = initial.low_power,
+        "Power mode"
+    );
+
+    tokio::spawn(async move {
+        apply(initial, &session_manager, &review_manager).await;
+        if policy != PowerPolicy::Auto {
+            return;
+        }
+        let mut interval = tokio::time::interval(POWER_POLL_INTERVAL);
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            let source = tokio::task::spawn_blocking(detect_power_source)
+                .await
+                .unwrap_or(PowerSource::Unknown);
+            let state = PowerState::new(policy, source);
+            let previous = *state_tx.borrow();
+            if state == previous {
+                continue;
+            }
+            tracing::info!(
+                source = source.name(),
+                low_power = state.low_power,
+                "Power source changed"
+            );
+            if state.low_power != previous.low_power {
+                apply(state, &session_manager, &review_manager).await;
+            }
+            state_tx.send_replace(state);
+        }
+    });
+    state_rx
+}
+
+async fn apply<D: Persistence>(
+    state: PowerState,
+    session_manager: &SessionManager<D>,
+    review_manager: &ReviewManager<D>,
+) {
+    session_manager.set_low_power(state.low_power).await;
+    review_manager.set_paused(state.low_power);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pmset() {
+        let on_battery =
+            "Now drawing from 'Battery Power'\n -InternalBattery-0 (id=1)\t81%; discharging";
+        assert_eq!(parse_pmset(on_battery), PowerSource::Battery);
+        assert_eq!(
+            parse_pmset("Now drawing from 'AC Power'\n"),
+            PowerSource::Ac
+        );
+        assert_eq!(parse_pmset(""), PowerSource::Unknown);
+    }
+
+    #[test]
+    fn test_read_power_supplies() {
+        let dir = tempfile::tempdir().unwrap();
+        let supply = |name: &str, files: &[(&str, &str)]| {
+            let path = dir.path().join(name);
+            std::fs::create_dir(&path).unwrap();
+            for (file, value) in files {
+                std::fs::write(path.join(file), format!("{}\n", value)).unwrap();
+            }
+        };
+        assert_eq!(read_power_supplies(dir.path()), PowerSource::Unknown);
+
+        supply("BAT0", &[("type", "Battery"), ("status", "Discharging")]);
+        supply("AC", &[("type", "Mains"), ("online", "0")]);
+        assert_eq!(read_power_supplies(dir.path()), PowerSource::Battery);
+
+        std::fs::write(dir.path().join("AC/online"), "1\n").unwrap();
+        assert_eq!(read_power_supplies(dir.path()), PowerSource::Ac);
+    }
+
+    #[test]
+    fn test_low_power_caps_engine_resources() {
+        assert_eq!(engine_resources(4, 256, false), (4, 256));
+        assert_eq!(engine_resources(4, 256, true), (1, 16));
+        assert_eq!(engine_resources(1, 8, true), (1, 8));
+
+        assert!(!PowerPolicy::Off.is_low_power(PowerSource::Battery));
+        assert!(PowerPolicy::Auto.is_low_power(PowerSource::Battery));
+        assert!(!PowerPolicy::Auto.is_low_power(PowerSource::Unknown));
+        assert!(PowerPolicy::On.is_low_power(PowerSource::Ac));
+    }
+}
@@ -0,0 +1,313 @@
+//! Deterministic replay of recorded games.
+//!
+//! A finished game's move list is the server's record of what happened in
+//! its session, with the position after every move. Replay feeds the moves
+//! one at a time through [`SessionState`], the state the session actor
+//! mutates, and compares each resulting snapshot with what was recorded.
+//! `chesstty-server replay [game_id...]` runs it over the database to chase
+//! reported desyncs; the tests run it as an invariant over games played
+//! through the session manager.
+
+use chess::{format_piece_upper, format_square, Game, GameMode, GamePhase, GameResult};
+
+use crate::import::IMPORTED_GAME_MODE;
+use crate::persistence::{
+    FinishedGameData, FinishedGameRepository, PersistenceError, StoredMoveRecord,
+};
+use crate::session::snapshot::MoveRecord;
+use crate::session::state::SessionState;
+
+/// Where a replay departed from the record.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum Divergence {
+    #[error("start position {0} does not parse")]
+    InvalidStartFen(String),
+    #[error("ply {ply}: recorded move {from}{to} is not legal here")]
+    IllegalMove {
+        ply: usize,
+        from: String,
+        to: String,
+    },
+    #[error("ply {ply}: {field} was recorded as {recorded:?} but replays as {replayed:?}")]
+    Mismatch {
+        ply: usize,
+        field: &'static str,
+        recorded: String,
+        replayed: String,
+    },
+    #[error("result was recorded as {recorded} but the final position gives {replayed}")]
+    Result { recorded: String, replayed: String },
+    #[error("move count was recorded as {recorded} but {replayed} moves were replayed")]
+    MoveCount { recorded: u32, replayed: usize },
+}
+
+/// Outcome of replaying one game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayReport {
+    pub game_id: String,
+    /// Moves replayed before the end of the record or an illegal move.
+    pub plies: usize,
+    pub divergences: Vec<Divergence>,
+}
+
+impl ReplayReport {
+    pub fn is_consistent(&self) -> bool {
+        self.divergences.is_empty()
+    }
+}
+
+fn result_name(result: &GameResult) -> &'static str {
+    match result {
+        GameResult::WhiteWins => "WhiteWins",
+        GameResult::BlackWins => "BlackWins",
+        GameResult::Draw => "Draw",
+    }
+}
+
+/// Fields of `replayed` that differ from `recorded`. Imported games keep the
+/// SAN from their file and never count castling as a capture, so only the
+/// squares, piece and position are compared for them.
+fn compare_move(
+    ply: usize,
+    recorded: &StoredMoveRecord,
+    replayed: &MoveRecord,
+    imported: bool,
+) -> Vec<Divergence> {
+    let fields = [
+        ("from", &recorded.from, &replayed.from),
+        ("to", &recorded.to, &replayed.to),
+        ("piece", &recorded.piece, &replayed.piece),
+        ("fen_after", &recorded.fen_after, &replayed.fen_after),
+    ];
+    let mut divergences: Vec<Divergence> = fields
+        .into_iter()
+        .filter(|(_, recorded, replayed)| recorded != replayed)
+        .map(|(field, recorded, replayed)| Divergence::Mismatch {
+            ply,
+            field,
+            recorded: recorded.clone(),
+            replayed: replayed.clone(),
+        })
+        .collect();
+
+    let optional = |value: &Option<String>| value.clone().unwrap_or_default();
+    let mut optional_fields = vec![(
+        "promotion",
+        optional(&recorded.promotion),
+        optional(&replayed.promotion),
+    )];
+    if !imported {
+        optional_fields.push(("san", recorded.san.clone(), replayed.san.clone()));
+        optional_fields.push((
+            "captured",
+            optional(&recorded.captured),
+            optional(&replayed.captured),
+        ));
+    }
+    divergences.extend(
+        optional_fields
+            .into_iter()
+            .filter(|(_, recorded, replayed)| recorded != replayed)
+            .map(|(field, recorded, replayed)| Divergence::Mismatch {
+                ply,
+                field,
+                recorded,
+                replayed,
+            }),
+    );
+    divergences
+}
+
+/// Replay `game` from its start position, checking every move against the
+/// record. Stops at the first move that is not legal.
+pub fn replay_game(game: &FinishedGameData) -> ReplayReport {
+    let mut report = ReplayReport {
+        game_id: game.game_id.clone(),
+        plies: 0,
+        divergences: Vec::new(),
+    };
+    let Ok(start) = Game::from_fen(&game.start_fen) else {
+        report
+            .divergences
+            .push(Divergence::InvalidStartFen(game.start_fen.clone()));
+        return report;
+    };
+    let imported = game.game_mode == IMPORTED_GAME_MODE;
+    let mut state = SessionState::new(game.game_id.clone(), start, GameMode::HumanVsHuman);
+
+    for (i, recorded) in game.moves.iter().enumerate() {
+        let ply = i + 1;
+        let promotion = recorded.promotion.as_deref().unwrap_or_default();
+        let mv = state.game.legal_moves().into_iter().find(|mv| {
+            format_square(mv.from) == recorded.from
+                && format_square(mv.to) == recorded.to
+                && mv
+                    .promotion
+                    .map(|p| format_piece_upper(p).to_string())
+                    .unwrap_or_default()
+                    == promotion
+        });
+        let Some(snapshot) = mv.and_then(|mv| state.apply_move(mv).ok()) else {
+            report.divergences.push(Divergence::IllegalMove {
+                ply,
+                from: recorded.from.clone(),
+                to: recorded.to.clone(),
+            });
+            return report;
+        };
+        report.plies = ply;
+        if let Some(replayed) = snapshot.history.last() {
+            report
+                .divergences
+                .extend(compare_move(ply, recorded, replayed, imported));
+        }
+    }
+
+    if game.move_count as usize != report.plies {
+        report.divergences.push(Divergence::MoveCount {
+            recorded: game.move_count,
+            replayed: report.plies,
+        });
+    }
+    // Games can also end on time, by resignation or by agreement; only a
+    // result the board decides is checked
+    if let GamePhase::Ended { result, .. } = &state.phase {
+        if game.result != result_name(result) {
+            report.divergences.push(Divergence::Result {
+                recorded: game.result.clone(),
+                replayed: result_name(result).to_string(),
+            });
+        }
+    }
+    report
+}
+
+/// Replay the games in `ids`, or every stored game when empty, printing one
+/// line per game and each divergence. Returns whether all games replayed
+/// as recorded.
+pub async fn run<R: FinishedGameRepository>(
+    store: &R,
+    ids: &[String],
+) -> Result<bool, PersistenceError> {
+    let games = if ids.is_empty() {
+        store.list_games().await?
+    } else {
+        let mut games = Vec::with_capacity(ids.len());
+        for id in ids {
+            match store.load_game(id).await? {
+                Some(game) => games.push(game),
+                None => println!("{}: not found", id),
+            }
+        }
+        games
+    };
+
+    let mut consistent = games.len() == ids.len() || ids.is_empty();
+    for game in &games {
+        let report = replay_game(game);
+        if report.is_consistent() {
+            println!("{}: ok ({} plies)", report.game_id, report.plies);
+        } else {
+            consistent = false;
+            println!(
+                "{}: {} divergences after {} plies",
+                report.game_id,
+                report.divergences.len(),
+                report.plies
+            );
+            for divergence in &report.divergences {
+                println!("  {}", divergence);
+            }
+        }
+    }
+    println!("Replayed {} games.", games.len());
+    Ok(consistent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::{
+        ActivityStore, ChatStore, FinishedGameStore, GoalStore, JsonPersistence, PositionStore,
+        SessionStore,
+    };
+    use crate::session::SessionManager;
+    use std::sync::Arc;
+
+    /// Play `sans` through a session and load the finished game it is saved as.
+    async fn play_and_save(sans: &[&str]) -> FinishedGameData {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().to_path_buf();
+        let finished = Arc::new(FinishedGameStore::new(path.clone()));
+        let mgr = SessionManager::<JsonPersistence>::new(
+            SessionStore::new(path.clone()),
+            PositionStore::new(path.clone(), None),
+            finished.clone(),
+            ActivityStore::new(path.clone()),
+            GoalStore::new(path.clone()),
+            ChatStore::new(path),
+        );
+
+        let snap = mgr
+            .create_session(None, GameMode::HumanVsHuman)
+            .await
+            .unwrap();
+        let handle = mgr.get_handle(&snap.session_id).await.unwrap();
+        for san in sans {
+            let fen = handle.get_snapshot().await.unwrap().fen;
+            let board = fen.parse().unwrap();
+            let mv = chess::parse_san(&board, san).unwrap();
+            handle.make_move(mv).await.unwrap();
+        }
+        let game_id = mgr.close_session(&snap.session_id).await.unwrap().unwrap();
+        finished.load_game(&game_id).await.unwrap().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_played_games_replay_as_recorded() {
+        let game = play_and_save(&["e4", "e5", "Qh5", "Nc6", "Bc4", "Nf6", "Qxf7#"]).await;
+        let report = replay_game(&game);
+        assert_eq!(report.divergences, Vec::new());
+        assert_eq!(report.plies, 7);
+    }
+
+    #[tokio::test]
+    async fn test_tampered_record_diverges() {
+        let mut game = play_and_save(&["f3", "e5", "g4", "Qh4#"]).await;
+        assert!(replay_game(&game).is_consistent());
+
+        game.moves[1].fen_after = "8/8/8/8/8/8/8/8 w - - 0 1".to_string();
+        game.moves[2].san = "g5".to_string();
+        let report = replay_game(&game);
+        assert_eq!(report.plies, 4);
+        assert!(matches!(
+            report.divergences[0],
+            Divergence::Mismatch {
+                ply: 2,
+                field: "fen_after",
+                ..
+            }
+        ));
+        assert!(matches!(
+            report.divergences[1],
+            Divergence::Mismatch {
+                ply: 3,
+                field: "san",
+                ..
+            }
+        ));
+
+        game.moves[3].to = "h3".to_string();
+        game.result = "WhiteWins".to_string();
+        let report = replay_game(&game);
+        assert_eq!(report.plies, 3);
+        assert_eq!(
+            report.divergences.last(),
+            Some(&Divergence::IllegalMove {
+                ply: 4,
+                from: "d8".to_string(),
+                to: "h3".to_string(),
+            })
+        );
+    }
+}
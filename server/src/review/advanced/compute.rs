@@ -2,8 +2,8 @@ use analysis::advanced::critical::is_critical_position;
 use analysis::advanced::psychological::compute_psychological_profile;
 use analysis::advanced::types::{AdvancedGameAnalysis, AdvancedPositionAnalysis, AnalysisConfig};
 use analysis::board_analysis::{
-    compute_king_safety, compute_tension, detect_tactics, AttackMap, PositionKingSafety,
-    PositionTensionMetrics, TacticalContext, TacticalTag,
+    compute_king_safety, compute_mobility, compute_tension, detect_tactics, AttackMap,
+    PositionKingSafety, PositionTensionMetrics, TacticalContext, TacticalTag,
 };
 use analysis::review_types::GameReview;
 use cozy_chess::Board;
@@ -19,17 +19,13 @@ pub fn compute_advanced_analysis(
     let mut critical_count: u32 = 0;
 
     for (i, pos) in review.positions.iter().enumerate() {
-        let prev_pos = if i > 0 {
-            Some(&review.positions[i - 1])
-        } else {
-            None
-        };
+        // Reviews of part of a game have gaps; only the ply right before counts
+        let prev_pos = i
+            .checked_sub(1)
+            .map(|j| &review.positions[j])
+            .filter(|prev| prev.ply + 1 == pos.ply);
 
-        let board_before = if i > 0 {
-            review.positions[i - 1].fen.parse::<Board>().ok()
-        } else {
-            None
-        };
+        let board_before = prev_pos.and_then(|prev| prev.fen.parse::<Board>().ok());
         let board_after = pos.fen.parse::<Board>().ok();
 
         // New pipeline: tactical tags
@@ -72,6 +68,12 @@ pub fn compute_advanced_analysis(
             .map(compute_tension)
             .unwrap_or_else(empty_tension);
 
+        // Mobility and space (from the resulting position)
+        let mobility = board_after
+            .as_ref()
+            .map(|board| compute_mobility(board, &AttackMap::compute(board)))
+            .unwrap_or_default();
+
         // Critical position detection
         let is_critical =
             is_critical_position(pos, prev_pos, &tactical_tags_after, &king_safety, &tension);
@@ -86,6 +88,7 @@ pub fn compute_advanced_analysis(
             tactical_tags_after,
             king_safety,
             tension,
+            mobility,
             is_critical,
             deep_depth: None,
         });
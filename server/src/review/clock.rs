@@ -0,0 +1,93 @@
+//! "Would I have flagged?": a time control run over the think times of a
+//! finished game, for games played without a clock.
+
+use analysis::{simulate_clock, ClockSimulation, TimeControl};
+
+use crate::persistence::FinishedGameData;
+
+/// Both sides' clocks over a game under a simulated time control.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameClockSimulation {
+    pub white: ClockSimulation,
+    pub black: ClockSimulation,
+}
+
+impl GameClockSimulation {
+    /// Run `control` over `game`, each side on its own clock.
+    pub fn new(game: &FinishedGameData, control: TimeControl) -> Self {
+        // The side to move in the start position plays the odd plies
+        let black_first = game.start_fen.split_whitespace().nth(1) == Some("b");
+        let (mut white, mut black) = (Vec::new(), Vec::new());
+        for (i, mv) in game.moves.iter().enumerate() {
+            let ply = i as u32 + 1;
+            if (i % 2 == 0) != black_first {
+                white.push((ply, mv.think_ms));
+            } else {
+                black.push((ply, mv.think_ms));
+            }
+        }
+        Self {
+            white: simulate_clock(&white, control),
+            black: simulate_clock(&black, control),
+        }
+    }
+
+    /// Ply of the first flag to fall, if either did.
+    pub fn first_flag(&self) -> Option<u32> {
+        match (self.white.flagged_at_ply, self.black.flagged_at_ply) {
+            (Some(w), Some(b)) => Some(w.min(b)),
+            (w, b) => w.or(b),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::StoredMoveRecord;
+
+    fn game(start_fen: &str, think_ms: &[Option<u64>]) -> FinishedGameData {
+        FinishedGameData {
+            start_fen: start_fen.to_string(),
+            result: "Draw".to_string(),
+            result_reason: "Agreement".to_string(),
+            game_mode: "HumanVsHuman".to_string(),
+            human_side: None,
+            move_count: think_ms.len() as u32,
+            moves: think_ms
+                .iter()
+                .map(|&think_ms| StoredMoveRecord {
+                    think_ms,
+                    ..StoredMoveRecord::sample("")
+                })
+                .collect(),
+            ..FinishedGameData::sample("game_1")
+        }
+    }
+
+    const CONTROL: TimeControl = TimeControl {
+        initial_ms: 60_000,
+        increment_ms: 0,
+    };
+
+    #[test]
+    fn test_each_side_runs_its_own_clock() {
+        let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        let simulation = GameClockSimulation::new(
+            &game(start, &[Some(50_000), Some(1_000), Some(20_000), None]),
+            CONTROL,
+        );
+        assert_eq!(simulation.white.flagged_at_ply, Some(3));
+        assert_eq!(simulation.black.final_ms(), 59_000);
+        assert_eq!(simulation.black.untimed_moves, 1);
+        assert_eq!(simulation.first_flag(), Some(3));
+    }
+
+    #[test]
+    fn test_black_plays_the_odd_plies_when_starting() {
+        let start = "4k3/8/8/8/8/8/8/4K3 b - - 0 1";
+        let simulation = GameClockSimulation::new(&game(start, &[Some(70_000)]), CONTROL);
+        assert_eq!(simulation.black.flagged_at_ply, Some(1));
+        assert_eq!(simulation.white.flagged_at_ply, None);
+    }
+}
@@ -0,0 +1,541 @@
+//! Periodic training digest: results, accuracy trend, recurring mistakes and
+//! recommended focus areas over the last few days, renderable as Markdown.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use analysis::advanced::types::{AdvancedGameAnalysis, PsychologicalProfile};
+use analysis::{GameReview, PhaseBoundaries};
+
+use super::outcome::{
+    human_accuracy, human_plays_white, is_players_game, mean_accuracy, HumanOutcome,
+};
+use super::types::{is_white_ply, MoveClassification};
+use crate::persistence::{day_of, iso_date, FinishedGameData};
+
+/// Days covered when the client does not ask for a period.
+pub const DEFAULT_DIGEST_DAYS: u32 = 7;
+
+/// Recurring mistakes listed in a digest.
+const TOP_MISTAKES: usize = 3;
+
+/// Blunders within one 5-move window that count as a cluster.
+const CLUSTER_DENSITY: u8 = 2;
+
+/// Average cp loss per move above which a game phase is worth a focus area.
+const WEAK_PHASE_CP_LOSS: f64 = 30.0;
+
+/// Drop in accuracy points between periods that is worth a focus area.
+const ACCURACY_DROP: f64 = 3.0;
+
+/// A finished game with whatever analysis exists for it.
+pub struct DigestGame {
+    pub game: FinishedGameData,
+    pub review: Option<GameReview>,
+    pub advanced: Option<AdvancedGameAnalysis>,
+}
+
+/// Mean human accuracy over the reviewed games of one day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccuracyPoint {
+    /// Days since the Unix epoch (UTC).
+    pub day: u32,
+    pub games: u32,
+    pub accuracy: f64,
+}
+
+/// A kind of mistake and how often the human made it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecurringMistake {
+    pub theme: String,
+    pub count: u32,
+    /// The tactic on the board, when the theme is one.
+    pub tactic: Option<&'static str>,
+}
+
+/// Summary of the human's training over a period ending today.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrainingDigest {
+    /// First and last day of the period, inclusive.
+    pub first_day: u32,
+    pub last_day: u32,
+    pub games_played: u32,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    pub reviewed_games: u32,
+    pub average_accuracy: Option<f64>,
+    /// Average accuracy over the period of the same length just before.
+    pub previous_average_accuracy: Option<f64>,
+    /// One point per day with reviewed games, oldest first.
+    pub accuracy_trend: Vec<AccuracyPoint>,
+    /// Mistakes and blunders grouped by theme, most frequent first.
+    pub top_mistakes: Vec<RecurringMistake>,
+    pub focus_areas: Vec<String>,
+}
+
+impl TrainingDigest {
+    /// Build the digest for the `days` days ending on `today`. Games from the
+    /// period before are only used for the accuracy comparison; older games
+    /// and games the player took no part in are ignored.
+    pub fn new(games: Vec<DigestGame>, today: u32, days: u32) -> Self {
+        let days = days.max(1);
+        let first_day = (today + 1).saturating_sub(days);
+        let previous_first_day = first_day.saturating_sub(days);
+
+        let (current, previous): (Vec<DigestGame>, Vec<DigestGame>) = games
+            .into_iter()
+            .filter(|g| is_players_game(&g.game))
+            .filter(|g| (previous_first_day..=today).contains(&day_of(g.game.created_at)))
+            .partition(|g| day_of(g.game.created_at) >= first_day);
+
+        let outcomes: Vec<HumanOutcome> =
+            current.iter().map(|g| HumanOutcome::of(&g.game)).collect();
+        let count = |outcome| outcomes.iter().filter(|&&o| o == outcome).count() as u32;
+
+        let accuracies: Vec<(u32, f64)> = current
+            .iter()
+            .filter_map(|g| {
+                let accuracy = human_accuracy(&g.game, g.review.as_ref()?)?;
+                Some((day_of(g.game.created_at), accuracy))
+            })
+            .collect();
+        let previous_average_accuracy = mean_accuracy(
+            previous
+                .iter()
+                .filter_map(|g| human_accuracy(&g.game, g.review.as_ref()?)),
+        );
+
+        let top_mistakes = recurring_mistakes(&current);
+        let mut digest = Self {
+            first_day,
+            last_day: today,
+            games_played: current.len() as u32,
+            wins: count(HumanOutcome::Win),
+            draws: count(HumanOutcome::Draw),
+            losses: count(HumanOutcome::Loss),
+            reviewed_games: accuracies.len() as u32,
+            average_accuracy: mean_accuracy(accuracies.iter().map(|&(_, a)| a)),
+            previous_average_accuracy,
+            accuracy_trend: accuracy_trend(&accuracies),
+            top_mistakes,
+            focus_areas: vec![],
+        };
+        digest.focus_areas = focus_areas(&digest, &current);
+        digest
+    }
+
+    /// The digest as a Markdown document.
+    pub fn to_markdown(&self) -> String {
+        let mut md = String::new();
+        let _ = writeln!(
+            md,
+            "# Training Digest: {} to {}\n",
+            iso_date(self.first_day),
+            iso_date(self.last_day)
+        );
+
+        md.push_str("## Summary\n\n");
+        let _ = writeln!(
+            md,
+            "- Games played: {} ({}W / {}D / {}L)",
+            self.games_played, self.wins, self.draws, self.losses
+        );
+        let _ = writeln!(md, "- Reviewed games: {}", self.reviewed_games);
+        match (self.average_accuracy, self.previous_average_accuracy) {
+            (Some(now), Some(before)) => {
+                let _ = writeln!(
+                    md,
+                    "- Average accuracy: {:.1}% ({:+.1} vs previous period)",
+                    now,
+                    now - before
+                );
+            }
+            (Some(now), None) => {
+                let _ = writeln!(md, "- Average accuracy: {:.1}%", now);
+            }
+            (None, _) => md.push_str("- Average accuracy: no reviewed games\n"),
+        }
+
+        if !self.accuracy_trend.is_empty() {
+            md.push_str("\n## Accuracy Trend\n\n");
+            md.push_str("| Day | Games | Accuracy |\n|-----|-------|----------|\n");
+            for point in &self.accuracy_trend {
+                let _ = writeln!(
+                    md,
+                    "| {} | {} | {:.1}% |",
+                    iso_date(point.day),
+                    point.games,
+                    point.accuracy
+                );
+            }
+        }
+
+        md.push_str("\n## Top Recurring Mistakes\n\n");
+        if self.top_mistakes.is_empty() {
+            md.push_str("No mistakes or blunders in reviewed games.\n");
+        }
+        for (i, mistake) in self.top_mistakes.iter().enumerate() {
+            let _ = writeln!(md, "{}. {} ({})", i + 1, mistake.theme, mistake.count);
+        }
+
+        md.push_str("\n## Recommended Focus\n\n");
+        for area in &self.focus_areas {
+            let _ = writeln!(md, "- {}", area);
+        }
+        md
+    }
+}
+
+fn accuracy_trend(accuracies: &[(u32, f64)]) -> Vec<AccuracyPoint> {
+    let mut by_day: BTreeMap<u32, Vec<f64>> = BTreeMap::new();
+    for &(day, accuracy) in accuracies {
+        by_day.entry(day).or_default().push(accuracy);
+    }
+    by_day
+        .into_iter()
+        .map(|(day, values)| AccuracyPoint {
+            day,
+            games: values.len() as u32,
+            accuracy: mean_accuracy(values).unwrap_or_default(),
+        })
+        .collect()
+}
+
+/// Group the human's mistakes and blunders by the tactic on the board when
+/// advanced analysis found one, otherwise by game phase.
+fn recurring_mistakes(games: &[DigestGame]) -> Vec<RecurringMistake> {
+    let mut counts: BTreeMap<String, (u32, Option<&'static str>)> = BTreeMap::new();
+    for g in games {
+        let Some(review) = &g.review else { continue };
+        let white = human_plays_white(&g.game);
+        let phases = PhaseBoundaries::of_review(&review.positions);
+        for pos in &review.positions {
+            if is_white_ply(pos.ply) != white
+                || !matches!(
+                    pos.classification,
+                    MoveClassification::Mistake
+                        | MoveClassification::Blunder
+                        | MoveClassification::Miss
+                )
+            {
+                continue;
+            }
+            let motif = g
+                .advanced
+                .as_ref()
+                .and_then(|a| a.positions.iter().find(|p| p.ply == pos.ply))
+                .and_then(|p| {
+                    p.tactical_tags_before
+                        .iter()
+                        .max_by(|a, b| a.confidence.total_cmp(&b.confidence))
+                });
+            let tactic = motif.map(|tag| tag.kind.name());
+            let theme = match tactic {
+                Some(name) => format!("Errors with a {} on the board", name),
+                None => format!("{} errors", phases.phase_of(pos.ply).name()),
+            };
+            counts.entry(theme).or_insert((0, tactic)).0 += 1;
+        }
+    }
+
+    let mut mistakes: Vec<RecurringMistake> = counts
+        .into_iter()
+        .map(|(theme, (count, tactic))| RecurringMistake {
+            theme,
+            count,
+            tactic,
+        })
+        .collect();
+    mistakes.sort_by(|a, b| b.count.cmp(&a.count));
+    mistakes.truncate(TOP_MISTAKES);
+    mistakes
+}
+
+/// Recommendations from blunder clustering, phase weaknesses, recurring
+/// tactics and the accuracy trend, most pressing first.
+fn focus_areas(digest: &TrainingDigest, games: &[DigestGame]) -> Vec<String> {
+    let profiles: Vec<&PsychologicalProfile> = games
+        .iter()
+        .filter_map(|g| {
+            let advanced = g.advanced.as_ref()?;
+            Some(if human_plays_white(&g.game) {
+                &advanced.white_psychology
+            } else {
+                &advanced.black_psychology
+            })
+        })
+        .collect();
+
+    let mut areas = Vec::new();
+
+    let clustered = profiles
+        .iter()
+        .filter(|p| p.blunder_cluster_density >= CLUSTER_DENSITY)
+        .count();
+    if clustered > 0 {
+        areas.push(format!(
+            "Blunders came in clusters in {} of {} analyzed games: after a mistake, slow down and re-check threats",
+            clustered,
+            profiles.len()
+        ));
+    }
+
+    if !profiles.is_empty() {
+        let n = profiles.len() as f64;
+        let phases = [
+            (
+                "Opening",
+                profiles.iter().map(|p| p.opening_avg_cp_loss).sum::<f64>() / n,
+            ),
+            (
+                "Middlegame",
+                profiles
+                    .iter()
+                    .map(|p| p.middlegame_avg_cp_loss)
+                    .sum::<f64>()
+                    / n,
+            ),
+            (
+                "Endgame",
+                profiles.iter().map(|p| p.endgame_avg_cp_loss).sum::<f64>() / n,
+            ),
+        ];
+        if let Some((phase, cp_loss)) = phases
+            .iter()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .filter(|(_, cp_loss)| *cp_loss > WEAK_PHASE_CP_LOSS)
+        {
+            areas.push(format!(
+                "{} play: {:.0} cp lost per move on average",
+                phase, cp_loss
+            ));
+        }
+    }
+
+    if let Some((count, tactic)) = digest
+        .top_mistakes
+        .iter()
+        .find_map(|m| Some((m.count, m.tactic?)))
+    {
+        areas.push(format!(
+            "Tactics: {} of your errors came with a {} on the board",
+            count, tactic
+        ));
+    }
+
+    if let (Some(now), Some(before)) = (digest.average_accuracy, digest.previous_average_accuracy) {
+        if before - now >= ACCURACY_DROP {
+            areas.push(format!(
+                "Accuracy dropped from {:.1}% to {:.1}%: go through the reviews of recent losses",
+                before, now
+            ));
+        }
+    }
+
+    if areas.is_empty() {
+        areas.push(if digest.games_played == 0 {
+            "Play and review a few games to get recommendations".to_string()
+        } else if digest.reviewed_games == 0 {
+            "Review your games to get recommendations".to_string()
+        } else {
+            "No recurring weakness stood out: keep it up".to_string()
+        });
+    }
+    areas
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use analysis::{AnalysisScore, PositionReview, ReviewStatus};
+
+    const DAY: u64 = 86_400;
+
+    fn game(id: &str, result: &str, day: u32) -> FinishedGameData {
+        FinishedGameData {
+            result: result.to_string(),
+            game_mode: "HumanVsEngine:White".to_string(),
+            move_count: 40,
+            created_at: u64::from(day) * DAY + 3600,
+            ..FinishedGameData::sample(id)
+        }
+    }
+
+    fn position(ply: u32, classification: MoveClassification) -> PositionReview {
+        PositionReview {
+            ply,
+            fen: String::new(),
+            played_san: "e4".to_string(),
+            best_move_san: "d4".to_string(),
+            best_move_uci: "d2d4".to_string(),
+            eval_before: AnalysisScore::Centipawns(0),
+            eval_after: AnalysisScore::Centipawns(0),
+            eval_best: AnalysisScore::Centipawns(0),
+            classification,
+            cp_loss: 0,
+            pv: vec![],
+            depth: 18,
+            clock_ms: None,
+            excluded: None,
+            only_move: false,
+        }
+    }
+
+    fn review(id: &str, white: f64, positions: Vec<PositionReview>) -> GameReview {
+        GameReview {
+            game_id: id.to_string(),
+            status: ReviewStatus::Complete,
+            positions,
+            white_accuracy: Some(white),
+            black_accuracy: Some(50.0),
+            total_plies: 40,
+            analyzed_plies: 40,
+            analysis_depth: 18,
+            started_at: None,
+            completed_at: None,
+            winner: None,
+            opening: None,
+            endgame: None,
+        }
+    }
+
+    fn unreviewed(game: FinishedGameData) -> DigestGame {
+        DigestGame {
+            game,
+            review: None,
+            advanced: None,
+        }
+    }
+
+    #[test]
+    fn test_digest_counts_only_the_period() {
+        let mut engine_game = game("e", "WhiteWins", 100);
+        engine_game.game_mode = "EngineVsEngine".to_string();
+        let digest = TrainingDigest::new(
+            vec![
+                unreviewed(game("a", "WhiteWins", 100)),
+                unreviewed(game("b", "Draw", 94)),
+                unreviewed(game("c", "BlackWins", 93)),
+                unreviewed(engine_game),
+            ],
+            100,
+            7,
+        );
+        assert_eq!((digest.first_day, digest.last_day), (94, 100));
+        assert_eq!(digest.games_played, 2);
+        assert_eq!((digest.wins, digest.draws, digest.losses), (1, 1, 0));
+        assert_eq!(digest.average_accuracy, None);
+        assert_eq!(
+            digest.focus_areas,
+            vec!["Review your games to get recommendations"]
+        );
+    }
+
+    #[test]
+    fn test_digest_ignores_imported_games() {
+        let mut imported = game("i", "BlackWins", 100);
+        imported.game_mode = crate::import::IMPORTED_GAME_MODE.to_string();
+        imported.human_side = None;
+        let digest = TrainingDigest::new(
+            vec![
+                unreviewed(game("a", "WhiteWins", 100)),
+                DigestGame {
+                    review: Some(review("i", 20.0, vec![])),
+                    game: imported,
+                    advanced: None,
+                },
+            ],
+            100,
+            7,
+        );
+        assert_eq!(digest.games_played, 1);
+        assert_eq!((digest.wins, digest.draws, digest.losses), (1, 0, 0));
+        assert_eq!(digest.reviewed_games, 0);
+        assert_eq!(digest.average_accuracy, None);
+    }
+
+    #[test]
+    fn test_accuracy_trend_and_previous_period() {
+        let with_review = |id: &str, day: u32, accuracy: f64| DigestGame {
+            game: game(id, "WhiteWins", day),
+            review: Some(review(id, accuracy, vec![])),
+            advanced: None,
+        };
+        let digest = TrainingDigest::new(
+            vec![
+                with_review("a", 98, 70.0),
+                with_review("b", 98, 80.0),
+                with_review("c", 100, 60.0),
+                with_review("old", 90, 90.0),
+            ],
+            100,
+            7,
+        );
+        assert_eq!(digest.reviewed_games, 3);
+        assert_eq!(
+            digest.accuracy_trend,
+            vec![
+                AccuracyPoint {
+                    day: 98,
+                    games: 2,
+                    accuracy: 75.0
+                },
+                AccuracyPoint {
+                    day: 100,
+                    games: 1,
+                    accuracy: 60.0
+                },
+            ]
+        );
+        assert_eq!(digest.previous_average_accuracy, Some(90.0));
+        assert!(digest.focus_areas[0].starts_with("Accuracy dropped"));
+    }
+
+    #[test]
+    fn test_recurring_mistakes_count_human_moves_by_phase() {
+        let positions = vec![
+            position(5, MoveClassification::Blunder),
+            position(7, MoveClassification::Mistake),
+            // Black's move: not the human's
+            position(8, MoveClassification::Blunder),
+            position(41, MoveClassification::Mistake),
+            position(43, MoveClassification::Inaccuracy),
+        ];
+        let digest = TrainingDigest::new(
+            vec![DigestGame {
+                game: game("a", "BlackWins", 100),
+                review: Some(review("a", 70.0, positions)),
+                advanced: None,
+            }],
+            100,
+            7,
+        );
+        assert_eq!(
+            digest.top_mistakes,
+            vec![
+                RecurringMistake {
+                    theme: "Opening errors".to_string(),
+                    count: 2,
+                    tactic: None,
+                },
+                RecurringMistake {
+                    theme: "Middlegame errors".to_string(),
+                    count: 1,
+                    tactic: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_markdown_sections() {
+        let digest = TrainingDigest::new(vec![unreviewed(game("a", "WhiteWins", 100))], 100, 7);
+        let md = digest.to_markdown();
+        assert!(md.starts_with("# Training Digest: 1970-04-05 to 1970-04-11"));
+        assert!(md.contains("- Games played: 1 (1W / 0D / 0L)"));
+        assert!(md.contains("## Top Recurring Mistakes"));
+        assert!(md.contains("## Recommended Focus"));
+        assert!(!md.contains("## Accuracy Trend"));
+    }
+}
@@ -1,19 +1,63 @@
 pub mod advanced;
+pub mod clock;
+pub mod digest;
+pub mod opponents;
+pub mod outcome;
+pub mod similar;
+pub mod simul;
 pub mod store;
 pub mod types;
 pub mod worker;
 
-use std::collections::HashSet;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-use analysis::AnalysisConfig;
-use tokio::sync::{mpsc, Mutex, RwLock};
+use analysis::{explain_best_line, AnalysisConfig, MoveExplanation, PositionSignature, Tablebase};
+use tokio::sync::{broadcast, mpsc, watch, Mutex, RwLock};
 
 use crate::persistence::{
     AdvancedAnalysisRepository, FinishedGameRepository, Persistence, ReviewRepository,
 };
 use types::*;
 
+/// Deepest analysis a review job can ask for.
+pub const MAX_REVIEW_DEPTH: u32 = 60;
+
+/// Lines the engine searches per position unless a job asks for more or
+/// fewer. The second-best line tells an only move from one of several good
+/// ones.
+pub const DEFAULT_REVIEW_MULTIPV: u32 = 2;
+
+/// Most lines a review job can ask for.
+pub const MAX_REVIEW_MULTIPV: u32 = 5;
+
+/// How much deeper than before a position is re-analysed when no depth is
+/// asked for.
+pub const REANALYZE_DEPTH_STEP: u32 = 6;
+
+/// Error a cancelled review is marked failed with; queueing the game again
+/// resumes from the plies analysed before.
+pub const REVIEW_CANCELLED: &str = "Review cancelled";
+
+/// Progress updates kept for a slow subscriber before it misses some. Each
+/// update carries the whole status, so one that lags just skips ahead.
+const PROGRESS_CAPACITY: usize = 64;
+
+/// What the startup recovery queued.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RecoveryReport {
+    /// Reviews that were queued or being analysed when the server stopped.
+    pub interrupted: usize,
+    /// Finished games that had no review.
+    pub unreviewed: usize,
+    /// Games of either kind that could not be queued.
+    pub failed: usize,
+    pub elapsed: Duration,
+}
+
 /// Configuration for the review system.
 pub struct ReviewConfig {
     /// Number of concurrent workers (each spawns its own Stockfish process).
@@ -22,6 +66,14 @@ pub struct ReviewConfig {
     pub analysis_depth: u32,
     /// Advanced analysis configuration.
     pub analysis: AnalysisConfig,
+    /// Whether games imported from PGN files without a review are queued
+    /// on startup, like games played here.
+    pub review_imports: bool,
+    /// Directories of Syzygy tables. Positions they cover are scored from
+    /// the tables instead of the engine.
+    pub tablebase_paths: Vec<PathBuf>,
+    /// Moves left out of the accuracy unless a job asks otherwise.
+    pub accuracy_exclusions: AccuracyExclusions,
 }
 
 impl Default for ReviewConfig {
@@ -30,6 +82,9 @@ impl Default for ReviewConfig {
             worker_count: 1,
             analysis_depth: 18,
             analysis: AnalysisConfig::default(),
+            review_imports: true,
+            tablebase_paths: Vec::new(),
+            accuracy_exclusions: AccuracyExclusions::default(),
         }
     }
 }
@@ -40,10 +95,21 @@ impl Default for ReviewConfig {
 /// Each worker spawns its own StockfishEngine process.
 pub struct ReviewManager<D: Persistence> {
     job_tx: mpsc::Sender<ReviewJob>,
-    enqueued: Arc<RwLock<HashSet<String>>>,
+    enqueued: PendingJobs,
+    /// Sequence of the next job id; seeded from the clock so ids are not
+    /// reused after a restart.
+    next_job: AtomicU64,
     review_store: Arc<D::Reviews>,
     finished_game_store: Arc<D::FinishedGames>,
     advanced_store: Arc<D::Advanced>,
+    review_imports: bool,
+    /// Workers hold off while set, in low-power mode or without an engine;
+    /// queued jobs wait.
+    paused: watch::Sender<bool>,
+    low_power: AtomicBool,
+    engine_missing: AtomicBool,
+    /// Progress of every review, published by the workers.
+    progress: broadcast::Sender<ReviewProgress>,
     /// Kept alive so the channel stays open even if no workers are spawned.
     _job_rx: Arc<Mutex<mpsc::Receiver<ReviewJob>>>,
 }
@@ -56,33 +122,45 @@ impl<D: Persistence> ReviewManager<D> {
         config: ReviewConfig,
     ) -> Self {
         let (job_tx, job_rx) = mpsc::channel::<ReviewJob>(64);
-        let enqueued = Arc::new(RwLock::new(HashSet::new()));
+        let enqueued: PendingJobs = Arc::new(RwLock::new(HashMap::new()));
+        let (paused, _) = watch::channel(false);
+        let (progress, _) = broadcast::channel(PROGRESS_CAPACITY);
 
         // Wrap the receiver so multiple workers can share it.
         // Each worker calls rx.lock().await.recv().await, ensuring only one
         // worker picks up each job.
         let shared_rx = Arc::new(Mutex::new(job_rx));
 
+        // The tables are shared read-only by all workers
+        let tablebase = if config.tablebase_paths.is_empty() {
+            None
+        } else {
+            match Tablebase::open(&config.tablebase_paths) {
+                Ok(tablebase) => {
+                    tracing::info!(max_men = tablebase.max_men(), "Syzygy tables loaded");
+                    Some(Arc::new(tablebase))
+                }
+                Err(e) => {
+                    tracing::warn!("Reviewing without tablebases: {}", e);
+                    None
+                }
+            }
+        };
+
         // Spawn worker pool
         for worker_id in 0..config.worker_count {
-            let rx = shared_rx.clone();
-            let store = review_store.clone();
-            let adv_store = advanced_store.clone();
-            let enqueued = enqueued.clone();
-            let depth = config.analysis_depth;
-            let analysis_config = config.analysis.clone();
-            tokio::spawn(async move {
-                worker::run_review_worker::<D>(
-                    worker_id,
-                    rx,
-                    store,
-                    adv_store,
-                    enqueued,
-                    depth,
-                    analysis_config,
-                )
-                .await;
-            });
+            let ctx = worker::WorkerContext::<D> {
+                store: review_store.clone(),
+                advanced_store: advanced_store.clone(),
+                enqueued: enqueued.clone(),
+                analysis_depth: config.analysis_depth,
+                accuracy_exclusions: config.accuracy_exclusions,
+                analysis_config: config.analysis.clone(),
+                tablebase: tablebase.clone(),
+                paused: paused.subscribe(),
+                progress: progress.clone(),
+            };
+            tokio::spawn(worker::run_review_worker(worker_id, shared_rx.clone(), ctx));
         }
 
         tracing::info!(
@@ -95,140 +173,434 @@ impl<D: Persistence> ReviewManager<D> {
         Self {
             job_tx,
             enqueued,
+            next_job: AtomicU64::new(job_seed()),
             review_store,
             finished_game_store,
             advanced_store,
+            review_imports: config.review_imports,
+            paused,
+            low_power: AtomicBool::new(false),
+            engine_missing: AtomicBool::new(false),
+            progress,
             _job_rx: shared_rx,
         }
     }
 
+    /// Hold background reviews, or let them carry on. A review in progress
+    /// stops after the ply it is analysing.
+    pub fn set_paused(&self, paused: bool) {
+        self.low_power.store(paused, Ordering::Relaxed);
+        self.update_hold();
+    }
+
+    /// Hold background reviews while no Stockfish binary is installed, so
+    /// queued ones wait for it rather than fail.
+    pub fn set_engine_available(&self, available: bool) {
+        self.engine_missing.store(!available, Ordering::Relaxed);
+        self.update_hold();
+    }
+
+    fn update_hold(&self) {
+        let paused =
+            self.low_power.load(Ordering::Relaxed) || self.engine_missing.load(Ordering::Relaxed);
+        let changed = self
+            .paused
+            .send_if_modified(|current| std::mem::replace(current, paused) != paused);
+        if changed {
+            tracing::info!(
+                paused,
+                "Background reviews {}",
+                if paused { "paused" } else { "resumed" }
+            );
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        *self.paused.borrow()
+    }
+
     /// Recover pending work on startup.
     ///
-    /// Scans for:
-    /// 1. Reviews stuck in Analyzing/Queued/Failed state (interrupted by a restart)
-    /// 2. Finished games with no review at all (auto-enqueue missed)
+    /// Looks up, with indexed queries run side by side:
+    /// 1. Reviews stuck in Analyzing/Queued state (interrupted by a restart)
+    /// 2. Finished games with no review at all (auto-enqueue missed), except
+    ///    imported games when imports are not reviewed automatically
     ///
-    /// Re-enqueues them so the worker picks them up.
-    pub async fn recover_pending_reviews(&self) {
-        let mut recovered = 0;
-
-        // 1. Scan for incomplete reviews on disk
-        if let Ok(reviews) = self.review_store.list_reviews().await {
-            for review in reviews {
-                match review.status {
-                    ReviewStatus::Complete => {} // nothing to do
-                    ReviewStatus::Analyzing { .. } | ReviewStatus::Queued => {
-                        tracing::info!(
-                            game_id = %review.game_id,
-                            status = ?review.status,
-                            "Recovering interrupted review"
-                        );
-                        if let Err(e) = self.enqueue(&review.game_id).await {
-                            tracing::warn!(
-                                game_id = %review.game_id,
-                                "Failed to re-enqueue interrupted review: {}",
-                                e
-                            );
-                        } else {
-                            recovered += 1;
-                        }
-                    }
-                    ReviewStatus::Failed { .. } => {
-                        // Don't auto-retry failed reviews -- user can manually retry via 'a'
-                    }
+    /// Re-enqueues them so the worker picks them up. Failed reviews are left
+    /// for the user to retry, except ones that had no engine, which are
+    /// retried once it is found.
+    pub async fn recover_pending_reviews(&self) -> RecoveryReport {
+        let started = Instant::now();
+        let mut report = RecoveryReport::default();
+
+        let (unfinished, unreviewed) = tokio::join!(
+            self.review_store.list_unfinished_reviews(),
+            self.review_store.list_unreviewed_games(self.review_imports),
+        );
+        let interrupted: Vec<String> = match unfinished {
+            Ok(reviews) => reviews
+                .into_iter()
+                .filter(|(_, status)| {
+                    matches!(
+                        status,
+                        ReviewStatus::Analyzing { .. } | ReviewStatus::Queued
+                    )
+                })
+                .map(|(game_id, _)| game_id)
+                .collect(),
+            Err(e) => {
+                tracing::warn!("Failed to look up interrupted reviews: {}", e);
+                vec![]
+            }
+        };
+        let unreviewed = unreviewed.unwrap_or_else(|e| {
+            tracing::warn!("Failed to look up unreviewed games: {}", e);
+            vec![]
+        });
+
+        for game_id in interrupted {
+            tracing::info!(game_id = %game_id, "Recovering interrupted review");
+            match self.enqueue(&game_id).await {
+                Ok(_) => report.interrupted += 1,
+                Err(e) => {
+                    tracing::warn!(game_id = %game_id, "Failed to re-enqueue interrupted review: {}", e);
+                    report.failed += 1;
                 }
             }
         }
-
-        // 2. Scan for finished games with no review at all
-        if let Ok(games) = self.finished_game_store.list_games().await {
-            for game in games {
-                if self
-                    .review_store
-                    .load_review(&game.game_id)
-                    .await
-                    .ok()
-                    .flatten()
-                    .is_none()
-                {
-                    tracing::info!(
-                        game_id = %game.game_id,
-                        "Enqueueing finished game with no review"
-                    );
-                    if let Err(e) = self.enqueue(&game.game_id).await {
-                        tracing::warn!(
-                            game_id = %game.game_id,
-                            "Failed to enqueue unreviewed game: {}",
-                            e
-                        );
-                    } else {
-                        recovered += 1;
-                    }
+        for game_id in unreviewed {
+            tracing::info!(game_id = %game_id, "Enqueueing finished game with no review");
+            match self.enqueue(&game_id).await {
+                Ok(_) => report.unreviewed += 1,
+                Err(e) => {
+                    tracing::warn!(game_id = %game_id, "Failed to enqueue unreviewed game: {}", e);
+                    report.failed += 1;
                 }
             }
         }
 
-        if recovered > 0 {
-            tracing::info!(recovered, "Recovery complete, enqueued pending reviews");
+        report.elapsed = started.elapsed();
+        if report.interrupted + report.unreviewed + report.failed > 0 {
+            tracing::info!(
+                interrupted = report.interrupted,
+                unreviewed = report.unreviewed,
+                failed = report.failed,
+                elapsed_ms = report.elapsed.as_millis() as u64,
+                "Recovery complete, enqueued pending reviews"
+            );
         } else {
-            tracing::debug!("Recovery complete, no pending reviews found");
+            tracing::debug!(
+                elapsed_ms = report.elapsed.as_millis() as u64,
+                "Recovery complete, no pending reviews found"
+            );
         }
+        report
     }
 
-    /// Enqueue a game for review analysis.
-    /// Returns an error if the game_id is already queued or already reviewed.
-    pub async fn enqueue(&self, game_id: &str) -> Result<(), String> {
-        tracing::info!(game_id = %game_id, "Enqueueing game for review");
+    /// Run [`Self::recover_pending_reviews`] in the background, so the server
+    /// takes requests while a large backlog is queued. The receiver holds the
+    /// report once recovery is done.
+    pub fn spawn_recovery(self: &Arc<Self>) -> watch::Receiver<Option<RecoveryReport>> {
+        let (report_tx, report_rx) = watch::channel(None);
+        let manager = self.clone();
+        tokio::spawn(async move {
+            let report = manager.recover_pending_reviews().await;
+            report_tx.send_replace(Some(report));
+        });
+        report_rx
+    }
 
-        // Check if already enqueued (prevents duplicate jobs)
-        {
-            let enqueued = self.enqueued.read().await;
-            if enqueued.contains(game_id) {
-                tracing::warn!(game_id = %game_id, "Duplicate enqueue rejected");
-                return Err(format!("Game {} is already queued for review", game_id));
+    /// Queue again the reviews that failed because Stockfish was missing,
+    /// once it is found. Returns how many were queued.
+    pub async fn retry_engine_failures(&self) -> usize {
+        let Ok(reviews) = self.review_store.list_unfinished_reviews().await else {
+            return 0;
+        };
+        let mut retried = 0;
+        for (game_id, status) in reviews {
+            let ReviewStatus::Failed { error } = &status else {
+                continue;
+            };
+            if !error.contains(engine::STOCKFISH_NOT_FOUND) {
+                continue;
+            }
+            match self.enqueue(&game_id).await {
+                Ok(_) => retried += 1,
+                Err(e) => tracing::warn!(
+                    game_id = %game_id,
+                    "Failed to retry review: {}",
+                    e
+                ),
             }
         }
+        if retried > 0 {
+            tracing::info!(retried, "Retrying reviews that had no engine");
+        }
+        retried
+    }
 
-        // Check if review already exists and is complete
-        if let Ok(Some(review)) = self.review_store.load_review(game_id).await {
-            if review.status == ReviewStatus::Complete {
-                tracing::warn!(game_id = %game_id, "Review already complete, rejecting enqueue");
-                return Err(format!("Review for game {} already exists", game_id));
+    /// Enqueue a game for review analysis. Returns the job id.
+    pub async fn enqueue(&self, game_id: &str) -> Result<String, EnqueueError> {
+        self.enqueue_range(game_id, None, None, ReviewOptions::default())
+            .await
+    }
+
+    /// Enqueue analysis of plies `from_ply` to `to_ply` of a game (1-indexed,
+    /// inclusive; open ends run to the start or end of the game), optionally
+    /// with other options than the configured ones. Returns the job id.
+    ///
+    /// A range or depth may be asked for on a reviewed game: plies already
+    /// analysed at least that deep are kept, the others are (re-)analysed and
+    /// merged into the review.
+    ///
+    /// Enqueueing is idempotent: asking again for a job that is still pending,
+    /// e.g. when a client retries after a timeout, returns its id. Asking for
+    /// other plies or options while one is pending is a conflict.
+    pub async fn enqueue_range(
+        &self,
+        game_id: &str,
+        from_ply: Option<u32>,
+        to_ply: Option<u32>,
+        options: ReviewOptions,
+    ) -> Result<String, EnqueueError> {
+        tracing::info!(game_id = %game_id, ?from_ply, ?to_ply, ?options, "Enqueueing game for review");
+
+        let depth = options.depth;
+        if let Some(multipv) = options.multipv {
+            if multipv == 0 || multipv > MAX_REVIEW_MULTIPV {
+                return Err(EnqueueError::InvalidRequest(format!(
+                    "MultiPV must be between 1 and {}, got {}",
+                    MAX_REVIEW_MULTIPV, multipv
+                )));
             }
-            // If failed or partial, allow re-enqueue (will resume)
         }
+        if let Some(depth) = depth {
+            if depth == 0 || depth > MAX_REVIEW_DEPTH {
+                return Err(EnqueueError::InvalidRequest(format!(
+                    "Depth must be between 1 and {}, got {}",
+                    MAX_REVIEW_DEPTH, depth
+                )));
+            }
+        }
+        if let Some(decided_cp) = options.exclusions.and_then(|e| e.decided_cp) {
+            if decided_cp <= 0 {
+                return Err(EnqueueError::InvalidRequest(format!(
+                    "Decisiveness threshold must be positive, got {}",
+                    decided_cp
+                )));
+            }
+        }
+        let partial = from_ply.is_some() || to_ply.is_some() || depth.is_some();
 
         // Load the finished game data
         let game_data = self
             .finished_game_store
             .load_game(game_id)
             .await
-            .map_err(|e| format!("Failed to load game: {}", e))?
-            .ok_or_else(|| format!("Finished game not found: {}", game_id))?;
+            .map_err(|e| EnqueueError::Store(e.to_string()))?
+            .ok_or_else(|| EnqueueError::GameNotFound(game_id.to_string()))?;
+
+        let total_plies = game_data.moves.len() as u32;
+        let plies = from_ply.unwrap_or(1).max(1)..=to_ply.unwrap_or(total_plies);
+        if (partial && plies.is_empty()) || *plies.end() > total_plies {
+            return Err(EnqueueError::InvalidRequest(format!(
+                "Ply range {}-{} is outside the game's {} plies",
+                plies.start(),
+                plies.end(),
+                total_plies
+            )));
+        }
 
-        // Mark as enqueued before sending to prevent duplicate enqueue attempts
-        self.enqueued.write().await.insert(game_id.to_string());
+        // A pending job answers a repeat of itself (prevents duplicate jobs)
+        if let Some(pending) = self.enqueued.read().await.get(game_id) {
+            return pending.answer(game_id, &plies, options);
+        }
+
+        // Check if review already exists and is complete
+        if let Ok(Some(review)) = self.review_store.load_review(game_id).await {
+            if review.status == ReviewStatus::Complete && !partial {
+                tracing::warn!(game_id = %game_id, "Review already complete, rejecting enqueue");
+                return Err(EnqueueError::AlreadyReviewed(game_id.to_string()));
+            }
+            // If failed or partial, allow re-enqueue (will resume)
+        }
+
+        let cancelled = Arc::new(AtomicBool::new(false));
+        // Mark as enqueued before sending to prevent duplicate enqueue attempts.
+        // The slot is taken under the write lock so two racing requests get
+        // the same job.
+        let job_id = {
+            let mut enqueued = self.enqueued.write().await;
+            if let Some(pending) = enqueued.get(game_id) {
+                return pending.answer(game_id, &plies, options);
+            }
+            let job_id = format!(
+                "{}#{}",
+                game_id,
+                self.next_job.fetch_add(1, Ordering::Relaxed)
+            );
+            enqueued.insert(
+                game_id.to_string(),
+                PendingJob {
+                    job_id: job_id.clone(),
+                    plies: plies.clone(),
+                    options,
+                    cancelled: cancelled.clone(),
+                },
+            );
+            job_id
+        };
 
         // Send to job queue
         let job = ReviewJob {
+            job_id: job_id.clone(),
             game_id: game_id.to_string(),
             game_data,
+            plies,
+            options,
+            cancelled,
         };
         if let Err(e) = self.job_tx.send(job).await {
             // Roll back: remove from enqueued set since the job wasn't actually sent
             self.enqueued.write().await.remove(game_id);
             tracing::error!(game_id = %game_id, "Failed to send job to queue: {}", e);
-            return Err("Review job queue full or closed".to_string());
+            return Err(EnqueueError::QueueClosed);
         }
 
-        tracing::info!(game_id = %game_id, "Job sent to review queue");
-        Ok(())
+        tracing::info!(game_id = %game_id, job_id = %job_id, "Job sent to review queue");
+        Ok(job_id)
+    }
+
+    /// The id of the job pending for a game, by game id or the id of one of
+    /// its jobs, if any.
+    pub async fn pending_job_id(&self, id: &str) -> Option<String> {
+        let game_id = game_of_job(id).unwrap_or(id);
+        self.enqueued
+            .read()
+            .await
+            .get(game_id)
+            .map(|pending| pending.job_id.clone())
+    }
+
+    /// Cancel the job pending for a game, by game id or job id. A queued job
+    /// is skipped when a worker picks it up; one being analysed stops before
+    /// its next ply. The game is let go of at once, so it can be deleted or
+    /// queued again, and an unfinished review is marked failed with
+    /// [`REVIEW_CANCELLED`], keeping the plies analysed so far. Returns the
+    /// id of the job cancelled.
+    pub async fn cancel_review(&self, id: &str) -> Result<String, String> {
+        let game_id = game_of_job(id).unwrap_or(id);
+        let pending = self
+            .enqueued
+            .write()
+            .await
+            .remove(game_id)
+            .ok_or_else(|| format!("Game {} has no pending review", game_id))?;
+        pending.cancelled.store(true, Ordering::Relaxed);
+        tracing::info!(game_id = %game_id, job_id = %pending.job_id, "Review cancelled");
+
+        // Otherwise the review would stay Queued or Analyzing and be
+        // recovered on the next start
+        if let Ok(Some(review)) = self.review_store.load_review(game_id).await {
+            if review.status != ReviewStatus::Complete {
+                let cancelled = GameReview {
+                    status: ReviewStatus::Failed {
+                        error: REVIEW_CANCELLED.to_string(),
+                    },
+                    ..review
+                };
+                if let Err(e) = self.review_store.save_review(&cancelled).await {
+                    tracing::warn!(game_id = %game_id, "Failed to mark review cancelled: {}", e);
+                }
+            }
+        }
+        // A job still queued never reaches a worker to report it
+        let _ = self.progress.send(ReviewProgress {
+            game_id: game_id.to_string(),
+            job_id: Some(pending.job_id.clone()),
+            status: ReviewStatus::Failed {
+                error: REVIEW_CANCELLED.to_string(),
+            },
+            eta: None,
+        });
+        Ok(pending.job_id)
     }
 
-    /// Get the status of a review for a given game_id.
-    pub async fn get_status(&self, game_id: &str) -> Result<ReviewStatus, String> {
+    /// Follow a game's review, by game id or job id: where it is now, then
+    /// every update the workers publish, for this game and others.
+    pub async fn subscribe_progress(
+        &self,
+        id: &str,
+    ) -> Result<(ReviewProgress, broadcast::Receiver<ReviewProgress>), String> {
+        // Subscribed first, so an update published meanwhile is not missed
+        let updates = self.progress.subscribe();
+        let current = self.current_progress(id).await?;
+        Ok((current, updates))
+    }
+
+    /// Where a game's review is now, by game id or job id, from the pending
+    /// jobs and the store rather than the workers' updates.
+    pub async fn current_progress(&self, id: &str) -> Result<ReviewProgress, String> {
+        let game_id = game_of_job(id).unwrap_or(id);
+        let status = self.get_status(game_id).await?;
+        Ok(ReviewProgress {
+            game_id: game_id.to_string(),
+            job_id: self.pending_job_id(game_id).await,
+            status,
+            eta: None,
+        })
+    }
+
+    /// Re-analyse the move at `ply` of a reviewed game, by default
+    /// [`REANALYZE_DEPTH_STEP`] deeper than it was analysed. Only that
+    /// position goes back to the engine; the worker merges it into the
+    /// review and recomputes the accuracies and the advanced analysis.
+    /// Returns the job id and the depth asked for.
+    pub async fn reanalyze_position(
+        &self,
+        game_id: &str,
+        ply: u32,
+        depth: Option<u32>,
+    ) -> Result<(String, u32), String> {
+        let review = self
+            .get_review(game_id)
+            .await?
+            .filter(|r| r.status == ReviewStatus::Complete)
+            .ok_or_else(|| format!("Game {} has no finished review", game_id))?;
+        if ply == 0 || ply > review.total_plies {
+            return Err(format!("Game {} has no ply {}", game_id, ply));
+        }
+        let analysed = review
+            .positions
+            .iter()
+            .find(|p| p.ply == ply)
+            .map(|p| p.depth);
+        let depth = depth.unwrap_or_else(|| {
+            (analysed.unwrap_or(review.analysis_depth) + REANALYZE_DEPTH_STEP).min(MAX_REVIEW_DEPTH)
+        });
+        if let Some(analysed) = analysed.filter(|analysed| depth <= *analysed) {
+            return Err(format!(
+                "Ply {} is already analysed at depth {}",
+                ply, analysed
+            ));
+        }
+        let options = ReviewOptions {
+            depth: Some(depth),
+            ..Default::default()
+        };
+        let job_id = self
+            .enqueue_range(game_id, Some(ply), Some(ply), options)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok((job_id, depth))
+    }
+
+    /// Get the status of a review by game id, or by the id of a job queued
+    /// for the game; a finished job reports the game's review.
+    pub async fn get_status(&self, id: &str) -> Result<ReviewStatus, String> {
+        let game_id = game_of_job(id).unwrap_or(id);
         // Check if it's in the enqueued set (job is pending or in-flight)
-        if self.enqueued.read().await.contains(game_id) {
+        if self.enqueued.read().await.contains_key(game_id) {
             // Check the store for in-progress updates from the worker
             if let Ok(Some(review)) = self.review_store.load_review(game_id).await {
                 if let ReviewStatus::Analyzing { .. } = review.status {
@@ -266,6 +638,22 @@ impl<D: Persistence> ReviewManager<D> {
             .map_err(|e| e.to_string())
     }
 
+    /// Get a finished game's stored data.
+    pub async fn get_finished_game(
+        &self,
+        game_id: &str,
+    ) -> Result<Option<crate::persistence::FinishedGameData>, String> {
+        self.finished_game_store
+            .load_game(game_id)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// The store reviewed games are read from, for adding games to it.
+    pub fn finished_game_store(&self) -> Arc<D::FinishedGames> {
+        self.finished_game_store.clone()
+    }
+
     /// List all finished games eligible for review.
     pub async fn list_finished_games(
         &self,
@@ -276,10 +664,225 @@ impl<D: Persistence> ReviewManager<D> {
             .map_err(|e| e.to_string())
     }
 
+    /// Combined report for every finished board of a simul.
+    pub async fn simul_report(&self, simul_id: &str) -> Result<simul::SimulReport, String> {
+        let games = self
+            .finished_game_store
+            .list_games_by_simul(simul_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        if games.is_empty() {
+            return Err(format!("No finished games for simul {}", simul_id));
+        }
+
+        let mut with_reviews = Vec::with_capacity(games.len());
+        for game in games {
+            let review = self.get_review(&game.game_id).await?;
+            with_reviews.push((game, review));
+        }
+        Ok(simul::SimulReport::new(simul_id, with_reviews))
+    }
+
+    /// A time control run over a finished game's recorded think times.
+    pub async fn simulate_clock(
+        &self,
+        game_id: &str,
+        control: analysis::TimeControl,
+    ) -> Result<clock::GameClockSimulation, String> {
+        let game = self
+            .get_finished_game(game_id)
+            .await?
+            .ok_or_else(|| format!("Finished game not found: {}", game_id))?;
+        Ok(clock::GameClockSimulation::new(&game, control))
+    }
+
+    /// Compare an earlier pass of a game's review with the current one:
+    /// `revision` if given, else the latest.
+    pub async fn compare_reviews(
+        &self,
+        game_id: &str,
+        revision: Option<u32>,
+    ) -> Result<ReviewComparison, String> {
+        let review = self
+            .get_review(game_id)
+            .await?
+            .ok_or_else(|| format!("No review found for game {}", game_id))?;
+        let revisions = self
+            .review_store
+            .list_revisions(game_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        let compared = match revision {
+            Some(n) => Some(
+                revisions
+                    .iter()
+                    .find(|r| r.revision == n)
+                    .ok_or_else(|| format!("Game {} has no review revision {}", game_id, n))?,
+            ),
+            None => revisions.last(),
+        };
+        let diff = compared
+            .map(|r| diff_reviews(&r.positions, &review.positions))
+            .unwrap_or_default();
+        let compared = compared.map(|r| r.revision);
+        Ok(ReviewComparison {
+            revisions,
+            compared,
+            diff,
+        })
+    }
+
+    /// Bookmark the position after `ply` of a finished game, replacing the
+    /// note of an earlier bookmark on it.
+    pub async fn set_bookmark(
+        &self,
+        game_id: &str,
+        ply: u32,
+        note: &str,
+    ) -> Result<ReviewBookmark, String> {
+        let game = self
+            .get_finished_game(game_id)
+            .await?
+            .ok_or_else(|| format!("Finished game not found: {}", game_id))?;
+        if ply as usize > game.moves.len() {
+            return Err(format!("Game {} has no ply {}", game_id, ply));
+        }
+        let bookmark = ReviewBookmark {
+            game_id: game_id.to_string(),
+            ply,
+            note: note.trim().to_string(),
+            created_at: crate::persistence::now_timestamp(),
+        };
+        self.review_store
+            .save_bookmark(&bookmark)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(bookmark)
+    }
+
+    /// A game's bookmarks, in ply order.
+    pub async fn list_bookmarks(&self, game_id: &str) -> Result<Vec<ReviewBookmark>, String> {
+        self.review_store
+            .list_bookmarks(game_id)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn delete_bookmark(&self, game_id: &str, ply: u32) -> Result<(), String> {
+        self.review_store
+            .delete_bookmark(game_id, ply)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Why the best move at `ply` of a reviewed game is best, from the best
+    /// line the review found there.
+    pub async fn explain_position(
+        &self,
+        game_id: &str,
+        ply: u32,
+    ) -> Result<(PositionReview, MoveExplanation), String> {
+        let review = self
+            .get_review(game_id)
+            .await?
+            .ok_or_else(|| format!("No review found for game {}", game_id))?;
+        let position = review
+            .positions
+            .into_iter()
+            .find(|p| p.ply == ply)
+            .ok_or_else(|| format!("Ply {} of game {} was not analysed", ply, game_id))?;
+        let game = self
+            .get_finished_game(game_id)
+            .await?
+            .ok_or_else(|| format!("Finished game not found: {}", game_id))?;
+        let fen_before = match ply {
+            0 | 1 => game.start_fen.clone(),
+            _ => game
+                .moves
+                .get(ply as usize - 2)
+                .map(|m| m.fen_after.clone())
+                .ok_or_else(|| format!("Game {} has no ply {}", game_id, ply))?,
+        };
+        let line = if position.pv.is_empty() {
+            vec![position.best_move_uci.clone()]
+        } else {
+            position.pv.clone()
+        };
+        let explanation = explain_best_line(&fen_before, &line)
+            .ok_or_else(|| format!("The best line at ply {} could not be played", ply))?;
+        Ok((position, explanation))
+    }
+
+    /// Head-to-head records against every named opponent.
+    pub async fn opponent_stats(&self) -> Result<Vec<opponents::OpponentStats>, String> {
+        let games = self.list_finished_games().await?;
+        let mut with_reviews = Vec::with_capacity(games.len());
+        for game in games.into_iter().filter(|g| g.opponent.is_some()) {
+            let review = self.get_review(&game.game_id).await?;
+            with_reviews.push((game, review));
+        }
+        Ok(opponents::opponent_stats(with_reviews))
+    }
+
+    /// The closest position of each finished game to `target`, most similar
+    /// first.
+    pub async fn similar_positions(
+        &self,
+        target: &PositionSignature,
+        limit: usize,
+    ) -> Result<Vec<similar::SimilarPosition>, String> {
+        let games = self.list_finished_games().await?;
+        Ok(similar::similar_positions(target, &games, limit))
+    }
+
+    /// Mean accuracy over the player's reviewed games finished on or after
+    /// `since_day`.
+    pub async fn average_accuracy_since(&self, since_day: u32) -> Result<Option<f64>, String> {
+        let mut accuracies = Vec::new();
+        for game in self.list_finished_games().await? {
+            if crate::persistence::day_of(game.created_at) < since_day
+                || !outcome::is_players_game(&game)
+            {
+                continue;
+            }
+            if let Some(review) = self.get_review(&game.game_id).await? {
+                accuracies.extend(outcome::human_accuracy(&game, &review));
+            }
+        }
+        Ok(outcome::mean_accuracy(accuracies))
+    }
+
+    /// Training digest for the `days` days ending today.
+    pub async fn training_digest(&self, days: u32) -> Result<digest::TrainingDigest, String> {
+        let days = if days == 0 {
+            digest::DEFAULT_DIGEST_DAYS
+        } else {
+            days
+        };
+        let today = crate::persistence::day_of(crate::persistence::now_timestamp());
+        // The period before this one feeds the accuracy comparison
+        let since = (today + 1).saturating_sub(days.saturating_mul(2));
+
+        let mut games = Vec::new();
+        for game in self.list_finished_games().await? {
+            if crate::persistence::day_of(game.created_at) < since {
+                continue;
+            }
+            let review = self.get_review(&game.game_id).await?;
+            let advanced = self.get_advanced_analysis(&game.game_id).await?;
+            games.push(digest::DigestGame {
+                game,
+                review,
+                advanced,
+            });
+        }
+        Ok(digest::TrainingDigest::new(games, today, days))
+    }
+
     /// Delete a finished game and its associated review.
     pub async fn delete_finished_game(&self, game_id: &str) -> Result<(), String> {
         // Don't allow deleting games that are currently being analyzed
-        if self.enqueued.read().await.contains(game_id) {
+        if self.enqueued.read().await.contains_key(game_id) {
             return Err(format!(
                 "Cannot delete game {} while it is queued for review",
                 game_id
@@ -303,6 +906,24 @@ impl<D: Persistence> ReviewManager<D> {
     }
 }
 
+/// The job `id` names, if it is a job id rather than a game id.
+pub fn requested_job(id: &str) -> Option<&str> {
+    game_of_job(id).map(|_| id)
+}
+
+/// The game a job id of the form `<game_id>#<seq>` was issued for.
+fn game_of_job(id: &str) -> Option<&str> {
+    let (game_id, seq) = id.rsplit_once('#')?;
+    (!seq.is_empty() && seq.bytes().all(|b| b.is_ascii_digit())).then_some(game_id)
+}
+
+fn job_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(1)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -360,10 +981,16 @@ mod tests {
         let (_keep_tx, keep_rx) = mpsc::channel::<ReviewJob>(1);
         ReviewManager {
             job_tx,
-            enqueued: Arc::new(RwLock::new(HashSet::new())),
+            enqueued: Arc::new(RwLock::new(HashMap::new())),
+            next_job: AtomicU64::new(1),
             review_store: reviews,
             finished_game_store: finished,
             advanced_store: advanced,
+            review_imports: true,
+            paused: watch::channel(false).0,
+            low_power: AtomicBool::new(false),
+            engine_missing: AtomicBool::new(false),
+            progress: broadcast::channel(PROGRESS_CAPACITY).0,
             _job_rx: Arc::new(Mutex::new(keep_rx)),
         }
     }
@@ -371,10 +998,7 @@ mod tests {
     /// Build a minimal finished game fixture.
     fn sample_finished_game(game_id: &str) -> FinishedGameData {
         FinishedGameData {
-            game_id: game_id.to_string(),
-            start_fen: "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".to_string(),
             result: "BlackWins".to_string(),
-            result_reason: "Checkmate".to_string(),
             game_mode: "HumanVsHuman".to_string(),
             human_side: None,
             skill_level: 0,
@@ -384,47 +1008,36 @@ mod tests {
                     from: "f2".into(),
                     to: "f3".into(),
                     piece: "P".into(),
-                    captured: None,
-                    promotion: None,
-                    san: "f3".into(),
                     fen_after: "rnbqkbnr/pppppppp/8/8/8/5P2/PPPPP1PP/RNBQKBNR b KQkq - 0 1".into(),
-                    clock_ms: None,
+                    ..StoredMoveRecord::sample("f3")
                 },
                 StoredMoveRecord {
                     from: "e7".into(),
                     to: "e5".into(),
                     piece: "P".into(),
-                    captured: None,
-                    promotion: None,
-                    san: "e5".into(),
                     fen_after: "rnbqkbnr/pppp1ppp/8/4p3/8/5P2/PPPPP1PP/RNBQKBNR w KQkq e6 0 2"
                         .into(),
-                    clock_ms: None,
+                    ..StoredMoveRecord::sample("e5")
                 },
                 StoredMoveRecord {
                     from: "g2".into(),
                     to: "g4".into(),
                     piece: "P".into(),
-                    captured: None,
-                    promotion: None,
-                    san: "g4".into(),
                     fen_after: "rnbqkbnr/pppp1ppp/8/4p3/6P1/5P2/PPPPP2P/RNBQKBNR b KQkq g3 0 2"
                         .into(),
-                    clock_ms: None,
+                    ..StoredMoveRecord::sample("g4")
                 },
                 StoredMoveRecord {
                     from: "d8".into(),
                     to: "h4".into(),
                     piece: "Q".into(),
-                    captured: None,
-                    promotion: None,
-                    san: "Qh4#".into(),
                     fen_after: "rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3"
                         .into(),
-                    clock_ms: None,
+                    ..StoredMoveRecord::sample("Qh4#")
                 },
             ],
             created_at: 1000,
+            ..FinishedGameData::sample(game_id)
         }
     }
 
@@ -439,7 +1052,7 @@ mod tests {
         assert!(result.is_err());
 
         // game_id should NOT remain in the enqueued set — a retry must be possible
-        assert!(!mgr.enqueued.read().await.contains("game_1"));
+        assert!(!mgr.enqueued.read().await.contains_key("game_1"));
     }
 
     #[tokio::test]
@@ -449,7 +1062,7 @@ mod tests {
 
         let result = mgr.enqueue("nonexistent").await;
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("not found"));
+        assert!(matches!(result, Err(EnqueueError::GameNotFound(_))));
     }
 
     #[tokio::test]
@@ -465,15 +1078,28 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_duplicate_enqueue_rejected() {
+    async fn test_duplicate_enqueue_returns_pending_job() {
         let (finished, reviews, advanced) = test_stores();
         finished.save(&sample_finished_game("game_1")).unwrap();
         let mgr = test_manager_no_workers(finished, reviews, advanced);
 
-        mgr.enqueue("game_1").await.unwrap();
-        let result = mgr.enqueue("game_1").await;
-        assert!(result.is_err());
-        assert!(result.unwrap_err().contains("already queued"));
+        let job_id = mgr.enqueue("game_1").await.unwrap();
+        assert_eq!(mgr.enqueue("game_1").await.unwrap(), job_id);
+        assert_eq!(mgr.pending_job_id("game_1").await, Some(job_id.clone()));
+        // Only one job was queued
+        let mut rx = mgr._job_rx.lock().await;
+        assert_eq!(rx.try_recv().unwrap().job_id, job_id);
+        assert!(rx.try_recv().is_err());
+        drop(rx);
+
+        let other = mgr
+            .enqueue_range("game_1", Some(2), None, ReviewOptions::default())
+            .await;
+        assert!(matches!(other, Err(EnqueueError::Conflict(_))));
+
+        // Status by job id is the game's
+        assert_eq!(mgr.get_status(&job_id).await.unwrap(), ReviewStatus::Queued);
+        assert!(mgr.get_status("game_2#1").await.is_err());
     }
 
     #[tokio::test]
@@ -494,6 +1120,8 @@ mod tests {
             started_at: Some(1000),
             completed_at: Some(2000),
             winner: Some("White".to_string()),
+            opening: None,
+            endgame: None,
         };
         reviews.save(&completed).unwrap();
 
@@ -501,7 +1129,195 @@ mod tests {
 
         let result = mgr.enqueue("game_1").await;
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("already exists"));
+        assert!(matches!(result, Err(EnqueueError::AlreadyReviewed(_))));
+    }
+
+    #[tokio::test]
+    async fn test_enqueue_range_on_completed_review() {
+        let (finished, reviews, advanced) = test_stores();
+        finished.save(&sample_finished_game("game_1")).unwrap();
+        reviews
+            .save(&GameReview {
+                game_id: "game_1".to_string(),
+                status: ReviewStatus::Complete,
+                positions: vec![],
+                white_accuracy: None,
+                black_accuracy: None,
+                total_plies: 4,
+                analyzed_plies: 4,
+                analysis_depth: 18,
+                started_at: None,
+                completed_at: None,
+                winner: None,
+                opening: None,
+                endgame: None,
+            })
+            .unwrap();
+        let mgr = test_manager_no_workers(finished, reviews, advanced);
+
+        let depth = |depth| ReviewOptions {
+            depth: Some(depth),
+            ..Default::default()
+        };
+        let err = mgr
+            .enqueue_range("game_1", Some(3), Some(5), ReviewOptions::default())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("outside"), "{}", err);
+        assert!(mgr
+            .enqueue_range("game_1", None, None, depth(0))
+            .await
+            .is_err());
+        let too_many_lines = ReviewOptions {
+            multipv: Some(MAX_REVIEW_MULTIPV + 1),
+            ..depth(24)
+        };
+        assert!(mgr
+            .enqueue_range("game_1", None, None, too_many_lines)
+            .await
+            .is_err());
+
+        mgr.enqueue_range("game_1", Some(2), None, depth(24))
+            .await
+            .unwrap();
+        let job = mgr._job_rx.lock().await.try_recv().unwrap();
+        assert_eq!(job.plies, 2..=4);
+        assert_eq!(job.options.depth, Some(24));
+    }
+
+    #[tokio::test]
+    async fn test_reanalyze_position_goes_deeper() {
+        let (finished, reviews, advanced) = test_stores();
+        finished.save(&sample_finished_game("game_1")).unwrap();
+        let position = |ply: u32, depth: u32| PositionReview {
+            ply,
+            fen: String::new(),
+            played_san: String::new(),
+            best_move_san: String::new(),
+            best_move_uci: String::new(),
+            eval_before: AnalysisScore::Centipawns(0),
+            eval_after: AnalysisScore::Centipawns(0),
+            eval_best: AnalysisScore::Centipawns(0),
+            classification: MoveClassification::Best,
+            cp_loss: 0,
+            pv: vec![],
+            depth,
+            clock_ms: None,
+            excluded: None,
+            only_move: false,
+        };
+        reviews
+            .save(&GameReview {
+                game_id: "game_1".to_string(),
+                status: ReviewStatus::Complete,
+                positions: vec![position(1, 12), position(2, MAX_REVIEW_DEPTH)],
+                white_accuracy: None,
+                black_accuracy: None,
+                total_plies: 4,
+                analyzed_plies: 2,
+                analysis_depth: 12,
+                started_at: None,
+                completed_at: None,
+                winner: None,
+                opening: None,
+                endgame: None,
+            })
+            .unwrap();
+        let mgr = test_manager_no_workers(finished, reviews, advanced);
+
+        assert!(mgr.reanalyze_position("game_1", 5, None).await.is_err());
+        assert!(mgr.reanalyze_position("game_1", 1, Some(10)).await.is_err());
+        let err = mgr.reanalyze_position("game_1", 2, None).await.unwrap_err();
+        assert!(err.contains("already analysed"), "{}", err);
+
+        let (job_id, depth) = mgr.reanalyze_position("game_1", 1, None).await.unwrap();
+        assert_eq!(depth, 18);
+        let job = mgr._job_rx.lock().await.try_recv().unwrap();
+        assert_eq!(job.job_id, job_id);
+        assert_eq!(job.plies, 1..=1);
+        assert_eq!(job.options.depth, Some(18));
+    }
+
+    #[tokio::test]
+    async fn test_compare_reviews_against_revision() {
+        let (finished, reviews, advanced) = test_stores();
+        finished.save(&sample_finished_game("game_1")).unwrap();
+        let mut review = GameReview {
+            game_id: "game_1".to_string(),
+            status: ReviewStatus::Complete,
+            positions: vec![],
+            white_accuracy: None,
+            black_accuracy: None,
+            total_plies: 4,
+            analyzed_plies: 0,
+            analysis_depth: 8,
+            started_at: None,
+            completed_at: None,
+            winner: None,
+            opening: None,
+            endgame: None,
+        };
+        reviews.save(&review).unwrap();
+        let mgr = test_manager_no_workers(finished, reviews.clone(), advanced);
+
+        let comparison = mgr.compare_reviews("game_1", None).await.unwrap();
+        assert!(comparison.revisions.is_empty());
+        assert_eq!(comparison.compared, None);
+
+        reviews
+            .save_revision(&ReviewRevision::of(&review, 1, 100))
+            .unwrap();
+        review.analysis_depth = 20;
+        reviews.save(&review).unwrap();
+        let comparison = mgr.compare_reviews("game_1", None).await.unwrap();
+        assert_eq!(comparison.compared, Some(1));
+        assert_eq!(comparison.revisions[0].analysis_depth, 8);
+        assert!(mgr.compare_reviews("game_1", Some(2)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_explain_position_plays_out_the_best_line() {
+        let (finished, reviews, advanced) = test_stores();
+        finished.save(&sample_finished_game("game_1")).unwrap();
+        let review = GameReview {
+            game_id: "game_1".to_string(),
+            status: ReviewStatus::Complete,
+            positions: vec![PositionReview {
+                ply: 4,
+                fen: String::new(),
+                played_san: "Qh4#".to_string(),
+                best_move_san: "Qh4#".to_string(),
+                best_move_uci: "d8h4".to_string(),
+                eval_before: AnalysisScore::Mate(1),
+                eval_after: AnalysisScore::Mate(-1),
+                eval_best: AnalysisScore::Mate(-1),
+                classification: MoveClassification::Best,
+                cp_loss: 0,
+                pv: vec!["d8h4".to_string()],
+                depth: 12,
+                clock_ms: None,
+                excluded: None,
+                only_move: false,
+            }],
+            white_accuracy: None,
+            black_accuracy: None,
+            total_plies: 4,
+            analyzed_plies: 1,
+            analysis_depth: 12,
+            started_at: None,
+            completed_at: None,
+            winner: None,
+            opening: None,
+            endgame: None,
+        };
+        reviews.save(&review).unwrap();
+        let mgr = test_manager_no_workers(finished, reviews, advanced);
+
+        let (position, explanation) = mgr.explain_position("game_1", 4).await.unwrap();
+        assert_eq!(position.best_move_san, "Qh4#");
+        assert_eq!(explanation.line[0].san, "Qh4");
+        assert!(explanation.ends_in_mate);
+        assert!(mgr.explain_position("game_1", 3).await.is_err());
     }
 
     #[tokio::test]
@@ -524,6 +1340,8 @@ mod tests {
             started_at: Some(1000),
             completed_at: None,
             winner: None,
+            opening: None,
+            endgame: None,
         };
         reviews.save(&failed).unwrap();
 
@@ -570,6 +1388,8 @@ mod tests {
             started_at: Some(1000),
             completed_at: Some(2000),
             winner: Some("Black".to_string()),
+            opening: None,
+            endgame: None,
         };
         reviews.save(&review).unwrap();
 
@@ -611,6 +1431,8 @@ mod tests {
             started_at: Some(1000),
             completed_at: Some(2000),
             winner: Some("Draw".to_string()),
+            opening: None,
+            endgame: None,
         };
         reviews.save(&review).unwrap();
 
@@ -623,6 +1445,31 @@ mod tests {
         assert!(reviews.load("game_1").unwrap().is_none());
     }
 
+    #[tokio::test]
+    async fn test_bookmarks_stay_within_the_game() {
+        let (finished, reviews, advanced) = test_stores();
+        finished.save(&sample_finished_game("game_1")).unwrap();
+        let mgr = test_manager_no_workers(finished, reviews, advanced);
+
+        let bookmark = mgr.set_bookmark("game_1", 4, "  mate  ").await.unwrap();
+        assert_eq!(bookmark.note, "mate");
+        mgr.set_bookmark("game_1", 0, "").await.unwrap();
+        assert!(mgr.set_bookmark("game_1", 5, "").await.is_err());
+        assert!(mgr.set_bookmark("game_2", 1, "").await.is_err());
+
+        let plies: Vec<u32> = mgr
+            .list_bookmarks("game_1")
+            .await
+            .unwrap()
+            .iter()
+            .map(|b| b.ply)
+            .collect();
+        assert_eq!(plies, vec![0, 4]);
+
+        mgr.delete_bookmark("game_1", 0).await.unwrap();
+        assert_eq!(mgr.list_bookmarks("game_1").await.unwrap().len(), 1);
+    }
+
     #[tokio::test]
     async fn test_delete_blocks_while_enqueued() {
         let (finished, reviews, advanced) = test_stores();
@@ -636,6 +1483,177 @@ mod tests {
         assert!(result.unwrap_err().contains("queued for review"));
     }
 
+    #[tokio::test]
+    async fn test_cancel_review_releases_game() {
+        let (finished, reviews, advanced) = test_stores();
+        finished.save(&sample_finished_game("game_1")).unwrap();
+        let mgr = test_manager_no_workers(finished, reviews.clone(), advanced);
+
+        let job_id = mgr.enqueue("game_1").await.unwrap();
+        // A worker got partway
+        reviews
+            .save(&GameReview {
+                game_id: "game_1".to_string(),
+                status: ReviewStatus::Analyzing {
+                    current_ply: 2,
+                    total_plies: 4,
+                },
+                positions: vec![],
+                white_accuracy: None,
+                black_accuracy: None,
+                total_plies: 4,
+                analyzed_plies: 2,
+                analysis_depth: 1,
+                started_at: None,
+                completed_at: None,
+                winner: None,
+                opening: None,
+                endgame: None,
+            })
+            .unwrap();
+
+        assert_eq!(mgr.cancel_review(&job_id).await.unwrap(), job_id);
+        let job = mgr._job_rx.lock().await.try_recv().unwrap();
+        assert!(job.cancelled.load(Ordering::Relaxed));
+        assert_eq!(
+            mgr.get_status("game_1").await.unwrap(),
+            ReviewStatus::Failed {
+                error: REVIEW_CANCELLED.to_string()
+            }
+        );
+        assert!(mgr.cancel_review("game_1").await.is_err());
+
+        // Queued again, it gets a new job
+        assert_ne!(mgr.enqueue("game_1").await.unwrap(), job_id);
+        mgr.cancel_review("game_1").await.unwrap();
+        mgr.delete_finished_game("game_1").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_worker_cancelled_mid_ply_leaves_review_alone() {
+        let (finished, reviews, advanced) = test_stores();
+        finished.save(&sample_finished_game("game_1")).unwrap();
+        let mgr = test_manager_no_workers(finished, reviews.clone(), advanced);
+        let analyzing = |current_ply| GameReview {
+            game_id: "game_1".to_string(),
+            status: ReviewStatus::Analyzing {
+                current_ply,
+                total_plies: 4,
+            },
+            positions: vec![],
+            white_accuracy: None,
+            black_accuracy: None,
+            total_plies: 4,
+            analyzed_plies: current_ply,
+            analysis_depth: 1,
+            started_at: None,
+            completed_at: None,
+            winner: None,
+            opening: None,
+            endgame: None,
+        };
+
+        // A worker picks up the job and is mid-ply when it is cancelled
+        mgr.enqueue("game_1").await.unwrap();
+        let first = mgr._job_rx.lock().await.try_recv().unwrap();
+        mgr.cancel_review("game_1").await.unwrap();
+
+        // Queued again, the new job gets going before the first one's ply ends
+        mgr.enqueue("game_1").await.unwrap();
+        let second = mgr._job_rx.lock().await.try_recv().unwrap();
+        reviews.save(&analyzing(1)).unwrap();
+
+        let failed = worker::fail_job::<JsonPersistence>(
+            reviews.as_ref(),
+            &mgr.enqueued,
+            &first,
+            1,
+            REVIEW_CANCELLED.to_string(),
+        )
+        .await;
+        assert_eq!(failed, None);
+        assert_eq!(
+            mgr.get_status("game_1").await.unwrap(),
+            ReviewStatus::Analyzing {
+                current_ply: 1,
+                total_plies: 4
+            }
+        );
+
+        // Cancelled and deleted, the game gets no review row back
+        mgr.cancel_review("game_1").await.unwrap();
+        mgr.delete_finished_game("game_1").await.unwrap();
+        let failed = worker::fail_job::<JsonPersistence>(
+            reviews.as_ref(),
+            &mgr.enqueued,
+            &second,
+            1,
+            REVIEW_CANCELLED.to_string(),
+        )
+        .await;
+        assert_eq!(failed, None);
+        assert!(mgr.get_review("game_1").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_progress_reports_current_then_updates() {
+        let (finished, reviews, advanced) = test_stores();
+        finished.save(&sample_finished_game("game_1")).unwrap();
+        let mgr = test_manager_no_workers(finished, reviews, advanced);
+
+        assert!(mgr.subscribe_progress("game_1").await.is_err());
+
+        let job_id = mgr.enqueue("game_1").await.unwrap();
+        let (current, mut updates) = mgr.subscribe_progress(&job_id).await.unwrap();
+        assert_eq!(current.game_id, "game_1");
+        assert_eq!(current.job_id.as_deref(), Some(job_id.as_str()));
+        assert_eq!(current.status, ReviewStatus::Queued);
+        assert!(!current.is_finished());
+
+        // A queued job that is cancelled never reaches a worker
+        mgr.cancel_review("game_1").await.unwrap();
+        let update = updates.try_recv().unwrap();
+        assert_eq!(update.job_id, Some(job_id));
+        assert_eq!(
+            update.status,
+            ReviewStatus::Failed {
+                error: REVIEW_CANCELLED.to_string()
+            }
+        );
+        assert!(update.is_finished());
+    }
+
+    #[tokio::test]
+    async fn test_progress_of_a_job_ignores_earlier_jobs() {
+        let (finished, reviews, advanced) = test_stores();
+        finished.save(&sample_finished_game("game_1")).unwrap();
+        let mgr = test_manager_no_workers(finished, reviews, advanced);
+
+        let first = mgr.enqueue("game_1").await.unwrap();
+        mgr.cancel_review(&first).await.unwrap();
+        let second = mgr.enqueue("game_1").await.unwrap();
+        assert_eq!(requested_job(&second), Some(second.as_str()));
+        assert_eq!(requested_job("game_1"), None);
+
+        // The first job's failure arrives late
+        let late = ReviewProgress {
+            game_id: "game_1".to_string(),
+            job_id: Some(first),
+            status: ReviewStatus::Failed {
+                error: REVIEW_CANCELLED.to_string(),
+            },
+            eta: None,
+        };
+        assert!(late.concerns("game_1", None));
+        assert!(!late.concerns("game_1", Some(&second)));
+        assert!(!late.concerns("game_2", None));
+
+        // Catching up after a lag reads the pending job
+        let current = mgr.current_progress(&second).await.unwrap();
+        assert!(current.concerns("game_1", Some(&second)));
+        assert_eq!(current.status, ReviewStatus::Queued);
+    }
+
     #[tokio::test]
     async fn test_delete_nonexistent_is_ok() {
         let (finished, reviews, advanced) = test_stores();
@@ -666,6 +1684,8 @@ mod tests {
             started_at: Some(1000),
             completed_at: None,
             winner: None,
+            opening: None,
+            endgame: None,
         };
         reviews.save(&stuck).unwrap();
 
@@ -694,12 +1714,33 @@ mod tests {
         finished.save(&sample_finished_game("game_1")).unwrap();
 
         let mgr = test_manager_no_workers(finished, reviews, advanced);
-        mgr.recover_pending_reviews().await;
+        let report = mgr.recover_pending_reviews().await;
+        assert_eq!(
+            (report.interrupted, report.unreviewed, report.failed),
+            (0, 1, 0)
+        );
 
         let status = mgr.get_status("game_1").await.unwrap();
         assert_eq!(status, ReviewStatus::Queued);
     }
 
+    #[tokio::test]
+    async fn test_recover_leaves_imports_unless_reviewed() {
+        let (finished, reviews, advanced) = test_stores();
+        let mut imported = sample_finished_game("pgn_1");
+        imported.game_mode = crate::import::IMPORTED_GAME_MODE.to_string();
+        finished.save(&imported).unwrap();
+
+        let mut mgr = test_manager_no_workers(finished, reviews, advanced);
+        mgr.review_imports = false;
+        mgr.recover_pending_reviews().await;
+        assert!(mgr.get_status("pgn_1").await.is_err());
+
+        mgr.review_imports = true;
+        mgr.recover_pending_reviews().await;
+        assert_eq!(mgr.get_status("pgn_1").await.unwrap(), ReviewStatus::Queued);
+    }
+
     #[tokio::test]
     async fn test_recover_skips_completed_reviews() {
         let (finished, reviews, advanced) = test_stores();
@@ -717,6 +1758,8 @@ mod tests {
             started_at: Some(1000),
             completed_at: Some(2000),
             winner: Some("White".to_string()),
+            opening: None,
+            endgame: None,
         };
         reviews.save(&completed).unwrap();
 
@@ -747,6 +1790,8 @@ mod tests {
             started_at: Some(1000),
             completed_at: None,
             winner: None,
+            opening: None,
+            endgame: None,
         };
         reviews.save(&failed).unwrap();
 
@@ -763,6 +1808,53 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_retry_engine_failures_requeues_only_missing_engine() {
+        let (finished, reviews, advanced) = test_stores();
+        for (game_id, error) in [
+            (
+                "game_1",
+                format!("Failed to spawn engine: {}", engine::STOCKFISH_NOT_FOUND),
+            ),
+            ("game_2", "engine error".to_string()),
+        ] {
+            finished.save(&sample_finished_game(game_id)).unwrap();
+            reviews
+                .save(&GameReview {
+                    game_id: game_id.to_string(),
+                    status: ReviewStatus::Failed { error },
+                    positions: vec![],
+                    white_accuracy: None,
+                    black_accuracy: None,
+                    total_plies: 4,
+                    analyzed_plies: 0,
+                    analysis_depth: 18,
+                    started_at: Some(1000),
+                    completed_at: None,
+                    winner: None,
+                    opening: None,
+                    endgame: None,
+                })
+                .unwrap();
+        }
+
+        let mgr = test_manager_no_workers(finished, reviews, advanced);
+        mgr.set_engine_available(false);
+        assert!(mgr.is_paused());
+        mgr.set_engine_available(true);
+        assert!(!mgr.is_paused());
+
+        assert_eq!(mgr.retry_engine_failures().await, 1);
+        assert_eq!(
+            mgr.get_status("game_1").await.unwrap(),
+            ReviewStatus::Queued
+        );
+        assert!(matches!(
+            mgr.get_status("game_2").await.unwrap(),
+            ReviewStatus::Failed { .. }
+        ));
+    }
+
     /// Full end-to-end: enqueue a game, let the worker analyze it with Stockfish,
     /// and verify the completed review has correct structure.
     #[tokio::test]
@@ -783,6 +1875,8 @@ mod tests {
                     deep_depth: 6,
                     max_critical_positions: 5,
                 },
+                review_imports: true,
+                tablebase_paths: Vec::new(),
             },
         );
 
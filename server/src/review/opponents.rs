@@ -0,0 +1,150 @@
+//! Head-to-head records against named engine opponents.
+//!
+//! Games started against an opponent profile carry its name; this module
+//! groups finished games by that name and summarizes the human's results.
+
+use std::collections::BTreeMap;
+
+use analysis::GameReview;
+
+use super::outcome::{human_accuracy, mean_accuracy, HumanOutcome};
+use crate::persistence::FinishedGameData;
+
+/// Rough Elo of Stockfish at a skill level, used as the opponent's rating.
+pub fn estimated_engine_elo(skill_level: u8) -> i32 {
+    1350 + i32::from(skill_level.min(20)) * 75
+}
+
+/// Human's record against one named opponent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpponentStats {
+    pub name: String,
+    pub games: u32,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    /// Skill level of the most recent game against this opponent.
+    pub skill_level: u8,
+    /// Mean human accuracy over reviewed games.
+    pub average_accuracy: Option<f64>,
+    /// Performance rating over all games, from the engines' estimated Elo.
+    pub performance_rating: i32,
+    pub last_played_at: u64,
+}
+
+/// Summarize finished games per opponent, most played first. Games without
+/// an opponent name are ignored.
+pub fn opponent_stats(games: Vec<(FinishedGameData, Option<GameReview>)>) -> Vec<OpponentStats> {
+    let mut by_name: BTreeMap<String, Vec<(FinishedGameData, Option<GameReview>)>> =
+        BTreeMap::new();
+    for (game, review) in games {
+        if let Some(name) = game.opponent.clone() {
+            by_name.entry(name).or_default().push((game, review));
+        }
+    }
+
+    let mut stats: Vec<OpponentStats> = by_name
+        .into_iter()
+        .map(|(name, games)| summarize(name, &games))
+        .collect();
+    stats.sort_by(|a, b| {
+        b.games
+            .cmp(&a.games)
+            .then(b.last_played_at.cmp(&a.last_played_at))
+    });
+    stats
+}
+
+fn summarize(name: String, games: &[(FinishedGameData, Option<GameReview>)]) -> OpponentStats {
+    let outcomes: Vec<HumanOutcome> = games.iter().map(|(g, _)| HumanOutcome::of(g)).collect();
+    let count = |outcome| outcomes.iter().filter(|&&o| o == outcome).count() as u32;
+    let (wins, losses) = (count(HumanOutcome::Win), count(HumanOutcome::Loss));
+    let n = games.len() as i32;
+
+    // Linear performance rating: average opposition plus 400 per net win per game.
+    let avg_opposition = games
+        .iter()
+        .map(|(g, _)| estimated_engine_elo(g.skill_level))
+        .sum::<i32>()
+        / n;
+    let performance_rating = avg_opposition + 400 * (wins as i32 - losses as i32) / n;
+
+    let latest = games
+        .iter()
+        .map(|(g, _)| g)
+        .max_by_key(|g| g.created_at)
+        .expect("opponent has at least one game");
+
+    OpponentStats {
+        name,
+        games: games.len() as u32,
+        wins,
+        draws: count(HumanOutcome::Draw),
+        losses,
+        skill_level: latest.skill_level,
+        average_accuracy: mean_accuracy(
+            games
+                .iter()
+                .filter_map(|(g, r)| r.as_ref().and_then(|r| human_accuracy(g, r))),
+        ),
+        performance_rating,
+        last_played_at: latest.created_at,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(
+        id: &str,
+        opponent: Option<&str>,
+        result: &str,
+        skill: u8,
+        ts: u64,
+    ) -> FinishedGameData {
+        FinishedGameData {
+            result: result.to_string(),
+            skill_level: skill,
+            move_count: 30,
+            created_at: ts,
+            opponent: opponent.map(str::to_string),
+            ..FinishedGameData::sample(id)
+        }
+    }
+
+    #[test]
+    fn test_engine_elo_is_capped_at_skill_twenty() {
+        assert_eq!(estimated_engine_elo(0), 1350);
+        assert_eq!(estimated_engine_elo(20), estimated_engine_elo(25));
+    }
+
+    #[test]
+    fn test_stats_group_by_opponent() {
+        let stats = opponent_stats(vec![
+            (game("a", Some("Aggro Bot"), "WhiteWins", 4, 1), None),
+            (game("b", Some("Stocky 1500"), "BlackWins", 2, 2), None),
+            (game("c", Some("Aggro Bot"), "Draw", 4, 3), None),
+            (game("d", None, "WhiteWins", 4, 4), None),
+        ]);
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].name, "Aggro Bot");
+        assert_eq!((stats[0].wins, stats[0].draws, stats[0].losses), (1, 1, 0));
+        assert_eq!(stats[0].last_played_at, 3);
+        assert_eq!(stats[1].name, "Stocky 1500");
+        assert_eq!(stats[1].losses, 1);
+    }
+
+    #[test]
+    fn test_performance_rating_tracks_score() {
+        let elo = estimated_engine_elo(10);
+        let even = opponent_stats(vec![
+            (game("a", Some("Bot"), "WhiteWins", 10, 1), None),
+            (game("b", Some("Bot"), "BlackWins", 10, 2), None),
+        ]);
+        assert_eq!(even[0].performance_rating, elo);
+
+        let winning = opponent_stats(vec![(game("a", Some("Bot"), "WhiteWins", 10, 1), None)]);
+        assert_eq!(winning[0].performance_rating, elo + 400);
+    }
+}
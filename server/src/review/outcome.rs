@@ -0,0 +1,63 @@
+//! A finished game's result and accuracy from the human player's side.
+
+use analysis::GameReview;
+use serde::{Deserialize, Serialize};
+
+use crate::import::IMPORTED_GAME_MODE;
+use crate::persistence::FinishedGameData;
+
+/// Result of a game for the human player.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HumanOutcome {
+    Win,
+    Draw,
+    Loss,
+}
+
+impl HumanOutcome {
+    /// Outcome for the human side; games without one are scored for White.
+    pub fn of(game: &FinishedGameData) -> Self {
+        match (game.result.as_str(), human_plays_white(game)) {
+            ("Draw", _) => HumanOutcome::Draw,
+            ("WhiteWins", true) | ("BlackWins", false) => HumanOutcome::Win,
+            _ => HumanOutcome::Loss,
+        }
+    }
+
+    /// Points scored: 1 for a win, ½ for a draw.
+    pub fn score(self) -> f64 {
+        match self {
+            HumanOutcome::Win => 1.0,
+            HumanOutcome::Draw => 0.5,
+            HumanOutcome::Loss => 0.0,
+        }
+    }
+}
+
+/// The human's accuracy in a reviewed game.
+pub fn human_accuracy(game: &FinishedGameData, review: &GameReview) -> Option<f64> {
+    if human_plays_white(game) {
+        review.white_accuracy
+    } else {
+        review.black_accuracy
+    }
+}
+
+/// Mean of the given accuracies, or `None` if there are none.
+pub fn mean_accuracy(accuracies: impl IntoIterator<Item = f64>) -> Option<f64> {
+    let (sum, count) = accuracies
+        .into_iter()
+        .fold((0.0, 0usize), |(sum, n), a| (sum + a, n + 1));
+    (count > 0).then(|| sum / count as f64)
+}
+
+/// Whether the human played White; games without a human side count as White.
+pub fn human_plays_white(game: &FinishedGameData) -> bool {
+    game.human_side.as_deref() != Some("black")
+}
+
+/// Whether the player took part in the game. Engine-vs-engine and imported
+/// games are someone else's and stay out of the player's stats.
+pub fn is_players_game(game: &FinishedGameData) -> bool {
+    !game.game_mode.starts_with("EngineVsEngine") && game.game_mode != IMPORTED_GAME_MODE
+}
@@ -0,0 +1,141 @@
+//! "Find positions like this": the positions of finished games closest to a
+//! given one by pawn structure, material and piece placement.
+//!
+//! Games imported from PGN are finished games too, so a master database
+//! imported that way is searched alongside the games played here.
+
+use std::cmp::Ordering;
+
+use analysis::PositionSignature;
+
+use crate::persistence::FinishedGameData;
+
+/// Positions returned when the client does not ask for a number.
+pub const DEFAULT_SIMILAR_LIMIT: usize = 20;
+
+/// Positions less similar than this are not worth showing.
+const MIN_SIMILARITY: f64 = 0.5;
+
+/// A position of a finished game and how close it is to the one searched.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimilarPosition {
+    pub game_id: String,
+    /// Ply of the move that reached the position; 0 for the start position.
+    pub ply: u32,
+    pub fen: String,
+    /// From 0.0 to 1.0, see [`PositionSignature::similarity`].
+    pub similarity: f64,
+    /// SAN of the move played from the position, unless the game ended there.
+    pub next_san: Option<String>,
+    /// Result of the game the position is from.
+    pub result: String,
+}
+
+/// The closest position of each game, most similar first, at most `limit`.
+/// A game contributes one position so that long games of the same
+/// structure do not crowd out the rest.
+pub fn similar_positions(
+    target: &PositionSignature,
+    games: &[FinishedGameData],
+    limit: usize,
+) -> Vec<SimilarPosition> {
+    let mut found: Vec<SimilarPosition> = games
+        .iter()
+        .filter_map(|game| closest_in_game(target, game))
+        .filter(|p| p.similarity >= MIN_SIMILARITY)
+        .collect();
+    found.sort_by(|a, b| {
+        b.similarity
+            .partial_cmp(&a.similarity)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| a.game_id.cmp(&b.game_id))
+    });
+    found.truncate(limit);
+    found
+}
+
+fn closest_in_game(target: &PositionSignature, game: &FinishedGameData) -> Option<SimilarPosition> {
+    let fens = std::iter::once(&game.start_fen).chain(game.moves.iter().map(|m| &m.fen_after));
+    let mut best: Option<(usize, f64)> = None;
+    for (ply, fen) in fens.enumerate() {
+        let Some(signature) = PositionSignature::from_fen(fen) else {
+            continue;
+        };
+        let similarity = target.similarity(&signature);
+        // The earliest ply wins ties: a structure is usually reached once
+        // and then kept for a while
+        match best {
+            Some((_, closest)) if closest >= similarity => {}
+            _ => best = Some((ply, similarity)),
+        }
+    }
+    let (ply, similarity) = best?;
+    let fen = match ply {
+        0 => &game.start_fen,
+        ply => &game.moves[ply - 1].fen_after,
+    };
+    Some(SimilarPosition {
+        game_id: game.game_id.clone(),
+        ply: ply as u32,
+        fen: fen.clone(),
+        similarity,
+        next_san: game.moves.get(ply).map(|m| m.san.clone()),
+        result: game.result.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::StoredMoveRecord;
+
+    fn game(id: &str, result: &str, moves: &[(&str, &str)]) -> FinishedGameData {
+        FinishedGameData {
+            result: result.to_string(),
+            game_mode: "HumanVsHuman".to_string(),
+            human_side: None,
+            move_count: moves.len() as u32,
+            moves: moves
+                .iter()
+                .map(|(san, fen)| StoredMoveRecord {
+                    fen_after: fen.to_string(),
+                    ..StoredMoveRecord::sample(san)
+                })
+                .collect(),
+            ..FinishedGameData::sample(id)
+        }
+    }
+
+    #[test]
+    fn test_closest_position_of_each_game() {
+        let e4 = "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1";
+        let e4_e5 = "rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2";
+        let d4 = "rnbqkbnr/pppppppp/8/8/3P4/8/PPP1PPPP/RNBQKBNR b KQkq - 0 1";
+        let games = vec![
+            game("a", "WhiteWins", &[("e4", e4), ("e5", e4_e5)]),
+            game("b", "Draw", &[("d4", d4)]),
+        ];
+
+        let target = PositionSignature::from_fen(e4).unwrap();
+        let found = similar_positions(&target, &games, DEFAULT_SIMILAR_LIMIT);
+        assert_eq!(found.len(), 2);
+        assert_eq!(
+            (found[0].game_id.as_str(), found[0].ply, found[0].similarity),
+            ("a", 1, 1.0)
+        );
+        assert_eq!(found[0].next_san.as_deref(), Some("e5"));
+        assert_eq!(found[0].result, "WhiteWins");
+        // Game b is closest at its start, before d4 changed the pawns
+        assert_eq!((found[1].game_id.as_str(), found[1].ply), ("b", 0));
+        assert_eq!(found[1].next_san.as_deref(), Some("d4"));
+
+        assert_eq!(similar_positions(&target, &games, 1).len(), 1);
+    }
+
+    #[test]
+    fn test_dissimilar_positions_are_left_out() {
+        let games = vec![game("a", "Draw", &[])];
+        let bare_kings = PositionSignature::from_fen("8/8/4k3/8/8/4K3/8/8 w - - 0 1").unwrap();
+        assert!(similar_positions(&bare_kings, &games, DEFAULT_SIMILAR_LIMIT).is_empty());
+    }
+}
@@ -0,0 +1,135 @@
+//! Combined report for the boards of a simul.
+//!
+//! A simul is a set of human-vs-engine games played at once; each board is
+//! saved as a regular finished game tagged with the same `simul_id`.
+
+use analysis::GameReview;
+
+use super::outcome::{human_accuracy, mean_accuracy, HumanOutcome};
+use crate::persistence::FinishedGameData;
+
+/// One board of a simul, with the human's accuracy once the review is complete.
+#[derive(Debug, Clone)]
+pub struct SimulBoard {
+    pub game: FinishedGameData,
+    pub outcome: HumanOutcome,
+    pub review: Option<GameReview>,
+    pub human_accuracy: Option<f64>,
+}
+
+/// Aggregated results across all boards of a simul.
+#[derive(Debug, Clone)]
+pub struct SimulReport {
+    pub simul_id: String,
+    pub boards: Vec<SimulBoard>,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    /// Mean human accuracy over the boards that have been reviewed.
+    pub average_accuracy: Option<f64>,
+}
+
+impl SimulReport {
+    /// Build the report from the simul's finished games and their reviews, if any.
+    pub fn new(simul_id: &str, games: Vec<(FinishedGameData, Option<GameReview>)>) -> Self {
+        let mut boards: Vec<SimulBoard> = games
+            .into_iter()
+            .map(|(game, review)| SimulBoard {
+                outcome: HumanOutcome::of(&game),
+                human_accuracy: review.as_ref().and_then(|r| human_accuracy(&game, r)),
+                game,
+                review,
+            })
+            .collect();
+        // Boards in the order they were started.
+        boards.sort_by_key(|b| b.game.created_at);
+
+        let count = |outcome| boards.iter().filter(|b| b.outcome == outcome).count() as u32;
+        let average_accuracy = mean_accuracy(boards.iter().filter_map(|b| b.human_accuracy));
+
+        Self {
+            simul_id: simul_id.to_string(),
+            wins: count(HumanOutcome::Win),
+            draws: count(HumanOutcome::Draw),
+            losses: count(HumanOutcome::Loss),
+            average_accuracy,
+            boards,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use analysis::ReviewStatus;
+
+    fn game(id: &str, result: &str, human_side: &str, ts: u64) -> FinishedGameData {
+        FinishedGameData {
+            result: result.to_string(),
+            human_side: Some(human_side.to_string()),
+            move_count: 20,
+            created_at: ts,
+            simul_id: Some("simul".to_string()),
+            ..FinishedGameData::sample(id)
+        }
+    }
+
+    fn review(id: &str, white: f64, black: f64) -> GameReview {
+        GameReview {
+            game_id: id.to_string(),
+            status: ReviewStatus::Complete,
+            positions: vec![],
+            white_accuracy: Some(white),
+            black_accuracy: Some(black),
+            total_plies: 20,
+            analyzed_plies: 20,
+            analysis_depth: 18,
+            started_at: None,
+            completed_at: None,
+            winner: None,
+            opening: None,
+            endgame: None,
+        }
+    }
+
+    #[test]
+    fn test_report_counts_results_from_human_side() {
+        let report = SimulReport::new(
+            "simul",
+            vec![
+                (game("b", "BlackWins", "black", 2), None),
+                (game("a", "WhiteWins", "white", 1), None),
+                (game("c", "WhiteWins", "black", 3), None),
+                (game("d", "Draw", "white", 4), None),
+            ],
+        );
+        assert_eq!((report.wins, report.draws, report.losses), (2, 1, 1));
+        let ids: Vec<&str> = report
+            .boards
+            .iter()
+            .map(|b| b.game.game_id.as_str())
+            .collect();
+        assert_eq!(ids, ["a", "b", "c", "d"]);
+        assert_eq!(report.average_accuracy, None);
+    }
+
+    #[test]
+    fn test_report_averages_reviewed_human_accuracy() {
+        let report = SimulReport::new(
+            "simul",
+            vec![
+                (
+                    game("a", "WhiteWins", "white", 1),
+                    Some(review("a", 90.0, 40.0)),
+                ),
+                (
+                    game("b", "WhiteWins", "black", 2),
+                    Some(review("b", 95.0, 70.0)),
+                ),
+                (game("c", "Draw", "white", 3), None),
+            ],
+        );
+        assert_eq!(report.boards[1].human_accuracy, Some(70.0));
+        assert_eq!(report.average_accuracy, Some(80.0));
+    }
+}
@@ -1,13 +1,44 @@
-use crate::persistence::{JsonStore, PersistenceError};
+use crate::persistence::{FinishedGameData, JsonStore, PersistenceError, Storable};
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
-use super::types::GameReview;
+use super::types::{GameReview, ReviewBookmark, ReviewRevision, ReviewStatus};
+
+/// A game's earlier review passes, stored as one JSON file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RevisionLogData {
+    game_id: String,
+    revisions: Vec<ReviewRevision>,
+}
+
+impl Storable for RevisionLogData {
+    fn id(&self) -> &str {
+        &self.game_id
+    }
+}
+
+/// A game's bookmarks, stored as one JSON file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BookmarkListData {
+    game_id: String,
+    bookmarks: Vec<ReviewBookmark>,
+}
+
+impl Storable for BookmarkListData {
+    fn id(&self) -> &str {
+        &self.game_id
+    }
+}
 
 /// Persistence layer for game reviews. Uses JSON files in a directory.
 /// Kept as a fallback trait implementation; production uses SqliteReviewRepository.
 #[allow(dead_code)]
 pub struct ReviewStore {
     inner: JsonStore<GameReview>,
+    revisions: JsonStore<RevisionLogData>,
+    bookmarks: JsonStore<BookmarkListData>,
+    /// The finished games beside the reviews, to tell which have none.
+    games: JsonStore<FinishedGameData>,
 }
 
 #[allow(dead_code)]
@@ -16,6 +47,9 @@ impl ReviewStore {
         let dir = data_dir.join("reviews");
         Self {
             inner: JsonStore::new(dir),
+            revisions: JsonStore::new(data_dir.join("review_revisions")),
+            bookmarks: JsonStore::new(data_dir.join("review_bookmarks")),
+            games: JsonStore::new(data_dir.join("finished_games")),
         }
     }
 
@@ -35,9 +69,68 @@ impl ReviewStore {
         self.inner.load_all()
     }
 
-    /// Delete a review by game_id.
+    /// Delete a review by game_id, with its revisions and bookmarks.
     pub fn delete(&self, game_id: &str) -> Result<(), PersistenceError> {
-        self.inner.delete(game_id)
+        self.inner.delete(game_id)?;
+        self.revisions.delete(game_id)?;
+        self.bookmarks.delete(game_id)
+    }
+
+    pub fn save_revision(&self, revision: &ReviewRevision) -> Result<(), PersistenceError> {
+        let mut log = self
+            .revisions
+            .load(&revision.game_id)?
+            .unwrap_or_else(|| RevisionLogData {
+                game_id: revision.game_id.clone(),
+                revisions: vec![],
+            });
+        log.revisions.retain(|r| r.revision != revision.revision);
+        log.revisions.push(revision.clone());
+        log.revisions.sort_by_key(|r| r.revision);
+        self.revisions.save(&log)?;
+        Ok(())
+    }
+
+    /// A game's revisions, oldest first.
+    pub fn list_revisions(&self, game_id: &str) -> Result<Vec<ReviewRevision>, PersistenceError> {
+        Ok(self
+            .revisions
+            .load(game_id)?
+            .map(|log| log.revisions)
+            .unwrap_or_default())
+    }
+
+    pub fn save_bookmark(&self, bookmark: &ReviewBookmark) -> Result<(), PersistenceError> {
+        let mut list =
+            self.bookmarks
+                .load(&bookmark.game_id)?
+                .unwrap_or_else(|| BookmarkListData {
+                    game_id: bookmark.game_id.clone(),
+                    bookmarks: vec![],
+                });
+        list.bookmarks.retain(|b| b.ply != bookmark.ply);
+        list.bookmarks.push(bookmark.clone());
+        list.bookmarks.sort_by_key(|b| b.ply);
+        self.bookmarks.save(&list)?;
+        Ok(())
+    }
+
+    /// A game's bookmarks, in ply order.
+    pub fn list_bookmarks(&self, game_id: &str) -> Result<Vec<ReviewBookmark>, PersistenceError> {
+        Ok(self
+            .bookmarks
+            .load(game_id)?
+            .map(|list| list.bookmarks)
+            .unwrap_or_default())
+    }
+
+    pub fn delete_bookmark(&self, game_id: &str, ply: u32) -> Result<(), PersistenceError> {
+        let Some(mut list) = self.bookmarks.load(game_id)? else {
+            return Ok(());
+        };
+        list.bookmarks.retain(|b| b.ply != ply);
+        self.bookmarks.save(&list)?;
+        Ok(())
     }
 }
 
@@ -62,18 +155,66 @@ impl crate::persistence::traits::ReviewRepository for ReviewStore {
         self.list()
     }
 
+    async fn list_unfinished_reviews(
+        &self,
+    ) -> Result<Vec<(String, ReviewStatus)>, PersistenceError> {
+        Ok(self
+            .list()?
+            .into_iter()
+            .filter(|r| r.status != ReviewStatus::Complete)
+            .map(|r| (r.game_id, r.status))
+            .collect())
+    }
+
+    async fn list_unreviewed_games(
+        &self,
+        include_imports: bool,
+    ) -> Result<Vec<String>, PersistenceError> {
+        let mut games = self.games.load_all()?;
+        games.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        Ok(games
+            .into_iter()
+            .filter(|g| include_imports || g.game_mode != crate::import::IMPORTED_GAME_MODE)
+            .filter(|g| !self.inner.file_path(&g.game_id).exists())
+            .map(|g| g.game_id)
+            .collect())
+    }
+
     async fn delete_review(
         &self,
         game_id: &str,
     ) -> Result<(), crate::persistence::PersistenceError> {
         self.delete(game_id)
     }
+
+    async fn save_revision(&self, revision: &ReviewRevision) -> Result<(), PersistenceError> {
+        ReviewStore::save_revision(self, revision)
+    }
+
+    async fn list_revisions(&self, game_id: &str) -> Result<Vec<ReviewRevision>, PersistenceError> {
+        ReviewStore::list_revisions(self, game_id)
+    }
+
+    async fn save_bookmark(&self, bookmark: &ReviewBookmark) -> Result<(), PersistenceError> {
+        ReviewStore::save_bookmark(self, bookmark)
+    }
+
+    async fn list_bookmarks(&self, game_id: &str) -> Result<Vec<ReviewBookmark>, PersistenceError> {
+        ReviewStore::list_bookmarks(self, game_id)
+    }
+
+    async fn delete_bookmark(&self, game_id: &str, ply: u32) -> Result<(), PersistenceError> {
+        ReviewStore::delete_bookmark(self, game_id, ply)
+    }
 }
 
 #[cfg(test)]
 impl ReviewStore {
     fn new_in(dir: PathBuf) -> Self {
         Self {
+            revisions: JsonStore::new(dir.join("revisions")),
+            bookmarks: JsonStore::new(dir.join("bookmarks")),
+            games: JsonStore::new(dir.join("games")),
             inner: JsonStore::new(dir),
         }
     }
@@ -102,6 +243,8 @@ mod tests {
                 pv: vec!["e2e4".to_string()],
                 depth: 18,
                 clock_ms: None,
+                excluded: None,
+                only_move: false,
             }],
             white_accuracy: Some(95.0),
             black_accuracy: None,
@@ -111,6 +254,8 @@ mod tests {
             started_at: Some(1000),
             completed_at: None,
             winner: Some("White".to_string()),
+            opening: None,
+            endgame: None,
         }
     }
 
@@ -178,4 +323,62 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn test_revisions_listed_in_order_and_deleted_with_review() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ReviewStore::new_in(dir.path().join("reviews"));
+        let review = sample_review("game_1", ReviewStatus::Complete);
+        store.save(&review).unwrap();
+        store
+            .save_revision(&ReviewRevision::of(&review, 2, 200))
+            .unwrap();
+        store
+            .save_revision(&ReviewRevision::of(&review, 1, 100))
+            .unwrap();
+
+        let revisions = store.list_revisions("game_1").unwrap();
+        assert_eq!(
+            revisions.iter().map(|r| r.revision).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        assert_eq!(store.list().unwrap().len(), 1);
+
+        store.delete("game_1").unwrap();
+        assert!(store.list_revisions("game_1").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_bookmarks_replace_per_ply_and_are_deleted_with_review() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ReviewStore::new_in(dir.path().join("reviews"));
+        store
+            .save(&sample_review("game_1", ReviewStatus::Complete))
+            .unwrap();
+        let bookmark = |ply: u32, note: &str| ReviewBookmark {
+            game_id: "game_1".to_string(),
+            ply,
+            note: note.to_string(),
+            created_at: 100,
+        };
+        store.save_bookmark(&bookmark(9, "missed Nxe5")).unwrap();
+        store.save_bookmark(&bookmark(4, "")).unwrap();
+        store
+            .save_bookmark(&bookmark(9, "Nxe5 wins a pawn"))
+            .unwrap();
+
+        let bookmarks = store.list_bookmarks("game_1").unwrap();
+        assert_eq!(
+            bookmarks.iter().map(|b| b.ply).collect::<Vec<_>>(),
+            vec![4, 9]
+        );
+        assert_eq!(bookmarks[1].note, "Nxe5 wins a pawn");
+
+        store.delete_bookmark("game_1", 4).unwrap();
+        store.delete_bookmark("game_2", 4).unwrap();
+        assert_eq!(store.list_bookmarks("game_1").unwrap().len(), 1);
+
+        store.delete("game_1").unwrap();
+        assert!(store.list_bookmarks("game_1").unwrap().is_empty());
+    }
 }
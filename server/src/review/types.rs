@@ -1,10 +1,20 @@
 // Thin shim — re-export everything from the analysis crate
 pub use analysis::{
-    compute_accuracy, is_white_ply, AnalysisScore, GameReview, MoveClassification, PositionReview,
+    compute_accuracy, compute_handicap_analysis, diff_reviews, is_white_ply, AccuracyExclusion,
+    AccuracyExclusions, AnalysisScore, CoveredRange, GameReview, HandicapAnalysis, HandicapSide,
+    MissedWin, MoveClassification, Opening, PlyChange, PositionReview, ReviewDiff, ReviewRevision,
     ReviewStatus,
 };
 
+use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::Duration;
+
 use crate::persistence::Storable;
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use tonic::Status;
 
 // Storable impl stays here (local trait, foreign type)
 impl Storable for GameReview {
@@ -16,6 +26,142 @@ impl Storable for GameReview {
 /// A job submitted to the review queue.
 #[derive(Debug, Clone)]
 pub struct ReviewJob {
+    pub job_id: String,
     pub game_id: String,
     pub game_data: crate::persistence::FinishedGameData,
+    /// Plies to analyse, 1-indexed; the whole game unless a range was asked for.
+    pub plies: std::ops::RangeInclusive<u32>,
+    pub options: ReviewOptions,
+    /// Set when the job is cancelled; the worker skips it, or stops after
+    /// the ply it is analysing.
+    pub cancelled: Arc<AtomicBool>,
+}
+
+/// How a job analyses where it differs from the server's configuration,
+/// e.g. a quick shallow review or a deep one left to run overnight.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReviewOptions {
+    /// Engine depth, instead of the configured one.
+    pub depth: Option<u32>,
+    /// Lines the engine searches per position, instead of
+    /// [`DEFAULT_REVIEW_MULTIPV`](super::DEFAULT_REVIEW_MULTIPV).
+    pub multipv: Option<u32>,
+    /// Leave out the advanced analysis.
+    pub skip_advanced: bool,
+    /// Moves left out of the accuracy, instead of the configured ones.
+    pub exclusions: Option<AccuracyExclusions>,
+}
+
+/// A job queued or being analysed, by game. Enqueueing the same plies with
+/// the same options again returns it instead of queueing a second job.
+#[derive(Debug, Clone)]
+pub struct PendingJob {
+    pub job_id: String,
+    pub plies: std::ops::RangeInclusive<u32>,
+    pub options: ReviewOptions,
+    /// Shared with the queued [`ReviewJob`].
+    pub cancelled: Arc<AtomicBool>,
+}
+
+impl PendingJob {
+    /// The answer to enqueueing `plies` with `options` while this job is
+    /// pending: its id for a repeat of it, a conflict otherwise.
+    pub fn answer(
+        &self,
+        game_id: &str,
+        plies: &std::ops::RangeInclusive<u32>,
+        options: ReviewOptions,
+    ) -> Result<String, EnqueueError> {
+        if self.plies == *plies && self.options == options {
+            tracing::info!(game_id = %game_id, job_id = %self.job_id, "Job already pending");
+            Ok(self.job_id.clone())
+        } else {
+            tracing::warn!(game_id = %game_id, "Conflicting enqueue rejected");
+            Err(EnqueueError::Conflict(game_id.to_string()))
+        }
+    }
+}
+
+/// Jobs queued or being analysed, by game id.
+pub type PendingJobs = Arc<RwLock<HashMap<String, PendingJob>>>;
+
+/// Where a game's review has got to, published by the workers after each
+/// ply and when a job ends.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReviewProgress {
+    pub game_id: String,
+    /// The pending job, if any.
+    pub job_id: Option<String>,
+    pub status: ReviewStatus,
+    /// Time left at the pace of the plies analysed so far in the job.
+    pub eta: Option<Duration>,
+}
+
+impl ReviewProgress {
+    /// Whether no further updates follow for the job.
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self.status,
+            ReviewStatus::Complete | ReviewStatus::Failed { .. }
+        )
+    }
+
+    /// Whether the update is about `game_id`, and about `job_id` when one
+    /// is given; a late update of an earlier job of the game is not.
+    pub fn concerns(&self, game_id: &str, job_id: Option<&str>) -> bool {
+        self.game_id == game_id && job_id.is_none_or(|job| self.job_id.as_deref() == Some(job))
+    }
+}
+
+/// Why a game could not be queued for review.
+#[derive(Debug, thiserror::Error)]
+pub enum EnqueueError {
+    #[error("Finished game not found: {0}")]
+    GameNotFound(String),
+    #[error("Review for game {0} already exists")]
+    AlreadyReviewed(String),
+    /// Another job for the game, with other plies or options, is pending.
+    #[error("Game {0} is already queued for review with other plies or options")]
+    Conflict(String),
+    #[error("{0}")]
+    InvalidRequest(String),
+    #[error("Review job queue full or closed")]
+    QueueClosed,
+    #[error("Failed to load game: {0}")]
+    Store(String),
+}
+
+impl From<EnqueueError> for Status {
+    fn from(e: EnqueueError) -> Self {
+        match e {
+            EnqueueError::GameNotFound(_) => Status::not_found(e.to_string()),
+            EnqueueError::AlreadyReviewed(_) => Status::already_exists(e.to_string()),
+            EnqueueError::Conflict(_) => Status::failed_precondition(e.to_string()),
+            EnqueueError::InvalidRequest(_) => Status::invalid_argument(e.to_string()),
+            EnqueueError::QueueClosed => Status::unavailable(e.to_string()),
+            EnqueueError::Store(_) => Status::internal(e.to_string()),
+        }
+    }
+}
+
+/// A game's earlier review passes, with one of them compared against the
+/// current review.
+#[derive(Debug, Clone)]
+pub struct ReviewComparison {
+    /// Oldest first; empty if the game was never re-analysed.
+    pub revisions: Vec<ReviewRevision>,
+    /// The revision compared, if the game has any.
+    pub compared: Option<u32>,
+    pub diff: ReviewDiff,
+}
+
+/// A position of a finished game the player marked during review, with an
+/// optional note. A game has at most one bookmark per ply.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReviewBookmark {
+    pub game_id: String,
+    /// The position after this ply; 0 is the starting position.
+    pub ply: u32,
+    pub note: String,
+    pub created_at: u64,
 }
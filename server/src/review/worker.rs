@@ -1,29 +1,50 @@
-use std::collections::HashSet;
+use std::collections::HashMap;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use analysis::AnalysisConfig;
+use analysis::tablebase::TABLEBASE_WIN_CP;
+use analysis::{
+    classify_opening, dominant_endgame, is_only_move, AnalysisConfig, MoveContext, Tablebase,
+};
 use engine::{EngineCommand, EngineEvent, GoParams, StockfishConfig, StockfishEngine};
-use tokio::sync::{mpsc, Mutex, RwLock};
+use tokio::sync::{broadcast, mpsc, watch, Mutex, RwLockReadGuard};
 
-use crate::persistence::{AdvancedAnalysisRepository, Persistence, ReviewRepository};
+use crate::persistence::{
+    AdvancedAnalysisRepository, Persistence, ReviewRepository, StoredMoveRecord,
+};
 
 use super::advanced::compute_advanced_analysis;
 use super::types::*;
+use super::{DEFAULT_REVIEW_MULTIPV, REVIEW_CANCELLED};
+
+/// What a worker shares with the manager and the other workers.
+pub struct WorkerContext<D: Persistence> {
+    pub store: Arc<D::Reviews>,
+    pub advanced_store: Arc<D::Advanced>,
+    pub enqueued: PendingJobs,
+    /// Depth of jobs that don't choose one.
+    pub analysis_depth: u32,
+    /// Moves left out of accuracy for jobs that don't choose.
+    pub accuracy_exclusions: AccuracyExclusions,
+    pub analysis_config: AnalysisConfig,
+    pub tablebase: Option<Arc<Tablebase>>,
+    pub paused: watch::Receiver<bool>,
+    pub progress: broadcast::Sender<ReviewProgress>,
+}
 
 /// A long-lived worker task. Receives jobs from the shared channel,
 /// processes them one at a time.
 pub async fn run_review_worker<D: Persistence>(
     worker_id: usize,
     job_rx: Arc<Mutex<mpsc::Receiver<ReviewJob>>>,
-    store: Arc<D::Reviews>,
-    advanced_store: Arc<D::Advanced>,
-    enqueued: Arc<RwLock<HashSet<String>>>,
-    analysis_depth: u32,
-    analysis_config: AnalysisConfig,
+    mut ctx: WorkerContext<D>,
 ) {
     tracing::info!(worker_id, "Review worker started");
 
     loop {
+        wait_while_paused(worker_id, &mut ctx.paused).await;
+
         // Wait for next job (only one worker picks up each job)
         tracing::debug!(worker_id, "Waiting for next job");
         let job = {
@@ -37,70 +58,159 @@ pub async fn run_review_worker<D: Persistence>(
             }
         };
 
-        tracing::info!(worker_id, game_id = %job.game_id, plies = job.game_data.moves.len(), "Starting review analysis");
+        if job.cancelled.load(Ordering::Relaxed) {
+            // Cancelled while queued; the manager already let go of it
+            tracing::info!(worker_id, job_id = %job.job_id, game_id = %job.game_id, "Skipping cancelled review job");
+            continue;
+        }
 
-        let result = analyze_game::<D>(
-            worker_id,
-            &job,
-            store.as_ref(),
-            advanced_store.as_ref(),
-            analysis_depth,
-            &analysis_config,
-        )
-        .await;
+        tracing::info!(worker_id, job_id = %job.job_id, game_id = %job.game_id, plies = job.game_data.moves.len(), "Starting review analysis");
+
+        let result = analyze_game(worker_id, &job, &mut ctx).await;
 
-        match result {
+        let status = match result {
             Ok(()) => {
                 tracing::info!(worker_id, game_id = %job.game_id, "Review analysis complete");
+                ReviewStatus::Complete
             }
             Err(e) => {
                 tracing::error!(worker_id, game_id = %job.game_id, "Review analysis failed: {}", e);
-                let failed_review = GameReview {
-                    game_id: job.game_id.clone(),
-                    status: ReviewStatus::Failed { error: e },
-                    positions: vec![],
-                    white_accuracy: None,
-                    black_accuracy: None,
-                    total_plies: job.game_data.move_count,
-                    analyzed_plies: 0,
-                    analysis_depth,
-                    started_at: None,
-                    completed_at: None,
-                    winner: None,
-                };
-                let _ = store.save_review(&failed_review).await;
+                match fail_job::<D>(&ctx.store, &ctx.enqueued, &job, ctx.analysis_depth, e).await {
+                    Some(status) => status,
+                    None => {
+                        // The manager already released the game, which may
+                        // have been queued again or deleted since
+                        tracing::info!(worker_id, job_id = %job.job_id, "Leaving the review of a cancelled job alone");
+                        continue;
+                    }
+                }
+            }
+        };
+
+        // No longer pending; the game can be queued again. A cancelled job
+        // was already removed, and the game may have a newer one since.
+        {
+            let mut pending = ctx.enqueued.write().await;
+            if pending
+                .get(&job.game_id)
+                .is_some_and(|pending| pending.job_id == job.job_id)
+            {
+                pending.remove(&job.game_id);
             }
         }
 
-        // Remove from enqueued set
-        enqueued.write().await.remove(&job.game_id);
+        // Published once the job is no longer pending, so a subscriber that
+        // misses this reads the final status from the store instead
+        let _ = ctx.progress.send(ReviewProgress {
+            game_id: job.game_id.clone(),
+            job_id: Some(job.job_id.clone()),
+            status,
+            eta: None,
+        });
+    }
+}
+
+/// The pending jobs, held so a cancel (and with it a re-enqueue or delete of
+/// the game) waits until the guard is dropped, as long as `job` is still the
+/// game's pending job. None once it was cancelled.
+async fn pending_guard<'a>(
+    enqueued: &'a PendingJobs,
+    job: &ReviewJob,
+) -> Option<RwLockReadGuard<'a, HashMap<String, PendingJob>>> {
+    let pending = enqueued.read().await;
+    let current = !job.cancelled.load(Ordering::Relaxed)
+        && pending
+            .get(&job.game_id)
+            .is_some_and(|pending| pending.job_id == job.job_id);
+    current.then_some(pending)
+}
+
+/// Mark the review of a failed job failed, keeping the plies analysed
+/// before, e.g. by an earlier pass over another range. A cancelled job
+/// leaves the review alone and returns None.
+pub(super) async fn fail_job<D: Persistence>(
+    store: &D::Reviews,
+    enqueued: &PendingJobs,
+    job: &ReviewJob,
+    analysis_depth: u32,
+    error: String,
+) -> Option<ReviewStatus> {
+    let _pending = pending_guard(enqueued, job).await?;
+    let status = ReviewStatus::Failed { error };
+    let failed_review = match store.load_review(&job.game_id).await {
+        Ok(Some(existing)) => GameReview {
+            status: status.clone(),
+            ..existing
+        },
+        _ => GameReview {
+            game_id: job.game_id.clone(),
+            status: status.clone(),
+            positions: vec![],
+            white_accuracy: None,
+            black_accuracy: None,
+            total_plies: job.game_data.move_count,
+            analyzed_plies: 0,
+            analysis_depth,
+            started_at: None,
+            completed_at: None,
+            winner: None,
+            opening: None,
+            endgame: None,
+        },
+    };
+    let _ = store.save_review(&failed_review).await;
+    Some(status)
+}
+
+/// Hold the worker while reviews are paused. A closed channel means the
+/// manager is gone, so nothing holds the worker any more.
+async fn wait_while_paused(worker_id: usize, paused: &mut watch::Receiver<bool>) {
+    if *paused.borrow() {
+        tracing::info!(worker_id, "Reviews paused, waiting");
+        let _ = paused.wait_for(|paused| !paused).await;
+        tracing::info!(worker_id, "Reviews resumed");
     }
 }
 
-/// Analyze all positions in a finished game.
+/// Analyze the positions of a finished game in the job's ply range.
+///
+/// Plies already analysed at least as deep are kept, which resumes an
+/// interrupted review and leaves the rest of a partly re-analysed one alone.
 ///
 /// Pipeline:
-///   Phase 1 — Engine analysis of each position (at configured depth)
+///   Phase 1 — Engine analysis of each position (at configured depth), with
+///             tablebase results replacing the engine's where they exist
 ///   Phase 2+4 — Board geometry metrics + psychological profiling (via analysis crate)
 async fn analyze_game<D: Persistence>(
     worker_id: usize,
     job: &ReviewJob,
-    store: &D::Reviews,
-    advanced_store: &D::Advanced,
-    analysis_depth: u32,
-    analysis_config: &AnalysisConfig,
+    ctx: &mut WorkerContext<D>,
 ) -> Result<(), String> {
+    let WorkerContext {
+        store,
+        advanced_store,
+        enqueued,
+        analysis_depth,
+        accuracy_exclusions,
+        analysis_config,
+        tablebase,
+        paused,
+        progress,
+    } = ctx;
+    let tablebase = tablebase.as_deref();
+    let accuracy_exclusions = job.options.exclusions.unwrap_or(*accuracy_exclusions);
     let game = &job.game_data;
     let total_plies = game.moves.len() as u32;
+    let depth = job.options.depth.unwrap_or(*analysis_depth);
 
-    // Check for partial review (crash recovery)
+    // Check for partial review (crash recovery, or analysis of another range)
     let mut review = match store.load_review(&job.game_id).await {
         Ok(Some(existing)) if !existing.positions.is_empty() => {
             tracing::info!(
                 worker_id,
                 game_id = %job.game_id,
-                "Resuming from ply {}",
-                existing.analyzed_plies
+                analyzed = existing.analyzed_plies,
+                "Extending existing review"
             );
             existing
         }
@@ -115,13 +225,40 @@ async fn analyze_game<D: Persistence>(
             black_accuracy: None,
             total_plies,
             analyzed_plies: 0,
-            analysis_depth,
+            analysis_depth: depth,
             started_at: Some(crate::persistence::now_timestamp()),
             completed_at: None,
             winner: None,
+            opening: None,
+            endgame: None,
         },
     };
 
+    // Re-analysing a finished review replaces some of its judgements: keep
+    // the finished pass as a revision so the two can be compared
+    let needs_analysis = |ply: u32| {
+        !review
+            .positions
+            .iter()
+            .any(|p| p.ply == ply && p.depth >= depth)
+    };
+    if review.status == ReviewStatus::Complete && job.plies.clone().any(needs_analysis) {
+        let revisions = store
+            .list_revisions(&job.game_id)
+            .await
+            .map_err(|e| format!("Failed to list review revisions: {}", e))?;
+        let next = revisions.last().map_or(1, |r| r.revision + 1);
+        store
+            .save_revision(&ReviewRevision::of(
+                &review,
+                next,
+                crate::persistence::now_timestamp(),
+            ))
+            .await
+            .map_err(|e| format!("Failed to save review revision: {}", e))?;
+        tracing::info!(worker_id, game_id = %job.game_id, revision = next, "Archived previous review pass");
+    }
+
     // =====================================================================
     // Phase 1: Engine analysis of each position
     // =====================================================================
@@ -135,13 +272,42 @@ async fn analyze_game<D: Persistence>(
     let mut engine = StockfishEngine::spawn_with_config(sf_config)
         .await
         .map_err(|e| format!("Failed to spawn engine: {}", e))?;
+    let multipv = job.options.multipv.unwrap_or(DEFAULT_REVIEW_MULTIPV);
+    engine
+        .send_command(EngineCommand::SetOption {
+            name: "MultiPV".to_string(),
+            value: Some(multipv.to_string()),
+        })
+        .await
+        .map_err(|e| e.to_string())?;
 
     tracing::info!(worker_id, game_id = %job.game_id, "Stockfish spawned, beginning ply analysis");
 
-    let start_ply = review.analyzed_plies as usize;
-
-    for (i, move_record) in game.moves.iter().enumerate().skip(start_ply) {
-        let ply = (i as u32) + 1; // 1-indexed: ply 1 = first move
+    // Pace of this job, for the time left; plies kept from before are free
+    let started = Instant::now();
+    let mut analysed = 0;
+
+    for ply in job.plies.clone() {
+        wait_while_paused(worker_id, paused).await;
+        if job.cancelled.load(Ordering::Relaxed) {
+            // The plies analysed so far are saved; the review fails with
+            // the partial results so it can be resumed
+            tracing::info!(worker_id, game_id = %job.game_id, ply, "Review cancelled");
+            engine.shutdown().await;
+            return Err(REVIEW_CANCELLED.to_string());
+        }
+        // 1-indexed: ply 1 = first move
+        let i = (ply - 1) as usize;
+        let Some(move_record) = game.moves.get(i) else {
+            break;
+        };
+        if review
+            .positions
+            .iter()
+            .any(|p| p.ply == ply && p.depth >= depth)
+        {
+            continue;
+        }
         let is_white_move = i % 2 == 0;
         let side = if is_white_move { "W" } else { "B" };
 
@@ -165,13 +331,27 @@ async fn analyze_game<D: Persistence>(
         };
 
         // 1. Evaluate the position before the move to find the best move and eval
-        let (best_eval, best_move_uci, pv) =
-            evaluate_position(&mut engine, &fen_before, analysis_depth).await?;
+        let (mut best_eval, mut second_eval, mut best_move_uci, mut pv) =
+            evaluate_position(&mut engine, &fen_before, depth).await?;
+
+        // Endgames the tables cover have an exact result and best move
+        let probe_before = tablebase.and_then(|tb| tb.probe(&fen_before));
+        if let Some(ref probe) = probe_before {
+            best_eval = probe.score();
+            second_eval = None;
+            if let Some(ref mv) = probe.best_move_uci {
+                if *mv != best_move_uci {
+                    best_move_uci = mv.clone();
+                    pv = vec![mv.clone()];
+                }
+            }
+        }
 
         // 2. Evaluate the position after the played move
         //    Skip engine call for terminal positions (checkmate/stalemate) —
         //    Stockfish responds with `bestmove (none)` which our parser can't handle.
         let fen_after = &move_record.fen_after;
+        let probe_after = tablebase.and_then(|tb| tb.probe(fen_after));
         let played_eval = if is_terminal_position(fen_after) {
             // Terminal position: infer eval from game status.
             // From the side-to-move's perspective in a terminal position:
@@ -182,8 +362,10 @@ async fn analyze_game<D: Persistence>(
             } else {
                 AnalysisScore::Centipawns(0) // stalemate = draw
             }
+        } else if let Some(ref probe) = probe_after {
+            probe.score()
         } else {
-            let (eval, _, _) = evaluate_position(&mut engine, fen_after, analysis_depth).await?;
+            let (eval, _, _, _) = evaluate_position(&mut engine, fen_after, depth).await?;
             eval
         };
 
@@ -193,12 +375,55 @@ async fn analyze_game<D: Persistence>(
         // So we negate played_eval to get it from the moving side's perspective.
         let best_cp = best_eval.to_cp();
         let played_cp = played_eval.negate().to_cp();
-        let cp_loss = (best_cp - played_cp).max(0);
+        // A tablebase result and an engine mate score are both exact, so
+        // a win (or loss) either way costs nothing
+        let decided = |cp: i32| cp.abs() >= TABLEBASE_WIN_CP;
+        let cp_loss = if (probe_before.is_some() || probe_after.is_some())
+            && decided(best_cp)
+            && decided(played_cp)
+            && best_cp.signum() == played_cp.signum()
+        {
+            0
+        } else {
+            (best_cp - played_cp).max(0)
+        };
 
-        // Check if move was forced (only one legal move)
-        let is_forced = check_forced_move(&fen_before);
+        // Store evals normalized to White's perspective for consistency
+        let eval_before_white = if is_white_move {
+            best_eval.clone()
+        } else {
+            best_eval.negate()
+        };
+        let eval_after_white = if is_white_move {
+            played_eval.negate() // After white's move, eval is from black's perspective
+        } else {
+            played_eval.clone() // After black's move, eval is from white's perspective
+        };
 
-        let classification = MoveClassification::from_cp_loss(cp_loss, is_forced);
+        let context = MoveContext {
+            // Only one legal move
+            is_forced: check_forced_move(&fen_before),
+            is_sacrifice: cp_loss <= 10
+                && is_sacrifice_move(
+                    &fen_before,
+                    move_record,
+                    eval_before_white.to_cp(),
+                    eval_after_white.to_cp(),
+                ),
+            is_only_move: second_eval
+                .as_ref()
+                .is_some_and(|second| is_only_move(&best_eval, second)),
+            opponent_erred: review.positions.iter().any(|p| {
+                p.ply + 1 == ply
+                    && matches!(
+                        p.classification,
+                        MoveClassification::Mistake
+                            | MoveClassification::Blunder
+                            | MoveClassification::Miss
+                    )
+            }),
+        };
+        let classification = MoveClassification::classify(cp_loss, context);
 
         tracing::debug!(
             worker_id,
@@ -208,21 +433,10 @@ async fn analyze_game<D: Persistence>(
             best = %best_move_uci,
             cp_loss = cp_loss,
             classification = ?classification,
+            tablebase = ?probe_before.as_ref().map(|p| p.wdl),
             "Ply analyzed"
         );
 
-        // Store evals normalized to White's perspective for consistency
-        let eval_before_white = if is_white_move {
-            best_eval.clone()
-        } else {
-            best_eval.negate()
-        };
-        let eval_after_white = if is_white_move {
-            played_eval.negate() // After white's move, eval is from black's perspective
-        } else {
-            played_eval.clone() // After black's move, eval is from white's perspective
-        };
-
         // Convert best move from UCI to SAN using the board position
         let best_move_san = uci_to_san(&fen_before, &best_move_uci);
 
@@ -238,27 +452,44 @@ async fn analyze_game<D: Persistence>(
             classification,
             cp_loss,
             pv,
-            depth: analysis_depth,
+            depth,
             clock_ms: move_record.clock_ms,
+            excluded: None,
+            // An only legal move is Forced; this is one move of several
+            // that alone holds the position
+            only_move: context.is_only_move && !context.is_forced,
         };
 
-        review.positions.push(position_review);
-        review.analyzed_plies = ply;
+        review.set_position(position_review);
         review.status = ReviewStatus::Analyzing {
             current_ply: ply,
             total_plies,
         };
 
-        // Persist partial results after each ply (crash recovery)
+        // Persist partial results after each ply (crash recovery), unless
+        // the job was cancelled while the ply was analysed
+        let Some(pending) = pending_guard(enqueued, job).await else {
+            tracing::info!(worker_id, game_id = %job.game_id, ply, "Review cancelled");
+            engine.shutdown().await;
+            return Err(REVIEW_CANCELLED.to_string());
+        };
         store
             .save_review(&review)
             .await
             .map_err(|e| format!("Failed to save partial review: {}", e))?;
+        drop(pending);
+
+        analysed += 1;
+        let _ = progress.send(ReviewProgress {
+            game_id: job.game_id.clone(),
+            job_id: Some(job.job_id.clone()),
+            status: review.status.clone(),
+            eta: estimate_remaining(started.elapsed(), analysed, job.plies.end() - ply),
+        });
     }
 
-    // Compute accuracy scores
-    review.white_accuracy = Some(compute_accuracy(&review.positions, true));
-    review.black_accuracy = Some(compute_accuracy(&review.positions, false));
+    tracing::debug!(worker_id, game_id = %job.game_id, "Shutting down Stockfish");
+    engine.shutdown().await;
 
     // Set winner from game result
     review.winner = match job.game_data.result.as_str() {
@@ -268,6 +499,12 @@ async fn analyze_game<D: Persistence>(
         _ => None,
     };
 
+    review.opening = classify_opening(game.moves.iter().map(|m| m.fen_after.as_str()));
+    review.endgame = dominant_endgame(game.moves.iter().map(|m| m.fen_after.as_str()));
+
+    // Compute accuracy scores, book moves by the opening just classified
+    review.apply_accuracy_exclusions(accuracy_exclusions);
+
     review.status = ReviewStatus::Complete;
     review.completed_at = Some(crate::persistence::now_timestamp());
 
@@ -276,19 +513,25 @@ async fn analyze_game<D: Persistence>(
         game_id = %job.game_id,
         white_accuracy = ?review.white_accuracy,
         black_accuracy = ?review.black_accuracy,
+        eco = ?review.opening.as_ref().map(|o| &o.eco),
+        endgame = ?review.endgame,
         plies = review.analyzed_plies,
         "Analysis complete, saving results"
     );
 
+    let Some(pending) = pending_guard(enqueued, job).await else {
+        return Err(REVIEW_CANCELLED.to_string());
+    };
     store
         .save_review(&review)
         .await
         .map_err(|e| format!("Failed to save completed review: {}", e))?;
+    drop(pending);
 
     // =====================================================================
     // Phase 2+4: Advanced analysis (board geometry + psychological profiling)
     // =====================================================================
-    if analysis_config.compute_advanced {
+    if analysis_config.compute_advanced && !job.options.skip_advanced {
         tracing::info!(
             worker_id,
             game_id = %job.game_id,
@@ -308,25 +551,32 @@ async fn analyze_game<D: Persistence>(
             "Advanced analysis complete, saving"
         );
 
+        let Some(_pending) = pending_guard(enqueued, job).await else {
+            return Err(REVIEW_CANCELLED.to_string());
+        };
         advanced_store
             .save_analysis(&advanced)
             .await
             .map_err(|e| format!("Failed to save advanced analysis: {}", e))?;
     }
 
-    // Shutdown engine
-    tracing::debug!(worker_id, game_id = %job.game_id, "Shutting down Stockfish");
-    engine.shutdown().await;
-
     Ok(())
 }
 
-/// Run engine analysis on a position and return (score, best_move_uci, pv).
+/// Time left for `remaining` plies, at the pace of the `analysed` ones that
+/// took `elapsed`. None before the first ply.
+fn estimate_remaining(elapsed: Duration, analysed: u32, remaining: u32) -> Option<Duration> {
+    (analysed > 0).then(|| elapsed / analysed * remaining)
+}
+
+/// Run engine analysis on a position and return (score, second-best score,
+/// best_move_uci, pv). The second-best score is missing when the position has
+/// one legal move.
 async fn evaluate_position(
     engine: &mut StockfishEngine,
     fen: &str,
     depth: u32,
-) -> Result<(AnalysisScore, String, Vec<String>), String> {
+) -> Result<(AnalysisScore, Option<AnalysisScore>, String, Vec<String>), String> {
     engine
         .send_command(EngineCommand::SetPosition {
             fen: fen.to_string(),
@@ -346,16 +596,28 @@ async fn evaluate_position(
 
     // Collect engine output until BestMove
     let mut last_score = AnalysisScore::Centipawns(0);
+    let mut second_score = None;
     let mut pv_moves = vec![];
 
     loop {
         match engine.recv_event().await {
             Some(EngineEvent::Info(info)) => {
-                if let Some(score) = info.score {
-                    last_score = match score {
-                        engine::Score::Centipawns(cp) => AnalysisScore::Centipawns(cp),
-                        engine::Score::Mate(m) => AnalysisScore::Mate(m as i32),
-                    };
+                // Bounded scores come from aspiration-window fails and are not exact
+                if info.score_bound.is_some() {
+                    continue;
+                }
+                let score = info.score.map(|score| match score {
+                    engine::Score::Centipawns(cp) => AnalysisScore::Centipawns(cp),
+                    engine::Score::Mate(m) => AnalysisScore::Mate(m as i32),
+                });
+                if info.multipv.is_some_and(|n| n > 1) {
+                    if score.is_some() {
+                        second_score = score;
+                    }
+                    continue;
+                }
+                if let Some(score) = score {
+                    last_score = score;
                 }
                 if !info.pv.is_empty() {
                     pv_moves = info.pv.iter().map(|m| chess::format_uci_move(*m)).collect();
@@ -363,7 +625,7 @@ async fn evaluate_position(
             }
             Some(EngineEvent::BestMove(mv)) => {
                 let best_uci = chess::format_uci_move(mv);
-                return Ok((last_score, best_uci, pv_moves));
+                return Ok((last_score, second_score, best_uci, pv_moves));
             }
             Some(EngineEvent::Error(e)) => {
                 return Err(format!("Engine error during analysis: {}", e));
@@ -376,6 +638,27 @@ async fn evaluate_position(
     }
 }
 
+/// Whether the played move gives up material without the evaluation
+/// dropping. The evaluations are centipawns from White's perspective.
+fn is_sacrifice_move(
+    fen_before: &str,
+    move_record: &StoredMoveRecord,
+    eval_before: i32,
+    eval_after: i32,
+) -> bool {
+    let (Ok(before), Ok(after)) = (
+        fen_before.parse::<cozy_chess::Board>(),
+        move_record.fen_after.parse::<cozy_chess::Board>(),
+    ) else {
+        return false;
+    };
+    let uci = format!("{}{}", move_record.from, move_record.to);
+    let Ok(mv) = engine::uci::parser::parse_uci_move(&uci) else {
+        return false;
+    };
+    analysis::board_analysis::is_sacrifice(&before, &after, mv, eval_before, eval_after)
+}
+
 /// Check if there is only one legal move in a position (forced move).
 fn check_forced_move(fen: &str) -> bool {
     if let Ok(board) = fen.parse::<cozy_chess::Board>() {
@@ -488,6 +771,19 @@ mod tests {
         assert!(!is_checkmate(fen));
     }
 
+    #[test]
+    fn test_estimate_remaining_follows_pace() {
+        assert_eq!(estimate_remaining(Duration::from_secs(10), 0, 40), None);
+        assert_eq!(
+            estimate_remaining(Duration::from_secs(10), 5, 20),
+            Some(Duration::from_secs(40))
+        );
+        assert_eq!(
+            estimate_remaining(Duration::from_secs(10), 5, 0),
+            Some(Duration::ZERO)
+        );
+    }
+
     #[test]
     fn test_not_terminal_ongoing() {
         assert!(!is_terminal_position(START_FEN));
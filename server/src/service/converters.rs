@@ -1,11 +1,13 @@
 //! Conversion functions from domain types to protobuf types
 
-use crate::session::commands::EngineConfig;
+use crate::service::endpoints::review::convert_tactical_tag_to_proto;
+use crate::session::commands::{EngineConfig, DEFAULT_BOOK_PLIES};
 use crate::session::snapshot::MoveRecord;
-use crate::session::{SessionEvent, SessionSnapshot, TimerSnapshot, UciDirection};
-use ::chess::{AnalysisScore, EngineAnalysis, GameMode, GamePhase, PlayerSide};
+use crate::session::{SessionEvent, SessionSnapshot, TimerSnapshot, UciDirection, UciLogEntry};
+use ::chess::{format_uci_move, AnalysisScore, EngineAnalysis, GamePhase};
 use chess_proto::*;
 use cozy_chess::GameStatus as CozyGameStatus;
+use engine::{EngineEvent, EngineInfo, ScoreBound, UciMessageDirection};
 
 /// Convert a domain SessionSnapshot into the proto SessionSnapshot.
 pub fn convert_snapshot_to_proto(snap: SessionSnapshot) -> chess_proto::SessionSnapshot {
@@ -23,14 +25,27 @@ pub fn convert_snapshot_to_proto(snap: SessionSnapshot) -> chess_proto::SessionS
             .map(convert_move_record_to_proto)
             .collect(),
         last_move: snap.last_move.map(|(from, to)| LastMove { from, to }),
+        undo_depth: snap.move_count as u32,
+        redo_depth: snap.redo_depth as u32,
+        next_redo: snap.next_redo.map(|(from, to)| LastMove { from, to }),
         analysis: snap.analysis.as_ref().map(convert_engine_analysis_to_proto),
         engine_config: snap
             .engine_config
             .as_ref()
             .map(convert_engine_config_to_proto),
-        game_mode: Some(convert_game_mode_to_proto(&snap.game_mode)),
+        game_mode: Some(GameModeProto::from(&snap.game_mode)),
         engine_thinking: snap.engine_thinking,
         timer: snap.timer.as_ref().map(convert_timer_to_proto),
+        engine_move_delay_ms: snap.move_delay_ms as u32,
+        step_mode: snap.step_mode,
+        tactical_hints_enabled: snap.tactical_hints_enabled,
+        tactical_hints: snap
+            .tactical_hints
+            .iter()
+            .map(convert_tactical_tag_to_proto)
+            .collect(),
+        simul_id: snap.labels.simul_id,
+        opponent: snap.labels.opponent,
     }
 }
 
@@ -68,35 +83,6 @@ pub fn convert_game_phase_to_proto(phase: &GamePhase) -> chess_proto::GamePhase
     }
 }
 
-/// Convert the domain GameMode to the proto GameModeProto message.
-pub fn convert_game_mode_to_proto(mode: &GameMode) -> GameModeProto {
-    match mode {
-        GameMode::HumanVsHuman => GameModeProto {
-            mode: GameModeType::HumanVsHuman as i32,
-            human_side: None,
-        },
-        GameMode::HumanVsEngine { human_side } => GameModeProto {
-            mode: GameModeType::HumanVsEngine as i32,
-            human_side: Some(match human_side {
-                PlayerSide::White => PlayerSideProto::White as i32,
-                PlayerSide::Black => PlayerSideProto::Black as i32,
-            }),
-        },
-        GameMode::EngineVsEngine => GameModeProto {
-            mode: GameModeType::EngineVsEngine as i32,
-            human_side: None,
-        },
-        GameMode::Analysis => GameModeProto {
-            mode: GameModeType::Analysis as i32,
-            human_side: None,
-        },
-        GameMode::Review => GameModeProto {
-            mode: GameModeType::Review as i32,
-            human_side: None,
-        },
-    }
-}
-
 /// Convert the domain EngineAnalysis to the proto EngineAnalysis message.
 pub fn convert_engine_analysis_to_proto(analysis: &EngineAnalysis) -> chess_proto::EngineAnalysis {
     chess_proto::EngineAnalysis {
@@ -110,6 +96,7 @@ pub fn convert_engine_analysis_to_proto(analysis: &EngineAnalysis) -> chess_prot
         }),
         pv: analysis.pv.clone(),
         nps: analysis.nps,
+        multipv: analysis.multipv,
     }
 }
 
@@ -120,6 +107,9 @@ pub fn convert_engine_config_to_proto(config: &EngineConfig) -> chess_proto::Eng
         skill_level: config.skill_level as u32,
         threads: config.threads.unwrap_or(0),
         hash_mb: config.hash_mb.unwrap_or(0),
+        multipv: config.multipv.unwrap_or(1),
+        book_path: config.book_path.clone(),
+        book_max_plies: config.book_max_plies.unwrap_or(DEFAULT_BOOK_PLIES),
     }
 }
 
@@ -150,41 +140,111 @@ pub fn convert_session_event_to_proto(event: SessionEvent, session_id: &str) ->
         },
         SessionEvent::UciMessage(entry) => SessionStreamEvent {
             session_id,
-            event: Some(session_stream_event::Event::UciMessage(UciMessageEvent {
-                session_id: String::new(),
-                direction: match entry.direction {
-                    UciDirection::ToEngine => chess_proto::UciDirection::ToEngine as i32,
-                    UciDirection::FromEngine => chess_proto::UciDirection::FromEngine as i32,
-                },
-                message: entry.message,
-                context: entry.context,
-            })),
+            event: Some(session_stream_event::Event::UciMessage(
+                convert_uci_entry_to_proto(entry, String::new()),
+            )),
         },
         SessionEvent::Error(message) => SessionStreamEvent {
             session_id,
             event: Some(session_stream_event::Event::Error(message)),
         },
+        SessionEvent::Chat(message) => SessionStreamEvent {
+            session_id,
+            event: Some(session_stream_event::Event::Chat(
+                convert_chat_message_to_proto(message),
+            )),
+        },
+    }
+}
+
+/// Convert a domain chat message into the proto ChatMessage.
+pub fn convert_chat_message_to_proto(message: crate::session::ChatMessage) -> ChatMessage {
+    ChatMessage {
+        seq: message.seq,
+        author: message.author,
+        text: message.text,
+        sent_at: message.sent_at,
+    }
+}
+
+/// Convert a domain UciLogEntry into a proto UciMessageEvent.
+pub fn convert_uci_entry_to_proto(entry: UciLogEntry, session_id: String) -> UciMessageEvent {
+    UciMessageEvent {
+        session_id,
+        direction: match entry.direction {
+            UciDirection::ToEngine => chess_proto::UciDirection::ToEngine as i32,
+            UciDirection::FromEngine => chess_proto::UciDirection::FromEngine as i32,
+            UciDirection::EngineStderr => chess_proto::UciDirection::EngineStderr as i32,
+        },
+        message: entry.message,
+        context: entry.context,
     }
 }
 
-/// Parse a proto GameModeProto into a domain GameMode.
-/// Defaults to HumanVsHuman when the mode value is unrecognized.
-pub fn parse_game_mode_from_proto(proto: &GameModeProto) -> GameMode {
-    match GameModeType::try_from(proto.mode) {
-        Ok(GameModeType::HumanVsEngine) => {
-            let human_side = match proto
-                .human_side
-                .and_then(|v| PlayerSideProto::try_from(v).ok())
-            {
-                Some(PlayerSideProto::Black) => PlayerSide::Black,
-                _ => PlayerSide::White,
-            };
-            GameMode::HumanVsEngine { human_side }
+/// Convert a raw engine event into the proto EngineTapEvent.
+pub fn convert_engine_event_to_proto(event: EngineEvent, session_id: &str) -> EngineTapEvent {
+    let event = match event {
+        EngineEvent::Ready => engine_tap_event::Event::Ready(Empty {}),
+        EngineEvent::BestMove(mv) => engine_tap_event::Event::BestMove(format_uci_move(mv)),
+        EngineEvent::Info(info) => {
+            engine_tap_event::Event::Info(convert_engine_info_to_proto(info))
+        }
+        EngineEvent::InfoString(text) => engine_tap_event::Event::InfoString(text),
+        EngineEvent::Error(message) => engine_tap_event::Event::Error(message),
+        EngineEvent::RawUciMessage { direction, message } => {
+            engine_tap_event::Event::UciMessage(UciMessageEvent {
+                session_id: session_id.to_string(),
+                direction: match direction {
+                    UciMessageDirection::ToEngine => chess_proto::UciDirection::ToEngine as i32,
+                    UciMessageDirection::FromEngine => chess_proto::UciDirection::FromEngine as i32,
+                    UciMessageDirection::EngineStderr => {
+                        chess_proto::UciDirection::EngineStderr as i32
+                    }
+                },
+                message,
+                context: None,
+            })
         }
-        Ok(GameModeType::EngineVsEngine) => GameMode::EngineVsEngine,
-        Ok(GameModeType::Analysis) => GameMode::Analysis,
-        Ok(GameModeType::Review) => GameMode::Review,
-        Ok(GameModeType::HumanVsHuman) | Err(_) => GameMode::HumanVsHuman,
+    };
+    EngineTapEvent {
+        session_id: session_id.to_string(),
+        event: Some(event),
+    }
+}
+
+fn convert_engine_info_to_proto(info: EngineInfo) -> EngineTapInfo {
+    let (score_cp, score_mate) = match info.score {
+        Some(engine::Score::Centipawns(cp)) => (Some(cp), None),
+        Some(engine::Score::Mate(m)) => (None, Some(i32::from(m))),
+        None => (None, None),
+    };
+    EngineTapInfo {
+        depth: info.depth.map(u32::from),
+        seldepth: info.seldepth.map(u32::from),
+        time_ms: info.time_ms,
+        nodes: info.nodes,
+        score_cp,
+        score_mate,
+        score_bound: info.score_bound.map(|bound| {
+            match bound {
+                ScoreBound::Lower => "lower",
+                ScoreBound::Upper => "upper",
+            }
+            .to_string()
+        }),
+        pv: info.pv.into_iter().map(format_uci_move).collect(),
+        multipv: info.multipv.map(u32::from),
+        currmove: info.currmove.map(format_uci_move),
+        hashfull: info.hashfull.map(u32::from),
+        nps: info.nps,
+        wdl: info.wdl.map(|wdl| EngineWdl {
+            win: u32::from(wdl.win),
+            draw: u32::from(wdl.draw),
+            loss: u32::from(wdl.loss),
+        }),
+        tbhits: info.tbhits,
+        cpuload: info.cpuload.map(u32::from),
+        extras: info.extras.into_iter().collect(),
     }
 }
 
@@ -231,49 +291,42 @@ mod tests {
     }
 
     #[test]
-    fn test_convert_game_mode_human_vs_human() {
-        let proto = convert_game_mode_to_proto(&GameMode::HumanVsHuman);
-        assert_eq!(proto.mode, GameModeType::HumanVsHuman as i32);
-        assert!(proto.human_side.is_none());
-    }
-
-    #[test]
-    fn test_convert_game_mode_human_vs_engine() {
-        let proto = convert_game_mode_to_proto(&GameMode::HumanVsEngine {
-            human_side: PlayerSide::Black,
-        });
-        assert_eq!(proto.mode, GameModeType::HumanVsEngine as i32);
-        assert_eq!(proto.human_side, Some(PlayerSideProto::Black as i32));
-    }
-
-    #[test]
-    fn test_parse_game_mode_roundtrip() {
-        let modes = vec![
-            GameMode::HumanVsHuman,
-            GameMode::HumanVsEngine {
-                human_side: PlayerSide::White,
-            },
-            GameMode::HumanVsEngine {
-                human_side: PlayerSide::Black,
-            },
-            GameMode::EngineVsEngine,
-            GameMode::Analysis,
-            GameMode::Review,
-        ];
-        for mode in modes {
-            let proto = convert_game_mode_to_proto(&mode);
-            let parsed = parse_game_mode_from_proto(&proto);
-            assert_eq!(format!("{:?}", mode), format!("{:?}", parsed));
-        }
-    }
+    fn test_convert_engine_info_keeps_full_line() {
+        let e2e4 = cozy_chess::Move {
+            from: cozy_chess::Square::E2,
+            to: cozy_chess::Square::E4,
+            promotion: None,
+        };
+        let info = EngineInfo {
+            depth: Some(18),
+            score: Some(engine::Score::Mate(-3)),
+            score_bound: Some(ScoreBound::Upper),
+            pv: vec![e2e4],
+            hashfull: Some(412),
+            wdl: Some(engine::Wdl {
+                win: 10,
+                draw: 90,
+                loss: 900,
+            }),
+            extras: [("refutation".to_string(), "e2e4".to_string())].into(),
+            ..Default::default()
+        };
 
-    #[test]
-    fn test_parse_game_mode_unknown_defaults() {
-        let proto = GameModeProto {
-            mode: 99, // unrecognized enum value
-            human_side: None,
+        let event = convert_engine_event_to_proto(EngineEvent::Info(info), "s1");
+        assert_eq!(event.session_id, "s1");
+        let Some(engine_tap_event::Event::Info(info)) = event.event else {
+            panic!("expected an info event, got {:?}", event.event);
         };
-        let mode = parse_game_mode_from_proto(&proto);
-        assert!(matches!(mode, GameMode::HumanVsHuman));
+        assert_eq!(info.depth, Some(18));
+        assert_eq!(info.score_cp, None);
+        assert_eq!(info.score_mate, Some(-3));
+        assert_eq!(info.score_bound.as_deref(), Some("upper"));
+        assert_eq!(info.pv, vec!["e2e4".to_string()]);
+        assert_eq!(info.hashfull, Some(412));
+        assert_eq!(info.wdl.map(|wdl| wdl.loss), Some(900));
+        assert_eq!(
+            info.extras.get("refutation").map(String::as_str),
+            Some("e2e4")
+        );
     }
 }
@@ -0,0 +1,51 @@
+//! Activity log endpoints
+
+use crate::persistence::Persistence;
+use crate::session::SessionManager;
+use chess_proto::*;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+pub struct ActivityEndpoints<D: Persistence> {
+    session_manager: Arc<SessionManager<D>>,
+}
+
+impl<D: Persistence> ActivityEndpoints<D> {
+    pub fn new(session_manager: Arc<SessionManager<D>>) -> Self {
+        Self { session_manager }
+    }
+
+    pub async fn get_activity_summary(
+        &self,
+        request: Request<GetActivitySummaryRequest>,
+    ) -> Result<Response<GetActivitySummaryResponse>, Status> {
+        let req = request.into_inner();
+        tracing::info!(profile = %req.profile, days = req.days, "RPC get_activity_summary");
+
+        if req.profile.trim().is_empty() {
+            return Err(Status::invalid_argument("profile must not be empty"));
+        }
+
+        let summary = self
+            .session_manager
+            .activity_summary(&req.profile, req.days)
+            .await
+            .map_err(Status::internal)?;
+
+        Ok(Response::new(GetActivitySummaryResponse {
+            days: summary
+                .days
+                .into_iter()
+                .map(|d| ActivityDayProto {
+                    day: d.day,
+                    games_played: d.games_played,
+                    puzzles_solved: d.puzzles_solved,
+                    reviews_read: d.reviews_read,
+                })
+                .collect(),
+            today: summary.today,
+            current_streak: summary.current_streak,
+            longest_streak: summary.longest_streak,
+        }))
+    }
+}
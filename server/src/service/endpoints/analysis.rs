@@ -0,0 +1,104 @@
+//! Position analysis endpoints, served by the engine pool
+
+use crate::engine_pool::{AnalysisLine, AnalysisRequest, EnginePool, PoolError};
+use chess_proto::*;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio_stream::Stream;
+use tonic::{Request, Response, Status};
+
+pub struct AnalysisEndpoints {
+    engine_pool: Arc<EnginePool>,
+}
+
+impl AnalysisEndpoints {
+    pub fn new(engine_pool: Arc<EnginePool>) -> Self {
+        Self { engine_pool }
+    }
+
+    pub async fn analyze_position(
+        &self,
+        request: Request<AnalyzePositionRequest>,
+    ) -> Result<Response<AnalyzePositionResponse>, Status> {
+        let req = request.into_inner();
+        tracing::info!(
+            fen = %req.fen,
+            depth = ?req.depth,
+            movetime_ms = ?req.movetime_ms,
+            multipv = req.multipv,
+            "RPC analyze_position"
+        );
+
+        let analysis = AnalysisRequest::new(
+            req.fen.trim().to_string(),
+            req.depth,
+            req.movetime_ms,
+            req.multipv,
+        );
+        let lines = self
+            .engine_pool
+            .analyze(&analysis)
+            .await
+            .map_err(convert_pool_error)?;
+
+        Ok(Response::new(AnalyzePositionResponse {
+            lines: lines.into_iter().map(convert_line_to_proto).collect(),
+        }))
+    }
+
+    /// Stream engine lines for a position until the client drops the stream,
+    /// which stops the search and frees the engine.
+    pub async fn stream_analysis(
+        &self,
+        request: Request<StreamAnalysisRequest>,
+    ) -> Result<Response<Pin<Box<dyn Stream<Item = Result<AnalysisUpdate, Status>> + Send>>>, Status>
+    {
+        let req = request.into_inner();
+        tracing::info!(fen = %req.fen, multipv = req.multipv, "RPC stream_analysis");
+
+        let mut updates = self
+            .engine_pool
+            .stream(req.fen.trim(), req.multipv)
+            .await
+            .map_err(convert_pool_error)?;
+
+        let stream = async_stream::stream! {
+            while let Some(update) = updates.recv().await {
+                yield update
+                    .map(|update| AnalysisUpdate {
+                        line: Some(convert_line_to_proto(update.line)),
+                        seldepth: update.seldepth,
+                        nodes: update.nodes,
+                        nps: update.nps,
+                        time_ms: update.time_ms,
+                    })
+                    .map_err(convert_pool_error);
+            }
+        };
+
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
+
+fn convert_pool_error(e: PoolError) -> Status {
+    match e {
+        PoolError::InvalidFen(_) => Status::invalid_argument(e.to_string()),
+        PoolError::Busy(_) => Status::resource_exhausted(e.to_string()),
+        PoolError::Engine(_) => Status::internal(e.to_string()),
+    }
+}
+
+fn convert_line_to_proto(line: AnalysisLine) -> chess_proto::AnalysisLine {
+    let (score_cp, score_mate) = match line.score {
+        engine::Score::Centipawns(cp) => (Some(cp), None),
+        engine::Score::Mate(m) => (None, Some(i32::from(m))),
+    };
+    chess_proto::AnalysisLine {
+        multipv: line.multipv,
+        depth: line.depth,
+        score_cp,
+        score_mate,
+        pv: line.pv_uci,
+        pv_san: line.pv_san,
+    }
+}
@@ -0,0 +1,56 @@
+//! Audit log endpoints
+
+use crate::audit::AuditLog;
+use crate::persistence::{AuditAction, AuditEntry, Persistence};
+use chess_proto::*;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+/// Entries returned when the request leaves the limit at 0.
+const DEFAULT_AUDIT_LIMIT: usize = 100;
+/// Most entries returned by one request.
+const MAX_AUDIT_LIMIT: usize = 10_000;
+
+pub struct AuditEndpoints<D: Persistence> {
+    audit_log: Arc<AuditLog<D>>,
+}
+
+impl<D: Persistence> AuditEndpoints<D> {
+    pub fn new(audit_log: Arc<AuditLog<D>>) -> Self {
+        Self { audit_log }
+    }
+
+    pub async fn list_audit_entries(
+        &self,
+        request: Request<ListAuditEntriesRequest>,
+    ) -> Result<Response<ListAuditEntriesResponse>, Status> {
+        let req = request.into_inner();
+        tracing::debug!(limit = req.limit, "RPC list_audit_entries");
+
+        let limit = match req.limit as usize {
+            0 => DEFAULT_AUDIT_LIMIT,
+            limit => limit.min(MAX_AUDIT_LIMIT),
+        };
+        let entries = self.audit_log.list(limit).await.map_err(Status::internal)?;
+
+        Ok(Response::new(ListAuditEntriesResponse {
+            entries: entries.into_iter().map(audit_entry_to_proto).collect(),
+        }))
+    }
+}
+
+fn audit_entry_to_proto(entry: AuditEntry) -> AuditEntryProto {
+    let action = match entry.action {
+        AuditAction::Delete => AuditActionType::AuditActionDelete,
+        AuditAction::Import => AuditActionType::AuditActionImport,
+        AuditAction::ConfigChange => AuditActionType::AuditActionConfigChange,
+        AuditAction::SessionClose => AuditActionType::AuditActionSessionClose,
+    };
+    AuditEntryProto {
+        at: entry.at,
+        actor: entry.actor,
+        action: action as i32,
+        target: entry.target,
+        detail: entry.detail,
+    }
+}
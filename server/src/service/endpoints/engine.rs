@@ -1,5 +1,6 @@
 //! Engine control and pause/resume endpoints
 
+use crate::config::MAX_ENGINE_MOVE_DELAY_MS;
 use crate::persistence::Persistence;
 use crate::session::commands::EngineConfig;
 use crate::session::SessionManager;
@@ -27,26 +28,25 @@ impl<D: Persistence> EngineEndpoints<D> {
             skill = req.skill_level,
             threads = ?req.threads,
             hash = ?req.hash_mb,
+            multipv = ?req.multipv,
+            book = ?req.book_path,
+            book_plies = ?req.book_max_plies,
             "RPC set_engine"
         );
 
-        let handle = self
-            .session_manager
-            .get_handle(&req.session_id)
-            .await
-            .map_err(Status::not_found)?;
+        let handle = self.session_manager.get_handle(&req.session_id).await?;
 
         let config = EngineConfig {
             enabled: req.enabled,
             skill_level: req.skill_level as u8,
             threads: req.threads,
             hash_mb: req.hash_mb,
+            multipv: req.multipv,
+            book_path: req.book_path,
+            book_max_plies: req.book_max_plies,
         };
 
-        handle
-            .configure_engine(config)
-            .await
-            .map_err(|e| Status::internal(e.to_string()))?;
+        handle.configure_engine(config).await?;
 
         Ok(Response::new(Empty {}))
     }
@@ -58,16 +58,56 @@ impl<D: Persistence> EngineEndpoints<D> {
         let req = request.into_inner();
         tracing::info!(session_id = %req.session_id, "RPC stop_engine");
 
-        let handle = self
-            .session_manager
-            .get_handle(&req.session_id)
-            .await
-            .map_err(Status::not_found)?;
+        let handle = self.session_manager.get_handle(&req.session_id).await?;
 
-        handle
-            .stop_engine()
-            .await
-            .map_err(|e| Status::internal(e.to_string()))?;
+        handle.stop_engine().await?;
+
+        Ok(Response::new(Empty {}))
+    }
+
+    pub async fn set_engine_move_delay(
+        &self,
+        request: Request<SetEngineMoveDelayRequest>,
+    ) -> Result<Response<Empty>, Status> {
+        let req = request.into_inner();
+        tracing::info!(
+            session_id = %req.session_id,
+            delay_ms = req.delay_ms,
+            "RPC set_engine_move_delay"
+        );
+
+        let handle = self.session_manager.get_handle(&req.session_id).await?;
+
+        let delay_ms = (req.delay_ms as u64).min(MAX_ENGINE_MOVE_DELAY_MS);
+        handle.set_move_delay(delay_ms).await?;
+
+        Ok(Response::new(Empty {}))
+    }
+
+    pub async fn set_step_mode(
+        &self,
+        request: Request<SetStepModeRequest>,
+    ) -> Result<Response<Empty>, Status> {
+        let req = request.into_inner();
+        tracing::info!(session_id = %req.session_id, enabled = req.enabled, "RPC set_step_mode");
+
+        let handle = self.session_manager.get_handle(&req.session_id).await?;
+
+        handle.set_step_mode(req.enabled).await?;
+
+        Ok(Response::new(Empty {}))
+    }
+
+    pub async fn step_engine(
+        &self,
+        request: Request<StepEngineRequest>,
+    ) -> Result<Response<Empty>, Status> {
+        let req = request.into_inner();
+        tracing::info!(session_id = %req.session_id, "RPC step_engine");
+
+        let handle = self.session_manager.get_handle(&req.session_id).await?;
+
+        handle.step().await?;
 
         Ok(Response::new(Empty {}))
     }
@@ -79,16 +119,9 @@ impl<D: Persistence> EngineEndpoints<D> {
         let req = request.into_inner();
         tracing::info!(session_id = %req.session_id, "RPC pause_session");
 
-        let handle = self
-            .session_manager
-            .get_handle(&req.session_id)
-            .await
-            .map_err(Status::not_found)?;
+        let handle = self.session_manager.get_handle(&req.session_id).await?;
 
-        handle
-            .pause()
-            .await
-            .map_err(|e| Status::internal(e.to_string()))?;
+        handle.pause().await?;
 
         Ok(Response::new(Empty {}))
     }
@@ -100,16 +133,9 @@ impl<D: Persistence> EngineEndpoints<D> {
         let req = request.into_inner();
         tracing::info!(session_id = %req.session_id, "RPC resume_session");
 
-        let handle = self
-            .session_manager
-            .get_handle(&req.session_id)
-            .await
-            .map_err(Status::not_found)?;
+        let handle = self.session_manager.get_handle(&req.session_id).await?;
 
-        handle
-            .resume()
-            .await
-            .map_err(|e| Status::internal(e.to_string()))?;
+        handle.resume().await?;
 
         Ok(Response::new(Empty {}))
     }
@@ -1,8 +1,11 @@
 //! Event streaming endpoint
 
 use crate::persistence::Persistence;
-use crate::service::converters::{convert_session_event_to_proto, convert_snapshot_to_proto};
-use crate::session::SessionManager;
+use crate::service::converters::{
+    convert_chat_message_to_proto, convert_engine_event_to_proto, convert_session_event_to_proto,
+    convert_snapshot_to_proto,
+};
+use crate::session::{SessionEvent, SessionHandle, SessionManager};
 use chess_proto::*;
 use std::pin::Pin;
 use std::sync::Arc;
@@ -14,14 +17,24 @@ use tonic::{Request, Response, Status};
 ///
 /// When a client disconnects (network failure, crash, or explicit drop),
 /// tonic drops the stream future, which drops this guard, which spawns a
-/// task to close the session and shut down the engine process.
+/// task to close the session and shut down the engine process. A session
+/// handed to another client since the stream started is left open.
 struct CleanupGuard<D: Persistence> {
     session_manager: Arc<SessionManager<D>>,
     session_id: String,
+    handle: SessionHandle,
+    owner_generation: u64,
 }
 
 impl<D: Persistence> Drop for CleanupGuard<D> {
     fn drop(&mut self) {
+        if self.handle.owner_generation() != self.owner_generation {
+            tracing::info!(
+                session_id = %self.session_id,
+                "Event stream of a handed-over session dropped, leaving it open"
+            );
+            return;
+        }
         let session_manager = self.session_manager.clone();
         let session_id = std::mem::take(&mut self.session_id);
         tracing::info!(
@@ -65,32 +78,36 @@ impl<D: Persistence> EventsEndpoints<D> {
         Status,
     > {
         let req = request.into_inner();
-        tracing::info!(session_id = %req.session_id, "RPC stream_events");
+        tracing::info!(session_id = %req.session_id, watch = req.watch, "RPC stream_events");
 
-        let handle = self
-            .session_manager
-            .get_handle(&req.session_id)
-            .await
-            .map_err(Status::not_found)?;
+        let handle = self.session_manager.get_handle(&req.session_id).await?;
 
         // Subscribe returns the current snapshot plus a receiver for future events.
         // This makes the stream reconnection-safe: clients always get the full
         // current state first, then incremental updates.
-        let (initial_snapshot, mut event_rx) = handle
-            .subscribe()
-            .await
-            .map_err(|e| Status::internal(e.to_string()))?;
+        let (initial_snapshot, mut event_rx) = handle.subscribe().await?;
+        // Asked for after subscribing, so no message falls in between; ones
+        // in both are skipped from the receiver by their seq.
+        let chat_history = handle.chat_history().await?;
+        let replayed_seq = chat_history.last().map_or(0, |m| m.seq);
+        // The client streaming the session has it until it is handed over
+        let mut owner_rx = handle.watch_owner();
+        let owner_generation = handle.owner_generation();
 
         let session_id = req.session_id.clone();
         let session_manager = self.session_manager.clone();
+        let watch = req.watch;
         let stream = async_stream::stream! {
             // The cleanup guard lives as long as the stream. When the client
             // disconnects, tonic drops the stream, which drops the guard,
             // which spawns a task to close the session and shut down the engine.
-            let _guard = CleanupGuard {
+            // Watchers come and go without closing the session.
+            let _guard = (!watch).then(|| CleanupGuard {
                 session_manager,
                 session_id: session_id.clone(),
-            };
+                handle,
+                owner_generation,
+            });
 
             // Emit the initial snapshot as the first event so the client
             // has a complete, consistent view of the session state.
@@ -102,9 +119,37 @@ impl<D: Persistence> EventsEndpoints<D> {
             };
             yield Ok(initial_event);
 
+            for message in chat_history {
+                yield Ok(SessionStreamEvent {
+                    session_id: session_id.clone(),
+                    event: Some(session_stream_event::Event::Chat(
+                        convert_chat_message_to_proto(message),
+                    )),
+                });
+            }
+
             // Then stream incremental events
             loop {
-                match event_rx.recv().await {
+                let received = tokio::select! {
+                    received = event_rx.recv() => Some(received),
+                    // Only the client's own stream ends when another claims the session
+                    Ok(()) = owner_rx.changed(), if !watch => None,
+                };
+                let Some(received) = received else {
+                    tracing::info!(
+                        session_id = %session_id,
+                        "Session handed to another client, ending its old stream"
+                    );
+                    yield Ok(SessionStreamEvent {
+                        session_id: session_id.clone(),
+                        event: Some(session_stream_event::Event::Transferred(
+                            SessionTransferred {},
+                        )),
+                    });
+                    break;
+                };
+                match received {
+                    Ok(SessionEvent::Chat(message)) if message.seq <= replayed_seq => continue,
                     Ok(event) => {
                         let proto_event = convert_session_event_to_proto(event, &session_id);
                         yield Ok(proto_event);
@@ -133,4 +178,78 @@ impl<D: Persistence> EventsEndpoints<D> {
 
         Ok(Response::new(Box::pin(stream)))
     }
+
+    pub async fn send_chat(
+        &self,
+        request: Request<SendChatRequest>,
+    ) -> Result<Response<ChatMessage>, Status> {
+        let req = request.into_inner();
+        tracing::info!(session_id = %req.session_id, "RPC send_chat");
+
+        let message = self
+            .session_manager
+            .send_chat(&req.session_id, &req.author, &req.text)
+            .await?;
+        Ok(Response::new(convert_chat_message_to_proto(message)))
+    }
+
+    /// The saved chat of a session, which outlives the session itself.
+    pub async fn get_chat_history(
+        &self,
+        request: Request<GetChatHistoryRequest>,
+    ) -> Result<Response<GetChatHistoryResponse>, Status> {
+        let req = request.into_inner();
+        tracing::info!(session_id = %req.session_id, "RPC get_chat_history");
+
+        let messages = self
+            .session_manager
+            .chat_history(&req.session_id)
+            .await
+            .map_err(Status::internal)?;
+        Ok(Response::new(GetChatHistoryResponse {
+            messages: messages
+                .into_iter()
+                .map(|m| ChatMessage {
+                    seq: m.seq,
+                    author: m.author,
+                    text: m.text,
+                    sent_at: m.sent_at,
+                })
+                .collect(),
+        }))
+    }
+
+    /// Stream a session's raw engine events to an external consumer. Unlike
+    /// `stream_events`, dropping the stream leaves the session open.
+    pub async fn attach_engine_tap(
+        &self,
+        request: Request<AttachEngineTapRequest>,
+    ) -> Result<Response<Pin<Box<dyn Stream<Item = Result<EngineTapEvent, Status>> + Send>>>, Status>
+    {
+        let req = request.into_inner();
+        tracing::info!(session_id = %req.session_id, "RPC attach_engine_tap");
+
+        let handle = self.session_manager.get_handle(&req.session_id).await?;
+        let mut tap_rx = handle.attach_engine_tap().await?;
+
+        let session_id = req.session_id;
+        let stream = async_stream::stream! {
+            loop {
+                match tap_rx.recv().await {
+                    Ok(event) => yield Ok(convert_engine_event_to_proto(event, &session_id)),
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        // Raw output is best effort; a slow consumer just misses lines
+                        tracing::warn!(session_id = %session_id, skipped, "Engine tap lagged");
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => {
+                        tracing::info!("Engine tap closed for session {}", session_id);
+                        break;
+                    }
+                }
+            }
+        };
+
+        Ok(Response::new(Box::pin(stream)))
+    }
 }
@@ -30,16 +30,9 @@ impl<D: Persistence> GameEndpoints<D> {
 
         let mv = parse_move_repr(&mv_repr).map_err(|e| *e)?;
 
-        let handle = self
-            .session_manager
-            .get_handle(&req.session_id)
-            .await
-            .map_err(Status::not_found)?;
+        let handle = self.session_manager.get_handle(&req.session_id).await?;
 
-        let snapshot = handle
-            .make_move(mv)
-            .await
-            .map_err(|e| Status::internal(e.to_string()))?;
+        let snapshot = handle.make_move(mv).await?;
 
         Ok(Response::new(convert_snapshot_to_proto(snapshot)))
     }
@@ -57,16 +50,9 @@ impl<D: Persistence> GameEndpoints<D> {
             None
         };
 
-        let handle = self
-            .session_manager
-            .get_handle(&req.session_id)
-            .await
-            .map_err(Status::not_found)?;
+        let handle = self.session_manager.get_handle(&req.session_id).await?;
 
-        let moves = handle
-            .get_legal_moves(from_square)
-            .await
-            .map_err(|e| Status::internal(e.to_string()))?;
+        let moves = handle.get_legal_moves(from_square).await?;
 
         let move_details: Vec<MoveDetail> = moves
             .into_iter()
@@ -93,16 +79,9 @@ impl<D: Persistence> GameEndpoints<D> {
         let req = request.into_inner();
         tracing::info!(session_id = %req.session_id, "RPC undo_move");
 
-        let handle = self
-            .session_manager
-            .get_handle(&req.session_id)
-            .await
-            .map_err(Status::not_found)?;
+        let handle = self.session_manager.get_handle(&req.session_id).await?;
 
-        let snapshot = handle
-            .undo()
-            .await
-            .map_err(|e| Status::internal(e.to_string()))?;
+        let snapshot = handle.undo().await?;
 
         Ok(Response::new(convert_snapshot_to_proto(snapshot)))
     }
@@ -114,16 +93,9 @@ impl<D: Persistence> GameEndpoints<D> {
         let req = request.into_inner();
         tracing::info!(session_id = %req.session_id, "RPC redo_move");
 
-        let handle = self
-            .session_manager
-            .get_handle(&req.session_id)
-            .await
-            .map_err(Status::not_found)?;
+        let handle = self.session_manager.get_handle(&req.session_id).await?;
 
-        let snapshot = handle
-            .redo()
-            .await
-            .map_err(|e| Status::internal(e.to_string()))?;
+        let snapshot = handle.redo().await?;
 
         Ok(Response::new(convert_snapshot_to_proto(snapshot)))
     }
@@ -135,16 +107,9 @@ impl<D: Persistence> GameEndpoints<D> {
         let req = request.into_inner();
         tracing::info!(session_id = %req.session_id, fen = ?req.fen, "RPC reset_game");
 
-        let handle = self
-            .session_manager
-            .get_handle(&req.session_id)
-            .await
-            .map_err(Status::not_found)?;
+        let handle = self.session_manager.get_handle(&req.session_id).await?;
 
-        let snapshot = handle
-            .reset(req.fen)
-            .await
-            .map_err(|e| Status::internal(e.to_string()))?;
+        let snapshot = handle.reset(req.fen).await?;
 
         Ok(Response::new(convert_snapshot_to_proto(snapshot)))
     }
@@ -0,0 +1,159 @@
+//! Goal setting and progress endpoints
+
+use crate::persistence::{self, GoalData, GoalMetric, GoalPeriod, Persistence};
+use crate::review::ReviewManager;
+use crate::session::goals::GoalProgress;
+use crate::session::SessionManager;
+use chess_proto::*;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+pub struct GoalEndpoints<D: Persistence> {
+    session_manager: Arc<SessionManager<D>>,
+    review_manager: Arc<ReviewManager<D>>,
+}
+
+impl<D: Persistence> GoalEndpoints<D> {
+    pub fn new(
+        session_manager: Arc<SessionManager<D>>,
+        review_manager: Arc<ReviewManager<D>>,
+    ) -> Self {
+        Self {
+            session_manager,
+            review_manager,
+        }
+    }
+
+    pub async fn add_goal(
+        &self,
+        request: Request<AddGoalRequest>,
+    ) -> Result<Response<AddGoalResponse>, Status> {
+        let req = request.into_inner();
+        tracing::info!(profile = %req.profile, metric = req.metric, target = req.target, "RPC add_goal");
+
+        check_profile(&req.profile)?;
+        let metric = GoalMetricType::try_from(req.metric)
+            .map(goal_metric_from_proto)
+            .map_err(|_| Status::invalid_argument("Unknown goal metric"))?;
+        let period = GoalPeriodType::try_from(req.period)
+            .map(goal_period_from_proto)
+            .map_err(|_| Status::invalid_argument("Unknown goal period"))?;
+
+        let goal = self
+            .session_manager
+            .add_goal(&req.profile, metric, req.target, period)
+            .await
+            .map_err(Status::invalid_argument)?;
+
+        Ok(Response::new(AddGoalResponse {
+            goal: Some(convert_goal_to_proto(goal)),
+        }))
+    }
+
+    pub async fn list_goals(
+        &self,
+        request: Request<ListGoalsRequest>,
+    ) -> Result<Response<ListGoalsResponse>, Status> {
+        let profile = &request.get_ref().profile;
+        tracing::info!(profile = %profile, "RPC list_goals");
+
+        check_profile(profile)?;
+        let goals = self
+            .session_manager
+            .list_goals(profile)
+            .await
+            .map_err(Status::internal)?;
+
+        let today = persistence::day_of(persistence::now_timestamp());
+        let mut progress = Vec::with_capacity(goals.len());
+        for goal in goals {
+            let since = goal.period.start_day(today);
+            let totals = self
+                .session_manager
+                .activity_totals(profile, since)
+                .await
+                .map_err(Status::internal)?;
+            let accuracy = if goal.metric == GoalMetric::AverageAccuracy {
+                self.review_manager
+                    .average_accuracy_since(since)
+                    .await
+                    .map_err(Status::internal)?
+            } else {
+                None
+            };
+            progress.push(GoalProgress::new(goal, &totals, accuracy));
+        }
+
+        Ok(Response::new(ListGoalsResponse {
+            goals: progress
+                .into_iter()
+                .map(|p| GoalProgressProto {
+                    achieved: p.achieved(),
+                    current: p.current,
+                    goal: Some(convert_goal_to_proto(p.goal)),
+                })
+                .collect(),
+        }))
+    }
+
+    pub async fn delete_goal(
+        &self,
+        request: Request<DeleteGoalRequest>,
+    ) -> Result<Response<Empty>, Status> {
+        let req = request.get_ref();
+        tracing::info!(profile = %req.profile, goal_id = %req.goal_id, "RPC delete_goal");
+
+        check_profile(&req.profile)?;
+        self.session_manager
+            .delete_goal(&req.profile, &req.goal_id)
+            .await
+            .map_err(Status::internal)?;
+
+        Ok(Response::new(Empty {}))
+    }
+}
+
+fn check_profile(profile: &str) -> Result<(), Status> {
+    if profile.trim().is_empty() {
+        return Err(Status::invalid_argument("profile must not be empty"));
+    }
+    Ok(())
+}
+
+fn goal_metric_from_proto(metric: GoalMetricType) -> GoalMetric {
+    match metric {
+        GoalMetricType::GoalMetricAverageAccuracy => GoalMetric::AverageAccuracy,
+        GoalMetricType::GoalMetricGamesPlayed => GoalMetric::GamesPlayed,
+        GoalMetricType::GoalMetricPuzzlesSolved => GoalMetric::PuzzlesSolved,
+        GoalMetricType::GoalMetricReviewsRead => GoalMetric::ReviewsRead,
+    }
+}
+
+fn goal_period_from_proto(period: GoalPeriodType) -> GoalPeriod {
+    match period {
+        GoalPeriodType::GoalPeriodWeek => GoalPeriod::Week,
+        GoalPeriodType::GoalPeriodMonth => GoalPeriod::Month,
+        GoalPeriodType::GoalPeriodAllTime => GoalPeriod::AllTime,
+    }
+}
+
+fn convert_goal_to_proto(goal: GoalData) -> GoalProto {
+    let metric = match goal.metric {
+        GoalMetric::AverageAccuracy => GoalMetricType::GoalMetricAverageAccuracy,
+        GoalMetric::GamesPlayed => GoalMetricType::GoalMetricGamesPlayed,
+        GoalMetric::PuzzlesSolved => GoalMetricType::GoalMetricPuzzlesSolved,
+        GoalMetric::ReviewsRead => GoalMetricType::GoalMetricReviewsRead,
+    };
+    let period = match goal.period {
+        GoalPeriod::Week => GoalPeriodType::GoalPeriodWeek,
+        GoalPeriod::Month => GoalPeriodType::GoalPeriodMonth,
+        GoalPeriod::AllTime => GoalPeriodType::GoalPeriodAllTime,
+    };
+    GoalProto {
+        goal_id: goal.goal_id,
+        metric: metric as i32,
+        target: goal.target,
+        period: period as i32,
+        created_at: goal.created_at,
+    }
+}
@@ -1,17 +1,31 @@
 //! Endpoint handlers organized by domain
 
+pub mod activity;
+pub mod analysis;
+pub mod audit;
 pub mod engine;
 pub mod events;
 pub mod game;
+pub mod goals;
 pub mod persistence;
 pub mod positions;
 pub mod review;
+pub mod server;
 pub mod session;
+pub mod studies;
+pub mod sync;
 
+pub use activity::ActivityEndpoints;
+pub use analysis::AnalysisEndpoints;
+pub use audit::AuditEndpoints;
 pub use engine::EngineEndpoints;
 pub use events::EventsEndpoints;
 pub use game::GameEndpoints;
+pub use goals::GoalEndpoints;
 pub use persistence::PersistenceEndpoints;
 pub use positions::PositionsEndpoints;
 pub use review::ReviewEndpoints;
+pub use server::ServerEndpoints;
 pub use session::SessionEndpoints;
+pub use studies::StudyEndpoints;
+pub use sync::SyncEndpoints;
@@ -3,6 +3,7 @@
 use crate::persistence::Persistence;
 use crate::service::converters::convert_snapshot_to_proto;
 use crate::session::SessionManager;
+use ::chess::GameMode;
 use chess_proto::*;
 use std::sync::Arc;
 use tonic::{Request, Response, Status};
@@ -50,7 +51,10 @@ impl<D: Persistence> PersistenceEndpoints<D> {
             .into_iter()
             .map(|s| {
                 // Parse the stored game_mode string into a GameModeProto
-                let game_mode_proto = parse_stored_game_mode(&s.game_mode, &s.human_side);
+                let game_mode_proto = GameModeProto::from(GameMode::from_stored(
+                    &s.game_mode,
+                    s.human_side.as_deref(),
+                ));
                 SuspendedSessionInfo {
                     suspended_id: s.suspended_id,
                     fen: s.fen,
@@ -58,6 +62,8 @@ impl<D: Persistence> PersistenceEndpoints<D> {
                     move_count: s.move_count,
                     side_to_move: s.side_to_move,
                     created_at: s.created_at,
+                    name: s.name.unwrap_or_default(),
+                    source_game: s.source_game.unwrap_or_default(),
                 }
             })
             .collect();
@@ -90,36 +96,12 @@ impl<D: Persistence> PersistenceEndpoints<D> {
         let req = request.into_inner();
         tracing::info!(name = %req.name, fen = %req.fen, "RPC save_snapshot");
 
-        // Convert proto game mode to storage strings
-        let (game_mode_str, human_side) = if let Some(ref gm) = req.game_mode {
-            let mode = GameModeType::try_from(gm.mode).unwrap_or(GameModeType::HumanVsHuman);
-            match mode {
-                GameModeType::HumanVsEngine => {
-                    let side = gm
-                        .human_side
-                        .and_then(|v| PlayerSideProto::try_from(v).ok())
-                        .map(|s| match s {
-                            PlayerSideProto::Black => "black".to_string(),
-                            _ => "white".to_string(),
-                        });
-                    let mode_str = format!(
-                        "HumanVsEngine:{:?}",
-                        if side.as_deref() == Some("black") {
-                            "Black"
-                        } else {
-                            "White"
-                        }
-                    );
-                    (mode_str, side)
-                }
-                GameModeType::EngineVsEngine => ("EngineVsEngine".to_string(), None),
-                GameModeType::Analysis => ("Analysis".to_string(), None),
-                GameModeType::Review => ("Review".to_string(), None),
-                _ => ("HumanVsHuman".to_string(), None),
-            }
-        } else {
-            ("HumanVsHuman".to_string(), None)
-        };
+        let (game_mode_str, human_side) = req
+            .game_mode
+            .as_ref()
+            .map(GameMode::from)
+            .unwrap_or_default()
+            .to_stored();
 
         let suspended_id = self
             .session_manager
@@ -130,6 +112,7 @@ impl<D: Persistence> PersistenceEndpoints<D> {
                 human_side,
                 req.move_count,
                 req.skill_level as u8,
+                Some(req.source_game).filter(|id| !id.is_empty()),
             )
             .await
             .map_err(Status::internal)?;
@@ -137,6 +120,27 @@ impl<D: Persistence> PersistenceEndpoints<D> {
         Ok(Response::new(SaveSnapshotResponse { suspended_id }))
     }
 
+    /// Snapshot a live session's position under a generated name; the game
+    /// carries on.
+    pub async fn quick_snapshot(
+        &self,
+        request: Request<QuickSnapshotRequest>,
+    ) -> Result<Response<QuickSnapshotResponse>, Status> {
+        let req = request.into_inner();
+        tracing::info!(session_id = %req.session_id, "RPC quick_snapshot");
+
+        let data = self
+            .session_manager
+            .quick_snapshot(&req.session_id)
+            .await
+            .map_err(Status::not_found)?;
+
+        Ok(Response::new(QuickSnapshotResponse {
+            suspended_id: data.suspended_id,
+            name: data.name.unwrap_or_default(),
+        }))
+    }
+
     pub async fn delete_suspended_session(
         &self,
         request: Request<DeleteSuspendedSessionRequest>,
@@ -152,36 +156,3 @@ impl<D: Persistence> PersistenceEndpoints<D> {
         Ok(Response::new(Empty {}))
     }
 }
-
-/// Parse the stored game_mode string (e.g., "HumanVsEngine") and optional
-/// human_side into a GameModeProto for the client.
-fn parse_stored_game_mode(game_mode: &str, human_side: &Option<String>) -> GameModeProto {
-    match game_mode {
-        s if s.starts_with("HumanVsEngine") => {
-            let side = match human_side.as_deref() {
-                Some("black") => PlayerSideProto::Black as i32,
-                _ => PlayerSideProto::White as i32,
-            };
-            GameModeProto {
-                mode: GameModeType::HumanVsEngine as i32,
-                human_side: Some(side),
-            }
-        }
-        "EngineVsEngine" => GameModeProto {
-            mode: GameModeType::EngineVsEngine as i32,
-            human_side: None,
-        },
-        "Analysis" => GameModeProto {
-            mode: GameModeType::Analysis as i32,
-            human_side: None,
-        },
-        "Review" => GameModeProto {
-            mode: GameModeType::Review as i32,
-            human_side: None,
-        },
-        _ => GameModeProto {
-            mode: GameModeType::HumanVsHuman as i32,
-            human_side: None,
-        },
-    }
-}
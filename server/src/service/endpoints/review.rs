@@ -1,26 +1,50 @@
 //! Post-game review endpoints
 
+use crate::audit::AuditLog;
+use crate::disk::DiskGuard;
+use crate::import::{GameImport, PgnImporter};
 use crate::persistence::Persistence;
-use crate::review::types::{is_white_ply, AnalysisScore, MoveClassification, ReviewStatus};
-use crate::review::ReviewManager;
+use crate::review::similar::DEFAULT_SIMILAR_LIMIT;
+use crate::review::types::{
+    compute_handicap_analysis, AccuracyExclusion, AccuracyExclusions, AnalysisScore,
+    HandicapAnalysis, HandicapSide, MoveClassification, Opening, ReviewOptions, ReviewProgress,
+    ReviewStatus,
+};
+use crate::review::{requested_job, ReviewManager};
 use analysis::advanced::types::{
     AdvancedGameAnalysis, AdvancedPositionAnalysis, PsychologicalProfile,
 };
 use analysis::board_analysis::{
-    KingSafetyMetrics, PositionKingSafety, PositionTensionMetrics, TacticalEvidence, TacticalLine,
-    TacticalTag, TacticalTagKind,
+    KingSafetyMetrics, MobilityMetrics, PositionKingSafety, PositionMobility,
+    PositionTensionMetrics, TacticalEvidence, TacticalLine, TacticalTag, TacticalTagKind,
 };
+use analysis::{ClockSimulation, EndgameType, PositionSignature, TimeControl};
+use chess::pgn::{PgnGame, PgnMove};
+use chess::GameMode;
 use chess_proto::*;
+use std::pin::Pin;
 use std::sync::Arc;
+use tokio::sync::broadcast;
+use tokio_stream::Stream;
 use tonic::{Request, Response, Status};
 
 pub struct ReviewEndpoints<D: Persistence> {
     review_manager: Arc<ReviewManager<D>>,
+    audit_log: Arc<AuditLog<D>>,
+    disk: Arc<DiskGuard>,
 }
 
 impl<D: Persistence> ReviewEndpoints<D> {
-    pub fn new(review_manager: Arc<ReviewManager<D>>) -> Self {
-        Self { review_manager }
+    pub fn new(
+        review_manager: Arc<ReviewManager<D>>,
+        audit_log: Arc<AuditLog<D>>,
+        disk: Arc<DiskGuard>,
+    ) -> Self {
+        Self {
+            review_manager,
+            audit_log,
+            disk,
+        }
     }
 
     pub async fn list_finished_games(
@@ -37,17 +61,29 @@ impl<D: Persistence> ReviewEndpoints<D> {
 
         let mut proto_games = Vec::with_capacity(games.len());
         for g in games {
-            // Check review status for this game
-            let review_status = self
+            // Review status, opening and endgame for this game
+            let review = self
                 .review_manager
                 .get_review(&g.game_id)
                 .await
                 .ok()
-                .flatten()
+                .flatten();
+            let review_status = review
+                .as_ref()
                 .map(|r| convert_review_status_type(&r.status) as i32);
+            let opening = review
+                .as_ref()
+                .and_then(|r| r.opening.as_ref())
+                .map(convert_opening_to_proto);
+            let endgame = review
+                .as_ref()
+                .and_then(|r| r.endgame)
+                .map(|e| convert_endgame_to_proto(e) as i32);
 
-            let game_mode = parse_game_mode_string(&g.game_mode, g.human_side.as_deref());
+            let game_mode =
+                GameModeProto::from(GameMode::from_stored(&g.game_mode, g.human_side.as_deref()));
 
+            let derived_result = g.derived_result.filter(|d| *d != g.result);
             proto_games.push(FinishedGameInfo {
                 game_id: g.game_id,
                 result: g.result,
@@ -56,6 +92,11 @@ impl<D: Persistence> ReviewEndpoints<D> {
                 move_count: g.move_count,
                 created_at: g.created_at,
                 review_status,
+                simul_id: g.simul_id,
+                opponent: g.opponent,
+                opening,
+                derived_result,
+                endgame,
             });
         }
 
@@ -68,13 +109,27 @@ impl<D: Persistence> ReviewEndpoints<D> {
         &self,
         request: Request<EnqueueReviewRequest>,
     ) -> Result<Response<EnqueueReviewResponse>, Status> {
-        let game_id = &request.get_ref().game_id;
-        tracing::info!(game_id = %game_id, "RPC enqueue_review");
+        let req = request.get_ref();
+        tracing::info!(game_id = %req.game_id, "RPC enqueue_review");
 
-        self.review_manager
-            .enqueue(game_id)
+        self.disk
+            .ensure_room("review")
             .await
-            .map_err(Status::internal)?;
+            .map_err(Status::resource_exhausted)?;
+        let job_id = self
+            .review_manager
+            .enqueue_range(
+                &req.game_id,
+                req.from_ply,
+                req.to_ply,
+                ReviewOptions {
+                    depth: req.depth,
+                    multipv: req.multipv,
+                    skip_advanced: req.skip_advanced,
+                    exclusions: requested_exclusions(req),
+                },
+            )
+            .await?;
 
         Ok(Response::new(EnqueueReviewResponse {
             status: Some(ReviewStatusInfo {
@@ -82,28 +137,135 @@ impl<D: Persistence> ReviewEndpoints<D> {
                 current_ply: None,
                 total_plies: None,
                 error: None,
+                job_id: Some(job_id),
             }),
         }))
     }
 
+    pub async fn reanalyze_position(
+        &self,
+        request: Request<ReanalyzePositionRequest>,
+    ) -> Result<Response<ReanalyzePositionResponse>, Status> {
+        let req = request.get_ref();
+        tracing::info!(game_id = %req.game_id, ply = req.ply, depth = ?req.depth, "RPC reanalyze_position");
+
+        self.disk
+            .ensure_room("review")
+            .await
+            .map_err(Status::resource_exhausted)?;
+        let (job_id, depth) = self
+            .review_manager
+            .reanalyze_position(&req.game_id, req.ply, req.depth)
+            .await
+            .map_err(Status::failed_precondition)?;
+
+        Ok(Response::new(ReanalyzePositionResponse {
+            status: Some(ReviewStatusInfo {
+                status: ReviewStatusType::ReviewStatusQueued as i32,
+                current_ply: None,
+                total_plies: None,
+                error: None,
+                job_id: Some(job_id),
+            }),
+            depth,
+        }))
+    }
+
     pub async fn get_review_status(
         &self,
         request: Request<GetReviewStatusRequest>,
     ) -> Result<Response<GetReviewStatusResponse>, Status> {
-        let game_id = &request.get_ref().game_id;
-        tracing::info!(game_id = %game_id, "RPC get_review_status");
+        let id = &request.get_ref().game_id;
+        tracing::info!(id = %id, "RPC get_review_status");
 
         let status = self
             .review_manager
-            .get_status(game_id)
+            .get_status(id)
             .await
             .map_err(Status::not_found)?;
+        let mut info = convert_review_status(&status);
+        info.job_id = self.review_manager.pending_job_id(id).await;
 
         Ok(Response::new(GetReviewStatusResponse {
-            status: Some(convert_review_status(&status)),
+            status: Some(info),
         }))
     }
 
+    pub async fn cancel_review(
+        &self,
+        request: Request<CancelReviewRequest>,
+    ) -> Result<Response<CancelReviewResponse>, Status> {
+        let id = &request.get_ref().game_id;
+        tracing::info!(id = %id, "RPC cancel_review");
+
+        let job_id = self
+            .review_manager
+            .cancel_review(id)
+            .await
+            .map_err(Status::not_found)?;
+
+        Ok(Response::new(CancelReviewResponse { job_id }))
+    }
+
+    /// Stream a game's review progress, starting with where it is now, until
+    /// the review completes or fails.
+    pub async fn stream_review_progress(
+        &self,
+        request: Request<StreamReviewProgressRequest>,
+    ) -> Result<
+        Response<Pin<Box<dyn Stream<Item = Result<ReviewProgressUpdate, Status>> + Send>>>,
+        Status,
+    > {
+        let id = &request.get_ref().game_id;
+        tracing::info!(id = %id, "RPC stream_review_progress");
+
+        let (current, mut updates) = self
+            .review_manager
+            .subscribe_progress(id)
+            .await
+            .map_err(Status::not_found)?;
+        // Asked by job id, the stream follows that job alone
+        let job_id = requested_job(id).map(str::to_string);
+        let review_manager = self.review_manager.clone();
+
+        let stream = async_stream::stream! {
+            let mut finished = current.is_finished();
+            let game_id = current.game_id.clone();
+            yield Ok(convert_review_progress(current));
+            while !finished {
+                match updates.recv().await {
+                    Ok(progress) if progress.concerns(&game_id, job_id.as_deref()) => {
+                        finished = progress.is_finished();
+                        yield Ok(convert_review_progress(progress));
+                    }
+                    Ok(_) => continue,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        // The final update may be among those missed, so
+                        // catch up from the store
+                        tracing::debug!(game_id = %game_id, skipped, "Review progress lagged");
+                        match review_manager.current_progress(&game_id).await {
+                            Ok(progress) => {
+                                // A job asked for that is no longer pending has ended
+                                finished = progress.is_finished()
+                                    || job_id
+                                        .as_ref()
+                                        .is_some_and(|job| progress.job_id.as_ref() != Some(job));
+                                yield Ok(convert_review_progress(progress));
+                            }
+                            Err(e) => {
+                                yield Err(Status::not_found(e));
+                                break;
+                            }
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        };
+
+        Ok(Response::new(Box::pin(stream)))
+    }
+
     pub async fn get_game_review(
         &self,
         request: Request<GetGameReviewRequest>,
@@ -118,8 +280,20 @@ impl<D: Persistence> ReviewEndpoints<D> {
             .map_err(Status::internal)?
             .ok_or_else(|| Status::not_found(format!("Review not found: {}", game_id)))?;
 
+        let mut proto = convert_game_review_to_proto(&review);
+
+        if let Ok(Some(game)) = self.review_manager.get_finished_game(game_id).await {
+            // Engine-vs-engine games get a handicap analysis instead of a human accuracy framing
+            if matches!(review.status, ReviewStatus::Complete) && game.game_mode == "EngineVsEngine"
+            {
+                let handicap = compute_handicap_analysis(&review.positions, game.skill_level);
+                proto.handicap = Some(convert_handicap_to_proto(&handicap));
+            }
+            fill_unanalyzed_plies(&mut proto, &game);
+        }
+
         Ok(Response::new(GetGameReviewResponse {
-            review: Some(convert_game_review_to_proto(&review)),
+            review: Some(proto),
         }))
     }
 
@@ -137,7 +311,12 @@ impl<D: Persistence> ReviewEndpoints<D> {
             .map_err(Status::internal)?
             .ok_or_else(|| Status::not_found(format!("Review not found: {}", game_id)))?;
 
-        let pgn = generate_annotated_pgn(&review);
+        let game = self
+            .review_manager
+            .get_finished_game(game_id)
+            .await
+            .map_err(Status::internal)?;
+        let pgn = generate_annotated_pgn(&review, game.as_ref());
         Ok(Response::new(ExportReviewPgnResponse { pgn }))
     }
 
@@ -156,6 +335,374 @@ impl<D: Persistence> ReviewEndpoints<D> {
         Ok(Response::new(Empty {}))
     }
 
+    pub async fn import_pgn(
+        &self,
+        request: Request<ImportPgnRequest>,
+    ) -> Result<Response<ImportPgnResponse>, Status> {
+        let req = request.get_ref();
+        tracing::info!(bytes = req.pgn.len(), review = req.review, "RPC import_pgn");
+
+        let disk_warning = self
+            .disk
+            .ensure_room("PGN import")
+            .await
+            .map_err(Status::resource_exhausted)?;
+        let importer = PgnImporter::new(
+            self.review_manager.finished_game_store(),
+            req.review.then(|| self.review_manager.clone()),
+            self.audit_log.clone(),
+        );
+        let games = importer
+            .import_games(&req.pgn)
+            .await
+            .map_err(Status::internal)?;
+        if games.is_empty() {
+            return Err(Status::invalid_argument("No PGN games found"));
+        }
+
+        let mut response = ImportPgnResponse::default();
+        response.warnings.extend(disk_warning);
+        for (index, game) in games.into_iter().enumerate() {
+            match game {
+                GameImport::Imported(game_id) => {
+                    response.imported += 1;
+                    response.game_ids.push(game_id);
+                }
+                GameImport::ResultMismatch {
+                    game_id,
+                    claimed,
+                    derived,
+                } => {
+                    response.imported += 1;
+                    response.game_ids.push(game_id);
+                    response.warnings.push(format!(
+                        "Game {}: Result tag says {} but the game shows {}",
+                        index + 1,
+                        claimed,
+                        derived
+                    ));
+                }
+                GameImport::Duplicate(game_id) => {
+                    response.duplicates += 1;
+                    response.game_ids.push(game_id);
+                }
+                GameImport::Skipped(e) => {
+                    response.errors.push(format!("Game {}: {}", index + 1, e))
+                }
+            }
+        }
+        if response.game_ids.is_empty() {
+            return Err(Status::invalid_argument(response.errors.join("; ")));
+        }
+        Ok(Response::new(response))
+    }
+
+    pub async fn get_simul_report(
+        &self,
+        request: Request<GetSimulReportRequest>,
+    ) -> Result<Response<GetSimulReportResponse>, Status> {
+        let simul_id = &request.get_ref().simul_id;
+        tracing::info!(simul_id = %simul_id, "RPC get_simul_report");
+
+        let report = self
+            .review_manager
+            .simul_report(simul_id)
+            .await
+            .map_err(Status::not_found)?;
+
+        let boards = report
+            .boards
+            .into_iter()
+            .map(|b| SimulBoardResult {
+                review_status: b
+                    .review
+                    .as_ref()
+                    .map(|r| convert_review_status_type(&r.status) as i32),
+                human_accuracy: b.human_accuracy,
+                game_id: b.game.game_id,
+                result: b.game.result,
+                result_reason: b.game.result_reason,
+                human_side: b.game.human_side,
+                move_count: b.game.move_count,
+            })
+            .collect();
+
+        Ok(Response::new(GetSimulReportResponse {
+            simul_id: report.simul_id,
+            boards,
+            wins: report.wins,
+            draws: report.draws,
+            losses: report.losses,
+            average_accuracy: report.average_accuracy,
+        }))
+    }
+
+    pub async fn get_opponent_stats(
+        &self,
+        _request: Request<GetOpponentStatsRequest>,
+    ) -> Result<Response<GetOpponentStatsResponse>, Status> {
+        tracing::info!("RPC get_opponent_stats");
+
+        let stats = self
+            .review_manager
+            .opponent_stats()
+            .await
+            .map_err(Status::internal)?;
+
+        let opponents = stats
+            .into_iter()
+            .map(|s| OpponentStatsProto {
+                name: s.name,
+                games: s.games,
+                wins: s.wins,
+                draws: s.draws,
+                losses: s.losses,
+                skill_level: u32::from(s.skill_level),
+                average_accuracy: s.average_accuracy,
+                performance_rating: s.performance_rating,
+                last_played_at: s.last_played_at,
+            })
+            .collect();
+
+        Ok(Response::new(GetOpponentStatsResponse { opponents }))
+    }
+
+    pub async fn find_similar_positions(
+        &self,
+        request: Request<FindSimilarPositionsRequest>,
+    ) -> Result<Response<FindSimilarPositionsResponse>, Status> {
+        let req = request.into_inner();
+        tracing::info!(fen = %req.fen, "RPC find_similar_positions");
+
+        let target = PositionSignature::from_fen(&req.fen)
+            .ok_or_else(|| Status::invalid_argument(format!("Invalid FEN: {}", req.fen)))?;
+        let limit = match req.limit {
+            Some(limit) if limit > 0 => limit as usize,
+            _ => DEFAULT_SIMILAR_LIMIT,
+        };
+        let found = self
+            .review_manager
+            .similar_positions(&target, limit)
+            .await
+            .map_err(Status::internal)?;
+
+        let count = |result: &str| found.iter().filter(|p| p.result == result).count() as u32;
+        let (white_wins, draws, black_wins) =
+            (count("WhiteWins"), count("Draw"), count("BlackWins"));
+        let positions = found
+            .into_iter()
+            .map(|p| SimilarPositionProto {
+                game_id: p.game_id,
+                ply: p.ply,
+                fen: p.fen,
+                similarity: p.similarity,
+                next_san: p.next_san,
+                result: p.result,
+            })
+            .collect();
+
+        Ok(Response::new(FindSimilarPositionsResponse {
+            positions,
+            white_wins,
+            draws,
+            black_wins,
+        }))
+    }
+
+    pub async fn simulate_clock(
+        &self,
+        request: Request<SimulateClockRequest>,
+    ) -> Result<Response<SimulateClockResponse>, Status> {
+        let req = request.into_inner();
+        tracing::info!(
+            game_id = %req.game_id,
+            initial_ms = req.initial_ms,
+            increment_ms = req.increment_ms,
+            "RPC simulate_clock"
+        );
+        if req.initial_ms == 0 {
+            return Err(Status::invalid_argument(
+                "Simulated clock needs a starting time",
+            ));
+        }
+
+        let simulation = self
+            .review_manager
+            .simulate_clock(
+                &req.game_id,
+                TimeControl {
+                    initial_ms: req.initial_ms,
+                    increment_ms: req.increment_ms,
+                },
+            )
+            .await
+            .map_err(Status::not_found)?;
+
+        let side = |s: ClockSimulation| ClockSimulationSide {
+            flagged_at_ply: s.flagged_at_ply,
+            lowest_ms: s.lowest_ms,
+            final_ms: s.final_ms(),
+            untimed_moves: s.untimed_moves,
+        };
+        Ok(Response::new(SimulateClockResponse {
+            white: Some(side(simulation.white)),
+            black: Some(side(simulation.black)),
+        }))
+    }
+
+    pub async fn compare_reviews(
+        &self,
+        request: Request<CompareReviewsRequest>,
+    ) -> Result<Response<CompareReviewsResponse>, Status> {
+        let req = request.into_inner();
+        tracing::info!(game_id = %req.game_id, revision = ?req.revision, "RPC compare_reviews");
+
+        let comparison = self
+            .review_manager
+            .compare_reviews(&req.game_id, req.revision)
+            .await
+            .map_err(Status::not_found)?;
+
+        Ok(Response::new(CompareReviewsResponse {
+            revisions: comparison
+                .revisions
+                .iter()
+                .map(|r| ReviewRevisionInfo {
+                    revision: r.revision,
+                    archived_at: r.archived_at,
+                    analysis_depth: r.analysis_depth,
+                    analyzed_plies: r.positions.len() as u32,
+                    white_accuracy: r.white_accuracy,
+                    black_accuracy: r.black_accuracy,
+                })
+                .collect(),
+            compared_revision: comparison.compared,
+            compared_plies: comparison.diff.compared_plies,
+            classification_changes: comparison.diff.classification_changes,
+            best_move_changes: comparison.diff.best_move_changes,
+            changes: comparison
+                .diff
+                .changes
+                .iter()
+                .map(|c| ReviewPlyChange {
+                    ply: c.ply,
+                    played_san: c.played_san.clone(),
+                    old_classification: convert_classification_to_proto(&c.old_classification)
+                        as i32,
+                    new_classification: convert_classification_to_proto(&c.new_classification)
+                        as i32,
+                    old_best_move_san: c.old_best_move_san.clone(),
+                    new_best_move_san: c.new_best_move_san.clone(),
+                    old_cp_loss: c.old_cp_loss,
+                    new_cp_loss: c.new_cp_loss,
+                    old_depth: c.old_depth,
+                    new_depth: c.new_depth,
+                })
+                .collect(),
+        }))
+    }
+
+    pub async fn set_review_bookmark(
+        &self,
+        request: Request<SetReviewBookmarkRequest>,
+    ) -> Result<Response<ReviewBookmark>, Status> {
+        let req = request.into_inner();
+        tracing::info!(game_id = %req.game_id, ply = req.ply, "RPC set_review_bookmark");
+
+        let bookmark = self
+            .review_manager
+            .set_bookmark(&req.game_id, req.ply, &req.note)
+            .await
+            .map_err(Status::not_found)?;
+        Ok(Response::new(convert_bookmark_to_proto(bookmark)))
+    }
+
+    pub async fn list_review_bookmarks(
+        &self,
+        request: Request<ListReviewBookmarksRequest>,
+    ) -> Result<Response<ListReviewBookmarksResponse>, Status> {
+        let game_id = &request.get_ref().game_id;
+        tracing::info!(game_id = %game_id, "RPC list_review_bookmarks");
+
+        let bookmarks = self
+            .review_manager
+            .list_bookmarks(game_id)
+            .await
+            .map_err(Status::internal)?;
+        Ok(Response::new(ListReviewBookmarksResponse {
+            bookmarks: bookmarks
+                .into_iter()
+                .map(convert_bookmark_to_proto)
+                .collect(),
+        }))
+    }
+
+    pub async fn delete_review_bookmark(
+        &self,
+        request: Request<DeleteReviewBookmarkRequest>,
+    ) -> Result<Response<Empty>, Status> {
+        let req = request.into_inner();
+        tracing::info!(game_id = %req.game_id, ply = req.ply, "RPC delete_review_bookmark");
+
+        self.review_manager
+            .delete_bookmark(&req.game_id, req.ply)
+            .await
+            .map_err(Status::internal)?;
+        Ok(Response::new(Empty {}))
+    }
+
+    pub async fn get_training_digest(
+        &self,
+        request: Request<GetTrainingDigestRequest>,
+    ) -> Result<Response<GetTrainingDigestResponse>, Status> {
+        let days = request.get_ref().days;
+        tracing::info!(days, "RPC get_training_digest");
+
+        let digest = self
+            .review_manager
+            .training_digest(days)
+            .await
+            .map_err(Status::internal)?;
+        let markdown = digest.to_markdown();
+        let (first_date, last_date) = (
+            crate::persistence::iso_date(digest.first_day),
+            crate::persistence::iso_date(digest.last_day),
+        );
+
+        Ok(Response::new(GetTrainingDigestResponse {
+            first_day: digest.first_day,
+            last_day: digest.last_day,
+            games_played: digest.games_played,
+            wins: digest.wins,
+            draws: digest.draws,
+            losses: digest.losses,
+            reviewed_games: digest.reviewed_games,
+            average_accuracy: digest.average_accuracy,
+            previous_average_accuracy: digest.previous_average_accuracy,
+            accuracy_trend: digest
+                .accuracy_trend
+                .into_iter()
+                .map(|p| AccuracyPointProto {
+                    day: p.day,
+                    games: p.games,
+                    accuracy: p.accuracy,
+                })
+                .collect(),
+            top_mistakes: digest
+                .top_mistakes
+                .into_iter()
+                .map(|m| RecurringMistakeProto {
+                    theme: m.theme,
+                    count: m.count,
+                })
+                .collect(),
+            focus_areas: digest.focus_areas,
+            markdown,
+            first_date,
+            last_date,
+        }))
+    }
+
     pub async fn get_advanced_analysis(
         &self,
         request: Request<GetAdvancedAnalysisRequest>,
@@ -176,6 +723,46 @@ impl<D: Persistence> ReviewEndpoints<D> {
             analysis: Some(convert_advanced_analysis_to_proto(&analysis)),
         }))
     }
+
+    pub async fn explain_position(
+        &self,
+        request: Request<ExplainPositionRequest>,
+    ) -> Result<Response<ExplainPositionResponse>, Status> {
+        let req = request.into_inner();
+        tracing::info!(game_id = %req.game_id, ply = req.ply, "RPC explain_position");
+
+        let (position, explanation) = self
+            .review_manager
+            .explain_position(&req.game_id, req.ply)
+            .await
+            .map_err(Status::not_found)?;
+
+        Ok(Response::new(ExplainPositionResponse {
+            ply: position.ply,
+            best_move_san: position.best_move_san,
+            line: explanation
+                .line
+                .iter()
+                .map(|step| ExplanationStepProto {
+                    san: step.san.clone(),
+                    by_mover: step.by_mover,
+                    tags: step
+                        .tags
+                        .iter()
+                        .map(convert_tactical_tag_to_proto)
+                        .collect(),
+                })
+                .collect(),
+            material_before: explanation.material_before,
+            material_after: explanation.material_after,
+            own_king_before: explanation.own_king_before,
+            own_king_after: explanation.own_king_after,
+            their_king_before: explanation.their_king_before,
+            their_king_after: explanation.their_king_after,
+            ends_in_mate: explanation.ends_in_mate,
+            points: explanation.points,
+        }))
+    }
 }
 
 // ============================================================================
@@ -191,6 +778,16 @@ fn convert_review_status_type(status: &ReviewStatus) -> ReviewStatusType {
     }
 }
 
+fn convert_review_progress(progress: ReviewProgress) -> ReviewProgressUpdate {
+    let mut status = convert_review_status(&progress.status);
+    status.job_id = progress.job_id;
+    ReviewProgressUpdate {
+        game_id: progress.game_id,
+        status: Some(status),
+        eta_ms: progress.eta.map(|eta| eta.as_millis() as u64),
+    }
+}
+
 fn convert_review_status(status: &ReviewStatus) -> ReviewStatusInfo {
     match status {
         ReviewStatus::Queued => ReviewStatusInfo {
@@ -198,6 +795,7 @@ fn convert_review_status(status: &ReviewStatus) -> ReviewStatusInfo {
             current_ply: None,
             total_plies: None,
             error: None,
+            job_id: None,
         },
         ReviewStatus::Analyzing {
             current_ply,
@@ -207,23 +805,26 @@ fn convert_review_status(status: &ReviewStatus) -> ReviewStatusInfo {
             current_ply: Some(*current_ply),
             total_plies: Some(*total_plies),
             error: None,
+            job_id: None,
         },
         ReviewStatus::Complete => ReviewStatusInfo {
             status: ReviewStatusType::ReviewStatusComplete as i32,
             current_ply: None,
             total_plies: None,
             error: None,
+            job_id: None,
         },
         ReviewStatus::Failed { error } => ReviewStatusInfo {
             status: ReviewStatusType::ReviewStatusFailed as i32,
             current_ply: None,
             total_plies: None,
             error: Some(error.clone()),
+            job_id: None,
         },
     }
 }
 
-fn convert_score_to_proto(score: &AnalysisScore) -> ReviewScore {
+pub(super) fn convert_score_to_proto(score: &AnalysisScore) -> ReviewScore {
     match score {
         AnalysisScore::Centipawns(cp) => ReviewScore {
             score: Some(review_score::Score::Centipawns(*cp)),
@@ -247,6 +848,8 @@ fn convert_classification_to_proto(
         MoveClassification::Forced => chess_proto::MoveClassification::ClassificationForced,
         MoveClassification::Book => chess_proto::MoveClassification::ClassificationBook,
         MoveClassification::Brilliant => chess_proto::MoveClassification::ClassificationBrilliant,
+        MoveClassification::Great => chess_proto::MoveClassification::ClassificationGreat,
+        MoveClassification::Miss => chess_proto::MoveClassification::ClassificationMiss,
     }
 }
 
@@ -273,6 +876,8 @@ fn convert_game_review_to_proto(
                 pv: p.pv.clone(),
                 depth: p.depth,
                 clock_ms: p.clock_ms,
+                excluded: p.excluded.map(|e| convert_exclusion_to_proto(e) as i32),
+                only_move: p.only_move,
             })
             .collect(),
         white_accuracy: review.white_accuracy,
@@ -283,30 +888,120 @@ fn convert_game_review_to_proto(
         started_at: review.started_at,
         completed_at: review.completed_at,
         winner: review.winner.clone(),
+        handicap: None,
+        opening: review.opening.as_ref().map(convert_opening_to_proto),
+        coverage: review
+            .coverage()
+            .iter()
+            .map(|r| CoveredRangeProto {
+                from_ply: r.from_ply,
+                to_ply: r.to_ply,
+                depth: r.depth,
+            })
+            .collect(),
+        endgame: review.endgame.map(|e| convert_endgame_to_proto(e) as i32),
     }
 }
 
-fn parse_game_mode_string(mode: &str, human_side: Option<&str>) -> GameModeProto {
-    if mode.starts_with("HumanVsEngine") {
-        let side = match human_side {
-            Some("black") => Some(PlayerSideProto::Black as i32),
-            _ => Some(PlayerSideProto::White as i32),
-        };
-        GameModeProto {
-            mode: GameModeType::HumanVsEngine as i32,
-            human_side: side,
-        }
-    } else {
-        let mode_type = match mode {
-            "EngineVsEngine" => GameModeType::EngineVsEngine,
-            "Analysis" => GameModeType::Analysis,
-            "Review" => GameModeType::Review,
-            _ => GameModeType::HumanVsHuman,
-        };
-        GameModeProto {
-            mode: mode_type as i32,
-            human_side: None,
-        }
+/// List the plies the review has not analysed with just their move, so a
+/// review of part of a game can be stepped through like a full one.
+fn fill_unanalyzed_plies(
+    proto: &mut chess_proto::GameReviewProto,
+    game: &crate::persistence::FinishedGameData,
+) {
+    let mut analyzed = std::mem::take(&mut proto.positions).into_iter().peekable();
+    proto.positions = game
+        .moves
+        .iter()
+        .zip(1u32..)
+        .map(|(mv, ply)| {
+            analyzed
+                .next_if(|p| p.ply == ply)
+                .unwrap_or_else(|| chess_proto::PositionReview {
+                    ply,
+                    fen: mv.fen_after.clone(),
+                    played_san: mv.san.clone(),
+                    clock_ms: mv.clock_ms,
+                    ..Default::default()
+                })
+        })
+        .collect();
+}
+
+/// The exclusions an enqueue asks for; `None` leaves the server's.
+fn requested_exclusions(req: &EnqueueReviewRequest) -> Option<AccuracyExclusions> {
+    (req.exclude_book || req.exclude_forced || req.exclude_decided_cp.is_some()).then_some(
+        AccuracyExclusions {
+            book: req.exclude_book,
+            forced: req.exclude_forced,
+            decided_cp: req.exclude_decided_cp,
+        },
+    )
+}
+
+fn convert_exclusion_to_proto(exclusion: AccuracyExclusion) -> AccuracyExclusionProto {
+    match exclusion {
+        AccuracyExclusion::Book => AccuracyExclusionProto::AccuracyExclusionBook,
+        AccuracyExclusion::Forced => AccuracyExclusionProto::AccuracyExclusionForced,
+        AccuracyExclusion::Decided => AccuracyExclusionProto::AccuracyExclusionDecided,
+    }
+}
+
+fn convert_opening_to_proto(opening: &Opening) -> OpeningProto {
+    OpeningProto {
+        eco: opening.eco.clone(),
+        name: opening.name.clone(),
+        ply: opening.ply,
+    }
+}
+
+fn convert_endgame_to_proto(endgame: EndgameType) -> EndgameTypeProto {
+    match endgame {
+        EndgameType::Pawn => EndgameTypeProto::EndgameTypePawn,
+        EndgameType::Knight => EndgameTypeProto::EndgameTypeKnight,
+        EndgameType::Bishop => EndgameTypeProto::EndgameTypeBishop,
+        EndgameType::OppositeBishops => EndgameTypeProto::EndgameTypeOppositeBishops,
+        EndgameType::Minor => EndgameTypeProto::EndgameTypeMinor,
+        EndgameType::Rook => EndgameTypeProto::EndgameTypeRook,
+        EndgameType::RookAndMinor => EndgameTypeProto::EndgameTypeRookAndMinor,
+        EndgameType::Queen => EndgameTypeProto::EndgameTypeQueen,
+        EndgameType::Mixed => EndgameTypeProto::EndgameTypeMixed,
+    }
+}
+
+fn convert_bookmark_to_proto(bookmark: crate::review::types::ReviewBookmark) -> ReviewBookmark {
+    ReviewBookmark {
+        game_id: bookmark.game_id,
+        ply: bookmark.ply,
+        note: bookmark.note,
+        created_at: bookmark.created_at,
+    }
+}
+
+fn convert_handicap_to_proto(handicap: &HandicapAnalysis) -> HandicapAnalysisProto {
+    HandicapAnalysisProto {
+        skill_level: handicap.skill_level as u32,
+        white: Some(convert_handicap_side_to_proto(&handicap.white)),
+        black: Some(convert_handicap_side_to_proto(&handicap.black)),
+    }
+}
+
+fn convert_handicap_side_to_proto(side: &HandicapSide) -> HandicapSideProto {
+    HandicapSideProto {
+        best_move_rate: side.best_move_rate,
+        avg_cp_loss: side.avg_cp_loss,
+        total_cp_cost: side.total_cp_cost,
+        missed_wins: side
+            .missed_wins
+            .iter()
+            .map(|m| MissedWinProto {
+                ply: m.ply,
+                played_san: m.played_san.clone(),
+                best_move_san: m.best_move_san.clone(),
+                best_cp: m.best_cp,
+                played_cp: m.played_cp,
+            })
+            .collect(),
     }
 }
 
@@ -353,10 +1048,11 @@ fn convert_advanced_position_to_proto(
             .iter()
             .map(convert_tactical_tag_to_proto)
             .collect(),
+        mobility: Some(convert_mobility_to_proto(&pos.mobility)),
     }
 }
 
-fn convert_tactical_tag_to_proto(tag: &TacticalTag) -> TacticalTagProto {
+pub(crate) fn convert_tactical_tag_to_proto(tag: &TacticalTag) -> TacticalTagProto {
     TacticalTagProto {
         kind: convert_tactical_tag_kind(&tag.kind) as i32,
         attacker: tag.attacker.clone(),
@@ -380,6 +1076,8 @@ fn convert_tactical_tag_kind(kind: &TacticalTagKind) -> TacticalTagKindProto {
         TacticalTagKind::Zwischenzug => TacticalTagKindProto::TacticalTagKindZwischenzug,
         TacticalTagKind::BackRankWeakness => TacticalTagKindProto::TacticalTagKindBackRankWeakness,
         TacticalTagKind::MateThreat => TacticalTagKindProto::TacticalTagKindMateThreat,
+        TacticalTagKind::Deflection => TacticalTagKindProto::TacticalTagKindDeflection,
+        TacticalTagKind::Decoy => TacticalTagKindProto::TacticalTagKindDecoy,
     }
 }
 
@@ -436,6 +1134,20 @@ fn convert_tension_to_proto(t: &PositionTensionMetrics) -> PositionTensionMetric
     }
 }
 
+fn convert_mobility_to_proto(mobility: &PositionMobility) -> PositionMobilityProto {
+    PositionMobilityProto {
+        white: Some(convert_mobility_metrics(&mobility.white)),
+        black: Some(convert_mobility_metrics(&mobility.black)),
+    }
+}
+
+fn convert_mobility_metrics(m: &MobilityMetrics) -> MobilityMetricsProto {
+    MobilityMetricsProto {
+        mobility: m.mobility as u32,
+        space: m.space as u32,
+    }
+}
+
 fn convert_psychology_to_proto(p: &PsychologicalProfile) -> PsychologicalProfileProto {
     PsychologicalProfileProto {
         color: p.color.to_string(),
@@ -456,64 +1168,83 @@ fn convert_psychology_to_proto(p: &PsychologicalProfile) -> PsychologicalProfile
     }
 }
 
-fn generate_annotated_pgn(review: &crate::review::types::GameReview) -> String {
-    let mut pgn = String::new();
-
-    // PGN headers
-    pgn.push_str("[Event \"ChessTTY Game\"]\n");
-    pgn.push_str(&format!(
-        "[WhiteAccuracy \"{:.1}\"]\n",
-        review.white_accuracy.unwrap_or(0.0)
-    ));
-    pgn.push_str(&format!(
-        "[BlackAccuracy \"{:.1}\"]\n",
-        review.black_accuracy.unwrap_or(0.0)
-    ));
-    pgn.push('\n');
-
-    // Moves with annotations
-    for pos in review.positions.iter() {
-        let is_white = is_white_ply(pos.ply);
-        let move_number = (pos.ply as usize).div_ceil(2);
-
-        if is_white {
-            pgn.push_str(&format!("{}. ", move_number));
-        }
+fn generate_annotated_pgn(
+    review: &crate::review::types::GameReview,
+    game: Option<&crate::persistence::FinishedGameData>,
+) -> String {
+    let mut tags = vec![
+        ("Event".to_string(), "ChessTTY Game".to_string()),
+        (
+            "WhiteAccuracy".to_string(),
+            format!("{:.1}", review.white_accuracy.unwrap_or(0.0)),
+        ),
+        (
+            "BlackAccuracy".to_string(),
+            format!("{:.1}", review.black_accuracy.unwrap_or(0.0)),
+        ),
+    ];
+    if let Some(opening) = &review.opening {
+        tags.push(("ECO".to_string(), opening.eco.clone()));
+        tags.push(("Opening".to_string(), opening.name.clone()));
+    }
 
-        pgn.push_str(&pos.played_san);
+    // Every move of the game; those outside a partial review go without
+    // annotations
+    let moves = match game {
+        Some(game) => game
+            .moves
+            .iter()
+            .zip(1u32..)
+            .map(
+                |(mv, ply)| match review.positions.iter().find(|p| p.ply == ply) {
+                    Some(pos) => annotated_move(pos),
+                    None => PgnMove::new(mv.san.as_str()),
+                },
+            )
+            .collect(),
+        None => review.positions.iter().map(annotated_move).collect(),
+    };
 
-        // Add NAG if applicable
-        if let Some(nag) = pos.classification.to_nag() {
-            pgn.push_str(&format!(" ${}", nag));
-        }
+    let result = match review.winner.as_deref() {
+        Some("White") => "1-0",
+        Some("Black") => "0-1",
+        Some("Draw") => "1/2-1/2",
+        _ => "*",
+    };
+    PgnGame {
+        tags,
+        moves,
+        result: result.to_string(),
+    }
+    .to_pgn()
+}
 
-        // Add clock annotation if available
-        if let Some(ms) = pos.clock_ms {
+/// A reviewed move with its NAG, clock and eval comment (richer for
+/// inaccuracies, mistakes, blunders and misses).
+fn annotated_move(pos: &crate::review::types::PositionReview) -> PgnMove {
+    let eval = if pos.classification.is_error() {
+        format!(
+            "{}; best: {} ({}cp)",
+            pos.eval_before.display(),
+            pos.best_move_san,
+            pos.cp_loss
+        )
+    } else {
+        pos.eval_before.display()
+    };
+    let comment = match pos.clock_ms {
+        Some(ms) => {
             let total_secs = ms / 1000;
             let h = total_secs / 3600;
             let m = (total_secs % 3600) / 60;
             let s = total_secs % 60;
-            pgn.push_str(&format!(" {{[%clk {}:{:02}:{:02}]}}", h, m, s));
+            format!("[%clk {}:{:02}:{:02}] {}", h, m, s, eval)
         }
-
-        // Add comment with eval (richer for inaccuracies, mistakes, blunders)
-        let comment = match pos.classification {
-            MoveClassification::Inaccuracy
-            | MoveClassification::Mistake
-            | MoveClassification::Blunder => {
-                format!(
-                    "{{ {}; best: {} ({}cp) }}",
-                    pos.eval_before.display(),
-                    pos.best_move_san,
-                    pos.cp_loss
-                )
-            }
-            _ => format!("{{ {} }}", pos.eval_before.display()),
-        };
-        pgn.push_str(&format!(" {}", comment));
-
-        pgn.push(' ');
+        None => eval,
+    };
+    PgnMove {
+        nags: pos.classification.to_nag().into_iter().collect(),
+        comment: Some(comment),
+        ..PgnMove::new(pos.played_san.as_str())
     }
-
-    pgn.trim_end().to_string()
 }
@@ -0,0 +1,125 @@
+//! Server identification endpoint
+
+use crate::disk::{DiskGuard, DiskUsage};
+use crate::persistence::now_timestamp;
+use crate::power::PowerState;
+use crate::review::RecoveryReport;
+use chess_proto::*;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::watch;
+use tonic::{Request, Response, Status};
+
+pub struct ServerEndpoints {
+    started_at: u64,
+    power: watch::Receiver<PowerState>,
+    disk: Arc<DiskGuard>,
+    engine_path: watch::Receiver<Option<PathBuf>>,
+    recovery: watch::Receiver<Option<RecoveryReport>>,
+}
+
+impl ServerEndpoints {
+    pub fn new(
+        power: watch::Receiver<PowerState>,
+        disk: Arc<DiskGuard>,
+        engine_path: watch::Receiver<Option<PathBuf>>,
+        recovery: watch::Receiver<Option<RecoveryReport>>,
+    ) -> Self {
+        Self {
+            started_at: now_timestamp(),
+            power,
+            disk,
+            engine_path,
+            recovery,
+        }
+    }
+
+    pub async fn get_server_info(
+        &self,
+        _request: Request<GetServerInfoRequest>,
+    ) -> Result<Response<GetServerInfoResponse>, Status> {
+        tracing::info!("RPC get_server_info");
+
+        let disk = self.disk.usage().await;
+        let power = *self.power.borrow();
+        let engine_path = self.engine_path.borrow().clone();
+        let recovery = *self.recovery.borrow();
+        Ok(Response::new(server_info(
+            self.started_at,
+            power,
+            disk,
+            engine_path,
+            recovery,
+        )))
+    }
+}
+
+fn server_info(
+    started_at: u64,
+    power: PowerState,
+    disk: DiskUsage,
+    engine_path: Option<PathBuf>,
+    recovery: Option<RecoveryReport>,
+) -> GetServerInfoResponse {
+    GetServerInfoResponse {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        pid: std::process::id(),
+        executable: std::env::current_exe()
+            .map(|p| p.display().to_string())
+            .unwrap_or_default(),
+        started_at,
+        power_source: power.source.name().to_string(),
+        low_power: power.low_power,
+        disk_free_bytes: disk.free_bytes,
+        db_size_bytes: disk.db_bytes,
+        log_size_bytes: disk.log_bytes,
+        disk_low: disk.low,
+        engine_missing: engine_path.is_none(),
+        engine_path: engine_path
+            .map(|p| p.display().to_string())
+            .unwrap_or_default(),
+        recovery: recovery.map(|r| RecoveryInfo {
+            interrupted: r.interrupted as u32,
+            unreviewed: r.unreviewed as u32,
+            failed: r.failed as u32,
+            elapsed_ms: r.elapsed.as_millis() as u64,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::power::{PowerPolicy, PowerSource};
+
+    #[test]
+    fn test_server_info_reports_this_binary() {
+        let power = PowerState::new(PowerPolicy::Auto, PowerSource::Battery);
+        let disk = DiskUsage {
+            free_bytes: Some(300 << 20),
+            db_bytes: 4096,
+            log_bytes: 0,
+            low: true,
+        };
+        let recovery = RecoveryReport {
+            interrupted: 2,
+            unreviewed: 5,
+            failed: 0,
+            elapsed: std::time::Duration::from_millis(30),
+        };
+        let info = server_info(42, power, disk, None, Some(recovery));
+        assert_eq!(info.version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(info.pid, std::process::id());
+        assert_eq!(info.started_at, 42);
+        assert_eq!(info.power_source, "battery");
+        assert!(info.low_power);
+        assert_eq!(info.disk_free_bytes, Some(300 << 20));
+        assert_eq!(info.db_size_bytes, 4096);
+        assert!(info.disk_low);
+        assert!(info.engine_missing);
+        assert_eq!(info.engine_path, "");
+        let recovery = info.recovery.unwrap();
+        assert_eq!((recovery.interrupted, recovery.unreviewed), (2, 5));
+        assert_eq!(recovery.elapsed_ms, 30);
+    }
+}
@@ -1,8 +1,9 @@
 //! Session management endpoints
 
 use crate::persistence::Persistence;
-use crate::service::converters::{convert_snapshot_to_proto, parse_game_mode_from_proto};
-use crate::session::SessionManager;
+use crate::service::converters::{convert_snapshot_to_proto, convert_uci_entry_to_proto};
+use crate::session::transfer::TRANSFER_CODE_TTL;
+use crate::session::{SessionLabels, SessionManager};
 use ::chess::GameMode;
 use chess_proto::*;
 use std::sync::Arc;
@@ -22,18 +23,32 @@ impl<D: Persistence> SessionEndpoints<D> {
         request: Request<CreateSessionRequest>,
     ) -> Result<Response<chess_proto::SessionSnapshot>, Status> {
         let req = request.into_inner();
-        tracing::info!(fen = ?req.fen, game_mode = ?req.game_mode, "RPC create_session");
+        tracing::info!(
+            fen = ?req.fen,
+            game_mode = ?req.game_mode,
+            simul_id = ?req.simul_id,
+            opponent = ?req.opponent,
+            "RPC create_session"
+        );
 
         // Parse game_mode from the request, defaulting to HumanVsHuman
         let game_mode = req
             .game_mode
             .as_ref()
-            .map(parse_game_mode_from_proto)
-            .unwrap_or(GameMode::HumanVsHuman);
+            .map(GameMode::from)
+            .unwrap_or_default();
 
         let snapshot = self
             .session_manager
-            .create_session(req.fen, game_mode)
+            .create_labeled_session(
+                req.fen,
+                game_mode,
+                SessionLabels {
+                    simul_id: req.simul_id,
+                    opponent: req.opponent,
+                    profile: req.profile,
+                },
+            )
             .await
             .map_err(Status::invalid_argument)?;
 
@@ -42,19 +57,14 @@ impl<D: Persistence> SessionEndpoints<D> {
             let handle = self
                 .session_manager
                 .get_handle(&snapshot.session_id)
-                .await
-                .map_err(Status::internal)?;
+                .await?;
 
             handle
                 .set_timer(timer.white_remaining_ms, timer.black_remaining_ms)
-                .await
-                .map_err(|e| Status::internal(e.to_string()))?;
+                .await?;
 
             // Re-fetch snapshot after timer is set
-            let updated = handle
-                .get_snapshot()
-                .await
-                .map_err(|e| Status::internal(e.to_string()))?;
+            let updated = handle.get_snapshot().await?;
 
             return Ok(Response::new(convert_snapshot_to_proto(updated)));
         }
@@ -62,6 +72,76 @@ impl<D: Persistence> SessionEndpoints<D> {
         Ok(Response::new(convert_snapshot_to_proto(snapshot)))
     }
 
+    pub async fn set_tactical_hints(
+        &self,
+        request: Request<SetTacticalHintsRequest>,
+    ) -> Result<Response<Empty>, Status> {
+        let req = request.into_inner();
+        tracing::info!(session_id = %req.session_id, enabled = req.enabled, "RPC set_tactical_hints");
+
+        let handle = self.session_manager.get_handle(&req.session_id).await?;
+
+        handle.set_tactical_hints(req.enabled).await?;
+
+        Ok(Response::new(Empty {}))
+    }
+
+    pub async fn set_game_mode(
+        &self,
+        request: Request<SetGameModeRequest>,
+    ) -> Result<Response<chess_proto::SessionSnapshot>, Status> {
+        let req = request.into_inner();
+        tracing::info!(session_id = %req.session_id, game_mode = ?req.game_mode, "RPC set_game_mode");
+
+        let game_mode = req
+            .game_mode
+            .as_ref()
+            .map(GameMode::from)
+            .ok_or_else(|| Status::invalid_argument("Game mode is required"))?;
+
+        let handle = self.session_manager.get_handle(&req.session_id).await?;
+        let snapshot = handle.set_game_mode(game_mode).await?;
+
+        Ok(Response::new(convert_snapshot_to_proto(snapshot)))
+    }
+
+    pub async fn list_active_sessions(
+        &self,
+        _request: Request<ListActiveSessionsRequest>,
+    ) -> Result<Response<ListActiveSessionsResponse>, Status> {
+        tracing::debug!("RPC list_active_sessions");
+
+        let sessions = self
+            .session_manager
+            .list_active_sessions()
+            .await
+            .into_iter()
+            .map(|s| ActiveSessionInfo {
+                session_id: s.session_id,
+                game_mode: Some(GameModeProto::from(&s.game_mode)),
+                move_count: s.move_count as u32,
+                age_secs: s.age.as_secs(),
+                engine_running: s.engine_running,
+                threads: s.usage.threads,
+                hash_mb: s.usage.hash_mb,
+                searches: s.usage.searches,
+                nodes: s.usage.nodes,
+                cpu_time_ms: s.usage.cpu_time_ms,
+            })
+            .collect();
+
+        let budget = self.session_manager.engine_budget();
+        let (engines_running, total_hash_mb) = budget.totals();
+        let limits = budget.limits();
+        Ok(Response::new(ListActiveSessionsResponse {
+            sessions,
+            engines_running,
+            total_hash_mb,
+            max_engine_sessions: limits.max_engine_sessions,
+            max_total_hash_mb: limits.max_total_hash_mb,
+        }))
+    }
+
     pub async fn get_session(
         &self,
         request: Request<GetSessionRequest>,
@@ -69,17 +149,73 @@ impl<D: Persistence> SessionEndpoints<D> {
         let req = request.into_inner();
         tracing::debug!(session_id = %req.session_id, "RPC get_session");
 
-        let handle = self
+        let handle = self.session_manager.get_handle(&req.session_id).await?;
+
+        let snapshot = handle.get_snapshot().await?;
+
+        Ok(Response::new(convert_snapshot_to_proto(snapshot)))
+    }
+
+    pub async fn create_session_transfer(
+        &self,
+        request: Request<CreateSessionTransferRequest>,
+    ) -> Result<Response<CreateSessionTransferResponse>, Status> {
+        let req = request.into_inner();
+        tracing::info!(session_id = %req.session_id, "RPC create_session_transfer");
+
+        let code = self
             .session_manager
-            .get_handle(&req.session_id)
-            .await
-            .map_err(Status::not_found)?;
+            .create_transfer(&req.session_id)
+            .await?;
 
-        let snapshot = handle
-            .get_snapshot()
-            .await
-            .map_err(|e| Status::internal(e.to_string()))?;
+        Ok(Response::new(CreateSessionTransferResponse {
+            code,
+            expires_in_secs: TRANSFER_CODE_TTL.as_secs(),
+        }))
+    }
+
+    /// Take over the session a transfer code was issued for. The client that
+    /// had it sees its event stream end; the session carries on.
+    pub async fn claim_session_transfer(
+        &self,
+        request: Request<ClaimSessionTransferRequest>,
+    ) -> Result<Response<chess_proto::SessionSnapshot>, Status> {
+        tracing::info!("RPC claim_session_transfer");
+
+        let snapshot = self
+            .session_manager
+            .claim_transfer(&request.into_inner().code)
+            .await?;
 
         Ok(Response::new(convert_snapshot_to_proto(snapshot)))
     }
+
+    /// State and UCI transcript of an open or recently closed session, for
+    /// `chesstty report`.
+    pub async fn get_debug_report(
+        &self,
+        request: Request<GetDebugReportRequest>,
+    ) -> Result<Response<GetDebugReportResponse>, Status> {
+        let req = request.into_inner();
+        tracing::info!(id = %req.id, "RPC get_debug_report");
+
+        let report = self
+            .session_manager
+            .debug_report(&req.id)
+            .await
+            .ok_or_else(|| {
+                Status::not_found(format!("No open or recently closed session: {}", req.id))
+            })?;
+        let session_id = report.snapshot.session_id.clone();
+
+        Ok(Response::new(GetDebugReportResponse {
+            session: Some(convert_snapshot_to_proto(report.snapshot)),
+            game_id: report.game_id,
+            uci_transcript: report
+                .uci_transcript
+                .into_iter()
+                .map(|entry| convert_uci_entry_to_proto(entry, session_id.clone()))
+                .collect(),
+        }))
+    }
 }
@@ -0,0 +1,484 @@
+//! Study endpoints: building, browsing and exporting studies, and writing
+//! their chapters
+
+use super::review::convert_score_to_proto;
+use crate::persistence::{
+    Arrow, ArrowColor, ChapterNode, Persistence, StudyChapter, StudyData, StudyFilter, StudyNode,
+};
+use crate::review::outcome::HumanOutcome;
+use crate::study::chapter::{Annotation, ChapterError};
+use crate::study::{ChapterSource, StudyError, StudyManager};
+use chess_proto::*;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+pub struct StudyEndpoints<D: Persistence> {
+    study_manager: Arc<StudyManager<D>>,
+}
+
+impl<D: Persistence> StudyEndpoints<D> {
+    pub fn new(study_manager: Arc<StudyManager<D>>) -> Self {
+        Self { study_manager }
+    }
+
+    pub async fn create_study(
+        &self,
+        request: Request<CreateStudyRequest>,
+    ) -> Result<Response<CreateStudyResponse>, Status> {
+        let req = request.into_inner();
+        tracing::info!(name = %req.name, "RPC create_study");
+
+        let name = req.name.trim();
+        if name.is_empty() {
+            return Err(Status::invalid_argument("name must not be empty"));
+        }
+        let study = if req.blank {
+            self.study_manager
+                .create_blank(name)
+                .await
+                .map_err(Status::internal)?
+        } else {
+            let filter = convert_filter_from_proto(req.filter.unwrap_or_default())?;
+            self.study_manager
+                .create(name, filter)
+                .await
+                .map_err(Status::not_found)?
+        };
+
+        Ok(Response::new(CreateStudyResponse {
+            study: Some(convert_study_info(&study)),
+        }))
+    }
+
+    pub async fn list_studies(
+        &self,
+        _request: Request<ListStudiesRequest>,
+    ) -> Result<Response<ListStudiesResponse>, Status> {
+        tracing::info!("RPC list_studies");
+
+        let studies = self.study_manager.list().await.map_err(Status::internal)?;
+
+        Ok(Response::new(ListStudiesResponse {
+            studies: studies.iter().map(convert_study_info).collect(),
+        }))
+    }
+
+    pub async fn get_study(
+        &self,
+        request: Request<GetStudyRequest>,
+    ) -> Result<Response<GetStudyResponse>, Status> {
+        let study_id = &request.get_ref().study_id;
+        tracing::info!(study_id = %study_id, "RPC get_study");
+
+        let study = self
+            .study_manager
+            .get(study_id)
+            .await
+            .map_err(Status::internal)?
+            .ok_or_else(|| Status::not_found(format!("Study not found: {}", study_id)))?;
+
+        Ok(Response::new(GetStudyResponse {
+            study: Some(convert_study_info(&study)),
+            root: Some(convert_node_to_proto(&study.root)),
+            chapters: study
+                .chapters
+                .iter()
+                .map(convert_chapter_to_proto)
+                .collect(),
+            game_ids: study.game_ids,
+        }))
+    }
+
+    pub async fn delete_study(
+        &self,
+        request: Request<DeleteStudyRequest>,
+    ) -> Result<Response<Empty>, Status> {
+        let study_id = &request.get_ref().study_id;
+        tracing::info!(study_id = %study_id, "RPC delete_study");
+
+        self.study_manager
+            .delete(study_id)
+            .await
+            .map_err(Status::internal)?;
+
+        Ok(Response::new(Empty {}))
+    }
+
+    pub async fn export_study_pgn(
+        &self,
+        request: Request<ExportStudyPgnRequest>,
+    ) -> Result<Response<ExportStudyPgnResponse>, Status> {
+        let study_id = &request.get_ref().study_id;
+        tracing::info!(study_id = %study_id, "RPC export_study_pgn");
+
+        let pgn = self
+            .study_manager
+            .export_pgn(study_id)
+            .await
+            .map_err(Status::internal)?
+            .ok_or_else(|| Status::not_found(format!("Study not found: {}", study_id)))?;
+
+        Ok(Response::new(ExportStudyPgnResponse { pgn }))
+    }
+
+    pub async fn add_study_chapter(
+        &self,
+        request: Request<AddStudyChapterRequest>,
+    ) -> Result<Response<StudyChapterResponse>, Status> {
+        let req = request.into_inner();
+        tracing::info!(study_id = %req.study_id, name = %req.name, "RPC add_study_chapter");
+
+        let name = req.name.trim();
+        if name.is_empty() {
+            return Err(Status::invalid_argument("name must not be empty"));
+        }
+        let source = match (req.fen, req.game_id) {
+            (Some(_), Some(_)) => {
+                return Err(Status::invalid_argument(
+                    "a chapter starts from a FEN or a game, not both",
+                ))
+            }
+            (Some(fen), None) => ChapterSource::Position(fen),
+            (None, Some(game_id)) => ChapterSource::Game(game_id),
+            (None, None) => ChapterSource::Position(chess::Game::new().to_fen()),
+        };
+        let chapter = self
+            .study_manager
+            .add_chapter(&req.study_id, name, source)
+            .await
+            .map_err(convert_study_error)?;
+
+        Ok(Response::new(StudyChapterResponse {
+            chapter: Some(convert_chapter_to_proto(&chapter)),
+        }))
+    }
+
+    pub async fn delete_study_chapter(
+        &self,
+        request: Request<DeleteStudyChapterRequest>,
+    ) -> Result<Response<Empty>, Status> {
+        let req = request.into_inner();
+        tracing::info!(study_id = %req.study_id, chapter_id = %req.chapter_id, "RPC delete_study_chapter");
+
+        self.study_manager
+            .delete_chapter(&req.study_id, &req.chapter_id)
+            .await
+            .map_err(convert_study_error)?;
+
+        Ok(Response::new(Empty {}))
+    }
+
+    pub async fn move_study_chapter(
+        &self,
+        request: Request<MoveStudyChapterRequest>,
+    ) -> Result<Response<Empty>, Status> {
+        let req = request.into_inner();
+        tracing::info!(
+            study_id = %req.study_id,
+            chapter_id = %req.chapter_id,
+            index = req.index,
+            "RPC move_study_chapter"
+        );
+
+        self.study_manager
+            .move_chapter(&req.study_id, &req.chapter_id, req.index as usize)
+            .await
+            .map_err(convert_study_error)?;
+
+        Ok(Response::new(Empty {}))
+    }
+
+    pub async fn add_chapter_move(
+        &self,
+        request: Request<AddChapterMoveRequest>,
+    ) -> Result<Response<AddChapterMoveResponse>, Status> {
+        let req = request.into_inner();
+        tracing::info!(
+            study_id = %req.study_id,
+            chapter_id = %req.chapter_id,
+            node_id = req.node_id,
+            notation = %req.notation,
+            "RPC add_chapter_move"
+        );
+
+        let (chapter, node_id) = self
+            .study_manager
+            .edit_chapter(&req.study_id, &req.chapter_id, |chapter| {
+                chapter.add_move(req.node_id, &req.notation)
+            })
+            .await
+            .map_err(convert_study_error)?;
+
+        Ok(Response::new(AddChapterMoveResponse {
+            chapter: Some(convert_chapter_to_proto(&chapter)),
+            node_id,
+        }))
+    }
+
+    pub async fn delete_chapter_move(
+        &self,
+        request: Request<DeleteChapterMoveRequest>,
+    ) -> Result<Response<DeleteChapterMoveResponse>, Status> {
+        let req = request.into_inner();
+        tracing::info!(
+            study_id = %req.study_id,
+            chapter_id = %req.chapter_id,
+            node_id = req.node_id,
+            "RPC delete_chapter_move"
+        );
+
+        let (chapter, parent_id) = self
+            .study_manager
+            .edit_chapter(&req.study_id, &req.chapter_id, |chapter| {
+                chapter.delete_node(req.node_id)
+            })
+            .await
+            .map_err(convert_study_error)?;
+
+        Ok(Response::new(DeleteChapterMoveResponse {
+            chapter: Some(convert_chapter_to_proto(&chapter)),
+            parent_id,
+        }))
+    }
+
+    pub async fn annotate_chapter_node(
+        &self,
+        request: Request<AnnotateChapterNodeRequest>,
+    ) -> Result<Response<StudyChapterResponse>, Status> {
+        let req = request.into_inner();
+        tracing::info!(
+            study_id = %req.study_id,
+            chapter_id = %req.chapter_id,
+            node_id = req.node_id,
+            "RPC annotate_chapter_node"
+        );
+
+        let annotation = Annotation {
+            comment: req.comment,
+            nags: req.nags,
+            arrows: req
+                .arrows
+                .into_iter()
+                .map(convert_arrow_from_proto)
+                .collect::<Result<_, _>>()?,
+        };
+        let (chapter, ()) = self
+            .study_manager
+            .edit_chapter(&req.study_id, &req.chapter_id, |chapter| {
+                chapter.annotate(req.node_id, annotation)
+            })
+            .await
+            .map_err(convert_study_error)?;
+
+        Ok(Response::new(StudyChapterResponse {
+            chapter: Some(convert_chapter_to_proto(&chapter)),
+        }))
+    }
+}
+
+fn convert_study_error(e: StudyError) -> Status {
+    match e {
+        StudyError::StudyNotFound(_)
+        | StudyError::ChapterNotFound(_)
+        | StudyError::GameNotFound(_)
+        | StudyError::Chapter(ChapterError::NoSuchNode(_)) => Status::not_found(e.to_string()),
+        StudyError::Chapter(ChapterError::TooManyNodes | ChapterError::TooManyChapters) => {
+            Status::resource_exhausted(e.to_string())
+        }
+        StudyError::Chapter(_) => Status::invalid_argument(e.to_string()),
+        StudyError::Storage(_) => Status::internal(e.to_string()),
+    }
+}
+
+fn convert_filter_from_proto(filter: StudyFilterProto) -> Result<StudyFilter, Status> {
+    let outcome = match StudyOutcomeType::try_from(filter.outcome) {
+        Ok(StudyOutcomeType::StudyOutcomeAny) => None,
+        Ok(StudyOutcomeType::StudyOutcomeWin) => Some(HumanOutcome::Win),
+        Ok(StudyOutcomeType::StudyOutcomeDraw) => Some(HumanOutcome::Draw),
+        Ok(StudyOutcomeType::StudyOutcomeLoss) => Some(HumanOutcome::Loss),
+        Err(_) => return Err(Status::invalid_argument("Unknown study outcome")),
+    };
+    let human_side = match filter.human_side.map(PlayerSideProto::try_from) {
+        None => None,
+        Some(Ok(PlayerSideProto::White)) => Some("white".to_string()),
+        Some(Ok(PlayerSideProto::Black)) => Some("black".to_string()),
+        Some(Err(_)) => return Err(Status::invalid_argument("Unknown player side")),
+    };
+    let non_empty = |s: Option<String>| s.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+    Ok(StudyFilter {
+        opening: non_empty(filter.opening),
+        outcome,
+        opponent: non_empty(filter.opponent),
+        human_side,
+    })
+}
+
+fn convert_filter_to_proto(filter: &StudyFilter) -> StudyFilterProto {
+    let outcome = match filter.outcome {
+        None => StudyOutcomeType::StudyOutcomeAny,
+        Some(HumanOutcome::Win) => StudyOutcomeType::StudyOutcomeWin,
+        Some(HumanOutcome::Draw) => StudyOutcomeType::StudyOutcomeDraw,
+        Some(HumanOutcome::Loss) => StudyOutcomeType::StudyOutcomeLoss,
+    };
+    let human_side = filter.human_side.as_deref().map(|side| match side {
+        "black" => PlayerSideProto::Black as i32,
+        _ => PlayerSideProto::White as i32,
+    });
+    StudyFilterProto {
+        opening: filter.opening.clone(),
+        outcome: outcome as i32,
+        opponent: filter.opponent.clone(),
+        human_side,
+    }
+}
+
+fn convert_study_info(study: &StudyData) -> StudyInfo {
+    StudyInfo {
+        study_id: study.study_id.clone(),
+        name: study.name.clone(),
+        filter: Some(convert_filter_to_proto(&study.filter)),
+        games: study.root.games,
+        white_wins: study.root.white_wins,
+        draws: study.root.draws,
+        black_wins: study.root.black_wins,
+        created_at: study.created_at,
+        chapters: study.chapters.len() as u32,
+    }
+}
+
+fn convert_arrow_from_proto(arrow: ChapterArrow) -> Result<Arrow, Status> {
+    let color = match ArrowColorType::try_from(arrow.color) {
+        Ok(ArrowColorType::ArrowColorGreen) => ArrowColor::Green,
+        Ok(ArrowColorType::ArrowColorRed) => ArrowColor::Red,
+        Ok(ArrowColorType::ArrowColorYellow) => ArrowColor::Yellow,
+        Ok(ArrowColorType::ArrowColorBlue) => ArrowColor::Blue,
+        Err(_) => return Err(Status::invalid_argument("Unknown arrow color")),
+    };
+    Ok(Arrow {
+        from: arrow.from,
+        to: arrow.to,
+        color,
+    })
+}
+
+fn convert_arrow_to_proto(arrow: &Arrow) -> ChapterArrow {
+    let color = match arrow.color {
+        ArrowColor::Green => ArrowColorType::ArrowColorGreen,
+        ArrowColor::Red => ArrowColorType::ArrowColorRed,
+        ArrowColor::Yellow => ArrowColorType::ArrowColorYellow,
+        ArrowColor::Blue => ArrowColorType::ArrowColorBlue,
+    };
+    ChapterArrow {
+        from: arrow.from.clone(),
+        to: arrow.to.clone(),
+        color: color as i32,
+    }
+}
+
+fn convert_chapter_node_to_proto(node: &ChapterNode) -> ChapterNodeProto {
+    ChapterNodeProto {
+        id: node.id,
+        parent: node.parent,
+        san: node.san.clone(),
+        uci: node.uci.clone(),
+        fen: node.fen.clone(),
+        comment: node.comment.clone(),
+        nags: node.nags.iter().map(|&n| u32::from(n)).collect(),
+        arrows: node.arrows.iter().map(convert_arrow_to_proto).collect(),
+        children: node.children.clone(),
+    }
+}
+
+fn convert_chapter_to_proto(chapter: &StudyChapter) -> StudyChapterProto {
+    StudyChapterProto {
+        chapter_id: chapter.chapter_id.clone(),
+        name: chapter.name.clone(),
+        game_id: chapter.game_id.clone(),
+        nodes: chapter
+            .nodes
+            .iter()
+            .map(convert_chapter_node_to_proto)
+            .collect(),
+    }
+}
+
+fn convert_node_to_proto(node: &StudyNode) -> StudyNodeProto {
+    StudyNodeProto {
+        san: node.san.clone(),
+        fen: node.fen.clone(),
+        games: node.games,
+        white_wins: node.white_wins,
+        draws: node.draws,
+        black_wins: node.black_wins,
+        reviewed: node.reviewed,
+        average_cp_loss: node.average_cp_loss,
+        inaccuracies: node.inaccuracies,
+        mistakes: node.mistakes,
+        blunders: node.blunders,
+        best_san: node.best_san.clone(),
+        eval: node.eval.as_ref().map(convert_score_to_proto),
+        children: node.children.iter().map(convert_node_to_proto).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_round_trip() {
+        let proto = StudyFilterProto {
+            opening: Some(" Sicilian ".to_string()),
+            outcome: StudyOutcomeType::StudyOutcomeLoss as i32,
+            opponent: Some(String::new()),
+            human_side: Some(PlayerSideProto::Black as i32),
+        };
+        let filter = convert_filter_from_proto(proto).unwrap();
+        assert_eq!(
+            filter,
+            StudyFilter {
+                opening: Some("Sicilian".to_string()),
+                outcome: Some(HumanOutcome::Loss),
+                opponent: None,
+                human_side: Some("black".to_string()),
+            }
+        );
+        let back = convert_filter_to_proto(&filter);
+        assert_eq!(back.opening.as_deref(), Some("Sicilian"));
+        assert_eq!(back.outcome, StudyOutcomeType::StudyOutcomeLoss as i32);
+        assert_eq!(back.human_side, Some(PlayerSideProto::Black as i32));
+
+        let unknown = StudyFilterProto {
+            outcome: 42,
+            ..Default::default()
+        };
+        assert!(convert_filter_from_proto(unknown).is_err());
+    }
+
+    #[test]
+    fn test_arrow_and_error_conversion() {
+        let proto = ChapterArrow {
+            from: "e2".to_string(),
+            to: "e4".to_string(),
+            color: ArrowColorType::ArrowColorBlue as i32,
+        };
+        let arrow = convert_arrow_from_proto(proto.clone()).unwrap();
+        assert_eq!(arrow.color, ArrowColor::Blue);
+        assert_eq!(convert_arrow_to_proto(&arrow), proto);
+        assert!(convert_arrow_from_proto(ChapterArrow { color: 9, ..proto }).is_err());
+
+        let code = |e| convert_study_error(e).code();
+        assert_eq!(
+            code(StudyError::Chapter(ChapterError::NoSuchNode(3))),
+            tonic::Code::NotFound
+        );
+        assert_eq!(
+            code(StudyError::Chapter(ChapterError::IllegalMove("e5".into()))),
+            tonic::Code::InvalidArgument
+        );
+        assert_eq!(
+            code(StudyError::Chapter(ChapterError::TooManyChapters)),
+            tonic::Code::ResourceExhausted
+        );
+    }
+}
@@ -0,0 +1,53 @@
+//! Server-to-server sync endpoints
+
+use crate::persistence::Persistence;
+use crate::sync::peer::GrpcPeer;
+use crate::sync::SyncManager;
+use chess_proto::*;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+pub struct SyncEndpoints<D: Persistence> {
+    sync_manager: Arc<SyncManager<D>>,
+    token: Option<String>,
+}
+
+impl<D: Persistence> SyncEndpoints<D> {
+    pub fn new(sync_manager: Arc<SyncManager<D>>, token: Option<String>) -> Self {
+        Self {
+            sync_manager,
+            token,
+        }
+    }
+
+    pub async fn sync_with_peer(
+        &self,
+        request: Request<SyncWithPeerRequest>,
+    ) -> Result<Response<SyncWithPeerResponse>, Status> {
+        let peer = request.get_ref().peer.trim();
+        tracing::info!(peer = %peer, "RPC sync_with_peer");
+
+        if peer.is_empty() {
+            return Err(Status::invalid_argument("peer must not be empty"));
+        }
+        let Some(token) = self.token.as_deref() else {
+            return Err(Status::failed_precondition(
+                "Set CHESSTTY_SYNC_TOKEN on both servers to sync",
+            ));
+        };
+
+        let peer = GrpcPeer::new(peer, token).map_err(Status::invalid_argument)?;
+        let (peer_node_id, report) = self
+            .sync_manager
+            .sync_with(&peer)
+            .await
+            .map_err(Status::unavailable)?;
+
+        Ok(Response::new(SyncWithPeerResponse {
+            peer_node_id,
+            received: report.received,
+            sent: report.sent,
+            conflicts: report.conflicts,
+        }))
+    }
+}
@@ -4,14 +4,24 @@
 //! - converters: Domain model <-> Proto conversions
 //! - parsers: Proto -> Domain model parsing
 //! - endpoints: Individual endpoint handlers organized by domain
+//! - sync: The separate SyncService answering sync peers over TCP
 
 mod converters;
 mod endpoints;
 mod parsers;
-
-use crate::persistence::Persistence;
-use crate::review::ReviewManager;
-use crate::session::SessionManager;
+mod sync;
+
+pub use sync::{require_sync_token, SyncServiceImpl};
+
+use crate::audit::{client_identity, AuditLog};
+use crate::disk::DiskGuard;
+use crate::engine_pool::EnginePool;
+use crate::persistence::{AuditAction, Persistence};
+use crate::power::PowerState;
+use crate::review::{RecoveryReport, ReviewManager};
+use crate::session::{ForcedClose, SessionManager, FORCE_CLOSE_TIMEOUT};
+use crate::study::StudyManager;
+use crate::sync::SyncManager;
 use chess_proto::chess_service_server::ChessService;
 use chess_proto::*;
 use endpoints::*;
@@ -26,6 +36,8 @@ use tonic::{Request, Response, Status};
 pub struct ChessServiceImpl<D: Persistence> {
     session_manager: Arc<SessionManager<D>>,
     review_manager: Arc<ReviewManager<D>>,
+    audit_log: Arc<AuditLog<D>>,
+    server_endpoints: ServerEndpoints,
     session_endpoints: SessionEndpoints<D>,
     game_endpoints: GameEndpoints<D>,
     engine_endpoints: EngineEndpoints<D>,
@@ -33,29 +45,63 @@ pub struct ChessServiceImpl<D: Persistence> {
     persistence_endpoints: PersistenceEndpoints<D>,
     positions_endpoints: PositionsEndpoints<D>,
     review_endpoints: ReviewEndpoints<D>,
+    activity_endpoints: ActivityEndpoints<D>,
+    goal_endpoints: GoalEndpoints<D>,
+    audit_endpoints: AuditEndpoints<D>,
+    sync_endpoints: SyncEndpoints<D>,
+    study_endpoints: StudyEndpoints<D>,
+    analysis_endpoints: AnalysisEndpoints,
 }
 
 impl<D: Persistence> ChessServiceImpl<D> {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         session_manager: Arc<SessionManager<D>>,
         review_manager: Arc<ReviewManager<D>>,
+        sync_manager: Arc<SyncManager<D>>,
+        audit_log: Arc<AuditLog<D>>,
+        study_manager: Arc<StudyManager<D>>,
+        engine_pool: Arc<EnginePool>,
+        power: tokio::sync::watch::Receiver<PowerState>,
+        disk: Arc<DiskGuard>,
+        engine_path: tokio::sync::watch::Receiver<Option<std::path::PathBuf>>,
+        recovery: tokio::sync::watch::Receiver<Option<RecoveryReport>>,
     ) -> Self {
         Self {
+            server_endpoints: ServerEndpoints::new(power, disk.clone(), engine_path, recovery),
             session_endpoints: SessionEndpoints::new(session_manager.clone()),
             game_endpoints: GameEndpoints::new(session_manager.clone()),
             engine_endpoints: EngineEndpoints::new(session_manager.clone()),
             events_endpoints: EventsEndpoints::new(session_manager.clone()),
             persistence_endpoints: PersistenceEndpoints::new(session_manager.clone()),
             positions_endpoints: PositionsEndpoints::new(session_manager.clone()),
-            review_endpoints: ReviewEndpoints::new(review_manager.clone()),
+            review_endpoints: ReviewEndpoints::new(review_manager.clone(), audit_log.clone(), disk),
+            activity_endpoints: ActivityEndpoints::new(session_manager.clone()),
+            goal_endpoints: GoalEndpoints::new(session_manager.clone(), review_manager.clone()),
+            audit_endpoints: AuditEndpoints::new(audit_log.clone()),
+            sync_endpoints: SyncEndpoints::new(sync_manager, crate::config::get_sync_token()),
+            study_endpoints: StudyEndpoints::new(study_manager),
+            analysis_endpoints: AnalysisEndpoints::new(engine_pool),
             session_manager,
             review_manager,
+            audit_log,
         }
     }
 }
 
 #[tonic::async_trait]
 impl<D: Persistence> ChessService for ChessServiceImpl<D> {
+    // =========================================================================
+    // Server Endpoint
+    // =========================================================================
+
+    async fn get_server_info(
+        &self,
+        request: Request<GetServerInfoRequest>,
+    ) -> Result<Response<GetServerInfoResponse>, Status> {
+        self.server_endpoints.get_server_info(request).await
+    }
+
     // =========================================================================
     // Session Management Endpoints
     // =========================================================================
@@ -74,6 +120,92 @@ impl<D: Persistence> ChessService for ChessServiceImpl<D> {
         self.session_endpoints.get_session(request).await
     }
 
+    async fn set_tactical_hints(
+        &self,
+        request: Request<SetTacticalHintsRequest>,
+    ) -> Result<Response<Empty>, Status> {
+        self.session_endpoints.set_tactical_hints(request).await
+    }
+
+    async fn set_game_mode(
+        &self,
+        request: Request<SetGameModeRequest>,
+    ) -> Result<Response<chess_proto::SessionSnapshot>, Status> {
+        self.session_endpoints.set_game_mode(request).await
+    }
+
+    async fn list_active_sessions(
+        &self,
+        request: Request<ListActiveSessionsRequest>,
+    ) -> Result<Response<ListActiveSessionsResponse>, Status> {
+        self.session_endpoints.list_active_sessions(request).await
+    }
+
+    async fn create_session_transfer(
+        &self,
+        request: Request<CreateSessionTransferRequest>,
+    ) -> Result<Response<CreateSessionTransferResponse>, Status> {
+        self.session_endpoints
+            .create_session_transfer(request)
+            .await
+    }
+
+    async fn claim_session_transfer(
+        &self,
+        request: Request<ClaimSessionTransferRequest>,
+    ) -> Result<Response<chess_proto::SessionSnapshot>, Status> {
+        self.session_endpoints.claim_session_transfer(request).await
+    }
+
+    async fn admin_close_session(
+        &self,
+        request: Request<AdminCloseSessionRequest>,
+    ) -> Result<Response<AdminCloseSessionResponse>, Status> {
+        let actor = client_identity(&request);
+        let req = request.into_inner();
+        tracing::info!(
+            session_id = %req.session_id,
+            suspend = req.suspend,
+            "RPC admin_close_session"
+        );
+
+        let closed = self
+            .session_manager
+            .force_close_session(&req.session_id, req.suspend, FORCE_CLOSE_TIMEOUT)
+            .await?;
+
+        let mut response = AdminCloseSessionResponse::default();
+        let detail = match closed {
+            ForcedClose::Closed { game_id } => {
+                if let Some(ref game_id) = game_id {
+                    if let Err(e) = self.review_manager.enqueue(game_id).await {
+                        tracing::warn!(game_id = %game_id, "Auto-enqueue for review failed: {}", e);
+                    }
+                }
+                response.game_id = game_id;
+                "closed".to_string()
+            }
+            ForcedClose::Suspended { suspended_id } => {
+                let detail = format!("suspended as {}", suspended_id);
+                response.suspended_id = Some(suspended_id);
+                detail
+            }
+            ForcedClose::Aborted => {
+                response.aborted = true;
+                "did not answer, aborted".to_string()
+            }
+        };
+        self.audit_log
+            .record(
+                &actor,
+                AuditAction::SessionClose,
+                format!("session {}", req.session_id),
+                detail,
+            )
+            .await;
+        Ok(Response::new(response))
+    }
+
     async fn close_session(
         &self,
         request: Request<CloseSessionRequest>,
@@ -144,7 +276,25 @@ impl<D: Persistence> ChessService for ChessServiceImpl<D> {
         &self,
         request: Request<SetEngineRequest>,
     ) -> Result<Response<Empty>, Status> {
-        self.engine_endpoints.set_engine(request).await
+        let actor = client_identity(&request);
+        let req = request.get_ref();
+        let target = format!("session {}", req.session_id);
+        let detail = if req.enabled {
+            format!(
+                "engine on, skill {}, threads {}, hash {}",
+                req.skill_level,
+                req.threads.map_or("default".to_string(), |t| t.to_string()),
+                req.hash_mb
+                    .map_or("default".to_string(), |mb| format!("{} MB", mb))
+            )
+        } else {
+            "engine off".to_string()
+        };
+        let response = self.engine_endpoints.set_engine(request).await?;
+        self.audit_log
+            .record(&actor, AuditAction::ConfigChange, target, detail)
+            .await;
+        Ok(response)
     }
 
     async fn stop_engine(
@@ -154,6 +304,50 @@ impl<D: Persistence> ChessService for ChessServiceImpl<D> {
         self.engine_endpoints.stop_engine(request).await
     }
 
+    async fn set_engine_move_delay(
+        &self,
+        request: Request<SetEngineMoveDelayRequest>,
+    ) -> Result<Response<Empty>, Status> {
+        let actor = client_identity(&request);
+        let target = format!("session {}", request.get_ref().session_id);
+        let detail = format!("engine move delay {} ms", request.get_ref().delay_ms);
+        let response = self.engine_endpoints.set_engine_move_delay(request).await?;
+        self.audit_log
+            .record(&actor, AuditAction::ConfigChange, target, detail)
+            .await;
+        Ok(response)
+    }
+
+    async fn set_step_mode(
+        &self,
+        request: Request<SetStepModeRequest>,
+    ) -> Result<Response<Empty>, Status> {
+        self.engine_endpoints.set_step_mode(request).await
+    }
+
+    async fn step_engine(
+        &self,
+        request: Request<StepEngineRequest>,
+    ) -> Result<Response<Empty>, Status> {
+        self.engine_endpoints.step_engine(request).await
+    }
+
+    async fn analyze_position(
+        &self,
+        request: Request<AnalyzePositionRequest>,
+    ) -> Result<Response<AnalyzePositionResponse>, Status> {
+        self.analysis_endpoints.analyze_position(request).await
+    }
+
+    type StreamAnalysisStream = Pin<Box<dyn Stream<Item = Result<AnalysisUpdate, Status>> + Send>>;
+
+    async fn stream_analysis(
+        &self,
+        request: Request<StreamAnalysisRequest>,
+    ) -> Result<Response<Self::StreamAnalysisStream>, Status> {
+        self.analysis_endpoints.stream_analysis(request).await
+    }
+
     // =========================================================================
     // Pause / Resume
     // =========================================================================
@@ -186,6 +380,29 @@ impl<D: Persistence> ChessService for ChessServiceImpl<D> {
         self.events_endpoints.stream_events(request).await
     }
 
+    async fn send_chat(
+        &self,
+        request: Request<SendChatRequest>,
+    ) -> Result<Response<ChatMessage>, Status> {
+        self.events_endpoints.send_chat(request).await
+    }
+
+    async fn get_chat_history(
+        &self,
+        request: Request<GetChatHistoryRequest>,
+    ) -> Result<Response<GetChatHistoryResponse>, Status> {
+        self.events_endpoints.get_chat_history(request).await
+    }
+
+    type AttachEngineTapStream = Pin<Box<dyn Stream<Item = Result<EngineTapEvent, Status>> + Send>>;
+
+    async fn attach_engine_tap(
+        &self,
+        request: Request<AttachEngineTapRequest>,
+    ) -> Result<Response<Self::AttachEngineTapStream>, Status> {
+        self.events_endpoints.attach_engine_tap(request).await
+    }
+
     // =========================================================================
     // Session Persistence Endpoints
     // =========================================================================
@@ -219,9 +436,16 @@ impl<D: Persistence> ChessService for ChessServiceImpl<D> {
         &self,
         request: Request<DeleteSuspendedSessionRequest>,
     ) -> Result<Response<Empty>, Status> {
-        self.persistence_endpoints
+        let actor = client_identity(&request);
+        let target = format!("suspended_session {}", request.get_ref().suspended_id);
+        let response = self
+            .persistence_endpoints
             .delete_suspended_session(request)
-            .await
+            .await?;
+        self.audit_log
+            .record(&actor, AuditAction::Delete, target, "")
+            .await;
+        Ok(response)
     }
 
     async fn save_snapshot(
@@ -231,6 +455,13 @@ impl<D: Persistence> ChessService for ChessServiceImpl<D> {
         self.persistence_endpoints.save_snapshot(request).await
     }
 
+    async fn quick_snapshot(
+        &self,
+        request: Request<QuickSnapshotRequest>,
+    ) -> Result<Response<QuickSnapshotResponse>, Status> {
+        self.persistence_endpoints.quick_snapshot(request).await
+    }
+
     // =========================================================================
     // Saved Positions Endpoints
     // =========================================================================
@@ -253,7 +484,13 @@ impl<D: Persistence> ChessService for ChessServiceImpl<D> {
         &self,
         request: Request<DeletePositionRequest>,
     ) -> Result<Response<Empty>, Status> {
-        self.positions_endpoints.delete_position(request).await
+        let actor = client_identity(&request);
+        let target = format!("position {}", request.get_ref().position_id);
+        let response = self.positions_endpoints.delete_position(request).await?;
+        self.audit_log
+            .record(&actor, AuditAction::Delete, target, "")
+            .await;
+        Ok(response)
     }
 
     // =========================================================================
@@ -274,6 +511,23 @@ impl<D: Persistence> ChessService for ChessServiceImpl<D> {
         self.review_endpoints.enqueue_review(request).await
     }
 
+    async fn cancel_review(
+        &self,
+        request: Request<CancelReviewRequest>,
+    ) -> Result<Response<CancelReviewResponse>, Status> {
+        self.review_endpoints.cancel_review(request).await
+    }
+
+    type StreamReviewProgressStream =
+        Pin<Box<dyn Stream<Item = Result<ReviewProgressUpdate, Status>> + Send>>;
+
+    async fn stream_review_progress(
+        &self,
+        request: Request<StreamReviewProgressRequest>,
+    ) -> Result<Response<Self::StreamReviewProgressStream>, Status> {
+        self.review_endpoints.stream_review_progress(request).await
+    }
+
     async fn get_review_status(
         &self,
         request: Request<GetReviewStatusRequest>,
@@ -285,7 +539,17 @@ impl<D: Persistence> ChessService for ChessServiceImpl<D> {
         &self,
         request: Request<GetGameReviewRequest>,
     ) -> Result<Response<GetGameReviewResponse>, Status> {
-        self.review_endpoints.get_game_review(request).await
+        let profile = request.get_ref().profile.clone();
+        let response = self.review_endpoints.get_game_review(request).await?;
+
+        // Reading a review counts towards the profile's activity
+        if let Some(profile) = profile {
+            self.session_manager
+                .record_activity(&profile, crate::persistence::ActivityKind::ReviewRead)
+                .await;
+        }
+
+        Ok(response)
     }
 
     async fn export_review_pgn(
@@ -299,7 +563,238 @@ impl<D: Persistence> ChessService for ChessServiceImpl<D> {
         &self,
         request: Request<DeleteFinishedGameRequest>,
     ) -> Result<Response<Empty>, Status> {
-        self.review_endpoints.delete_finished_game(request).await
+        let actor = client_identity(&request);
+        let target = format!("finished_game {}", request.get_ref().game_id);
+        let response = self.review_endpoints.delete_finished_game(request).await?;
+        self.audit_log
+            .record(&actor, AuditAction::Delete, target, "with its review")
+            .await;
+        Ok(response)
+    }
+
+    async fn import_pgn(
+        &self,
+        request: Request<ImportPgnRequest>,
+    ) -> Result<Response<ImportPgnResponse>, Status> {
+        let actor = client_identity(&request);
+        let response = self.review_endpoints.import_pgn(request).await?;
+        let result = response.get_ref();
+        if result.imported > 0 {
+            self.audit_log
+                .record(
+                    &actor,
+                    AuditAction::Import,
+                    "PGN upload",
+                    format!(
+                        "{} games, {} duplicates, {} skipped",
+                        result.imported,
+                        result.duplicates,
+                        result.errors.len()
+                    ),
+                )
+                .await;
+        }
+        Ok(response)
+    }
+
+    async fn get_simul_report(
+        &self,
+        request: Request<GetSimulReportRequest>,
+    ) -> Result<Response<GetSimulReportResponse>, Status> {
+        self.review_endpoints.get_simul_report(request).await
+    }
+
+    async fn get_opponent_stats(
+        &self,
+        request: Request<GetOpponentStatsRequest>,
+    ) -> Result<Response<GetOpponentStatsResponse>, Status> {
+        self.review_endpoints.get_opponent_stats(request).await
+    }
+
+    async fn find_similar_positions(
+        &self,
+        request: Request<FindSimilarPositionsRequest>,
+    ) -> Result<Response<FindSimilarPositionsResponse>, Status> {
+        self.review_endpoints.find_similar_positions(request).await
+    }
+
+    async fn simulate_clock(
+        &self,
+        request: Request<SimulateClockRequest>,
+    ) -> Result<Response<SimulateClockResponse>, Status> {
+        self.review_endpoints.simulate_clock(request).await
+    }
+
+    async fn reanalyze_position(
+        &self,
+        request: Request<ReanalyzePositionRequest>,
+    ) -> Result<Response<ReanalyzePositionResponse>, Status> {
+        self.review_endpoints.reanalyze_position(request).await
+    }
+
+    async fn compare_reviews(
+        &self,
+        request: Request<CompareReviewsRequest>,
+    ) -> Result<Response<CompareReviewsResponse>, Status> {
+        self.review_endpoints.compare_reviews(request).await
+    }
+
+    async fn set_review_bookmark(
+        &self,
+        request: Request<SetReviewBookmarkRequest>,
+    ) -> Result<Response<ReviewBookmark>, Status> {
+        self.review_endpoints.set_review_bookmark(request).await
+    }
+
+    async fn list_review_bookmarks(
+        &self,
+        request: Request<ListReviewBookmarksRequest>,
+    ) -> Result<Response<ListReviewBookmarksResponse>, Status> {
+        self.review_endpoints.list_review_bookmarks(request).await
+    }
+
+    async fn delete_review_bookmark(
+        &self,
+        request: Request<DeleteReviewBookmarkRequest>,
+    ) -> Result<Response<Empty>, Status> {
+        self.review_endpoints.delete_review_bookmark(request).await
+    }
+
+    async fn get_activity_summary(
+        &self,
+        request: Request<GetActivitySummaryRequest>,
+    ) -> Result<Response<GetActivitySummaryResponse>, Status> {
+        self.activity_endpoints.get_activity_summary(request).await
+    }
+
+    async fn get_training_digest(
+        &self,
+        request: Request<GetTrainingDigestRequest>,
+    ) -> Result<Response<GetTrainingDigestResponse>, Status> {
+        self.review_endpoints.get_training_digest(request).await
+    }
+
+    async fn add_goal(
+        &self,
+        request: Request<AddGoalRequest>,
+    ) -> Result<Response<AddGoalResponse>, Status> {
+        self.goal_endpoints.add_goal(request).await
+    }
+
+    async fn list_goals(
+        &self,
+        request: Request<ListGoalsRequest>,
+    ) -> Result<Response<ListGoalsResponse>, Status> {
+        self.goal_endpoints.list_goals(request).await
+    }
+
+    async fn delete_goal(
+        &self,
+        request: Request<DeleteGoalRequest>,
+    ) -> Result<Response<Empty>, Status> {
+        let actor = client_identity(&request);
+        let req = request.get_ref();
+        let target = format!("goal {}", req.goal_id);
+        let detail = format!("of profile {}", req.profile);
+        let response = self.goal_endpoints.delete_goal(request).await?;
+        self.audit_log
+            .record(&actor, AuditAction::Delete, target, detail)
+            .await;
+        Ok(response)
+    }
+
+    // =========================================================================
+    // Study Endpoints
+    // =========================================================================
+
+    async fn create_study(
+        &self,
+        request: Request<CreateStudyRequest>,
+    ) -> Result<Response<CreateStudyResponse>, Status> {
+        self.study_endpoints.create_study(request).await
+    }
+
+    async fn list_studies(
+        &self,
+        request: Request<ListStudiesRequest>,
+    ) -> Result<Response<ListStudiesResponse>, Status> {
+        self.study_endpoints.list_studies(request).await
+    }
+
+    async fn get_study(
+        &self,
+        request: Request<GetStudyRequest>,
+    ) -> Result<Response<GetStudyResponse>, Status> {
+        self.study_endpoints.get_study(request).await
+    }
+
+    async fn delete_study(
+        &self,
+        request: Request<DeleteStudyRequest>,
+    ) -> Result<Response<Empty>, Status> {
+        let actor = client_identity(&request);
+        let target = format!("study {}", request.get_ref().study_id);
+        let response = self.study_endpoints.delete_study(request).await?;
+        self.audit_log
+            .record(&actor, AuditAction::Delete, target, "")
+            .await;
+        Ok(response)
+    }
+
+    async fn export_study_pgn(
+        &self,
+        request: Request<ExportStudyPgnRequest>,
+    ) -> Result<Response<ExportStudyPgnResponse>, Status> {
+        self.study_endpoints.export_study_pgn(request).await
+    }
+
+    async fn add_study_chapter(
+        &self,
+        request: Request<AddStudyChapterRequest>,
+    ) -> Result<Response<StudyChapterResponse>, Status> {
+        self.study_endpoints.add_study_chapter(request).await
+    }
+
+    async fn delete_study_chapter(
+        &self,
+        request: Request<DeleteStudyChapterRequest>,
+    ) -> Result<Response<Empty>, Status> {
+        let actor = client_identity(&request);
+        let req = request.get_ref();
+        let target = format!("study {} chapter {}", req.study_id, req.chapter_id);
+        let response = self.study_endpoints.delete_study_chapter(request).await?;
+        self.audit_log
+            .record(&actor, AuditAction::Delete, target, "")
+            .await;
+        Ok(response)
+    }
+
+    async fn move_study_chapter(
+        &self,
+        request: Request<MoveStudyChapterRequest>,
+    ) -> Result<Response<Empty>, Status> {
+        self.study_endpoints.move_study_chapter(request).await
+    }
+
+    async fn add_chapter_move(
+        &self,
+        request: Request<AddChapterMoveRequest>,
+    ) -> Result<Response<AddChapterMoveResponse>, Status> {
+        self.study_endpoints.add_chapter_move(request).await
+    }
+
+    async fn delete_chapter_move(
+        &self,
+        request: Request<DeleteChapterMoveRequest>,
+    ) -> Result<Response<DeleteChapterMoveResponse>, Status> {
+        self.study_endpoints.delete_chapter_move(request).await
+    }
+
+    async fn annotate_chapter_node(
+        &self,
+        request: Request<AnnotateChapterNodeRequest>,
+    ) -> Result<Response<StudyChapterResponse>, Status> {
+        self.study_endpoints.annotate_chapter_node(request).await
     }
 
     // =========================================================================
@@ -312,4 +807,61 @@ impl<D: Persistence> ChessService for ChessServiceImpl<D> {
     ) -> Result<Response<GetAdvancedAnalysisResponse>, Status> {
         self.review_endpoints.get_advanced_analysis(request).await
     }
+
+    async fn explain_position(
+        &self,
+        request: Request<ExplainPositionRequest>,
+    ) -> Result<Response<ExplainPositionResponse>, Status> {
+        self.review_endpoints.explain_position(request).await
+    }
+
+    // =========================================================================
+    // Sync Endpoint
+    // =========================================================================
+
+    async fn sync_with_peer(
+        &self,
+        request: Request<SyncWithPeerRequest>,
+    ) -> Result<Response<SyncWithPeerResponse>, Status> {
+        let actor = client_identity(&request);
+        let target = format!("peer {}", request.get_ref().peer.trim());
+        let response = self.sync_endpoints.sync_with_peer(request).await?;
+        let report = response.get_ref();
+        if report.received > 0 {
+            self.audit_log
+                .record(
+                    &actor,
+                    AuditAction::Import,
+                    target,
+                    format!(
+                        "{} received, {} sent, {} conflicts",
+                        report.received, report.sent, report.conflicts
+                    ),
+                )
+                .await;
+        }
+        Ok(response)
+    }
+
+    // =========================================================================
+    // Audit Log Endpoint
+    // =========================================================================
+
+    async fn list_audit_entries(
+        &self,
+        request: Request<ListAuditEntriesRequest>,
+    ) -> Result<Response<ListAuditEntriesResponse>, Status> {
+        self.audit_endpoints.list_audit_entries(request).await
+    }
+
+    // =========================================================================
+    // Bug Report Endpoint
+    // =========================================================================
+
+    async fn get_debug_report(
+        &self,
+        request: Request<GetDebugReportRequest>,
+    ) -> Result<Response<GetDebugReportResponse>, Status> {
+        self.session_endpoints.get_debug_report(request).await
+    }
 }
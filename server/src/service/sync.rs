@@ -0,0 +1,191 @@
+//! gRPC service answering sync peers on the TCP sync listener.
+//!
+//! Kept apart from [`super::ChessServiceImpl`] so that only the sync
+//! exchange, and nothing else, is reachable over the network. Peers are
+//! checked by [`require_sync_token`] before their messages are decoded.
+
+use crate::audit::{client_identity, AuditLog};
+use crate::persistence::{AuditAction, Persistence};
+use crate::sync::peer::{
+    item_from_proto, item_to_proto, key_from_proto, key_to_proto, version_from_proto,
+    SYNC_TOKEN_HEADER,
+};
+use crate::sync::SyncManager;
+use chess_proto::sync_service_server::SyncService;
+use chess_proto::{
+    CompareSyncRequest, CompareSyncResponse, FetchSyncRequest, FetchSyncResponse, PushSyncRequest,
+    PushSyncResponse,
+};
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+pub struct SyncServiceImpl<D: Persistence> {
+    sync_manager: Arc<SyncManager<D>>,
+    audit_log: Arc<AuditLog<D>>,
+}
+
+impl<D: Persistence> SyncServiceImpl<D> {
+    pub fn new(sync_manager: Arc<SyncManager<D>>, audit_log: Arc<AuditLog<D>>) -> Self {
+        Self {
+            sync_manager,
+            audit_log,
+        }
+    }
+}
+
+/// Compare tokens without leaking the length of the matching prefix.
+fn tokens_match(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Interceptor turning away peers without the shared `token`. It sees only
+/// the request headers, so the message of a rejected peer is never read.
+pub fn require_sync_token(
+    token: String,
+) -> impl FnMut(Request<()>) -> Result<Request<()>, Status> + Clone {
+    let expected = format!("Bearer {}", token);
+    move |request: Request<()>| {
+        let given = request
+            .metadata()
+            .get(SYNC_TOKEN_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default();
+        if tokens_match(given, &expected) {
+            Ok(request)
+        } else {
+            tracing::warn!(peer = %client_identity(&request), "Rejected sync peer with a wrong token");
+            Err(Status::unauthenticated("Invalid sync token"))
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl<D: Persistence> SyncService for SyncServiceImpl<D> {
+    async fn compare_sync(
+        &self,
+        request: Request<CompareSyncRequest>,
+    ) -> Result<Response<CompareSyncResponse>, Status> {
+        let req = request.into_inner();
+        tracing::info!(peer = %req.node_id, versions = req.versions.len(), "RPC compare_sync");
+
+        let versions = req
+            .versions
+            .into_iter()
+            .map(version_from_proto)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(Status::invalid_argument)?;
+        let comparison = self
+            .sync_manager
+            .handle_compare(versions)
+            .await
+            .map_err(Status::internal)?;
+
+        Ok(Response::new(CompareSyncResponse {
+            node_id: comparison.node_id,
+            wanted: comparison.wanted.into_iter().map(key_to_proto).collect(),
+            offered: comparison.offered.into_iter().map(key_to_proto).collect(),
+        }))
+    }
+
+    async fn push_sync(
+        &self,
+        request: Request<PushSyncRequest>,
+    ) -> Result<Response<PushSyncResponse>, Status> {
+        let address = client_identity(&request);
+        let req = request.into_inner();
+        tracing::info!(peer = %req.node_id, items = req.items.len(), "RPC push_sync");
+
+        let items = req
+            .items
+            .into_iter()
+            .map(item_from_proto)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(Status::invalid_argument)?;
+        let counts = self
+            .sync_manager
+            .handle_push(items)
+            .await
+            .map_err(Status::internal)?;
+
+        if counts.applied > 0 {
+            self.audit_log
+                .record(
+                    &format!("{} ({})", req.node_id, address),
+                    AuditAction::Import,
+                    format!("peer {}", req.node_id),
+                    format!(
+                        "{} received, {} conflicts",
+                        counts.applied, counts.conflicts
+                    ),
+                )
+                .await;
+        }
+
+        Ok(Response::new(PushSyncResponse {
+            accepted: counts.applied,
+            conflicts: counts.conflicts,
+        }))
+    }
+
+    async fn fetch_sync(
+        &self,
+        request: Request<FetchSyncRequest>,
+    ) -> Result<Response<FetchSyncResponse>, Status> {
+        let req = request.into_inner();
+        tracing::info!(peer = %req.node_id, keys = req.keys.len(), "RPC fetch_sync");
+
+        let keys = req
+            .keys
+            .into_iter()
+            .map(key_from_proto)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(Status::invalid_argument)?;
+        let items = self
+            .sync_manager
+            .handle_fetch(keys)
+            .await
+            .map_err(Status::internal)?;
+
+        Ok(Response::new(FetchSyncResponse {
+            items: items.into_iter().map(item_to_proto).collect(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokens_match() {
+        assert!(tokens_match("s3cret", "s3cret"));
+        assert!(!tokens_match("s3cret", "s3cre"));
+        assert!(!tokens_match("s3crex", "s3cret"));
+        assert!(!tokens_match("", "s3cret"));
+    }
+
+    #[test]
+    fn test_require_sync_token_checks_metadata() {
+        let mut check = require_sync_token("s3cret".to_string());
+        let with_token = |value: &str| {
+            let mut request = Request::new(());
+            request
+                .metadata_mut()
+                .insert(SYNC_TOKEN_HEADER, value.parse().unwrap());
+            request
+        };
+
+        assert!(check(with_token("Bearer s3cret")).is_ok());
+        assert_eq!(
+            check(with_token("Bearer wrong")).unwrap_err().code(),
+            tonic::Code::Unauthenticated
+        );
+        assert!(check(with_token("s3cret")).is_err());
+        assert!(check(Request::new(())).is_err());
+    }
+}
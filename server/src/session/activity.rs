@@ -0,0 +1,86 @@
+//! Daily activity summaries for the Stats heat-map.
+
+use crate::persistence::ActivityDay;
+
+/// Days covered when the client does not ask for a window.
+pub const DEFAULT_WINDOW_DAYS: u32 = 365;
+
+/// A profile's activity over a window of days, with streaks.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActivitySummary {
+    /// Days with any activity, oldest first.
+    pub days: Vec<ActivityDay>,
+    pub today: u32,
+    /// Consecutive active days ending today, or yesterday if today is still empty.
+    pub current_streak: u32,
+    /// Longest run of consecutive active days within the window.
+    pub longest_streak: u32,
+}
+
+impl ActivitySummary {
+    pub fn new(mut days: Vec<ActivityDay>, today: u32) -> Self {
+        days.retain(|d| d.total() > 0);
+        days.sort_by_key(|d| d.day);
+
+        let mut longest_streak = 0;
+        let mut run = 0;
+        let mut previous: Option<u32> = None;
+        for d in &days {
+            run = match previous {
+                Some(p) if p + 1 == d.day => run + 1,
+                _ => 1,
+            };
+            longest_streak = longest_streak.max(run);
+            previous = Some(d.day);
+        }
+
+        // `run` is the streak ending at the last active day
+        let current_streak = match previous {
+            Some(last) if last + 1 >= today => run,
+            _ => 0,
+        };
+
+        Self {
+            days,
+            today,
+            current_streak,
+            longest_streak,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::ActivityKind;
+
+    fn active(day: u32) -> ActivityDay {
+        let mut d = ActivityDay::new(day);
+        d.add(ActivityKind::GamePlayed);
+        d
+    }
+
+    #[test]
+    fn test_streaks() {
+        let summary = ActivitySummary::new(
+            vec![active(1), active(2), active(3), active(7), active(8)],
+            9,
+        );
+        assert_eq!(summary.longest_streak, 3);
+        assert_eq!(summary.current_streak, 2);
+    }
+
+    #[test]
+    fn test_streak_broken_by_missed_day() {
+        let summary = ActivitySummary::new(vec![active(5), active(6)], 8);
+        assert_eq!(summary.current_streak, 0);
+        assert_eq!(summary.longest_streak, 2);
+    }
+
+    #[test]
+    fn test_empty_days_are_dropped() {
+        let summary = ActivitySummary::new(vec![active(4), ActivityDay::new(5)], 5);
+        assert_eq!(summary.days.len(), 1);
+        assert_eq!(summary.current_streak, 1);
+    }
+}
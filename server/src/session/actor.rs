@@ -1,14 +1,19 @@
 use chess::{
-    convert_uci_castling_to_cozy, format_uci_move, AnalysisScore, EngineAnalysis, PlayerSide,
+    convert_uci_castling_to_cozy, format_uci_move, AnalysisScore, EngineAnalysis,
+    EngineMoveTelemetry, PlayerSide,
 };
-use engine::{EngineCommand, EngineEvent, StockfishConfig, StockfishEngine};
+use engine::{EngineCommand, EngineEvent, PolyglotBook, StockfishConfig, StockfishEngine};
+use std::path::{Component, Path};
+use tokio::io::AsyncReadExt;
 use tokio::sync::{broadcast, mpsc};
 use tokio::time;
 use tracing::Instrument;
 
 use super::commands::*;
 use super::events::*;
-use super::state::{SessionState, TimerState};
+use super::resources::DEFAULT_ENGINE_HASH_MB;
+use super::state::{PendingEngineMove, SessionState, TimerState};
+use crate::power;
 
 /// The main session actor loop.
 /// Owns all mutable state. Processes commands and engine events sequentially.
@@ -37,6 +42,8 @@ async fn run_session_actor_inner(
     maybe_auto_trigger(&mut state, &event_tx).await;
 
     loop {
+        let pending_move_due = state.pending_engine_move.as_ref().map(|p| p.due);
+
         tokio::select! {
             biased;
 
@@ -47,6 +54,7 @@ async fn run_session_actor_inner(
                         if let Some(engine) = state.engine.take() {
                             let _ = engine.shutdown().await;
                         }
+                        state.release_engine();
                         break;
                     }
                     Some(cmd) => {
@@ -57,10 +65,20 @@ async fn run_session_actor_inner(
             }
 
             Some(engine_event) = state.next_engine_event() => {
+                state.tap_engine_event(&engine_event);
                 handle_engine_event(&mut state, engine_event, &event_tx).await;
                 state.shutdown_engine_if_ended().await;
             }
 
+            _ = time::sleep_until(pending_move_due.unwrap_or_else(time::Instant::now)),
+                if pending_move_due.is_some() =>
+            {
+                if let Some(pending) = state.pending_engine_move.take() {
+                    play_engine_move(&mut state, pending.mv, pending.telemetry, &event_tx).await;
+                    state.shutdown_engine_if_ended().await;
+                }
+            }
+
             _ = timer_interval.tick(), if state.timer_active() => {
                 if state.tick_timer() {
                     // Flag fell — broadcast state change
@@ -127,11 +145,9 @@ async fn handle_command(
         SessionCommand::Pause { reply } => {
             if let chess::GamePhase::Playing { turn } = &state.phase {
                 state.phase = chess::GamePhase::Paused { resume_turn: *turn };
-                // Stop engine if thinking
-                if state.engine_thinking {
-                    let _ = stop_engine(state).await;
-                    state.engine_thinking = false;
-                }
+                state.start_pause();
+                // Stop the search; its bestmove must not be played after resume
+                abandon_engine_move(state).await;
                 // Pause timer
                 if let Some(ref mut timer) = state.timer {
                     timer.stop();
@@ -149,6 +165,7 @@ async fn handle_command(
             if let chess::GamePhase::Paused { resume_turn } = &state.phase {
                 let turn = *resume_turn;
                 state.phase = chess::GamePhase::Playing { turn };
+                state.end_pause();
                 // Resume timer
                 if let Some(ref mut timer) = state.timer {
                     timer.start(PlayerSide::from(state.game.side_to_move()));
@@ -163,6 +180,42 @@ async fn handle_command(
                 ))));
             }
         }
+        SessionCommand::SetMoveDelay { delay_ms, reply } => {
+            state.set_move_delay(delay_ms);
+            let _ = event_tx.send(SessionEvent::StateChanged(state.snapshot()));
+            let _ = reply.send(Ok(()));
+        }
+        SessionCommand::SetTacticalHints { enabled, reply } => {
+            state.set_tactical_hints(enabled);
+            let _ = event_tx.send(SessionEvent::StateChanged(state.snapshot()));
+            let _ = reply.send(Ok(()));
+        }
+        SessionCommand::SetStepMode { enabled, reply } => {
+            state.step_mode = enabled;
+            state.step_requested = false;
+            let _ = event_tx.send(SessionEvent::StateChanged(state.snapshot()));
+            let _ = reply.send(Ok(()));
+            // Leaving step mode lets the game run on by itself again
+            maybe_auto_trigger(state, event_tx).await;
+        }
+        SessionCommand::SetLowPower { enabled, reply } => {
+            let _ = reply.send(set_low_power(state, enabled).await);
+        }
+        SessionCommand::Step { reply } => {
+            if !state.step_mode {
+                let _ = reply.send(Err(SessionError::InvalidPhaseTransition(
+                    "Step mode is not enabled".to_string(),
+                )));
+            } else if state.is_paused() {
+                let _ = reply.send(Err(SessionError::InvalidPhaseTransition(
+                    "Cannot step while paused".to_string(),
+                )));
+            } else {
+                state.step_requested = true;
+                let _ = reply.send(Ok(()));
+                maybe_auto_trigger(state, event_tx).await;
+            }
+        }
         SessionCommand::SetTimer {
             white_ms,
             black_ms,
@@ -180,22 +233,67 @@ async fn handle_command(
             let _ = event_tx.send(SessionEvent::StateChanged(state.snapshot()));
             let _ = reply.send(Ok(()));
         }
+        SessionCommand::SetGameMode { game_mode, reply } => {
+            // The side the engine was searching for may now be a human's
+            abandon_engine_move(state).await;
+            state.game_mode = game_mode;
+            let snapshot = state.snapshot();
+            let _ = event_tx.send(SessionEvent::StateChanged(snapshot.clone()));
+            let _ = reply.send(Ok(snapshot));
+            maybe_auto_trigger(state, event_tx).await;
+        }
         SessionCommand::GetSnapshot { reply } => {
             let _ = reply.send(state.snapshot());
         }
+        SessionCommand::GetResourceUsage { reply } => {
+            let _ = reply.send(state.resources.usage());
+        }
+        SessionCommand::GetUciTranscript { reply } => {
+            let _ = reply.send(state.uci_transcript.lines());
+        }
         SessionCommand::GetLegalMoves { from, reply } => {
             let moves = compute_legal_moves(state, from);
             let _ = reply.send(moves);
         }
+        SessionCommand::AttachEngineTap { reply } => {
+            let _ = reply.send(state.engine_tap.subscribe());
+        }
         SessionCommand::Subscribe { reply } => {
             let snapshot = state.snapshot();
             let rx = event_tx.subscribe();
             let _ = reply.send((snapshot, rx));
         }
+        SessionCommand::SendChat {
+            author,
+            text,
+            reply,
+        } => {
+            let message = state
+                .chat
+                .push(author, text, crate::persistence::now_timestamp());
+            let _ = event_tx.send(SessionEvent::Chat(message.clone()));
+            let _ = reply.send(message);
+        }
+        SessionCommand::GetChatHistory { reply } => {
+            let _ = reply.send(state.chat.messages());
+        }
         SessionCommand::Shutdown => unreachable!(),
     }
 }
 
+/// Drop an engine move waiting out the move delay, or stop a running search
+/// so its bestmove is ignored when it arrives.
+async fn abandon_engine_move(state: &mut SessionState) {
+    if state.cancel_pending_engine_move() {
+        tracing::debug!("Dropped engine move held for move delay");
+    } else if state.engine_thinking {
+        if stop_engine(state).await.is_ok() {
+            state.stale_bestmoves += 1;
+        }
+        state.engine_thinking = false;
+    }
+}
+
 fn compute_legal_moves(state: &SessionState, from: Option<cozy_chess::Square>) -> Vec<LegalMove> {
     let legal = state.game.legal_moves();
     legal
@@ -223,24 +321,62 @@ fn compute_legal_moves(state: &SessionState, from: Option<cozy_chess::Square>) -
 
 async fn configure_engine(
     state: &mut SessionState,
-    config: EngineConfig,
+    mut config: EngineConfig,
 ) -> Result<(), SessionError> {
     if config.skill_level > 20 {
         return Err(SessionError::Internal(
             "Skill level must be 0-20".to_string(),
         ));
     }
+    let previous_multipv = state.engine_config.as_ref().and_then(|c| c.multipv);
+    config.multipv = config
+        .multipv
+        .map(|lines| lines.clamp(1, MAX_MULTIPV))
+        .or(previous_multipv);
+
+    let previous_book = state
+        .engine_config
+        .as_ref()
+        .and_then(|c| c.book_path.clone());
+    config.book_path = match config.book_path.take() {
+        Some(path) if path.is_empty() => None,
+        Some(path) => Some(path),
+        None => previous_book.clone(),
+    };
+    config.book_max_plies = config
+        .book_max_plies
+        .or_else(|| state.engine_config.as_ref().and_then(|c| c.book_max_plies));
+    // Load before anything else changes, so a bad path leaves the engine as it was
+    if config.book_path != previous_book {
+        state.book = match &config.book_path {
+            Some(path) => Some(load_book(path).await?),
+            None => None,
+        };
+    }
 
     if config.enabled && state.engine.is_none() {
+        // Reserve the engine's hash before the process allocates it. The
+        // configured hash is reserved even in low-power mode, so leaving it
+        // never needs more than the lease holds
+        let (threads, hash_mb) = configured_resources(&config);
+        let lease = state.engine_budget.acquire(hash_mb)?;
+        let (threads, hash_mb) = power::engine_resources(threads, hash_mb, state.low_power);
+
         let sf_config = StockfishConfig {
             skill_level: Some(config.skill_level),
-            threads: config.threads,
-            hash_mb: config.hash_mb,
+            threads: Some(threads),
+            hash_mb: Some(hash_mb),
             label: Some(state.session_id.clone()),
         };
         let engine = StockfishEngine::spawn_with_config(sf_config)
             .await
-            .map_err(|e| SessionError::Internal(format!("Failed to spawn engine: {}", e)))?;
+            .map_err(|e| {
+                if e == engine::STOCKFISH_NOT_FOUND {
+                    SessionError::EngineUnavailable(e)
+                } else {
+                    SessionError::Internal(format!("Failed to spawn engine: {}", e))
+                }
+            })?;
 
         engine
             .send_command(EngineCommand::SetOption {
@@ -251,17 +387,133 @@ async fn configure_engine(
             .map_err(|e| SessionError::Internal(e.to_string()))?;
 
         state.engine = Some(engine);
+        state.engine_lease = Some(lease);
+        state.resources.engine_started(hash_mb, threads);
+        if let Some(lines) = config.multipv.filter(|&lines| lines > 1) {
+            set_multipv(state, lines).await?;
+        }
+    } else if config.enabled && config.multipv != previous_multipv {
+        set_multipv(state, config.multipv.unwrap_or(1)).await?;
     } else if !config.enabled {
         if let Some(engine) = state.engine.take() {
             let _ = engine.shutdown().await;
         }
+        state.release_engine();
         state.engine_thinking = false;
+        state.stale_bestmoves = 0;
     }
 
     state.engine_config = Some(config);
     Ok(())
 }
 
+/// Threads and hash the engine is configured with, within what a session
+/// engine may use.
+fn configured_resources(config: &EngineConfig) -> (u32, u32) {
+    let threads = config.threads.map_or(1, |t| t.clamp(1, 16));
+    let hash_mb = config
+        .hash_mb
+        .map_or(DEFAULT_ENGINE_HASH_MB, |mb| mb.clamp(1, 2048));
+    (threads, hash_mb)
+}
+
+/// Enter or leave low-power mode. A running engine is resized in place;
+/// Stockfish applies the new threads and hash once any search in progress
+/// has finished.
+async fn set_low_power(state: &mut SessionState, enabled: bool) -> Result<(), SessionError> {
+    if state.low_power == enabled {
+        return Ok(());
+    }
+    state.low_power = enabled;
+    let (Some(engine), Some(config)) = (&state.engine, &state.engine_config) else {
+        return Ok(());
+    };
+    let (threads, hash_mb) = configured_resources(config);
+    let (threads, hash_mb) = power::engine_resources(threads, hash_mb, enabled);
+    for (name, value) in [("Threads", threads), ("Hash", hash_mb)] {
+        engine
+            .send_command(EngineCommand::SetOption {
+                name: name.to_string(),
+                value: Some(value.to_string()),
+            })
+            .await
+            .map_err(|e| SessionError::Internal(e.to_string()))?;
+    }
+    state.resources.engine_started(hash_mb, threads);
+    tracing::info!(threads, hash_mb, low_power = enabled, "Engine resized");
+    Ok(())
+}
+
+/// Largest opening book a session will read.
+const MAX_BOOK_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Read the polyglot book `name` from the server's books directory.
+async fn load_book(name: &str) -> Result<PolyglotBook, SessionError> {
+    let invalid = |e: &dyn std::fmt::Display| SessionError::InvalidBook(format!("{}: {}", name, e));
+    let bytes = read_book(&crate::config::get_books_dir(), name)
+        .await
+        .map_err(|e| invalid(&e))?;
+    let book = PolyglotBook::from_bytes(&bytes).map_err(|e| invalid(&e))?;
+    tracing::info!("Loaded opening book {} ({} entries)", name, book.len());
+    Ok(book)
+}
+
+/// Read the book file `name` in `dir`. Only a plain file name is accepted,
+/// it must resolve to a regular file inside `dir`, and files over
+/// `MAX_BOOK_BYTES` are refused.
+async fn read_book(dir: &Path, name: &str) -> Result<Vec<u8>, String> {
+    let mut components = Path::new(name).components();
+    if !matches!(
+        (components.next(), components.next()),
+        (Some(Component::Normal(_)), None)
+    ) {
+        return Err("not a book name".to_string());
+    }
+    // Symlinks may point out of the directory; the resolved path must not
+    let missing = |_| "no such book".to_string();
+    let dir = tokio::fs::canonicalize(dir).await.map_err(missing)?;
+    let path = tokio::fs::canonicalize(dir.join(name))
+        .await
+        .map_err(missing)?;
+    if !path.starts_with(&dir) {
+        return Err("no such book".to_string());
+    }
+
+    let file = tokio::fs::File::open(&path).await.map_err(missing)?;
+    let metadata = file.metadata().await.map_err(|e| e.to_string())?;
+    if !metadata.is_file() {
+        return Err("not a file".to_string());
+    }
+    if metadata.len() > MAX_BOOK_BYTES {
+        return Err(format!("larger than {} MiB", MAX_BOOK_BYTES >> 20));
+    }
+    // The file may grow after the check; never read past the cap
+    let mut bytes = Vec::with_capacity(metadata.len() as usize);
+    file.take(MAX_BOOK_BYTES + 1)
+        .read_to_end(&mut bytes)
+        .await
+        .map_err(|e| e.to_string())?;
+    if bytes.len() as u64 > MAX_BOOK_BYTES {
+        return Err(format!("larger than {} MiB", MAX_BOOK_BYTES >> 20));
+    }
+    Ok(bytes)
+}
+
+/// Tell the running engine how many lines to search at once. Takes effect
+/// from its next search.
+async fn set_multipv(state: &mut SessionState, lines: u32) -> Result<(), SessionError> {
+    if let Some(ref engine) = state.engine {
+        engine
+            .send_command(EngineCommand::SetOption {
+                name: "MultiPV".to_string(),
+                value: Some(lines.to_string()),
+            })
+            .await
+            .map_err(|e| SessionError::Internal(e.to_string()))?;
+    }
+    Ok(())
+}
+
 async fn stop_engine(state: &mut SessionState) -> Result<(), SessionError> {
     if let Some(ref engine) = state.engine {
         engine
@@ -273,6 +525,26 @@ async fn stop_engine(state: &mut SessionState) -> Result<(), SessionError> {
     Ok(())
 }
 
+/// Apply an engine move and let the opponent engine (if any) start thinking.
+async fn play_engine_move(
+    state: &mut SessionState,
+    mv: cozy_chess::Move,
+    telemetry: Option<EngineMoveTelemetry>,
+    event_tx: &broadcast::Sender<SessionEvent>,
+) {
+    state.engine_thinking = false;
+    match state.apply_engine_move(mv, telemetry) {
+        Ok(snapshot) => {
+            let _ = event_tx.send(SessionEvent::StateChanged(snapshot));
+            maybe_auto_trigger(state, event_tx).await;
+        }
+        Err(e) => {
+            tracing::error!("Failed to apply engine move: {}", e);
+            let _ = event_tx.send(SessionEvent::Error(e.to_string()));
+        }
+    }
+}
+
 /// Auto-trigger engine if it's the engine's turn and game is ongoing.
 async fn maybe_auto_trigger(state: &mut SessionState, event_tx: &broadcast::Sender<SessionEvent>) {
     if state.should_auto_trigger_engine() {
@@ -290,10 +562,19 @@ async fn handle_engine_event(
 ) {
     match event {
         EngineEvent::BestMove(mv) => {
-            state.engine_thinking = false;
+            // Every search costs resources, whether or not its move is played
+            state.resources.finish_search();
+
+            // A search stopped by pause still answers, possibly after a quick resume
+            if state.stale_bestmoves > 0 {
+                state.stale_bestmoves -= 1;
+                tracing::debug!("Discarding bestmove from search stopped on pause: {:?}", mv);
+                return;
+            }
 
             // Discard bestmove if we're paused — it's a leftover from a stop command
-            if matches!(state.phase, chess::GamePhase::Paused { .. }) {
+            if state.is_paused() {
+                state.engine_thinking = false;
                 tracing::debug!("Discarding bestmove while paused: {:?}", mv);
                 return;
             }
@@ -302,6 +583,7 @@ async fn handle_engine_event(
             let converted = convert_uci_castling_to_cozy(mv, &legal_moves);
 
             if !legal_moves.contains(&converted) {
+                state.engine_thinking = false;
                 tracing::error!("Engine suggested illegal move: {:?}", mv);
                 let _ = event_tx.send(SessionEvent::Error(format!(
                     "Engine suggested illegal move: {:?}",
@@ -310,48 +592,79 @@ async fn handle_engine_event(
                 return;
             }
 
-            match state.apply_move(converted) {
-                Ok(snapshot) => {
-                    let _ = event_tx.send(SessionEvent::StateChanged(snapshot));
-                    maybe_auto_trigger(state, event_tx).await;
-                }
-                Err(e) => {
-                    tracing::error!("Failed to apply engine move: {}", e);
-                    let _ = event_tx.send(SessionEvent::Error(e.to_string()));
-                }
+            // The last info before bestmove is what the engine actually played on
+            let telemetry = state.analysis.as_ref().map(EngineMoveTelemetry::from);
+
+            // Hold the move back until the move delay has elapsed; the engine
+            // still counts as thinking so nothing else triggers meanwhile
+            if let Some(due) = state.engine_move_due() {
+                tracing::debug!("Holding engine move {:?} for move delay", mv);
+                state.pending_engine_move = Some(PendingEngineMove {
+                    mv: converted,
+                    telemetry,
+                    due,
+                });
+                return;
             }
+
+            play_engine_move(state, converted, telemetry, event_tx).await;
         }
         EngineEvent::Info(info) => {
+            // Lines after the best one are only passed on for display
+            let line = info.multipv.map(u32::from).filter(|&n| n > 1);
+            if line.is_some() && info.score_bound.is_some() {
+                return;
+            }
+            // Keep the last exact score while the engine reports aspiration-window bounds
+            let score = match (info.score_bound, &state.analysis) {
+                (Some(_), Some(previous)) => previous.score.clone(),
+                _ => info.score.map(|s| match s {
+                    engine::Score::Centipawns(cp) => AnalysisScore::Centipawns(cp),
+                    engine::Score::Mate(m) => AnalysisScore::Mate(m as i32),
+                }),
+            };
             let analysis = EngineAnalysis {
                 depth: info.depth.map(|d| d as u32),
                 seldepth: info.seldepth.map(|d| d as u32),
                 time_ms: info.time_ms,
                 nodes: info.nodes,
-                score: info.score.map(|s| match s {
-                    engine::Score::Centipawns(cp) => AnalysisScore::Centipawns(cp),
-                    engine::Score::Mate(m) => AnalysisScore::Mate(m as i32),
-                }),
+                score,
                 pv: info.pv.iter().map(|mv| format_uci_move(*mv)).collect(),
                 nps: info.nps,
+                multipv: info.multipv.map(u32::from),
             };
+            if line.is_some() {
+                let _ = event_tx.send(SessionEvent::EngineThinking(analysis));
+                return;
+            }
+            state
+                .resources
+                .observe_info(analysis.nodes, analysis.time_ms);
             state.analysis = Some(analysis.clone());
             let _ = event_tx.send(SessionEvent::EngineThinking(analysis));
         }
         EngineEvent::RawUciMessage { direction, message } => {
-            let _ = event_tx.send(SessionEvent::UciMessage(UciLogEntry {
+            let entry = UciLogEntry {
                 direction: match direction {
                     engine::UciMessageDirection::ToEngine => UciDirection::ToEngine,
                     engine::UciMessageDirection::FromEngine => UciDirection::FromEngine,
+                    engine::UciMessageDirection::EngineStderr => UciDirection::EngineStderr,
                 },
                 message,
                 context: None,
-            }));
+            };
+            state.uci_transcript.push(entry.clone());
+            let _ = event_tx.send(SessionEvent::UciMessage(entry));
+        }
+        EngineEvent::InfoString(text) => {
+            tracing::info!(engine_log = %text, "Engine info string");
         }
         EngineEvent::Ready => {
             tracing::debug!("Engine ready");
         }
         EngineEvent::Error(err) => {
             tracing::error!("Engine error: {}", err);
+            state.engine_thinking = false;
             let _ = event_tx.send(SessionEvent::Error(format!("Engine error: {}", err)));
         }
     }
@@ -399,6 +712,26 @@ mod tests {
         assert!(!snapshot.engine_thinking);
     }
 
+    #[tokio::test]
+    async fn test_chat_is_broadcast_and_kept() {
+        let (handle, mut events) = spawn_test_actor().await;
+        let sent = handle.send_chat(" ana ", "good luck ").await.unwrap();
+        assert_eq!((sent.seq, sent.author.as_str()), (1, "ana"));
+        assert_eq!(sent.text, "good luck");
+        assert!(matches!(
+            handle.send_chat("ana", " ").await,
+            Err(SessionError::InvalidChat(_))
+        ));
+
+        match events.recv().await.unwrap() {
+            SessionEvent::Chat(message) => assert_eq!(message, sent),
+            other => panic!("expected a chat event, got {:?}", other),
+        }
+        let second = handle.send_chat("", "thanks").await.unwrap();
+        assert_eq!(second.seq, 2);
+        assert_eq!(handle.chat_history().await.unwrap(), vec![sent, second]);
+    }
+
     #[tokio::test]
     async fn test_pause_resume() {
         let (handle, _) = spawn_test_actor().await;
@@ -409,6 +742,16 @@ mod tests {
 
         let snap = handle.get_snapshot().await.unwrap();
         assert!(matches!(snap.phase, chess::GamePhase::Paused { .. }));
+        assert!(snap.timer.is_none_or(|t| t.active_side.is_none()));
+
+        // Moves are rejected until resume
+        let e4 = mv(
+            cozy_chess::File::E,
+            cozy_chess::Rank::Second,
+            cozy_chess::File::E,
+            cozy_chess::Rank::Fourth,
+        );
+        assert!(handle.make_move(e4).await.is_err());
 
         // Resume
         let result = handle.resume().await;
@@ -556,6 +899,9 @@ mod tests {
                 skill_level: 1,
                 threads: None,
                 hash_mb: None,
+                multipv: None,
+                book_path: None,
+                book_max_plies: None,
             })
             .await
             .unwrap();
@@ -586,4 +932,53 @@ mod tests {
         let snap = handle.get_snapshot().await.unwrap();
         assert!(matches!(snap.phase, chess::GamePhase::Ended { .. }));
     }
+
+    #[tokio::test]
+    async fn test_read_book_stays_in_books_dir() {
+        let root = tempfile::tempdir().unwrap();
+        let books = root.path().join("books");
+        std::fs::create_dir(&books).unwrap();
+        std::fs::write(books.join("main.bin"), [0u8; 16]).unwrap();
+        std::fs::write(root.path().join("secret.bin"), [0u8; 16]).unwrap();
+        std::fs::create_dir(books.join("nested")).unwrap();
+
+        assert_eq!(read_book(&books, "main.bin").await.unwrap().len(), 16);
+        for name in ["../secret.bin", "nested/../main.bin", "", ".", ".."] {
+            assert!(read_book(&books, name).await.is_err(), "{}", name);
+        }
+        let absolute = root.path().join("secret.bin");
+        assert!(read_book(&books, absolute.to_str().unwrap()).await.is_err());
+        assert_eq!(read_book(&books, "nested").await.unwrap_err(), "not a file");
+        assert_eq!(
+            read_book(&books, "missing.bin").await.unwrap_err(),
+            "no such book"
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_read_book_refuses_links_out_of_books_dir() {
+        let root = tempfile::tempdir().unwrap();
+        let books = root.path().join("books");
+        std::fs::create_dir(&books).unwrap();
+        std::fs::write(root.path().join("secret.bin"), [0u8; 16]).unwrap();
+        std::os::unix::fs::symlink(root.path().join("secret.bin"), books.join("link.bin")).unwrap();
+
+        assert_eq!(
+            read_book(&books, "link.bin").await.unwrap_err(),
+            "no such book"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_book_refuses_oversized_files() {
+        let books = tempfile::tempdir().unwrap();
+        let file = std::fs::File::create(books.path().join("huge.bin")).unwrap();
+        file.set_len(MAX_BOOK_BYTES + 1).unwrap();
+
+        assert!(read_book(books.path(), "huge.bin")
+            .await
+            .unwrap_err()
+            .starts_with("larger than"));
+    }
 }
@@ -0,0 +1,112 @@
+//! Chat between the clients connected to a session. The actor keeps the
+//! latest messages so a client joining late can catch up; the manager
+//! persists every message with the session's id.
+
+use std::collections::VecDeque;
+
+use super::commands::SessionError;
+
+/// Messages kept per session for late joiners; older ones are dropped first.
+pub const CHAT_HISTORY: usize = 200;
+
+/// Longest message, in characters.
+pub const MAX_CHAT_CHARS: usize = 500;
+
+/// Longest author name, in characters.
+pub const MAX_AUTHOR_CHARS: usize = 32;
+
+/// Author shown for messages sent without a name.
+pub const ANONYMOUS_AUTHOR: &str = "anonymous";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChatMessage {
+    /// Position in the session's chat, from 1.
+    pub seq: u32,
+    pub author: String,
+    pub text: String,
+    /// Unix time in seconds.
+    pub sent_at: u64,
+}
+
+/// Trim a message and its author, naming an unnamed author [`ANONYMOUS_AUTHOR`].
+pub fn validate(author: &str, text: &str) -> Result<(String, String), SessionError> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(SessionError::InvalidChat("message is empty".to_string()));
+    }
+    if text.chars().count() > MAX_CHAT_CHARS {
+        return Err(SessionError::InvalidChat(format!(
+            "message is longer than {} characters",
+            MAX_CHAT_CHARS
+        )));
+    }
+    let author = match author.trim() {
+        "" => ANONYMOUS_AUTHOR,
+        name if name.chars().count() > MAX_AUTHOR_CHARS => {
+            return Err(SessionError::InvalidChat(format!(
+                "author is longer than {} characters",
+                MAX_AUTHOR_CHARS
+            )))
+        }
+        name => name,
+    };
+    Ok((author.to_string(), text.to_string()))
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ChatLog {
+    messages: VecDeque<ChatMessage>,
+    sent: u32,
+}
+
+impl ChatLog {
+    /// Number the message after the last one sent and keep it.
+    pub fn push(&mut self, author: String, text: String, sent_at: u64) -> ChatMessage {
+        self.sent += 1;
+        let message = ChatMessage {
+            seq: self.sent,
+            author,
+            text,
+            sent_at,
+        };
+        if self.messages.len() == CHAT_HISTORY {
+            self.messages.pop_front();
+        }
+        self.messages.push_back(message.clone());
+        message
+    }
+
+    /// The kept messages, oldest first.
+    pub fn messages(&self) -> Vec<ChatMessage> {
+        self.messages.iter().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(
+            validate("  ana ", " nice move\n").unwrap(),
+            ("ana".to_string(), "nice move".to_string())
+        );
+        assert_eq!(validate("", "hi").unwrap().0, ANONYMOUS_AUTHOR);
+        assert!(validate("ana", "   ").is_err());
+        assert!(validate("ana", &"x".repeat(MAX_CHAT_CHARS + 1)).is_err());
+        assert!(validate(&"a".repeat(MAX_AUTHOR_CHARS + 1), "hi").is_err());
+    }
+
+    #[test]
+    fn test_log_numbers_messages_and_keeps_the_latest() {
+        let mut log = ChatLog::default();
+        for i in 0..CHAT_HISTORY + 2 {
+            log.push("ana".to_string(), format!("message {}", i), 0);
+        }
+        let messages = log.messages();
+        assert_eq!(messages.len(), CHAT_HISTORY);
+        assert_eq!(messages[0].seq, 3);
+        assert_eq!(messages.last().unwrap().seq as usize, CHAT_HISTORY + 2);
+    }
+}
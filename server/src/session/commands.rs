@@ -1,33 +1,88 @@
+use chess::GameMode;
 use cozy_chess::{Move, Square};
+use engine::EngineEvent;
 use tokio::sync::{broadcast, oneshot};
+use tonic::Status;
 
-use super::events::SessionEvent;
+use super::chat::ChatMessage;
+use super::events::{SessionEvent, UciLogEntry};
+use super::resources::ResourceUsage;
 use super::snapshot::SessionSnapshot;
 
 #[derive(Debug, Clone, thiserror::Error)]
 pub enum SessionError {
+    #[error("Session not found: {0}")]
+    NotFound(String),
+    #[error("Session closed")]
+    Closed,
     #[error("Illegal move: {0}")]
     IllegalMove(String),
     #[error("Invalid FEN: {0}")]
     InvalidFen(String),
     #[error("Engine not configured")]
     EngineNotConfigured,
+    #[error("Engine unavailable: {0}")]
+    EngineUnavailable(String),
     #[error("Nothing to undo")]
     NothingToUndo,
     #[error("Nothing to redo")]
     NothingToRedo,
     #[error("Invalid phase transition: {0}")]
     InvalidPhaseTransition(String),
+    #[error("Resource limit reached: {0}")]
+    ResourceLimit(String),
+    #[error("Opening book: {0}")]
+    InvalidBook(String),
+    #[error("Invalid chat message: {0}")]
+    InvalidChat(String),
+    #[error("Unknown or expired transfer code")]
+    InvalidTransferCode,
     #[error("Internal error: {0}")]
     Internal(String),
 }
 
+impl From<SessionError> for Status {
+    fn from(e: SessionError) -> Self {
+        match e {
+            SessionError::NotFound(_) | SessionError::InvalidTransferCode => {
+                Status::not_found(e.to_string())
+            }
+            SessionError::IllegalMove(_)
+            | SessionError::InvalidFen(_)
+            | SessionError::InvalidBook(_)
+            | SessionError::InvalidChat(_) => Status::invalid_argument(e.to_string()),
+            SessionError::EngineNotConfigured
+            | SessionError::EngineUnavailable(_)
+            | SessionError::NothingToUndo
+            | SessionError::NothingToRedo
+            | SessionError::InvalidPhaseTransition(_) => Status::failed_precondition(e.to_string()),
+            SessionError::ResourceLimit(_) => Status::resource_exhausted(e.to_string()),
+            SessionError::Closed => Status::unavailable(e.to_string()),
+            SessionError::Internal(_) => Status::internal(e.to_string()),
+        }
+    }
+}
+
+/// Most lines an engine may search at once.
+pub const MAX_MULTIPV: u32 = 5;
+
+/// Plies an opening book is consulted for when no depth is given.
+pub const DEFAULT_BOOK_PLIES: u32 = 16;
+
 #[derive(Debug, Clone)]
 pub struct EngineConfig {
     pub enabled: bool,
     pub skill_level: u8,
     pub threads: Option<u32>,
     pub hash_mb: Option<u32>,
+    /// Lines searched at once; `None` keeps the current count.
+    pub multipv: Option<u32>,
+    /// Polyglot book played from before searching; `None` keeps the current
+    /// book and an empty path turns it off.
+    pub book_path: Option<String>,
+    /// Plies from the start of the game the book is consulted for; `None`
+    /// keeps the current depth.
+    pub book_max_plies: Option<u32>,
 }
 
 #[derive(Debug, Clone)]
@@ -71,14 +126,45 @@ pub enum SessionCommand {
     Resume {
         reply: oneshot::Sender<Result<(), SessionError>>,
     },
+    SetMoveDelay {
+        delay_ms: u64,
+        reply: oneshot::Sender<Result<(), SessionError>>,
+    },
+    SetTacticalHints {
+        enabled: bool,
+        reply: oneshot::Sender<Result<(), SessionError>>,
+    },
+    SetStepMode {
+        enabled: bool,
+        reply: oneshot::Sender<Result<(), SessionError>>,
+    },
+    /// Run the engine with reduced threads and hash, or back with the
+    /// configured ones.
+    SetLowPower {
+        enabled: bool,
+        reply: oneshot::Sender<Result<(), SessionError>>,
+    },
+    Step {
+        reply: oneshot::Sender<Result<(), SessionError>>,
+    },
     SetTimer {
         white_ms: u64,
         black_ms: u64,
         reply: oneshot::Sender<Result<(), SessionError>>,
     },
+    SetGameMode {
+        game_mode: GameMode,
+        reply: oneshot::Sender<Result<SessionSnapshot, SessionError>>,
+    },
     GetSnapshot {
         reply: oneshot::Sender<SessionSnapshot>,
     },
+    GetResourceUsage {
+        reply: oneshot::Sender<ResourceUsage>,
+    },
+    GetUciTranscript {
+        reply: oneshot::Sender<Vec<UciLogEntry>>,
+    },
     GetLegalMoves {
         from: Option<Square>,
         reply: oneshot::Sender<Vec<LegalMove>>,
@@ -86,5 +172,17 @@ pub enum SessionCommand {
     Subscribe {
         reply: oneshot::Sender<(SessionSnapshot, broadcast::Receiver<SessionEvent>)>,
     },
+    /// Broadcast an already validated chat message.
+    SendChat {
+        author: String,
+        text: String,
+        reply: oneshot::Sender<ChatMessage>,
+    },
+    GetChatHistory {
+        reply: oneshot::Sender<Vec<ChatMessage>>,
+    },
+    AttachEngineTap {
+        reply: oneshot::Sender<broadcast::Receiver<EngineEvent>>,
+    },
     Shutdown,
 }
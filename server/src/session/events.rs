@@ -1,5 +1,6 @@
 use chess::EngineAnalysis;
 
+use super::chat::ChatMessage;
 use super::snapshot::SessionSnapshot;
 
 /// Events broadcast from the session actor to all subscribers.
@@ -14,6 +15,8 @@ pub enum SessionEvent {
     UciMessage(UciLogEntry),
     /// Error notification.
     Error(String),
+    /// A client sent a chat message.
+    Chat(ChatMessage),
 }
 
 #[derive(Debug, Clone)]
@@ -27,4 +30,5 @@ pub struct UciLogEntry {
 pub enum UciDirection {
     ToEngine,
     FromEngine,
+    EngineStderr,
 }
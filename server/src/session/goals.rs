@@ -0,0 +1,111 @@
+//! Progress of a profile's goals against the stats.
+
+use crate::persistence::{ActivityDay, GoalData, GoalMetric};
+
+/// Check that a goal's metric can be tracked and its target makes sense
+/// for it.
+pub fn validate_target(metric: GoalMetric, target: f64) -> Result<(), String> {
+    // Nothing records solved puzzles until there is a puzzle trainer
+    if metric == GoalMetric::PuzzlesSolved {
+        return Err("Puzzle goals are not available yet".to_string());
+    }
+    if !target.is_finite() || target <= 0.0 {
+        return Err("Goal target must be positive".to_string());
+    }
+    if metric == GoalMetric::AverageAccuracy && target > 100.0 {
+        return Err("Accuracy target cannot exceed 100%".to_string());
+    }
+    Ok(())
+}
+
+/// A goal with its current value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoalProgress {
+    pub goal: GoalData,
+    /// Accuracy percentage or count so far; `None` when there is nothing to
+    /// measure yet (no reviewed games for an accuracy goal).
+    pub current: Option<f64>,
+}
+
+impl GoalProgress {
+    /// Progress from the activity totals and average accuracy over the
+    /// goal's period.
+    pub fn new(goal: GoalData, totals: &ActivityDay, average_accuracy: Option<f64>) -> Self {
+        let current = match goal.metric {
+            GoalMetric::AverageAccuracy => average_accuracy,
+            GoalMetric::GamesPlayed => Some(f64::from(totals.games_played)),
+            GoalMetric::PuzzlesSolved => Some(f64::from(totals.puzzles_solved)),
+            GoalMetric::ReviewsRead => Some(f64::from(totals.reviews_read)),
+        };
+        Self { goal, current }
+    }
+
+    /// Share of the target reached, from 0 to 1.
+    pub fn fraction(&self) -> f64 {
+        match self.current {
+            Some(current) => (current / self.goal.target).clamp(0.0, 1.0),
+            None => 0.0,
+        }
+    }
+
+    pub fn achieved(&self) -> bool {
+        self.current.is_some_and(|c| c >= self.goal.target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::GoalPeriod;
+
+    fn goal(metric: GoalMetric, target: f64) -> GoalData {
+        GoalData {
+            goal_id: "g".to_string(),
+            profile: "alice".to_string(),
+            metric,
+            target,
+            period: GoalPeriod::Month,
+            created_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_validate_target() {
+        assert!(validate_target(GoalMetric::GamesPlayed, 20.0).is_ok());
+        assert!(validate_target(GoalMetric::GamesPlayed, 0.0).is_err());
+        assert!(validate_target(GoalMetric::GamesPlayed, f64::NAN).is_err());
+        assert!(validate_target(GoalMetric::AverageAccuracy, 85.0).is_ok());
+        assert!(validate_target(GoalMetric::AverageAccuracy, 101.0).is_err());
+        assert!(validate_target(GoalMetric::PuzzlesSolved, 10.0).is_err());
+    }
+
+    #[test]
+    fn test_count_progress() {
+        let totals = ActivityDay {
+            day: 0,
+            games_played: 5,
+            puzzles_solved: 0,
+            reviews_read: 12,
+        };
+        let games = GoalProgress::new(goal(GoalMetric::GamesPlayed, 20.0), &totals, Some(90.0));
+        assert_eq!(games.current, Some(5.0));
+        assert_eq!(games.fraction(), 0.25);
+        assert!(!games.achieved());
+
+        let reviews = GoalProgress::new(goal(GoalMetric::ReviewsRead, 10.0), &totals, None);
+        assert_eq!(reviews.fraction(), 1.0);
+        assert!(reviews.achieved());
+    }
+
+    #[test]
+    fn test_accuracy_progress() {
+        let totals = ActivityDay::new(0);
+        let progress = GoalProgress::new(goal(GoalMetric::AverageAccuracy, 80.0), &totals, None);
+        assert_eq!(progress.fraction(), 0.0);
+        assert!(!progress.achieved());
+
+        let progress =
+            GoalProgress::new(goal(GoalMetric::AverageAccuracy, 80.0), &totals, Some(60.0));
+        assert_eq!(progress.fraction(), 0.75);
+    }
+}
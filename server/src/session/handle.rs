@@ -1,126 +1,232 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chess::GameMode;
 use cozy_chess::{Move, Square};
-use tokio::sync::{broadcast, mpsc, oneshot};
+use engine::EngineEvent;
+use tokio::sync::{broadcast, mpsc, oneshot, watch};
+use tokio::task::AbortHandle;
 
+use super::chat::{self, ChatMessage};
 use super::commands::*;
-use super::events::SessionEvent;
+use super::events::{SessionEvent, UciLogEntry};
+use super::resources::ResourceUsage;
 use super::snapshot::SessionSnapshot;
 
 /// Cheap, cloneable handle to a session actor.
+///
+/// Every operation the actor supports has a typed method here; callers never
+/// build a [`SessionCommand`] themselves. Errors are [`SessionError`]s, which
+/// convert into a gRPC `Status` with a matching code.
 #[derive(Clone)]
 pub struct SessionHandle {
     cmd_tx: mpsc::Sender<SessionCommand>,
+    opened_at: Instant,
+    /// The actor task, for sessions the manager spawned.
+    task: Option<AbortHandle>,
+    /// Counts the times the session was handed to another client; the
+    /// event stream of the client that had it ends on each change.
+    owner: Arc<watch::Sender<u64>>,
 }
 
 impl SessionHandle {
     pub(crate) fn new(cmd_tx: mpsc::Sender<SessionCommand>) -> Self {
-        Self { cmd_tx }
+        Self {
+            cmd_tx,
+            opened_at: Instant::now(),
+            task: None,
+            owner: Arc::new(watch::channel(0).0),
+        }
+    }
+
+    pub(crate) fn with_task(mut self, task: AbortHandle) -> Self {
+        self.task = Some(task);
+        self
+    }
+
+    /// How long the session has been open on this server. A resumed
+    /// session counts from when it was resumed.
+    pub fn age(&self) -> Duration {
+        self.opened_at.elapsed()
+    }
+
+    /// How many times the session has been handed to another client.
+    pub fn owner_generation(&self) -> u64 {
+        *self.owner.borrow()
+    }
+
+    /// Notified each time the session is handed to another client.
+    pub fn watch_owner(&self) -> watch::Receiver<u64> {
+        self.owner.subscribe()
+    }
+
+    /// Hand the session to another client: the client streaming it now
+    /// loses it, and its stream ending no longer closes the session.
+    pub(crate) fn hand_over(&self) {
+        self.owner.send_modify(|generation| *generation += 1);
     }
 
     pub async fn make_move(&self, mv: Move) -> Result<SessionSnapshot, SessionError> {
-        let (tx, rx) = oneshot::channel();
-        self.send(SessionCommand::MakeMove { mv, reply: tx })
-            .await?;
-        rx.await
-            .map_err(|_| SessionError::Internal("Reply dropped".into()))?
+        self.request(|reply| SessionCommand::MakeMove { mv, reply })
+            .await?
     }
 
     pub async fn undo(&self) -> Result<SessionSnapshot, SessionError> {
-        let (tx, rx) = oneshot::channel();
-        self.send(SessionCommand::Undo { reply: tx }).await?;
-        rx.await
-            .map_err(|_| SessionError::Internal("Reply dropped".into()))?
+        self.request(|reply| SessionCommand::Undo { reply }).await?
     }
 
     pub async fn redo(&self) -> Result<SessionSnapshot, SessionError> {
-        let (tx, rx) = oneshot::channel();
-        self.send(SessionCommand::Redo { reply: tx }).await?;
-        rx.await
-            .map_err(|_| SessionError::Internal("Reply dropped".into()))?
+        self.request(|reply| SessionCommand::Redo { reply }).await?
     }
 
     pub async fn reset(&self, fen: Option<String>) -> Result<SessionSnapshot, SessionError> {
-        let (tx, rx) = oneshot::channel();
-        self.send(SessionCommand::Reset { fen, reply: tx }).await?;
-        rx.await
-            .map_err(|_| SessionError::Internal("Reply dropped".into()))?
+        self.request(|reply| SessionCommand::Reset { fen, reply })
+            .await?
+    }
+
+    /// Switch who plays which side, e.g. hand a human-vs-human game to the
+    /// engine. An engine search for the old mode is abandoned.
+    pub async fn set_game_mode(
+        &self,
+        game_mode: GameMode,
+    ) -> Result<SessionSnapshot, SessionError> {
+        self.request(|reply| SessionCommand::SetGameMode { game_mode, reply })
+            .await?
     }
 
     pub async fn configure_engine(&self, config: EngineConfig) -> Result<(), SessionError> {
-        let (tx, rx) = oneshot::channel();
-        self.send(SessionCommand::ConfigureEngine { config, reply: tx })
-            .await?;
-        rx.await
-            .map_err(|_| SessionError::Internal("Reply dropped".into()))?
+        self.request(|reply| SessionCommand::ConfigureEngine { config, reply })
+            .await?
     }
 
     pub async fn stop_engine(&self) -> Result<(), SessionError> {
-        let (tx, rx) = oneshot::channel();
-        self.send(SessionCommand::StopEngine { reply: tx }).await?;
-        rx.await
-            .map_err(|_| SessionError::Internal("Reply dropped".into()))?
+        self.request(|reply| SessionCommand::StopEngine { reply })
+            .await?
     }
 
     pub async fn pause(&self) -> Result<(), SessionError> {
-        let (tx, rx) = oneshot::channel();
-        self.send(SessionCommand::Pause { reply: tx }).await?;
-        rx.await
-            .map_err(|_| SessionError::Internal("Reply dropped".into()))?
+        self.request(|reply| SessionCommand::Pause { reply })
+            .await?
     }
 
     pub async fn resume(&self) -> Result<(), SessionError> {
-        let (tx, rx) = oneshot::channel();
-        self.send(SessionCommand::Resume { reply: tx }).await?;
-        rx.await
-            .map_err(|_| SessionError::Internal("Reply dropped".into()))?
+        self.request(|reply| SessionCommand::Resume { reply })
+            .await?
+    }
+
+    pub async fn set_move_delay(&self, delay_ms: u64) -> Result<(), SessionError> {
+        self.request(|reply| SessionCommand::SetMoveDelay { delay_ms, reply })
+            .await?
+    }
+
+    pub async fn set_tactical_hints(&self, enabled: bool) -> Result<(), SessionError> {
+        self.request(|reply| SessionCommand::SetTacticalHints { enabled, reply })
+            .await?
+    }
+
+    pub async fn set_step_mode(&self, enabled: bool) -> Result<(), SessionError> {
+        self.request(|reply| SessionCommand::SetStepMode { enabled, reply })
+            .await?
+    }
+
+    pub async fn set_low_power(&self, enabled: bool) -> Result<(), SessionError> {
+        self.request(|reply| SessionCommand::SetLowPower { enabled, reply })
+            .await?
+    }
+
+    pub async fn step(&self) -> Result<(), SessionError> {
+        self.request(|reply| SessionCommand::Step { reply }).await?
     }
 
     pub async fn set_timer(&self, white_ms: u64, black_ms: u64) -> Result<(), SessionError> {
-        let (tx, rx) = oneshot::channel();
-        self.send(SessionCommand::SetTimer {
+        self.request(|reply| SessionCommand::SetTimer {
             white_ms,
             black_ms,
-            reply: tx,
+            reply,
         })
-        .await?;
-        rx.await
-            .map_err(|_| SessionError::Internal("Reply dropped".into()))?
+        .await?
     }
 
     pub async fn get_snapshot(&self) -> Result<SessionSnapshot, SessionError> {
-        let (tx, rx) = oneshot::channel();
-        self.send(SessionCommand::GetSnapshot { reply: tx }).await?;
-        rx.await
-            .map_err(|_| SessionError::Internal("Reply dropped".into()))
+        self.request(|reply| SessionCommand::GetSnapshot { reply })
+            .await
+    }
+
+    pub async fn resource_usage(&self) -> Result<ResourceUsage, SessionError> {
+        self.request(|reply| SessionCommand::GetResourceUsage { reply })
+            .await
+    }
+
+    pub async fn uci_transcript(&self) -> Result<Vec<UciLogEntry>, SessionError> {
+        self.request(|reply| SessionCommand::GetUciTranscript { reply })
+            .await
     }
 
     pub async fn get_legal_moves(
         &self,
         from: Option<Square>,
     ) -> Result<Vec<LegalMove>, SessionError> {
-        let (tx, rx) = oneshot::channel();
-        self.send(SessionCommand::GetLegalMoves { from, reply: tx })
-            .await?;
-        rx.await
-            .map_err(|_| SessionError::Internal("Reply dropped".into()))
+        self.request(|reply| SessionCommand::GetLegalMoves { from, reply })
+            .await
     }
 
     pub async fn subscribe(
         &self,
     ) -> Result<(SessionSnapshot, broadcast::Receiver<SessionEvent>), SessionError> {
-        let (tx, rx) = oneshot::channel();
-        self.send(SessionCommand::Subscribe { reply: tx }).await?;
-        rx.await
-            .map_err(|_| SessionError::Internal("Reply dropped".into()))
+        self.request(|reply| SessionCommand::Subscribe { reply })
+            .await
+    }
+
+    /// Send a chat message to every client of the session. The message is
+    /// trimmed and numbered; one that is empty or too long is refused.
+    pub async fn send_chat(&self, author: &str, text: &str) -> Result<ChatMessage, SessionError> {
+        let (author, text) = chat::validate(author, text)?;
+        self.request(|reply| SessionCommand::SendChat {
+            author,
+            text,
+            reply,
+        })
+        .await
+    }
+
+    /// The latest chat messages, oldest first.
+    pub async fn chat_history(&self) -> Result<Vec<ChatMessage>, SessionError> {
+        self.request(|reply| SessionCommand::GetChatHistory { reply })
+            .await
+    }
+
+    /// Receive every raw engine event of the session, as the engine sent it.
+    /// Dropping the receiver detaches; the session carries on regardless.
+    pub async fn attach_engine_tap(
+        &self,
+    ) -> Result<broadcast::Receiver<EngineEvent>, SessionError> {
+        self.request(|reply| SessionCommand::AttachEngineTap { reply })
+            .await
     }
 
     pub async fn shutdown(&self) {
         let _ = self.cmd_tx.send(SessionCommand::Shutdown).await;
     }
 
-    async fn send(&self, cmd: SessionCommand) -> Result<(), SessionError> {
+    /// Stop the actor task where it is, without a shutdown. Its engine exits
+    /// once the dropped task closes the engine's input.
+    pub fn abort(&self) {
+        if let Some(task) = &self.task {
+            task.abort();
+        }
+    }
+
+    /// Send the command built around a fresh reply channel and wait for the
+    /// actor's answer.
+    async fn request<T>(
+        &self,
+        command: impl FnOnce(oneshot::Sender<T>) -> SessionCommand,
+    ) -> Result<T, SessionError> {
+        let (tx, rx) = oneshot::channel();
         self.cmd_tx
-            .send(cmd)
+            .send(command(tx))
             .await
-            .map_err(|_| SessionError::Internal("Session actor closed".into()))
+            .map_err(|_| SessionError::Closed)?;
+        rx.await.map_err(|_| SessionError::Closed)
     }
 }
@@ -1,26 +1,41 @@
+pub mod activity;
 pub mod actor;
+pub mod chat;
 pub mod commands;
 pub mod events;
+pub mod goals;
 pub mod handle;
+pub mod resources;
 pub mod snapshot;
 pub mod state;
+pub mod transcript;
+pub mod transfer;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use chess::{Game, GameMode, GamePhase, PlayerSide};
+use chess::{Game, GameMode, GamePhase};
 use tokio::sync::{broadcast, mpsc, RwLock};
 use uuid::Uuid;
 
 use crate::persistence::{
-    self, FinishedGameData, FinishedGameRepository, Persistence, PositionRepository,
-    SavedPositionData, SessionRepository, StoredMoveRecord, SuspendedSessionData,
+    self, ActivityDay, ActivityKind, ActivityRepository, ChatMessageData, ChatRepository,
+    FinishedGameData, FinishedGameRepository, GoalData, GoalMetric, GoalPeriod, GoalRepository,
+    Persistence, PositionRepository, SavedPositionData, SessionRepository, StoredMoveRecord,
+    SuspendedSessionData,
 };
+use activity::ActivitySummary;
 use actor::run_session_actor;
-pub use events::{SessionEvent, UciDirection};
+pub use chat::ChatMessage;
+use commands::SessionError;
+pub use events::{SessionEvent, UciDirection, UciLogEntry};
 pub use handle::SessionHandle;
-pub use snapshot::{SessionSnapshot, TimerSnapshot};
+use resources::{EngineBudget, EngineLimits, ResourceUsage};
+pub use snapshot::{SessionLabels, SessionSnapshot, TimerSnapshot};
 use state::SessionState;
+use transfer::TransferCodes;
 
 /// Manages all active sessions. Spawns an actor task per session.
 pub struct SessionManager<D: Persistence> {
@@ -28,6 +43,58 @@ pub struct SessionManager<D: Persistence> {
     store: D::Sessions,
     position_store: D::Positions,
     finished_game_store: Arc<D::FinishedGames>,
+    activity_store: D::Activity,
+    goal_store: D::Goals,
+    chat_store: D::Chat,
+    engine_budget: Arc<EngineBudget>,
+    /// Session engines run with reduced threads and hash, e.g. on battery.
+    low_power: AtomicBool,
+    /// The last sessions closed, newest last, so a bug report can still be
+    /// made after the client has gone.
+    recently_closed: RwLock<VecDeque<DebugReport>>,
+    /// Codes that hand a session to another client.
+    transfers: RwLock<TransferCodes>,
+}
+
+/// How long [`SessionManager::force_close_session`] waits for a session
+/// before aborting it.
+pub const FORCE_CLOSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Sessions kept in [`SessionManager::debug_report`] after they close.
+const RECENTLY_CLOSED_KEPT: usize = 16;
+
+/// What the server knows about a session for a bug report.
+#[derive(Debug, Clone)]
+pub struct DebugReport {
+    /// Last known state; the final one once the session has closed.
+    pub snapshot: SessionSnapshot,
+    /// Finished game saved when the session closed.
+    pub game_id: Option<String>,
+    /// Latest UCI lines, oldest first.
+    pub uci_transcript: Vec<UciLogEntry>,
+}
+
+/// An open session and the engine resources it has used.
+#[derive(Debug, Clone)]
+pub struct ActiveSession {
+    pub session_id: String,
+    pub game_mode: GameMode,
+    pub move_count: usize,
+    /// Time since the session was opened.
+    pub age: Duration,
+    pub engine_running: bool,
+    pub usage: ResourceUsage,
+}
+
+/// How an operator's close of a session went.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ForcedClose {
+    /// Closed as a client would, with the id of the game saved if it had ended.
+    Closed { game_id: Option<String> },
+    /// Saved as a suspended session, then closed.
+    Suspended { suspended_id: String },
+    /// The session did not answer, so its actor was aborted and nothing saved.
+    Aborted,
 }
 
 impl<D: Persistence> SessionManager<D> {
@@ -35,12 +102,44 @@ impl<D: Persistence> SessionManager<D> {
         store: D::Sessions,
         position_store: D::Positions,
         finished_game_store: Arc<D::FinishedGames>,
+        activity_store: D::Activity,
+        goal_store: D::Goals,
+        chat_store: D::Chat,
     ) -> Self {
         Self {
             sessions: RwLock::new(HashMap::new()),
             store,
             position_store,
             finished_game_store,
+            activity_store,
+            goal_store,
+            chat_store,
+            engine_budget: EngineBudget::new(EngineLimits::default()),
+            low_power: AtomicBool::new(false),
+            recently_closed: RwLock::new(VecDeque::new()),
+            transfers: RwLock::new(TransferCodes::default()),
+        }
+    }
+
+    /// Cap the engines sessions may run; without this engines are unlimited.
+    pub fn with_engine_limits(mut self, limits: EngineLimits) -> Self {
+        self.engine_budget = EngineBudget::new(limits);
+        self
+    }
+
+    pub fn engine_budget(&self) -> &EngineBudget {
+        &self.engine_budget
+    }
+
+    /// Enter or leave low-power mode, for open sessions and ones created
+    /// later.
+    pub async fn set_low_power(&self, enabled: bool) {
+        self.low_power.store(enabled, Ordering::Relaxed);
+        let handles: Vec<SessionHandle> = self.sessions.read().await.values().cloned().collect();
+        for handle in handles {
+            if let Err(e) = handle.set_low_power(enabled).await {
+                tracing::warn!("Failed to change a session's power mode: {}", e);
+            }
         }
     }
 
@@ -48,6 +147,18 @@ impl<D: Persistence> SessionManager<D> {
         &self,
         fen: Option<String>,
         game_mode: GameMode,
+    ) -> Result<SessionSnapshot, String> {
+        self.create_labeled_session(fen, game_mode, SessionLabels::default())
+            .await
+    }
+
+    /// Create a session with labels (simul id, opponent name) that are saved
+    /// with the finished game so related games can be reported on together.
+    pub async fn create_labeled_session(
+        &self,
+        fen: Option<String>,
+        game_mode: GameMode,
+        labels: SessionLabels,
     ) -> Result<SessionSnapshot, String> {
         let session_id = Uuid::new_v4().to_string();
         let game = match fen {
@@ -58,27 +169,62 @@ impl<D: Persistence> SessionManager<D> {
         let (cmd_tx, cmd_rx) = mpsc::channel(32);
         let (event_tx, _) = broadcast::channel(100);
 
-        let state = SessionState::new(session_id.clone(), game, game_mode);
+        let mut state = SessionState::new(session_id.clone(), game, game_mode);
+        state.move_delay_ms = crate::config::get_engine_move_delay_ms();
+        state.labels = labels;
+        state.engine_budget = Arc::clone(&self.engine_budget);
+        state.low_power = self.low_power.load(Ordering::Relaxed);
         let initial_snapshot = state.snapshot();
 
         let event_tx_clone = event_tx.clone();
-        tokio::spawn(async move {
+        let task = tokio::spawn(async move {
             run_session_actor(state, cmd_rx, event_tx_clone).await;
         });
 
-        let handle = SessionHandle::new(cmd_tx);
+        let handle = SessionHandle::new(cmd_tx).with_task(task.abort_handle());
         self.sessions.write().await.insert(session_id, handle);
 
         Ok(initial_snapshot)
     }
 
-    pub async fn get_handle(&self, session_id: &str) -> Result<SessionHandle, String> {
+    pub async fn get_handle(&self, session_id: &str) -> Result<SessionHandle, SessionError> {
         self.sessions
             .read()
             .await
             .get(session_id)
             .cloned()
-            .ok_or_else(|| format!("Session not found: {}", session_id))
+            .ok_or_else(|| SessionError::NotFound(session_id.to_string()))
+    }
+
+    /// Open sessions with their engine usage, sorted by session ID.
+    pub async fn list_active_sessions(&self) -> Vec<ActiveSession> {
+        let handles: Vec<(String, SessionHandle)> = self
+            .sessions
+            .read()
+            .await
+            .iter()
+            .map(|(id, handle)| (id.clone(), handle.clone()))
+            .collect();
+
+        let mut active = Vec::with_capacity(handles.len());
+        for (session_id, handle) in handles {
+            // A session closed while we were asking is simply left out
+            let (Ok(snapshot), Ok(usage)) =
+                (handle.get_snapshot().await, handle.resource_usage().await)
+            else {
+                continue;
+            };
+            active.push(ActiveSession {
+                session_id,
+                game_mode: snapshot.game_mode,
+                move_count: snapshot.move_count,
+                age: handle.age(),
+                engine_running: usage.threads > 0,
+                usage,
+            });
+        }
+        active.sort_by(|a, b| a.session_id.cmp(&b.session_id));
+        active
     }
 
     /// Close a session. If the game ended, saves it to the finished game store
@@ -90,28 +236,151 @@ impl<D: Persistence> SessionManager<D> {
             .await
             .remove(session_id)
             .ok_or_else(|| format!("Session not found: {}", session_id))?;
+        self.transfers.write().await.revoke(session_id);
 
         // Save finished game data if the game reached the Ended phase.
         // GamePhase::Ended is the source of truth — the starting position is irrelevant.
         // The review worker handles any starting position (it analyzes using per-ply FENs).
-        let saved_game_id = if let Ok(snapshot) = handle.get_snapshot().await {
+        let mut saved_game_id = None;
+        if let Ok(snapshot) = handle.get_snapshot().await {
             if let GamePhase::Ended {
                 ref result,
                 ref reason,
             } = snapshot.phase
             {
-                self.save_finished_game(&snapshot, result, reason).await
-            } else {
-                None
+                saved_game_id = self.save_finished_game(&snapshot, result, reason).await;
             }
-        } else {
-            None
-        };
+            let report = DebugReport {
+                snapshot,
+                game_id: saved_game_id.clone(),
+                uci_transcript: handle.uci_transcript().await.unwrap_or_default(),
+            };
+            let mut closed = self.recently_closed.write().await;
+            if closed.len() == RECENTLY_CLOSED_KEPT {
+                closed.pop_front();
+            }
+            closed.push_back(report);
+        }
 
         handle.shutdown().await;
         Ok(saved_game_id)
     }
 
+    /// Close or suspend a session on an operator's behalf. A session that
+    /// does not answer within `timeout` is taken to be stuck: it is removed
+    /// and its actor aborted, and neither a finished game nor a suspended
+    /// session is saved.
+    pub async fn force_close_session(
+        &self,
+        session_id: &str,
+        suspend: bool,
+        timeout: Duration,
+    ) -> Result<ForcedClose, SessionError> {
+        let handle = self.get_handle(session_id).await?;
+        let closing = async {
+            if suspend {
+                self.suspend_session(session_id)
+                    .await
+                    .map(|suspended_id| ForcedClose::Suspended { suspended_id })
+            } else {
+                self.close_session(session_id)
+                    .await
+                    .map(|game_id| ForcedClose::Closed { game_id })
+            }
+        };
+        match tokio::time::timeout(timeout, closing).await {
+            Ok(result) => result.map_err(SessionError::Internal),
+            Err(_) => {
+                tracing::warn!(session_id, "Session did not answer, aborting its actor");
+                self.sessions.write().await.remove(session_id);
+                handle.abort();
+                Ok(ForcedClose::Aborted)
+            }
+        }
+    }
+
+    /// Issue a code another client can claim the session with, replacing any
+    /// earlier code for it.
+    pub async fn create_transfer(&self, session_id: &str) -> Result<String, SessionError> {
+        self.get_handle(session_id).await?;
+        Ok(self
+            .transfers
+            .write()
+            .await
+            .issue(session_id, Instant::now()))
+    }
+
+    /// Hand the session a code was issued for to the client claiming it. The
+    /// event stream of the client that had it ends, leaving the session
+    /// open for the claiming client to stream; its current state is returned.
+    pub async fn claim_transfer(&self, code: &str) -> Result<SessionSnapshot, SessionError> {
+        let session_id = self
+            .transfers
+            .write()
+            .await
+            .claim(code, Instant::now())
+            .ok_or(SessionError::InvalidTransferCode)?;
+        let handle = self.get_handle(&session_id).await?;
+        let snapshot = handle.get_snapshot().await?;
+        handle.hand_over();
+        tracing::info!(session_id = %session_id, "Session handed to another client");
+        Ok(snapshot)
+    }
+
+    /// The state and UCI transcript of an open or recently closed session,
+    /// looked up by session id or by the id of the game it was saved as.
+    pub async fn debug_report(&self, id: &str) -> Option<DebugReport> {
+        if let Ok(handle) = self.get_handle(id).await {
+            if let Ok(snapshot) = handle.get_snapshot().await {
+                return Some(DebugReport {
+                    snapshot,
+                    game_id: None,
+                    uci_transcript: handle.uci_transcript().await.unwrap_or_default(),
+                });
+            }
+        }
+        self.recently_closed
+            .read()
+            .await
+            .iter()
+            .rev()
+            .find(|report| {
+                report.snapshot.session_id == id || report.game_id.as_deref() == Some(id)
+            })
+            .cloned()
+    }
+
+    /// Send a chat message to the clients of a session and save it with the
+    /// session's chat. A failed save is logged; the message is still sent.
+    pub async fn send_chat(
+        &self,
+        session_id: &str,
+        author: &str,
+        text: &str,
+    ) -> Result<ChatMessage, SessionError> {
+        let handle = self.get_handle(session_id).await?;
+        let message = handle.send_chat(author, text).await?;
+        let data = ChatMessageData {
+            session_id: session_id.to_string(),
+            seq: message.seq,
+            author: message.author.clone(),
+            text: message.text.clone(),
+            sent_at: message.sent_at,
+        };
+        if let Err(e) = self.chat_store.save_chat_message(&data).await {
+            tracing::warn!(session_id, "Failed to save chat message: {}", e);
+        }
+        Ok(message)
+    }
+
+    /// The saved chat of an open or closed session, oldest first.
+    pub async fn chat_history(&self, session_id: &str) -> Result<Vec<ChatMessageData>, String> {
+        self.chat_store
+            .list_chat_messages(session_id)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
     /// Persist a finished game's move history for post-game review.
     /// Returns the game_id if saved successfully.
     async fn save_finished_game(
@@ -126,21 +395,7 @@ impl<D: Persistence> SessionManager<D> {
             chess::GameResult::Draw => "Draw",
         };
 
-        let game_mode_str = match &snapshot.game_mode {
-            GameMode::HumanVsHuman => "HumanVsHuman".to_string(),
-            GameMode::HumanVsEngine { human_side } => format!("HumanVsEngine:{:?}", human_side),
-            GameMode::EngineVsEngine => "EngineVsEngine".to_string(),
-            GameMode::Analysis => "Analysis".to_string(),
-            GameMode::Review => "Review".to_string(),
-        };
-
-        let human_side = match &snapshot.game_mode {
-            GameMode::HumanVsEngine { human_side } => Some(match human_side {
-                PlayerSide::White => "white".to_string(),
-                PlayerSide::Black => "black".to_string(),
-            }),
-            _ => None,
-        };
+        let (game_mode_str, human_side) = snapshot.game_mode.to_stored();
 
         let skill_level = snapshot
             .engine_config
@@ -160,6 +415,8 @@ impl<D: Persistence> SessionManager<D> {
                 san: m.san.clone(),
                 fen_after: m.fen_after.clone(),
                 clock_ms: m.clock_ms,
+                engine_telemetry: m.engine_telemetry.clone(),
+                think_ms: m.think_ms,
             })
             .collect();
 
@@ -174,11 +431,18 @@ impl<D: Persistence> SessionManager<D> {
             move_count: snapshot.move_count as u32,
             moves,
             created_at: persistence::now_timestamp(),
+            simul_id: snapshot.labels.simul_id.clone(),
+            opponent: snapshot.labels.opponent.clone(),
+            derived_result: None,
         };
 
         match self.finished_game_store.save_game(&data).await {
             Ok(()) => {
                 tracing::info!(game_id = %data.game_id, "Saved finished game for review");
+                if let Some(ref profile) = snapshot.labels.profile {
+                    self.record_activity(profile, ActivityKind::GamePlayed)
+                        .await;
+                }
                 Some(data.game_id)
             }
             Err(e) => {
@@ -188,26 +452,109 @@ impl<D: Persistence> SessionManager<D> {
         }
     }
 
-    /// Suspend a session — server owns all state, client just passes session_id.
-    pub async fn suspend_session(&self, session_id: &str) -> Result<String, String> {
-        let handle = self.get_handle(session_id).await?;
-        let snapshot = handle.get_snapshot().await.map_err(|e| e.to_string())?;
+    /// Count an activity for `profile` today. Failures are logged, not returned,
+    /// since the activity log must never block the action being counted.
+    pub async fn record_activity(&self, profile: &str, kind: ActivityKind) {
+        let today = persistence::day_of(persistence::now_timestamp());
+        if let Err(e) = self
+            .activity_store
+            .record_activity(profile, today, kind)
+            .await
+        {
+            tracing::warn!(profile = %profile, "Failed to record activity: {}", e);
+        }
+    }
 
-        let game_mode_str = match &snapshot.game_mode {
-            GameMode::HumanVsHuman => "HumanVsHuman".to_string(),
-            GameMode::HumanVsEngine { human_side } => format!("HumanVsEngine:{:?}", human_side),
-            GameMode::EngineVsEngine => "EngineVsEngine".to_string(),
-            GameMode::Analysis => "Analysis".to_string(),
-            GameMode::Review => "Review".to_string(),
+    /// Activity of `profile` over the last `days` days (0 for the default window).
+    pub async fn activity_summary(
+        &self,
+        profile: &str,
+        days: u32,
+    ) -> Result<ActivitySummary, String> {
+        let window = if days == 0 {
+            activity::DEFAULT_WINDOW_DAYS
+        } else {
+            days
         };
+        let today = persistence::day_of(persistence::now_timestamp());
+        let since = today.saturating_sub(window - 1);
+        let days = self
+            .activity_store
+            .list_activity(profile, since)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(ActivitySummary::new(days, today))
+    }
 
-        let human_side = match &snapshot.game_mode {
-            GameMode::HumanVsEngine { human_side } => Some(match human_side {
-                PlayerSide::White => "white".to_string(),
-                PlayerSide::Black => "black".to_string(),
-            }),
-            _ => None,
+    /// Activity of `profile` from `since_day` up to today, summed into one entry.
+    pub async fn activity_totals(
+        &self,
+        profile: &str,
+        since_day: u32,
+    ) -> Result<ActivityDay, String> {
+        let days = self
+            .activity_store
+            .list_activity(profile, since_day)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(days
+            .into_iter()
+            .fold(ActivityDay::new(since_day), |mut total, d| {
+                total.games_played += d.games_played;
+                total.puzzles_solved += d.puzzles_solved;
+                total.reviews_read += d.reviews_read;
+                total
+            }))
+    }
+
+    /// Set a new goal for `profile`.
+    pub async fn add_goal(
+        &self,
+        profile: &str,
+        metric: GoalMetric,
+        target: f64,
+        period: GoalPeriod,
+    ) -> Result<GoalData, String> {
+        goals::validate_target(metric, target)?;
+        let goal = GoalData {
+            goal_id: persistence::generate_goal_id(),
+            profile: profile.to_string(),
+            metric,
+            target,
+            period,
+            created_at: persistence::now_timestamp(),
         };
+        self.goal_store
+            .save_goal(&goal)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(goal)
+    }
+
+    /// Goals of `profile`, oldest first.
+    pub async fn list_goals(&self, profile: &str) -> Result<Vec<GoalData>, String> {
+        self.goal_store
+            .list_goals(profile)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn delete_goal(&self, profile: &str, goal_id: &str) -> Result<(), String> {
+        self.goal_store
+            .delete_goal(profile, goal_id)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Suspend a session — server owns all state, client just passes session_id.
+    pub async fn suspend_session(&self, session_id: &str) -> Result<String, String> {
+        let handle = self
+            .get_handle(session_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        let snapshot = handle.get_snapshot().await.map_err(|e| e.to_string())?;
+
+        let (game_mode_str, human_side) = snapshot.game_mode.to_stored();
 
         let skill_level = snapshot
             .engine_config
@@ -224,6 +571,8 @@ impl<D: Persistence> SessionManager<D> {
             human_side,
             skill_level,
             created_at: persistence::now_timestamp(),
+            name: None,
+            source_game: None,
         };
 
         self.store
@@ -243,21 +592,7 @@ impl<D: Persistence> SessionManager<D> {
             .map_err(|e| e.to_string())?
             .ok_or_else(|| format!("Suspended session not found: {}", suspended_id))?;
 
-        let game_mode = if data.game_mode.starts_with("HumanVsEngine") {
-            let human_side = if data.human_side.as_deref() == Some("black") {
-                PlayerSide::Black
-            } else {
-                PlayerSide::White
-            };
-            GameMode::HumanVsEngine { human_side }
-        } else {
-            match data.game_mode.as_str() {
-                "EngineVsEngine" => GameMode::EngineVsEngine,
-                "Analysis" => GameMode::Analysis,
-                "Review" => GameMode::Review,
-                _ => GameMode::HumanVsHuman,
-            }
-        };
+        let game_mode = GameMode::from_stored(&data.game_mode, data.human_side.as_deref());
 
         let snapshot = self.create_session(Some(data.fen), game_mode).await?;
         self.store
@@ -278,15 +613,56 @@ impl<D: Persistence> SessionManager<D> {
             .map_err(|e| e.to_string())
     }
 
-    /// Save a snapshot directly as a suspended session (from review mode, no active session).
+    /// Save a live session's position as a named snapshot without
+    /// interrupting it. The name is made up from the opponent, the move
+    /// number and the time.
+    pub async fn quick_snapshot(&self, session_id: &str) -> Result<SuspendedSessionData, String> {
+        let handle = self
+            .get_handle(session_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        let snapshot = handle.get_snapshot().await.map_err(|e| e.to_string())?;
+
+        let (game_mode_str, human_side) = snapshot.game_mode.to_stored();
+        let skill_level = snapshot
+            .engine_config
+            .as_ref()
+            .map(|c| c.skill_level)
+            .unwrap_or(0);
+        let created_at = persistence::now_timestamp();
+
+        let data = SuspendedSessionData {
+            suspended_id: persistence::generate_suspended_id(),
+            name: Some(snapshot_name(&snapshot, created_at)),
+            source_game: Some(session_id.to_string()),
+            fen: snapshot.fen,
+            side_to_move: snapshot.side_to_move,
+            move_count: snapshot.move_count as u32,
+            game_mode: game_mode_str,
+            human_side,
+            skill_level,
+            created_at,
+        };
+
+        self.store
+            .save_session(&data)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(data)
+    }
+
+    /// Save a snapshot directly as a suspended session (from review mode, no
+    /// active session). `source_game` is the game the position comes from.
+    #[allow(clippy::too_many_arguments)]
     pub async fn save_snapshot(
         &self,
         fen: &str,
-        _name: &str,
+        name: &str,
         game_mode: &str,
         human_side: Option<String>,
         move_count: u32,
         skill_level: u8,
+        source_game: Option<String>,
     ) -> Result<String, String> {
         // Validate the FEN
         let board: cozy_chess::Board = fen.parse().map_err(|_| format!("Invalid FEN: {}", fen))?;
@@ -304,6 +680,8 @@ impl<D: Persistence> SessionManager<D> {
             human_side,
             skill_level,
             created_at: persistence::now_timestamp(),
+            name: Some(name.trim().to_string()).filter(|name| !name.is_empty()),
+            source_game,
         };
 
         self.store
@@ -344,10 +722,43 @@ impl<D: Persistence> SessionManager<D> {
     }
 }
 
+/// Name for a quick snapshot: who the game is against, the move number and
+/// the time in UTC, e.g. `vs Stocky 1500, move 12, 2026-10-16 14:03`.
+fn snapshot_name(snapshot: &SessionSnapshot, created_at: u64) -> String {
+    let opponent = match (&snapshot.labels.opponent, &snapshot.game_mode) {
+        (Some(name), _) => format!("vs {}", name),
+        (None, GameMode::HumanVsEngine { .. }) => match &snapshot.engine_config {
+            Some(config) => format!("vs engine level {}", config.skill_level),
+            None => "vs engine".to_string(),
+        },
+        (None, GameMode::HumanVsHuman) => "Human vs Human".to_string(),
+        (None, GameMode::EngineVsEngine) => "Engine vs Engine".to_string(),
+        (None, GameMode::Analysis) => "Analysis".to_string(),
+        (None, GameMode::Review) => "Review".to_string(),
+    };
+    let move_number = snapshot
+        .fen
+        .parse::<cozy_chess::Board>()
+        .map(|board| u32::from(board.fullmove_number()))
+        .unwrap_or(snapshot.move_count as u32 / 2 + 1);
+    let seconds = created_at % 86_400;
+    format!(
+        "{}, move {}, {} {:02}:{:02}",
+        opponent,
+        move_number,
+        persistence::iso_date(persistence::day_of(created_at)),
+        seconds / 3600,
+        seconds / 60 % 60
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::persistence::{FinishedGameStore, JsonPersistence, PositionStore, SessionStore};
+    use crate::persistence::{
+        ActivityStore, ChatStore, FinishedGameStore, GoalStore, JsonPersistence, PositionStore,
+        SessionStore,
+    };
     use std::sync::Arc;
 
     fn test_manager() -> SessionManager<JsonPersistence> {
@@ -360,10 +771,20 @@ mod tests {
         let store = SessionStore::new(dir.path().to_path_buf());
         let position_store = PositionStore::new(dir.path().to_path_buf(), None);
         let finished_game_store = Arc::new(FinishedGameStore::new(dir.path().to_path_buf()));
+        let activity_store = ActivityStore::new(dir.path().to_path_buf());
+        let goal_store = GoalStore::new(dir.path().to_path_buf());
+        let chat_store = ChatStore::new(dir.path().to_path_buf());
         // Leak the TempDir so it lives for the test duration.
         // (Tests are short-lived so this is fine.)
         std::mem::forget(dir);
-        let mgr = SessionManager::new(store, position_store, finished_game_store.clone());
+        let mgr = SessionManager::new(
+            store,
+            position_store,
+            finished_game_store.clone(),
+            activity_store,
+            goal_store,
+            chat_store,
+        );
         (mgr, finished_game_store)
     }
 
@@ -388,6 +809,128 @@ mod tests {
         assert!(mgr.get_handle(&session_id).await.is_err());
     }
 
+    #[tokio::test]
+    async fn test_list_active_sessions() {
+        let mgr = test_manager();
+        let a = mgr
+            .create_session(None, GameMode::HumanVsHuman)
+            .await
+            .unwrap();
+        let b = mgr.create_session(None, GameMode::Analysis).await.unwrap();
+
+        let active = mgr.list_active_sessions().await;
+        assert_eq!(active.len(), 2);
+        assert!(active.iter().all(|s| !s.engine_running));
+        assert!(active.iter().all(|s| s.usage == ResourceUsage::default()));
+        assert!(active.iter().all(|s| s.age < Duration::from_secs(60)));
+
+        mgr.close_session(&a.session_id).await.unwrap();
+        let active = mgr.list_active_sessions().await;
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].session_id, b.session_id);
+        assert_eq!(active[0].game_mode, GameMode::Analysis);
+    }
+
+    #[tokio::test]
+    async fn test_force_close_session() {
+        let mgr = test_manager();
+        let a = mgr
+            .create_session(None, GameMode::HumanVsHuman)
+            .await
+            .unwrap();
+        let b = mgr
+            .create_session(None, GameMode::HumanVsHuman)
+            .await
+            .unwrap();
+
+        let closed = mgr
+            .force_close_session(&a.session_id, false, FORCE_CLOSE_TIMEOUT)
+            .await
+            .unwrap();
+        assert_eq!(closed, ForcedClose::Closed { game_id: None });
+        let suspended = mgr
+            .force_close_session(&b.session_id, true, FORCE_CLOSE_TIMEOUT)
+            .await
+            .unwrap();
+        assert!(matches!(suspended, ForcedClose::Suspended { .. }));
+        assert_eq!(mgr.list_suspended().await.unwrap().len(), 1);
+        assert!(mgr.list_active_sessions().await.is_empty());
+        assert!(matches!(
+            mgr.force_close_session(&a.session_id, false, FORCE_CLOSE_TIMEOUT)
+                .await,
+            Err(SessionError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_force_close_aborts_a_stuck_session() {
+        let mgr = test_manager();
+        // An actor that never reads its commands
+        let (cmd_tx, _cmd_rx) = mpsc::channel(8);
+        mgr.sessions
+            .write()
+            .await
+            .insert("stuck".to_string(), SessionHandle::new(cmd_tx));
+
+        let closed = mgr
+            .force_close_session("stuck", true, Duration::from_millis(50))
+            .await
+            .unwrap();
+        assert_eq!(closed, ForcedClose::Aborted);
+        assert!(mgr.get_handle("stuck").await.is_err());
+        assert!(mgr.list_suspended().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_set_game_mode() {
+        let mgr = test_manager();
+        let snap = mgr
+            .create_session(None, GameMode::HumanVsHuman)
+            .await
+            .unwrap();
+        let handle = mgr.get_handle(&snap.session_id).await.unwrap();
+
+        let snap = handle.set_game_mode(GameMode::Analysis).await.unwrap();
+        assert_eq!(snap.game_mode, GameMode::Analysis);
+        let snap = handle.get_snapshot().await.unwrap();
+        assert_eq!(snap.game_mode, GameMode::Analysis);
+    }
+
+    #[tokio::test]
+    async fn test_chat_history_outlives_the_session() {
+        let mgr = test_manager();
+        let snap = mgr
+            .create_session(None, GameMode::HumanVsHuman)
+            .await
+            .unwrap();
+        let session_id = snap.session_id;
+
+        mgr.send_chat(&session_id, "ana", "hello").await.unwrap();
+        mgr.send_chat(&session_id, "ben", "hi ana").await.unwrap();
+        assert!(mgr.send_chat(&session_id, "ben", "").await.is_err());
+        mgr.close_session(&session_id).await.unwrap();
+
+        let history = mgr.chat_history(&session_id).await.unwrap();
+        let lines: Vec<(u32, &str, &str)> = history
+            .iter()
+            .map(|m| (m.seq, m.author.as_str(), m.text.as_str()))
+            .collect();
+        assert_eq!(lines, [(1, "ana", "hello"), (2, "ben", "hi ana")]);
+        assert!(matches!(
+            mgr.send_chat(&session_id, "ana", "still there?").await,
+            Err(SessionError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_session_is_not_found() {
+        let mgr = test_manager();
+        assert!(matches!(
+            mgr.get_handle("no-such-session").await,
+            Err(SessionError::NotFound(_))
+        ));
+    }
+
     #[tokio::test]
     async fn test_close_session_twice_returns_error() {
         let mgr = test_manager();
@@ -426,6 +969,33 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_claiming_a_transfer_hands_the_session_over() {
+        let mgr = test_manager();
+        let snap = mgr
+            .create_session(None, GameMode::HumanVsHuman)
+            .await
+            .unwrap();
+        let handle = mgr.get_handle(&snap.session_id).await.unwrap();
+        let owner = handle.watch_owner();
+
+        let code = mgr.create_transfer(&snap.session_id).await.unwrap();
+        let claimed = mgr.claim_transfer(&code).await.unwrap();
+        assert_eq!(claimed.session_id, snap.session_id);
+        assert!(owner.has_changed().unwrap());
+        assert_eq!(handle.owner_generation(), 1);
+
+        // A code is good for one claim
+        assert!(matches!(
+            mgr.claim_transfer(&code).await,
+            Err(SessionError::InvalidTransferCode)
+        ));
+        assert!(matches!(
+            mgr.create_transfer("no-such-session").await,
+            Err(SessionError::NotFound(_))
+        ));
+    }
+
     /// Test the cleanup guard pattern: dropping a session handle via close_session
     /// from an Arc<SessionManager> (simulates what the CleanupGuard does).
     #[tokio::test]
@@ -587,6 +1157,127 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_debug_report_outlives_the_session() {
+        let mgr = test_manager();
+        let snap = mgr
+            .create_session(None, GameMode::HumanVsHuman)
+            .await
+            .unwrap();
+        let session_id = snap.session_id.clone();
+        let handle = mgr.get_handle(&session_id).await.unwrap();
+
+        let open = mgr.debug_report(&session_id).await.unwrap();
+        assert_eq!(open.game_id, None);
+
+        play_fools_mate(&handle).await;
+        let game_id = mgr.close_session(&session_id).await.unwrap().unwrap();
+
+        let by_session = mgr.debug_report(&session_id).await.unwrap();
+        assert_eq!(by_session.snapshot.move_count, 4);
+        assert_eq!(by_session.game_id.as_deref(), Some(game_id.as_str()));
+        assert!(mgr.debug_report(&game_id).await.is_some());
+        assert!(mgr.debug_report("missing").await.is_none());
+    }
+
+    /// Labels given at creation are saved with the finished game.
+    #[tokio::test]
+    async fn test_labels_saved_with_finished_game() {
+        let (mgr, finished_store) = test_manager_with_store();
+        let labels = SessionLabels {
+            simul_id: Some("simul_1".to_string()),
+            opponent: Some("Stocky 1500".to_string()),
+            profile: None,
+        };
+        let snap = mgr
+            .create_labeled_session(None, GameMode::HumanVsHuman, labels.clone())
+            .await
+            .unwrap();
+        assert_eq!(snap.labels, labels);
+        let handle = mgr.get_handle(&snap.session_id).await.unwrap();
+
+        play_fools_mate(&handle).await;
+        mgr.close_session(&snap.session_id).await.unwrap();
+
+        let game = &finished_store.list().unwrap()[0];
+        assert_eq!(game.simul_id, labels.simul_id);
+        assert_eq!(game.opponent, labels.opponent);
+    }
+
+    /// A finished game counts towards its profile's activity for today.
+    #[tokio::test]
+    async fn test_finished_game_recorded_as_activity() {
+        let mgr = test_manager();
+        let labels = SessionLabels {
+            profile: Some("alice".to_string()),
+            ..Default::default()
+        };
+        let snap = mgr
+            .create_labeled_session(None, GameMode::HumanVsHuman, labels)
+            .await
+            .unwrap();
+        let handle = mgr.get_handle(&snap.session_id).await.unwrap();
+
+        play_fools_mate(&handle).await;
+        mgr.close_session(&snap.session_id).await.unwrap();
+
+        let summary = mgr.activity_summary("alice", 0).await.unwrap();
+        assert_eq!(summary.days.len(), 1);
+        assert_eq!(summary.days[0].day, summary.today);
+        assert_eq!(summary.days[0].games_played, 1);
+        assert_eq!(summary.current_streak, 1);
+        assert!(mgr
+            .activity_summary("bob", 0)
+            .await
+            .unwrap()
+            .days
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_goals_are_per_profile() {
+        let mgr = test_manager();
+        let goal = mgr
+            .add_goal("alice", GoalMetric::GamesPlayed, 20.0, GoalPeriod::Month)
+            .await
+            .unwrap();
+        assert!(mgr
+            .add_goal(
+                "alice",
+                GoalMetric::AverageAccuracy,
+                120.0,
+                GoalPeriod::Week
+            )
+            .await
+            .is_err());
+
+        assert_eq!(mgr.list_goals("alice").await.unwrap(), vec![goal.clone()]);
+        assert!(mgr.list_goals("bob").await.unwrap().is_empty());
+
+        mgr.delete_goal("alice", &goal.goal_id).await.unwrap();
+        assert!(mgr.list_goals("alice").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_activity_totals_sum_days() {
+        let mgr = test_manager();
+        mgr.record_activity("alice", ActivityKind::GamePlayed).await;
+        mgr.record_activity("alice", ActivityKind::ReviewRead).await;
+        mgr.record_activity("alice", ActivityKind::GamePlayed).await;
+
+        let totals = mgr.activity_totals("alice", 0).await.unwrap();
+        assert_eq!(totals.games_played, 2);
+        assert_eq!(totals.reviews_read, 1);
+        let today = persistence::day_of(persistence::now_timestamp());
+        assert_eq!(
+            mgr.activity_totals("alice", today + 1)
+                .await
+                .unwrap()
+                .total(),
+            0
+        );
+    }
+
     /// Closing a non-finished game should NOT save to the finished game store.
     #[tokio::test]
     async fn test_close_ongoing_game_does_not_save() {
@@ -689,6 +1380,7 @@ mod tests {
                 Some("white".to_string()),
                 10,
                 5,
+                Some("game_1".to_string()),
             )
             .await;
         assert!(result.is_ok());
@@ -700,13 +1392,46 @@ mod tests {
         assert_eq!(suspended[0].move_count, 10);
         assert_eq!(suspended[0].skill_level, 5);
         assert_eq!(suspended[0].side_to_move, "white");
+        assert_eq!(suspended[0].name.as_deref(), Some("Test Snapshot"));
+        assert_eq!(suspended[0].source_game.as_deref(), Some("game_1"));
+    }
+
+    #[tokio::test]
+    async fn test_quick_snapshot_keeps_the_session_running() {
+        let mgr = test_manager();
+        let snap = mgr
+            .create_session(None, GameMode::HumanVsHuman)
+            .await
+            .unwrap();
+        let handle = mgr.get_handle(&snap.session_id).await.unwrap();
+        handle
+            .make_move(cozy_chess::Move {
+                from: cozy_chess::Square::E2,
+                to: cozy_chess::Square::E4,
+                promotion: None,
+            })
+            .await
+            .unwrap();
+
+        let data = mgr.quick_snapshot(&snap.session_id).await.unwrap();
+        assert_eq!(data.source_game.as_deref(), Some(snap.session_id.as_str()));
+        assert!(data
+            .name
+            .as_deref()
+            .is_some_and(|name| name.starts_with("Human vs Human, move 1, ")));
+        assert_eq!(data.move_count, 1);
+
+        // The game goes on and the snapshot is listed
+        assert!(mgr.get_handle(&snap.session_id).await.is_ok());
+        let suspended = mgr.list_suspended().await.unwrap();
+        assert_eq!(suspended, vec![data]);
     }
 
     #[tokio::test]
     async fn test_save_snapshot_invalid_fen_fails() {
         let mgr = test_manager();
         let result = mgr
-            .save_snapshot("not a valid fen", "Bad", "HumanVsHuman", None, 0, 0)
+            .save_snapshot("not a valid fen", "Bad", "HumanVsHuman", None, 0, 0, None)
             .await;
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("Invalid FEN"));
@@ -718,7 +1443,7 @@ mod tests {
         // A normal (non-terminal) position after 1. e4 e5
         let fen = "rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2";
         let suspended_id = mgr
-            .save_snapshot(fen, "Test", "HumanVsHuman", None, 2, 0)
+            .save_snapshot(fen, "Test", "HumanVsHuman", None, 2, 0, None)
             .await
             .unwrap();
 
@@ -737,7 +1462,7 @@ mod tests {
         // Fool's mate final position — checkmate, White to move but no legal moves
         let checkmate_fen = "rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3";
         let suspended_id = mgr
-            .save_snapshot(checkmate_fen, "Checkmate", "HumanVsHuman", None, 4, 0)
+            .save_snapshot(checkmate_fen, "Checkmate", "HumanVsHuman", None, 4, 0, None)
             .await
             .unwrap();
 
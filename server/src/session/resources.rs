@@ -0,0 +1,216 @@
+//! Per-session engine resource accounting and server-wide engine caps.
+
+use std::sync::{Arc, Mutex};
+
+use super::commands::SessionError;
+
+/// Hash Stockfish allocates when none is configured.
+pub const DEFAULT_ENGINE_HASH_MB: u32 = 16;
+
+/// Engine resources a session has used and currently holds.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResourceUsage {
+    /// Completed engine searches.
+    pub searches: u64,
+    /// Nodes searched over all searches.
+    pub nodes: u64,
+    /// Engine CPU time, estimated as search time times threads.
+    pub cpu_time_ms: u64,
+    /// Hash held by the running engine; 0 when none is running.
+    pub hash_mb: u32,
+    /// Threads of the running engine; 0 when none is running.
+    pub threads: u32,
+}
+
+/// Accumulates a session's engine usage from the engine's search info.
+///
+/// Stockfish reports nodes and time cumulatively within one search, so the
+/// latest figures are held until the search's bestmove and only then added.
+#[derive(Debug, Default)]
+pub struct ResourceMeter {
+    usage: ResourceUsage,
+    search_nodes: u64,
+    search_time_ms: u64,
+}
+
+impl ResourceMeter {
+    pub fn observe_info(&mut self, nodes: Option<u64>, time_ms: Option<u64>) {
+        if let Some(nodes) = nodes {
+            self.search_nodes = self.search_nodes.max(nodes);
+        }
+        if let Some(time_ms) = time_ms {
+            self.search_time_ms = self.search_time_ms.max(time_ms);
+        }
+    }
+
+    /// Add the search that just answered with a bestmove.
+    pub fn finish_search(&mut self) {
+        self.usage.searches += 1;
+        self.usage.nodes += self.search_nodes;
+        self.usage.cpu_time_ms += self.search_time_ms * u64::from(self.usage.threads.max(1));
+        self.search_nodes = 0;
+        self.search_time_ms = 0;
+    }
+
+    pub fn engine_started(&mut self, hash_mb: u32, threads: u32) {
+        self.usage.hash_mb = hash_mb;
+        self.usage.threads = threads;
+    }
+
+    pub fn engine_stopped(&mut self) {
+        self.usage.hash_mb = 0;
+        self.usage.threads = 0;
+        self.search_nodes = 0;
+        self.search_time_ms = 0;
+    }
+
+    pub fn usage(&self) -> ResourceUsage {
+        self.usage.clone()
+    }
+}
+
+/// Optional server-wide caps on engine processes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EngineLimits {
+    /// Most sessions that may run an engine at once.
+    pub max_engine_sessions: Option<u32>,
+    /// Most hash, in MB, all session engines may hold together.
+    pub max_total_hash_mb: Option<u32>,
+}
+
+#[derive(Debug, Default)]
+struct BudgetTotals {
+    engines: u32,
+    hash_mb: u32,
+}
+
+/// Engines and hash handed out to sessions, checked against the limits.
+#[derive(Debug)]
+pub struct EngineBudget {
+    limits: EngineLimits,
+    totals: Mutex<BudgetTotals>,
+}
+
+impl EngineBudget {
+    pub fn new(limits: EngineLimits) -> Arc<Self> {
+        Arc::new(Self {
+            limits,
+            totals: Mutex::new(BudgetTotals::default()),
+        })
+    }
+
+    pub fn limits(&self) -> EngineLimits {
+        self.limits
+    }
+
+    /// Engines running and hash held across all sessions.
+    pub fn totals(&self) -> (u32, u32) {
+        let totals = self.totals.lock().unwrap();
+        (totals.engines, totals.hash_mb)
+    }
+
+    /// Reserve an engine with `hash_mb` of hash, if the limits allow it.
+    /// The reservation is given back when the lease is dropped.
+    pub fn acquire(self: &Arc<Self>, hash_mb: u32) -> Result<EngineLease, SessionError> {
+        let mut totals = self.totals.lock().unwrap();
+        if let Some(max) = self.limits.max_engine_sessions {
+            if totals.engines >= max {
+                return Err(SessionError::ResourceLimit(format!(
+                    "{} engine sessions already running (limit {})",
+                    totals.engines, max
+                )));
+            }
+        }
+        if let Some(max) = self.limits.max_total_hash_mb {
+            if totals.hash_mb + hash_mb > max {
+                return Err(SessionError::ResourceLimit(format!(
+                    "{} MB hash requested but only {} MB of {} MB is free",
+                    hash_mb,
+                    max.saturating_sub(totals.hash_mb),
+                    max
+                )));
+            }
+        }
+        totals.engines += 1;
+        totals.hash_mb += hash_mb;
+        Ok(EngineLease {
+            budget: Arc::clone(self),
+            hash_mb,
+        })
+    }
+}
+
+/// A session's share of the engine budget, held while its engine runs.
+#[derive(Debug)]
+pub struct EngineLease {
+    budget: Arc<EngineBudget>,
+    hash_mb: u32,
+}
+
+impl Drop for EngineLease {
+    fn drop(&mut self) {
+        let mut totals = self.budget.totals.lock().unwrap();
+        totals.engines = totals.engines.saturating_sub(1);
+        totals.hash_mb = totals.hash_mb.saturating_sub(self.hash_mb);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_meter_adds_each_search_once() {
+        let mut meter = ResourceMeter::default();
+        meter.engine_started(64, 2);
+        meter.observe_info(Some(1_000), Some(10));
+        meter.observe_info(Some(5_000), Some(50));
+        meter.finish_search();
+        meter.observe_info(Some(2_000), Some(20));
+        meter.finish_search();
+
+        let usage = meter.usage();
+        assert_eq!(usage.searches, 2);
+        assert_eq!(usage.nodes, 7_000);
+        assert_eq!(usage.cpu_time_ms, 140);
+        assert_eq!(usage.hash_mb, 64);
+
+        meter.engine_stopped();
+        let usage = meter.usage();
+        assert_eq!((usage.hash_mb, usage.threads), (0, 0));
+        assert_eq!(usage.nodes, 7_000);
+    }
+
+    #[test]
+    fn test_budget_enforces_limits() {
+        let budget = EngineBudget::new(EngineLimits {
+            max_engine_sessions: Some(2),
+            max_total_hash_mb: Some(256),
+        });
+
+        let first = budget.acquire(128).unwrap();
+        assert!(matches!(
+            budget.acquire(200),
+            Err(SessionError::ResourceLimit(_))
+        ));
+        let _second = budget.acquire(128).unwrap();
+        assert!(matches!(
+            budget.acquire(1),
+            Err(SessionError::ResourceLimit(_))
+        ));
+        assert_eq!(budget.totals(), (2, 256));
+
+        drop(first);
+        assert_eq!(budget.totals(), (1, 128));
+        assert!(budget.acquire(64).is_ok());
+    }
+
+    #[test]
+    fn test_unlimited_budget() {
+        let budget = EngineBudget::new(EngineLimits::default());
+        let leases: Vec<_> = (0..10).map(|_| budget.acquire(2048).unwrap()).collect();
+        assert_eq!(budget.totals(), (10, 20_480));
+        drop(leases);
+        assert_eq!(budget.totals(), (0, 0));
+    }
+}
@@ -1,4 +1,5 @@
-use chess::{EngineAnalysis, GameMode, GamePhase};
+use analysis::board_analysis::TacticalTag;
+use chess::{EngineAnalysis, EngineMoveTelemetry, GameMode, GamePhase};
 
 use super::commands::EngineConfig;
 
@@ -16,10 +17,37 @@ pub struct SessionSnapshot {
     pub move_count: usize,
     pub history: Vec<MoveRecord>,
     pub last_move: Option<(String, String)>,
+    /// Undone moves that can be redone; a move other than the next one
+    /// drops them.
+    pub redo_depth: usize,
+    /// From and to squares of the move the next redo plays.
+    pub next_redo: Option<(String, String)>,
     pub engine_config: Option<EngineConfig>,
     pub analysis: Option<EngineAnalysis>,
     pub engine_thinking: bool,
     pub timer: Option<TimerSnapshot>,
+    /// Minimum delay between engine moves.
+    pub move_delay_ms: u64,
+    /// Whether engine-vs-engine moves wait for an explicit step.
+    pub step_mode: bool,
+    /// Whether live tactical hints are being computed.
+    pub tactical_hints_enabled: bool,
+    /// Tactical patterns available to the side to move.
+    pub tactical_hints: Vec<TacticalTag>,
+    /// Labels given at creation, saved with the finished game.
+    pub labels: SessionLabels,
+}
+
+/// Labels given to a session when it is created and saved with the finished
+/// game, so games can later be grouped by simul or by named opponent.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionLabels {
+    /// Simul this session is one board of.
+    pub simul_id: Option<String>,
+    /// Name of the engine opponent profile played against.
+    pub opponent: Option<String>,
+    /// Player profile the finished game counts towards in the activity log.
+    pub profile: Option<String>,
 }
 
 /// A single move in the history.
@@ -33,6 +61,10 @@ pub struct MoveRecord {
     pub san: String,
     pub fen_after: String,
     pub clock_ms: Option<u64>,
+    /// Final search info for engine-played moves.
+    pub engine_telemetry: Option<EngineMoveTelemetry>,
+    /// Time taken over the move, pauses excluded.
+    pub think_ms: Option<u64>,
 }
 
 /// Timer state for the client to render.
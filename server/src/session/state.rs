@@ -1,13 +1,25 @@
+use analysis::board_analysis::{detect_tactics, AttackMap, TacticalContext, TacticalTag};
 use chess::{
-    format_color, format_piece_upper, format_square, EngineAnalysis, Game, GameMode, GamePhase,
-    GameResult, HistoryEntry, PlayerSide,
+    format_color, format_piece_upper, format_square, EngineAnalysis, EngineMoveTelemetry, Game,
+    GameMode, GamePhase, GameResult, HistoryEntry, PlayerSide,
 };
 use cozy_chess::Move;
-use engine::{EngineCommand, EngineEvent, GoParams, StockfishEngine};
-use std::time::Instant;
+use engine::{EngineCommand, EngineEvent, GoParams, PolyglotBook, StockfishEngine};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
 
-use super::commands::{EngineConfig, SessionError};
-use super::snapshot::{MoveRecord, SessionSnapshot, TimerSnapshot};
+use super::chat::ChatLog;
+use super::commands::{EngineConfig, SessionError, DEFAULT_BOOK_PLIES};
+use super::resources::{EngineBudget, EngineLease, EngineLimits, ResourceMeter};
+use super::snapshot::{MoveRecord, SessionLabels, SessionSnapshot, TimerSnapshot};
+use super::transcript::UciTranscript;
+
+/// Live tactical hints kept per position; callouts beyond this are noise for spectators.
+const MAX_TACTICAL_HINTS: usize = 3;
+
+/// Raw engine events buffered per tap before a slow consumer starts missing them.
+const ENGINE_TAP_CAPACITY: usize = 256;
 
 /// Internal mutable state, owned entirely by the session actor. No locks.
 pub(crate) struct SessionState {
@@ -18,12 +30,64 @@ pub(crate) struct SessionState {
     pub game_mode: GameMode,
     pub engine: Option<StockfishEngine>,
     pub engine_config: Option<EngineConfig>,
+    /// Opening book the engine plays from for the first plies of the game.
+    pub book: Option<PolyglotBook>,
+    /// The server is in low-power mode: the engine runs with fewer threads
+    /// and less hash than configured.
+    pub low_power: bool,
+    /// Share of the server's engine budget held while `engine` runs.
+    pub engine_lease: Option<EngineLease>,
+    /// Budget engines are leased from, shared by all sessions.
+    pub engine_budget: Arc<EngineBudget>,
+    /// Engine usage accumulated over the session.
+    pub resources: ResourceMeter,
+    /// Latest UCI lines exchanged with the engine, for bug reports.
+    pub uci_transcript: UciTranscript,
+    /// Latest chat between the clients, replayed to ones that subscribe later.
+    pub chat: ChatLog,
+    /// Raw engine events for external consumers attached with `AttachEngineTap`.
+    pub engine_tap: broadcast::Sender<EngineEvent>,
     pub analysis: Option<EngineAnalysis>,
     pub engine_thinking: bool,
     pub timer: Option<TimerState>,
     /// Per-move clock data: remaining time (ms) for the player who made each move.
     /// Parallel to game.history().
     pub move_clock_data: Vec<Option<u64>>,
+    /// Per-move engine search telemetry; `None` for moves not played by the engine.
+    /// Parallel to game.history().
+    pub move_telemetry: Vec<Option<EngineMoveTelemetry>>,
+    /// Per-move think time (ms): time since the previous move, pauses excluded.
+    /// Parallel to game.history().
+    pub move_think_ms: Vec<Option<u64>>,
+    /// Time spent paused since the last move, left out of the next think time.
+    pub paused_since_move: Duration,
+    /// When the current pause began.
+    pub paused_at: Option<tokio::time::Instant>,
+    /// Bestmoves still owed by searches stopped on pause; they are discarded on arrival.
+    pub stale_bestmoves: u32,
+    /// Minimum time between consecutive moves when the engine is to play.
+    pub move_delay_ms: u64,
+    /// When the last move was applied (engine move delay is measured from here).
+    pub last_move_at: tokio::time::Instant,
+    /// Engine move held back until the move delay has elapsed.
+    pub pending_engine_move: Option<PendingEngineMove>,
+    /// Step-through mode: the engine only moves when a step is requested.
+    pub step_mode: bool,
+    /// Whether a requested step has not yet been used to trigger the engine.
+    pub step_requested: bool,
+    /// Whether tactical hints are computed for each new position.
+    pub tactical_hints_enabled: bool,
+    /// Tactical patterns available to the side to move in the current position.
+    pub tactical_hints: Vec<TacticalTag>,
+    /// Labels given at creation, saved with the finished game.
+    pub labels: SessionLabels,
+}
+
+/// An engine bestmove waiting for the move delay to elapse.
+pub(crate) struct PendingEngineMove {
+    pub mv: Move,
+    pub telemetry: Option<EngineMoveTelemetry>,
+    pub due: tokio::time::Instant,
 }
 
 /// Server-owned timer state.
@@ -86,10 +150,7 @@ impl TimerState {
         TimerSnapshot {
             white_remaining_ms: self.white_remaining_ms,
             black_remaining_ms: self.black_remaining_ms,
-            active_side: self.active_side.map(|s| match s {
-                PlayerSide::White => "white".to_string(),
-                PlayerSide::Black => "black".to_string(),
-            }),
+            active_side: self.active_side.map(|s| s.as_str().to_string()),
         }
     }
 
@@ -110,10 +171,31 @@ impl SessionState {
             game_mode,
             engine: None,
             engine_config: None,
+            book: None,
+            low_power: false,
+            engine_lease: None,
+            engine_budget: EngineBudget::new(EngineLimits::default()),
+            resources: ResourceMeter::default(),
+            uci_transcript: UciTranscript::default(),
+            chat: ChatLog::default(),
+            engine_tap: broadcast::channel(ENGINE_TAP_CAPACITY).0,
             analysis: None,
             engine_thinking: false,
             timer: None,
             move_clock_data: Vec::new(),
+            move_telemetry: Vec::new(),
+            move_think_ms: Vec::new(),
+            paused_since_move: Duration::ZERO,
+            paused_at: None,
+            stale_bestmoves: 0,
+            move_delay_ms: 0,
+            last_move_at: tokio::time::Instant::now(),
+            pending_engine_move: None,
+            step_mode: false,
+            step_requested: false,
+            tactical_hints_enabled: false,
+            tactical_hints: Vec::new(),
+            labels: SessionLabels::default(),
         }
     }
 
@@ -126,7 +208,9 @@ impl SessionState {
             .enumerate()
             .map(|(i, entry)| {
                 let clock_ms = self.move_clock_data.get(i).copied().flatten();
-                history_entry_to_record(entry, clock_ms)
+                let telemetry = self.move_telemetry.get(i).cloned().flatten();
+                let think_ms = self.move_think_ms.get(i).copied().flatten();
+                history_entry_to_record(entry, clock_ms, telemetry, think_ms)
             })
             .collect();
 
@@ -147,10 +231,20 @@ impl SessionState {
             move_count: self.game.history().len(),
             history,
             last_move,
+            redo_depth: self.game.redo_depth(),
+            next_redo: self
+                .game
+                .next_redo()
+                .map(|e| (format_square(e.from), format_square(e.to))),
             engine_config: self.engine_config.clone(),
             analysis: self.analysis.clone(),
             engine_thinking: self.engine_thinking,
             timer: self.timer.as_ref().map(|t| t.to_snapshot()),
+            move_delay_ms: self.move_delay_ms,
+            step_mode: self.step_mode,
+            tactical_hints_enabled: self.tactical_hints_enabled,
+            tactical_hints: self.tactical_hints.clone(),
+            labels: self.labels.clone(),
         }
     }
 
@@ -162,14 +256,47 @@ impl SessionState {
         }
     }
 
+    /// Pass a raw engine event on to attached taps. Nothing is cloned while
+    /// no one is listening.
+    pub fn tap_engine_event(&self, event: &EngineEvent) {
+        if self.engine_tap.receiver_count() > 0 {
+            let _ = self.engine_tap.send(event.clone());
+        }
+    }
+
     pub fn timer_active(&self) -> bool {
         self.timer.as_ref().is_some_and(|t| t.is_active())
     }
 
     pub fn apply_move(&mut self, mv: Move) -> Result<SessionSnapshot, SessionError> {
-        self.game
-            .make_move(mv)
-            .map_err(|e| SessionError::IllegalMove(e.to_string()))?;
+        self.apply_move_with_telemetry(mv, None)
+    }
+
+    /// Apply an engine-played move, recording what the engine saw when it chose it.
+    pub fn apply_engine_move(
+        &mut self,
+        mv: Move,
+        telemetry: Option<EngineMoveTelemetry>,
+    ) -> Result<SessionSnapshot, SessionError> {
+        self.apply_move_with_telemetry(mv, telemetry)
+    }
+
+    fn apply_move_with_telemetry(
+        &mut self,
+        mv: Move,
+        telemetry: Option<EngineMoveTelemetry>,
+    ) -> Result<SessionSnapshot, SessionError> {
+        if self.is_paused() {
+            return Err(SessionError::InvalidPhaseTransition(
+                "Cannot move while paused".to_string(),
+            ));
+        }
+        self.game.make_move(mv).map_err(|e| match e {
+            chess::GameError::IllegalMoveExplained(reason) => {
+                SessionError::IllegalMove(reason.to_string())
+            }
+            e => SessionError::IllegalMove(e.to_string()),
+        })?;
         self.phase = GamePhase::from_game(&self.game);
         self.analysis = None;
 
@@ -193,24 +320,115 @@ impl SessionState {
             PlayerSide::Black => t.black_remaining_ms,
         });
         self.move_clock_data.push(clock);
+        self.move_telemetry.push(telemetry);
+        let think_ms = self
+            .last_move_at
+            .elapsed()
+            .saturating_sub(self.paused_since_move)
+            .as_millis() as u64;
+        self.move_think_ms.push(Some(think_ms));
+        self.paused_since_move = Duration::ZERO;
+        self.last_move_at = tokio::time::Instant::now();
+        self.refresh_tactical_hints();
 
         Ok(self.snapshot())
     }
 
+    /// Stop counting think time while the game is paused.
+    pub fn start_pause(&mut self) {
+        self.paused_at = Some(tokio::time::Instant::now());
+    }
+
+    /// Count think time again after a pause.
+    pub fn end_pause(&mut self) {
+        if let Some(at) = self.paused_at.take() {
+            self.paused_since_move += at.elapsed();
+        }
+    }
+
+    /// When an engine move found now may be played, or `None` if it can be played immediately.
+    pub fn engine_move_due(&self) -> Option<tokio::time::Instant> {
+        // A step was explicitly asked for; don't make the user wait twice
+        if self.step_mode {
+            return None;
+        }
+        let due = self.last_move_at + Duration::from_millis(self.move_delay_ms);
+        (due > tokio::time::Instant::now()).then_some(due)
+    }
+
+    /// Change the move delay, rescheduling any engine move already waiting.
+    pub fn set_move_delay(&mut self, delay_ms: u64) {
+        self.move_delay_ms = delay_ms;
+        let due = self.last_move_at + Duration::from_millis(delay_ms);
+        if let Some(ref mut pending) = self.pending_engine_move {
+            pending.due = due;
+        }
+    }
+
+    /// Drop a held-back engine move; the engine is no longer considered thinking.
+    pub fn cancel_pending_engine_move(&mut self) -> bool {
+        if self.pending_engine_move.take().is_some() {
+            self.engine_thinking = false;
+            return true;
+        }
+        false
+    }
+
+    /// Outside step-through mode always true; inside it, only once a step was requested.
+    pub fn step_allows_engine_move(&self) -> bool {
+        !self.step_mode || self.step_requested
+    }
+
+    /// Turn live tactical hints on or off, computing them for the current position.
+    pub fn set_tactical_hints(&mut self, enabled: bool) {
+        self.tactical_hints_enabled = enabled;
+        self.refresh_tactical_hints();
+    }
+
+    fn refresh_tactical_hints(&mut self) {
+        self.tactical_hints = if self.tactical_hints_enabled {
+            detect_position_hints(self.game.position())
+        } else {
+            Vec::new()
+        };
+    }
+
+    pub fn is_paused(&self) -> bool {
+        matches!(self.phase, GamePhase::Paused { .. })
+    }
+
+    /// Recompute the phase from the game, staying paused if the session was paused.
+    fn refresh_phase(&mut self) {
+        self.phase = match (&self.phase, GamePhase::from_game(&self.game)) {
+            (GamePhase::Paused { .. }, GamePhase::Playing { turn }) => {
+                GamePhase::Paused { resume_turn: turn }
+            }
+            (_, phase) => phase,
+        };
+    }
+
     pub fn apply_undo(&mut self) -> Result<SessionSnapshot, SessionError> {
         self.game.undo().map_err(|_| SessionError::NothingToUndo)?;
-        self.phase = GamePhase::from_game(&self.game);
+        self.cancel_pending_engine_move();
+        self.refresh_phase();
         self.analysis = None;
         self.engine_thinking = false;
         self.move_clock_data.pop();
+        self.move_telemetry.pop();
+        self.move_think_ms.pop();
+        self.refresh_tactical_hints();
         Ok(self.snapshot())
     }
 
     pub fn apply_redo(&mut self) -> Result<SessionSnapshot, SessionError> {
         self.game.redo().map_err(|_| SessionError::NothingToRedo)?;
-        self.phase = GamePhase::from_game(&self.game);
+        self.cancel_pending_engine_move();
+        self.refresh_phase();
         self.analysis = None;
         self.move_clock_data.push(None); // original timing lost
+        self.move_telemetry.push(None);
+        self.move_think_ms.push(None);
+        self.refresh_tactical_hints();
         Ok(self.snapshot())
     }
 
@@ -221,12 +439,19 @@ impl SessionState {
             }
             None => Game::new(),
         };
+        self.cancel_pending_engine_move();
         self.game = new_game;
         self.start_fen = self.game.to_fen();
-        self.phase = GamePhase::from_game(&self.game);
+        self.refresh_phase();
         self.analysis = None;
         self.engine_thinking = false;
         self.move_clock_data.clear();
+        self.move_telemetry.clear();
+        self.move_think_ms.clear();
+        // The first move's think time starts now
+        self.paused_since_move = Duration::ZERO;
+        self.last_move_at = tokio::time::Instant::now();
+        self.refresh_tactical_hints();
         Ok(self.snapshot())
     }
 
@@ -247,7 +472,8 @@ impl SessionState {
         }
 
         match &self.game_mode {
-            GameMode::EngineVsEngine => true,
+            // In step-through mode each engine move needs an explicit step
+            GameMode::EngineVsEngine => self.step_allows_engine_move(),
             GameMode::HumanVsEngine { human_side } => {
                 let current = PlayerSide::from(self.game.side_to_move());
                 current != *human_side
@@ -256,8 +482,42 @@ impl SessionState {
         }
     }
 
+    /// A move from the opening book for the current position, while the game
+    /// is within the book's depth.
+    pub fn book_move(&self) -> Option<Move> {
+        let book = self.book.as_ref()?;
+        let max_plies = self
+            .engine_config
+            .as_ref()
+            .and_then(|c| c.book_max_plies)
+            .unwrap_or(DEFAULT_BOOK_PLIES);
+        if self.game.history().len() >= max_plies as usize {
+            return None;
+        }
+        let seed = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.subsec_nanos().into());
+        book.pick(self.game.position(), seed)
+    }
+
     /// Trigger engine move calculation. Called internally by the actor.
     pub async fn trigger_engine(&mut self) -> Result<(), SessionError> {
+        // Book moves skip the search and go through the move delay like any
+        // other engine move
+        if let Some(mv) = self.book_move().filter(|_| self.engine.is_some()) {
+            tracing::debug!("Playing book move {:?}", mv);
+            self.pending_engine_move = Some(PendingEngineMove {
+                mv,
+                telemetry: None,
+                due: self
+                    .engine_move_due()
+                    .unwrap_or_else(tokio::time::Instant::now),
+            });
+            self.engine_thinking = true;
+            self.step_requested = false;
+            return Ok(());
+        }
+
         let engine = self
             .engine
             .as_ref()
@@ -304,9 +564,16 @@ impl SessionState {
             .map_err(|e| SessionError::Internal(e.to_string()))?;
 
         self.engine_thinking = true;
+        self.step_requested = false;
         Ok(())
     }
 
+    /// Give the engine's share of the budget back once its process is gone.
+    pub fn release_engine(&mut self) {
+        self.engine_lease = None;
+        self.resources.engine_stopped();
+    }
+
     /// Shut down the engine process if the game has ended.
     pub async fn shutdown_engine_if_ended(&mut self) {
         if matches!(self.phase, GamePhase::Ended { .. }) {
@@ -314,7 +581,9 @@ impl SessionState {
                 tracing::info!("Game ended, shutting down engine");
                 let _ = engine.shutdown().await;
             }
+            self.release_engine();
             self.engine_thinking = false;
+            self.stale_bestmoves = 0;
         }
     }
 
@@ -340,7 +609,12 @@ impl SessionState {
     }
 }
 
-fn history_entry_to_record(entry: &HistoryEntry, clock_ms: Option<u64>) -> MoveRecord {
+fn history_entry_to_record(
+    entry: &HistoryEntry,
+    clock_ms: Option<u64>,
+    engine_telemetry: Option<EngineMoveTelemetry>,
+    think_ms: Option<u64>,
+) -> MoveRecord {
     MoveRecord {
         from: format_square(entry.from),
         to: format_square(entry.to),
@@ -350,9 +624,28 @@ fn history_entry_to_record(entry: &HistoryEntry, clock_ms: Option<u64>) -> MoveR
         san: entry.san.clone(),
         fen_after: entry.fen.clone(),
         clock_ms,
+        engine_telemetry,
+        think_ms,
     }
 }
 
+/// Run the tactical detectors on a position from the point of view of the side to move.
+fn detect_position_hints(board: &cozy_chess::Board) -> Vec<TacticalTag> {
+    let attacks = AttackMap::compute(board);
+    let ctx = TacticalContext {
+        before: board,
+        after: board,
+        mv: None,
+        side_to_move_before: board.side_to_move(),
+        before_attacks: &attacks,
+        after_attacks: &attacks,
+        eval_before: None,
+        eval_after: None,
+        best_line: None,
+    };
+    detect_tactics(&ctx, Some(MAX_TACTICAL_HINTS))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -385,6 +678,192 @@ mod tests {
         assert_eq!(snap.last_move, Some(("e2".into(), "e4".into())));
     }
 
+    #[test]
+    fn test_book_move_within_book_depth() {
+        let mut state = test_state();
+        // A one-entry book: e2e4 from the start position
+        let mut bytes = engine::book::polyglot_key(state.game.position())
+            .to_be_bytes()
+            .to_vec();
+        bytes.extend(0x031Cu16.to_be_bytes());
+        bytes.extend(1u16.to_be_bytes());
+        bytes.extend(0u32.to_be_bytes());
+        state.book = Some(PolyglotBook::from_bytes(&bytes).unwrap());
+
+        assert_eq!(
+            state.book_move().map(|mv| mv.to_string()),
+            Some("e2e4".into())
+        );
+
+        state.engine_config = Some(EngineConfig {
+            enabled: true,
+            skill_level: 10,
+            threads: None,
+            hash_mb: None,
+            multipv: None,
+            book_path: None,
+            book_max_plies: Some(0),
+        });
+        assert_eq!(state.book_move(), None);
+    }
+
+    #[test]
+    fn test_engine_move_records_telemetry() {
+        let mut state = test_state();
+        let e4 = Move {
+            from: Square::new(File::E, Rank::Second),
+            to: Square::new(File::E, Rank::Fourth),
+            promotion: None,
+        };
+        let telemetry = EngineMoveTelemetry {
+            depth: Some(18),
+            score: Some(chess::AnalysisScore::Centipawns(31)),
+            nodes: Some(250_000),
+            time_ms: Some(500),
+        };
+        let snap = state
+            .apply_engine_move(e4, Some(telemetry.clone()))
+            .unwrap();
+        assert_eq!(snap.history[0].engine_telemetry, Some(telemetry));
+
+        let snap = state.apply_undo().unwrap();
+        assert!(snap.history.is_empty());
+        assert!(state.move_telemetry.is_empty());
+
+        let snap = state.apply_move(e4).unwrap();
+        assert_eq!(snap.history[0].engine_telemetry, None);
+    }
+
+    #[test]
+    fn test_think_time_leaves_out_pauses() {
+        let mut state = test_state();
+        let e4 = Move {
+            from: Square::new(File::E, Rank::Second),
+            to: Square::new(File::E, Rank::Fourth),
+            promotion: None,
+        };
+        state.last_move_at = tokio::time::Instant::now() - Duration::from_millis(5_000);
+        state.paused_since_move = Duration::from_millis(3_000);
+        let snap = state.apply_move(e4).unwrap();
+        let think_ms = snap.history[0].think_ms.unwrap();
+        assert!((2_000..3_000).contains(&think_ms));
+        assert_eq!(state.paused_since_move, Duration::ZERO);
+
+        // A redone move's timing is lost
+        state.apply_undo().unwrap();
+        let snap = state.apply_redo().unwrap();
+        assert_eq!(snap.history[0].think_ms, None);
+    }
+
+    #[test]
+    fn test_snapshot_exposes_redo_depth() {
+        let mut state = test_state();
+        let e4 = Move {
+            from: Square::new(File::E, Rank::Second),
+            to: Square::new(File::E, Rank::Fourth),
+            promotion: None,
+        };
+        assert_eq!(state.apply_move(e4).unwrap().redo_depth, 0);
+
+        let snap = state.apply_undo().unwrap();
+        assert_eq!(snap.redo_depth, 1);
+        assert_eq!(snap.next_redo, Some(("e2".to_string(), "e4".to_string())));
+
+        let snap = state.apply_redo().unwrap();
+        assert_eq!(snap.redo_depth, 0);
+        assert_eq!(snap.next_redo, None);
+    }
+
+    #[test]
+    fn test_paused_session_rejects_moves_and_stays_paused() {
+        let mut state = test_state();
+        let e4 = Move {
+            from: Square::new(File::E, Rank::Second),
+            to: Square::new(File::E, Rank::Fourth),
+            promotion: None,
+        };
+        state.apply_move(e4).unwrap();
+        state.phase = GamePhase::Paused {
+            resume_turn: cozy_chess::Color::Black,
+        };
+
+        let e5 = Move {
+            from: Square::new(File::E, Rank::Seventh),
+            to: Square::new(File::E, Rank::Fifth),
+            promotion: None,
+        };
+        assert!(matches!(
+            state.apply_move(e5),
+            Err(SessionError::InvalidPhaseTransition(_))
+        ));
+
+        let snap = state.apply_undo().unwrap();
+        assert!(matches!(
+            snap.phase,
+            GamePhase::Paused {
+                resume_turn: cozy_chess::Color::White
+            }
+        ));
+    }
+
+    #[test]
+    fn test_engine_move_delay() {
+        let mut state = test_state();
+        assert!(state.engine_move_due().is_none());
+
+        state.set_move_delay(1000);
+        state.last_move_at = tokio::time::Instant::now();
+        let due = state.engine_move_due().unwrap();
+        assert_eq!(due - state.last_move_at, Duration::from_millis(1000));
+
+        // The delay is measured from the last move, not from when bestmove arrived
+        state.last_move_at = tokio::time::Instant::now() - Duration::from_millis(1500);
+        assert!(state.engine_move_due().is_none());
+    }
+
+    #[test]
+    fn test_step_mode_gates_engine_vs_engine() {
+        let mut state =
+            SessionState::new("test".to_string(), Game::new(), GameMode::EngineVsEngine);
+        assert!(state.step_allows_engine_move());
+
+        state.step_mode = true;
+        assert!(!state.step_allows_engine_move());
+
+        state.step_requested = true;
+        assert!(state.step_allows_engine_move());
+
+        // Stepped moves skip the watchability delay
+        state.set_move_delay(1000);
+        assert!(state.engine_move_due().is_none());
+    }
+
+    #[test]
+    fn test_tactical_hints_follow_position() {
+        // Black knight on d5 is attacked by the f3 bishop and undefended
+        let game = Game::from_fen("4k3/8/8/3n4/8/5B2/8/4K3 w - - 0 1").unwrap();
+        let mut state = SessionState::new("test".to_string(), game, GameMode::HumanVsHuman);
+        assert!(state.tactical_hints.is_empty());
+
+        state.set_tactical_hints(true);
+        assert!(state
+            .tactical_hints
+            .iter()
+            .any(|t| t.kind == analysis::board_analysis::TacticalTagKind::HangingPiece));
+        assert!(state.snapshot().tactical_hints_enabled);
+
+        // Hints are recomputed after every move and cleared when disabled
+        let bxd5 = Move {
+            from: Square::new(File::F, Rank::Third),
+            to: Square::new(File::D, Rank::Fifth),
+            promotion: None,
+        };
+        state.apply_move(bxd5).unwrap();
+        assert!(state.tactical_hints.len() <= MAX_TACTICAL_HINTS);
+        state.set_tactical_hints(false);
+        assert!(state.tactical_hints.is_empty());
+    }
+
     #[test]
     fn test_auto_trigger_human_vs_human() {
         let state = test_state();
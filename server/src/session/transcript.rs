@@ -0,0 +1,57 @@
+//! The most recent UCI lines a session exchanged with its engine, kept for
+//! bug reports (`chesstty report`).
+
+use std::collections::VecDeque;
+
+use super::events::UciLogEntry;
+
+/// UCI lines kept per session; older lines are dropped first.
+pub const TRANSCRIPT_LINES: usize = 500;
+
+#[derive(Debug, Clone, Default)]
+pub struct UciTranscript {
+    lines: VecDeque<UciLogEntry>,
+}
+
+impl UciTranscript {
+    pub fn push(&mut self, entry: UciLogEntry) {
+        if self.lines.len() == TRANSCRIPT_LINES {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(entry);
+    }
+
+    /// The kept lines, oldest first.
+    pub fn lines(&self) -> Vec<UciLogEntry> {
+        self.lines.iter().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::UciDirection;
+
+    fn entry(message: String) -> UciLogEntry {
+        UciLogEntry {
+            direction: UciDirection::FromEngine,
+            message,
+            context: None,
+        }
+    }
+
+    #[test]
+    fn test_transcript_keeps_the_latest_lines() {
+        let mut transcript = UciTranscript::default();
+        for i in 0..TRANSCRIPT_LINES + 2 {
+            transcript.push(entry(format!("info depth {}", i)));
+        }
+        let lines = transcript.lines();
+        assert_eq!(lines.len(), TRANSCRIPT_LINES);
+        assert_eq!(lines[0].message, "info depth 2");
+        assert_eq!(
+            lines.last().unwrap().message,
+            format!("info depth {}", TRANSCRIPT_LINES + 1)
+        );
+    }
+}
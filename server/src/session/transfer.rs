@@ -0,0 +1,113 @@
+//! Moving a session to another client. The client playing the session asks
+//! for a short code, the other client claims the session with it, and the
+//! event stream of the first client ends without closing the session.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use uuid::Uuid;
+
+/// How long a transfer code can be claimed.
+pub const TRANSFER_CODE_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// Characters in a transfer code.
+pub const TRANSFER_CODE_LEN: usize = 6;
+
+/// Letters and digits of a code, without ones easily mistaken for each
+/// other (0/O, 1/I/L).
+const CODE_ALPHABET: &[u8] = b"ABCDEFGHJKMNPQRSTUVWXYZ23456789";
+
+#[derive(Debug, Clone)]
+struct PendingTransfer {
+    session_id: String,
+    expires_at: Instant,
+}
+
+/// Codes issued for sessions and not yet claimed. A session has at most one
+/// code; asking again replaces it.
+#[derive(Debug, Default)]
+pub struct TransferCodes {
+    pending: HashMap<String, PendingTransfer>,
+}
+
+impl TransferCodes {
+    /// Issue a code for `session_id`, valid for [`TRANSFER_CODE_TTL`].
+    pub fn issue(&mut self, session_id: &str, now: Instant) -> String {
+        self.prune(now);
+        self.pending.retain(|_, t| t.session_id != session_id);
+        let code = loop {
+            let code = new_code();
+            if !self.pending.contains_key(&code) {
+                break code;
+            }
+        };
+        self.pending.insert(
+            code.clone(),
+            PendingTransfer {
+                session_id: session_id.to_string(),
+                expires_at: now + TRANSFER_CODE_TTL,
+            },
+        );
+        code
+    }
+
+    /// The session a code was issued for, once; `None` for an unknown or
+    /// expired code. Case and surrounding spaces are ignored.
+    pub fn claim(&mut self, code: &str, now: Instant) -> Option<String> {
+        self.prune(now);
+        let code = code.trim().to_ascii_uppercase();
+        self.pending.remove(&code).map(|t| t.session_id)
+    }
+
+    /// Forget the code of a session that has closed.
+    pub fn revoke(&mut self, session_id: &str) {
+        self.pending.retain(|_, t| t.session_id != session_id);
+    }
+
+    fn prune(&mut self, now: Instant) {
+        self.pending.retain(|_, t| t.expires_at > now);
+    }
+}
+
+fn new_code() -> String {
+    Uuid::new_v4().as_bytes()[..TRANSFER_CODE_LEN]
+        .iter()
+        .map(|b| CODE_ALPHABET[*b as usize % CODE_ALPHABET.len()] as char)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_code_is_claimed_once() {
+        let mut codes = TransferCodes::default();
+        let now = Instant::now();
+        let code = codes.issue("session_1", now);
+        assert_eq!(code.len(), TRANSFER_CODE_LEN);
+        assert!(code.bytes().all(|b| CODE_ALPHABET.contains(&b)));
+
+        let typed = format!(" {} ", code.to_ascii_lowercase());
+        assert_eq!(codes.claim(&typed, now), Some("session_1".to_string()));
+        assert_eq!(codes.claim(&code, now), None);
+    }
+
+    #[test]
+    fn test_new_code_replaces_the_old_and_codes_expire() {
+        let mut codes = TransferCodes::default();
+        let now = Instant::now();
+        let old = codes.issue("session_1", now);
+        let new = codes.issue("session_1", now);
+        if old != new {
+            assert_eq!(codes.claim(&old, now), None);
+        }
+
+        let later = now + TRANSFER_CODE_TTL;
+        assert_eq!(codes.claim(&new, later), None);
+
+        let code = codes.issue("session_2", now);
+        codes.revoke("session_2");
+        assert_eq!(codes.claim(&code, now), None);
+    }
+}
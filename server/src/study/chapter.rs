@@ -0,0 +1,380 @@
+//! Chapters: move trees written by hand, from a position or a finished game.
+//!
+//! Nodes are addressed by their id, which stays the same when other nodes
+//! are added or deleted. Moves are checked against the position they are
+//! played from and stored with the SAN, UCI and FEN a move played on this
+//! server would have.
+
+use chess::converters::parse_square;
+use chess::Game;
+use cozy_chess::Move;
+
+use crate::import::pgn::find_move;
+use crate::persistence::{Arrow, ChapterNode, FinishedGameData, StudyChapter};
+
+/// Chapters one study holds, as on Lichess.
+pub const MAX_CHAPTERS: usize = 64;
+/// Nodes of one chapter, the root included.
+pub const MAX_CHAPTER_NODES: usize = 2000;
+pub const MAX_COMMENT_CHARS: usize = 2000;
+pub const MAX_ARROWS: usize = 32;
+
+/// Why a chapter could not be made or edited.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ChapterError {
+    #[error("Invalid FEN: {0}")]
+    InvalidFen(String),
+    #[error("No node {0} in the chapter")]
+    NoSuchNode(u32),
+    #[error("Illegal or ambiguous move {0}")]
+    IllegalMove(String),
+    #[error("The root of a chapter cannot be deleted")]
+    DeleteRoot,
+    #[error("A chapter holds at most {} moves", MAX_CHAPTER_NODES - 1)]
+    TooManyNodes,
+    #[error("A study holds at most {} chapters", MAX_CHAPTERS)]
+    TooManyChapters,
+    #[error("Invalid annotation: {0}")]
+    InvalidAnnotation(String),
+}
+
+/// What the author wrote about a position, replacing what was there.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Annotation {
+    pub comment: Option<String>,
+    pub nags: Vec<u32>,
+    pub arrows: Vec<Arrow>,
+}
+
+impl StudyChapter {
+    /// A chapter with no moves yet, starting from `fen`.
+    pub fn from_position(
+        chapter_id: String,
+        name: String,
+        fen: &str,
+    ) -> Result<Self, ChapterError> {
+        let game =
+            Game::from_fen(fen.trim()).map_err(|_| ChapterError::InvalidFen(fen.to_string()))?;
+        Ok(Self {
+            chapter_id,
+            name,
+            game_id: None,
+            nodes: vec![ChapterNode {
+                fen: game.to_fen(),
+                ..Default::default()
+            }],
+        })
+    }
+
+    /// A chapter whose main line is `game`, cut off at [`MAX_CHAPTER_NODES`].
+    pub fn from_game(chapter_id: String, name: String, game: &FinishedGameData) -> Self {
+        let mut nodes = vec![ChapterNode {
+            fen: game.start_fen.clone(),
+            ..Default::default()
+        }];
+        for mv in game.moves.iter().take(MAX_CHAPTER_NODES - 1) {
+            let id = nodes.len() as u32;
+            nodes[id as usize - 1].children.push(id);
+            let promotion = mv.promotion.as_deref().unwrap_or_default();
+            nodes.push(ChapterNode {
+                id,
+                parent: Some(id - 1),
+                san: mv.san.clone(),
+                uci: format!("{}{}{}", mv.from, mv.to, promotion.to_lowercase()),
+                fen: mv.fen_after.clone(),
+                ..Default::default()
+            });
+        }
+        Self {
+            chapter_id,
+            name,
+            game_id: Some(game.game_id.clone()),
+            nodes,
+        }
+    }
+
+    pub fn node(&self, id: u32) -> Option<&ChapterNode> {
+        self.nodes.iter().find(|n| n.id == id)
+    }
+
+    fn node_mut(&mut self, id: u32) -> Result<&mut ChapterNode, ChapterError> {
+        self.nodes
+            .iter_mut()
+            .find(|n| n.id == id)
+            .ok_or(ChapterError::NoSuchNode(id))
+    }
+
+    /// Play `notation`, in UCI or SAN, from node `parent` and return the
+    /// id of the node it leads to. A move already in the tree is not added
+    /// twice; a new one becomes the last variation.
+    pub fn add_move(&mut self, parent: u32, notation: &str) -> Result<u32, ChapterError> {
+        let node = self.node(parent).ok_or(ChapterError::NoSuchNode(parent))?;
+        let illegal = || ChapterError::IllegalMove(notation.to_string());
+        let mut game =
+            Game::from_fen(&node.fen).map_err(|_| ChapterError::InvalidFen(node.fen.clone()))?;
+        let legal = game.legal_moves();
+        let notation = notation.trim();
+        let mv = match notation.parse::<Move>() {
+            Ok(mv) => chess::convert_uci_castling_to_cozy(mv, &legal),
+            Err(_) => find_move(game.position(), &legal, notation).ok_or_else(illegal)?,
+        };
+        let entry = game.make_move(mv).map_err(|_| illegal())?;
+        let uci = chess::format_uci_move(mv);
+
+        if let Some(&existing) = node
+            .children
+            .iter()
+            .find(|&&id| self.node(id).is_some_and(|child| child.uci == uci))
+        {
+            return Ok(existing);
+        }
+        if self.nodes.len() >= MAX_CHAPTER_NODES {
+            return Err(ChapterError::TooManyNodes);
+        }
+
+        let id = self.nodes.iter().map(|n| n.id).max().unwrap_or(0) + 1;
+        self.node_mut(parent)?.children.push(id);
+        self.nodes.push(ChapterNode {
+            id,
+            parent: Some(parent),
+            san: entry.san,
+            uci,
+            fen: entry.fen,
+            ..Default::default()
+        });
+        Ok(id)
+    }
+
+    /// Delete node `id` and every move after it, returning its parent.
+    pub fn delete_node(&mut self, id: u32) -> Result<u32, ChapterError> {
+        let parent = self
+            .node(id)
+            .ok_or(ChapterError::NoSuchNode(id))?
+            .parent
+            .ok_or(ChapterError::DeleteRoot)?;
+        self.node_mut(parent)?.children.retain(|&child| child != id);
+
+        let mut doomed = vec![id];
+        let mut i = 0;
+        while let Some(&next) = doomed.get(i) {
+            if let Some(node) = self.node(next) {
+                doomed.extend(node.children.iter().copied());
+            }
+            i += 1;
+        }
+        self.nodes.retain(|n| !doomed.contains(&n.id));
+        Ok(parent)
+    }
+
+    /// Replace the comment, NAGs and arrows of node `id`.
+    pub fn annotate(&mut self, id: u32, annotation: Annotation) -> Result<(), ChapterError> {
+        let invalid = |reason: &str| ChapterError::InvalidAnnotation(reason.to_string());
+
+        let comment = annotation
+            .comment
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty());
+        if comment
+            .as_ref()
+            .is_some_and(|c| c.chars().count() > MAX_COMMENT_CHARS)
+        {
+            return Err(invalid("comment is too long"));
+        }
+
+        let mut nags = Vec::with_capacity(annotation.nags.len());
+        for nag in annotation.nags {
+            let nag = u8::try_from(nag)
+                .ok()
+                .filter(|&n| n > 0)
+                .ok_or_else(|| invalid("NAGs run from 1 to 255"))?;
+            if !nags.contains(&nag) {
+                nags.push(nag);
+            }
+        }
+
+        if annotation.arrows.len() > MAX_ARROWS {
+            return Err(invalid("too many arrows"));
+        }
+        let mut arrows: Vec<Arrow> = Vec::with_capacity(annotation.arrows.len());
+        for arrow in annotation.arrows {
+            let from = arrow.from.trim().to_lowercase();
+            let to = arrow.to.trim().to_lowercase();
+            if parse_square(&from).is_none() || parse_square(&to).is_none() || from == to {
+                return Err(invalid("an arrow needs two different squares"));
+            }
+            if !arrows.iter().any(|a| a.from == from && a.to == to) {
+                arrows.push(Arrow { from, to, ..arrow });
+            }
+        }
+
+        let node = self.node_mut(id)?;
+        node.comment = comment;
+        node.nags = nags;
+        node.arrows = arrows;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::{ArrowColor, StoredMoveRecord};
+
+    const START: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+    fn chapter(fen: &str) -> StudyChapter {
+        StudyChapter::from_position("c1".to_string(), "Test".to_string(), fen).unwrap()
+    }
+
+    fn sans(chapter: &StudyChapter, id: u32) -> Vec<String> {
+        chapter
+            .node(id)
+            .unwrap()
+            .children
+            .iter()
+            .map(|&c| chapter.node(c).unwrap().san.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_moves_in_uci_and_san() {
+        let mut chapter = chapter(START);
+        let e4 = chapter.add_move(0, "e2e4").unwrap();
+        let c5 = chapter.add_move(e4, "c5").unwrap();
+        let e5 = chapter.add_move(e4, "e7e5").unwrap();
+        // Adding a move that is there already leads to it
+        assert_eq!(chapter.add_move(e4, "c7c5").unwrap(), c5);
+        assert_eq!(sans(&chapter, e4), ["c5", "e5"]);
+        assert_eq!(chapter.node(e5).unwrap().parent, Some(e4));
+        assert!(chapter
+            .node(c5)
+            .unwrap()
+            .fen
+            .starts_with("rnbqkbnr/pp1ppppp/8/2p5/4P3/8/PPPP1PPP/RNBQKBNR w KQkq"));
+
+        assert!(matches!(
+            chapter.add_move(e4, "e2e4"),
+            Err(ChapterError::IllegalMove(_))
+        ));
+        assert!(matches!(
+            chapter.add_move(e4, "Qxf7"),
+            Err(ChapterError::IllegalMove(_))
+        ));
+        assert_eq!(
+            chapter.add_move(99, "e4"),
+            Err(ChapterError::NoSuchNode(99))
+        );
+    }
+
+    #[test]
+    fn test_castling_in_either_notation() {
+        let mut chapter = chapter("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+        let castled = chapter.add_move(0, "e1g1").unwrap();
+        assert_eq!(chapter.node(castled).unwrap().san, "O-O");
+        assert_eq!(chapter.add_move(0, "O-O").unwrap(), castled);
+        let long = chapter.add_move(castled, "O-O-O").unwrap();
+        assert_eq!(chapter.node(long).unwrap().san, "O-O-O");
+    }
+
+    #[test]
+    fn test_delete_keeps_other_ids() {
+        let mut chapter = chapter(START);
+        let e4 = chapter.add_move(0, "e4").unwrap();
+        let e5 = chapter.add_move(e4, "e5").unwrap();
+        let nf3 = chapter.add_move(e5, "Nf3").unwrap();
+        let c5 = chapter.add_move(e4, "c5").unwrap();
+
+        assert_eq!(chapter.delete_node(e5), Ok(e4));
+        assert!(chapter.node(nf3).is_none());
+        assert_eq!(chapter.nodes.len(), 3);
+        assert_eq!(chapter.node(c5).unwrap().san, "c5");
+        assert_eq!(chapter.node(e4).unwrap().children, [c5]);
+        // A new node never reuses an id still in the tree
+        assert!(chapter.add_move(c5, "Nf3").unwrap() > c5);
+        assert_eq!(chapter.delete_node(0), Err(ChapterError::DeleteRoot));
+    }
+
+    #[test]
+    fn test_annotations_are_checked() {
+        let mut chapter = chapter(START);
+        let arrow = |from: &str, to: &str| Arrow {
+            from: from.to_string(),
+            to: to.to_string(),
+            color: ArrowColor::Green,
+        };
+        chapter
+            .annotate(
+                0,
+                Annotation {
+                    comment: Some("  The usual start  ".to_string()),
+                    nags: vec![1, 1, 14],
+                    arrows: vec![arrow("E2", "e4"), arrow("e2", "e4"), arrow("d2", "d4")],
+                },
+            )
+            .unwrap();
+        let root = chapter.node(0).unwrap();
+        assert_eq!(root.comment.as_deref(), Some("The usual start"));
+        assert_eq!(root.nags, [1, 14]);
+        assert_eq!(root.arrows, [arrow("e2", "e4"), arrow("d2", "d4")]);
+
+        let bad = [
+            Annotation {
+                nags: vec![256],
+                ..Default::default()
+            },
+            Annotation {
+                arrows: vec![arrow("e2", "e2")],
+                ..Default::default()
+            },
+            Annotation {
+                arrows: vec![arrow("e9", "e4")],
+                ..Default::default()
+            },
+        ];
+        for annotation in bad {
+            assert!(matches!(
+                chapter.annotate(0, annotation),
+                Err(ChapterError::InvalidAnnotation(_))
+            ));
+        }
+        // An empty comment clears it
+        chapter.annotate(0, Annotation::default()).unwrap();
+        assert_eq!(chapter.node(0).unwrap().comment, None);
+    }
+
+    #[test]
+    fn test_chapter_from_game() {
+        let record = |from: &str, to: &str, san: &str, promotion: Option<&str>| StoredMoveRecord {
+            from: from.to_string(),
+            to: to.to_string(),
+            promotion: promotion.map(str::to_string),
+            fen_after: format!("after {}", san),
+            ..StoredMoveRecord::sample(san)
+        };
+        let game = FinishedGameData {
+            start_fen: START.to_string(),
+            result_reason: "Resignation".to_string(),
+            game_mode: "HumanVsHuman".to_string(),
+            human_side: None,
+            move_count: 2,
+            moves: vec![
+                record("e2", "e4", "e4", None),
+                record("b2", "a1", "bxa1=Q", Some("Q")),
+            ],
+            ..FinishedGameData::sample("g1")
+        };
+        let chapter = StudyChapter::from_game("c1".to_string(), "Game".to_string(), &game);
+        assert_eq!(chapter.game_id.as_deref(), Some("g1"));
+        assert_eq!(chapter.node(1).unwrap().children, [2]);
+        assert_eq!(chapter.node(2).unwrap().uci, "b2a1q");
+        assert_eq!(chapter.node(2).unwrap().fen, "after bxa1=Q");
+    }
+
+    #[test]
+    fn test_invalid_start_position() {
+        assert!(matches!(
+            StudyChapter::from_position("c1".to_string(), "Bad".to_string(), "not a fen"),
+            Err(ChapterError::InvalidFen(_))
+        ));
+    }
+}
@@ -0,0 +1,556 @@
+//! Studies: a merged variation tree of the finished games matching a filter,
+//! e.g. all Sicilian games the human lost.
+//!
+//! Each node of the tree counts the games that played its move and their
+//! results, and carries what the reviews of those games said about the move.
+//! Only games played from the standard start position are merged, and only
+//! their first [`tree::STUDY_PLIES`] plies. The tree is stored as a snapshot.
+//!
+//! A study also holds chapters, written by hand: move trees from a position
+//! or a finished game, with comments, NAGs and arrows on their moves. A
+//! study can be made with no games to hold chapters only. It is exported as
+//! a multi-game PGN: the tree, the chapters, then each of its games.
+
+pub mod chapter;
+pub mod pgn;
+pub mod tree;
+
+use std::sync::Arc;
+
+use tokio::sync::Mutex;
+
+use crate::persistence::{
+    generate_chapter_id, generate_study_id, now_timestamp, FinishedGameData,
+    FinishedGameRepository, Persistence, ReviewRepository, StudyChapter, StudyData, StudyFilter,
+    StudyNode, StudyRepository,
+};
+use crate::review::outcome::{human_plays_white, HumanOutcome};
+use crate::review::types::{GameReview, ReviewStatus};
+
+/// Board and castling fields of the standard start position's FEN.
+const START_POSITION: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -";
+
+/// Why a chapter could not be added or edited.
+#[derive(Debug, thiserror::Error)]
+pub enum StudyError {
+    #[error("Study not found: {0}")]
+    StudyNotFound(String),
+    #[error("Chapter not found: {0}")]
+    ChapterNotFound(String),
+    #[error("Finished game not found: {0}")]
+    GameNotFound(String),
+    #[error(transparent)]
+    Chapter(#[from] chapter::ChapterError),
+    #[error("{0}")]
+    Storage(String),
+}
+
+/// What a new chapter starts from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChapterSource {
+    Position(String),
+    /// A finished game, whose moves become the main line.
+    Game(String),
+}
+
+/// Whether `game` (with its review, if any) passes `filter`.
+pub fn matches(filter: &StudyFilter, game: &FinishedGameData, review: Option<&GameReview>) -> bool {
+    if let Some(opening) = &filter.opening {
+        let wanted = opening.to_lowercase();
+        let Some(found) = review.and_then(|r| r.opening.as_ref()) else {
+            return false;
+        };
+        if !found.eco.to_lowercase().starts_with(&wanted)
+            && !found.name.to_lowercase().contains(&wanted)
+        {
+            return false;
+        }
+    }
+    if filter.outcome.is_some_and(|o| o != HumanOutcome::of(game)) {
+        return false;
+    }
+    if filter.opponent.is_some() && game.opponent != filter.opponent {
+        return false;
+    }
+    if let Some(side) = &filter.human_side {
+        if human_plays_white(game) != (side == "white") {
+            return false;
+        }
+    }
+    true
+}
+
+fn is_standard_start(fen: &str) -> bool {
+    let position: Vec<&str> = fen.split_whitespace().take(4).collect();
+    position.join(" ") == START_POSITION
+}
+
+fn chapter_index(study: &StudyData, chapter_id: &str) -> Result<usize, StudyError> {
+    study
+        .chapters
+        .iter()
+        .position(|c| c.chapter_id == chapter_id)
+        .ok_or_else(|| StudyError::ChapterNotFound(chapter_id.to_string()))
+}
+
+pub struct StudyManager<D: Persistence> {
+    finished_game_store: Arc<D::FinishedGames>,
+    review_store: Arc<D::Reviews>,
+    store: D::Studies,
+    /// Held while a chapter edit loads, changes and saves its study, so two
+    /// edits of one study do not overwrite each other.
+    edit_lock: Mutex<()>,
+}
+
+impl<D: Persistence> StudyManager<D> {
+    pub fn new(
+        finished_game_store: Arc<D::FinishedGames>,
+        review_store: Arc<D::Reviews>,
+        store: D::Studies,
+    ) -> Self {
+        Self {
+            finished_game_store,
+            review_store,
+            store,
+            edit_lock: Mutex::new(()),
+        }
+    }
+
+    /// Build and save a study of the games matching `filter`. Fails if no
+    /// game played from the standard start position matches.
+    pub async fn create(&self, name: &str, filter: StudyFilter) -> Result<StudyData, String> {
+        let mut games = Vec::new();
+        for game in self.list_games().await? {
+            if !is_standard_start(&game.start_fen) {
+                continue;
+            }
+            let review = self.load_review(&game.game_id).await?;
+            if matches(&filter, &game, review.as_ref()) {
+                games.push((game, review));
+            }
+        }
+        if games.is_empty() {
+            return Err("No finished games match the filter".to_string());
+        }
+        games.sort_by_key(|(game, _)| game.created_at);
+
+        let root = tree::build_tree(&games[0].0.start_fen, &games);
+        let study = StudyData {
+            study_id: generate_study_id(),
+            name: name.to_string(),
+            filter,
+            game_ids: games.into_iter().map(|(game, _)| game.game_id).collect(),
+            root,
+            chapters: vec![],
+            created_at: now_timestamp(),
+        };
+        self.store
+            .save_study(&study)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(study)
+    }
+
+    /// Save a study built from no games, to hold chapters.
+    pub async fn create_blank(&self, name: &str) -> Result<StudyData, String> {
+        let study = StudyData {
+            study_id: generate_study_id(),
+            name: name.to_string(),
+            filter: StudyFilter::default(),
+            game_ids: vec![],
+            root: StudyNode {
+                fen: chess::Game::new().to_fen(),
+                ..Default::default()
+            },
+            chapters: vec![],
+            created_at: now_timestamp(),
+        };
+        self.store
+            .save_study(&study)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(study)
+    }
+
+    /// Add a chapter after the study's last one.
+    pub async fn add_chapter(
+        &self,
+        study_id: &str,
+        name: &str,
+        source: ChapterSource,
+    ) -> Result<StudyChapter, StudyError> {
+        let new_chapter = match source {
+            ChapterSource::Position(fen) => {
+                StudyChapter::from_position(generate_chapter_id(), name.to_string(), &fen)?
+            }
+            ChapterSource::Game(game_id) => {
+                let game = self
+                    .finished_game_store
+                    .load_game(&game_id)
+                    .await
+                    .map_err(|e| StudyError::Storage(e.to_string()))?
+                    .ok_or(StudyError::GameNotFound(game_id))?;
+                StudyChapter::from_game(generate_chapter_id(), name.to_string(), &game)
+            }
+        };
+        self.edit(study_id, |study| {
+            if study.chapters.len() >= chapter::MAX_CHAPTERS {
+                return Err(chapter::ChapterError::TooManyChapters.into());
+            }
+            study.chapters.push(new_chapter.clone());
+            Ok(())
+        })
+        .await?;
+        Ok(new_chapter)
+    }
+
+    pub async fn delete_chapter(&self, study_id: &str, chapter_id: &str) -> Result<(), StudyError> {
+        self.edit(study_id, |study| {
+            let index = chapter_index(study, chapter_id)?;
+            study.chapters.remove(index);
+            Ok(())
+        })
+        .await
+    }
+
+    /// Move a chapter to `index` among the study's chapters, or to the end
+    /// if `index` is past it.
+    pub async fn move_chapter(
+        &self,
+        study_id: &str,
+        chapter_id: &str,
+        index: usize,
+    ) -> Result<(), StudyError> {
+        self.edit(study_id, |study| {
+            let from = chapter_index(study, chapter_id)?;
+            let moved = study.chapters.remove(from);
+            let to = index.min(study.chapters.len());
+            study.chapters.insert(to, moved);
+            Ok(())
+        })
+        .await
+    }
+
+    /// Apply `change` to a chapter and save it, returning the chapter as
+    /// saved and what `change` returned.
+    pub async fn edit_chapter<T>(
+        &self,
+        study_id: &str,
+        chapter_id: &str,
+        change: impl FnOnce(&mut StudyChapter) -> Result<T, chapter::ChapterError>,
+    ) -> Result<(StudyChapter, T), StudyError> {
+        self.edit(study_id, |study| {
+            let index = chapter_index(study, chapter_id)?;
+            let chapter = &mut study.chapters[index];
+            let value = change(chapter)?;
+            Ok((chapter.clone(), value))
+        })
+        .await
+    }
+
+    /// Load a study, change it and save it, with other edits held off.
+    async fn edit<T>(
+        &self,
+        study_id: &str,
+        change: impl FnOnce(&mut StudyData) -> Result<T, StudyError>,
+    ) -> Result<T, StudyError> {
+        let _guard = self.edit_lock.lock().await;
+        let mut study = self
+            .get(study_id)
+            .await
+            .map_err(StudyError::Storage)?
+            .ok_or_else(|| StudyError::StudyNotFound(study_id.to_string()))?;
+        let value = change(&mut study)?;
+        self.store
+            .save_study(&study)
+            .await
+            .map_err(|e| StudyError::Storage(e.to_string()))?;
+        Ok(value)
+    }
+
+    /// All studies, newest first.
+    pub async fn list(&self) -> Result<Vec<StudyData>, String> {
+        self.store.list_studies().await.map_err(|e| e.to_string())
+    }
+
+    pub async fn get(&self, study_id: &str) -> Result<Option<StudyData>, String> {
+        self.store
+            .load_study(study_id)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn delete(&self, study_id: &str) -> Result<(), String> {
+        // An edit in flight would save the study again
+        let _guard = self.edit_lock.lock().await;
+        self.store
+            .delete_study(study_id)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// The study as a multi-game PGN, or `None` if there is no such study.
+    /// Games deleted since the study was made are left out.
+    pub async fn export_pgn(&self, study_id: &str) -> Result<Option<String>, String> {
+        let Some(study) = self.get(study_id).await? else {
+            return Ok(None);
+        };
+        let mut games = Vec::with_capacity(study.game_ids.len());
+        for game_id in &study.game_ids {
+            let game = self
+                .finished_game_store
+                .load_game(game_id)
+                .await
+                .map_err(|e| e.to_string())?;
+            if let Some(game) = game {
+                let review = self.load_review(game_id).await?;
+                games.push((game, review));
+            }
+        }
+        Ok(Some(pgn::study_pgn(&study, &games)))
+    }
+
+    async fn list_games(&self) -> Result<Vec<FinishedGameData>, String> {
+        self.finished_game_store
+            .list_games()
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// The game's review, if it is complete: a review in progress would
+    /// annotate some moves and not others.
+    async fn load_review(&self, game_id: &str) -> Result<Option<GameReview>, String> {
+        let review = self
+            .review_store
+            .load_review(game_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(review.filter(|r| r.status == ReviewStatus::Complete))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::{FinishedGameStore, JsonPersistence, StoredMoveRecord, StudyStore};
+    use crate::review::store::ReviewStore;
+    use crate::review::types::Opening;
+
+    const START: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+    fn game(id: &str, result: &str, human_side: Option<&str>, sans: &[&str]) -> FinishedGameData {
+        FinishedGameData {
+            result: result.to_string(),
+            human_side: human_side.map(str::to_string),
+            move_count: sans.len() as u32,
+            moves: sans
+                .iter()
+                .map(|san| StoredMoveRecord::sample(san))
+                .collect(),
+            ..FinishedGameData::sample(id)
+        }
+    }
+
+    fn review(game_id: &str, eco: &str, name: &str) -> GameReview {
+        GameReview {
+            game_id: game_id.to_string(),
+            status: ReviewStatus::Complete,
+            positions: vec![],
+            white_accuracy: None,
+            black_accuracy: None,
+            total_plies: 0,
+            analyzed_plies: 0,
+            analysis_depth: 18,
+            started_at: None,
+            completed_at: None,
+            winner: None,
+            opening: Some(Opening {
+                eco: eco.to_string(),
+                name: name.to_string(),
+                ply: 2,
+            }),
+            endgame: None,
+        }
+    }
+
+    #[test]
+    fn test_filter_by_opening_outcome_and_side() {
+        let lost_as_white = game("a", "BlackWins", Some("white"), &["e4", "c5"]);
+        let sicilian = review("a", "B20", "Sicilian Defense");
+
+        let by_name = StudyFilter {
+            opening: Some("sicilian".to_string()),
+            ..Default::default()
+        };
+        assert!(matches(&by_name, &lost_as_white, Some(&sicilian)));
+        // Without a review the opening is unknown
+        assert!(!matches(&by_name, &lost_as_white, None));
+
+        let by_eco = StudyFilter {
+            opening: Some("B2".to_string()),
+            outcome: Some(HumanOutcome::Loss),
+            human_side: Some("white".to_string()),
+            ..Default::default()
+        };
+        assert!(matches(&by_eco, &lost_as_white, Some(&sicilian)));
+
+        let wins = StudyFilter {
+            outcome: Some(HumanOutcome::Win),
+            ..Default::default()
+        };
+        assert!(!matches(&wins, &lost_as_white, Some(&sicilian)));
+        let as_black = StudyFilter {
+            human_side: Some("black".to_string()),
+            ..Default::default()
+        };
+        assert!(!matches(&as_black, &lost_as_white, None));
+        let against = StudyFilter {
+            opponent: Some("Aggro Bot".to_string()),
+            ..Default::default()
+        };
+        assert!(!matches(&against, &lost_as_white, None));
+    }
+
+    #[tokio::test]
+    async fn test_create_and_export_study() {
+        let dir = tempfile::tempdir().unwrap();
+        let finished = FinishedGameStore::new(dir.path().to_path_buf());
+        let reviews = ReviewStore::new(dir.path().to_path_buf());
+        finished
+            .save(&game("a", "BlackWins", None, &["e4", "c5", "Nf3"]))
+            .unwrap();
+        finished
+            .save(&game("b", "BlackWins", None, &["e4", "c5", "Nc3"]))
+            .unwrap();
+        finished
+            .save(&game("c", "WhiteWins", None, &["e4", "c5"]))
+            .unwrap();
+        let mut from_fen = game("d", "BlackWins", None, &["Kd2"]);
+        from_fen.start_fen = "4k3/8/8/8/8/8/8/4K3 w - - 0 1".to_string();
+        finished.save(&from_fen).unwrap();
+        for id in ["a", "b", "c"] {
+            reviews
+                .save(&review(id, "B20", "Sicilian Defense"))
+                .unwrap();
+        }
+
+        let manager = StudyManager::<JsonPersistence>::new(
+            Arc::new(finished),
+            Arc::new(reviews),
+            StudyStore::new(dir.path().to_path_buf()),
+        );
+        let filter = StudyFilter {
+            opening: Some("Sicilian".to_string()),
+            outcome: Some(HumanOutcome::Loss),
+            ..Default::default()
+        };
+        let study = manager.create("Sicilian losses", filter).await.unwrap();
+        assert_eq!(study.game_ids.len(), 2);
+        assert_eq!(study.root.games, 2);
+        let c5 = &study.root.children[0].children[0];
+        assert_eq!((c5.san.as_str(), c5.black_wins), ("c5", 2));
+        assert_eq!(c5.children.len(), 2);
+
+        assert_eq!(manager.list().await.unwrap().len(), 1);
+        let pgn = manager.export_pgn(&study.study_id).await.unwrap().unwrap();
+        assert_eq!(pgn.matches("[Event ").count(), 3, "tree and two games");
+        assert!(pgn.contains("[Event \"Sicilian losses: game 2\"]"));
+
+        let no_match = StudyFilter {
+            opening: Some("French".to_string()),
+            ..Default::default()
+        };
+        assert!(manager.create("French", no_match).await.is_err());
+
+        manager.delete(&study.study_id).await.unwrap();
+        assert!(manager.get(&study.study_id).await.unwrap().is_none());
+        assert!(manager.export_pgn(&study.study_id).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_chapters_are_added_edited_and_reordered() {
+        let dir = tempfile::tempdir().unwrap();
+        let finished = FinishedGameStore::new(dir.path().to_path_buf());
+        finished
+            .save(&game("a", "WhiteWins", None, &["e4", "e5"]))
+            .unwrap();
+        let manager = StudyManager::<JsonPersistence>::new(
+            Arc::new(finished),
+            Arc::new(ReviewStore::new(dir.path().to_path_buf())),
+            StudyStore::new(dir.path().to_path_buf()),
+        );
+        let study = manager.create_blank("Repertoire").await.unwrap();
+        assert!(study.game_ids.is_empty());
+
+        let from_game = manager
+            .add_chapter(
+                &study.study_id,
+                "Club game",
+                ChapterSource::Game("a".to_string()),
+            )
+            .await
+            .unwrap();
+        assert_eq!(from_game.nodes.len(), 3);
+        let from_fen = manager
+            .add_chapter(
+                &study.study_id,
+                "Start",
+                ChapterSource::Position(START.to_string()),
+            )
+            .await
+            .unwrap();
+
+        let (saved, e4) = manager
+            .edit_chapter(&study.study_id, &from_fen.chapter_id, |c| {
+                c.add_move(0, "e4")
+            })
+            .await
+            .unwrap();
+        assert_eq!(saved.node(e4).unwrap().san, "e4");
+        let (_, c5) = manager
+            .edit_chapter(&study.study_id, &from_fen.chapter_id, |c| {
+                c.add_move(e4, "c5")
+            })
+            .await
+            .unwrap();
+
+        manager
+            .move_chapter(&study.study_id, &from_fen.chapter_id, 0)
+            .await
+            .unwrap();
+        let loaded = manager.get(&study.study_id).await.unwrap().unwrap();
+        let names: Vec<&str> = loaded.chapters.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["Start", "Club game"]);
+        assert_eq!(loaded.chapters[0].node(c5).unwrap().parent, Some(e4));
+
+        let pgn = manager.export_pgn(&study.study_id).await.unwrap().unwrap();
+        assert_eq!(pgn.matches("[Event ").count(), 2, "two chapters, no tree");
+
+        assert!(matches!(
+            manager
+                .add_chapter(
+                    &study.study_id,
+                    "Lost",
+                    ChapterSource::Game("z".to_string())
+                )
+                .await,
+            Err(StudyError::GameNotFound(_))
+        ));
+        assert!(matches!(
+            manager.delete_chapter(&study.study_id, "missing").await,
+            Err(StudyError::ChapterNotFound(_))
+        ));
+        assert!(matches!(
+            manager
+                .delete_chapter("missing", &from_game.chapter_id)
+                .await,
+            Err(StudyError::StudyNotFound(_))
+        ));
+        manager
+            .delete_chapter(&study.study_id, &from_game.chapter_id)
+            .await
+            .unwrap();
+        let loaded = manager.get(&study.study_id).await.unwrap().unwrap();
+        assert_eq!(loaded.chapters.len(), 1);
+    }
+}
@@ -0,0 +1,446 @@
+//! A study as a multi-game PGN, one game per chapter: the merged tree with
+//! its statistics first, then the chapters written by hand, then each game
+//! the tree was built from. Arrows are written into comments the way Lichess
+//! does, e.g. `[%cal Ge2e4,Rd7d5]`.
+
+use chess::pgn::{PgnGame, PgnMove};
+
+use crate::persistence::{
+    ArrowColor, ChapterNode, FinishedGameData, StudyChapter, StudyData, StudyNode,
+};
+use crate::review::types::GameReview;
+
+/// The study's chapters as PGN, separated by blank lines. A study built
+/// from no games has no tree chapter. Games no longer in the library are
+/// left out of `games`.
+pub fn study_pgn(study: &StudyData, games: &[(FinishedGameData, Option<GameReview>)]) -> String {
+    let mut chapters = Vec::new();
+    if !study.game_ids.is_empty() {
+        chapters.push(tree_chapter(study));
+    }
+    for chapter in &study.chapters {
+        chapters.push(written_chapter(&study.name, chapter));
+    }
+    for (i, (game, review)) in games.iter().enumerate() {
+        chapters.push(game_chapter(&study.name, i + 1, game, review.as_ref()));
+    }
+    chapters
+        .iter()
+        .map(PgnGame::to_pgn)
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn tree_chapter(study: &StudyData) -> PgnGame {
+    let mut moves = line(&study.root);
+    if let Some(first) = moves.first_mut() {
+        first.comment_before = Some(format!(
+            "{} {}",
+            counted(study.root.games, "game", "games"),
+            score(&study.root)
+        ));
+    }
+    PgnGame {
+        tags: vec![
+            ("Event".to_string(), format!("{}: tree", study.name)),
+            ("Site".to_string(), "ChessTTY".to_string()),
+            ("Result".to_string(), "*".to_string()),
+        ],
+        moves,
+        result: "*".to_string(),
+    }
+}
+
+/// The most played line from `node`, with every other move as a variation.
+fn line(node: &StudyNode) -> Vec<PgnMove> {
+    let mut moves = Vec::new();
+    let mut node = node;
+    while let Some((main, others)) = node.children.split_first() {
+        let mut mv = tree_move(main);
+        mv.variations = others
+            .iter()
+            .map(|other| {
+                let mut variation = vec![tree_move(other)];
+                variation.extend(line(other));
+                variation
+            })
+            .collect();
+        moves.push(mv);
+        node = main;
+    }
+    moves
+}
+
+fn tree_move(node: &StudyNode) -> PgnMove {
+    PgnMove {
+        comment: Some(node_comment(node)),
+        ..PgnMove::new(node.san.as_str())
+    }
+}
+
+/// Results from White's side, e.g. `(+3 =1 -2)`.
+fn score(node: &StudyNode) -> String {
+    format!(
+        "(+{} ={} -{})",
+        node.white_wins, node.draws, node.black_wins
+    )
+}
+
+/// Games, results and what the reviews said about the move, e.g.
+/// `5 games (+2 =1 -2); avg loss 48cp; 1 mistake; best: Nf3`.
+fn node_comment(node: &StudyNode) -> String {
+    let mut parts = vec![format!(
+        "{} {}",
+        counted(node.games, "game", "games"),
+        score(node)
+    )];
+    if let Some(loss) = node.average_cp_loss {
+        parts.push(format!("avg loss {:.0}cp", loss));
+    }
+    for (count, singular, plural) in [
+        (node.inaccuracies, "inaccuracy", "inaccuracies"),
+        (node.mistakes, "mistake", "mistakes"),
+        (node.blunders, "blunder", "blunders"),
+    ] {
+        if count > 0 {
+            parts.push(counted(count, singular, plural));
+        }
+    }
+    if let Some(best) = &node.best_san {
+        parts.push(format!("best: {}", best));
+    }
+    parts.join("; ")
+}
+
+fn counted(count: u32, singular: &str, plural: &str) -> String {
+    if count == 1 {
+        format!("1 {}", singular)
+    } else {
+        format!("{} {}", count, plural)
+    }
+}
+
+fn written_chapter(study_name: &str, chapter: &StudyChapter) -> PgnGame {
+    let mut tags = vec![
+        (
+            "Event".to_string(),
+            format!("{}: {}", study_name, chapter.name),
+        ),
+        ("Site".to_string(), "ChessTTY".to_string()),
+        ("Result".to_string(), "*".to_string()),
+    ];
+    let mut moves = Vec::new();
+    if let Some(root) = chapter.nodes.first() {
+        if !super::is_standard_start(&root.fen) {
+            tags.push(("SetUp".to_string(), "1".to_string()));
+            tags.push(("FEN".to_string(), root.fen.clone()));
+        }
+        moves = written_line(chapter, root);
+        if let Some(first) = moves.first_mut() {
+            first.comment_before = written_comment(root);
+        }
+    }
+    PgnGame {
+        tags,
+        moves,
+        result: "*".to_string(),
+    }
+}
+
+/// The moves after `node`, the first move from each position as the main
+/// line and the others as variations.
+fn written_line(chapter: &StudyChapter, node: &ChapterNode) -> Vec<PgnMove> {
+    let mut moves = Vec::new();
+    let mut node = node;
+    while let Some((&main, others)) = node.children.split_first() {
+        let Some(main) = chapter.node(main) else {
+            break;
+        };
+        let mut mv = written_move(main);
+        mv.variations = others
+            .iter()
+            .filter_map(|&id| chapter.node(id))
+            .map(|other| {
+                let mut variation = vec![written_move(other)];
+                variation.extend(written_line(chapter, other));
+                variation
+            })
+            .collect();
+        moves.push(mv);
+        node = main;
+    }
+    moves
+}
+
+fn written_move(node: &ChapterNode) -> PgnMove {
+    PgnMove {
+        nags: node.nags.clone(),
+        comment: written_comment(node),
+        ..PgnMove::new(node.san.as_str())
+    }
+}
+
+/// The node's arrows and comment. Braces would end the PGN comment early,
+/// so they are dropped.
+fn written_comment(node: &ChapterNode) -> Option<String> {
+    let mut parts = vec![];
+    if !node.arrows.is_empty() {
+        let arrows: Vec<String> = node
+            .arrows
+            .iter()
+            .map(|a| {
+                let color = match a.color {
+                    ArrowColor::Green => 'G',
+                    ArrowColor::Red => 'R',
+                    ArrowColor::Yellow => 'Y',
+                    ArrowColor::Blue => 'B',
+                };
+                format!("{}{}{}", color, a.from, a.to)
+            })
+            .collect();
+        parts.push(format!("[%cal {}]", arrows.join(",")));
+    }
+    if let Some(comment) = &node.comment {
+        parts.push(comment.replace(['{', '}'], ""));
+    }
+    (!parts.is_empty()).then(|| parts.join(" "))
+}
+
+fn game_chapter(
+    study_name: &str,
+    number: usize,
+    game: &FinishedGameData,
+    review: Option<&GameReview>,
+) -> PgnGame {
+    let result = match game.result.as_str() {
+        "WhiteWins" => "1-0",
+        "BlackWins" => "0-1",
+        "Draw" => "1/2-1/2",
+        _ => "*",
+    };
+    let mut tags = vec![
+        (
+            "Event".to_string(),
+            format!("{}: game {}", study_name, number),
+        ),
+        ("Site".to_string(), "ChessTTY".to_string()),
+        ("Result".to_string(), result.to_string()),
+    ];
+    if let Some(opponent) = &game.opponent {
+        tags.push(("Opponent".to_string(), opponent.clone()));
+    }
+    if let Some(opening) = review.and_then(|r| r.opening.as_ref()) {
+        tags.push(("ECO".to_string(), opening.eco.clone()));
+        tags.push(("Opening".to_string(), opening.name.clone()));
+    }
+
+    let moves = game
+        .moves
+        .iter()
+        .enumerate()
+        .map(|(i, mv)| {
+            let position = review.and_then(|r| r.positions.iter().find(|p| p.ply == i as u32 + 1));
+            let Some(position) = position else {
+                return PgnMove::new(mv.san.as_str());
+            };
+            let comment = position
+                .classification
+                .is_error()
+                .then(|| format!("best: {} ({}cp)", position.best_move_san, position.cp_loss));
+            PgnMove {
+                nags: position.classification.to_nag().into_iter().collect(),
+                comment,
+                ..PgnMove::new(mv.san.as_str())
+            }
+        })
+        .collect();
+
+    PgnGame {
+        tags,
+        moves,
+        result: result.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::{Arrow, StoredMoveRecord, StudyFilter};
+    use crate::review::types::{AnalysisScore, MoveClassification, PositionReview, ReviewStatus};
+
+    fn node(san: &str, games: u32, children: Vec<StudyNode>) -> StudyNode {
+        StudyNode {
+            san: san.to_string(),
+            games,
+            black_wins: games,
+            children,
+            ..Default::default()
+        }
+    }
+
+    fn study() -> StudyData {
+        let qh5 = StudyNode {
+            reviewed: 2,
+            average_cp_loss: Some(250.0),
+            mistakes: 1,
+            blunders: 1,
+            best_san: Some("Nf3".to_string()),
+            ..node("Qh5", 2, vec![])
+        };
+        StudyData {
+            study_id: "study_1".to_string(),
+            name: "Sicilian losses".to_string(),
+            filter: StudyFilter::default(),
+            game_ids: vec!["a".to_string()],
+            root: node(
+                "",
+                3,
+                vec![node(
+                    "e4",
+                    3,
+                    vec![node("c5", 3, vec![qh5, node("Nf3", 1, vec![])])],
+                )],
+            ),
+            chapters: vec![],
+            created_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_node_comment() {
+        let study = study();
+        let qh5 = &study.root.children[0].children[0].children[0];
+        assert_eq!(
+            node_comment(qh5),
+            "2 games (+0 =0 -2); avg loss 250cp; 1 mistake; 1 blunder; best: Nf3"
+        );
+        assert_eq!(node_comment(&node("e4", 1, vec![])), "1 game (+0 =0 -1)");
+    }
+
+    #[test]
+    fn test_tree_and_games_become_chapters() {
+        let game = FinishedGameData {
+            start_fen: String::new(),
+            result: "BlackWins".to_string(),
+            human_side: None,
+            move_count: 2,
+            moves: ["e4", "c5"]
+                .iter()
+                .map(|san| StoredMoveRecord::sample(san))
+                .collect(),
+            opponent: Some("Aggro Bot".to_string()),
+            ..FinishedGameData::sample("a")
+        };
+        let review = GameReview {
+            game_id: "a".to_string(),
+            status: ReviewStatus::Complete,
+            positions: vec![PositionReview {
+                ply: 2,
+                fen: String::new(),
+                played_san: "c5".to_string(),
+                best_move_san: "e5".to_string(),
+                best_move_uci: String::new(),
+                eval_before: AnalysisScore::Centipawns(0),
+                eval_after: AnalysisScore::Centipawns(0),
+                eval_best: AnalysisScore::Centipawns(0),
+                classification: MoveClassification::Inaccuracy,
+                cp_loss: 40,
+                pv: vec![],
+                depth: 18,
+                clock_ms: None,
+                excluded: None,
+                only_move: false,
+            }],
+            white_accuracy: None,
+            black_accuracy: None,
+            total_plies: 2,
+            analyzed_plies: 2,
+            analysis_depth: 18,
+            started_at: None,
+            completed_at: None,
+            winner: None,
+            opening: None,
+            endgame: None,
+        };
+
+        let text = study_pgn(&study(), &[(game, Some(review))]);
+        let chapters = chess::pgn::parse_games(&text);
+        assert_eq!(chapters.len(), 2);
+
+        let tree = &chapters[0];
+        assert_eq!(tree.tag("Event"), Some("Sicilian losses: tree"));
+        assert_eq!(tree.main_line().collect::<Vec<_>>(), ["e4", "c5", "Qh5"]);
+        assert_eq!(
+            tree.moves[0].comment_before.as_deref(),
+            Some("3 games (+0 =0 -3)")
+        );
+        let qh5 = &tree.moves[2];
+        assert_eq!(qh5.variations.len(), 1);
+        assert_eq!(qh5.variations[0][0].san, "Nf3");
+
+        let game = &chapters[1];
+        assert_eq!(game.tag("Event"), Some("Sicilian losses: game 1"));
+        assert_eq!(game.tag("Opponent"), Some("Aggro Bot"));
+        assert_eq!(game.result, "0-1");
+        assert_eq!(game.moves[1].nags, [6]);
+        assert_eq!(game.moves[1].comment.as_deref(), Some("best: e5 (40cp)"));
+    }
+
+    #[test]
+    fn test_written_chapters_keep_annotations() {
+        let mut chapter = StudyChapter::from_position(
+            "c1".to_string(),
+            "Endgame".to_string(),
+            "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1",
+        )
+        .unwrap();
+        let e4 = chapter.add_move(0, "e4").unwrap();
+        let kd7 = chapter.add_move(e4, "Kd7").unwrap();
+        chapter.add_move(e4, "Ke7").unwrap();
+        chapter
+            .annotate(
+                0,
+                crate::study::chapter::Annotation {
+                    comment: Some("Push {the} pawn".to_string()),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        chapter
+            .annotate(
+                kd7,
+                crate::study::chapter::Annotation {
+                    comment: Some("Opposition".to_string()),
+                    nags: vec![1],
+                    arrows: vec![Arrow {
+                        from: "e1".to_string(),
+                        to: "e2".to_string(),
+                        color: ArrowColor::Green,
+                    }],
+                },
+            )
+            .unwrap();
+
+        let mut study = study();
+        study.game_ids.clear();
+        study.chapters.push(chapter);
+        let text = study_pgn(&study, &[]);
+        let chapters = chess::pgn::parse_games(&text);
+        assert_eq!(chapters.len(), 1, "no tree without games");
+
+        let endgame = &chapters[0];
+        assert_eq!(endgame.tag("Event"), Some("Sicilian losses: Endgame"));
+        assert_eq!(endgame.tag("FEN"), Some("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1"));
+        assert_eq!(endgame.main_line().collect::<Vec<_>>(), ["e4", "Kd7"]);
+        assert_eq!(
+            endgame.moves[0].comment_before.as_deref(),
+            Some("Push the pawn")
+        );
+        assert_eq!(endgame.moves[1].nags, [1]);
+        assert_eq!(
+            endgame.moves[1].comment.as_deref(),
+            Some("[%cal Ge1e2] Opposition")
+        );
+        assert_eq!(endgame.moves[1].variations[0][0].san, "Ke7");
+    }
+}
@@ -0,0 +1,272 @@
+//! Merging games into a study's variation tree.
+
+use std::collections::BTreeMap;
+
+use crate::persistence::{FinishedGameData, StudyNode};
+use crate::review::types::{GameReview, MoveClassification, PositionReview};
+
+/// Plies of each game merged into the tree: the opening and the start of
+/// the middlegame, where games of one opening still share moves.
+pub const STUDY_PLIES: usize = 40;
+
+/// A node while games are merged into it.
+#[derive(Default)]
+struct NodeBuilder {
+    /// The node so far, without its children.
+    node: StudyNode,
+    total_cp_loss: i64,
+    /// How often reviews suggested each better move.
+    suggestions: BTreeMap<String, u32>,
+    children: Vec<NodeBuilder>,
+}
+
+impl NodeBuilder {
+    fn new(san: &str, fen: &str) -> Self {
+        Self {
+            node: StudyNode {
+                san: san.to_string(),
+                fen: fen.to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    /// The child for `san`, added if no game played it yet.
+    fn child(&mut self, san: &str, fen: &str) -> &mut NodeBuilder {
+        let index = match self.children.iter().position(|c| c.node.san == san) {
+            Some(index) => index,
+            None => {
+                self.children.push(NodeBuilder::new(san, fen));
+                self.children.len() - 1
+            }
+        };
+        &mut self.children[index]
+    }
+
+    fn add_result(&mut self, result: &str) {
+        self.node.games += 1;
+        match result {
+            "WhiteWins" => self.node.white_wins += 1,
+            "BlackWins" => self.node.black_wins += 1,
+            "Draw" => self.node.draws += 1,
+            _ => {}
+        }
+    }
+
+    fn add_review(&mut self, position: &PositionReview) {
+        self.node.reviewed += 1;
+        self.total_cp_loss += i64::from(position.cp_loss.max(0));
+        if self.node.eval.is_none() {
+            self.node.eval = Some(position.eval_after.clone());
+        }
+        match position.classification {
+            MoveClassification::Inaccuracy => self.node.inaccuracies += 1,
+            MoveClassification::Mistake => self.node.mistakes += 1,
+            MoveClassification::Blunder => self.node.blunders += 1,
+            // A miss counts by what it cost
+            MoveClassification::Miss => {
+                match MoveClassification::from_cp_loss(position.cp_loss, false) {
+                    MoveClassification::Blunder => self.node.blunders += 1,
+                    _ => self.node.mistakes += 1,
+                }
+            }
+            _ => return,
+        }
+        *self
+            .suggestions
+            .entry(position.best_move_san.clone())
+            .or_default() += 1;
+    }
+
+    fn finish(self) -> StudyNode {
+        let mut node = self.node;
+        if node.reviewed > 0 {
+            node.average_cp_loss = Some(self.total_cp_loss as f64 / f64::from(node.reviewed));
+        }
+        // The most suggested move; alphabetical order breaks ties
+        node.best_san = self
+            .suggestions
+            .into_iter()
+            .max_by(|(a, a_count), (b, b_count)| a_count.cmp(b_count).then(b.cmp(a)))
+            .map(|(san, _)| san);
+        node.children = self.children.into_iter().map(NodeBuilder::finish).collect();
+        // Stable, so of equally played moves the one seen first stays first
+        node.children.sort_by(|a, b| b.games.cmp(&a.games));
+        node
+    }
+}
+
+/// Merge the first [`STUDY_PLIES`] plies of `games`, all played from
+/// `start_fen`, into one tree. Reviews annotate the moves they cover.
+pub fn build_tree(start_fen: &str, games: &[(FinishedGameData, Option<GameReview>)]) -> StudyNode {
+    let mut root = NodeBuilder::new("", start_fen);
+    for (game, review) in games {
+        root.add_result(&game.result);
+        let mut node = &mut root;
+        for (i, mv) in game.moves.iter().take(STUDY_PLIES).enumerate() {
+            node = node.child(&mv.san, &mv.fen_after);
+            node.add_result(&game.result);
+            let ply = i as u32 + 1;
+            if let Some(position) = review
+                .as_ref()
+                .and_then(|r| r.positions.iter().find(|p| p.ply == ply))
+            {
+                node.add_review(position);
+            }
+        }
+    }
+    root.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::StoredMoveRecord;
+    use crate::review::types::{AnalysisScore, ReviewStatus};
+
+    const START: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+    fn game(id: &str, result: &str, sans: &[&str]) -> FinishedGameData {
+        FinishedGameData {
+            result: result.to_string(),
+            game_mode: "HumanVsHuman".to_string(),
+            human_side: None,
+            move_count: sans.len() as u32,
+            moves: sans
+                .iter()
+                .map(|san| StoredMoveRecord {
+                    fen_after: format!("after {}", san),
+                    ..StoredMoveRecord::sample(san)
+                })
+                .collect(),
+            ..FinishedGameData::sample(id)
+        }
+    }
+
+    fn position(
+        ply: u32,
+        played: &str,
+        best: &str,
+        classification: MoveClassification,
+        cp_loss: i32,
+    ) -> PositionReview {
+        PositionReview {
+            ply,
+            fen: String::new(),
+            played_san: played.to_string(),
+            best_move_san: best.to_string(),
+            best_move_uci: String::new(),
+            eval_before: AnalysisScore::Centipawns(0),
+            eval_after: AnalysisScore::Centipawns(-cp_loss),
+            eval_best: AnalysisScore::Centipawns(0),
+            classification,
+            cp_loss,
+            pv: vec![],
+            depth: 18,
+            clock_ms: None,
+            excluded: None,
+            only_move: false,
+        }
+    }
+
+    fn review(game_id: &str, positions: Vec<PositionReview>) -> GameReview {
+        GameReview {
+            game_id: game_id.to_string(),
+            status: ReviewStatus::Complete,
+            positions,
+            white_accuracy: None,
+            black_accuracy: None,
+            total_plies: 0,
+            analyzed_plies: 0,
+            analysis_depth: 18,
+            started_at: None,
+            completed_at: None,
+            winner: None,
+            opening: None,
+            endgame: None,
+        }
+    }
+
+    #[test]
+    fn test_games_merge_into_one_tree() {
+        let games = vec![
+            (game("a", "WhiteWins", &["d4", "d5"]), None),
+            (game("b", "BlackWins", &["e4", "c5", "Nf3"]), None),
+            (game("c", "Draw", &["e4", "e5"]), None),
+            (game("d", "BlackWins", &["e4", "c5", "Nc3"]), None),
+        ];
+        let root = build_tree(START, &games);
+        assert_eq!(root.fen, START);
+        assert_eq!(
+            (root.games, root.white_wins, root.draws, root.black_wins),
+            (4, 1, 1, 2)
+        );
+
+        // Most played first, then in the order first played
+        let sans = |node: &StudyNode| -> Vec<String> {
+            node.children.iter().map(|c| c.san.clone()).collect()
+        };
+        assert_eq!(sans(&root), ["e4", "d4"]);
+        let e4 = &root.children[0];
+        assert_eq!((e4.games, e4.fen.as_str()), (3, "after e4"));
+        assert_eq!(sans(e4), ["c5", "e5"]);
+        assert_eq!(sans(&e4.children[0]), ["Nf3", "Nc3"]);
+        assert_eq!(e4.children[0].black_wins, 2);
+        assert_eq!(e4.reviewed, 0);
+        assert_eq!(e4.average_cp_loss, None);
+    }
+
+    #[test]
+    fn test_reviews_annotate_moves() {
+        let games = vec![
+            (
+                game("a", "BlackWins", &["e4", "c5", "Qh5"]),
+                Some(review(
+                    "a",
+                    vec![
+                        position(1, "e4", "e4", MoveClassification::Best, 0),
+                        position(3, "Qh5", "Nf3", MoveClassification::Mistake, 150),
+                    ],
+                )),
+            ),
+            (
+                game("b", "BlackWins", &["e4", "c5", "Qh5"]),
+                Some(review(
+                    "b",
+                    vec![position(3, "Qh5", "Nc3", MoveClassification::Blunder, 350)],
+                )),
+            ),
+            (game("c", "Draw", &["e4", "c5", "Qh5"]), None),
+        ];
+        let root = build_tree(START, &games);
+        let qh5 = &root.children[0].children[0].children[0];
+        assert_eq!(qh5.san, "Qh5");
+        assert_eq!((qh5.games, qh5.reviewed), (3, 2));
+        assert_eq!((qh5.mistakes, qh5.blunders), (1, 1));
+        assert_eq!(qh5.average_cp_loss, Some(250.0));
+        // One suggestion each: the alphabetically first wins
+        assert_eq!(qh5.best_san.as_deref(), Some("Nc3"));
+        assert_eq!(qh5.eval, Some(AnalysisScore::Centipawns(-150)));
+
+        let e4 = &root.children[0];
+        assert_eq!(e4.reviewed, 1);
+        assert_eq!(e4.best_san, None, "a good move has no suggestion");
+        // c5 is not in any review
+        assert_eq!(root.children[0].children[0].reviewed, 0);
+    }
+
+    #[test]
+    fn test_only_the_first_plies_are_merged() {
+        let long: Vec<String> = (0..STUDY_PLIES + 10).map(|i| format!("m{}", i)).collect();
+        let sans: Vec<&str> = long.iter().map(String::as_str).collect();
+        let root = build_tree(START, &[(game("a", "Draw", &sans), None)]);
+        let mut depth = 0;
+        let mut node = &root;
+        while let Some(child) = node.children.first() {
+            depth += 1;
+            node = child;
+        }
+        assert_eq!(depth, STUDY_PLIES);
+    }
+}
@@ -0,0 +1,116 @@
+//! Version vectors for detecting concurrent edits across sync peers.
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+/// How two versions of a record relate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClockOrder {
+    Equal,
+    /// This version is an ancestor of the other.
+    Before,
+    /// This version descends from the other.
+    After,
+    /// Both versions were changed independently.
+    Concurrent,
+}
+
+/// Per-node change counters of one record.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VectorClock(BTreeMap<String, u64>);
+
+impl VectorClock {
+    pub fn from_map(counters: BTreeMap<String, u64>) -> Self {
+        Self(counters)
+    }
+
+    pub fn as_map(&self) -> &BTreeMap<String, u64> {
+        &self.0
+    }
+
+    pub fn into_map(self) -> BTreeMap<String, u64> {
+        self.0
+    }
+
+    fn get(&self, node: &str) -> u64 {
+        self.0.get(node).copied().unwrap_or(0)
+    }
+
+    /// Record a change made on `node`.
+    pub fn tick(&mut self, node: &str) {
+        *self.0.entry(node.to_string()).or_insert(0) += 1;
+    }
+
+    /// The smallest clock that descends from both.
+    pub fn merged(&self, other: &VectorClock) -> VectorClock {
+        let mut counters = self.0.clone();
+        for (node, &count) in &other.0 {
+            let entry = counters.entry(node.clone()).or_insert(0);
+            *entry = (*entry).max(count);
+        }
+        VectorClock(counters)
+    }
+
+    pub fn compare(&self, other: &VectorClock) -> ClockOrder {
+        let mut order = Ordering::Equal;
+        for node in self.0.keys().chain(other.0.keys()) {
+            match (self.get(node).cmp(&other.get(node)), order) {
+                (Ordering::Equal, _) => {}
+                (o, Ordering::Equal) => order = o,
+                (o, current) if o != current => return ClockOrder::Concurrent,
+                _ => {}
+            }
+        }
+        match order {
+            Ordering::Equal => ClockOrder::Equal,
+            Ordering::Less => ClockOrder::Before,
+            Ordering::Greater => ClockOrder::After,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clock(counters: &[(&str, u64)]) -> VectorClock {
+        VectorClock::from_map(
+            counters
+                .iter()
+                .map(|(node, count)| (node.to_string(), *count))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_compare() {
+        let a1 = clock(&[("a", 1)]);
+        let a2 = clock(&[("a", 2)]);
+        let a1b1 = clock(&[("a", 1), ("b", 1)]);
+        let b1 = clock(&[("b", 1)]);
+
+        assert_eq!(a1.compare(&a1.clone()), ClockOrder::Equal);
+        assert_eq!(a1.compare(&a2), ClockOrder::Before);
+        assert_eq!(a1b1.compare(&a1), ClockOrder::After);
+        assert_eq!(a2.compare(&a1b1), ClockOrder::Concurrent);
+        assert_eq!(a1.compare(&b1), ClockOrder::Concurrent);
+        // Missing entries count as zero
+        assert_eq!(
+            clock(&[("a", 0)]).compare(&VectorClock::default()),
+            ClockOrder::Equal
+        );
+    }
+
+    #[test]
+    fn test_tick_and_merge() {
+        let mut a = clock(&[("a", 2), ("b", 1)]);
+        let b = clock(&[("b", 3)]);
+        let merged = a.merged(&b);
+        assert_eq!(merged, clock(&[("a", 2), ("b", 3)]));
+        assert_eq!(merged.compare(&a), ClockOrder::After);
+        assert_eq!(merged.compare(&b), ClockOrder::After);
+
+        a.tick("c");
+        assert_eq!(a, clock(&[("a", 2), ("b", 1), ("c", 1)]));
+    }
+}
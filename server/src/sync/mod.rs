@@ -0,0 +1,847 @@
+//! Server-to-server sync of finished games and reviews.
+//!
+//! Every synced record carries a version vector ([`clock::VectorClock`]).
+//! Local changes are picked up lazily: at the start of each sync run the
+//! current content of every record is hashed, and a record that is new,
+//! changed or deleted since the last run gets this node's counter bumped.
+//! An incoming version that descends from the local one replaces it;
+//! concurrent versions are resolved deterministically so that both peers
+//! keep the same winner.
+//!
+//! A run sends ids and clocks first; the peer answers with the records each
+//! side needs from the other, and only those travel, in pages of at most
+//! [`SYNC_PAGE_BYTES`].
+//!
+//! Only completed reviews are synced, so an analysis in progress is never
+//! overwritten by a peer's partial one.
+
+pub mod clock;
+pub mod peer;
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+
+use tokio::sync::Mutex;
+
+use crate::persistence::{
+    FinishedGameData, FinishedGameRepository, Persistence, PersistenceError, ReviewRepository,
+    SyncKind, SyncRecord, SyncRepository,
+};
+use crate::review::types::{GameReview, ReviewStatus};
+use clock::{ClockOrder, VectorClock};
+
+/// One version of a record, as exchanged with a peer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyncItem {
+    pub kind: SyncKind,
+    pub id: String,
+    pub clock: VectorClock,
+    /// JSON-encoded record; `None` once the record was deleted.
+    pub payload: Option<Vec<u8>>,
+}
+
+impl SyncItem {
+    /// Rough size on the wire, for paging.
+    fn wire_size(&self) -> usize {
+        let clock: usize = self.clock.as_map().keys().map(|node| node.len() + 8).sum();
+        self.id.len() + clock + self.payload.as_ref().map_or(0, Vec::len)
+    }
+}
+
+/// Which version of a record a server has, without the record.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyncVersion {
+    pub kind: SyncKind,
+    pub id: String,
+    pub clock: VectorClock,
+}
+
+/// A peer's answer to the versions we compared with it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyncComparison {
+    pub node_id: String,
+    /// Records the peer wants our version of.
+    pub wanted: Vec<RecordKey>,
+    /// Records the peer has a version of that we lack.
+    pub offered: Vec<RecordKey>,
+}
+
+/// Counts from one sync run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyncReport {
+    /// Versions taken over from the peer.
+    pub received: u32,
+    /// Versions the peer took over from us.
+    pub sent: u32,
+    /// Records changed on both sides since the last sync.
+    pub conflicts: u32,
+}
+
+/// The other side of a sync run.
+pub trait SyncPeer: Send + Sync {
+    /// Compare our version of every record with the peer's.
+    fn compare(
+        &self,
+        node_id: &str,
+        versions: Vec<SyncVersion>,
+    ) -> impl Future<Output = Result<SyncComparison, String>> + Send;
+
+    /// Have the peer take over a page of our records.
+    fn push(
+        &self,
+        node_id: &str,
+        items: Vec<SyncItem>,
+    ) -> impl Future<Output = Result<MergeCounts, String>> + Send;
+
+    /// The peer's current versions of the first of `keys`, in order, as many
+    /// as fit in a page.
+    fn fetch(
+        &self,
+        node_id: &str,
+        keys: Vec<RecordKey>,
+    ) -> impl Future<Output = Result<Vec<SyncItem>, String>> + Send;
+}
+
+/// Largest page of records sent in one message. A single larger record
+/// still travels, alone.
+pub const SYNC_PAGE_BYTES: usize = 4 * 1024 * 1024;
+
+/// Most records asked for in one fetch.
+const FETCH_PAGE_KEYS: usize = 256;
+
+pub type RecordKey = (SyncKind, String);
+
+/// A record's local version during a sync run.
+struct LocalVersion {
+    clock: VectorClock,
+    payload: Option<Vec<u8>>,
+}
+
+/// Counts from merging a batch of incoming versions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MergeCounts {
+    pub applied: u32,
+    pub conflicts: u32,
+}
+
+fn storage_error(e: PersistenceError) -> String {
+    e.to_string()
+}
+
+fn encode_error(e: serde_json::Error) -> String {
+    format!("Failed to encode sync payload: {}", e)
+}
+
+/// FNV-1a hash of a payload. Stable across builds, unlike `DefaultHasher`,
+/// because it is stored between runs.
+pub(crate) fn content_hash(payload: &[u8]) -> u64 {
+    payload.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Order in which concurrent versions win: live records beat deletions,
+/// then longer games and deeper analyses, then the later timestamp, then
+/// the content hash so that both peers pick the same version.
+fn rank(kind: SyncKind, payload: Option<&[u8]>) -> (u64, u64, u64) {
+    let Some(payload) = payload else {
+        return (0, 0, 0);
+    };
+    let hash = content_hash(payload);
+    let rank = match kind {
+        SyncKind::FinishedGame => serde_json::from_slice::<FinishedGameData>(payload)
+            .map(|g| (1 + u64::from(g.move_count), g.created_at))
+            .ok(),
+        SyncKind::Review => serde_json::from_slice::<GameReview>(payload)
+            .map(|r| (1 + u64::from(r.analysis_depth), r.completed_at.unwrap_or(0)))
+            .ok(),
+    };
+    let (size, time) = rank.unwrap_or((1, 0));
+    (size, time, hash)
+}
+
+/// Keeps finished games and reviews in step with sync peers.
+pub struct SyncManager<D: Persistence> {
+    finished_game_store: Arc<D::FinishedGames>,
+    review_store: Arc<D::Reviews>,
+    sync_store: D::Sync,
+    /// Held for a whole run, or one call from a peer, so manual, scheduled and
+    /// incoming syncs don't interleave.
+    running: Mutex<()>,
+}
+
+impl<D: Persistence> SyncManager<D> {
+    pub fn new(
+        finished_game_store: Arc<D::FinishedGames>,
+        review_store: Arc<D::Reviews>,
+        sync_store: D::Sync,
+    ) -> Self {
+        Self {
+            finished_game_store,
+            review_store,
+            sync_store,
+            running: Mutex::new(()),
+        }
+    }
+
+    /// Sync with `peer`: compare versions, push the records it wants and
+    /// take over the ones it offers. Returns the peer's node id with the
+    /// counts.
+    pub async fn sync_with<P: SyncPeer>(&self, peer: &P) -> Result<(String, SyncReport), String> {
+        let _running = self.running.lock().await;
+        let node_id = self.sync_store.node_id().await.map_err(storage_error)?;
+        let mut local = self.refresh(&node_id).await?;
+
+        let comparison = peer.compare(&node_id, versions_of(&local)).await?;
+        if comparison.node_id == node_id {
+            return Err("Refusing to sync a server with itself".to_string());
+        }
+        let mut report = SyncReport::default();
+
+        // Push first, so that conflicts are resolved on the peer before we
+        // fetch its result. Games go before reviews, which need their game
+        let mut wanted: Vec<SyncItem> = comparison
+            .wanted
+            .into_iter()
+            .filter_map(|key| {
+                let version = local.get(&key)?;
+                Some(SyncItem {
+                    kind: key.0,
+                    id: key.1,
+                    clock: version.clock.clone(),
+                    payload: version.payload.clone(),
+                })
+            })
+            .collect();
+        wanted.sort_by_key(|item| item.kind);
+        for page in pages(wanted) {
+            let counts = peer.push(&node_id, page).await?;
+            report.sent += counts.applied;
+            report.conflicts += counts.conflicts;
+        }
+
+        let mut offered = comparison.offered;
+        offered.sort_by_key(|key| key.0);
+        let mut next = 0;
+        while next < offered.len() {
+            let keys = offered[next..offered.len().min(next + FETCH_PAGE_KEYS)].to_vec();
+            let asked = keys.len();
+            let page = peer.fetch(&node_id, keys).await?;
+            let in_order = page
+                .iter()
+                .zip(&offered[next..])
+                .all(|(item, key)| item.kind == key.0 && item.id == key.1);
+            if page.is_empty() || page.len() > asked || !in_order {
+                return Err("Sync peer answered a fetch with other records".to_string());
+            }
+            next += page.len();
+            let counts = self.merge(&mut local, page).await?;
+            report.received += counts.applied;
+            report.conflicts += counts.conflicts;
+        }
+
+        tracing::info!(
+            peer = %comparison.node_id,
+            received = report.received,
+            sent = report.sent,
+            conflicts = report.conflicts,
+            "Sync complete"
+        );
+        Ok((comparison.node_id, report))
+    }
+
+    /// Answer a peer's comparison: the records whose version it has that we
+    /// lack, and the ones we have that it lacks.
+    pub async fn handle_compare(
+        &self,
+        versions: Vec<SyncVersion>,
+    ) -> Result<SyncComparison, String> {
+        let _running = self.running.lock().await;
+        let node_id = self.sync_store.node_id().await.map_err(storage_error)?;
+        let local = self.refresh(&node_id).await?;
+
+        let mut wanted = Vec::new();
+        let mut remote = HashMap::with_capacity(versions.len());
+        for version in versions {
+            let key = (version.kind, version.id);
+            let newer = match local.get(&key) {
+                None => true,
+                Some(ours) => matches!(
+                    ours.clock.compare(&version.clock),
+                    ClockOrder::Before | ClockOrder::Concurrent
+                ),
+            };
+            if newer {
+                wanted.push(key.clone());
+            }
+            remote.insert(key, version.clock);
+        }
+
+        let offered = local
+            .into_iter()
+            .filter(|(key, ours)| match remote.get(key) {
+                None => true,
+                Some(clock) => matches!(
+                    ours.clock.compare(clock),
+                    ClockOrder::After | ClockOrder::Concurrent
+                ),
+            })
+            .map(|(key, _)| key)
+            .collect();
+
+        Ok(SyncComparison {
+            node_id,
+            wanted,
+            offered,
+        })
+    }
+
+    /// Take over a page of a peer's records.
+    pub async fn handle_push(&self, items: Vec<SyncItem>) -> Result<MergeCounts, String> {
+        let _running = self.running.lock().await;
+        let node_id = self.sync_store.node_id().await.map_err(storage_error)?;
+        let keys = items.iter().map(|item| (item.kind, item.id.clone()));
+        let mut local = self.refresh_keys(&node_id, keys).await?;
+        self.merge(&mut local, items).await
+    }
+
+    /// Our current versions of the first of `keys`, in order, up to a page.
+    pub async fn handle_fetch(&self, keys: Vec<RecordKey>) -> Result<Vec<SyncItem>, String> {
+        let _running = self.running.lock().await;
+        let node_id = self.sync_store.node_id().await.map_err(storage_error)?;
+        let mut records = self.sync_records().await?;
+
+        let mut items: Vec<SyncItem> = Vec::new();
+        let mut bytes = 0;
+        for key in keys.into_iter().take(FETCH_PAGE_KEYS) {
+            if items
+                .iter()
+                .any(|item| item.kind == key.0 && item.id == key.1)
+            {
+                break;
+            }
+            let payload = self.current_payload(&key).await?;
+            let version = self
+                .track(&node_id, &key, records.remove(&key), payload)
+                .await?;
+            let item = SyncItem {
+                kind: key.0,
+                id: key.1,
+                clock: version.clock,
+                payload: version.payload,
+            };
+            bytes += item.wire_size();
+            if !items.is_empty() && bytes > SYNC_PAGE_BYTES {
+                break;
+            }
+            items.push(item);
+        }
+        Ok(items)
+    }
+
+    /// Load every record and bump this node's counter on those that changed
+    /// since the last run.
+    async fn refresh(&self, node_id: &str) -> Result<HashMap<RecordKey, LocalVersion>, String> {
+        let mut records = self.sync_records().await?;
+
+        let mut current = Vec::new();
+        for game in self
+            .finished_game_store
+            .list_games()
+            .await
+            .map_err(storage_error)?
+        {
+            let payload = serde_json::to_vec(&game).map_err(encode_error)?;
+            current.push(((SyncKind::FinishedGame, game.game_id), payload));
+        }
+        for review in self
+            .review_store
+            .list_reviews()
+            .await
+            .map_err(storage_error)?
+        {
+            if review.status == ReviewStatus::Complete {
+                let payload = serde_json::to_vec(&review).map_err(encode_error)?;
+                current.push(((SyncKind::Review, review.game_id), payload));
+            }
+        }
+
+        let mut local = HashMap::with_capacity(current.len() + records.len());
+        for (key, payload) in current {
+            let version = self
+                .track(node_id, &key, records.remove(&key), Some(payload))
+                .await?;
+            local.insert(key, version);
+        }
+
+        // Records that are gone were deleted, now or in an earlier run
+        for (key, record) in records {
+            let version = self.track(node_id, &key, Some(record), None).await?;
+            local.insert(key, version);
+        }
+
+        Ok(local)
+    }
+
+    /// Like [`Self::refresh`], for the records with `keys` only.
+    async fn refresh_keys(
+        &self,
+        node_id: &str,
+        keys: impl IntoIterator<Item = RecordKey>,
+    ) -> Result<HashMap<RecordKey, LocalVersion>, String> {
+        let mut records = self.sync_records().await?;
+        let mut local = HashMap::new();
+        for key in keys {
+            if local.contains_key(&key) {
+                continue;
+            }
+            let payload = self.current_payload(&key).await?;
+            let version = self
+                .track(node_id, &key, records.remove(&key), payload)
+                .await?;
+            local.insert(key, version);
+        }
+        Ok(local)
+    }
+
+    async fn sync_records(&self) -> Result<HashMap<RecordKey, SyncRecord>, String> {
+        Ok(self
+            .sync_store
+            .list_sync_records()
+            .await
+            .map_err(storage_error)?
+            .into_iter()
+            .map(|r| ((r.kind, r.record_id.clone()), r))
+            .collect())
+    }
+
+    /// The record as synced, or `None` when it doesn't exist (or, for a
+    /// review, isn't complete).
+    async fn current_payload(&self, key: &RecordKey) -> Result<Option<Vec<u8>>, String> {
+        match key.0 {
+            SyncKind::FinishedGame => self
+                .finished_game_store
+                .load_game(&key.1)
+                .await
+                .map_err(storage_error)?
+                .map(|g| serde_json::to_vec(&g).map_err(encode_error))
+                .transpose(),
+            SyncKind::Review => self
+                .review_store
+                .load_review(&key.1)
+                .await
+                .map_err(storage_error)?
+                .filter(|r| r.status == ReviewStatus::Complete)
+                .map(|r| serde_json::to_vec(&r).map_err(encode_error))
+                .transpose(),
+        }
+    }
+
+    /// A record's local version, with this node's counter bumped when its
+    /// content changed since the last run.
+    async fn track(
+        &self,
+        node_id: &str,
+        key: &RecordKey,
+        record: Option<SyncRecord>,
+        payload: Option<Vec<u8>>,
+    ) -> Result<LocalVersion, String> {
+        let hash = payload.as_deref().map(content_hash);
+        let changed = match &record {
+            Some(record) => record.content_hash != hash,
+            None => hash.is_some(),
+        };
+        let mut clock = record
+            .map(|r| VectorClock::from_map(r.clock))
+            .unwrap_or_default();
+        if changed {
+            clock.tick(node_id);
+            self.save_record(key, &clock, hash).await?;
+        }
+        Ok(LocalVersion { clock, payload })
+    }
+
+    /// Take over incoming versions that descend from, or win against, ours.
+    async fn merge(
+        &self,
+        local: &mut HashMap<RecordKey, LocalVersion>,
+        mut items: Vec<SyncItem>,
+    ) -> Result<MergeCounts, String> {
+        let mut counts = MergeCounts::default();
+        // Games before reviews, which need their game
+        items.sort_by_key(|item| item.kind);
+
+        for item in items {
+            let key = (item.kind, item.id.clone());
+            let (clock, take) = match local.get(&key) {
+                None => (item.clock.clone(), true),
+                Some(version) => match version.clock.compare(&item.clock) {
+                    ClockOrder::Equal | ClockOrder::After => continue,
+                    ClockOrder::Before => (item.clock.clone(), true),
+                    ClockOrder::Concurrent => {
+                        counts.conflicts += 1;
+                        let remote_wins = rank(item.kind, item.payload.as_deref())
+                            > rank(item.kind, version.payload.as_deref());
+                        tracing::info!(
+                            kind = item.kind.as_str(),
+                            id = %item.id,
+                            remote_wins,
+                            "Resolved sync conflict"
+                        );
+                        (version.clock.merged(&item.clock), remote_wins)
+                    }
+                },
+            };
+
+            let payload = if take {
+                counts.applied += 1;
+                self.apply(&item).await?
+            } else {
+                local.remove(&key).and_then(|v| v.payload)
+            };
+            self.save_record(&key, &clock, payload.as_deref().map(content_hash))
+                .await?;
+            local.insert(key, LocalVersion { clock, payload });
+        }
+
+        Ok(counts)
+    }
+
+    /// Store an incoming version. Returns the payload as stored locally, so
+    /// that the next run does not mistake storage round-trips for edits.
+    async fn apply(&self, item: &SyncItem) -> Result<Option<Vec<u8>>, String> {
+        let decode_error =
+            |e: serde_json::Error| format!("Invalid sync payload for {}: {}", item.id, e);
+
+        match (item.kind, &item.payload) {
+            (SyncKind::FinishedGame, Some(payload)) => {
+                let game: FinishedGameData =
+                    serde_json::from_slice(payload).map_err(decode_error)?;
+                // Replacing a game row cascades to its review in SQLite
+                let review = self
+                    .review_store
+                    .load_review(&game.game_id)
+                    .await
+                    .map_err(storage_error)?;
+                self.finished_game_store
+                    .save_game(&game)
+                    .await
+                    .map_err(storage_error)?;
+                if let Some(review) = review {
+                    self.review_store
+                        .save_review(&review)
+                        .await
+                        .map_err(storage_error)?;
+                }
+
+                let stored = self
+                    .finished_game_store
+                    .load_game(&game.game_id)
+                    .await
+                    .map_err(storage_error)?;
+                stored
+                    .map(|g| serde_json::to_vec(&g).map_err(encode_error))
+                    .transpose()
+            }
+            (SyncKind::Review, Some(payload)) => {
+                let review: GameReview = serde_json::from_slice(payload).map_err(decode_error)?;
+                let game = self
+                    .finished_game_store
+                    .load_game(&review.game_id)
+                    .await
+                    .map_err(storage_error)?;
+                if game.is_none() {
+                    tracing::warn!(game_id = %review.game_id, "Dropping synced review of a deleted game");
+                    return Ok(None);
+                }
+                self.review_store
+                    .save_review(&review)
+                    .await
+                    .map_err(storage_error)?;
+
+                let stored = self
+                    .review_store
+                    .load_review(&review.game_id)
+                    .await
+                    .map_err(storage_error)?;
+                stored
+                    .map(|r| serde_json::to_vec(&r).map_err(encode_error))
+                    .transpose()
+            }
+            (SyncKind::FinishedGame, None) => {
+                self.finished_game_store
+                    .delete_game(&item.id)
+                    .await
+                    .map_err(storage_error)?;
+                Ok(None)
+            }
+            (SyncKind::Review, None) => {
+                self.review_store
+                    .delete_review(&item.id)
+                    .await
+                    .map_err(storage_error)?;
+                Ok(None)
+            }
+        }
+    }
+
+    async fn save_record(
+        &self,
+        key: &RecordKey,
+        clock: &VectorClock,
+        content_hash: Option<u64>,
+    ) -> Result<(), String> {
+        self.sync_store
+            .save_sync_record(&SyncRecord {
+                kind: key.0,
+                record_id: key.1.clone(),
+                clock: clock.as_map().clone(),
+                content_hash,
+            })
+            .await
+            .map_err(storage_error)
+    }
+}
+
+fn versions_of(local: &HashMap<RecordKey, LocalVersion>) -> Vec<SyncVersion> {
+    local
+        .iter()
+        .map(|((kind, id), version)| SyncVersion {
+            kind: *kind,
+            id: id.clone(),
+            clock: version.clock.clone(),
+        })
+        .collect()
+}
+
+/// Split records into pages of at most [`SYNC_PAGE_BYTES`].
+fn pages(items: Vec<SyncItem>) -> Vec<Vec<SyncItem>> {
+    let mut pages = Vec::new();
+    let mut page = Vec::new();
+    let mut bytes = 0;
+    for item in items {
+        let size = item.wire_size();
+        if !page.is_empty() && bytes + size > SYNC_PAGE_BYTES {
+            pages.push(std::mem::take(&mut page));
+            bytes = 0;
+        }
+        bytes += size;
+        page.push(item);
+    }
+    if !page.is_empty() {
+        pages.push(page);
+    }
+    pages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::{FinishedGameStore, JsonPersistence, SyncStore};
+    use crate::review::store::ReviewStore;
+
+    /// A peer answered in-process by another manager.
+    struct LocalPeer<'a>(&'a SyncManager<JsonPersistence>);
+
+    impl SyncPeer for LocalPeer<'_> {
+        async fn compare(
+            &self,
+            _node_id: &str,
+            versions: Vec<SyncVersion>,
+        ) -> Result<SyncComparison, String> {
+            self.0.handle_compare(versions).await
+        }
+
+        async fn push(&self, _node_id: &str, items: Vec<SyncItem>) -> Result<MergeCounts, String> {
+            self.0.handle_push(items).await
+        }
+
+        async fn fetch(
+            &self,
+            _node_id: &str,
+            keys: Vec<RecordKey>,
+        ) -> Result<Vec<SyncItem>, String> {
+            self.0.handle_fetch(keys).await
+        }
+    }
+
+    fn manager(dir: &std::path::Path) -> SyncManager<JsonPersistence> {
+        SyncManager::new(
+            Arc::new(FinishedGameStore::new(dir.to_path_buf())),
+            Arc::new(ReviewStore::new(dir.to_path_buf())),
+            SyncStore::new(dir.to_path_buf()),
+        )
+    }
+
+    fn game(id: &str, move_count: u32) -> FinishedGameData {
+        FinishedGameData {
+            game_mode: "HumanVsEngine:White".to_string(),
+            move_count,
+            created_at: 1000,
+            ..FinishedGameData::sample(id)
+        }
+    }
+
+    fn complete_review(game_id: &str) -> GameReview {
+        GameReview {
+            game_id: game_id.to_string(),
+            status: ReviewStatus::Complete,
+            positions: vec![],
+            white_accuracy: Some(88.0),
+            black_accuracy: Some(70.0),
+            total_plies: 0,
+            analyzed_plies: 0,
+            analysis_depth: 18,
+            started_at: Some(1000),
+            completed_at: Some(1100),
+            winner: Some("White".to_string()),
+            opening: None,
+            endgame: None,
+        }
+    }
+
+    #[test]
+    fn test_content_hash_is_fnv1a() {
+        assert_eq!(content_hash(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(content_hash(b"a"), 0xaf63_dc4c_8601_ec8c);
+    }
+
+    #[test]
+    fn test_rank_prefers_live_and_longer_games() {
+        let short = serde_json::to_vec(&game("g", 10)).unwrap();
+        let long = serde_json::to_vec(&game("g", 40)).unwrap();
+        assert!(
+            rank(SyncKind::FinishedGame, Some(&long)) > rank(SyncKind::FinishedGame, Some(&short))
+        );
+        assert!(rank(SyncKind::FinishedGame, Some(&short)) > rank(SyncKind::FinishedGame, None));
+    }
+
+    #[tokio::test]
+    async fn test_sync_copies_both_ways() {
+        let (dir_a, dir_b) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let (a, b) = (manager(dir_a.path()), manager(dir_b.path()));
+        a.finished_game_store.save(&game("game_a", 20)).unwrap();
+        a.review_store.save(&complete_review("game_a")).unwrap();
+        b.finished_game_store.save(&game("game_b", 30)).unwrap();
+
+        let (_, report) = a.sync_with(&LocalPeer(&b)).await.unwrap();
+        assert_eq!(
+            report,
+            SyncReport {
+                received: 1,
+                sent: 2,
+                conflicts: 0
+            }
+        );
+        assert!(a.finished_game_store.load("game_b").unwrap().is_some());
+        assert!(b.finished_game_store.load("game_a").unwrap().is_some());
+        assert!(b.review_store.load("game_a").unwrap().is_some());
+
+        // Nothing changed since
+        let (_, report) = b.sync_with(&LocalPeer(&a)).await.unwrap();
+        assert_eq!(report, SyncReport::default());
+    }
+
+    #[tokio::test]
+    async fn test_sync_propagates_deletions() {
+        let (dir_a, dir_b) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let (a, b) = (manager(dir_a.path()), manager(dir_b.path()));
+        a.finished_game_store.save(&game("game_1", 20)).unwrap();
+        a.sync_with(&LocalPeer(&b)).await.unwrap();
+
+        b.finished_game_store.delete("game_1").unwrap();
+        let (_, report) = a.sync_with(&LocalPeer(&b)).await.unwrap();
+        assert_eq!(report.received, 1);
+        assert!(a.finished_game_store.load("game_1").unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_edits_converge() {
+        let (dir_a, dir_b) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let (a, b) = (manager(dir_a.path()), manager(dir_b.path()));
+        a.finished_game_store.save(&game("game_1", 20)).unwrap();
+        a.sync_with(&LocalPeer(&b)).await.unwrap();
+
+        a.finished_game_store.save(&game("game_1", 25)).unwrap();
+        b.finished_game_store.save(&game("game_1", 40)).unwrap();
+        let (_, report) = a.sync_with(&LocalPeer(&b)).await.unwrap();
+        assert_eq!(report.conflicts, 1);
+
+        // The longer game wins on both sides
+        assert_eq!(
+            a.finished_game_store
+                .load("game_1")
+                .unwrap()
+                .unwrap()
+                .move_count,
+            40
+        );
+        assert_eq!(
+            b.finished_game_store
+                .load("game_1")
+                .unwrap()
+                .unwrap()
+                .move_count,
+            40
+        );
+        let (_, report) = b.sync_with(&LocalPeer(&a)).await.unwrap();
+        assert_eq!(report, SyncReport::default());
+    }
+
+    #[test]
+    fn test_pages_stay_under_the_page_size() {
+        let item = |id: &str, bytes: usize| SyncItem {
+            kind: SyncKind::FinishedGame,
+            id: id.to_string(),
+            clock: VectorClock::default(),
+            payload: Some(vec![0; bytes]),
+        };
+        let third = SYNC_PAGE_BYTES / 3;
+        let pages = pages(vec![
+            item("a", third),
+            item("b", third),
+            item("c", third),
+            item("d", SYNC_PAGE_BYTES * 2),
+            item("e", 10),
+        ]);
+        let ids: Vec<Vec<&str>> = pages
+            .iter()
+            .map(|page| page.iter().map(|item| item.id.as_str()).collect())
+            .collect();
+        // An oversized record travels alone
+        assert_eq!(ids, vec![vec!["a", "b"], vec!["c"], vec!["d"], vec!["e"]]);
+    }
+
+    #[tokio::test]
+    async fn test_compare_lists_only_records_that_differ() {
+        let (dir_a, dir_b) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let (a, b) = (manager(dir_a.path()), manager(dir_b.path()));
+        a.finished_game_store.save(&game("game_1", 20)).unwrap();
+        a.finished_game_store.save(&game("game_2", 20)).unwrap();
+        a.sync_with(&LocalPeer(&b)).await.unwrap();
+
+        a.finished_game_store.save(&game("game_2", 30)).unwrap();
+        b.finished_game_store.save(&game("game_3", 30)).unwrap();
+        let node_a = a.sync_store.node_id().await.unwrap();
+        let local = a.refresh(&node_a).await.unwrap();
+        let comparison = b.handle_compare(versions_of(&local)).await.unwrap();
+        assert_eq!(
+            comparison.wanted,
+            vec![(SyncKind::FinishedGame, "game_2".to_string())]
+        );
+        assert_eq!(
+            comparison.offered,
+            vec![(SyncKind::FinishedGame, "game_3".to_string())]
+        );
+
+        // A fetch returns the requested records, in order
+        let keys = vec![
+            (SyncKind::FinishedGame, "game_3".to_string()),
+            (SyncKind::FinishedGame, "game_1".to_string()),
+        ];
+        let fetched = b.handle_fetch(keys).await.unwrap();
+        let ids: Vec<&str> = fetched.iter().map(|item| item.id.as_str()).collect();
+        assert_eq!(ids, vec!["game_3", "game_1"]);
+    }
+}
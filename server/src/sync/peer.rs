@@ -0,0 +1,305 @@
+//! Sync peers reached over gRPC, and the wire format of sync items.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chess_proto::sync_service_client::SyncServiceClient;
+use chess_proto::{
+    CompareSyncRequest, FetchSyncRequest, PushSyncRequest, SyncItemKind, SyncItemProto,
+    SyncKeyProto, SyncVersionProto,
+};
+use tonic::metadata::{Ascii, MetadataValue};
+use tonic::transport::{Certificate, Channel, ClientTlsConfig, Endpoint};
+
+use super::clock::VectorClock;
+use super::{MergeCounts, RecordKey, SyncComparison, SyncItem, SyncManager, SyncPeer, SyncVersion};
+use crate::audit::{AuditLog, SERVER_ACTOR};
+use crate::config::DEFAULT_SYNC_PORT;
+use crate::persistence::{AuditAction, Persistence, SyncKind};
+
+/// Largest sync message accepted either way: a page of records, or the ids
+/// and clocks of a whole library.
+pub const MAX_SYNC_MESSAGE_SIZE: usize = 32 * 1024 * 1024;
+
+/// Metadata key carrying the shared secret, as `Bearer <token>`.
+pub const SYNC_TOKEN_HEADER: &str = "authorization";
+
+fn kind_to_proto(kind: SyncKind) -> i32 {
+    let kind = match kind {
+        SyncKind::FinishedGame => SyncItemKind::SyncItemFinishedGame,
+        SyncKind::Review => SyncItemKind::SyncItemReview,
+    };
+    kind as i32
+}
+
+fn kind_from_proto(kind: i32) -> Result<SyncKind, String> {
+    match SyncItemKind::try_from(kind) {
+        Ok(SyncItemKind::SyncItemFinishedGame) => Ok(SyncKind::FinishedGame),
+        Ok(SyncItemKind::SyncItemReview) => Ok(SyncKind::Review),
+        Err(_) => Err(format!("Unknown sync item kind {}", kind)),
+    }
+}
+
+pub fn item_to_proto(item: SyncItem) -> SyncItemProto {
+    SyncItemProto {
+        kind: kind_to_proto(item.kind),
+        id: item.id,
+        clock: item.clock.into_map().into_iter().collect(),
+        payload: item.payload,
+    }
+}
+
+pub fn item_from_proto(item: SyncItemProto) -> Result<SyncItem, String> {
+    Ok(SyncItem {
+        kind: kind_from_proto(item.kind)?,
+        id: item.id,
+        clock: VectorClock::from_map(item.clock.into_iter().collect()),
+        payload: item.payload,
+    })
+}
+
+pub fn version_to_proto(version: SyncVersion) -> SyncVersionProto {
+    SyncVersionProto {
+        kind: kind_to_proto(version.kind),
+        id: version.id,
+        clock: version.clock.into_map().into_iter().collect(),
+    }
+}
+
+pub fn version_from_proto(version: SyncVersionProto) -> Result<SyncVersion, String> {
+    Ok(SyncVersion {
+        kind: kind_from_proto(version.kind)?,
+        id: version.id,
+        clock: VectorClock::from_map(version.clock.into_iter().collect()),
+    })
+}
+
+pub fn key_to_proto((kind, id): RecordKey) -> SyncKeyProto {
+    SyncKeyProto {
+        kind: kind_to_proto(kind),
+        id,
+    }
+}
+
+pub fn key_from_proto(key: SyncKeyProto) -> Result<RecordKey, String> {
+    Ok((kind_from_proto(key.kind)?, key.id))
+}
+
+/// URI for a peer address, adding the scheme and default port when left out.
+pub fn peer_uri(peer: &str) -> String {
+    let peer = peer.trim().trim_end_matches('/');
+    let (scheme, authority) = peer.split_once("://").unwrap_or(("http", peer));
+    let has_port = match authority.rsplit_once(']') {
+        // Bracketed IPv6 address
+        Some((_, rest)) => rest.starts_with(':'),
+        None => authority.contains(':'),
+    };
+    if has_port {
+        format!("{}://{}", scheme, authority)
+    } else {
+        format!("{}://{}:{}", scheme, authority, DEFAULT_SYNC_PORT)
+    }
+}
+
+/// Another chesstty server's sync listener.
+pub struct GrpcPeer {
+    uri: String,
+    channel: Channel,
+    token: MetadataValue<Ascii>,
+}
+
+impl GrpcPeer {
+    /// A peer at `peer`, connected on first use. `https://` peers are
+    /// verified against `CHESSTTY_SYNC_CA`, or the system roots without it.
+    pub fn new(peer: &str, token: &str) -> Result<Self, String> {
+        let uri = peer_uri(peer);
+        let mut endpoint = Endpoint::from_shared(uri.clone())
+            .map_err(|e| format!("Invalid sync peer {}: {}", peer, e))?;
+        if uri.starts_with("https://") {
+            endpoint = endpoint
+                .tls_config(peer_tls_config()?)
+                .map_err(|e| format!("Invalid TLS settings for sync peer {}: {}", peer, e))?;
+        }
+        let token: MetadataValue<Ascii> = format!("Bearer {}", token)
+            .parse()
+            .map_err(|_| "CHESSTTY_SYNC_TOKEN must be printable ASCII".to_string())?;
+        Ok(Self {
+            uri,
+            channel: endpoint.connect_lazy(),
+            token,
+        })
+    }
+
+    fn client(&self) -> SyncServiceClient<Channel> {
+        SyncServiceClient::new(self.channel.clone())
+            .max_decoding_message_size(MAX_SYNC_MESSAGE_SIZE)
+            .max_encoding_message_size(MAX_SYNC_MESSAGE_SIZE)
+    }
+
+    fn request<T>(&self, message: T) -> tonic::Request<T> {
+        let mut request = tonic::Request::new(message);
+        request
+            .metadata_mut()
+            .insert(SYNC_TOKEN_HEADER, self.token.clone());
+        request
+    }
+
+    fn failed(&self, status: tonic::Status) -> String {
+        format!("Sync with {} failed: {}", self.uri, status.message())
+    }
+}
+
+fn peer_tls_config() -> Result<ClientTlsConfig, String> {
+    match crate::config::get_sync_ca() {
+        Some(path) => {
+            let pem = std::fs::read(&path)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            Ok(ClientTlsConfig::new().ca_certificate(Certificate::from_pem(pem)))
+        }
+        None => Ok(ClientTlsConfig::new().with_native_roots()),
+    }
+}
+
+impl SyncPeer for GrpcPeer {
+    async fn compare(
+        &self,
+        node_id: &str,
+        versions: Vec<SyncVersion>,
+    ) -> Result<SyncComparison, String> {
+        let response = self
+            .client()
+            .compare_sync(self.request(CompareSyncRequest {
+                node_id: node_id.to_string(),
+                versions: versions.into_iter().map(version_to_proto).collect(),
+            }))
+            .await
+            .map_err(|s| self.failed(s))?
+            .into_inner();
+
+        Ok(SyncComparison {
+            node_id: response.node_id,
+            wanted: response
+                .wanted
+                .into_iter()
+                .map(key_from_proto)
+                .collect::<Result<_, _>>()?,
+            offered: response
+                .offered
+                .into_iter()
+                .map(key_from_proto)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    async fn push(&self, node_id: &str, items: Vec<SyncItem>) -> Result<MergeCounts, String> {
+        let response = self
+            .client()
+            .push_sync(self.request(PushSyncRequest {
+                node_id: node_id.to_string(),
+                items: items.into_iter().map(item_to_proto).collect(),
+            }))
+            .await
+            .map_err(|s| self.failed(s))?
+            .into_inner();
+
+        Ok(MergeCounts {
+            applied: response.accepted,
+            conflicts: response.conflicts,
+        })
+    }
+
+    async fn fetch(&self, node_id: &str, keys: Vec<RecordKey>) -> Result<Vec<SyncItem>, String> {
+        let response = self
+            .client()
+            .fetch_sync(self.request(FetchSyncRequest {
+                node_id: node_id.to_string(),
+                keys: keys.into_iter().map(key_to_proto).collect(),
+            }))
+            .await
+            .map_err(|s| self.failed(s))?
+            .into_inner();
+
+        response.items.into_iter().map(item_from_proto).collect()
+    }
+}
+
+/// Sync with every peer in turn, once per `interval`, for as long as the
+/// server runs. Failures are logged and retried on the next round.
+pub async fn run_scheduled_sync<D: Persistence>(
+    sync_manager: Arc<SyncManager<D>>,
+    audit_log: Arc<AuditLog<D>>,
+    peers: Vec<String>,
+    token: String,
+    interval: Duration,
+) {
+    let peers: Vec<(String, GrpcPeer)> = peers
+        .into_iter()
+        .filter_map(|peer| match GrpcPeer::new(&peer, &token) {
+            Ok(grpc) => Some((peer, grpc)),
+            Err(e) => {
+                tracing::warn!(peer = %peer, "Not syncing with peer: {}", e);
+                None
+            }
+        })
+        .collect();
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        ticker.tick().await;
+        for (peer, grpc) in &peers {
+            match sync_manager.sync_with(grpc).await {
+                Ok((_, report)) if report.received > 0 => {
+                    audit_log
+                        .record(
+                            SERVER_ACTOR,
+                            AuditAction::Import,
+                            format!("peer {}", peer),
+                            format!(
+                                "scheduled sync: {} received, {} sent, {} conflicts",
+                                report.received, report.sent, report.conflicts
+                            ),
+                        )
+                        .await;
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!(peer = %peer, "Scheduled sync failed: {}", e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_peer_uri() {
+        assert_eq!(peer_uri("desktop.local"), "http://desktop.local:50151");
+        assert_eq!(peer_uri("10.0.0.2:6000"), "http://10.0.0.2:6000");
+        assert_eq!(peer_uri("https://desktop/"), "https://desktop:50151");
+        assert_eq!(peer_uri("[::1]"), "http://[::1]:50151");
+        assert_eq!(peer_uri("[::1]:6000"), "http://[::1]:6000");
+    }
+
+    #[test]
+    fn test_item_round_trip() {
+        let mut clock = VectorClock::default();
+        clock.tick("node_a");
+        let item = SyncItem {
+            kind: SyncKind::Review,
+            id: "game_1".to_string(),
+            clock,
+            payload: None,
+        };
+        assert_eq!(item_from_proto(item_to_proto(item.clone())), Ok(item));
+
+        let unknown = SyncItemProto {
+            kind: 7,
+            ..Default::default()
+        };
+        assert!(item_from_proto(unknown).is_err());
+
+        let key = (SyncKind::FinishedGame, "game_1".to_string());
+        assert_eq!(key_from_proto(key_to_proto(key.clone())), Ok(key));
+    }
+}